@@ -12,11 +12,26 @@ Supports Ethereum Mainnet and Base chains via Alchemy Enhanced APIs.
 Background worker polls every 40s, detects swaps, estimates USD values,
 and flags large trades above configurable thresholds.
 
+Large trades are graded into severity tiers by USD value and routed to each
+watchlist entry's alert targets (a channel chat via the backend, or a
+webhook). Entries can be muted until a time or during a daily window, and
+each tx is delivered at most once per target — even when several watched
+wallets (or chains) report the same hash.
+
+Counterparties are named from a label database: bundled labels for known
+exchanges, bridges and DEX routers (labels.json), the watchlist's own labels,
+and user labels, which take precedence.
+
 RPC protocol endpoints:
   GET  /rpc/status             -> service health
   POST /rpc/tools/watchlist    -> manage watchlist (action-based)
   POST /rpc/tools/activity     -> query activity (action-based)
   POST /rpc/tools/control      -> worker control (action-based)
+  POST /rpc/tools/alerts       -> alert routing, mutes and tiers (action-based)
+  POST /rpc/tools/labels       -> address label management (action-based)
+  GET  /export/activity        -> activity as CSV or JSONL (filters in query string)
+  GET  /export/watchlist       -> watchlist as CSV or JSON
+  POST /rpc/watchlist/import   -> add watchlist entries from CSV
   POST /rpc/backup/export      -> export watchlist for backup
   POST /rpc/backup/restore     -> restore watchlist from backup
   GET  /                       -> HTML dashboard
@@ -24,10 +39,12 @@ RPC protocol endpoints:
 Launch with:  uv run service.py
 """
 
-from flask import request
+from flask import Response, request
 from starkbot_sdk import create_app, success, error
 import sqlite3
 import os
+import csv
+import io
 import re
 import json
 import time
@@ -35,20 +52,34 @@ import logging
 import threading
 import requests as http_requests
 from datetime import datetime, timezone
+from html import escape as html_escape
 
 DB_PATH = os.path.join(os.path.dirname(os.path.abspath(__file__)), "wallet_monitor.db")
+LABELS_PATH = os.path.join(os.path.dirname(os.path.abspath(__file__)), "labels.json")
 POLL_INTERVAL = int(os.environ.get("WALLET_MONITOR_POLL_INTERVAL", "40"))
 ALCHEMY_API_KEY = os.environ.get("ALCHEMY_API_KEY", "")
 ALERT_CALLBACK_URL = os.environ.get("ALERT_CALLBACK_URL")
+BACKEND_URL = os.environ.get("STARKBOT_SELF_URL", "http://127.0.0.1:8080")
+INTERNAL_TOKEN = os.environ.get("STARKBOT_INTERNAL_TOKEN", "")
 FIRST_RUN_LOOKBACK_BLOCKS = 500
 PRICE_CACHE_TTL = 60
 
+# Severity tiers, lowest first. "low" is anything over the entry's own
+# large-trade threshold; the others start at these USD amounts by default.
+SEVERITIES = ["low", "medium", "high", "critical"]
+DEFAULT_SEVERITY_TIERS = {"medium": 50_000.0, "high": 250_000.0, "critical": 1_000_000.0}
+TARGET_TYPES = ["channel", "webhook"]
+LABEL_CATEGORIES = ["exchange", "bridge", "dex_router", "token", "fund", "other"]
+EXPORT_MAX_ROWS = 100_000
+
 # Module-level state for worker
 _start_time = time.time()
 _last_tick_at = None
 _last_tick_lock = threading.Lock()
 _price_cache: dict[str, tuple[float, float]] = {}  # symbol -> (price, timestamp)
 _price_cache_lock = threading.Lock()
+_bundled_labels: dict[tuple[str, str], dict] = {}  # (address, chain or "") -> label entry
+_token_cache: dict[tuple[str, str], dict | None] = {}  # (chain, contract) -> backend token metadata
 
 
 # ---------------------------------------------------------------------------
@@ -113,6 +144,63 @@ def init_db():
     conn.execute("CREATE INDEX IF NOT EXISTS idx_wallet_activity_watchlist ON wallet_activity(watchlist_id, block_number DESC)")
     conn.execute("CREATE INDEX IF NOT EXISTS idx_wallet_activity_large ON wallet_activity(is_large_trade, created_at DESC)")
     conn.execute("CREATE INDEX IF NOT EXISTS idx_wallet_activity_chain ON wallet_activity(chain, block_number DESC)")
+    conn.execute("""
+        CREATE TABLE IF NOT EXISTS wallet_alert_targets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            watchlist_id INTEGER NOT NULL,
+            target_type TEXT NOT NULL,
+            channel_id INTEGER,
+            chat_id TEXT,
+            webhook_url TEXT,
+            min_severity TEXT NOT NULL DEFAULT 'low',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (watchlist_id) REFERENCES wallet_watchlist(id) ON DELETE CASCADE
+        )
+    """)
+    # One row per (tx, destination): the UNIQUE constraint is the dedup.
+    # destination is '' for alerts that went nowhere (muted, no targets).
+    conn.execute("""
+        CREATE TABLE IF NOT EXISTS wallet_alert_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            tx_hash TEXT NOT NULL,
+            destination TEXT NOT NULL,
+            watchlist_id INTEGER,
+            chain TEXT NOT NULL,
+            severity TEXT NOT NULL,
+            usd_value REAL,
+            status TEXT NOT NULL,
+            error TEXT,
+            message TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE(tx_hash, destination)
+        )
+    """)
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_wallet_alert_log_created ON wallet_alert_log(created_at DESC)")
+    conn.execute("""
+        CREATE TABLE IF NOT EXISTS wallet_monitor_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )
+    """)
+    # chain is '' for a label that applies on every chain
+    conn.execute("""
+        CREATE TABLE IF NOT EXISTS address_labels (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            address TEXT NOT NULL,
+            chain TEXT NOT NULL DEFAULT '',
+            label TEXT NOT NULL,
+            category TEXT NOT NULL DEFAULT 'other',
+            notes TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE(address, chain)
+        )
+    """)
+    for col, col_type in [("mute_until", "TEXT"), ("mute_hours", "TEXT")]:
+        try:
+            conn.execute(f"ALTER TABLE wallet_watchlist ADD COLUMN {col} {col_type}")
+        except Exception:
+            pass  # column already exists
     conn.commit()
     conn.close()
 
@@ -170,7 +258,13 @@ def watchlist_list():
         "SELECT * FROM wallet_watchlist ORDER BY created_at ASC"
     ).fetchall()
     conn.close()
-    return [row_to_dict(r) for r in rows]
+    targets = target_list()
+    entries = []
+    for r in rows:
+        entry = row_to_dict(r)
+        entry["alert_targets"] = [t for t in targets if t["watchlist_id"] == entry["id"]]
+        entries.append(entry)
+    return entries
 
 
 def watchlist_update(entry_id: int, label=None, threshold_usd=None, monitor_enabled=None, notes=None):
@@ -199,11 +293,135 @@ def watchlist_update(entry_id: int, label=None, threshold_usd=None, monitor_enab
 
 
 # ---------------------------------------------------------------------------
-# Activity operations
+# Address labels
 # ---------------------------------------------------------------------------
 
-def activity_query(watchlist_id=None, address=None, activity_type=None, chain=None, large_only=False, limit=50):
+def load_bundled_labels():
+    _bundled_labels.clear()
+    try:
+        with open(LABELS_PATH) as f:
+            entries = json.load(f).get("labels", [])
+    except (OSError, ValueError) as e:
+        logging.warning(f"[WALLET_MONITOR] Could not load bundled labels: {e}")
+        return
+    for e in entries:
+        if is_valid_eth_address(e.get("address", "")) and e.get("label"):
+            key = (e["address"].lower(), e.get("chain", ""))
+            _bundled_labels[key] = {"label": e["label"], "category": e.get("category", "other"), "source": "bundled"}
+
+
+def label_lookup(addresses, chain: str) -> dict[str, dict]:
+    """Best label for each address on `chain`: user, then watchlist, then bundled;
+    chain-specific labels beat any-chain ones within each source."""
+    addrs = {a.lower() for a in addresses if a}
+    if not addrs:
+        return {}
+    found: dict[str, dict] = {}
+    for addr in addrs:
+        bundled = _bundled_labels.get((addr, chain)) or _bundled_labels.get((addr, ""))
+        if bundled:
+            found[addr] = dict(bundled)
+
+    placeholders = ",".join("?" for _ in addrs)
     conn = get_db()
+    watched = conn.execute(
+        f"SELECT address, label FROM wallet_watchlist WHERE label IS NOT NULL AND address IN ({placeholders}) ORDER BY chain = ?",
+        [*addrs, chain],
+    ).fetchall()
+    user = conn.execute(
+        f"SELECT address, label, category FROM address_labels WHERE address IN ({placeholders}) AND chain IN ('', ?) ORDER BY chain = ?",
+        [*addrs, chain, chain],
+    ).fetchall()
+    conn.close()
+    # Rows are ordered so the chain-specific one comes last and wins
+    for r in watched:
+        found[r["address"]] = {"label": r["label"], "category": "watched", "source": "watchlist"}
+    for r in user:
+        found[r["address"]] = {"label": r["label"], "category": r["category"], "source": "user"}
+    return found
+
+
+def label_set(address: str, label: str, chain: str | None = None, category: str | None = None, notes: str | None = None):
+    if not is_valid_eth_address(address):
+        return None, "Invalid Ethereum address"
+    if not label or not label.strip():
+        return None, "label is required"
+    category = category or "other"
+    if category not in LABEL_CATEGORIES:
+        return None, f"category must be one of: {', '.join(LABEL_CATEGORIES)}"
+    ts = now_iso()
+    conn = get_db()
+    conn.execute(
+        """INSERT INTO address_labels (address, chain, label, category, notes, created_at, updated_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?)
+           ON CONFLICT(address, chain) DO UPDATE SET
+               label = excluded.label, category = excluded.category,
+               notes = excluded.notes, updated_at = excluded.updated_at""",
+        (address.lower(), chain or "", label.strip(), category, notes, ts, ts),
+    )
+    conn.commit()
+    row = conn.execute(
+        "SELECT * FROM address_labels WHERE address = ? AND chain = ?", (address.lower(), chain or "")
+    ).fetchone()
+    conn.close()
+    return row_to_dict(row), None
+
+
+def label_remove(address: str, chain: str | None = None) -> bool:
+    conn = get_db()
+    cursor = conn.execute(
+        "DELETE FROM address_labels WHERE address = ? AND chain = ?", ((address or "").lower(), chain or "")
+    )
+    conn.commit()
+    conn.close()
+    return cursor.rowcount > 0
+
+
+def label_list(search: str | None = None, category: str | None = None, source: str | None = None) -> list[dict]:
+    conn = get_db()
+    rows = conn.execute("SELECT address, chain, label, category, notes FROM address_labels ORDER BY label").fetchall()
+    conn.close()
+    labels = [{**row_to_dict(r), "source": "user"} for r in rows]
+    labels += [
+        {"address": addr, "chain": chain, "label": e["label"], "category": e["category"], "notes": None, "source": "bundled"}
+        for (addr, chain), e in sorted(_bundled_labels.items(), key=lambda item: item[1]["label"])
+    ]
+    if search:
+        needle = search.lower()
+        labels = [l for l in labels if needle in l["label"].lower() or l["address"].startswith(needle)]
+    if category:
+        labels = [l for l in labels if l["category"] == category]
+    if source:
+        labels = [l for l in labels if l["source"] == source]
+    return labels
+
+
+def describe_address(address: str, labels: dict[str, dict]) -> str:
+    """'Binance 14 hot wallet (0x28c6c0...)' for labeled addresses, else the short address."""
+    addr = (address or "").lower()
+    short = f"{addr[:8]}...{addr[-4:]}" if len(addr) > 14 else addr
+    entry = labels.get(addr)
+    return f"{entry['label']} ({short})" if entry else short
+
+
+def enrich_activity(rows: list[dict]) -> list[dict]:
+    """Add from_label / to_label to activity rows."""
+    by_chain: dict[str, set] = {}
+    for r in rows:
+        by_chain.setdefault(r["chain"], set()).update((r["from_address"], r["to_address"]))
+    labels = {chain: label_lookup(addrs, chain) for chain, addrs in by_chain.items()}
+    for r in rows:
+        chain_labels = labels.get(r["chain"], {})
+        r["from_label"] = (chain_labels.get((r["from_address"] or "").lower()) or {}).get("label")
+        r["to_label"] = (chain_labels.get((r["to_address"] or "").lower()) or {}).get("label")
+    return rows
+
+
+# ---------------------------------------------------------------------------
+# Activity operations
+# ---------------------------------------------------------------------------
+
+def _activity_filter(watchlist_id=None, address=None, activity_type=None, chain=None, large_only=False, since=None, until=None):
     conditions = ["1=1"]
     params: list = []
     if watchlist_id is not None:
@@ -220,16 +438,29 @@ def activity_query(watchlist_id=None, address=None, activity_type=None, chain=No
         params.append(chain)
     if large_only:
         conditions.append("a.is_large_trade = 1")
+    # block_timestamp is ISO ("...T...Z"), created_at is SQLite ("... ..."); compare both as "YYYY-MM-DD HH:MM:SS"
+    if since:
+        conditions.append("replace(COALESCE(a.block_timestamp, a.created_at), 'T', ' ') >= replace(?, 'T', ' ')")
+        params.append(since)
+    if until:
+        conditions.append("replace(COALESCE(a.block_timestamp, a.created_at), 'T', ' ') < replace(?, 'T', ' ')")
+        params.append(until)
+    return " AND ".join(conditions), params
+
+
+def activity_query(watchlist_id=None, address=None, activity_type=None, chain=None, large_only=False, limit=50):
+    conn = get_db()
+    where, params = _activity_filter(watchlist_id, address, activity_type, chain, large_only)
     limit = min(limit or 50, 200)
     sql = f"""
         SELECT a.* FROM wallet_activity a
-        WHERE {' AND '.join(conditions)}
+        WHERE {where}
         ORDER BY a.block_number DESC, a.id DESC
         LIMIT {limit}
     """
     rows = conn.execute(sql, params).fetchall()
     conn.close()
-    return [row_to_dict(r) for r in rows]
+    return enrich_activity([row_to_dict(r) for r in rows])
 
 
 def activity_stats():
@@ -247,6 +478,407 @@ def activity_stats():
     }
 
 
+# ---------------------------------------------------------------------------
+# Export / import
+# ---------------------------------------------------------------------------
+
+# Column order of the activity export. One row per transfer; swap rows also
+# carry both legs of the swap they belong to.
+ACTIVITY_EXPORT_FIELDS = [
+    "timestamp", "chain", "tx_hash", "block_number",
+    "wallet_address", "wallet_label", "direction", "activity_type",
+    "asset_symbol", "asset_address", "amount", "usd_value",
+    "from_address", "from_label", "to_address", "to_label",
+    "swap_sold_token", "swap_sold_amount", "swap_bought_token", "swap_bought_amount",
+    "is_large_trade",
+]
+WATCHLIST_EXPORT_FIELDS = ["address", "label", "chain", "threshold_usd", "monitor_enabled", "notes"]
+
+
+def activity_export(filters: dict, limit=None) -> list[dict]:
+    """Activity matching `filters` (see _activity_filter), oldest first, flattened for accounting tools."""
+    where, params = _activity_filter(**filters)
+    limit = min(limit or EXPORT_MAX_ROWS, EXPORT_MAX_ROWS)
+    conn = get_db()
+    rows = conn.execute(
+        f"""SELECT a.*, w.address AS wallet_address, w.label AS wallet_label
+            FROM wallet_activity a JOIN wallet_watchlist w ON w.id = a.watchlist_id
+            WHERE {where}
+            ORDER BY a.block_number ASC, a.id ASC
+            LIMIT {limit}""",
+        params,
+    ).fetchall()
+    conn.close()
+    records = []
+    for a in enrich_activity([row_to_dict(r) for r in rows]):
+        records.append({
+            "timestamp": a["block_timestamp"] or a["created_at"],
+            "chain": a["chain"],
+            "tx_hash": a["tx_hash"],
+            "block_number": a["block_number"],
+            "wallet_address": a["wallet_address"],
+            "wallet_label": a["wallet_label"],
+            "direction": "out" if a["from_address"] == a["wallet_address"] else "in",
+            "activity_type": a["activity_type"],
+            "asset_symbol": a["asset_symbol"],
+            "asset_address": a["asset_address"],
+            "amount": a["amount_formatted"],
+            "usd_value": a["usd_value"],
+            "from_address": a["from_address"],
+            "from_label": a["from_label"],
+            "to_address": a["to_address"],
+            "to_label": a["to_label"],
+            "swap_sold_token": a["swap_from_token"],
+            "swap_sold_amount": a["swap_from_amount"],
+            "swap_bought_token": a["swap_to_token"],
+            "swap_bought_amount": a["swap_to_amount"],
+            "is_large_trade": bool(a["is_large_trade"]),
+        })
+    return records
+
+
+def watchlist_export() -> list[dict]:
+    return [
+        {
+            "address": w["address"], "label": w["label"], "chain": w["chain"],
+            "threshold_usd": w["large_trade_threshold_usd"], "monitor_enabled": bool(w["monitor_enabled"]),
+            "notes": w["notes"],
+        }
+        for w in watchlist_list()
+    ]
+
+
+def to_csv(records: list[dict], fields: list[str]) -> str:
+    out = io.StringIO()
+    writer = csv.DictWriter(out, fieldnames=fields, extrasaction="ignore")
+    writer.writeheader()
+    for r in records:
+        writer.writerow({k: ("" if r.get(k) is None else r[k]) for k in fields})
+    return out.getvalue()
+
+
+def _csv_bool(value: str | None, default: bool) -> bool:
+    if value is None or not value.strip():
+        return default
+    return value.strip().lower() in ("1", "true", "yes", "y", "on")
+
+
+def watchlist_import_csv(text: str) -> dict:
+    """Add watchlist entries from CSV with an `address` column and optional
+    label, chain, threshold_usd, monitor_enabled and notes columns."""
+    reader = csv.DictReader(io.StringIO(text.lstrip("\ufeff")))
+    if not reader.fieldnames or "address" not in [f.strip().lower() for f in reader.fieldnames]:
+        return {"added": [], "skipped": [], "errors": ["CSV needs a header row with an 'address' column"]}
+    added, skipped, errors = [], [], []
+    for line, raw in enumerate(reader, start=2):
+        row = {(k or "").strip().lower(): (v or "").strip() for k, v in raw.items()}
+        address = row.get("address", "")
+        if not address:
+            continue
+        chain = (row.get("chain") or "mainnet").lower()
+        if chain not in ("mainnet", "base"):
+            errors.append(f"line {line}: unknown chain '{chain}'")
+            continue
+        try:
+            threshold = float(row["threshold_usd"]) if row.get("threshold_usd") else 1000.0
+        except ValueError:
+            errors.append(f"line {line}: threshold_usd '{row['threshold_usd']}' is not a number")
+            continue
+        entry, err = watchlist_add(address, row.get("label") or None, chain, threshold)
+        if err:
+            (skipped if "already on watchlist" in err else errors).append(f"line {line}: {err}")
+            continue
+        monitor_enabled = _csv_bool(row.get("monitor_enabled"), True)
+        if row.get("notes") or not monitor_enabled:
+            watchlist_update(entry["id"], notes=row.get("notes") or None, monitor_enabled=monitor_enabled)
+        added.append(entry["id"])
+    return {"added": added, "skipped": skipped, "errors": errors}
+
+
+# ---------------------------------------------------------------------------
+# Alert routing
+# ---------------------------------------------------------------------------
+
+def severity_rank(severity: str | None) -> int:
+    return SEVERITIES.index(severity) if severity in SEVERITIES else 0
+
+
+def get_severity_tiers() -> dict[str, float]:
+    tiers = dict(DEFAULT_SEVERITY_TIERS)
+    conn = get_db()
+    row = conn.execute("SELECT value FROM wallet_monitor_settings WHERE key = 'severity_tiers'").fetchone()
+    conn.close()
+    if row:
+        try:
+            tiers.update({k: float(v) for k, v in json.loads(row["value"]).items() if k in tiers})
+        except (ValueError, TypeError, AttributeError):
+            pass
+    return tiers
+
+
+def set_severity_tiers(medium=None, high=None, critical=None):
+    tiers = get_severity_tiers()
+    for name, value in (("medium", medium), ("high", high), ("critical", critical)):
+        if value is not None:
+            tiers[name] = float(value)
+    if not 0 < tiers["medium"] < tiers["high"] < tiers["critical"]:
+        return None, "Tiers must increase: 0 < medium_usd < high_usd < critical_usd"
+    conn = get_db()
+    conn.execute(
+        "INSERT INTO wallet_monitor_settings (key, value) VALUES ('severity_tiers', ?) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        (json.dumps(tiers),),
+    )
+    conn.commit()
+    conn.close()
+    return tiers, None
+
+
+def classify_severity(usd_value: float | None, tiers: dict[str, float]) -> str:
+    severity = "low"
+    for name in SEVERITIES[1:]:
+        if usd_value is not None and usd_value >= tiers[name]:
+            severity = name
+    return severity
+
+
+def parse_mute_hours(value: str | None) -> tuple[int, int] | None:
+    """Parse a daily "HH:MM-HH:MM" window (UTC, may wrap midnight) into minutes of the day."""
+    if not value or not value.strip():
+        return None
+    m = re.fullmatch(r"\s*(\d{1,2}):(\d{2})\s*-\s*(\d{1,2}):(\d{2})\s*", value)
+    if not m:
+        raise ValueError(f"'{value}' is not a time window (expected e.g. 22:00-07:00)")
+    sh, sm, eh, em = map(int, m.groups())
+    if sh > 23 or eh > 23 or sm > 59 or em > 59:
+        raise ValueError(f"'{value}' is not a valid time window")
+    start, end = sh * 60 + sm, eh * 60 + em
+    if start == end:
+        raise ValueError("Mute window must start and end at different times")
+    return start, end
+
+
+def mute_reason(entry: dict, now: datetime) -> str | None:
+    """Why alerts for this entry are muted right now, or None."""
+    until = entry.get("mute_until")
+    if until:
+        try:
+            if datetime.fromisoformat(until) > now:
+                return f"muted until {until}"
+        except ValueError:
+            pass
+    try:
+        window = parse_mute_hours(entry.get("mute_hours"))
+    except ValueError:
+        window = None
+    if window:
+        start, end = window
+        minute = now.hour * 60 + now.minute
+        inside = start <= minute < end if start < end else (minute >= start or minute < end)
+        if inside:
+            return f"mute hours {entry['mute_hours']} UTC"
+    return None
+
+
+def watchlist_mute(entry_id: int, minutes=None, mute_hours=None):
+    """Mute an entry for `minutes` from now and/or set its daily mute window ("" clears it)."""
+    updates = ["updated_at = ?"]
+    params: list = [now_iso()]
+    if minutes is not None:
+        until = datetime.fromtimestamp(time.time() + float(minutes) * 60, timezone.utc)
+        updates.append("mute_until = ?")
+        params.append(until.strftime("%Y-%m-%dT%H:%M:%S+00:00"))
+    if mute_hours is not None:
+        try:
+            parse_mute_hours(mute_hours)
+        except ValueError as e:
+            return False, str(e)
+        updates.append("mute_hours = ?")
+        params.append(mute_hours.strip() or None)
+    if len(updates) == 1:
+        return False, "mute_minutes or mute_hours is required"
+    params.append(entry_id)
+    conn = get_db()
+    cursor = conn.execute(f"UPDATE wallet_watchlist SET {', '.join(updates)} WHERE id = ?", params)
+    conn.commit()
+    conn.close()
+    if cursor.rowcount == 0:
+        return False, f"Entry #{entry_id} not found"
+    return True, None
+
+
+def watchlist_unmute(entry_id: int) -> bool:
+    conn = get_db()
+    cursor = conn.execute(
+        "UPDATE wallet_watchlist SET mute_until = NULL, mute_hours = NULL, updated_at = ? WHERE id = ?",
+        (now_iso(), entry_id),
+    )
+    conn.commit()
+    conn.close()
+    return cursor.rowcount > 0
+
+
+def target_add(watchlist_id: int, target_type: str, channel_id=None, chat_id=None, webhook_url=None, min_severity="low"):
+    if target_type not in TARGET_TYPES:
+        return None, f"target_type must be one of: {', '.join(TARGET_TYPES)}"
+    if min_severity not in SEVERITIES:
+        return None, f"min_severity must be one of: {', '.join(SEVERITIES)}"
+    if target_type == "channel" and (channel_id is None or not chat_id):
+        return None, "channel_id and chat_id are required for a channel target"
+    if target_type == "webhook" and not (webhook_url or "").startswith(("http://", "https://")):
+        return None, "webhook_url must be an http(s) URL"
+    conn = get_db()
+    if not conn.execute("SELECT 1 FROM wallet_watchlist WHERE id = ?", (watchlist_id,)).fetchone():
+        conn.close()
+        return None, f"Entry #{watchlist_id} not found"
+    if target_type == "channel":
+        webhook_url = None
+    else:
+        channel_id = chat_id = None
+    cursor = conn.execute(
+        "INSERT INTO wallet_alert_targets (watchlist_id, target_type, channel_id, chat_id, webhook_url, min_severity, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        (watchlist_id, target_type, channel_id, str(chat_id) if chat_id is not None else None, webhook_url, min_severity, now_iso()),
+    )
+    conn.commit()
+    row = conn.execute("SELECT * FROM wallet_alert_targets WHERE id = ?", (cursor.lastrowid,)).fetchone()
+    conn.close()
+    return row_to_dict(row), None
+
+
+def target_remove(target_id: int) -> bool:
+    conn = get_db()
+    cursor = conn.execute("DELETE FROM wallet_alert_targets WHERE id = ?", (target_id,))
+    conn.commit()
+    conn.close()
+    return cursor.rowcount > 0
+
+
+def target_list(watchlist_id=None) -> list[dict]:
+    conn = get_db()
+    if watchlist_id is None:
+        rows = conn.execute("SELECT * FROM wallet_alert_targets ORDER BY watchlist_id, id").fetchall()
+    else:
+        rows = conn.execute("SELECT * FROM wallet_alert_targets WHERE watchlist_id = ? ORDER BY id", (watchlist_id,)).fetchall()
+    conn.close()
+    return [row_to_dict(r) for r in rows]
+
+
+def target_destination(target: dict) -> str:
+    """Dedup key for where a target delivers — two entries pointing at the same chat share it."""
+    if target["target_type"] == "channel":
+        return f"channel:{target['channel_id']}:{target['chat_id']}"
+    return f"webhook:{target['webhook_url']}"
+
+
+def send_to_channel(channel_id: int, chat_id: str, message: str):
+    """Post to a channel chat through the backend (subject to its proactive message limits)."""
+    if not INTERNAL_TOKEN:
+        raise RuntimeError("STARKBOT_INTERNAL_TOKEN not set")
+    resp = http_requests.post(
+        f"{BACKEND_URL}/api/internal/modules/notify",
+        json={"module": "wallet_monitor", "channel_id": channel_id, "chat_id": chat_id, "message": message},
+        headers={"X-Internal-Token": INTERNAL_TOKEN},
+        timeout=10,
+    )
+    if resp.status_code >= 400:
+        try:
+            reason = resp.json().get("error")
+        except ValueError:
+            reason = None
+        raise RuntimeError(reason or f"HTTP {resp.status_code}")
+
+
+def deliver_alert(target: dict, alert: dict):
+    if target["target_type"] == "channel":
+        send_to_channel(target["channel_id"], target["chat_id"], alert["message"])
+    else:
+        http_requests.post(target["webhook_url"], json=alert, timeout=10).raise_for_status()
+
+
+def collapse_alerts(alerts: list[dict]) -> list[dict]:
+    """Keep the largest alert per wallet and tx (a swap reports every leg)."""
+    best: dict[tuple[int, str], dict] = {}
+    for a in alerts:
+        key = (a["watchlist_id"], a["tx_hash"].lower())
+        if key not in best or (a["usd_value"] or 0) > (best[key]["usd_value"] or 0):
+            best[key] = a
+    return list(best.values())
+
+
+def _log_alert(conn, alert: dict, destination: str, status: str, error_msg: str | None = None) -> int | None:
+    """Claim (tx, destination) in the alert log; None if it was already claimed."""
+    cursor = conn.execute(
+        """INSERT OR IGNORE INTO wallet_alert_log
+           (tx_hash, destination, watchlist_id, chain, severity, usd_value, status, error, message, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)""",
+        (
+            alert["tx_hash"].lower(), destination, alert["watchlist_id"], alert["chain"],
+            alert["severity"], alert["usd_value"], status, error_msg, alert["message"], now_iso(),
+        ),
+    )
+    conn.commit()
+    return cursor.lastrowid if cursor.rowcount > 0 else None
+
+
+def route_alert(alert: dict, entry: dict, logger) -> int:
+    """Deliver one graded alert to the entry's targets. Returns how many deliveries were made."""
+    conn = get_db()
+    try:
+        reason = mute_reason(entry, datetime.now(timezone.utc))
+        if reason:
+            _log_alert(conn, alert, "", "muted", reason)
+            return 0
+
+        targets = target_list(entry["id"])
+        destinations = [
+            (target_destination(t), t) for t in targets
+            if severity_rank(alert["severity"]) >= severity_rank(t["min_severity"])
+        ]
+        if not targets and ALERT_CALLBACK_URL:
+            # Entries without targets keep the old behaviour: everything to the global callback
+            destinations = [("callback", {"target_type": "webhook", "webhook_url": ALERT_CALLBACK_URL})]
+        if not destinations:
+            _log_alert(conn, alert, "", "logged", "below target severity" if targets else "no alert targets")
+            return 0
+
+        sent = 0
+        for destination, target in destinations:
+            log_id = _log_alert(conn, alert, destination, "pending")
+            if log_id is None:
+                logger.debug(f"[WALLET_MONITOR] Alert for {alert['tx_hash']} already sent to {destination}")
+                continue
+            try:
+                deliver_alert(target, alert)
+                status, err = "sent", None
+                sent += 1
+            except Exception as e:
+                status, err = "failed", str(e)
+                logger.warning(f"[WALLET_MONITOR] Failed to deliver alert to {destination}: {e}")
+            conn.execute("UPDATE wallet_alert_log SET status = ?, error = ? WHERE id = ?", (status, err, log_id))
+            conn.commit()
+        return sent
+    finally:
+        conn.close()
+
+
+def alert_history(watchlist_id=None, status=None, limit=50) -> list[dict]:
+    conditions = ["1=1"]
+    params: list = []
+    if watchlist_id is not None:
+        conditions.append("watchlist_id = ?")
+        params.append(watchlist_id)
+    if status:
+        conditions.append("status = ?")
+        params.append(status)
+    limit = min(limit or 50, 200)
+    conn = get_db()
+    rows = conn.execute(
+        f"SELECT * FROM wallet_alert_log WHERE {' AND '.join(conditions)} ORDER BY id DESC LIMIT {limit}",
+        params,
+    ).fetchall()
+    conn.close()
+    return [row_to_dict(r) for r in rows]
+
+
 # ---------------------------------------------------------------------------
 # Backup operations
 # ---------------------------------------------------------------------------
@@ -254,10 +886,21 @@ def activity_stats():
 def backup_export():
     conn = get_db()
     rows = conn.execute(
-        "SELECT address, label, chain, monitor_enabled, large_trade_threshold_usd, copy_trade_enabled, copy_trade_max_usd, notes FROM wallet_watchlist ORDER BY created_at ASC"
+        "SELECT id, address, label, chain, monitor_enabled, large_trade_threshold_usd, copy_trade_enabled, copy_trade_max_usd, notes, mute_until, mute_hours FROM wallet_watchlist ORDER BY created_at ASC"
+    ).fetchall()
+    targets = conn.execute(
+        "SELECT watchlist_id, target_type, channel_id, chat_id, webhook_url, min_severity FROM wallet_alert_targets ORDER BY id"
     ).fetchall()
     conn.close()
-    return [row_to_dict(r) for r in rows]
+    wallets = []
+    for r in rows:
+        entry = row_to_dict(r)
+        entry_id = entry.pop("id")
+        entry["alert_targets"] = [
+            {k: t[k] for k in t.keys() if k != "watchlist_id"} for t in targets if t["watchlist_id"] == entry_id
+        ]
+        wallets.append(entry)
+    return wallets
 
 
 def backup_restore(wallets: list) -> int:
@@ -270,15 +913,24 @@ def backup_restore(wallets: list) -> int:
         addr = entry.get("address")
         if not addr:
             continue
-        conn.execute(
-            "INSERT OR IGNORE INTO wallet_watchlist (address, label, chain, monitor_enabled, large_trade_threshold_usd, copy_trade_enabled, copy_trade_max_usd, notes, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        cursor = conn.execute(
+            "INSERT OR IGNORE INTO wallet_watchlist (address, label, chain, monitor_enabled, large_trade_threshold_usd, copy_trade_enabled, copy_trade_max_usd, notes, mute_until, mute_hours, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             (
                 addr, entry.get("label"), entry.get("chain", "mainnet"),
                 entry.get("monitor_enabled", 1), entry.get("large_trade_threshold_usd", 1000.0),
                 entry.get("copy_trade_enabled", 0), entry.get("copy_trade_max_usd"),
-                entry.get("notes"), ts, ts,
+                entry.get("notes"), entry.get("mute_until"), entry.get("mute_hours"), ts, ts,
             ),
         )
+        if cursor.rowcount > 0:
+            for t in entry.get("alert_targets") or []:
+                conn.execute(
+                    "INSERT INTO wallet_alert_targets (watchlist_id, target_type, channel_id, chat_id, webhook_url, min_severity, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    (
+                        cursor.lastrowid, t.get("target_type", "webhook"), t.get("channel_id"), t.get("chat_id"),
+                        t.get("webhook_url"), t.get("min_severity", "low"), ts,
+                    ),
+                )
         count += 1
     conn.commit()
     conn.close()
@@ -429,18 +1081,69 @@ def wallet_monitor_tick(logger):
         except Exception as e:
             logger.warning(f"[WALLET_MONITOR] Error processing wallet {entry['address']} ({entry['chain']}): {e}")
 
-    if alerts and ALERT_CALLBACK_URL:
+    if alerts:
+        alerts = collapse_alerts(alerts)
+        tiers = get_severity_tiers()
+        entries = {e["id"]: e for e in map(row_to_dict, watchlist)}
+        delivered = 0
         for alert in alerts:
+            alert["severity"] = classify_severity(alert["usd_value"], tiers)
+            alert["message"] = f"[{alert['severity'].upper()}] {alert['message']}"
             try:
-                http_requests.post(ALERT_CALLBACK_URL, json=alert, timeout=10)
+                delivered += route_alert(alert, entries[alert["watchlist_id"]], logger)
             except Exception as e:
-                logger.warning(f"[WALLET_MONITOR] Failed to send alert callback: {e}")
-        logger.warning(f"[WALLET_MONITOR] LARGE TRADE ALERTS: {' | '.join(a['message'] for a in alerts)}")
+                logger.warning(f"[WALLET_MONITOR] Failed to route alert for {alert['tx_hash']}: {e}")
+        logger.warning(f"[WALLET_MONITOR] LARGE TRADE ALERTS ({delivered} delivered): {' | '.join(a['message'] for a in alerts)}")
 
     if total_new > 0:
         logger.info(f"[WALLET_MONITOR] Tick complete: {total_new} new transactions, {len(alerts)} large trades")
 
 
+def token_metadata(chain: str, contract: str) -> dict | None:
+    """Symbol and decimals of a token from the backend's metadata cache (memoized)."""
+    key = (chain, contract.lower())
+    if key in _token_cache:
+        return _token_cache[key]
+    meta = None
+    if INTERNAL_TOKEN:
+        try:
+            resp = http_requests.get(
+                f"{BACKEND_URL}/api/internal/tokens/metadata",
+                params={"network": chain, "address": contract},
+                headers={"X-Internal-Token": INTERNAL_TOKEN},
+                timeout=15,
+            )
+            if resp.status_code == 200:
+                meta = resp.json().get("token")
+            elif resp.status_code != 404:
+                return None  # transient; retry next time
+        except Exception:
+            return None
+    _token_cache[key] = meta
+    return meta
+
+
+def fill_token_metadata(transfers: list[dict], chain: str):
+    """Alchemy leaves asset/value empty for tokens it hasn't indexed; fill them
+    in from the backend's token metadata (overrides included)."""
+    for t in transfers:
+        if t.get("category") != "erc20" or (t.get("asset") and t.get("value") is not None):
+            continue
+        raw = t.get("rawContract") or {}
+        if not raw.get("address"):
+            continue
+        meta = token_metadata(chain, raw["address"])
+        if not meta:
+            continue
+        if not t.get("asset"):
+            t["asset"] = meta["symbol"]
+        if t.get("value") is None and raw.get("value"):
+            try:
+                t["value"] = int(raw["value"], 16) / 10 ** int(meta["decimals"])
+            except (TypeError, ValueError):
+                pass
+
+
 def process_wallet(entry: dict, logger) -> tuple[int, list[dict]]:
     from_block = None
     if entry["last_checked_block"] is not None:
@@ -452,6 +1155,7 @@ def process_wallet(entry: dict, logger) -> tuple[int, list[dict]]:
 
     outgoing = alchemy_get_asset_transfers(entry["chain"], entry["address"], from_block, "from")
     incoming = alchemy_get_asset_transfers(entry["chain"], entry["address"], from_block, "to")
+    fill_token_metadata(outgoing + incoming, entry["chain"])
 
     if not outgoing and not incoming:
         try:
@@ -472,6 +1176,11 @@ def process_wallet(entry: dict, logger) -> tuple[int, list[dict]]:
     for t in incoming:
         tx_groups.setdefault(t["hash"], []).append((t, "incoming"))
 
+    labels = label_lookup(
+        {t.get("from") for t in outgoing + incoming} | {t.get("to") for t in outgoing + incoming},
+        entry["chain"],
+    )
+
     new_count = 0
     max_block = entry["last_checked_block"] or 0
     alerts = []
@@ -539,12 +1248,21 @@ def process_wallet(entry: dict, logger) -> tuple[int, list[dict]]:
                         usd_str = f"${usd_value:.0f}" if usd_value else "unknown"
                         addr_short = entry["address"][:10]
                         if is_swap:
-                            message = f"**{label}** ({addr_short}) swapped {swap_from_amount or '?'} {swap_from_token or '?'} -> {swap_to_amount or '?'} {swap_to_token or '?'} ({usd_str}) on {entry['chain']} [tx: {tx_hash}]"
+                            # Name the router if any leg touched a known one
+                            counterparty = next(
+                                (a for t, _ in transfers for a in (t.get("from"), t.get("to"))
+                                 if a and labels.get(a.lower(), {}).get("category") == "dex_router"),
+                                None,
+                            )
+                            via = f" via {describe_address(counterparty, labels)}" if counterparty else ""
+                            message = f"**{label}** ({addr_short}) swapped {swap_from_amount or '?'} {swap_from_token or '?'} -> {swap_to_amount or '?'} {swap_to_token or '?'} ({usd_str}){via} on {entry['chain']} [tx: {tx_hash}]"
                         else:
                             asset = transfer.get("asset") or "ETH"
                             amt = amount_formatted or "?"
+                            counterparty = transfer.get("to") if direction == "outgoing" else transfer.get("from")
                             dir_str = "sent" if direction == "outgoing" else "received"
-                            message = f"**{label}** ({addr_short}) {dir_str} {amt} {asset} ({usd_str}) on {entry['chain']} [tx: {tx_hash}]"
+                            to_from = "to" if direction == "outgoing" else "from"
+                            message = f"**{label}** ({addr_short}) {dir_str} {amt} {asset} ({usd_str}) {to_from} {describe_address(counterparty, labels)} on {entry['chain']} [tx: {tx_hash}]"
                         alerts.append({
                             "watchlist_id": entry["id"], "address": entry["address"],
                             "label": entry.get("label"), "chain": entry["chain"],
@@ -553,6 +1271,8 @@ def process_wallet(entry: dict, logger) -> tuple[int, list[dict]]:
                             "amount_formatted": amount_formatted,
                             "swap_from_token": swap_from_token, "swap_from_amount": swap_from_amount,
                             "swap_to_token": swap_to_token, "swap_to_amount": swap_to_amount,
+                            "counterparty": counterparty,
+                            "counterparty_label": labels.get((counterparty or "").lower(), {}).get("label"),
                             "message": message,
                         })
             except Exception:
@@ -625,8 +1345,14 @@ def rpc_watchlist():
                 return success(True)
             return error(f"Entry #{entry_id} not found", 404)
 
+        elif action == "import":
+            text = body.get("csv")
+            if not text or not text.strip():
+                return error("csv is required")
+            return success(watchlist_import_csv(text))
+
         else:
-            return error(f"Unknown action: {action}. Valid: add, remove, list, update")
+            return error(f"Unknown action: {action}. Valid: add, remove, list, update, import")
     except Exception as e:
         return error(str(e))
 
@@ -689,6 +1415,178 @@ def rpc_control():
         return error(str(e))
 
 
+# ---------------------------------------------------------------------------
+# RPC: Alerts tool
+# ---------------------------------------------------------------------------
+
+@app.route("/rpc/tools/alerts", methods=["POST"])
+def rpc_alerts():
+    body = request.get_json(silent=True) or {}
+    action = body.get("action")
+    try:
+        if action == "targets":
+            return success(target_list(body.get("id")))
+
+        elif action == "add_target":
+            entry_id = body.get("id")
+            if entry_id is None:
+                return error("id is required")
+            target, err = target_add(
+                entry_id, body.get("target_type", ""), body.get("channel_id"), body.get("chat_id"),
+                body.get("webhook_url"), body.get("min_severity", "low"),
+            )
+            if err:
+                return error(err)
+            return success(target)
+
+        elif action == "remove_target":
+            target_id = body.get("target_id")
+            if target_id is None:
+                return error("target_id is required")
+            if target_remove(target_id):
+                return success(True)
+            return error(f"Target #{target_id} not found", 404)
+
+        elif action == "mute":
+            entry_id = body.get("id")
+            if entry_id is None:
+                return error("id is required")
+            ok, err = watchlist_mute(entry_id, body.get("mute_minutes"), body.get("mute_hours"))
+            if not ok:
+                return error(err)
+            return success(True)
+
+        elif action == "unmute":
+            entry_id = body.get("id")
+            if entry_id is None:
+                return error("id is required")
+            if watchlist_unmute(entry_id):
+                return success(True)
+            return error(f"Entry #{entry_id} not found", 404)
+
+        elif action == "history":
+            return success(alert_history(body.get("id"), body.get("status"), body.get("limit", 25)))
+
+        elif action == "tiers":
+            return success(get_severity_tiers())
+
+        elif action == "set_tiers":
+            tiers, err = set_severity_tiers(body.get("medium_usd"), body.get("high_usd"), body.get("critical_usd"))
+            if err:
+                return error(err)
+            return success(tiers)
+
+        else:
+            return error(f"Unknown action: {action}. Valid: targets, add_target, remove_target, mute, unmute, history, tiers, set_tiers")
+    except Exception as e:
+        return error(str(e))
+
+
+# ---------------------------------------------------------------------------
+# RPC: Labels tool
+# ---------------------------------------------------------------------------
+
+@app.route("/rpc/tools/labels", methods=["POST"])
+def rpc_labels():
+    body = request.get_json(silent=True) or {}
+    action = body.get("action")
+    try:
+        if action == "list":
+            return success(label_list(body.get("search"), body.get("category"), body.get("source")))
+
+        elif action == "lookup":
+            address = body.get("address")
+            if not address:
+                return error("address is required")
+            found = label_lookup([address], body.get("chain", "mainnet")).get(address.lower())
+            return success({"address": address.lower(), **found} if found else None)
+
+        elif action == "set":
+            address = body.get("address")
+            if not address:
+                return error("address is required")
+            entry, err = label_set(address, body.get("label"), body.get("chain"), body.get("category"), body.get("notes"))
+            if err:
+                return error(err)
+            return success(entry)
+
+        elif action == "remove":
+            address = body.get("address")
+            if not address:
+                return error("address is required")
+            if label_remove(address, body.get("chain")):
+                return success(True)
+            return error(f"No user label for {address}" + (f" on {body['chain']}" if body.get("chain") else ""), 404)
+
+        else:
+            return error(f"Unknown action: {action}. Valid: list, lookup, set, remove")
+    except Exception as e:
+        return error(str(e))
+
+
+# ---------------------------------------------------------------------------
+# Export / Import
+# ---------------------------------------------------------------------------
+
+def _export_response(body: str, mimetype: str, filename: str) -> Response:
+    return Response(body, mimetype=mimetype, headers={"Content-Disposition": f'attachment; filename="{filename}"'})
+
+
+@app.route("/export/activity", methods=["GET"])
+def export_activity():
+    args = request.args
+    fmt = args.get("format", "csv").lower()
+    if fmt not in ("csv", "jsonl"):
+        return error("format must be 'csv' or 'jsonl'")
+    try:
+        records = activity_export(
+            {
+                "watchlist_id": args.get("watchlist_id", type=int),
+                "address": args.get("address"),
+                "activity_type": args.get("activity_type"),
+                "chain": args.get("chain"),
+                "large_only": args.get("large_only", "").lower() in ("1", "true", "yes"),
+                "since": args.get("since"),
+                "until": args.get("until"),
+            },
+            args.get("limit", type=int),
+        )
+    except Exception as e:
+        return error(str(e))
+    stamp = datetime.now(timezone.utc).strftime("%Y%m%d")
+    if fmt == "jsonl":
+        body = "".join(json.dumps(r) + "\n" for r in records)
+        return _export_response(body, "application/x-ndjson", f"wallet_activity_{stamp}.jsonl")
+    return _export_response(to_csv(records, ACTIVITY_EXPORT_FIELDS), "text/csv", f"wallet_activity_{stamp}.csv")
+
+
+@app.route("/export/watchlist", methods=["GET"])
+def export_watchlist():
+    fmt = request.args.get("format", "csv").lower()
+    if fmt not in ("csv", "json"):
+        return error("format must be 'csv' or 'json'")
+    records = watchlist_export()
+    if fmt == "json":
+        return success(records)
+    stamp = datetime.now(timezone.utc).strftime("%Y%m%d")
+    return _export_response(to_csv(records, WATCHLIST_EXPORT_FIELDS), "text/csv", f"wallet_watchlist_{stamp}.csv")
+
+
+@app.route("/rpc/watchlist/import", methods=["POST"])
+def rpc_watchlist_import():
+    # Raw CSV body, or JSON {"csv": "..."}
+    if request.is_json:
+        text = (request.get_json(silent=True) or {}).get("csv", "")
+    else:
+        text = request.get_data(as_text=True)
+    if not text or not text.strip():
+        return error("CSV content is required")
+    try:
+        return success(watchlist_import_csv(text))
+    except csv.Error as e:
+        return error(f"Invalid CSV: {e}")
+
+
 # ---------------------------------------------------------------------------
 # RPC: Backup / Restore
 # ---------------------------------------------------------------------------
@@ -768,6 +1666,7 @@ def dashboard():
     if not watchlist_rows:
         watchlist_rows = '<tr><td colspan="8" style="text-align:center;color:#8b949e;padding:20px;">No wallets on watchlist. Add one below.</td></tr>'
 
+    watched_addrs = {w["id"]: w["address"] for w in wl}
     activity_rows = ""
     for a in recent:
         usd = f"${a['usd_value']:.0f}" if a.get("usd_value") is not None else "-"
@@ -776,9 +1675,14 @@ def dashboard():
         amount = a.get("amount_formatted") or "-"
         tx = a["tx_hash"]
         tx_short = f"{tx[:8]}...{tx[-4:]}" if len(tx) > 14 else tx
-        activity_rows += f'<tr{large_cls}><td>{a["activity_type"]}</td><td>{a["chain"]}</td><td>{amount} {asset}</td><td>{usd}</td><td class="mono">{tx_short}</td><td>{a["created_at"]}</td></tr>\n'
+        outgoing = a["from_address"] == watched_addrs.get(a["watchlist_id"])
+        cp_addr, cp_label = (a["to_address"], a["to_label"]) if outgoing else (a["from_address"], a["from_label"])
+        cp_short = f"{cp_addr[:8]}...{cp_addr[-4:]}" if len(cp_addr) > 14 else cp_addr
+        cp_name = html_escape(cp_label) if cp_label else f'<span class="mono">{cp_short}</span>'
+        counterparty = f'{"&rarr;" if outgoing else "&larr;"} {cp_name}'
+        activity_rows += f'<tr{large_cls}><td>{a["activity_type"]}</td><td>{a["chain"]}</td><td>{amount} {asset}</td><td>{usd}</td><td>{counterparty}</td><td class="mono">{tx_short}</td><td>{a["created_at"]}</td></tr>\n'
     if not activity_rows:
-        activity_rows = '<tr><td colspan="6">No activity recorded yet.</td></tr>'
+        activity_rows = '<tr><td colspan="7">No activity recorded yet.</td></tr>'
 
     html = f"""<!DOCTYPE html>
 <html lang="en">
@@ -886,7 +1790,7 @@ def dashboard():
   <div class="section">
     <h2>Recent Activity</h2>
     <table>
-      <thead><tr><th>Type</th><th>Chain</th><th>Amount</th><th>USD</th><th>Tx</th><th>Time</th></tr></thead>
+      <thead><tr><th>Type</th><th>Chain</th><th>Amount</th><th>USD</th><th>Counterparty</th><th>Tx</th><th>Time</th></tr></thead>
       <tbody>{activity_rows}</tbody>
     </table>
   </div>
@@ -979,6 +1883,7 @@ if __name__ == "__main__":
     logging.basicConfig(level=logging.INFO, format="%(asctime)s %(levelname)s %(message)s")
     logging.getLogger("werkzeug").setLevel(logging.ERROR)
     init_db()
+    load_bundled_labels()
 
     if ALCHEMY_API_KEY:
         worker_thread = threading.Thread(target=worker_loop, daemon=True)
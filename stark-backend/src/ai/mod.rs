@@ -1,6 +1,9 @@
 pub mod archetypes;
 pub mod claude;
+#[cfg(any(test, feature = "ai-recorder"))]
+pub mod fixtures;
 pub mod llama;
+pub mod models;
 pub mod multi_agent;
 pub mod openai;
 pub mod streaming;
@@ -128,6 +131,9 @@ pub enum AiClient {
     OpenAI(OpenAIClient),
     Llama(LlamaClient),
     Mock(MockAiClient),
+    /// Wraps a real client and records its exchanges as a test fixture
+    #[cfg(feature = "ai-recorder")]
+    Recording(Box<AiClient>, Arc<fixtures::AiRecorder>),
 }
 
 impl AiClient {
@@ -222,7 +228,7 @@ impl AiClient {
                 Some(&settings.endpoint),
                 Some(model),
             )?;
-            return Ok(AiClient::Claude(client));
+            return Ok(Self::with_recorder(AiClient::Claude(client), settings));
         }
 
         // All other archetypes use OpenAI-compatible client
@@ -234,7 +240,22 @@ impl AiClient {
             Some(settings.max_response_tokens as u32),
             payment_mode,
         )?;
-        Ok(AiClient::OpenAI(client))
+        Ok(Self::with_recorder(AiClient::OpenAI(client), settings))
+    }
+
+    /// Wrap the client in a fixture recorder when `STARK_AI_RECORD_DIR` is set
+    #[cfg(feature = "ai-recorder")]
+    fn with_recorder(client: Self, settings: &AgentSettings) -> Self {
+        match fixtures::AiRecorder::from_env(settings) {
+            Some(recorder) => AiClient::Recording(Box::new(client), Arc::new(recorder)),
+            None => client,
+        }
+    }
+
+    /// Recording is compiled out without the `ai-recorder` feature
+    #[cfg(not(feature = "ai-recorder"))]
+    fn with_recorder(client: Self, _settings: &AgentSettings) -> Self {
+        client
     }
 
     /// Get the archetype ID from agent settings
@@ -244,14 +265,24 @@ impl AiClient {
 
     /// Generate text using the configured provider
     pub async fn generate_text(&self, messages: Vec<Message>) -> Result<String, String> {
-        match self {
+        self.check_breaker()?;
+        let result = match self {
             AiClient::Claude(client) => client.generate_text(messages).await,
             AiClient::OpenAI(client) => client.generate_text(messages).await,
             AiClient::Llama(client) => client.generate_text(messages).await,
             AiClient::Mock(client) => client.next_response()
                 .map(|r| r.content)
                 .map_err(|e| e.message),
-        }
+            #[cfg(feature = "ai-recorder")]
+            AiClient::Recording(inner, recorder) => {
+                let result = Box::pin(inner.generate_text(messages.clone())).await;
+                let response = result.as_ref().map(|text| AiResponse::text(text.clone())).map_err(AiError::new);
+                recorder.record("generate_text", &messages, vec![], response.as_ref());
+                result
+            }
+        };
+        self.record_breaker(result.as_ref().err().map(|e| AiError::new(e.clone())).as_ref());
+        result
     }
 
     /// Generate text and emit x402 payment event if applicable
@@ -262,9 +293,9 @@ impl AiClient {
         broadcaster: &Arc<EventBroadcaster>,
         channel_id: i64,
     ) -> Result<(String, Option<X402PaymentInfo>), String> {
-        match self {
-            AiClient::OpenAI(client) => {
-                let (content, payment) = client.generate_text_with_payment_info(messages).await?;
+        self.check_breaker()?;
+        let result = match self {
+            AiClient::OpenAI(client) => client.generate_text_with_payment_info(messages).await.map(|(content, payment)| {
                 // Emit x402 payment event if payment was made
                 if let Some(ref payment_info) = payment {
                     broadcaster.broadcast(GatewayEvent::x402_payment(
@@ -276,15 +307,24 @@ impl AiClient {
                         payment_info.resource.as_deref(),
                     ));
                 }
-                Ok((content, payment))
-            }
+                (content, payment)
+            }),
             // Other providers don't support x402
-            AiClient::Claude(client) => Ok((client.generate_text(messages).await?, None)),
-            AiClient::Llama(client) => Ok((client.generate_text(messages).await?, None)),
+            AiClient::Claude(client) => client.generate_text(messages).await.map(|content| (content, None)),
+            AiClient::Llama(client) => client.generate_text(messages).await.map(|content| (content, None)),
             AiClient::Mock(client) => client.next_response()
                 .map(|r| (r.content, None))
                 .map_err(|e| e.message),
-        }
+            #[cfg(feature = "ai-recorder")]
+            AiClient::Recording(inner, recorder) => {
+                let result = Box::pin(inner.generate_text_with_events(messages.clone(), broadcaster, channel_id)).await;
+                let response = result.as_ref().map(|(text, _)| AiResponse::text(text.clone())).map_err(AiError::new);
+                recorder.record("generate_text", &messages, vec![], response.as_ref());
+                result
+            }
+        };
+        self.record_breaker(result.as_ref().err().map(|e| AiError::new(e.clone())).as_ref());
+        result
     }
 
     /// Generate response with tool support (Claude, OpenAI, and Llama 3.1+)
@@ -294,7 +334,8 @@ impl AiClient {
         tool_history: Vec<ToolHistoryEntry>,
         tools: Vec<ToolDefinition>,
     ) -> Result<AiResponse, AiError> {
-        match self {
+        self.check_breaker().map_err(|e| AiError::with_status(e, 503))?;
+        let result = match self {
             AiClient::Claude(client) => {
                 // Convert tool history to Claude format
                 let tool_messages = Self::tool_history_to_claude(&tool_history);
@@ -318,22 +359,70 @@ impl AiClient {
                     .map_err(AiError::from)
             }
             AiClient::Mock(client) => client.next_response_traced(messages, tool_history, tools),
+            #[cfg(feature = "ai-recorder")]
+            AiClient::Recording(inner, recorder) => {
+                let tool_names = tools.iter().map(|t| t.name.clone()).collect();
+                let result = Box::pin(inner.generate_with_tools(messages.clone(), tool_history, tools)).await;
+                recorder.record("generate_with_tools", &messages, tool_names, result.as_ref());
+                result
+            }
+        };
+        self.record_breaker(result.as_ref().err());
+        result
+    }
+
+    /// Provider clients go through the AI provider's circuit breaker. Mocks don't,
+    /// and a recorder's inner client already does.
+    fn uses_breaker(&self) -> bool {
+        matches!(self, AiClient::Claude(_) | AiClient::OpenAI(_) | AiClient::Llama(_))
+    }
+
+    fn check_breaker(&self) -> Result<(), String> {
+        if !self.uses_breaker() {
+            return Ok(());
+        }
+        crate::circuit_breaker::check(crate::circuit_breaker::AI_PROVIDER)
+    }
+
+    /// Rejected requests (4xx other than 429) mean the provider is up
+    fn record_breaker(&self, error: Option<&AiError>) {
+        if !self.uses_breaker() {
+            return;
+        }
+        match error {
+            Some(e) if !e.is_client_error() || e.status_code == Some(429) => {
+                crate::circuit_breaker::record_failure(crate::circuit_breaker::AI_PROVIDER, &e.message)
+            }
+            _ => crate::circuit_breaker::record_success(crate::circuit_breaker::AI_PROVIDER),
         }
     }
 
     /// Check if the current provider supports tools
     pub fn supports_tools(&self) -> bool {
+        #[cfg(feature = "ai-recorder")]
+        if let AiClient::Recording(inner, _) = self {
+            return inner.supports_tools();
+        }
         // All providers now support tools
         matches!(self, AiClient::Claude(_) | AiClient::OpenAI(_) | AiClient::Llama(_) | AiClient::Mock(_))
     }
 
     /// Check if the current provider supports extended thinking
     pub fn supports_thinking(&self) -> bool {
+        #[cfg(feature = "ai-recorder")]
+        if let AiClient::Recording(inner, _) = self {
+            return inner.supports_thinking();
+        }
         matches!(self, AiClient::Claude(_))
     }
 
     /// Set the thinking level for Claude models
     pub fn set_thinking_level(&self, level: ThinkingLevel) {
+        #[cfg(feature = "ai-recorder")]
+        if let AiClient::Recording(inner, _) = self {
+            inner.set_thinking_level(level);
+            return;
+        }
         if let AiClient::Claude(client) = self {
             client.set_thinking_level(level);
         }
@@ -352,6 +441,10 @@ impl AiClient {
                 AiClient::Llama(client.with_broadcaster(broadcaster, channel_id))
             }
             AiClient::Mock(_) => self, // Mock doesn't need broadcaster
+            #[cfg(feature = "ai-recorder")]
+            AiClient::Recording(inner, recorder) => {
+                AiClient::Recording(Box::new(inner.with_broadcaster(broadcaster, channel_id)), recorder)
+            }
         }
     }
 
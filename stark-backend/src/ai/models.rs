@@ -0,0 +1,145 @@
+//! Model capability registry
+//!
+//! What each known provider/model can do: context window, output limit, tool
+//! calling, image input and list pricing. Sessions take their context budget
+//! from here instead of a fixed 100k, and `/api/models` shows it to the UI.
+//! Model names are matched by prefix ("claude-sonnet-4-5-20250929" matches
+//! "claude-sonnet-4"); unknown models fall back to their archetype's entry.
+
+use serde::Serialize;
+
+use crate::models::agent_settings::{AgentSettings, DEFAULT_CONTEXT_TOKENS};
+
+/// What a model supports
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ModelCapabilities {
+    pub provider: &'static str,
+    /// Model name, or the prefix shared by its dated versions
+    pub model: &'static str,
+    pub display_name: &'static str,
+    pub context_tokens: i32,
+    pub max_output_tokens: i32,
+    pub supports_tools: bool,
+    pub supports_vision: bool,
+    /// USD per million input tokens, where published
+    pub input_price_per_mtok: Option<f64>,
+    /// USD per million output tokens, where published
+    pub output_price_per_mtok: Option<f64>,
+}
+
+#[allow(clippy::too_many_arguments)]
+const fn model(
+    provider: &'static str,
+    model: &'static str,
+    display_name: &'static str,
+    context_tokens: i32,
+    max_output_tokens: i32,
+    supports_tools: bool,
+    supports_vision: bool,
+    pricing: Option<(f64, f64)>,
+) -> ModelCapabilities {
+    let (input, output) = match pricing {
+        Some((input, output)) => (Some(input), Some(output)),
+        None => (None, None),
+    };
+    ModelCapabilities {
+        provider,
+        model,
+        display_name,
+        context_tokens,
+        max_output_tokens,
+        supports_tools,
+        supports_vision,
+        input_price_per_mtok: input,
+        output_price_per_mtok: output,
+    }
+}
+
+/// Known models. More specific prefixes come first.
+pub const MODELS: &[ModelCapabilities] = &[
+    model("anthropic", "claude-opus-4", "Claude Opus 4", 200_000, 32_000, true, true, Some((15.0, 75.0))),
+    model("anthropic", "claude-sonnet-4", "Claude Sonnet 4", 200_000, 64_000, true, true, Some((3.0, 15.0))),
+    model("anthropic", "claude-sonnet", "Claude Sonnet", 200_000, 64_000, true, true, Some((3.0, 15.0))),
+    model("anthropic", "claude-haiku-4", "Claude Haiku 4.5", 200_000, 64_000, true, true, Some((1.0, 5.0))),
+    model("anthropic", "claude-3-7-sonnet", "Claude 3.7 Sonnet", 200_000, 64_000, true, true, Some((3.0, 15.0))),
+    model("anthropic", "claude-3-5-sonnet", "Claude 3.5 Sonnet", 200_000, 8_192, true, true, Some((3.0, 15.0))),
+    model("anthropic", "claude-3-5-haiku", "Claude 3.5 Haiku", 200_000, 8_192, true, false, Some((0.8, 4.0))),
+    model("openai", "gpt-5-nano", "GPT-5 Nano", 400_000, 128_000, true, true, Some((0.05, 0.4))),
+    model("openai", "gpt-5-mini", "GPT-5 Mini", 400_000, 128_000, true, true, Some((0.25, 2.0))),
+    model("openai", "gpt-5", "GPT-5", 400_000, 128_000, true, true, Some((1.25, 10.0))),
+    model("openai", "gpt-4.1-mini", "GPT-4.1 Mini", 1_047_576, 32_768, true, true, Some((0.4, 1.6))),
+    model("openai", "gpt-4.1", "GPT-4.1", 1_047_576, 32_768, true, true, Some((2.0, 8.0))),
+    model("openai", "gpt-4o-mini", "GPT-4o Mini", 128_000, 16_384, true, true, Some((0.15, 0.6))),
+    model("openai", "gpt-4o", "GPT-4o", 128_000, 16_384, true, true, Some((2.5, 10.0))),
+    model("openai", "o4-mini", "o4-mini", 200_000, 100_000, true, true, Some((1.1, 4.4))),
+    model("openai", "o3", "o3", 200_000, 100_000, true, true, Some((2.0, 8.0))),
+    model("moonshot", "kimi-k2.5", "Kimi K2.5", 256_000, 32_768, true, true, None),
+    model("moonshot", "kimi-turbo", "Kimi K2 Turbo", 256_000, 32_768, true, false, None),
+    model("moonshot", "kimi-k2", "Kimi K2", 256_000, 32_768, true, false, Some((0.6, 2.5))),
+    model("minimax", "minimax-m2", "MiniMax M2", 204_800, 40_000, true, false, Some((0.3, 1.2))),
+    model("meta", "llama-3.3", "Llama 3.3", 128_000, 8_192, true, false, None),
+    model("meta", "llama-3.1", "Llama 3.1", 128_000, 8_192, true, false, None),
+    model("meta", "llama3.1", "Llama 3.1", 128_000, 8_192, true, false, None),
+    model("meta", "llama", "Llama", 8_192, 4_096, false, false, None),
+];
+
+/// Defaults for models the registry doesn't know, by archetype
+fn archetype_default(archetype: &str) -> Option<&'static ModelCapabilities> {
+    let model = match archetype.to_lowercase().as_str() {
+        "claude" | "anthropic" => "claude-sonnet-4",
+        "openai" => "gpt-4o",
+        "kimi" | "moonshot" | "native" | "standard" => "kimi-k2",
+        "minimax" => "minimax-m2",
+        "llama" | "text" | "json" => "llama-3.1",
+        _ => return None,
+    };
+    MODELS.iter().find(|m| m.model == model)
+}
+
+/// Capabilities of a model by name, falling back to the archetype's defaults
+pub fn lookup(model: Option<&str>, archetype: &str) -> Option<&'static ModelCapabilities> {
+    let name = model.map(|m| m.trim().to_lowercase()).filter(|m| !m.is_empty());
+    let by_name = name.as_deref().and_then(|name| {
+        // Router-style names may carry a provider prefix: "anthropic/claude-sonnet-4"
+        let name = name.rsplit('/').next().unwrap_or(name);
+        MODELS.iter().find(|m| name.starts_with(m.model))
+    });
+    by_name.or_else(|| archetype_default(archetype))
+}
+
+/// Capabilities of the model behind agent settings
+pub fn for_settings(settings: &AgentSettings) -> Option<&'static ModelCapabilities> {
+    lookup(settings.model.as_deref(), &settings.model_archetype)
+}
+
+/// The context budget sessions should use with these settings. A budget left
+/// at the default follows the model's window; one set by hand is kept, but
+/// never beyond what the model can take.
+pub fn effective_context_tokens(settings: &AgentSettings) -> i32 {
+    match for_settings(settings) {
+        Some(caps) if settings.max_context_tokens == DEFAULT_CONTEXT_TOKENS => caps.context_tokens,
+        Some(caps) => settings.max_context_tokens.min(caps.context_tokens),
+        None => settings.max_context_tokens,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_and_effective_context() {
+        assert_eq!(lookup(Some("claude-sonnet-4-5-20250929"), "claude").unwrap().model, "claude-sonnet-4");
+        assert_eq!(lookup(Some("openai/gpt-5-mini"), "openai").unwrap().model, "gpt-5-mini");
+        assert_eq!(lookup(Some("MiniMax-M2.5"), "minimax").unwrap().context_tokens, 204_800);
+        assert_eq!(lookup(Some("some-new-model"), "kimi").unwrap().model, "kimi-k2");
+        assert!(lookup(Some("some-new-model"), "unknown").is_none());
+
+        let mut settings = AgentSettings::default();
+        assert_eq!(effective_context_tokens(&settings), 204_800);
+        settings.max_context_tokens = 150_000;
+        assert_eq!(effective_context_tokens(&settings), 150_000);
+        settings.max_context_tokens = 900_000;
+        assert_eq!(effective_context_tokens(&settings), 204_800);
+    }
+}
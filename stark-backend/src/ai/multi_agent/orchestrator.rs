@@ -1,13 +1,19 @@
 //! Simplified orchestrator - manages agent context without mode transitions
 
 use super::tools;
-use super::types::{self, AgentContext, AgentMode};
+use super::types::{self, AgentContext, AgentMode, Clarification, ClarificationRequest};
 use crate::tools::ToolDefinition;
 use serde_json::Value;
 
 /// Maximum iterations before forcing completion
 const MAX_ITERATIONS: u32 = 100;
 
+/// Planner prompt filler when no workflow templates are saved
+const NO_WORKFLOW_TEMPLATES: &str = "No saved workflow templates.";
+
+/// Clarification questions the planner may ask per request before it has to plan
+const MAX_CLARIFICATIONS: usize = 2;
+
 /// The orchestrator manages agent context and tool processing
 pub struct Orchestrator {
     context: AgentContext,
@@ -79,6 +85,7 @@ impl Orchestrator {
         self.context.total_iterations = 0;
         self.context.actual_tool_calls = 0;
         self.context.no_tool_warnings = 0;
+        self.context.repair_prompts = 0;
     }
 
     /// Clear the active skill
@@ -161,9 +168,15 @@ impl Orchestrator {
 
     /// Get the system prompt for task planner mode with available skills
     pub fn get_planner_prompt_with_skills(&self, skills_text: &str) -> String {
+        self.get_planner_prompt_with_skills_and_templates(skills_text, NO_WORKFLOW_TEMPLATES)
+    }
+
+    /// Get the system prompt for task planner mode with available skills and saved workflow templates
+    pub fn get_planner_prompt_with_skills_and_templates(&self, skills_text: &str, templates_text: &str) -> String {
         include_str!("prompts/task_planner.md")
-            .replace("{original_request}", &self.context.original_request)
+            .replace("{original_request}", &self.request_with_clarifications())
             .replace("{available_skills}", skills_text)
+            .replace("{workflow_templates}", templates_text)
             .replace("{available_subtypes}", &Self::generate_subtypes_table())
     }
 
@@ -174,8 +187,9 @@ impl Orchestrator {
         resource_manager: &crate::telemetry::ResourceManager,
     ) -> String {
         resource_manager.resolve_prompt("system_prompt.task_planner")
-            .replace("{original_request}", &self.context.original_request)
+            .replace("{original_request}", &self.request_with_clarifications())
             .replace("{available_skills}", skills_text)
+            .replace("{workflow_templates}", NO_WORKFLOW_TEMPLATES)
             .replace("{available_subtypes}", &Self::generate_subtypes_table())
     }
 
@@ -363,6 +377,9 @@ impl Orchestrator {
             if ch == "twitter" {
                 prompt.push_str("\n\n");
                 prompt.push_str(include_str!("prompts/twitter.md"));
+            } else if ch == "farcaster" {
+                prompt.push_str("\n\n");
+                prompt.push_str(include_str!("prompts/farcaster.md"));
             }
         }
 
@@ -440,7 +457,7 @@ impl Orchestrator {
         let mut summary = String::new();
 
         summary.push_str("## Current Context\n\n");
-        summary.push_str(&format!("**Request**: {}\n\n", self.context.original_request));
+        summary.push_str(&format!("**Request**: {}\n\n", self.request_with_clarifications()));
         if let Some(ref key) = self.context.subtype {
             summary.push_str(&format!(
                 "**Subtype**: {} {}\n\n",
@@ -501,6 +518,45 @@ impl Orchestrator {
         summary
     }
 
+    /// Whether the planner may still ask the user to clarify the request
+    pub fn can_request_clarification(&self) -> bool {
+        self.context.pending_clarification.is_none()
+            && self.context.clarifications.len() < MAX_CLARIFICATIONS
+    }
+
+    /// Park the request on a clarification question
+    pub fn request_clarification(&mut self, question: String, options: Vec<String>) {
+        self.context.pending_clarification = Some(ClarificationRequest { question, options });
+    }
+
+    /// Attach the user's answer to the pending clarification question.
+    /// Returns false if no question was pending.
+    pub fn answer_clarification(&mut self, answer: &str) -> bool {
+        match self.context.pending_clarification.take() {
+            Some(request) => {
+                self.context.clarifications.push(Clarification {
+                    question: request.question,
+                    answer: answer.trim().to_string(),
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The original request followed by the clarifications the user gave for it
+    pub fn request_with_clarifications(&self) -> String {
+        if self.context.clarifications.is_empty() {
+            return self.context.original_request.clone();
+        }
+        let mut request = self.context.original_request.clone();
+        request.push_str("\n\nClarifications from the user:");
+        for c in &self.context.clarifications {
+            request.push_str(&format!("\n- Q: {}\n  A: {}", c.question, c.answer));
+        }
+        request
+    }
+
     /// Clear the waiting_for_user_context after it's been consumed
     pub fn clear_waiting_for_user_context(&mut self) {
         self.context.waiting_for_user_context = None;
@@ -573,15 +629,15 @@ impl Orchestrator {
         &self.context.task_queue
     }
 
-    /// Delete a task by ID. Returns true if the task was found and deleted.
-    /// Also returns whether the deleted task was the current one.
-    pub fn delete_task(&mut self, task_id: u32) -> (bool, bool) {
+    /// Cancel a task by ID. Returns true if the task was found and still open.
+    /// Also returns whether the cancelled task was the current one.
+    pub fn cancel_task(&mut self, task_id: u32) -> (bool, bool) {
         let was_current = self.context.task_queue
             .current_task()
             .map(|t| t.id == task_id)
             .unwrap_or(false);
-        let deleted = self.context.task_queue.delete_task(task_id);
-        (deleted, was_current)
+        let cancelled = self.context.task_queue.cancel_task(task_id);
+        (cancelled, was_current)
     }
 
     /// Get a task by ID
@@ -612,3 +668,32 @@ pub enum ProcessResult {
     /// Error occurred
     Error(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clarification_roundtrip() {
+        let mut orch = Orchestrator::new("swap my ETH".to_string());
+        assert!(orch.can_request_clarification());
+        assert!(!orch.answer_clarification("ignored"));
+
+        orch.request_clarification(
+            "Swap it for which token?".to_string(),
+            vec!["USDC".to_string(), "DAI".to_string()],
+        );
+        assert!(!orch.can_request_clarification());
+        assert!(orch.answer_clarification(" USDC "));
+        assert!(orch.context().pending_clarification.is_none());
+
+        let request = orch.request_with_clarifications();
+        assert!(request.starts_with("swap my ETH"));
+        assert!(request.contains("Q: Swap it for which token?\n  A: USDC"));
+        assert!(orch.get_planner_prompt().contains("A: USDC"));
+
+        orch.request_clarification("On which network?".to_string(), vec![]);
+        orch.answer_clarification("base");
+        assert!(!orch.can_request_clarification(), "capped at MAX_CLARIFICATIONS");
+    }
+}
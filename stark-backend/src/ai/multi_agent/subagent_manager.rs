@@ -12,7 +12,7 @@ use crate::ai::{AiClient, Message, MessageRole, ToolHistoryEntry};
 use crate::db::Database;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::{AgentSettings, MessageRole as DbMessageRole, SessionScope};
+use crate::models::{AgentSettings, MessageRole as DbMessageRole, SessionScope, ToolCallRecord};
 use crate::notes::NoteStore;
 use crate::tools::{ToolContext, ToolDefinition, ToolRegistry};
 use crate::skills::SkillRegistry;
@@ -22,6 +22,7 @@ use serde_json::json;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::sync::OnceLock;
+use std::sync::Weak;
 use tokio::sync::{oneshot, Semaphore};
 use tokio::time::{timeout, Duration};
 
@@ -82,6 +83,8 @@ pub struct SubAgentManager {
     disk_quota: OnceLock<Arc<crate::disk_quota::DiskQuotaManager>>,
     /// Notes store for Obsidian-compatible notes
     notes_store: OnceLock<Arc<NoteStore>>,
+    /// Handle back to this manager for sub-agent tool contexts (nested invoke_skill)
+    self_ref: OnceLock<Weak<SubAgentManager>>,
 }
 
 impl SubAgentManager {
@@ -119,6 +122,7 @@ impl SubAgentManager {
             process_manager: OnceLock::new(),
             disk_quota: OnceLock::new(),
             notes_store: OnceLock::new(),
+            self_ref: OnceLock::new(),
         }
     }
 
@@ -147,6 +151,12 @@ impl SubAgentManager {
         let _ = self.notes_store.set(store);
     }
 
+    /// Let sub-agents spawn through this manager, so a skill run by
+    /// invoke_skill can invoke further skills (call once after Arc wrapping)
+    pub fn set_self_ref(self: &Arc<Self>) {
+        let _ = self.self_ref.set(Arc::downgrade(self));
+    }
+
     /// Generate a unique sub-agent ID
     pub fn generate_id(label: &str) -> String {
         let counter = SUBAGENT_COUNTER.fetch_add(1, Ordering::SeqCst);
@@ -220,6 +230,7 @@ impl SubAgentManager {
         let process_manager = self.process_manager.get().cloned();
         let disk_quota = self.disk_quota.get().cloned();
         let notes_store = self.notes_store.get().cloned();
+        let manager = self.self_ref.get().and_then(Weak::upgrade);
         let active_agents = self.active_agents.clone();
         let last_activity = self.last_activity.clone();
         let subagent_id_for_cleanup = subagent_id.clone();
@@ -254,6 +265,7 @@ impl SubAgentManager {
                 process_manager,
                 disk_quota,
                 notes_store,
+                manager,
                 last_activity.clone(),
             );
 
@@ -346,6 +358,7 @@ impl SubAgentManager {
         process_manager: Option<Arc<crate::execution::ProcessManager>>,
         disk_quota: Option<Arc<crate::disk_quota::DiskQuotaManager>>,
         notes_store: Option<Arc<NoteStore>>,
+        manager: Option<Arc<SubAgentManager>>,
         last_activity: Arc<DashMap<String, chrono::DateTime<chrono::Utc>>>,
     ) -> Result<String, String> {
         log::info!("[SUBAGENT] Starting execution for {}", context.id);
@@ -525,7 +538,8 @@ impl SubAgentManager {
             .with_workspace(workspace_dir)
             .with_broadcaster(broadcaster.clone())
             .with_database(db.clone())
-            .with_subagent_identity(context.id.clone(), context.depth);
+            .with_subagent_identity(context.id.clone(), context.depth)
+            .with_active_skill(context.active_skill.clone());
 
         // Attach optional stores so sub-agent tools work (use_skill, memory_search, web3_tx, etc.)
         if let Some(registry) = skill_registry.clone() {
@@ -546,6 +560,9 @@ impl SubAgentManager {
         if let Some(ns) = notes_store {
             tool_context = tool_context.with_notes_store(ns);
         }
+        if let Some(manager) = manager {
+            tool_context = tool_context.with_subagent_manager(manager);
+        }
 
         // Load API keys from database so sub-agent tools can call external services
         if !parent_channel_safe_mode {
@@ -774,20 +791,26 @@ impl SubAgentManager {
                     "**Tool Call:** {}\n```json\n{}\n```",
                     tool_call.name, params_preview
                 );
-                let _ = db.add_session_message(
+                let _ = db.add_tool_session_message(
                     session.id,
                     DbMessageRole::ToolCall,
                     &tool_call_content,
-                    None,
-                    None,
-                    None,
-                    None,
+                    Some(&tool_call.name),
+                    &ToolCallRecord::call(&tool_call.id, &tool_call.name, &tool_call.arguments),
                 );
 
+                let started = std::time::Instant::now();
                 let result = tool_registry
                     .execute(&tool_call.name, tool_call.arguments.clone(), &tool_context, Some(&tool_config))
                     .await;
 
+                // A skill loaded by the sub-agent bounds its later finance tool calls
+                if tool_call.name == "use_skill" && result.success {
+                    if let Some(name) = result.metadata.as_ref().and_then(|m| m.get("skill_name")).and_then(|v| v.as_str()) {
+                        tool_context.active_skill = Some(name.to_string());
+                    }
+                }
+
                 // Broadcast tool_result event (strip <think> blocks from preview)
                 let cleaned_content = strip_think_blocks(&result.content);
                 let content_preview = if cleaned_content.len() > 500 {
@@ -814,14 +837,17 @@ impl SubAgentManager {
                     "**{}:** {}\n{}",
                     status_label, tool_call.name, content_preview
                 );
-                let _ = db.add_session_message(
+                let _ = db.add_tool_session_message(
                     session.id,
                     DbMessageRole::ToolResult,
                     &tool_result_content,
-                    None,
-                    None,
-                    None,
-                    None,
+                    Some(&tool_call.name),
+                    &ToolCallRecord::result(
+                        &tool_call.id,
+                        &tool_call.name,
+                        result.success,
+                        started.elapsed().as_millis() as i64,
+                    ),
                 );
 
                 // Track say_to_user content so it can be preferred over task_fully_completed summary
@@ -1192,6 +1218,7 @@ impl SubAgentManager {
                     checkpoints: checkpoints_json
                         .and_then(|j| serde_json::from_str(&j).ok())
                         .unwrap_or_default(),
+                    active_skill: None,
                 })
             },
         );
@@ -1255,6 +1282,7 @@ impl SubAgentManager {
                     checkpoints: checkpoints_json
                         .and_then(|j| serde_json::from_str(&j).ok())
                         .unwrap_or_default(),
+                    active_skill: None,
                 })
             })
             .map_err(|e| format!("Failed to execute query: {}", e))?;
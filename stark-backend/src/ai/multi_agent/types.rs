@@ -245,6 +245,8 @@ pub enum TaskStatus {
     Pending,
     InProgress,
     Completed,
+    /// Cancelled by the user before it finished
+    Cancelled,
 }
 
 impl Default for TaskStatus {
@@ -259,6 +261,7 @@ impl std::fmt::Display for TaskStatus {
             TaskStatus::Pending => write!(f, "pending"),
             TaskStatus::InProgress => write!(f, "in_progress"),
             TaskStatus::Completed => write!(f, "completed"),
+            TaskStatus::Cancelled => write!(f, "cancelled"),
         }
     }
 }
@@ -272,19 +275,30 @@ pub struct PlannerTask {
     /// If set, this task auto-completes when the named tool succeeds
     #[serde(default)]
     pub auto_complete_tool: Option<String>,
+    /// The planner marked this task optional ("Optional: ..."): a failure skips it
+    #[serde(default)]
+    pub optional: bool,
 }
 
 impl PlannerTask {
     pub fn new(id: u32, description: String) -> Self {
+        let optional = is_optional_description(&description);
         Self {
             id,
             description,
             status: TaskStatus::Pending,
             auto_complete_tool: None,
+            optional,
         }
     }
 }
 
+/// Whether a task description is marked optional ("Optional: ..." or "(optional) ...")
+pub fn is_optional_description(description: &str) -> bool {
+    let lower = description.trim_start().to_lowercase();
+    lower.starts_with("optional:") || lower.starts_with("(optional)") || lower.starts_with("[optional]")
+}
+
 /// Queue of tasks to be executed
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TaskQueue {
@@ -338,9 +352,13 @@ impl TaskQueue {
         None
     }
 
-    /// Check if all tasks are complete
+    /// Check if all tasks are complete (cancelled tasks count as done)
     pub fn all_complete(&self) -> bool {
-        !self.tasks.is_empty() && self.tasks.iter().all(|t| t.status == TaskStatus::Completed)
+        !self.tasks.is_empty()
+            && self
+                .tasks
+                .iter()
+                .all(|t| matches!(t.status, TaskStatus::Completed | TaskStatus::Cancelled))
     }
 
     /// Get the total number of tasks
@@ -378,6 +396,23 @@ impl TaskQueue {
         }
     }
 
+    /// Cancel a pending or in-progress task by ID, keeping it in the list as
+    /// `cancelled`. Returns true if the task was found and not already finished.
+    /// If the cancelled task was the current one, clears current_task_idx.
+    pub fn cancel_task(&mut self, task_id: u32) -> bool {
+        let Some(idx) = self.tasks.iter().position(|t| t.id == task_id) else {
+            return false;
+        };
+        if matches!(self.tasks[idx].status, TaskStatus::Completed | TaskStatus::Cancelled) {
+            return false;
+        }
+        self.tasks[idx].status = TaskStatus::Cancelled;
+        if self.current_task_idx == Some(idx) {
+            self.current_task_idx = None;
+        }
+        true
+    }
+
     /// Get a task by ID
     pub fn get_task(&self, task_id: u32) -> Option<&PlannerTask> {
         self.tasks.iter().find(|t| t.id == task_id)
@@ -409,6 +444,7 @@ impl TaskQueue {
         "say_to_user",
         "task_fully_completed",
         "define_tasks",
+        "request_clarification",
         "set_agent_subtype",
         "add_task",
         "ask_user",
@@ -416,7 +452,9 @@ impl TaskQueue {
         "spawn_subagents",
         "subagent_status",
         "use_skill",
+        "invoke_skill",
         "manage_skills",
+        "fetch_full_output",
     ];
 
     /// Create a task queue with auto-complete tool matching.
@@ -643,6 +681,19 @@ mod task_queue_tests {
         let json = r#"{"id": 1, "description": "test", "status": "pending"}"#;
         let task: PlannerTask = serde_json::from_str(json).unwrap();
         assert_eq!(task.auto_complete_tool, None);
+        assert!(!task.optional);
+    }
+
+    #[test]
+    fn test_optional_tasks() {
+        let queue = TaskQueue::from_descriptions(vec![
+            "Swap 1 ETH to USDC".to_string(),
+            "Optional: post the swap on Twitter".to_string(),
+            " (optional) save a note".to_string(),
+        ]);
+        assert!(!queue.tasks[0].optional);
+        assert!(queue.tasks[1].optional);
+        assert!(queue.tasks[2].optional);
     }
 }
 
@@ -736,6 +787,10 @@ pub struct AgentContext {
     #[serde(default)]
     pub no_tool_warnings: u32,
 
+    /// Failed calls sent back to the agent for parameter repair this turn
+    #[serde(default)]
+    pub repair_prompts: u32,
+
     /// Context saved when waiting for user response (e.g., from ask_user tool).
     /// Contains a summary of what tool calls were made before asking the user,
     /// so the AI can continue where it left off when the user responds.
@@ -760,6 +815,30 @@ pub struct AgentContext {
     /// `assistant_skilled`/`assistant_director`.
     #[serde(default)]
     pub is_hook_session: bool,
+
+    /// Clarification question the planner is waiting on (the execution is parked until the user answers)
+    #[serde(default)]
+    pub pending_clarification: Option<ClarificationRequest>,
+
+    /// Clarifications the user has given for this request, in order
+    #[serde(default)]
+    pub clarifications: Vec<Clarification>,
+}
+
+/// A clarification question the planner asked instead of guessing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClarificationRequest {
+    pub question: String,
+    /// Suggested answers, offered as quick replies
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+/// A clarification question together with the user's answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Clarification {
+    pub question: String,
+    pub answer: String,
 }
 
 /// Active skill context that persists across turns
@@ -957,6 +1036,9 @@ pub struct SubAgentContext {
     /// Worker checkpoints for overflow recovery
     #[serde(default)]
     pub checkpoints: Vec<WorkerCheckpoint>,
+    /// Skill whose finance grants bound this sub-agent's tool calls
+    #[serde(default)]
+    pub active_skill: Option<String>,
 }
 
 impl SubAgentContext {
@@ -993,6 +1075,7 @@ impl SubAgentContext {
             mode: SubAgentMode::Standard,
             parent_context_snapshot: None,
             checkpoints: Vec::new(),
+            active_skill: None,
         }
     }
 
@@ -1032,6 +1115,12 @@ impl SubAgentContext {
         self
     }
 
+    /// Set the skill whose finance grants apply (inherited from the spawner)
+    pub fn with_active_skill(mut self, skill_name: Option<String>) -> Self {
+        self.active_skill = skill_name;
+        self
+    }
+
     /// Set parent sub-agent identity (for recursive sub-agent tracking)
     /// depth is set to parent_depth + 1
     pub fn with_parent_subagent(mut self, parent_id: String, parent_depth: u32) -> Self {
@@ -0,0 +1,364 @@
+//! AI spending quotas
+//!
+//! Every LLM call the dispatcher makes is metered into `ai_usage` by a
+//! [`UsageMeter`]: estimated tokens, and USD from the x402 payment or, when
+//! none was made, the model preset's price. Quotas cap that usage per day or
+//! month (server-local), for the whole instance (the tenant) or for one
+//! channel, in tokens, USD or both. Before each execution [`check`] looks at
+//! every quota that applies:
+//!
+//! - from [`DEGRADE_AT`] of a limit the execution runs on a cheaper model
+//!   (the quota's fallback preset, else the cheapest cheaper preset)
+//! - at the limit the execution is refused until the period rolls over
+//!
+//! The owner is told (web UI event and owner chat) the first time a quota
+//! crosses each of [`ALERT_PERCENTS`] in a period.
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, TimeZone, Utc};
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use crate::ai::Message;
+use crate::ai_endpoint_config::{self, AiEndpointPreset};
+use crate::channels::outbound;
+use crate::context::estimate_tokens;
+use crate::db::tables::ai_usage::AiQuota;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::models::AgentSettings;
+use crate::x402::X402PaymentInfo;
+
+/// Share of a limit from which executions use a cheaper model
+pub const DEGRADE_AT: f64 = 0.8;
+
+/// Thresholds (percent of a limit) the owner is told about
+pub const ALERT_PERCENTS: [i64; 2] = [80, 100];
+
+/// x402 prices are in micro-USDC
+const MICRO_USD: f64 = 1_000_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Daily,
+    Monthly,
+}
+
+impl Period {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "daily" => Some(Self::Daily),
+            "monthly" => Some(Self::Monthly),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Monthly => "monthly",
+        }
+    }
+
+    /// Start of the current period and its key ("2026-10-16" / "2026-10")
+    pub fn current(&self, now: DateTime<Local>) -> (DateTime<Utc>, String) {
+        let (first_day, key) = match self {
+            Self::Daily => (now.date_naive(), now.format("%Y-%m-%d").to_string()),
+            Self::Monthly => (
+                now.date_naive().with_day(1).unwrap_or(now.date_naive()),
+                now.format("%Y-%m").to_string(),
+            ),
+        };
+        let start = Local
+            .from_local_datetime(&first_day.and_time(NaiveTime::MIN))
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_else(|| now.with_timezone(&Utc));
+        (start, key)
+    }
+}
+
+/// Highest share of either limit used (1.0 = at the limit)
+pub fn used_fraction(tokens: i64, cost_usd: f64, max_tokens: Option<i64>, max_usd: Option<f64>) -> f64 {
+    let by_tokens = max_tokens.filter(|m| *m > 0).map(|m| tokens as f64 / m as f64);
+    let by_usd = max_usd.filter(|m| *m > 0.0).map(|m| cost_usd / m);
+    by_tokens.into_iter().chain(by_usd).fold(0.0, f64::max)
+}
+
+/// A quota with its usage in the current period
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaStatus {
+    #[serde(flatten)]
+    pub quota: AiQuota,
+    pub period_start: DateTime<Utc>,
+    #[serde(skip)]
+    pub period_key: String,
+    pub used_tokens: i64,
+    pub used_usd: f64,
+    pub used_fraction: f64,
+}
+
+impl QuotaStatus {
+    pub fn load(db: &Database, quota: AiQuota, now: DateTime<Local>) -> SqliteResult<Self> {
+        let period = Period::parse(&quota.period).unwrap_or(Period::Monthly);
+        let (period_start, period_key) = period.current(now);
+        let used = db.ai_usage_since(quota.channel_id, period_start)?;
+        Ok(Self {
+            used_fraction: used_fraction(used.tokens, used.cost_usd, quota.max_tokens, quota.max_usd),
+            quota,
+            period_start,
+            period_key,
+            used_tokens: used.tokens,
+            used_usd: used.cost_usd,
+        })
+    }
+
+    /// e.g. "the daily AI budget of channel 3 is 82% used (41000/50000 tokens)"
+    pub fn describe(&self) -> String {
+        let scope = match self.quota.channel_id {
+            Some(id) => format!("channel {}", id),
+            None => "this instance".to_string(),
+        };
+        let mut limits = Vec::new();
+        if let Some(max) = self.quota.max_tokens {
+            limits.push(format!("{}/{} tokens", self.used_tokens, max));
+        }
+        if let Some(max) = self.quota.max_usd {
+            limits.push(format!("${:.2}/${:.2}", self.used_usd, max));
+        }
+        format!(
+            "the {} AI budget of {} is {:.0}% used ({})",
+            self.quota.period,
+            scope,
+            self.used_fraction * 100.0,
+            limits.join(", ")
+        )
+    }
+}
+
+/// What an execution may do
+#[derive(Debug, Clone, PartialEq)]
+pub enum Budget {
+    Ok,
+    /// Run on this preset instead of the configured model
+    Degrade { model: String, reason: String },
+    /// Don't run
+    Exhausted(String),
+}
+
+/// The verdict for one execution, plus threshold alerts to send the owner
+#[derive(Debug, Clone)]
+pub struct BudgetCheck {
+    pub budget: Budget,
+    pub alerts: Vec<String>,
+}
+
+/// The cheapest preset that costs less than `current`, if `current` has a known price
+pub fn cheaper_preset(presets: &[(String, AiEndpointPreset)], current: Option<&str>) -> Option<String> {
+    let current_cost = presets.iter().find(|(key, _)| Some(key.as_str()) == current)?.1.x402_cost?;
+    presets
+        .iter()
+        .filter_map(|(key, preset)| preset.x402_cost.map(|cost| (cost, key)))
+        .filter(|(cost, _)| *cost < current_cost)
+        .min()
+        .map(|(_, key)| key.clone())
+}
+
+/// Check every quota that applies to an execution on `channel_id`. Fails
+/// open: quotas that can't be read don't block anything.
+pub fn check(db: &Database, channel_id: i64, settings: &AgentSettings) -> BudgetCheck {
+    let quotas = match db.ai_quotas_for_channel(channel_id) {
+        Ok(quotas) => quotas,
+        Err(e) => {
+            log::warn!("[AI_QUOTA] Failed to load quotas for channel {}: {}", channel_id, e);
+            return BudgetCheck { budget: Budget::Ok, alerts: Vec::new() };
+        }
+    };
+    let now = Local::now();
+    let mut budget = Budget::Ok;
+    let mut alerts = Vec::new();
+
+    for quota in quotas {
+        let status = match QuotaStatus::load(db, quota, now) {
+            Ok(status) => status,
+            Err(e) => {
+                log::warn!("[AI_QUOTA] Failed to read usage: {}", e);
+                continue;
+            }
+        };
+        let percent = (status.used_fraction * 100.0).floor() as i64;
+        if let Some(threshold) = ALERT_PERCENTS.iter().rev().find(|t| percent >= **t) {
+            if db.mark_ai_quota_alerted(status.quota.id, &status.period_key, *threshold).unwrap_or(false) {
+                let consequence = if status.used_fraction >= 1.0 {
+                    "new requests are refused until it resets"
+                } else {
+                    "switching to a cheaper model"
+                };
+                alerts.push(format!("{} — {}", status.describe(), consequence));
+            }
+        }
+
+        if status.used_fraction >= 1.0 {
+            budget = Budget::Exhausted(status.describe());
+        } else if status.used_fraction >= DEGRADE_AT && budget == Budget::Ok {
+            let model = status.quota.fallback_model.clone().or_else(|| {
+                cheaper_preset(&ai_endpoint_config::list_ai_endpoints(), settings.endpoint_name.as_deref())
+            });
+            if let Some(model) = model.filter(|m| Some(m.as_str()) != settings.endpoint_name.as_deref()) {
+                budget = Budget::Degrade { model, reason: status.describe() };
+            }
+        }
+    }
+    BudgetCheck { budget, alerts }
+}
+
+/// Point `settings` at a preset; false if the preset is unknown
+pub fn degrade(settings: &mut AgentSettings, model_key: &str) -> bool {
+    let Some(preset) = ai_endpoint_config::get_ai_endpoint(model_key) else {
+        return false;
+    };
+    settings.endpoint_name = Some(model_key.to_string());
+    settings.endpoint = preset.endpoint;
+    settings.model_archetype = preset.model_archetype;
+    settings.model = preset.model;
+    true
+}
+
+/// Tell the owner about crossed thresholds
+pub async fn notify_owner(db: &Database, broadcaster: &EventBroadcaster, channel_id: i64, alerts: &[String]) {
+    for alert in alerts {
+        log::warn!("[AI_QUOTA] {}", alert);
+        broadcaster.broadcast(GatewayEvent::custom(
+            "ai_quota.threshold",
+            serde_json::json!({ "channel_id": channel_id, "message": alert }),
+        ));
+    }
+    if alerts.is_empty() {
+        return;
+    }
+    if let Some((owner_channel, chat_id)) = db.get_bot_settings().ok().and_then(|s| s.owner_chat()) {
+        let text = format!("**AI budget**\n- {}", alerts.join("\n- "));
+        if let Err(e) = outbound::send_proactive(db, owner_channel, &chat_id, &text, "ai_quota").await {
+            log::warn!("[AI_QUOTA] Failed to notify the owner: {}", e);
+        }
+    }
+}
+
+/// Records the LLM calls of one execution
+#[derive(Debug, Clone)]
+pub struct UsageMeter {
+    pub channel_id: i64,
+    pub session_id: Option<i64>,
+    /// Preset key, else model name
+    pub model: String,
+    /// The preset's price per call, used when no x402 payment reports one
+    pub usd_per_call: Option<f64>,
+}
+
+impl UsageMeter {
+    pub fn new(channel_id: i64, session_id: Option<i64>, settings: &AgentSettings) -> Self {
+        let preset = settings.endpoint_name.as_deref().and_then(ai_endpoint_config::get_ai_endpoint);
+        Self {
+            channel_id,
+            session_id,
+            model: settings
+                .endpoint_name
+                .clone()
+                .or_else(|| settings.model.clone())
+                .unwrap_or_else(|| settings.endpoint.clone()),
+            usd_per_call: preset.and_then(|p| p.x402_cost).map(|c| c as f64 / MICRO_USD),
+        }
+    }
+
+    /// The same execution, switched to another preset
+    pub fn for_preset(&self, model_key: &str) -> Self {
+        Self {
+            model: model_key.to_string(),
+            usd_per_call: ai_endpoint_config::get_ai_endpoint(model_key)
+                .and_then(|p| p.x402_cost)
+                .map(|c| c as f64 / MICRO_USD),
+            ..self.clone()
+        }
+    }
+
+    pub fn record(&self, db: &Database, input_tokens: i64, output_tokens: i64, payment: Option<&X402PaymentInfo>) {
+        let paid = payment
+            .filter(|p| p.asset.eq_ignore_ascii_case("USDC"))
+            .and_then(|p| p.amount.parse::<u64>().ok())
+            .map(|amount| amount as f64 / MICRO_USD);
+        let cost = paid.or(self.usd_per_call).unwrap_or(0.0);
+        if let Err(e) =
+            db.record_ai_usage(self.channel_id, self.session_id, &self.model, input_tokens, output_tokens, cost)
+        {
+            log::warn!("[AI_QUOTA] Failed to record usage: {}", e);
+        }
+    }
+}
+
+/// Estimated prompt tokens: the messages plus anything else sent (tool history, tool schemas)
+pub fn estimate_input(messages: &[Message], extra: &impl Serialize) -> i64 {
+    let extra = serde_json::to_string(extra).unwrap_or_default();
+    messages.iter().map(|m| estimate_tokens(&m.content) as i64).sum::<i64>() + estimate_tokens(&extra) as i64
+}
+
+/// Estimated completion tokens: the text plus any tool calls
+pub fn estimate_output(content: &str, extra: &impl Serialize) -> i64 {
+    let extra = serde_json::to_string(extra).unwrap_or_default();
+    estimate_tokens(content) as i64 + estimate_tokens(&extra) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(cost: Option<u64>) -> AiEndpointPreset {
+        AiEndpointPreset {
+            display_name: String::new(),
+            endpoint: String::new(),
+            model_archetype: "kimi".to_string(),
+            model: None,
+            x402_cost: cost,
+        }
+    }
+
+    #[test]
+    fn test_quota_fractions_periods_and_fallbacks() {
+        assert_eq!(used_fraction(500, 0.0, Some(1000), None), 0.5);
+        assert_eq!(used_fraction(500, 9.0, Some(1000), Some(10.0)), 0.9);
+        assert_eq!(used_fraction(500, 9.0, None, None), 0.0);
+
+        let now = Local.with_ymd_and_hms(2026, 10, 16, 15, 30, 0).unwrap();
+        let (start, key) = Period::Monthly.current(now);
+        assert_eq!(key, "2026-10");
+        assert_eq!(start.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(), "2026-10-01 00:00");
+        assert_eq!(Period::Daily.current(now).1, "2026-10-16");
+        assert_eq!(Period::parse(" Daily "), Some(Period::Daily));
+        assert_eq!(Period::parse("weekly"), None);
+
+        let presets = vec![
+            ("claude-sonnet".to_string(), preset(Some(30000))),
+            ("kimi-k2.5".to_string(), preset(Some(10000))),
+            ("minimax".to_string(), preset(Some(1000))),
+            ("custom".to_string(), preset(None)),
+        ];
+        assert_eq!(cheaper_preset(&presets, Some("claude-sonnet")).as_deref(), Some("minimax"));
+        assert_eq!(cheaper_preset(&presets, Some("minimax")), None);
+        assert_eq!(cheaper_preset(&presets, Some("custom")), None);
+
+        let db = Database::new(":memory:").unwrap();
+        let quota = db.upsert_ai_quota(Some(3), "daily", Some(1000), None, None).unwrap();
+        db.upsert_ai_quota(None, "monthly", None, Some(5.0), None).unwrap();
+        db.upsert_ai_quota(Some(4), "daily", Some(10), None, None).unwrap();
+        assert_eq!(db.ai_quotas_for_channel(3).unwrap().len(), 2);
+
+        db.record_ai_usage(3, None, "minimax", 700, 150, 0.001).unwrap();
+        db.record_ai_usage(4, None, "minimax", 10, 10, 0.001).unwrap();
+        let status = QuotaStatus::load(&db, quota.clone(), Local::now()).unwrap();
+        assert_eq!((status.used_tokens, status.used_fraction), (850, 0.85));
+        assert!(db.mark_ai_quota_alerted(quota.id, "2026-10-16", 80).unwrap());
+        assert!(!db.mark_ai_quota_alerted(quota.id, "2026-10-16", 80).unwrap());
+        assert!(db.mark_ai_quota_alerted(quota.id, "2026-10-16", 100).unwrap());
+        assert!(db.mark_ai_quota_alerted(quota.id, "2026-10-17", 80).unwrap());
+        assert_eq!(db.ai_usage_since(None, status.period_start).unwrap().calls, 2);
+    }
+}
@@ -0,0 +1,318 @@
+//! Alert evaluation and action execution.
+//!
+//! The engine observes each rule's condition against live state (telemetry
+//! spans, module health, disk quota), and when a condition triggers outside
+//! the rule's cooldown it runs the rule's actions and records an alert event.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+
+use super::types::{AlertAction, AlertCondition, AlertRule};
+use crate::db::Database;
+use crate::disk_quota::DiskQuotaManager;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::telemetry::span::SpanType;
+use crate::tools::{ToolContext, ToolRegistry};
+
+/// Result of observing a single condition
+#[derive(Debug, Clone)]
+pub struct Observation {
+    /// The measured value (rate, count, or percentage)
+    pub value: f64,
+    pub triggered: bool,
+    pub message: String,
+}
+
+/// Compute a failure rate and whether it crosses `threshold`.
+/// Below `min_samples` calls the rate is reported but never triggers.
+pub fn evaluate_failure_rate(total: i64, failed: i64, threshold: f64, min_samples: i64) -> (f64, bool) {
+    if total <= 0 {
+        return (0.0, false);
+    }
+    let rate = failed as f64 / total as f64;
+    (rate, total >= min_samples && rate > threshold)
+}
+
+/// Whether a rule that last fired at `last_fired_at` is still cooling down.
+pub fn is_in_cooldown(last_fired_at: Option<&str>, cooldown_minutes: i64, now: DateTime<Utc>) -> bool {
+    let Some(last) = last_fired_at else {
+        return false;
+    };
+    match DateTime::parse_from_rfc3339(last) {
+        Ok(ts) => now - ts.with_timezone(&Utc) < Duration::minutes(cooldown_minutes),
+        Err(_) => false,
+    }
+}
+
+pub struct AlertEngine {
+    db: Arc<Database>,
+    tool_registry: Arc<ToolRegistry>,
+    broadcaster: Arc<EventBroadcaster>,
+    disk_quota: Option<Arc<DiskQuotaManager>>,
+}
+
+impl AlertEngine {
+    pub fn new(
+        db: Arc<Database>,
+        tool_registry: Arc<ToolRegistry>,
+        broadcaster: Arc<EventBroadcaster>,
+        disk_quota: Option<Arc<DiskQuotaManager>>,
+    ) -> Self {
+        Self { db, tool_registry, broadcaster, disk_quota }
+    }
+
+    /// Observe a condition against current state
+    pub async fn observe(&self, condition: &AlertCondition) -> Result<Observation, String> {
+        match condition {
+            AlertCondition::ToolFailureRate { threshold, tool, window_minutes, min_samples } => {
+                let since = Utc::now() - Duration::minutes(*window_minutes);
+                let (total, failed) = self
+                    .db
+                    .count_spans_since(SpanType::ToolCall, tool.as_deref(), since)
+                    .map_err(|e| format!("Database error: {}", e))?;
+                let (rate, triggered) = evaluate_failure_rate(total, failed, *threshold, *min_samples);
+                let scope = tool.as_deref().map(|t| format!("'{}'", t)).unwrap_or_else(|| "all tools".to_string());
+                Ok(Observation {
+                    value: rate,
+                    triggered,
+                    message: format!(
+                        "Tool failure rate for {} is {:.0}% ({}/{} calls in the last {} min, threshold {:.0}%)",
+                        scope, rate * 100.0, failed, total, window_minutes, threshold * 100.0
+                    ),
+                })
+            }
+            AlertCondition::ProviderErrors { threshold, window_minutes } => {
+                let since = Utc::now() - Duration::minutes(*window_minutes);
+                let (_total, failed) = self
+                    .db
+                    .count_spans_since(SpanType::LlmCall, None, since)
+                    .map_err(|e| format!("Database error: {}", e))?;
+                Ok(Observation {
+                    value: failed as f64,
+                    triggered: failed > *threshold,
+                    message: format!(
+                        "{} AI provider error(s) in the last {} min (threshold {})",
+                        failed, window_minutes, threshold
+                    ),
+                })
+            }
+            AlertCondition::ModuleStalled { module } => {
+                // Disabled modules are expected to be offline
+                if !self.db.is_module_enabled(module).unwrap_or(false) {
+                    return Ok(Observation {
+                        value: 0.0,
+                        triggered: false,
+                        message: format!("Module '{}' is disabled", module),
+                    });
+                }
+                let healthy = check_module_health(module).await;
+                Ok(Observation {
+                    value: if healthy { 0.0 } else { 1.0 },
+                    triggered: !healthy,
+                    message: if healthy {
+                        format!("Module '{}' is healthy", module)
+                    } else {
+                        format!("Module '{}' is enabled but its service is not responding", module)
+                    },
+                })
+            }
+            AlertCondition::DiskQuota { threshold_percent } => {
+                let Some(ref dq) = self.disk_quota else {
+                    return Ok(Observation {
+                        value: 0.0,
+                        triggered: false,
+                        message: "Disk quota is disabled".to_string(),
+                    });
+                };
+                let pct = dq.usage_percentage();
+                Ok(Observation {
+                    value: pct as f64,
+                    triggered: pct >= *threshold_percent,
+                    message: format!("Disk usage at {}% ({})", pct, dq.status_line()),
+                })
+            }
+        }
+    }
+
+    /// Evaluate all enabled rules once. Returns the number of rules that fired.
+    pub async fn run_once(&self) -> usize {
+        let rules = match self.db.list_enabled_alert_rules() {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("[ALERTS] Failed to load rules: {}", e);
+                return 0;
+            }
+        };
+
+        let now = Utc::now();
+        let mut fired = 0;
+        for rule in &rules {
+            if is_in_cooldown(rule.last_fired_at.as_deref(), rule.cooldown_minutes, now) {
+                continue;
+            }
+            let observation = match self.observe(&rule.condition).await {
+                Ok(o) => o,
+                Err(e) => {
+                    log::warn!("[ALERTS] Rule '{}' evaluation failed: {}", rule.name, e);
+                    continue;
+                }
+            };
+            if !observation.triggered {
+                continue;
+            }
+
+            log::warn!("[ALERTS] Rule '{}' fired: {}", rule.name, observation.message);
+            let actions_taken = self.fire(rule, &observation).await;
+            if let Err(e) = self.db.record_alert_event(rule, &observation.message, observation.value, &actions_taken) {
+                log::error!("[ALERTS] Failed to record alert event for '{}': {}", rule.name, e);
+            }
+            fired += 1;
+        }
+        fired
+    }
+
+    /// Run a rule's actions. Returns a description of each action taken.
+    async fn fire(&self, rule: &AlertRule, observation: &Observation) -> Vec<String> {
+        let text = format!("🚨 Alert '{}': {}", rule.name, observation.message);
+        let mut taken = Vec::new();
+
+        for action in &rule.actions {
+            match action {
+                AlertAction::NotifyChannel { channel, platform } => {
+                    self.broadcaster.broadcast(GatewayEvent::custom(
+                        "alert.fired",
+                        json!({
+                            "rule_id": rule.id,
+                            "rule_name": rule.name,
+                            "condition": rule.condition.kind(),
+                            "value": observation.value,
+                            "message": observation.message,
+                        }),
+                    ));
+                    match channel {
+                        Some(ch) => {
+                            let ctx = ToolContext::new().with_database(self.db.clone());
+                            let mut params = json!({ "channel": ch, "message": text });
+                            if let Some(p) = platform {
+                                params["platform"] = json!(p);
+                            }
+                            let result = crate::tools::timeouts::execute(
+                                &self.db,
+                                &self.tool_registry,
+                                "agent_send",
+                                params,
+                                &ctx,
+                                None,
+                            )
+                            .await;
+                            if result.success {
+                                taken.push(format!("notified {}", ch));
+                            } else {
+                                log::warn!("[ALERTS] Failed to notify {}: {}", ch, result.content);
+                                taken.push(format!("notify {} failed", ch));
+                            }
+                        }
+                        None => taken.push("notified web".to_string()),
+                    }
+                }
+                AlertAction::DisableTool { tool } => {
+                    let Some(tool_name) = tool.as_deref().or(rule.condition.target_tool()) else {
+                        continue;
+                    };
+                    match self.disable_tool(tool_name) {
+                        Ok(true) => taken.push(format!("disabled tool {}", tool_name)),
+                        Ok(false) => taken.push(format!("tool {} already disabled", tool_name)),
+                        Err(e) => {
+                            log::error!("[ALERTS] Failed to disable tool {}: {}", tool_name, e);
+                            taken.push(format!("disable {} failed", tool_name));
+                        }
+                    }
+                }
+                AlertAction::CreateMemory { importance } => {
+                    let today = Utc::now().format("%Y-%m-%d").to_string();
+                    match self.db.insert_memory(
+                        "daily_log",
+                        &text,
+                        Some("system"),
+                        Some("alert"),
+                        importance.unwrap_or(6).clamp(1, 10),
+                        None,
+                        None,
+                        None,
+                        None,
+                        Some("alert_rule"),
+                        Some(&today),
+                        None,
+                    ) {
+                        Ok(id) => taken.push(format!("created memory {}", id)),
+                        Err(e) => {
+                            log::error!("[ALERTS] Failed to create memory: {}", e);
+                            taken.push("create memory failed".to_string());
+                        }
+                    }
+                }
+            }
+        }
+        taken
+    }
+
+    /// Add a tool to the global deny list. Returns false if it was already denied.
+    fn disable_tool(&self, tool_name: &str) -> Result<bool, String> {
+        let mut config = self
+            .db
+            .get_global_tool_config()
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default();
+        if config.deny_list.iter().any(|t| t == tool_name) {
+            return Ok(false);
+        }
+        config.deny_list.push(tool_name.to_string());
+        self.db.save_tool_config(&config).map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+}
+
+/// Probe a module's `/rpc/status` endpoint.
+async fn check_module_health(module_name: &str) -> bool {
+    let registry = crate::modules::ModuleRegistry::new();
+    let Some(module) = registry.get(module_name) else {
+        return false;
+    };
+    let url = format!("{}/rpc/status", module.service_url());
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(3))
+        .build()
+        .unwrap_or_default();
+    matches!(client.get(&url).send().await, Ok(resp) if resp.status().is_success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_rate_respects_min_samples() {
+        assert_eq!(evaluate_failure_rate(0, 0, 0.5, 5), (0.0, false));
+        // 3/4 failed but below min_samples
+        let (rate, triggered) = evaluate_failure_rate(4, 3, 0.5, 5);
+        assert!((rate - 0.75).abs() < f64::EPSILON);
+        assert!(!triggered);
+        assert!(evaluate_failure_rate(10, 6, 0.5, 5).1);
+        // Equal to threshold does not trigger
+        assert!(!evaluate_failure_rate(10, 5, 0.5, 5).1);
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let now = Utc::now();
+        let recent = (now - Duration::minutes(5)).to_rfc3339();
+        let old = (now - Duration::minutes(90)).to_rfc3339();
+        assert!(!is_in_cooldown(None, 30, now));
+        assert!(is_in_cooldown(Some(&recent), 30, now));
+        assert!(!is_in_cooldown(Some(&old), 30, now));
+        assert!(!is_in_cooldown(Some("garbage"), 30, now));
+    }
+}
@@ -0,0 +1,44 @@
+//! Alerting rules engine for operational events
+//!
+//! Users define rules (via `/api/rules`) pairing a condition over internal
+//! state — tool failure rate, AI provider errors, a stalled module service,
+//! disk quota usage — with one or more actions: notify a channel, disable a
+//! tool, or create a memory. A lightweight background worker evaluates all
+//! enabled rules on a fixed interval.
+
+pub mod engine;
+pub mod types;
+
+pub use engine::AlertEngine;
+
+use std::sync::Arc;
+
+/// Configuration for the background alert worker.
+pub struct AlertWorkerConfig {
+    /// Interval between evaluation passes in seconds (default: 60).
+    pub interval_secs: u64,
+}
+
+impl Default for AlertWorkerConfig {
+    fn default() -> Self {
+        Self { interval_secs: 60 }
+    }
+}
+
+/// Spawn the background worker that evaluates alert rules.
+pub fn spawn_alert_worker(
+    engine: Arc<AlertEngine>,
+    config: AlertWorkerConfig,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(config.interval_secs));
+        interval.tick().await; // skip immediate tick
+        loop {
+            interval.tick().await;
+            let fired = engine.run_once().await;
+            if fired > 0 {
+                log::info!("[ALERTS] Evaluation pass complete: {} rule(s) fired", fired);
+            }
+        }
+    })
+}
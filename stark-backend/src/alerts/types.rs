@@ -0,0 +1,264 @@
+//! Alert rule types — conditions, actions, and fired-event records.
+
+use serde::{Deserialize, Serialize};
+
+fn default_window_minutes() -> i64 {
+    15
+}
+
+fn default_min_samples() -> i64 {
+    5
+}
+
+fn default_module() -> String {
+    "wallet_monitor".to_string()
+}
+
+/// A condition evaluated against internal operational state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// Fraction of failed tool calls in the window exceeds `threshold` (0.0–1.0).
+    /// Optionally scoped to a single tool.
+    ToolFailureRate {
+        threshold: f64,
+        #[serde(default)]
+        tool: Option<String>,
+        #[serde(default = "default_window_minutes")]
+        window_minutes: i64,
+        /// Minimum number of calls before the rate is considered meaningful
+        #[serde(default = "default_min_samples")]
+        min_samples: i64,
+    },
+    /// Number of failed LLM calls in the window exceeds `threshold`.
+    ProviderErrors {
+        threshold: i64,
+        #[serde(default = "default_window_minutes")]
+        window_minutes: i64,
+    },
+    /// A module service (wallet_monitor by default) is enabled but not responding.
+    ModuleStalled {
+        #[serde(default = "default_module")]
+        module: String,
+    },
+    /// Disk quota usage is at or above `threshold_percent`.
+    DiskQuota { threshold_percent: u64 },
+}
+
+impl AlertCondition {
+    /// Short machine-readable key (matches the serde tag)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AlertCondition::ToolFailureRate { .. } => "tool_failure_rate",
+            AlertCondition::ProviderErrors { .. } => "provider_errors",
+            AlertCondition::ModuleStalled { .. } => "module_stalled",
+            AlertCondition::DiskQuota { .. } => "disk_quota",
+        }
+    }
+
+    /// Validate thresholds and windows. Returns a human-readable error on failure.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            AlertCondition::ToolFailureRate { threshold, window_minutes, min_samples, .. } => {
+                if !(0.0..=1.0).contains(threshold) {
+                    return Err("tool_failure_rate threshold must be between 0.0 and 1.0".to_string());
+                }
+                if *window_minutes <= 0 {
+                    return Err("window_minutes must be positive".to_string());
+                }
+                if *min_samples < 1 {
+                    return Err("min_samples must be at least 1".to_string());
+                }
+            }
+            AlertCondition::ProviderErrors { threshold, window_minutes } => {
+                if *threshold < 0 {
+                    return Err("provider_errors threshold must be non-negative".to_string());
+                }
+                if *window_minutes <= 0 {
+                    return Err("window_minutes must be positive".to_string());
+                }
+            }
+            AlertCondition::ModuleStalled { module } => {
+                if module.trim().is_empty() {
+                    return Err("module_stalled requires a module name".to_string());
+                }
+            }
+            AlertCondition::DiskQuota { threshold_percent } => {
+                if *threshold_percent == 0 || *threshold_percent > 100 {
+                    return Err("disk_quota threshold_percent must be between 1 and 100".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The tool this condition is scoped to, if any (used as the default target
+    /// for `disable_tool` actions).
+    pub fn target_tool(&self) -> Option<&str> {
+        match self {
+            AlertCondition::ToolFailureRate { tool, .. } => tool.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// An action taken when a rule fires.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertAction {
+    /// Broadcast to the web UI and, if `channel` is set, post to that external
+    /// chat (Telegram chat ID, Discord channel ID, Slack channel) via agent_send.
+    NotifyChannel {
+        #[serde(default)]
+        channel: Option<String>,
+        #[serde(default)]
+        platform: Option<String>,
+    },
+    /// Add a tool to the global deny list. Defaults to the condition's tool.
+    DisableTool {
+        #[serde(default)]
+        tool: Option<String>,
+    },
+    /// Record the alert as a memory so the agent is aware of it.
+    CreateMemory {
+        #[serde(default)]
+        importance: Option<i64>,
+    },
+}
+
+impl AlertAction {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AlertAction::NotifyChannel { .. } => "notify_channel",
+            AlertAction::DisableTool { .. } => "disable_tool",
+            AlertAction::CreateMemory { .. } => "create_memory",
+        }
+    }
+}
+
+/// A user-editable alert rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub condition: AlertCondition,
+    pub actions: Vec<AlertAction>,
+    /// Minimum minutes between consecutive firings of this rule
+    pub cooldown_minutes: i64,
+    pub enabled: bool,
+    pub last_fired_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Request body for creating a rule
+#[derive(Debug, Deserialize)]
+pub struct CreateAlertRuleRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub condition: AlertCondition,
+    #[serde(default)]
+    pub actions: Vec<AlertAction>,
+    pub cooldown_minutes: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+/// Request body for updating a rule (all fields optional)
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateAlertRuleRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub condition: Option<AlertCondition>,
+    pub actions: Option<Vec<AlertAction>>,
+    pub cooldown_minutes: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+/// A record of a rule firing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub id: i64,
+    pub rule_id: i64,
+    pub rule_name: String,
+    pub message: String,
+    pub value: f64,
+    pub actions_taken: Vec<String>,
+    pub fired_at: String,
+}
+
+/// Validate a full rule definition (name, condition, actions).
+pub fn validate_rule(name: &str, condition: &AlertCondition, actions: &[AlertAction]) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Rule name is required".to_string());
+    }
+    condition.validate()?;
+    if actions.is_empty() {
+        return Err("At least one action is required".to_string());
+    }
+    for action in actions {
+        if let AlertAction::DisableTool { tool } = action {
+            if tool.is_none() && condition.target_tool().is_none() {
+                return Err(
+                    "disable_tool requires a 'tool' unless the condition is scoped to a tool".to_string(),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condition_deserialize_defaults() {
+        let cond: AlertCondition =
+            serde_json::from_str(r#"{"type":"tool_failure_rate","threshold":0.5}"#).unwrap();
+        assert_eq!(
+            cond,
+            AlertCondition::ToolFailureRate {
+                threshold: 0.5,
+                tool: None,
+                window_minutes: 15,
+                min_samples: 5,
+            }
+        );
+
+        let cond: AlertCondition = serde_json::from_str(r#"{"type":"module_stalled"}"#).unwrap();
+        assert_eq!(cond, AlertCondition::ModuleStalled { module: "wallet_monitor".to_string() });
+    }
+
+    #[test]
+    fn test_condition_validate() {
+        assert!(AlertCondition::DiskQuota { threshold_percent: 90 }.validate().is_ok());
+        assert!(AlertCondition::DiskQuota { threshold_percent: 0 }.validate().is_err());
+        assert!(AlertCondition::ToolFailureRate {
+            threshold: 1.5,
+            tool: None,
+            window_minutes: 15,
+            min_samples: 5,
+        }
+        .validate()
+        .is_err());
+        assert!(AlertCondition::ProviderErrors { threshold: 3, window_minutes: 0 }.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rule_disable_tool_needs_target() {
+        let cond = AlertCondition::ProviderErrors { threshold: 3, window_minutes: 10 };
+        let actions = vec![AlertAction::DisableTool { tool: None }];
+        assert!(validate_rule("r", &cond, &actions).is_err());
+
+        let cond = AlertCondition::ToolFailureRate {
+            threshold: 0.5,
+            tool: Some("web_fetch".to_string()),
+            window_minutes: 15,
+            min_samples: 5,
+        };
+        assert!(validate_rule("r", &cond, &actions).is_ok());
+        assert!(validate_rule("  ", &cond, &actions).is_err());
+        assert!(validate_rule("r", &cond, &[]).is_err());
+    }
+}
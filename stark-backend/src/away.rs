@@ -0,0 +1,218 @@
+//! Away mode — the owner is out of office
+//!
+//! While away mode is active (switched on, and inside its optional start/end
+//! window), correspondents — anyone whose messages run in safe mode, i.e. not
+//! the channel admin — get an automatic reply saying the owner is away. Their
+//! non-urgent requests are queued in `away_requests` and handed to the agent
+//! once away mode ends, with the answers sent back to the chat they came
+//! from; urgent ones (see `channels::triage`) are answered straight away.
+//! Autonomous runs (cron jobs, heartbeats, kanban tasks, Gmail triggers,
+//! strategies) may only use read-only tools plus the allow-listed actions. Settings live in
+//! the kv_store and are changed with `/api/away` or the `/away` command.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::channels::dispatcher::MessageDispatcher;
+use crate::channels::outbound;
+use crate::channels::types::NormalizedMessage;
+use crate::db::Database;
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolContext, ToolResult, ToolSafetyLevel};
+
+/// kv_store namespace for the settings (outside what the kv_store tool can reach)
+const NAMESPACE: &str = "away_mode";
+const SETTINGS_KEY: &str = "settings";
+/// `ToolContext::extra` flag set on runs nobody asked for in a chat
+pub const AUTONOMOUS_RUN_KEY: &str = "autonomous_run";
+
+/// Set while queued requests are being answered
+static RELEASING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AwaySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Sent to correspondents instead of the default notice
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Away mode only applies from here (immediately when unset)
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+    /// ... and until here (until switched off when unset)
+    #[serde(default)]
+    pub ends_at: Option<DateTime<Utc>>,
+    /// Tools autonomous runs may still use, besides read-only ones
+    #[serde(default)]
+    pub allowed_actions: Vec<String>,
+}
+
+impl AwaySettings {
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        self.enabled
+            && self.starts_at.is_none_or(|start| start <= now)
+            && self.ends_at.is_none_or(|end| now < end)
+    }
+
+    /// The automatic reply to a queued request
+    pub fn auto_reply(&self) -> String {
+        let notice = match self.message.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+            Some(message) => message.to_string(),
+            None => match self.ends_at {
+                Some(end) => format!("The owner is away until {}.", end.format("%Y-%m-%d %H:%M UTC")),
+                None => "The owner is away at the moment.".to_string(),
+            },
+        };
+        format!("{} Your message has been noted and will be answered when they're back.", notice)
+    }
+}
+
+pub fn load(db: &Database) -> AwaySettings {
+    db.kv_get(NAMESPACE, SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|entry| serde_json::from_value(entry.value).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(db: &Database, settings: &AwaySettings) -> Result<(), String> {
+    let value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    db.kv_set(NAMESPACE, SETTINGS_KEY, &value, None).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Whether away mode applies right now
+pub fn is_active(db: &Database) -> bool {
+    load(db).is_active_at(Utc::now())
+}
+
+/// Parse a `/away on` duration such as `30m`, `4h` or `3d`
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim().to_lowercase();
+    let split = text.find(|c: char| !c.is_ascii_digit())?;
+    let amount: i64 = text[..split].parse().ok().filter(|n| *n > 0)?;
+    match &text[split..] {
+        "m" | "min" | "mins" => Some(Duration::minutes(amount)),
+        "h" | "hr" | "hrs" => Some(Duration::hours(amount)),
+        "d" | "day" | "days" => Some(Duration::days(amount)),
+        "w" | "wk" | "wks" => Some(Duration::weeks(amount)),
+        _ => None,
+    }
+}
+
+/// Runs started by a schedule or trigger rather than a person: cron jobs,
+/// heartbeats and hooks (which run in their own session mode), kanban tasks
+/// and Gmail triggers
+pub fn is_autonomous(message: &NormalizedMessage) -> bool {
+    message.session_mode.is_some() || message.channel_type == "gmail"
+}
+
+/// Whether away mode refuses this tool in an autonomous run
+pub fn blocks(name: &str, level: ToolSafetyLevel, allowed: &[String]) -> bool {
+    level == ToolSafetyLevel::Standard && !allowed.iter().any(|a| a == name)
+}
+
+/// Refuse a tool call if this is an autonomous run, away mode is active and
+/// the tool isn't on the allow-list
+pub fn check(db: &Database, context: &ToolContext, tool: &dyn Tool) -> Option<ToolResult> {
+    if !context.extra.get(AUTONOMOUS_RUN_KEY).and_then(|v| v.as_bool()).unwrap_or(false) {
+        return None;
+    }
+    let settings = load(db);
+    let name = tool.definition().name;
+    if !blocks(&name, tool.safety_level(), &settings.allowed_actions) || !settings.is_active_at(Utc::now()) {
+        return None;
+    }
+    log::info!("[AWAY] Refused '{}' in an autonomous run", name);
+    Some(ToolResult::error(format!(
+        "'{}' is not allowed right now: the owner is away, and autonomous runs may only use read-only \
+         tools and these actions: {}. Note what you would have done so it can be picked up later.",
+        name,
+        if settings.allowed_actions.is_empty() { "none".to_string() } else { settings.allowed_actions.join(", ") }
+    )))
+}
+
+/// Answer the queued requests, oldest first, sending each answer to the chat
+/// it came from. Stops early if away mode becomes active again.
+pub async fn release_queue(db: &Database, dispatcher: &MessageDispatcher) -> usize {
+    if RELEASING.swap(true, Ordering::SeqCst) {
+        return 0;
+    }
+    let requests = db.list_away_requests().unwrap_or_default();
+    let mut answered = 0;
+    for request in requests {
+        if is_active(db) {
+            break;
+        }
+        // Removed first so a failing request isn't retried forever
+        if !matches!(db.delete_away_request(request.id), Ok(true)) {
+            continue;
+        }
+        let result = dispatcher.dispatch(request.to_message()).await;
+        if let Some(error) = result.error {
+            log::warn!("[AWAY] Queued request {} from {} failed: {}", request.id, request.user_name, error);
+            continue;
+        }
+        let delivered = if result.response.trim().is_empty() {
+            Ok(())
+        } else {
+            outbound::send_proactive(db, request.channel_id, &request.chat_id, &result.response, "away_followup").await
+        };
+        match delivered {
+            Ok(()) => answered += 1,
+            Err(e) => log::warn!("[AWAY] Failed to deliver the answer to request {}: {}", request.id, e),
+        }
+    }
+    RELEASING.store(false, Ordering::SeqCst);
+    answered
+}
+
+/// Answer queued requests whenever away mode is off, whether it was switched
+/// off or its end time passed
+pub fn spawn_release_worker(
+    db: Arc<Database>,
+    dispatcher: Arc<MessageDispatcher>,
+    interval_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if is_active(&db) || db.count_away_requests().unwrap_or(0) == 0 {
+                continue;
+            }
+            let answered = release_queue(&db, &dispatcher).await;
+            log::info!("[AWAY] Away mode over: answered {} queued request(s)", answered);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_and_durations() {
+        let now = Utc::now();
+        let mut settings = AwaySettings { enabled: true, ..Default::default() };
+        assert!(settings.is_active_at(now));
+        settings.starts_at = Some(now + Duration::hours(1));
+        assert!(!settings.is_active_at(now));
+        settings.starts_at = Some(now - Duration::hours(1));
+        settings.ends_at = Some(now - Duration::minutes(1));
+        assert!(!settings.is_active_at(now));
+        assert!(settings.auto_reply().contains("away until"));
+
+        assert_eq!(parse_duration("3d"), Some(Duration::days(3)));
+        assert_eq!(parse_duration("90m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("0h"), None);
+        assert_eq!(parse_duration("soon"), None);
+
+        let allowed = vec!["memory_store".to_string()];
+        assert!(blocks("send_eth", ToolSafetyLevel::Standard, &allowed));
+        assert!(!blocks("memory_store", ToolSafetyLevel::Standard, &allowed));
+        assert!(!blocks("read_file", ToolSafetyLevel::ReadOnly, &allowed));
+    }
+}
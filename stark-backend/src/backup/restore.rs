@@ -567,6 +567,7 @@ pub async fn restore_all(
                     tags: skill_entry.tags.clone(),
                     subagent_type: skill_entry.subagent_type.clone(),
                     requires_api_keys,
+                    finance_permissions: Vec::new(),
                     scripts: skill_entry.scripts.iter().map(|s| crate::skills::ParsedScript {
                         name: s.name.clone(),
                         code: s.code.clone(),
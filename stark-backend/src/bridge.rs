@@ -0,0 +1,299 @@
+//! Bridge arrival tracking
+//!
+//! `bridge_usdc` only queues the source-chain transaction; this module follows
+//! the transfer to the other side. Every queued bridge gets a row in
+//! `bridge_transfers`. A background worker picks up the source tx hash once
+//! the queued transaction is broadcast, then watches the destination chain for
+//! the relayer's fill: a USDC `Transfer` to the recipient, at or after the
+//! destination block recorded when the bridge was queued, for at least the
+//! minimum expected output. The proposing chat (and the dashboard) is told when
+//! the funds arrive, or when they haven't after `ARRIVAL_TIMEOUT_SECS`.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use ethers::types::U256;
+use serde_json::{json, Value};
+
+use crate::channels::outbound;
+use crate::db::tables::bridge_transfers::{
+    BridgeTransfer, BRIDGE_CANCELLED, BRIDGE_COMPLETED, BRIDGE_IN_FLIGHT, BRIDGE_QUEUED, BRIDGE_TIMED_OUT,
+};
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::tools::rpc_config::resolve_rpc_readonly;
+use crate::tx_queue::{QueuedTxStatus, TxQueueManager};
+
+/// keccak256("Transfer(address,address,uint256)")
+const TRANSFER_TOPIC: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+/// How long after broadcast a fill may take before the user is told it's late
+/// (Across normally fills in seconds)
+pub const ARRIVAL_TIMEOUT_SECS: i64 = 30 * 60;
+
+/// A bridge that is still queued after this long was never broadcast
+const QUEUED_EXPIRY_SECS: i64 = 24 * 3600;
+
+/// Destination blocks to look back when the start block wasn't recorded at queue time (~10 minutes)
+fn lookback_blocks(network: &str) -> u64 {
+    match network {
+        "mainnet" => 50,
+        "arbitrum" => 2400,
+        _ => 300,
+    }
+}
+
+pub async fn rpc_request(network: &str, method: &str, params: Value) -> Result<Value, String> {
+    let rpc = resolve_rpc_readonly(network);
+    let body: Value = crate::http::shared_client()
+        .post(&rpc.url)
+        .json(&json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 }))
+        .send()
+        .await
+        .map_err(|e| format!("RPC request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse RPC response: {}", e))?;
+    match body.get("result") {
+        Some(result) => Ok(result.clone()),
+        None => Err(format!(
+            "RPC error: {}",
+            body.get("error").map(|e| e.to_string()).unwrap_or_default()
+        )),
+    }
+}
+
+/// Latest block number on a network
+pub async fn block_number(network: &str) -> Result<u64, String> {
+    let result = rpc_request(network, "eth_blockNumber", json!([])).await?;
+    result
+        .as_str()
+        .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| format!("Unexpected eth_blockNumber result: {}", result))
+}
+
+/// Pick the fill out of `Transfer` logs to the recipient: the first one whose
+/// amount lies between the minimum expected output and the amount sent.
+/// Returns the destination tx hash and amount.
+fn match_fill(logs: &[Value], min_output: U256, max_output: U256) -> Option<(String, U256)> {
+    logs.iter().find_map(|log| {
+        let amount = U256::from_str_radix(log["data"].as_str()?.trim_start_matches("0x"), 16).ok()?;
+        if amount < min_output || amount > max_output {
+            return None;
+        }
+        Some((log["transactionHash"].as_str()?.to_string(), amount))
+    })
+}
+
+/// Look for the fill on the destination chain
+async fn find_fill(transfer: &BridgeTransfer, from_block: u64) -> Result<Option<(String, U256)>, String> {
+    let min_output = U256::from_dec_str(&transfer.min_output_raw).map_err(|e| format!("Bad min output: {}", e))?;
+    let max_output = U256::from_dec_str(&transfer.amount_raw).map_err(|e| format!("Bad amount: {}", e))?;
+    let recipient_topic = format!("0x{:0>64}", transfer.recipient.trim_start_matches("0x").to_lowercase());
+    let logs = rpc_request(
+        &transfer.to_network,
+        "eth_getLogs",
+        json!([{
+            "address": transfer.dest_token,
+            "topics": [TRANSFER_TOPIC, Value::Null, recipient_topic],
+            "fromBlock": format!("0x{:x}", from_block),
+            "toBlock": "latest",
+        }]),
+    )
+    .await?;
+    Ok(match_fill(logs.as_array().map(Vec::as_slice).unwrap_or(&[]), min_output, max_output))
+}
+
+fn age_secs(timestamp: Option<&str>) -> Option<i64> {
+    let at = DateTime::parse_from_rfc3339(timestamp?).ok()?;
+    Some((Utc::now() - at.with_timezone(&Utc)).num_seconds())
+}
+
+fn short(hash: &str) -> &str {
+    &hash[..hash.len().min(10)]
+}
+
+/// Tell the dashboard and the chat that queued the bridge about a status change
+async fn notify(db: &Database, broadcaster: Option<&EventBroadcaster>, transfer: &BridgeTransfer, text: &str) {
+    if let Some(broadcaster) = broadcaster {
+        broadcaster.broadcast(GatewayEvent::custom(
+            "bridge.transfer_updated",
+            json!({
+                "bridge_tx_uuid": transfer.bridge_tx_uuid,
+                "from_network": transfer.from_network,
+                "to_network": transfer.to_network,
+                "amount": transfer.amount,
+                "status": transfer.status,
+                "source_tx_hash": transfer.source_tx_hash,
+                "dest_tx_hash": transfer.dest_tx_hash,
+                "message": text,
+            }),
+        ));
+    }
+    if let (Some(channel_id), Some(chat_id)) = (transfer.channel_id, transfer.chat_id.as_deref()) {
+        if let Err(e) = outbound::send_direct(db, channel_id, chat_id, text, &[]).await {
+            log::warn!("[BRIDGE] Failed to notify chat about {}: {}", transfer.bridge_tx_uuid, e);
+        }
+    }
+}
+
+/// Source tx hash of the queued bridge transaction once it has been broadcast.
+/// `Err` carries a reason when the transaction will never be broadcast.
+fn source_tx_state(db: &Database, tx_queue: Option<&TxQueueManager>, uuid: &str) -> Result<Option<String>, String> {
+    if let Some(tx) = tx_queue.and_then(|q| q.get(uuid)) {
+        return match tx.status {
+            QueuedTxStatus::Broadcast | QueuedTxStatus::Confirmed => Ok(tx.tx_hash),
+            QueuedTxStatus::Failed => Err(format!("the bridge transaction failed: {}", tx.error.unwrap_or_default())),
+            QueuedTxStatus::Expired => Err("the bridge transaction expired without being broadcast".to_string()),
+            QueuedTxStatus::Pending | QueuedTxStatus::Broadcasting => Ok(None),
+        };
+    }
+    // Dropped from the in-memory queue (e.g. restart): fall back to broadcast history
+    match db.get_broadcasted_transaction(uuid) {
+        Ok(Some(tx)) => Ok(tx.tx_hash),
+        _ => Ok(None),
+    }
+}
+
+/// Advance one transfer: pick up the broadcast, look for the fill, time out.
+/// Also used by `bridge_status` to re-check a timed-out transfer on demand.
+/// Returns the updated transfer.
+pub async fn refresh_transfer(
+    db: &Database,
+    broadcaster: Option<&EventBroadcaster>,
+    tx_queue: Option<&TxQueueManager>,
+    transfer: &BridgeTransfer,
+) -> Result<BridgeTransfer, String> {
+    let mut updated = transfer.clone();
+    let route = format!("{} USDC {} → {}", transfer.amount, transfer.from_network, transfer.to_network);
+
+    if transfer.status == BRIDGE_QUEUED {
+        match source_tx_state(db, tx_queue, &transfer.bridge_tx_uuid) {
+            Ok(Some(source_tx_hash)) => {
+                let start_block = match transfer.dest_start_block {
+                    Some(block) => Some(block),
+                    None => block_number(&transfer.to_network)
+                        .await
+                        .ok()
+                        .map(|b| b.saturating_sub(lookback_blocks(&transfer.to_network)) as i64),
+                };
+                db.mark_bridge_transfer_in_flight(&transfer.bridge_tx_uuid, &source_tx_hash, start_block)
+                    .map_err(|e| format!("Database error: {}", e))?;
+                updated.status = BRIDGE_IN_FLIGHT.to_string();
+                updated.source_tx_hash = Some(source_tx_hash);
+                updated.dest_start_block = start_block;
+                updated.broadcast_at = Some(Utc::now().to_rfc3339());
+            }
+            Ok(None) => {
+                if age_secs(Some(&transfer.created_at)).is_some_and(|age| age > QUEUED_EXPIRY_SECS) {
+                    db.finish_bridge_transfer(&transfer.bridge_tx_uuid, BRIDGE_CANCELLED, None)
+                        .map_err(|e| format!("Database error: {}", e))?;
+                    updated.status = BRIDGE_CANCELLED.to_string();
+                }
+                return Ok(updated);
+            }
+            Err(reason) => {
+                db.finish_bridge_transfer(&transfer.bridge_tx_uuid, BRIDGE_CANCELLED, None)
+                    .map_err(|e| format!("Database error: {}", e))?;
+                updated.status = BRIDGE_CANCELLED.to_string();
+                notify(db, broadcaster, &updated, &format!("Bridge of {} cancelled: {}.", route, reason)).await;
+                return Ok(updated);
+            }
+        }
+    }
+
+    if updated.status != BRIDGE_IN_FLIGHT && updated.status != BRIDGE_TIMED_OUT {
+        return Ok(updated);
+    }
+
+    let from_block = match updated.dest_start_block {
+        Some(block) => block.max(0) as u64,
+        None => block_number(&updated.to_network)
+            .await?
+            .saturating_sub(lookback_blocks(&updated.to_network)),
+    };
+    if let Some((dest_tx_hash, amount)) = find_fill(&updated, from_block).await? {
+        db.finish_bridge_transfer(&updated.bridge_tx_uuid, BRIDGE_COMPLETED, Some(&dest_tx_hash))
+            .map_err(|e| format!("Database error: {}", e))?;
+        updated.status = BRIDGE_COMPLETED.to_string();
+        updated.dest_tx_hash = Some(dest_tx_hash.clone());
+        updated.completed_at = Some(Utc::now().to_rfc3339());
+        let received = crate::web3::decode::format_token_amount(amount, 6, "USDC");
+        let text = format!(
+            "✅ Bridge complete: {} arrived on {} ({} received).\nDestination tx: `{}`",
+            route, updated.to_network, received, dest_tx_hash
+        );
+        notify(db, broadcaster, &updated, &text).await;
+    } else if updated.status == BRIDGE_IN_FLIGHT
+        && age_secs(updated.broadcast_at.as_deref()).is_some_and(|age| age > ARRIVAL_TIMEOUT_SECS)
+    {
+        db.finish_bridge_transfer(&updated.bridge_tx_uuid, BRIDGE_TIMED_OUT, None)
+            .map_err(|e| format!("Database error: {}", e))?;
+        updated.status = BRIDGE_TIMED_OUT.to_string();
+        let text = format!(
+            "⚠️ Bridge of {} has not arrived {} minutes after broadcast (source tx `{}`). \
+             Across may refund it on {} if the deposit isn't filled; check with bridge_status.",
+            route,
+            ARRIVAL_TIMEOUT_SECS / 60,
+            updated.source_tx_hash.as_deref().map(short).unwrap_or("?"),
+            updated.from_network
+        );
+        notify(db, broadcaster, &updated, &text).await;
+    }
+
+    Ok(updated)
+}
+
+/// Advance every queued and in-flight transfer once. Returns how many were checked.
+pub async fn run_tracking_pass(db: &Database, broadcaster: &EventBroadcaster, tx_queue: &TxQueueManager) -> usize {
+    let active = match db.list_active_bridge_transfers(200) {
+        Ok(active) => active,
+        Err(e) => {
+            log::error!("[BRIDGE] Failed to load active transfers: {}", e);
+            return 0;
+        }
+    };
+    let mut checked = 0;
+    for transfer in &active {
+        match refresh_transfer(db, Some(broadcaster), Some(tx_queue), transfer).await {
+            Ok(_) => checked += 1,
+            Err(e) => log::warn!("[BRIDGE] Failed to refresh {}: {}", transfer.bridge_tx_uuid, e),
+        }
+    }
+    checked
+}
+
+/// Spawn the background worker that follows bridges to arrival (every `interval_secs`).
+pub fn spawn_tracking_worker(
+    db: Arc<Database>,
+    broadcaster: Arc<EventBroadcaster>,
+    tx_queue: Arc<TxQueueManager>,
+    interval_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            run_tracking_pass(&db, &broadcaster, &tx_queue).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_fill_respects_amount_window() {
+        let log = |amount: u64, hash: &str| {
+            json!({ "data": format!("0x{:064x}", amount), "transactionHash": hash })
+        };
+        let logs = vec![log(5_000_000, "0xsmall"), log(99_500_000, "0xfill"), log(100_000_000, "0xlater")];
+        let (hash, amount) = match_fill(&logs, U256::from(99_000_000u64), U256::from(100_000_000u64)).unwrap();
+        assert_eq!(hash, "0xfill");
+        assert_eq!(amount, U256::from(99_500_000u64));
+        // Nothing in range (e.g. an unrelated larger payment)
+        assert!(match_fill(&[log(500_000_000, "0xbig")], U256::from(99_000_000u64), U256::from(100_000_000u64)).is_none());
+    }
+}
@@ -0,0 +1,156 @@
+//! Interactive buttons and quick replies on outgoing channel messages
+//!
+//! The agent attaches buttons through `say_to_user(buttons=[...])`, which
+//! stores them in the [`REPLY_ACTIONS_REGISTER`] register; the dispatcher copies
+//! them onto the `DispatchResult`. Listeners render them natively (Telegram
+//! inline keyboards, Discord button components) with callback data pointing at
+//! a stored token, and turn presses back into a `NormalizedMessage` carrying a
+//! structured [`ActionEvent`] — except `reply` buttons, which are quick replies
+//! and arrive as ordinary text.
+
+use crate::channels::types::{ActionButton, ActionEvent, NormalizedMessage};
+use crate::db::Database;
+use crate::tools::RegisterStore;
+
+/// Register that say_to_user writes the buttons for its message to
+pub const REPLY_ACTIONS_REGISTER: &str = "reply_actions";
+/// Prefix on platform callback data so foreign callbacks are ignored
+pub const CALLBACK_PREFIX: &str = "act:";
+/// Quick reply: pressing sends the label as if the user had typed it
+pub const ACTION_REPLY: &str = "reply";
+/// Built-in action that just acknowledges and removes the buttons
+pub const ACTION_DISMISS: &str = "dismiss";
+/// Built-in action that resumes the execution paused in this chat
+pub const ACTION_RESUME: &str = "resume";
+
+/// Discord allows 25 buttons; more than a handful is unusable on mobile anyway
+pub const MAX_BUTTONS: usize = 10;
+/// Buttons per keyboard row
+pub const BUTTONS_PER_ROW: usize = 3;
+/// Discord rejects labels over 80 characters
+const MAX_LABEL_CHARS: usize = 80;
+
+/// Buttons attached by the agent during this dispatch
+pub fn actions_from_registers(registers: &RegisterStore) -> Vec<ActionButton> {
+    registers
+        .get(REPLY_ACTIONS_REGISTER)
+        .and_then(|value| serde_json::from_value::<Vec<ActionButton>>(value).ok())
+        .map(sanitize)
+        .unwrap_or_default()
+}
+
+/// Drop unusable buttons and enforce platform limits
+pub fn sanitize(buttons: Vec<ActionButton>) -> Vec<ActionButton> {
+    buttons
+        .into_iter()
+        .filter(|b| !b.label.trim().is_empty() && !b.action.trim().is_empty())
+        .map(|mut b| {
+            b.label = b.label.trim().chars().take(MAX_LABEL_CHARS).collect();
+            b.action = b.action.trim().to_string();
+            b
+        })
+        .take(MAX_BUTTONS)
+        .collect()
+}
+
+/// Persist buttons for a message. Returns (label, callback data) pairs in order,
+/// or nothing if they couldn't be stored (the message is then sent without buttons).
+pub fn register_buttons(db: &Database, channel_id: i64, chat_id: &str, buttons: &[ActionButton]) -> Vec<(String, String)> {
+    if buttons.is_empty() {
+        return Vec::new();
+    }
+    match db.create_message_actions(channel_id, chat_id, buttons) {
+        Ok(tokens) => buttons
+            .iter()
+            .zip(tokens)
+            .map(|(b, token)| (b.label.clone(), format!("{}{}", CALLBACK_PREFIX, token)))
+            .collect(),
+        Err(e) => {
+            log::error!("[ACTIONS] Failed to store buttons for channel {}: {}", channel_id, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Resolve platform callback data. None if it isn't ours, is unknown, or was already used.
+pub fn resolve_callback(db: &Database, channel_id: i64, data: &str) -> Option<ActionButton> {
+    let token = data.strip_prefix(CALLBACK_PREFIX)?;
+    match db.consume_message_action(channel_id, token) {
+        Ok(button) => button,
+        Err(e) => {
+            log::error!("[ACTIONS] Failed to resolve button {}: {}", token, e);
+            None
+        }
+    }
+}
+
+/// Message text and structured event for a pressed button
+pub fn press_to_message(button: ActionButton) -> (String, Option<ActionEvent>) {
+    if button.action == ACTION_REPLY {
+        return (button.label, None);
+    }
+    let text = match &button.payload {
+        Some(payload) => format!(
+            "[Pressed button \"{}\" — action: {}, payload: {}]",
+            button.label, button.action, payload
+        ),
+        None => format!("[Pressed button \"{}\" — action: {}]", button.label, button.action),
+    };
+    let event = ActionEvent {
+        action: button.action,
+        label: button.label,
+        payload: button.payload,
+    };
+    (text, Some(event))
+}
+
+/// Response for actions handled without involving the agent
+pub fn builtin_response(event: &ActionEvent) -> Option<String> {
+    match event.action.as_str() {
+        ACTION_DISMISS => Some("Dismissed.".to_string()),
+        _ => None,
+    }
+}
+
+/// Whether a message asks to resume a paused execution
+pub fn is_resume(message: &NormalizedMessage) -> bool {
+    message.action.as_ref().is_some_and(|a| a.action == ACTION_RESUME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_press_roundtrip() {
+        let db = Database::new(":memory:").unwrap();
+        let registers = RegisterStore::new();
+        registers.set(
+            REPLY_ACTIONS_REGISTER,
+            serde_json::json!([
+                {"label": "Yes", "action": "reply"},
+                {"label": "Re-run", "action": "rerun_task", "payload": {"task_id": 7}},
+                {"label": "  ", "action": "reply"}
+            ]),
+            "say_to_user",
+        );
+        let buttons = actions_from_registers(&registers);
+        assert_eq!(buttons.len(), 2);
+
+        let callbacks = register_buttons(&db, 1, "42", &buttons);
+        assert!(callbacks.iter().all(|(_, data)| data.starts_with(CALLBACK_PREFIX) && data.len() <= 64));
+
+        let pressed = resolve_callback(&db, 1, &callbacks[1].1).unwrap();
+        let (text, event) = press_to_message(pressed);
+        let event = event.unwrap();
+        assert_eq!(event.action, "rerun_task");
+        assert_eq!(event.payload, Some(serde_json::json!({"task_id": 7})));
+        assert!(text.contains("Re-run"));
+
+        // Quick replies are plain text
+        let (text, event) = press_to_message(buttons[0].clone());
+        assert_eq!(text, "Yes");
+        assert!(event.is_none());
+        assert!(resolve_callback(&db, 1, "other:data").is_none());
+    }
+}
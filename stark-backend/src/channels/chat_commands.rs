@@ -0,0 +1,175 @@
+//! Slash commands answered by the backend
+//!
+//! Messages that start with `/` (`/new`, `/stop`, `/tasks`, `/memory list`,
+//! `/mode plan`, `/style concise`, ...) are handled deterministically by the dispatcher instead
+//! of going to the AI. Each channel can switch individual commands off with the
+//! `disabled_commands` channel setting; a disabled command reaches the agent as
+//! ordinary text. Thinking directives (`/t:high ...`, `/think`) and paths like
+//! `/usr/bin` are not commands and pass through untouched.
+
+use crate::db::Database;
+
+/// A command the backend answers itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub usage: &'static str,
+    pub description: &'static str,
+    /// Refused for users whose messages run in safe mode
+    pub owner_only: bool,
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec { name: "help", aliases: &["commands"], usage: "/help", description: "List the available commands", owner_only: false },
+    CommandSpec { name: "new", aliases: &["reset"], usage: "/new", description: "Start a fresh session", owner_only: false },
+    CommandSpec { name: "stop", aliases: &["cancel"], usage: "/stop", description: "Stop the current run and its subagents", owner_only: true },
+    CommandSpec { name: "tasks", aliases: &[], usage: "/tasks", description: "Show the current task list", owner_only: false },
+    CommandSpec { name: "memory", aliases: &[], usage: "/memory list", description: "Show your most recent memories", owner_only: true },
+    CommandSpec { name: "mode", aliases: &[], usage: "/mode [plan|direct|auto]", description: "Show or set how this chat runs requests", owner_only: true },
+    CommandSpec { name: "style", aliases: &[], usage: "/style [concise|verbose|technical|default]", description: "Show or set how the agent answers in this session", owner_only: false },
+    CommandSpec { name: "away", aliases: &["ooo"], usage: "/away [on|off|allow|deny]", description: "Show or set away mode", owner_only: true },
+];
+
+/// Names that belong to thinking directives, handled separately by the dispatcher
+const THINKING_NAMES: &[&str] = &["t", "think", "thinking"];
+
+/// A parsed `/command arg ...` message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedCommand {
+    /// Lowercased command name, without the slash or a `@botname` suffix
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+/// Parse a slash command. None if the message isn't one.
+pub fn parse(text: &str) -> Option<ParsedCommand> {
+    let rest = text.trim().strip_prefix('/')?;
+    let mut parts = rest.split_whitespace();
+    let token = parts.next()?;
+    // Telegram appends the bot's username in groups: /tasks@starkbot
+    let name = token.split('@').next().unwrap_or("").to_lowercase();
+    if name.is_empty()
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        || THINKING_NAMES.contains(&name.as_str())
+    {
+        return None;
+    }
+    Some(ParsedCommand { name, args: parts.map(|a| a.to_string()).collect() })
+}
+
+/// Look a command up by name or alias
+pub fn lookup(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|c| c.name == name || c.aliases.contains(&name))
+}
+
+/// Command names switched off by a `disabled_commands` setting ("memory, /mode")
+pub fn parse_disabled(setting: &str) -> Vec<String> {
+    setting
+        .split(',')
+        .map(|s| s.trim().trim_start_matches('/').to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Whether a command is available in a channel. /help always is.
+pub fn is_enabled(spec: &CommandSpec, disabled: &[String]) -> bool {
+    spec.name == "help" || !disabled.iter().any(|d| d == spec.name)
+}
+
+/// Help output listing the commands available to this user in this channel
+pub fn help_text(disabled: &[String], is_owner: bool) -> String {
+    let mut lines = vec!["**Commands**".to_string()];
+    for spec in COMMANDS.iter().filter(|c| is_enabled(c, disabled) && (is_owner || !c.owner_only)) {
+        lines.push(format!("`{}` — {}", spec.usage, spec.description));
+    }
+    lines.push("Anything else goes to the agent.".to_string());
+    lines.join("\n")
+}
+
+/// How a chat runs requests, set with /mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatMode {
+    /// Always break the request into tasks with the task planner first
+    Plan,
+    /// Skip the task planner and act on the request directly
+    Direct,
+}
+
+impl ChatMode {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "plan" | "planner" => Some(ChatMode::Plan),
+            "direct" | "assistant" | "execute" => Some(ChatMode::Direct),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChatMode::Plan => "plan",
+            ChatMode::Direct => "direct",
+        }
+    }
+}
+
+/// kv_store namespace for per-chat modes (outside what the kv_store tool can reach)
+const CHAT_MODE_NAMESPACE: &str = "chat_mode";
+
+fn chat_mode_key(channel_id: i64, chat_id: &str) -> String {
+    format!("{}:{}", channel_id, chat_id)
+}
+
+/// The mode set for a chat with /mode, if any
+pub fn get_chat_mode(db: &Database, channel_id: i64, chat_id: &str) -> Option<ChatMode> {
+    db.kv_get(CHAT_MODE_NAMESPACE, &chat_mode_key(channel_id, chat_id))
+        .ok()
+        .flatten()
+        .and_then(|entry| entry.value.as_str().and_then(ChatMode::from_str))
+}
+
+/// Set (or with None, clear) the mode for a chat
+pub fn set_chat_mode(db: &Database, channel_id: i64, chat_id: &str, mode: Option<ChatMode>) -> Result<(), String> {
+    let key = chat_mode_key(channel_id, chat_id);
+    match mode {
+        Some(mode) => db
+            .kv_set(CHAT_MODE_NAMESPACE, &key, &serde_json::Value::String(mode.as_str().to_string()), None)
+            .map(|_| ()),
+        None => db.kv_delete(CHAT_MODE_NAMESPACE, &key).map(|_| ()),
+    }
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        let parsed = parse("  /memory list ").unwrap();
+        assert_eq!(parsed.name, "memory");
+        assert_eq!(parsed.args, vec!["list".to_string()]);
+        assert_eq!(parse("/Tasks@starkbot").unwrap().name, "tasks");
+        assert_eq!(lookup("reset").map(|c| c.name), Some("new"));
+
+        assert!(parse("hello /stop").is_none());
+        assert!(parse("/t:high what is this").is_none());
+        assert!(parse("/think").is_none());
+        assert!(parse("/usr/bin is missing").is_none());
+        assert!(parse("/").is_none());
+    }
+
+    #[test]
+    fn test_disabled_commands() {
+        let disabled = parse_disabled(" /Memory, mode ,");
+        assert_eq!(disabled, vec!["memory".to_string(), "mode".to_string()]);
+        assert!(!is_enabled(lookup("memory").unwrap(), &disabled));
+        assert!(is_enabled(lookup("tasks").unwrap(), &disabled));
+        assert!(is_enabled(lookup("help").unwrap(), &parse_disabled("help")));
+
+        let help = help_text(&disabled, false);
+        assert!(help.contains("/tasks"));
+        assert!(!help.contains("/memory"));
+        assert!(!help.contains("/stop"));
+    }
+}
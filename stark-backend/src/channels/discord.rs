@@ -1,15 +1,20 @@
+use crate::channels::actions;
 use crate::channels::dispatcher::MessageDispatcher;
+use crate::channels::format::{self, RenderTarget};
+use crate::channels::outbound::{self, OutboundTarget};
 use crate::channels::safe_mode_rate_limiter::SafeModeChannelRateLimiter;
-use crate::channels::types::{ChannelType, NormalizedMessage};
+use crate::channels::types::{ActionButton, ChannelType, NormalizedMessage};
 use crate::channels::util;
+use crate::db::tables::artifacts::Artifact;
 use crate::db::Database;
 use crate::discord_hooks;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::{Channel, ToolOutputVerbosity};
+use crate::models::{Channel, MessageAttachment, ToolOutputVerbosity};
 use serenity::all::{
-    Client, Context, CreateEmbed, CreateMessage, EditMessage, EventHandler, GatewayIntents,
-    GetMessages, Message, MessageId, Ready, UserId,
+    ButtonStyle, ChannelId, Client, Context, CreateActionRow, CreateAttachment, CreateButton, CreateEmbed,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditMessage,
+    EventHandler, GatewayIntents, GetMessages, Http, Interaction, Message, MessageId, Ready, UserId,
 };
 use std::sync::Arc;
 use tokio::sync::oneshot;
@@ -108,6 +113,21 @@ fn extract_image_urls(text: &str) -> Vec<String> {
     urls
 }
 
+/// References to the files attached to an incoming message
+fn message_attachments(msg: &Message) -> Vec<MessageAttachment> {
+    msg.attachments
+        .iter()
+        .map(|a| MessageAttachment {
+            kind: MessageAttachment::kind_for_mime(a.content_type.as_deref()).to_string(),
+            url: Some(a.url.clone()),
+            storage_ref: Some(a.id.to_string()),
+            file_name: Some(a.filename.clone()),
+            mime_type: a.content_type.clone(),
+            size_bytes: Some(a.size as i64),
+        })
+        .collect()
+}
+
 struct DiscordHandler {
     channel_id: i64,
     dispatcher: Arc<MessageDispatcher>,
@@ -119,6 +139,76 @@ struct DiscordHandler {
     bot_user_id: Arc<tokio::sync::OnceCell<UserId>>,
 }
 
+impl DiscordHandler {
+    /// Render and send an agent reply (buttons go on the last chunk); undeliverable chunks are queued
+    async fn deliver_reply(&self, http: &Arc<Http>, discord_channel: ChannelId, response: &str, buttons: &[ActionButton]) {
+        let chunks = format::render_chunks(response, RenderTarget::Discord);
+        let components = button_rows(&self.db, self.channel_id, discord_channel, buttons);
+        let target = OutboundTarget {
+            channel_id: self.channel_id,
+            channel_type: ChannelType::Discord,
+            chat_id: discord_channel.to_string(),
+            reply_to: None,
+        };
+        let last = chunks.len();
+        let mut sent = 0;
+        outbound::deliver_or_queue(&self.db, &target, chunks, |chunk| {
+            sent += 1;
+            let mut builder = CreateMessage::new().content(chunk);
+            if sent == last && !components.is_empty() {
+                builder = builder.components(components.clone());
+            }
+            async move {
+                discord_channel
+                    .send_message(http, builder)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }
+        })
+        .await;
+    }
+}
+
+/// Discord's upload cap for bots on unboosted servers
+const DISCORD_MAX_UPLOAD_BYTES: u64 = 8 * 1024 * 1024;
+/// Discord allows at most 10 files per message
+const DISCORD_FILES_PER_MESSAGE: usize = 10;
+
+/// Upload the execution's artifacts as file attachments after the reply
+async fn deliver_artifacts(http: &Arc<Http>, discord_channel: ChannelId, artifacts: &[Artifact]) {
+    let files = util::uploadable_artifacts(artifacts, DISCORD_MAX_UPLOAD_BYTES);
+    for batch in files.chunks(DISCORD_FILES_PER_MESSAGE) {
+        let attachments = batch
+            .iter()
+            .map(|(name, bytes)| CreateAttachment::bytes(bytes.clone(), name.clone()));
+        if let Err(e) = discord_channel
+            .send_message(http, CreateMessage::new().add_files(attachments))
+            .await
+        {
+            log::warn!("Discord: Failed to upload artifacts: {}", e);
+        }
+    }
+}
+
+/// Button rows for the agent's buttons (empty if there are none or they couldn't be stored)
+pub(crate) fn button_rows(db: &Database, channel_id: i64, discord_channel: ChannelId, buttons: &[ActionButton]) -> Vec<CreateActionRow> {
+    actions::register_buttons(db, channel_id, &discord_channel.to_string(), buttons)
+        .chunks(actions::BUTTONS_PER_ROW)
+        .map(|row| {
+            CreateActionRow::Buttons(
+                row.iter()
+                    .map(|(label, custom_id)| {
+                        CreateButton::new(custom_id.clone())
+                            .label(label.clone())
+                            .style(ButtonStyle::Primary)
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
 #[serenity::async_trait]
 impl EventHandler for DiscordHandler {
     async fn message(&self, ctx: Context, msg: Message) {
@@ -182,7 +272,7 @@ impl EventHandler for DiscordHandler {
             Ok(result) => {
                 // If module handled it with a direct response, send it and return
                 if let Some(response) = result.response {
-                    let chunks = util::split_message(&response, 2000);
+                    let chunks = format::render_chunks(&response, RenderTarget::Discord);
                     for chunk in chunks {
                         if let Err(e) = msg.channel_id.say(&ctx.http, &chunk).await {
                             log::error!("Discord: Failed to send hooks response: {}", e);
@@ -297,15 +387,41 @@ impl EventHandler for DiscordHandler {
                         session_mode: None,
                         selected_network: None,
                         force_safe_mode: forward.force_safe_mode,
+                        shared_chat: msg.guild_id.is_some(),
                         platform_role_ids: forward.platform_role_ids,
+                        attachments: message_attachments(&msg),
                         chat_context,
+                        action: None,
                     };
 
                     self.dispatch_and_respond(&ctx, &msg, normalized, &user_name).await;
                     return;
                 }
 
-                // Module didn't handle it (bot not mentioned), ignore the message
+                // Passive group chat: keep the message as context for the next answer
+                if result.remember {
+                    self.dispatcher.remember(&NormalizedMessage {
+                        channel_id: self.channel_id,
+                        channel_type: ChannelType::Discord.to_string(),
+                        chat_id: msg.channel_id.to_string(),
+                        chat_name: None,
+                        user_id: msg.author.id.to_string(),
+                        user_name: msg.author.name.clone(),
+                        text: msg.content.clone(),
+                        message_id: Some(msg.id.to_string()),
+                        session_mode: None,
+                        selected_network: None,
+                        force_safe_mode: false,
+                        shared_chat: msg.guild_id.is_some(),
+                        platform_role_ids: vec![],
+                        attachments: message_attachments(&msg),
+                        chat_context: None,
+                        action: None,
+                    });
+                    return;
+                }
+
+                // Module didn't handle it (no group trigger matched), ignore the message
                 if !result.handled {
                     return;
                 }
@@ -328,6 +444,76 @@ impl EventHandler for DiscordHandler {
         // ===== End Discord Hooks Integration =====
     }
 
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Component(component) = interaction else {
+            return;
+        };
+        let Some(button) = actions::resolve_callback(&self.db, self.channel_id, &component.data.custom_id) else {
+            let expired = CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content("This button has expired.")
+                    .ephemeral(true),
+            );
+            let _ = component.create_response(&ctx.http, expired).await;
+            return;
+        };
+
+        // Acknowledge by removing the spent buttons from the message
+        let ack = CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new().components(vec![]));
+        if let Err(e) = component.create_response(&ctx.http, ack).await {
+            log::warn!("Discord: Failed to acknowledge button press: {}", e);
+        }
+
+        let user_id = component.user.id.to_string();
+        let user_name = component.user.name.clone();
+        log::info!("Discord: User {} pressed button '{}' ({})", user_name, button.label, button.action);
+
+        // Same admin rules as messages: explicit admin list, else the Administrator permission (DMs count as admin)
+        let config = discord_hooks::DiscordHooksConfig::from_channel_settings(&self.db, self.channel_id);
+        let is_admin = if config.has_explicit_admins() {
+            config.is_admin_by_id(&user_id)
+        } else {
+            component
+                .member
+                .as_ref()
+                .is_none_or(|m| m.permissions.is_some_and(|p| p.administrator()))
+        };
+        let platform_role_ids = component
+            .member
+            .as_ref()
+            .map(|m| m.roles.iter().map(|r| r.to_string()).collect())
+            .unwrap_or_default();
+        let (text, action) = actions::press_to_message(button);
+
+        let normalized = NormalizedMessage {
+            channel_id: self.channel_id,
+            channel_type: ChannelType::Discord.to_string(),
+            chat_id: component.channel_id.to_string(),
+            chat_name: None,
+            user_id,
+            user_name,
+            text,
+            message_id: None,
+            session_mode: None,
+            selected_network: None,
+            force_safe_mode: !is_admin,
+            shared_chat: component.guild_id.is_some(),
+            platform_role_ids,
+            chat_context: None,
+            action,
+            attachments: Vec::new(),
+        };
+
+        let result = self.dispatcher.dispatch_safe(normalized).await;
+        if result.error.is_none() && !result.response.is_empty() {
+            self.deliver_reply(&ctx.http, component.channel_id, &result.response, &result.actions).await;
+            deliver_artifacts(&ctx.http, component.channel_id, &result.attachments).await;
+        } else if let Some(error) = result.error {
+            let error_msg = format!("Sorry, I encountered an error: {}", error);
+            let _ = component.channel_id.say(&ctx.http, &error_msg).await;
+        }
+    }
+
     async fn ready(&self, _ctx: Context, ready: Ready) {
         log::info!("Discord: Bot connected as {} (id={})", ready.user.name, ready.user.id);
         let _ = self.bot_user_id.set(ready.user.id);
@@ -438,14 +624,12 @@ impl DiscordHandler {
                         let duration_ms = event.data.get("duration_ms")
                             .and_then(|v| v.as_i64())
                             .unwrap_or(0);
-                        let content = event.data.get("content")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("");
+                        let content = crate::tools::payload::event_display(&event.data, RenderTarget::Plain);
                         // Skip say_to_user in event stream — content comes through result.response
                         if tool_name == "say_to_user" {
                             None
                         } else {
-                            format_tool_result_for_discord(tool_name, success, duration_ms, content, verbosity)
+                            format_tool_result_for_discord(tool_name, success, duration_ms, &content, verbosity)
                         }
                     }
                     "subagent.tool_call" => {
@@ -615,13 +799,8 @@ impl DiscordHandler {
         if result.error.is_none() && !result.response.is_empty() {
             // Discord has a 2000 character limit per message
             let response = &result.response;
-            let chunks = util::split_message(response, 2000);
-
-            for chunk in chunks {
-                if let Err(e) = msg.channel_id.say(&ctx.http, &chunk).await {
-                    log::error!("Failed to send Discord message: {}", e);
-                }
-            }
+            self.deliver_reply(&ctx.http, msg.channel_id, response, &result.actions).await;
+            deliver_artifacts(&ctx.http, msg.channel_id, &result.attachments).await;
 
             // Send image embeds for any image URLs found in the response
             let image_urls = extract_image_urls(response);
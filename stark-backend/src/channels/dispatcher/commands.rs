@@ -1,12 +1,18 @@
 use crate::ai::{AiClient, AiResponse, Message, ThinkingLevel, ToolHistoryEntry};
+use crate::ai::multi_agent::types::TaskStatus;
+use crate::away;
 use crate::tools::ToolDefinition;
+use crate::channels::chat_commands::{self, ChatMode, ParsedCommand};
+use crate::channels::response_style::{self, ResponseStyle};
 use crate::channels::types::{DispatchResult, NormalizedMessage};
 use crate::context;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::SessionScope;
-use crate::telemetry;
+use crate::models::{ChannelSettingKey, SessionScope};
+use crate::telemetry::{self, Watchdog};
+use chrono::Utc;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::interval;
 
@@ -15,6 +21,11 @@ use super::MessageDispatcher;
 /// How often to broadcast "still waiting" events during long AI calls
 const AI_PROGRESS_INTERVAL_SECS: u64 = 30;
 
+/// Memories shown by /memory list
+const MEMORY_LIST_LIMIT: usize = 10;
+/// Memory content shown per line in /memory list
+const MEMORY_PREVIEW_CHARS: usize = 120;
+
 /// Compiled regex pattern for inline thinking (e.g., "/t:medium What is...")
 pub(super) static INLINE_THINKING_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?i)^/(?:t|think|thinking):(\w+)\s+(.+)$").unwrap()
@@ -45,6 +56,273 @@ pub(super) fn parse_inline_thinking(text: &str) -> (Option<ThinkingLevel>, Optio
 }
 
 impl MessageDispatcher {
+    /// Commands switched off for this channel
+    fn disabled_commands(&self, channel_id: i64) -> Vec<String> {
+        self.db
+            .get_channel_setting(channel_id, ChannelSettingKey::DisabledCommands.as_ref())
+            .ok()
+            .flatten()
+            .map(|s| chat_commands::parse_disabled(&s))
+            .unwrap_or_default()
+    }
+
+    /// The slash command in this message, if the backend should answer it.
+    /// Disabled commands return None and go to the agent as ordinary text.
+    pub(super) fn resolve_chat_command(&self, message: &NormalizedMessage) -> Option<ParsedCommand> {
+        if message.action.is_some() {
+            return None;
+        }
+        let mut command = chat_commands::parse(&message.text)?;
+        match chat_commands::lookup(&command.name) {
+            Some(spec) if !chat_commands::is_enabled(spec, &self.disabled_commands(message.channel_id)) => None,
+            Some(spec) => {
+                command.name = spec.name.to_string();
+                Some(command)
+            }
+            None => Some(command),
+        }
+    }
+
+    /// Answer a slash command without invoking the AI
+    pub(super) async fn handle_chat_command(&self, message: &NormalizedMessage, command: &ParsedCommand) -> DispatchResult {
+        let spec = match chat_commands::lookup(&command.name) {
+            Some(spec) => spec,
+            None => {
+                return self.command_reply(
+                    message,
+                    format!("Unknown command `/{}`. Send /help to see what's available.", command.name),
+                )
+            }
+        };
+        if spec.owner_only && message.force_safe_mode {
+            log::warn!("[COMMANDS] Non-admin {} tried /{}", message.user_name, spec.name);
+            return self.command_reply(message, format!("Only the owner can use `/{}`.", spec.name));
+        }
+        log::info!("[COMMANDS] /{} from {} on channel {}", spec.name, message.user_name, message.channel_id);
+
+        let response = match spec.name {
+            "new" => return self.handle_reset_command(message).await,
+            "help" => chat_commands::help_text(&self.disabled_commands(message.channel_id), !message.force_safe_mode),
+            "stop" => self.stop_command(message),
+            "tasks" => self.tasks_command(message),
+            "memory" => self.memory_command(message, &command.args),
+            "mode" => self.mode_command(message, &command.args),
+            "style" => self.style_command(message, &command.args),
+            "away" => self.away_command(&command.args),
+            _ => format!("`/{}` isn't handled here.", spec.name),
+        };
+        self.command_reply(message, response)
+    }
+
+    pub(super) fn command_reply(&self, message: &NormalizedMessage, response: String) -> DispatchResult {
+        self.broadcaster.broadcast(GatewayEvent::agent_response(
+            message.channel_id,
+            &message.user_name,
+            &response,
+        ));
+        DispatchResult::success(response)
+    }
+
+    /// /stop: cancel the current run and its subagents
+    fn stop_command(&self, message: &NormalizedMessage) -> String {
+        let running = self.execution_tracker.get_execution_id(message.channel_id).is_some();
+        self.execution_tracker.cancel_execution(message.channel_id);
+        self.execution_tracker.cancel_all_sessions_for_channel(message.channel_id);
+        let subagents = self
+            .subagent_manager
+            .as_ref()
+            .map(|manager| manager.cancel_all_for_channel(message.channel_id))
+            .unwrap_or(0);
+        match (running, subagents) {
+            (false, 0) => "Nothing is running.".to_string(),
+            (_, 0) => "Stopped.".to_string(),
+            (_, n) => format!("Stopped, along with {} subagent(s).", n),
+        }
+    }
+
+    /// /tasks: the planner's task list for the current run
+    fn tasks_command(&self, message: &NormalizedMessage) -> String {
+        let tasks = self.execution_tracker.get_planner_tasks(message.channel_id);
+        if tasks.is_empty() {
+            return "No tasks right now.".to_string();
+        }
+        let mut lines = vec!["**Tasks**".to_string()];
+        for task in tasks {
+            let marker = match task.status {
+                TaskStatus::Pending => "⬜",
+                TaskStatus::InProgress => "▶️",
+                TaskStatus::Completed => "✅",
+                TaskStatus::Cancelled => "✖️",
+            };
+            lines.push(format!("{} {}. {}", marker, task.id, task.description));
+        }
+        lines.join("\n")
+    }
+
+    /// /memory list: the user's most recent memories
+    fn memory_command(&self, message: &NormalizedMessage, args: &[String]) -> String {
+        if args.first().map(|a| a.to_lowercase()).as_deref() != Some("list") {
+            return "Usage: `/memory list`".to_string();
+        }
+        let identity_id = match self.db.get_or_create_identity(
+            &message.channel_type,
+            &message.user_id,
+            Some(&message.user_name),
+        ) {
+            Ok(identity) => identity.identity_id,
+            Err(e) => return format!("Failed to look up your identity: {}", e),
+        };
+        let memories = match self.db.list_memories_filtered(None, Some(&identity_id), None, None) {
+            Ok(memories) => memories,
+            Err(e) => return format!("Failed to load memories: {}", e),
+        };
+        if memories.is_empty() {
+            return "No memories saved for you yet.".to_string();
+        }
+        let mut lines = vec![format!("**Your memories** ({} total, newest first)", memories.len())];
+        for memory in memories.iter().rev().take(MEMORY_LIST_LIMIT) {
+            let mut preview: String = memory.content.split_whitespace().collect::<Vec<_>>().join(" ");
+            if preview.chars().count() > MEMORY_PREVIEW_CHARS {
+                preview = preview.chars().take(MEMORY_PREVIEW_CHARS).collect::<String>() + "…";
+            }
+            lines.push(format!("#{} [{}] {}", memory.id, memory.memory_type, preview));
+        }
+        lines.join("\n")
+    }
+
+    /// /mode [plan|direct|auto]: show or set how this chat runs requests
+    fn mode_command(&self, message: &NormalizedMessage, args: &[String]) -> String {
+        let requested = match args.first() {
+            None => {
+                return match chat_commands::get_chat_mode(&self.db, message.channel_id, &message.chat_id) {
+                    Some(mode) => format!("This chat is in **{}** mode. `/mode auto` goes back to the default.", mode.as_str()),
+                    None => "This chat is in **auto** mode: the agent decides whether to plan. Use `/mode plan` or `/mode direct` to change it.".to_string(),
+                };
+            }
+            Some(arg) => arg.to_lowercase(),
+        };
+        let mode = match requested.as_str() {
+            "auto" | "default" => None,
+            other => match ChatMode::from_str(other) {
+                Some(mode) => Some(mode),
+                None => return format!("Unknown mode '{}'. Use `/mode plan`, `/mode direct` or `/mode auto`.", other),
+            },
+        };
+        if let Err(e) = chat_commands::set_chat_mode(&self.db, message.channel_id, &message.chat_id, mode) {
+            return format!("Failed to set mode: {}", e);
+        }
+        match mode {
+            Some(ChatMode::Plan) => "**Plan** mode: requests are broken into tasks before anything runs.".to_string(),
+            Some(ChatMode::Direct) => "**Direct** mode: the agent acts on requests without planning first.".to_string(),
+            None => "**Auto** mode: the agent decides whether to plan.".to_string(),
+        }
+    }
+
+    /// /style [concise|verbose|technical|default]: show or set this session's response style
+    fn style_command(&self, message: &NormalizedMessage, args: &[String]) -> String {
+        // Gateway channels start a fresh session per message and carry the style
+        // over from the latest one, so that's the session to set it on
+        let session = match self.carry_over_session(message) {
+            Ok(session) => session,
+            Err(e) => return format!("Failed to load the session: {}", e),
+        };
+        let choices = "`/style concise`, `/style verbose`, `/style technical` or `/style default`";
+        let requested = match args.first() {
+            None => {
+                return match response_style::resolve(&self.db, &session) {
+                    Some(style) => format!("Answering in **{}** style ({}). Switch with {}.", style.as_str(), style.description().to_lowercase(), choices),
+                    None => format!("No response style set. Pick one with {}.", choices),
+                };
+            }
+            Some(arg) => arg.to_lowercase(),
+        };
+        let style = match requested.as_str() {
+            "default" | "auto" | "off" => None,
+            other => match ResponseStyle::from_str(other) {
+                Some(style) => Some(style),
+                None => return format!("Unknown style '{}'. Use {}.", other, choices),
+            },
+        };
+        if let Err(e) = self.db.set_session_response_style(session.id, style.map(|s| s.as_str())) {
+            return format!("Failed to set style: {}", e);
+        }
+        match style {
+            Some(style) => format!("**{}** style: {}.", style.as_str(), style.description().to_lowercase()),
+            None => match response_style::channel_style(&self.db, message.channel_id) {
+                Some(style) => format!("Style cleared; this channel's default (**{}**) applies.", style.as_str()),
+                None => "Style cleared; the agent answers as usual.".to_string(),
+            },
+        }
+    }
+
+    /// /away [on [duration] [message]|off|allow <tool>|deny <tool>]: show or change away mode
+    fn away_command(&self, args: &[String]) -> String {
+        let mut settings = away::load(&self.db);
+        let usage = "Use `/away on [30m|4h|3d] [message]`, `/away off`, `/away allow <tool>` or `/away deny <tool>`.";
+        let action = args.first().map(|a| a.to_lowercase());
+        match action.as_deref() {
+            None => {
+                let queued = self.db.count_away_requests().unwrap_or(0);
+                let now = Utc::now();
+                let scheduled = settings.starts_at.filter(|start| settings.enabled && *start > now);
+                let state = match (settings.is_active_at(now), settings.ends_at, scheduled) {
+                    (true, Some(end), _) => format!("**Away** until {}", end.format("%Y-%m-%d %H:%M UTC")),
+                    (true, None, _) => "**Away**".to_string(),
+                    (false, _, Some(start)) => format!("Away from {}", start.format("%Y-%m-%d %H:%M UTC")),
+                    (false, _, None) => "Not away".to_string(),
+                };
+                let allowed = if settings.allowed_actions.is_empty() {
+                    "read-only tools only".to_string()
+                } else {
+                    settings.allowed_actions.join(", ")
+                };
+                return format!(
+                    "{}. {} queued request(s). Autonomous runs while away: {}.\n{}",
+                    state, queued, allowed, usage
+                );
+            }
+            Some("on") => {
+                let mut rest = &args[1..];
+                settings.ends_at = None;
+                if let Some(duration) = rest.first().and_then(|a| away::parse_duration(a)) {
+                    settings.ends_at = Some(Utc::now() + duration);
+                    rest = &rest[1..];
+                }
+                if !rest.is_empty() {
+                    settings.message = Some(rest.join(" "));
+                }
+                settings.enabled = true;
+                settings.starts_at = None;
+            }
+            Some("off") => settings.enabled = false,
+            Some("allow") | Some("deny") => {
+                let Some(tool) = args.get(1).map(|t| t.to_lowercase()) else {
+                    return usage.to_string();
+                };
+                settings.allowed_actions.retain(|a| a != &tool);
+                if action.as_deref() == Some("allow") {
+                    settings.allowed_actions.push(tool);
+                }
+            }
+            Some(other) => return format!("Unknown option '{}'. {}", other, usage),
+        }
+        if let Err(e) = away::save(&self.db, &settings) {
+            return format!("Failed to save away mode: {}", e);
+        }
+        self.broadcaster.broadcast(GatewayEvent::custom("away_mode.changed", serde_json::json!(settings)));
+        match action.as_deref() {
+            Some("on") => match settings.ends_at {
+                Some(end) => format!("Away until {}. Correspondents will be told and their requests queued.", end.format("%Y-%m-%d %H:%M UTC")),
+                None => "Away until `/away off`. Correspondents will be told and their requests queued.".to_string(),
+            },
+            Some("off") => "Welcome back. Queued requests will be answered shortly.".to_string(),
+            _ => format!(
+                "Allowed while away: {}.",
+                if settings.allowed_actions.is_empty() { "read-only tools only".to_string() } else { settings.allowed_actions.join(", ") }
+            ),
+        }
+    }
+
     /// Handle thinking directive messages (e.g., "/think:medium" sets session default)
     pub(super) async fn handle_thinking_directive(&self, message: &NormalizedMessage) -> Option<DispatchResult> {
         let text = message.text.trim();
@@ -112,6 +390,7 @@ impl MessageDispatcher {
         tools: Vec<ToolDefinition>,
         channel_id: i64,
         session_id: i64,
+        watchdog: &Arc<Watchdog>,
     ) -> Result<AiResponse, crate::ai::AiError> {
         let broadcaster = self.broadcaster.clone();
         let mut elapsed_secs = 0u64;
@@ -145,6 +424,8 @@ impl MessageDispatcher {
 
         // Get cancellation token for immediate interruption
         let cancel_token = self.execution_tracker.get_cancellation_token(channel_id);
+        // Cancelling just the current planner task interrupts the call too
+        let task_token = self.execution_tracker.planner_task_token(channel_id).unwrap_or_default();
 
         // Broadcast the full context being sent to the AI (for debug panel)
         broadcaster.broadcast(GatewayEvent::agent_context_update(
@@ -156,13 +437,15 @@ impl MessageDispatcher {
         ));
 
         // Spawn the actual AI request
-        let ai_future = client.generate_with_tools(conversation, tool_history, tools.clone());
+        let ai_future = client.generate_with_tools(conversation.clone(), tool_history.clone(), tools.clone());
         tokio::pin!(ai_future);
 
-        // Watchdog LLM timeout
+        // Watchdog LLM timeout; a timed-out call is retried before giving up
         let llm_timeout = self.watchdog_config.timeout_for_llm();
         let llm_deadline = tokio::time::sleep(llm_timeout);
         tokio::pin!(llm_deadline);
+        let mut llm_retries_left = self.watchdog_config.llm_timeout_retries;
+        let _llm_phase = watchdog.enter_phase("llm", llm_timeout);
 
         // Create a ticker for progress updates (shorter interval for more visibility)
         let mut progress_ticker = interval(Duration::from_secs(AI_PROGRESS_INTERVAL_SECS));
@@ -192,6 +475,15 @@ impl MessageDispatcher {
 
                     return Err(crate::ai::AiError::new("Execution cancelled by user"));
                 }
+                _ = task_token.cancelled() => {
+                    log::info!("[AI_PROGRESS] Current planner task cancelled while waiting for AI response");
+
+                    if let Some(ref task_id) = thinking_task_id {
+                        self.execution_tracker.complete_task(task_id);
+                    }
+
+                    return Err(crate::ai::AiError::new("Task cancelled by user"));
+                }
                 // Watchdog: enforce LLM call timeout
                 _ = &mut llm_deadline => {
                     log::warn!(
@@ -200,18 +492,34 @@ impl MessageDispatcher {
                         channel_id
                     );
 
-                    // Complete the thinking task
-                    if let Some(ref task_id) = thinking_task_id {
-                        self.execution_tracker.complete_task(task_id);
-                    }
-
                     // Emit timeout telemetry
                     telemetry::emit_annotation("watchdog_llm_timeout", serde_json::json!({
                         "timeout_secs": llm_timeout.as_secs(),
                         "elapsed_secs": elapsed_secs,
                         "channel_id": channel_id,
+                        "retries_left": llm_retries_left,
                     }));
 
+                    // Abandon the hung request and send it again
+                    if llm_retries_left > 0 {
+                        llm_retries_left -= 1;
+                        watchdog.mark_degraded(format!("AI call timed out after {}s, retrying", llm_timeout.as_secs()));
+                        broadcaster.broadcast(GatewayEvent::agent_error(
+                            channel_id,
+                            &format!("AI call timed out after {}s. Retrying...", llm_timeout.as_secs()),
+                        ));
+                        ai_future.set(client.generate_with_tools(conversation.clone(), tool_history.clone(), tools.clone()));
+                        llm_deadline.as_mut().reset(tokio::time::Instant::now() + llm_timeout);
+                        watchdog.heartbeat();
+                        continue;
+                    }
+                    watchdog.mark_degraded(format!("AI call timed out after {}s", llm_timeout.as_secs()));
+
+                    // Complete the thinking task
+                    if let Some(ref task_id) = thinking_task_id {
+                        self.execution_tracker.complete_task(task_id);
+                    }
+
                     // Dispatch OnWatchdogTimeout hook
                     if let Some(hook_manager) = &self.hook_manager {
                         use crate::hooks::{HookContext, HookEvent};
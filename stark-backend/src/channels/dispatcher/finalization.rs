@@ -1,7 +1,11 @@
 use crate::ai::multi_agent::Orchestrator;
+use crate::ai::{AiClient, Message, ToolHistoryEntry};
 use crate::channels::types::NormalizedMessage;
 use crate::models::session_message::MessageRole as DbMessageRole;
-use crate::models::CompletionStatus;
+use crate::context::CompactionLevel;
+use crate::db::tables::paused_executions::PausedExecution;
+use crate::gateway::protocol::GatewayEvent;
+use crate::models::{AgentSettings, ChannelSettingKey, CompletionStatus};
 use crate::telemetry::Watchdog;
 use std::sync::Arc;
 
@@ -75,6 +79,149 @@ impl MessageDispatcher {
         }
     }
 
+    /// Learn stable facts about the sender from a completed session and merge
+    /// them into their identity profile. Runs in the background; safe mode
+    /// sessions never update profiles.
+    pub(super) fn spawn_profile_update(
+        &self,
+        original_message: &NormalizedMessage,
+        bot_response: &str,
+        is_safe_mode: bool,
+    ) {
+        if is_safe_mode || bot_response.is_empty() { return; }
+        let enabled = self.db.get_bot_settings()
+            .map(|s| s.chat_session_memory_generation)
+            .unwrap_or(true);
+        if !enabled { return; }
+
+        let identity_id = match self.db.get_or_create_identity(
+            &original_message.channel_type,
+            &original_message.user_id,
+            Some(&original_message.user_name),
+        ) {
+            Ok(identity) => identity.identity_id,
+            Err(e) => {
+                log::warn!("[PROFILE] Could not resolve identity: {}", e);
+                return;
+            }
+        };
+        let Ok(Some(settings)) = self.db.get_active_agent_settings() else { return };
+        let client = match AiClient::from_settings_with_wallet_provider(&settings, self.wallet_provider.clone()) {
+            Ok(c) => c,
+            Err(e) => {
+                log::warn!("[PROFILE] Failed to create AI client: {}", e);
+                return;
+            }
+        };
+
+        let db = Arc::clone(&self.db);
+        let user_input = original_message.text.clone();
+        let bot_response = bot_response.to_string();
+        tokio::spawn(async move {
+            match crate::memory::profile::update_from_session(&db, &client, &identity_id, &user_input, &bot_response).await {
+                Ok(true) => log::info!("[PROFILE] Updated profile for identity {}", identity_id),
+                Ok(false) => {}
+                Err(e) => log::warn!("[PROFILE] {}", e),
+            }
+        });
+    }
+
+    /// Queue a background compaction when the session crossed its threshold.
+    ///
+    /// The task waits on the conversation's session lane, so it runs after the
+    /// current turn releases it and before the next message is processed. The
+    /// channel's `auto_compaction` / `compaction_threshold` settings override
+    /// the global background tier.
+    pub(super) fn schedule_auto_compaction(
+        &self,
+        message: &NormalizedMessage,
+        session_id: i64,
+        settings: &AgentSettings,
+        memory_identity: Option<&str>,
+    ) {
+        let channel_setting = |key: ChannelSettingKey| {
+            self.db.get_channel_setting(message.channel_id, key.as_ref()).ok().flatten()
+        };
+        if channel_setting(ChannelSettingKey::AutoCompaction).as_deref() == Some("false") {
+            return;
+        }
+        let threshold_override = channel_setting(ChannelSettingKey::CompactionThreshold)
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .map(|pct| pct.clamp(10.0, 95.0) / 100.0);
+
+        let level = self.context_manager.auto_compaction_level(session_id, threshold_override);
+        if level == CompactionLevel::None {
+            return;
+        }
+        // Already queued by an earlier turn
+        if !self.compacting_sessions.insert(session_id) {
+            return;
+        }
+        let client = match AiClient::from_settings_with_wallet_provider(settings, self.wallet_provider.clone()) {
+            Ok(c) => c,
+            Err(e) => {
+                log::warn!("[COMPACTION] Failed to create AI client: {}", e);
+                self.compacting_sessions.remove(&session_id);
+                return;
+            }
+        };
+
+        let context_manager = Arc::clone(&self.context_manager);
+        let compacting_sessions = Arc::clone(&self.compacting_sessions);
+        let broadcaster = Arc::clone(&self.broadcaster);
+        let session_lanes = Arc::clone(&self.session_lanes);
+        let lane_key = format!("{}:{}:{}", message.channel_type, message.channel_id, message.chat_id);
+        let channel_id = message.channel_id;
+        let identity = memory_identity.map(str::to_string);
+        let threshold = threshold_override.unwrap_or_else(|| self.context_manager.background_threshold());
+
+        tokio::spawn(async move {
+            let _lane_guard = session_lanes.acquire(&lane_key).await;
+            // A /new or another compaction may have shrunk the context meanwhile
+            let level = match context_manager.auto_compaction_level(session_id, threshold_override) {
+                CompactionLevel::None => {
+                    compacting_sessions.remove(&session_id);
+                    return;
+                }
+                level => level,
+            };
+            let tokens_before = context_manager.context_tokens(session_id);
+            let level_name = match level {
+                CompactionLevel::Emergency => "emergency",
+                CompactionLevel::Aggressive => "aggressive",
+                _ => "background",
+            };
+            log::info!(
+                "[COMPACTION] Session {} at {} tokens, running {} compaction",
+                session_id, tokens_before, level_name
+            );
+            broadcaster.broadcast(GatewayEvent::compaction_started(
+                channel_id, session_id, level_name, tokens_before, threshold,
+            ));
+
+            let result = context_manager
+                .run_auto_compaction(session_id, level, &client, identity.as_deref())
+                .await;
+            let tokens_after = context_manager.context_tokens(session_id);
+            match &result {
+                Ok(kind) => log::info!(
+                    "[COMPACTION] Session {} {} compaction done: {} -> {} tokens",
+                    session_id, kind, tokens_before, tokens_after
+                ),
+                Err(e) => log::error!("[COMPACTION] Session {} compaction failed: {}", session_id, e),
+            }
+            broadcaster.broadcast(GatewayEvent::compaction_finished(
+                channel_id,
+                session_id,
+                result.as_ref().ok().copied(),
+                tokens_before,
+                tokens_after,
+                result.as_ref().err().map(String::as_str),
+            ));
+            compacting_sessions.remove(&session_id);
+        });
+    }
+
     /// Try to advance to the next task in the queue.
     /// If a next task exists, marks it as in_progress and broadcasts updates.
     /// If no tasks remain, marks the session as complete in the database and broadcasts completion.
@@ -91,6 +238,7 @@ impl MessageDispatcher {
                 next_task.id,
                 next_task.description
             );
+            self.execution_tracker.begin_planner_task(channel_id, next_task.id);
             self.broadcast_task_status_change(
                 channel_id,
                 session_id,
@@ -103,6 +251,7 @@ impl MessageDispatcher {
         } else if orchestrator.task_queue_is_empty() || orchestrator.all_tasks_complete() {
             // Queue is empty or all tasks completed - end the session
             log::info!("[ORCHESTRATED_LOOP] All tasks completed, stopping loop");
+            self.execution_tracker.end_planner_task(channel_id);
             self.active_cache.update_completion_status(session_id, CompletionStatus::Complete);
             self.broadcast_session_complete(channel_id, session_id);
             TaskAdvanceResult::AllTasksComplete
@@ -115,6 +264,124 @@ impl MessageDispatcher {
         }
     }
 
+    /// Checkpoint the tool loop for a pause: persist the full agent context and
+    /// the loop's progress so `/api/chat/resume` continues where it stopped.
+    /// Returning ends the dispatch, which releases the session lane.
+    pub(super) fn pause_tool_loop(
+        &self,
+        original_message: &NormalizedMessage,
+        session_id: i64,
+        orchestrator: &Orchestrator,
+        tool_history: &[ToolHistoryEntry],
+        messages: &[Message],
+        tool_call_log: &[String],
+    ) -> Result<(String, bool, Option<String>), String> {
+        self.active_cache.save_agent_context(session_id, orchestrator.context());
+        self.db
+            .save_paused_execution(
+                original_message.channel_id,
+                &original_message.chat_id,
+                session_id,
+                orchestrator.context(),
+                tool_history,
+                messages,
+                tool_call_log,
+            )
+            .map_err(|e| format!("Failed to save paused execution: {}", e))?;
+        self.execution_tracker.end_planner_task(original_message.channel_id);
+
+        let queue = orchestrator.task_queue();
+        log::info!(
+            "[ORCHESTRATED_LOOP] Paused session {} after {} tool call(s), {}/{} task(s) done",
+            session_id,
+            tool_call_log.len(),
+            queue.completed_count(),
+            queue.total()
+        );
+        self.broadcaster.broadcast(GatewayEvent::custom(
+            "execution.paused",
+            serde_json::json!({
+                "channel_id": original_message.channel_id,
+                "session_id": session_id,
+                "current_task_id": queue.current_task().map(|t| t.id),
+            }),
+        ));
+
+        Ok((
+            format!(
+                "⏸️ Paused after {} tool call(s) ({}/{} tasks done). Resume to continue where I left off.",
+                tool_call_log.len(),
+                queue.completed_count(),
+                queue.total()
+            ),
+            false,
+            None,
+        ))
+    }
+
+    /// Park the execution on the planner's clarification question: the agent
+    /// context is stored like a pause, and the user's next message in this chat
+    /// resumes it with the answer attached (see `take_parked_clarification`).
+    /// Returns the question as the response.
+    pub(super) fn park_for_clarification(
+        &self,
+        original_message: &NormalizedMessage,
+        session_id: i64,
+        orchestrator: &Orchestrator,
+        tool_call_log: &[String],
+        question_content: &str,
+    ) -> Result<(String, bool, Option<String>), String> {
+        self.active_cache.save_agent_context(session_id, orchestrator.context());
+        // The planner has done nothing but ask, so there is no tool history to keep
+        self.db
+            .save_paused_execution(
+                original_message.channel_id,
+                &original_message.chat_id,
+                session_id,
+                orchestrator.context(),
+                &[],
+                &[],
+                tool_call_log,
+            )
+            .map_err(|e| format!("Failed to park execution for clarification: {}", e))?;
+
+        let pending = orchestrator.context().pending_clarification.as_ref();
+        log::info!(
+            "[ORCHESTRATED_LOOP] Session {} waiting on clarification: {}",
+            session_id,
+            pending.map(|c| c.question.as_str()).unwrap_or("")
+        );
+        self.broadcaster.broadcast(GatewayEvent::custom(
+            "execution.clarification",
+            serde_json::json!({
+                "channel_id": original_message.channel_id,
+                "session_id": session_id,
+                "question": pending.map(|c| c.question.clone()),
+                "options": pending.map(|c| c.options.clone()).unwrap_or_default(),
+            }),
+        ));
+
+        Ok((question_content.to_string(), false, None))
+    }
+
+    /// The execution parked on a clarification question in this chat, removed
+    /// so it resumes exactly once. Executions paused by the owner stay put.
+    pub(super) fn take_parked_clarification(&self, original_message: &NormalizedMessage) -> Option<PausedExecution> {
+        let paused = match self.db.get_paused_execution(original_message.channel_id, &original_message.chat_id) {
+            Ok(Some(paused)) if paused.context.pending_clarification.is_some() => paused,
+            Ok(_) => return None,
+            Err(e) => {
+                log::error!("[MULTI_AGENT] Failed to load parked execution: {}", e);
+                return None;
+            }
+        };
+        if let Err(e) = self.db.delete_paused_execution(paused.id) {
+            log::error!("[MULTI_AGENT] Failed to clear parked execution {}: {}", paused.id, e);
+            return None;
+        }
+        Some(paused)
+    }
+
     /// Finalization logic shared by both native and text tool loop paths:
     /// clearing active skill, saving orchestrator context, updating completion status,
     /// saving cancellation/max-iteration summaries, building final return value.
@@ -176,6 +443,7 @@ impl MessageDispatcher {
                     is_safe_mode,
                     subtype_opt,
                 );
+                self.spawn_profile_update(original_message, memory_content, is_safe_mode);
             }
         }
 
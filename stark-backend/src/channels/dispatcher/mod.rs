@@ -5,19 +5,24 @@ use crate::ai::{
     AiClient, ArchetypeId, ArchetypeRegistry, Message, MessageRole, ModelArchetype,
     ThinkingLevel,
 };
+use crate::ai_quota;
+use crate::channels::{actions, redaction, triage};
 use crate::channels::types::{DispatchResult, NormalizedMessage};
 use crate::config::{MemoryConfig, NotesConfig};
 use crate::notes::NoteStore;
 use crate::context::{self, estimate_tokens, ContextManager};
+use crate::db::tables::handoffs::{Handoff, NewHandoff};
 use crate::db::{ActiveSessionCache, Database};
 use crate::execution::{ExecutionTracker, SessionLaneManager};
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
 use crate::models::session_message::MessageRole as DbMessageRole;
-use crate::models::{AgentSettings, CompletionStatus, SessionScope, SpecialRoleGrants, DEFAULT_MAX_TOOL_ITERATIONS};
+use crate::models::{
+    AgentSettings, ChannelSettingKey, CompletionStatus, SessionScope, SpecialRoleGrants, DEFAULT_MAX_TOOL_ITERATIONS,
+};
 use crate::telemetry::{
     self, Rollout, RolloutConfig, RolloutManager, SpanCollector, SpanType,
-    RewardEmitter, TelemetryStore, Watchdog, WatchdogConfig, ResourceManager,
+    RewardEmitter, TelemetryStore, Watchdog, WatchdogConfig, WatchdogNotifier, ResourceManager,
 };
 use crate::tools::{ToolConfig, ToolContext, ToolDefinition, ToolExecution, ToolRegistry};
 use chrono::Utc;
@@ -35,6 +40,13 @@ mod tool_processing;
 /// Actual value is configurable via bot settings
 pub(super) const FALLBACK_MAX_TOOL_ITERATIONS: usize = DEFAULT_MAX_TOOL_ITERATIONS as usize;
 
+/// How long inbound platform message IDs are remembered for redelivery dedup
+const INBOUND_DEDUP_TTL_SECS: i64 = 24 * 3600;
+
+/// Channel types whose message IDs come from an external platform that may redeliver.
+/// Internal triggers (cron, kanban, hooks) mint their own IDs and are never deduped.
+const DEDUP_CHANNEL_TYPES: &[&str] = &["discord", "telegram", "slack", "twitter", "farcaster", "gmail"];
+
 /// Dispatcher routes messages to the AI and returns responses
 pub struct MessageDispatcher {
     db: Arc<Database>,
@@ -47,7 +59,9 @@ pub struct MessageDispatcher {
     /// Encapsulates both Standard mode (EnvWalletProvider with raw private key)
     /// and Flash mode (FlashWalletProvider with Privy proxy)
     wallet_provider: Option<Arc<dyn crate::wallet::WalletProvider>>,
-    context_manager: ContextManager,
+    context_manager: Arc<ContextManager>,
+    /// Sessions with an automatic compaction queued or running
+    compacting_sessions: Arc<dashmap::DashSet<i64>>,
     archetype_registry: ArchetypeRegistry,
     /// Memory configuration
     memory_config: MemoryConfig,
@@ -79,6 +93,8 @@ pub struct MessageDispatcher {
     session_lanes: Arc<SessionLaneManager>,
     /// In-memory cache for active session metadata + agent context (reduces SQLite writes)
     active_cache: Arc<ActiveSessionCache>,
+    /// Cluster coordinator for cross-instance execution locks (None = single instance)
+    cluster: Option<Arc<crate::cluster::Cluster>>,
     /// Mock AI client for integration tests (bypasses real AI API)
     #[cfg(test)]
     mock_ai_client: Option<crate::ai::MockAiClient>,
@@ -154,6 +170,7 @@ impl MessageDispatcher {
         if let Some(ref store) = notes_store {
             subagent_manager.set_notes_store(store.clone());
         }
+        subagent_manager.set_self_ref();
         log::info!("[DISPATCHER] SubAgentManager initialized");
 
         // In-memory session cache — reduces SQLite writes on the hot path
@@ -192,7 +209,8 @@ impl MessageDispatcher {
             execution_tracker,
             session_writer,
             wallet_provider,
-            context_manager,
+            context_manager: Arc::new(context_manager),
+            compacting_sessions: Arc::new(dashmap::DashSet::new()),
             archetype_registry: ArchetypeRegistry::new(),
             memory_config,
             hybrid_search: None,
@@ -209,6 +227,7 @@ impl MessageDispatcher {
             watchdog_config: WatchdogConfig::default(),
             session_lanes: SessionLaneManager::new(),
             active_cache,
+            cluster: None,
             #[cfg(test)]
             mock_ai_client: None,
         }
@@ -245,9 +264,18 @@ impl MessageDispatcher {
         self
     }
 
+    /// Set the cluster coordinator (serializes conversations across instances)
+    pub fn with_cluster(mut self, cluster: Arc<crate::cluster::Cluster>) -> Self {
+        self.cluster = Some(cluster);
+        self
+    }
+
     /// Set the hybrid search engine (shared with both tool context and context manager)
     pub fn with_hybrid_search(mut self, engine: Arc<crate::memory::HybridSearchEngine>) -> Self {
-        self.context_manager.set_hybrid_search(engine.clone());
+        match Arc::get_mut(&mut self.context_manager) {
+            Some(context_manager) => context_manager.set_hybrid_search(engine.clone()),
+            None => log::warn!("[DISPATCH] Context manager already shared; hybrid search not applied to context"),
+        }
         self.hybrid_search = Some(engine);
         self
     }
@@ -298,7 +326,8 @@ impl MessageDispatcher {
             execution_tracker,
             session_writer,
             wallet_provider: None,
-            context_manager,
+            context_manager: Arc::new(context_manager),
+            compacting_sessions: Arc::new(dashmap::DashSet::new()),
             archetype_registry: ArchetypeRegistry::new(),
             memory_config,
             hybrid_search: None,
@@ -315,6 +344,7 @@ impl MessageDispatcher {
             watchdog_config: WatchdogConfig::default(),
             session_lanes: SessionLaneManager::new(),
             active_cache,
+            cluster: None,
             #[cfg(test)]
             mock_ai_client: None,
         }
@@ -356,7 +386,29 @@ impl MessageDispatcher {
         use futures_util::FutureExt;
 
         let channel_id = message.channel_id;
-        match AssertUnwindSafe(self.dispatch(message)).catch_unwind().await {
+
+        // Platforms can redeliver the same message (webhook retries, gateway reconnects).
+        // The original delivery already replied, so duplicates are dropped silently.
+        let dedup_key = message
+            .message_id
+            .as_ref()
+            .filter(|_| DEDUP_CHANNEL_TYPES.contains(&message.channel_type.as_str()))
+            .map(|mid| {
+                (message.channel_type.clone(), format!("{}:{}:{}", message.channel_id, message.chat_id, mid))
+            });
+        if let Some((ref scope, ref key)) = dedup_key {
+            match self.db.claim_idempotency_key(scope, key, None) {
+                Ok(crate::db::tables::idempotency::IdempotencyClaim::New) => {}
+                Ok(_) => {
+                    log::info!("[DISPATCH] Ignoring duplicate delivery of {} message {}", scope, key);
+                    return DispatchResult::success(String::new());
+                }
+                // Fail open: a dedup miss is better than dropping a real message
+                Err(e) => log::warn!("[DISPATCH] Idempotency check failed for {} {}: {}", scope, key, e),
+            }
+        }
+
+        let result = match AssertUnwindSafe(self.dispatch(message)).catch_unwind().await {
             Ok(result) => result,
             Err(panic_info) => {
                 let panic_msg = if let Some(s) = panic_info.downcast_ref::<&str>() {
@@ -374,9 +426,112 @@ impl MessageDispatcher {
                 self.execution_tracker.complete_execution(channel_id);
                 DispatchResult::error(format!("Internal error (panic): {}", panic_msg))
             }
+        };
+
+        if let Some((scope, key)) = dedup_key {
+            // Failed runs release the key so a genuine retry can try again
+            let recorded = if result.error.is_some() {
+                self.db.release_idempotency_key(&scope, &key)
+            } else {
+                self.db.complete_idempotency_key(
+                    &scope,
+                    &key,
+                    &result.response,
+                    result.message_id.as_deref(),
+                    INBOUND_DEDUP_TTL_SECS,
+                )
+            };
+            if let Err(e) = recorded {
+                log::warn!("[DISPATCH] Failed to record idempotency result for {} {}: {}", scope, key, e);
+            }
+        }
+
+        result
+    }
+
+    /// The session whose state the next dispatch for this chat carries over:
+    /// the channel's latest session for gateway channels (which start a fresh
+    /// session per message), the chat's own session otherwise
+    pub(crate) fn carry_over_session(&self, message: &NormalizedMessage) -> rusqlite::Result<crate::models::ChatSession> {
+        let scope = if message.chat_id != message.user_id { SessionScope::Group } else { SessionScope::Dm };
+        let is_gateway_channel = matches!(
+            message.channel_type.to_lowercase().as_str(),
+            "discord" | "telegram" | "web" | "external_channel"
+        );
+        if is_gateway_channel {
+            match self.db.get_latest_session_for_channel(&message.channel_type, message.channel_id)? {
+                Some(session) => Ok(session),
+                None => self.db.create_gateway_session(&message.channel_type, message.channel_id, scope, None),
+            }
+        } else {
+            self.db.get_or_create_chat_session(&message.channel_type, message.channel_id, &message.chat_id, scope, None)
         }
     }
 
+    /// Keep a group message the bot wasn't summoned for as context for its
+    /// next answer, without running the agent (passive group-chat mode)
+    pub fn remember(&self, message: &NormalizedMessage) {
+        let session = match self.carry_over_session(message) {
+            Ok(session) => session,
+            Err(e) => {
+                log::warn!("[DISPATCH] Failed to load session to remember a {} message: {}", message.channel_type, e);
+                return;
+            }
+        };
+        let content = format!("(overheard, not addressed to you) @{}: {}", message.user_name, message.text);
+        if let Err(e) = self.db.add_session_message(
+            session.id,
+            DbMessageRole::User,
+            &content,
+            Some(&message.user_id),
+            Some(&message.user_name),
+            message.message_id.as_deref(),
+            None,
+        ) {
+            log::warn!("[DISPATCH] Failed to remember {} message in session {}: {}", message.channel_type, session.id, e);
+        }
+    }
+
+    /// Keep a message from a chat a human operator has taken over in the
+    /// handoff's session, for the operator to read and answer
+    fn hold_for_operator(&self, message: &NormalizedMessage, handoff: &Handoff) {
+        let session_id = match handoff.session_id {
+            Some(id) => id,
+            None => match self.carry_over_session(message) {
+                Ok(session) => {
+                    if let Err(e) = self.db.set_handoff_session(handoff.id, session.id) {
+                        log::warn!("[DISPATCH] Failed to link handoff {} to session {}: {}", handoff.id, session.id, e);
+                    }
+                    session.id
+                }
+                Err(e) => {
+                    log::warn!("[DISPATCH] Failed to load session for handoff {}: {}", handoff.id, e);
+                    return;
+                }
+            },
+        };
+        if let Err(e) = self.db.add_session_message(
+            session_id,
+            DbMessageRole::User,
+            &message.text,
+            Some(&message.user_id),
+            Some(&message.user_name),
+            message.message_id.as_deref(),
+            None,
+        ) {
+            log::warn!("[DISPATCH] Failed to store message for handoff {}: {}", handoff.id, e);
+        }
+        self.broadcaster.broadcast(GatewayEvent::custom(
+            "handoff.message",
+            serde_json::json!({
+                "handoff_id": handoff.id,
+                "session_id": session_id,
+                "from": message.user_name,
+                "text": message.text,
+            }),
+        ));
+    }
+
     /// Dispatch a normalized message to the AI and return the response
     pub async fn dispatch(&self, message: NormalizedMessage) -> DispatchResult {
         // Emit message received event
@@ -387,16 +542,122 @@ impl MessageDispatcher {
             &message.text,
         ));
 
+        // Button presses: notify listeners, and answer built-in, tx/post approval and tool confirmation actions without the agent
+        if let Some(ref action) = message.action {
+            self.broadcaster.broadcast(GatewayEvent::channel_action(
+                message.channel_id,
+                &message.chat_id,
+                &message.user_name,
+                action,
+            ));
+            if let Some(response) = actions::builtin_response(action) {
+                return DispatchResult::success(response);
+            }
+            if let Some(response) = crate::tx_queue::approval::handle_action(
+                &self.db,
+                self.tx_queue.clone(),
+                self.broadcaster.clone(),
+                self.wallet_provider.clone(),
+                &message,
+                action,
+            )
+            .await
+            {
+                return DispatchResult::success(response);
+            }
+            if let Some(response) = crate::social_calendar::handle_action(&self.db, &self.broadcaster, &message, action) {
+                return DispatchResult::success(response);
+            }
+            if let Some(response) = crate::tools::confirmation::handle_action(&self.db, &message, action) {
+                return DispatchResult::success(response);
+            }
+        }
+
+        // Slash commands are answered without the agent. /new waits for the session lane below;
+        // the rest (notably /stop) must not queue behind the run they act on.
+        let command = self.resolve_chat_command(&message);
+        if let Some(ref command) = command {
+            if command.name != "new" {
+                return self.handle_chat_command(&message, command).await;
+            }
+        }
+
         // Acquire session lane to serialize requests for the same channel/chat.
         // This prevents concurrent dispatches from racing on session creation,
         // context building, and tool execution for the same conversation.
         let lane_key = format!("{}:{}:{}", message.channel_type, message.channel_id, message.chat_id);
-        let _lane_guard = self.session_lanes.acquire(&lane_key).await;
+        // Typed user messages are triaged first; urgent ones reach the owner
+        // right away and go ahead of messages already waiting for this chat
+        let tags = (message.action.is_none() && message.session_mode.is_none()).then(|| triage::classify(&message.text));
+        let urgent = tags.is_some_and(|t| t.urgency == triage::Urgency::Urgent);
+        if let Some(tags) = tags.filter(|_| urgent) {
+            log::info!(
+                "[DISPATCH] Urgent message from {} on {} (lane busy: {})",
+                message.user_name,
+                message.channel_type,
+                self.session_lanes.is_session_busy(&lane_key)
+            );
+            let (db, broadcaster, urgent_message) = (self.db.clone(), self.broadcaster.clone(), message.clone());
+            tokio::spawn(async move {
+                triage::notify_owner(&db, &broadcaster, &urgent_message, tags).await;
+            });
+        }
+        // A chat handed to a human operator gets no automated answers until it is
+        // released; correspondents can also be handed over by a configured rule
+        if let Some(tags) = tags {
+            if let Ok(Some(handoff)) = self.db.get_active_handoff(message.channel_id, &message.chat_id) {
+                self.hold_for_operator(&message, &handoff);
+                return DispatchResult::success(String::new());
+            }
+            let rule = message
+                .force_safe_mode
+                .then(|| crate::handoff::rule_match(&crate::handoff::load_config(&self.db), &message.text, tags))
+                .flatten();
+            if let Some(reason) = rule {
+                let request = NewHandoff {
+                    session_id: None,
+                    channel_id: message.channel_id,
+                    channel_type: &message.channel_type,
+                    chat_id: &message.chat_id,
+                    user_name: Some(&message.user_name),
+                    reason: &reason,
+                    requested_by: "rule",
+                };
+                match crate::handoff::escalate(&self.db, Some(&*self.broadcaster), &request).await {
+                    Ok(handoff) => {
+                        self.hold_for_operator(&message, &handoff);
+                        return self.command_reply(&message, crate::handoff::RULE_REPLY.to_string());
+                    }
+                    Err(e) => log::warn!("[DISPATCH] Failed to hand the chat over: {}", e),
+                }
+            }
+        }
+        // While the owner is away, correspondents' non-urgent requests wait for their return
+        if tags.is_some() && !urgent && message.force_safe_mode {
+            let away = crate::away::load(&self.db);
+            if away.is_active_at(Utc::now()) {
+                match self.db.queue_away_request(&message) {
+                    Ok(id) => {
+                        log::info!("[DISPATCH] Owner away: queued request {} from {}", id, message.user_name);
+                        return self.command_reply(&message, away.auto_reply());
+                    }
+                    Err(e) => log::warn!("[DISPATCH] Failed to queue request while away: {}", e),
+                }
+            }
+        }
+        let _lane_guard = if urgent {
+            self.session_lanes.acquire_priority(&lane_key).await
+        } else {
+            self.session_lanes.acquire(&lane_key).await
+        };
+        // Same lane on other instances sharing the DB (no-op outside cluster mode)
+        let _cluster_lock = match self.cluster {
+            Some(ref cluster) => cluster.acquire_execution_lock(&lane_key).await,
+            None => None,
+        };
 
-        // Check for reset commands
-        let text_lower = message.text.trim().to_lowercase();
-        if text_lower == "/new" || text_lower == "/reset" {
-            return self.handle_reset_command(&message).await;
+        if let Some(ref command) = command {
+            return self.handle_chat_command(&message, command).await;
         }
 
         // Check for thinking directives (session-level setting)
@@ -407,6 +668,11 @@ impl MessageDispatcher {
         // Parse inline thinking directive and extract clean message
         let (thinking_level, clean_text) = commands::parse_inline_thinking(&message.text);
 
+        // An installed update is waiting for running executions; don't start another
+        if crate::self_update::is_draining() {
+            return DispatchResult::error("Restarting to install an update, please try again in a minute".to_string());
+        }
+
         // Start execution tracking with user message for descriptive display
         let user_msg = clean_text.as_deref().unwrap_or(&message.text);
         let execution_id = self.execution_tracker.start_execution(
@@ -429,16 +695,23 @@ impl MessageDispatcher {
         // Set up the watchdog for timeout enforcement
         let reward_emitter = Arc::new(RewardEmitter::new(Arc::clone(&span_collector)));
         let watchdog = Watchdog::new(
-            self.watchdog_config.clone(),
+            crate::tools::timeouts::watchdog_config(&self.db, &self.watchdog_config),
             Arc::clone(&span_collector),
             Arc::clone(&reward_emitter),
-        );
+        )
+        .with_notifier(WatchdogNotifier {
+            db: Arc::clone(&self.db),
+            broadcaster: Arc::clone(&self.broadcaster),
+            channel_id: message.channel_id,
+            chat_id: message.chat_id.clone(),
+        });
 
-        // Start heartbeat monitor for long-running executions
+        // Start heartbeat monitor for long-running executions (cancels stalled ones)
         let watchdog = Arc::new(watchdog);
         let heartbeat_handle = watchdog.start_heartbeat_monitor(
             message.channel_id,
             Arc::clone(&self.broadcaster),
+            Arc::clone(&self.execution_tracker),
         );
 
         // Install thread-local span collector for emit_* functions
@@ -451,6 +724,7 @@ impl MessageDispatcher {
             "user_name": message.user_name,
             "channel_type": message.channel_type,
             "rollout_id": rollout.rollout_id,
+            "instance_id": crate::cluster::instance_id(),
         });
         rollout_span.succeed();
         span_collector.record(rollout_span);
@@ -514,6 +788,9 @@ impl MessageDispatcher {
             || channel_type_lower == "web"
             || channel_type_lower == "external_channel";
 
+        // A response style picked with /style carries over to the next gateway session
+        let mut previous_response_style: Option<String> = None;
+
         // Collect previous session messages for gateway channels (max 10)
         let previous_gateway_messages: Vec<crate::models::SessionMessage> = if is_gateway_channel {
             const MAX_PREVIOUS_MESSAGES: i32 = 6;
@@ -525,6 +802,7 @@ impl MessageDispatcher {
             ) {
                 let messages = self.db.get_recent_session_messages(prev_session.id, MAX_PREVIOUS_MESSAGES)
                     .unwrap_or_default();
+                previous_response_style = prev_session.response_style.clone();
 
                 // Deactivate the old session
                 if let Err(e) = self.db.deactivate_session(prev_session.id) {
@@ -553,7 +831,13 @@ impl MessageDispatcher {
                 scope,
                 None,
             ) {
-                Ok(s) => {
+                Ok(mut s) => {
+                    if let Some(style) = previous_response_style.take() {
+                        match self.db.set_session_response_style(s.id, Some(&style)) {
+                            Ok(_) => s.response_style = Some(style),
+                            Err(e) => log::warn!("[DISPATCH] Failed to carry over response style: {}", e),
+                        }
+                    }
                     log::info!(
                         "[DISPATCH] Created fresh {} session {} (previous context: {} messages)",
                         message.channel_type, s.id, previous_gateway_messages.len()
@@ -657,8 +941,8 @@ impl MessageDispatcher {
         // Estimate tokens for the user message
         let user_tokens = estimate_tokens(message_text);
 
-        // Store user message in session with token count
-        if let Err(e) = self.db.add_session_message(
+        // Store user message in session with token count and attachment references
+        match self.db.add_session_message_with_attachments(
             session.id,
             DbMessageRole::User,
             message_text,
@@ -666,15 +950,21 @@ impl MessageDispatcher {
             Some(&message.user_name),
             message.message_id.as_deref(),
             Some(user_tokens),
+            &message.attachments,
         ) {
-            log::error!("Failed to store user message: {}", e);
-        } else {
-            // Update context tokens
-            self.context_manager.update_context_tokens(session.id, user_tokens);
+            Ok(stored) => {
+                // Update context tokens
+                self.context_manager.update_context_tokens(session.id, user_tokens);
+                let tagged = tags.map(|t| self.db.set_session_message_tags(stored.id, t.sentiment.as_str(), t.urgency.as_str()));
+                if let Some(Err(e)) = tagged {
+                    log::warn!("[DISPATCH] Failed to store message tags: {}", e);
+                }
+            }
+            Err(e) => log::error!("Failed to store user message: {}", e),
         }
 
         // Get active agent settings from database — if none are enabled, AI is disabled
-        let settings = match self.db.get_active_agent_settings() {
+        let mut settings = match self.db.get_active_agent_settings() {
             Ok(Some(settings)) => settings,
             Ok(None) => {
                 let error = "No AI model configured. Select a model in your instance settings to enable chat.".to_string();
@@ -715,18 +1005,62 @@ impl MessageDispatcher {
             }
         };
 
+        // Response style preset (the session's, else the channel's) caps the reply length
+        let response_style = crate::channels::response_style::resolve(&self.db, &session);
+        if let Some(style) = response_style {
+            settings.max_response_tokens = settings.max_response_tokens.min(style.max_output_tokens());
+        }
+
+        // AI spending quotas: refuse once a budget is used up, switch to a cheaper model near it
+        let budget_check = ai_quota::check(&self.db, message.channel_id, &settings);
+        if !budget_check.alerts.is_empty() {
+            let (db, broadcaster) = (Arc::clone(&self.db), Arc::clone(&self.broadcaster));
+            let (channel_id, alerts) = (message.channel_id, budget_check.alerts);
+            tokio::spawn(async move { ai_quota::notify_owner(&db, &broadcaster, channel_id, &alerts).await });
+        }
+        let mut quota_degraded = false;
+        match budget_check.budget {
+            ai_quota::Budget::Exhausted(reason) => {
+                let error = format!("AI budget exhausted: {}. Try again after it resets.", reason);
+                log::info!("[AI_QUOTA] Refusing execution on channel {}: {}", message.channel_id, reason);
+                let _ = self.db.add_session_message(
+                    session.id, DbMessageRole::Assistant,
+                    &format!("[Error] {}", error), None, None, None, None,
+                );
+                self.active_cache.update_completion_status(session.id, CompletionStatus::Failed);
+                self.active_cache.flush_and_evict(session.id, &self.db);
+                self.broadcast_session_complete(message.channel_id, session.id);
+                self.broadcaster.broadcast(GatewayEvent::agent_error(message.channel_id, &error));
+                self.execution_tracker.complete_execution(message.channel_id);
+                self.rollout_manager.fail_attempt(&mut rollout, &error, &span_collector);
+                self.telemetry_store.persist_spans(&span_collector);
+                heartbeat_handle.abort();
+                telemetry::clear_active_collector();
+                return DispatchResult::error(error);
+            }
+            ai_quota::Budget::Degrade { model, reason } => {
+                if ai_quota::degrade(&mut settings, &model) {
+                    log::info!("[AI_QUOTA] Using '{}' on channel {}: {}", model, message.channel_id, reason);
+                    quota_degraded = true;
+                }
+            }
+            ai_quota::Budget::Ok => {}
+        }
+
         // Infer archetype from settings
         let archetype_id = AiClient::infer_archetype(&settings);
+        // Context budget sized to the model's window (capped by the configured value if set)
+        let max_context_tokens = crate::ai::models::effective_context_tokens(&settings);
         log::info!(
             "Using endpoint {} for message dispatch (archetype={}, max_response={}, max_context={})",
             settings.endpoint,
             archetype_id,
             settings.max_response_tokens,
-            settings.max_context_tokens
+            max_context_tokens
         );
 
         // Sync session's max_context_tokens with agent settings for dynamic compaction
-        self.context_manager.sync_max_context_tokens(session.id, settings.max_context_tokens);
+        self.context_manager.sync_max_context_tokens(session.id, max_context_tokens);
 
         // Create AI client — use mock in tests if configured, otherwise create from settings
         #[cfg(test)]
@@ -954,8 +1288,8 @@ impl MessageDispatcher {
             }
         }
 
-        // Twitter has no interactive session — ask_user can never work, so block it.
-        if message.channel_type == "twitter" {
+        // Twitter and Farcaster have no interactive session — ask_user can never work, so block it.
+        if message.channel_type == "twitter" || message.channel_type == "farcaster" {
             tool_config.deny_list.push("ask_user".to_string());
         }
 
@@ -968,7 +1302,10 @@ impl MessageDispatcher {
         );
 
         // Build context from memories, tools, skills, and session history
-        let system_prompt = self.build_system_prompt(&message, &identity.identity_id, &tool_config, is_safe_mode, special_role_grants.as_ref()).await;
+        let mut system_prompt = self.build_system_prompt(&message, &identity.identity_id, &tool_config, is_safe_mode, special_role_grants.as_ref()).await;
+        if let Some(style) = response_style {
+            system_prompt.push_str(&style.prompt_section());
+        }
 
         // Debug: Log full system prompt
         log::debug!("[DISPATCH] System prompt:\n{}", system_prompt);
@@ -1068,7 +1405,7 @@ impl MessageDispatcher {
             }
             messages.push(Message {
                 role,
-                content: msg.content.clone(),
+                content: msg.content_with_attachments(),
             });
         }
 
@@ -1076,7 +1413,7 @@ impl MessageDispatcher {
         // Chat context (recent channel history) is combined into the user message
         // so the AI model treats it as conversational flow rather than ignoring it
         // as background system info.
-        let user_content = if let Some(ref ctx) = message.chat_context {
+        let mut user_content = if let Some(ref ctx) = message.chat_context {
             format!(
                 "{}\n\n[USER QUERY - this is what you are responding to:]\n{}",
                 ctx, message_text
@@ -1084,6 +1421,10 @@ impl MessageDispatcher {
         } else {
             message_text.to_string()
         };
+        if let Some(refs) = crate::models::session_message::format_attachment_references(&message.attachments) {
+            user_content.push_str("\n\n");
+            user_content.push_str(&refs);
+        }
         messages.push(Message {
             role: MessageRole::User,
             content: user_content,
@@ -1123,7 +1464,16 @@ impl MessageDispatcher {
             .with_workspace(workspace_dir.clone())
             .with_broadcaster(self.broadcaster.clone())
             .with_database(self.db.clone())
-            .with_selected_network(message.selected_network.clone());
+            .with_selected_network(message.selected_network.clone())
+            .with_ai_usage(ai_quota::UsageMeter::new(message.channel_id, Some(session.id), &settings));
+        if quota_degraded {
+            // Keeps subtypes from switching back to a pricier preferred model
+            tool_context.extra.insert("ai_quota_degraded".to_string(), serde_json::json!(true));
+        }
+        if crate::away::is_autonomous(&message) {
+            // Away mode restricts what scheduled and triggered runs may do
+            tool_context.extra.insert(crate::away::AUTONOMOUS_RUN_KEY.to_string(), serde_json::json!(true));
+        }
 
         // Log selected network if present
         if let Some(ref network) = message.selected_network {
@@ -1183,6 +1533,15 @@ impl MessageDispatcher {
         // Populate tool context with the context bank items scanned earlier
         if !context_bank_items.is_empty() {
             tool_context.context_bank.add_all(context_bank_items.clone());
+            tool_context.context_bank.sync_registers(&tool_context.registers);
+            // What the user talks about feeds the cross-session knowledge graph
+            if !is_safe_mode {
+                crate::memory::knowledge_graph::ingest_context_items(
+                    &self.db,
+                    &context_bank_items,
+                    tool_context.session_id,
+                );
+            }
             log::info!(
                 "[DISPATCH] Context bank populated with {} items: {:?}",
                 tool_context.context_bank.len(),
@@ -1200,6 +1559,15 @@ impl MessageDispatcher {
         // Ensure workspace directory exists
         let _ = std::fs::create_dir_all(&workspace_dir);
 
+        // Give this execution its own scratch directory (garbage-collected in complete_execution)
+        tool_context = tool_context.with_execution_id(execution_id.clone());
+        match crate::execution::scratch::create(&execution_id) {
+            Ok(path) => {
+                tool_context = tool_context.with_scratch_dir(path.to_string_lossy().to_string());
+            }
+            Err(e) => log::warn!("[DISPATCH] Failed to create scratch dir for {}: {}", execution_id, e),
+        }
+
         // Load API keys from database into ToolContext (per-session, no global env mutation)
         // In safe mode, skip loading API keys (discord/telegram/slack tokens come from channel settings)
         if !is_safe_mode {
@@ -1237,6 +1605,18 @@ impl MessageDispatcher {
                 serde_json::json!(bot_settings.rogue_mode_enabled),
             );
 
+            // Paper trading: globally, or opted into by this channel
+            let channel_paper = self
+                .db
+                .get_channel_setting(message.channel_id, ChannelSettingKey::PaperTrading.as_ref())
+                .ok()
+                .flatten()
+                .is_some_and(|v| v == "true");
+            tool_context.extra.insert(
+                "paper_mode".to_string(),
+                serde_json::json!(bot_settings.paper_trading_enabled || channel_paper),
+            );
+
             // Configure HTTP proxy for tool requests if set
             if let Some(ref url) = bot_settings.proxy_url {
                 if !url.is_empty() {
@@ -1278,6 +1658,14 @@ impl MessageDispatcher {
                 // Simple generation without tools - with x402 event emission
                 match client.generate_text_with_events(messages.clone(), &self.broadcaster, message.channel_id).await {
                     Ok((content, payment)) => {
+                        if let Some(ref meter) = tool_context.ai_usage {
+                            meter.record(
+                                &self.db,
+                                ai_quota::estimate_input(&messages, &()),
+                                ai_quota::estimate_output(&content, &()),
+                                payment.as_ref(),
+                            );
+                        }
                         // Save x402 payment if one was made
                         if let Some(ref payment_info) = payment {
                             if let Err(e) = self.db.record_x402_payment(
@@ -1360,6 +1748,9 @@ impl MessageDispatcher {
 
         match final_response {
             Ok((response, delivered_via_say_to_user, message_id)) => {
+                // Blank out private data the requester doesn't own before anything is stored or sent
+                let response = redaction::apply(&self.db, &tool_context.sensitive_data, &message, &identity.identity_id, response);
+
                 // Estimate tokens for the response
                 let response_tokens = estimate_tokens(&response);
 
@@ -1381,61 +1772,8 @@ impl MessageDispatcher {
                     // Update context tokens
                     self.context_manager.update_context_tokens(session.id, response_tokens);
 
-                    // Check if incremental compaction is needed (earlier trigger, smaller batches)
-                    if self.context_manager.needs_incremental_compaction(session.id) {
-                        log::info!("[COMPACTION] Context threshold reached for session {}, triggering incremental compaction", session.id);
-                        // Broadcast compaction event to UI
-                        self.broadcaster.broadcast(GatewayEvent::context_compacting(
-                            message.channel_id,
-                            session.id,
-                            "incremental",
-                            "Context threshold reached",
-                        ));
-                        if let Err(e) = self.context_manager.compact_incremental(
-                            session.id,
-                            &client,
-                            memory_identity,
-                        ).await {
-                            log::error!("[COMPACTION] Incremental compaction failed: {}", e);
-                            // Fall back to full compaction if incremental fails
-                            if self.context_manager.needs_compaction(session.id) {
-                                log::info!("[COMPACTION] Falling back to full compaction");
-                                // Broadcast fallback compaction event
-                                self.broadcaster.broadcast(GatewayEvent::context_compacting(
-                                    message.channel_id,
-                                    session.id,
-                                    "full",
-                                    "Incremental failed, falling back to full compaction",
-                                ));
-                                if let Err(e) = self.context_manager.compact_session(
-                                    session.id,
-                                    &client,
-                                    memory_identity,
-                                    None, // agent_subtype not available in non-orchestrated path
-                                ).await {
-                                    log::error!("[COMPACTION] Full compaction also failed: {}", e);
-                                }
-                            }
-                        }
-                    } else if self.context_manager.needs_compaction(session.id) {
-                        // Hard limit reached - do full compaction
-                        log::info!("[COMPACTION] Hard context limit reached for session {}, triggering full compaction", session.id);
-                        // Broadcast compaction event to UI
-                        self.broadcaster.broadcast(GatewayEvent::context_compacting(
-                            message.channel_id,
-                            session.id,
-                            "full",
-                            "Hard context limit reached",
-                        ));
-                        if let Err(e) = self.context_manager.compact_session(
-                            session.id,
-                            &client,
-                            memory_identity,
-                            None, // agent_subtype not available in non-orchestrated path
-                        ).await {
-                            log::error!("[COMPACTION] Failed to compact session: {}", e);
-                        }
-                    }
+                    // Compact between turns once the threshold is crossed (never blocks this reply)
+                    self.schedule_auto_compaction(&message, session.id, &settings, memory_identity);
                 }
 
                 // Emit response event — skip if empty or if say_to_user already broadcast it
@@ -1467,6 +1805,7 @@ impl MessageDispatcher {
                 self.broadcaster.broadcast(GatewayEvent::rollout_status_change(
                     message.channel_id, &rollout.rollout_id, "succeeded", rollout.attempt_count(),
                 ));
+                self.rollout_manager.mark_degraded(&mut rollout, &watchdog.degraded_reasons());
                 if !use_tools {
                     reward_emitter.session_completed(true, 0, 0, 1);
                 }
@@ -1495,7 +1834,22 @@ impl MessageDispatcher {
                 // Flush cached state to SQLite and evict (dispatch complete)
                 self.active_cache.flush_and_evict(session.id, &self.db);
 
+                // Artifacts registered during this execution ride along with the reply
+                let artifacts = self.db.list_artifacts_for_execution(&execution_id).unwrap_or_default();
+                if !artifacts.is_empty() {
+                    self.broadcaster.broadcast(GatewayEvent::custom(
+                        "execution.artifacts",
+                        serde_json::json!({
+                            "channel_id": message.channel_id,
+                            "execution_id": execution_id,
+                            "artifacts": artifacts,
+                        }),
+                    ));
+                }
+
                 DispatchResult::success_with_message_id(response, message_id)
+                    .with_actions(actions::actions_from_registers(&tool_context.registers))
+                    .with_attachments(artifacts)
             }
             Err(e) => {
                 let mut error = format!("AI generation error ({}): {}", archetype_id, e);
@@ -1558,6 +1912,7 @@ impl MessageDispatcher {
                 if !use_tools {
                     reward_emitter.session_completed(false, 0, 0, 1);
                 }
+                self.rollout_manager.mark_degraded(&mut rollout, &watchdog.degraded_reasons());
                 self.telemetry_store.persist_spans(&span_collector);
                 heartbeat_handle.abort();
                 telemetry::clear_active_collector();
@@ -1572,7 +1927,7 @@ impl MessageDispatcher {
     async fn generate_with_tool_loop(
         &self,
         client: &AiClient,
-        messages: Vec<Message>,
+        mut messages: Vec<Message>,
         tool_config: &ToolConfig,
         tool_context: &ToolContext,
         _identity_id: &str,
@@ -1582,6 +1937,20 @@ impl MessageDispatcher {
         is_safe_mode: bool,
         watchdog: &Arc<Watchdog>,
     ) -> Result<(String, bool, Option<String>), String> {
+        // A resume action restores the paused execution exactly as it was checkpointed;
+        // any other message answers a clarification question the planner parked on
+        let resumed = if actions::is_resume(original_message) {
+            match self.db.take_paused_execution(original_message.channel_id, &original_message.chat_id) {
+                Ok(paused) => paused,
+                Err(e) => {
+                    log::error!("[MULTI_AGENT] Failed to load paused execution: {}", e);
+                    None
+                }
+            }
+        } else {
+            self.take_parked_clarification(original_message)
+        };
+
         // Load existing agent context or create new one (prefer cache, fallback to DB)
         let cached_ctx = self.active_cache.get_agent_context(session_id);
         let db_ctx = if cached_ctx.is_some() {
@@ -1589,56 +1958,88 @@ impl MessageDispatcher {
         } else {
             self.db.get_agent_context(session_id).ok().flatten()
         };
-        let mut orchestrator = match db_ctx {
-            Some(context) => {
-                log::info!(
-                    "[MULTI_AGENT] Resuming session {} (iteration {})",
-                    session_id,
-                    context.mode_iterations
-                );
-                let mut orch = Orchestrator::from_context(context);
-                // Clear active skill at the start of each new message to prevent stale skills
-                // from being used. Skills should only be active for the turn they were invoked.
-                orch.clear_active_skill();
-                // Reset per-turn counters so they don't carry over from previous messages.
-                // mode_iterations/actual_tool_calls/no_tool_warnings are per-turn state,
-                // not cumulative session state.
-                orch.reset_turn_counters();
-                // Reset subtype back to director on each new message so the
-                // director can re-evaluate and route to the correct subagent.
-                // Without this, a stale subtype (e.g. "finance") persists and
-                // the agent skips director routing on subsequent messages.
-                let prev_subtype = orch.context().subtype.clone();
-                let default_key = agent_types::default_subtype_key();
-                orch.set_subtype(Some(default_key.clone()));
-                // Reset planner state so the new subtype can plan fresh
-                orch.context_mut().planner_completed = false;
-                orch.context_mut().mode = AgentMode::TaskPlanner;
-                orch.context_mut().task_queue = Default::default();
-                if prev_subtype.as_deref() != Some(&default_key) {
+        let mut orchestrator = if let Some(ref paused) = resumed {
+            log::info!(
+                "[MULTI_AGENT] Resuming execution paused in session {} ({} task(s), {} tool call(s) done)",
+                paused.session_id,
+                paused.context.task_queue.total(),
+                paused.tool_call_log.len()
+            );
+            let mut orch = Orchestrator::from_context(paused.context.clone());
+            if !actions::is_resume(original_message) && orch.answer_clarification(&original_message.text) {
+                log::info!("[MULTI_AGENT] Clarification answered, planning resumes with it attached");
+            }
+            orch
+        } else {
+            match db_ctx {
+                Some(context) => {
                     log::info!(
-                        "[MULTI_AGENT] Reset subtype from {:?} to '{}' for new message",
-                        prev_subtype, default_key
+                        "[MULTI_AGENT] Resuming session {} (iteration {})",
+                        session_id,
+                        context.mode_iterations
                     );
+                    let mut orch = Orchestrator::from_context(context);
+                    // Clear active skill at the start of each new message to prevent stale skills
+                    // from being used. Skills should only be active for the turn they were invoked.
+                    orch.clear_active_skill();
+                    // Reset per-turn counters so they don't carry over from previous messages.
+                    // mode_iterations/actual_tool_calls/no_tool_warnings are per-turn state,
+                    // not cumulative session state.
+                    orch.reset_turn_counters();
+                    // Reset subtype back to director on each new message so the
+                    // director can re-evaluate and route to the correct subagent.
+                    // Without this, a stale subtype (e.g. "finance") persists and
+                    // the agent skips director routing on subsequent messages.
+                    let prev_subtype = orch.context().subtype.clone();
+                    let default_key = agent_types::default_subtype_key();
+                    orch.set_subtype(Some(default_key.clone()));
+                    // Reset planner state so the new subtype can plan fresh
+                    orch.context_mut().planner_completed = false;
+                    orch.context_mut().mode = AgentMode::TaskPlanner;
+                    orch.context_mut().task_queue = Default::default();
+                    if prev_subtype.as_deref() != Some(&default_key) {
+                        log::info!(
+                            "[MULTI_AGENT] Reset subtype from {:?} to '{}' for new message",
+                            prev_subtype, default_key
+                        );
+                    }
+                    orch
+                }
+                None => {
+                    log::info!(
+                        "[MULTI_AGENT] Starting new orchestrator for session {}",
+                        session_id
+                    );
+                    Orchestrator::new(original_message.text.clone())
                 }
-                orch
-            }
-            None => {
-                log::info!(
-                    "[MULTI_AGENT] Starting new orchestrator for session {}",
-                    session_id
-                );
-                Orchestrator::new(original_message.text.clone())
             }
         };
 
+        // Show the restored task list and re-arm cancellation for the task in progress
+        if resumed.is_some() {
+            if let Some(task_id) = orchestrator.task_queue().current_task().map(|t| t.id) {
+                self.execution_tracker.begin_planner_task(original_message.channel_id, task_id);
+            }
+            self.broadcast_task_queue_update(original_message.channel_id, session_id, &orchestrator);
+        }
+
         // Auto-select hidden subtypes by matching channel_type to subtype key
         // (e.g., channel_type "impulse_evolver" → hidden subtype "impulse_evolver")
-        if let Some(config) = agent_types::get_subtype_config(&original_message.channel_type) {
-            if config.hidden {
-                orchestrator.set_subtype(Some(config.key.clone()));
-                log::info!("[MULTI_AGENT] Hidden subtype auto-selected: {}", config.key);
-            }
+        let hidden_subtype = agent_types::get_subtype_config(&original_message.channel_type).filter(|c| c.hidden);
+        if let Some(config) = hidden_subtype {
+            orchestrator.set_subtype(Some(config.key.clone()));
+            log::info!("[MULTI_AGENT] Hidden subtype auto-selected: {}", config.key);
+        } else if let Some(config) = self
+            .db
+            .get_channel_setting(original_message.channel_id, ChannelSettingKey::AgentSubtype.as_ref())
+            .ok()
+            .flatten()
+            .and_then(|key| agent_types::get_subtype_config(key.trim()))
+            .filter(|c| c.enabled)
+        {
+            // The channel pins a subtype: skip director routing
+            orchestrator.set_subtype(Some(config.key.clone()));
+            log::info!("[MULTI_AGENT] Channel subtype selected: {}", config.key);
         }
 
         // Mark hook sessions so the orchestrator uses the autonomous hook prompt
@@ -1654,17 +2055,90 @@ impl MessageDispatcher {
             log::info!("[MULTI_AGENT] Selected network set to: {}", network);
         }
 
+        // Match the request against installed skills: suggest a close match in the
+        // system prompt, or activate it outright on channels set to auto
+        if !is_safe_mode && resumed.is_none() && !orchestrator.context().is_hook_session {
+            if let Some(ref hybrid) = self.hybrid_search {
+                let emb_gen = hybrid.embedding_generator().clone();
+                let suggestion = crate::skills::suggest::suggest(
+                    &self.db,
+                    &emb_gen,
+                    &original_message.text,
+                    original_message.channel_id,
+                    session_id,
+                ).await;
+                match suggestion {
+                    Some(suggestion) if suggestion.activate => {
+                        let language = crate::skills::locales::preferred_language(&self.db, tool_context.identity_id.as_deref());
+                        let skill = suggestion.skill.localized(language.as_deref());
+                        let skill_base_dir = format!("{}/{}", crate::config::runtime_skills_dir(), skill.name);
+                        let instructions = skill.body.replace("{baseDir}", &skill_base_dir);
+                        log::info!(
+                            "[SKILL_SUGGEST] Auto-activating skill '{}' ({:.0}% match)",
+                            skill.name,
+                            suggestion.similarity * 100.0
+                        );
+                        self.apply_skill_subtype(&skill, &mut orchestrator, original_message.channel_id);
+                        orchestrator.context_mut().active_skill = Some(crate::ai::multi_agent::types::ActiveSkill {
+                            name: skill.name.clone(),
+                            instructions,
+                            activated_at: Utc::now().to_rfc3339(),
+                            tool_calls_made: 0,
+                            requires_tools: skill.requires_tools.clone(),
+                        });
+                    }
+                    Some(suggestion) => {
+                        if let Some(system) = messages.first_mut().filter(|m| m.role == MessageRole::System) {
+                            log::info!(
+                                "[SKILL_SUGGEST] Suggesting skill '{}' ({:.0}% match)",
+                                suggestion.skill.name,
+                                suggestion.similarity * 100.0
+                            );
+                            system.content.push_str("\n\n");
+                            system.content.push_str(&suggestion.prompt_section());
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        // Approaches that already failed on similar requests, for planning around them
+        if resumed.is_none() {
+            let failed = crate::memory::negative_results::recall(
+                &self.db,
+                &original_message.text,
+                tool_context.identity_id.as_deref(),
+            );
+            if !failed.is_empty() {
+                if let Some(system) = messages.first_mut().filter(|m| m.role == MessageRole::System) {
+                    log::info!("[NEGATIVE_RESULT] Recalled {} failed approach(es) for this request", failed.len());
+                    system.content.push_str("\n\n");
+                    system.content.push_str(&crate::memory::negative_results::prompt_section(&failed));
+                }
+            }
+        }
+
         // Config-driven TaskPlanner skip: subtypes with skip_task_planner=true go straight
         // to Assistant mode (e.g. Director delegates planning to specialized agents).
+        // A mode set on the chat with /mode overrides the subtype.
         if orchestrator.current_mode() == AgentMode::TaskPlanner
             && !orchestrator.context().planner_completed
         {
             let subtype_key = orchestrator.current_subtype_key();
-            let should_skip = agent_types::get_subtype_config(subtype_key)
-                .map(|c| c.skip_task_planner)
-                .unwrap_or(false);
+            let should_skip = match crate::channels::chat_commands::get_chat_mode(
+                &self.db,
+                original_message.channel_id,
+                &original_message.chat_id,
+            ) {
+                Some(crate::channels::chat_commands::ChatMode::Plan) => false,
+                Some(crate::channels::chat_commands::ChatMode::Direct) => true,
+                None => agent_types::get_subtype_config(subtype_key)
+                    .map(|c| c.skip_task_planner)
+                    .unwrap_or(false),
+            };
             if should_skip {
-                log::info!("[MULTI_AGENT] Subtype '{}' has skip_task_planner=true, going to Assistant mode", subtype_key);
+                log::info!("[MULTI_AGENT] Skipping task planner for subtype '{}', going to Assistant mode", subtype_key);
                 orchestrator.transition_to_assistant();
             }
         }
@@ -1685,10 +2159,13 @@ impl MessageDispatcher {
         // Get the current subtype key
         let subtype_key = orchestrator.current_subtype_key().to_string();
 
-        // Check if subtype has a preferred AI model override
+        // Check if subtype has a preferred AI model override (not while an AI quota
+        // has switched the execution to a cheaper model)
         let override_client: Option<AiClient>;
+        let mut override_model: Option<String> = None;
         let mut effective_archetype_id = archetype_id;
-        if let Some(config) = agent_types::get_subtype_config(&subtype_key) {
+        let quota_degraded = tool_context.extra.contains_key("ai_quota_degraded");
+        if let Some(config) = agent_types::get_subtype_config(&subtype_key).filter(|_| !quota_degraded) {
             if let Some(ref model_key) = config.preferred_ai_model {
                 if let Some(preset) = crate::ai_endpoint_config::get_ai_endpoint(model_key) {
                     log::info!(
@@ -1710,6 +2187,7 @@ impl MessageDispatcher {
                             override_client = Some(
                                 c.with_broadcaster(Arc::clone(&self.broadcaster), original_message.channel_id)
                             );
+                            override_model = Some(model_key.clone());
                         }
                         Err(e) => {
                             log::warn!(
@@ -1733,6 +2211,10 @@ impl MessageDispatcher {
             override_client = None;
         }
         let effective_client = override_client.as_ref().unwrap_or(client);
+        let usage_meter = match (&tool_context.ai_usage, &override_model) {
+            (Some(meter), Some(model_key)) => Some(meter.for_preset(model_key)),
+            (meter, _) => meter.clone(),
+        };
 
         log::info!(
             "[MULTI_AGENT] Started in {} mode ({} subtype) for request: {}",
@@ -1768,7 +2250,11 @@ impl MessageDispatcher {
 
         if tools.is_empty() {
             log::warn!("[TOOL_LOOP] No tools available, falling back to text-only generation");
+            let input_tokens = ai_quota::estimate_input(&messages, &());
             let (content, payment) = effective_client.generate_text_with_events(messages, &self.broadcaster, original_message.channel_id).await?;
+            if let Some(ref meter) = usage_meter {
+                meter.record(&self.db, input_tokens, ai_quota::estimate_output(&content, &()), payment.as_ref());
+            }
             // Save x402 payment if one was made
             if let Some(ref payment_info) = payment {
                 if let Err(e) = self.db.record_x402_payment(
@@ -1800,6 +2286,7 @@ impl MessageDispatcher {
 
         // Inject current agent_subtype into tool_context for memory tagging and search boosting
         let mut tool_context = tool_context.clone();
+        tool_context.ai_usage = usage_meter;
         let subtype = orchestrator.current_subtype_key();
         if !subtype.is_empty() {
             tool_context.extra.insert(
@@ -1812,12 +2299,12 @@ impl MessageDispatcher {
         if archetype.uses_native_tool_calling() {
             self.generate_with_native_tools_orchestrated(
                 effective_client, messages, tools, tool_config, &tool_context,
-                original_message, archetype, &mut orchestrator, session_id, is_safe_mode, watchdog
+                original_message, archetype, &mut orchestrator, session_id, is_safe_mode, watchdog, resumed
             ).await
         } else {
             self.generate_with_text_tools_orchestrated(
                 effective_client, messages, tools, tool_config, &tool_context,
-                original_message, archetype, &mut orchestrator, session_id, is_safe_mode, watchdog
+                original_message, archetype, &mut orchestrator, session_id, is_safe_mode, watchdog, resumed
             ).await
         }
     }
@@ -127,6 +127,16 @@ impl MessageDispatcher {
                 enum_values: None,
             },
         );
+        properties.insert(
+            "args".to_string(),
+            PropertySchema {
+                schema_type: "object".to_string(),
+                description: "Values for the skill's declared arguments, e.g. {\"path\": \"./src\"}".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
 
         let formatted_skills = skills
             .iter()
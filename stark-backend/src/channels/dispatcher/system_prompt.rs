@@ -251,6 +251,13 @@ impl MessageDispatcher {
             }
         }
 
+        // Pre-warmed context (portfolio, calendar, watchlist) refreshed ahead of busy hours
+        if !is_safe_mode {
+            if let Some(block) = crate::context::prewarm::context_for_prompt(&self.db) {
+                prompt.push_str(&block);
+            }
+        }
+
         // Semantic skill discovery: inject relevant skills based on user query
         // Primary: vector similarity via embeddings. Fallback: text matching with stemming.
         if !is_safe_mode {
@@ -2,7 +2,9 @@ use crate::ai::{
     multi_agent::{types::{self as agent_types, AgentMode}, Orchestrator},
     AiClient, AiResponse, Message, MessageRole, ModelArchetype, ToolHistoryEntry, ToolResponse,
 };
+use crate::ai_quota;
 use crate::channels::types::NormalizedMessage;
+use crate::db::tables::paused_executions::PausedExecution;
 use crate::gateway::protocol::GatewayEvent;
 use crate::models::session_message::MessageRole as DbMessageRole;
 use crate::models::TaskType;
@@ -29,6 +31,7 @@ impl MessageDispatcher {
         session_id: i64,
         is_safe_mode: bool,
         watchdog: &Arc<Watchdog>,
+        resumed: Option<PausedExecution>,
     ) -> Result<(String, bool, Option<String>), String> {
         // Get max tool iterations from bot settings
         let max_tool_iterations = self.db.get_bot_settings()
@@ -76,9 +79,12 @@ impl MessageDispatcher {
         // Clear waiting_for_user_context now that it's been consumed into the prompt
         orchestrator.clear_waiting_for_user_context();
 
-        let mut tool_history: Vec<ToolHistoryEntry> = Vec::new();
+        // A resumed execution picks up the tool rounds it had already made
+        let (mut tool_history, mut tool_call_log): (Vec<ToolHistoryEntry>, Vec<String>) = match resumed {
+            Some(paused) => (paused.tool_history, paused.tool_call_log),
+            None => (Vec::new(), Vec::new()),
+        };
         let mut iterations = 0;
-        let mut tool_call_log: Vec<String> = Vec::new();
         let mut orchestrator_complete = false;
         let mut memory_suppressed = false;
         let mut final_summary = String::new();
@@ -126,23 +132,58 @@ impl MessageDispatcher {
                     _ => "No skills currently available.".to_string(),
                 };
 
-                // Update conversation with planner prompt including skills
+                // Saved workflow templates, with their tasks so the planner can reuse them verbatim
+                let templates_text = match self.db.list_workflow_templates() {
+                    Ok(templates) if !templates.is_empty() => {
+                        templates.iter()
+                            .map(|t| {
+                                let params: Vec<&str> = t.params.iter().map(|p| p.name.as_str()).collect();
+                                let tasks: Vec<String> = t.tasks.iter()
+                                    .enumerate()
+                                    .map(|(i, task)| format!("  {}. {}", i + 1, task.description))
+                                    .collect();
+                                format!(
+                                    "- **{}**{} (params: {})\n{}",
+                                    t.name,
+                                    t.description.as_ref().map(|d| format!(": {}", d)).unwrap_or_default(),
+                                    if params.is_empty() { "none".to_string() } else { params.join(", ") },
+                                    tasks.join("\n")
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    }
+                    _ => "No saved workflow templates.".to_string(),
+                };
+
+                // Update conversation with planner prompt including skills and templates
                 if let Some(system_msg) = conversation.first_mut() {
                     if system_msg.role == MessageRole::System {
-                        let planner_prompt = orchestrator.get_planner_prompt_with_skills(&skills_text);
+                        let planner_prompt = orchestrator.get_planner_prompt_with_skills_and_templates(&skills_text, &templates_text);
                         system_msg.content = planner_prompt;
                     }
                 }
                 // define_tasks is ALWAYS available in TaskPlanner mode, regardless of
                 // tool config (safe mode, standard, etc.). Pull directly from registry
                 // to bypass tool config filtering.
-                match self.tool_registry.get("define_tasks") {
+                let mut planner_tools = match self.tool_registry.get("define_tasks") {
                     Some(tool) => vec![tool.definition()],
                     None => {
                         log::error!("[ORCHESTRATED_LOOP] define_tasks tool not found in registry!");
                         vec![]
                     }
+                };
+                // The planner may ask one clarification question at a time, but not of
+                // untrusted users or on channels where nobody is there to answer
+                if !is_safe_mode
+                    && !orchestrator.context().is_hook_session
+                    && orchestrator.can_request_clarification()
+                {
+                    if let Some(tool) = self.tool_registry.get("request_clarification") {
+                        planner_tools.push(tool.definition());
+                    }
                 }
+                planner_tools
             } else {
                 // In assistant mode, tools already have define_tasks stripped
                 // by build_tool_list() — just clone.
@@ -179,18 +220,26 @@ impl MessageDispatcher {
                 break;
             }
 
-            // Check for pending task deletions
-            let pending_deletions = self.execution_tracker.take_pending_task_deletions(original_message.channel_id);
-            for task_id in pending_deletions {
-                let (deleted, was_current) = orchestrator.delete_task(task_id);
-                if deleted {
-                    log::info!("[ORCHESTRATED_LOOP] Deleted task {}", task_id);
+            // Apply planner task cancellations requested through the API
+            let pending_cancellations = self.execution_tracker.take_pending_task_deletions(original_message.channel_id);
+            for task_id in pending_cancellations {
+                let (cancelled, was_current) = orchestrator.cancel_task(task_id);
+                if cancelled {
+                    log::info!("[ORCHESTRATED_LOOP] Cancelled task {}", task_id);
+                    self.broadcast_task_status_change(
+                        original_message.channel_id,
+                        session_id,
+                        task_id,
+                        "cancelled",
+                        orchestrator.get_task(task_id).map(|t| t.description.as_str()).unwrap_or(""),
+                    );
                     // Broadcast the updated task queue
                     self.broadcast_task_queue_update(original_message.channel_id, session_id, orchestrator);
 
-                    // If we deleted the current task, move to the next one
+                    // If we cancelled the current task, move to the next one
                     if was_current {
-                        log::info!("[ORCHESTRATED_LOOP] Deleted task was the current task, moving to next");
+                        log::info!("[ORCHESTRATED_LOOP] Cancelled task was the current task, moving to next");
+                        self.execution_tracker.end_planner_task(original_message.channel_id);
                         if let TaskAdvanceResult::AllTasksComplete = self.advance_to_next_task_or_complete(
                             original_message.channel_id,
                             session_id,
@@ -201,10 +250,15 @@ impl MessageDispatcher {
                         }
                     }
                 } else {
-                    log::warn!("[ORCHESTRATED_LOOP] Task {} not found for deletion", task_id);
+                    log::warn!("[ORCHESTRATED_LOOP] Task {} not found or already finished, nothing to cancel", task_id);
                 }
             }
 
+            // Safe boundary: the previous tool batch is done, nothing is in flight
+            if self.execution_tracker.take_pause_request(original_message.channel_id) {
+                return self.pause_tool_loop(original_message, session_id, orchestrator, &tool_history, &[], &tool_call_log);
+            }
+
             if iterations > max_tool_iterations {
                 log::warn!("Orchestrated tool loop exceeded max iterations ({})", max_tool_iterations);
                 break;
@@ -219,6 +273,7 @@ impl MessageDispatcher {
                         first_task.id,
                         first_task.description
                     );
+                    self.execution_tracker.begin_planner_task(original_message.channel_id, first_task.id);
                     self.broadcast_task_status_change(
                         original_message.channel_id,
                         session_id,
@@ -359,8 +414,15 @@ impl MessageDispatcher {
                 current_tools.clone(),
                 original_message.channel_id,
                 session_id,
+                watchdog,
             ).await {
                 Ok(response) => response,
+                // Only the current planner task was cancelled: the next
+                // iteration applies the cancellation and moves on
+                Err(_) if self.execution_tracker.is_planner_task_cancelled(original_message.channel_id) => {
+                    log::info!("[ORCHESTRATED_LOOP] AI call interrupted by planner task cancellation");
+                    continue;
+                }
                 Err(e) => {
                     // Payment/infrastructure errors (402, timeouts) should NOT be retried here —
                     // the HTTP client already retried 3 times internally.
@@ -436,6 +498,15 @@ impl MessageDispatcher {
                 ai_response.tool_calls.len()
             );
 
+            if let Some(ref meter) = tool_context.ai_usage {
+                meter.record(
+                    &self.db,
+                    ai_quota::estimate_input(&conversation, &(&tool_history, &current_tools)),
+                    ai_quota::estimate_output(&ai_response.content, &ai_response.tool_calls),
+                    ai_response.x402_payment.as_ref(),
+                );
+            }
+
             // Handle x402 payments
             if let Some(ref payment_info) = ai_response.x402_payment {
                 self.broadcaster.broadcast(GatewayEvent::x402_payment(
@@ -735,6 +806,17 @@ impl MessageDispatcher {
                 break;
             }
 
+            // A clarification question parks the whole execution until the user answers
+            if waiting_for_user_response && orchestrator.context().pending_clarification.is_some() {
+                return self.park_for_clarification(
+                    original_message,
+                    session_id,
+                    orchestrator,
+                    &tool_call_log,
+                    &user_question_content,
+                );
+            }
+
             // If a tool requires user response (e.g., ask_user), break the loop
             // and return the question content. Context is preserved for when user responds.
             if waiting_for_user_response {
@@ -780,6 +862,7 @@ impl MessageDispatcher {
         session_id: i64,
         is_safe_mode: bool,
         watchdog: &Arc<Watchdog>,
+        resumed: Option<PausedExecution>,
     ) -> Result<(String, bool, Option<String>), String> {
         // Get max tool iterations from bot settings
         let max_tool_iterations = self.db.get_bot_settings()
@@ -828,9 +911,17 @@ impl MessageDispatcher {
         // Clear waiting_for_user_context now that it's been consumed into the prompt
         orchestrator.clear_waiting_for_user_context();
 
+        // Messages added during this run are what a pause has to keep; a
+        // resumed execution starts with the ones it had already added
+        let base_conversation_len = conversation.len();
+        let mut tool_call_log: Vec<String> = Vec::new();
+        if let Some(paused) = resumed {
+            conversation.extend(paused.messages);
+            tool_call_log = paused.tool_call_log;
+        }
+
         let mut final_response = String::new();
         let mut iterations = 0;
-        let mut tool_call_log: Vec<String> = Vec::new();
         let mut orchestrator_complete = false;
         let mut memory_suppressed = false;
         let mut waiting_for_user_response = false;
@@ -866,6 +957,17 @@ impl MessageDispatcher {
                 break;
             }
 
+            if self.execution_tracker.take_pause_request(original_message.channel_id) {
+                return self.pause_tool_loop(
+                    original_message,
+                    session_id,
+                    orchestrator,
+                    &[],
+                    conversation.get(base_conversation_len..).unwrap_or(&[]),
+                    &tool_call_log,
+                );
+            }
+
             if iterations > max_tool_iterations {
                 log::warn!("Text orchestrated loop exceeded max iterations ({})", max_tool_iterations);
                 break;
@@ -957,6 +1059,15 @@ impl MessageDispatcher {
                 }
             };
 
+            if let Some(ref meter) = tool_context.ai_usage {
+                meter.record(
+                    &self.db,
+                    ai_quota::estimate_input(&conversation, &()),
+                    ai_quota::estimate_output(&ai_content, &()),
+                    payment.as_ref(),
+                );
+            }
+
             if let Some(ref payment_info) = payment {
                 let _ = self.db.record_x402_payment(
                     Some(original_message.channel_id),
@@ -1100,6 +1211,15 @@ impl MessageDispatcher {
                         if orchestrator_complete {
                             break;
                         }
+                        if waiting_for_user_response && orchestrator.context().pending_clarification.is_some() {
+                            return self.park_for_clarification(
+                                original_message,
+                                session_id,
+                                orchestrator,
+                                &tool_call_log,
+                                &user_question_content,
+                            );
+                        }
                         // If a tool requires user response (e.g., ask_user), break the loop
                         if waiting_for_user_response {
                             log::info!("[TEXT_ORCHESTRATED] Breaking loop to wait for user response");
@@ -2,17 +2,46 @@ use crate::ai::multi_agent::{
     types::{self as agent_types, AgentMode},
     Orchestrator, ProcessResult as OrchestratorResult,
 };
+use crate::channels::redaction;
 use crate::channels::types::NormalizedMessage;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::session_message::MessageRole as DbMessageRole;
+use crate::models::ChannelSettingKey;
+use crate::models::session_message::{MessageRole as DbMessageRole, ToolCallRecord};
 use crate::telemetry::{self, Watchdog};
+use crate::tools::recovery::{self, FailureKind, RecoveryMode};
 use crate::tools::{ToolConfig, ToolContext, ToolDefinition};
 use serde_json::Value;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 use super::finalization::TaskAdvanceResult;
 use super::MessageDispatcher;
 
+/// Run a tool call, abandoning it as soon as `task_token` (the current
+/// planner task's token) is cancelled.
+async fn with_task_cancellation<F>(
+    task_token: Option<CancellationToken>,
+    tool_name: &str,
+    fut: F,
+) -> crate::tools::ToolResult
+where
+    F: std::future::Future<Output = crate::tools::ToolResult>,
+{
+    let Some(token) = task_token else {
+        return fut.await;
+    };
+    tokio::select! {
+        result = fut => result,
+        _ = token.cancelled() => {
+            log::info!("[ORCHESTRATED_LOOP] Tool '{}' aborted: current task was cancelled", tool_name);
+            crate::tools::ToolResult::error(format!(
+                "Tool '{}' was aborted because the user cancelled the current task",
+                tool_name
+            ))
+        }
+    }
+}
+
 /// Mutable state within one batch of tool calls (one AI response).
 /// Native path: spans multiple tool calls. Text path: spans one.
 pub(super) struct BatchState {
@@ -56,6 +85,177 @@ pub(super) struct ToolCallProcessed {
 }
 
 impl MessageDispatcher {
+    /// Hold a tool call for the user's confirmation when its safety level is below the
+    /// channel's `tool_confirmation` threshold, or for a quick confirm of its parameter
+    /// preview when the tool has previews on. Returns the result to report instead of
+    /// running the tool, or None when it may run.
+    async fn confirm_tool_call(
+        &self,
+        tool_name: &str,
+        tool_arguments: &Value,
+        original_message: &NormalizedMessage,
+        session_id: i64,
+        task_token: Option<CancellationToken>,
+    ) -> Option<crate::tools::ToolResult> {
+        use crate::tools::confirmation::{self, ConfirmationOutcome, Prompt};
+
+        let threshold = self
+            .db
+            .get_channel_setting(original_message.channel_id, ChannelSettingKey::ToolConfirmation.as_ref())
+            .ok()
+            .flatten()
+            .and_then(|v| crate::tools::ToolSafetyLevel::from_str_opt(&v));
+        let tool = self.tool_registry.get(tool_name)?;
+        let prompt = if confirmation::requires_confirmation(threshold, tool.safety_level(), tool.group()) {
+            Prompt::Full(tool_arguments)
+        } else {
+            let rule = crate::tools::previews::rule_for(&self.db, tool_name, tool.safety_level())?;
+            Prompt::Preview {
+                text: crate::tools::previews::compact_preview(tool_arguments, &rule.fields),
+                ttl_secs: rule.timeout_secs(),
+            }
+        };
+
+        match confirmation::request_and_wait(
+            &self.db,
+            &self.broadcaster,
+            original_message,
+            session_id,
+            tool_name,
+            prompt,
+            tool.safety_level(),
+            task_token,
+        )
+        .await
+        {
+            Ok(ConfirmationOutcome::Confirmed { .. }) => None,
+            Ok(ConfirmationOutcome::Declined { by }) => Some(crate::tools::ToolResult::error(format!(
+                "The user declined to run '{}' (decided by {}). Do not retry it; ask the user how to proceed.",
+                tool_name, by
+            ))),
+            Ok(ConfirmationOutcome::Expired) => Some(crate::tools::ToolResult::error(format!(
+                "'{}' was not run: the confirmation request expired without an answer.",
+                tool_name
+            ))),
+            Ok(ConfirmationOutcome::Cancelled) => Some(crate::tools::ToolResult::error(format!(
+                "'{}' was not run because the user cancelled the current task",
+                tool_name
+            ))),
+            // Fail closed: a tool that needs confirmation never runs without one
+            Err(e) => Some(crate::tools::ToolResult::error(format!(
+                "'{}' requires confirmation, but the request could not be made: {}",
+                tool_name, e
+            ))),
+        }
+    }
+
+    /// Run a tool under the watchdog. A read-only tool that times out is run
+    /// again (it can't have changed anything); any timeout marks the execution
+    /// degraded and comes back to the AI as an error result.
+    async fn run_watched_tool(
+        &self,
+        watchdog: &Arc<Watchdog>,
+        tool_name: &str,
+        tool_arguments: &Value,
+        tool_context: &ToolContext,
+        exec_config: &ToolConfig,
+        task_token: Option<CancellationToken>,
+    ) -> crate::tools::ToolResult {
+        let timeout_secs = watchdog.config().timeout_for_tool(tool_name).as_secs();
+        let read_only = self
+            .tool_registry
+            .get(tool_name)
+            .map(|t| t.safety_level() == crate::tools::ToolSafetyLevel::ReadOnly)
+            .unwrap_or(false);
+        let mut retries_left = if read_only { watchdog.config().read_only_tool_retries } else { 0 };
+
+        loop {
+            // Each attempt gets the full budget, visible to the tool as its deadline
+            let call_context = tool_context.clone().with_deadline(watchdog.config().timeout_for_tool(tool_name));
+            let result = watchdog.guard_tool_call(
+                tool_name,
+                with_task_cancellation(
+                    task_token.clone(),
+                    tool_name,
+                    self.tool_registry.execute(tool_name, tool_arguments.clone(), &call_context, Some(exec_config)),
+                ),
+            ).await;
+            if let Some(result) = result {
+                return result;
+            }
+            if retries_left > 0 {
+                retries_left -= 1;
+                watchdog.mark_degraded(format!("'{}' timed out after {}s, retrying", tool_name, timeout_secs));
+                continue;
+            }
+            watchdog.mark_degraded(format!("'{}' timed out after {}s", tool_name, timeout_secs));
+            return crate::tools::ToolResult::error(format!(
+                "Tool '{}' timed out after {}s",
+                tool_name, timeout_secs
+            ));
+        }
+    }
+
+    /// Run a tool under the watchdog, retrying transient failures with backoff
+    /// when the channel's recovery mode allows it. Only tools that asked for a
+    /// retry (`retry_after_secs`) or are read-only are run again.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_with_recovery(
+        &self,
+        watchdog: &Arc<Watchdog>,
+        tool_name: &str,
+        tool_arguments: &Value,
+        tool_context: &ToolContext,
+        exec_config: &ToolConfig,
+        task_token: Option<CancellationToken>,
+        mode: RecoveryMode,
+        channel_id: i64,
+    ) -> crate::tools::ToolResult {
+        let read_only = self
+            .tool_registry
+            .get(tool_name)
+            .map(|t| t.safety_level() == crate::tools::ToolSafetyLevel::ReadOnly)
+            .unwrap_or(false);
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .run_watched_tool(watchdog, tool_name, tool_arguments, tool_context, exec_config, task_token.clone())
+                .await;
+            let retryable = !result.success
+                && mode.retries()
+                && (result.retry_after_secs.is_some() || read_only)
+                && recovery::classify(&result) == FailureKind::Transient;
+            if !retryable {
+                return result;
+            }
+            if attempt >= recovery::MAX_RETRIES {
+                log::warn!("[TOOL_RECOVERY] '{}' still failing after {} retries, giving up", tool_name, attempt);
+                return recovery::gave_up(result, attempt + 1);
+            }
+            let wait = recovery::backoff_secs(attempt, result.retry_after_secs);
+            attempt += 1;
+            log::info!(
+                "[TOOL_RECOVERY] '{}' failed transiently ({}), retry {}/{} in {}s",
+                tool_name,
+                result.error.as_deref().unwrap_or(&result.content),
+                attempt,
+                recovery::MAX_RETRIES,
+                wait
+            );
+            self.broadcaster.broadcast(GatewayEvent::tool_waiting(channel_id, tool_name, wait));
+            let sleep = tokio::time::sleep(std::time::Duration::from_secs(wait));
+            match task_token {
+                Some(ref token) => {
+                    tokio::select! {
+                        _ = sleep => {}
+                        _ = token.cancelled() => return result,
+                    }
+                }
+                None => sleep.await,
+            }
+        }
+    }
+
     /// Processes a single tool call: logging, orchestrator dispatch, skill handling,
     /// subtype checks, validators, execution, metadata processing (define_tasks,
     /// task_fully_completed, say_to_user, auto-complete), hooks, and DB persistence.
@@ -83,6 +283,8 @@ impl MessageDispatcher {
         current_tools: &[ToolDefinition],
         watchdog: &Arc<Watchdog>,
     ) -> ToolCallProcessed {
+        let started = std::time::Instant::now();
+        let call_id = uuid::Uuid::new_v4().to_string();
         let args_pretty = serde_json::to_string_pretty(tool_arguments)
             .unwrap_or_else(|_| tool_arguments.to_string());
 
@@ -119,7 +321,7 @@ impl MessageDispatcher {
             session_id,
             DbMessageRole::ToolCall,
             tool_call_content,
-            Some(tool_name),
+            ToolCallRecord::call(&call_id, tool_name, tool_arguments),
         );
 
         // If define_tasks just replaced the queue, skip all remaining tool calls.
@@ -157,6 +359,22 @@ impl MessageDispatcher {
             };
         }
 
+        // Clarifications belong to planning and are capped per request
+        if tool_name == "request_clarification"
+            && (orchestrator.context().planner_completed || !orchestrator.can_request_clarification())
+        {
+            log::info!("[ORCHESTRATED_LOOP] Refusing request_clarification — planning is done or the question cap is reached");
+            return ToolCallProcessed {
+                result_content: "No further clarification questions can be asked for this request. \
+                     Make the most reasonable interpretation and call define_tasks.".to_string(),
+                success: false,
+                orchestrator_complete: false,
+                final_summary: None,
+                waiting_for_user_response: false,
+                user_question_content: None,
+            };
+        }
+
         // Check if this is an orchestrator tool
         let orchestrator_result = orchestrator.process_tool_result(tool_name, tool_arguments);
 
@@ -202,6 +420,8 @@ impl MessageDispatcher {
             tool_arguments,
         ));
 
+        let recovery_mode = RecoveryMode::for_channel(&self.db, original_message.channel_id);
+
         // Pre-checks for use_skill: guard against disallowed skills and redundant reloads
         let skill_pre_check_result = if tool_name == "use_skill" {
             let requested_skill = tool_arguments.get("skill_name")
@@ -283,7 +503,7 @@ impl MessageDispatcher {
             None
         };
 
-        let result = if let Some(result) = skill_pre_check_result {
+        let mut result = if let Some(result) = skill_pre_check_result {
             result
         } else {
             // Normal execution path for all tools (including use_skill)
@@ -325,8 +545,33 @@ impl MessageDispatcher {
                     tool_config
                 };
 
+                // The registry checks finance tools against the active skill's grants;
+                // issue_tracker files the orchestrator's planner tasks, which live only here
+                let call_context = {
+                    let mut c = tool_context
+                        .clone()
+                        .with_active_skill(orchestrator.context().active_skill.as_ref().map(|s| s.name.clone()));
+                    if tool_name == "issue_tracker" {
+                        c.extra.insert(
+                            "planner_tasks".to_string(),
+                            serde_json::json!(orchestrator.task_queue().tasks),
+                        );
+                    }
+                    c
+                };
+                let tool_context = &call_context;
+
+                // Dropping the tool future aborts it when the current planner task is cancelled
+                let task_token = self.execution_tracker.planner_task_token(original_message.channel_id);
+
+                // Tools below the channel's confirmation threshold wait for the user first
+                if let Some(declined) = self
+                    .confirm_tool_call(tool_name, tool_arguments, original_message, session_id, task_token.clone())
+                    .await
+                {
+                    declined
                 // Run tool validators before execution
-                if let Some(ref validator_registry) = self.validator_registry {
+                } else if let Some(ref validator_registry) = self.validator_registry {
                     let validation_ctx = crate::tool_validators::ValidationContext::new(
                         tool_name.to_string(),
                         tool_arguments.clone(),
@@ -342,16 +587,11 @@ impl MessageDispatcher {
                         crate::tools::ToolResult::error(error_msg)
                     } else {
                         let start = std::time::Instant::now();
-                        let tool_result = match watchdog.guard_tool_call(
-                            tool_name,
-                            self.tool_registry.execute(tool_name, tool_arguments.clone(), tool_context, Some(exec_config)),
-                        ).await {
-                            Some(result) => result,
-                            None => crate::tools::ToolResult::error(format!(
-                                "Tool '{}' timed out after {}s",
-                                tool_name, watchdog.config().timeout_for_tool(tool_name).as_secs()
-                            )),
-                        };
+                        let tool_result = self.run_with_recovery(
+                            watchdog, tool_name, tool_arguments, tool_context, exec_config,
+                            task_token.clone(), recovery_mode, original_message.channel_id,
+                        )
+                        .await;
                         let duration_ms = start.elapsed().as_millis() as u64;
                         if tool_result.success {
                             orchestrator.record_tool_call(tool_name);
@@ -361,16 +601,11 @@ impl MessageDispatcher {
                     }
                 } else {
                     let start = std::time::Instant::now();
-                    let tool_result = match watchdog.guard_tool_call(
-                        tool_name,
-                        self.tool_registry.execute(tool_name, tool_arguments.clone(), tool_context, Some(exec_config)),
-                    ).await {
-                        Some(result) => result,
-                        None => crate::tools::ToolResult::error(format!(
-                            "Tool '{}' timed out after {}s",
-                            tool_name, watchdog.config().timeout_for_tool(tool_name).as_secs()
-                        )),
-                    };
+                    let tool_result = self.run_with_recovery(
+                        watchdog, tool_name, tool_arguments, tool_context, exec_config,
+                        task_token.clone(), recovery_mode, original_message.channel_id,
+                    )
+                    .await;
                     let duration_ms = start.elapsed().as_millis() as u64;
                     if tool_result.success {
                         orchestrator.record_tool_call(tool_name);
@@ -381,6 +616,36 @@ impl MessageDispatcher {
             }
         };
 
+        // Note private data for redaction; say_to_user reaches the chat before the final reply
+        redaction::observe(&self.db, &tool_context.sensitive_data, tool_name, tool_arguments, &result);
+        if tool_name == "say_to_user" && result.success {
+            result.content = redaction::apply(
+                &self.db,
+                &tool_context.sensitive_data,
+                original_message,
+                tool_context.identity_id.as_deref().unwrap_or_default(),
+                result.content,
+            );
+        }
+
+        // Collect entities from the tool's output into the context bank
+        if result.success && tool_name != "say_to_user" {
+            let added = tool_context.context_bank.add_all(crate::tools::scan_tool_output(&result.content_for_model(), tool_name));
+            if added > 0 {
+                log::debug!("[CONTEXT_BANK] {} new item(s) from '{}' output", added, tool_name);
+                tool_context.context_bank.sync_registers(&tool_context.registers);
+                self.broadcaster.broadcast(GatewayEvent::context_bank_update(
+                    original_message.channel_id,
+                    tool_context.context_bank.to_json(),
+                ));
+            }
+        }
+
+        // Structured results stay available to the next tools as `<tool>_result`
+        if let Some(payload) = result.payload.as_ref().filter(|_| result.success) {
+            tool_context.registers.set(&format!("{}_result", tool_name), payload.structured(), tool_name);
+        }
+
         // Handle subtype change: update orchestrator and refresh tools
         if tool_name == "set_agent_subtype" && result.success {
             if let Some(subtype_str) = tool_arguments.get("subtype").and_then(|v| v.as_str()) {
@@ -432,9 +697,13 @@ impl MessageDispatcher {
         if tool_name == "use_skill" && result.success {
             if let Some(skill_name_val) = tool_arguments.get("skill_name").or_else(|| tool_arguments.get("name")).and_then(|v| v.as_str()) {
                 if let Ok(Some(skill)) = self.db.get_enabled_skill_by_name(skill_name_val) {
+                    let language = crate::skills::locales::preferred_language(&self.db, tool_context.identity_id.as_deref());
+                    let skill = skill.localized(language.as_deref());
                     let skills_dir = crate::config::runtime_skills_dir();
                     let skill_base_dir = format!("{}/{}", skills_dir, skill.name);
-                    let instructions = skill.body.replace("{baseDir}", &skill_base_dir);
+                    let args = tool_arguments.get("args").map(crate::skills::types::args_from_json).unwrap_or_default();
+                    let body = if args.is_empty() { skill.body.clone() } else { skill.render_body(&args) };
+                    let instructions = body.replace("{baseDir}", &skill_base_dir);
 
                     let requires_tools = skill.requires_tools.clone();
                     log::info!(
@@ -466,8 +735,8 @@ impl MessageDispatcher {
             }
         }
 
-        // Handle retry backoff
-        let result = if let Some(retry_secs) = result.retry_after_secs {
+        // Handle retry backoff (with recovery on, run_with_recovery already retried)
+        let result = if let Some(retry_secs) = result.retry_after_secs.filter(|_| !recovery_mode.retries()) {
             self.broadcaster.broadcast(GatewayEvent::tool_waiting(
                 original_message.channel_id,
                 tool_name,
@@ -483,6 +752,72 @@ impl MessageDispatcher {
             result
         };
 
+        // What retries couldn't fix: send a call with bad parameters back for
+        // repair, or skip the current task if the planner marked it optional
+        let mut repair_pending = false;
+        let result = if result.success {
+            result
+        } else {
+            let error = result.error.clone().unwrap_or_else(|| result.content.clone());
+            // Remember approaches that failed for good (not a passing glitch or a bad call)
+            if recovery::classify(&result) == FailureKind::Other {
+                crate::memory::negative_results::record(
+                    &self.db,
+                    tool_name,
+                    &error,
+                    &original_message.text,
+                    tool_context.identity_id.as_deref(),
+                    session_id,
+                );
+            }
+            let repair_definition = self
+                .tool_registry
+                .get(tool_name)
+                .map(|t| t.definition())
+                .filter(|_| {
+                    recovery_mode.repairs()
+                        && recovery::classify(&result) == FailureKind::Validation
+                        && orchestrator.context().repair_prompts < recovery::MAX_REPAIRS_PER_TURN
+                });
+            let optional_task = orchestrator
+                .task_queue()
+                .current_task()
+                .filter(|t| t.optional && recovery_mode.skips_optional())
+                .map(|t| (t.id, t.description.clone()));
+
+            if let Some(definition) = repair_definition {
+                orchestrator.context_mut().repair_prompts += 1;
+                repair_pending = true;
+                log::info!("[TOOL_RECOVERY] '{}' rejected its parameters, asking the agent to repair the call", tool_name);
+                crate::tools::ToolResult {
+                    content: recovery::repair_prompt(&definition, &error),
+                    ..result
+                }
+            } else if let Some((task_id, description)) = optional_task {
+                let note = recovery::skip_note(task_id, &description, tool_name, &error);
+                log::info!("[TOOL_RECOVERY] {}", note);
+                orchestrator.cancel_task(task_id);
+                orchestrator.context_mut().exploration_notes.push(note.clone());
+                self.broadcast_task_status_change(original_message.channel_id, session_id, task_id, "cancelled", &note);
+                self.execution_tracker.end_planner_task(original_message.channel_id);
+                if let TaskAdvanceResult::InconsistentState = self.advance_to_next_task_or_complete(
+                    original_message.channel_id,
+                    session_id,
+                    orchestrator,
+                ) {
+                    processed.orchestrator_complete = true;
+                }
+                self.broadcast_task_queue_update(original_message.channel_id, session_id, orchestrator);
+                batch_state.task_auto_advanced = true;
+                crate::tools::ToolResult {
+                    content: format!("{}\n\nThe task was optional, so it was skipped. Carry on with the current task.", note),
+                    ..result
+                }
+            } else {
+                result
+            }
+        };
+
         // Check metadata for various control signals
         if let Some(metadata) = &result.metadata {
             if metadata.get("requires_user_response").and_then(|v| v.as_bool()).unwrap_or(false) {
@@ -490,6 +825,19 @@ impl MessageDispatcher {
                 processed.user_question_content = Some(result.content.clone());
                 log::info!("[ORCHESTRATED_LOOP] Tool requires user response, will break after processing");
             }
+            // The planner asked a clarification question: the loop parks the execution on it
+            if metadata.get("request_clarification").and_then(|v| v.as_bool()).unwrap_or(false) {
+                let question = metadata.get("question").and_then(|v| v.as_str()).unwrap_or_default();
+                let options = metadata
+                    .get("options")
+                    .and_then(|v| v.as_array())
+                    .map(|opts| opts.iter().filter_map(|o| o.as_str().map(|s| s.to_string())).collect())
+                    .unwrap_or_default();
+                orchestrator.request_clarification(question.to_string(), options);
+                processed.waiting_for_user_response = true;
+                processed.user_question_content = Some(result.content.clone());
+                log::info!("[ORCHESTRATED_LOOP] Planner requested clarification, will park after processing");
+            }
             // Check if add_task was called
             if metadata.get("add_task").and_then(|v| v.as_bool()).unwrap_or(false) {
                 if let Some(desc) = metadata.get("task_description").and_then(|v| v.as_str()) {
@@ -538,6 +886,14 @@ impl MessageDispatcher {
                             "[ORCHESTRATED_LOOP] define_tasks: replacing queue with {} tasks",
                             task_descriptions.len()
                         );
+                        // Remember the plan so it can be saved as a workflow template later
+                        if let Err(e) = self.db.record_session_plan(
+                            session_id,
+                            &orchestrator.context().original_request,
+                            &task_descriptions,
+                        ) {
+                            log::warn!("[ORCHESTRATED_LOOP] Failed to record session plan: {}", e);
+                        }
                         let available_tool_names: Vec<String> = tools.iter().map(|t| t.name.clone()).collect();
                         let ctx = orchestrator.context_mut();
                         ctx.task_queue =
@@ -796,8 +1152,9 @@ impl MessageDispatcher {
         // channels that capture them (e.g. Twitter). Minimal-style channels (Discord,
         // Telegram, AgentChat) skip say_to_user in their event handlers and instead
         // receive the content via the final result.response.
-        if !is_duplicate_say_to_user {
-            self.broadcaster.broadcast(GatewayEvent::tool_result(
+        // A call sent back for repair isn't a failure the chat needs to see
+        if !is_duplicate_say_to_user && !repair_pending {
+            let mut event = GatewayEvent::tool_result(
                 original_message.channel_id,
                 Some(&original_message.chat_id),
                 tool_name,
@@ -806,7 +1163,11 @@ impl MessageDispatcher {
                 &result.content,
                 is_safe_mode,
                 say_to_user_msg_id.as_deref(),
-            ));
+            );
+            if let Some(payload) = &result.payload {
+                event.data["payload"] = serde_json::json!(payload);
+            }
+            self.broadcaster.broadcast(event);
         }
 
         // Execute AfterToolCall hooks
@@ -818,6 +1179,7 @@ impl MessageDispatcher {
                 .with_tool_result(serde_json::json!({
                     "success": result.success,
                     "content": result.content,
+                    "payload": result.payload,
                 }));
             let hook_result = hook_manager.execute(HookEvent::AfterToolCall, &mut hook_context).await;
             if let HookResult::Error(e) = hook_result {
@@ -825,6 +1187,14 @@ impl MessageDispatcher {
             }
         }
 
+        // From here on the content carries the payload's data: the model reads it as JSON
+        let result = crate::tools::ToolResult { content: result.content_for_model(), ..result };
+
+        // Oversized results reach the session and the model as a summary + handle
+        let result = self
+            .apply_tool_output_budget(tool_name, result, original_message, session_id, is_safe_mode)
+            .await;
+
         // Save tool result to session via async writer (non-blocking)
         // Skip ALL successful say_to_user results — the content is returned as the final
         // response by finalize_tool_loop and stored once as an Assistant message by dispatch().
@@ -842,7 +1212,7 @@ impl MessageDispatcher {
                 session_id,
                 DbMessageRole::ToolResult,
                 tool_result_content,
-                Some(tool_name),
+                ToolCallRecord::result(&call_id, tool_name, result.success, started.elapsed().as_millis() as i64),
             );
         }
 
@@ -871,4 +1241,41 @@ impl MessageDispatcher {
         processed.success = result.success;
         processed
     }
+
+    /// Replace a result above the tool output token budget with a summary and
+    /// a `fetch_full_output` handle. Results the model or user must see verbatim
+    /// (say_to_user, ask_user, fetched pages) and safe-mode results pass through.
+    async fn apply_tool_output_budget(
+        &self,
+        tool_name: &str,
+        mut result: crate::tools::ToolResult,
+        original_message: &NormalizedMessage,
+        session_id: i64,
+        is_safe_mode: bool,
+    ) -> crate::tools::ToolResult {
+        use crate::context::tool_output;
+
+        const VERBATIM_TOOLS: &[&str] = &["say_to_user", "ask_user", tool_output::FETCH_TOOL_NAME];
+        let limit = crate::config::tool_output_token_limit();
+        if is_safe_mode || VERBATIM_TOOLS.contains(&tool_name) || !tool_output::exceeds_budget(&result.content, limit) {
+            return result;
+        }
+
+        let client = match self.db.get_active_agent_settings() {
+            Ok(Some(settings)) => crate::ai::AiClient::from_settings_with_wallet_provider(&settings, self.wallet_provider.clone())
+                .map_err(|e| log::warn!("[TOOL_OUTPUT] Failed to create AI client: {}", e))
+                .ok(),
+            _ => None,
+        };
+        result.content = tool_output::condense(
+            &self.db,
+            client.as_ref(),
+            Some(session_id),
+            tool_name,
+            &result.content,
+            &original_message.text,
+        )
+        .await;
+        result
+    }
 }
@@ -4,7 +4,7 @@
 //! to complete a task (say_to_user, task_fully_completed, or both), the user
 //! sees exactly 1 message across all channel types and modes.
 
-use crate::ai::{AiResponse, MockAiClient, TraceEntry, ToolCall};
+use crate::ai::{fixtures, AiResponse, MockAiClient, TraceEntry, ToolCall};
 use crate::ai::multi_agent::types as agent_types;
 use crate::channels::dispatcher::MessageDispatcher;
 use crate::channels::types::{DispatchResult, NormalizedMessage};
@@ -201,6 +201,16 @@ impl TestHarness {
         }
     }
 
+    /// Build a test harness that replays a recorded fixture from
+    /// `tests/fixtures/ai/<fixture>.json` instead of hand-written responses.
+    fn from_fixture(channel_type: &str, safe_mode: bool, fixture: &str) -> Self {
+        let path = fixtures::fixtures_dir().join(format!("{}.json", fixture));
+        let mock = MockAiClient::from_fixture(&path).unwrap_or_else(|e| panic!("{}", e));
+        let mut harness = Self::new(channel_type, safe_mode, false, vec![]);
+        harness.dispatcher = harness.dispatcher.with_mock_ai_client(mock);
+        harness
+    }
+
     /// Create a NormalizedMessage for this harness.
     fn make_message(&self, text: &str, force_safe_mode: bool) -> NormalizedMessage {
         NormalizedMessage {
@@ -215,8 +225,11 @@ impl TestHarness {
             session_mode: None,
             selected_network: None,
             force_safe_mode,
+            shared_chat: false,
             platform_role_ids: vec![],
+            attachments: vec![],
             chat_context: None,
+            action: None,
         }
     }
 
@@ -917,8 +930,11 @@ async fn swap_flow_realistic() {
         session_mode: None,
         selected_network: None,
         force_safe_mode: false,
+        shared_chat: false,
         platform_role_ids: vec![],
+        attachments: vec![],
             chat_context: None,
+            action: None,
     };
 
     eprintln!("  Dispatching: \"{}\"", msg.text);
@@ -1516,6 +1532,25 @@ async fn consecutive_say_to_user_with_pending_tasks_does_not_terminate() {
     assert_eq!(task_numbers[2], Some((2, 2)), "Iteration 3: TASK 2/2 (task 1 advanced)");
 }
 
+/// Recorded fixture replay: a planned two-task turn reaches the user exactly once.
+#[tokio::test]
+async fn fixture_planned_two_tasks_replays_deterministically() {
+    let mut harness = TestHarness::from_fixture("web", false, "planned_two_tasks");
+    let (result, events) = harness
+        .dispatch("check my wallet balance and tell me the gas price on base", false)
+        .await;
+
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+    let trace = harness.get_trace();
+    assert_eq!(trace.len(), 3, "Expected the 3 recorded iterations to replay");
+    assert_eq!(
+        trace[0].output_response.as_ref().unwrap().tool_calls[0].name,
+        "define_tasks"
+    );
+    let count = count_user_messages(&events, &result.response);
+    assert_eq!(count, 1, "Expected exactly 1 user-visible message, got {}", count);
+}
+
 /// Regression: say_to_user mixed with other tools should not trigger loop break.
 ///
 /// Scenario: no task queue. The AI calls say_to_user + another tool in
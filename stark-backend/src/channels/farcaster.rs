@@ -0,0 +1,282 @@
+//! Farcaster mention listener using polling-based approach
+//!
+//! Polls Neynar mention/reply notifications for the bot's fid and answers each
+//! cast with a reply cast (split into a reply chain when it is too long).
+//! On start the bot's account is linked to the agent's EIP-8004 registration.
+
+use crate::channels::dispatcher::MessageDispatcher;
+use crate::channels::twitter::dispatch_and_capture;
+use crate::channels::types::{ChannelType, NormalizedMessage};
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::integrations::farcaster::{self, Cast, NeynarClient};
+use crate::models::{Channel, ChannelSettingKey};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::time::interval;
+
+/// Minimum poll interval in seconds
+const MIN_POLL_INTERVAL_SECS: u64 = 30;
+
+/// Default poll interval in seconds
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
+
+/// Configuration for the Farcaster listener
+#[derive(Debug, Clone)]
+pub struct FarcasterConfig {
+    pub bot_fid: u64,
+    pub poll_interval_secs: u64,
+    pub max_replies_per_hour: u32,
+    pub admin_fid: Option<u64>,
+}
+
+impl FarcasterConfig {
+    /// Load configuration from channel settings
+    pub fn from_channel(channel: &Channel, db: &Database) -> Result<Self, String> {
+        let setting = |key: ChannelSettingKey| {
+            db.get_channel_setting(channel.id, key.as_ref())
+                .ok()
+                .flatten()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        let bot_fid = setting(ChannelSettingKey::FarcasterBotFid)
+            .ok_or_else(|| "Farcaster bot fid not configured".to_string())?
+            .parse()
+            .map_err(|_| "Farcaster bot fid must be numeric".to_string())?;
+
+        let poll_interval_secs = setting(ChannelSettingKey::FarcasterPollIntervalSecs)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_POLL_INTERVAL_SECS)
+            .max(MIN_POLL_INTERVAL_SECS);
+
+        let max_replies_per_hour = setting(ChannelSettingKey::FarcasterMaxRepliesPerHour)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+
+        let admin_fid = setting(ChannelSettingKey::FarcasterAdminFid).and_then(|s| s.parse().ok());
+
+        Ok(Self {
+            bot_fid,
+            poll_interval_secs,
+            max_replies_per_hour,
+            admin_fid,
+        })
+    }
+}
+
+pub async fn start_farcaster_listener(
+    channel: Channel,
+    dispatcher: Arc<MessageDispatcher>,
+    broadcaster: Arc<EventBroadcaster>,
+    db: Arc<Database>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) -> Result<(), String> {
+    let channel_id = channel.id;
+    let channel_name = channel.name.clone();
+
+    log::info!("Starting Farcaster listener for channel: {}", channel_name);
+
+    let config = FarcasterConfig::from_channel(&channel, &db)?;
+    let client = NeynarClient::from_keys(&db)?;
+
+    // SECURITY: as with Twitter, safe mode is decided per cast via force_safe_mode —
+    // only the configured admin fid gets standard mode.
+    if channel.safe_mode {
+        if let Err(e) = db.set_channel_safe_mode(channel_id, false) {
+            log::error!("Failed to disable channel-level safe_mode: {}", e);
+        }
+    }
+
+    let bot = client.user(config.bot_fid).await.map_err(|e| {
+        let error = format!("Farcaster: Failed to look up bot fid {}: {}", config.bot_fid, e);
+        log::error!("{}", error);
+        error
+    })?;
+
+    log::info!(
+        "Farcaster: Bot @{} (fid {}), poll_interval={}s, max_replies/hr={}, admin_fid={}",
+        bot.username,
+        bot.fid,
+        config.poll_interval_secs,
+        if config.max_replies_per_hour == 0 { "unlimited".to_string() } else { config.max_replies_per_hour.to_string() },
+        config.admin_fid.map(|f| f.to_string()).unwrap_or_else(|| "none".to_string())
+    );
+
+    // Give the onchain agent its social presence: list this account in the EIP-8004 registration
+    if let Err(e) = farcaster::link_agent_identity(&db, &bot) {
+        log::warn!("Farcaster: Failed to link account to agent identity: {}", e);
+    }
+
+    broadcaster.broadcast(GatewayEvent::channel_started(
+        channel_id,
+        ChannelType::Farcaster.as_str(),
+        &channel_name,
+    ));
+
+    let mut seeded = db.has_processed_casts(channel_id).unwrap_or(false);
+    let mut hour_start = Instant::now();
+    let mut replies_this_hour: u32 = 0;
+    let mut poll_interval = interval(Duration::from_secs(config.poll_interval_secs));
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown_rx => {
+                log::info!("Farcaster listener {} received shutdown signal", channel_name);
+                break;
+            }
+            _ = poll_interval.tick() => {
+                let casts = match client.mentions(config.bot_fid).await {
+                    Ok(casts) => casts,
+                    Err(e) => {
+                        log::error!("Farcaster: Error polling notifications: {}", e);
+                        if e.contains("429") {
+                            log::warn!("Farcaster: Rate limited, backing off for 5 minutes");
+                            tokio::time::sleep(Duration::from_secs(300)).await;
+                        }
+                        continue;
+                    }
+                };
+
+                // First run: record what is already there instead of answering a backlog
+                if !seeded {
+                    for cast in &casts {
+                        let _ = db.mark_cast_processed(channel_id, &cast.hash, cast.author.fid, &cast.author.username, &cast.text);
+                    }
+                    log::info!("Farcaster: Skipped {} existing notification(s) on first start", casts.len());
+                    seeded = true;
+                    continue;
+                }
+
+                // Oldest first
+                for cast in casts.into_iter().rev() {
+                    if cast.author.fid == config.bot_fid
+                        || db.is_cast_processed(channel_id, &cast.hash).unwrap_or(false)
+                    {
+                        continue;
+                    }
+
+                    if hour_start.elapsed() >= Duration::from_secs(3600) {
+                        hour_start = Instant::now();
+                        replies_this_hour = 0;
+                    }
+
+                    let is_admin = config.admin_fid == Some(cast.author.fid);
+                    if !is_admin && config.max_replies_per_hour > 0 && replies_this_hour >= config.max_replies_per_hour {
+                        log::info!(
+                            "Farcaster: Hourly reply limit reached ({}/{}), skipping cast {}",
+                            replies_this_hour, config.max_replies_per_hour, cast.hash
+                        );
+                    } else {
+                        let response = process_cast(&cast, &bot.username, &client, channel_id, !is_admin, &dispatcher, &broadcaster).await;
+                        if let Some(text) = response {
+                            match client.publish_thread(&text, Some(&cast.hash), None, &[]).await {
+                                Ok(hashes) => {
+                                    if !is_admin {
+                                        replies_this_hour += 1;
+                                    }
+                                    log::info!("Farcaster: Replied to @{} with {} cast(s)", cast.author.username, hashes.len());
+                                }
+                                Err(e) => log::error!("Farcaster: Failed to post reply to {}: {}", cast.hash, e),
+                            }
+                        }
+                    }
+
+                    if let Err(e) = db.mark_cast_processed(channel_id, &cast.hash, cast.author.fid, &cast.author.username, &cast.text) {
+                        log::error!("Farcaster: Failed to mark cast {} as processed: {}", cast.hash, e);
+                    }
+                }
+            }
+        }
+    }
+
+    broadcaster.broadcast(GatewayEvent::channel_stopped(
+        channel_id,
+        ChannelType::Farcaster.as_str(),
+        &channel_name,
+    ));
+
+    Ok(())
+}
+
+/// Strip the leading mention of the bot from a cast
+fn extract_command_text(text: &str, bot_username: &str) -> String {
+    let mention = format!("@{}", bot_username.to_lowercase());
+    let trimmed = text.trim_start();
+    match trimmed.get(..mention.len()) {
+        Some(prefix) if prefix.to_lowercase() == mention => trimmed[mention.len()..].trim().to_string(),
+        _ => trimmed.trim().to_string(),
+    }
+}
+
+/// Dispatch a cast to the agent and return the reply text
+async fn process_cast(
+    cast: &Cast,
+    bot_username: &str,
+    client: &NeynarClient,
+    channel_id: i64,
+    force_safe_mode: bool,
+    dispatcher: &Arc<MessageDispatcher>,
+    broadcaster: &Arc<EventBroadcaster>,
+) -> Option<String> {
+    let command_text = extract_command_text(&cast.text, bot_username);
+    if command_text.is_empty() {
+        log::debug!("Farcaster: Empty cast after removing mention, ignoring");
+        return None;
+    }
+
+    // Include the cast being replied to, if any
+    let parent = match cast.parent_hash.as_deref() {
+        Some(hash) => client.cast(hash).await.ok(),
+        None => None,
+    };
+
+    let hint = format!(
+        "[FARCASTER CAST from @{} - Reply using say_to_user — your LAST say_to_user message becomes the reply cast. Keep it under 320 characters if you can; longer replies are split into a thread. It MUST contain the actual answer, not a summary of what you did.]",
+        cast.author.username
+    );
+    let text = match parent {
+        Some(parent) => format!(
+            "{}\n\n[IN REPLY TO @{}:]\n{}\n\n[CAST DIRECTED TO YOU:]\n@{}: {}",
+            hint, parent.author.username, parent.text, cast.author.username, command_text
+        ),
+        None => format!("{}\n\n{}", hint, command_text),
+    };
+
+    let normalized = NormalizedMessage {
+        channel_id,
+        channel_type: ChannelType::Farcaster.to_string(),
+        // One session per cast, so safe-mode casts never share a session with admin casts
+        chat_id: cast.hash.clone(),
+        chat_name: None,
+        user_id: cast.author.fid.to_string(),
+        user_name: cast.author.username.clone(),
+        text,
+        message_id: Some(cast.hash.clone()),
+        session_mode: None,
+        selected_network: None,
+        force_safe_mode,
+        shared_chat: true,
+        platform_role_ids: vec![],
+        attachments: vec![],
+        chat_context: None,
+        action: None,
+    };
+
+    dispatch_and_capture(normalized, &cast.author.username, channel_id, dispatcher, broadcaster).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_command_text() {
+        assert_eq!(extract_command_text("@StarkBot what is gas on base?", "starkbot"), "what is gas on base?");
+        assert_eq!(extract_command_text("hey @starkbot", "starkbot"), "hey @starkbot");
+        assert_eq!(extract_command_text("@starkbot", "starkbot"), "");
+    }
+}
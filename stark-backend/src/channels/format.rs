@@ -0,0 +1,469 @@
+//! Per-channel rendering of the agent's markdown output
+//!
+//! The agent always answers in canonical (GitHub-flavoured) markdown. Each
+//! platform understands a different subset of it, so replies go through
+//! [`render_chunks`] / [`render`] before sending:
+//! - Telegram: HTML parse mode (`<b>`, `<i>`, `<pre>`, links); tables become `<pre>` grids
+//! - Discord: native markdown; tables become fenced monospace grids
+//! - Slack: mrkdwn (`*bold*`, `_italic_`, `<url|text>`); tables become fenced grids
+//! - Email: an HTML body with real `<table>`s (sent alongside a plain-text part)
+//! - Plain: markup stripped, for platforms without formatting (Twitter, Farcaster, fallbacks)
+//!
+//! Chunking happens on block boundaries so a code block or table split across
+//! messages is closed and reopened rather than left dangling.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::channels::types::ChannelType;
+
+static CODE_SPAN_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"`([^`\n]+)`").unwrap());
+static LINK_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]\n]+)\]\((https?://[^\s)]+)\)").unwrap());
+static BOLD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*\*([^*\n]+?)\*\*").unwrap());
+static STRIKE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"~~([^~\n]+?)~~").unwrap());
+static ITALIC_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(^|[^\w*])\*([^*\s][^*\n]*?)\*").unwrap());
+static BULLET_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\s*)[-*] ").unwrap());
+static HTML_TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+/// Output format for a destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderTarget {
+    Telegram,
+    Discord,
+    Slack,
+    Email,
+    Plain,
+}
+
+impl RenderTarget {
+    /// Native format for a channel type
+    pub fn for_channel(channel_type: ChannelType) -> Self {
+        match channel_type {
+            ChannelType::Telegram => Self::Telegram,
+            ChannelType::Discord => Self::Discord,
+            ChannelType::Slack => Self::Slack,
+            ChannelType::Twitter | ChannelType::Farcaster | ChannelType::ExternalChannel => Self::Plain,
+        }
+    }
+
+    /// Maximum size of one message on the platform (bytes, which is conservative)
+    pub fn max_len(&self) -> usize {
+        match self {
+            Self::Telegram => 4096,
+            Self::Discord => 2000,
+            Self::Slack => 4000,
+            Self::Email | Self::Plain => usize::MAX,
+        }
+    }
+
+    fn is_html(&self) -> bool {
+        matches!(self, Self::Telegram | Self::Email)
+    }
+
+    fn block_separator(&self) -> &'static str {
+        match self {
+            Self::Email => "\n",
+            _ => "\n\n",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Text(String),
+    Heading { level: usize, text: String },
+    Code { lang: String, code: String },
+    Table { header: Vec<String>, rows: Vec<Vec<String>> },
+}
+
+impl Block {
+    /// Halve an oversized block into two of the same kind
+    fn split(&self) -> Option<(Block, Block)> {
+        match self {
+            Block::Text(text) => split_text(text).map(|(a, b)| (Block::Text(a), Block::Text(b))),
+            Block::Code { lang, code } => split_text(code).map(|(a, b)| {
+                (
+                    Block::Code { lang: lang.clone(), code: a },
+                    Block::Code { lang: lang.clone(), code: b },
+                )
+            }),
+            Block::Table { header, rows } if rows.len() > 1 => {
+                let (a, b) = rows.split_at(rows.len() / 2);
+                Some((
+                    Block::Table { header: header.clone(), rows: a.to_vec() },
+                    Block::Table { header: header.clone(), rows: b.to_vec() },
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Render markdown for `target` as a single string (no chunking)
+pub fn render(markdown: &str, target: RenderTarget) -> String {
+    parse_blocks(markdown)
+        .iter()
+        .map(|block| render_block(block, target))
+        .collect::<Vec<_>>()
+        .join(target.block_separator())
+}
+
+/// Render markdown for `target`, split into messages within the platform limit
+pub fn render_chunks(markdown: &str, target: RenderTarget) -> Vec<String> {
+    let limit = target.max_len();
+    let separator = target.block_separator();
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for block in parse_blocks(markdown) {
+        for piece in render_block_fitting(&block, target, limit) {
+            if piece.is_empty() {
+                continue;
+            }
+            if !current.is_empty() && current.len() + separator.len() + piece.len() > limit {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push_str(separator);
+            }
+            current.push_str(&piece);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Strip tags and entities from Telegram-style HTML (fallback when the platform rejects it)
+pub fn html_to_plain(html: &str) -> String {
+    HTML_TAG_RE
+        .replace_all(html, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
+fn render_block_fitting(block: &Block, target: RenderTarget, limit: usize) -> Vec<String> {
+    let rendered = render_block(block, target);
+    if rendered.len() <= limit {
+        return vec![rendered];
+    }
+    match block.split() {
+        Some((a, b)) => {
+            let mut pieces = render_block_fitting(&a, target, limit);
+            pieces.extend(render_block_fitting(&b, target, limit));
+            pieces
+        }
+        None => hard_split(&rendered, limit),
+    }
+}
+
+fn split_text(text: &str) -> Option<(String, String)> {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() > 1 {
+        let (a, b) = lines.split_at(lines.len() / 2);
+        return Some((a.join("\n"), b.join("\n")));
+    }
+    let char_count = text.chars().count();
+    if char_count < 2 {
+        return None;
+    }
+    let mid = text.char_indices().nth(char_count / 2).map(|(i, _)| i)?;
+    // Prefer breaking on whitespace before the midpoint
+    let at = text[..mid].rfind(char::is_whitespace).filter(|&i| i > 0).unwrap_or(mid);
+    Some((text[..at].trim_end().to_string(), text[at..].trim_start().to_string()))
+}
+
+fn hard_split(text: &str, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        if current.len() + c.len_utf8() > limit {
+            pieces.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+fn parse_blocks(markdown: &str) -> Vec<Block> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+
+    fn flush(paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>) {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Text(paragraph.join("\n")));
+            paragraph.clear();
+        }
+    }
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+
+        if let Some(rest) = trimmed.strip_prefix("```") {
+            flush(&mut paragraph, &mut blocks);
+            let lang = rest.trim().to_string();
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].trim_start().starts_with("```") {
+                code.push(lines[i]);
+                i += 1;
+            }
+            i += 1; // closing fence (an unclosed block runs to the end)
+            blocks.push(Block::Code { lang, code: code.join("\n") });
+            continue;
+        }
+
+        if line.contains('|') && lines.get(i + 1).is_some_and(|next| is_table_separator(next)) {
+            flush(&mut paragraph, &mut blocks);
+            let header = split_row(line);
+            let mut rows = Vec::new();
+            i += 2;
+            while i < lines.len() && lines[i].contains('|') && !lines[i].trim().is_empty() {
+                rows.push(split_row(lines[i]));
+                i += 1;
+            }
+            blocks.push(Block::Table { header, rows });
+            continue;
+        }
+
+        if let Some((level, text)) = parse_heading(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading { level, text: text.to_string() });
+        } else if line.trim().is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else {
+            paragraph.push(line);
+        }
+        i += 1;
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    line[level..].strip_prefix(' ').map(|text| (level, text.trim()))
+}
+
+fn is_table_separator(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.contains('|')
+        && trimmed.contains("---")
+        && trimmed.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+fn split_row(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix('|').unwrap_or(trimmed);
+    trimmed.split('|').map(|cell| cell.trim().to_string()).collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn render_block(block: &Block, target: RenderTarget) -> String {
+    match block {
+        Block::Text(text) => {
+            let lines: Vec<String> = text
+                .lines()
+                .map(|line| {
+                    let line = match target {
+                        RenderTarget::Discord => line.to_string(),
+                        _ => BULLET_RE.replace(line, "${1}• ").into_owned(),
+                    };
+                    render_inline(&line, target)
+                })
+                .collect();
+            match target {
+                RenderTarget::Email => format!("<p>{}</p>", lines.join("<br>\n")),
+                _ => lines.join("\n"),
+            }
+        }
+        Block::Heading { level, text } => match target {
+            RenderTarget::Telegram => format!("<b>{}</b>", render_inline(text, target)),
+            RenderTarget::Discord if *level <= 3 => format!("{} {}", "#".repeat(*level), text),
+            RenderTarget::Discord => format!("**{}**", text),
+            RenderTarget::Slack => format!("*{}*", render_inline(text, RenderTarget::Plain)),
+            RenderTarget::Email => format!("<h{0}>{1}</h{0}>", level, render_inline(text, target)),
+            RenderTarget::Plain => render_inline(text, target),
+        },
+        Block::Code { lang, code } => match target {
+            RenderTarget::Telegram => {
+                let lang: String = lang.chars().filter(|c| c.is_ascii_alphanumeric() || "+-#.".contains(*c)).collect();
+                if lang.is_empty() {
+                    format!("<pre>{}</pre>", escape_html(code))
+                } else {
+                    format!("<pre><code class=\"language-{}\">{}</code></pre>", lang, escape_html(code))
+                }
+            }
+            RenderTarget::Discord => format!("```{}\n{}\n```", lang, code),
+            RenderTarget::Slack => format!("```\n{}\n```", code),
+            RenderTarget::Email => format!("<pre><code>{}</code></pre>", escape_html(code)),
+            RenderTarget::Plain => code.clone(),
+        },
+        Block::Table { header, rows } => match target {
+            RenderTarget::Email => render_html_table(header, rows),
+            RenderTarget::Telegram => format!("<pre>{}</pre>", escape_html(&render_text_table(header, rows))),
+            RenderTarget::Discord | RenderTarget::Slack => format!("```\n{}\n```", render_text_table(header, rows)),
+            RenderTarget::Plain => render_text_table(header, rows),
+        },
+    }
+}
+
+/// Inline markup (code spans, links, bold, italic, strikethrough)
+fn render_inline(text: &str, target: RenderTarget) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for caps in CODE_SPAN_RE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        out.push_str(&render_inline_markup(&text[last..whole.start()], target));
+        let code = &caps[1];
+        out.push_str(&match target {
+            RenderTarget::Telegram | RenderTarget::Email => format!("<code>{}</code>", escape_html(code)),
+            RenderTarget::Discord | RenderTarget::Slack => format!("`{}`", code),
+            RenderTarget::Plain => code.to_string(),
+        });
+        last = whole.end();
+    }
+    out.push_str(&render_inline_markup(&text[last..], target));
+    out
+}
+
+fn render_inline_markup(text: &str, target: RenderTarget) -> String {
+    match target {
+        RenderTarget::Discord => text.to_string(),
+        _ if target.is_html() => {
+            let text = escape_html(text);
+            let text = LINK_RE.replace_all(&text, "<a href=\"${2}\">${1}</a>");
+            let text = BOLD_RE.replace_all(&text, "<b>${1}</b>");
+            let text = STRIKE_RE.replace_all(&text, "<s>${1}</s>");
+            ITALIC_RE.replace_all(&text, "${1}<i>${2}</i>").into_owned()
+        }
+        RenderTarget::Slack => {
+            let text = escape_html(text);
+            let text = LINK_RE.replace_all(&text, "<${2}|${1}>");
+            // Italic first: Slack's bold marker is a single asterisk
+            let text = ITALIC_RE.replace_all(&text, "${1}_${2}_");
+            let text = BOLD_RE.replace_all(&text, "*${1}*");
+            STRIKE_RE.replace_all(&text, "~${1}~").into_owned()
+        }
+        _ => {
+            let text = LINK_RE.replace_all(text, "${1} (${2})");
+            let text = BOLD_RE.replace_all(&text, "${1}");
+            let text = STRIKE_RE.replace_all(&text, "${1}");
+            ITALIC_RE.replace_all(&text, "${1}${2}").into_owned()
+        }
+    }
+}
+
+/// Monospace grid with padded columns
+fn render_text_table(header: &[String], rows: &[Vec<String>]) -> String {
+    let plain = |cell: &String| render_inline(cell, RenderTarget::Plain);
+    let header: Vec<String> = header.iter().map(plain).collect();
+    let rows: Vec<Vec<String>> = rows.iter().map(|row| row.iter().map(plain).collect()).collect();
+
+    let columns = rows.iter().map(Vec::len).chain(std::iter::once(header.len())).max().unwrap_or(0);
+    let mut widths = vec![0; columns];
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let format_row = |row: &Vec<String>| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| {
+                let cell = row.get(i).map(String::as_str).unwrap_or("");
+                format!("{}{}", cell, " ".repeat(widths[i] - cell.chars().count()))
+            })
+            .collect();
+        cells.join(" | ").trim_end().to_string()
+    };
+
+    let mut lines = vec![format_row(&header)];
+    lines.push(widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("-+-"));
+    lines.extend(rows.iter().map(format_row));
+    lines.join("\n")
+}
+
+fn render_html_table(header: &[String], rows: &[Vec<String>]) -> String {
+    let mut html = String::from("<table border=\"1\" cellpadding=\"4\" cellspacing=\"0\" style=\"border-collapse:collapse\">\n<tr>");
+    for cell in header {
+        html.push_str(&format!("<th>{}</th>", render_inline(cell, RenderTarget::Email)));
+    }
+    html.push_str("</tr>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", render_inline(cell, RenderTarget::Email)));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "## Balances\n\nYou hold **2 ETH** on *Base* — see [scan](https://basescan.org).\n\n| Token | Amount |\n|---|---:|\n| ETH | 2 |\n| USDC | 150.5 |\n\n```rust\nlet x = a < b;\n```";
+
+    #[test]
+    fn test_telegram_html() {
+        let out = render(SAMPLE, RenderTarget::Telegram);
+        assert!(out.starts_with("<b>Balances</b>"));
+        assert!(out.contains("<b>2 ETH</b> on <i>Base</i>"));
+        assert!(out.contains("<a href=\"https://basescan.org\">scan</a>"));
+        assert!(out.contains("<pre>Token | Amount\n------+-------\nETH   | 2\nUSDC  | 150.5</pre>"));
+        assert!(out.contains("<pre><code class=\"language-rust\">let x = a &lt; b;</code></pre>"));
+    }
+
+    #[test]
+    fn test_slack_and_plain() {
+        let slack = render("**bold** and *it* ~~gone~~ [x](https://a.io)", RenderTarget::Slack);
+        assert_eq!(slack, "*bold* and _it_ ~gone~ <https://a.io|x>");
+
+        let plain = render(SAMPLE, RenderTarget::Plain);
+        assert!(plain.contains("You hold 2 ETH on Base — see scan (https://basescan.org)."));
+        assert!(!plain.contains("**"));
+    }
+
+    #[test]
+    fn test_email_table_and_discord_passthrough() {
+        let email = render(SAMPLE, RenderTarget::Email);
+        assert!(email.contains("<h2>Balances</h2>"));
+        assert!(email.contains("<tr><th>Token</th><th>Amount</th></tr>"));
+
+        let discord = render(SAMPLE, RenderTarget::Discord);
+        assert!(discord.contains("**2 ETH**"));
+        assert!(discord.contains("```\nToken | Amount"));
+    }
+
+    #[test]
+    fn test_chunks_keep_code_fences_balanced() {
+        let code: String = (0..400).map(|i| format!("line {}\n", i)).collect();
+        let markdown = format!("intro\n\n```\n{}```", code);
+        let chunks = render_chunks(&markdown, RenderTarget::Discord);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 2000);
+            assert_eq!(chunk.matches("```").count() % 2, 0);
+        }
+        assert!(chunks[0].starts_with("intro"));
+    }
+}
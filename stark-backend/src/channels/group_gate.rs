@@ -0,0 +1,174 @@
+//! Group-chat etiquette: when the bot answers in group chats
+//!
+//! Each Telegram, Discord or Slack channel decides which messages are meant
+//! for the bot. A message triggers an answer when the bot is @mentioned, when
+//! it replies to one of the bot's messages, or when it matches the channel's
+//! trigger pattern (each trigger can be turned off). The response mode then
+//! picks what happens to everything else:
+//!
+//! - **triggered** (default): untriggered messages are ignored
+//! - **passive**: untriggered messages are kept in the session as context,
+//!   so the bot knows the conversation when it is finally summoned
+//! - **all**: every message is answered
+
+use regex::Regex;
+
+use crate::db::Database;
+use crate::models::ChannelSettingKey;
+
+/// What a channel does with messages that don't trigger it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupResponseMode {
+    #[default]
+    Triggered,
+    Passive,
+    All,
+}
+
+impl GroupResponseMode {
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "passive" => Self::Passive,
+            "all" => Self::All,
+            _ => Self::Triggered,
+        }
+    }
+}
+
+/// How a message relates to the bot, as the platform reports it
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Addressing {
+    /// The bot was @mentioned
+    pub mentioned: bool,
+    /// The message replies to one of the bot's messages
+    pub reply_to_bot: bool,
+}
+
+/// What to do with one incoming message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateDecision {
+    /// Run the agent and answer
+    Respond,
+    /// Keep the message as session context without answering
+    Remember,
+    /// Drop the message
+    Ignore,
+}
+
+/// A channel's group-chat triggers
+#[derive(Debug, Clone)]
+pub struct GroupGate {
+    pub mode: GroupResponseMode,
+    pub on_mention: bool,
+    pub on_reply: bool,
+    pub pattern: Option<Regex>,
+}
+
+impl Default for GroupGate {
+    /// Answer mentions and replies, ignore the rest (the behaviour before
+    /// these settings existed)
+    fn default() -> Self {
+        Self {
+            mode: GroupResponseMode::Triggered,
+            on_mention: true,
+            on_reply: true,
+            pattern: None,
+        }
+    }
+}
+
+impl GroupGate {
+    /// Read the channel's settings; unset settings keep the defaults
+    pub fn load(db: &Database, channel_id: i64) -> Self {
+        let setting = |key: ChannelSettingKey| db.get_channel_setting(channel_id, key.as_ref()).ok().flatten();
+        let toggle = |key: ChannelSettingKey| setting(key).map(|v| v.trim() != "false").unwrap_or(true);
+
+        Self {
+            mode: setting(ChannelSettingKey::GroupResponseMode)
+                .map(|v| GroupResponseMode::from_setting(&v))
+                .unwrap_or_default(),
+            on_mention: toggle(ChannelSettingKey::GroupTriggerMention),
+            on_reply: toggle(ChannelSettingKey::GroupTriggerReply),
+            pattern: setting(ChannelSettingKey::GroupTriggerPattern).and_then(|p| compile_pattern(&p, channel_id)),
+        }
+    }
+
+    /// Whether the message summons the bot
+    pub fn is_triggered(&self, text: &str, addressing: Addressing) -> bool {
+        (self.on_mention && addressing.mentioned)
+            || (self.on_reply && addressing.reply_to_bot)
+            || self.pattern.as_ref().is_some_and(|p| p.is_match(text))
+    }
+
+    pub fn decide(&self, text: &str, addressing: Addressing) -> GateDecision {
+        if self.mode == GroupResponseMode::All || self.is_triggered(text, addressing) {
+            GateDecision::Respond
+        } else if self.mode == GroupResponseMode::Passive {
+            GateDecision::Remember
+        } else {
+            GateDecision::Ignore
+        }
+    }
+}
+
+/// Case-insensitive trigger pattern; a bad pattern is logged and disables
+/// the pattern trigger rather than the channel
+fn compile_pattern(pattern: &str, channel_id: i64) -> Option<Regex> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return None;
+    }
+    match regex::RegexBuilder::new(pattern).case_insensitive(true).size_limit(1 << 20).build() {
+        Ok(re) => Some(re),
+        Err(e) => {
+            log::warn!("[GROUP_GATE] Channel {}: ignoring invalid trigger pattern '{}': {}", channel_id, pattern, e);
+            None
+        }
+    }
+}
+
+/// Check a trigger pattern before it is saved
+pub fn validate_pattern(pattern: &str) -> Result<(), String> {
+    if pattern.trim().is_empty() {
+        return Ok(());
+    }
+    regex::RegexBuilder::new(pattern.trim())
+        .size_limit(1 << 20)
+        .build()
+        .map(|_| ())
+        .map_err(|e| format!("Invalid trigger pattern: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_gate_decisions() {
+        let mentioned = Addressing { mentioned: true, reply_to_bot: false };
+        let reply = Addressing { mentioned: false, reply_to_bot: true };
+        let plain = Addressing::default();
+
+        let gate = GroupGate::default();
+        assert_eq!(gate.decide("hi @bot", mentioned), GateDecision::Respond);
+        assert_eq!(gate.decide("sure", reply), GateDecision::Respond);
+        assert_eq!(gate.decide("lunch?", plain), GateDecision::Ignore);
+
+        let gate = GroupGate {
+            mode: GroupResponseMode::Passive,
+            on_mention: true,
+            on_reply: false,
+            pattern: compile_pattern(r"^(hey )?stark\b", 1),
+        };
+        assert_eq!(gate.decide("Hey Stark, price of ETH?", plain), GateDecision::Respond);
+        assert_eq!(gate.decide("sure", reply), GateDecision::Remember);
+        assert_eq!(gate.decide("starknet is up", plain), GateDecision::Remember);
+
+        let gate = GroupGate { mode: GroupResponseMode::from_setting("all"), ..GroupGate::default() };
+        assert_eq!(gate.decide("lunch?", plain), GateDecision::Respond);
+
+        assert!(compile_pattern("(unclosed", 1).is_none());
+        assert!(validate_pattern("(unclosed").is_err());
+        assert!(validate_pattern("").is_ok());
+    }
+}
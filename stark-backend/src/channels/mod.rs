@@ -1,9 +1,20 @@
+pub mod actions;
+pub mod chat_commands;
 pub mod discord;
 pub mod dispatcher;
+pub mod farcaster;
+pub mod format;
+pub mod group_gate;
+pub mod outbound;
+pub mod outbox;
+pub mod proactive;
+pub mod redaction;
+pub mod response_style;
 pub mod safe_mode_rate_limiter;
 pub mod session_writer;
 pub mod slack;
 pub mod telegram;
+pub mod triage;
 pub mod twitter;
 pub mod types;
 pub mod util;
@@ -23,6 +34,9 @@ use dashmap::DashMap;
 use std::sync::Arc;
 use tokio::sync::oneshot;
 
+/// Pause between stopping and restarting a channel on config reload
+const CHANNEL_RESTART_DELAY_MS: u64 = 1000;
+
 /// Manages all running channel listeners
 pub struct ChannelManager {
     db: Arc<Database>,
@@ -104,6 +118,15 @@ impl ChannelManager {
         self.running_channels.iter().map(|e| *e.key()).collect()
     }
 
+    /// Get IDs of running channels of one type (e.g. "twitter")
+    pub fn running_channel_ids_by_type(&self, channel_type: &str) -> Vec<i64> {
+        self.running_channels
+            .iter()
+            .filter(|e| e.value().channel_type == channel_type)
+            .map(|e| *e.key())
+            .collect()
+    }
+
     /// Start a channel listener
     pub async fn start_channel(&self, mut channel: Channel) -> Result<(), String> {
         let channel_id = channel.id;
@@ -122,7 +145,7 @@ impl ChannelManager {
                 "discord" => "discord_bot_token",
                 "telegram" => "telegram_bot_token",
                 "slack" => "slack_bot_token",
-                _ => "", // Twitter and ExternalChannel don't use bot_token
+                _ => "", // Twitter, Farcaster and ExternalChannel don't use bot_token
             };
             if !setting_key.is_empty() {
                 if let Ok(Some(token)) = self.db.get_channel_setting(channel_id, setting_key) {
@@ -278,6 +301,27 @@ impl ChannelManager {
                     running_channels.remove(&channel_id);
                 });
             }
+            types::ChannelType::Farcaster => {
+                let db = self.db.clone();
+                tokio::spawn(async move {
+                    let result = farcaster::start_farcaster_listener(
+                        channel,
+                        dispatcher,
+                        broadcaster.clone(),
+                        db,
+                        shutdown_rx,
+                    )
+                    .await;
+
+                    if let Err(e) = result {
+                        log::error!("Farcaster listener error: {}", e);
+                        broadcaster.broadcast(GatewayEvent::channel_error(channel_id, &e));
+                    }
+
+                    // Remove from running channels
+                    running_channels.remove(&channel_id);
+                });
+            }
             types::ChannelType::ExternalChannel => {
                 // No listener needed — HTTP request/response model.
                 // Channel being in running_channels is sufficient.
@@ -315,6 +359,26 @@ impl ChannelManager {
         }
     }
 
+    /// Restart a running channel so its listener picks up fresh credentials and settings.
+    /// Returns Ok(false) if the channel wasn't running (nothing to reload).
+    pub async fn restart_channel(&self, channel_id: i64) -> Result<bool, String> {
+        if !self.is_running(channel_id) {
+            return Ok(false);
+        }
+        let channel = self.db.get_channel(channel_id)
+            .map_err(|e| format!("Failed to load channel {}: {}", channel_id, e))?;
+
+        self.stop_channel(channel_id).await?;
+        let Some(channel) = channel.filter(|c| c.enabled) else {
+            // Deleted or disabled since it was started — leave it stopped
+            return Ok(true);
+        };
+        // Give the old listener a moment to release its connection (e.g. Telegram long-poll)
+        tokio::time::sleep(std::time::Duration::from_millis(CHANNEL_RESTART_DELAY_MS)).await;
+        self.start_channel(channel).await?;
+        Ok(true)
+    }
+
     /// Stop all running channels
     pub async fn stop_all(&self) {
         let ids: Vec<i64> = self.running_channels.iter().map(|e| *e.key()).collect();
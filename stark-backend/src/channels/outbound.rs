@@ -0,0 +1,296 @@
+//! Outbound delivery with offline buffering
+//!
+//! Listeners hand their final replies to [`deliver_or_queue`]: chunks are sent
+//! directly while the platform is reachable; the first failed chunk and
+//! everything after it are persisted to `outbound_messages`. A chat with queued
+//! messages keeps queueing new replies so ordering is preserved, and the
+//! background delivery worker drains each chat's queue oldest-first with
+//! exponential backoff, dead-lettering after [`MAX_DELIVERY_ATTEMPTS`].
+//! Queued content is already rendered for the platform (see `channels::format`).
+
+use std::future::Future;
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+
+use crate::channels::format::{self, RenderTarget};
+use crate::channels::proactive;
+use crate::channels::types::{ActionButton, ChannelType};
+use crate::db::tables::outbound::OutboundMessage;
+use crate::db::Database;
+
+/// Attempts (including the original send) before a message is dead-lettered
+pub const MAX_DELIVERY_ATTEMPTS: i64 = 8;
+
+/// Retry delays (seconds) indexed by attempts so far; stays at the last entry
+const RETRY_BACKOFF_SECS: &[i64] = &[5, 15, 60, 5 * 60, 15 * 60, 30 * 60, 60 * 60];
+
+/// Messages claimed per worker pass
+const DELIVERY_BATCH_SIZE: usize = 20;
+
+/// Delay before the next attempt after `attempts` failures
+pub fn retry_delay(attempts: i64) -> Duration {
+    let idx = (attempts.max(1) - 1) as usize;
+    Duration::seconds(RETRY_BACKOFF_SECS[idx.min(RETRY_BACKOFF_SECS.len() - 1)])
+}
+
+/// Where a reply goes
+#[derive(Debug, Clone)]
+pub struct OutboundTarget {
+    pub channel_id: i64,
+    pub channel_type: ChannelType,
+    pub chat_id: String,
+    /// Platform message ID to reply to (Telegram only)
+    pub reply_to: Option<String>,
+}
+
+/// Send `chunks` in order via `send`, queueing whatever can't be delivered now.
+pub async fn deliver_or_queue<F, Fut>(db: &Database, target: &OutboundTarget, chunks: Vec<String>, mut send: F)
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let mut remaining = chunks.into_iter();
+
+    // Earlier replies for this chat are still queued — send nothing ahead of them
+    let queued_ahead = db
+        .has_queued_outbound(target.channel_id, &target.chat_id)
+        .unwrap_or(false);
+    if !queued_ahead {
+        for chunk in remaining.by_ref() {
+            if let Err(e) = send(chunk.clone()).await {
+                log::warn!(
+                    "[OUTBOUND] {} send to {} failed, queueing for retry: {}",
+                    target.channel_type, target.chat_id, e
+                );
+                enqueue(db, target, &chunk, Some(&e));
+                break;
+            }
+        }
+    }
+
+    for chunk in remaining {
+        enqueue(db, target, &chunk, None);
+    }
+}
+
+fn enqueue(db: &Database, target: &OutboundTarget, content: &str, failed_with: Option<&str>) {
+    let failed_attempt = failed_with.map(|e| (e, Utc::now() + retry_delay(1)));
+    if let Err(e) = db.enqueue_outbound_message(
+        target.channel_id,
+        target.channel_type.as_str(),
+        &target.chat_id,
+        target.reply_to.as_deref(),
+        content,
+        failed_attempt,
+    ) {
+        log::error!("[OUTBOUND] Failed to persist reply for {} — message lost: {}", target.chat_id, e);
+    }
+}
+
+/// Send one queued message using the channel's stored credentials
+async fn send_queued(db: &Database, msg: &OutboundMessage) -> Result<(), String> {
+    let channel = db
+        .get_channel(msg.channel_id)
+        .map_err(|e| format!("Failed to load channel: {}", e))?
+        .ok_or_else(|| format!("Channel {} no longer exists", msg.channel_id))?;
+
+    match ChannelType::from_str(&msg.channel_type) {
+        Some(ChannelType::Telegram) => {
+            use teloxide::prelude::*;
+            use teloxide::types::MessageId;
+
+            let chat_id: i64 = msg.chat_id.parse().map_err(|_| format!("Invalid Telegram chat id {}", msg.chat_id))?;
+            let bot = Bot::new(&channel.bot_token);
+            let reply_to = msg.reply_to.as_ref().and_then(|r| r.parse::<i32>().ok()).map(MessageId);
+            // Queued content was rendered to Telegram HTML before queueing
+            crate::channels::telegram::send_html_message(&bot, ChatId(chat_id), reply_to, &msg.content, None).await
+        }
+        Some(ChannelType::Discord) => {
+            let id: u64 = msg.chat_id.parse().map_err(|_| format!("Invalid Discord channel id {}", msg.chat_id))?;
+            let http = Arc::new(serenity::http::Http::new(&channel.bot_token));
+            serenity::all::ChannelId::new(id)
+                .say(&http, &msg.content)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+        _ => Err(format!("Queued delivery is not supported for {} channels", msg.channel_type)),
+    }
+}
+
+/// Send a standalone message with optional buttons right now, without queueing.
+/// For notifications that must reach the chat now or fail visibly (e.g. approval cards).
+pub async fn send_direct(
+    db: &Database,
+    channel_id: i64,
+    chat_id: &str,
+    markdown: &str,
+    buttons: &[ActionButton],
+) -> Result<(), String> {
+    let channel = db
+        .get_channel(channel_id)
+        .map_err(|e| format!("Failed to load channel: {}", e))?
+        .ok_or_else(|| format!("Channel {} no longer exists", channel_id))?;
+
+    match ChannelType::from_str(&channel.channel_type) {
+        Some(ChannelType::Telegram) => {
+            use teloxide::prelude::*;
+
+            let chat: i64 = chat_id.parse().map_err(|_| format!("Invalid Telegram chat id {}", chat_id))?;
+            let bot = Bot::new(&channel.bot_token);
+            let chunks = format::render_chunks(markdown, RenderTarget::Telegram);
+            let keyboard = crate::channels::telegram::inline_keyboard(db, channel_id, ChatId(chat), buttons);
+            let last = chunks.len();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let keyboard = if i + 1 == last { keyboard.clone() } else { None };
+                crate::channels::telegram::send_html_message(&bot, ChatId(chat), None, chunk, keyboard).await?;
+            }
+            Ok(())
+        }
+        Some(ChannelType::Discord) => {
+            use serenity::all::{ChannelId, CreateMessage};
+
+            let id: u64 = chat_id.parse().map_err(|_| format!("Invalid Discord channel id {}", chat_id))?;
+            let discord_channel = ChannelId::new(id);
+            let http = Arc::new(serenity::http::Http::new(&channel.bot_token));
+            let chunks = format::render_chunks(markdown, RenderTarget::Discord);
+            let components = crate::channels::discord::button_rows(db, channel_id, discord_channel, buttons);
+            let last = chunks.len();
+            for (i, chunk) in chunks.into_iter().enumerate() {
+                let mut builder = CreateMessage::new().content(chunk);
+                if i + 1 == last && !components.is_empty() {
+                    builder = builder.components(components.clone());
+                }
+                discord_channel
+                    .send_message(&http, builder)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        }
+        _ => Err(format!("Direct messages are not supported for {} channels", channel.channel_type)),
+    }
+}
+
+/// Send an unsolicited message (cron result, alert, notification) if the
+/// channel's proactive settings allow it right now; `kind` labels it in the logs.
+/// Refusals come back as errors, like a failed send.
+pub async fn send_proactive(
+    db: &Database,
+    channel_id: i64,
+    chat_id: &str,
+    markdown: &str,
+    kind: &str,
+) -> Result<(), String> {
+    let reservation = proactive::admit(db, channel_id, chat_id, kind)?;
+    let sent = send_direct(db, channel_id, chat_id, markdown, &[]).await;
+    if sent.is_err() {
+        proactive::release(db, reservation);
+    }
+    sent
+}
+
+/// Deliver every due queue head once. Returns (sent, failed).
+pub async fn run_delivery_pass(db: &Database) -> (usize, usize) {
+    let batch = match db.claim_due_outbound_messages(DELIVERY_BATCH_SIZE) {
+        Ok(batch) => batch,
+        Err(e) => {
+            log::error!("[OUTBOUND] Failed to load queue: {}", e);
+            return (0, 0);
+        }
+    };
+
+    let (mut sent, mut failed) = (0, 0);
+    for msg in batch {
+        let outcome = send_queued(db, &msg).await;
+        let recorded = match outcome {
+            Ok(()) => {
+                sent += 1;
+                db.mark_outbound_sent(msg.id)
+            }
+            Err(e) => {
+                failed += 1;
+                let attempts = msg.attempts + 1;
+                let retry_at = (attempts < MAX_DELIVERY_ATTEMPTS).then(|| Utc::now() + retry_delay(attempts));
+                if retry_at.is_none() {
+                    log::error!(
+                        "[OUTBOUND] Message {} to {} {} dead-lettered after {} attempts: {}",
+                        msg.id, msg.channel_type, msg.chat_id, attempts, e
+                    );
+                }
+                db.mark_outbound_failed(msg.id, &e, retry_at)
+            }
+        };
+        if let Err(e) = recorded {
+            log::error!("[OUTBOUND] Failed to update message {}: {}", msg.id, e);
+        }
+    }
+    (sent, failed)
+}
+
+/// Spawn the background delivery worker (polls every `interval_secs`).
+pub fn spawn_delivery_worker(db: Arc<Database>, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            let (sent, failed) = run_delivery_pass(&db).await;
+            if sent + failed > 0 {
+                log::info!("[OUTBOUND] Delivery pass: {} sent, {} failed", sent, failed);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::tables::outbound::OUTBOUND_PENDING;
+
+    fn target() -> OutboundTarget {
+        OutboundTarget {
+            channel_id: 1,
+            channel_type: ChannelType::Telegram,
+            chat_id: "42".to_string(),
+            reply_to: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queues_from_first_failure_onward() {
+        let db = Database::new(":memory:").unwrap();
+        let chunks = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let mut delivered = Vec::new();
+        deliver_or_queue(&db, &target(), chunks, |chunk| {
+            let ok = chunk == "a";
+            if ok {
+                delivered.push(chunk);
+            }
+            async move { if ok { Ok(()) } else { Err("network down".to_string()) } }
+        })
+        .await;
+        assert_eq!(delivered, vec!["a".to_string()]);
+
+        let queued = db.list_outbound_messages(Some(OUTBOUND_PENDING), 10).unwrap();
+        let contents: Vec<&str> = queued.iter().rev().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["b", "c"]);
+        assert_eq!(queued.last().unwrap().attempts, 1);
+
+        // A later reply must queue behind the backlog instead of jumping ahead
+        let mut sent_later = false;
+        deliver_or_queue(&db, &target(), vec!["d".to_string()], |_| {
+            sent_later = true;
+            async { Ok(()) }
+        })
+        .await;
+        assert!(!sent_later);
+        assert_eq!(db.outbound_stats().unwrap().pending, 3);
+    }
+
+    #[test]
+    fn test_retry_delay_caps() {
+        assert_eq!(retry_delay(1), Duration::seconds(5));
+        assert_eq!(retry_delay(100), Duration::seconds(3600));
+    }
+}
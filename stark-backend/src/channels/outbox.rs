@@ -0,0 +1,169 @@
+//! Review-before-send outbox
+//!
+//! Where review is enabled (the Twitter channel's `twitter_review_before_send`
+//! setting, Gmail's `review_before_send`), outgoing agent text is handed to
+//! [`hold`] instead of the platform. The draft waits in `outbox_drafts` until
+//! the owner approves it through `/api/outbox` — optionally after editing —
+//! and [`approve`] releases it: tweets go to the Twitter channel's outgoing
+//! queue, email replies are sent straight away. Undecided drafts expire after
+//! `STARK_OUTBOX_DRAFT_TTL_HOURS`.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+
+use crate::channels::format::{self, RenderTarget};
+use crate::channels::types::ChannelType;
+use crate::db::tables::outbox_drafts::{DraftTarget, OutboxDraft};
+use crate::db::tables::twitter_outgoing::NewQueuedTweet;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::integrations::gmail::GmailClient;
+use crate::models::ChannelSettingKey;
+
+/// Whether tweets must be held for review.
+/// For a specific channel (mention replies) that channel's setting decides;
+/// without one (`twitter_post`, which posts from the shared account) any
+/// Twitter channel with review enabled holds the tweet.
+pub fn twitter_review_enabled(db: &Database, channel_id: Option<i64>) -> bool {
+    let enabled = |id: i64| {
+        db.get_channel_setting(id, ChannelSettingKey::TwitterReviewBeforeSend.as_ref())
+            .ok()
+            .flatten()
+            .is_some_and(|v| v == "true")
+    };
+    match channel_id {
+        Some(id) => enabled(id),
+        None => db
+            .list_channels()
+            .unwrap_or_default()
+            .iter()
+            .filter(|c| c.channel_type == ChannelType::Twitter.as_str())
+            .any(|c| enabled(c.id)),
+    }
+}
+
+/// Store `content` as a pending draft instead of sending it
+pub fn hold(
+    db: &Database,
+    broadcaster: Option<&EventBroadcaster>,
+    target: DraftTarget,
+    content: &str,
+    session_id: Option<i64>,
+) -> Result<OutboxDraft, String> {
+    let expires_at = Utc::now() + Duration::hours(crate::config::outbox_draft_ttl_hours());
+    let draft = db
+        .create_outbox_draft(&target, content, session_id, expires_at)
+        .map_err(|e| format!("Failed to store draft: {}", e))?;
+    log::info!(
+        "[OUTBOX] Held {} draft {} for review (expires {})",
+        draft.platform, draft.id, draft.expires_at
+    );
+    if let Some(broadcaster) = broadcaster {
+        broadcaster.broadcast(GatewayEvent::custom(
+            "outbox.draft_created",
+            serde_json::json!({
+                "id": draft.id,
+                "platform": draft.platform,
+                "content": draft.content,
+                "expires_at": draft.expires_at,
+            }),
+        ));
+    }
+    Ok(draft)
+}
+
+/// Approve a pending draft (replacing its text first when `content` is given)
+/// and release it to the platform. Returns the updated draft.
+pub async fn approve(
+    db: &Database,
+    id: i64,
+    decided_by: &str,
+    content: Option<&str>,
+) -> Result<OutboxDraft, String> {
+    if let Some(content) = content {
+        if content.trim().is_empty() {
+            return Err("Draft content cannot be empty".to_string());
+        }
+        if !db.edit_outbox_draft(id, content).map_err(|e| e.to_string())? {
+            return Err(format!("Draft {} is not pending or has expired", id));
+        }
+    }
+    if !db.decide_outbox_draft(id, true, decided_by).map_err(|e| e.to_string())? {
+        return Err(format!("Draft {} is not pending or has expired", id));
+    }
+    let draft = db
+        .get_outbox_draft(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Draft {} not found", id))?;
+
+    let recorded = match release(db, &draft).await {
+        Ok(delivery_ref) => {
+            log::info!("[OUTBOX] Released {} draft {} ({})", draft.platform, id, delivery_ref);
+            db.mark_outbox_draft_released(id, Some(&delivery_ref))
+        }
+        Err(e) => {
+            log::error!("[OUTBOX] Failed to send approved draft {}: {}", id, e);
+            db.mark_outbox_draft_failed(id, &e)
+        }
+    };
+    recorded.map_err(|e| e.to_string())?;
+    db.get_outbox_draft(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Draft {} not found", id))
+}
+
+/// Hand an approved draft to its platform. Returns a delivery reference.
+async fn release(db: &Database, draft: &OutboxDraft) -> Result<String, String> {
+    match &draft.target {
+        DraftTarget::Twitter { channel_id, reply_to_id, quote_tweet_id, not_before } => {
+            // The running Twitter channel threads and paces queued tweets
+            let queued = NewQueuedTweet {
+                channel_id: *channel_id,
+                text: &draft.content,
+                reply_to_id: reply_to_id.as_deref(),
+                quote_tweet_id: quote_tweet_id.as_deref(),
+                session_id: draft.session_id,
+                not_before: *not_before,
+            };
+            db.enqueue_tweet(&queued)
+                .map(|queue_id| format!("tweet_queue:{}", queue_id))
+                .map_err(|e| format!("Failed to queue tweet: {}", e))
+        }
+        DraftTarget::Gmail { thread_id, to, subject, in_reply_to } => {
+            let config = db
+                .get_gmail_config()
+                .map_err(|e| e.to_string())?
+                .ok_or("Gmail is not configured")?;
+            let client = GmailClient::new(config.access_token.clone(), config.refresh_token.clone());
+            client
+                .send_reply(
+                    "me",
+                    thread_id,
+                    to,
+                    subject,
+                    &format::render(&draft.content, RenderTarget::Plain),
+                    Some(&format::render(&draft.content, RenderTarget::Email)),
+                    in_reply_to.as_deref(),
+                )
+                .await
+                .map(|message| format!("gmail:{}", message.id))
+        }
+    }
+}
+
+/// Spawn the background worker that expires overdue drafts (checks every `interval_secs`).
+pub fn spawn_expiry_worker(db: Arc<Database>, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            match db.expire_outbox_drafts(Utc::now()) {
+                Ok(0) => {}
+                Ok(n) => log::info!("[OUTBOX] {} draft(s) expired without approval", n),
+                Err(e) => log::error!("[OUTBOX] Failed to expire drafts: {}", e),
+            }
+        }
+    })
+}
@@ -0,0 +1,197 @@
+//! Permissions for unsolicited messages
+//!
+//! Cron results, alerts, heartbeat check-ins and notifications are posted to
+//! a channel without anyone asking. Each channel decides whether it accepts
+//! them at all, sets quiet hours (server-local time) and caps how many arrive
+//! per day. Every proactive send goes through [`admit`] first — see
+//! `outbound::send_proactive` and the `agent_send` tool — so no integration can
+//! bypass the limits.
+
+use chrono::{DateTime, Local, NaiveTime, TimeZone, Utc};
+
+use crate::db::Database;
+use crate::models::ChannelSettingKey;
+
+/// A daily time window, possibly wrapping past midnight (e.g. 22:00-07:00)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Parse "HH:MM-HH:MM"; empty means no quiet hours
+    pub fn parse(value: &str) -> Result<Option<Self>, String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        let parse_time = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                .map_err(|_| format!("'{}' is not a time (expected HH:MM)", t.trim()))
+        };
+        let (start, end) = value
+            .split_once('-')
+            .ok_or_else(|| format!("'{}' is not a time range (expected e.g. 22:00-07:00)", value))?;
+        let (start, end) = (parse_time(start)?, parse_time(end)?);
+        if start == end {
+            return Err("Quiet hours must start and end at different times".to_string());
+        }
+        Ok(Some(Self { start, end }))
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Why a proactive message was not sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    Disabled,
+    QuietHours(QuietHours),
+    DailyLimit(u32),
+}
+
+impl std::fmt::Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "proactive messages are disabled for this channel"),
+            Self::QuietHours(q) => write!(
+                f,
+                "quiet hours ({}-{})",
+                q.start.format("%H:%M"),
+                q.end.format("%H:%M")
+            ),
+            Self::DailyLimit(limit) => write!(f, "daily limit of {} proactive messages reached", limit),
+        }
+    }
+}
+
+/// A channel's proactive messaging settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProactivePolicy {
+    pub allowed: bool,
+    pub quiet_hours: Option<QuietHours>,
+    /// None = unlimited
+    pub daily_limit: Option<u32>,
+}
+
+impl Default for ProactivePolicy {
+    /// Allowed at any time, without a limit (the behaviour before these settings existed)
+    fn default() -> Self {
+        Self { allowed: true, quiet_hours: None, daily_limit: None }
+    }
+}
+
+impl ProactivePolicy {
+    /// Read the channel's settings; unset or invalid settings keep the defaults
+    pub fn load(db: &Database, channel_id: i64) -> Self {
+        let setting = |key: ChannelSettingKey| db.get_channel_setting(channel_id, key.as_ref()).ok().flatten();
+
+        let quiet_hours = setting(ChannelSettingKey::ProactiveQuietHours).and_then(|v| {
+            QuietHours::parse(&v).unwrap_or_else(|e| {
+                log::warn!("[PROACTIVE] Channel {}: ignoring quiet hours '{}': {}", channel_id, v, e);
+                None
+            })
+        });
+        Self {
+            allowed: setting(ChannelSettingKey::ProactiveMessages).is_none_or(|v| v.trim() != "false"),
+            quiet_hours,
+            daily_limit: setting(ChannelSettingKey::ProactiveDailyLimit)
+                .and_then(|v| v.trim().parse::<u32>().ok())
+                .filter(|limit| *limit > 0),
+        }
+    }
+
+    /// Checks that don't need the day's count
+    pub fn check(&self, now: NaiveTime) -> Result<(), Refusal> {
+        if !self.allowed {
+            return Err(Refusal::Disabled);
+        }
+        match self.quiet_hours {
+            Some(quiet) if quiet.contains(now) => Err(Refusal::QuietHours(quiet)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Start of the current server-local day
+fn local_midnight(now: DateTime<Local>) -> DateTime<Utc> {
+    let midnight = now.date_naive().and_time(NaiveTime::MIN);
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| now.with_timezone(&Utc))
+}
+
+/// Ask to send one proactive message of `kind` (e.g. "cron", "alert") to a
+/// chat. On success the message already counts toward today's limit; call
+/// [`release`] with the returned id if it then fails to send.
+pub fn admit(db: &Database, channel_id: i64, chat_id: &str, kind: &str) -> Result<i64, String> {
+    let policy = ProactivePolicy::load(db, channel_id);
+    let now = Local::now();
+    let refused = |refusal: Refusal| {
+        log::info!("[PROACTIVE] Suppressed {} message to channel {} ({}): {}", kind, channel_id, chat_id, refusal);
+        format!("Not sent: {}", refusal)
+    };
+
+    policy.check(now.time()).map_err(&refused)?;
+    match db.reserve_proactive_message(channel_id, chat_id, kind, local_midnight(now), policy.daily_limit) {
+        Ok(Some(id)) => Ok(id),
+        Ok(None) => Err(refused(Refusal::DailyLimit(policy.daily_limit.unwrap_or_default()))),
+        Err(e) => Err(format!("Failed to check proactive message limit: {}", e)),
+    }
+}
+
+/// Return a reservation from [`admit`] after a failed send
+pub fn release(db: &Database, id: i64) {
+    if let Err(e) = db.release_proactive_message(id) {
+        log::warn!("[PROACTIVE] Failed to release reservation {}: {}", id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hh: u32, mm: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hh, mm, 0).unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_and_daily_limit() {
+        let overnight = QuietHours::parse("22:00-07:00").unwrap().unwrap();
+        assert!(overnight.contains(at(23, 30)));
+        assert!(overnight.contains(at(6, 59)));
+        assert!(!overnight.contains(at(7, 0)));
+        assert!(!overnight.contains(at(12, 0)));
+        let lunch = QuietHours::parse(" 12:00 - 13:00 ").unwrap().unwrap();
+        assert!(lunch.contains(at(12, 30)) && !lunch.contains(at(13, 0)));
+        assert_eq!(QuietHours::parse("").unwrap(), None);
+        assert!(QuietHours::parse("10pm-7am").is_err());
+        assert!(QuietHours::parse("08:00-08:00").is_err());
+
+        let policy = ProactivePolicy { quiet_hours: Some(overnight), ..ProactivePolicy::default() };
+        assert_eq!(policy.check(at(23, 0)), Err(Refusal::QuietHours(overnight)));
+        assert!(policy.check(at(9, 0)).is_ok());
+        let off = ProactivePolicy { allowed: false, ..ProactivePolicy::default() };
+        assert_eq!(off.check(at(9, 0)), Err(Refusal::Disabled));
+
+        let db = Database::new(":memory:").unwrap();
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let first = db.reserve_proactive_message(1, "42", "cron", since, Some(2)).unwrap();
+        assert!(first.is_some());
+        assert!(db.reserve_proactive_message(1, "42", "alert", since, Some(2)).unwrap().is_some());
+        assert!(db.reserve_proactive_message(1, "43", "cron", since, Some(2)).unwrap().is_none());
+        assert!(db.reserve_proactive_message(2, "42", "cron", since, Some(2)).unwrap().is_some());
+        db.release_proactive_message(first.unwrap()).unwrap();
+        assert!(db.reserve_proactive_message(1, "42", "cron", since, Some(2)).unwrap().is_some());
+        assert!(db.reserve_proactive_message(1, "42", "cron", since, None).unwrap().is_some());
+    }
+}
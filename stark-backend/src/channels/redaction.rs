@@ -0,0 +1,272 @@
+//! Identity-aware redaction of replies in shared chats
+//!
+//! While the agent works, [`observe`] notes the private data its tools turned
+//! up and who that data belongs to:
+//!
+//! - **wallet balances** (balance tools and presets) and **email** (gmail)
+//!   belong to the operator — the admins configured for the channel
+//! - **memories** read or searched belong to the identity they are about
+//!
+//! Before a reply leaves for a group chat or public thread, [`apply`] blanks
+//! out every value the requesting identity doesn't own: amounts, email
+//! addresses and sentences quoted from an email or memory. Direct messages
+//! are never touched, and each channel can turn redaction off.
+
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::channels::types::{ChannelType, NormalizedMessage};
+use crate::db::Database;
+use crate::models::ChannelSettingKey;
+use crate::tools::ToolResult;
+
+/// What replaces a redacted value
+pub const REDACTED: &str = "[redacted]";
+
+/// Memories in the shared safe-mode sandbox, visible to every safe-mode user
+const SAFE_MODE_IDENTITY: &str = "safemode";
+
+/// Quoted sentences shorter than this (in words) are too generic to redact
+const MIN_PHRASE_WORDS: usize = 4;
+
+static AMOUNT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\d[\d,]*\.\d+|\d{1,3}(?:,\d{3})+|\d{3,}").expect("amount regex is valid"));
+static EMAIL_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").expect("email regex is valid"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensitiveKind {
+    WalletBalance,
+    Email,
+    Memory,
+}
+
+/// Who may see a piece of data in a shared chat
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataOwner {
+    /// The bot's operator (wallet, inbox)
+    Operator,
+    /// The identity a memory is about
+    Identity(String),
+}
+
+/// Private values one tool result revealed
+#[derive(Debug, Clone)]
+pub struct SensitiveItem {
+    pub kind: SensitiveKind,
+    pub owner: DataOwner,
+    pub values: Vec<String>,
+}
+
+/// Private data seen during one execution (shared by clones of the tool context)
+#[derive(Debug, Clone, Default)]
+pub struct SensitiveLedger(Arc<Mutex<Vec<SensitiveItem>>>);
+
+impl SensitiveLedger {
+    pub fn record(&self, item: SensitiveItem) {
+        if item.values.is_empty() {
+            return;
+        }
+        if let Ok(mut items) = self.0.lock() {
+            items.push(item);
+        }
+    }
+
+    pub fn items(&self) -> Vec<SensitiveItem> {
+        self.0.lock().map(|items| items.clone()).unwrap_or_default()
+    }
+}
+
+/// Values worth hiding in `text`: amounts and email addresses, plus whole
+/// sentences when `phrases` is set (for email bodies and memories)
+fn sensitive_values(text: &str, phrases: bool) -> Vec<String> {
+    let mut values: Vec<String> = AMOUNT_RE
+        .find_iter(text)
+        .chain(EMAIL_RE.find_iter(text))
+        .map(|m| m.as_str().to_string())
+        .collect();
+    if phrases {
+        values.extend(
+            text.split(['.', '!', '?', '\n'])
+                .map(|s| s.trim().trim_start_matches(['-', '*', '#', '>']).trim())
+                .filter(|s| s.split_whitespace().count() >= MIN_PHRASE_WORDS && s.len() <= 300)
+                .map(str::to_string),
+        );
+    }
+    values.sort();
+    values.dedup();
+    values
+}
+
+/// Memory IDs a memory tool reported in its metadata
+fn memory_ids(result: &ToolResult) -> Vec<i64> {
+    let Some(metadata) = result.metadata.as_ref() else {
+        return Vec::new();
+    };
+    match metadata.get("memory_ids").and_then(|v| v.as_array()) {
+        Some(ids) => ids.iter().filter_map(|v| v.as_i64()).collect(),
+        None => metadata.get("id").and_then(|v| v.as_i64()).into_iter().collect(),
+    }
+}
+
+/// Note the private data in a tool result
+pub fn observe(db: &Database, ledger: &SensitiveLedger, tool_name: &str, arguments: &Value, result: &ToolResult) {
+    if !result.success {
+        return;
+    }
+    let preset = arguments.get("preset").and_then(|v| v.as_str()).unwrap_or("");
+
+    if tool_name.contains("balance") || preset.contains("balance") {
+        ledger.record(SensitiveItem {
+            kind: SensitiveKind::WalletBalance,
+            owner: DataOwner::Operator,
+            values: sensitive_values(&result.content, false),
+        });
+    } else if tool_name == "gmail" {
+        ledger.record(SensitiveItem {
+            kind: SensitiveKind::Email,
+            owner: DataOwner::Operator,
+            values: sensitive_values(&result.content, true),
+        });
+    } else if tool_name.starts_with("memory_") {
+        for id in memory_ids(result) {
+            let Ok(Some(memory)) = db.get_memory(id) else {
+                continue;
+            };
+            let Some(identity_id) = memory.identity_id.filter(|i| i != SAFE_MODE_IDENTITY) else {
+                continue;
+            };
+            ledger.record(SensitiveItem {
+                kind: SensitiveKind::Memory,
+                owner: DataOwner::Identity(identity_id),
+                values: sensitive_values(&memory.content, true),
+            });
+        }
+    }
+}
+
+/// Whether the sender is one of the channel's configured admins (the operator).
+/// Channels without an admin list have no verified operator.
+fn is_operator(db: &Database, message: &NormalizedMessage) -> bool {
+    let key = match ChannelType::from_str(&message.channel_type) {
+        Some(ChannelType::Discord) => ChannelSettingKey::DiscordAdminUserIds,
+        Some(ChannelType::Telegram) => ChannelSettingKey::TelegramAdminUserId,
+        Some(ChannelType::Slack) => ChannelSettingKey::SlackAdminUserIds,
+        Some(ChannelType::Twitter) => ChannelSettingKey::TwitterAdminXAccount,
+        Some(ChannelType::Farcaster) => ChannelSettingKey::FarcasterAdminFid,
+        _ => return false,
+    };
+    db.get_channel_setting(message.channel_id, key.as_ref())
+        .ok()
+        .flatten()
+        .is_some_and(|ids| ids.split(',').any(|id| id.trim() == message.user_id))
+}
+
+/// Replace each value (longest first, case-insensitive, whole words) with [`REDACTED`].
+/// Returns the text and how many spans were replaced.
+pub fn redact(text: &str, values: &[String]) -> (String, usize) {
+    let mut values: Vec<&String> = values.iter().filter(|v| v.trim().len() >= 3).collect();
+    values.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    values.dedup();
+
+    let mut out = text.to_string();
+    let mut count = 0;
+    for value in values {
+        let escaped = regex::escape(value);
+        let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let pattern = format!(
+            "(?i){}{}{}",
+            if word(value.chars().next()) { r"\b" } else { "" },
+            escaped,
+            if word(value.chars().last()) { r"\b" } else { "" },
+        );
+        let Ok(re) = Regex::new(&pattern) else {
+            continue;
+        };
+        let found = re.find_iter(&out).count();
+        if found > 0 {
+            count += found;
+            out = re.replace_all(&out, REDACTED).into_owned();
+        }
+    }
+    (out, count)
+}
+
+/// Redact a reply before it is sent to the chat `message` came from
+pub fn apply(
+    db: &Database,
+    ledger: &SensitiveLedger,
+    message: &NormalizedMessage,
+    requester_identity: &str,
+    response: String,
+) -> String {
+    if !message.shared_chat {
+        return response;
+    }
+    let enabled = db
+        .get_channel_setting(message.channel_id, ChannelSettingKey::ResponseRedaction.as_ref())
+        .ok()
+        .flatten()
+        .is_none_or(|v| v.trim() != "false");
+    if !enabled {
+        return response;
+    }
+
+    let items = ledger.items();
+    if items.is_empty() {
+        return response;
+    }
+    let operator = items.iter().any(|i| i.owner == DataOwner::Operator) && is_operator(db, message);
+    let hidden: Vec<SensitiveItem> = items
+        .into_iter()
+        .filter(|item| match &item.owner {
+            DataOwner::Operator => !operator,
+            DataOwner::Identity(id) => id != requester_identity,
+        })
+        .collect();
+    let mut kinds: Vec<SensitiveKind> = hidden.iter().map(|item| item.kind).collect();
+    kinds.dedup();
+    let values: Vec<String> = hidden.into_iter().flat_map(|item| item.values).collect();
+
+    let (redacted, count) = redact(&response, &values);
+    if count > 0 {
+        log::info!(
+            "[REDACTION] Redacted {} value(s) ({:?}) from the reply to {} in shared chat {} (channel {})",
+            count, kinds, message.user_name, message.chat_id, message.channel_id
+        );
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_values_the_requester_does_not_own() {
+        let balance = sensitive_values("Wallet balance: 1,250.75 USDC and 0.42 ETH on chain 8453", false);
+        assert!(balance.contains(&"1,250.75".to_string()) && balance.contains(&"0.42".to_string()));
+
+        let memory = sensitive_values("Alice is moving to Lisbon in March. Email: alice@example.com", true);
+        assert!(memory.contains(&"Alice is moving to Lisbon in March".to_string()));
+        assert!(memory.contains(&"alice@example.com".to_string()));
+
+        let reply = "You hold 1,250.75 USDC. Also, alice is moving to Lisbon in March (ALICE@example.com).";
+        let mut values = balance;
+        values.extend(memory);
+        let (redacted, count) = redact(reply, &values);
+        assert_eq!(count, 3);
+        assert_eq!(redacted, "You hold [redacted] USDC. Also, [redacted] ([redacted]).");
+
+        // Numbers match whole: 250 is not part of 2500
+        let (untouched, count) = redact("2500 apples", &["250".to_string()]);
+        assert_eq!((untouched.as_str(), count), ("2500 apples", 0));
+
+        let ledger = SensitiveLedger::default();
+        ledger.record(SensitiveItem { kind: SensitiveKind::Email, owner: DataOwner::Operator, values: vec![] });
+        assert!(ledger.clone().items().is_empty());
+    }
+}
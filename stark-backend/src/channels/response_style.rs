@@ -0,0 +1,115 @@
+//! Response-style presets
+//!
+//! A style (concise, verbose, technical) adjusts how the agent answers: extra
+//! system prompt directives, a cap on output tokens, and formatting
+//! preferences. It is stored on the session (set with `/style` or
+//! `PUT /api/sessions/{id}/style`); sessions without one use the channel's
+//! `response_style` setting, and with neither the agent answers as usual.
+
+use crate::db::Database;
+use crate::models::{ChannelSettingKey, ChatSession};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseStyle {
+    /// Short, direct answers
+    Concise,
+    /// Thorough answers with explanation and context
+    Verbose,
+    /// Precise answers for a technical reader: exact values, code, no hand-holding
+    Technical,
+}
+
+impl ResponseStyle {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "concise" | "brief" | "short" => Some(ResponseStyle::Concise),
+            "verbose" | "detailed" | "long" => Some(ResponseStyle::Verbose),
+            "technical" | "tech" => Some(ResponseStyle::Technical),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResponseStyle::Concise => "concise",
+            ResponseStyle::Verbose => "verbose",
+            ResponseStyle::Technical => "technical",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ResponseStyle::Concise => "Short, direct answers",
+            ResponseStyle::Verbose => "Thorough answers with explanation",
+            ResponseStyle::Technical => "Precise, detail-dense answers for technical readers",
+        }
+    }
+
+    /// Cap on the agent's output tokens; the configured limit applies if lower
+    pub fn max_output_tokens(&self) -> i32 {
+        match self {
+            ResponseStyle::Concise => 2_048,
+            ResponseStyle::Verbose => 16_384,
+            ResponseStyle::Technical => 8_192,
+        }
+    }
+
+    /// Directives appended to the system prompt
+    pub fn directives(&self) -> &'static str {
+        match self {
+            ResponseStyle::Concise => {
+                "- Answer in as few words as the question allows; lead with the answer.\n\
+                 - No preamble, recaps or closing offers of further help.\n\
+                 - Prefer a sentence or a short list over headings and long paragraphs.\n"
+            }
+            ResponseStyle::Verbose => {
+                "- Explain your reasoning and the context behind the answer.\n\
+                 - Cover caveats, alternatives and next steps where they matter.\n\
+                 - Use headings and lists to keep longer answers easy to scan.\n"
+            }
+            ResponseStyle::Technical => {
+                "- Assume a technical reader: skip basics and use precise terminology.\n\
+                 - Give exact values (addresses, amounts, hashes, versions) rather than approximations.\n\
+                 - Put commands, code and data in fenced code blocks; prefer tables for comparisons.\n"
+            }
+        }
+    }
+
+    /// System prompt section for this style
+    pub fn prompt_section(&self) -> String {
+        format!("## Response Style: {}\n{}\n", self.as_str(), self.directives())
+    }
+}
+
+/// The channel's default style, from its `response_style` setting
+pub fn channel_style(db: &Database, channel_id: i64) -> Option<ResponseStyle> {
+    db.get_channel_setting(channel_id, ChannelSettingKey::ResponseStyle.as_ref())
+        .ok()
+        .flatten()
+        .and_then(|s| ResponseStyle::from_str(&s))
+}
+
+/// The style a session answers in: its own, else its channel's
+pub fn resolve(db: &Database, session: &ChatSession) -> Option<ResponseStyle> {
+    session
+        .response_style
+        .as_deref()
+        .and_then(ResponseStyle::from_str)
+        .or_else(|| channel_style(db, session.channel_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_parsing() {
+        for style in [ResponseStyle::Concise, ResponseStyle::Verbose, ResponseStyle::Technical] {
+            assert_eq!(ResponseStyle::from_str(style.as_str()), Some(style));
+        }
+        assert_eq!(ResponseStyle::from_str(" Brief "), Some(ResponseStyle::Concise));
+        assert_eq!(ResponseStyle::from_str("default"), None);
+        assert!(ResponseStyle::Concise.max_output_tokens() < ResponseStyle::Verbose.max_output_tokens());
+        assert!(ResponseStyle::Technical.prompt_section().starts_with("## Response Style: technical"));
+    }
+}
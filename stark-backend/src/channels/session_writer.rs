@@ -5,7 +5,7 @@
 //! off the agentic loop's hot path.
 
 use crate::db::Database;
-use crate::models::session_message::MessageRole;
+use crate::models::session_message::{MessageRole, ToolCallRecord};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
@@ -14,7 +14,7 @@ struct PendingMessage {
     session_id: i64,
     role: MessageRole,
     content: String,
-    user_name: Option<String>,
+    record: ToolCallRecord,
 }
 
 /// Non-blocking writer that queues session messages for async DB persistence.
@@ -31,19 +31,15 @@ impl SessionMessageWriter {
         Self { tx }
     }
 
-    /// Queue a message for async DB write. Returns immediately.
-    pub fn send(
-        &self,
-        session_id: i64,
-        role: MessageRole,
-        content: String,
-        user_name: Option<&str>,
-    ) {
+    /// Queue a ToolCall / ToolResult message with its structured record for
+    /// async DB write. Returns immediately. The record lets clients that
+    /// reconnect mid-run tell which calls are still running.
+    pub fn send(&self, session_id: i64, role: MessageRole, content: String, record: ToolCallRecord) {
         if let Err(e) = self.tx.send(PendingMessage {
             session_id,
             role,
             content,
-            user_name: user_name.map(|s| s.to_string()),
+            record,
         }) {
             log::error!(
                 "[SESSION_WRITER] Failed to queue {:?} message for session {} — background drain task may have crashed: {}",
@@ -66,24 +62,16 @@ impl SessionMessageWriter {
             }
 
             // Write the batch in a single transaction
-            let entries: Vec<(i64, MessageRole, String, Option<String>, Option<String>)> = batch
+            let entries: Vec<_> = batch
                 .drain(..)
-                .map(|m| (m.session_id, m.role, m.content, None, m.user_name))
+                .map(|m| (m.session_id, m.role, m.content, None, Some(m.record.tool_name.clone()), Some(m.record)))
                 .collect();
 
             if let Err(e) = db.add_session_messages_batch(&entries) {
                 log::error!("[SESSION_WRITER] Failed to batch-write {} messages: {}", entries.len(), e);
                 // Fall back to individual writes
-                for (session_id, role, content, _, user_name) in entries {
-                    if let Err(e) = db.add_session_message(
-                        session_id,
-                        role,
-                        &content,
-                        None,
-                        user_name.as_deref(),
-                        None,
-                        None,
-                    ) {
+                for entry in &entries {
+                    if let Err(e) = db.add_session_messages_batch(std::slice::from_ref(entry)) {
                         log::error!("[SESSION_WRITER] Individual write also failed: {}", e);
                     }
                 }
@@ -1,4 +1,6 @@
 use crate::channels::dispatcher::MessageDispatcher;
+use crate::channels::format::{self, RenderTarget};
+use crate::channels::group_gate::{Addressing, GateDecision, GroupGate};
 use crate::channels::safe_mode_rate_limiter::SafeModeChannelRateLimiter;
 use crate::channels::types::{ChannelType, NormalizedMessage};
 use crate::channels::util;
@@ -379,6 +381,36 @@ async fn handle_shortcircuit_command(
 // Core message processing
 // ---------------------------------------------------------------------------
 
+/// Keep a channel message the bot wasn't summoned for as session context
+async fn remember_slack_message(
+    client: Arc<SlackHyperClient>,
+    state: SlackAppState,
+    slack_channel: SlackChannelId,
+    user_id: String,
+    text: String,
+    message_ts: SlackTs,
+) {
+    let user_name = resolve_user_name(&client, &state.bot_token, &user_id).await;
+    state.dispatcher.remember(&NormalizedMessage {
+        channel_id: state.channel_id,
+        channel_type: ChannelType::Slack.to_string(),
+        chat_id: slack_channel.to_string(),
+        chat_name: None,
+        user_id,
+        user_name,
+        text,
+        message_id: Some(message_ts.to_string()),
+        session_mode: None,
+        selected_network: None,
+        force_safe_mode: false,
+        shared_chat: true,
+        platform_role_ids: vec![],
+        attachments: vec![],
+        chat_context: None,
+        action: None,
+    });
+}
+
 async fn process_slack_message(
     client: Arc<SlackHyperClient>,
     state: SlackAppState,
@@ -501,8 +533,11 @@ async fn process_slack_message(
         session_mode: None,
         selected_network: None,
         force_safe_mode,
+        shared_chat: !slack_channel.0.starts_with('D'),
         platform_role_ids: vec![],
+        attachments: vec![],
         chat_context: None,
+        action: None,
     };
 
     // Subscribe to events for real-time tool call forwarding
@@ -561,11 +596,7 @@ async fn process_slack_message(
                         .get("duration_ms")
                         .and_then(|v| v.as_i64())
                         .unwrap_or(0);
-                    let content = event
-                        .data
-                        .get("content")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("");
+                    let content = crate::tools::payload::event_display(&event.data, RenderTarget::Plain);
 
                     if tool_name == "say_to_user" {
                         None
@@ -574,7 +605,7 @@ async fn process_slack_message(
                             tool_name,
                             success,
                             duration_ms,
-                            content,
+                            &content,
                             verbosity.display_verbosity(),
                         )
                     }
@@ -728,7 +759,7 @@ async fn process_slack_message(
 
     // Send final response in thread
     if result.error.is_none() && !result.response.is_empty() {
-        let chunks = util::split_message(&result.response, 4000);
+        let chunks = format::render_chunks(&result.response, RenderTarget::Slack);
         for chunk in chunks {
             if let Err(e) = send_slack_message(
                 &client,
@@ -825,7 +856,8 @@ fn handle_push_event(
                 ));
             }
 
-            // DM messages: only process direct messages, not channel messages
+            // DMs are always answered; channel messages only per the group triggers
+            // (needs the message.channels / message.groups event subscriptions)
             SlackEventCallbackBody::Message(msg_event) => {
                 // Skip bot messages
                 if msg_event.sender.bot_id.is_some() {
@@ -836,16 +868,12 @@ fn handle_push_event(
                     return Ok(());
                 }
 
-                // Only process DMs (channel_type == "im")
                 let is_dm = msg_event
                     .origin
                     .channel_type
                     .as_ref()
                     .map(|ct| ct.0 == "im")
                     .unwrap_or(false);
-                if !is_dm {
-                    return Ok(());
-                }
 
                 // Skip messages from the bot itself
                 let sender_id = msg_event
@@ -874,8 +902,24 @@ fn handle_push_event(
                 let message_ts = msg_event.origin.ts;
                 let thread_ts = msg_event.origin.thread_ts;
 
+                if !is_dm {
+                    // Mentions also arrive as AppMention events and are answered there
+                    if text.contains(&format!("<@{}>", state.bot_user_id)) {
+                        return Ok(());
+                    }
+                    match GroupGate::load(&state.db, state.channel_id).decide(&text, Addressing::default()) {
+                        GateDecision::Respond => {}
+                        GateDecision::Remember => {
+                            tokio::spawn(remember_slack_message(client, state, slack_channel, sender_id, text, message_ts));
+                            return Ok(());
+                        }
+                        GateDecision::Ignore => return Ok(()),
+                    }
+                }
+
                 log::info!(
-                    "Slack: DM from {} in {}: {}",
+                    "Slack: {} from {} in {}: {}",
+                    if is_dm { "DM" } else { "Channel message" },
                     sender_id,
                     slack_channel,
                     if text.len() > 50 {
@@ -1,6 +1,11 @@
+use crate::channels::actions;
 use crate::channels::dispatcher::MessageDispatcher;
-use crate::channels::types::{ChannelType, NormalizedMessage};
+use crate::channels::format::{self, RenderTarget};
+use crate::channels::group_gate::{Addressing, GateDecision, GroupGate};
+use crate::channels::outbound::{self, OutboundTarget};
+use crate::channels::types::{ActionButton, ChannelType, NormalizedMessage};
 use crate::channels::util;
+use crate::db::tables::artifacts::Artifact;
 use crate::db::Database;
 use crate::discord_hooks::db as user_db;
 use crate::gateway::events::EventBroadcaster;
@@ -11,7 +16,7 @@ use rand::seq::SliceRandom;
 use std::sync::Arc;
 use teloxide::prelude::*;
 use teloxide::requests::Requester;
-use teloxide::types::MessageId;
+use teloxide::types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, InputFile, MessageId, ParseMode};
 use tokio::sync::oneshot;
 
 /// Format a tool call event for Telegram display based on verbosity
@@ -230,6 +235,162 @@ async fn handle_shortcircuit_command(
     }
 }
 
+/// Send a rendered HTML message, falling back to plain text if Telegram rejects the markup
+pub(crate) async fn send_html_message(
+    bot: &Bot,
+    chat_id: ChatId,
+    reply_to: Option<MessageId>,
+    html: &str,
+    keyboard: Option<InlineKeyboardMarkup>,
+) -> Result<(), String> {
+    let mut request = bot.send_message(chat_id, html).parse_mode(ParseMode::Html);
+    if let Some(id) = reply_to {
+        request = request.reply_to_message_id(id).allow_sending_without_reply(true);
+    }
+    if let Some(ref keyboard) = keyboard {
+        request = request.reply_markup(keyboard.clone());
+    }
+    match request.await {
+        Ok(_) => Ok(()),
+        Err(e) if e.to_string().contains("can't parse entities") => {
+            log::warn!("Telegram: Rejected HTML formatting, resending as plain text: {}", e);
+            let mut request = bot.send_message(chat_id, format::html_to_plain(html));
+            if let Some(id) = reply_to {
+                request = request.reply_to_message_id(id).allow_sending_without_reply(true);
+            }
+            if let Some(keyboard) = keyboard {
+                request = request.reply_markup(keyboard);
+            }
+            request.await.map(|_| ()).map_err(|e| e.to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Inline keyboard for the agent's buttons (None if there are none or they couldn't be stored)
+pub(crate) fn inline_keyboard(db: &Database, channel_id: i64, chat_id: ChatId, buttons: &[ActionButton]) -> Option<InlineKeyboardMarkup> {
+    let callbacks = actions::register_buttons(db, channel_id, &chat_id.to_string(), buttons);
+    if callbacks.is_empty() {
+        return None;
+    }
+    let rows: Vec<Vec<InlineKeyboardButton>> = callbacks
+        .chunks(actions::BUTTONS_PER_ROW)
+        .map(|row| {
+            row.iter()
+                .map(|(label, data)| InlineKeyboardButton::callback(label.clone(), data.clone()))
+                .collect()
+        })
+        .collect();
+    Some(InlineKeyboardMarkup::new(rows))
+}
+
+/// Render and send an agent reply (buttons go on the last chunk); undeliverable chunks are queued
+async fn deliver_reply(
+    bot: &Bot,
+    db: &Database,
+    channel_id: i64,
+    chat_id: ChatId,
+    reply_to: Option<MessageId>,
+    response: &str,
+    buttons: &[ActionButton],
+) {
+    let chunks = format::render_chunks(response, RenderTarget::Telegram);
+    let keyboard = inline_keyboard(db, channel_id, chat_id, buttons);
+    let target = OutboundTarget {
+        channel_id,
+        channel_type: ChannelType::Telegram,
+        chat_id: chat_id.to_string(),
+        reply_to: reply_to.map(|id| id.0.to_string()),
+    };
+    let last = chunks.len();
+    let mut sent = 0;
+    outbound::deliver_or_queue(db, &target, chunks, |chunk| {
+        sent += 1;
+        let keyboard = if sent == last { keyboard.clone() } else { None };
+        let bot = bot.clone();
+        async move { send_html_message(&bot, chat_id, reply_to, &chunk, keyboard).await }
+    })
+    .await;
+}
+
+/// Bot API limit for uploaded documents
+const TELEGRAM_MAX_UPLOAD_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Upload the execution's artifacts as documents after the reply
+async fn deliver_artifacts(bot: &Bot, chat_id: ChatId, artifacts: &[Artifact]) {
+    for (name, bytes) in util::uploadable_artifacts(artifacts, TELEGRAM_MAX_UPLOAD_BYTES) {
+        if let Err(e) = bot
+            .send_document(chat_id, InputFile::memory(bytes).file_name(name.clone()))
+            .await
+        {
+            log::warn!("Telegram: Failed to upload artifact {}: {}", name, e);
+        }
+    }
+}
+
+/// Handle a press on one of our inline keyboard buttons
+async fn handle_callback_query(
+    bot: Bot,
+    query: CallbackQuery,
+    dispatcher: Arc<MessageDispatcher>,
+    db: Arc<Database>,
+    channel_id: i64,
+    admin_user_id: Option<String>,
+) {
+    let button = query
+        .data
+        .as_deref()
+        .and_then(|data| actions::resolve_callback(&db, channel_id, data));
+    let Some(button) = button else {
+        let _ = bot.answer_callback_query(query.id).text("This button has expired").await;
+        return;
+    };
+    // Stop the client's loading spinner
+    let _ = bot.answer_callback_query(query.id.clone()).await;
+    let Some(message) = query.message else {
+        return;
+    };
+
+    // The set is spent — remove it so nobody presses a dead button
+    let chat_id = message.chat.id;
+    let _ = bot.edit_message_reply_markup(chat_id, message.id).await;
+
+    let user_id = query.from.id.to_string();
+    let user_name = query.from.username.clone().unwrap_or_else(|| query.from.first_name.clone());
+    log::info!("Telegram: User {} pressed button '{}' ({})", user_name, button.label, button.action);
+    let force_safe_mode = admin_user_id.as_ref().is_some_and(|admin_id| admin_id != &user_id);
+    let (text, action) = actions::press_to_message(button);
+
+    let normalized = NormalizedMessage {
+        channel_id,
+        channel_type: ChannelType::Telegram.to_string(),
+        chat_id: chat_id.to_string(),
+        chat_name: message.chat.title().map(|t| t.to_string()),
+        user_id,
+        user_name,
+        text,
+        message_id: None,
+        session_mode: None,
+        selected_network: None,
+        force_safe_mode,
+        shared_chat: !message.chat.is_private(),
+        platform_role_ids: vec![],
+        attachments: vec![],
+        chat_context: None,
+        action,
+    };
+
+    let result = dispatcher.dispatch_safe(normalized).await;
+    if result.error.is_none() && !result.response.is_empty() {
+        deliver_reply(&bot, &db, channel_id, chat_id, None, &result.response, &result.actions).await;
+        deliver_artifacts(&bot, chat_id, &result.attachments).await;
+    } else if let Some(error) = result.error {
+        let _ = bot
+            .send_message(chat_id, format!("Sorry, I encountered an error: {}", error))
+            .await;
+    }
+}
+
 /// Start a Telegram bot listener
 pub async fn start_telegram_listener(
     channel: Channel,
@@ -301,8 +462,10 @@ pub async fn start_telegram_listener(
     let bot_username_for_handler = bot_username.clone();
     let db_for_handler = db.clone();
 
+    let admin_user_id_for_callbacks = admin_user_id.clone();
+
     // Create message handler
-    let handler = Update::filter_message().endpoint(
+    let message_handler = Update::filter_message().endpoint(
         move |bot: Bot, msg: teloxide::types::Message, dispatcher: Arc<MessageDispatcher>, db: Arc<Database>| {
             let channel_id = channel_id;
             let broadcaster = broadcaster_for_handler.clone();
@@ -375,7 +538,7 @@ pub async fn start_telegram_listener(
                         log::warn!("Telegram: Failed to store passive chat message: {}", e);
                     }
 
-                    // Only respond if bot is @mentioned or replied to (all chat types including DMs)
+                    // Respond per the channel's group triggers (mention and reply by default, all chat types including DMs)
                     let mentioned = is_bot_mentioned(text, &bot_username);
                     let is_reply_to_bot = msg.reply_to_message()
                         .and_then(|r| r.from())
@@ -387,13 +550,41 @@ pub async fn start_telegram_listener(
                         msg.chat.id, mentioned, is_reply_to_bot, bot_username
                     );
 
-                    if !mentioned && !is_reply_to_bot {
-                        log::debug!("Telegram: Ignoring message (bot not @mentioned or replied to)");
-                        return Ok(());
+                    let addressing = Addressing { mentioned, reply_to_bot: is_reply_to_bot };
+                    match GroupGate::load(&db, channel_id).decide(text, addressing) {
+                        GateDecision::Respond => {}
+                        GateDecision::Remember => {
+                            let user = msg.from();
+                            dispatcher.remember(&NormalizedMessage {
+                                channel_id,
+                                channel_type: ChannelType::Telegram.to_string(),
+                                chat_id: msg.chat.id.to_string(),
+                                chat_name: msg.chat.title().map(|t| t.to_string()),
+                                user_id: user.map(|u| u.id.to_string()).unwrap_or_default(),
+                                user_name: user
+                                    .map(|u| u.username.clone().unwrap_or_else(|| u.first_name.clone()))
+                                    .unwrap_or_else(|| "Unknown".to_string()),
+                                text: text.to_string(),
+                                message_id: Some(msg.id.to_string()),
+                                session_mode: None,
+                                selected_network: None,
+                                force_safe_mode: false,
+                                shared_chat: !msg.chat.is_private(),
+                                platform_role_ids: vec![],
+                                attachments: vec![],
+                                chat_context: None,
+                                action: None,
+                            });
+                            return Ok(());
+                        }
+                        GateDecision::Ignore => {
+                            log::debug!("Telegram: Ignoring message (no group trigger matched)");
+                            return Ok(());
+                        }
                     }
 
                     // Fire telegram_mention persona hooks in background
-                    {
+                    if mentioned || is_reply_to_bot {
                         let hook_dispatcher = Arc::clone(&dispatcher);
                         let hook_chat_id = msg.chat.id.0;
                         let hook_chat_name = msg.chat.title().unwrap_or("DM").to_string();
@@ -532,8 +723,11 @@ pub async fn start_telegram_listener(
                         session_mode: None,
                         selected_network: None,
                         force_safe_mode,
+                        shared_chat: !msg.chat.is_private(),
                         platform_role_ids: vec![],
+                        attachments: vec![],
                         chat_context: None,
+                        action: None,
                     };
 
                     // Subscribe to events for real-time tool call forwarding
@@ -617,11 +811,7 @@ pub async fn start_telegram_listener(
                                         .get("duration_ms")
                                         .and_then(|v| v.as_i64())
                                         .unwrap_or(0);
-                                    let content = event
-                                        .data
-                                        .get("content")
-                                        .and_then(|v| v.as_str())
-                                        .unwrap_or("");
+                                    let content = crate::tools::payload::event_display(&event.data, RenderTarget::Plain);
 
                                     // Skip say_to_user in event stream — content comes through result.response
                                     if tool_name == "say_to_user" {
@@ -631,7 +821,7 @@ pub async fn start_telegram_listener(
                                             tool_name,
                                             success,
                                             duration_ms,
-                                            content,
+                                            &content,
                                             verbosity.display_verbosity(),
                                         )
                                     }
@@ -842,16 +1032,8 @@ pub async fn start_telegram_listener(
                             true,
                         );
 
-                        let chunks = util::split_message(&result.response, 4096);
-                        for chunk in chunks {
-                            if let Err(e) = bot
-                                .send_message(msg.chat.id, &chunk)
-                                .reply_to_message_id(msg.id)
-                                .await
-                            {
-                                log::error!("Failed to send Telegram message: {}", e);
-                            }
-                        }
+                        deliver_reply(&bot, &db, channel_id, msg.chat.id, Some(msg.id), &result.response, &result.actions).await;
+                        deliver_artifacts(&bot, msg.chat.id, &result.attachments).await;
                     } else if let Some(error) = result.error {
                         let error_msg =
                             format!("Sorry, I encountered an error: {}", error);
@@ -869,6 +1051,18 @@ pub async fn start_telegram_listener(
         },
     );
 
+    // Button presses on inline keyboards the agent attached
+    let callback_handler = Update::filter_callback_query().endpoint(
+        move |bot: Bot, query: CallbackQuery, dispatcher: Arc<MessageDispatcher>, db: Arc<Database>| {
+            let admin_user_id = admin_user_id_for_callbacks.clone();
+            async move {
+                handle_callback_query(bot, query, dispatcher, db, channel_id, admin_user_id).await;
+                Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+            }
+        },
+    );
+    let handler = dptree::entry().branch(message_handler).branch(callback_handler);
+
     // Create dispatcher
     let mut tg_dispatcher = Dispatcher::builder(bot, handler)
         .dependencies(dptree::deps![dispatcher, db_for_handler])
@@ -0,0 +1,211 @@
+//! Inbound message triage: sentiment and urgency tags
+//!
+//! A cheap lexicon classifier that runs on every user message before it
+//! reaches the agent. The tags are stored on the session message for
+//! analytics; urgent messages jump ahead of messages already queued for the
+//! same chat and are forwarded to the owner right away.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::channels::outbound;
+use crate::channels::types::NormalizedMessage;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+
+/// Minimum time between two urgent-message notifications for one chat
+const NOTIFY_COOLDOWN: Duration = Duration::from_secs(600);
+/// Characters of the message quoted in the owner notification
+const NOTIFY_PREVIEW_CHARS: usize = 280;
+
+const POSITIVE: &[&str] = &[
+    "thanks", "thank", "thx", "great", "awesome", "love", "nice", "perfect", "excellent", "amazing",
+    "good", "cool", "happy", "glad", "appreciate", "brilliant", "wonderful", "works", "fixed",
+];
+const NEGATIVE: &[&str] = &[
+    "bad", "broken", "wrong", "hate", "terrible", "awful", "angry", "annoyed", "frustrated", "useless",
+    "worse", "worst", "fail", "failed", "failing", "error", "stuck", "lost", "scam", "stolen", "hacked",
+    "drained", "disappointed", "upset", "ridiculous", "sucks",
+];
+const NEGATIONS: &[&str] = &["not", "no", "never", "don't", "dont", "doesn't", "isn't", "wasn't", "can't"];
+/// Words that make a message urgent on their own
+const URGENT_WORDS: &[&str] = &[
+    "urgent", "urgently", "emergency", "asap", "immediately", "hacked", "drained", "compromised",
+    "stolen", "exploit", "critical",
+];
+const URGENT_PHRASES: &[&str] = &["right now", "help!", "as soon as possible", "stop everything"];
+/// Words that raise urgency
+const PRESSING_WORDS: &[&str] = &[
+    "quickly", "soon", "deadline", "today", "important", "broken", "down", "outage", "failing", "stuck",
+    "hurry", "now",
+];
+const RELAXED_PHRASES: &[&str] = &[
+    "no rush", "no hurry", "whenever", "when you get a chance", "when you have time", "fyi", "low priority",
+];
+
+/// Last urgent notification per chat
+static LAST_NOTIFIED: Lazy<Mutex<HashMap<String, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sentiment {
+    Positive,
+    Neutral,
+    Negative,
+}
+
+impl Sentiment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Positive => "positive",
+            Self::Neutral => "neutral",
+            Self::Negative => "negative",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Urgency {
+    Low,
+    Normal,
+    High,
+    Urgent,
+}
+
+impl Urgency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+            Self::Urgent => "urgent",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MessageTags {
+    pub sentiment: Sentiment,
+    pub urgency: Urgency,
+}
+
+/// Tag a message with sentiment and urgency
+pub fn classify(text: &str) -> MessageTags {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    // Sentiment: lexicon hits, flipped by a negation just before the word
+    let mut score = 0i32;
+    for (i, word) in words.iter().enumerate() {
+        let polarity = if POSITIVE.contains(word) {
+            1
+        } else if NEGATIVE.contains(word) {
+            -1
+        } else {
+            continue;
+        };
+        let negated = i > 0 && NEGATIONS.contains(&words[i - 1]);
+        score += if negated { -polarity } else { polarity };
+    }
+    let sentiment = match score {
+        s if s > 0 => Sentiment::Positive,
+        s if s < 0 => Sentiment::Negative,
+        _ => Sentiment::Neutral,
+    };
+
+    // Urgency: explicit cues, repeated exclamation marks and shouting
+    let mut pressure = 0;
+    pressure += 3 * words.iter().filter(|w| URGENT_WORDS.contains(w)).count();
+    pressure += 3 * URGENT_PHRASES.iter().filter(|p| lower.contains(*p)).count();
+    pressure += words.iter().filter(|w| PRESSING_WORDS.contains(w)).count();
+    if text.contains("!!") {
+        pressure += 1;
+    }
+    let shouted = text
+        .split_whitespace()
+        .filter(|w| w.chars().filter(|c| c.is_alphabetic()).count() >= 3)
+        .filter(|w| w.chars().filter(|c| c.is_alphabetic()).all(|c| c.is_uppercase()))
+        .count();
+    if shouted >= 2 {
+        pressure += 1;
+    }
+    let relaxed = RELAXED_PHRASES.iter().any(|p| lower.contains(p));
+    let urgency = match pressure {
+        0 if relaxed => Urgency::Low,
+        p if p >= 3 => Urgency::Urgent,
+        p if p >= 2 && !relaxed => Urgency::High,
+        _ => Urgency::Normal,
+    };
+
+    MessageTags { sentiment, urgency }
+}
+
+/// Tell the owner about an urgent message, at most once per chat per cooldown.
+/// Messages from the owner's own chat are not echoed back.
+pub async fn notify_owner(db: &Database, broadcaster: &EventBroadcaster, message: &NormalizedMessage, tags: MessageTags) {
+    broadcaster.broadcast(GatewayEvent::custom(
+        "channel.urgent_message",
+        serde_json::json!({
+            "channel_id": message.channel_id,
+            "channel_type": message.channel_type,
+            "chat_id": message.chat_id,
+            "from": message.user_name,
+            "sentiment": tags.sentiment,
+        }),
+    ));
+
+    let Some((owner_channel, owner_chat)) = db.get_bot_settings().ok().and_then(|s| s.owner_chat()) else {
+        return;
+    };
+    if owner_channel == message.channel_id && owner_chat == message.chat_id {
+        return;
+    }
+    let key = format!("{}:{}", message.channel_id, message.chat_id);
+    {
+        let mut last = LAST_NOTIFIED.lock().unwrap_or_else(|e| e.into_inner());
+        if last.get(&key).is_some_and(|at| at.elapsed() < NOTIFY_COOLDOWN) {
+            return;
+        }
+        last.retain(|_, at| at.elapsed() < NOTIFY_COOLDOWN);
+        last.insert(key, Instant::now());
+    }
+
+    let preview: String = message.text.chars().take(NOTIFY_PREVIEW_CHARS).collect();
+    let ellipsis = if message.text.chars().count() > NOTIFY_PREVIEW_CHARS { "…" } else { "" };
+    let text = format!(
+        "**Urgent message** from {} on {}:\n> {}{}",
+        message.user_name, message.channel_type, preview, ellipsis
+    );
+    if let Err(e) = outbound::send_proactive(db, owner_channel, &owner_chat, &text, "urgent_message").await {
+        log::warn!("[TRIAGE] Failed to notify the owner: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let tags = classify("URGENT my wallet got DRAINED!! please help");
+        assert_eq!(tags.urgency, Urgency::Urgent);
+        assert_eq!(tags.sentiment, Sentiment::Negative);
+
+        let tags = classify("thanks, that works great");
+        assert_eq!(tags, MessageTags { sentiment: Sentiment::Positive, urgency: Urgency::Normal });
+
+        assert_eq!(classify("the bot is not good").sentiment, Sentiment::Negative);
+        assert_eq!(classify("deploy is broken, need it fixed today").urgency, Urgency::High);
+        assert_eq!(classify("fyi, no rush on the report").urgency, Urgency::Low);
+        assert_eq!(classify("what's the ETH price?").urgency, Urgency::Normal);
+    }
+}
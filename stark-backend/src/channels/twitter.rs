@@ -1,15 +1,19 @@
 //! Twitter @mention listener using polling-based approach
 //!
-//! Polls the Twitter API v2 mentions endpoint to detect and respond to @mentions.
+//! Polls the Twitter API v2 mentions endpoint to detect and respond to @mentions,
+//! optionally polls direct messages, and drains the agent's outgoing tweet queue.
 //! Uses OAuth 1.0a for authentication and respects rate limits.
 
 use crate::channels::dispatcher::MessageDispatcher;
+use crate::channels::outbox;
 use crate::channels::types::{ChannelType, NormalizedMessage};
+use crate::circuit_breaker::{self, GuardedSend};
 use crate::controllers::api_keys::ApiKeyId;
+use crate::db::tables::outbox_drafts::DraftTarget;
 use crate::db::Database;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::{Channel, ChannelSettingKey};
+use crate::models::{Channel, ChannelSettingKey, MessageRole};
 use crate::tools::builtin::social_media::{
     check_subscription_tier, generate_oauth_header, percent_encode, TwitterCredentials,
     XSubscriptionTier, TWITTER_MAX_CHARS, TWITTER_PREMIUM_MAX_CHARS,
@@ -42,6 +46,15 @@ const MAX_THREAD_TWEETS: usize = 100;
 /// Maximum characters of thread context to include in the hint
 const MAX_THREAD_CONTEXT_CHARS: usize = 15_000;
 
+/// Number of recent DM events fetched per poll
+const DM_FETCH_LIMIT: &str = "20";
+
+/// Maximum queued tweets posted per poll tick
+const MAX_QUEUED_POSTS_PER_TICK: usize = 3;
+
+/// Backoff when posting is rate limited and the reset time is unknown
+const POST_RATE_LIMIT_BACKOFF_SECS: u64 = 900;
+
 /// Configuration for the Twitter listener
 #[derive(Debug, Clone)]
 pub struct TwitterConfig {
@@ -52,6 +65,7 @@ pub struct TwitterConfig {
     pub reply_chance: u8,
     pub max_mentions_per_hour: u32,
     pub admin_user_id: Option<String>,
+    pub read_dms: bool,
     pub credentials: TwitterCredentials,
 }
 
@@ -108,15 +122,14 @@ impl TwitterConfig {
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()));
 
+        let read_dms = db
+            .get_channel_setting(channel_id, ChannelSettingKey::TwitterReadDms.as_ref())
+            .ok()
+            .flatten()
+            .is_some_and(|v| v == "true");
+
         // Load OAuth credentials from API keys
-        let consumer_key = get_api_key(db, ApiKeyId::TwitterConsumerKey)
-            .ok_or_else(|| "TWITTER_CONSUMER_KEY not configured".to_string())?;
-        let consumer_secret = get_api_key(db, ApiKeyId::TwitterConsumerSecret)
-            .ok_or_else(|| "TWITTER_CONSUMER_SECRET not configured".to_string())?;
-        let access_token = get_api_key(db, ApiKeyId::TwitterAccessToken)
-            .ok_or_else(|| "TWITTER_ACCESS_TOKEN not configured".to_string())?;
-        let access_token_secret = get_api_key(db, ApiKeyId::TwitterAccessTokenSecret)
-            .ok_or_else(|| "TWITTER_ACCESS_TOKEN_SECRET not configured".to_string())?;
+        let credentials = credentials_from_keys(db)?;
 
         Ok(Self {
             bot_handle,
@@ -126,16 +139,31 @@ impl TwitterConfig {
             reply_chance,
             max_mentions_per_hour,
             admin_user_id,
-            credentials: TwitterCredentials::new(
-                consumer_key,
-                consumer_secret,
-                access_token,
-                access_token_secret,
-            ),
+            read_dms,
+            credentials,
         })
     }
 }
 
+/// Load the OAuth 1.0a credentials from the API keys page (env var fallback)
+pub fn credentials_from_keys(db: &Database) -> Result<TwitterCredentials, String> {
+    let consumer_key = get_api_key(db, ApiKeyId::TwitterConsumerKey)
+        .ok_or_else(|| "TWITTER_CONSUMER_KEY not configured".to_string())?;
+    let consumer_secret = get_api_key(db, ApiKeyId::TwitterConsumerSecret)
+        .ok_or_else(|| "TWITTER_CONSUMER_SECRET not configured".to_string())?;
+    let access_token = get_api_key(db, ApiKeyId::TwitterAccessToken)
+        .ok_or_else(|| "TWITTER_ACCESS_TOKEN not configured".to_string())?;
+    let access_token_secret = get_api_key(db, ApiKeyId::TwitterAccessTokenSecret)
+        .ok_or_else(|| "TWITTER_ACCESS_TOKEN_SECRET not configured".to_string())?;
+
+    Ok(TwitterCredentials::new(
+        consumer_key,
+        consumer_secret,
+        access_token,
+        access_token_secret,
+    ))
+}
+
 /// Get an API key from the database with env var fallback
 fn get_api_key(db: &Database, key_id: ApiKeyId) -> Option<String> {
     // Try database first
@@ -249,6 +277,21 @@ struct PollResult {
     rate_limit: RateLimitInfo,
 }
 
+/// Twitter API v2 DM events response
+#[derive(Debug, Deserialize)]
+struct DmEventsResponse {
+    data: Option<Vec<DmEvent>>,
+    errors: Option<Vec<TwitterApiError>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DmEvent {
+    id: String,
+    text: Option<String>,
+    sender_id: Option<String>,
+    dm_conversation_id: Option<String>,
+}
+
 /// Twitter API v2 users response (for looking up usernames - single user)
 #[derive(Debug, Deserialize)]
 struct SingleUserResponse {
@@ -338,13 +381,14 @@ pub async fn start_twitter_listener(
     }
 
     log::info!(
-        "Twitter: Bot handle=@{}, user_id={}, poll_interval={}s, reply_chance={}%, max_mentions/hr={}, admin_id={}",
+        "Twitter: Bot handle=@{}, user_id={}, poll_interval={}s, reply_chance={}%, max_mentions/hr={}, admin_id={}, read_dms={}",
         config.bot_handle,
         config.bot_user_id,
         config.poll_interval_secs,
         config.reply_chance,
         if config.max_mentions_per_hour == 0 { "unlimited".to_string() } else { config.max_mentions_per_hour.to_string() },
-        config.admin_user_id.as_deref().unwrap_or("none")
+        config.admin_user_id.as_deref().unwrap_or("none"),
+        config.read_dms
     );
 
     // Pre-compile bot mention regex (used per-tweet in extract_command_text)
@@ -394,6 +438,10 @@ pub async fn start_twitter_listener(
     let mut hour_start = Instant::now();
     let mut replies_this_hour: u32 = 0;
 
+    // Outgoing queue backoff, and whether the DM inbox backlog has been skipped
+    let mut queue_paused_until: Option<Instant> = None;
+    let mut dms_seeded = db.has_processed_dms(channel_id).unwrap_or(false);
+
     // Create poll interval
     let mut poll_interval = interval(Duration::from_secs(config.poll_interval_secs));
 
@@ -405,6 +453,23 @@ pub async fn start_twitter_listener(
                 break;
             }
             _ = poll_interval.tick() => {
+                // Post whatever is due from the agent's outgoing tweet queue
+                drain_outgoing_queue(&client, &config, &db, &broadcaster, channel_id, &mut queue_paused_until).await;
+
+                if config.read_dms {
+                    handle_new_dms(
+                        &client,
+                        &config,
+                        &db,
+                        &dispatcher,
+                        &broadcaster,
+                        channel_id,
+                        &mut dms_seeded,
+                        &mut hour_start,
+                        &mut replies_this_hour,
+                    ).await;
+                }
+
                 // Poll for new mentions
                 let poll_result = poll_mentions(&client, &config, since_id.as_deref()).await;
 
@@ -577,17 +642,29 @@ pub async fn start_twitter_listener(
                                     ).await;
 
                                     if let Some(response_text) = response {
-                                        match post_reply(
-                                            &client,
-                                            &config,
-                                            &mention.id,
-                                            &response_text,
-                                        ).await {
-                                            Ok(_) => {
-                                                replies_this_hour += 1;
+                                        if outbox::twitter_review_enabled(&db, Some(channel_id)) {
+                                            let target = DraftTarget::Twitter {
+                                                channel_id: Some(channel_id),
+                                                reply_to_id: Some(mention.id.clone()),
+                                                quote_tweet_id: None,
+                                                not_before: None,
+                                            };
+                                            if let Err(e) = outbox::hold(&db, Some(broadcaster.as_ref()), target, &response_text, None) {
+                                                log::error!("Twitter: Failed to hold reply for review: {}", e);
                                             }
-                                            Err(e) => {
-                                                log::error!("Twitter: Failed to post reply: {}", e);
+                                        } else {
+                                            match post_reply(
+                                                &client,
+                                                &config,
+                                                &mention.id,
+                                                &response_text,
+                                            ).await {
+                                                Ok(_) => {
+                                                    replies_this_hour += 1;
+                                                }
+                                                Err(e) => {
+                                                    log::error!("Twitter: Failed to post reply: {}", e);
+                                                }
                                             }
                                         }
                                     }
@@ -657,7 +734,7 @@ async fn verify_credentials(
     let response = client
         .get(&url)
         .header("Authorization", auth_header)
-        .send()
+        .send_guarded(circuit_breaker::TWITTER)
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
 
@@ -721,7 +798,7 @@ async fn poll_mentions(
     let response = client
         .get(&full_url)
         .header("Authorization", auth_header)
-        .send()
+        .send_guarded(circuit_breaker::TWITTER)
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
 
@@ -754,6 +831,307 @@ async fn poll_mentions(
     })
 }
 
+/// Poll recent direct messages (newest first)
+async fn poll_dms(
+    client: &reqwest::Client,
+    config: &TwitterConfig,
+) -> Result<Vec<DmEvent>, String> {
+    let url = format!("{}/dm_events", TWITTER_API_BASE);
+    let params: Vec<(&str, &str)> = vec![
+        ("event_types", "MessageCreate"),
+        ("dm_event.fields", "id,text,sender_id,dm_conversation_id"),
+        ("max_results", DM_FETCH_LIMIT),
+    ];
+
+    let query_string: String = params
+        .iter()
+        .map(|(k, v)| format!("{}={}", percent_encode(k), percent_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+    let full_url = format!("{}?{}", url, query_string);
+    let auth_header = generate_oauth_header("GET", &url, &config.credentials, Some(&params));
+
+    let response = client
+        .get(&full_url)
+        .header("Authorization", auth_header)
+        .send_guarded(circuit_breaker::TWITTER)
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+
+    if !status.is_success() {
+        return Err(format!("API error ({}): {}", status, body));
+    }
+
+    let data: DmEventsResponse =
+        serde_json::from_str(&body).map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    if let Some(errors) = data.errors {
+        let error_msg = errors
+            .iter()
+            .map(|e| e.message.clone())
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Err(format!("Twitter API errors: {}", error_msg));
+    }
+
+    Ok(data.data.unwrap_or_default())
+}
+
+/// Send a direct message into an existing DM conversation
+async fn send_dm(
+    client: &reqwest::Client,
+    config: &TwitterConfig,
+    dm_conversation_id: &str,
+    text: &str,
+) -> Result<(), String> {
+    let url = format!("{}/dm_conversations/{}/messages", TWITTER_API_BASE, dm_conversation_id);
+    let auth_header = generate_oauth_header("POST", &url, &config.credentials, None);
+
+    let response = client
+        .post(&url)
+        .header("Authorization", auth_header)
+        .header("Content-Type", "application/json")
+        .json(&serde_json::json!({ "text": text }))
+        .send_guarded(circuit_breaker::TWITTER)
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error ({}): {}", status, body));
+    }
+    Ok(())
+}
+
+/// Dispatch a direct message and get the AI response.
+/// Each DM conversation keeps its own session so follow-ups have context.
+#[allow(clippy::too_many_arguments)]
+async fn process_dm(
+    event: &DmEvent,
+    sender_id: &str,
+    sender_username: &str,
+    dm_conversation_id: &str,
+    channel_id: i64,
+    force_safe_mode: bool,
+    dispatcher: &Arc<MessageDispatcher>,
+    broadcaster: &Arc<EventBroadcaster>,
+) -> Option<String> {
+    let text = event.text.as_deref().unwrap_or("").trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let normalized = NormalizedMessage {
+        channel_id,
+        channel_type: ChannelType::Twitter.to_string(),
+        chat_id: format!("dm:{}", dm_conversation_id),
+        chat_name: None,
+        user_id: sender_id.to_string(),
+        user_name: sender_username.to_string(),
+        text: format!(
+            "[TWITTER DM from @{}. Reply using say_to_user — your LAST say_to_user message is sent back as the direct message.]\n\n{}",
+            sender_username, text
+        ),
+        message_id: Some(event.id.clone()),
+        session_mode: None,
+        selected_network: None,
+        force_safe_mode,
+        shared_chat: false,
+        platform_role_ids: vec![],
+        attachments: vec![],
+        chat_context: None,
+        action: None,
+    };
+
+    dispatch_and_capture(normalized, sender_username, channel_id, dispatcher, broadcaster).await
+}
+
+/// Poll DMs and answer new ones. On the very first poll the existing inbox is
+/// only marked processed, so enabling DMs doesn't answer a backlog.
+#[allow(clippy::too_many_arguments)]
+async fn handle_new_dms(
+    client: &reqwest::Client,
+    config: &TwitterConfig,
+    db: &Database,
+    dispatcher: &Arc<MessageDispatcher>,
+    broadcaster: &Arc<EventBroadcaster>,
+    channel_id: i64,
+    dms_seeded: &mut bool,
+    hour_start: &mut Instant,
+    replies_this_hour: &mut u32,
+) {
+    let events = match poll_dms(client, config).await {
+        Ok(events) => events,
+        Err(e) => {
+            log::error!("Twitter: Error polling DMs: {}", e);
+            return;
+        }
+    };
+
+    // Oldest first
+    for event in events.into_iter().rev() {
+        let dedupe_id = format!("dm:{}", event.id);
+        let (Some(sender_id), Some(conversation_id)) = (event.sender_id.clone(), event.dm_conversation_id.clone()) else {
+            continue;
+        };
+        // Our own outgoing messages show up in the event list too
+        if sender_id == config.bot_user_id || db.is_tweet_processed(&dedupe_id).unwrap_or(false) {
+            continue;
+        }
+        let text = event.text.clone().unwrap_or_default();
+
+        if !*dms_seeded {
+            let _ = db.mark_tweet_processed(&dedupe_id, channel_id, &sender_id, "unknown", &text);
+            continue;
+        }
+
+        if hour_start.elapsed() >= Duration::from_secs(3600) {
+            *hour_start = Instant::now();
+            *replies_this_hour = 0;
+        }
+
+        let is_admin = config.admin_user_id.as_deref() == Some(sender_id.as_str());
+        if !is_admin && config.max_mentions_per_hour > 0 && *replies_this_hour >= config.max_mentions_per_hour {
+            log::info!(
+                "Twitter: Hourly rate limit reached ({}/{}), skipping DM {}",
+                replies_this_hour, config.max_mentions_per_hour, event.id
+            );
+            let _ = db.mark_tweet_processed(&dedupe_id, channel_id, &sender_id, "unknown", &text);
+            continue;
+        }
+
+        let sender_username = match lookup_user(client, config, &sender_id).await {
+            Ok(user) => user.username,
+            Err(e) => {
+                log::warn!("Twitter: Failed to lookup user {}: {}", sender_id, e);
+                format!("user_{}", sender_id)
+            }
+        };
+        log::info!("Twitter: Processing DM from @{} (admin={})", sender_username, is_admin);
+
+        // Mark first so a slow or failed dispatch isn't answered twice
+        if let Err(e) = db.mark_tweet_processed(&dedupe_id, channel_id, &sender_id, &sender_username, &text) {
+            log::error!("Twitter: Failed to mark DM {} as processed: {}", event.id, e);
+        }
+
+        let response = process_dm(
+            &event,
+            &sender_id,
+            &sender_username,
+            &conversation_id,
+            channel_id,
+            !is_admin,
+            dispatcher,
+            broadcaster,
+        )
+        .await;
+
+        if let Some(response_text) = response {
+            match send_dm(client, config, &conversation_id, &response_text).await {
+                Ok(()) => *replies_this_hour += 1,
+                Err(e) => log::error!("Twitter: Failed to send DM reply: {}", e),
+            }
+        }
+    }
+
+    *dms_seeded = true;
+}
+
+/// Post due tweets from the agent's outgoing queue.
+/// Stops early and defers the rest of the queue when the posting limit is
+/// exhausted; `paused_until` carries that backoff across ticks.
+async fn drain_outgoing_queue(
+    client: &reqwest::Client,
+    config: &TwitterConfig,
+    db: &Database,
+    broadcaster: &EventBroadcaster,
+    channel_id: i64,
+    paused_until: &mut Option<Instant>,
+) {
+    if let Some(until) = *paused_until {
+        if Instant::now() < until {
+            return;
+        }
+        *paused_until = None;
+    }
+
+    let due = match db.list_due_queued_tweets(channel_id, MAX_QUEUED_POSTS_PER_TICK) {
+        Ok(due) => due,
+        Err(e) => {
+            log::error!("Twitter: Failed to load outgoing queue: {}", e);
+            return;
+        }
+    };
+
+    for queued in due {
+        let result = post_thread(
+            client,
+            config,
+            &queued.text,
+            queued.reply_to_id.as_deref(),
+            queued.quote_tweet_id.as_deref(),
+        )
+        .await;
+
+        match result {
+            Ok((tweet_ids, rate_limit)) => {
+                let tweet_id = tweet_ids.first().cloned().unwrap_or_default();
+                log::info!("Twitter: Posted queued tweet #{} as {}", queued.id, tweet_id);
+                if let Err(e) = db.mark_queued_tweet_sent(queued.id, &tweet_id) {
+                    log::error!("Twitter: Failed to mark queued tweet #{} sent: {}", queued.id, e);
+                }
+                if let Some(session_id) = queued.session_id {
+                    let note = format!(
+                        "Queued tweet #{} was posted: https://twitter.com/i/web/status/{}",
+                        queued.id, tweet_id
+                    );
+                    let _ = db.add_session_message(session_id, MessageRole::System, &note, None, None, None, None);
+                }
+                broadcaster.broadcast(GatewayEvent::custom(
+                    "twitter_queued_tweet_posted",
+                    serde_json::json!({
+                        "channel_id": channel_id,
+                        "queue_id": queued.id,
+                        "tweet_ids": tweet_ids,
+                        "session_id": queued.session_id,
+                    }),
+                ));
+
+                if rate_limit.is_rate_limited() {
+                    let wait_secs = rate_limit.seconds_until_reset().unwrap_or(POST_RATE_LIMIT_BACKOFF_SECS);
+                    pause_outgoing_queue(db, channel_id, paused_until, wait_secs);
+                    break;
+                }
+            }
+            Err(e) if e.contains("429") => {
+                log::warn!("Twitter: Posting rate limited, deferring queued tweet #{}", queued.id);
+                pause_outgoing_queue(db, channel_id, paused_until, POST_RATE_LIMIT_BACKOFF_SECS);
+                break;
+            }
+            Err(e) => {
+                log::error!("Twitter: Failed to post queued tweet #{}: {}", queued.id, e);
+                // Back off 5, 10, 20... minutes between attempts
+                let backoff = chrono::Duration::minutes(5 * (1 << queued.attempts.clamp(0, 6)));
+                let _ = db.mark_queued_tweet_failed(queued.id, &e, Some(chrono::Utc::now() + backoff));
+            }
+        }
+    }
+}
+
+/// Stop posting from the queue for `wait_secs` and push due tweets past the reset
+fn pause_outgoing_queue(db: &Database, channel_id: i64, paused_until: &mut Option<Instant>, wait_secs: u64) {
+    log::warn!("Twitter: Posting limit reached, pausing outgoing queue for {}s", wait_secs);
+    *paused_until = Some(Instant::now() + Duration::from_secs(wait_secs));
+    let until = chrono::Utc::now() + chrono::Duration::seconds(wait_secs as i64);
+    if let Err(e) = db.defer_queued_tweets(channel_id, until) {
+        log::error!("Twitter: Failed to defer outgoing queue: {}", e);
+    }
+}
+
 /// Check if a tweet is a reply to the bot that doesn't explicitly mention @bot_handle.
 /// Twitter auto-prepends @bot_handle when replying to the bot's tweet, which causes
 /// the search API to pick it up. We only want to respond if the user explicitly
@@ -820,7 +1198,7 @@ async fn lookup_user(
     let response = client
         .get(&url)
         .header("Authorization", auth_header)
-        .send()
+        .send_guarded(circuit_breaker::TWITTER)
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
 
@@ -872,7 +1250,7 @@ async fn fetch_thread_context(
     let response = match client
         .get(&full_url)
         .header("Authorization", auth_header)
-        .send()
+        .send_guarded(circuit_breaker::TWITTER)
         .await
     {
         Ok(r) => r,
@@ -1047,13 +1425,33 @@ async fn process_mention(
         session_mode: None,
         selected_network: None,
         force_safe_mode,
+        shared_chat: true,
         platform_role_ids: vec![],
+        attachments: vec![],
         chat_context: None,
+        action: None,
     };
 
+    dispatch_and_capture(normalized, author_username, channel_id, dispatcher, broadcaster).await
+}
+
+/// Dispatch a normalized message and return the agent's last say_to_user
+/// message (the text to post), or None if nothing postable was produced.
+/// Shared by the polling-based public channels (Twitter, Farcaster).
+pub(crate) async fn dispatch_and_capture(
+    normalized: NormalizedMessage,
+    author_username: &str,
+    channel_id: i64,
+    dispatcher: &Arc<MessageDispatcher>,
+    broadcaster: &Arc<EventBroadcaster>,
+) -> Option<String> {
+    let platform = ChannelType::from_str(&normalized.channel_type)
+        .map(|t| t.display_name())
+        .unwrap_or("Channel");
+
     // Subscribe to events to capture say_to_user messages.
     // Unlike Discord/Telegram which forward events in real-time via WebSocket,
-    // Twitter is polling-based and needs to capture the message for post_reply().
+    // polling channels need to capture the message to post it as the reply.
     let (client_id, mut event_rx) = broadcaster.subscribe();
 
     // Collect say_to_user messages from broadcast events in a background task
@@ -1081,7 +1479,7 @@ async fn process_mention(
     });
 
     // Dispatch to AI
-    log::info!("Twitter: Dispatching message to AI for @{}", author_username);
+    log::info!("{}: Dispatching message to AI for @{}", platform, author_username);
     let result = dispatcher.dispatch_safe(normalized).await;
 
     // Unsubscribe prevents new events; give the capture task a moment to drain
@@ -1092,7 +1490,8 @@ async fn process_mention(
     event_task.abort();
 
     log::info!(
-        "Twitter: Dispatch complete for @{}, error={:?}",
+        "{}: Dispatch complete for @{}, error={:?}",
+        platform,
         author_username,
         result.error
     );
@@ -1102,12 +1501,13 @@ async fn process_mention(
     let captured = say_to_user_messages.lock().await;
     if !captured.is_empty() {
         let last = captured.last().unwrap().clone();
-        log::info!("Twitter: Using say_to_user message ({} chars, {} total captured)", last.len(), captured.len());
+        log::info!("{}: Using say_to_user message ({} chars, {} total captured)", platform, last.len(), captured.len());
         Some(last)
     } else if let Some(error) = result.error {
         // Never tweet internal errors (loop detection, AI failures, etc.)
         log::warn!(
-            "Twitter: Suppressing error response for @{}: {}",
+            "{}: Suppressing error response for @{}: {}",
+            platform,
             author_username,
             error
         );
@@ -1117,12 +1517,13 @@ async fn process_mention(
         // task summaries, and raw AI text not intended for public tweets.
         if !result.response.is_empty() {
             log::warn!(
-                "Twitter: No say_to_user captured for @{}, suppressing result.response ({} chars)",
+                "{}: No say_to_user captured for @{}, suppressing result.response ({} chars)",
+                platform,
                 author_username,
                 result.response.len()
             );
         } else {
-            log::warn!("Twitter: No response from dispatch and no say_to_user events for @{}", author_username);
+            log::warn!("{}: No response from dispatch and no say_to_user events for @{}", platform, author_username);
         }
         None
     }
@@ -1195,22 +1596,40 @@ async fn post_reply(
     reply_to_id: &str,
     text: &str,
 ) -> Result<String, String> {
+    let (tweet_ids, _) = post_thread(client, config, text, Some(reply_to_id), None).await?;
+    Ok(tweet_ids.last().cloned().unwrap_or_else(|| reply_to_id.to_string()))
+}
+
+/// Post text as a tweet, threading it if it exceeds the character limit.
+/// The first chunk replies to / quotes the given tweet; each later chunk replies
+/// to the previous one. Returns the posted IDs and the last rate limit seen.
+async fn post_thread(
+    client: &reqwest::Client,
+    config: &TwitterConfig,
+    text: &str,
+    reply_to_id: Option<&str>,
+    quote_tweet_id: Option<&str>,
+) -> Result<(Vec<String>, RateLimitInfo), String> {
     let chunks = split_for_twitter(text, config.max_chars());
-    let mut last_tweet_id = reply_to_id.to_string();
+    let mut tweet_ids: Vec<String> = Vec::with_capacity(chunks.len());
+    let mut rate_limit = RateLimitInfo::default();
 
     for (i, chunk) in chunks.iter().enumerate() {
         log::info!(
-            "Twitter: Posting reply chunk {}/{} ({} chars)",
+            "Twitter: Posting chunk {}/{} ({} chars)",
             i + 1,
             chunks.len(),
             chunk.chars().count()
         );
 
-        let tweet_id = post_single_tweet(client, config, &chunk, Some(&last_tweet_id)).await?;
-        last_tweet_id = tweet_id;
+        let in_reply_to = tweet_ids.last().map(|id| id.as_str()).or(reply_to_id);
+        let quote = if i == 0 { quote_tweet_id } else { None };
+        let (tweet_id, info) = post_single_tweet(client, config, chunk, in_reply_to, quote).await?;
+        tweet_ids.push(tweet_id);
+        rate_limit = info;
     }
 
-    Ok(last_tweet_id)
+    Ok((tweet_ids, rate_limit))
 }
 
 /// Post a single tweet
@@ -1219,7 +1638,8 @@ async fn post_single_tweet(
     config: &TwitterConfig,
     text: &str,
     reply_to_id: Option<&str>,
-) -> Result<String, String> {
+    quote_tweet_id: Option<&str>,
+) -> Result<(String, RateLimitInfo), String> {
     let url = format!("{}/tweets", TWITTER_API_BASE);
     let auth_header = generate_oauth_header("POST", &url, &config.credentials, None);
 
@@ -1234,15 +1654,20 @@ async fn post_single_tweet(
         });
     }
 
+    if let Some(quote_id) = quote_tweet_id {
+        body["quote_tweet_id"] = serde_json::json!(quote_id);
+    }
+
     let response = client
         .post(&url)
         .header("Authorization", auth_header)
         .header("Content-Type", "application/json")
         .json(&body)
-        .send()
+        .send_guarded(circuit_breaker::TWITTER)
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
 
+    let rate_limit = RateLimitInfo::from_response(&response);
     let status = response.status();
     let response_body = response.text().await.unwrap_or_default();
 
@@ -1273,7 +1698,7 @@ async fn post_single_tweet(
                     tweet.text.clone()
                 }
             );
-            tweet.id
+            (tweet.id, rate_limit)
         })
         .ok_or_else(|| "No tweet data returned".to_string())
 }
@@ -1429,6 +1854,7 @@ mod tests {
             reply_chance: 100,
             max_mentions_per_hour: 0,
             admin_user_id: None,
+            read_dms: false,
             credentials: TwitterCredentials::new(
                 "k".to_string(), "s".to_string(), "t".to_string(), "ts".to_string(),
             ),
@@ -1,3 +1,5 @@
+use crate::db::tables::artifacts::Artifact;
+use crate::models::MessageAttachment;
 use serde::{Deserialize, Serialize};
 
 /// Supported channel types
@@ -8,6 +10,7 @@ pub enum ChannelType {
     Slack,
     Discord,
     Twitter,
+    Farcaster,
     ExternalChannel,
 }
 
@@ -19,6 +22,7 @@ impl ChannelType {
             Self::Slack => "slack",
             Self::Discord => "discord",
             Self::Twitter => "twitter",
+            Self::Farcaster => "farcaster",
             Self::ExternalChannel => "external_channel",
         }
     }
@@ -30,6 +34,7 @@ impl ChannelType {
             "slack" => Some(Self::Slack),
             "discord" => Some(Self::Discord),
             "twitter" => Some(Self::Twitter),
+            "farcaster" => Some(Self::Farcaster),
             "external_channel" => Some(Self::ExternalChannel),
             _ => None,
         }
@@ -37,7 +42,7 @@ impl ChannelType {
 
     /// All supported channel types
     pub fn all() -> &'static [ChannelType] {
-        &[Self::Telegram, Self::Slack, Self::Discord, Self::Twitter, Self::ExternalChannel]
+        &[Self::Telegram, Self::Slack, Self::Discord, Self::Twitter, Self::Farcaster, Self::ExternalChannel]
     }
 
     /// Display name for UI
@@ -47,6 +52,7 @@ impl ChannelType {
             Self::Slack => "Slack",
             Self::Discord => "Discord",
             Self::Twitter => "Twitter",
+            Self::Farcaster => "Farcaster",
             Self::ExternalChannel => "External Channel",
         }
     }
@@ -88,6 +94,10 @@ pub struct NormalizedMessage {
     /// Force safe mode for this message (e.g., non-admin Discord queries)
     #[serde(default)]
     pub force_safe_mode: bool,
+    /// The chat has other members besides the sender (group chat, public thread).
+    /// Replies to shared chats are redacted (see `channels::redaction`).
+    #[serde(default)]
+    pub shared_chat: bool,
     /// Platform role IDs the user holds (e.g. Discord role snowflakes).
     /// Used for role-based special role resolution.
     #[serde(default)]
@@ -97,6 +107,34 @@ pub struct NormalizedMessage {
     /// stored user message.
     #[serde(default)]
     pub chat_context: Option<String>,
+    /// Set when this message is a button press rather than typed text.
+    /// `text` then holds a readable description for the transcript.
+    #[serde(default)]
+    pub action: Option<ActionEvent>,
+    /// Files attached to the message (stored by reference with the user message)
+    #[serde(default)]
+    pub attachments: Vec<MessageAttachment>,
+}
+
+/// A button the agent attached to an outgoing message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionButton {
+    /// Text shown on the button
+    pub label: String,
+    /// Action identifier (`reply` sends the label back as a normal message)
+    pub action: String,
+    /// Structured data passed back with the action (e.g. `{"tx_id": 3}`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+}
+
+/// A button press delivered back to the dispatcher
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionEvent {
+    pub action: String,
+    pub label: String,
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
 }
 
 /// Handle to a running channel listener
@@ -132,6 +170,10 @@ pub struct DispatchResult {
     /// via a say_to_user WebSocket event. The frontend can use this ID to avoid
     /// rendering the same message twice.
     pub message_id: Option<String>,
+    /// Buttons to attach to the response (channels without buttons ignore them)
+    pub actions: Vec<ActionButton>,
+    /// Files generated by the execution (channels without uploads ignore them)
+    pub attachments: Vec<Artifact>,
 }
 
 impl DispatchResult {
@@ -140,6 +182,8 @@ impl DispatchResult {
             response,
             error: None,
             message_id: None,
+            actions: Vec::new(),
+            attachments: Vec::new(),
         }
     }
 
@@ -148,6 +192,8 @@ impl DispatchResult {
             response,
             error: None,
             message_id,
+            actions: Vec::new(),
+            attachments: Vec::new(),
         }
     }
 
@@ -156,6 +202,18 @@ impl DispatchResult {
             response: String::new(),
             error: Some(error),
             message_id: None,
+            actions: Vec::new(),
+            attachments: Vec::new(),
         }
     }
+
+    pub fn with_actions(mut self, actions: Vec<ActionButton>) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    pub fn with_attachments(mut self, attachments: Vec<Artifact>) -> Self {
+        self.attachments = attachments;
+        self
+    }
 }
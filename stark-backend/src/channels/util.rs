@@ -1,5 +1,7 @@
 //! Shared utilities for channel implementations.
 
+use crate::db::tables::artifacts::Artifact;
+
 /// Split a message into chunks respecting a platform's character limit.
 /// Splits on line boundaries; lines exceeding `max_len` are hard-split.
 pub fn split_message(text: &str, max_len: usize) -> Vec<String> {
@@ -43,6 +45,26 @@ pub fn split_message(text: &str, max_len: usize) -> Vec<String> {
     chunks
 }
 
+/// Load artifacts for upload as (file name, bytes), skipping files that are
+/// missing or larger than the platform's `max_bytes` upload limit.
+pub fn uploadable_artifacts(artifacts: &[Artifact], max_bytes: u64) -> Vec<(String, Vec<u8>)> {
+    artifacts
+        .iter()
+        .filter_map(|artifact| {
+            let path = artifact.absolute_path();
+            let size = std::fs::metadata(&path).ok()?.len();
+            if size > max_bytes {
+                log::info!(
+                    "Skipping artifact upload {} ({} bytes exceeds {} byte limit)",
+                    artifact.path, size, max_bytes
+                );
+                return None;
+            }
+            std::fs::read(&path).ok().map(|bytes| (artifact.name.clone(), bytes))
+        })
+        .collect()
+}
+
 /// Parse "Retry after Xs" from a platform API error string.
 /// Returns the number of seconds to wait, or None if not a rate-limit error.
 pub fn parse_retry_after(err: &str) -> Option<u64> {
@@ -0,0 +1,318 @@
+//! Circuit breakers around external integrations
+//!
+//! Every call to an external dependency (Alchemy, StarkHub, Twitter, Gmail,
+//! the AI provider) reports its outcome here. Transport errors, 5xx and 429
+//! responses count as failures; after `FAILURE_THRESHOLD` of them in a row the
+//! dependency's circuit opens and calls fail fast for `OPEN_SECS` instead of
+//! piling up behind a dead service. Callers with something better to do while
+//! a circuit is open take that path: RPC resolution skips Alchemy, StarkHub
+//! listings serve the last good response, and scheduled tweets stay queued.
+//! Once the open period is over a single trial call goes through (half-open);
+//! success closes the circuit, failure opens it again. States are reported by
+//! `/api/health`.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+pub const ALCHEMY: &str = "alchemy";
+pub const STARKHUB: &str = "starkhub";
+pub const TWITTER: &str = "twitter";
+pub const GMAIL: &str = "gmail";
+pub const AI_PROVIDER: &str = "ai_provider";
+
+/// Dependencies listed by the health endpoint
+pub const DEPENDENCIES: &[&str] = &[ALCHEMY, STARKHUB, TWITTER, GMAIL, AI_PROVIDER];
+
+/// Consecutive failures that open a circuit
+const FAILURE_THRESHOLD: u32 = 5;
+/// How long an open circuit refuses calls before letting a trial through
+const OPEN_SECS: u64 = 60;
+/// Outcomes kept for the reported failure rate
+const RATE_WINDOW: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    /// Recent outcomes, true = failure
+    recent: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    opened_at_utc: Option<DateTime<Utc>>,
+    /// When the half-open trial call went out; a trial that never reports back expires
+    trial_started: Option<Instant>,
+    calls: u64,
+    failures: u64,
+    last_error: Option<String>,
+    last_failure_at: Option<DateTime<Utc>>,
+}
+
+impl Breaker {
+    fn state(&self, now: Instant) -> BreakerState {
+        match self.opened_at {
+            None => BreakerState::Closed,
+            Some(at) if now.duration_since(at) < Duration::from_secs(OPEN_SECS) => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+
+    /// Ok if a call may go out now, else how long until the next trial
+    fn allow(&mut self, now: Instant) -> Result<(), Duration> {
+        match self.state(now) {
+            BreakerState::Closed => Ok(()),
+            BreakerState::Open => {
+                let elapsed = now.duration_since(self.opened_at.unwrap_or(now));
+                Err(Duration::from_secs(OPEN_SECS).saturating_sub(elapsed))
+            }
+            BreakerState::HalfOpen => match self.trial_started {
+                Some(at) if now.duration_since(at) < Duration::from_secs(OPEN_SECS) => Err(Duration::from_secs(1)),
+                _ => {
+                    self.trial_started = Some(now);
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    fn record(&mut self, failed: bool, error: Option<&str>, now: Instant) {
+        self.calls += 1;
+        self.recent.push_back(failed);
+        if self.recent.len() > RATE_WINDOW {
+            self.recent.pop_front();
+        }
+        self.trial_started = None;
+        if !failed {
+            self.consecutive_failures = 0;
+            self.opened_at = None;
+            self.opened_at_utc = None;
+            return;
+        }
+        self.failures += 1;
+        self.consecutive_failures += 1;
+        self.last_error = error.map(|e| e.chars().take(300).collect());
+        self.last_failure_at = Some(Utc::now());
+        // A failed trial re-opens at once; otherwise wait for the threshold
+        let reopen = self.opened_at.is_some();
+        if reopen || self.consecutive_failures >= FAILURE_THRESHOLD {
+            self.opened_at = Some(now);
+            self.opened_at_utc = Some(Utc::now());
+        }
+    }
+
+    fn failure_rate(&self) -> f64 {
+        if self.recent.is_empty() {
+            return 0.0;
+        }
+        self.recent.iter().filter(|f| **f).count() as f64 / self.recent.len() as f64
+    }
+}
+
+static BREAKERS: Lazy<Mutex<HashMap<&'static str, Breaker>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Last good responses, served by `with_fallback` while a circuit is open
+static LAST_GOOD: Lazy<Mutex<HashMap<(&'static str, String), Value>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn label(dependency: &str) -> &str {
+    match dependency {
+        ALCHEMY => "Alchemy",
+        STARKHUB => "StarkHub",
+        TWITTER => "Twitter",
+        GMAIL => "Gmail",
+        AI_PROVIDER => "The AI provider",
+        other => other,
+    }
+}
+
+/// Ok if a call to `dependency` may go out, else an explanation to return instead
+pub fn check(dependency: &'static str) -> Result<(), String> {
+    let mut breakers = BREAKERS.lock();
+    let breaker = breakers.entry(dependency).or_default();
+    breaker.allow(Instant::now()).map_err(|wait| {
+        format!(
+            "{} is temporarily unavailable after repeated failures (circuit open, retrying in {}s)",
+            label(dependency),
+            wait.as_secs().max(1)
+        )
+    })
+}
+
+/// Whether the circuit is open right now (no trial call is due yet)
+pub fn is_open(dependency: &'static str) -> bool {
+    BREAKERS
+        .lock()
+        .get(dependency)
+        .map(|b| b.state(Instant::now()) == BreakerState::Open)
+        .unwrap_or(false)
+}
+
+pub fn record_success(dependency: &'static str) {
+    BREAKERS.lock().entry(dependency).or_default().record(false, None, Instant::now());
+}
+
+pub fn record_failure(dependency: &'static str, error: &str) {
+    let mut breakers = BREAKERS.lock();
+    let breaker = breakers.entry(dependency).or_default();
+    let was_open = breaker.opened_at.is_some();
+    breaker.record(true, Some(error), Instant::now());
+    if !was_open && breaker.opened_at.is_some() {
+        log::warn!(
+            "[CIRCUIT_BREAKER] {} circuit opened after {} consecutive failures: {}",
+            dependency, breaker.consecutive_failures, error
+        );
+    }
+}
+
+/// Server errors and rate limiting count against a dependency; other 4xx are the caller's problem
+pub fn is_failure_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// `send()` that goes through a dependency's circuit breaker
+#[async_trait]
+pub trait GuardedSend {
+    async fn send_guarded(self, dependency: &'static str) -> Result<reqwest::Response, String>;
+}
+
+#[async_trait]
+impl GuardedSend for reqwest::RequestBuilder {
+    async fn send_guarded(self, dependency: &'static str) -> Result<reqwest::Response, String> {
+        check(dependency)?;
+        match self.send().await {
+            Ok(response) if is_failure_status(response.status()) => {
+                record_failure(dependency, &format!("HTTP {}", response.status()));
+                Ok(response)
+            }
+            Ok(response) => {
+                record_success(dependency);
+                Ok(response)
+            }
+            Err(e) => {
+                record_failure(dependency, &e.to_string());
+                Err(e.to_string())
+            }
+        }
+    }
+}
+
+/// Run a read through the breaker's last-good cache: successes are remembered
+/// under `key`, and a failure while the circuit is open serves the remembered value.
+pub async fn with_fallback<T, F>(dependency: &'static str, key: &str, fut: F) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = Result<T, String>>,
+{
+    match fut.await {
+        Ok(value) => {
+            if let Ok(json) = serde_json::to_value(&value) {
+                LAST_GOOD.lock().insert((dependency, key.to_string()), json);
+            }
+            Ok(value)
+        }
+        Err(e) if is_open(dependency) => {
+            let cached = LAST_GOOD.lock().get(&(dependency, key.to_string())).cloned();
+            match cached.and_then(|json| serde_json::from_value(json).ok()) {
+                Some(value) => {
+                    log::info!("[CIRCUIT_BREAKER] {} unavailable, serving cached '{}'", dependency, key);
+                    Ok(value)
+                }
+                None => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// A breaker's state for the health endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerStatus {
+    pub name: &'static str,
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    /// Share of the last calls (up to 20) that failed
+    pub failure_rate: f64,
+    pub calls: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    pub opened_at: Option<DateTime<Utc>>,
+}
+
+/// State of every known dependency
+pub fn snapshot() -> Vec<BreakerStatus> {
+    let breakers = BREAKERS.lock();
+    let now = Instant::now();
+    DEPENDENCIES
+        .iter()
+        .map(|name| match breakers.get(name) {
+            Some(b) => BreakerStatus {
+                name,
+                state: b.state(now),
+                consecutive_failures: b.consecutive_failures,
+                failure_rate: (b.failure_rate() * 100.0).round() / 100.0,
+                calls: b.calls,
+                failures: b.failures,
+                last_error: b.last_error.clone(),
+                last_failure_at: b.last_failure_at,
+                opened_at: b.opened_at_utc,
+            },
+            None => BreakerStatus {
+                name,
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                failure_rate: 0.0,
+                calls: 0,
+                failures: 0,
+                last_error: None,
+                last_failure_at: None,
+                opened_at: None,
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold_and_recovers_through_trial() {
+        let mut breaker = Breaker::default();
+        let start = Instant::now();
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            breaker.record(true, Some("HTTP 503"), start);
+        }
+        assert_eq!(breaker.state(start), BreakerState::Closed);
+        breaker.record(true, Some("HTTP 503"), start);
+        assert_eq!(breaker.state(start), BreakerState::Open);
+        assert!(breaker.allow(start).is_err());
+
+        // After the open period one trial goes through; a failure re-opens at once
+        let later = start + Duration::from_secs(OPEN_SECS + 1);
+        assert!(breaker.allow(later).is_ok());
+        assert!(breaker.allow(later).is_err());
+        breaker.record(true, Some("timeout"), later);
+        assert_eq!(breaker.state(later), BreakerState::Open);
+
+        let much_later = later + Duration::from_secs(OPEN_SECS + 1);
+        assert!(breaker.allow(much_later).is_ok());
+        breaker.record(false, None, much_later);
+        assert_eq!(breaker.state(much_later), BreakerState::Closed);
+        assert_eq!(breaker.consecutive_failures, 0);
+        assert!(breaker.failure_rate() > 0.8);
+    }
+}
@@ -0,0 +1,320 @@
+//! Clustered deployment support
+//!
+//! Several backend instances can share one database for high availability.
+//! With `STARK_CLUSTER_MODE=1`:
+//! - each instance registers itself (`cluster_instances`) and tags its logs with
+//!   its instance ID (`STARK_INSTANCE_ID`, default `<hostname>-<random>`)
+//! - singleton work (cron jobs, heartbeats, module services such as the wallet
+//!   monitor) only runs on the instance holding the matching leader lease
+//! - conversations are serialized cluster-wide with a per-lane execution lock,
+//!   on top of the in-process session lanes
+//! - only the instance holding the signer lease signs transactions: nonce
+//!   reservations live in that process's memory, so two signers could hand
+//!   out the same nonce
+//!
+//! With cluster mode off every instance is its own leader and locks are no-ops,
+//! so single-instance deployments behave exactly as before.
+
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::RwLock;
+
+use crate::db::Database;
+
+pub const CLUSTER_MODE_ENV: &str = "STARK_CLUSTER_MODE";
+pub const INSTANCE_ID_ENV: &str = "STARK_INSTANCE_ID";
+
+/// Leader lease for the scheduler (cron jobs + heartbeats)
+pub const LEASE_SCHEDULER: &str = "leader:scheduler";
+/// Leader lease for module services (wallet monitor etc.)
+pub const LEASE_MODULE_SERVICES: &str = "leader:module_services";
+/// Leader lease for signing transactions (and so reserving nonces)
+pub const LEASE_SIGNER: &str = "leader:signer";
+/// All leader leases every instance campaigns for
+const LEADER_LEASES: &[&str] = &[LEASE_SCHEDULER, LEASE_MODULE_SERVICES, LEASE_SIGNER];
+
+/// Leases expire if not renewed within this window (failover time)
+const LEASE_TTL_SECS: i64 = 30;
+/// How often leases and the instance heartbeat are renewed
+const LEASE_RENEW_SECS: u64 = 10;
+/// Poll interval while waiting for another instance to finish a conversation
+const EXEC_LOCK_POLL_MS: u64 = 500;
+
+static INSTANCE_ID: OnceLock<String> = OnceLock::new();
+/// The process's coordinator, for code that has no handle to it (nonce reservations)
+static CLUSTER: OnceLock<Arc<Cluster>> = OnceLock::new();
+
+/// Whether cluster mode is enabled via env
+pub fn cluster_mode_enabled() -> bool {
+    std::env::var(CLUSTER_MODE_ENV)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// This process's instance ID (stable for the life of the process)
+pub fn instance_id() -> &'static str {
+    INSTANCE_ID.get_or_init(|| {
+        std::env::var(INSTANCE_ID_ENV)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| {
+                let suffix: u32 = rand::random();
+                format!("{}-{:08x}", hostname(), suffix)
+            })
+    })
+}
+
+/// Make `cluster` the process's coordinator (once, at startup)
+pub fn install(cluster: Arc<Cluster>) {
+    let _ = CLUSTER.set(cluster);
+}
+
+/// Refuse to sign unless this instance is the cluster's signer
+pub fn check_signer() -> Result<(), String> {
+    signer_check(CLUSTER.get().map(Arc::as_ref))
+}
+
+fn signer_check(cluster: Option<&Cluster>) -> Result<(), String> {
+    match cluster {
+        Some(cluster) if !cluster.is_leader(LEASE_SIGNER) => Err(format!(
+            "This instance ({}) doesn't hold the cluster's {} lease, so it can't sign transactions \
+             without risking a nonce another instance already used. Retry; the signer instance handles it.",
+            cluster.instance_id(),
+            LEASE_SIGNER
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "starkbot".to_string())
+}
+
+/// Cluster coordinator for this instance
+pub struct Cluster {
+    db: Arc<Database>,
+    enabled: bool,
+    /// Leader leases this instance currently holds
+    held: RwLock<HashSet<&'static str>>,
+}
+
+impl Cluster {
+    pub fn new(db: Arc<Database>, enabled: bool) -> Self {
+        Self {
+            db,
+            enabled,
+            held: RwLock::new(HashSet::new()),
+        }
+    }
+
+    pub fn from_env(db: Arc<Database>) -> Self {
+        Self::new(db, cluster_mode_enabled())
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn instance_id(&self) -> &'static str {
+        instance_id()
+    }
+
+    /// Whether this instance should run the singleton work guarded by `lease`
+    pub fn is_leader(&self, lease: &str) -> bool {
+        !self.enabled || self.held.read().contains(lease)
+    }
+
+    /// Renew the instance heartbeat and campaign for every leader lease.
+    /// Returns the leases gained in this round.
+    pub fn campaign(&self) -> Vec<&'static str> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        if let Err(e) = self.db.heartbeat_cluster_instance(
+            self.instance_id(),
+            &hostname(),
+            crate::controllers::health::VERSION,
+        ) {
+            log::warn!("[CLUSTER] Failed to record instance heartbeat: {}", e);
+        }
+
+        let mut gained = Vec::new();
+        for &lease in LEADER_LEASES {
+            let holds = match self.db.try_acquire_lease(lease, self.instance_id(), LEASE_TTL_SECS) {
+                Ok(holds) => holds,
+                Err(e) => {
+                    // Can't reach the DB — assume we lost it rather than risk a split brain
+                    log::warn!("[CLUSTER] Failed to renew lease {}: {}", lease, e);
+                    false
+                }
+            };
+            let mut held = self.held.write();
+            if holds && held.insert(lease) {
+                log::info!("[CLUSTER] Instance {} elected leader for {}", self.instance_id(), lease);
+                gained.push(lease);
+            } else if !holds && held.remove(lease) {
+                log::warn!("[CLUSTER] Instance {} lost leadership of {}", self.instance_id(), lease);
+            }
+        }
+        gained
+    }
+
+    /// Acquire the cluster-wide execution lock for a conversation lane, waiting
+    /// while another instance holds it. Returns None when cluster mode is off.
+    pub async fn acquire_execution_lock(&self, lane_key: &str) -> Option<ExecutionLock> {
+        if !self.enabled {
+            return None;
+        }
+        let name = format!("exec:{}", lane_key);
+        let mut waited = false;
+        loop {
+            match self.db.try_acquire_lease(&name, self.instance_id(), LEASE_TTL_SECS) {
+                Ok(true) => break,
+                Ok(false) => {
+                    if !waited {
+                        log::info!("[CLUSTER] {} is executing on another instance, waiting", lane_key);
+                        waited = true;
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(EXEC_LOCK_POLL_MS)).await;
+                }
+                Err(e) => {
+                    // Fail open: the in-process lane still serializes this instance
+                    log::warn!("[CLUSTER] Execution lock unavailable for {}: {}", lane_key, e);
+                    return None;
+                }
+            }
+        }
+
+        // Keep the lease alive for as long as the execution runs
+        let db = self.db.clone();
+        let renew_name = name.clone();
+        let renewer = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(LEASE_RENEW_SECS));
+            interval.tick().await; // skip immediate tick
+            loop {
+                interval.tick().await;
+                if let Err(e) = db.try_acquire_lease(&renew_name, instance_id(), LEASE_TTL_SECS) {
+                    log::warn!("[CLUSTER] Failed to renew execution lock {}: {}", renew_name, e);
+                }
+            }
+        });
+
+        Some(ExecutionLock {
+            db: self.db.clone(),
+            name,
+            renewer,
+        })
+    }
+
+    /// Release everything this instance holds (graceful shutdown)
+    pub fn shutdown(&self) {
+        if !self.enabled {
+            return;
+        }
+        self.held.write().clear();
+        let _ = self.db.release_all_leases(self.instance_id());
+        let _ = self.db.remove_cluster_instance(self.instance_id());
+        log::info!("[CLUSTER] Instance {} released its leases", self.instance_id());
+    }
+}
+
+/// Held cluster-wide execution lock; released on drop
+pub struct ExecutionLock {
+    db: Arc<Database>,
+    name: String,
+    renewer: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for ExecutionLock {
+    fn drop(&mut self) {
+        self.renewer.abort();
+        if let Err(e) = self.db.release_lease(&self.name, instance_id()) {
+            log::warn!("[CLUSTER] Failed to release execution lock {}: {}", self.name, e);
+        }
+    }
+}
+
+/// Spawn the worker that renews leases and the instance heartbeat.
+/// `on_elected` is called with each leader lease this instance gains.
+pub fn spawn_cluster_worker<F>(cluster: Arc<Cluster>, on_elected: F) -> tokio::task::JoinHandle<()>
+where
+    F: Fn(&'static str) + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(LEASE_RENEW_SECS));
+        loop {
+            interval.tick().await;
+            for lease in cluster.campaign() {
+                on_elected(lease);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_cluster_is_always_leader() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let cluster = Cluster::new(db, false);
+        assert!(cluster.is_leader(LEASE_SCHEDULER));
+        assert!(cluster.campaign().is_empty());
+    }
+
+    #[test]
+    fn test_only_the_signer_instance_signs() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        // No coordinator installed or cluster mode off: sign as before
+        assert!(signer_check(None).is_ok());
+        assert!(signer_check(Some(&Cluster::new(db.clone(), false))).is_ok());
+
+        let follower = Cluster::new(db.clone(), true);
+        assert!(db.try_acquire_lease(LEASE_SIGNER, "other-node", 30).unwrap());
+        follower.campaign();
+        assert!(signer_check(Some(&follower)).unwrap_err().contains(LEASE_SIGNER));
+
+        db.release_lease(LEASE_SIGNER, "other-node").unwrap();
+        follower.campaign();
+        assert!(signer_check(Some(&follower)).is_ok());
+    }
+
+    #[test]
+    fn test_campaign_elects_single_leader() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let cluster = Cluster::new(db.clone(), true);
+        assert!(!cluster.is_leader(LEASE_SCHEDULER));
+        assert_eq!(cluster.campaign(), LEADER_LEASES.to_vec());
+        assert!(cluster.is_leader(LEASE_SCHEDULER));
+        // Renewal doesn't report the lease as newly gained
+        assert!(cluster.campaign().is_empty());
+
+        // Another instance can't take a live lease
+        assert!(!db.try_acquire_lease(LEASE_SCHEDULER, "other-node", 30).unwrap());
+
+        cluster.shutdown();
+        assert!(!cluster.is_leader(LEASE_SCHEDULER));
+        assert!(db.try_acquire_lease(LEASE_SCHEDULER, "other-node", 30).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_execution_lock_released_on_drop() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let cluster = Cluster::new(db.clone(), true);
+        let lock = cluster.acquire_execution_lock("web:0:abc").await;
+        assert!(lock.is_some());
+        assert!(!db.try_acquire_lease("exec:web:0:abc", "other-node", 30).unwrap());
+        drop(lock);
+        assert!(db.try_acquire_lease("exec:web:0:abc", "other-node", 30).unwrap());
+
+        assert!(Cluster::new(db, false).acquire_execution_lock("web:0:abc").await.is_none());
+    }
+}
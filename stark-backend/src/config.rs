@@ -20,6 +20,12 @@ pub mod env_vars {
     pub const PUBLIC_URL: &str = "STARK_PUBLIC_URL";
     // Disk quota (0 = disabled)
     pub const DISK_QUOTA_MB: &str = "STARK_DISK_QUOTA_MB";
+    // Tool results above this many tokens are summarized (0 = disabled)
+    pub const TOOL_OUTPUT_TOKEN_LIMIT: &str = "STARK_TOOL_OUTPUT_TOKEN_LIMIT";
+    // Hours a review-before-send draft waits for approval before it expires
+    pub const OUTBOX_DRAFT_TTL_HOURS: &str = "STARK_OUTBOX_DRAFT_TTL_HOURS";
+    // Per-execution scratch directory size cap in MB (0 = only the global disk quota applies)
+    pub const SCRATCH_MAX_MB: &str = "STARK_SCRATCH_MAX_MB";
     // QMD Memory configuration (simplified file-based memory system)
     pub const MEMORY_DIR: &str = "STARK_MEMORY_DIR";
     pub const MEMORY_REINDEX_INTERVAL_SECS: &str = "STARK_MEMORY_REINDEX_INTERVAL_SECS";
@@ -27,6 +33,13 @@ pub mod env_vars {
     pub const MEMORY_ENABLE_PRE_COMPACTION_FLUSH: &str = "STARK_MEMORY_ENABLE_PRE_COMPACTION_FLUSH";
     pub const MEMORY_ENABLE_CROSS_SESSION: &str = "STARK_MEMORY_ENABLE_CROSS_SESSION";
     pub const MEMORY_CROSS_SESSION_LIMIT: &str = "STARK_MEMORY_CROSS_SESSION_LIMIT";
+    // Module process sandbox: "auto" (bubblewrap when installed), "bwrap" (required) or "off"
+    pub const MODULE_SANDBOX: &str = "STARK_MODULE_SANDBOX";
+    /// Unprivileged uid/gid module processes run as (requires the bot to run as root)
+    pub const MODULE_UID: &str = "STARK_MODULE_UID";
+    pub const MODULE_GID: &str = "STARK_MODULE_GID";
+    /// GitHub-style "latest release" endpoint checked by the self-updater
+    pub const RELEASES_URL: &str = "STARK_RELEASES_URL";
 }
 
 /// Default values
@@ -40,6 +53,10 @@ pub mod defaults {
     pub const PUBLIC_DIR: &str = "public";
     pub const MEMORY_DIR: &str = "memory";
     pub const DISK_QUOTA_MB: u64 = 1024;
+    pub const TOOL_OUTPUT_TOKEN_LIMIT: usize = 6000;
+    pub const OUTBOX_DRAFT_TTL_HOURS: i64 = 24;
+    pub const SCRATCH_MAX_MB: u64 = 256;
+    pub const RELEASES_URL: &str = "https://api.github.com/repos/ethereumdegen/stark-bot/releases/latest";
 }
 
 /// Returns the absolute path to the stark-backend directory.
@@ -397,6 +414,41 @@ pub fn disk_quota_mb() -> u64 {
         .unwrap_or(defaults::DISK_QUOTA_MB)
 }
 
+/// Get the tool output token budget (0 = disabled)
+pub fn tool_output_token_limit() -> usize {
+    env::var(env_vars::TOOL_OUTPUT_TOKEN_LIMIT)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::TOOL_OUTPUT_TOKEN_LIMIT)
+}
+
+/// Get how long outbox drafts wait for approval (hours, at least 1)
+pub fn outbox_draft_ttl_hours() -> i64 {
+    env::var(env_vars::OUTBOX_DRAFT_TTL_HOURS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::OUTBOX_DRAFT_TTL_HOURS)
+        .max(1)
+}
+
+/// Get the per-execution scratch directory size cap in bytes (0 = unlimited)
+pub fn scratch_max_bytes() -> u64 {
+    env::var(env_vars::SCRATCH_MAX_MB)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::SCRATCH_MAX_MB)
+        * 1024
+        * 1024
+}
+
+/// Get the release endpoint the self-updater checks for new versions
+pub fn releases_url() -> String {
+    env::var(env_vars::RELEASES_URL)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| defaults::RELEASES_URL.to_string())
+}
+
 /// Get the burner wallet private key from environment (for tools)
 pub fn burner_wallet_private_key() -> Option<String> {
     env::var(env_vars::BURNER_WALLET_PRIVATE_KEY).ok()
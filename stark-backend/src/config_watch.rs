@@ -0,0 +1,176 @@
+//! Live configuration reload
+//!
+//! Controllers that write settings publish a [`ConfigChange`] on the shared
+//! [`ConfigWatch`] bus. The reload worker subscribes and refreshes everything
+//! that captured the old value at startup:
+//! - `ALCHEMY_API_KEY` → the global RPC resolver
+//! - channel config / settings → restart the running listener with new credentials
+//! - Twitter API keys → restart running Twitter channels
+//!
+//! The dispatcher, safe-mode limiter and Gmail handlers already read
+//! AgentSettings, bot settings, tool configs and Gmail credentials per message,
+//! so they need no rebuild — but every change is re-broadcast to the gateway as
+//! `config.changed` so the UI and any listener can refetch.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::channels::ChannelManager;
+use crate::controllers::api_keys::ApiKeyId;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::tools::rpc_config;
+
+/// Buffered changes per subscriber before it lags (and falls back to a full reload)
+const CONFIG_BUS_CAPACITY: usize = 64;
+
+/// A settings change published by whoever wrote it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConfigChange {
+    /// Active AI endpoint / model settings
+    AgentSettings,
+    /// Bot settings (RPC provider, custom endpoints, embeddings URL, ...)
+    BotSettings,
+    /// An API key was added, replaced or deleted
+    ApiKey { key_name: String },
+    /// A channel's credentials, enabled flag or settings changed
+    Channel { channel_id: i64 },
+    /// Tool profile / allow-lists (global when `channel_id` is None)
+    ToolConfig { channel_id: Option<i64> },
+    /// Gmail integration credentials
+    Gmail,
+}
+
+/// Notification bus for settings changes
+pub struct ConfigWatch {
+    tx: broadcast::Sender<ConfigChange>,
+}
+
+impl ConfigWatch {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(CONFIG_BUS_CAPACITY);
+        Self { tx }
+    }
+
+    /// Subscribe to future changes
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigChange> {
+        self.tx.subscribe()
+    }
+
+    /// Publish a change. Never blocks; a bus with no subscribers just drops it.
+    pub fn notify(&self, change: ConfigChange) {
+        log::debug!("[CONFIG] Change published: {:?}", change);
+        let _ = self.tx.send(change);
+    }
+}
+
+impl Default for ConfigWatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Load the Alchemy key (DB first, then env) into the global RPC resolver
+pub fn reload_alchemy_api_key(db: &Database) {
+    let key = db.get_api_key(ApiKeyId::AlchemyApiKey.as_str()).ok().flatten()
+        .map(|k| k.api_key)
+        .or_else(|| std::env::var("ALCHEMY_API_KEY").ok().filter(|v| !v.is_empty()));
+    rpc_config::set_alchemy_api_key(key);
+}
+
+/// Apply one change to everything that cached the old value
+async fn apply_change(db: &Database, channel_manager: &ChannelManager, change: &ConfigChange) {
+    match change {
+        ConfigChange::ApiKey { key_name } => {
+            if key_name == ApiKeyId::AlchemyApiKey.as_str() {
+                reload_alchemy_api_key(db);
+                log::info!(
+                    "[CONFIG] Alchemy API key reloaded ({})",
+                    if rpc_config::get_alchemy_api_key().is_some() { "set" } else { "cleared" }
+                );
+            } else if key_name.starts_with("TWITTER_") {
+                for channel_id in channel_manager.running_channel_ids_by_type("twitter") {
+                    restart_channel(channel_manager, channel_id).await;
+                }
+            } else if key_name == "NEYNAR_API_KEY" || key_name == "FARCASTER_SIGNER_UUID" {
+                for channel_id in channel_manager.running_channel_ids_by_type("farcaster") {
+                    restart_channel(channel_manager, channel_id).await;
+                }
+            }
+        }
+        ConfigChange::Channel { channel_id } => {
+            restart_channel(channel_manager, *channel_id).await;
+        }
+        ConfigChange::BotSettings => {
+            if let Ok(settings) = db.get_bot_settings() {
+                rpc_config::set_custom_rpc_endpoints(settings.custom_rpc_endpoints.unwrap_or_default());
+            }
+        }
+        // Read per message by the dispatcher / Gmail handlers — nothing to rebuild
+        ConfigChange::AgentSettings | ConfigChange::ToolConfig { .. } | ConfigChange::Gmail => {}
+    }
+}
+
+async fn restart_channel(channel_manager: &ChannelManager, channel_id: i64) {
+    match channel_manager.restart_channel(channel_id).await {
+        Ok(true) => log::info!("[CONFIG] Restarted channel {} with updated config", channel_id),
+        Ok(false) => {}
+        Err(e) => log::error!("[CONFIG] Failed to restart channel {}: {}", channel_id, e),
+    }
+}
+
+/// Spawn the worker that applies config changes as they are published.
+pub fn spawn_config_reload_worker(
+    watch: Arc<ConfigWatch>,
+    db: Arc<Database>,
+    channel_manager: Arc<ChannelManager>,
+    broadcaster: Arc<EventBroadcaster>,
+) -> tokio::task::JoinHandle<()> {
+    let mut rx = watch.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(change) => {
+                    apply_change(&db, &channel_manager, &change).await;
+                    broadcaster.broadcast(GatewayEvent::config_changed(
+                        serde_json::to_value(&change).unwrap_or_default(),
+                    ));
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    // Individual changes were lost — refresh the global caches wholesale
+                    log::warn!("[CONFIG] Reload worker lagged by {} change(s), reloading globals", missed);
+                    apply_change(&db, &channel_manager, &ConfigChange::BotSettings).await;
+                    reload_alchemy_api_key(&db);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_changes() {
+        let watch = ConfigWatch::new();
+        // Publishing with no subscribers is a no-op
+        watch.notify(ConfigChange::AgentSettings);
+
+        let mut rx = watch.subscribe();
+        watch.notify(ConfigChange::Channel { channel_id: 7 });
+        assert_eq!(rx.recv().await.unwrap(), ConfigChange::Channel { channel_id: 7 });
+    }
+
+    #[test]
+    fn test_change_serializes_with_kind_tag() {
+        let value = serde_json::to_value(ConfigChange::ApiKey { key_name: "ALCHEMY_API_KEY".into() }).unwrap();
+        assert_eq!(value["kind"], "api_key");
+        assert_eq!(value["key_name"], "ALCHEMY_API_KEY");
+    }
+}
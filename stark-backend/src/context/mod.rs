@@ -8,8 +8,11 @@
 //! - Pre-compaction memory flush (AI extracts memories before summarization)
 //! - Cross-session memory integration
 //! - Session memory hooks (saving session summaries on reset)
+//! - Scheduled pre-warming of expensive context ahead of busy hours
 
+pub mod prewarm;
 pub mod tokenizer;
+pub mod tool_output;
 
 use crate::ai::{AiClient, Message, MessageRole};
 use crate::config::MemoryConfig;
@@ -110,6 +113,31 @@ impl Default for ThreeTierCompactionConfig {
     }
 }
 
+impl ThreeTierCompactionConfig {
+    /// Copy with a different background tier; the higher tiers never drop below it
+    pub fn with_background_threshold(&self, threshold: f64) -> Self {
+        Self {
+            background_threshold: threshold,
+            aggressive_threshold: self.aggressive_threshold.max(threshold),
+            emergency_threshold: self.emergency_threshold.max(threshold),
+            ..self.clone()
+        }
+    }
+
+    /// Tier for a context fullness ratio (tokens used / tokens available)
+    pub fn level_for_ratio(&self, ratio: f64) -> CompactionLevel {
+        if ratio >= self.emergency_threshold {
+            CompactionLevel::Emergency
+        } else if ratio >= self.aggressive_threshold {
+            CompactionLevel::Aggressive
+        } else if ratio >= self.background_threshold {
+            CompactionLevel::Background
+        } else {
+            CompactionLevel::None
+        }
+    }
+}
+
 /// Estimate token count for a string using content-aware estimation
 /// This provides more accurate estimates than simple character counting
 /// by considering content type (JSON, code, prose)
@@ -404,7 +432,7 @@ impl ContextManager {
                     DbMessageRole::ToolCall => "Tool Call",
                     DbMessageRole::ToolResult => "Tool Result",
                 };
-                format!("{}: {}", role, m.content)
+                format!("{}: {}", role, m.content_with_attachments())
             })
             .collect::<Vec<_>>()
             .join("\n\n");
@@ -493,7 +521,7 @@ impl ContextManager {
                     DbMessageRole::Assistant => "Assistant",
                     _ => unreachable!(),
                 };
-                format!("{}: {}", role, m.content)
+                format!("{}: {}", role, m.content_with_attachments())
             })
             .collect::<Vec<_>>()
             .join("\n\n");
@@ -626,7 +654,7 @@ impl ContextManager {
                     DbMessageRole::ToolCall => "Tool Call",
                     DbMessageRole::ToolResult => "Tool Result",
                 };
-                format!("{}: {}", role, m.content)
+                format!("{}: {}", role, m.content_with_attachments())
             })
             .collect::<Vec<_>>()
             .join("\n\n");
@@ -851,7 +879,17 @@ impl ContextManager {
 
     /// Check the compaction urgency level based on current token usage
     pub fn check_compaction_level(&self, session_id: i64) -> CompactionLevel {
-        let config = &self.compaction_config;
+        self.check_compaction_level_with(session_id, None)
+    }
+
+    /// Like `check_compaction_level`, with the background tier replaced by
+    /// `background_threshold` (per-channel override). The higher tiers never
+    /// drop below it.
+    pub fn check_compaction_level_with(&self, session_id: i64, background_threshold: Option<f64>) -> CompactionLevel {
+        let config = match background_threshold {
+            Some(threshold) => self.compaction_config.with_background_threshold(threshold),
+            None => self.compaction_config.clone(),
+        };
 
         let session = self.get_session_cached(session_id);
         let max_tokens = session.as_ref()
@@ -865,17 +903,7 @@ impl ContextManager {
         let current = session
             .map(|s| s.context_tokens)
             .unwrap_or(0);
-        let ratio = current as f64 / available as f64;
-
-        if ratio >= config.emergency_threshold {
-            CompactionLevel::Emergency
-        } else if ratio >= config.aggressive_threshold {
-            CompactionLevel::Aggressive
-        } else if ratio >= config.background_threshold {
-            CompactionLevel::Background
-        } else {
-            CompactionLevel::None
-        }
+        config.level_for_ratio(current as f64 / available as f64)
     }
 
     /// Emergency compaction: synchronously hard-drop oldest 50% of messages
@@ -912,6 +940,60 @@ impl ContextManager {
         Ok(deleted as usize)
     }
 
+    /// Current context token count for a session
+    pub fn context_tokens(&self, session_id: i64) -> i32 {
+        self.get_session_cached(session_id).map(|s| s.context_tokens).unwrap_or(0)
+    }
+
+    /// Background tier in effect (before any per-channel override)
+    pub fn background_threshold(&self) -> f64 {
+        self.compaction_config.background_threshold
+    }
+
+    /// Level the automatic trigger acts on: the tier from token usage, or
+    /// `Background` once the sliding-window trigger is reached.
+    pub fn auto_compaction_level(&self, session_id: i64, background_threshold: Option<f64>) -> CompactionLevel {
+        match self.check_compaction_level_with(session_id, background_threshold) {
+            CompactionLevel::None if self.needs_incremental_compaction(session_id) => CompactionLevel::Background,
+            level => level,
+        }
+    }
+
+    /// Run the compaction for `level`. Emergency hard-drops; otherwise the
+    /// sliding window is tried first, falling back to full compaction and then
+    /// to an emergency drop. Returns the kind of compaction performed.
+    pub async fn run_auto_compaction(
+        &self,
+        session_id: i64,
+        level: CompactionLevel,
+        client: &AiClient,
+        identity_id: Option<&str>,
+    ) -> Result<&'static str, String> {
+        match level {
+            CompactionLevel::None => Ok("none"),
+            CompactionLevel::Emergency => {
+                self.compact_emergency(session_id)?;
+                Ok("emergency")
+            }
+            CompactionLevel::Background | CompactionLevel::Aggressive => {
+                if level == CompactionLevel::Background {
+                    match self.compact_incremental(session_id, client, identity_id).await {
+                        Ok(_) => return Ok("incremental"),
+                        Err(e) => log::warn!("[COMPACTION] Incremental compaction failed: {}, trying full", e),
+                    }
+                }
+                match self.compact_session(session_id, client, identity_id, None).await {
+                    Ok(_) => Ok("full"),
+                    Err(e) => {
+                        log::error!("[COMPACTION] Full compaction failed: {}, trying emergency", e);
+                        self.compact_emergency(session_id)?;
+                        Ok("emergency")
+                    }
+                }
+            }
+        }
+    }
+
     /// Tiered compaction: determine level and apply appropriate strategy
     pub async fn compact_tiered(
         &self,
@@ -952,6 +1034,19 @@ pub async fn save_session_memory(
     message_limit: i32,
     agent_subtype: Option<&str>,
 ) -> Result<(), String> {
+    let (title, summary) = summarize_session(db, client, session_id, message_limit).await?;
+    store_session_summary(db, &title, &summary, identity_id, session_id, "session_reset", agent_subtype)?;
+    Ok(())
+}
+
+/// Summarize a session's recent messages into a (title, summary) pair.
+/// Leaves the session untouched.
+pub async fn summarize_session(
+    db: &Arc<Database>,
+    client: &AiClient,
+    session_id: i64,
+    message_limit: i32,
+) -> Result<(String, String), String> {
     // Get recent messages from the session
     let messages = db.get_recent_session_messages(session_id, message_limit)
         .map_err(|e| format!("Failed to get session messages: {}", e))?;
@@ -960,7 +1055,7 @@ pub async fn save_session_memory(
         return Err("No messages to summarize".to_string());
     }
 
-    log::info!("[SESSION_MEMORY] Saving session memory for {} messages", messages.len());
+    log::info!("[SESSION_MEMORY] Summarizing {} messages of session {}", messages.len(), session_id);
 
     // Build conversation text (all roles — the AI summarizes into a single
     // TITLE+SUMMARY entry so individual tool results don't leak into memory)
@@ -973,7 +1068,7 @@ pub async fn save_session_memory(
                 DbMessageRole::ToolCall => "Tool Call",
                 DbMessageRole::ToolResult => "Tool Result",
             };
-            format!("{}: {}", role, m.content)
+            format!("{}: {}", role, m.content_with_attachments())
         })
         .collect::<Vec<_>>()
         .join("\n\n");
@@ -1005,20 +1100,29 @@ pub async fn save_session_memory(
         .map_err(|e| format!("Failed to generate session summary: {}", e))?;
 
     // Parse title and summary from response
-    let (title, summary) = parse_title_summary(&response);
+    Ok(parse_title_summary(&response))
+}
 
-    // Write to DB memories table
+/// Write a session summary to today's daily log. Returns the memory ID.
+pub fn store_session_summary(
+    db: &Database,
+    title: &str,
+    summary: &str,
+    identity_id: Option<&str>,
+    session_id: i64,
+    source_type: &str,
+    agent_subtype: Option<&str>,
+) -> Result<i64, String> {
     let content = format!("### {}\n{}", title, summary);
     let today = Utc::now().format("%Y-%m-%d").to_string();
-    db.insert_memory(
+    let memory_id = db.insert_memory(
         "daily_log",
         &content,
         None, None, 5, identity_id, Some(session_id), None, None,
-        Some("session_reset"), Some(&today), agent_subtype,
+        Some(source_type), Some(&today), agent_subtype,
     ).map_err(|e| format!("Failed to write session summary: {}", e))?;
     log::info!("[SESSION_MEMORY] Saved session summary to daily log: {}", title);
-
-    Ok(())
+    Ok(memory_id)
 }
 
 /// Truncate a summary to approximately max_words, breaking at word boundaries
@@ -1089,4 +1193,20 @@ mod tests {
         assert_eq!(title, "Discussion about Rust programming");
         assert!(summary.contains("ownership"));
     }
+
+    #[test]
+    fn test_background_threshold_override() {
+        let config = ThreeTierCompactionConfig::default();
+        assert_eq!(config.level_for_ratio(0.70), CompactionLevel::None);
+        assert_eq!(config.level_for_ratio(0.90), CompactionLevel::Aggressive);
+
+        let early = config.with_background_threshold(0.60);
+        assert_eq!(early.level_for_ratio(0.70), CompactionLevel::Background);
+        assert_eq!(early.level_for_ratio(0.96), CompactionLevel::Emergency);
+
+        // A late background tier pushes the higher tiers up with it
+        let late = config.with_background_threshold(0.90);
+        assert_eq!(late.level_for_ratio(0.87), CompactionLevel::None);
+        assert_eq!(late.level_for_ratio(0.91), CompactionLevel::Aggressive);
+    }
 }
@@ -0,0 +1,351 @@
+//! Scheduled context pre-warming
+//!
+//! Ahead of the configured active hours, a background worker renders the
+//! context that usually costs several tool calls to gather — portfolio
+//! snapshot (trade journal marked to market), calendar (upcoming cron jobs and
+//! scheduled posts) and watchlist (price-triggered strategies at current
+//! prices) — into `context_prewarm_cache`. The system prompt includes fresh
+//! sections with their age so the model knows when to fetch live data instead.
+
+use crate::db::tables::context_prewarm::{PrewarmConfig, PrewarmEntry};
+use crate::db::tables::social_posts::SOCIAL_POST_APPROVED;
+use crate::db::Database;
+use crate::strategies::StrategyTrigger;
+use chrono::{DateTime, Duration, Local, Timelike, Utc};
+use std::sync::Arc;
+
+pub const SECTION_PORTFOLIO: &str = "portfolio";
+pub const SECTION_CALENDAR: &str = "calendar";
+pub const SECTION_WATCHLIST: &str = "watchlist";
+
+pub const ALL_SECTIONS: &[&str] = &[SECTION_PORTFOLIO, SECTION_CALENDAR, SECTION_WATCHLIST];
+
+/// Cached sections older than this are left out of the prompt
+const MAX_AGE_MINUTES: i64 = 180;
+/// How far ahead the calendar looks
+const CALENDAR_HORIZON_HOURS: i64 = 24;
+const MAX_ITEMS: usize = 10;
+
+/// Parse "HH:MM-HH:MM" windows (or bare hours, "9-17") into minute-of-day
+/// ranges. A window whose end is before its start wraps past midnight.
+pub fn parse_active_hours(spec: &str) -> Result<Vec<(u32, u32)>, String> {
+    fn minute_of_day(t: &str) -> Result<u32, String> {
+        let t = t.trim();
+        let (h, m) = match t.split_once(':') {
+            Some((h, m)) => (h, m),
+            None => (t, "0"),
+        };
+        let h: u32 = h.trim().parse().map_err(|_| format!("Invalid hour in '{}'", t))?;
+        let m: u32 = m.trim().parse().map_err(|_| format!("Invalid minute in '{}'", t))?;
+        if h > 24 || m > 59 || (h == 24 && m > 0) {
+            return Err(format!("Time out of range: '{}'", t));
+        }
+        Ok(h * 60 + m)
+    }
+
+    let mut windows = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = part
+            .split_once('-')
+            .ok_or_else(|| format!("Window '{}' must look like 09:00-12:00", part))?;
+        let (start, end) = (minute_of_day(start)?, minute_of_day(end)?);
+        if start == end {
+            return Err(format!("Window '{}' is empty", part));
+        }
+        windows.push((start, end));
+    }
+    Ok(windows)
+}
+
+/// Whether `minute` (of the local day) falls in a window, or within
+/// `lead_minutes` before one opens
+pub fn in_prewarm_window(windows: &[(u32, u32)], minute: u32, lead_minutes: u32) -> bool {
+    const DAY: u32 = 24 * 60;
+    windows.iter().any(|&(start, end)| {
+        let start = (start + DAY - lead_minutes.min(DAY - 1)) % DAY;
+        let end = end % DAY;
+        if start < end {
+            minute >= start && minute < end
+        } else {
+            minute >= start || minute < end
+        }
+    })
+}
+
+/// Minutes since an RFC 3339 timestamp (None if unparseable)
+fn age_minutes(refreshed_at: &str, now: DateTime<Utc>) -> Option<i64> {
+    DateTime::parse_from_rfc3339(refreshed_at)
+        .ok()
+        .map(|at| (now - at.with_timezone(&Utc)).num_minutes().max(0))
+}
+
+/// Staleness annotation shown with each injected section
+pub fn age_label(minutes: i64) -> String {
+    match minutes {
+        0 => "refreshed just now".to_string(),
+        1..=59 => format!("refreshed {} min ago", minutes),
+        _ => format!("refreshed {}h{:02} ago", minutes / 60, minutes % 60),
+    }
+}
+
+fn usd(v: f64) -> String {
+    if v < 0.0 {
+        format!("-${:.2}", -v)
+    } else {
+        format!("${:.2}", v)
+    }
+}
+
+/// Held tokens from the trade journal, marked to market
+async fn render_portfolio(db: &Database) -> Result<Option<String>, String> {
+    let report = crate::journal::pnl_report(db, None).await.map_err(|e| e.to_string())?;
+    let held: Vec<_> = report.tokens.iter().filter(|t| t.held > 0.0).collect();
+    if held.is_empty() {
+        return Ok(None);
+    }
+    let mut out = String::new();
+    for t in held.iter().take(MAX_ITEMS) {
+        let value = t.current_price_usd.map(|p| usd(p * t.held)).unwrap_or_else(|| "price n/a".to_string());
+        out.push_str(&format!("- {} {} on {} ({})\n", t.held, t.symbol, t.network, value));
+    }
+    out.push_str(&format!(
+        "Realized PnL {} · unrealized {}\n",
+        usd(report.realized_usd),
+        usd(report.unrealized_usd)
+    ));
+    Ok(Some(out))
+}
+
+/// Cron jobs and approved posts due within the horizon
+fn render_calendar(db: &Database, now: DateTime<Utc>) -> Result<Option<String>, String> {
+    let horizon = now + Duration::hours(CALENDAR_HORIZON_HOURS);
+    let due_within = |at: &str| {
+        DateTime::parse_from_rfc3339(at)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+            .filter(|t| *t >= now && *t <= horizon)
+    };
+
+    let mut items: Vec<(DateTime<Utc>, String)> = Vec::new();
+    for job in db.list_cron_jobs().map_err(|e| e.to_string())? {
+        if job.status != "active" {
+            continue;
+        }
+        if let Some(at) = job.next_run_at.as_deref().and_then(due_within) {
+            items.push((at, format!("scheduled job \"{}\"", job.name)));
+        }
+    }
+    for post in db.list_social_posts(Some(SOCIAL_POST_APPROVED), 50).map_err(|e| e.to_string())? {
+        if let Some(at) = post.scheduled_at.as_deref().and_then(due_within) {
+            let text: String = post.text.chars().take(60).collect();
+            items.push((at, format!("{} post: {}", post.platform, text)));
+        }
+    }
+    if items.is_empty() {
+        return Ok(None);
+    }
+    items.sort_by_key(|(at, _)| *at);
+    let out: String = items
+        .iter()
+        .take(MAX_ITEMS)
+        .map(|(at, what)| format!("- {} — {}\n", at.with_timezone(&Local).format("%a %H:%M"), what))
+        .collect();
+    Ok(Some(out))
+}
+
+/// Tokens watched by enabled price-triggered strategies, at current prices
+async fn render_watchlist(db: &Database) -> Result<Option<String>, String> {
+    let mut out = String::new();
+    for strategy in db.list_enabled_strategies().map_err(|e| e.to_string())?.iter().take(MAX_ITEMS) {
+        let StrategyTrigger::Price { token, network, above, below } = &strategy.trigger else {
+            continue;
+        };
+        let price = match crate::strategies::engine::price_usd(token, network).await {
+            Ok(p) => format!("${:.4}", p),
+            Err(_) => "price n/a".to_string(),
+        };
+        let mut thresholds = Vec::new();
+        if let Some(a) = above {
+            thresholds.push(format!("above ${}", a));
+        }
+        if let Some(b) = below {
+            thresholds.push(format!("below ${}", b));
+        }
+        out.push_str(&format!(
+            "- {} on {}: {} (strategy \"{}\" fires {})\n",
+            token.to_uppercase(),
+            network,
+            price,
+            strategy.name,
+            thresholds.join(" / ")
+        ));
+    }
+    Ok(if out.is_empty() { None } else { Some(out) })
+}
+
+/// Render one section. `Ok(None)` means there is nothing worth caching.
+pub async fn render_section(db: &Database, section: &str) -> Result<Option<String>, String> {
+    match section {
+        SECTION_PORTFOLIO => render_portfolio(db).await,
+        SECTION_CALENDAR => render_calendar(db, Utc::now()),
+        SECTION_WATCHLIST => render_watchlist(db).await,
+        other => Err(format!("Unknown section '{}'", other)),
+    }
+}
+
+/// Refresh configured sections. Unless `force`, only sections older than
+/// `refresh_minutes` are re-rendered. Returns how many were refreshed.
+pub async fn run_prewarm_pass(db: &Database, config: &PrewarmConfig, force: bool) -> usize {
+    let now = Utc::now();
+    let cached = db.list_prewarm_entries().unwrap_or_default();
+    let mut refreshed = 0;
+    for section in config.section_list() {
+        let fresh = cached
+            .iter()
+            .find(|e| e.section == section)
+            .and_then(|e| age_minutes(&e.refreshed_at, now))
+            .is_some_and(|age| age < config.refresh_minutes);
+        if fresh && !force {
+            continue;
+        }
+        match render_section(db, &section).await {
+            Ok(content) => {
+                let content = content.unwrap_or_else(|| "(nothing to report)\n".to_string());
+                match db.upsert_prewarm_entry(&section, &content) {
+                    Ok(()) => refreshed += 1,
+                    Err(e) => log::error!("[PREWARM] Failed to cache {}: {}", section, e),
+                }
+            }
+            Err(e) => log::warn!("[PREWARM] Failed to render {}: {}", section, e),
+        }
+    }
+    refreshed
+}
+
+/// Prompt block with every cached section young enough to use
+pub fn prompt_section(entries: &[PrewarmEntry], enabled_sections: &[String], now: DateTime<Utc>) -> Option<String> {
+    let mut out = String::new();
+    for entry in entries {
+        if !enabled_sections.contains(&entry.section) {
+            continue;
+        }
+        let Some(age) = age_minutes(&entry.refreshed_at, now).filter(|a| *a <= MAX_AGE_MINUTES) else {
+            continue;
+        };
+        let mut title = entry.section.clone();
+        if let Some(first) = title.get_mut(0..1) {
+            first.make_ascii_uppercase();
+        }
+        out.push_str(&format!("### {} _({})_\n{}\n", title, age_label(age), entry.content.trim_end()));
+    }
+    if out.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "## Pre-warmed Context\nSnapshots refreshed in the background. Use them to answer directly; \
+         call the relevant tools only if the user needs live values or a snapshot looks too old.\n\n{}\n",
+        out
+    ))
+}
+
+/// The pre-warmed block for the system prompt, if pre-warming is enabled
+pub fn context_for_prompt(db: &Database) -> Option<String> {
+    let config = db.get_prewarm_config().ok().filter(|c| c.enabled)?;
+    let entries = db.list_prewarm_entries().ok()?;
+    prompt_section(&entries, &config.section_list(), Utc::now())
+}
+
+/// Spawn the pre-warm worker. Every `interval_secs` it checks whether the
+/// local time is inside (or just ahead of) an active window and refreshes
+/// sections that have gone stale.
+pub fn spawn_prewarm_worker(
+    db: Arc<Database>,
+    interval_secs: u64,
+    is_leader: impl Fn() -> bool + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if !is_leader() {
+                continue;
+            }
+            let config = match db.get_prewarm_config() {
+                Ok(c) if c.enabled => c,
+                _ => continue,
+            };
+            let windows = match parse_active_hours(&config.active_hours) {
+                Ok(w) => w,
+                Err(e) => {
+                    log::warn!("[PREWARM] Invalid active hours '{}': {}", config.active_hours, e);
+                    continue;
+                }
+            };
+            let now = Local::now();
+            let minute = now.hour() * 60 + now.minute();
+            if !in_prewarm_window(&windows, minute, config.lead_minutes.max(0) as u32) {
+                continue;
+            }
+            let refreshed = run_prewarm_pass(&db, &config, false).await;
+            if refreshed > 0 {
+                log::info!("[PREWARM] Refreshed {} context section(s)", refreshed);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_active_hours() {
+        assert_eq!(parse_active_hours("09:00-12:30, 18-23").unwrap(), vec![(540, 750), (1080, 1380)]);
+        assert_eq!(parse_active_hours("22:00-02:00").unwrap(), vec![(1320, 120)]);
+        assert!(parse_active_hours("").unwrap().is_empty());
+        assert!(parse_active_hours("9am-5pm").is_err());
+        assert!(parse_active_hours("25:00-26:00").is_err());
+        assert!(parse_active_hours("10-10").is_err());
+    }
+
+    #[test]
+    fn test_prewarm_window_includes_lead_time() {
+        let windows = parse_active_hours("09:00-12:00").unwrap();
+        assert!(!in_prewarm_window(&windows, 8 * 60 + 30, 15));
+        assert!(in_prewarm_window(&windows, 8 * 60 + 50, 15));
+        assert!(in_prewarm_window(&windows, 11 * 60 + 59, 15));
+        assert!(!in_prewarm_window(&windows, 12 * 60, 15));
+
+        let overnight = parse_active_hours("23:00-01:00").unwrap();
+        assert!(in_prewarm_window(&overnight, 22 * 60 + 45, 30));
+        assert!(in_prewarm_window(&overnight, 30, 30));
+        assert!(!in_prewarm_window(&overnight, 90, 30));
+
+        let midnight = parse_active_hours("00:00-02:00").unwrap();
+        assert!(in_prewarm_window(&midnight, 23 * 60 + 50, 15));
+    }
+
+    #[test]
+    fn test_prompt_section_annotates_age_and_drops_stale() {
+        let now = Utc::now();
+        let entries = vec![
+            PrewarmEntry {
+                section: SECTION_PORTFOLIO.to_string(),
+                content: "- 1.5 ETH on base ($4500.00)\n".to_string(),
+                refreshed_at: (now - Duration::minutes(12)).to_rfc3339(),
+            },
+            PrewarmEntry {
+                section: SECTION_WATCHLIST.to_string(),
+                content: "- PEPE".to_string(),
+                refreshed_at: (now - Duration::minutes(MAX_AGE_MINUTES + 5)).to_rfc3339(),
+            },
+        ];
+        let enabled: Vec<String> = ALL_SECTIONS.iter().map(|s| s.to_string()).collect();
+        let block = prompt_section(&entries, &enabled, now).unwrap();
+        assert!(block.contains("### Portfolio _(refreshed 12 min ago)_"));
+        assert!(!block.contains("PEPE"));
+
+        assert!(prompt_section(&entries, &["calendar".to_string()], now).is_none());
+        assert_eq!(age_label(135), "refreshed 2h15 ago");
+    }
+}
@@ -0,0 +1,182 @@
+//! Token budget for tool results
+//!
+//! Diffs, fetched pages and activity lists can be tens of thousands of tokens.
+//! A result above the budget (`STARK_TOOL_OUTPUT_TOKEN_LIMIT`) is stored in
+//! `tool_outputs` and replaced in the context by an AI-written summary plus a
+//! handle; the model pages through the original with `fetch_full_output`.
+
+use crate::ai::{AiClient, Message, MessageRole};
+use crate::db::Database;
+
+use super::estimate_tokens;
+
+/// Tool that pages through stored outputs (never condensed itself)
+pub const FETCH_TOOL_NAME: &str = "fetch_full_output";
+/// Characters per `fetch_full_output` page (~3k tokens)
+pub const PAGE_CHARS: usize = 12_000;
+/// Raw output handed to the summarizer is cut to this many characters
+const SUMMARY_INPUT_CHARS: usize = 60_000;
+/// Excerpt used when no summary can be generated
+const PREVIEW_CHARS: usize = 3_000;
+
+/// Whether `content` is over a token budget (0 = unlimited)
+pub fn exceeds_budget(content: &str, limit: usize) -> bool {
+    limit > 0 && estimate_tokens(content).max(0) as usize > limit
+}
+
+pub fn page_count(content: &str) -> usize {
+    content.chars().count().div_ceil(PAGE_CHARS).max(1)
+}
+
+/// Page `page` (1-based) of `content`, split on character boundaries
+pub fn page(content: &str, page: usize) -> Option<&str> {
+    if page == 0 || page > page_count(content) {
+        return None;
+    }
+    let byte_at = |chars: usize| {
+        content
+            .char_indices()
+            .nth(chars)
+            .map(|(i, _)| i)
+            .unwrap_or(content.len())
+    };
+    Some(&content[byte_at((page - 1) * PAGE_CHARS)..byte_at(page * PAGE_CHARS)])
+}
+
+/// Head and tail of `content` within `max_chars`
+fn excerpt(content: &str, max_chars: usize) -> String {
+    let total = content.chars().count();
+    if total <= max_chars {
+        return content.to_string();
+    }
+    let half = max_chars / 2;
+    let head: String = content.chars().take(half).collect();
+    let tail: String = content.chars().skip(total - half).collect();
+    format!("{}\n\n[... {} characters omitted ...]\n\n{}", head, total - max_chars, tail)
+}
+
+async fn summarize(client: &AiClient, tool_name: &str, content: &str, request: &str) -> Result<String, String> {
+    let prompt = format!(
+        "The tool `{}` returned the output below while the agent was working on this request:\n\
+         \"{}\"\n\n\
+         Summarize the output for the agent in under 250 words. Keep exact identifiers, \
+         addresses, amounts, file paths, line numbers and error messages that matter for \
+         the request. Say what kinds of details were left out.\n\n\
+         Output:\n{}",
+        tool_name,
+        request,
+        excerpt(content, SUMMARY_INPUT_CHARS)
+    );
+    let messages = vec![
+        Message {
+            role: MessageRole::System,
+            content: "You condense tool output accurately. Never invent details.".to_string(),
+        },
+        Message {
+            role: MessageRole::User,
+            content: prompt,
+        },
+    ];
+    client
+        .generate_text(messages)
+        .await
+        .map_err(|e| format!("Failed to summarize tool output: {}", e))
+}
+
+/// Store `content` and return the condensed replacement the model sees: a
+/// summary (or an excerpt when no summary can be produced) plus the handle.
+pub async fn condense(
+    db: &Database,
+    client: Option<&AiClient>,
+    session_id: Option<i64>,
+    tool_name: &str,
+    content: &str,
+    request: &str,
+) -> String {
+    let tokens = estimate_tokens(content);
+    let handle = format!("out_{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+    let stored = match db.save_tool_output(&handle, session_id, tool_name, content) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("[TOOL_OUTPUT] Failed to store output of '{}': {}", tool_name, e);
+            false
+        }
+    };
+
+    let summary = match client {
+        Some(client) => match summarize(client, tool_name, content, request).await {
+            Ok(summary) if !summary.trim().is_empty() => Some(summary),
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("[TOOL_OUTPUT] {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    let body = match summary {
+        Some(summary) => format!("Summary:\n{}", summary.trim()),
+        None => format!("Excerpt:\n{}", excerpt(content, PREVIEW_CHARS)),
+    };
+
+    log::info!(
+        "[TOOL_OUTPUT] Condensed '{}' output ({} tokens) behind handle {}",
+        tool_name, tokens, handle
+    );
+    if stored {
+        format!(
+            "[Output of `{}` was ~{} tokens and was condensed. The full output is stored as handle \
+             \"{}\" ({} pages); call {} with this handle and a page number when you need exact content.]\n\n{}",
+            tool_name,
+            tokens,
+            handle,
+            page_count(content),
+            FETCH_TOOL_NAME,
+            body
+        )
+    } else {
+        format!("[Output of `{}` was ~{} tokens and was condensed.]\n\n{}", tool_name, tokens, body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paging_covers_content_on_char_boundaries() {
+        let content = "é".repeat(PAGE_CHARS * 2 + 5);
+        assert_eq!(page_count(&content), 3);
+        assert_eq!(page(&content, 1).unwrap().chars().count(), PAGE_CHARS);
+        assert_eq!(page(&content, 3).unwrap().chars().count(), 5);
+        assert!(page(&content, 0).is_none());
+        assert!(page(&content, 4).is_none());
+        assert_eq!(page_count(""), 1);
+    }
+
+    #[test]
+    fn test_budget_and_excerpt() {
+        assert!(!exceeds_budget(&"word ".repeat(100), 6000));
+        assert!(exceeds_budget(&"word ".repeat(20_000), 6000));
+        assert!(!exceeds_budget(&"word ".repeat(20_000), 0));
+
+        let long = format!("{}{}", "a".repeat(5000), "z".repeat(5000));
+        let short = excerpt(&long, 100);
+        assert!(short.starts_with("aaaa"));
+        assert!(short.ends_with("zzzz"));
+        assert!(short.contains("9900 characters omitted"));
+    }
+
+    #[tokio::test]
+    async fn test_condense_stores_full_output() {
+        let db = Database::new(":memory:").unwrap();
+        let content = "line of output\n".repeat(5000);
+        let condensed = condense(&db, None, Some(3), "web_fetch", &content, "read the page").await;
+
+        let handle = condensed.split('"').nth(1).unwrap();
+        let stored = db.get_tool_output(handle).unwrap().unwrap();
+        assert_eq!(stored.content, content);
+        assert!(condensed.contains("Excerpt:"));
+        assert!(condensed.len() < content.len());
+    }
+}
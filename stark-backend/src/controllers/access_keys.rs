@@ -0,0 +1,154 @@
+//! Access key management
+//!
+//! Create, list and revoke the scoped keys checked by
+//! `middleware::access_keys`. Only a login session can manage keys; the
+//! middleware turns away access keys on these routes.
+
+use std::str::FromStr;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use strum::IntoEnumIterator;
+
+use super::openapi::{array, date_time, integer, nullable, object, reference, string, string_enum, ApiDoc};
+use super::validate_session;
+use crate::db::tables::access_keys::generate_access_key;
+use crate::error::{AppError, AppResult};
+use crate::middleware::access_keys::AccessScope;
+use crate::AppState;
+
+const DEFAULT_RATE_LIMIT_PER_MINUTE: i64 = 60;
+const MAX_RATE_LIMIT_PER_MINUTE: i64 = 10_000;
+
+#[derive(Deserialize)]
+struct CreateAccessKeyRequest {
+    name: String,
+    scopes: Vec<String>,
+    rate_limit_per_minute: Option<i64>,
+    /// Days until the key stops working; never expires when left out
+    expires_in_days: Option<i64>,
+}
+
+/// GET /api/access-keys - All keys (never the key itself)
+async fn list_access_keys(data: web::Data<AppState>, req: HttpRequest) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    Ok(HttpResponse::Ok().json(data.db.list_access_keys()?))
+}
+
+/// POST /api/access-keys - Create a key; the response is the only time it is shown
+async fn create_access_key(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CreateAccessKeyRequest>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let body = body.into_inner();
+
+    let name = body.name.trim();
+    if name.is_empty() {
+        return Err(AppError::validation("name is required"));
+    }
+    if body.scopes.is_empty() {
+        return Err(AppError::validation("at least one scope is required"));
+    }
+    let mut scopes = Vec::new();
+    for scope in &body.scopes {
+        let scope = AccessScope::from_str(scope.trim()).map_err(|_| {
+            let known: Vec<String> = AccessScope::iter().map(|s| s.as_ref().to_string()).collect();
+            AppError::validation(format!("unknown scope '{}' (expected one of: {})", scope, known.join(", ")))
+        })?;
+        if !scopes.iter().any(|s: &String| s == scope.as_ref()) {
+            scopes.push(scope.as_ref().to_string());
+        }
+    }
+
+    let rate_limit = body.rate_limit_per_minute.unwrap_or(DEFAULT_RATE_LIMIT_PER_MINUTE);
+    if !(1..=MAX_RATE_LIMIT_PER_MINUTE).contains(&rate_limit) {
+        return Err(AppError::validation(format!(
+            "rate_limit_per_minute must be between 1 and {}",
+            MAX_RATE_LIMIT_PER_MINUTE
+        )));
+    }
+    let expires_at = match body.expires_in_days {
+        Some(days) if days <= 0 => return Err(AppError::validation("expires_in_days must be positive")),
+        Some(days) => Some((Utc::now() + Duration::days(days)).to_rfc3339()),
+        None => None,
+    };
+
+    let key = generate_access_key();
+    let record = data
+        .db
+        .create_access_key(name, &key, &scopes, rate_limit, expires_at.as_deref())?;
+    log::info!("[ACCESS_KEY] Created key '{}' ({}) with scopes {:?}", record.name, record.key_prefix, record.scopes);
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "key": key,
+        "access_key": record,
+    })))
+}
+
+/// DELETE /api/access-keys/{id} - Revoke a key
+async fn revoke_access_key(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let id = path.into_inner();
+    if !data.db.revoke_access_key(id)? {
+        return Err(AppError::not_found(format!("No active access key {}", id)));
+    }
+    log::info!("[ACCESS_KEY] Revoked key {}", id);
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/access-keys")
+            .route("", web::get().to(list_access_keys))
+            .route("", web::post().to(create_access_key))
+            .route("/{id}", web::delete().to(revoke_access_key)),
+    );
+}
+
+pub fn openapi(doc: &mut ApiDoc) {
+    let all_scopes: Vec<AccessScope> = AccessScope::iter().collect();
+    let scopes: Vec<&str> = all_scopes.iter().map(|s| s.as_ref()).collect();
+    doc.schema(
+        "AccessKey",
+        object(
+            &[
+                ("id", integer()),
+                ("name", string()),
+                ("key_prefix", string()),
+                ("scopes", array(string_enum(&scopes))),
+                ("rate_limit_per_minute", integer()),
+                ("expires_at", nullable(date_time())),
+                ("last_used_at", nullable(date_time())),
+                ("revoked_at", nullable(date_time())),
+                ("created_at", date_time()),
+            ],
+            &["id", "name", "key_prefix", "scopes", "rate_limit_per_minute", "created_at"],
+        ),
+    );
+    doc.get("/api/access-keys", "admin", "List access keys").returns(array(reference("AccessKey")));
+    doc.post("/api/access-keys", "admin", "Create an access key (the key is only returned here)")
+        .body(object(
+            &[
+                ("name", string()),
+                ("scopes", array(string_enum(&scopes))),
+                ("rate_limit_per_minute", integer()),
+                ("expires_in_days", integer()),
+            ],
+            &["name", "scopes"],
+        ))
+        .returns(object(&[("key", string()), ("access_key", reference("AccessKey"))], &["key", "access_key"]));
+    doc.delete("/api/access-keys/{id}", "admin", "Revoke an access key");
+}
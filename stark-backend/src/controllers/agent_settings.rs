@@ -1,8 +1,13 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use crate::ai::ArchetypeId;
+use crate::ai::models as ai_models;
 use crate::keystore_client::{KEYSTORE_CLIENT, DEFAULT_KEYSTORE_URL};
 use crate::models::{AgentSettings, AgentSettingsResponse, UpdateAgentSettingsRequest, UpdateBotSettingsRequest, DEFAULT_EMBEDDINGS_SERVER_URL, DEFAULT_WHISPER_SERVER_URL};
 use crate::ai_endpoint_config;
+use crate::config_watch::ConfigChange;
+use crate::controllers::openapi::{
+    any_object, array, boolean, date_time, integer, nullable, object, reference, string, string_enum, ApiDoc,
+};
 use crate::tools::rpc_config;
 use crate::AppState;
 
@@ -15,7 +20,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -26,7 +32,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "error": "Invalid or expired session"
@@ -160,6 +166,7 @@ pub async fn update_agent_settings(
         match state.db.disable_agent_settings() {
             Ok(_) => {
                 log::info!("Disabled AI agent (payment_mode=none)");
+                state.config_watch.notify(ConfigChange::AgentSettings);
                 let response: AgentSettingsResponse = AgentSettings::default().into();
                 return HttpResponse::Ok().json(response);
             }
@@ -201,6 +208,7 @@ pub async fn update_agent_settings(
     match state.db.save_agent_settings(request.endpoint_name.as_deref(), &request.endpoint, &request.model_archetype, request.model.as_deref(), request.max_response_tokens, request.max_context_tokens, request.secret_key.as_deref(), payment_mode) {
         Ok(settings) => {
             log::info!("Updated agent settings to use {:?} / {} endpoint with {} archetype", request.endpoint_name, request.endpoint, request.model_archetype);
+            state.config_watch.notify(ConfigChange::AgentSettings);
             let response: AgentSettingsResponse = settings.into();
             HttpResponse::Ok().json(response)
         }
@@ -224,6 +232,7 @@ pub async fn disable_agent(
     match state.db.disable_agent_settings() {
         Ok(_) => {
             log::info!("Disabled AI agent");
+            state.config_watch.notify(ConfigChange::AgentSettings);
             HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
                 "message": "AI agent disabled"
@@ -322,6 +331,7 @@ pub async fn update_bot_settings(
             if let Some(ref endpoints) = settings.custom_rpc_endpoints {
                 crate::tools::rpc_config::set_custom_rpc_endpoints(endpoints.clone());
             }
+            state.config_watch.notify(ConfigChange::BotSettings);
             HttpResponse::Ok().json(settings)
         }
         Err(e) => {
@@ -440,6 +450,32 @@ pub async fn get_ai_endpoint_presets(
     HttpResponse::Ok().json(presets)
 }
 
+/// Model capability registry, plus what the active model supports
+pub async fn get_models(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let settings = state.db.get_active_agent_settings().ok().flatten();
+    let current = settings.as_ref().map(|s| {
+        serde_json::json!({
+            "model_archetype": s.model_archetype,
+            "model": s.model,
+            "capabilities": ai_models::for_settings(s),
+            "configured_max_context_tokens": s.max_context_tokens,
+            "effective_max_context_tokens": ai_models::effective_context_tokens(s),
+        })
+    });
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "models": ai_models::MODELS,
+        "current": current,
+    }))
+}
+
 /// Health check for infrastructure services (whisper + embeddings)
 pub async fn services_health(
     state: web::Data<AppState>,
@@ -474,9 +510,16 @@ pub async fn services_health(
 
     let (whisper_healthy, embeddings_healthy) = tokio::join!(whisper_check, embeddings_check);
 
+    // Self-maintenance jobs: healthy unless an enabled job's last run failed
+    let maintenance = crate::scheduler::maintenance::status(&state.db);
+    let maintenance_healthy = maintenance
+        .iter()
+        .all(|job| !job.config.enabled || job.last_run.as_ref().is_none_or(|run| run.success));
+
     HttpResponse::Ok().json(serde_json::json!({
         "whisper": { "url": whisper_url, "healthy": whisper_healthy },
         "embeddings": { "url": embeddings_url, "healthy": embeddings_healthy },
+        "maintenance": { "healthy": maintenance_healthy, "jobs": maintenance },
     }))
 }
 
@@ -614,8 +657,67 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         web::resource("/api/auto-sync-status")
             .route(web::get().to(get_auto_sync_status))
     );
+    cfg.service(
+        web::resource("/api/models")
+            .route(web::get().to(get_models))
+    );
     cfg.service(
         web::resource("/api/services/health")
             .route(web::get().to(services_health))
     );
 }
+
+/// OpenAPI description of the agent and bot settings routes
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.schema(
+        "AgentSettings",
+        object(
+            &[
+                ("id", integer()),
+                ("endpoint_name", nullable(string())),
+                ("endpoint", string()),
+                ("model_archetype", string()),
+                ("model", nullable(string())),
+                ("max_response_tokens", integer()),
+                ("max_context_tokens", integer()),
+                ("enabled", boolean()),
+                ("has_secret_key", boolean()),
+                ("payment_mode", string()),
+                ("created_at", date_time()),
+                ("updated_at", date_time()),
+            ],
+            &["id", "endpoint", "model_archetype", "enabled"],
+        ),
+    );
+
+    doc.get("/api/agent-settings", "admin", "The active AI endpoint").returns(reference("AgentSettings"));
+    doc.put("/api/agent-settings", "admin", "Set the active AI endpoint")
+        .body(object(
+            &[
+                ("endpoint_name", string()),
+                ("endpoint", string()),
+                ("model_archetype", string()),
+                ("model", string()),
+                ("max_response_tokens", integer()),
+                ("max_context_tokens", integer()),
+                ("secret_key", string()),
+                ("payment_mode", string_enum(&["none", "credits", "x402", "custom"])),
+            ],
+            &["endpoint"],
+        ))
+        .returns(reference("AgentSettings"));
+    doc.get("/api/agent-settings/list", "admin", "All configured AI endpoints").returns(array(reference("AgentSettings")));
+    doc.get("/api/agent-settings/archetypes", "admin", "Model archetypes").returns(any_object());
+    doc.get("/api/agent-settings/endpoints", "admin", "AI endpoint presets").returns(any_object());
+    doc.get("/api/agent-settings/credit-balance", "admin", "Inference credit balance").returns(any_object());
+    doc.post("/api/agent-settings/disable", "admin", "Disable the agent (no active endpoint)").returns(any_object());
+    doc.get("/api/bot-settings", "admin", "Bot settings").returns(any_object());
+    doc.put("/api/bot-settings", "admin", "Update bot settings (only the fields sent change)")
+        .body(any_object())
+        .returns(any_object());
+    doc.get("/api/rpc-providers", "admin", "Available RPC providers").returns(any_object());
+    doc.get("/api/auto-sync-status", "admin", "Auto-sync status of the current wallet").returns(any_object());
+    doc.get("/api/models", "admin", "Model capability registry and the active model's capabilities")
+        .returns(any_object());
+    doc.get("/api/services/health", "admin", "Health of the whisper and embeddings services and the last run of each maintenance job").returns(any_object());
+}
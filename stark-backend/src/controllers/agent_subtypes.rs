@@ -3,6 +3,7 @@ use serde::Deserialize;
 
 use crate::agents::loader;
 use crate::ai::multi_agent::types::{self, AgentSubtypeConfig};
+use crate::models::ChannelSettingKey;
 use crate::AppState;
 
 const MAX_SUBTYPES: usize = 10;
@@ -15,7 +16,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -26,7 +28,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "error": "Invalid or expired session"
@@ -283,6 +285,7 @@ async fn delete_subtype(
     match loader::delete_agent_folder(&agents_dir, &key) {
         Ok(_) => {
             loader::reload_registry_from_disk();
+            let _ = data.db.delete_agent_subtype_install(&key);
             HttpResponse::Ok().json(serde_json::json!({
                 "success": true,
                 "message": format!("Agent subtype '{}' deleted", key)
@@ -710,6 +713,113 @@ async fn publish_to_hub(
     HttpResponse::Ok().json(resp)
 }
 
+/// Whether a bundle/file path stays inside the agent folder
+fn is_safe_relative_path(name: &str) -> bool {
+    use std::path::{Component, Path};
+    !name.is_empty() && Path::new(name).components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Write files into an agent folder, skipping unsafe paths. Returns the names written.
+fn write_agent_files(agent_folder: &std::path::Path, files: &[(String, Vec<u8>)]) -> Vec<String> {
+    let mut written = Vec::new();
+    for (name, content) in files {
+        if !is_safe_relative_path(name) {
+            log::warn!("[AGENTS] Skipping file outside the agent folder: '{}'", name);
+            continue;
+        }
+        let file_path = agent_folder.join(name);
+        if let Some(parent) = file_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = std::fs::write(&file_path, content) {
+            log::warn!("Failed to write file '{}': {}", name, e);
+        } else {
+            written.push(name.clone());
+        }
+    }
+    written
+}
+
+/// Download an agent subtype from StarkHub into the runtime agents dir and
+/// record where it came from. Returns the parsed config and the files written.
+async fn install_subtype_from_hub(
+    data: &web::Data<AppState>,
+    username: &str,
+    slug: &str,
+    auth_token: &str,
+) -> Result<(AgentSubtypeConfig, Vec<String>), HttpResponse> {
+    let client = crate::integrations::starkhub_client::StarkHubClient::new();
+
+    // Try ZIP bundle download first (faster — single request for all files)
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    if let Ok(Some(zip_bytes)) = client.download_bundle("agent-subtypes", username, slug, auth_token).await {
+        match extract_agent_zip(&zip_bytes) {
+            Ok(extracted) if extracted.iter().any(|(name, _)| name == "agent.md") => files = extracted,
+            Ok(_) => log::warn!("[AGENTS] ZIP bundle missing agent.md, falling back to individual downloads"),
+            Err(e) => log::warn!("[AGENTS] ZIP bundle extract failed, falling back: {}", e),
+        }
+    }
+
+    let bundle_config = files
+        .iter()
+        .find(|(name, _)| name == "agent.md")
+        .map(|(_, md)| loader::parse_agent_file(&String::from_utf8_lossy(md)));
+    let config = match bundle_config {
+        Some(Ok(config)) => config,
+        other => {
+            if let Some(Err(e)) = other {
+                log::warn!("[AGENTS] ZIP bundle agent.md parse failed, falling back: {}", e);
+            }
+            // Fallback: individual file downloads (legacy items without bundles)
+            let raw_agent_md = client.download_agent_subtype(username, slug, auth_token).await.map_err(|e| {
+                HttpResponse::BadGateway().json(serde_json::json!({
+                    "error": format!("Failed to download from StarkHub: {}", e)
+                }))
+            })?;
+            let config = loader::parse_agent_file(&raw_agent_md).map_err(|e| {
+                HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Failed to parse downloaded agent.md: {}", e)
+                }))
+            })?;
+            files = vec![("agent.md".to_string(), raw_agent_md.into_bytes())];
+            if let Ok(summaries) = client.list_agent_subtype_files(username, slug).await {
+                for summary in &summaries {
+                    if let Ok(file) = client.get_agent_subtype_file(username, slug, &summary.file_name).await {
+                        files.push((file.file_name, file.content.into_bytes()));
+                    }
+                }
+            }
+            config
+        }
+    };
+
+    let agents_dir = crate::config::runtime_agents_dir();
+    let agent_folder = agents_dir.join(&config.key);
+    if !is_safe_relative_path(&config.key) {
+        return Err(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Invalid agent subtype key '{}'", config.key)
+        })));
+    }
+    if let Err(e) = std::fs::create_dir_all(&agent_folder) {
+        return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to create agent folder: {}", e)
+        })));
+    }
+    let written = write_agent_files(&agent_folder, &files);
+    loader::reload_registry_from_disk();
+
+    // Remember the hub release so it can be checked for updates and re-synced
+    let version = match client.get_agent_subtype(username, slug).await {
+        Ok(detail) => detail.version,
+        Err(_) => config.version.clone(),
+    };
+    if let Err(e) = data.db.record_agent_subtype_install(&config.key, username, slug, &version) {
+        log::warn!("[AGENTS] Failed to record install of '{}': {}", config.key, e);
+    }
+
+    Ok((config, written))
+}
+
 /// POST /api/agent-subtypes/install — install an agent subtype from StarkHub
 async fn install_from_hub(
     data: web::Data<AppState>,
@@ -720,142 +830,207 @@ async fn install_from_hub(
         return resp;
     }
 
-    let auth_token = req
-        .headers()
+    let auth_token = starkhub_token(&req);
+    match install_subtype_from_hub(&data, &body.username, &body.slug, &auth_token).await {
+        Ok((config, files)) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "key": config.key,
+            "label": config.label,
+            "files": files,
+            "message": format!("Installed agent subtype '{}' from @{}/{}", config.key, body.username, body.slug),
+        })),
+        Err(resp) => resp,
+    }
+}
+
+/// Optional StarkHub token (needed for private items)
+fn starkhub_token(req: &HttpRequest) -> String {
+    req.headers()
         .get("X-StarkHub-Token")
         .and_then(|h| h.to_str().ok())
         .unwrap_or("")
-        .to_string();
-
-    let client = crate::integrations::starkhub_client::StarkHubClient::new();
+        .to_string()
+}
 
-    // Try ZIP bundle download first (faster — single request for all files)
-    if let Ok(Some(zip_bytes)) = client
-        .download_bundle("agent-subtypes", &body.username, &body.slug, &auth_token)
-        .await
-    {
-        match extract_agent_zip(&zip_bytes) {
-            Ok(files) => {
-                // Find agent.md in the extracted files
-                let agent_md = files.iter().find(|(name, _)| name == "agent.md");
-                if let Some((_, md_content)) = agent_md {
-                    let raw_agent_md = String::from_utf8_lossy(md_content).to_string();
-
-                    match loader::parse_agent_file(&raw_agent_md) {
-                        Ok(config) => {
-                            let agents_dir = crate::config::runtime_agents_dir();
-                            let agent_folder = agents_dir.join(&config.key);
-
-                            if let Err(e) = std::fs::create_dir_all(&agent_folder) {
-                                return HttpResponse::InternalServerError().json(serde_json::json!({
-                                    "error": format!("Failed to create agent folder: {}", e)
-                                }));
-                            }
-
-                            // Write all extracted files
-                            let mut downloaded_files = Vec::new();
-                            for (name, content) in &files {
-                                let file_path = agent_folder.join(name);
-                                if let Some(parent) = file_path.parent() {
-                                    let _ = std::fs::create_dir_all(parent);
-                                }
-                                if let Err(e) = std::fs::write(&file_path, content) {
-                                    log::warn!("Failed to write file '{}': {}", name, e);
-                                } else {
-                                    downloaded_files.push(name.clone());
-                                }
-                            }
-
-                            loader::reload_registry_from_disk();
-
-                            return HttpResponse::Ok().json(serde_json::json!({
-                                "success": true,
-                                "key": config.key,
-                                "label": config.label,
-                                "files": downloaded_files,
-                                "message": format!("Installed agent subtype '{}' from @{}/{}", config.key, body.username, body.slug),
-                            }));
-                        }
-                        Err(e) => {
-                            log::warn!("[AGENTS] ZIP bundle agent.md parse failed, falling back: {}", e);
-                        }
-                    }
-                } else {
-                    log::warn!("[AGENTS] ZIP bundle missing agent.md, falling back to individual downloads");
-                }
-            }
-            Err(e) => {
-                log::warn!("[AGENTS] ZIP bundle extract failed, falling back: {}", e);
-            }
-        }
+/// GET /api/agent-subtypes/remote/{username}/{slug} — a StarkHub subtype with its file list
+async fn get_remote(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
     }
-
-    // Fallback: individual file downloads (legacy items without bundles)
-    let raw_agent_md = match client
-        .download_agent_subtype(&body.username, &body.slug, &auth_token)
-        .await
-    {
-        Ok(md) => md,
+    let (username, slug) = path.into_inner();
+    let client = crate::integrations::starkhub_client::StarkHubClient::new();
+    let detail = match client.get_agent_subtype(&username, &slug).await {
+        Ok(d) => d,
         Err(e) => {
             return HttpResponse::BadGateway().json(serde_json::json!({
-                "error": format!("Failed to download from StarkHub: {}", e)
+                "error": format!("Failed to fetch from StarkHub: {}", e)
             }));
         }
     };
+    let files = client.list_agent_subtype_files(&username, &slug).await.unwrap_or_default();
+    let installed = types::get_subtype_config(&detail.key).is_some();
+    HttpResponse::Ok().json(serde_json::json!({
+        "subtype": detail,
+        "files": files,
+        "installed": installed,
+    }))
+}
 
-    let config = match loader::parse_agent_file(&raw_agent_md) {
-        Ok(c) => c,
+/// GET /api/agent-subtypes/installed — subtypes installed from StarkHub, with available updates
+async fn list_installed(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    let installs = data.db.list_agent_subtype_installs().unwrap_or_default();
+    let client = crate::integrations::starkhub_client::StarkHubClient::new();
+    let mut result = Vec::new();
+    for install in installs {
+        let latest = client.get_agent_subtype(&install.username, &install.slug).await.ok().map(|d| d.version);
+        let update_available = latest
+            .as_deref()
+            .filter(|v| crate::config::semver_is_newer(v, &install.version))
+            .map(|v| v.to_string());
+        result.push(serde_json::json!({
+            "key": install.key,
+            "username": install.username,
+            "slug": install.slug,
+            "version": install.version,
+            "installed_at": install.installed_at,
+            "updated_at": install.updated_at,
+            "on_disk": types::get_subtype_config(&install.key).is_some(),
+            "update_available": update_available,
+        }));
+    }
+    HttpResponse::Ok().json(result)
+}
+
+/// POST /api/agent-subtypes/{key}/sync — re-download a hub-installed subtype (picks up updates)
+async fn sync_from_hub(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    let key = path.into_inner();
+    let install = match data.db.get_agent_subtype_install(&key) {
+        Ok(Some(i)) => i,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Agent subtype '{}' was not installed from StarkHub", key)
+            }));
+        }
         Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": format!("Failed to parse downloaded agent.md: {}", e)
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
             }));
         }
     };
 
-    let agents_dir = crate::config::runtime_agents_dir();
-    let agent_folder = agents_dir.join(&config.key);
+    let auth_token = starkhub_token(&req);
+    match install_subtype_from_hub(&data, &install.username, &install.slug, &auth_token).await {
+        Ok((config, files)) => {
+            let version = data
+                .db
+                .get_agent_subtype_install(&config.key)
+                .ok()
+                .flatten()
+                .map(|i| i.version)
+                .unwrap_or_default();
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "key": config.key,
+                "previous_version": install.version,
+                "version": version,
+                "files": files,
+                "message": format!("Synced agent subtype '{}' from @{}/{}", config.key, install.username, install.slug),
+            }))
+        }
+        Err(resp) => resp,
+    }
+}
 
-    if let Err(e) = std::fs::create_dir_all(&agent_folder) {
-        return HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to create agent folder: {}", e)
-        }));
+#[derive(Deserialize)]
+struct ChannelSubtypeRequest {
+    /// Subtype key, or null/empty to go back to director routing
+    key: Option<String>,
+}
+
+/// GET /api/agent-subtypes/channels/{channel_id} — the channel's active subtype
+async fn get_channel_subtype(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
     }
+    let channel_id = path.into_inner();
+    let key = data
+        .db
+        .get_channel_setting(channel_id, ChannelSettingKey::AgentSubtype.as_ref())
+        .ok()
+        .flatten()
+        .filter(|k| !k.is_empty());
+    HttpResponse::Ok().json(serde_json::json!({
+        "channel_id": channel_id,
+        "key": key,
+        "default_key": types::default_subtype_key(),
+    }))
+}
 
-    if let Err(e) = std::fs::write(agent_folder.join("agent.md"), &raw_agent_md) {
-        return HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to write agent.md: {}", e)
+/// PUT /api/agent-subtypes/channels/{channel_id} — pin a subtype for every conversation in a channel
+async fn set_channel_subtype(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<ChannelSubtypeRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    let channel_id = path.into_inner();
+    if !matches!(data.db.get_channel(channel_id), Ok(Some(_))) {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Channel {} not found", channel_id)
         }));
     }
 
-    let mut downloaded_files = vec!["agent.md".to_string()];
-    if let Ok(files) = client
-        .list_agent_subtype_files(&body.username, &body.slug)
-        .await
-    {
-        for file_summary in &files {
-            if let Ok(file_detail) = client
-                .get_agent_subtype_file(&body.username, &body.slug, &file_summary.file_name)
-                .await
-            {
-                let file_path = agent_folder.join(&file_detail.file_name);
-                if let Err(e) = std::fs::write(&file_path, &file_detail.content) {
-                    log::warn!("Failed to write file '{}': {}", file_detail.file_name, e);
-                } else {
-                    downloaded_files.push(file_detail.file_name);
-                }
+    let key = body.key.as_deref().map(str::trim).unwrap_or("");
+    if !key.is_empty() {
+        match types::get_subtype_config(key) {
+            Some(config) if config.enabled => {}
+            Some(_) => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Agent subtype '{}' is disabled", key)
+                }));
+            }
+            None => {
+                return HttpResponse::NotFound().json(serde_json::json!({
+                    "error": format!("Agent subtype '{}' not found", key)
+                }));
             }
         }
     }
 
-    loader::reload_registry_from_disk();
-
-    HttpResponse::Ok().json(serde_json::json!({
-        "success": true,
-        "key": config.key,
-        "label": config.label,
-        "files": downloaded_files,
-        "message": format!("Installed agent subtype '{}' from @{}/{}", config.key, body.username, body.slug),
-    }))
+    match data.db.set_channel_setting(channel_id, ChannelSettingKey::AgentSubtype.as_ref(), key) {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "channel_id": channel_id,
+            "key": if key.is_empty() { None } else { Some(key) },
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
 }
 
 /// Extract files from an agent subtype ZIP bundle.
@@ -898,8 +1073,13 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/import", web::post().to(import_subtypes))
             .route("/featured_remote", web::get().to(featured_remote))
             .route("/install", web::post().to(install_from_hub))
+            .route("/installed", web::get().to(list_installed))
+            .route("/remote/{username}/{slug}", web::get().to(get_remote))
+            .route("/channels/{channel_id}", web::get().to(get_channel_subtype))
+            .route("/channels/{channel_id}", web::put().to(set_channel_subtype))
             .route("/publish/{key}", web::post().to(publish_to_hub))
             .route("/{key}/export", web::get().to(export_single_subtype))
+            .route("/{key}/sync", web::post().to(sync_from_hub))
             .route("/{key}", web::get().to(get_subtype))
             .route("", web::post().to(create_subtype))
             .route("/{key}", web::put().to(update_subtype))
@@ -0,0 +1,130 @@
+//! AI spending quota API — daily/monthly token or USD limits for the whole
+//! instance or one channel, with their usage in the current period.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Local;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate_session;
+use super::validation::{Valid, Validate, Validator};
+use crate::ai_quota::{Period, QuotaStatus, DEGRADE_AT};
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/ai-quotas")
+            .route("", web::get().to(list_quotas))
+            .route("", web::put().to(upsert_quota))
+            .route("/{id}", web::delete().to(delete_quota)),
+    );
+}
+
+#[derive(Deserialize)]
+struct UpsertQuotaRequest {
+    /// Omit for an instance-wide quota
+    channel_id: Option<i64>,
+    /// "daily" or "monthly"
+    period: String,
+    max_tokens: Option<i64>,
+    max_usd: Option<f64>,
+    /// Preset key to switch to near the limit (default: cheapest cheaper preset)
+    fallback_model: Option<String>,
+}
+
+impl Validate for UpsertQuotaRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.one_of("period", &self.period, &["daily", "monthly"]);
+        if self.max_tokens.is_none() && self.max_usd.is_none() {
+            v.error("max_tokens", "set max_tokens, max_usd or both");
+        }
+        if let Some(max) = self.max_tokens {
+            v.range("max_tokens", max, 1, i64::MAX);
+        }
+        if let Some(max) = self.max_usd {
+            if !(max > 0.0 && max.is_finite()) {
+                v.error("max_usd", format!("must be a positive amount (got {})", max));
+            }
+        }
+        if let Some(model) = self.fallback_model.as_deref().filter(|m| !m.trim().is_empty()) {
+            if crate::ai_endpoint_config::get_ai_endpoint(model.trim()).is_none() {
+                v.error("fallback_model", format!("'{}' is not a known model preset", model));
+            }
+        }
+    }
+}
+
+/// GET /api/ai-quotas - Every quota with its usage this period
+async fn list_quotas(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let quotas = match data.db.list_ai_quotas() {
+        Ok(quotas) => quotas,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+    let now = Local::now();
+    let statuses: Vec<QuotaStatus> = quotas
+        .into_iter()
+        .filter_map(|quota| QuotaStatus::load(&data.db, quota, now).ok())
+        .collect();
+    HttpResponse::Ok().json(json!({ "quotas": statuses, "degrade_at": DEGRADE_AT }))
+}
+
+/// PUT /api/ai-quotas - Create or replace the quota for a scope and period
+async fn upsert_quota(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: Valid<UpsertQuotaRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let body = body.into_inner();
+    if let Some(channel_id) = body.channel_id {
+        if data.db.get_channel(channel_id).ok().flatten().is_none() {
+            return HttpResponse::NotFound().json(json!({ "error": format!("Channel {} not found", channel_id) }));
+        }
+    }
+    let period = Period::parse(&body.period).unwrap_or(Period::Monthly);
+    let fallback_model = body.fallback_model.as_deref().map(str::trim).filter(|m| !m.is_empty());
+    match data.db.upsert_ai_quota(body.channel_id, period.as_str(), body.max_tokens, body.max_usd, fallback_model) {
+        Ok(quota) => {
+            log::info!(
+                "AI quota set: {} {} (tokens {:?}, usd {:?})",
+                quota.channel_id.map(|id| format!("channel {}", id)).unwrap_or_else(|| "instance".to_string()),
+                quota.period,
+                quota.max_tokens,
+                quota.max_usd
+            );
+            match QuotaStatus::load(&data.db, quota, Local::now()) {
+                Ok(status) => HttpResponse::Ok().json(json!({ "quota": status })),
+                Err(e) => HttpResponse::InternalServerError().json(json!({
+                    "error": format!("Database error: {}", e)
+                })),
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// DELETE /api/ai-quotas/{id}
+async fn delete_quota(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.delete_ai_quota(id) {
+        Ok(true) => HttpResponse::Ok().json(json!({ "deleted": id })),
+        Ok(false) => HttpResponse::NotFound().json(json!({ "error": format!("Quota {} not found", id) })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
@@ -0,0 +1,97 @@
+//! Analytics API
+//!
+//! Skill-defined counters and metrics recorded with the increment_counter /
+//! record_metric tools, and the sentiment/urgency tags of user messages.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct MetricsQuery {
+    since_hours: Option<i64>,
+    skill: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TagsQuery {
+    since_hours: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct SeriesQuery {
+    since_hours: Option<i64>,
+    /// "hour" or "day" (default)
+    bucket: Option<String>,
+}
+
+/// GET /api/analytics/metrics - Summary of every counter and metric
+async fn list_metrics(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<MetricsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_metric_summaries(query.since_hours, query.skill.as_deref()) {
+        Ok(metrics) => HttpResponse::Ok().json(metrics),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+/// GET /api/analytics/metrics/{name} - Summary and time series of one metric
+async fn get_metric(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<SeriesQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let name = path.into_inner();
+    let bucket = match query.bucket.as_deref() {
+        Some("hour") => "hour",
+        Some("day") | None => "day",
+        Some(other) => {
+            return HttpResponse::BadRequest()
+                .json(serde_json::json!({ "error": format!("Unknown bucket '{}'", other) }))
+        }
+    };
+    let summary = match data.db.get_metric_summary(&name, query.since_hours) {
+        Ok(Some(s)) => s,
+        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({ "error": "Metric not found" })),
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() })),
+    };
+    match data.db.get_metric_series(&name, bucket, query.since_hours) {
+        Ok(series) => HttpResponse::Ok().json(serde_json::json!({
+            "summary": summary,
+            "bucket": bucket,
+            "series": series,
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+/// GET /api/analytics/message-tags - User messages per sentiment and urgency
+async fn message_tags(data: web::Data<AppState>, req: HttpRequest, query: web::Query<TagsQuery>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.count_message_tags(query.since_hours) {
+        Ok(counts) => HttpResponse::Ok().json(counts),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/analytics")
+            .route("/metrics", web::get().to(list_metrics))
+            .route("/metrics/{name}", web::get().to(get_metric))
+            .route("/message-tags", web::get().to(message_tags)),
+    );
+}
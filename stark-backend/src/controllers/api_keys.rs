@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, EnumIter, EnumString, IntoEnumIterator};
 
 use crate::backup::{ApiKeyEntry, BackupData};
+use crate::config_watch::ConfigChange;
 use crate::keystore_client::KEYSTORE_CLIENT;
 use crate::models::ApiKeyResponse;
 use crate::AppState;
@@ -50,6 +51,24 @@ pub enum ApiKeyId {
     XaiApiKey,
     #[strum(serialize = "ZEROX_API_KEY")]
     ZeroxApiKey,
+    #[strum(serialize = "SAFE_API_KEY")]
+    SafeApiKey,
+    #[strum(serialize = "LINEAR_API_KEY")]
+    LinearApiKey,
+    #[strum(serialize = "LINEAR_WEBHOOK_SECRET")]
+    LinearWebhookSecret,
+    #[strum(serialize = "JIRA_BASE_URL")]
+    JiraBaseUrl,
+    #[strum(serialize = "JIRA_EMAIL")]
+    JiraEmail,
+    #[strum(serialize = "JIRA_API_TOKEN")]
+    JiraApiToken,
+    #[strum(serialize = "JIRA_WEBHOOK_SECRET")]
+    JiraWebhookSecret,
+    #[strum(serialize = "NEYNAR_API_KEY")]
+    NeynarApiKey,
+    #[strum(serialize = "FARCASTER_SIGNER_UUID")]
+    FarcasterSignerUuid,
 }
 
 impl ApiKeyId {
@@ -66,6 +85,15 @@ impl ApiKeyId {
             Self::AlchemyApiKey => "ALCHEMY_API_KEY",
             Self::XaiApiKey => "XAI_API_KEY",
             Self::ZeroxApiKey => "ZEROX_API_KEY",
+            Self::SafeApiKey => "SAFE_API_KEY",
+            Self::LinearApiKey => "LINEAR_API_KEY",
+            Self::LinearWebhookSecret => "LINEAR_WEBHOOK_SECRET",
+            Self::JiraBaseUrl => "JIRA_BASE_URL",
+            Self::JiraEmail => "JIRA_EMAIL",
+            Self::JiraApiToken => "JIRA_API_TOKEN",
+            Self::JiraWebhookSecret => "JIRA_WEBHOOK_SECRET",
+            Self::NeynarApiKey => "NEYNAR_API_KEY",
+            Self::FarcasterSignerUuid => "FARCASTER_SIGNER_UUID",
         }
     }
 
@@ -81,6 +109,15 @@ impl ApiKeyId {
             Self::AlchemyApiKey => Some(&["ALCHEMY_API_KEY"]),
             Self::XaiApiKey => Some(&["XAI_API_KEY"]),
             Self::ZeroxApiKey => Some(&["ZEROX_API_KEY"]),
+            Self::SafeApiKey => Some(&["SAFE_API_KEY"]),
+            Self::LinearApiKey => Some(&["LINEAR_API_KEY"]),
+            Self::LinearWebhookSecret => None,
+            Self::JiraBaseUrl => Some(&["JIRA_BASE_URL"]),
+            Self::JiraEmail => Some(&["JIRA_EMAIL"]),
+            Self::JiraApiToken => Some(&["JIRA_API_TOKEN"]),
+            Self::JiraWebhookSecret => None,
+            Self::NeynarApiKey => Some(&["NEYNAR_API_KEY"]),
+            Self::FarcasterSignerUuid => None,
         }
     }
 
@@ -155,6 +192,70 @@ pub fn get_service_configs() -> Vec<ServiceConfig> {
                 secret: true,
             }],
         },
+        ServiceConfig {
+            group: "jira".into(),
+            label: "Jira".into(),
+            description: "Create and update Jira issues from chat. Use your site URL (https://your-team.atlassian.net), account email and an API token. The webhook secret verifies status-change webhooks sent to /api/issue-tracker/webhook/jira.".into(),
+            url: "https://id.atlassian.com/manage-profile/security/api-tokens".into(),
+            keys: vec![
+                KeyConfig {
+                    name: "JIRA_BASE_URL".into(),
+                    label: "Site URL".into(),
+                    secret: false,
+                },
+                KeyConfig {
+                    name: "JIRA_EMAIL".into(),
+                    label: "Account Email".into(),
+                    secret: false,
+                },
+                KeyConfig {
+                    name: "JIRA_API_TOKEN".into(),
+                    label: "API Token".into(),
+                    secret: true,
+                },
+                KeyConfig {
+                    name: "JIRA_WEBHOOK_SECRET".into(),
+                    label: "Webhook Secret".into(),
+                    secret: true,
+                },
+            ],
+        },
+        ServiceConfig {
+            group: "linear".into(),
+            label: "Linear".into(),
+            description: "Create and update Linear issues from chat. Create a personal API key; the webhook signing secret verifies status-change webhooks sent to /api/issue-tracker/webhook/linear.".into(),
+            url: "https://linear.app/settings/api".into(),
+            keys: vec![
+                KeyConfig {
+                    name: "LINEAR_API_KEY".into(),
+                    label: "API Key".into(),
+                    secret: true,
+                },
+                KeyConfig {
+                    name: "LINEAR_WEBHOOK_SECRET".into(),
+                    label: "Webhook Signing Secret".into(),
+                    secret: true,
+                },
+            ],
+        },
+        ServiceConfig {
+            group: "farcaster".into(),
+            label: "Farcaster".into(),
+            description: "Post casts and answer mentions on Farcaster through Neynar. The API key is enough to read; casting needs the UUID of a Neynar managed signer approved for the bot's account.".into(),
+            url: "https://dev.neynar.com".into(),
+            keys: vec![
+                KeyConfig {
+                    name: "NEYNAR_API_KEY".into(),
+                    label: "Neynar API Key".into(),
+                    secret: true,
+                },
+                KeyConfig {
+                    name: "FARCASTER_SIGNER_UUID".into(),
+                    label: "Signer UUID".into(),
+                    secret: true,
+                },
+            ],
+        },
         ServiceConfig {
             group: "supabase".into(),
             label: "Supabase".into(),
@@ -205,6 +306,17 @@ pub fn get_service_configs() -> Vec<ServiceConfig> {
                 secret: true,
             }],
         },
+        ServiceConfig {
+            group: "safe".into(),
+            label: "Safe (Multisig)".into(),
+            description: "API key for the Safe Transaction Service, used to propose and track multisig transactions. Works without a key at lower rate limits.".into(),
+            url: "https://developer.safe.global/".into(),
+            keys: vec![KeyConfig {
+                name: "SAFE_API_KEY".into(),
+                label: "API Key".into(),
+                secret: true,
+            }],
+        },
         ServiceConfig {
             group: "xai".into(),
             label: "xAI (Grok)".into(),
@@ -508,7 +620,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -521,7 +634,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(ApiKeysListResponse {
             success: false,
@@ -547,6 +660,9 @@ async fn get_api_key_value(
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
     }
+    if let Err(resp) = super::require_second_factor(&state, &req) {
+        return resp;
+    }
 
     match state.db.get_api_key(&query.key_name) {
         Ok(Some(key)) => HttpResponse::Ok().json(GetApiKeyValueResponse {
@@ -609,6 +725,9 @@ async fn upsert_api_key(
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
     }
+    if let Err(resp) = super::require_second_factor(&state, &req) {
+        return resp;
+    }
 
     // Validate key name: non-empty, uppercase alphanumeric + underscores, max 64 chars
     let key_name = body.key_name.trim();
@@ -645,11 +764,14 @@ async fn upsert_api_key(
 
     // Store the key (key_name is the service_name in the database)
     match state.db.upsert_api_key(&body.key_name, &body.api_key) {
-        Ok(key) => HttpResponse::Ok().json(ApiKeyOperationResponse {
-            success: true,
-            key: Some(key.to_response()),
-            error: None,
-        }),
+        Ok(key) => {
+            state.config_watch.notify(ConfigChange::ApiKey { key_name: body.key_name.clone() });
+            HttpResponse::Ok().json(ApiKeyOperationResponse {
+                success: true,
+                key: Some(key.to_response()),
+                error: None,
+            })
+        }
         Err(e) => {
             log::error!("Failed to save API key: {}", e);
             HttpResponse::InternalServerError().json(ApiKeyOperationResponse {
@@ -669,10 +791,14 @@ async fn delete_api_key(
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
     }
+    if let Err(resp) = super::require_second_factor(&state, &req) {
+        return resp;
+    }
 
     match state.db.delete_api_key(&body.key_name) {
         Ok(deleted) => {
             if deleted {
+                state.config_watch.notify(ConfigChange::ApiKey { key_name: body.key_name.clone() });
                 HttpResponse::Ok().json(ApiKeyOperationResponse {
                     success: true,
                     key: None,
@@ -702,6 +828,9 @@ async fn backup_to_cloud(state: web::Data<AppState>, req: HttpRequest) -> impl R
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
     }
+    if let Err(resp) = super::require_second_factor(&state, &req) {
+        return resp;
+    }
 
     // Wallet provider is the source of truth (Standard=EnvWalletProvider, Flash=FlashWalletProvider)
     let wallet_provider = match &state.wallet_provider {
@@ -1000,6 +1129,9 @@ async fn restore_from_cloud(state: web::Data<AppState>, req: HttpRequest) -> imp
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
     }
+    if let Err(resp) = super::require_second_factor(&state, &req) {
+        return resp;
+    }
 
     // Wallet provider is the source of truth (Standard=EnvWalletProvider, Flash=FlashWalletProvider)
     let wallet_provider = match &state.wallet_provider {
@@ -1309,6 +1441,11 @@ async fn restore_from_cloud(state: web::Data<AppState>, req: HttpRequest) -> imp
         let _ = state.db.record_keystore_retrieval(&wallet_address);
     }
 
+    // Restored keys and settings replace whatever the running services loaded
+    state.config_watch.notify(ConfigChange::ApiKey { key_name: ApiKeyId::AlchemyApiKey.as_str().to_string() });
+    state.config_watch.notify(ConfigChange::BotSettings);
+    state.config_watch.notify(ConfigChange::AgentSettings);
+
     HttpResponse::Ok().json(BackupResponse {
         success: true,
         key_count: Some(restore_result.api_keys),
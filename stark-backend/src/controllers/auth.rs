@@ -4,6 +4,14 @@ use ethers::core::types::Signature;
 use ethers::utils::hash_message;
 use serde::{Deserialize, Serialize};
 
+use crate::controllers::openapi::{
+    any_object, array, boolean, date_time, integer, nullable, object, reference, string, ApiDoc,
+};
+use crate::controllers::validate_session;
+use crate::db::tables::auth::RefreshOutcome;
+use crate::error::{AppError, AppResult};
+use crate::middleware::http_security::{cleared_cookies, session_cookies};
+use crate::models::AuthDevice;
 use crate::AppState;
 
 const SERVICE_NAME: &str = "StarkBot";
@@ -36,13 +44,46 @@ pub struct LoginResponse {
     token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     expires_at: Option<i64>,
+    /// Exchange at /api/auth/refresh for a new token; rotated on every use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refresh_expires_at: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
 }
 
+impl LoginResponse {
+    fn failure(error: &str) -> Self {
+        LoginResponse {
+            success: false,
+            token: None,
+            expires_at: None,
+            refresh_token: None,
+            refresh_expires_at: None,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RefreshRequest {
+    refresh_token: String,
+}
+
+/// A device in the session list
+#[derive(Serialize)]
+struct DeviceResponse {
+    #[serde(flatten)]
+    device: AuthDevice,
+    /// The session making this request
+    current: bool,
+}
+
 #[derive(Deserialize)]
 pub struct LogoutRequest {
-    token: String,
+    /// Defaults to the session cookie or bearer token of the request
+    token: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -61,7 +102,11 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/generate_challenge", web::post().to(generate_challenge))
             .route("/validate_auth", web::post().to(validate_auth))
             .route("/logout", web::post().to(logout))
-            .route("/validate", web::get().to(validate)),
+            .route("/validate", web::get().to(validate))
+            .route("/refresh", web::post().to(refresh))
+            .route("/sessions", web::get().to(list_devices))
+            .route("/sessions/revoke_others", web::post().to(revoke_other_devices))
+            .route("/sessions/{id}", web::delete().to(revoke_device)),
     );
     // Flash mode auth - separate from /api/auth scope to allow redirect
     cfg.route("/auth/flash", web::get().to(flash_login));
@@ -123,6 +168,7 @@ async fn generate_challenge(
 
 async fn validate_auth(
     state: web::Data<AppState>,
+    req: HttpRequest,
     body: web::Json<ValidateAuthRequest>,
 ) -> impl Responder {
     let public_address = body.public_address.trim().to_lowercase();
@@ -131,96 +177,188 @@ async fn validate_auth(
 
     // Validate it looks like an Ethereum address
     if !public_address.starts_with("0x") || public_address.len() != 42 {
-        return HttpResponse::BadRequest().json(LoginResponse {
-            success: false,
-            token: None,
-            expires_at: None,
-            error: Some("Invalid public address".to_string()),
-        });
+        return HttpResponse::BadRequest().json(LoginResponse::failure("Invalid public address"));
     }
 
     // Check that login is configured
     let admin_address = match &state.config.login_admin_public_address {
         Some(addr) => addr.to_lowercase(),
         None => {
-            return HttpResponse::ServiceUnavailable().json(LoginResponse {
-                success: false,
-                token: None,
-                expires_at: None,
-                error: Some("Login is not configured for this instance.".to_string()),
-            });
+            return HttpResponse::ServiceUnavailable().json(LoginResponse::failure("Login is not configured for this instance."));
         }
     };
 
     // Check that this address is the admin address
     if public_address != admin_address {
-        return HttpResponse::Unauthorized().json(LoginResponse {
-            success: false,
-            token: None,
-            expires_at: None,
-            error: Some("Unauthorized wallet address".to_string()),
-        });
+        return HttpResponse::Unauthorized().json(LoginResponse::failure("Unauthorized wallet address"));
     }
 
     // Verify the challenge exists and matches
     match state.db.validate_challenge(&public_address, challenge) {
         Ok(true) => {}
         Ok(false) => {
-            return HttpResponse::Unauthorized().json(LoginResponse {
-                success: false,
-                token: None,
-                expires_at: None,
-                error: Some("No active challenge found or challenge mismatch".to_string()),
-            });
+            return HttpResponse::Unauthorized().json(LoginResponse::failure("No active challenge found or challenge mismatch"));
         }
         Err(e) => {
             log::error!("Failed to validate challenge: {}", e);
-            return HttpResponse::InternalServerError().json(LoginResponse {
-                success: false,
-                token: None,
-                expires_at: None,
-                error: Some("Database error".to_string()),
-            });
+            return HttpResponse::InternalServerError().json(LoginResponse::failure("Database error"));
         }
     }
 
     // Verify signature
     let recovered_address = recover_address(challenge, signature);
     if recovered_address.as_deref() != Some(public_address.as_str()) {
-        return HttpResponse::Unauthorized().json(LoginResponse {
-            success: false,
-            token: None,
-            expires_at: None,
-            error: Some("Invalid signature".to_string()),
-        });
+        return HttpResponse::Unauthorized().json(LoginResponse::failure("Invalid signature"));
     }
 
     // Delete the used challenge
     let _ = state.db.delete_challenge(&public_address);
 
-    // Create session
-    match state.db.create_session_for_address(Some(&public_address)) {
-        Ok(session) => HttpResponse::Ok().json(LoginResponse {
+    // Create session, remember the device and hand out a refresh token
+    let created = state.db.create_session_for_address(Some(&public_address)).and_then(|session| {
+        let (user_agent, ip_address) = device_info(&req);
+        state.db.set_session_device(session.id, user_agent.as_deref(), ip_address.as_deref())?;
+        let (refresh_token, refresh_expires_at) = state.db.issue_refresh_token(session.id)?;
+        Ok((session, refresh_token, refresh_expires_at))
+    });
+    match created {
+        Ok((session, refresh_token, refresh_expires_at)) => {
+            signed_in(&req, session, refresh_token, refresh_expires_at)
+        }
+        Err(e) => {
+            log::error!("Failed to create session: {}", e);
+            HttpResponse::InternalServerError().json(LoginResponse::failure("Failed to create session"))
+        }
+    }
+}
+
+/// Login/refresh response: the tokens in the body, plus the session and CSRF
+/// cookies for cookie-based frontends
+fn signed_in(
+    req: &HttpRequest,
+    session: crate::models::Session,
+    refresh_token: String,
+    refresh_expires_at: chrono::DateTime<Utc>,
+) -> HttpResponse {
+    let secure = req.connection_info().scheme() == "https";
+    let max_age = (refresh_expires_at - Utc::now()).num_seconds();
+    let (session_cookie, csrf_cookie) = session_cookies(&session.token, max_age, secure);
+    HttpResponse::Ok()
+        .cookie(session_cookie)
+        .cookie(csrf_cookie)
+        .json(LoginResponse {
             success: true,
             token: Some(session.token),
             expires_at: Some(session.expires_at.timestamp()),
+            refresh_token: Some(refresh_token),
+            refresh_expires_at: Some(refresh_expires_at.timestamp()),
             error: None,
-        }),
+        })
+}
+
+/// User agent and client IP of a request, for the device list
+fn device_info(req: &HttpRequest) -> (Option<String>, Option<String>) {
+    let user_agent = req
+        .headers()
+        .get(actix_web::http::header::USER_AGENT)
+        .and_then(|h| h.to_str().ok())
+        .map(|ua| ua.chars().take(256).collect());
+    let ip_address = req.connection_info().realip_remote_addr().map(str::to_string);
+    (user_agent, ip_address)
+}
+
+fn bearer_token(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string)
+}
+
+/// POST /api/auth/refresh - Trade a refresh token for a new session token and refresh token
+async fn refresh(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<RefreshRequest>,
+) -> impl Responder {
+    match state.db.refresh_session(body.refresh_token.trim()) {
+        Ok(RefreshOutcome::Rotated { session, refresh_token, refresh_expires_at }) => {
+            signed_in(&req, session, refresh_token, refresh_expires_at)
+        }
+        Ok(RefreshOutcome::Reused) => {
+            log::warn!("[AUTH] Rotated refresh token presented again; revoked the device's session");
+            HttpResponse::Unauthorized().json(LoginResponse::failure(
+                "Refresh token was already used; the session has been revoked",
+            ))
+        }
+        Ok(RefreshOutcome::Invalid) => {
+            HttpResponse::Unauthorized().json(LoginResponse::failure("Invalid or expired refresh token"))
+        }
         Err(e) => {
-            log::error!("Failed to create session: {}", e);
-            HttpResponse::InternalServerError().json(LoginResponse {
-                success: false,
-                token: None,
-                expires_at: None,
-                error: Some("Failed to create session".to_string()),
-            })
+            log::error!("Failed to refresh session: {}", e);
+            HttpResponse::InternalServerError().json(LoginResponse::failure("Database error"))
         }
     }
 }
 
-async fn logout(state: web::Data<AppState>, body: web::Json<LogoutRequest>) -> impl Responder {
-    match state.db.delete_session(&body.token) {
-        Ok(_) => HttpResponse::Ok().json(LogoutResponse { success: true }),
+/// GET /api/auth/sessions - Signed-in devices
+async fn list_devices(state: web::Data<AppState>, req: HttpRequest) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&state, &req) {
+        return Ok(resp);
+    }
+    let current_id = match bearer_token(&req) {
+        Some(token) => state.db.session_id_for_token(&token)?,
+        None => None,
+    };
+    let devices: Vec<DeviceResponse> = state
+        .db
+        .list_auth_devices()?
+        .into_iter()
+        .map(|device| DeviceResponse { current: Some(device.id) == current_id, device })
+        .collect();
+    Ok(HttpResponse::Ok().json(devices))
+}
+
+/// DELETE /api/auth/sessions/{id} - Sign a device out
+async fn revoke_device(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&state, &req) {
+        return Ok(resp);
+    }
+    let id = path.into_inner();
+    if !state.db.delete_session_by_id(id)? {
+        return Err(AppError::not_found(format!("No session {}", id)));
+    }
+    log::info!("[AUTH] Revoked session {}", id);
+    Ok(HttpResponse::Ok().json(LogoutResponse { success: true }))
+}
+
+/// POST /api/auth/sessions/revoke_others - Sign out every other device
+async fn revoke_other_devices(state: web::Data<AppState>, req: HttpRequest) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&state, &req) {
+        return Ok(resp);
+    }
+    let token = bearer_token(&req).unwrap_or_default();
+    let revoked = state.db.delete_other_sessions(&token)?;
+    log::info!("[AUTH] Revoked {} other session(s)", revoked);
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true, "revoked": revoked })))
+}
+
+async fn logout(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<LogoutRequest>,
+) -> impl Responder {
+    let token = body.into_inner().token.or_else(|| bearer_token(&req)).unwrap_or_default();
+    let (session_cookie, csrf_cookie) = cleared_cookies();
+    match state.db.delete_session(&token) {
+        Ok(_) => HttpResponse::Ok()
+            .cookie(session_cookie)
+            .cookie(csrf_cookie)
+            .json(LogoutResponse { success: true }),
         Err(e) => {
             log::error!("Failed to delete session: {}", e);
             HttpResponse::InternalServerError().json(LogoutResponse { success: false })
@@ -229,20 +367,14 @@ async fn logout(state: web::Data<AppState>, body: web::Json<LogoutRequest>) -> i
 }
 
 async fn validate(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
-    let token = req
-        .headers()
-        .get("Authorization")
-        .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
-
-    let token = match token {
+    let token = match bearer_token(&req) {
         Some(t) => t,
         None => {
             return HttpResponse::Ok().json(ValidateResponse { valid: false });
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, &req, &token) {
         Ok(Some(_)) => HttpResponse::Ok().json(ValidateResponse { valid: true }),
         Ok(None) => HttpResponse::Ok().json(ValidateResponse { valid: false }),
         Err(e) => {
@@ -274,6 +406,7 @@ struct FlashValidateResponse {
 /// and redirect to the dashboard with the session cookie set.
 async fn flash_login(
     state: web::Data<AppState>,
+    req: HttpRequest,
     query: web::Query<FlashLoginQuery>,
 ) -> impl Responder {
     use crate::wallet;
@@ -341,6 +474,10 @@ async fn flash_login(
             return HttpResponse::InternalServerError().body("Failed to create session");
         }
     };
+    let (user_agent, ip_address) = device_info(&req);
+    if let Err(e) = state.db.set_session_device(session.id, user_agent.as_deref(), ip_address.as_deref()) {
+        log::warn!("Failed to record session device: {}", e);
+    }
 
     // Redirect to auth page with token in query params
     // The frontend will extract the token and store it
@@ -350,3 +487,58 @@ async fn flash_login(
         .append_header(("Location", redirect_url))
         .finish()
 }
+
+/// OpenAPI description of the auth routes
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.post("/api/auth/generate_challenge", "auth", "Get a SIWE challenge for a wallet address")
+        .public()
+        .body(object(&[("public_address", string())], &["public_address"]))
+        .returns(any_object());
+    doc.post("/api/auth/validate_auth", "auth", "Exchange a signed SIWE challenge for a session token")
+        .public()
+        .body(object(
+            &[("public_address", string()), ("challenge", string()), ("signature", string())],
+            &["public_address", "challenge", "signature"],
+        ))
+        .returns(any_object());
+    doc.post("/api/auth/logout", "auth", "End a session")
+        .public()
+        .body(object(&[("token", string())], &[]))
+        .returns(any_object());
+    doc.get("/api/auth/validate", "auth", "Check the session token").returns(any_object());
+    doc.post("/api/auth/refresh", "auth", "Exchange a refresh token for a new session token and refresh token")
+        .public()
+        .body(object(&[("refresh_token", string())], &["refresh_token"]))
+        .returns(object(
+            &[
+                ("success", boolean()),
+                ("token", string()),
+                ("expires_at", integer()),
+                ("refresh_token", string()),
+                ("refresh_expires_at", integer()),
+            ],
+            &["success"],
+        ));
+    doc.schema(
+        "AuthDevice",
+        object(
+            &[
+                ("id", integer()),
+                ("public_address", nullable(string())),
+                ("user_agent", nullable(string())),
+                ("ip_address", nullable(string())),
+                ("created_at", date_time()),
+                ("last_active_at", nullable(date_time())),
+                ("expires_at", date_time()),
+                ("refresh_expires_at", nullable(date_time())),
+                ("second_factor_at", nullable(date_time())),
+                ("current", boolean()),
+            ],
+            &["id", "created_at", "expires_at", "current"],
+        ),
+    );
+    doc.get("/api/auth/sessions", "auth", "Signed-in devices").returns(array(reference("AuthDevice")));
+    doc.delete("/api/auth/sessions/{id}", "auth", "Sign a device out");
+    doc.post("/api/auth/sessions/revoke_others", "auth", "Sign out every other device")
+        .returns(object(&[("success", boolean()), ("revoked", integer())], &["success"]));
+}
@@ -0,0 +1,127 @@
+//! Away mode API — schedule, auto-reply message and the actions autonomous
+//! runs may take while the owner is away, plus the queue of held requests
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use super::validate_session;
+use crate::away::{self, AwaySettings};
+use crate::controllers::openapi::{any_object, array, boolean, object, string, ApiDoc};
+use crate::gateway::protocol::GatewayEvent;
+use crate::AppState;
+
+/// GET /api/away - Settings, whether away mode applies now, and the queue size
+async fn get_away(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let settings = away::load(&data.db);
+    HttpResponse::Ok().json(serde_json::json!({
+        "active": settings.is_active_at(chrono::Utc::now()),
+        "queued": data.db.count_away_requests().unwrap_or(0),
+        "settings": settings,
+    }))
+}
+
+/// PUT /api/away - Replace the away mode settings
+async fn update_away(data: web::Data<AppState>, req: HttpRequest, body: web::Json<AwaySettings>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let mut settings = body.into_inner();
+    if settings.starts_at.zip(settings.ends_at).is_some_and(|(start, end)| end <= start) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": "ends_at must be after starts_at" }));
+    }
+    settings.message = settings.message.map(|m| m.trim().to_string()).filter(|m| !m.is_empty());
+    settings.allowed_actions = settings
+        .allowed_actions
+        .iter()
+        .map(|a| a.trim().to_lowercase())
+        .filter(|a| !a.is_empty())
+        .collect();
+    settings.allowed_actions.sort();
+    settings.allowed_actions.dedup();
+    if let Err(e) = away::save(&data.db, &settings) {
+        return HttpResponse::InternalServerError().json(serde_json::json!({ "error": e }));
+    }
+    log::info!("Away mode {}", if settings.enabled { "enabled" } else { "disabled" });
+    data.broadcaster.broadcast(GatewayEvent::custom("away_mode.changed", serde_json::json!(settings)));
+    HttpResponse::Ok().json(serde_json::json!({
+        "active": settings.is_active_at(chrono::Utc::now()),
+        "settings": settings,
+    }))
+}
+
+/// GET /api/away/queue - Requests held until away mode ends
+async fn list_queue(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_away_requests() {
+        Ok(requests) => HttpResponse::Ok().json(requests),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// DELETE /api/away/queue - Drop every held request without answering it
+async fn clear_queue(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.clear_away_requests() {
+        Ok(deleted) => HttpResponse::Ok().json(serde_json::json!({ "deleted": deleted })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// DELETE /api/away/queue/{id} - Drop one held request
+async fn delete_request(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.delete_away_request(path.into_inner()) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({ "error": "Request not found" })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/away")
+            .route("", web::get().to(get_away))
+            .route("", web::put().to(update_away))
+            .route("/queue", web::get().to(list_queue))
+            .route("/queue", web::delete().to(clear_queue))
+            .route("/queue/{id}", web::delete().to(delete_request)),
+    );
+}
+
+/// OpenAPI description of the away mode routes
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.get("/api/away", "away", "Away mode settings, whether it applies now, and queued requests")
+        .returns(any_object());
+    doc.put("/api/away", "away", "Set away mode: schedule, auto-reply and actions allowed while away")
+        .body(object(
+            &[
+                ("enabled", boolean()),
+                ("message", string()),
+                ("starts_at", string()),
+                ("ends_at", string()),
+                ("allowed_actions", array(string())),
+            ],
+            &["enabled"],
+        ))
+        .returns(any_object());
+    doc.get("/api/away/queue", "away", "Requests held until away mode ends")
+        .returns(any_object());
+    doc.delete("/api/away/queue", "away", "Drop all held requests")
+        .returns(any_object());
+    doc.delete("/api/away/queue/{id}", "away", "Drop one held request")
+        .returns(any_object());
+}
@@ -4,10 +4,35 @@ use serde::Serialize;
 use crate::models::{
     get_settings_for_channel_type, ChannelResponse, ChannelSettingsResponse,
     ChannelSettingsSchemaResponse, ChannelType, CreateChannelRequest, CreateSafeModeChannelRequest,
-    UpdateChannelRequest, UpdateChannelSettingsRequest,
+    ChannelSettingKey, UpdateChannelRequest, UpdateChannelSettingsRequest,
 };
+use crate::channels::{group_gate, proactive};
+use crate::config_watch::ConfigChange;
+use crate::controllers::validation::{Valid, Validate, Validator};
 use crate::AppState;
 
+impl Validate for UpdateChannelSettingsRequest {
+    fn validate(&self, v: &mut Validator) {
+        for (i, setting) in self.settings.iter().enumerate() {
+            let field = format!("settings[{}].value", i);
+            if setting.key == ChannelSettingKey::GroupTriggerPattern.as_ref() {
+                if let Err(e) = group_gate::validate_pattern(&setting.value) {
+                    v.error(&field, e);
+                }
+            } else if setting.key == ChannelSettingKey::ProactiveQuietHours.as_ref() {
+                if let Err(e) = proactive::QuietHours::parse(&setting.value) {
+                    v.error(&field, e);
+                }
+            } else if setting.key == ChannelSettingKey::ProactiveDailyLimit.as_ref()
+                && !setting.value.trim().is_empty()
+                && setting.value.trim().parse::<u32>().is_err()
+            {
+                v.error(&field, "must be a whole number (0 = unlimited)");
+            }
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct ChannelsListResponse {
     pub success: bool,
@@ -66,7 +91,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -79,7 +105,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(ChannelsListResponse {
             success: false,
@@ -183,7 +209,7 @@ async fn create_channel(
         return HttpResponse::BadRequest().json(ChannelOperationResponse {
             success: false,
             channel: None,
-            error: Some("Invalid channel type. Valid options: telegram, slack, discord, twitter, external_channel".to_string()),
+            error: Some("Invalid channel type. Valid options: telegram, slack, discord, twitter, farcaster, external_channel".to_string()),
         });
     }
 
@@ -251,7 +277,7 @@ async fn create_safe_mode_channel(
         return HttpResponse::BadRequest().json(SafeModeChannelResponse {
             success: false,
             channel: None,
-            error: Some("Invalid channel type. Valid options: telegram, slack, discord, twitter".to_string()),
+            error: Some("Invalid channel type. Valid options: telegram, slack, discord, twitter, farcaster".to_string()),
             queue_length: state.safe_mode_rate_limiter.queue_len(),
             next_slot_ms: state.safe_mode_rate_limiter.time_until_available_ms(),
         });
@@ -404,6 +430,8 @@ async fn update_channel(
         Ok(Some(channel)) => {
             let channel_manager = state.gateway.channel_manager();
             let running = channel_manager.is_running(channel.id);
+            // Running listeners hold the old token — the reload worker restarts them
+            state.config_watch.notify(ConfigChange::Channel { channel_id: channel.id });
             let response = ChannelResponse::from(channel).with_running(running);
 
             HttpResponse::Ok().json(ChannelOperationResponse {
@@ -665,7 +693,7 @@ async fn update_channel_settings(
     state: web::Data<AppState>,
     req: HttpRequest,
     path: web::Path<i64>,
-    body: web::Json<UpdateChannelSettingsRequest>,
+    body: Valid<UpdateChannelSettingsRequest>,
 ) -> impl Responder {
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
@@ -700,6 +728,7 @@ async fn update_channel_settings(
 
     match state.db.update_channel_settings(id, &settings_tuples) {
         Ok(()) => {
+            state.config_watch.notify(ConfigChange::Channel { channel_id: id });
             // Return updated settings
             match state.db.get_channel_settings(id) {
                 Ok(settings) => HttpResponse::Ok().json(ChannelSettingsResponse {
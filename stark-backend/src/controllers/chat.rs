@@ -1,7 +1,19 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 
+use crate::ai::multi_agent::types::PlannerTask;
+use crate::channels::actions;
+use crate::channels::chat_commands;
+use crate::channels::types::ActionEvent;
 use crate::channels::NormalizedMessage;
+use crate::db::tables::idempotency::IdempotencyClaim;
+use crate::db::tables::tool_confirmations::TOOL_CONFIRMATION_PENDING;
+use crate::db::tables::tx_approvals::TX_APPROVAL_PENDING;
+use crate::models::{ChatSession, MessageAttachment};
+use crate::controllers::openapi::{
+    any_object, array, boolean, envelope, integer, object, reference, string, string_enum, ApiDoc,
+};
+use crate::controllers::validation::{Valid, Validate, Validator};
 use crate::AppState;
 
 /// Web channel ID - a reserved ID for web-based chat
@@ -9,7 +21,19 @@ use crate::AppState;
 const WEB_CHANNEL_ID: i64 = 0;
 const WEB_CHANNEL_TYPE: &str = "web";
 
-#[derive(Debug, Deserialize)]
+/// Header clients set to make /api/chat retries safe
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// How long a completed Idempotency-Key result is replayable
+const IDEMPOTENCY_TTL_SECS: i64 = 24 * 3600;
+/// Approvals of each kind checked for the session status bar
+const PENDING_APPROVALS_LIMIT: usize = 50;
+/// Longest single chat message accepted from the web UI
+const MAX_CHAT_MESSAGE_CHARS: usize = 50_000;
+/// Most history messages a chat request may carry
+const MAX_CHAT_HISTORY: usize = 200;
+const MAX_CHAT_ATTACHMENTS: usize = 20;
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ChatRequest {
     pub messages: Vec<ChatMessage>,
     /// Optional user identifier for the web session
@@ -18,6 +42,9 @@ pub struct ChatRequest {
     /// Currently selected network from the UI (e.g., "base", "polygon", "mainnet")
     #[serde(default)]
     pub network: Option<String>,
+    /// Files attached to the latest user message (uploaded elsewhere; referenced here)
+    #[serde(default)]
+    pub attachments: Vec<MessageAttachment>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -26,6 +53,30 @@ pub struct ChatMessage {
     pub content: String,
 }
 
+impl Validate for ChatRequest {
+    fn validate(&self, v: &mut Validator) {
+        if self.messages.is_empty() {
+            v.error("messages", "must contain at least one message");
+        }
+        if self.messages.len() > MAX_CHAT_HISTORY {
+            v.error("messages", format!("must contain at most {} messages", MAX_CHAT_HISTORY));
+        }
+        for (i, message) in self.messages.iter().enumerate() {
+            v.one_of(&format!("messages[{}].role", i), &message.role, &["user", "assistant", "system"]);
+            v.max_len(&format!("messages[{}].content", i), &message.content, MAX_CHAT_MESSAGE_CHARS);
+        }
+        if self.attachments.len() > MAX_CHAT_ATTACHMENTS {
+            v.error("attachments", format!("at most {} attachments per message", MAX_CHAT_ATTACHMENTS));
+        }
+        if let Some(user_id) = &self.user_id {
+            v.max_len("user_id", user_id, 128);
+        }
+        if let Some(network) = &self.network {
+            v.max_len("network", network, 64);
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct ChatResponse {
     pub success: bool,
@@ -131,6 +182,8 @@ pub struct GetPlannerTasksResponse {
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(web::resource("/api/chat").route(web::post().to(chat)))
         .service(web::resource("/api/chat/stop").route(web::post().to(stop_execution)))
+        .service(web::resource("/api/chat/pause").route(web::post().to(pause_execution)))
+        .service(web::resource("/api/chat/resume").route(web::post().to(resume_execution)))
         .service(web::resource("/api/chat/execution-status").route(web::get().to(get_execution_status)))
         .service(web::resource("/api/chat/subagents").route(web::get().to(list_subagents)))
         .service(web::resource("/api/chat/subagents/cancel").route(web::post().to(cancel_subagent)))
@@ -145,14 +198,15 @@ pub fn config(cfg: &mut web::ServiceConfig) {
 async fn chat(
     state: web::Data<AppState>,
     req: HttpRequest,
-    body: web::Json<ChatRequest>,
+    body: Valid<ChatRequest>,
 ) -> impl Responder {
     // Validate session token
     let token = req
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -168,7 +222,7 @@ async fn chat(
     };
 
     // Validate the session
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, &req, &token) {
         Ok(Some(_)) => {} // Session is valid
         Ok(None) => {
             return HttpResponse::Unauthorized().json(ChatResponse {
@@ -241,6 +295,66 @@ async fn chat(
 
     let chat_context = if recent_context.is_empty() { None } else { Some(recent_context) };
 
+    // Client-supplied Idempotency-Key: a retry of a completed request replays the
+    // original answer instead of running the agent a second time.
+    let idempotency = match req.headers().get(IDEMPOTENCY_KEY_HEADER).map(|h| h.to_str()) {
+        None => None,
+        Some(Ok(key)) if !key.trim().is_empty() && key.len() <= 255 => {
+            Some((format!("http:{}", user_id), key.trim().to_string()))
+        }
+        Some(_) => {
+            return HttpResponse::BadRequest().json(ChatResponse {
+                success: false,
+                message: None,
+                error: Some("Idempotency-Key must be 1-255 visible ASCII characters".to_string()),
+                session_id: None,
+                message_id: None,
+            });
+        }
+    };
+    // A key only replays for the same request body
+    let request_hash = serde_json::to_vec(&*body)
+        .map(|bytes| hex::encode(<sha2::Sha256 as sha2::Digest>::digest(&bytes)))
+        .unwrap_or_default();
+    if let Some((ref scope, ref key)) = idempotency {
+        match state.db.claim_idempotency_key(scope, key, Some(&request_hash)) {
+            Ok(IdempotencyClaim::New) => {}
+            Ok(IdempotencyClaim::Completed(stored)) => {
+                return HttpResponse::Ok()
+                    .insert_header(("Idempotent-Replayed", "true"))
+                    .json(ChatResponse {
+                        success: true,
+                        message: Some(ChatMessage {
+                            role: "assistant".to_string(),
+                            content: stored.response,
+                        }),
+                        error: None,
+                        session_id: None,
+                        message_id: stored.message_id,
+                    });
+            }
+            Ok(IdempotencyClaim::Mismatch) => {
+                return HttpResponse::UnprocessableEntity().json(ChatResponse {
+                    success: false,
+                    message: None,
+                    error: Some("This Idempotency-Key was already used for a different request".to_string()),
+                    session_id: None,
+                    message_id: None,
+                });
+            }
+            Ok(IdempotencyClaim::InProgress) => {
+                return HttpResponse::Conflict().json(ChatResponse {
+                    success: false,
+                    message: None,
+                    error: Some("A request with this Idempotency-Key is still in progress".to_string()),
+                    session_id: None,
+                    message_id: None,
+                });
+            }
+            Err(e) => log::warn!("Idempotency check failed for key {}: {}", key, e),
+        }
+    }
+
     // Create a normalized message for the dispatcher
     // This makes web chat go through the same pipeline as Telegram/Slack
     let normalized = NormalizedMessage {
@@ -255,14 +369,34 @@ async fn chat(
         session_mode: None,
         selected_network: body.network.clone(),
         force_safe_mode: false,
+        shared_chat: false,
         platform_role_ids: vec![],
+        attachments: body.attachments.clone(),
         chat_context,
+        action: None,
     };
 
     // Dispatch through the unified pipeline
     // This gives us: sessions, identities, memories, tool execution, gateway events
     let result = state.dispatcher.dispatch_safe(normalized).await;
 
+    if let Some((scope, key)) = idempotency {
+        let recorded = if result.error.is_some() {
+            state.db.release_idempotency_key(&scope, &key)
+        } else {
+            state.db.complete_idempotency_key(
+                &scope,
+                &key,
+                &result.response,
+                result.message_id.as_deref(),
+                IDEMPOTENCY_TTL_SECS,
+            )
+        };
+        if let Err(e) = recorded {
+            log::warn!("Failed to record idempotency result for key {}: {}", key, e);
+        }
+    }
+
     if let Some(error) = result.error {
         log::error!("Chat dispatch error: {}", error);
         return HttpResponse::InternalServerError().json(ChatResponse {
@@ -298,7 +432,8 @@ async fn stop_execution(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -312,7 +447,7 @@ async fn stop_execution(
     };
 
     // Validate the session
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, &req, &token) {
         Ok(Some(_)) => {} // Session is valid
         Ok(None) => {
             return HttpResponse::Unauthorized().json(StopResponse {
@@ -366,6 +501,114 @@ async fn stop_execution(
     })
 }
 
+/// Pause the current agent execution for the web channel.
+/// The tool loop checkpoints at its next safe boundary and releases the channel.
+async fn pause_execution(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(resp) = super::validate_session(&state, &req) {
+        return resp;
+    }
+
+    if !state.execution_tracker.request_pause(WEB_CHANNEL_ID) {
+        return HttpResponse::Conflict().json(StopResponse {
+            success: false,
+            message: None,
+            error: Some("No execution is running".to_string()),
+        });
+    }
+
+    log::info!("[CHAT_PAUSE] Pause requested for web channel {}", WEB_CHANNEL_ID);
+    HttpResponse::Ok().json(StopResponse {
+        success: true,
+        message: Some("Execution will pause after the current step".to_string()),
+        error: None,
+    })
+}
+
+/// Resume the most recently paused execution on the web channel
+async fn resume_execution(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(resp) = super::validate_session(&state, &req) {
+        return resp;
+    }
+
+    let paused = match state.db.get_latest_paused_execution(WEB_CHANNEL_ID) {
+        Ok(Some(paused)) => paused,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ChatResponse {
+                success: false,
+                message: None,
+                error: Some("No paused execution to resume".to_string()),
+                session_id: None,
+                message_id: None,
+            });
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ChatResponse {
+                success: false,
+                message: None,
+                error: Some(format!("Database error: {}", e)),
+                session_id: None,
+                message_id: None,
+            });
+        }
+    };
+
+    log::info!(
+        "[CHAT_RESUME] Resuming execution paused at {} (session {})",
+        paused.paused_at, paused.session_id
+    );
+    let normalized = NormalizedMessage {
+        channel_id: WEB_CHANNEL_ID,
+        channel_type: WEB_CHANNEL_TYPE.to_string(),
+        chat_id: paused.chat_id.clone(),
+        chat_name: None,
+        user_id: paused.chat_id.clone(),
+        user_name: format!("web-user-{}", &paused.chat_id[..8.min(paused.chat_id.len())]),
+        text: format!("Resume: {}", paused.context.original_request),
+        message_id: None,
+        session_mode: None,
+        selected_network: paused.context.selected_network.clone(),
+        force_safe_mode: false,
+        shared_chat: false,
+        platform_role_ids: vec![],
+        attachments: vec![],
+        chat_context: None,
+        action: Some(ActionEvent {
+            action: actions::ACTION_RESUME.to_string(),
+            label: "Resume".to_string(),
+            payload: None,
+        }),
+    };
+
+    let result = state.dispatcher.dispatch_safe(normalized).await;
+    if let Some(error) = result.error {
+        log::error!("Resume dispatch error: {}", error);
+        return HttpResponse::InternalServerError().json(ChatResponse {
+            success: false,
+            message: None,
+            error: Some(error),
+            session_id: None,
+            message_id: None,
+        });
+    }
+
+    HttpResponse::Ok().json(ChatResponse {
+        success: true,
+        message: Some(ChatMessage {
+            role: "assistant".to_string(),
+            content: result.response,
+        }),
+        error: None,
+        session_id: None,
+        message_id: result.message_id,
+    })
+}
+
 /// Get the current execution status for the web channel
 async fn get_execution_status(
     state: web::Data<AppState>,
@@ -376,7 +619,8 @@ async fn get_execution_status(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -389,7 +633,7 @@ async fn get_execution_status(
     };
 
     // Validate the session
-    if state.db.validate_session(&token).ok().flatten().is_none() {
+    if crate::middleware::session_auth::validate_token(&state.db, &req, &token).ok().flatten().is_none() {
         return HttpResponse::Unauthorized().json(ExecutionStatusResponse {
             running: false,
             execution_id: None,
@@ -416,7 +660,8 @@ async fn list_subagents(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -429,7 +674,7 @@ async fn list_subagents(
     };
 
     // Validate the session
-    if state.db.validate_session(&token).ok().flatten().is_none() {
+    if crate::middleware::session_auth::validate_token(&state.db, &req, &token).ok().flatten().is_none() {
         return HttpResponse::Unauthorized().json(SubagentListResponse {
             success: false,
             subagents: vec![],
@@ -486,7 +731,8 @@ async fn cancel_subagent(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -500,7 +746,7 @@ async fn cancel_subagent(
     };
 
     // Validate the session
-    if state.db.validate_session(&token).ok().flatten().is_none() {
+    if crate::middleware::session_auth::validate_token(&state.db, &req, &token).ok().flatten().is_none() {
         return HttpResponse::Unauthorized().json(SubagentResponse {
             success: false,
             message: None,
@@ -553,7 +799,8 @@ async fn get_planner_tasks(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -566,7 +813,7 @@ async fn get_planner_tasks(
     };
 
     // Validate the session
-    if state.db.validate_session(&token).ok().flatten().is_none() {
+    if crate::middleware::session_auth::validate_token(&state.db, &req, &token).ok().flatten().is_none() {
         return HttpResponse::Unauthorized().json(GetPlannerTasksResponse {
             success: false,
             tasks: vec![],
@@ -590,7 +837,7 @@ async fn get_planner_tasks(
     })
 }
 
-/// Delete a planner task by ID
+/// Cancel a planner task by ID
 async fn delete_task(
     state: web::Data<AppState>,
     req: HttpRequest,
@@ -601,7 +848,8 @@ async fn delete_task(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -616,7 +864,7 @@ async fn delete_task(
     };
 
     // Validate the session
-    if state.db.validate_session(&token).ok().flatten().is_none() {
+    if crate::middleware::session_auth::validate_token(&state.db, &req, &token).ok().flatten().is_none() {
         return HttpResponse::Unauthorized().json(DeleteTaskResponse {
             success: false,
             message: None,
@@ -626,21 +874,31 @@ async fn delete_task(
     }
 
     let task_id = path.into_inner();
-    log::info!("[CHAT] Deleting planner task {} for web channel", task_id);
-
-    // Queue the task deletion - the dispatcher will handle it during the next checkpoint
-    state.execution_tracker.queue_task_deletion(WEB_CHANNEL_ID, task_id);
+    log::info!("[CHAT] Cancelling planner task {} for web channel", task_id);
+
+    let known = state
+        .execution_tracker
+        .get_planner_tasks(WEB_CHANNEL_ID)
+        .iter()
+        .any(|t| t.id == task_id);
+    if !known {
+        return HttpResponse::NotFound().json(DeleteTaskResponse {
+            success: false,
+            message: None,
+            error: Some(format!("Task {} not found", task_id)),
+            was_current_task: None,
+        });
+    }
 
-    // If there's an active execution, we need to signal the dispatcher to check for deletions
-    // The execution loop will pick up the deletion on its next iteration
-    // If the deleted task is the current one, we should also stop the execution
-    // For now, we'll just queue the deletion and let the frontend show it as deleted
+    // Cancels the running AI call and tool calls immediately when this is the
+    // current task; the dispatcher marks it cancelled and moves on to the next one
+    let was_current = state.execution_tracker.cancel_planner_task(WEB_CHANNEL_ID, task_id);
 
     HttpResponse::Ok().json(DeleteTaskResponse {
         success: true,
-        message: Some(format!("Task {} queued for deletion", task_id)),
+        message: Some(format!("Task {} cancelled", task_id)),
         error: None,
-        was_current_task: None, // We don't know until dispatcher processes it
+        was_current_task: Some(was_current),
     })
 }
 
@@ -656,10 +914,110 @@ pub struct WebSessionResponse {
     pub message_count: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<String>,
+    /// Live state for the session status bar (mode, tasks, approvals, context usage)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<WebSessionState>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 }
 
+/// Everything the frontend status bar shows for the current web session
+#[derive(Serialize)]
+pub struct WebSessionState {
+    pub running: bool,
+    /// Agent mode of the last run ("task_planner" or "assistant")
+    pub mode: Option<String>,
+    pub subtype: Option<String>,
+    /// Mode set with /mode: "plan", "direct" or "auto"
+    pub chat_mode: String,
+    pub tasks: Vec<PlannerTask>,
+    pub pending_approvals: Vec<PendingApproval>,
+    pub context_tokens: i32,
+    pub max_context_tokens: i32,
+    pub context_usage_pct: u32,
+    pub last_compaction_at: Option<String>,
+}
+
+/// Something in this session waiting on the user
+#[derive(Serialize)]
+pub struct PendingApproval {
+    /// "queued_tx", "tx_approval" or "tool_confirmation"
+    pub kind: &'static str,
+    pub id: String,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+}
+
+/// Share of the context window in use, as a whole percentage
+fn context_usage_pct(context_tokens: i32, max_context_tokens: i32) -> u32 {
+    if max_context_tokens <= 0 {
+        return 0;
+    }
+    ((context_tokens.max(0) as f64 / max_context_tokens as f64) * 100.0).round() as u32
+}
+
+/// Gather the status bar state for a web session
+fn web_session_state(state: &AppState, session: &ChatSession) -> WebSessionState {
+    let agent_context = state.db.get_agent_context(session.id).ok().flatten();
+
+    let mut pending_approvals: Vec<PendingApproval> = state
+        .tx_queue
+        .list_pending()
+        .into_iter()
+        .map(|tx| PendingApproval {
+            kind: "queued_tx",
+            summary: format!("{} to {} on {}", tx.value_formatted, tx.to, tx.network),
+            id: tx.uuid,
+            expires_at: None,
+        })
+        .collect();
+    pending_approvals.extend(
+        state
+            .db
+            .list_tx_approvals(Some(TX_APPROVAL_PENDING), PENDING_APPROVALS_LIMIT)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|a| a.channel_id == WEB_CHANNEL_ID)
+            .map(|a| PendingApproval { kind: "tx_approval", id: a.tx_uuid, summary: a.summary, expires_at: Some(a.expires_at) }),
+    );
+    pending_approvals.extend(
+        state
+            .db
+            .list_tool_confirmations(Some(TOOL_CONFIRMATION_PENDING), PENDING_APPROVALS_LIMIT)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| c.channel_id == WEB_CHANNEL_ID)
+            .map(|c| PendingApproval {
+                kind: "tool_confirmation",
+                id: c.uuid,
+                summary: format!("Run `{}`", c.tool_name),
+                expires_at: Some(c.expires_at),
+            }),
+    );
+
+    WebSessionState {
+        running: state.execution_tracker.get_execution_id(WEB_CHANNEL_ID).is_some(),
+        mode: agent_context.as_ref().map(|c| c.mode.to_string()),
+        subtype: agent_context.and_then(|c| c.subtype),
+        chat_mode: chat_commands::get_chat_mode(&state.db, WEB_CHANNEL_ID, &session.platform_chat_id)
+            .map(|m| m.as_str())
+            .unwrap_or("auto")
+            .to_string(),
+        tasks: state.execution_tracker.get_planner_tasks(WEB_CHANNEL_ID),
+        pending_approvals,
+        context_tokens: session.context_tokens,
+        max_context_tokens: session.max_context_tokens,
+        context_usage_pct: context_usage_pct(session.context_tokens, session.max_context_tokens),
+        last_compaction_at: state
+            .db
+            .get_session_last_compaction(session.id)
+            .ok()
+            .flatten()
+            .map(|t| t.to_rfc3339()),
+    }
+}
+
 /// Get the current active web session (or create one if none exists)
 async fn get_web_session(
     state: web::Data<AppState>,
@@ -670,7 +1028,8 @@ async fn get_web_session(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -681,19 +1040,21 @@ async fn get_web_session(
                 completion_status: None,
                 message_count: None,
                 created_at: None,
+                state: None,
                 error: Some("No authorization token provided".to_string()),
             });
         }
     };
 
     // Validate the session
-    if state.db.validate_session(&token).ok().flatten().is_none() {
+    if crate::middleware::session_auth::validate_token(&state.db, &req, &token).ok().flatten().is_none() {
         return HttpResponse::Unauthorized().json(WebSessionResponse {
             success: false,
             session_id: None,
             completion_status: None,
             message_count: None,
             created_at: None,
+            state: None,
             error: Some("Invalid or expired session".to_string()),
         });
     }
@@ -714,6 +1075,7 @@ async fn get_web_session(
                 completion_status: Some(session.completion_status.as_str().to_string()),
                 message_count,
                 created_at: Some(session.created_at.to_rfc3339()),
+                state: Some(web_session_state(&state, &session)),
                 error: None,
             })
         }
@@ -726,6 +1088,7 @@ async fn get_web_session(
                 completion_status: None,
                 message_count: Some(0),
                 created_at: None,
+                state: None,
                 error: None,
             })
         }
@@ -737,6 +1100,7 @@ async fn get_web_session(
                 completion_status: None,
                 message_count: None,
                 created_at: None,
+                state: None,
                 error: Some(format!("Database error: {}", e)),
             })
         }
@@ -753,7 +1117,8 @@ async fn new_web_session(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -764,19 +1129,21 @@ async fn new_web_session(
                 completion_status: None,
                 message_count: None,
                 created_at: None,
+                state: None,
                 error: Some("No authorization token provided".to_string()),
             });
         }
     };
 
     // Validate the session
-    if state.db.validate_session(&token).ok().flatten().is_none() {
+    if crate::middleware::session_auth::validate_token(&state.db, &req, &token).ok().flatten().is_none() {
         return HttpResponse::Unauthorized().json(WebSessionResponse {
             success: false,
             session_id: None,
             completion_status: None,
             message_count: None,
             created_at: None,
+            state: None,
             error: Some("Invalid or expired session".to_string()),
         });
     }
@@ -800,6 +1167,7 @@ async fn new_web_session(
                 completion_status: None,
                 message_count: Some(0),
                 created_at: None,
+                state: None,
                 error: None,
             })
         }
@@ -811,6 +1179,7 @@ async fn new_web_session(
                 completion_status: None,
                 message_count: Some(0),
                 created_at: None,
+                state: None,
                 error: None,
             })
         }
@@ -822,8 +1191,92 @@ async fn new_web_session(
                 completion_status: None,
                 message_count: None,
                 created_at: None,
+                state: None,
                 error: Some(format!("Database error: {}", e)),
             })
         }
     }
 }
+
+/// OpenAPI description of the chat routes
+pub fn openapi(doc: &mut ApiDoc) {
+    let message = object(&[("role", string_enum(&["user", "assistant"])), ("content", string())], &["role", "content"]);
+    doc.schema("ChatMessage", message);
+    doc.schema(
+        "ChatRequest",
+        object(
+            &[
+                ("messages", array(reference("ChatMessage"))),
+                ("user_id", string()),
+                ("network", string()),
+                (
+                    "attachments",
+                    array(object(
+                        &[
+                            ("type", string_enum(&["image", "audio", "video", "file"])),
+                            ("url", string()),
+                            ("storage_ref", string()),
+                            ("file_name", string()),
+                        ],
+                        &["type"],
+                    )),
+                ),
+            ],
+            &["messages"],
+        ),
+    );
+    doc.schema(
+        "ChatResponse",
+        envelope(&[
+            ("message", reference("ChatMessage")),
+            ("session_id", integer()),
+            ("message_id", string()),
+        ]),
+    );
+
+    doc.post("/api/chat", "chat", "Send a message to the agent and wait for its reply (send an Idempotency-Key header to make retries safe)")
+        .body(reference("ChatRequest"))
+        .returns(reference("ChatResponse"));
+    doc.post("/api/chat/stop", "chat", "Stop the running execution").returns(envelope(&[("message", string())]));
+    doc.post("/api/chat/pause", "chat", "Pause the running execution").returns(envelope(&[("message", string())]));
+    doc.post("/api/chat/resume", "chat", "Resume a paused execution").returns(envelope(&[("message", string())]));
+    doc.get("/api/chat/execution-status", "chat", "Whether an execution is running")
+        .returns(object(&[("running", boolean()), ("execution_id", string())], &["running"]));
+    doc.get("/api/chat/subagents", "chat", "Running subagents")
+        .query("session_id", integer(), "Only subagents of this session")
+        .returns(object(
+            &[
+                ("success", boolean()),
+                (
+                    "subagents",
+                    array(object(
+                        &[
+                            ("id", string()),
+                            ("label", string()),
+                            ("task", string()),
+                            ("status", string()),
+                            ("started_at", string()),
+                            ("session_id", integer()),
+                            ("parent_session_id", integer()),
+                        ],
+                        &["id", "label", "task", "status", "started_at", "parent_session_id"],
+                    )),
+                ),
+            ],
+            &["success", "subagents"],
+        ));
+    doc.post("/api/chat/subagents/cancel", "chat", "Cancel a subagent")
+        .body(object(&[("subagent_id", string())], &["subagent_id"]))
+        .returns(envelope(&[("message", string())]));
+    doc.get("/api/chat/tasks", "chat", "The task planner's tasks for the web session").returns(object(
+        &[
+            ("success", boolean()),
+            ("tasks", array(object(&[("id", integer()), ("description", string()), ("status", string())], &["id", "description", "status"]))),
+        ],
+        &["success", "tasks"],
+    ));
+    doc.delete("/api/chat/tasks/{task_id}", "chat", "Delete a planner task")
+        .returns(envelope(&[("message", string()), ("was_current_task", boolean())]));
+    doc.get("/api/chat/session", "chat", "The current web chat session and its recent messages").returns(any_object());
+    doc.post("/api/chat/session/new", "chat", "Start a new web chat session").returns(any_object());
+}
@@ -0,0 +1,46 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use super::validate_session;
+use crate::cluster::{LEASE_MODULE_SERVICES, LEASE_SCHEDULER};
+use crate::AppState;
+
+/// Instances whose heartbeat is older than this are considered gone
+const INSTANCE_ACTIVE_WITHIN_SECS: i64 = 60;
+
+/// GET /api/cluster - This instance's identity, leadership, and the cluster view
+async fn get_status(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let cluster = &data.cluster;
+    let (instances, leases) = if cluster.enabled() {
+        match (
+            data.db.list_cluster_instances(INSTANCE_ACTIVE_WITHIN_SECS),
+            data.db.list_active_leases(),
+        ) {
+            (Ok(instances), Ok(leases)) => (instances, leases),
+            (Err(e), _) | (_, Err(e)) => {
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }));
+            }
+        }
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "enabled": cluster.enabled(),
+        "instance_id": cluster.instance_id(),
+        "leader": {
+            "scheduler": cluster.is_leader(LEASE_SCHEDULER),
+            "module_services": cluster.is_leader(LEASE_MODULE_SERVICES),
+        },
+        "instances": instances,
+        "leases": leases,
+    }))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/api/cluster").route("", web::get().to(get_status)));
+}
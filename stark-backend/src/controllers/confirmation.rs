@@ -151,7 +151,8 @@ fn validate_auth(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), H
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -165,7 +166,7 @@ fn validate_auth(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), H
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => {
             Err(HttpResponse::Unauthorized().json(ConfirmationResponse {
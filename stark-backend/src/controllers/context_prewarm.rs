@@ -0,0 +1,138 @@
+//! Context pre-warm API — configure active hours and sections, inspect the
+//! cache, and force a refresh.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate_session;
+use crate::context::prewarm::{self, ALL_SECTIONS};
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/context-prewarm")
+            .route("/config", web::get().to(get_config))
+            .route("/config", web::put().to(update_config))
+            .route("/cache", web::get().to(get_cache))
+            .route("/cache", web::delete().to(clear_cache))
+            .route("/refresh", web::post().to(refresh)),
+    );
+}
+
+#[derive(Deserialize)]
+struct UpdatePrewarmRequest {
+    enabled: Option<bool>,
+    active_hours: Option<String>,
+    lead_minutes: Option<i64>,
+    refresh_minutes: Option<i64>,
+    sections: Option<Vec<String>>,
+}
+
+async fn get_config(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.get_prewarm_config() {
+        Ok(config) => HttpResponse::Ok().json(json!({ "config": config, "available_sections": ALL_SECTIONS })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn update_config(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<UpdatePrewarmRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let mut config = match data.db.get_prewarm_config() {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+
+    let body = body.into_inner();
+    if let Some(enabled) = body.enabled {
+        config.enabled = enabled;
+    }
+    if let Some(hours) = body.active_hours {
+        if let Err(e) = prewarm::parse_active_hours(&hours) {
+            return HttpResponse::BadRequest().json(json!({ "error": e }));
+        }
+        config.active_hours = hours.trim().to_string();
+    }
+    if let Some(lead) = body.lead_minutes {
+        config.lead_minutes = lead.clamp(0, 180);
+    }
+    if let Some(refresh) = body.refresh_minutes {
+        config.refresh_minutes = refresh.clamp(5, 240);
+    }
+    if let Some(sections) = body.sections {
+        let sections: Vec<String> = sections.iter().map(|s| s.trim().to_lowercase()).collect();
+        if let Some(unknown) = sections.iter().find(|s| !ALL_SECTIONS.contains(&s.as_str())) {
+            return HttpResponse::BadRequest().json(json!({
+                "error": format!("Unknown section '{}'. Sections: {}", unknown, ALL_SECTIONS.join(", "))
+            }));
+        }
+        config.sections = sections.join(",");
+    }
+
+    match data.db.save_prewarm_config(&config) {
+        Ok(saved) => HttpResponse::Ok().json(json!({ "config": saved, "available_sections": ALL_SECTIONS })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn get_cache(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_prewarm_entries() {
+        Ok(entries) => HttpResponse::Ok().json(json!({ "entries": entries })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn clear_cache(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.clear_prewarm_cache() {
+        Ok(cleared) => HttpResponse::Ok().json(json!({ "success": true, "cleared": cleared })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// Refresh every configured section now, regardless of active hours
+async fn refresh(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let config = match data.db.get_prewarm_config() {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+    let refreshed = prewarm::run_prewarm_pass(&data.db, &config, true).await;
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "refreshed": refreshed,
+        "entries": data.db.list_prewarm_entries().unwrap_or_default(),
+    }))
+}
@@ -5,9 +5,58 @@ use crate::models::{
     CreateCronJobRequest, CronJobResponse,
     UpdateCronJobRequest,
 };
+use crate::controllers::validation::{Valid, Validate, Validator};
 use crate::scheduler::Scheduler;
 use crate::AppState;
 
+const SCHEDULE_TYPES: &[&str] = &["at", "every", "cron"];
+const SESSION_MODES: &[&str] = &["main", "isolated"];
+const MAX_JOB_NAME_CHARS: usize = 200;
+const MAX_JOB_MESSAGE_CHARS: usize = 20_000;
+
+fn validate_schedule(v: &mut Validator, schedule_type: &str, schedule_value: &str) {
+    v.one_of("schedule_type", schedule_type, SCHEDULE_TYPES);
+    v.not_blank("schedule_value", schedule_value);
+    if schedule_type.eq_ignore_ascii_case("cron") {
+        v.cron("schedule_value", schedule_value);
+    }
+}
+
+impl Validate for CreateCronJobRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.not_blank("name", &self.name);
+        v.max_len("name", &self.name, MAX_JOB_NAME_CHARS);
+        validate_schedule(v, &self.schedule_type, &self.schedule_value);
+        v.one_of("session_mode", &self.session_mode, SESSION_MODES);
+        if let Some(message) = &self.message {
+            v.max_len("message", message, MAX_JOB_MESSAGE_CHARS);
+        }
+        if let Some(timeout) = self.timeout_seconds {
+            v.range("timeout_seconds", timeout, 1, 86_400);
+        }
+    }
+}
+
+impl Validate for UpdateCronJobRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(name) = &self.name {
+            v.not_blank("name", name);
+            v.max_len("name", name, MAX_JOB_NAME_CHARS);
+        }
+        match (&self.schedule_type, &self.schedule_value) {
+            (Some(schedule_type), Some(schedule_value)) => validate_schedule(v, schedule_type, schedule_value),
+            (Some(schedule_type), None) => v.one_of("schedule_type", schedule_type, SCHEDULE_TYPES),
+            _ => {}
+        }
+        if let Some(mode) = &self.session_mode {
+            v.one_of("session_mode", mode, SESSION_MODES);
+        }
+        if let Some(message) = &self.message {
+            v.max_len("message", message, MAX_JOB_MESSAGE_CHARS);
+        }
+    }
+}
+
 fn validate_session_from_request(
     state: &web::Data<AppState>,
     req: &HttpRequest,
@@ -16,7 +65,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -30,7 +80,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(CronJobResponse {
             success: false,
@@ -93,49 +143,12 @@ async fn list_jobs(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse
 async fn create_job(
     state: web::Data<AppState>,
     req: HttpRequest,
-    body: web::Json<CreateCronJobRequest>,
+    body: Valid<CreateCronJobRequest>,
 ) -> HttpResponse {
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
     }
 
-    // Validate schedule type
-    let valid_types = ["at", "every", "cron"];
-    if !valid_types.contains(&body.schedule_type.to_lowercase().as_str()) {
-        return HttpResponse::BadRequest().json(CronJobResponse {
-            success: false,
-            job: None,
-            jobs: None,
-            error: Some("Invalid schedule_type. Valid options: at, every, cron".to_string()),
-        });
-    }
-
-    // Validate cron expression if type is cron
-    if body.schedule_type.to_lowercase() == "cron" {
-        use cron::Schedule;
-        use std::str::FromStr;
-
-        if Schedule::from_str(&body.schedule_value).is_err() {
-            return HttpResponse::BadRequest().json(CronJobResponse {
-                success: false,
-                job: None,
-                jobs: None,
-                error: Some(format!("Invalid cron expression: {}", body.schedule_value)),
-            });
-        }
-    }
-
-    // Validate session mode
-    let valid_modes = ["main", "isolated"];
-    if !valid_modes.contains(&body.session_mode.to_lowercase().as_str()) {
-        return HttpResponse::BadRequest().json(CronJobResponse {
-            success: false,
-            job: None,
-            jobs: None,
-            error: Some("Invalid session_mode. Valid options: main, isolated".to_string()),
-        });
-    }
-
     match state.db.create_cron_job(
         &body.name,
         body.description.as_deref(),
@@ -203,7 +216,7 @@ async fn update_job(
     state: web::Data<AppState>,
     req: HttpRequest,
     path: web::Path<i64>,
-    body: web::Json<UpdateCronJobRequest>,
+    body: Valid<UpdateCronJobRequest>,
 ) -> HttpResponse {
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
@@ -211,25 +224,6 @@ async fn update_job(
 
     let id = path.into_inner();
 
-    // Validate cron expression if updating schedule
-    if let (Some(schedule_type), Some(schedule_value)) =
-        (&body.schedule_type, &body.schedule_value)
-    {
-        if schedule_type.to_lowercase() == "cron" {
-            use cron::Schedule;
-            use std::str::FromStr;
-
-            if Schedule::from_str(schedule_value).is_err() {
-                return HttpResponse::BadRequest().json(CronJobResponse {
-                    success: false,
-                    job: None,
-                    jobs: None,
-                    error: Some(format!("Invalid cron expression: {}", schedule_value)),
-                });
-            }
-        }
-    }
-
     match state.db.update_cron_job(
         id,
         body.name.as_deref(),
@@ -23,7 +23,8 @@ async fn get_dashboard(state: web::Data<AppState>, req: HttpRequest) -> impl Res
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -34,7 +35,7 @@ async fn get_dashboard(state: web::Data<AppState>, req: HttpRequest) -> impl Res
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, &req, &token) {
         Ok(Some(_session)) => HttpResponse::Ok().json(DashboardData {
             message: "Welcome to StarkBot Dashboard!".to_string(),
             timestamp: chrono::Utc::now().to_rfc3339(),
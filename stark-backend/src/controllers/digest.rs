@@ -0,0 +1,158 @@
+//! Activity digest API — configure schedule, sections and delivery chat,
+//! list past digests, and send one now.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate_session;
+use crate::digest::{self, ALL_SECTIONS, FREQUENCY_DAILY, FREQUENCY_WEEKLY};
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/digest")
+            .route("/config", web::get().to(get_config))
+            .route("/config", web::put().to(update_config))
+            .route("/runs", web::get().to(list_runs))
+            .route("/send", web::post().to(send_now)),
+    );
+}
+
+#[derive(Deserialize)]
+struct UpdateDigestRequest {
+    enabled: Option<bool>,
+    frequency: Option<String>,
+    send_hour: Option<u32>,
+    weekday: Option<u32>,
+    sections: Option<Vec<String>>,
+    /// Delivery chat (both must be set)
+    channel_id: Option<i64>,
+    chat_id: Option<String>,
+    /// Forget the delivery chat and send to the owner's chat from bot settings
+    #[serde(default)]
+    use_owner_chat: bool,
+}
+
+#[derive(Deserialize)]
+struct RunsQuery {
+    limit: Option<usize>,
+}
+
+async fn get_config(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.get_digest_config() {
+        Ok(config) => HttpResponse::Ok().json(json!({ "config": config, "available_sections": ALL_SECTIONS })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn update_config(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<UpdateDigestRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let mut config = match data.db.get_digest_config() {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+
+    let body = body.into_inner();
+    if let Some(enabled) = body.enabled {
+        config.enabled = enabled;
+    }
+    if let Some(frequency) = body.frequency {
+        let frequency = frequency.trim().to_lowercase();
+        if frequency != FREQUENCY_DAILY && frequency != FREQUENCY_WEEKLY {
+            return HttpResponse::BadRequest().json(json!({ "error": "frequency must be 'daily' or 'weekly'" }));
+        }
+        config.frequency = frequency;
+    }
+    if let Some(hour) = body.send_hour {
+        if hour > 23 {
+            return HttpResponse::BadRequest().json(json!({ "error": "send_hour must be 0-23" }));
+        }
+        config.send_hour = hour;
+    }
+    if let Some(weekday) = body.weekday {
+        if weekday > 6 {
+            return HttpResponse::BadRequest().json(json!({ "error": "weekday must be 0 (Monday) to 6 (Sunday)" }));
+        }
+        config.weekday = weekday;
+    }
+    if let Some(sections) = body.sections {
+        let sections: Vec<String> = sections.iter().map(|s| s.trim().to_lowercase()).collect();
+        if let Some(unknown) = sections.iter().find(|s| !ALL_SECTIONS.contains(&s.as_str())) {
+            return HttpResponse::BadRequest().json(json!({
+                "error": format!("Unknown section '{}'. Sections: {}", unknown, ALL_SECTIONS.join(", "))
+            }));
+        }
+        config.sections = sections.join(",");
+    }
+    if body.use_owner_chat {
+        config.channel_id = None;
+        config.chat_id = None;
+    } else if body.channel_id.is_some() || body.chat_id.is_some() {
+        let chat_id = body.chat_id.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+        let (channel_id, chat_id) = match (body.channel_id, chat_id) {
+            (Some(channel_id), Some(chat_id)) => (channel_id, chat_id),
+            _ => {
+                return HttpResponse::BadRequest().json(json!({ "error": "Set both channel_id and chat_id" }));
+            }
+        };
+        if data.db.get_channel(channel_id).ok().flatten().is_none() {
+            return HttpResponse::BadRequest().json(json!({ "error": format!("Channel {} not found", channel_id) }));
+        }
+        config.channel_id = Some(channel_id);
+        config.chat_id = Some(chat_id);
+    }
+
+    match data.db.save_digest_config(&config) {
+        Ok(saved) => HttpResponse::Ok().json(json!({ "config": saved, "available_sections": ALL_SECTIONS })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn list_runs(data: web::Data<AppState>, req: HttpRequest, query: web::Query<RunsQuery>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_digest_runs(query.limit.unwrap_or(20).min(100)) {
+        Ok(runs) => HttpResponse::Ok().json(json!({ "runs": runs })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// Generate and send a digest for the period ending now, regardless of schedule
+async fn send_now(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let config = match data.db.get_digest_config() {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+    match digest::run_digest(&data.db, &config).await {
+        Ok(run) => HttpResponse::Ok().json(json!({ "success": run.delivered, "run": run })),
+        Err(e) => HttpResponse::BadRequest().json(json!({ "error": e })),
+    }
+}
@@ -0,0 +1,36 @@
+//! Doctor controller — run the configuration self-test on demand.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use super::validate_session;
+use crate::controllers::openapi::{any_object, ApiDoc};
+use crate::AppState;
+
+/// POST /api/admin/doctor - Probe every dependency and return the findings
+async fn run_doctor(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let report = crate::doctor::run(
+        &data.db,
+        &data.channel_manager,
+        data.disk_quota.as_deref(),
+        data.wallet_provider.clone(),
+    )
+    .await;
+    HttpResponse::Ok().json(report)
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/api/admin").route("/doctor", web::post().to(run_doctor)));
+}
+
+/// OpenAPI description of the doctor route
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.post(
+        "/api/admin/doctor",
+        "admin",
+        "Check AI key, embeddings, Alchemy, channels, disk quota and migrations; returns findings with fixes",
+    )
+    .returns(any_object());
+}
@@ -66,6 +66,11 @@ pub struct CreateRegistrationRequest {
     services: Option<Vec<ServiceInput>>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ReceiptsConfigRequest {
+    enabled: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ServiceInput {
     name: String,
@@ -93,6 +98,11 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/agents", web::get().to(discover_agents))
             .route("/agents/search", web::get().to(search_agents))
             .route("/agents/{agent_id}", web::get().to(get_agent_details))
+            // Delegation to remote agents
+            .route("/delegations", web::get().to(list_delegations))
+            // Public action receipts log
+            .route("/receipts/config", web::get().to(get_receipts_config))
+            .route("/receipts/config", web::put().to(update_receipts_config))
     );
 }
 
@@ -517,6 +527,69 @@ async fn get_agent_details(
     }
 }
 
+// =====================================================
+// Delegation Endpoints
+// =====================================================
+
+/// Subtasks delegated to remote agents, newest first
+async fn list_delegations(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<DiscoverQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_auth(&state, &req) {
+        return resp;
+    }
+
+    let limit = query.limit.unwrap_or(50).min(200) as usize;
+    match state.db.list_remote_delegations(None, limit) {
+        Ok(delegations) => HttpResponse::Ok().json(ApiResponse::success(delegations)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())),
+    }
+}
+
+// =====================================================
+// Receipts Endpoints
+// =====================================================
+
+fn receipts_config_json(state: &AppState, enabled: bool) -> serde_json::Value {
+    let (total, head) = state.db.action_receipts_head().unwrap_or_default();
+    serde_json::json!({
+        "enabled": enabled,
+        "feed_url": crate::eip8004::receipts::feed_url(),
+        "total": total,
+        "head": head,
+    })
+}
+
+/// Whether confirmed txs and paid tasks are published as signed receipts
+async fn get_receipts_config(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_auth(&state, &req) {
+        return resp;
+    }
+    match state.db.get_bot_settings() {
+        Ok(settings) => HttpResponse::Ok().json(ApiResponse::success(receipts_config_json(&state, settings.receipts_log_enabled))),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())),
+    }
+}
+
+async fn update_receipts_config(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<ReceiptsConfigRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_auth(&state, &req) {
+        return resp;
+    }
+    match state.db.update_receipts_log(body.enabled) {
+        Ok(settings) => {
+            log::info!("Action receipts log {}", if settings.receipts_log_enabled { "enabled" } else { "disabled" });
+            HttpResponse::Ok().json(ApiResponse::success(receipts_config_json(&state, settings.receipts_log_enabled)))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())),
+    }
+}
+
 // =====================================================
 // Auth Helper
 // =====================================================
@@ -526,7 +599,8 @@ fn validate_auth(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), H
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -538,7 +612,7 @@ fn validate_auth(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), H
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "success": false,
@@ -0,0 +1,231 @@
+//! Event bus controller — lets modules and skills publish events and
+//! subscribe to agent activity.
+//!
+//! Modules authenticate with the `X-Internal-Token` header; everything else
+//! (the dashboard, skills using an access key) with a session.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::controllers::openapi::{any_object, array, integer, object, string, ApiDoc};
+use crate::gateway::event_bus::{self, BusEventType};
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct PollQuery {
+    #[serde(default)]
+    after: u64,
+    /// Comma-separated event names or patterns
+    events: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct PublishBody {
+    source: String,
+    event: String,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionsQuery {
+    subscriber: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CreateSubscriptionBody {
+    subscriber: String,
+    events: Vec<String>,
+    callback_url: Option<String>,
+}
+
+/// Modules pass the internal token; anyone else needs a session
+fn authorize(data: &web::Data<AppState>, req: &HttpRequest) -> Result<(), HttpResponse> {
+    let internal = req
+        .headers()
+        .get("X-Internal-Token")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("");
+    if !internal.is_empty() {
+        if internal == data.internal_token {
+            return Ok(());
+        }
+        return Err(HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Invalid X-Internal-Token"
+        })));
+    }
+    validate_session(data, req)
+}
+
+/// GET /api/events/types - Core event types and what they mean
+async fn list_types(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = authorize(&data, &req) {
+        return resp;
+    }
+    let types: Vec<_> = BusEventType::ALL
+        .iter()
+        .map(|t| serde_json::json!({ "event": t.as_str(), "description": t.description() }))
+        .collect();
+    HttpResponse::Ok().json(types)
+}
+
+/// GET /api/events/poll - Buffered events after a sequence number
+async fn poll(data: web::Data<AppState>, req: HttpRequest, query: web::Query<PollQuery>) -> impl Responder {
+    if let Err(resp) = authorize(&data, &req) {
+        return resp;
+    }
+    let patterns: Vec<String> = query
+        .events
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if let Err(e) = event_bus::validate_patterns(&patterns) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+    }
+    let limit = query.limit.unwrap_or(100);
+    HttpResponse::Ok().json(data.event_bus.poll(query.after, &patterns, limit))
+}
+
+/// POST /api/events/publish - Publish an event as `<source>.<event>`
+async fn publish(data: web::Data<AppState>, req: HttpRequest, body: web::Json<PublishBody>) -> impl Responder {
+    if let Err(resp) = authorize(&data, &req) {
+        return resp;
+    }
+    let body = body.into_inner();
+    match data.event_bus.publish_extension(body.source.trim(), body.event.trim(), body.data) {
+        Ok(event) => HttpResponse::Ok().json(event),
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({ "error": e })),
+    }
+}
+
+/// GET /api/events/subscriptions
+async fn list_subscriptions(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<SubscriptionsQuery>,
+) -> impl Responder {
+    if let Err(resp) = authorize(&data, &req) {
+        return resp;
+    }
+    match data.db.list_event_subscriptions(query.subscriber.as_deref()) {
+        Ok(subscriptions) => HttpResponse::Ok().json(subscriptions),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// POST /api/events/subscriptions - Subscribe to events, optionally with a callback URL
+async fn create_subscription(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CreateSubscriptionBody>,
+) -> impl Responder {
+    if let Err(resp) = authorize(&data, &req) {
+        return resp;
+    }
+    let subscriber = body.subscriber.trim();
+    if subscriber.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": "subscriber is required" }));
+    }
+    let events: Vec<String> = body.events.iter().map(|e| e.trim().to_string()).collect();
+    if events.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "events must list at least one event name or pattern"
+        }));
+    }
+    if let Err(e) = event_bus::validate_patterns(&events) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+    }
+    let callback_url = body.callback_url.as_deref().map(str::trim).filter(|u| !u.is_empty());
+    if callback_url.is_some_and(|url| !(url.starts_with("http://") || url.starts_with("https://"))) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "callback_url must be an http(s) URL"
+        }));
+    }
+    match data.db.create_event_subscription(subscriber, &events, callback_url) {
+        Ok(subscription) => {
+            log::info!("[EVENT_BUS] '{}' subscribed to {:?}", subscriber, events);
+            HttpResponse::Ok().json(subscription)
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// DELETE /api/events/subscriptions/{id}
+async fn delete_subscription(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = authorize(&data, &req) {
+        return resp;
+    }
+    match data.db.delete_event_subscription(path.into_inner()) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({ "error": "Subscription not found" })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// POST /api/events/subscriptions/{id}/enable - Resume a subscription disabled by failed deliveries
+async fn enable_subscription(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = authorize(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.enable_event_subscription(id) {
+        Ok(true) => match data.db.get_event_subscription(id) {
+            Ok(Some(subscription)) => HttpResponse::Ok().json(subscription),
+            _ => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        },
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({ "error": "Subscription not found" })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/events")
+            .route("/types", web::get().to(list_types))
+            .route("/poll", web::get().to(poll))
+            .route("/publish", web::post().to(publish))
+            .route("/subscriptions", web::get().to(list_subscriptions))
+            .route("/subscriptions", web::post().to(create_subscription))
+            .route("/subscriptions/{id}", web::delete().to(delete_subscription))
+            .route("/subscriptions/{id}/enable", web::post().to(enable_subscription)),
+    );
+}
+
+/// OpenAPI description of the event bus routes
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.get("/api/events/types", "events", "Core event types modules and skills can subscribe to")
+        .returns(any_object());
+    doc.get("/api/events/poll", "events", "Buffered events after a sequence number")
+        .query("after", integer(), "Return events with a higher sequence number")
+        .query("events", string(), "Comma-separated event names or patterns (e.g. tx.*)")
+        .query("limit", integer(), "Most events to return (max 500)")
+        .returns(any_object());
+    doc.post("/api/events/publish", "events", "Publish an extension event as <source>.<event>")
+        .body(object(&[("source", string()), ("event", string()), ("data", any_object())], &["source", "event"]))
+        .returns(any_object());
+    doc.get("/api/events/subscriptions", "events", "List event subscriptions")
+        .returns(any_object());
+    doc.post("/api/events/subscriptions", "events", "Subscribe to event names or patterns, optionally with a callback URL")
+        .body(object(
+            &[("subscriber", string()), ("events", array(string())), ("callback_url", string())],
+            &["subscriber", "events"],
+        ))
+        .returns(any_object());
+    doc.delete("/api/events/subscriptions/{id}", "events", "Delete an event subscription")
+        .returns(any_object());
+    doc.post("/api/events/subscriptions/{id}/enable", "events", "Re-enable a subscription disabled by failed deliveries")
+        .returns(any_object());
+}
@@ -0,0 +1,70 @@
+//! Execution artifacts API
+//!
+//! Lists the files an execution registered as artifacts and serves their
+//! contents for download.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use super::validate_session;
+use crate::AppState;
+
+/// GET /api/executions/{id}/artifacts - Artifacts generated by an execution
+async fn list_artifacts(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_artifacts_for_execution(&path.into_inner()) {
+        Ok(artifacts) => HttpResponse::Ok().json(artifacts),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// GET /api/executions/{id}/artifacts/{artifact_id} - Download an artifact
+async fn download_artifact(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, i64)>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let (execution_id, artifact_id) = path.into_inner();
+    let artifact = match data.db.get_artifact(artifact_id) {
+        Ok(Some(a)) if a.execution_id == execution_id => a,
+        Ok(_) => {
+            return HttpResponse::NotFound().json(serde_json::json!({ "error": "Artifact not found" }))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+
+    match tokio::fs::read(artifact.absolute_path()).await {
+        Ok(contents) => HttpResponse::Ok()
+            .content_type(artifact.mime_type.as_str())
+            .append_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", artifact.name.replace('"', "")),
+            ))
+            .body(contents),
+        Err(_) => HttpResponse::Gone().json(serde_json::json!({
+            "error": "Artifact file no longer exists"
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/executions")
+            .route("/{id}/artifacts", web::get().to(list_artifacts))
+            .route("/{id}/artifacts/{artifact_id}", web::get().to(download_artifact)),
+    );
+}
@@ -249,6 +249,14 @@ async fn ext_proxy(
         }
     }
 
+    // A verified x402 payment becomes a public receipt once the request succeeds
+    let paid_by = forward_headers
+        .get("x-payment-verified")
+        .and_then(|_| forward_headers.get("x-payment-payer").cloned());
+    let payment_hash = forward_headers.get("x-payment").map(|p| {
+        format!("0x{}", hex::encode(crate::eip8004::abi::common::keccak256(p.as_bytes())))
+    });
+
     // Proxy the request
     match module
         .proxy_ext_request(&ext_ep.rpc_endpoint, &request_method, body.to_vec(), forward_headers)
@@ -270,6 +278,33 @@ async fn ext_proxy(
                 }
             }
 
+            if let (Some(payer), Some(payment_hash)) = (&paid_by, &payment_hash) {
+                if status.is_success() {
+                    // Prefer the settlement tx the module reports, else the payment authorization hash
+                    let reference = proxy_resp
+                        .headers
+                        .iter()
+                        .find(|(k, _)| {
+                            k.eq_ignore_ascii_case("x-transaction-hash") || k.eq_ignore_ascii_case("x-payment-transaction")
+                        })
+                        .map(|(_, v)| v.clone())
+                        .unwrap_or_else(|| payment_hash.clone());
+                    crate::eip8004::receipts::record(
+                        &data.db,
+                        crate::eip8004::receipts::RECEIPT_TASK_SERVED,
+                        &reference,
+                        ext_ep.x402_network.as_deref().unwrap_or("base"),
+                        &format!("Served paid request /ext/{}/{}", module_name, method),
+                        serde_json::json!({
+                            "payer": payer,
+                            "price": ext_ep.x402_price,
+                            "currency": ext_ep.x402_currency.as_deref().unwrap_or("USDC"),
+                            "paymentHash": payment_hash,
+                        }),
+                    );
+                }
+            }
+
             response.body(proxy_resp.body)
         }
         Err(e) => {
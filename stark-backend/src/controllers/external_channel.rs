@@ -5,7 +5,7 @@ use std::sync::Arc;
 
 use crate::channels::NormalizedMessage;
 use crate::models::chat_session::SessionScope;
-use crate::models::Channel;
+use crate::models::{Channel, MessageAttachment};
 use crate::AppState;
 
 const CHANNEL_TYPE: &str = "external_channel";
@@ -19,6 +19,8 @@ pub struct GatewayChatRequest {
     pub session_id: Option<String>,
     #[serde(default)]
     pub user_name: Option<String>,
+    #[serde(default)]
+    pub attachments: Vec<MessageAttachment>,
 }
 
 #[derive(Debug, Serialize)]
@@ -228,7 +230,8 @@ fn validate_web_session(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -240,7 +243,7 @@ fn validate_web_session(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(GatewayErrorResponse {
             success: false,
@@ -354,8 +357,11 @@ async fn gateway_chat(
         session_mode: None,
         selected_network: None,
         force_safe_mode: safe_mode,
+        shared_chat: false,
         platform_role_ids: vec![],
+        attachments: body.attachments.clone(),
         chat_context: None,
+        action: None,
     };
 
     let result = state.dispatcher.dispatch_safe(normalized).await;
@@ -438,6 +444,7 @@ async fn gateway_chat_stream(
     // Dispatch in a background task
     let dispatcher = state.dispatcher.clone();
     let msg_text = body.message.clone();
+    let attachments = body.attachments.clone();
     let broadcaster_bg = broadcaster.clone();
     let client_id_bg = client_id.clone();
 
@@ -454,8 +461,11 @@ async fn gateway_chat_stream(
             session_mode: None,
             selected_network: None,
             force_safe_mode: safe_mode,
+            shared_chat: false,
             platform_role_ids: vec![],
+            attachments,
         chat_context: None,
+        action: None,
         };
         let _ = dispatcher.dispatch_safe(normalized).await;
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
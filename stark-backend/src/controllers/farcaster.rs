@@ -0,0 +1,121 @@
+//! Farcaster API — configuration status and linking the bot's Farcaster
+//! account to the agent's EIP-8004 registration.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate_session;
+use crate::eip8004::types::ServiceEntry;
+use crate::integrations::farcaster::{self, NeynarClient, FARCASTER_SERVICE_NAME};
+use crate::models::ChannelSettingKey;
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/farcaster")
+            .route("/status", web::get().to(get_status))
+            .route("/link-identity", web::post().to(link_identity)),
+    );
+}
+
+#[derive(Deserialize)]
+struct LinkIdentityRequest {
+    /// Account to link; defaults to the bot fid of the first Farcaster channel
+    fid: Option<u64>,
+    /// Also set the Farcaster profile URL to the agent's registration URI
+    #[serde(default)]
+    update_profile_url: bool,
+}
+
+/// The `farcaster` service entry currently in the agent's registration
+fn linked_service(data: &AppState) -> Option<ServiceEntry> {
+    let identity = data.db.get_agent_identity_full()?;
+    let services: Vec<ServiceEntry> = serde_json::from_str(&identity.services_json).unwrap_or_default();
+    services.into_iter().find(|s| s.name == FARCASTER_SERVICE_NAME)
+}
+
+/// Bot fid configured on the first Farcaster channel
+fn channel_bot_fid(data: &AppState) -> Option<u64> {
+    let channels = data.db.list_channels().ok()?;
+    channels
+        .iter()
+        .filter(|c| c.channel_type == "farcaster")
+        .find_map(|c| {
+            data.db
+                .get_channel_setting(c.id, ChannelSettingKey::FarcasterBotFid.as_ref())
+                .ok()
+                .flatten()
+                .and_then(|fid| fid.trim().parse().ok())
+        })
+}
+
+async fn get_status(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let client = NeynarClient::from_keys(&data.db);
+    HttpResponse::Ok().json(json!({
+        "configured": client.is_ok(),
+        "can_cast": client.as_ref().is_ok_and(|c| c.can_cast()),
+        "bot_fid": channel_bot_fid(&data),
+        "agent_registered": data.db.get_agent_identity_full().is_some(),
+        "identity_service": linked_service(&data),
+    }))
+}
+
+async fn link_identity(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<LinkIdentityRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let client = match NeynarClient::from_keys(&data.db) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::BadRequest().json(json!({ "error": e })),
+    };
+    let Some(identity) = data.db.get_agent_identity_full() else {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "No EIP-8004 identity registered — register the agent onchain first"
+        }));
+    };
+    let Some(fid) = body.fid.or_else(|| channel_bot_fid(&data)) else {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "fid is required (no Farcaster channel has a bot fid configured)"
+        }));
+    };
+
+    let user = match client.user(fid).await {
+        Ok(u) => u,
+        Err(e) => return HttpResponse::BadGateway().json(json!({ "error": e })),
+    };
+    let updated = match farcaster::link_agent_identity(&data.db, &user) {
+        Ok(updated) => updated,
+        Err(e) => return HttpResponse::InternalServerError().json(json!({ "error": e })),
+    };
+
+    // Point the Farcaster profile back at the onchain registration
+    let mut profile_url = None;
+    if body.update_profile_url {
+        let Some(uri) = identity.registration_uri.filter(|u| u.starts_with("https://") || u.starts_with("http://")) else {
+            return HttpResponse::BadRequest().json(json!({
+                "error": "The agent has no http(s) registration URI to link from the profile"
+            }));
+        };
+        if let Err(e) = client.set_profile_url(&uri).await {
+            return HttpResponse::BadGateway().json(json!({ "error": e }));
+        }
+        profile_url = Some(uri);
+    }
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "updated": updated,
+        "user": user,
+        "service": linked_service(&data),
+        "profile_url": profile_url,
+        "note": "The registration file now lists this account. Republish it onchain if your registration URI is not served by this bot.",
+    }))
+}
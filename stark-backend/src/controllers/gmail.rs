@@ -6,8 +6,11 @@ use actix_web::{web, HttpRequest, HttpResponse};
 use base64::Engine;
 use std::sync::Arc;
 
+use crate::channels::format::{self, RenderTarget};
+use crate::channels::outbox;
 use crate::channels::types::{DispatchResult, NormalizedMessage};
 use crate::channels::MessageDispatcher;
+use crate::db::tables::outbox_drafts::DraftTarget;
 use crate::db::Database;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
@@ -15,6 +18,7 @@ use crate::integrations::gmail::{
     GmailClient, GmailConfig, GmailConfigResponse, GmailNotificationData,
     ParsedEmail, PubSubPushNotification, SetupGmailRequest, UpdateGmailRequest,
 };
+use crate::config_watch::ConfigChange;
 use crate::AppState;
 
 /// Configure Gmail routes
@@ -233,8 +237,11 @@ async fn dispatch_email(
         session_mode: None,
         selected_network: None,
         force_safe_mode: false,
+        shared_chat: false,
         platform_role_ids: vec![],
+        attachments: vec![],
         chat_context: None,
+        action: None,
     };
 
     // Broadcast event
@@ -253,18 +260,32 @@ async fn dispatch_email(
     // If auto-reply is enabled and we got a successful response, send reply
     if config.auto_reply && result.error.is_none() && !result.response.is_empty() {
         let response_text = &result.response;
-        log::info!("[GMAIL] Sending auto-reply to {}", email.from);
 
         // Extract reply-to address
         let reply_to = &email.from;
         let message_id_header = email.message_id.clone();
 
+        if config.review_before_send {
+            let target = DraftTarget::Gmail {
+                thread_id: email.thread_id.clone(),
+                to: reply_to.clone(),
+                subject: email.subject.clone(),
+                in_reply_to: Some(format!("<{}>", message_id_header)),
+            };
+            if let Err(e) = outbox::hold(db, Some(broadcaster.as_ref()), target, response_text, None) {
+                log::error!("[GMAIL] Failed to hold auto-reply for review: {}", e);
+            }
+            return Ok(result);
+        }
+
+        log::info!("[GMAIL] Sending auto-reply to {}", email.from);
         match client.send_reply(
             "me",
             &email.thread_id,
             reply_to,
             &email.subject,
-            response_text,
+            &format::render(response_text, RenderTarget::Plain),
+            Some(&format::render(response_text, RenderTarget::Email)),
             Some(&format!("<{}>", message_id_header)),
         ).await {
             Ok(_) => {
@@ -306,7 +327,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -319,7 +341,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(GmailConfigResponse {
             success: false,
@@ -408,11 +430,14 @@ async fn setup_gmail(
         body.response_channel_id,
         body.auto_reply.unwrap_or(false),
     ) {
-        Ok(config) => HttpResponse::Created().json(GmailConfigResponse {
-            success: true,
-            config: Some(config.into()),
-            error: None,
-        }),
+        Ok(config) => {
+            state.config_watch.notify(ConfigChange::Gmail);
+            HttpResponse::Created().json(GmailConfigResponse {
+                success: true,
+                config: Some(config.into()),
+                error: None,
+            })
+        }
         Err(e) => HttpResponse::InternalServerError().json(GmailConfigResponse {
             success: false,
             config: None,
@@ -436,12 +461,17 @@ async fn update_config(
         body.response_channel_id,
         body.auto_reply,
         body.enabled,
+        body.send_allowlist.as_deref(),
+        body.review_before_send,
     ) {
-        Ok(config) => HttpResponse::Ok().json(GmailConfigResponse {
-            success: true,
-            config: Some(config.into()),
-            error: None,
-        }),
+        Ok(config) => {
+            state.config_watch.notify(ConfigChange::Gmail);
+            HttpResponse::Ok().json(GmailConfigResponse {
+                success: true,
+                config: Some(config.into()),
+                error: None,
+            })
+        }
         Err(e) => HttpResponse::InternalServerError().json(GmailConfigResponse {
             success: false,
             config: None,
@@ -457,11 +487,14 @@ async fn delete_config(state: web::Data<AppState>, req: HttpRequest) -> HttpResp
     }
 
     match state.db.delete_gmail_config() {
-        Ok(true) => HttpResponse::Ok().json(GmailConfigResponse {
-            success: true,
-            config: None,
-            error: None,
-        }),
+        Ok(true) => {
+            state.config_watch.notify(ConfigChange::Gmail);
+            HttpResponse::Ok().json(GmailConfigResponse {
+                success: true,
+                config: None,
+                error: None,
+            })
+        }
         Ok(false) => HttpResponse::NotFound().json(GmailConfigResponse {
             success: false,
             config: None,
@@ -0,0 +1,299 @@
+//! Handoff API — chats handed over to a human operator, who joins, replies
+//! as themselves and hands the chat back to the agent (see `crate::handoff`)
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::channels::outbound;
+use crate::controllers::openapi::{any_object, array, boolean, integer, object, string, ApiDoc};
+use crate::db::tables::handoffs::{Handoff, NewHandoff};
+use crate::gateway::protocol::GatewayEvent;
+use crate::handoff::{self, HandoffConfig};
+use crate::models::session_message::MessageRole;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct ListQuery {
+    #[serde(default)]
+    active: bool,
+    limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct EscalateRequest {
+    channel_id: i64,
+    chat_id: String,
+    reason: String,
+    session_id: Option<i64>,
+    user_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JoinRequest {
+    operator: String,
+}
+
+#[derive(Deserialize)]
+struct ReplyRequest {
+    text: String,
+    /// Defaults to the operator who joined
+    operator: Option<String>,
+}
+
+fn db_error(e: rusqlite::Error) -> HttpResponse {
+    HttpResponse::InternalServerError().json(serde_json::json!({ "error": format!("Database error: {}", e) }))
+}
+
+fn load_handoff(data: &AppState, id: i64) -> Result<Handoff, HttpResponse> {
+    match data.db.get_handoff(id) {
+        Ok(Some(handoff)) => Ok(handoff),
+        Ok(None) => Err(HttpResponse::NotFound().json(serde_json::json!({ "error": "Handoff not found" }))),
+        Err(e) => Err(db_error(e)),
+    }
+}
+
+/// GET /api/handoffs - Handoffs, newest first (?active=true for open ones only)
+async fn list_handoffs(data: web::Data<AppState>, req: HttpRequest, query: web::Query<ListQuery>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_handoffs(query.active, query.limit.unwrap_or(100).clamp(1, 500)) {
+        Ok(handoffs) => HttpResponse::Ok().json(handoffs),
+        Err(e) => db_error(e),
+    }
+}
+
+/// POST /api/handoffs - Hand a chat over from the web UI
+async fn escalate(data: web::Data<AppState>, req: HttpRequest, body: web::Json<EscalateRequest>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let reason = body.reason.trim();
+    if reason.is_empty() || body.chat_id.trim().is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": "chat_id and reason are required" }));
+    }
+    let channel = match data.db.get_channel(body.channel_id) {
+        Ok(Some(channel)) => channel,
+        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({ "error": "Channel not found" })),
+        Err(e) => return db_error(e),
+    };
+    let request = NewHandoff {
+        session_id: body.session_id,
+        channel_id: channel.id,
+        channel_type: &channel.channel_type,
+        chat_id: body.chat_id.trim(),
+        user_name: body.user_name.as_deref(),
+        reason,
+        requested_by: "operator",
+    };
+    match handoff::escalate(&data.db, Some(&*data.broadcaster), &request).await {
+        Ok(handoff) => HttpResponse::Ok().json(handoff),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e })),
+    }
+}
+
+/// GET /api/handoffs/{id} - A handoff with its conversation so far
+async fn get_handoff(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let handoff = match load_handoff(&data, path.into_inner()) {
+        Ok(handoff) => handoff,
+        Err(resp) => return resp,
+    };
+    let messages = match handoff.session_id {
+        Some(session_id) => match data.db.get_session_messages(session_id) {
+            Ok(messages) => messages,
+            Err(e) => return db_error(e),
+        },
+        None => vec![],
+    };
+    HttpResponse::Ok().json(serde_json::json!({ "handoff": handoff, "messages": messages }))
+}
+
+/// POST /api/handoffs/{id}/join - Take the chat over as an operator
+async fn join_handoff(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<JoinRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let operator = body.operator.trim();
+    if operator.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": "operator is required" }));
+    }
+    let id = path.into_inner();
+    match data.db.join_handoff(id, operator) {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::Conflict().json(serde_json::json!({ "error": "Handoff is closed or missing" })),
+        Err(e) => return db_error(e),
+    }
+    let handoff = match load_handoff(&data, id) {
+        Ok(handoff) => handoff,
+        Err(resp) => return resp,
+    };
+    let notice = format!("{} has joined the conversation.", operator);
+    if let Err(e) = outbound::send_direct(&data.db, handoff.channel_id, &handoff.chat_id, &notice, &[]).await {
+        log::warn!("[HANDOFF] Failed to announce operator in handoff {}: {}", id, e);
+    }
+    data.broadcaster.broadcast(GatewayEvent::custom("handoff.joined", serde_json::json!(handoff)));
+    HttpResponse::Ok().json(handoff)
+}
+
+/// POST /api/handoffs/{id}/reply - Answer the chat as the operator
+async fn reply(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<ReplyRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let handoff = match load_handoff(&data, path.into_inner()) {
+        Ok(handoff) => handoff,
+        Err(resp) => return resp,
+    };
+    if !handoff.is_active() {
+        return HttpResponse::Conflict().json(serde_json::json!({ "error": "Handoff was released" }));
+    }
+    let text = body.text.trim();
+    let Some(operator) = body.operator.as_deref().or(handoff.operator.as_deref()).map(str::trim).filter(|o| !o.is_empty())
+    else {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": "Join the handoff or give an operator name" }));
+    };
+    if text.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": "text is required" }));
+    }
+    // Replying takes the chat over, if nobody (or someone else) had it
+    if let Err(e) = data.db.join_handoff(handoff.id, operator) {
+        return db_error(e);
+    }
+
+    let markdown = format!("**{}:** {}", operator, text);
+    if let Err(e) = outbound::send_direct(&data.db, handoff.channel_id, &handoff.chat_id, &markdown, &[]).await {
+        return HttpResponse::BadGateway().json(serde_json::json!({ "error": e }));
+    }
+    let stored = handoff.session_id.map(|session_id| {
+        data.db.add_session_message(session_id, MessageRole::Assistant, text, Some("operator"), Some(operator), None, None)
+    });
+    if let Some(Err(e)) = stored {
+        log::warn!("[HANDOFF] Failed to store operator reply in handoff {}: {}", handoff.id, e);
+    }
+    data.broadcaster.broadcast(GatewayEvent::custom(
+        "handoff.reply",
+        serde_json::json!({ "handoff_id": handoff.id, "operator": operator, "text": text }),
+    ));
+    HttpResponse::Ok().json(serde_json::json!({ "success": true }))
+}
+
+/// POST /api/handoffs/{id}/release - Hand the chat back to the agent
+async fn release(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.release_handoff(id) {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::Conflict().json(serde_json::json!({ "error": "Handoff is already released or missing" })),
+        Err(e) => return db_error(e),
+    }
+    let handoff = match load_handoff(&data, id) {
+        Ok(handoff) => handoff,
+        Err(resp) => return resp,
+    };
+    log::info!("[HANDOFF] #{} released by {}", id, handoff.operator.as_deref().unwrap_or("an operator"));
+    data.broadcaster.broadcast(GatewayEvent::custom("handoff.released", serde_json::json!(handoff)));
+    HttpResponse::Ok().json(handoff)
+}
+
+/// GET /api/handoffs/config - Operators to notify and the handoff rules
+async fn get_config(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    HttpResponse::Ok().json(handoff::load_config(&data.db))
+}
+
+/// PUT /api/handoffs/config - Replace the operators and rules
+async fn update_config(data: web::Data<AppState>, req: HttpRequest, body: web::Json<HandoffConfig>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let mut config = body.into_inner();
+    if config.operators.iter().any(|o| o.name.trim().is_empty() || o.chat_id.trim().is_empty()) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": "Every operator needs a name and chat_id" }));
+    }
+    config.keywords = config
+        .keywords
+        .iter()
+        .map(|k| k.trim().to_lowercase())
+        .filter(|k| !k.is_empty())
+        .collect();
+    config.keywords.sort();
+    config.keywords.dedup();
+    if let Err(e) = handoff::save_config(&data.db, &config) {
+        return HttpResponse::InternalServerError().json(serde_json::json!({ "error": e }));
+    }
+    HttpResponse::Ok().json(config)
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/handoffs")
+            .route("", web::get().to(list_handoffs))
+            .route("", web::post().to(escalate))
+            .route("/config", web::get().to(get_config))
+            .route("/config", web::put().to(update_config))
+            .route("/{id}", web::get().to(get_handoff))
+            .route("/{id}/join", web::post().to(join_handoff))
+            .route("/{id}/reply", web::post().to(reply))
+            .route("/{id}/release", web::post().to(release)),
+    );
+}
+
+/// OpenAPI description of the handoff routes
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.get("/api/handoffs", "handoffs", "Handoffs to human operators, newest first")
+        .query("active", boolean(), "Only handoffs not yet released")
+        .query("limit", integer(), "At most this many (default 100)")
+        .returns(any_object());
+    doc.post("/api/handoffs", "handoffs", "Hand a chat over to a human operator")
+        .body(object(
+            &[
+                ("channel_id", integer()),
+                ("chat_id", string()),
+                ("reason", string()),
+                ("session_id", integer()),
+                ("user_name", string()),
+            ],
+            &["channel_id", "chat_id", "reason"],
+        ))
+        .returns(any_object());
+    doc.get("/api/handoffs/config", "handoffs", "Operators to notify and the handoff rules")
+        .returns(any_object());
+    doc.put("/api/handoffs/config", "handoffs", "Replace the operators and handoff rules")
+        .body(object(
+            &[
+                ("operators", array(any_object())),
+                ("keywords", array(string())),
+                ("escalate_urgent_negative", boolean()),
+            ],
+            &[],
+        ))
+        .returns(any_object());
+    doc.get("/api/handoffs/{id}", "handoffs", "A handoff with its conversation so far")
+        .returns(any_object());
+    doc.post("/api/handoffs/{id}/join", "handoffs", "Take the chat over as an operator")
+        .body(object(&[("operator", string())], &["operator"]))
+        .returns(any_object());
+    doc.post("/api/handoffs/{id}/reply", "handoffs", "Reply in the chat as the operator")
+        .body(object(&[("text", string()), ("operator", string())], &["text"]))
+        .returns(any_object());
+    doc.post("/api/handoffs/{id}/release", "handoffs", "Hand the chat back to the agent")
+        .returns(any_object());
+}
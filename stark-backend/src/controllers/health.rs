@@ -1,5 +1,6 @@
 use actix_web::{web, HttpResponse, Responder};
 
+use crate::controllers::openapi::{any_object, ApiDoc};
 use crate::AppState;
 
 /// Version from Cargo.toml, available at compile time
@@ -12,9 +13,16 @@ pub fn config_routes(cfg: &mut web::ServiceConfig) {
 }
 
 async fn health_check() -> impl Responder {
+    // An open circuit degrades the report but the process itself is still healthy
+    let dependencies = crate::circuit_breaker::snapshot();
+    let degraded = dependencies
+        .iter()
+        .any(|d| d.state != crate::circuit_breaker::BreakerState::Closed);
     HttpResponse::Ok().json(serde_json::json!({
-        "status": "ok",
-        "version": VERSION
+        "status": if degraded { "degraded" } else { "ok" },
+        "version": VERSION,
+        "instance_id": crate::cluster::instance_id(),
+        "dependencies": dependencies
     }))
 }
 
@@ -42,3 +50,10 @@ async fn get_config_status(state: web::Data<AppState>) -> impl Responder {
         "wallet_mode": wallet_mode
     }))
 }
+
+/// OpenAPI description of the health routes
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.get("/api/health", "system", "Liveness check").public();
+    doc.get("/api/version", "system", "Backend version").public().returns(any_object());
+    doc.get("/api/health/config", "system", "Whether login and the wallet are configured").public().returns(any_object());
+}
@@ -13,7 +13,8 @@ fn validate_session_for_heartbeat(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -26,7 +27,7 @@ fn validate_session_for_heartbeat(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(HeartbeatConfigResponse {
             success: false,
@@ -105,6 +106,25 @@ async fn update_heartbeat_config(
         }
     };
 
+    if let Some(jobs) = &body.maintenance_jobs {
+        if let Err(e) = crate::scheduler::maintenance::validate_jobs(jobs) {
+            return HttpResponse::BadRequest().json(HeartbeatConfigResponse {
+                success: false,
+                config: None,
+                error: Some(e),
+            });
+        }
+        if let Err(e) = state.db.set_heartbeat_maintenance_jobs(config.id, jobs) {
+            log::error!("[HEARTBEAT] Failed to save maintenance jobs: {}", e);
+            return HttpResponse::InternalServerError().json(HeartbeatConfigResponse {
+                success: false,
+                config: None,
+                error: Some(format!("Failed to save maintenance jobs: {}", e)),
+            });
+        }
+        log::info!("[HEARTBEAT] Maintenance jobs updated ({} configured)", jobs.len());
+    }
+
     match state.db.update_heartbeat_config(
         config.id,
         body.interval_minutes,
@@ -172,6 +192,14 @@ async fn update_channel_heartbeat_config(
 
     let channel_id = path.into_inner();
 
+    if body.maintenance_jobs.is_some() {
+        return HttpResponse::BadRequest().json(HeartbeatConfigResponse {
+            success: false,
+            config: None,
+            error: Some("Maintenance jobs are set on the global heartbeat config".to_string()),
+        });
+    }
+
     // Get or create first
     let config = match state.db.get_or_create_heartbeat_config(Some(channel_id)) {
         Ok(c) => c,
@@ -0,0 +1,78 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::config_watch::ConfigChange;
+use crate::middleware::http_security::parse_origins;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct CorsRequest {
+    /// Origins such as "https://dash.example.com"; "*" allows any origin
+    cors_allowed_origins: Vec<String>,
+}
+
+/// GET /api/http-security - CORS allow-list
+async fn get_settings(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.get_bot_settings() {
+        Ok(settings) => HttpResponse::Ok().json(serde_json::json!({
+            "cors_allowed_origins": settings.cors_allowed_origins,
+            "self_origin": crate::config::self_url(),
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// PUT /api/http-security - Replace the CORS allow-list (takes effect immediately)
+async fn update_settings(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CorsRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    if let Err(resp) = super::require_second_factor(&data, &req) {
+        return resp;
+    }
+    let mut origins = Vec::new();
+    for entry in &body.cors_allowed_origins {
+        match parse_origins(entry).as_slice() {
+            [origin] => {
+                if !origins.contains(origin) {
+                    origins.push(origin.clone());
+                }
+            }
+            _ => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("'{}' is not an origin (expected e.g. https://dash.example.com or *)", entry)
+                }));
+            }
+        }
+    }
+    match data.db.update_cors_allowed_origins(&origins) {
+        Ok(settings) => {
+            log::info!("CORS allow-list set to {:?}", settings.cors_allowed_origins);
+            data.config_watch.notify(ConfigChange::BotSettings);
+            HttpResponse::Ok().json(serde_json::json!({
+                "cors_allowed_origins": settings.cors_allowed_origins,
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/http-security")
+            .route("", web::get().to(get_settings))
+            .route("", web::put().to(update_settings)),
+    );
+}
@@ -0,0 +1,128 @@
+//! Identity profiles — view and correct the structured facts the agent learned
+//! about each person it talks to.
+//!
+//! A field set through `PUT` is locked: the background profile builder will no
+//! longer change it until it is listed in `unlock`.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::validate_session;
+use crate::db::tables::identity_profiles::IdentityProfile;
+use crate::memory::profile::{self, ProfileUpdate, PROFILE_FIELDS};
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/profiles")
+            .route("", web::get().to(list_profiles))
+            .route("/{identity_id}", web::get().to(get_profile))
+            .route("/{identity_id}", web::put().to(correct_profile))
+            .route("/{identity_id}", web::delete().to(delete_profile)),
+    );
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    limit: Option<usize>,
+}
+
+async fn list_profiles(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ListQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(100).min(500);
+    match data.db.list_identity_profiles(limit) {
+        Ok(profiles) => HttpResponse::Ok().json(json!({ "profiles": profiles })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": format!("Database error: {}", e) })),
+    }
+}
+
+async fn get_profile(data: web::Data<AppState>, req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let identity_id = path.into_inner();
+    let linked = data.db.get_linked_identities(&identity_id).unwrap_or_default();
+    match data.db.get_identity_profile(&identity_id) {
+        Ok(Some(profile)) => HttpResponse::Ok().json(json!({ "profile": profile, "linked_accounts": linked })),
+        Ok(None) => HttpResponse::NotFound().json(json!({ "error": "No profile for this identity" })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": format!("Database error: {}", e) })),
+    }
+}
+
+/// Body: any of the profile fields (null clears it) plus optional `unlock: [field, ...]`
+async fn correct_profile(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<Value>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let identity_id = path.into_inner();
+    let Some(body) = body.into_inner().as_object().cloned() else {
+        return HttpResponse::BadRequest().json(json!({ "error": "Expected a JSON object" }));
+    };
+
+    if let Some(unknown) = body.keys().find(|k| *k != "unlock" && !PROFILE_FIELDS.contains(&k.as_str())) {
+        return HttpResponse::BadRequest().json(json!({
+            "error": format!("Unknown field '{}'. Fields: {}", unknown, PROFILE_FIELDS.join(", "))
+        }));
+    }
+    let provided: Vec<String> = body.keys().filter(|k| *k != "unlock").cloned().collect();
+    let unlock: Vec<String> = match body.get("unlock") {
+        Some(v) => match serde_json::from_value(v.clone()) {
+            Ok(u) => u,
+            Err(_) => return HttpResponse::BadRequest().json(json!({ "error": "unlock must be a list of field names" })),
+        },
+        None => Vec::new(),
+    };
+    // Nulls clear a field; drop them so collections deserialize as empty
+    let values: serde_json::Map<String, Value> = body.into_iter().filter(|(k, v)| k != "unlock" && !v.is_null()).collect();
+    let update: ProfileUpdate = match serde_json::from_value(Value::Object(values)) {
+        Ok(u) => u,
+        Err(e) => return HttpResponse::BadRequest().json(json!({ "error": format!("Invalid profile fields: {}", e) })),
+    };
+
+    let mut current = match data.db.get_identity_profile(&identity_id) {
+        Ok(Some(p)) => p,
+        Ok(None) => {
+            if data.db.get_linked_identities(&identity_id).unwrap_or_default().is_empty() {
+                return HttpResponse::NotFound().json(json!({ "error": "Unknown identity" }));
+            }
+            IdentityProfile { identity_id: identity_id.clone(), ..Default::default() }
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({ "error": format!("Database error: {}", e) }));
+        }
+    };
+
+    profile::apply_correction(&mut current, &update, &provided, &unlock);
+    if let Err(e) = data.db.save_identity_profile(&current) {
+        return HttpResponse::InternalServerError().json(json!({ "error": format!("Failed to save profile: {}", e) }));
+    }
+    log::info!("[PROFILE] Corrected {:?} for identity {}", provided, identity_id);
+
+    match data.db.get_identity_profile(&identity_id) {
+        Ok(Some(profile)) => HttpResponse::Ok().json(json!({ "success": true, "profile": profile })),
+        _ => HttpResponse::Ok().json(json!({ "success": true, "profile": current })),
+    }
+}
+
+async fn delete_profile(data: web::Data<AppState>, req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.delete_identity_profile(&path.into_inner()) {
+        Ok(true) => HttpResponse::Ok().json(json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(json!({ "error": "No profile for this identity" })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": format!("Database error: {}", e) })),
+    }
+}
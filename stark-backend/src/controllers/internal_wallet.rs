@@ -41,7 +41,8 @@ fn validate_internal_token(state: &web::Data<AppState>, req: &HttpRequest) -> Re
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
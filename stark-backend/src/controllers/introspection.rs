@@ -0,0 +1,57 @@
+//! Read-only introspection query API
+//!
+//! Admin access to the same whitelisted q_* views the query_database tool uses.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::validate_session;
+use crate::db::tables::query_views::QUERY_VIEWS;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct QueryRequest {
+    sql: String,
+    #[serde(default)]
+    params: Vec<Value>,
+    limit: Option<usize>,
+}
+
+/// GET /api/introspection/views - Queryable views and their columns
+async fn list_views(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let views: Vec<Value> = QUERY_VIEWS
+        .iter()
+        .map(|(name, description, _)| serde_json::json!({ "name": name, "description": description }))
+        .collect();
+    HttpResponse::Ok().json(views)
+}
+
+/// POST /api/introspection/query - Run a parameterized read-only query
+async fn run_query(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<QueryRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let db = data.db.clone();
+    let body = body.into_inner();
+    match web::block(move || db.run_introspection_query(&body.sql, &body.params, body.limit)).await {
+        Ok(Ok(result)) => HttpResponse::Ok().json(result),
+        Ok(Err(e)) => HttpResponse::BadRequest().json(serde_json::json!({ "error": e })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/introspection")
+            .route("/views", web::get().to(list_views))
+            .route("/query", web::post().to(run_query)),
+    );
+}
@@ -0,0 +1,109 @@
+//! Issue tracker API — Jira/Linear status-change webhooks and the list of
+//! issues the agent has filed.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate_session;
+use crate::controllers::api_keys::ApiKeyId;
+use crate::integrations::issue_tracker::{self, webhook};
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/issue-tracker")
+            // Webhooks (no session auth - verified by HMAC signature)
+            .route("/webhook/{tracker}", web::post().to(handle_webhook))
+            .route("/issues", web::get().to(list_issues)),
+    );
+}
+
+#[derive(Deserialize)]
+struct IssuesQuery {
+    session_id: Option<i64>,
+    limit: Option<usize>,
+}
+
+async fn handle_webhook(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Bytes,
+) -> impl Responder {
+    let tracker = path.into_inner();
+    let (secret_key, signature_header) = match tracker.as_str() {
+        "linear" => (ApiKeyId::LinearWebhookSecret, "Linear-Signature"),
+        "jira" => (ApiKeyId::JiraWebhookSecret, "X-Hub-Signature"),
+        _ => {
+            return HttpResponse::NotFound().json(json!({
+                "error": format!("Unknown issue tracker '{}'", tracker)
+            }))
+        }
+    };
+
+    // Refuse unsigned webhooks outright: a forged status change would be
+    // injected into the agent's conversation
+    let Some(secret) = issue_tracker::api_key(&data.db, secret_key) else {
+        log::warn!("[ISSUE_TRACKER] {} webhook received but {} is not configured", tracker, secret_key.as_str());
+        return HttpResponse::ServiceUnavailable().json(json!({
+            "error": format!("{} is not configured", secret_key.as_str())
+        }));
+    };
+    let signature = req
+        .headers()
+        .get(signature_header)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default();
+    if !webhook::verify_signature(&secret, &body, signature) {
+        log::warn!("[ISSUE_TRACKER] Rejected {} webhook with invalid signature", tracker);
+        return HttpResponse::Unauthorized().json(json!({ "error": "Invalid signature" }));
+    }
+
+    let payload: serde_json::Value = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(json!({
+                "error": format!("Invalid JSON: {}", e)
+            }))
+        }
+    };
+
+    let change = if tracker == "linear" {
+        webhook::parse_linear(&payload)
+    } else {
+        webhook::parse_jira(&payload)
+    };
+    let Some(change) = change else {
+        // Not a status change — acknowledge so the tracker doesn't retry
+        return HttpResponse::Ok().json(json!({ "handled": false }));
+    };
+
+    match webhook::apply_status_change(&data.db, &data.broadcaster, &tracker, &change) {
+        Ok(Some(issue)) => {
+            log::info!("[ISSUE_TRACKER] {} moved to {}", issue.issue_key, change.status);
+            HttpResponse::Ok().json(json!({ "handled": true }))
+        }
+        Ok(None) => HttpResponse::Ok().json(json!({ "handled": false })),
+        Err(e) => {
+            log::error!("[ISSUE_TRACKER] Failed to apply {} status change: {}", tracker, e);
+            HttpResponse::InternalServerError().json(json!({ "error": e }))
+        }
+    }
+}
+
+async fn list_issues(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<IssuesQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_tracked_issues(query.session_id, query.limit.unwrap_or(100).min(500)) {
+        Ok(issues) => HttpResponse::Ok().json(issues),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
@@ -5,6 +5,8 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 
+use crate::controllers::pagination::{Page, PageQuery};
+use crate::db::tables::memories::MemoryListFilter;
 use crate::AppState;
 
 /// Validate session token from request
@@ -16,7 +18,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -27,7 +30,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "error": "Invalid or expired session"
@@ -208,6 +211,17 @@ struct ListQuery {
     identity_id: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct MemoryFilterQuery {
+    memory_type: Option<String>,
+    identity_id: Option<String>,
+    category: Option<String>,
+    min_importance: Option<i64>,
+}
+
+/// Sort fields for GET /api/memory/list
+const MEMORY_SORTS: &[&str] = &["created_at", "updated_at", "importance", "id"];
+
 #[derive(Debug, Deserialize)]
 struct AppendBody {
     content: String,
@@ -370,6 +384,39 @@ fn rows_to_items(rows: Vec<crate::db::tables::memories::MemoryRow>) -> Vec<Memor
         .collect()
 }
 
+/// GET /api/memory/list?limit=&offset=&cursor=&sort=&order=&memory_type=&identity_id=&category=&min_importance=
+/// - Current memories, newest first. Text search is `/api/memory/search`.
+async fn list_memories(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    page: web::Query<PageQuery>,
+    filter: web::Query<MemoryFilterQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+
+    let filter = MemoryListFilter {
+        memory_type: filter.memory_type.clone(),
+        identity_id: filter.identity_id.clone(),
+        category: filter.category.clone(),
+        min_importance: filter.min_importance,
+    };
+    let offset = page.offset();
+    match data.db.list_memories_page(
+        &filter,
+        page.sort_by(MEMORY_SORTS, "created_at"),
+        page.descending(true),
+        page.limit(100, 500),
+        offset,
+    ) {
+        Ok((rows, total)) => Page::new(rows_to_items(rows), total, offset).respond(),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to list memories: {}", e)
+        })),
+    }
+}
+
 /// GET /api/memory/daily - Get today's or a specific date's daily log
 async fn get_daily_log(
     data: web::Data<AppState>,
@@ -799,7 +846,7 @@ async fn get_graph(data: web::Data<AppState>, req: HttpRequest) -> impl Responde
             Ok(GraphNode {
                 id: row.get(0)?,
                 content: {
-                    let c: String = row.get(1)?;
+                    let c = crate::db::encryption::decrypt_field(row.get(1)?);
                     if c.chars().count() > 200 {
                         let truncated: String = c.chars().take(200).collect();
                         format!("{}...", truncated)
@@ -1173,6 +1220,150 @@ async fn rebuild_associations(data: web::Data<AppState>, req: HttpRequest) -> im
     })
 }
 
+// ============================================================================
+// Knowledge Graph Handlers
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct KnowledgeGraphQuery {
+    entity_type: Option<String>,
+    #[serde(default)]
+    query: String,
+    #[serde(default = "default_knowledge_limit")]
+    limit: usize,
+}
+
+fn default_knowledge_limit() -> usize {
+    300
+}
+
+#[derive(Debug, Serialize)]
+struct KnowledgeGraphResponse {
+    success: bool,
+    nodes: Vec<crate::db::tables::knowledge_graph::KnowledgeEntity>,
+    edges: Vec<crate::db::tables::knowledge_graph::KnowledgeRelation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<crate::db::tables::knowledge_graph::KnowledgeGraphStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct KnowledgeEntityResponse {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity: Option<crate::db::tables::knowledge_graph::KnowledgeEntity>,
+    relations: Vec<crate::db::tables::knowledge_graph::KnowledgeRelation>,
+    /// Entities on the other end of `relations`
+    related: Vec<crate::db::tables::knowledge_graph::KnowledgeEntity>,
+    mentions: Vec<crate::db::tables::knowledge_graph::KnowledgeMention>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// GET /api/memory/knowledge/graph - Knowledge graph entities (nodes) and relations (edges)
+async fn get_knowledge_graph(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<KnowledgeGraphQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+
+    let result = data
+        .db
+        .search_knowledge_entities(&query.query, query.entity_type.as_deref(), query.limit.clamp(1, 2000))
+        .and_then(|nodes| {
+            let ids: Vec<i64> = nodes.iter().map(|n| n.id).collect();
+            let edges = data.db.list_knowledge_relations_among(&ids)?;
+            Ok((nodes, edges, data.db.get_knowledge_graph_stats()?))
+        });
+
+    match result {
+        Ok((nodes, edges, stats)) => HttpResponse::Ok().json(KnowledgeGraphResponse {
+            success: true,
+            nodes,
+            edges,
+            stats: Some(stats),
+            error: None,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(KnowledgeGraphResponse {
+            success: false,
+            nodes: vec![],
+            edges: vec![],
+            stats: None,
+            error: Some(format!("Failed to load knowledge graph: {}", e)),
+        }),
+    }
+}
+
+/// GET /api/memory/knowledge/entities/{id} - An entity with its relations and mentions
+async fn get_knowledge_entity(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+
+    let id = path.into_inner();
+    let error = |status: actix_web::http::StatusCode, message: String| {
+        HttpResponse::build(status).json(KnowledgeEntityResponse {
+            success: false,
+            entity: None,
+            relations: vec![],
+            related: vec![],
+            mentions: vec![],
+            error: Some(message),
+        })
+    };
+
+    let entity = match data.db.get_knowledge_entity(id) {
+        Ok(Some(e)) => e,
+        Ok(None) => return error(actix_web::http::StatusCode::NOT_FOUND, format!("Entity {} not found", id)),
+        Err(e) => return error(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+    let relations = data.db.list_knowledge_relations_for(id).unwrap_or_default();
+    let related = relations
+        .iter()
+        .map(|r| if r.source_entity_id == id { r.target_entity_id } else { r.source_entity_id })
+        .filter_map(|other| data.db.get_knowledge_entity(other).ok().flatten())
+        .collect();
+    let mentions = data.db.list_knowledge_mentions(id, 50).unwrap_or_default();
+
+    HttpResponse::Ok().json(KnowledgeEntityResponse {
+        success: true,
+        entity: Some(entity),
+        relations,
+        related,
+        mentions,
+        error: None,
+    })
+}
+
+/// POST /api/memory/knowledge/rebuild - Rebuild the knowledge graph from all memories
+async fn rebuild_knowledge_graph(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+
+    let db = data.db.clone();
+    tokio::spawn(async move {
+        match crate::memory::knowledge_graph::rebuild(&db) {
+            Ok(count) => log::info!("[KNOWLEDGE_GRAPH] Rebuilt from {} memories", count),
+            Err(e) => log::error!("[KNOWLEDGE_GRAPH] Rebuild failed: {}", e),
+        }
+    });
+
+    HttpResponse::Ok().json(BackfillResponse {
+        success: true,
+        message: Some("Knowledge graph rebuild started in background".to_string()),
+        error: None,
+    })
+}
+
 /// DELETE /api/memory/all - Delete all memories
 async fn delete_all_memories(
     data: web::Data<AppState>,
@@ -1216,6 +1407,34 @@ async fn delete_all_memories(
     }))
 }
 
+/// DELETE /api/memory/{id} - Move a single memory to the trash (restorable via /api/retention/trash)
+async fn delete_memory(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+
+    let memory_id = path.into_inner();
+
+    match data.db.soft_delete_memory(memory_id) {
+        Ok(Some(trash_id)) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "trash_id": trash_id
+        })),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "error": format!("Memory {} not found", memory_id)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "error": format!("Failed to delete memory: {}", e)
+        })),
+    }
+}
+
 // ============================================================================
 // Merge, Export & Import Types
 // ============================================================================
@@ -1383,6 +1602,9 @@ async fn export_memories(
     if let Err(resp) = validate_session_from_request(&data, &req) {
         return resp;
     }
+    if let Err(resp) = super::require_second_factor(&data, &req) {
+        return resp;
+    }
 
     let memories = match data.db.list_memories_filtered(
         query.memory_type.as_deref(),
@@ -1569,6 +1791,7 @@ async fn import_memories(
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/memory")
+            .route("/list", web::get().to(list_memories))
             .route("/files", web::get().to(list_files))
             .route("/search", web::get().to(search))
             .route("/daily", web::get().to(get_daily_log))
@@ -1587,7 +1810,11 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/embeddings/stats", web::get().to(embedding_stats))
             .route("/embeddings/backfill", web::post().to(backfill_embeddings))
             .route("/associations/rebuild", web::post().to(rebuild_associations))
+            .route("/knowledge/graph", web::get().to(get_knowledge_graph))
+            .route("/knowledge/entities/{id}", web::get().to(get_knowledge_entity))
+            .route("/knowledge/rebuild", web::post().to(rebuild_knowledge_graph))
             .route("/all", web::delete().to(delete_all_memories))
+            .route("/{id}", web::delete().to(delete_memory))
             // Phase 2: Dedup, merge, export/import
             .route("/merge", web::post().to(merge_memories))
             .route("/export", web::get().to(export_memories))
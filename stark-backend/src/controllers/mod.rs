@@ -1,39 +1,77 @@
+pub mod access_keys;
 pub mod agent_settings;
+pub mod ai_quotas;
 pub mod agent_subtypes;
+pub mod analytics;
 pub mod api_keys;
 pub mod auth;
+pub mod away;
 pub mod broadcasted_transactions;
 pub mod channels;
 pub mod chat;
+pub mod context_prewarm;
+pub mod cluster;
 pub mod cron;
 pub mod dashboard;
+pub mod digest;
+pub mod doctor;
 pub mod heartbeat;
+pub mod http_security;
 pub mod eip8004;
+pub mod event_bus;
+pub mod executions;
 pub mod ext;
 pub mod external_channel;
+pub mod farcaster;
 pub mod files;
 pub mod gmail;
+pub mod handoffs;
 pub mod health;
 pub mod hooks_api;
 pub mod identity;
+pub mod identity_profiles;
 pub mod internal_wallet;
 pub mod intrinsic;
+pub mod introspection;
+pub mod issue_tracker;
 pub mod kanban;
 pub mod notes;
+pub mod note_export;
 pub mod memory;
 pub mod impulse_map;
 pub mod modules;
+pub mod openapi;
+pub mod outbound;
+pub mod outbox;
+pub mod pagination;
+pub mod paper;
 pub mod payments;
 pub mod public_files;
+pub mod read_only_mode;
+pub mod reputation_feedback;
+pub mod retention;
+pub mod rpc_endpoints;
+pub mod rules;
+pub mod safe;
 pub mod sessions;
 pub mod skills;
+pub mod social_posts;
+pub mod strategies;
+pub mod tokens;
+pub mod tool_confirmations;
+pub mod tool_timeouts;
 pub mod tools;
+pub mod trades;
+pub mod tx_approvals;
 pub mod tx_queue;
+pub mod two_factor;
 pub mod well_known;
 pub mod system;
 pub mod special_roles;
 pub mod telemetry;
 pub mod transcribe;
+pub mod validation;
+pub mod wallet_monitor;
 pub mod x402;
 pub mod x402_limits;
 
@@ -49,7 +87,8 @@ pub fn validate_session(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -60,7 +99,7 @@ pub fn validate_session(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "error": "Invalid or expired session"
@@ -73,3 +112,36 @@ pub fn validate_session(
         }
     }
 }
+
+/// Gate for sensitive actions (secrets, wallet policy, module installs,
+/// exports). Once two-factor authentication is enrolled, the request must come
+/// from a login session that verified a code within the last few minutes;
+/// access keys never pass. Call after `validate_session`.
+pub fn require_second_factor(
+    state: &web::Data<AppState>,
+    req: &HttpRequest,
+) -> Result<(), HttpResponse> {
+    let internal_error = |e: rusqlite::Error| {
+        log::error!("Second factor check failed: {}", e);
+        HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Internal server error"
+        }))
+    };
+    if !state.db.is_two_factor_enabled().map_err(internal_error)? {
+        return Ok(());
+    }
+    let verified_at = match crate::middleware::session_auth::extract_token(req) {
+        Some(token) => state.db.session_second_factor_at(&token).map_err(internal_error)?,
+        None => None,
+    };
+    let recent = verified_at.is_some_and(|at| {
+        (chrono::Utc::now() - at).num_seconds() < crate::two_factor::RECENT_VERIFICATION_SECS
+    });
+    if recent {
+        return Ok(());
+    }
+    Err(HttpResponse::Forbidden().json(serde_json::json!({
+        "error": "This action needs a recent second-factor verification (POST /api/two-factor/verify)",
+        "second_factor_required": true
+    })))
+}
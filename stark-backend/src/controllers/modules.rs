@@ -8,99 +8,10 @@ use actix_multipart::Multipart;
 use actix_web::{web, HttpRequest, HttpResponse};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use crate::modules::lifecycle;
+use crate::modules::supervisor::{self, kill_service_on_port, start_module_service, ModuleHealth};
 use crate::AppState;
 
-/// Kill the service process listening on a given port (if any).
-fn kill_service_on_port(port: u16) {
-    let output = std::process::Command::new("lsof")
-        .args(["-ti", &format!("tcp:{}", port)])
-        .output();
-    if let Ok(out) = output {
-        let pids = String::from_utf8_lossy(&out.stdout);
-        let my_pid = std::process::id().to_string();
-        for pid_str in pids.split_whitespace() {
-            let pid = pid_str.trim();
-            if !pid.is_empty() && pid != my_pid {
-                log::info!("[MODULE] Killing service process PID {} on port {}", pid, port);
-                let _ = std::process::Command::new("kill").arg(pid).output();
-            }
-        }
-    }
-}
-
-/// Start a module's service if not already running.
-/// Checks the module manifest for a `command` field first, falling back to binary discovery.
-fn start_module_service(module_name: &str, port: u16, db: &crate::db::Database) {
-    // Already running?
-    if std::net::TcpStream::connect(format!("127.0.0.1:{}", port)).is_ok() {
-        log::info!("[MODULE] {} already running on port {} — skipping start", module_name, port);
-        return;
-    }
-
-    // Check if the module has a command in its manifest
-    let registry = crate::modules::ModuleRegistry::new();
-    if let Some(module) = registry.get(module_name) {
-        if let Some(command) = module.manifest_command() {
-            let module_dir = match module.module_dir() {
-                Some(dir) => dir.clone(),
-                None => {
-                    log::warn!("[MODULE] {} has command but no module_dir — cannot start", module_name);
-                    return;
-                }
-            };
-            let mut cmd = std::process::Command::new("sh");
-            cmd.arg("-c").arg(&command);
-            cmd.current_dir(&module_dir);
-            cmd.stdout(std::process::Stdio::inherit())
-                .stderr(std::process::Stdio::inherit());
-            cmd.env("MODULE_PORT", port.to_string());
-
-            // Also set the module-specific port env var (e.g. OPENAGENT_PORT, WALLET_MONITOR_PORT)
-            if let Some(port_var) = module.manifest_port_env_var() {
-                cmd.env(&port_var, port.to_string());
-            }
-
-            // Pass all declared env vars from DB api_keys, falling back to process env
-            for env_key in module.manifest_env_var_keys() {
-                if let Ok(Some(key)) = db.get_api_key(&env_key) {
-                    cmd.env(&env_key, &key.api_key);
-                } else if let Ok(val) = std::env::var(&env_key) {
-                    if !val.is_empty() {
-                        cmd.env(&env_key, &val);
-                    }
-                }
-            }
-
-            match cmd.spawn() {
-                Ok(_) => log::info!("[MODULE] Started {} via `{}` (port {})", module_name, command, port),
-                Err(e) => log::error!("[MODULE] Failed to start {} via `{}`: {}", module_name, command, e),
-            }
-            return;
-        }
-    }
-
-    // Fallback: binary discovery
-    let self_exe = std::env::current_exe().unwrap_or_default();
-    let exe_dir = self_exe.parent().unwrap_or(std::path::Path::new("."));
-
-    let binary_name = module_name.replace('_', "-") + "-service";
-    let exe_path = exe_dir.join(&binary_name);
-    if !exe_path.exists() {
-        log::warn!("[MODULE] Service binary not found: {} — cannot start", exe_path.display());
-        return;
-    }
-
-    let mut cmd = std::process::Command::new(&exe_path);
-    cmd.stdout(std::process::Stdio::inherit())
-        .stderr(std::process::Stdio::inherit());
-    cmd.env("MODULE_PORT", port.to_string());
-
-    match cmd.spawn() {
-        Ok(_) => log::info!("[MODULE] Started {} (port {})", binary_name, port),
-        Err(e) => log::error!("[MODULE] Failed to start {}: {}", binary_name, e),
-    }
-}
-
 #[derive(Serialize)]
 struct ModuleInfo {
     name: String,
@@ -117,6 +28,10 @@ struct ModuleInfo {
     service_url: String,
     service_port: u16,
     installed_at: Option<String>,
+    /// Supervisor health (None until the first check)
+    health: Option<ModuleHealth>,
+    /// Newer StarkHub version, if one is available
+    update_available: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -178,6 +93,7 @@ async fn deactivate_module(data: &web::Data<AppState>, module_name: &str) {
 async fn list_modules(data: web::Data<AppState>, _req: HttpRequest) -> HttpResponse {
     let registry = crate::modules::ModuleRegistry::new();
     let installed = data.db.list_installed_modules().unwrap_or_default();
+    let updates = data.db.list_module_updates().unwrap_or_default();
 
     let mut modules = Vec::new();
     for module in registry.available_modules() {
@@ -199,6 +115,11 @@ async fn list_modules(data: web::Data<AppState>, _req: HttpRequest) -> HttpRespo
             service_url: module.service_url(),
             service_port: module.default_port(),
             installed_at: installed_entry.map(|e| e.installed_at.to_rfc3339()),
+            health: supervisor::health(module.name()),
+            update_available: updates
+                .iter()
+                .find(|u| u.module_name == module.name())
+                .map(|u| u.available_version.clone()),
         });
     }
 
@@ -208,7 +129,7 @@ async fn list_modules(data: web::Data<AppState>, _req: HttpRequest) -> HttpRespo
 /// POST /api/modules/{name} — install, uninstall, enable, or disable a module
 async fn module_action(
     data: web::Data<AppState>,
-    _req: HttpRequest,
+    req: HttpRequest,
     name: web::Path<String>,
     body: web::Json<ModuleActionRequest>,
 ) -> HttpResponse {
@@ -217,6 +138,9 @@ async fn module_action(
 
     match action.as_str() {
         "install" => {
+            if let Err(resp) = super::require_second_factor(&data, &req) {
+                return resp;
+            }
             if data.db.is_module_installed(&name).unwrap_or(false) {
                 return HttpResponse::Conflict().json(serde_json::json!({
                     "error": format!("Module '{}' is already installed", name)
@@ -239,6 +163,14 @@ async fn module_action(
                 module.has_dashboard(),
             ) {
                 Ok(_) => {
+                    if let Some(dir) = module.module_dir() {
+                        if let Err(e) = lifecycle::run_install_hook(&data.db, &name, dir, false).await {
+                            return HttpResponse::InternalServerError().json(serde_json::json!({
+                                "error": format!("Install failed: {}", e)
+                            }));
+                        }
+                    }
+
                     // Install skill if provided
                     data.skill_registry.sync_module_skill(&name).await;
 
@@ -263,13 +195,16 @@ async fn module_action(
         "uninstall" => {
             deactivate_module(&data, &name).await;
 
-            // Delete module skill and kill the service process
+            // Delete module skill, kill the service process, then run the uninstall hook
             {
                 data.skill_registry.delete_module_skill(&name);
                 let registry = crate::modules::ModuleRegistry::new();
                 if let Some(module) = registry.get(&name) {
-                    kill_service_on_port(module.default_port());
+                    kill_service_on_port(supervisor::runtime_port(module));
+                    lifecycle::run_uninstall_hook(&data.db, &name, module.module_dir().map(|d| d.as_path())).await;
                 }
+                supervisor::forget(&name);
+                let _ = data.db.clear_module_update(&name);
             }
 
             match data.db.uninstall_module(&name) {
@@ -310,6 +245,7 @@ async fn module_action(
                 Ok(true) | Ok(false) => {
                     // Activate tools + start service regardless of previous state
                     activate_module(&data, &name).await;
+                    supervisor::reset(&name);
                     start_module_service(&name, module.default_port(), &data.db);
                     HttpResponse::Ok().json(serde_json::json!({
                         "status": "enabled",
@@ -331,8 +267,9 @@ async fn module_action(
                 data.skill_registry.disable_module_skill(&name);
                 let registry = crate::modules::ModuleRegistry::new();
                 if let Some(module) = registry.get(&name) {
-                    kill_service_on_port(module.default_port());
+                    kill_service_on_port(supervisor::runtime_port(module));
                 }
+                supervisor::forget(&name);
             }
 
             match data.db.set_module_enabled(&name, false) {
@@ -350,33 +287,18 @@ async fn module_action(
         }
 
         "restart" => {
-            let registry = crate::modules::ModuleRegistry::new();
-            match registry.get(&name) {
-                Some(module) => {
-                    // Use the actual runtime port (from env var) rather than the
-                    // default_port, since start_module_services() assigns dynamic ports.
-                    let service_url = module.service_url();
-                    let port = service_url
-                        .rsplit(':')
-                        .next()
-                        .and_then(|s| s.parse::<u16>().ok())
-                        .unwrap_or(module.default_port());
-                    kill_service_on_port(port);
-                    // Also kill on default port in case the service was started there
-                    if port != module.default_port() {
-                        kill_service_on_port(module.default_port());
-                    }
-                    // Brief pause to let the port free up
-                    std::thread::sleep(std::time::Duration::from_millis(500));
-                    start_module_service(&name, port, &data.db);
+            // Uses the actual runtime port rather than the default_port,
+            // since start_module_services() assigns dynamic ports.
+            match supervisor::restart_module_service(&data.db, &name) {
+                Ok(port) => {
+                    // A manual restart also clears a "failed" supervisor state
+                    supervisor::reset(&name);
                     HttpResponse::Ok().json(serde_json::json!({
                         "status": "restarted",
                         "message": format!("Module '{}' service restarted on port {}.", name, port)
                     }))
                 }
-                None => HttpResponse::NotFound().json(serde_json::json!({
-                    "error": format!("Unknown module: '{}'", name)
-                })),
+                Err(e) => HttpResponse::NotFound().json(serde_json::json!({ "error": e })),
             }
         }
 
@@ -443,7 +365,7 @@ async fn module_status(
         })),
     };
 
-    let url = format!("{}/rpc/status", module.service_url());
+    let url = module.health_url();
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(3))
         .build()
@@ -458,11 +380,13 @@ async fn module_status(
         }
         Ok(_) => HttpResponse::ServiceUnavailable().json(serde_json::json!({
             "status": "unhealthy",
-            "error": "Service returned non-200 response"
+            "error": "Service returned non-200 response",
+            "supervisor": supervisor::health(&name),
         })),
         Err(_) => HttpResponse::ServiceUnavailable().json(serde_json::json!({
             "status": "offline",
-            "error": "Service unreachable"
+            "error": "Service unreachable",
+            "supervisor": supervisor::health(&name),
         })),
     }
 }
@@ -823,6 +747,11 @@ async fn fetch_remote(
                         None,
                     ) {
                         Ok(_) => {
+                            if let Err(e) = lifecycle::run_install_hook(&data.db, &name_underscore, &module_dir, true).await {
+                                return HttpResponse::InternalServerError().json(serde_json::json!({
+                                    "error": format!("Install failed: {}", e)
+                                }));
+                            }
                             activate_module(&data, &name_underscore).await;
                             return HttpResponse::Ok().json(serde_json::json!({
                                 "status": "installed",
@@ -928,6 +857,15 @@ async fn fetch_remote(
         Some(&computed_hash),
     ) {
         Ok(_) => {
+            // Pin the extracted binary; every launch is checked against it
+            if let Ok(binary_hash) = crate::modules::sandbox::sha256_file(&binary_path) {
+                let _ = data.db.set_module_binary_sha256(&name_underscore, &binary_hash);
+            }
+            if let Err(e) = lifecycle::run_install_hook(&data.db, &name_underscore, &module_dir, true).await {
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Install failed: {}", e)
+                }));
+            }
             activate_module(&data, &name_underscore).await;
             HttpResponse::Ok().json(serde_json::json!({
                 "status": "installed",
@@ -948,9 +886,13 @@ async fn fetch_remote(
 /// POST /api/modules/upload — import a module from a ZIP file upload
 async fn upload_module(
     data: web::Data<AppState>,
-    _req: HttpRequest,
+    req: HttpRequest,
     mut payload: Multipart,
 ) -> HttpResponse {
+    if let Err(resp) = super::require_second_factor(&data, &req) {
+        return resp;
+    }
+
     // Read the uploaded file
     let mut file_data: Vec<u8> = Vec::new();
 
@@ -1042,6 +984,12 @@ async fn upload_module(
         None,
     ) {
         Ok(_) => {
+            if let Err(e) = lifecycle::run_install_hook(&data.db, &module_name, &module_dir, true).await {
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Install failed: {}", e)
+                }));
+            }
+
             // Hot-activate: register tools immediately
             activate_module(&data, &module_name).await;
 
@@ -1089,7 +1037,8 @@ fn validate_session(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -1100,7 +1049,7 @@ fn validate_session(
         }
     };
 
-    match data.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&data.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "error": "Invalid or expired session"
@@ -1244,6 +1193,75 @@ async fn publish_to_hub(
     HttpResponse::Ok().json(resp)
 }
 
+#[derive(Deserialize)]
+struct LaunchesQuery {
+    module: Option<String>,
+    limit: Option<usize>,
+}
+
+/// GET /api/modules/launches?module=&limit= — launch audit (binary hashes, sandbox, refusals)
+async fn module_launches(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<LaunchesQuery>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match data.db.list_module_launches(query.module.as_deref(), limit) {
+        Ok(launches) => HttpResponse::Ok().json(launches),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// GET /api/modules/updates — newer StarkHub versions of installed modules
+async fn module_updates(data: web::Data<AppState>, req: HttpRequest) -> HttpResponse {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_module_updates() {
+        Ok(updates) => HttpResponse::Ok().json(updates),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// POST /api/modules/{name}/upgrade — download, verify and hot-swap the latest StarkHub release
+async fn upgrade_module(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let name = path.into_inner();
+
+    // Unregister the old release's tools; the new release's are registered after the swap
+    deactivate_module(&data, &name).await;
+    let result = crate::modules::updates::upgrade_module(&data.db, &name).await;
+    let enabled = data.db.get_installed_module(&name).ok().flatten().is_some_and(|m| m.enabled);
+    if enabled {
+        activate_module(&data, &name).await;
+        data.skill_registry.sync_module_skill(&name).await;
+    }
+
+    match result {
+        Ok(outcome) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "upgraded",
+            "module": outcome.module_name,
+            "previous_version": outcome.previous_version,
+            "version": outcome.version,
+            "message": format!("Module '{}' upgraded to v{}.", name, outcome.version)
+        })),
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({ "error": e })),
+    }
+}
+
 /// GET /api/modules/{name}/logs — return captured service stdout/stderr lines.
 async fn module_logs(path: web::Path<String>) -> HttpResponse {
     let name = path.into_inner();
@@ -1300,18 +1318,22 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/reload", web::post().to(reload_modules))
             .route("/featured_remote", web::get().to(featured_remote))
             .route("/fetch_remote", web::post().to(fetch_remote))
+            .route("/launches", web::get().to(module_launches))
+            .route("/updates", web::get().to(module_updates))
             .route("/publish/{name}", web::post().to(publish_to_hub))
             .route("/{name}/dashboard", web::get().to(module_dashboard))
             .route("/{name}/download", web::get().to(download_module))
             .route("/{name}/logs", web::get().to(module_logs))
             .route("/{name}/status", web::get().to(module_status))
+            .route("/{name}/upgrade", web::post().to(upgrade_module))
             .route("/{name}/proxy/{path:.*}", web::get().to(module_proxy))
             .route("/{name}/proxy/{path:.*}", web::post().to(module_proxy_post))
             .route("/{name}", web::post().to(module_action)),
     );
     cfg.service(
         web::scope("/api/internal/modules")
-            .route("/tui-invalidate", web::post().to(tui_invalidate)),
+            .route("/tui-invalidate", web::post().to(tui_invalidate))
+            .route("/notify", web::post().to(module_notify)),
     );
 }
 
@@ -1350,3 +1372,47 @@ async fn tui_invalidate(
 
     HttpResponse::Ok().json(serde_json::json!({ "ok": true }))
 }
+
+#[derive(Deserialize)]
+struct ModuleNotifyRequest {
+    module: String,
+    channel_id: i64,
+    chat_id: String,
+    message: String,
+}
+
+/// POST /api/internal/modules/notify — post a module alert to a channel chat.
+/// Goes through the channel's proactive message settings like any other alert.
+async fn module_notify(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<ModuleNotifyRequest>,
+) -> HttpResponse {
+    let token = req
+        .headers()
+        .get("X-Internal-Token")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("");
+
+    if token.is_empty() || token != state.internal_token {
+        return HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Invalid or missing X-Internal-Token"
+        }));
+    }
+
+    let module = body.module.trim();
+    if module.is_empty() || body.chat_id.trim().is_empty() || body.message.trim().is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "'module', 'chat_id' and 'message' are required"
+        }));
+    }
+
+    let kind = format!("module:{}", module);
+    match crate::channels::outbound::send_proactive(&state.db, body.channel_id, body.chat_id.trim(), &body.message, &kind).await {
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({ "ok": true })),
+        Err(e) => {
+            log::warn!("[MODULE] '{}' notification to channel {} not sent: {}", module, body.channel_id, e);
+            HttpResponse::UnprocessableEntity().json(serde_json::json!({ "error": e }))
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Note export API — configure the Notion/Obsidian mirror, run a sync,
+//! and resolve conflicts.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate_session;
+use crate::db::tables::note_exports::NoteExportConfig;
+use crate::integrations::note_export::{sync, target_for};
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/note-export")
+            .route("/config", web::get().to(get_config))
+            .route("/config", web::put().to(update_config))
+            .route("/sync", web::post().to(run_sync))
+            .route("/exports", web::get().to(list_exports))
+            .route("/exports/{id}/resolve", web::post().to(resolve_export)),
+    );
+}
+
+#[derive(Deserialize)]
+struct UpdateNoteExportRequest {
+    target: Option<String>,
+    enabled: Option<bool>,
+    /// Empty string clears the stored token
+    notion_token: Option<String>,
+    notion_parent_page_id: Option<String>,
+    obsidian_vault_path: Option<String>,
+    folder: Option<String>,
+    min_importance: Option<i64>,
+    memory_types: Option<String>,
+    include_session_summaries: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct ExportsQuery {
+    status: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ResolveRequest {
+    /// "local" or "remote"
+    keep: String,
+}
+
+/// Config as returned to the UI (token never leaves the server)
+fn config_json(config: &NoteExportConfig) -> serde_json::Value {
+    let mut value = json!(config);
+    value["notion_token"] = json!(null);
+    value["has_notion_token"] = json!(config.notion_token.as_deref().is_some_and(|t| !t.is_empty()));
+    value
+}
+
+fn empty_to_none(value: String) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() { None } else { Some(trimmed.to_string()) }
+}
+
+async fn get_config(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.get_note_export_config() {
+        Ok(config) => HttpResponse::Ok().json(config_json(&config)),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn update_config(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<UpdateNoteExportRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let mut config = match data.db.get_note_export_config() {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+
+    let body = body.into_inner();
+    if let Some(target) = body.target {
+        if target != "notion" && target != "obsidian" {
+            return HttpResponse::BadRequest().json(json!({
+                "error": "target must be 'notion' or 'obsidian'"
+            }));
+        }
+        config.target = target;
+    }
+    if let Some(enabled) = body.enabled {
+        config.enabled = enabled;
+    }
+    if let Some(token) = body.notion_token {
+        config.notion_token = empty_to_none(token);
+    }
+    if let Some(parent) = body.notion_parent_page_id {
+        config.notion_parent_page_id = empty_to_none(parent);
+    }
+    if let Some(path) = body.obsidian_vault_path {
+        config.obsidian_vault_path = empty_to_none(path);
+    }
+    if let Some(folder) = body.folder.and_then(empty_to_none) {
+        config.folder = folder;
+    }
+    if let Some(min) = body.min_importance {
+        config.min_importance = min.clamp(1, 10);
+    }
+    if let Some(types) = body.memory_types {
+        config.memory_types = types;
+    }
+    if let Some(include) = body.include_session_summaries {
+        config.include_session_summaries = include;
+    }
+
+    // Refuse to enable an export that can't run
+    if let Some(Err(e)) = config.enabled.then(|| target_for(&config)) {
+        return HttpResponse::BadRequest().json(json!({ "error": e }));
+    }
+
+    match data.db.save_note_export_config(&config) {
+        Ok(saved) => HttpResponse::Ok().json(config_json(&saved)),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn run_sync(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match sync::run_sync(&data.db).await {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => HttpResponse::BadRequest().json(json!({ "error": e })),
+    }
+}
+
+async fn list_exports(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ExportsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_note_exports(query.status.as_deref()) {
+        Ok(exports) => HttpResponse::Ok().json(exports),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn resolve_export(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<ResolveRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let keep_local = match body.keep.as_str() {
+        "local" => true,
+        "remote" => false,
+        _ => {
+            return HttpResponse::BadRequest().json(json!({
+                "error": "keep must be 'local' or 'remote'"
+            }))
+        }
+    };
+    match sync::resolve_export(&data.db, path.into_inner(), keep_local).await {
+        Ok(()) => HttpResponse::Ok().json(json!({ "success": true })),
+        Err(e) => HttpResponse::BadRequest().json(json!({ "error": e })),
+    }
+}
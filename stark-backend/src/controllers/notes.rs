@@ -22,7 +22,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -33,7 +34,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "error": "Invalid or expired session"
@@ -662,6 +663,9 @@ async fn export_notes(data: web::Data<AppState>, req: HttpRequest) -> impl Respo
     if let Err(resp) = validate_session_from_request(&data, &req) {
         return resp;
     }
+    if let Err(resp) = super::require_second_factor(&data, &req) {
+        return resp;
+    }
 
     let notes = notes_dir();
     let notes_path = Path::new(&notes);
@@ -0,0 +1,340 @@
+//! OpenAPI description of the REST API
+//!
+//! `GET /api/openapi.json` serves an OpenAPI 3.0 document and `GET /api/docs`
+//! a Swagger UI page for it, so integrators can browse the API and generate
+//! clients. Controllers describe their own routes in an `openapi` function
+//! next to their `config`, so a route and its description change together;
+//! `spec()` collects them. Routes without a description still work, they just
+//! don't show up in the document.
+
+use std::collections::BTreeMap;
+
+use actix_web::{web, HttpResponse, Responder};
+use once_cell::sync::Lazy;
+use serde_json::{json, Map, Value};
+
+use super::pagination::{NEXT_CURSOR_HEADER, TOTAL_COUNT_HEADER};
+
+/// The assembled document
+pub struct ApiDoc {
+    paths: BTreeMap<String, BTreeMap<&'static str, Operation>>,
+    schemas: Map<String, Value>,
+}
+
+/// One method on one path
+pub struct Operation {
+    tag: &'static str,
+    summary: String,
+    parameters: Vec<Value>,
+    request_body: Option<Value>,
+    response: Option<Value>,
+    response_headers: bool,
+    public: bool,
+}
+
+impl ApiDoc {
+    pub fn new() -> Self {
+        let mut schemas = Map::new();
+        schemas.insert(
+            "Error".to_string(),
+            object(&[("error", string()), ("category", string())], &["error"]),
+        );
+        ApiDoc { paths: BTreeMap::new(), schemas }
+    }
+
+    /// Add a named schema under `components/schemas`
+    pub fn schema(&mut self, name: &str, schema: Value) -> &mut Self {
+        self.schemas.insert(name.to_string(), schema);
+        self
+    }
+
+    fn op(&mut self, method: &'static str, path: &str, tag: &'static str, summary: &str) -> &mut Operation {
+        let mut operation = Operation {
+            tag,
+            summary: summary.to_string(),
+            parameters: Vec::new(),
+            request_body: None,
+            response: None,
+            response_headers: false,
+            public: false,
+        };
+        // Path parameters are required by the spec; declare them from the template
+        for name in path.split('/').filter_map(|s| s.strip_prefix('{')?.strip_suffix('}')) {
+            operation.parameters.push(json!({
+                "name": name, "in": "path", "required": true, "schema": string(),
+            }));
+        }
+        let methods = self.paths.entry(path.to_string()).or_default();
+        methods.insert(method, operation);
+        methods.get_mut(method).expect("just inserted")
+    }
+
+    pub fn get(&mut self, path: &str, tag: &'static str, summary: &str) -> &mut Operation {
+        self.op("get", path, tag, summary)
+    }
+
+    pub fn post(&mut self, path: &str, tag: &'static str, summary: &str) -> &mut Operation {
+        self.op("post", path, tag, summary)
+    }
+
+    pub fn put(&mut self, path: &str, tag: &'static str, summary: &str) -> &mut Operation {
+        self.op("put", path, tag, summary)
+    }
+
+    pub fn delete(&mut self, path: &str, tag: &'static str, summary: &str) -> &mut Operation {
+        self.op("delete", path, tag, summary)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let paths: Map<String, Value> = self
+            .paths
+            .iter()
+            .map(|(path, methods)| {
+                let ops: Map<String, Value> =
+                    methods.iter().map(|(m, op)| (m.to_string(), op.to_json())).collect();
+                (path.clone(), Value::Object(ops))
+            })
+            .collect();
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "Starkbot API",
+                "version": env!("CARGO_PKG_VERSION"),
+                "description": "REST API of the Starkbot backend. Sign in with SIWE \
+                    (`POST /api/auth/generate_challenge`, then `POST /api/auth/validate_auth`) and send \
+                    the returned token as `Authorization: Bearer <token>`, or send a scoped access key \
+                    (`sk_stark_...`, created under `/api/access-keys`) the same way.",
+            },
+            "paths": paths,
+            "components": {
+                "schemas": self.schemas,
+                "securitySchemes": { "bearerAuth": { "type": "http", "scheme": "bearer" } },
+            },
+            "security": [{ "bearerAuth": [] }],
+        })
+    }
+}
+
+impl Default for ApiDoc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Operation {
+    /// A query parameter
+    pub fn query(&mut self, name: &str, schema: Value, description: &str) -> &mut Self {
+        self.parameters.push(json!({
+            "name": name, "in": "query", "schema": schema, "description": description,
+        }));
+        self
+    }
+
+    /// The shared paging parameters and response headers (see `pagination`)
+    pub fn paged(&mut self, sorts: &[&str]) -> &mut Self {
+        self.query("limit", integer(), "Page size")
+            .query("offset", integer(), "Index of the first item")
+            .query("cursor", string(), "X-Next-Cursor of the previous page (overrides offset)")
+            .query("sort", json!({ "type": "string", "enum": sorts }), "Sort field")
+            .query("order", json!({ "type": "string", "enum": ["asc", "desc"] }), "Sort order")
+            .query("q", string(), "Case-insensitive text filter");
+        self.response_headers = true;
+        self
+    }
+
+    /// JSON request body
+    pub fn body(&mut self, schema: Value) -> &mut Self {
+        self.request_body = Some(schema);
+        self
+    }
+
+    /// JSON body of the 200 response
+    pub fn returns(&mut self, schema: Value) -> &mut Self {
+        self.response = Some(schema);
+        self
+    }
+
+    /// Callable without a session token
+    pub fn public(&mut self) -> &mut Self {
+        self.public = true;
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut ok = json!({ "description": "OK" });
+        if let Some(schema) = &self.response {
+            ok["content"] = json!({ "application/json": { "schema": schema } });
+        }
+        if self.response_headers {
+            ok["headers"] = json!({
+                (TOTAL_COUNT_HEADER): { "description": "Items matching the filters", "schema": integer() },
+                (NEXT_CURSOR_HEADER): { "description": "Cursor for the next page; absent on the last page", "schema": string() },
+            });
+        }
+        let mut responses = json!({
+            "200": ok,
+            "default": { "description": "Error", "content": { "application/json": { "schema": reference("Error") } } },
+        });
+        let mut op = json!({
+            "tags": [self.tag],
+            "summary": self.summary,
+            "parameters": self.parameters,
+        });
+        if let Some(schema) = &self.request_body {
+            op["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": schema } },
+            });
+        }
+        if self.public {
+            op["security"] = json!([]);
+        } else {
+            responses["401"] = json!({ "description": "Missing, invalid or expired session token" });
+        }
+        op["responses"] = responses;
+        op
+    }
+}
+
+// Schema helpers
+
+pub fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+pub fn integer() -> Value {
+    json!({ "type": "integer" })
+}
+
+pub fn boolean() -> Value {
+    json!({ "type": "boolean" })
+}
+
+pub fn date_time() -> Value {
+    json!({ "type": "string", "format": "date-time" })
+}
+
+pub fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+pub fn string_enum(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+/// A value that may also be null
+pub fn nullable(mut schema: Value) -> Value {
+    if schema.get("$ref").is_some() {
+        return json!({ "allOf": [schema], "nullable": true });
+    }
+    schema["nullable"] = json!(true);
+    schema
+}
+
+/// Any JSON object
+pub fn any_object() -> Value {
+    json!({ "type": "object", "additionalProperties": true })
+}
+
+pub fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+pub fn object(properties: &[(&str, Value)], required: &[&str]) -> Value {
+    let props: Map<String, Value> = properties.iter().map(|(k, v)| (k.to_string(), v.clone())).collect();
+    let mut schema = json!({ "type": "object", "properties": props });
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    schema
+}
+
+/// `{ success, error? }` plus the given fields, the envelope most handlers answer with
+pub fn envelope(properties: &[(&str, Value)]) -> Value {
+    let mut all = vec![("success", boolean()), ("error", string())];
+    all.extend(properties.iter().cloned());
+    object(&all, &["success"])
+}
+
+/// The document for every described route
+pub fn spec() -> Value {
+    let mut doc = ApiDoc::new();
+    super::chat::openapi(&mut doc);
+    super::sessions::openapi(&mut doc);
+    super::skills::openapi(&mut doc);
+    super::agent_settings::openapi(&mut doc);
+    super::system::openapi(&mut doc);
+    super::doctor::openapi(&mut doc);
+    super::event_bus::openapi(&mut doc);
+    super::away::openapi(&mut doc);
+    super::handoffs::openapi(&mut doc);
+    super::tool_timeouts::openapi(&mut doc);
+    super::auth::openapi(&mut doc);
+    super::health::openapi(&mut doc);
+    super::access_keys::openapi(&mut doc);
+    super::two_factor::openapi(&mut doc);
+    doc.get("/api/openapi.json", "system", "This document").public();
+    doc.to_json()
+}
+
+static SPEC: Lazy<Value> = Lazy::new(spec);
+
+const SWAGGER_UI: &str = r##"<!doctype html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>Starkbot API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/api/openapi.json", dom_id: "#swagger-ui", persistAuthorization: true });
+  </script>
+</body>
+</html>
+"##;
+
+/// GET /api/openapi.json
+async fn openapi_json() -> impl Responder {
+    HttpResponse::Ok().json(&*SPEC)
+}
+
+/// GET /api/docs - Swagger UI
+async fn swagger_ui() -> impl Responder {
+    HttpResponse::Ok().content_type("text/html; charset=utf-8").body(SWAGGER_UI)
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/api/openapi.json").route(web::get().to(openapi_json)))
+        .service(web::resource("/api/docs").route(web::get().to(swagger_ui)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_is_complete() {
+        let spec = spec();
+        let paths = spec["paths"].as_object().unwrap();
+        assert!(paths["/api/chat"]["post"]["requestBody"].is_object());
+        assert!(paths["/api/sessions"]["get"]["responses"]["200"]["headers"][TOTAL_COUNT_HEADER].is_object());
+        assert_eq!(paths["/api/health"]["get"]["security"], json!([]));
+
+        // Every $ref points at a schema that exists
+        let text = spec.to_string();
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+        for part in text.split("#/components/schemas/").skip(1) {
+            let name = part.split('"').next().unwrap();
+            assert!(schemas.contains_key(name), "missing schema {}", name);
+        }
+        for (path, methods) in paths {
+            for op in methods.as_object().unwrap().values() {
+                let declared = op["parameters"].as_array().unwrap().iter().filter(|p| p["in"] == "path").count();
+                assert_eq!(declared, path.matches('{').count(), "{}", path);
+            }
+        }
+    }
+}
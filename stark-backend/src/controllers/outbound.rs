@@ -0,0 +1,119 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::db::tables::outbound::OUTBOUND_DEAD;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct OutboundQuery {
+    status: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct LimitQuery {
+    limit: Option<usize>,
+}
+
+/// GET /api/outbound?status=&limit= - Queued outbound channel messages
+async fn list_messages(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<OutboundQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match data.db.list_outbound_messages(query.status.as_deref(), limit) {
+        Ok(messages) => HttpResponse::Ok().json(messages),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// GET /api/outbound/dead?limit= - Dead-letter queue (delivery gave up)
+async fn list_dead(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<LimitQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match data.db.list_outbound_messages(Some(OUTBOUND_DEAD), limit) {
+        Ok(messages) => HttpResponse::Ok().json(messages),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// GET /api/outbound/stats - Queue depth by status
+async fn get_stats(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.outbound_stats() {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// POST /api/outbound/{id}/retry - Requeue a dead-lettered message
+async fn retry_message(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.retry_dead_outbound_message(id) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Dead-lettered message {} not found", id)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// DELETE /api/outbound/{id} - Drop a queued or dead-lettered message
+async fn delete_message(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.delete_outbound_message(id) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Message {} not found or currently being sent", id)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/outbound")
+            .route("", web::get().to(list_messages))
+            .route("/stats", web::get().to(get_stats))
+            .route("/dead", web::get().to(list_dead))
+            .route("/{id}/retry", web::post().to(retry_message))
+            .route("/{id}", web::delete().to(delete_message)),
+    );
+}
@@ -0,0 +1,102 @@
+//! Review-before-send outbox API
+//!
+//! Lists drafts held on channels with review enabled and lets the owner
+//! approve (optionally with edited text) or reject them.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::channels::outbox;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct DraftsQuery {
+    status: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+struct ApproveRequest {
+    /// Replacement text to send instead of the agent's draft
+    content: Option<String>,
+}
+
+/// GET /api/outbox?status=&limit= - Held drafts, newest first
+async fn list_drafts(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<DraftsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match data.db.list_outbox_drafts(query.status.as_deref(), limit) {
+        Ok(drafts) => HttpResponse::Ok().json(drafts),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// GET /api/outbox/{id} - A single draft
+async fn get_draft(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.get_outbox_draft(path.into_inner()) {
+        Ok(Some(draft)) => HttpResponse::Ok().json(draft),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({ "error": "Draft not found" })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// POST /api/outbox/{id}/approve - Approve a pending draft and send it
+async fn approve_draft(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: Option<web::Json<ApproveRequest>>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    match outbox::approve(&data.db, path.into_inner(), "dashboard", body.content.as_deref()).await {
+        Ok(draft) => HttpResponse::Ok().json(draft),
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({ "error": e })),
+    }
+}
+
+/// POST /api/outbox/{id}/reject - Discard a pending draft
+async fn reject_draft(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.decide_outbox_draft(id, false, "dashboard") {
+        Ok(true) => {
+            log::info!("[OUTBOX] Draft {} rejected from the dashboard", id);
+            HttpResponse::Ok().json(serde_json::json!({ "success": true }))
+        }
+        Ok(false) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Draft {} is not pending", id)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/outbox")
+            .route("", web::get().to(list_drafts))
+            .route("/{id}", web::get().to(get_draft))
+            .route("/{id}/approve", web::post().to(approve_draft))
+            .route("/{id}/reject", web::post().to(reject_draft)),
+    );
+}
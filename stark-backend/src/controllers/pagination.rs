@@ -0,0 +1,169 @@
+//! Pagination, filtering and sorting for list endpoints
+//!
+//! List endpoints take the same query parameters:
+//! - `limit` / `offset`, or `cursor` (the `X-Next-Cursor` of the previous page)
+//! - `sort` (a field the endpoint names) and `order` (`asc` / `desc`)
+//! - `q`, a case-insensitive text filter, plus any endpoint-specific filters
+//!
+//! The body stays the array the UI already reads. The number of matching
+//! items goes in `X-Total-Count` and the cursor for the next page in
+//! `X-Next-Cursor`, which is left out on the last page.
+
+use actix_web::{HttpResponse, HttpResponseBuilder};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+pub const TOTAL_COUNT_HEADER: &str = "X-Total-Count";
+pub const NEXT_CURSOR_HEADER: &str = "X-Next-Cursor";
+
+/// Common list query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct PageQuery {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    pub cursor: Option<String>,
+    pub sort: Option<String>,
+    pub order: Option<String>,
+    pub q: Option<String>,
+}
+
+impl PageQuery {
+    /// Page size, `default` when not given and never above `max`
+    pub fn limit(&self, default: usize, max: usize) -> usize {
+        self.limit.unwrap_or(default).clamp(1, max)
+    }
+
+    /// Index of the first item; a valid cursor wins over `offset`
+    pub fn offset(&self) -> usize {
+        self.cursor
+            .as_deref()
+            .and_then(decode_cursor)
+            .or(self.offset)
+            .unwrap_or(0)
+    }
+
+    /// The requested sort field if it is one of `allowed`, else `default`
+    pub fn sort_by<'a>(&self, allowed: &[&'a str], default: &'a str) -> &'a str {
+        self.sort
+            .as_deref()
+            .and_then(|s| allowed.iter().find(|a| a.eq_ignore_ascii_case(s.trim())))
+            .copied()
+            .unwrap_or(default)
+    }
+
+    /// Whether to sort descending; `default_desc` when `order` is missing or unknown
+    pub fn descending(&self, default_desc: bool) -> bool {
+        match self.order.as_deref().map(|o| o.trim().to_lowercase()).as_deref() {
+            Some("asc") => false,
+            Some("desc") => true,
+            _ => default_desc,
+        }
+    }
+
+    /// The text filter, trimmed, if one was given
+    pub fn text(&self) -> Option<&str> {
+        self.q.as_deref().map(str::trim).filter(|q| !q.is_empty())
+    }
+
+    /// Whether any of `fields` contains the text filter (always true without one)
+    pub fn matches(&self, fields: &[&str]) -> bool {
+        match self.text() {
+            Some(q) => {
+                let q = q.to_lowercase();
+                fields.iter().any(|f| f.to_lowercase().contains(&q))
+            }
+            None => true,
+        }
+    }
+}
+
+fn encode_cursor(offset: usize) -> String {
+    URL_SAFE_NO_PAD.encode(format!("o:{}", offset))
+}
+
+fn decode_cursor(cursor: &str) -> Option<usize> {
+    let bytes = URL_SAFE_NO_PAD.decode(cursor.trim()).ok()?;
+    String::from_utf8(bytes).ok()?.strip_prefix("o:")?.parse().ok()
+}
+
+/// One page of a list
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// A page fetched elsewhere (e.g. with SQL LIMIT/OFFSET) out of `total` matches
+    pub fn new(items: Vec<T>, total: usize, offset: usize) -> Self {
+        let end = offset + items.len();
+        let next_cursor = (!items.is_empty() && end < total).then(|| encode_cursor(end));
+        Page { items, total, next_cursor }
+    }
+
+    /// Cut a page out of an already filtered and sorted list
+    pub fn slice(items: Vec<T>, offset: usize, limit: usize) -> Self {
+        let total = items.len();
+        let items: Vec<T> = items.into_iter().skip(offset).take(limit).collect();
+        Self::new(items, total, offset)
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            next_cursor: self.next_cursor,
+        }
+    }
+
+    /// A 200 response with the paging headers set, for endpoints that wrap
+    /// the items in their own body
+    pub fn ok(&self) -> HttpResponseBuilder {
+        let mut resp = HttpResponse::Ok();
+        resp.insert_header((TOTAL_COUNT_HEADER, self.total.to_string()));
+        if let Some(cursor) = &self.next_cursor {
+            resp.insert_header((NEXT_CURSOR_HEADER, cursor.as_str()));
+        }
+        resp
+    }
+}
+
+impl<T: Serialize> Page<T> {
+    /// 200 with the items as the body and the paging headers set
+    pub fn respond(self) -> HttpResponse {
+        self.ok().json(self.items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paging_with_cursor() {
+        let query = PageQuery { limit: Some(2), ..Default::default() };
+        let page = Page::slice(vec![1, 2, 3, 4, 5], query.offset(), query.limit(50, 100));
+        assert_eq!(page.items, vec![1, 2]);
+        assert_eq!(page.total, 5);
+
+        let next = PageQuery { limit: Some(2), offset: Some(0), cursor: page.next_cursor, ..Default::default() };
+        assert_eq!(next.offset(), 2);
+        let last = Page::slice(vec![1, 2, 3, 4, 5], 4, 2);
+        assert_eq!(last.items, vec![5]);
+        assert!(last.next_cursor.is_none());
+
+        let query = PageQuery {
+            sort: Some("NAME".into()),
+            order: Some("asc".into()),
+            q: Some(" Swap ".into()),
+            ..Default::default()
+        };
+        assert_eq!(query.sort_by(&["name", "version"], "version"), "name");
+        assert!(!query.descending(true));
+        assert!(query.matches(&["token-swap", "other"]));
+        assert!(!query.matches(&["bridge"]));
+        assert_eq!(PageQuery { limit: Some(10_000), ..Default::default() }.limit(50, 500), 500);
+    }
+}
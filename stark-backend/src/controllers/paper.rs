@@ -0,0 +1,100 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::config_watch::ConfigChange;
+use crate::error::AppResult;
+use crate::journal;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct PaperConfigRequest {
+    enabled: bool,
+}
+
+#[derive(Deserialize)]
+struct LedgerQuery {
+    /// Only this channel's fills (omit for all channels)
+    channel_id: Option<i64>,
+    /// day, week, month, year or all
+    period: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ChannelQuery {
+    channel_id: Option<i64>,
+}
+
+/// GET /api/paper/config - Whether paper trading is on for every channel
+async fn get_config(data: web::Data<AppState>, req: HttpRequest) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let settings = data.db.get_bot_settings()?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "enabled": settings.paper_trading_enabled })))
+}
+
+/// PUT /api/paper/config - Turn global paper trading on or off
+async fn update_config(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<PaperConfigRequest>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let settings = data.db.update_paper_trading(body.enabled)?;
+    log::info!("Paper trading {}", if settings.paper_trading_enabled { "enabled" } else { "disabled" });
+    data.config_watch.notify(ConfigChange::BotSettings);
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "enabled": settings.paper_trading_enabled })))
+}
+
+/// GET /api/paper/trades?channel_id=&period=&limit= - Simulated fills, newest first
+async fn list_trades(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<LedgerQuery>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let since = journal::period_start(query.period.as_deref().unwrap_or("all"))?;
+    let limit = query.limit.unwrap_or(100).min(1000);
+    let trades = data.db.list_paper_trades(query.channel_id, since.as_deref(), limit)?;
+    Ok(HttpResponse::Ok().json(trades))
+}
+
+/// DELETE /api/paper/trades?channel_id= - Reset the paper ledger
+async fn clear_trades(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ChannelQuery>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let deleted = data.db.clear_paper_trades(query.channel_id)?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "deleted": deleted })))
+}
+
+/// GET /api/paper/pnl?channel_id=&period= - Paper PnL per token
+async fn get_pnl(data: web::Data<AppState>, req: HttpRequest, query: web::Query<LedgerQuery>) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let since = journal::period_start(query.period.as_deref().unwrap_or("all"))?;
+    let report = crate::paper::pnl_report(&data.db, query.channel_id, since.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/paper")
+            .route("/config", web::get().to(get_config))
+            .route("/config", web::put().to(update_config))
+            .route("/trades", web::get().to(list_trades))
+            .route("/trades", web::delete().to(clear_trades))
+            .route("/pnl", web::get().to(get_pnl)),
+    );
+}
@@ -0,0 +1,58 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::config_watch::ConfigChange;
+use crate::gateway::protocol::GatewayEvent;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct ReadOnlyModeRequest {
+    enabled: bool,
+}
+
+/// GET /api/read-only-mode - Whether read-only mirror mode is on
+async fn get_mode(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.get_bot_settings() {
+        Ok(settings) => HttpResponse::Ok().json(serde_json::json!({ "enabled": settings.read_only_mode })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// PUT /api/read-only-mode - Turn read-only mirror mode on or off
+async fn update_mode(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<ReadOnlyModeRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.update_read_only_mode(body.enabled) {
+        Ok(settings) => {
+            log::info!("Read-only mirror mode {}", if settings.read_only_mode { "enabled" } else { "disabled" });
+            data.config_watch.notify(ConfigChange::BotSettings);
+            data.broadcaster.broadcast(GatewayEvent::custom(
+                "read_only_mode.changed",
+                serde_json::json!({ "enabled": settings.read_only_mode }),
+            ));
+            HttpResponse::Ok().json(serde_json::json!({ "enabled": settings.read_only_mode }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/read-only-mode")
+            .route("", web::get().to(get_mode))
+            .route("", web::put().to(update_mode)),
+    );
+}
@@ -0,0 +1,113 @@
+//! EIP-8004 feedback endpoint — accepts signed feedback from other agents and
+//! clients and serves the aggregated score.
+//!
+//! Submitting and reading the score are public (the endpoint is listed in the
+//! registration file); listing individual submissions requires a session.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate_session;
+use crate::db::tables::reputation_feedback::{NewReputationFeedback, FEEDBACK_RECEIVED};
+use crate::eip8004::feedback::{self, FeedbackSubmission};
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/feedback")
+            .route("", web::get().to(get_score))
+            .route("", web::post().to(submit_feedback))
+            .route("/received", web::get().to(list_received)),
+    );
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    limit: Option<usize>,
+}
+
+fn non_empty(s: &str) -> Option<&str> {
+    Some(s.trim()).filter(|s| !s.is_empty())
+}
+
+async fn get_score(data: web::Data<AppState>) -> impl Responder {
+    let Some(identity) = data.db.get_agent_identity_full() else {
+        return HttpResponse::NotFound().json(json!({ "error": "Agent registration not configured" }));
+    };
+    HttpResponse::Ok().json(json!({
+        "agentId": identity.agent_id,
+        "agentRegistry": identity.agent_registry,
+        "reputation": feedback::score(&data.db, &identity),
+    }))
+}
+
+async fn submit_feedback(data: web::Data<AppState>, body: web::Json<FeedbackSubmission>) -> impl Responder {
+    let Some(identity) = data.db.get_agent_identity_full() else {
+        return HttpResponse::NotFound().json(json!({ "error": "Agent registration not configured" }));
+    };
+    let submission = body.into_inner();
+
+    let now = chrono::Utc::now().timestamp();
+    let feedback_hash = match submission.verify(identity.agent_id as u64, &identity.agent_registry, now) {
+        Ok(hash) => hash,
+        Err(e) => return HttpResponse::BadRequest().json(json!({ "error": e })),
+    };
+
+    match data.db.reputation_feedback_exists(&feedback_hash) {
+        Ok(true) => {
+            return HttpResponse::Conflict().json(json!({ "error": "This feedback was already submitted" }));
+        }
+        Ok(false) => {}
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({ "error": format!("Database error: {}", e) }));
+        }
+    }
+
+    let client_address = submission.client_address.to_lowercase();
+    let row = NewReputationFeedback {
+        direction: FEEDBACK_RECEIVED,
+        agent_id: identity.agent_id,
+        agent_registry: &identity.agent_registry,
+        client_address: &client_address,
+        value: submission.value,
+        tag1: non_empty(&submission.tag1),
+        tag2: non_empty(&submission.tag2),
+        endpoint: non_empty(&submission.endpoint),
+        feedback_uri: non_empty(&submission.feedback_uri),
+        feedback_hash: Some(&feedback_hash),
+        comment: non_empty(&submission.comment),
+        proof_of_payment_tx: non_empty(&submission.proof_of_payment_tx),
+        tx_hash: None,
+    };
+    if let Err(e) = data.db.insert_reputation_feedback(&row) {
+        return HttpResponse::InternalServerError().json(json!({ "error": format!("Failed to store feedback: {}", e) }));
+    }
+
+    log::info!(
+        "[EIP8004] Accepted feedback {} from {} (value {})",
+        feedback_hash, client_address, submission.value
+    );
+
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "feedbackHash": feedback_hash,
+        "reputation": feedback::score(&data.db, &identity),
+    }))
+}
+
+async fn list_received(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ListQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let agent_id = data.db.get_agent_identity_full().map(|i| i.agent_id);
+    let limit = query.limit.unwrap_or(100).min(500);
+    match data.db.list_reputation_feedback(Some(FEEDBACK_RECEIVED), agent_id, limit) {
+        Ok(feedback) => HttpResponse::Ok().json(json!({ "feedback": feedback })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": format!("Database error: {}", e) })),
+    }
+}
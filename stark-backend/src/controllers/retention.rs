@@ -0,0 +1,253 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::db::tables::retention::validate_retention_scope;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct SetPolicyBody {
+    scope: String,
+    retention_days: i64,
+    #[serde(default = "default_true")]
+    enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct TrashQuery {
+    record_type: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ForgetBody {
+    identity_id: String,
+    #[serde(default)]
+    confirm: bool,
+    requested_by: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AuditQuery {
+    limit: Option<usize>,
+}
+
+/// GET /api/retention/policies
+async fn list_policies(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_retention_policies() {
+        Ok(policies) => HttpResponse::Ok().json(serde_json::json!({
+            "policies": policies,
+            "trash_grace_days": data.db.trash_grace_days(),
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// PUT /api/retention/policies - Create or replace the policy for a scope
+async fn set_policy(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<SetPolicyBody>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let scope = body.scope.trim();
+    if let Err(e) = validate_retention_scope(scope) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+    }
+    if body.retention_days < 0 {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "retention_days must be zero or positive"
+        }));
+    }
+    match data.db.set_retention_policy(scope, body.retention_days, body.enabled) {
+        Ok(policy) => HttpResponse::Ok().json(policy),
+        Err(e) => {
+            log::error!("Failed to save retention policy: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// DELETE /api/retention/policies/{scope}
+async fn delete_policy(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let scope = path.into_inner();
+    match data.db.delete_retention_policy(&scope) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("No retention policy for scope '{}'", scope)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// POST /api/retention/run - Apply all retention policies now
+async fn run_now(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match crate::retention::run_retention_pass(&data.db) {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e })),
+    }
+}
+
+/// GET /api/retention/trash?record_type=&limit=
+async fn list_trash(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<TrashQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match data.db.list_deleted_records(query.record_type.as_deref(), limit) {
+        Ok(records) => HttpResponse::Ok().json(records),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// POST /api/retention/trash/{id}/restore
+async fn restore_trash(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.restore_deleted_record(id) {
+        Ok(Some(record_type)) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "record_type": record_type,
+        })),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Trash record {} not found", id)
+        })),
+        Err(e) => {
+            log::error!("Failed to restore trash record {}: {}", id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Restore failed: {}", e)
+            }))
+        }
+    }
+}
+
+/// DELETE /api/retention/trash/{id} - Purge a trashed record immediately
+async fn purge_trash(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.purge_deleted_record(id) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Trash record {} not found", id)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// POST /api/retention/forget - Erase all data tied to an identity
+async fn forget_identity(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<ForgetBody>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let identity_id = body.identity_id.trim();
+    if identity_id.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "identity_id is required"
+        }));
+    }
+    if !body.confirm {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Must set confirm: true to forget an identity"
+        }));
+    }
+
+    match data.db.forget_identity(identity_id, body.requested_by.as_deref()) {
+        Ok(summary) => {
+            log::info!(
+                "[RETENTION] Forgot identity {}: {} memories, {} sessions, {} messages",
+                identity_id,
+                summary.memories_deleted,
+                summary.sessions_deleted,
+                summary.messages_deleted
+            );
+            HttpResponse::Ok().json(summary)
+        }
+        Err(e) => {
+            log::error!("Failed to forget identity {}: {}", identity_id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// GET /api/retention/forget/audit
+async fn list_forget_audit(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<AuditQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_forget_audit(query.limit.unwrap_or(50).min(500)) {
+        Ok(entries) => HttpResponse::Ok().json(entries),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/retention")
+            .route("/policies", web::get().to(list_policies))
+            .route("/policies", web::put().to(set_policy))
+            .route("/policies/{scope}", web::delete().to(delete_policy))
+            .route("/run", web::post().to(run_now))
+            .route("/trash", web::get().to(list_trash))
+            .route("/trash/{id}", web::delete().to(purge_trash))
+            .route("/trash/{id}/restore", web::post().to(restore_trash))
+            .route("/forget", web::post().to(forget_identity))
+            .route("/forget/audit", web::get().to(list_forget_audit)),
+    );
+}
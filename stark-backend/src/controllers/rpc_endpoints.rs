@@ -0,0 +1,143 @@
+//! RPC endpoint API — the per-network RPC pool with each endpoint's health,
+//! and user-added endpoints (public mirrors, paid plans, private nodes).
+//! New endpoints are probed before they are saved so a typo or an endpoint
+//! for the wrong chain never joins the pool.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate_session;
+use crate::tools::rpc_pool;
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/rpc-endpoints")
+            .route("", web::get().to(list_endpoints))
+            .route("", web::post().to(add_endpoint))
+            .route("/check", web::post().to(check_endpoints))
+            .route("/{id}", web::put().to(update_endpoint))
+            .route("/{id}", web::delete().to(delete_endpoint)),
+    );
+}
+
+#[derive(Deserialize)]
+struct AddEndpointRequest {
+    network: String,
+    url: String,
+    #[serde(default)]
+    label: String,
+    /// Lower is preferred; Alchemy is 10, the public RPC 20, DeFi Relay 30
+    #[serde(default)]
+    priority: i64,
+}
+
+#[derive(Deserialize)]
+struct UpdateEndpointRequest {
+    label: Option<String>,
+    priority: Option<i64>,
+    enabled: Option<bool>,
+}
+
+/// GET /api/rpc-endpoints - Every network's pool, in selection order, with health
+async fn list_endpoints(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    HttpResponse::Ok().json(json!({ "endpoints": rpc_pool::status() }))
+}
+
+/// POST /api/rpc-endpoints - Add an endpoint after checking it answers for the right chain
+async fn add_endpoint(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<AddEndpointRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let network = body.network.trim().to_lowercase();
+    let url = body.url.trim();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return HttpResponse::BadRequest().json(json!({ "error": "url must start with http:// or https://" }));
+    }
+    let Some(expected) = rpc_pool::chain_id(&network) else {
+        return HttpResponse::BadRequest().json(json!({ "error": format!("Unsupported network '{}'", network) }));
+    };
+    match rpc_pool::probe(url).await {
+        Ok((chain_id, latency)) if chain_id == expected => rpc_pool::record_success(url, latency),
+        Ok((chain_id, _)) => {
+            return HttpResponse::BadRequest().json(json!({
+                "error": format!("Endpoint reports chain id {}, but {} is {}", chain_id, network, expected)
+            }))
+        }
+        Err(e) => {
+            return HttpResponse::BadRequest().json(json!({ "error": format!("Endpoint did not answer: {}", e) }))
+        }
+    }
+
+    let label = if body.label.trim().is_empty() { rpc_pool::mask_url(url) } else { body.label.trim().to_string() };
+    match data.db.add_rpc_endpoint(&network, url, &label, body.priority) {
+        Ok(endpoint) => {
+            rpc_pool::load(&data.db);
+            log::info!("RPC endpoint added for {}: {} ({})", network, rpc_pool::mask_url(url), label);
+            HttpResponse::Ok().json(json!({ "endpoint": endpoint }))
+        }
+        Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == rusqlite::ErrorCode::ConstraintViolation => {
+            HttpResponse::Conflict().json(json!({ "error": "That endpoint is already in the pool" }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// PUT /api/rpc-endpoints/{id} - Change label, priority or enabled
+async fn update_endpoint(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<UpdateEndpointRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let label = body.label.as_deref().map(str::trim);
+    match data.db.update_rpc_endpoint(path.into_inner(), label, body.priority, body.enabled) {
+        Ok(Some(endpoint)) => {
+            rpc_pool::load(&data.db);
+            HttpResponse::Ok().json(json!({ "endpoint": endpoint }))
+        }
+        Ok(None) => HttpResponse::NotFound().json(json!({ "error": "Endpoint not found" })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// DELETE /api/rpc-endpoints/{id}
+async fn delete_endpoint(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.delete_rpc_endpoint(path.into_inner()) {
+        Ok(true) => {
+            rpc_pool::load(&data.db);
+            HttpResponse::Ok().json(json!({ "deleted": true }))
+        }
+        Ok(false) => HttpResponse::NotFound().json(json!({ "error": "Endpoint not found" })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// POST /api/rpc-endpoints/check - Probe every free endpoint now
+async fn check_endpoints(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let healthy = rpc_pool::run_health_checks().await;
+    HttpResponse::Ok().json(json!({ "healthy": healthy, "endpoints": rpc_pool::status() }))
+}
@@ -0,0 +1,204 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::alerts::types::{validate_rule, CreateAlertRuleRequest, UpdateAlertRuleRequest};
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct EventsQuery {
+    rule_id: Option<i64>,
+    limit: Option<usize>,
+}
+
+/// List all alert rules
+async fn list_rules(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_alert_rules() {
+        Ok(rules) => HttpResponse::Ok().json(rules),
+        Err(e) => {
+            log::error!("Failed to list alert rules: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// Create a new alert rule
+async fn create_rule(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CreateAlertRuleRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    if let Err(e) = validate_rule(&body.name, &body.condition, &body.actions) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+    }
+    match data.db.create_alert_rule(&body) {
+        Ok(rule) => HttpResponse::Created().json(rule),
+        Err(e) => {
+            log::error!("Failed to create alert rule: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get a single alert rule
+async fn get_rule(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.get_alert_rule(id) {
+        Ok(Some(rule)) => HttpResponse::Ok().json(rule),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Alert rule {} not found", id)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// Update an alert rule
+async fn update_rule(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<UpdateAlertRuleRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+
+    // Validate the merged result before persisting
+    let existing = match data.db.get_alert_rule(id) {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Alert rule {} not found", id)
+            }))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+    let name = body.name.as_deref().unwrap_or(&existing.name);
+    let condition = body.condition.as_ref().unwrap_or(&existing.condition);
+    let actions = body.actions.as_ref().unwrap_or(&existing.actions);
+    if let Err(e) = validate_rule(name, condition, actions) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+    }
+
+    match data.db.update_alert_rule(id, &body) {
+        Ok(Some(rule)) => HttpResponse::Ok().json(rule),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Alert rule {} not found", id)
+        })),
+        Err(e) => {
+            log::error!("Failed to update alert rule: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// Delete an alert rule
+async fn delete_rule(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.delete_alert_rule(id) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Alert rule {} not found", id)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// Evaluate a rule's condition right now without firing its actions
+async fn test_rule(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    let rule = match data.db.get_alert_rule(id) {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Alert rule {} not found", id)
+            }))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+    match data.alert_engine.observe(&rule.condition).await {
+        Ok(obs) => HttpResponse::Ok().json(serde_json::json!({
+            "triggered": obs.triggered,
+            "value": obs.value,
+            "message": obs.message,
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e })),
+    }
+}
+
+/// List recently fired alerts (?rule_id=&limit=)
+async fn list_events(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<EventsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match data.db.list_alert_events(query.rule_id, limit) {
+        Ok(events) => HttpResponse::Ok().json(events),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/rules")
+            .route("", web::get().to(list_rules))
+            .route("", web::post().to(create_rule))
+            .route("/events", web::get().to(list_events))
+            .route("/{id}", web::get().to(get_rule))
+            .route("/{id}", web::put().to(update_rule))
+            .route("/{id}", web::delete().to(delete_rule))
+            .route("/{id}/test", web::post().to(test_rule)),
+    );
+}
@@ -0,0 +1,102 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use super::validation::{Valid, Validate, Validator};
+use crate::config_watch::ConfigChange;
+use crate::AppState;
+
+const SAFE_NETWORKS: &[&str] = &["base", "mainnet", "polygon"];
+
+#[derive(Deserialize)]
+struct ProposalsQuery {
+    status: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct SafeConfigRequest {
+    /// Treasury Safe address; null clears it
+    safe_address: Option<String>,
+    network: Option<String>,
+}
+
+impl Validate for SafeConfigRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(address) = self.safe_address.as_deref().filter(|a| !a.trim().is_empty()) {
+            v.address("safe_address", address);
+        }
+        if let Some(network) = self.network.as_deref().filter(|n| !n.trim().is_empty()) {
+            v.one_of("network", network, SAFE_NETWORKS);
+        }
+    }
+}
+
+/// GET /api/safe/proposals?status=&limit= - Transactions proposed to Safes, newest first
+async fn list_proposals(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ProposalsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match data.db.list_safe_proposals(query.status.as_deref(), limit) {
+        Ok(proposals) => HttpResponse::Ok().json(proposals),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// GET /api/safe/config - Treasury Safe used by propose_safe_tx
+async fn get_config(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.get_bot_settings() {
+        Ok(settings) => HttpResponse::Ok().json(serde_json::json!({
+            "safe_address": settings.safe_address,
+            "network": settings.safe_network,
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// PUT /api/safe/config - Set or clear the treasury Safe
+async fn update_config(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: Valid<SafeConfigRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let safe_address = body.safe_address.as_deref().map(str::trim).filter(|a| !a.is_empty());
+    let network = body.network.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(str::to_lowercase);
+    match data.db.update_safe_config(safe_address, safe_address.and(network.as_deref())) {
+        Ok(settings) => {
+            log::info!("Updated treasury Safe: {:?} on {:?}", settings.safe_address, settings.safe_network);
+            data.config_watch.notify(ConfigChange::BotSettings);
+            HttpResponse::Ok().json(serde_json::json!({
+                "safe_address": settings.safe_address,
+                "network": settings.safe_network,
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/safe")
+            .route("/proposals", web::get().to(list_proposals))
+            .route("/config", web::get().to(get_config))
+            .route("/config", web::put().to(update_config)),
+    );
+}
@@ -1,10 +1,18 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::models::{
-    ChatSessionResponse, CompletionStatus, GetOrCreateSessionRequest, SessionScope,
+    ChatSessionResponse, CompletionStatus, GetOrCreateSessionRequest, SessionMessage, SessionScope,
     SessionTranscriptResponse, UpdateResetPolicyRequest,
 };
+use crate::ai::AiClient;
+use crate::channels::response_style::ResponseStyle;
+use crate::context;
+use crate::controllers::openapi::{
+    any_object, array, boolean, date_time, integer, nullable, object, reference, string, string_enum, ApiDoc,
+};
+use crate::controllers::pagination::{Page, PageQuery};
+use crate::db::tables::chat_sessions::SessionListFilter;
 use crate::AppState;
 
 /// Validate session token from request
@@ -16,7 +24,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -27,7 +36,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "error": "Invalid or expired session"
@@ -41,42 +50,62 @@ fn validate_session_from_request(
     }
 }
 
-/// List all chat sessions
+#[derive(Deserialize)]
+struct SessionFilterQuery {
+    channel_type: Option<String>,
+    channel_id: Option<i64>,
+}
+
+/// Sort fields for GET /api/sessions
+const SESSION_SORTS: &[&str] = &["last_activity_at", "created_at", "updated_at", "id"];
+
+/// GET /api/sessions?limit=&offset=&cursor=&sort=&order=&q=&channel_type=&channel_id=
+/// - Chat sessions, most recently active first
 async fn list_sessions(
     data: web::Data<AppState>,
     req: HttpRequest,
+    page: web::Query<PageQuery>,
+    filter: web::Query<SessionFilterQuery>,
 ) -> impl Responder {
     if let Err(resp) = validate_session_from_request(&data, &req) {
         return resp;
     }
 
-    match data.db.list_chat_sessions() {
-        Ok(sessions) => {
-            let responses: Vec<ChatSessionResponse> = sessions
-                .into_iter()
-                .map(|s| {
-                    let is_web = s.channel_type == "web";
-                    let session_id = s.id;
-                    let mut response: ChatSessionResponse = s.into();
-                    if let Ok(count) = data.db.count_session_messages(session_id) {
-                        response.message_count = Some(count);
-                    }
-                    // For web sessions, get the initial query (first user message)
-                    if is_web {
-                        if let Ok(Some(first_msg)) = data.db.get_first_user_message(session_id) {
-                            // Truncate to 100 chars for the list view
-                            response.initial_query = Some(if first_msg.len() > 100 {
-                                format!("{}...", &first_msg[..100])
-                            } else {
-                                first_msg
-                            });
-                        }
+    let filter = SessionListFilter {
+        channel_type: filter.channel_type.clone(),
+        channel_id: filter.channel_id,
+        text: page.text().map(str::to_string),
+    };
+    let offset = page.offset();
+    match data.db.list_chat_sessions_page(
+        &filter,
+        page.sort_by(SESSION_SORTS, "last_activity_at"),
+        page.descending(true),
+        page.limit(500, 500),
+        offset,
+    ) {
+        Ok((sessions, total)) => Page::new(sessions, total, offset)
+            .map(|s| {
+                let is_web = s.channel_type == "web";
+                let session_id = s.id;
+                let mut response: ChatSessionResponse = s.into();
+                if let Ok(count) = data.db.count_session_messages(session_id) {
+                    response.message_count = Some(count);
+                }
+                // For web sessions, get the initial query (first user message)
+                if is_web {
+                    if let Ok(Some(first_msg)) = data.db.get_first_user_message(session_id) {
+                        // Truncate to 100 chars for the list view
+                        response.initial_query = Some(if first_msg.len() > 100 {
+                            format!("{}...", &first_msg[..100])
+                        } else {
+                            first_msg
+                        });
                     }
-                    response
-                })
-                .collect();
-            HttpResponse::Ok().json(responses)
-        }
+                }
+                response
+            })
+            .respond(),
         Err(e) => {
             log::error!("Failed to list sessions: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -183,6 +212,113 @@ async fn reset_session(
     }
 }
 
+/// Messages summarized when the request doesn't say
+const DEFAULT_SUMMARIZE_MESSAGES: i32 = 50;
+const MAX_SUMMARIZE_MESSAGES: i32 = 500;
+
+#[derive(Debug, Default, Deserialize)]
+struct SummarizeSessionRequest {
+    /// Also save the summary to the daily log as a memory
+    #[serde(default)]
+    store: bool,
+    /// How many recent messages to summarize
+    #[serde(default)]
+    message_limit: Option<i32>,
+    /// Identity the stored memory belongs to
+    #[serde(default)]
+    identity_id: Option<String>,
+}
+
+/// Summarize a session without resetting it (checkpoint a long working session)
+async fn summarize_session(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: Option<web::Json<SummarizeSessionRequest>>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    let session_id = path.into_inner();
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+
+    match data.db.get_chat_session(session_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Session not found"
+            }))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+
+    let client = match data.db.get_active_agent_settings() {
+        Ok(Some(settings)) => match AiClient::from_settings(&settings) {
+            Ok(client) => client,
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Failed to create AI client: {}", e)
+                }))
+            }
+        },
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "No AI endpoint is configured"
+            }))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+
+    let message_limit = body
+        .message_limit
+        .unwrap_or(DEFAULT_SUMMARIZE_MESSAGES)
+        .clamp(1, MAX_SUMMARIZE_MESSAGES);
+    let (title, summary) = match context::summarize_session(&data.db, &client, session_id, message_limit).await {
+        Ok(result) => result,
+        Err(e) => {
+            log::warn!("Failed to summarize session {}: {}", session_id, e);
+            return HttpResponse::UnprocessableEntity().json(serde_json::json!({ "error": e }));
+        }
+    };
+
+    let memory_id = if body.store {
+        let agent_subtype = data.db.get_agent_context(session_id).ok().flatten().and_then(|c| c.subtype);
+        match context::store_session_summary(
+            &data.db,
+            &title,
+            &summary,
+            body.identity_id.as_deref(),
+            session_id,
+            "session_checkpoint",
+            agent_subtype.as_deref(),
+        ) {
+            Ok(id) => Some(id),
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(serde_json::json!({ "error": e }));
+            }
+        }
+    } else {
+        None
+    };
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "session_id": session_id,
+        "title": title,
+        "summary": summary,
+        "message_limit": message_limit,
+        "memory_id": memory_id,
+    }))
+}
+
 /// Update session reset policy
 async fn update_reset_policy(
     data: web::Data<AppState>,
@@ -217,6 +353,58 @@ async fn update_reset_policy(
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct UpdateResponseStyleRequest {
+    /// concise, verbose or technical; null (or "default") clears it
+    style: Option<String>,
+}
+
+/// Set or clear a session's response style preset
+async fn update_response_style(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<UpdateResponseStyleRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    let session_id = path.into_inner();
+
+    let style = match body.style.as_deref().map(str::trim) {
+        None | Some("") | Some("default") => None,
+        Some(s) => match ResponseStyle::from_str(s) {
+            Some(style) => Some(style),
+            None => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Unknown style '{}'. Use concise, verbose, technical or null.", s)
+                }))
+            }
+        },
+    };
+
+    match data.db.set_session_response_style(session_id, style.map(|s| s.as_str())) {
+        Ok(true) => match data.db.get_chat_session(session_id) {
+            Ok(Some(session)) => {
+                let response: ChatSessionResponse = session.into();
+                HttpResponse::Ok().json(response)
+            }
+            _ => HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Session not found"
+            })),
+        },
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Session not found"
+        })),
+        Err(e) => {
+            log::error!("Failed to update session response style: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
 /// Delete all sessions and cancel any running agentic loops
 async fn delete_all_sessions(
     data: web::Data<AppState>,
@@ -322,14 +510,15 @@ async fn delete_session(
     // Evict from active cache before deleting
     data.active_cache.force_evict(session_id);
 
-    // Now delete the session
-    match data.db.delete_chat_session(session_id) {
-        Ok(true) => HttpResponse::Ok().json(serde_json::json!({
+    // Now move the session to the trash (restorable until the grace period expires)
+    match data.db.soft_delete_chat_session(session_id) {
+        Ok(Some(trash_id)) => HttpResponse::Ok().json(serde_json::json!({
             "success": true,
             "message": "Session deleted",
-            "cancelled_agents": cancelled_agents
+            "cancelled_agents": cancelled_agents,
+            "trash_id": trash_id
         })),
-        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
             "error": "Session not found"
         })),
         Err(e) => {
@@ -526,6 +715,107 @@ async fn get_transcript(
     }
 }
 
+/// GET /api/sessions/{id}/tool-activity - Tool calls and results of the current
+/// turn and the call still running, for clients reconnecting mid-execution
+async fn get_tool_activity(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    let session_id = path.into_inner();
+
+    let activity = data.db.get_current_turn_tool_messages(session_id).and_then(|messages| {
+        let status = data.db.get_session_completion_status(session_id)?;
+        // A stopped or finished run has nothing running, whatever was last written
+        let running = match status {
+            None | Some(CompletionStatus::Active) => data.db.get_running_tool_call(session_id)?,
+            Some(_) => None,
+        };
+        Ok((messages, status, running))
+    });
+
+    match activity {
+        Ok((messages, status, running)) => HttpResponse::Ok().json(serde_json::json!({
+            "session_id": session_id,
+            "completion_status": status.map(|s| s.as_str()),
+            "running": running,
+            "messages": messages,
+        })),
+        Err(e) => {
+            log::error!("Failed to get tool activity: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// Full session export: metadata plus every message with its attachment references
+#[derive(Serialize)]
+struct SessionExport {
+    version: u32,
+    exported_at: String,
+    session: ChatSessionResponse,
+    messages: Vec<SessionMessage>,
+}
+
+/// GET /api/sessions/{id}/export - Download a session as JSON
+async fn export_session(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    if let Err(resp) = super::require_second_factor(&data, &req) {
+        return resp;
+    }
+    let session_id = path.into_inner();
+
+    let session = match data.db.get_chat_session(session_id) {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Session not found"
+            }));
+        }
+        Err(e) => {
+            log::error!("Failed to get session: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }));
+        }
+    };
+
+    match data.db.get_session_messages(session_id) {
+        Ok(messages) => {
+            let mut session: ChatSessionResponse = session.into();
+            session.message_count = Some(messages.len() as i64);
+            HttpResponse::Ok()
+                .insert_header((
+                    "Content-Disposition",
+                    format!("attachment; filename=\"session_{}.json\"", session_id),
+                ))
+                .json(SessionExport {
+                    version: 1,
+                    exported_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                    session,
+                    messages,
+                })
+        }
+        Err(e) => {
+            log::error!("Failed to export session {}: {}", session_id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/sessions")
@@ -537,7 +827,96 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/{id}/reset", web::post().to(reset_session))
             .route("/{id}/stop", web::post().to(stop_session))
             .route("/{id}/resume", web::post().to(resume_session))
+            .route("/{id}/summarize", web::post().to(summarize_session))
             .route("/{id}/policy", web::put().to(update_reset_policy))
-            .route("/{id}/transcript", web::get().to(get_transcript)),
+            .route("/{id}/style", web::put().to(update_response_style))
+            .route("/{id}/transcript", web::get().to(get_transcript))
+            .route("/{id}/tool-activity", web::get().to(get_tool_activity))
+            .route("/{id}/export", web::get().to(export_session)),
     );
 }
+
+/// OpenAPI description of the session routes
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.schema(
+        "ChatSession",
+        object(
+            &[
+                ("id", integer()),
+                ("session_key", string()),
+                ("agent_id", nullable(string())),
+                ("scope", string_enum(&["dm", "group", "cron", "webhook", "api"])),
+                ("channel_type", string()),
+                ("channel_id", integer()),
+                ("platform_chat_id", string()),
+                ("is_active", boolean()),
+                ("reset_policy", string_enum(&["daily", "idle", "manual", "never"])),
+                ("idle_timeout_minutes", nullable(integer())),
+                ("daily_reset_hour", nullable(integer())),
+                ("created_at", date_time()),
+                ("updated_at", date_time()),
+                ("last_activity_at", date_time()),
+                ("message_count", nullable(integer())),
+                ("context_tokens", integer()),
+                ("max_context_tokens", integer()),
+                ("compaction_id", nullable(integer())),
+                ("completion_status", string()),
+                ("initial_query", string()),
+                ("safe_mode", boolean()),
+                ("special_role_name", string()),
+                ("response_style", nullable(string_enum(&["concise", "verbose", "technical"]))),
+            ],
+            &["id", "session_key", "channel_type", "channel_id", "platform_chat_id", "is_active", "created_at"],
+        ),
+    );
+
+    doc.get("/api/sessions", "sessions", "List chat sessions, most recently active first")
+        .paged(SESSION_SORTS)
+        .query("channel_type", string(), "Only sessions of this channel type")
+        .query("channel_id", integer(), "Only sessions of this channel")
+        .returns(array(reference("ChatSession")));
+    doc.post("/api/sessions", "sessions", "Get or create the session for a channel chat")
+        .body(object(
+            &[
+                ("channel_type", string()),
+                ("channel_id", integer()),
+                ("platform_chat_id", string()),
+                ("scope", string_enum(&["dm", "group", "cron", "webhook", "api"])),
+                ("agent_id", string()),
+            ],
+            &["channel_type", "channel_id", "platform_chat_id"],
+        ))
+        .returns(reference("ChatSession"));
+    doc.delete("/api/sessions", "sessions", "Delete all sessions").returns(any_object());
+    doc.get("/api/sessions/{id}", "sessions", "Get a session").returns(reference("ChatSession"));
+    doc.delete("/api/sessions/{id}", "sessions", "Delete a session").returns(any_object());
+    doc.post("/api/sessions/{id}/reset", "sessions", "Reset a session (clears its context)").returns(reference("ChatSession"));
+    doc.post("/api/sessions/{id}/stop", "sessions", "Stop the session's running execution").returns(any_object());
+    doc.post("/api/sessions/{id}/resume", "sessions", "Resume the session's paused execution").returns(any_object());
+    doc.post("/api/sessions/{id}/summarize", "sessions", "Summarize the session").returns(any_object());
+    doc.put("/api/sessions/{id}/policy", "sessions", "Set the session's reset policy")
+        .body(object(
+            &[
+                ("reset_policy", string_enum(&["daily", "idle", "manual", "never"])),
+                ("idle_timeout_minutes", integer()),
+                ("daily_reset_hour", integer()),
+            ],
+            &["reset_policy"],
+        ))
+        .returns(reference("ChatSession"));
+    doc.put("/api/sessions/{id}/style", "sessions", "Set or clear the session's response style")
+        .body(object(&[("style", nullable(string_enum(&["concise", "verbose", "technical", "default"])))], &[]))
+        .returns(any_object());
+    doc.get("/api/sessions/{id}/transcript", "sessions", "The session's messages").returns(any_object());
+    doc.get("/api/sessions/{id}/tool-activity", "sessions", "Tool calls of the current turn and the one still running")
+        .returns(object(
+            &[
+                ("session_id", integer()),
+                ("completion_status", nullable(string())),
+                ("running", nullable(any_object())),
+                ("messages", array(any_object())),
+            ],
+            &["session_id", "messages"],
+        ));
+    doc.get("/api/sessions/{id}/export", "sessions", "Export the session as a file").returns(any_object());
+}
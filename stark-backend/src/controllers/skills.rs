@@ -3,6 +3,9 @@ use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 
+use crate::controllers::openapi::{any_object, array, boolean, envelope, integer, object, reference, string, ApiDoc};
+use crate::controllers::pagination::{Page, PageQuery};
+use crate::skills::permissions::{self, FinanceCapability};
 use crate::skills::{DbSkillScript, Skill};
 use crate::AppState;
 
@@ -29,6 +32,8 @@ pub struct SkillInfo {
     pub homepage: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<String>,
+    /// Languages the skill has localized description/prompt blocks for
+    pub locales: Vec<String>,
 }
 
 impl From<&Skill> for SkillInfo {
@@ -44,6 +49,7 @@ impl From<&Skill> for SkillInfo {
             tags: skill.metadata.tags.clone(),
             homepage: skill.metadata.homepage.clone(),
             metadata: skill.metadata.metadata.clone(),
+            locales: skill.locales(),
         }
     }
 }
@@ -253,6 +259,9 @@ pub struct CreateAssociationRequest {
 struct InstallFromHubRequest {
     username: String,
     slug: String,
+    /// Finance capabilities the user grants on install ("read", "send"); declared ones left out are denied
+    #[serde(default)]
+    grant_permissions: Vec<String>,
 }
 
 /// GET /api/skills/featured_remote — get featured skills from StarkHub
@@ -371,11 +380,13 @@ async fn install_from_hub(
                             }
                         }
 
+                        let permissions = apply_install_grants(&state, &skill_name, &body.grant_permissions);
                         return HttpResponse::Ok().json(serde_json::json!({
                             "success": true,
                             "skill_name": skill_name,
                             "already_existed": already_exists,
                             "files": downloaded_files,
+                            "permissions": permissions,
                             "message": format!("{} skill '{}' from @{}/{}",
                                 if already_exists { "Updated" } else { "Installed" },
                                 skill_name, body.username, body.slug),
@@ -453,11 +464,13 @@ async fn install_from_hub(
         }
     }
 
+    let permissions = apply_install_grants(&state, &skill_name, &body.grant_permissions);
     HttpResponse::Ok().json(serde_json::json!({
         "success": true,
         "skill_name": skill_name,
         "already_existed": already_exists,
         "files": downloaded_files,
+        "permissions": permissions,
         "message": format!("{} skill '{}' from @{}/{}",
             if already_exists { "Updated" } else { "Installed" },
             skill_name, body.username, body.slug),
@@ -606,10 +619,15 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/featured_remote", web::get().to(featured_remote))
             .route("/install_from_hub", web::post().to(install_from_hub))
             .route("/publish/{name}", web::post().to(publish_to_hub))
+            .route("/runs", web::get().to(list_skill_runs))
+            .route("/runs/{id}", web::get().to(get_skill_run))
             .route("/{name}", web::get().to(get_skill))
             .route("/{name}", web::put().to(update_skill))
             .route("/{name}", web::delete().to(delete_skill))
             .route("/{name}/enabled", web::put().to(set_enabled))
+            .route("/{name}/permissions", web::get().to(get_permissions))
+            .route("/{name}/render", web::get().to(render_skill))
+            .route("/{name}/permissions", web::put().to(set_permissions))
             .route("/{name}/scripts", web::get().to(get_skill_scripts)),
     );
 }
@@ -622,7 +640,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -636,7 +655,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(OperationResponse {
             success: false,
@@ -656,19 +675,50 @@ fn validate_session_from_request(
     }
 }
 
-async fn list_skills(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+#[derive(Deserialize)]
+struct SkillFilterQuery {
+    enabled: Option<bool>,
+    source: Option<String>,
+    tag: Option<String>,
+}
+
+/// Sort fields for GET /api/skills
+const SKILL_SORTS: &[&str] = &["name", "version", "source"];
+
+/// GET /api/skills?limit=&offset=&cursor=&sort=&order=&q=&enabled=&source=&tag=
+async fn list_skills(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    page: web::Query<PageQuery>,
+    filter: web::Query<SkillFilterQuery>,
+) -> impl Responder {
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
     }
 
-    let skills: Vec<SkillInfo> = state
+    let mut skills: Vec<SkillInfo> = state
         .skill_registry
         .list()
         .iter()
         .map(|s| s.into())
+        .filter(|s: &SkillInfo| {
+            filter.enabled.is_none_or(|e| s.enabled == e)
+                && filter.source.as_deref().is_none_or(|src| s.source.eq_ignore_ascii_case(src))
+                && filter.tag.as_deref().is_none_or(|t| s.tags.iter().any(|tag| tag.eq_ignore_ascii_case(t)))
+                && page.matches(&[&s.name, &s.description])
+        })
         .collect();
 
-    HttpResponse::Ok().json(skills)
+    match page.sort_by(SKILL_SORTS, "name") {
+        "version" => skills.sort_by(|a, b| a.version.cmp(&b.version)),
+        "source" => skills.sort_by(|a, b| a.source.cmp(&b.source).then_with(|| a.name.cmp(&b.name))),
+        _ => skills.sort_by_key(|s| s.name.to_lowercase()),
+    }
+    if page.descending(false) {
+        skills.reverse();
+    }
+
+    Page::slice(skills, page.offset(), page.limit(1000, 1000)).respond()
 }
 
 async fn get_skill(
@@ -739,6 +789,236 @@ async fn set_enabled(
     })
 }
 
+#[derive(Deserialize)]
+struct SkillRunsQuery {
+    skill: Option<String>,
+    limit: Option<usize>,
+}
+
+/// GET /api/skills/runs — recent top-level invoke_skill runs, newest first
+async fn list_skill_runs(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<SkillRunsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match state.db.list_root_skill_runs(query.skill.as_deref(), limit) {
+        Ok(runs) => HttpResponse::Ok().json(serde_json::json!({ "runs": runs })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": format!("Database error: {}", e) })),
+    }
+}
+
+/// GET /api/skills/runs/{id} — a run with every skill it invoked and their combined totals
+async fn get_skill_run(state: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+    let run = match state.db.get_skill_run(path.into_inner()) {
+        Ok(Some(run)) => run,
+        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({ "error": "Skill run not found" })),
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({ "error": format!("Database error: {}", e) }))
+        }
+    };
+    let root_id = run.root_run_id.unwrap_or(run.id);
+    match state.db.list_skill_run_tree(root_id) {
+        Ok(tree) => HttpResponse::Ok().json(serde_json::json!({
+            "run": run,
+            "root_run_id": root_id,
+            "totals": crate::db::tables::skill_runs::SkillRunTotals::from_tree(&tree),
+            "tree": tree,
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": format!("Database error: {}", e) })),
+    }
+}
+
+#[derive(Deserialize)]
+struct RenderQuery {
+    /// Argument values as a JSON object, e.g. {"path":"./src"}
+    args: Option<String>,
+    /// Subtype whose toolset to build (defaults to the skill's subagent_type)
+    subtype: Option<String>,
+    /// Channel whose tool config applies (defaults to the global config)
+    channel_id: Option<i64>,
+    /// Language to render the localized prompt for (defaults to the base prompt)
+    locale: Option<String>,
+}
+
+/// GET /api/skills/{name}/render — dry-run: the substituted prompt and the tools the skill would get
+async fn render_skill(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<RenderQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+    let name = path.into_inner();
+    let Some(skill) = state.skill_registry.get_localized(&name, query.locale.as_deref()) else {
+        return HttpResponse::NotFound().json(serde_json::json!({ "error": format!("Skill '{}' not found", name) }));
+    };
+
+    let args: std::collections::HashMap<String, String> = match query.args.as_deref().map(str::trim) {
+        None | Some("") => Default::default(),
+        Some(raw) => match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(raw) {
+            Ok(map) => crate::skills::types::args_from_json(&serde_json::Value::Object(map)),
+            Err(e) => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("args must be a JSON object of argument values: {}", e)
+                }))
+            }
+        },
+    };
+
+    // Same substitutions the agent sees: arguments, then {baseDir}
+    let skill_base_dir = format!("{}/{}", crate::config::runtime_skills_dir(), skill.metadata.name);
+    let prompt = skill.render_prompt(&args).replace("{baseDir}", &skill_base_dir);
+    let missing_args = skill.validate_args(&args).err().unwrap_or_default();
+    let unknown_args: Vec<&String> = args.keys().filter(|k| !skill.metadata.arguments.contains_key(*k)).collect();
+    let resolved_args: std::collections::HashMap<&String, Option<String>> = skill
+        .metadata
+        .arguments
+        .iter()
+        .map(|(k, def)| (k, args.get(k).cloned().or_else(|| def.default.clone())))
+        .collect();
+
+    // Toolset as the dispatcher builds it once the skill is active (mode tools aside)
+    let subtype_key = query
+        .subtype
+        .as_deref()
+        .or(skill.metadata.subagent_type.as_deref())
+        .and_then(crate::ai::multi_agent::types::resolve_subtype_key)
+        .unwrap_or_default();
+    let tool_config = state.db.get_effective_tool_config(query.channel_id).unwrap_or_default();
+    let requires_tools = &skill.metadata.requires_tools;
+    let mut tools = state
+        .tool_registry
+        .get_tool_definitions_for_subtype_with_required(&tool_config, &subtype_key, requires_tools);
+    let subtype_has_define_tasks = crate::ai::multi_agent::types::get_subtype_config(&subtype_key)
+        .map(|c| c.additional_tools.iter().any(|t| t == "define_tasks"))
+        .unwrap_or(false);
+    if !requires_tools.iter().any(|t| t == "define_tasks") && !subtype_has_define_tasks {
+        tools.retain(|t| t.name != "define_tasks");
+    }
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+    let unavailable_tools: Vec<&String> = requires_tools.iter().filter(|t| !tools.iter().any(|d| &d.name == *t)).collect();
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "skill": skill.metadata.name,
+        "prompt": prompt,
+        "args": resolved_args,
+        "missing_args": missing_args,
+        "unknown_args": unknown_args,
+        "unresolved_placeholders": Skill::unresolved_placeholders(&prompt),
+        "subtype": if subtype_key.is_empty() { None } else { Some(subtype_key) },
+        "tools": tools
+            .iter()
+            .map(|t| serde_json::json!({
+                "name": t.name,
+                "group": t.group,
+                "required_by_skill": requires_tools.contains(&t.name),
+            }))
+            .collect::<Vec<_>>(),
+        "unavailable_tools": unavailable_tools,
+    }))
+}
+
+/// Declared finance capabilities of a skill and whether each is granted
+fn permissions_view(state: &AppState, skill: &crate::skills::DbSkill) -> serde_json::Value {
+    let declared = permissions::declared_capabilities(skill, |tool| {
+        state.tool_registry.get(tool).map(|t| t.group())
+    });
+    let capabilities: Vec<serde_json::Value> = FinanceCapability::ALL
+        .into_iter()
+        .map(|cap| {
+            serde_json::json!({
+                "capability": cap,
+                "declared": declared.contains(&cap),
+                "granted": permissions::is_granted(&state.db, skill, cap),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "skill": skill.name,
+        "bundled": permissions::is_bundled(skill),
+        "capabilities": capabilities,
+    })
+}
+
+/// Record the user's install-time decision for every capability the skill declares
+fn apply_install_grants(state: &AppState, skill_name: &str, grants: &[String]) -> Option<serde_json::Value> {
+    let skill = state.db.get_skill(skill_name).ok().flatten()?;
+    let granted: Vec<FinanceCapability> = grants.iter().filter_map(|g| FinanceCapability::from_str(g)).collect();
+    let declared = permissions::declared_capabilities(&skill, |tool| {
+        state.tool_registry.get(tool).map(|t| t.group())
+    });
+    for cap in declared {
+        let allow = granted.contains(&cap);
+        if let Err(e) = state.db.set_skill_permission(skill_name, cap.as_str(), allow) {
+            log::warn!("[SKILLS] Failed to record '{}' permission for '{}': {}", cap.as_str(), skill_name, e);
+        }
+    }
+    Some(permissions_view(state, &skill))
+}
+
+/// GET /api/skills/{name}/permissions — declared finance capabilities and grants
+async fn get_permissions(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+    match state.db.get_skill(&path.into_inner()) {
+        Ok(Some(skill)) => HttpResponse::Ok().json(permissions_view(&state, &skill)),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({ "error": "Skill not found" })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// PUT /api/skills/{name}/permissions — grant or revoke capabilities, e.g. {"send": false}
+async fn set_permissions(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<std::collections::HashMap<String, bool>>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+    let name = path.into_inner();
+    let skill = match state.db.get_skill(&name) {
+        Ok(Some(skill)) => skill,
+        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({ "error": "Skill not found" })),
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+    for (key, granted) in body.iter() {
+        let Some(cap) = FinanceCapability::from_str(key) else {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Unknown capability '{}' (expected read or send)", key)
+            }));
+        };
+        if let Err(e) = state.db.set_skill_permission(&name, cap.as_str(), *granted) {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }));
+        }
+        log::info!("[SKILLS] '{}' permission for skill '{}' {}", cap.as_str(), name, if *granted { "granted" } else { "revoked" });
+    }
+    HttpResponse::Ok().json(permissions_view(&state, &skill))
+}
+
 async fn reload_skills(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
@@ -960,6 +1240,7 @@ async fn update_skill(
         requires_api_keys: existing.metadata.requires_api_keys.clone(),
         created_at: now.clone(),
         updated_at: now,
+        finance_permissions: existing.metadata.finance_permissions.clone(),
     };
 
     // Write updated SKILL.md to disk
@@ -1014,6 +1295,7 @@ async fn update_skill(
             tags: db_skill.tags.clone(),
             subagent_type: db_skill.subagent_type.clone(),
             requires_api_keys: db_skill.requires_api_keys.clone(),
+            finance_permissions: db_skill.finance_permissions.clone(),
             scripts: Vec::new(),
             abis: Vec::new(),
             presets_content: None,
@@ -1520,6 +1802,9 @@ fn reconstruct_skill_md(parsed: &crate::skills::zip_parser::ParsedSkill) -> Stri
     if !parsed.tags.is_empty() {
         fm.push_str(&format!("tags: [{}]\n", parsed.tags.join(", ")));
     }
+    if !parsed.finance_permissions.is_empty() {
+        fm.push_str(&format!("finance_permissions: [{}]\n", parsed.finance_permissions.join(", ")));
+    }
     if let Some(ref subagent_type) = parsed.subagent_type {
         fm.push_str(&format!("subagent_type: {}\n", subagent_type));
     }
@@ -1541,3 +1826,77 @@ fn reconstruct_skill_md(parsed: &crate::skills::zip_parser::ParsedSkill) -> Stri
 
     format!("---\n{}\n---\n{}", fm.trim(), parsed.body)
 }
+
+/// OpenAPI description of the skill routes
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.schema(
+        "Skill",
+        object(
+            &[
+                ("name", string()),
+                ("description", string()),
+                ("version", string()),
+                ("source", string()),
+                ("enabled", boolean()),
+                ("requires_tools", array(string())),
+                ("requires_binaries", array(string())),
+                ("tags", array(string())),
+                ("homepage", string()),
+                ("metadata", string()),
+                ("locales", array(string())),
+            ],
+            &["name", "description", "version", "source", "enabled"],
+        ),
+    );
+    doc.schema("SkillOperation", envelope(&[("message", string()), ("count", integer())]));
+
+    doc.get("/api/skills", "skills", "List installed skills")
+        .paged(SKILL_SORTS)
+        .query("enabled", boolean(), "Only enabled (true) or disabled (false) skills")
+        .query("source", string(), "Only skills from this source")
+        .query("tag", string(), "Only skills with this tag")
+        .returns(array(reference("Skill")));
+    doc.get("/api/skills/{name}", "skills", "Get a skill with its body and scripts")
+        .returns(envelope(&[("skill", any_object())]));
+    doc.put("/api/skills/{name}", "skills", "Replace a skill's SKILL.md body")
+        .body(object(&[("body", string())], &["body"]))
+        .returns(reference("SkillOperation"));
+    doc.delete("/api/skills/{name}", "skills", "Delete a skill").returns(reference("SkillOperation"));
+    doc.put("/api/skills/{name}/enabled", "skills", "Enable or disable a skill")
+        .body(object(&[("enabled", boolean())], &["enabled"]))
+        .returns(reference("SkillOperation"));
+    doc.get("/api/skills/{name}/scripts", "skills", "A skill's scripts").returns(any_object());
+    doc.get("/api/skills/runs", "skills", "Recent top-level invoke_skill runs")
+        .query("skill", string(), "Only runs of this skill")
+        .query("limit", integer(), "Maximum runs to return (default 50)")
+        .returns(object(&[("runs", array(any_object()))], &["runs"]));
+    doc.get("/api/skills/runs/{id}", "skills", "A skill run with the runs it invoked and combined totals")
+        .returns(object(
+            &[("run", any_object()), ("root_run_id", integer()), ("totals", any_object()), ("tree", array(any_object()))],
+            &["run", "tree"],
+        ));
+    doc.get("/api/skills/{name}/render", "skills", "Dry-run a skill: substituted prompt and the toolset it would get")
+        .query("args", string(), "Argument values as a JSON object")
+        .query("subtype", string(), "Subtype to build the toolset for (defaults to the skill's own)")
+        .query("channel_id", integer(), "Channel whose tool config applies")
+        .query("locale", string(), "Language to render the localized prompt for")
+        .returns(any_object());
+    doc.get("/api/skills/{name}/permissions", "skills", "Declared finance capabilities (read, send) and grants")
+        .returns(any_object());
+    doc.put("/api/skills/{name}/permissions", "skills", "Grant or revoke finance capabilities")
+        .body(object(&[("read", boolean()), ("send", boolean())], &[]))
+        .returns(any_object());
+    doc.post("/api/skills/upload", "skills", "Upload a skill (multipart: a SKILL.md or a ZIP)")
+        .returns(reference("SkillOperation"));
+    doc.post("/api/skills/reload", "skills", "Reload skills from disk").returns(reference("SkillOperation"));
+    doc.get("/api/skills/bundled/available", "skills", "Bundled skills that can be restored").returns(any_object());
+    doc.post("/api/skills/bundled/restore/{name}", "skills", "Restore a bundled skill").returns(reference("SkillOperation"));
+    doc.get("/api/skills/featured_remote", "skills", "Featured skills on StarkHub").returns(any_object());
+    doc.post("/api/skills/install_from_hub", "skills", "Install a skill from StarkHub")
+        .body(object(
+            &[("username", string()), ("slug", string()), ("grant_permissions", array(string()))],
+            &["username", "slug"],
+        ))
+        .returns(reference("SkillOperation"));
+    doc.post("/api/skills/publish/{name}", "skills", "Publish a skill to StarkHub").returns(any_object());
+}
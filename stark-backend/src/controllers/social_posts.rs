@@ -0,0 +1,140 @@
+//! Social content calendar API — review, edit, approve and reject the posts
+//! the agent drafted, and see what was published with its engagement.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate_session;
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/social-posts")
+            .route("", web::get().to(list_posts))
+            .route("/{id}", web::get().to(get_post))
+            .route("/{id}", web::put().to(update_post))
+            .route("/{id}", web::delete().to(cancel_post))
+            .route("/{id}/approve", web::post().to(approve_post))
+            .route("/{id}/reject", web::post().to(reject_post)),
+    );
+}
+
+#[derive(Deserialize)]
+struct PostsQuery {
+    status: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct UpdatePostRequest {
+    text: Option<String>,
+    /// RFC 3339 publish time; empty string = publish as soon as approved
+    scheduled_at: Option<String>,
+}
+
+async fn list_posts(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<PostsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(100).min(500);
+    match data.db.list_social_posts(query.status.as_deref(), limit) {
+        Ok(posts) => HttpResponse::Ok().json(posts),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn get_post(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.get_social_post(path.into_inner()) {
+        Ok(Some(post)) => HttpResponse::Ok().json(post),
+        Ok(None) => HttpResponse::NotFound().json(json!({ "error": "Post not found" })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn update_post(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<UpdatePostRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    let body = body.into_inner();
+
+    if body.text.as_deref().is_some_and(|t| t.trim().is_empty()) {
+        return HttpResponse::BadRequest().json(json!({ "error": "text cannot be empty" }));
+    }
+    let scheduled_at = match body.scheduled_at.as_deref().map(str::trim) {
+        None => None,
+        Some("") => Some(None),
+        Some(raw) => match DateTime::parse_from_rfc3339(raw) {
+            Ok(t) => Some(Some(t.with_timezone(&Utc))),
+            Err(e) => {
+                return HttpResponse::BadRequest().json(json!({
+                    "error": format!("Invalid scheduled_at: {}", e)
+                }))
+            }
+        },
+    };
+
+    match data.db.update_social_post(id, body.text.as_deref(), scheduled_at) {
+        Ok(true) => match data.db.get_social_post(id) {
+            Ok(Some(post)) => HttpResponse::Ok().json(post),
+            _ => HttpResponse::NotFound().json(json!({ "error": "Post not found" })),
+        },
+        Ok(false) => HttpResponse::Conflict().json(json!({
+            "error": "Only pending or approved posts can be edited"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+async fn decide(data: web::Data<AppState>, req: HttpRequest, id: i64, approve: bool) -> HttpResponse {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match crate::social_calendar::decide(&data.db, &data.broadcaster, id, approve, "dashboard") {
+        Ok(post) => HttpResponse::Ok().json(post),
+        Err(e) => HttpResponse::Conflict().json(json!({ "error": e })),
+    }
+}
+
+async fn approve_post(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    decide(data, req, path.into_inner(), true).await
+}
+
+async fn reject_post(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    decide(data, req, path.into_inner(), false).await
+}
+
+async fn cancel_post(data: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.cancel_social_post(path.into_inner()) {
+        Ok(true) => HttpResponse::Ok().json(json!({ "success": true })),
+        Ok(false) => HttpResponse::Conflict().json(json!({
+            "error": "Post not found or already published"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
@@ -0,0 +1,214 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::strategies::types::{validate_strategy, CreateStrategyRequest, StrategyAction, UpdateStrategyRequest};
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct RunsQuery {
+    strategy_id: Option<i64>,
+    limit: Option<usize>,
+}
+
+/// Every action must name a registered tool
+fn check_tools(data: &AppState, actions: &[StrategyAction]) -> Result<(), String> {
+    match actions.iter().find(|a| !data.tool_registry.has_tool(&a.tool)) {
+        Some(action) => Err(format!("Unknown tool '{}'", action.tool)),
+        None => Ok(()),
+    }
+}
+
+/// List all strategies
+async fn list_strategies(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_strategies() {
+        Ok(strategies) => HttpResponse::Ok().json(strategies),
+        Err(e) => {
+            log::error!("Failed to list strategies: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// Create a new strategy
+async fn create_strategy(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CreateStrategyRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    if let Err(e) = validate_strategy(&body.name, &body.trigger, &body.conditions, &body.actions, &body.guardrails)
+        .and_then(|_| check_tools(&data, &body.actions))
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+    }
+    match data.db.create_strategy(&body) {
+        Ok(strategy) => HttpResponse::Created().json(strategy),
+        Err(e) => {
+            log::error!("Failed to create strategy: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// Get a single strategy
+async fn get_strategy(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.get_strategy(id) {
+        Ok(Some(strategy)) => HttpResponse::Ok().json(strategy),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Strategy {} not found", id)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// Update a strategy (also used to enable/disable it)
+async fn update_strategy(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<UpdateStrategyRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+
+    // Validate the merged result before persisting
+    let existing = match data.db.get_strategy(id) {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Strategy {} not found", id)
+            }))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+    let name = body.name.as_deref().unwrap_or(&existing.name);
+    let trigger = body.trigger.as_ref().unwrap_or(&existing.trigger);
+    let conditions = body.conditions.as_ref().unwrap_or(&existing.conditions);
+    let actions = body.actions.as_ref().unwrap_or(&existing.actions);
+    let guardrails = body.guardrails.as_ref().unwrap_or(&existing.guardrails);
+    if let Err(e) =
+        validate_strategy(name, trigger, conditions, actions, guardrails).and_then(|_| check_tools(&data, actions))
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+    }
+
+    match data.db.update_strategy(id, &body) {
+        Ok(Some(strategy)) => HttpResponse::Ok().json(strategy),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Strategy {} not found", id)
+        })),
+        Err(e) => {
+            log::error!("Failed to update strategy: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// Delete a strategy (its run history is kept)
+async fn delete_strategy(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    match data.db.delete_strategy(id) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Strategy {} not found", id)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// Run a strategy's actions now, skipping its trigger and conditions
+async fn run_strategy(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let id = path.into_inner();
+    let strategy = match data.db.get_strategy(id) {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Strategy {} not found", id)
+            }))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    };
+    match data.strategy_engine.run(&strategy, "manual run").await {
+        Ok(run) => HttpResponse::Ok().json(run),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e })),
+    }
+}
+
+/// List recent strategy runs (?strategy_id=&limit=)
+async fn list_runs(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<RunsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match data.db.list_strategy_runs(query.strategy_id, limit) {
+        Ok(runs) => HttpResponse::Ok().json(runs),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/strategies")
+            .route("", web::get().to(list_strategies))
+            .route("", web::post().to(create_strategy))
+            .route("/runs", web::get().to(list_runs))
+            .route("/{id}", web::get().to(get_strategy))
+            .route("/{id}", web::put().to(update_strategy))
+            .route("/{id}", web::delete().to(delete_strategy))
+            .route("/{id}/run", web::post().to(run_strategy)),
+    );
+}
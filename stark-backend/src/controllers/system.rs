@@ -7,6 +7,8 @@ use walkdir::WalkDir;
 
 use crate::config;
 use crate::controllers::health::VERSION;
+use crate::controllers::openapi::{any_object, integer, object, ApiDoc};
+use crate::self_update;
 use crate::AppState;
 
 /// Validate session token from request (same pattern as memory controller)
@@ -18,7 +20,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -29,7 +32,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "error": "Invalid or expired session"
@@ -302,12 +305,84 @@ async fn cleanup_workspace(
     })
 }
 
+/// GET /api/system/update
+///
+/// Check the release endpoint for a newer version.
+async fn check_update(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    match self_update::check().await {
+        Ok(check) => HttpResponse::Ok().json(check),
+        Err(e) => HttpResponse::BadGateway().json(serde_json::json!({ "error": e })),
+    }
+}
+
+/// POST /api/system/update
+///
+/// Install the latest release and restart into it once running executions
+/// have finished. Owner only: access keys are refused by the access key
+/// middleware, and a recent second factor is needed when 2FA is enrolled.
+async fn install_update(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    if let Err(resp) = super::require_second_factor(&data, &req) {
+        return resp;
+    }
+
+    let check = match self_update::check().await {
+        Ok(check) => check,
+        Err(e) => return HttpResponse::BadGateway().json(serde_json::json!({ "error": e })),
+    };
+    let Some(release) = check.release else {
+        return HttpResponse::Conflict().json(serde_json::json!({
+            "error": format!("Already on the latest version (v{})", check.current_version)
+        }));
+    };
+    if let Err(e) = self_update::install(&release).await {
+        log::error!("[SELF_UPDATE] Update to v{} failed: {}", release.version, e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({ "error": e }));
+    }
+
+    let tracker = data.execution_tracker.clone();
+    tokio::spawn(async move { self_update::restart_after_drain(&tracker).await });
+
+    HttpResponse::Accepted().json(serde_json::json!({
+        "previous_version": check.current_version,
+        "version": release.version,
+        "restarting": true,
+        "drain_timeout_secs": self_update::DRAIN_TIMEOUT.as_secs(),
+    }))
+}
+
 /// Configure system routes
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/system")
             .route("/info", web::get().to(system_info))
             .route("/cleanup/memories", web::post().to(cleanup_memories))
-            .route("/cleanup/workspace", web::post().to(cleanup_workspace)),
+            .route("/cleanup/workspace", web::post().to(cleanup_workspace))
+            .route("/update", web::get().to(check_update))
+            .route("/update", web::post().to(install_update)),
     );
 }
+
+/// OpenAPI description of the system routes
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.get("/api/system/info", "admin", "Version and disk usage of the workspace and memory directories")
+        .returns(any_object());
+    doc.post("/api/system/cleanup/memories", "admin", "Delete daily-log memories older than N days")
+        .body(object(&[("older_than_days", integer())], &[]))
+        .returns(any_object());
+    doc.post("/api/system/cleanup/workspace", "admin", "Delete all files in the workspace directory")
+        .returns(any_object());
+    doc.get("/api/system/update", "admin", "Check the release endpoint for a newer stark-backend version")
+        .returns(any_object());
+    doc.post(
+        "/api/system/update",
+        "admin",
+        "Install the latest release (checksum verified) and restart once running executions finish; login session only",
+    )
+    .returns(any_object());
+}
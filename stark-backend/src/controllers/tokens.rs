@@ -0,0 +1,141 @@
+//! Token metadata API — symbol/decimals lookups through the metadata cache,
+//! and the override table for tokens whose on-chain or indexed metadata is
+//! wrong. Modules resolve tokens through the internal route.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::validate_session;
+use crate::token_metadata;
+use crate::AppState;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/tokens")
+            .route("/metadata", web::get().to(get_metadata))
+            .route("/overrides", web::get().to(list_overrides))
+            .route("/overrides", web::put().to(upsert_override))
+            .route("/overrides", web::delete().to(delete_override)),
+    );
+    cfg.service(web::scope("/api/internal/tokens").route("/metadata", web::get().to(internal_metadata)));
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    #[serde(default = "default_network")]
+    network: String,
+    address: String,
+}
+
+fn default_network() -> String {
+    "base".to_string()
+}
+
+#[derive(Deserialize)]
+struct UpsertOverrideRequest {
+    #[serde(default = "default_network")]
+    network: String,
+    address: String,
+    symbol: String,
+    #[serde(default)]
+    name: String,
+    decimals: u8,
+    note: Option<String>,
+}
+
+async fn resolve_response(data: &web::Data<AppState>, query: &TokenQuery) -> HttpResponse {
+    match token_metadata::resolve(&data.db, &query.network, &query.address).await {
+        Some(token) => HttpResponse::Ok().json(json!({ "token": token })),
+        None => HttpResponse::NotFound().json(json!({
+            "error": format!("No metadata found for {} on {}", query.address, query.network)
+        })),
+    }
+}
+
+/// GET /api/tokens/metadata?network=&address= - Symbol, name and decimals
+/// (fetched and cached on a miss)
+async fn get_metadata(data: web::Data<AppState>, req: HttpRequest, query: web::Query<TokenQuery>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    resolve_response(&data, &query).await
+}
+
+/// GET /api/internal/tokens/metadata?network=&address= - Same, for modules
+async fn internal_metadata(data: web::Data<AppState>, req: HttpRequest, query: web::Query<TokenQuery>) -> impl Responder {
+    let token = req
+        .headers()
+        .get("X-Internal-Token")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("");
+    if token.is_empty() || token != data.internal_token {
+        return HttpResponse::Unauthorized().json(json!({ "error": "Invalid or missing X-Internal-Token" }));
+    }
+    resolve_response(&data, &query).await
+}
+
+/// GET /api/tokens/overrides
+async fn list_overrides(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.list_token_overrides() {
+        Ok(overrides) => {
+            let overrides: Vec<_> = overrides
+                .into_iter()
+                .map(|(token, note)| json!({ "token": token, "note": note }))
+                .collect();
+            HttpResponse::Ok().json(json!({ "overrides": overrides }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// PUT /api/tokens/overrides - Create or replace the override for a token
+async fn upsert_override(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<UpsertOverrideRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let symbol = body.symbol.trim();
+    if symbol.is_empty() {
+        return HttpResponse::BadRequest().json(json!({ "error": "symbol is required" }));
+    }
+    if body.decimals > 36 {
+        return HttpResponse::BadRequest().json(json!({ "error": "decimals must be between 0 and 36" }));
+    }
+    let name = if body.name.trim().is_empty() { symbol } else { body.name.trim() };
+    match token_metadata::set_override(
+        &data.db,
+        &body.network,
+        &body.address,
+        symbol,
+        name,
+        body.decimals,
+        body.note.as_deref(),
+    ) {
+        Ok(token) => {
+            log::info!("Token override set: {} {} = {} ({} decimals)", token.network, token.address, token.symbol, token.decimals);
+            HttpResponse::Ok().json(json!({ "token": token }))
+        }
+        Err(e) => HttpResponse::BadRequest().json(json!({ "error": e })),
+    }
+}
+
+/// DELETE /api/tokens/overrides?network=&address=
+async fn delete_override(data: web::Data<AppState>, req: HttpRequest, query: web::Query<TokenQuery>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match token_metadata::remove_override(&data.db, &query.network, &query.address) {
+        Ok(true) => HttpResponse::Ok().json(json!({ "deleted": true })),
+        Ok(false) => HttpResponse::NotFound().json(json!({ "error": "Override not found" })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({ "error": e })),
+    }
+}
@@ -0,0 +1,103 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::tools::previews::{self, PreviewSettings};
+use crate::tools::ToolSafetyLevel;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct ConfirmationsQuery {
+    status: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct DecideRequest {
+    /// true runs the tool, false cancels it
+    confirm: bool,
+}
+
+/// GET /api/tool-confirmations?status=&limit= - Confirmation requests and decisions (audit log)
+async fn list_confirmations(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ConfirmationsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match data.db.list_tool_confirmations(query.status.as_deref(), limit) {
+        Ok(confirmations) => HttpResponse::Ok().json(confirmations),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// POST /api/tool-confirmations/{uuid}/decide - Confirm or cancel a waiting tool call
+async fn decide(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<DecideRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let uuid = path.into_inner();
+    match crate::tools::confirmation::decide(&data.db, &uuid, body.confirm, "dashboard") {
+        Ok(confirmation) => HttpResponse::Ok().json(confirmation),
+        Err(e) => HttpResponse::Conflict().json(serde_json::json!({ "error": e })),
+    }
+}
+
+/// GET /api/tool-confirmations/previews - Tools that get a parameter preview before they run
+async fn get_previews(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    HttpResponse::Ok().json(previews::load(&data.db))
+}
+
+/// PUT /api/tool-confirmations/previews - Replace the previewed tools
+async fn update_previews(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<PreviewSettings>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let mut settings = body.into_inner();
+    for (name, rule) in settings.tools.iter_mut() {
+        match data.tool_registry.get(name) {
+            None => {
+                return HttpResponse::BadRequest().json(serde_json::json!({ "error": format!("Unknown tool '{}'", name) }))
+            }
+            Some(tool) if tool.safety_level() != ToolSafetyLevel::Standard => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("'{}' is {}: only standard tools can have previews", name, tool.safety_level().as_str())
+                }))
+            }
+            Some(_) => {}
+        }
+        rule.fields = rule.fields.iter().map(|f| f.trim().to_string()).filter(|f| !f.is_empty()).collect();
+    }
+    if let Err(e) = previews::save(&data.db, &settings) {
+        return HttpResponse::InternalServerError().json(serde_json::json!({ "error": e }));
+    }
+    log::info!("[TOOL_CONFIRM] Parameter previews on for {} tool(s)", settings.tools.len());
+    HttpResponse::Ok().json(settings)
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/tool-confirmations")
+            .route("", web::get().to(list_confirmations))
+            .route("/previews", web::get().to(get_previews))
+            .route("/previews", web::put().to(update_previews))
+            .route("/{uuid}/decide", web::post().to(decide)),
+    );
+}
@@ -0,0 +1,67 @@
+//! Tool timeout API — the default and per-tool execution timeouts (see
+//! `crate::tools::timeouts`)
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use super::validate_session;
+use crate::controllers::openapi::{any_object, integer, object, ApiDoc};
+use crate::telemetry::WatchdogConfig;
+use crate::tools::timeouts::{self, ToolTimeouts, MAX_TIMEOUT_SECS, MIN_TIMEOUT_SECS};
+use crate::AppState;
+
+fn effective(configured: ToolTimeouts) -> serde_json::Value {
+    let config = configured.apply(&WatchdogConfig::default());
+    let tools: std::collections::BTreeMap<_, _> = config.tool_overrides.into_iter().collect();
+    serde_json::json!({
+        "default_secs": config.tool_timeout_secs,
+        "tools": tools,
+        "configured": configured,
+    })
+}
+
+/// GET /api/tool-timeouts - Timeouts in effect, and what was configured on top of the built-in ones
+async fn get_timeouts(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    HttpResponse::Ok().json(effective(timeouts::load(&data.db)))
+}
+
+/// PUT /api/tool-timeouts - Replace the configured timeouts (applies to new executions)
+async fn update_timeouts(data: web::Data<AppState>, req: HttpRequest, body: web::Json<ToolTimeouts>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let configured = body.into_inner();
+    let out_of_range = |secs: u64| !(MIN_TIMEOUT_SECS..=MAX_TIMEOUT_SECS).contains(&secs);
+    if configured.default_secs.is_some_and(out_of_range) || configured.tools.values().any(|s| out_of_range(*s)) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Timeouts must be between {} and {} seconds", MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS)
+        }));
+    }
+    if let Some(unknown) = configured.tools.keys().find(|name| data.tool_registry.get(name).is_none()) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": format!("Unknown tool '{}'", unknown) }));
+    }
+    if let Err(e) = timeouts::save(&data.db, &configured) {
+        return HttpResponse::InternalServerError().json(serde_json::json!({ "error": e }));
+    }
+    log::info!("[TOOL_TIMEOUT] Timeouts updated ({} per-tool)", configured.tools.len());
+    HttpResponse::Ok().json(effective(configured))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/tool-timeouts")
+            .route("", web::get().to(get_timeouts))
+            .route("", web::put().to(update_timeouts)),
+    );
+}
+
+/// OpenAPI description of the tool timeout routes
+pub fn openapi(doc: &mut ApiDoc) {
+    doc.get("/api/tool-timeouts", "tools", "Tool execution timeouts in effect and the configured overrides")
+        .returns(any_object());
+    doc.put("/api/tool-timeouts", "tools", "Set the default and per-tool execution timeouts (seconds)")
+        .body(object(&[("default_secs", integer()), ("tools", any_object())], &[]))
+        .returns(any_object());
+}
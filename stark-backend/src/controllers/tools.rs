@@ -2,6 +2,8 @@ use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 
 use crate::tools::{ToolConfig, ToolDefinition, ToolExecution, ToolGroup, ToolProfile};
+use crate::config_watch::ConfigChange;
+use crate::controllers::pagination::{Page, PageQuery};
 use crate::AppState;
 
 #[derive(Serialize)]
@@ -111,8 +113,6 @@ pub struct HistoryResponse {
 #[derive(Deserialize)]
 pub struct HistoryQuery {
     pub channel_id: Option<i64>,
-    pub limit: Option<i32>,
-    pub offset: Option<i32>,
 }
 
 pub fn config(cfg: &mut web::ServiceConfig) {
@@ -137,7 +137,8 @@ fn validate_session_from_request(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -150,7 +151,7 @@ fn validate_session_from_request(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(ToolsListResponse {
             success: false,
@@ -360,11 +361,14 @@ async fn update_global_config(
     }
 
     match state.db.save_tool_config(&config) {
-        Ok(_) => HttpResponse::Ok().json(ConfigResponse {
-            success: true,
-            config: Some(config.into()),
-            error: None,
-        }),
+        Ok(_) => {
+            state.config_watch.notify(ConfigChange::ToolConfig { channel_id: config.channel_id });
+            HttpResponse::Ok().json(ConfigResponse {
+                success: true,
+                config: Some(config.into()),
+                error: None,
+            })
+        }
         Err(e) => {
             log::error!("Failed to save tool config: {}", e);
             HttpResponse::InternalServerError().json(ConfigResponse {
@@ -454,11 +458,14 @@ async fn update_channel_config(
     }
 
     match state.db.save_tool_config(&config) {
-        Ok(_) => HttpResponse::Ok().json(ConfigResponse {
-            success: true,
-            config: Some(config.into()),
-            error: None,
-        }),
+        Ok(_) => {
+            state.config_watch.notify(ConfigChange::ToolConfig { channel_id: config.channel_id });
+            HttpResponse::Ok().json(ConfigResponse {
+                success: true,
+                config: Some(config.into()),
+                error: None,
+            })
+        }
         Err(e) => {
             log::error!("Failed to save channel tool config: {}", e);
             HttpResponse::InternalServerError().json(ConfigResponse {
@@ -474,26 +481,31 @@ async fn get_history(
     state: web::Data<AppState>,
     req: HttpRequest,
     query: web::Query<HistoryQuery>,
+    page: web::Query<PageQuery>,
 ) -> impl Responder {
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
     }
 
-    let limit = query.limit.unwrap_or(50);
-    let offset = query.offset.unwrap_or(0);
+    let limit = page.limit(50, 500) as i32;
+    let offset = page.offset();
 
     let executions = if let Some(channel_id) = query.channel_id {
-        state.db.get_tool_execution_history(channel_id, limit, offset)
+        state.db.get_tool_execution_history(channel_id, limit, offset as i32)
     } else {
-        state.db.get_all_tool_execution_history(limit, offset)
+        state.db.get_all_tool_execution_history(limit, offset as i32)
     };
-
-    match executions {
-        Ok(execs) => HttpResponse::Ok().json(HistoryResponse {
-            success: true,
-            executions: Some(execs),
-            error: None,
-        }),
+    let total = state.db.count_tool_executions(query.channel_id);
+
+    match executions.and_then(|execs| total.map(|total| (execs, total))) {
+        Ok((execs, total)) => {
+            let history = Page::new(execs, total, offset);
+            history.ok().json(HistoryResponse {
+                success: true,
+                executions: Some(history.items),
+                error: None,
+            })
+        }
         Err(e) => {
             log::error!("Failed to get tool execution history: {}", e);
             HttpResponse::InternalServerError().json(HistoryResponse {
@@ -0,0 +1,53 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+
+use super::validate_session;
+use crate::error::AppResult;
+use crate::journal;
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct TradesQuery {
+    status: Option<String>,
+    /// day, week, month, year or all
+    period: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct PnlQuery {
+    period: Option<String>,
+}
+
+/// GET /api/trades?status=&period=&limit= - Trade journal, newest first
+async fn list_trades(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<TradesQuery>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let since = journal::period_start(query.period.as_deref().unwrap_or("all"))?;
+    let limit = query.limit.unwrap_or(100).min(1000);
+    let trades = data.db.list_trades(query.status.as_deref(), since.as_deref(), limit)?;
+    Ok(HttpResponse::Ok().json(trades))
+}
+
+/// GET /api/trades/pnl?period= - Realized/unrealized PnL per token
+async fn get_pnl(data: web::Data<AppState>, req: HttpRequest, query: web::Query<PnlQuery>) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let since = journal::period_start(query.period.as_deref().unwrap_or("all"))?;
+    let report = journal::pnl_report(&data.db, since.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/trades")
+            .route("", web::get().to(list_trades))
+            .route("/pnl", web::get().to(get_pnl)),
+    );
+}
@@ -0,0 +1,263 @@
+//! Two-factor enrollment and verification
+//!
+//! Enrollment is two steps: `enroll` creates a secret for the authenticator
+//! app, `confirm` checks the first code, turns 2FA on and returns the recovery
+//! codes (shown only then). `verify` marks the current session as verified for
+//! sensitive actions. Disabling or replacing recovery codes needs a code too.
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+
+use super::openapi::{array, boolean, date_time, integer, nullable, object, string, ApiDoc};
+use super::validate_session;
+use crate::error::{AppError, AppResult};
+use crate::middleware::session_auth::extract_token;
+use crate::two_factor::{
+    generate_recovery_codes, generate_secret, hash_recovery_code, otpauth_uri, verify_code,
+    MAX_ACCOUNT_FAILURES, MAX_SESSION_FAILURES, RECENT_VERIFICATION_SECS,
+};
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct CodeRequest {
+    /// 6-digit code from the authenticator app
+    code: Option<String>,
+    /// One of the recovery codes, instead of `code`
+    recovery_code: Option<String>,
+}
+
+/// Failure counter shared by every session
+const ACCOUNT_SUBJECT: &str = "account";
+
+/// Check a TOTP or recovery code against the enrolled secret for the session
+/// behind `token`. Accepted codes are used up; wrong ones count towards the
+/// session's and the account's lockout.
+fn check_code(data: &AppState, token: &str, body: &CodeRequest) -> AppResult<()> {
+    let two_factor = data
+        .db
+        .get_two_factor()?
+        .filter(|tf| tf.enabled)
+        .ok_or_else(|| AppError::validation("Two-factor authentication is not enabled"))?;
+
+    let session_id = data
+        .db
+        .session_id_for_token(token)?
+        .ok_or_else(|| AppError::validation("A login session is required"))?;
+    let session_subject = format!("session:{}", session_id);
+    for subject in [ACCOUNT_SUBJECT, session_subject.as_str()] {
+        if let Some(until) = data.db.two_factor_locked_until(subject)? {
+            return Err(AppError::rate_limited(format!(
+                "Too many wrong codes; try again after {}",
+                until.to_rfc3339()
+            )));
+        }
+    }
+
+    let accepted = match (body.code.as_deref(), body.recovery_code.as_deref()) {
+        (Some(code), _) => match verify_code(&two_factor.secret, code, Utc::now().timestamp(), two_factor.last_used_step) {
+            // A concurrent request may have used the same step first
+            Some(step) => data.db.set_two_factor_last_step(step)?,
+            None => false,
+        },
+        (None, Some(recovery_code)) => {
+            let used = data.db.use_two_factor_recovery_code(&hash_recovery_code(recovery_code))?;
+            if used {
+                log::warn!("[2FA] Recovery code used");
+            }
+            used
+        }
+        (None, None) => return Err(AppError::validation("code or recovery_code is required")),
+    };
+
+    if accepted {
+        data.db.clear_two_factor_failures(&[ACCOUNT_SUBJECT, &session_subject])?;
+        return Ok(());
+    }
+    let session_failures = data.db.record_two_factor_failure(&session_subject, MAX_SESSION_FAILURES)?;
+    let account_failures = data.db.record_two_factor_failure(ACCOUNT_SUBJECT, MAX_ACCOUNT_FAILURES)?;
+    log::warn!(
+        "[2FA] Wrong code for session {} ({} for the session, {} for the account)",
+        session_id, session_failures, account_failures
+    );
+    Err(AppError::validation(if body.code.is_some() {
+        "Invalid or already used code"
+    } else {
+        "Invalid or already used recovery code"
+    }))
+}
+
+/// The session token; access keys can't take part in 2FA
+fn session_token(req: &HttpRequest) -> AppResult<String> {
+    extract_token(req)
+        .filter(|t| !t.starts_with(crate::db::tables::access_keys::ACCESS_KEY_PREFIX))
+        .ok_or_else(|| AppError::validation("A login session is required"))
+}
+
+/// GET /api/two-factor - Enrollment state and this session's verification
+async fn get_status(data: web::Data<AppState>, req: HttpRequest) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let two_factor = data.db.get_two_factor()?;
+    let enabled = two_factor.as_ref().is_some_and(|tf| tf.enabled);
+    let verified_until = match extract_token(&req) {
+        Some(token) => data
+            .db
+            .session_second_factor_at(&token)?
+            .map(|at| at + Duration::seconds(RECENT_VERIFICATION_SECS))
+            .filter(|until| *until > Utc::now()),
+        None => None,
+    };
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "enabled": enabled,
+        "pending": two_factor.as_ref().is_some_and(|tf| !tf.enabled),
+        "enabled_at": two_factor.and_then(|tf| tf.enabled_at),
+        "recovery_codes_left": if enabled { Some(data.db.count_unused_two_factor_recovery_codes()?) } else { None },
+        "verified_until": verified_until.map(|t| t.to_rfc3339()),
+    })))
+}
+
+/// POST /api/two-factor/enroll - New secret for the authenticator app
+async fn enroll(data: web::Data<AppState>, req: HttpRequest) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    session_token(&req)?;
+    if data.db.is_two_factor_enabled()? {
+        return Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Two-factor authentication is already enabled; disable it first"
+        })));
+    }
+    let secret = generate_secret();
+    data.db.save_pending_two_factor(&secret)?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "secret": secret,
+        "otpauth_uri": otpauth_uri(&secret, "admin"),
+    })))
+}
+
+/// POST /api/two-factor/confirm - First code; turns 2FA on and returns recovery codes
+async fn confirm(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CodeRequest>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let token = session_token(&req)?;
+    let pending = data
+        .db
+        .get_two_factor()?
+        .filter(|tf| !tf.enabled)
+        .ok_or_else(|| AppError::validation("No enrollment in progress; call /api/two-factor/enroll first"))?;
+    let code = body.code.as_deref().ok_or_else(|| AppError::validation("code is required"))?;
+    let step = verify_code(&pending.secret, code, Utc::now().timestamp(), None)
+        .ok_or_else(|| AppError::validation("Invalid code; check the authenticator app's clock"))?;
+
+    let recovery_codes = generate_recovery_codes();
+    let hashes: Vec<String> = recovery_codes.iter().map(|c| hash_recovery_code(c)).collect();
+    data.db.enable_two_factor(step, &hashes)?;
+    data.db.mark_session_second_factor(&token)?;
+    log::info!("[2FA] Two-factor authentication enabled");
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "enabled": true,
+        "recovery_codes": recovery_codes,
+    })))
+}
+
+/// POST /api/two-factor/verify - Verify this session for sensitive actions
+async fn verify(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CodeRequest>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let token = session_token(&req)?;
+    check_code(&data, &token, &body)?;
+    data.db.mark_session_second_factor(&token)?;
+    let until = Utc::now() + Duration::seconds(RECENT_VERIFICATION_SECS);
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "verified": true,
+        "verified_until": until.to_rfc3339(),
+    })))
+}
+
+/// POST /api/two-factor/recovery-codes - Replace the recovery codes
+async fn regenerate_recovery_codes(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CodeRequest>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let token = session_token(&req)?;
+    if body.code.is_none() {
+        return Err(AppError::validation("code is required"));
+    }
+    check_code(&data, &token, &body)?;
+    let recovery_codes = generate_recovery_codes();
+    let hashes: Vec<String> = recovery_codes.iter().map(|c| hash_recovery_code(c)).collect();
+    data.db.replace_two_factor_recovery_codes(&hashes)?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "recovery_codes": recovery_codes })))
+}
+
+/// POST /api/two-factor/disable - Turn 2FA off
+async fn disable(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CodeRequest>,
+) -> AppResult<HttpResponse> {
+    if let Err(resp) = validate_session(&data, &req) {
+        return Ok(resp);
+    }
+    let token = session_token(&req)?;
+    check_code(&data, &token, &body)?;
+    data.db.disable_two_factor()?;
+    log::warn!("[2FA] Two-factor authentication disabled");
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "enabled": false })))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/two-factor")
+            .route("", web::get().to(get_status))
+            .route("/enroll", web::post().to(enroll))
+            .route("/confirm", web::post().to(confirm))
+            .route("/verify", web::post().to(verify))
+            .route("/recovery-codes", web::post().to(regenerate_recovery_codes))
+            .route("/disable", web::post().to(disable)),
+    );
+}
+
+pub fn openapi(doc: &mut ApiDoc) {
+    let code = object(&[("code", string()), ("recovery_code", string())], &[]);
+    doc.get("/api/two-factor", "auth", "Two-factor state and this session's verification")
+        .returns(object(
+            &[
+                ("enabled", boolean()),
+                ("pending", boolean()),
+                ("enabled_at", nullable(date_time())),
+                ("recovery_codes_left", nullable(integer())),
+                ("verified_until", nullable(date_time())),
+            ],
+            &["enabled", "pending"],
+        ));
+    doc.post("/api/two-factor/enroll", "auth", "Start enrollment with a new TOTP secret")
+        .returns(object(&[("secret", string()), ("otpauth_uri", string())], &["secret", "otpauth_uri"]));
+    doc.post("/api/two-factor/confirm", "auth", "Confirm the first code; returns recovery codes once")
+        .body(object(&[("code", string())], &["code"]))
+        .returns(object(&[("enabled", boolean()), ("recovery_codes", array(string()))], &["enabled", "recovery_codes"]));
+    doc.post("/api/two-factor/verify", "auth", "Verify this session for sensitive actions")
+        .body(code.clone())
+        .returns(object(&[("verified", boolean()), ("verified_until", date_time())], &["verified"]));
+    doc.post("/api/two-factor/recovery-codes", "auth", "Replace the recovery codes")
+        .body(object(&[("code", string())], &["code"]))
+        .returns(object(&[("recovery_codes", array(string()))], &["recovery_codes"]));
+    doc.post("/api/two-factor/disable", "auth", "Turn two-factor authentication off").body(code);
+}
@@ -0,0 +1,190 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use super::validate_session;
+use super::validation::{Valid, Validate, Validator};
+use crate::config_watch::ConfigChange;
+use crate::models::DEFAULT_TX_APPROVAL_TTL_SECS;
+use crate::web3::amounts::{self, AmountBounds};
+use crate::AppState;
+
+#[derive(Deserialize)]
+struct ApprovalsQuery {
+    status: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct PolicyRequest {
+    /// ETH value above which approval is required; null disables chat approvals
+    threshold_eth: Option<f64>,
+    channel_id: Option<i64>,
+    chat_id: Option<String>,
+    ttl_secs: Option<i64>,
+}
+
+impl Validate for PolicyRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(threshold) = self.threshold_eth {
+            if threshold < 0.0 || !threshold.is_finite() {
+                v.error("threshold_eth", "must be a non-negative number");
+            }
+            if self.channel_id.is_none() {
+                v.error("channel_id", "is required when threshold_eth is set");
+            }
+            if self.chat_id.as_deref().is_none_or(|c| c.trim().is_empty()) {
+                v.error("chat_id", "is required when threshold_eth is set");
+            }
+        }
+    }
+}
+
+/// GET /api/tx-approvals?status=&limit= - Approval requests and decisions (audit log)
+async fn list_approvals(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ApprovalsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let limit = query.limit.unwrap_or(50).min(500);
+    match data.db.list_tx_approvals(query.status.as_deref(), limit) {
+        Ok(approvals) => HttpResponse::Ok().json(approvals),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// GET /api/tx-approvals/policy - Current approval policy
+async fn get_policy(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    match data.db.get_bot_settings() {
+        Ok(settings) => HttpResponse::Ok().json(serde_json::json!({
+            "threshold_eth": settings.tx_approval_threshold_eth,
+            "channel_id": settings.tx_approval_channel_id,
+            "chat_id": settings.tx_approval_chat_id,
+            "ttl_secs": settings.tx_approval_ttl_secs,
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// PUT /api/tx-approvals/policy - Replace the approval policy
+async fn update_policy(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: Valid<PolicyRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    if let Err(resp) = super::require_second_factor(&data, &req) {
+        return resp;
+    }
+    if body.threshold_eth.is_some() {
+        let channel = match body.channel_id {
+            Some(id) => data.db.get_channel(id).ok().flatten(),
+            None => None,
+        };
+        match channel {
+            Some(ch) if ch.channel_type == "telegram" || ch.channel_type == "discord" => {}
+            Some(ch) => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Approval cards need buttons; {} channels are not supported", ch.channel_type)
+                }));
+            }
+            None => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "channel_id must be an existing Telegram or Discord channel"
+                }));
+            }
+        }
+    }
+
+    let ttl_secs = body.ttl_secs.unwrap_or(DEFAULT_TX_APPROVAL_TTL_SECS).clamp(60, 24 * 3600);
+    match data.db.update_tx_approval_policy(
+        body.threshold_eth,
+        body.channel_id,
+        body.chat_id.as_deref().map(str::trim),
+        ttl_secs,
+    ) {
+        Ok(settings) => {
+            log::info!(
+                "Updated tx approval policy: threshold={:?} ETH, channel={:?}",
+                settings.tx_approval_threshold_eth,
+                settings.tx_approval_channel_id
+            );
+            data.config_watch.notify(ConfigChange::BotSettings);
+            HttpResponse::Ok().json(serde_json::json!({
+                "threshold_eth": settings.tx_approval_threshold_eth,
+                "channel_id": settings.tx_approval_channel_id,
+                "chat_id": settings.tx_approval_chat_id,
+                "ttl_secs": settings.tx_approval_ttl_secs,
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+impl Validate for AmountBounds {
+    fn validate(&self, v: &mut Validator) {
+        let in_range = |pct: f64| pct.is_finite() && pct > 0.0 && pct <= 100.0;
+        if !in_range(self.warn_balance_pct) {
+            v.error("warn_balance_pct", "must be a percentage above 0 and at most 100");
+        }
+        if let Some(block) = self.block_balance_pct {
+            if !in_range(block) {
+                v.error("block_balance_pct", "must be a percentage above 0 and at most 100");
+            } else if block < self.warn_balance_pct {
+                v.error("block_balance_pct", "must not be below warn_balance_pct");
+            }
+        }
+    }
+}
+
+/// GET /api/tx-approvals/bounds - Share of the balance one transaction may spend before it is flagged or refused
+async fn get_bounds(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    HttpResponse::Ok().json(amounts::load_bounds(&data.db))
+}
+
+/// PUT /api/tx-approvals/bounds - Replace the amount sanity bounds
+async fn update_bounds(data: web::Data<AppState>, req: HttpRequest, body: Valid<AmountBounds>) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    if let Err(resp) = super::require_second_factor(&data, &req) {
+        return resp;
+    }
+    let bounds = body.into_inner();
+    if let Err(e) = amounts::save_bounds(&data.db, &bounds) {
+        return HttpResponse::InternalServerError().json(serde_json::json!({ "error": e }));
+    }
+    log::info!(
+        "Updated amount bounds: warn above {}%, block above {:?}%",
+        bounds.warn_balance_pct,
+        bounds.block_balance_pct
+    );
+    HttpResponse::Ok().json(bounds)
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/tx-approvals")
+            .route("", web::get().to(list_approvals))
+            .route("/policy", web::get().to(get_policy))
+            .route("/policy", web::put().to(update_policy))
+            .route("/bounds", web::get().to(get_bounds))
+            .route("/bounds", web::put().to(update_bounds)),
+    );
+}
@@ -0,0 +1,248 @@
+//! Request body validation
+//!
+//! Handlers take `Valid<T>` instead of `web::Json<T>` for bodies that
+//! implement `Validate`. The body is deserialized and checked before the
+//! handler runs; problems come back as a 422 listing every bad field:
+//!
+//! ```json
+//! { "error": "Validation failed", "fields": [{ "field": "schedule_value", "message": "..." }] }
+//! ```
+//!
+//! Bodies that don't parse at all (missing fields, wrong types, unknown enum
+//! values) get the same shape from `json_config`, which is installed for every
+//! `web::Json` extractor, instead of actix's plain-text 400.
+
+use std::ops::Deref;
+use std::str::FromStr;
+
+use actix_web::dev::Payload;
+use actix_web::error::JsonPayloadError;
+use actix_web::http::StatusCode;
+use actix_web::{web, FromRequest, HttpRequest, HttpResponse, ResponseError};
+use futures_util::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Largest JSON body accepted (attachments are uploaded separately)
+pub const MAX_JSON_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// One problem with one field
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Every problem found in a body; answers with 422
+#[derive(Debug)]
+pub struct ValidationErrors(pub Vec<FieldError>);
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(|e| format!("{}: {}", e.field, e.message)).collect();
+        write!(f, "Validation failed: {}", parts.join("; "))
+    }
+}
+
+impl ResponseError for ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::UnprocessableEntity().json(serde_json::json!({
+            "error": "Validation failed",
+            "fields": self.0,
+        }))
+    }
+}
+
+/// Collects field errors while a body is checked
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn error(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError { field: field.to_string(), message: message.into() });
+    }
+
+    pub fn not_blank(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.error(field, "must not be empty");
+        }
+    }
+
+    /// At most `max` characters
+    pub fn max_len(&mut self, field: &str, value: &str, max: usize) {
+        let len = value.chars().count();
+        if len > max {
+            self.error(field, format!("must be at most {} characters (got {})", max, len));
+        }
+    }
+
+    pub fn range<T: PartialOrd + std::fmt::Display>(&mut self, field: &str, value: T, min: T, max: T) {
+        if value < min || value > max {
+            self.error(field, format!("must be between {} and {} (got {})", min, max, value));
+        }
+    }
+
+    /// Case-insensitively one of `allowed`
+    pub fn one_of(&mut self, field: &str, value: &str, allowed: &[&str]) {
+        if !allowed.iter().any(|a| a.eq_ignore_ascii_case(value.trim())) {
+            self.error(field, format!("must be one of: {} (got '{}')", allowed.join(", "), value));
+        }
+    }
+
+    /// A 0x-prefixed 20-byte hex address
+    pub fn address(&mut self, field: &str, value: &str) {
+        if value.trim().parse::<ethers::types::Address>().is_err() || !value.trim().starts_with("0x") {
+            self.error(field, format!("'{}' is not a valid 0x address", value));
+        }
+    }
+
+    /// A cron expression the scheduler can run
+    pub fn cron(&mut self, field: &str, value: &str) {
+        if let Err(e) = cron::Schedule::from_str(value.trim()) {
+            self.error(field, format!("'{}' is not a valid cron expression: {}", value, e));
+        }
+    }
+
+    /// A non-negative integer given as a string (token amounts)
+    pub fn uint_string(&mut self, field: &str, value: &str) {
+        if value.trim().parse::<u128>().is_err() {
+            self.error(field, "must be a non-negative integer string");
+        }
+    }
+
+    pub fn finish(self) -> Result<(), ValidationErrors> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors(self.errors))
+        }
+    }
+}
+
+/// A request body that can check itself
+pub trait Validate {
+    fn validate(&self, v: &mut Validator);
+}
+
+/// Run a body's checks
+pub fn check<T: Validate>(body: &T) -> Result<(), ValidationErrors> {
+    let mut v = Validator::default();
+    body.validate(&mut v);
+    v.finish()
+}
+
+/// JSON body extractor that runs `Validate` before the handler
+pub struct Valid<T>(pub T);
+
+impl<T> Valid<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Valid<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned + Validate + 'static> FromRequest for Valid<T> {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json = web::Json::<T>::from_request(req, payload);
+        Box::pin(async move {
+            let body = json.await?.into_inner();
+            check(&body)?;
+            Ok(Valid(body))
+        })
+    }
+}
+
+/// The field a serde error is about, when it says
+fn field_of(message: &str) -> Option<String> {
+    let start = message.find('`')? + 1;
+    let end = start + message[start..].find('`')?;
+    (message.contains("missing field") || message.contains("unknown field") || message.contains("duplicate field"))
+        .then(|| message[start..end].to_string())
+}
+
+/// `web::Json` settings for the whole app: body size limit and 422 field
+/// errors for bodies that don't deserialize
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default()
+        .limit(MAX_JSON_BODY_BYTES)
+        .error_handler(|err, _req| match err {
+            JsonPayloadError::Deserialize(e) => {
+                let message = e.to_string();
+                let field = field_of(&message).unwrap_or_else(|| "body".to_string());
+                ValidationErrors(vec![FieldError { field, message }]).into()
+            }
+            other => {
+                let status = other.status_code();
+                let resp = HttpResponse::build(status).json(serde_json::json!({ "error": other.to_string() }));
+                actix_web::error::InternalError::from_response(other, resp).into()
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Body {
+        name: String,
+        schedule: String,
+        wallet: String,
+        kind: String,
+        retries: i32,
+    }
+
+    impl Validate for Body {
+        fn validate(&self, v: &mut Validator) {
+            v.not_blank("name", &self.name);
+            v.max_len("name", &self.name, 5);
+            v.cron("schedule", &self.schedule);
+            v.address("wallet", &self.wallet);
+            v.one_of("kind", &self.kind, &["at", "every", "cron"]);
+            v.range("retries", self.retries, 0, 10);
+        }
+    }
+
+    #[test]
+    fn test_field_errors() {
+        let ok = Body {
+            name: "daily".into(),
+            schedule: "0 0 9 * * *".into(),
+            wallet: "0x0000000000000000000000000000000000000001".into(),
+            kind: "CRON".into(),
+            retries: 3,
+        };
+        assert!(check(&ok).is_ok());
+
+        let bad = Body {
+            name: "much too long".into(),
+            schedule: "every day".into(),
+            wallet: "0x123".into(),
+            kind: "hourly".into(),
+            retries: 11,
+        };
+        let errors = check(&bad).unwrap_err().0;
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["name", "schedule", "wallet", "kind", "retries"]);
+        assert_eq!(
+            field_of("missing field `schedule_type` at line 1 column 20").as_deref(),
+            Some("schedule_type")
+        );
+        assert_eq!(field_of("expected value at line 1 column 1"), None);
+    }
+}
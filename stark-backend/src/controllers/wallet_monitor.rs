@@ -0,0 +1,130 @@
+//! Wallet monitor export/import API — downloads activity (CSV or JSONL, for
+//! tax and accounting tools) and the watchlist, and imports a watchlist from
+//! CSV. The data lives in the wallet_monitor module's service; these routes
+//! authenticate the session and forward to it.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+
+use super::validate_session;
+use crate::AppState;
+
+const MODULE_NAME: &str = "wallet_monitor";
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/wallet-monitor")
+            .route("/activity/export", web::get().to(export_activity))
+            .route("/watchlist/export", web::get().to(export_watchlist))
+            .route("/watchlist/import", web::post().to(import_watchlist)),
+    );
+}
+
+/// Base URL of the wallet monitor service, or the response to send when it isn't running
+fn service_url(data: &web::Data<AppState>) -> Result<String, HttpResponse> {
+    let enabled = data
+        .db
+        .get_installed_module(MODULE_NAME)
+        .ok()
+        .flatten()
+        .is_some_and(|m| m.enabled);
+    let registry = crate::modules::ModuleRegistry::new();
+    match registry.get(MODULE_NAME) {
+        Some(module) if enabled => Ok(module.service_url()),
+        _ => Err(HttpResponse::NotFound().json(json!({
+            "error": "The wallet_monitor module is not installed or not enabled"
+        }))),
+    }
+}
+
+/// Relay the service's response, keeping its content type and download filename
+async fn relay(sent: Result<reqwest::Response, reqwest::Error>) -> HttpResponse {
+    let resp = match sent {
+        Ok(resp) => resp,
+        Err(e) => {
+            return HttpResponse::BadGateway().json(json!({
+                "error": format!("Could not reach wallet monitor service: {}", e)
+            }))
+        }
+    };
+    let status = actix_web::http::StatusCode::from_u16(resp.status().as_u16())
+        .unwrap_or(actix_web::http::StatusCode::BAD_GATEWAY);
+    let header = |name: &str| resp.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    let content_type = header("content-type").unwrap_or_else(|| "application/octet-stream".to_string());
+    let disposition = header("content-disposition");
+    let body = resp.bytes().await.unwrap_or_default();
+
+    let mut builder = HttpResponse::build(status);
+    builder.content_type(content_type);
+    if let Some(disposition) = disposition {
+        builder.insert_header(("Content-Disposition", disposition));
+    }
+    builder.body(body)
+}
+
+/// Forward a GET with the caller's query string (filters, format) untouched
+async fn forward_get(data: &web::Data<AppState>, req: &HttpRequest, path: &str) -> HttpResponse {
+    let base = match service_url(data) {
+        Ok(url) => url,
+        Err(resp) => return resp,
+    };
+    let url = match req.uri().query() {
+        Some(qs) => format!("{}{}?{}", base, path, qs),
+        None => format!("{}{}", base, path),
+    };
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .unwrap_or_default();
+    relay(client.get(&url).send().await).await
+}
+
+/// GET /api/wallet-monitor/activity/export - Activity as CSV (default) or JSONL.
+/// Query: format, watchlist_id, address, chain, activity_type, large_only,
+/// since, until (ISO dates), limit.
+async fn export_activity(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    forward_get(&data, &req, "/export/activity").await
+}
+
+/// GET /api/wallet-monitor/watchlist/export - Watchlist as CSV (default) or JSON
+async fn export_watchlist(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    forward_get(&data, &req, "/export/watchlist").await
+}
+
+/// POST /api/wallet-monitor/watchlist/import - Add watchlist entries from a CSV
+/// body (columns: address, label, chain, threshold_usd, monitor_enabled, notes)
+async fn import_watchlist(data: web::Data<AppState>, req: HttpRequest, body: web::Bytes) -> impl Responder {
+    if let Err(resp) = validate_session(&data, &req) {
+        return resp;
+    }
+    let base = match service_url(&data) {
+        Ok(url) => url,
+        Err(resp) => return resp,
+    };
+    if body.is_empty() {
+        return HttpResponse::BadRequest().json(json!({ "error": "CSV body is required" }));
+    }
+    let content_type = req
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("text/csv")
+        .to_string();
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .unwrap_or_default();
+    let sent = client
+        .post(format!("{}/rpc/watchlist/import", base))
+        .header("content-type", content_type)
+        .body(body.to_vec())
+        .send()
+        .await;
+    relay(sent).await
+}
@@ -1,14 +1,24 @@
 use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
 
+use crate::eip8004::receipts::{self, ReceiptFeed, FEED_SCHEME};
 use crate::AppState;
 
+#[derive(Deserialize)]
+struct ReceiptsQuery {
+    /// Return entries after this id (for incremental sync)
+    after: Option<i64>,
+    limit: Option<usize>,
+}
+
 /// Serve the agent registration file at /.well-known/agent-registration.json
 /// This is a PUBLIC endpoint (no auth) per EIP-8004 for domain verification.
-/// Reads identity from the database (single source of truth).
+/// Reads identity from the database (single source of truth), plus the
+/// score aggregated from signed feedback.
 async fn agent_registration(state: web::Data<AppState>) -> impl Responder {
     match state.db.get_agent_identity_full() {
         Some(row) => {
-            let reg = row.to_registration_file();
+            let reg = crate::eip8004::feedback::registration_file(&state.db, &row);
             HttpResponse::Ok()
                 .content_type("application/json")
                 .json(reg)
@@ -21,9 +31,58 @@ async fn agent_registration(state: web::Data<AppState>) -> impl Responder {
     }
 }
 
+/// Serve the action receipts feed at /.well-known/agent-receipts.json
+/// PUBLIC endpoint, only when the receipts log is enabled. Entries not yet
+/// signed are signed with the agent wallet before being served.
+async fn agent_receipts(state: web::Data<AppState>, query: web::Query<ReceiptsQuery>) -> impl Responder {
+    if !receipts::is_enabled(&state.db) {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Action receipts are not published by this agent"
+        }));
+    }
+    let Some(identity) = state.db.get_agent_identity_full() else {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Agent registration not configured"
+        }));
+    };
+
+    let mut signer = None;
+    if let Some(ref wallet) = state.wallet_provider {
+        if let Err(e) = receipts::sign_pending(&state.db, wallet.as_ref()).await {
+            log::warn!("[RECEIPTS] Failed to sign pending receipts: {}", e);
+        }
+        signer = Some(wallet.get_address().to_lowercase());
+    }
+
+    let (total, head) = match state.db.action_receipts_head() {
+        Ok(h) => h,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }));
+        }
+    };
+    let limit = query.limit.unwrap_or(100).min(500);
+    let entries = match state.db.list_action_receipts(query.after.unwrap_or(0), limit) {
+        Ok(e) => e,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }));
+        }
+    };
+
+    HttpResponse::Ok().json(ReceiptFeed {
+        agent_id: identity.agent_id,
+        agent_registry: identity.agent_registry,
+        signer,
+        head,
+        total,
+        scheme: FEED_SCHEME,
+        entries,
+    })
+}
+
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/.well-known")
-            .route("/agent-registration.json", web::get().to(agent_registration)),
+            .route("/agent-registration.json", web::get().to(agent_registration))
+            .route("/agent-receipts.json", web::get().to(agent_receipts)),
     );
 }
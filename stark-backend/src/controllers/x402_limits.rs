@@ -1,5 +1,6 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
+use crate::controllers::validation::{Valid, Validate, Validator};
 use crate::AppState;
 use crate::x402::payment_limits;
 
@@ -12,7 +13,8 @@ fn validate_session(
         .headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string());
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string);
 
     let token = match token {
         Some(t) => t,
@@ -23,7 +25,7 @@ fn validate_session(
         }
     };
 
-    match state.db.validate_session(&token) {
+    match crate::middleware::session_auth::validate_token(&state.db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "error": "Invalid or expired session"
@@ -79,27 +81,35 @@ fn default_decimals() -> u8 {
     6
 }
 
+impl Validate for UpdateLimitRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.not_blank("asset", &self.asset);
+        v.max_len("asset", &self.asset, 16);
+        v.uint_string("max_amount", &self.max_amount);
+        v.range("decimals", self.decimals, 0, 36);
+        if let Some(address) = self.address.as_deref().filter(|a| !a.trim().is_empty()) {
+            v.address("address", address);
+        }
+    }
+}
+
 /// PUT /api/x402-limits — update a single payment limit
 pub async fn update_x402_limit(
     state: web::Data<AppState>,
     req: HttpRequest,
-    body: web::Json<UpdateLimitRequest>,
+    body: Valid<UpdateLimitRequest>,
 ) -> impl Responder {
     if let Err(resp) = validate_session(&state, &req) {
         return resp;
     }
+    if let Err(resp) = super::require_second_factor(&state, &req) {
+        return resp;
+    }
 
     let r = body.into_inner();
     let asset = r.asset.to_uppercase();
     let display_name = r.display_name.unwrap_or_else(|| asset.clone());
 
-    // Validate max_amount is a valid integer
-    if r.max_amount.parse::<u128>().is_err() {
-        return HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "max_amount must be a valid non-negative integer string"
-        }));
-    }
-
     // Persist to DB
     if let Err(e) = state.db.set_x402_payment_limit(&asset, &r.max_amount, r.decimals, &display_name, r.address.as_deref()) {
         log::error!("Failed to save x402 payment limit: {}", e);
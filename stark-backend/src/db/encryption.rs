@@ -0,0 +1,518 @@
+//! Optional at-rest encryption for sensitive text columns
+//!
+//! When `STARK_DB_ENCRYPTION_KEY` is set, session message content is stored
+//! encrypted with AES-256-GCM using a key derived from that master secret.
+//! Memory content is encrypted too when `STARK_DB_ENCRYPT_MEMORIES=true`
+//! (off by default, since encrypted memories no longer match keyword search).
+//!
+//! The same cipher covers trashed records, the 2FA secret, queued away-mode
+//! requests and replayable idempotent replies. Encryption failures are errors:
+//! the write is aborted rather than falling back to plaintext.
+//!
+//! Encrypted values look like `enc1:<key_id>:<base64(nonce || ciphertext)>`.
+//! Anything without that prefix is treated as plaintext, so existing rows keep
+//! working and can be migrated later with `stark-backend --encrypt-db`.
+//!
+//! Key rotation: set the new secret as `STARK_DB_ENCRYPTION_KEY`, move the old
+//! one into `STARK_DB_ENCRYPTION_PREVIOUS_KEYS` (comma-separated), then run
+//! `--encrypt-db` to re-encrypt every row under the new key.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+
+pub const ENCRYPTION_KEY_ENV: &str = "STARK_DB_ENCRYPTION_KEY";
+pub const PREVIOUS_KEYS_ENV: &str = "STARK_DB_ENCRYPTION_PREVIOUS_KEYS";
+pub const ENCRYPT_MEMORIES_ENV: &str = "STARK_DB_ENCRYPT_MEMORIES";
+
+const PREFIX: &str = "enc1:";
+const KDF_INFO: &[u8] = b"stark-bot/db-field-encryption/v1";
+const NONCE_LEN: usize = 12;
+
+static CIPHER: Lazy<Option<FieldCipher>> = Lazy::new(FieldCipher::from_env);
+
+struct DerivedKey {
+    id: String,
+    cipher: Aes256Gcm,
+}
+
+impl DerivedKey {
+    fn derive(secret: &str) -> Self {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(KDF_INFO);
+        let key_bytes = mac.finalize().into_bytes();
+        let id = hex::encode(&Sha256::digest(key_bytes)[..4]);
+        let cipher = Aes256Gcm::new_from_slice(&key_bytes).expect("derived key is 32 bytes");
+        Self { id, cipher }
+    }
+}
+
+/// Encrypts and decrypts column values with the current key, and decrypts
+/// values written under any previous key.
+pub struct FieldCipher {
+    current: DerivedKey,
+    previous: Vec<DerivedKey>,
+    encrypt_memories: bool,
+}
+
+impl FieldCipher {
+    pub fn new(secret: &str, previous_secrets: &[&str], encrypt_memories: bool) -> Self {
+        Self {
+            current: DerivedKey::derive(secret),
+            previous: previous_secrets
+                .iter()
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| DerivedKey::derive(s.trim()))
+                .collect(),
+            encrypt_memories,
+        }
+    }
+
+    fn from_env() -> Option<Self> {
+        let secret = std::env::var(ENCRYPTION_KEY_ENV).ok().filter(|s| !s.trim().is_empty())?;
+        let previous = std::env::var(PREVIOUS_KEYS_ENV).unwrap_or_default();
+        let previous: Vec<&str> = previous.split(',').collect();
+        let encrypt_memories = std::env::var(ENCRYPT_MEMORIES_ENV)
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+        let cipher = Self::new(secret.trim(), &previous, encrypt_memories);
+        log::info!(
+            "[DB_ENCRYPTION] At-rest encryption enabled (key {}, {} previous key(s), memories: {})",
+            cipher.current.id,
+            cipher.previous.len(),
+            encrypt_memories
+        );
+        Some(cipher)
+    }
+
+    /// ID of the key new values are encrypted with
+    pub fn current_key_id(&self) -> &str {
+        &self.current.id
+    }
+
+    /// Encrypt a value under the current key
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        let nonce_bytes: [u8; NONCE_LEN] = rand::random();
+        let ciphertext = self
+            .current
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_bytes())
+            .map_err(|e| format!("Encryption failed with key {}: {}", self.current.id, e))?;
+        let mut blob = nonce_bytes.to_vec();
+        blob.extend_from_slice(&ciphertext);
+        Ok(format!(
+            "{}{}:{}",
+            PREFIX,
+            self.current.id,
+            base64::engine::general_purpose::STANDARD.encode(blob)
+        ))
+    }
+
+    /// Decrypt a stored value. Plaintext values pass through unchanged.
+    pub fn decrypt(&self, stored: &str) -> Result<String, String> {
+        let Some(rest) = stored.strip_prefix(PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let (key_id, encoded) = rest.split_once(':').ok_or("Malformed encrypted value")?;
+        let key = std::iter::once(&self.current)
+            .chain(self.previous.iter())
+            .find(|k| k.id == key_id)
+            .ok_or_else(|| format!("No key available for key id {}", key_id))?;
+        let blob = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("Invalid base64: {}", e))?;
+        if blob.len() < NONCE_LEN {
+            return Err("Encrypted value is truncated".to_string());
+        }
+        let (nonce, ciphertext) = blob.split_at(NONCE_LEN);
+        let plaintext = key
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| format!("Decryption failed with key {}", key_id))?;
+        String::from_utf8(plaintext).map_err(|e| format!("Decrypted value is not UTF-8: {}", e))
+    }
+
+    /// Whether a stored value should be (re-)encrypted under the current key
+    pub fn needs_rewrite(&self, stored: &str) -> bool {
+        match stored.strip_prefix(PREFIX).and_then(|rest| rest.split_once(':')) {
+            Some((key_id, _)) => key_id != self.current.id,
+            None => true,
+        }
+    }
+}
+
+/// Whether a stored value is in the encrypted format
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(PREFIX)
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_CIPHER: std::cell::Cell<Option<&'static FieldCipher>> = const { std::cell::Cell::new(None) };
+}
+
+/// The process-wide cipher, if encryption is configured
+pub fn cipher() -> Option<&'static FieldCipher> {
+    #[cfg(test)]
+    if let Some(c) = TEST_CIPHER.with(|c| c.get()) {
+        return Some(c);
+    }
+    CIPHER.as_ref()
+}
+
+/// Stand in for the configured cipher on the current thread
+#[cfg(test)]
+pub fn set_test_cipher(cipher: FieldCipher) {
+    TEST_CIPHER.with(|c| c.set(Some(Box::leak(Box::new(cipher)))));
+}
+
+/// A failed encryption as a database error, so the write is aborted rather
+/// than storing plaintext
+fn encryption_error(e: String) -> rusqlite::Error {
+    rusqlite::Error::ToSqlConversionFailure(e.into())
+}
+
+/// Prepare session message content (and other always-encrypted values) for storage
+pub fn encrypt_message(plaintext: &str) -> rusqlite::Result<String> {
+    match cipher() {
+        Some(c) => c.encrypt(plaintext).map_err(encryption_error),
+        None => Ok(plaintext.to_string()),
+    }
+}
+
+/// Prepare memory content for storage
+pub fn encrypt_memory(plaintext: &str) -> rusqlite::Result<String> {
+    match cipher() {
+        Some(c) if c.encrypt_memories => c.encrypt(plaintext).map_err(encryption_error),
+        _ => Ok(plaintext.to_string()),
+    }
+}
+
+/// Decrypt a stored column value. Plaintext passes through; values that can't
+/// be decrypted (e.g. missing key) are returned as stored rather than lost.
+pub fn decrypt_field(stored: String) -> String {
+    if !stored.starts_with(PREFIX) {
+        return stored;
+    }
+    let Some(c) = cipher() else {
+        log::warn!("[DB_ENCRYPTION] Found encrypted value but {} is not set", ENCRYPTION_KEY_ENV);
+        return stored;
+    };
+    match c.decrypt(&stored) {
+        Ok(plain) => plain,
+        Err(e) => {
+            log::warn!("[DB_ENCRYPTION] {}", e);
+            stored
+        }
+    }
+}
+
+/// Columns holding values written with `encrypt_message`, besides the trash
+/// payloads: (table, column)
+const MESSAGE_COLUMNS: &[(&str, &str)] = &[
+    ("session_messages", "content"),
+    ("session_messages", "tool_call"),
+    ("away_requests", "text"),
+    ("two_factor", "secret"),
+    ("idempotency_keys", "response"),
+];
+
+/// Rows rewritten by `encrypt_existing_rows`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RewrittenRows {
+    pub messages: usize,
+    /// Memories and the identity profile values learned from them
+    pub memories: usize,
+    /// Trashed records (`deleted_records` payloads)
+    pub trash: usize,
+    /// Away requests, 2FA secret, tool calls and idempotent replies
+    pub other: usize,
+}
+
+/// What `stored` should become: encrypted under the current key when
+/// `encrypt` is set, plaintext otherwise. None if it is fine as it is or
+/// can't be decrypted (logged and left alone).
+fn rewrite_value(cipher: &FieldCipher, stored: &str, encrypt: bool, what: &str) -> Result<Option<String>, String> {
+    let should_rewrite = if encrypt { cipher.needs_rewrite(stored) } else { is_encrypted(stored) };
+    if !should_rewrite {
+        return Ok(None);
+    }
+    let plain = match cipher.decrypt(stored) {
+        Ok(p) => p,
+        Err(e) => {
+            log::warn!("[DB_ENCRYPTION] Skipping {}: {}", what, e);
+            return Ok(None);
+        }
+    };
+    Ok(Some(if encrypt { cipher.encrypt(&plain)? } else { plain }))
+}
+
+/// Run `rewrite` over every non-null `column` value in `table` and store what
+/// it returns. An error (failed encryption) aborts the whole table.
+fn rewrite_values(
+    conn: &rusqlite::Connection,
+    table: &str,
+    column: &str,
+    rewrite: impl Fn(&str, &str) -> Result<Option<String>, String>,
+) -> rusqlite::Result<usize> {
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = conn.prepare(&format!("SELECT rowid, {column} FROM {table} WHERE {column} IS NOT NULL"))?;
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect()
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    let mut rewritten = 0;
+    {
+        let mut update = tx.prepare(&format!("UPDATE {table} SET {column} = ?1 WHERE rowid = ?2"))?;
+        for (rowid, stored) in rows {
+            let what = format!("{}.{} row {}", table, column, rowid);
+            if let Some(value) = rewrite(&stored, &what).map_err(encryption_error)? {
+                update.execute(rusqlite::params![value, rowid])?;
+                rewritten += 1;
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(rewritten)
+}
+
+fn rewrite_column(
+    conn: &rusqlite::Connection,
+    table: &str,
+    column: &str,
+    cipher: &FieldCipher,
+    encrypt: bool,
+) -> rusqlite::Result<usize> {
+    rewrite_values(conn, table, column, |stored, what| rewrite_value(cipher, stored, encrypt, what))
+}
+
+/// Re-key a trash payload: the snapshotted message and memory values inside
+/// it, then the payload itself
+fn rewrite_trash_payload(cipher: &FieldCipher, stored: &str, what: &str) -> Result<Option<String>, String> {
+    let plain = match cipher.decrypt(stored) {
+        Ok(p) => p,
+        Err(e) => {
+            log::warn!("[DB_ENCRYPTION] Skipping {}: {}", what, e);
+            return Ok(None);
+        }
+    };
+    let mut groups: Vec<serde_json::Value> = match serde_json::from_str(&plain) {
+        Ok(groups) => groups,
+        Err(e) => {
+            log::warn!("[DB_ENCRYPTION] Skipping {}: unreadable payload: {}", what, e);
+            return Ok(None);
+        }
+    };
+
+    let mut changed = cipher.needs_rewrite(stored);
+    for group in &mut groups {
+        let (columns, encrypt): (&[&str], bool) = match group.get("table").and_then(|t| t.as_str()) {
+            Some("session_messages") => (&["content", "tool_call"], true),
+            Some("memories") => (&["content"], cipher.encrypt_memories),
+            _ => continue,
+        };
+        let rows = group.get_mut("rows").and_then(|r| r.as_array_mut());
+        for row in rows.into_iter().flatten() {
+            for column in columns {
+                let Some(value) = row.get_mut(*column) else { continue };
+                let Some(inner) = value.as_str() else { continue };
+                if let Some(new) = rewrite_value(cipher, inner, encrypt, what)? {
+                    *value = serde_json::Value::String(new);
+                    changed = true;
+                }
+            }
+        }
+    }
+    if !changed {
+        return Ok(None);
+    }
+    cipher.encrypt(&serde_json::Value::Array(groups).to_string()).map(Some)
+}
+
+impl super::Database {
+    /// Bring existing rows in line with the configured cipher: encrypt plaintext
+    /// rows, re-encrypt rows written under a previous key, and decrypt memories
+    /// if memory encryption has been turned off. Covers message content, memories
+    /// and identity profiles, trashed records and every other column written
+    /// with `encrypt_message`.
+    pub fn encrypt_existing_rows(&self, cipher: &FieldCipher) -> rusqlite::Result<RewrittenRows> {
+        let conn = self.conn();
+        let mut memories = rewrite_column(&conn, "memories", "content", cipher, cipher.encrypt_memories)?;
+        for column in crate::db::tables::identity_profiles::ENCRYPTED_COLUMNS {
+            memories += rewrite_column(&conn, "identity_profiles", column, cipher, cipher.encrypt_memories)?;
+        }
+        let mut rewritten = RewrittenRows {
+            memories,
+            trash: rewrite_values(&conn, "deleted_records", "payload", |stored, what| {
+                rewrite_trash_payload(cipher, stored, what)
+            })?,
+            ..Default::default()
+        };
+        for (table, column) in MESSAGE_COLUMNS {
+            let count = rewrite_column(&conn, table, column, cipher, true)?;
+            if (*table, *column) == ("session_messages", "content") {
+                rewritten.messages = count;
+            } else {
+                rewritten.other += count;
+            }
+        }
+        Ok(rewritten)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_and_plaintext_passthrough() {
+        let c = FieldCipher::new("master-secret", &[], false);
+        let stored = c.encrypt("send 5 ETH to alice").unwrap();
+        assert!(stored.starts_with("enc1:"));
+        assert!(!stored.contains("alice"));
+        assert_eq!(c.decrypt(&stored).unwrap(), "send 5 ETH to alice");
+        assert_eq!(c.decrypt("legacy plaintext").unwrap(), "legacy plaintext");
+        // Random nonce per value
+        assert_ne!(c.encrypt("same").unwrap(), c.encrypt("same").unwrap());
+    }
+
+    #[test]
+    fn test_rotation_reads_old_key() {
+        let old = FieldCipher::new("old-secret", &[], false);
+        let stored = old.encrypt("hello").unwrap();
+
+        let rotated = FieldCipher::new("new-secret", &["old-secret"], false);
+        assert_eq!(rotated.decrypt(&stored).unwrap(), "hello");
+        assert!(rotated.needs_rewrite(&stored));
+        assert!(rotated.needs_rewrite("plaintext"));
+        assert!(!rotated.needs_rewrite(&rotated.encrypt("hello").unwrap()));
+
+        let unrelated = FieldCipher::new("other", &[], false);
+        assert!(unrelated.decrypt(&stored).is_err());
+    }
+
+    #[test]
+    fn test_tampered_value_fails() {
+        let c = FieldCipher::new("k", &[], false);
+        let mut stored = c.encrypt("hello").unwrap();
+        let last = stored.pop().unwrap();
+        stored.push(if last == 'A' { 'B' } else { 'A' });
+        assert!(c.decrypt(&stored).is_err());
+    }
+
+    #[test]
+    fn test_encrypt_existing_rows() {
+        let db = crate::db::Database::new(":memory:").unwrap();
+        let id = db
+            .insert_memory("fact", "plain memory", None, None, 5, None, None, None, None, None, None, None)
+            .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO chat_sessions (session_key, channel_type, channel_id, platform_chat_id,
+                        created_at, updated_at, last_activity_at)
+                 VALUES ('k', 'web', 0, 'c', 'now', 'now', 'now')",
+                [],
+            )
+            .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO session_messages (session_id, role, content, created_at)
+                 VALUES (1, 'user', 'secret plan', 'now')",
+                [],
+            )
+            .unwrap();
+
+        db.conn()
+            .execute(
+                "INSERT INTO idempotency_keys (scope, key, status, response, created_at, expires_at)
+                 VALUES ('http', 'k', 'completed', 'cached reply', 'now', 'later')",
+                [],
+            )
+            .unwrap();
+        // Trash a second message; its snapshot holds the plaintext content
+        db.conn()
+            .execute(
+                "INSERT INTO chat_sessions (session_key, channel_type, channel_id, platform_chat_id,
+                        created_at, updated_at, last_activity_at)
+                 VALUES ('k2', 'web', 0, 'c2', 'now', 'now', 'now')",
+                [],
+            )
+            .unwrap();
+        db.conn()
+            .execute(
+                "INSERT INTO session_messages (session_id, role, content, created_at)
+                 VALUES (2, 'user', 'trashed plan', 'now')",
+                [],
+            )
+            .unwrap();
+        db.soft_delete_chat_session(2).unwrap().unwrap();
+        // Timezone plus the three JSON columns
+        let profile = crate::db::tables::identity_profiles::IdentityProfile {
+            identity_id: "id-1".to_string(),
+            timezone: Some("Europe/Berlin".to_string()),
+            ..Default::default()
+        };
+        db.save_identity_profile(&profile).unwrap();
+
+        let c = FieldCipher::new("k1", &[], true);
+        let rewritten = db.encrypt_existing_rows(&c).unwrap();
+        assert_eq!(
+            rewritten,
+            RewrittenRows { messages: 1, memories: 5, trash: 1, other: 1 }
+        );
+        // Idempotent under the same key
+        assert_eq!(db.encrypt_existing_rows(&c).unwrap(), RewrittenRows::default());
+
+        let payload: String = db
+            .conn()
+            .query_row("SELECT payload FROM deleted_records", [], |r| r.get(0))
+            .unwrap();
+        assert!(is_encrypted(&payload));
+        let inner = c.decrypt(&payload).unwrap();
+        assert!(!inner.contains("trashed plan"));
+        let reply: String = db
+            .conn()
+            .query_row("SELECT response FROM idempotency_keys", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(c.decrypt(&reply).unwrap(), "cached reply");
+
+        let stored: String = db
+            .conn()
+            .query_row("SELECT content FROM memories WHERE id = ?1", [id], |r| r.get(0))
+            .unwrap();
+        assert!(is_encrypted(&stored));
+        assert_eq!(c.decrypt(&stored).unwrap(), "plain memory");
+        let timezone: String = db
+            .conn()
+            .query_row("SELECT timezone FROM identity_profiles", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(c.decrypt(&timezone).unwrap(), "Europe/Berlin");
+
+        // Rotate, with memory encryption turned off
+        let rotated = FieldCipher::new("k2", &["k1"], false);
+        assert_eq!(
+            db.encrypt_existing_rows(&rotated).unwrap(),
+            RewrittenRows { messages: 1, memories: 5, trash: 1, other: 1 }
+        );
+        // Nothing in the trash is left under the old key
+        let payload: String = db
+            .conn()
+            .query_row("SELECT payload FROM deleted_records", [], |r| r.get(0))
+            .unwrap();
+        let inner = FieldCipher::new("k2", &[], false).decrypt(&payload).unwrap();
+        let groups: Vec<serde_json::Value> = serde_json::from_str(&inner).unwrap();
+        let content = groups[1]["rows"][0]["content"].as_str().unwrap();
+        assert_eq!(FieldCipher::new("k2", &[], false).decrypt(content).unwrap(), "trashed plan");
+        let stored: String = db
+            .conn()
+            .query_row("SELECT content FROM memories WHERE id = ?1", [id], |r| r.get(0))
+            .unwrap();
+        assert_eq!(stored, "plain memory");
+    }
+}
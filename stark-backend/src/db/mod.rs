@@ -1,5 +1,6 @@
 pub mod active_session_cache;
 pub mod cache;
+pub mod encryption;
 pub mod sqlite;
 pub mod tables;
 
@@ -109,6 +109,52 @@ impl Database {
             [],
         )?;
 
+        // Migration: when the session last passed a second-factor check
+        let _ = conn.execute("ALTER TABLE auth_sessions ADD COLUMN second_factor_at TEXT", []);
+
+        // Migration: device info and rotating refresh tokens for auth sessions
+        let _ = conn.execute("ALTER TABLE auth_sessions ADD COLUMN user_agent TEXT", []);
+        let _ = conn.execute("ALTER TABLE auth_sessions ADD COLUMN ip_address TEXT", []);
+        let _ = conn.execute("ALTER TABLE auth_sessions ADD COLUMN last_active_at TEXT", []);
+        let _ = conn.execute("ALTER TABLE auth_sessions ADD COLUMN refresh_token_hash TEXT", []);
+        let _ = conn.execute("ALTER TABLE auth_sessions ADD COLUMN previous_refresh_token_hash TEXT", []);
+        let _ = conn.execute("ALTER TABLE auth_sessions ADD COLUMN refresh_expires_at TEXT", []);
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_auth_sessions_refresh ON auth_sessions(refresh_token_hash)",
+            [],
+        )?;
+
+        // TOTP second factor (single row) and its hashed recovery codes
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS two_factor (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                secret TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 0,
+                last_used_step INTEGER,
+                created_at TEXT NOT NULL,
+                enabled_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS two_factor_recovery_codes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                code_hash TEXT UNIQUE NOT NULL,
+                used_at TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        // Failed code counters per session ("session:<id>") and for the account
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS two_factor_failures (
+                subject TEXT PRIMARY KEY,
+                failures INTEGER NOT NULL DEFAULT 0,
+                locked_until TEXT
+            )",
+            [],
+        )?;
+
         // Auth challenges table for SIWE
         conn.execute(
             "CREATE TABLE IF NOT EXISTS auth_challenges (
@@ -519,6 +565,8 @@ impl Database {
         let _ = conn.execute("ALTER TABLE chat_sessions ADD COLUMN safe_mode INTEGER NOT NULL DEFAULT 0", []);
         // Special role: Track which special role (if any) enriched this safe-mode session
         let _ = conn.execute("ALTER TABLE chat_sessions ADD COLUMN special_role_name TEXT", []);
+        // Response style preset chosen for this session (NULL = channel default)
+        let _ = conn.execute("ALTER TABLE chat_sessions ADD COLUMN response_style TEXT", []);
 
         // Session messages table - conversation transcripts
         conn.execute(
@@ -537,6 +585,20 @@ impl Database {
             [],
         )?;
 
+        // Attachment references (JSON array of MessageAttachment) on session messages
+        let _ = conn.execute("ALTER TABLE session_messages ADD COLUMN attachments TEXT", []);
+
+        // Structured tool call / result (JSON ToolCallRecord); tool_call_id pairs a call with its result
+        let _ = conn.execute("ALTER TABLE session_messages ADD COLUMN tool_call TEXT", []);
+        let _ = conn.execute("ALTER TABLE session_messages ADD COLUMN tool_call_id TEXT", []);
+        // Triage tags on user messages (see channels::triage)
+        let _ = conn.execute("ALTER TABLE session_messages ADD COLUMN sentiment TEXT", []);
+        let _ = conn.execute("ALTER TABLE session_messages ADD COLUMN urgency TEXT", []);
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_session_messages_tool_call ON session_messages(session_id, tool_call_id)",
+            [],
+        )?;
+
         // Telegram chat messages - passive log of ALL messages in Telegram chats
         // Independent of session system, used by telegram_read readHistory
         conn.execute(
@@ -609,6 +671,8 @@ impl Database {
         let _ = conn.execute("ALTER TABLE memories ADD COLUMN valid_from TEXT", []);
         let _ = conn.execute("ALTER TABLE memories ADD COLUMN valid_until TEXT", []);
         let _ = conn.execute("ALTER TABLE memories ADD COLUMN agent_subtype TEXT", []);
+        // Notion page ID / Obsidian vault path the memory is mirrored to
+        let _ = conn.execute("ALTER TABLE memories ADD COLUMN external_page_id TEXT", []);
 
         // FTS5 virtual table for full-text search on memories
         conn.execute(
@@ -743,6 +807,9 @@ impl Database {
         // Migration: Add requires_api_keys column to skills if it doesn't exist
         let _ = conn.execute("ALTER TABLE skills ADD COLUMN requires_api_keys TEXT NOT NULL DEFAULT '{}'", []);
 
+        // Migration: Add finance_permissions column to skills if it doesn't exist
+        let _ = conn.execute("ALTER TABLE skills ADD COLUMN finance_permissions TEXT NOT NULL DEFAULT '[]'", []);
+
         // Skill scripts table (Python/Bash scripts bundled with skills)
         conn.execute(
             "CREATE TABLE IF NOT EXISTS skill_scripts (
@@ -949,6 +1016,88 @@ impl Database {
             "ALTER TABLE heartbeat_configs ADD COLUMN last_session_id INTEGER",
             [],
         );
+        // Migration: self-maintenance jobs (JSON list of MaintenanceJobConfig)
+        let _ = conn.execute(
+            "ALTER TABLE heartbeat_configs ADD COLUMN maintenance_jobs TEXT",
+            [],
+        );
+
+        // Self-maintenance job runs (reported by the services health endpoint)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS maintenance_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                job TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL DEFAULT 0,
+                success INTEGER NOT NULL,
+                summary TEXT NOT NULL DEFAULT '',
+                error TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_maintenance_runs_job ON maintenance_runs(job, id DESC)",
+            [],
+        )?;
+
+        // Event bus subscriptions registered by modules and skills
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS event_subscriptions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                subscriber TEXT NOT NULL,
+                events TEXT NOT NULL DEFAULT '[]',
+                callback_url TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                failure_count INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                last_delivered_at TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Requests held back while the owner is away, answered when away mode ends
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS away_requests (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id INTEGER NOT NULL,
+                channel_type TEXT NOT NULL,
+                chat_id TEXT NOT NULL,
+                chat_name TEXT,
+                user_id TEXT NOT NULL,
+                user_name TEXT NOT NULL,
+                text TEXT NOT NULL,
+                message_id TEXT,
+                force_safe_mode INTEGER NOT NULL DEFAULT 1,
+                shared_chat INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Chats handed over to a human operator; the agent stays quiet until released
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS handoffs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id INTEGER,
+                channel_id INTEGER NOT NULL,
+                channel_type TEXT NOT NULL,
+                chat_id TEXT NOT NULL,
+                user_name TEXT,
+                reason TEXT NOT NULL,
+                requested_by TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'waiting',
+                operator TEXT,
+                created_at TEXT NOT NULL,
+                joined_at TEXT,
+                released_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_handoffs_chat ON handoffs(channel_id, chat_id, status)",
+            [],
+        )?;
 
         // Gmail integration configuration
         conn.execute(
@@ -972,6 +1121,18 @@ impl Database {
             [],
         )?;
 
+        // Migration: recipients the agent may email without a review draft
+        let _ = conn.execute(
+            "ALTER TABLE gmail_configs ADD COLUMN send_allowlist TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Migration: hold auto-replies in the outbox until approved
+        let _ = conn.execute(
+            "ALTER TABLE gmail_configs ADD COLUMN review_before_send INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
         // =====================================================
         // EIP-8004 Tables (Trustless Agents)
         // =====================================================
@@ -1144,6 +1305,13 @@ impl Database {
             [],
         )?;
 
+        // Free-text comment of signed feedback received at /api/feedback
+        let _ = conn.execute("ALTER TABLE reputation_feedback ADD COLUMN comment TEXT", []);
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_reputation_feedback_hash ON reputation_feedback(feedback_hash)",
+            [],
+        )?;
+
         // Known agents (discovered from registry)
         conn.execute(
             "CREATE TABLE IF NOT EXISTS known_agents (
@@ -1449,7 +1617,8 @@ impl Database {
                 author TEXT,
                 sha256_checksum TEXT,
                 installed_at TEXT NOT NULL DEFAULT (datetime('now')),
-                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                binary_sha256 TEXT
             )",
             [],
         )?;
@@ -1464,6 +1633,7 @@ impl Database {
             "binary_path TEXT",
             "author TEXT",
             "sha256_checksum TEXT",
+            "binary_sha256 TEXT",
         ] {
             let _ = conn.execute(
                 &format!("ALTER TABLE installed_modules ADD COLUMN {}", col),
@@ -1815,6 +1985,1077 @@ impl Database {
             [],
         );
 
+        // Alert rules - user-editable conditions over operational events
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS alert_rules (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                description TEXT,
+                condition_json TEXT NOT NULL,
+                actions_json TEXT NOT NULL DEFAULT '[]',
+                cooldown_minutes INTEGER NOT NULL DEFAULT 30,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                last_fired_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Alert events - history of fired alert rules
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS alert_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                rule_id INTEGER NOT NULL,
+                rule_name TEXT NOT NULL,
+                message TEXT NOT NULL,
+                value REAL NOT NULL DEFAULT 0,
+                actions_taken TEXT NOT NULL DEFAULT '[]',
+                fired_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_alert_events_rule ON alert_events(rule_id, fired_at)",
+            [],
+        )?;
+
+        // Retention policies - per-scope auto-purge windows (session_messages, memory:<type>, trash)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS retention_policies (
+                scope TEXT PRIMARY KEY,
+                retention_days INTEGER NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Soft-deleted records - row snapshots kept until their grace period expires
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS deleted_records (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                record_type TEXT NOT NULL,
+                record_id INTEGER NOT NULL,
+                label TEXT,
+                subject_ids TEXT NOT NULL DEFAULT '[]',
+                payload TEXT NOT NULL,
+                deleted_at TEXT NOT NULL,
+                purge_after TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_deleted_records_purge ON deleted_records(purge_after)",
+            [],
+        )?;
+
+        // Forget-identity audit log (what was erased, never the erased content itself)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS forget_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                identity_id TEXT NOT NULL,
+                requested_by TEXT,
+                memories_deleted INTEGER NOT NULL DEFAULT 0,
+                embeddings_deleted INTEGER NOT NULL DEFAULT 0,
+                sessions_deleted INTEGER NOT NULL DEFAULT 0,
+                messages_deleted INTEGER NOT NULL DEFAULT 0,
+                links_deleted INTEGER NOT NULL DEFAULT 0,
+                trash_purged INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Idempotency keys - dedupe redelivered inbound messages and retried API calls
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS idempotency_keys (
+                scope TEXT NOT NULL,
+                key TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                response TEXT,
+                message_id TEXT,
+                created_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL,
+                PRIMARY KEY (scope, key)
+            )",
+            [],
+        )?;
+        let _ = conn.execute("ALTER TABLE idempotency_keys ADD COLUMN request_hash TEXT", []);
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires ON idempotency_keys(expires_at)",
+            [],
+        )?;
+
+        // Cluster leases (leader election + distributed execution locks across instances)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cluster_leases (
+                name TEXT PRIMARY KEY,
+                holder TEXT NOT NULL,
+                acquired_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Cluster instances (liveness registry of backend instances sharing this DB)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cluster_instances (
+                instance_id TEXT PRIMARY KEY,
+                hostname TEXT NOT NULL,
+                pid INTEGER NOT NULL,
+                version TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                last_seen TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Outbound delivery queue (channel replies that failed to send, retried in order per chat)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS outbound_messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id INTEGER NOT NULL,
+                channel_type TEXT NOT NULL,
+                chat_id TEXT NOT NULL,
+                reply_to TEXT,
+                content TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                next_attempt_at TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                sent_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_outbound_messages_chat ON outbound_messages(channel_id, chat_id, status)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_outbound_messages_status ON outbound_messages(status, next_attempt_at)",
+            [],
+        )?;
+
+        // Proactive messages sent per channel (daily limits for unsolicited messages)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS proactive_messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id INTEGER NOT NULL,
+                chat_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_proactive_messages_channel ON proactive_messages(channel_id, created_at)",
+            [],
+        )?;
+
+        // Interactive buttons attached to outgoing channel messages (callback token -> action)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS message_actions (
+                token TEXT PRIMARY KEY,
+                group_id TEXT NOT NULL,
+                channel_id INTEGER NOT NULL,
+                chat_id TEXT NOT NULL,
+                label TEXT NOT NULL,
+                action TEXT NOT NULL,
+                payload TEXT,
+                created_at TEXT NOT NULL,
+                used_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_message_actions_group ON message_actions(group_id)",
+            [],
+        )?;
+
+        // Chat approval policy for queued transactions
+        let _ = conn.execute("ALTER TABLE bot_settings ADD COLUMN tx_approval_threshold_eth REAL", []);
+        let _ = conn.execute("ALTER TABLE bot_settings ADD COLUMN tx_approval_channel_id INTEGER", []);
+        let _ = conn.execute("ALTER TABLE bot_settings ADD COLUMN tx_approval_chat_id TEXT", []);
+        let _ = conn.execute(
+            "ALTER TABLE bot_settings ADD COLUMN tx_approval_ttl_secs INTEGER NOT NULL DEFAULT 900",
+            [],
+        );
+
+        // Owner approvals for queued transactions (also the audit log of decisions)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tx_approvals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tx_uuid TEXT NOT NULL UNIQUE,
+                channel_id INTEGER NOT NULL,
+                chat_id TEXT NOT NULL,
+                network TEXT NOT NULL,
+                to_address TEXT NOT NULL,
+                value_wei TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                requested_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL,
+                decided_at TEXT,
+                decided_by TEXT,
+                tx_hash TEXT,
+                error TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tx_approvals_status ON tx_approvals(status, expires_at)",
+            [],
+        )?;
+
+        // Treasury Safe (multisig) that transactions can be proposed to
+        let _ = conn.execute("ALTER TABLE bot_settings ADD COLUMN safe_address TEXT", []);
+        let _ = conn.execute("ALTER TABLE bot_settings ADD COLUMN safe_network TEXT", []);
+
+        // Global paper trading switch (channels can also opt in individually)
+        let _ = conn.execute(
+            "ALTER TABLE bot_settings ADD COLUMN paper_trading_enabled INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Safe transactions proposed by the bot, tracked until executed or replaced
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS safe_proposals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                safe_tx_hash TEXT NOT NULL UNIQUE,
+                safe_address TEXT NOT NULL,
+                network TEXT NOT NULL,
+                to_address TEXT NOT NULL,
+                value_wei TEXT NOT NULL,
+                data TEXT NOT NULL,
+                nonce INTEGER NOT NULL,
+                description TEXT NOT NULL,
+                channel_id INTEGER,
+                chat_id TEXT,
+                confirmations INTEGER NOT NULL DEFAULT 1,
+                threshold INTEGER NOT NULL,
+                confirmed_by TEXT NOT NULL DEFAULT '[]',
+                status TEXT NOT NULL DEFAULT 'pending',
+                tx_hash TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_safe_proposals_status ON safe_proposals(status)",
+            [],
+        )?;
+
+        // Bridges queued by bridge_usdc, tracked until the funds land on the destination chain
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS bridge_transfers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                bridge_tx_uuid TEXT NOT NULL UNIQUE,
+                from_network TEXT NOT NULL,
+                to_network TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                amount_raw TEXT NOT NULL,
+                min_output_raw TEXT NOT NULL,
+                recipient TEXT NOT NULL,
+                dest_token TEXT NOT NULL,
+                dest_start_block INTEGER,
+                source_tx_hash TEXT,
+                dest_tx_hash TEXT,
+                channel_id INTEGER,
+                chat_id TEXT,
+                status TEXT NOT NULL DEFAULT 'queued',
+                broadcast_at TEXT,
+                completed_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_bridge_transfers_status ON bridge_transfers(status)",
+            [],
+        )?;
+
+        // Trade journal: swaps and transfers made by the agent, priced at execution time
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS trades (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tx_uuid TEXT NOT NULL UNIQUE,
+                kind TEXT NOT NULL,
+                network TEXT NOT NULL,
+                sell_token TEXT NOT NULL,
+                sell_symbol TEXT NOT NULL,
+                sell_amount_raw TEXT NOT NULL,
+                sell_decimals INTEGER NOT NULL,
+                buy_token TEXT,
+                buy_symbol TEXT,
+                buy_amount_raw TEXT,
+                buy_decimals INTEGER,
+                counterparty TEXT,
+                sell_price_usd REAL,
+                buy_price_usd REAL,
+                priced_at TEXT,
+                status TEXT NOT NULL DEFAULT 'queued',
+                tx_hash TEXT,
+                channel_id INTEGER,
+                created_at TEXT NOT NULL,
+                executed_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_trades_status ON trades(status, executed_at)",
+            [],
+        )?;
+
+        // Paper trading ledger: simulated fills recorded instead of signing in paper mode
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS paper_trades (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                network TEXT NOT NULL,
+                sell_token TEXT NOT NULL,
+                sell_symbol TEXT NOT NULL,
+                sell_amount_raw TEXT NOT NULL,
+                sell_decimals INTEGER NOT NULL,
+                buy_token TEXT,
+                buy_symbol TEXT,
+                buy_amount_raw TEXT,
+                buy_decimals INTEGER,
+                counterparty TEXT,
+                sell_price_usd REAL,
+                buy_price_usd REAL,
+                details TEXT,
+                channel_id INTEGER,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_paper_trades_channel ON paper_trades(channel_id, created_at)",
+            [],
+        )?;
+
+        // Access keys: scoped API keys for dashboards and integrations (only the hash is stored)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS access_keys (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                key_hash TEXT UNIQUE NOT NULL,
+                key_prefix TEXT NOT NULL,
+                scopes TEXT NOT NULL,
+                rate_limit_per_minute INTEGER NOT NULL DEFAULT 60,
+                expires_at TEXT,
+                last_used_at TEXT,
+                revoked_at TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Strategies: user-defined trigger -> conditions -> tool actions automations
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS strategies (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                description TEXT,
+                trigger_json TEXT NOT NULL,
+                conditions_json TEXT NOT NULL DEFAULT '[]',
+                actions_json TEXT NOT NULL DEFAULT '[]',
+                guardrails_json TEXT NOT NULL DEFAULT '{}',
+                channel_id INTEGER,
+                chat_id TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                state_json TEXT,
+                last_run_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Strategy run history (one row per fired trigger, including skipped runs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS strategy_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                strategy_id INTEGER NOT NULL,
+                strategy_name TEXT NOT NULL,
+                reason TEXT NOT NULL,
+                status TEXT NOT NULL,
+                steps_json TEXT NOT NULL DEFAULT '[]',
+                error TEXT,
+                started_at TEXT NOT NULL,
+                finished_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_strategy_runs_strategy ON strategy_runs(strategy_id, started_at)",
+            [],
+        )?;
+
+        // Module launch audit: binary hash, sandbox and outcome of every service launch attempt
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS module_launches (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                module_name TEXT NOT NULL,
+                target TEXT NOT NULL,
+                sha256 TEXT,
+                expected_sha256 TEXT,
+                sandbox TEXT NOT NULL,
+                uid INTEGER,
+                allowed INTEGER NOT NULL,
+                error TEXT,
+                launched_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_module_launches_module ON module_launches(module_name, launched_at)",
+            [],
+        )?;
+
+        // Module updates: newer StarkHub versions of installed modules
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS module_updates (
+                module_name TEXT PRIMARY KEY,
+                current_version TEXT NOT NULL,
+                available_version TEXT NOT NULL,
+                checked_at TEXT NOT NULL,
+                notified_version TEXT
+            )",
+            [],
+        )?;
+
+        // Agent subtype installs: which subtypes came from StarkHub, and which release
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS agent_subtype_installs (
+                key TEXT PRIMARY KEY,
+                username TEXT NOT NULL,
+                slug TEXT NOT NULL,
+                version TEXT NOT NULL,
+                installed_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Note export: where memories and session summaries are mirrored (single row)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_export_config (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                target TEXT NOT NULL DEFAULT 'obsidian',
+                enabled INTEGER NOT NULL DEFAULT 0,
+                notion_token TEXT,
+                notion_parent_page_id TEXT,
+                obsidian_vault_path TEXT,
+                folder TEXT NOT NULL DEFAULT 'StarkBot',
+                min_importance INTEGER NOT NULL DEFAULT 7,
+                memory_types TEXT NOT NULL DEFAULT 'long_term',
+                include_session_summaries INTEGER NOT NULL DEFAULT 1,
+                last_sync_at TEXT,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Note export links: one row per exported item, with the hashes the
+        // sync worker compares to tell local edits from remote ones
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS note_exports (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_kind TEXT NOT NULL,
+                source_id INTEGER NOT NULL,
+                target TEXT NOT NULL,
+                external_id TEXT NOT NULL,
+                local_hash TEXT NOT NULL,
+                remote_hash TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'synced',
+                last_error TEXT,
+                synced_at TEXT NOT NULL,
+                UNIQUE(source_kind, source_id, target)
+            )",
+            [],
+        )?;
+
+        // Issues filed in Jira/Linear, linked to the session they came from so
+        // status-change webhooks can be routed back into that conversation
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tracked_issues (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tracker TEXT NOT NULL,
+                external_id TEXT NOT NULL,
+                issue_key TEXT NOT NULL,
+                url TEXT,
+                title TEXT NOT NULL,
+                status TEXT,
+                channel_id INTEGER,
+                session_id INTEGER,
+                task_id INTEGER,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE(tracker, external_id)
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tracked_issues_session ON tracked_issues(session_id)",
+            [],
+        )?;
+
+        // Outgoing tweet queue - posted by the Twitter channel as rate limits allow
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS twitter_outgoing_queue (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id INTEGER,
+                text TEXT NOT NULL,
+                reply_to_id TEXT,
+                quote_tweet_id TEXT,
+                session_id INTEGER,
+                status TEXT NOT NULL DEFAULT 'pending',
+                not_before TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                posted_tweet_id TEXT,
+                last_error TEXT,
+                created_at TEXT NOT NULL,
+                sent_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_twitter_outgoing_due ON twitter_outgoing_queue(status, not_before)",
+            [],
+        )?;
+
+        // Social content calendar - agent-drafted posts awaiting owner approval / scheduled publishing
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS social_posts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                platform TEXT NOT NULL,
+                target_channel_id INTEGER,
+                target_chat_id TEXT,
+                text TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                scheduled_at TEXT,
+                session_id INTEGER,
+                approval_channel_id INTEGER,
+                approval_chat_id TEXT,
+                decided_by TEXT,
+                decided_at TEXT,
+                external_id TEXT,
+                external_url TEXT,
+                last_error TEXT,
+                metrics_json TEXT,
+                metrics_updated_at TEXT,
+                published_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_social_posts_status ON social_posts(status, scheduled_at)",
+            [],
+        )?;
+
+        // Farcaster mentions/replies handled by a Farcaster channel
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS farcaster_processed_casts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id INTEGER NOT NULL,
+                cast_hash TEXT NOT NULL,
+                author_fid INTEGER NOT NULL,
+                author_username TEXT NOT NULL,
+                cast_text TEXT NOT NULL,
+                processed_at TEXT NOT NULL,
+                UNIQUE(channel_id, cast_hash),
+                FOREIGN KEY (channel_id) REFERENCES external_channels(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_farcaster_casts_processed ON farcaster_processed_casts(processed_at)",
+            [],
+        )?;
+
+        // Subtasks delegated to remote EIP-8004 agents (audit of calls, payments and verification)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS remote_delegations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                agent_id INTEGER NOT NULL,
+                agent_registry TEXT NOT NULL,
+                agent_name TEXT,
+                protocol TEXT NOT NULL,
+                endpoint TEXT NOT NULL,
+                task TEXT NOT NULL,
+                budget_usdc TEXT NOT NULL,
+                amount_paid TEXT,
+                pay_to TEXT,
+                status TEXT NOT NULL,
+                result TEXT,
+                error TEXT,
+                channel_id INTEGER,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_remote_delegations_agent ON remote_delegations(agent_id, created_at)",
+            [],
+        )?;
+
+        // Transparency log: hash-chained, wallet-signed receipts of confirmed txs and paid tasks
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS action_receipts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                reference TEXT NOT NULL,
+                network TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                details TEXT NOT NULL,
+                prev_hash TEXT NOT NULL,
+                entry_hash TEXT NOT NULL UNIQUE,
+                signer TEXT,
+                signature TEXT,
+                created_at TEXT NOT NULL,
+                UNIQUE(kind, reference)
+            )",
+            [],
+        )?;
+        let _ = conn.execute(
+            "ALTER TABLE bot_settings ADD COLUMN receipts_log_enabled INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Identity profiles — structured facts learned about each counterparty
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS identity_profiles (
+                identity_id TEXT PRIMARY KEY,
+                display_name TEXT,
+                timezone TEXT,
+                language TEXT,
+                preferences TEXT NOT NULL DEFAULT '{}',
+                wallet_addresses TEXT NOT NULL DEFAULT '[]',
+                projects TEXT NOT NULL DEFAULT '[]',
+                locked_fields TEXT NOT NULL DEFAULT '[]',
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        let _ = conn.execute(
+            "ALTER TABLE forget_audit ADD COLUMN profiles_deleted INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Context pre-warming: settings (single row) and rendered sections
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS context_prewarm_config (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                enabled INTEGER NOT NULL DEFAULT 0,
+                active_hours TEXT NOT NULL DEFAULT '09:00-18:00',
+                lead_minutes INTEGER NOT NULL DEFAULT 15,
+                refresh_minutes INTEGER NOT NULL DEFAULT 30,
+                sections TEXT NOT NULL DEFAULT 'portfolio,calendar,watchlist',
+                updated_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS context_prewarm_cache (
+                section TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                refreshed_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Tool outputs: raw results replaced by a summary in the context
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tool_outputs (
+                handle TEXT PRIMARY KEY,
+                session_id INTEGER,
+                tool_name TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tool_outputs_created ON tool_outputs(created_at)",
+            [],
+        )?;
+
+        // Review-before-send outbox: drafts held until the owner approves them
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS outbox_drafts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                platform TEXT NOT NULL,
+                target_json TEXT NOT NULL,
+                content TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                session_id INTEGER,
+                expires_at TEXT NOT NULL,
+                decided_by TEXT,
+                decided_at TEXT,
+                delivery_ref TEXT,
+                last_error TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_outbox_drafts_status ON outbox_drafts(status, expires_at)",
+            [],
+        )?;
+
+        // Executions paused at a safe boundary, resumed later where they left off
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS paused_executions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id INTEGER NOT NULL,
+                chat_id TEXT NOT NULL,
+                session_id INTEGER NOT NULL,
+                context_json TEXT NOT NULL,
+                tool_history_json TEXT NOT NULL DEFAULT '[]',
+                messages_json TEXT NOT NULL DEFAULT '[]',
+                tool_call_log_json TEXT NOT NULL DEFAULT '[]',
+                paused_at TEXT NOT NULL,
+                UNIQUE(channel_id, chat_id)
+            )",
+            [],
+        )?;
+
+        // Artifacts: files generated during an execution (reports, CSVs, images, tx blobs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS artifacts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                execution_id TEXT NOT NULL,
+                session_id INTEGER,
+                channel_id INTEGER,
+                name TEXT NOT NULL,
+                path TEXT NOT NULL,
+                mime_type TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                UNIQUE(execution_id, path)
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_artifacts_session ON artifacts(session_id)",
+            [],
+        )?;
+
+        // Durable agent variables, namespaced per identity or global, with optional TTL
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS kv_store (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                namespace TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                expires_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE(namespace, key)
+            )",
+            [],
+        )?;
+
+        // Skill-defined counters and metrics (one row per increment / observation)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS metric_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                value REAL NOT NULL,
+                skill TEXT,
+                labels TEXT,
+                recorded_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_metric_events_name ON metric_events(name, recorded_at)",
+            [],
+        )?;
+
+        // Reusable parameterized plans, and the last plan each session ran (source for saving)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS workflow_templates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                description TEXT,
+                params TEXT NOT NULL DEFAULT '[]',
+                tasks TEXT NOT NULL DEFAULT '[]',
+                source_session_id INTEGER,
+                use_count INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_plans (
+                session_id INTEGER PRIMARY KEY,
+                request TEXT NOT NULL,
+                tasks TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Tool calls held for user confirmation (safety level below the channel threshold) + decision audit
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tool_confirmations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                uuid TEXT NOT NULL UNIQUE,
+                channel_id INTEGER NOT NULL,
+                chat_id TEXT NOT NULL,
+                session_id INTEGER,
+                tool_name TEXT NOT NULL,
+                arguments TEXT NOT NULL,
+                safety_level TEXT NOT NULL,
+                status TEXT NOT NULL,
+                requested_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL,
+                decided_at TEXT,
+                decided_by TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tool_confirmations_status ON tool_confirmations(status)",
+            [],
+        )?;
+        // Parameter previews share the table ('confirmation' or 'preview')
+        let _ = conn.execute(
+            "ALTER TABLE tool_confirmations ADD COLUMN kind TEXT NOT NULL DEFAULT 'confirmation'",
+            [],
+        );
+
+        // Global read-only mirror mode (mutating tools refused, for public demos)
+        let _ = conn.execute(
+            "ALTER TABLE bot_settings ADD COLUMN read_only_mode INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // CORS origin allow-list (JSON array)
+        let _ = conn.execute("ALTER TABLE bot_settings ADD COLUMN cors_allowed_origins TEXT", []);
+
+        // Scheduled activity digest: settings (single row) and generated digests
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS digest_config (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                enabled INTEGER NOT NULL DEFAULT 0,
+                frequency TEXT NOT NULL DEFAULT 'daily',
+                send_hour INTEGER NOT NULL DEFAULT 8,
+                weekday INTEGER NOT NULL DEFAULT 0,
+                sections TEXT NOT NULL DEFAULT 'executions,transactions,skills,memories',
+                channel_id INTEGER,
+                chat_id TEXT,
+                last_sent_at TEXT,
+                updated_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS digest_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                period_start TEXT NOT NULL,
+                period_end TEXT NOT NULL,
+                content TEXT NOT NULL,
+                delivered INTEGER NOT NULL DEFAULT 0,
+                error TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // AI usage ledger (one row per LLM call) and spending quotas per instance/channel
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ai_usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id INTEGER NOT NULL,
+                session_id INTEGER,
+                model TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                cost_usd REAL NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_ai_usage_created ON ai_usage(created_at)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_ai_usage_channel ON ai_usage(channel_id, created_at)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ai_quotas (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                scope TEXT NOT NULL,
+                channel_id INTEGER NOT NULL DEFAULT 0,
+                period TEXT NOT NULL,
+                max_tokens INTEGER,
+                max_usd REAL,
+                fallback_model TEXT,
+                alerted_period TEXT,
+                alerted_percent INTEGER NOT NULL DEFAULT 0,
+                updated_at TEXT NOT NULL,
+                UNIQUE(scope, channel_id, period)
+            )",
+            [],
+        )?;
+
+        // Token metadata cache (symbol/name/decimals fetched from Alchemy or CoinGecko)
+        // and hand-maintained overrides for tokens those sources get wrong
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS token_metadata (
+                network TEXT NOT NULL,
+                address TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                name TEXT NOT NULL,
+                decimals INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                fetched_at TEXT NOT NULL,
+                PRIMARY KEY (network, address)
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS token_metadata_overrides (
+                network TEXT NOT NULL,
+                address TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                name TEXT NOT NULL,
+                decimals INTEGER NOT NULL,
+                note TEXT,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (network, address)
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS rpc_endpoints (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                network TEXT NOT NULL,
+                url TEXT NOT NULL,
+                label TEXT NOT NULL DEFAULT '',
+                priority INTEGER NOT NULL DEFAULT 0,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE(network, url)
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_permissions (
+                skill_name TEXT NOT NULL,
+                capability TEXT NOT NULL,
+                granted INTEGER NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (skill_name, capability)
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                skill_name TEXT NOT NULL,
+                caller_skill TEXT,
+                parent_run_id INTEGER,
+                root_run_id INTEGER,
+                depth INTEGER NOT NULL DEFAULT 1,
+                session_id INTEGER,
+                channel_id INTEGER,
+                subagent_id TEXT,
+                input TEXT NOT NULL DEFAULT '',
+                args TEXT NOT NULL DEFAULT '{}',
+                status TEXT NOT NULL DEFAULT 'running',
+                output TEXT,
+                error TEXT,
+                duration_ms INTEGER,
+                started_at TEXT NOT NULL,
+                completed_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_skill_runs_root ON skill_runs(root_run_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_skill_runs_subagent ON skill_runs(subagent_id)",
+            [],
+        )?;
+
+        // Knowledge graph of entities (people, projects, addresses, tokens...) and their
+        // relations, built from memories and context bank items. Derived data: rebuildable.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS knowledge_entities (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type TEXT NOT NULL,
+                name TEXT NOT NULL,
+                display_name TEXT NOT NULL,
+                mention_count INTEGER NOT NULL DEFAULT 1,
+                first_seen TEXT NOT NULL,
+                last_seen TEXT NOT NULL,
+                UNIQUE(entity_type, name)
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS knowledge_relations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_entity_id INTEGER NOT NULL,
+                target_entity_id INTEGER NOT NULL,
+                relation_type TEXT NOT NULL,
+                strength REAL NOT NULL DEFAULT 0.3,
+                mention_count INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE(source_entity_id, target_entity_id, relation_type),
+                FOREIGN KEY (source_entity_id) REFERENCES knowledge_entities(id) ON DELETE CASCADE,
+                FOREIGN KEY (target_entity_id) REFERENCES knowledge_entities(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_knowledge_relations_source ON knowledge_relations(source_entity_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_knowledge_relations_target ON knowledge_relations(target_entity_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS knowledge_mentions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_id INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                memory_id INTEGER,
+                session_id INTEGER,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (entity_id) REFERENCES knowledge_entities(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_knowledge_mentions_entity ON knowledge_mentions(entity_id)",
+            [],
+        )?;
+
+        // Read-only introspection views (q_*) for the query_database tool and admin API
+        super::tables::query_views::create_query_views(&conn)?;
+
         Ok(())
     }
 
@@ -2168,6 +3409,7 @@ impl AgentIdentityRow {
             active: self.active,
             registrations: None,
             supported_trust,
+            reputation: None,
         }
     }
 }
@@ -0,0 +1,165 @@
+//! Access key database operations (access_keys)
+//!
+//! Access keys let a dashboard or integration call the REST API without a
+//! login session. Only a SHA-256 hash of the key is stored; the key itself is
+//! shown once, when it is created. Keys are revoked rather than deleted so
+//! their last use stays visible.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::super::Database;
+use crate::models::Session;
+
+/// Every access key starts with this, which is how a bearer token is told
+/// apart from a login session token
+pub const ACCESS_KEY_PREFIX: &str = "sk_stark_";
+
+/// Characters of the key kept in `key_prefix` to recognise it in the UI
+const DISPLAY_PREFIX_LEN: usize = 14;
+
+/// A scoped API key
+#[derive(Debug, Clone, Serialize)]
+pub struct AccessKey {
+    pub id: i64,
+    pub name: String,
+    /// The first characters of the key, e.g. "sk_stark_3f9a2"
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i64,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
+    pub created_at: String,
+}
+
+impl AccessKey {
+    /// Not revoked and not expired
+    pub fn is_usable(&self) -> bool {
+        if self.revoked_at.is_some() {
+            return false;
+        }
+        match self.expires_at.as_deref().and_then(|e| DateTime::parse_from_rfc3339(e).ok()) {
+            Some(expires_at) => expires_at > Utc::now(),
+            None => true,
+        }
+    }
+
+    /// The key as a login session, so handlers that validate sessions accept it
+    pub fn as_session(&self, token: &str) -> Session {
+        let parse = |s: &str| DateTime::parse_from_rfc3339(s).ok().map(|d| d.with_timezone(&Utc));
+        Session {
+            id: -self.id,
+            token: token.to_string(),
+            created_at: parse(&self.created_at).unwrap_or_else(Utc::now),
+            expires_at: self
+                .expires_at
+                .as_deref()
+                .and_then(parse)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        }
+    }
+}
+
+/// SHA-256 of a key, hex encoded
+pub fn hash_access_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// A new random key
+pub fn generate_access_key() -> String {
+    let bytes: [u8; 24] = rand::random();
+    format!("{}{}", ACCESS_KEY_PREFIX, hex::encode(bytes))
+}
+
+const ACCESS_KEY_COLUMNS: &str =
+    "id, name, key_prefix, scopes, rate_limit_per_minute, expires_at, last_used_at, revoked_at, created_at";
+
+fn row_to_access_key(row: &rusqlite::Row) -> rusqlite::Result<AccessKey> {
+    let scopes: String = row.get(3)?;
+    Ok(AccessKey {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        key_prefix: row.get(2)?,
+        scopes: scopes.split(',').filter(|s| !s.is_empty()).map(str::to_string).collect(),
+        rate_limit_per_minute: row.get(4)?,
+        expires_at: row.get(5)?,
+        last_used_at: row.get(6)?,
+        revoked_at: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+impl Database {
+    /// Store a new key (by hash) and return its record
+    pub fn create_access_key(
+        &self,
+        name: &str,
+        key: &str,
+        scopes: &[String],
+        rate_limit_per_minute: i64,
+        expires_at: Option<&str>,
+    ) -> SqliteResult<AccessKey> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let prefix: String = key.chars().take(DISPLAY_PREFIX_LEN).collect();
+        conn.execute(
+            "INSERT INTO access_keys (name, key_hash, key_prefix, scopes, rate_limit_per_minute, expires_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![name, hash_access_key(key), prefix, scopes.join(","), rate_limit_per_minute, expires_at, now],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.query_row(
+            &format!("SELECT {} FROM access_keys WHERE id = ?1", ACCESS_KEY_COLUMNS),
+            [id],
+            row_to_access_key,
+        )
+    }
+
+    /// The key a bearer token belongs to, usable or not
+    pub fn find_access_key(&self, key: &str) -> SqliteResult<Option<AccessKey>> {
+        let conn = self.conn();
+        let result = conn.query_row(
+            &format!("SELECT {} FROM access_keys WHERE key_hash = ?1", ACCESS_KEY_COLUMNS),
+            [hash_access_key(key)],
+            row_to_access_key,
+        );
+        match result {
+            Ok(k) => Ok(Some(k)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// All keys, newest first
+    pub fn list_access_keys(&self) -> SqliteResult<Vec<AccessKey>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM access_keys ORDER BY id DESC",
+            ACCESS_KEY_COLUMNS
+        ))?;
+        stmt.query_map([], row_to_access_key)?.collect()
+    }
+
+    /// Revoke a key. Returns false if it doesn't exist or was already revoked.
+    pub fn revoke_access_key(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let changed = conn.execute(
+            "UPDATE access_keys SET revoked_at = ?1 WHERE id = ?2 AND revoked_at IS NULL",
+            rusqlite::params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Record that a key was just used
+    pub fn touch_access_key(&self, id: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE access_keys SET last_used_at = ?1 WHERE id = ?2",
+            rusqlite::params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+}
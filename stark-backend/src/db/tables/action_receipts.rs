@@ -0,0 +1,169 @@
+//! Action receipts - hash-chained transparency log served publicly
+//!
+//! Entries are append-only. Each stores the hash of the previous entry, so
+//! the chain is computed under the connection lock when appending.
+
+use chrono::{SecondsFormat, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::eip8004::receipts::{entry_hash, GENESIS_HASH};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionReceipt {
+    pub id: i64,
+    pub kind: String,
+    pub reference: String,
+    pub network: String,
+    pub summary: String,
+    /// JSON object, kept as the exact string that was hashed
+    pub details: String,
+    pub prev_hash: String,
+    pub entry_hash: String,
+    pub signer: Option<String>,
+    pub signature: Option<String>,
+    pub created_at: String,
+}
+
+const RECEIPT_COLS: &str =
+    "id, kind, reference, network, summary, details, prev_hash, entry_hash, signer, signature, created_at";
+
+fn row_to_receipt(row: &rusqlite::Row) -> rusqlite::Result<ActionReceipt> {
+    Ok(ActionReceipt {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        reference: row.get(2)?,
+        network: row.get(3)?,
+        summary: row.get(4)?,
+        details: row.get(5)?,
+        prev_hash: row.get(6)?,
+        entry_hash: row.get(7)?,
+        signer: row.get(8)?,
+        signature: row.get(9)?,
+        created_at: row.get(10)?,
+    })
+}
+
+impl Database {
+    /// Append an entry to the chain. Returns None if this kind/reference is already logged.
+    pub fn append_action_receipt(
+        &self,
+        kind: &str,
+        reference: &str,
+        network: &str,
+        summary: &str,
+        details_json: &str,
+    ) -> SqliteResult<Option<ActionReceipt>> {
+        let conn = self.conn();
+        let exists: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM action_receipts WHERE kind = ?1 AND reference = ?2",
+            rusqlite::params![kind, reference],
+            |row| row.get(0),
+        )?;
+        if exists > 0 {
+            return Ok(None);
+        }
+
+        let prev_hash: String = conn
+            .query_row("SELECT entry_hash FROM action_receipts ORDER BY id DESC LIMIT 1", [], |row| row.get(0))
+            .optional()?
+            .unwrap_or_else(|| GENESIS_HASH.to_string());
+        let created_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        let hash = entry_hash(&prev_hash, kind, reference, network, summary, details_json, &created_at);
+
+        conn.execute(
+            "INSERT INTO action_receipts (kind, reference, network, summary, details, prev_hash, entry_hash, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![kind, reference, network, summary, details_json, prev_hash, hash, created_at],
+        )?;
+        Ok(Some(ActionReceipt {
+            id: conn.last_insert_rowid(),
+            kind: kind.to_string(),
+            reference: reference.to_string(),
+            network: network.to_string(),
+            summary: summary.to_string(),
+            details: details_json.to_string(),
+            prev_hash,
+            entry_hash: hash,
+            signer: None,
+            signature: None,
+            created_at,
+        }))
+    }
+
+    /// Entries in chain order, starting after `after_id`
+    pub fn list_action_receipts(&self, after_id: i64, limit: usize) -> SqliteResult<Vec<ActionReceipt>> {
+        let conn = self.conn();
+        let sql = format!("SELECT {} FROM action_receipts WHERE id > ?1 ORDER BY id ASC LIMIT ?2", RECEIPT_COLS);
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params![after_id, limit as i64], row_to_receipt)?;
+        rows.collect()
+    }
+
+    /// Entry count and latest hash
+    pub fn action_receipts_head(&self) -> SqliteResult<(i64, String)> {
+        let conn = self.conn();
+        let total: i64 = conn.query_row("SELECT COUNT(*) FROM action_receipts", [], |row| row.get(0))?;
+        let head: Option<String> = conn
+            .query_row("SELECT entry_hash FROM action_receipts ORDER BY id DESC LIMIT 1", [], |row| row.get(0))
+            .optional()?;
+        Ok((total, head.unwrap_or_else(|| GENESIS_HASH.to_string())))
+    }
+
+    pub fn unsigned_action_receipts(&self, limit: usize) -> SqliteResult<Vec<ActionReceipt>> {
+        let conn = self.conn();
+        let sql = format!(
+            "SELECT {} FROM action_receipts WHERE signature IS NULL ORDER BY id ASC LIMIT ?1",
+            RECEIPT_COLS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([limit as i64], row_to_receipt)?;
+        rows.collect()
+    }
+
+    pub fn set_action_receipt_signature(&self, id: i64, signer: &str, signature: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE action_receipts SET signer = ?1, signature = ?2 WHERE id = ?3",
+            rusqlite::params![signer, signature, id],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eip8004::receipts::{verify_chain, RECEIPT_TRANSACTION};
+
+    #[test]
+    fn test_append_builds_verifiable_chain() {
+        let db = Database::new(":memory:").unwrap();
+        let first = db
+            .append_action_receipt(RECEIPT_TRANSACTION, "0xaaa", "base", "sent 1 ETH", "{}")
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+        assert!(db.append_action_receipt(RECEIPT_TRANSACTION, "0xaaa", "base", "again", "{}").unwrap().is_none());
+        let second = db
+            .append_action_receipt(RECEIPT_TRANSACTION, "0xbbb", "base", "sent 2 ETH", "{\"v\":2}")
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.prev_hash, first.entry_hash);
+
+        let entries = db.list_action_receipts(0, 100).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(verify_chain(&entries).is_ok());
+        assert_eq!(db.action_receipts_head().unwrap(), (2, second.entry_hash.clone()));
+
+        let mut tampered = entries.clone();
+        tampered[0].summary = "sent 100 ETH".to_string();
+        assert!(verify_chain(&tampered).is_err());
+
+        assert_eq!(db.unsigned_action_receipts(10).unwrap().len(), 2);
+        db.set_action_receipt_signature(first.id, "0xsigner", "0xsig").unwrap();
+        assert_eq!(db.unsigned_action_receipts(10).unwrap().len(), 1);
+    }
+}
@@ -56,11 +56,14 @@ impl Database {
                 active_skill,
                 actual_tool_calls: 0,      // Reset on load
                 no_tool_warnings: 0,       // Reset on load
+                repair_prompts: 0,         // Reset on load
                 waiting_for_user_context: None, // Reset on load
                 task_queue: TaskQueue::default(), // Reset on load
                 planner_completed: false,  // Reset on load
                 selected_network: None,    // Reset on load
                 is_hook_session: false,    // Set by dispatcher, not persisted
+                pending_clarification: None, // Parked executions carry their own
+                clarifications: Vec::new(),  // Reset on load
             })
         });
 
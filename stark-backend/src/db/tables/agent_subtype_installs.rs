@@ -0,0 +1,77 @@
+//! StarkHub provenance for agent subtypes (agent_subtype_installs)
+//!
+//! Subtypes themselves live on disk (`runtime_agents_dir()/<key>/agent.md`);
+//! this table only remembers which ones were installed from StarkHub so they
+//! can be checked for updates and re-synced.
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use super::super::Database;
+
+/// A subtype installed from StarkHub
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentSubtypeInstall {
+    pub key: String,
+    pub username: String,
+    pub slug: String,
+    pub version: String,
+    pub installed_at: String,
+    pub updated_at: String,
+}
+
+fn row_to_install(row: &rusqlite::Row) -> rusqlite::Result<AgentSubtypeInstall> {
+    Ok(AgentSubtypeInstall {
+        key: row.get(0)?,
+        username: row.get(1)?,
+        slug: row.get(2)?,
+        version: row.get(3)?,
+        installed_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+impl Database {
+    /// Record a hub install (or re-sync) of a subtype
+    pub fn record_agent_subtype_install(&self, key: &str, username: &str, slug: &str, version: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO agent_subtype_installs (key, username, slug, version, installed_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+             ON CONFLICT(key) DO UPDATE SET
+                username = excluded.username,
+                slug = excluded.slug,
+                version = excluded.version,
+                updated_at = excluded.updated_at",
+            rusqlite::params![key, username, slug, version, now],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_agent_subtype_install(&self, key: &str) -> SqliteResult<Option<AgentSubtypeInstall>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT key, username, slug, version, installed_at, updated_at FROM agent_subtype_installs WHERE key = ?1",
+            [key],
+            row_to_install,
+        )
+        .optional()
+    }
+
+    pub fn list_agent_subtype_installs(&self) -> SqliteResult<Vec<AgentSubtypeInstall>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT key, username, slug, version, installed_at, updated_at FROM agent_subtype_installs ORDER BY key",
+        )?;
+        let installs = stmt.query_map([], row_to_install)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(installs)
+    }
+
+    pub fn delete_agent_subtype_install(&self, key: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM agent_subtype_installs WHERE key = ?1", [key])?;
+        Ok(rows > 0)
+    }
+}
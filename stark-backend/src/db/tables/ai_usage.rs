@@ -0,0 +1,172 @@
+//! AI usage ledger and spending quotas (ai_usage, ai_quotas)
+//!
+//! `ai_usage` holds one row per LLM call the dispatcher made: estimated
+//! tokens and its USD cost. `ai_quotas` caps that usage per day or month,
+//! either for the whole instance or for one channel; each quota also
+//! remembers the highest alert threshold already reported for its current
+//! period so the owner is told only once.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+const SCOPE_TENANT: &str = "tenant";
+const SCOPE_CHANNEL: &str = "channel";
+
+/// A token and/or USD limit on AI usage
+#[derive(Debug, Clone, Serialize)]
+pub struct AiQuota {
+    pub id: i64,
+    /// None = the whole instance (every channel together)
+    pub channel_id: Option<i64>,
+    /// "daily" or "monthly"
+    pub period: String,
+    pub max_tokens: Option<i64>,
+    pub max_usd: Option<f64>,
+    /// Preset key (ai_endpoints.ron) to switch to near the limit; None = cheapest preset
+    pub fallback_model: Option<String>,
+    /// Period key ("2026-10-16", "2026-10") of the last alert
+    pub alerted_period: Option<String>,
+    /// Highest threshold (percent) already reported in `alerted_period`
+    pub alerted_percent: i64,
+    pub updated_at: String,
+}
+
+/// Usage summed over a time window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct AiUsageTotals {
+    pub calls: i64,
+    pub tokens: i64,
+    pub cost_usd: f64,
+}
+
+const QUOTA_COLUMNS: &str =
+    "id, scope, channel_id, period, max_tokens, max_usd, fallback_model, alerted_period, alerted_percent, updated_at";
+
+fn row_to_quota(row: &rusqlite::Row) -> rusqlite::Result<AiQuota> {
+    let scope: String = row.get(1)?;
+    Ok(AiQuota {
+        id: row.get(0)?,
+        channel_id: (scope == SCOPE_CHANNEL).then(|| row.get(2)).transpose()?,
+        period: row.get(3)?,
+        max_tokens: row.get(4)?,
+        max_usd: row.get(5)?,
+        fallback_model: row.get(6)?,
+        alerted_period: row.get(7)?,
+        alerted_percent: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+impl Database {
+    /// Record one LLM call
+    pub fn record_ai_usage(
+        &self,
+        channel_id: i64,
+        session_id: Option<i64>,
+        model: &str,
+        input_tokens: i64,
+        output_tokens: i64,
+        cost_usd: f64,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO ai_usage (channel_id, session_id, model, input_tokens, output_tokens, cost_usd, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![channel_id, session_id, model, input_tokens, output_tokens, cost_usd, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Usage since `since`, for one channel or (None) the whole instance
+    pub fn ai_usage_since(&self, channel_id: Option<i64>, since: DateTime<Utc>) -> SqliteResult<AiUsageTotals> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(input_tokens + output_tokens), 0), COALESCE(SUM(cost_usd), 0)
+             FROM ai_usage WHERE created_at >= ?1 AND (?2 IS NULL OR channel_id = ?2)",
+            rusqlite::params![since.to_rfc3339(), channel_id],
+            |row| Ok(AiUsageTotals { calls: row.get(0)?, tokens: row.get(1)?, cost_usd: row.get(2)? }),
+        )
+    }
+
+    /// Create or replace the quota for a scope and period. Changing a limit
+    /// re-arms its alerts.
+    pub fn upsert_ai_quota(
+        &self,
+        channel_id: Option<i64>,
+        period: &str,
+        max_tokens: Option<i64>,
+        max_usd: Option<f64>,
+        fallback_model: Option<&str>,
+    ) -> SqliteResult<AiQuota> {
+        let conn = self.conn();
+        let scope = if channel_id.is_some() { SCOPE_CHANNEL } else { SCOPE_TENANT };
+        conn.execute(
+            "INSERT INTO ai_quotas (scope, channel_id, period, max_tokens, max_usd, fallback_model, alerted_percent, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, ?7)
+             ON CONFLICT(scope, channel_id, period) DO UPDATE SET
+                max_tokens = excluded.max_tokens,
+                max_usd = excluded.max_usd,
+                fallback_model = excluded.fallback_model,
+                alerted_period = NULL,
+                alerted_percent = 0,
+                updated_at = excluded.updated_at",
+            rusqlite::params![
+                scope,
+                channel_id.unwrap_or(0),
+                period,
+                max_tokens,
+                max_usd,
+                fallback_model,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        conn.query_row(
+            &format!("SELECT {} FROM ai_quotas WHERE scope = ?1 AND channel_id = ?2 AND period = ?3", QUOTA_COLUMNS),
+            rusqlite::params![scope, channel_id.unwrap_or(0), period],
+            row_to_quota,
+        )
+    }
+
+    pub fn list_ai_quotas(&self) -> SqliteResult<Vec<AiQuota>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM ai_quotas ORDER BY scope DESC, channel_id, period",
+            QUOTA_COLUMNS
+        ))?;
+        let rows = stmt.query_map([], row_to_quota)?;
+        rows.collect()
+    }
+
+    /// Quotas that apply to an execution on `channel_id`: the instance's and the channel's
+    pub fn ai_quotas_for_channel(&self, channel_id: i64) -> SqliteResult<Vec<AiQuota>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM ai_quotas WHERE scope = ?1 OR (scope = ?2 AND channel_id = ?3)
+             ORDER BY scope DESC, period",
+            QUOTA_COLUMNS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![SCOPE_TENANT, SCOPE_CHANNEL, channel_id], row_to_quota)?;
+        rows.collect()
+    }
+
+    pub fn delete_ai_quota(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        Ok(conn.execute("DELETE FROM ai_quotas WHERE id = ?1", [id])? > 0)
+    }
+
+    /// Note that `percent` of the quota was reached in `period_key`. Returns
+    /// true only the first time that threshold (or a higher one) is reported
+    /// for the period, so concurrent executions alert once.
+    pub fn mark_ai_quota_alerted(&self, id: i64, period_key: &str, percent: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let updated = conn.execute(
+            "UPDATE ai_quotas SET alerted_period = ?2, alerted_percent = ?3
+             WHERE id = ?1 AND (alerted_period IS NULL OR alerted_period != ?2 OR alerted_percent < ?3)",
+            rusqlite::params![id, period_key, percent],
+        )?;
+        Ok(updated > 0)
+    }
+}
@@ -0,0 +1,257 @@
+//! Alert rules database operations (alert_rules, alert_events)
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+
+use super::super::Database;
+use crate::alerts::types::{
+    AlertAction, AlertCondition, AlertEvent, AlertRule, CreateAlertRuleRequest,
+    UpdateAlertRuleRequest,
+};
+
+const RULE_COLUMNS: &str = "id, name, description, condition_json, actions_json, cooldown_minutes,
+                            enabled, last_fired_at, created_at, updated_at";
+
+impl Database {
+    /// Create a new alert rule
+    pub fn create_alert_rule(&self, request: &CreateAlertRuleRequest) -> SqliteResult<AlertRule> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let condition_json = serde_json::to_string(&request.condition).unwrap_or_default();
+        let actions_json = serde_json::to_string(&request.actions).unwrap_or_else(|_| "[]".to_string());
+
+        conn.execute(
+            "INSERT INTO alert_rules (name, description, condition_json, actions_json, cooldown_minutes, enabled, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+            rusqlite::params![
+                request.name.trim(),
+                request.description,
+                condition_json,
+                actions_json,
+                request.cooldown_minutes.unwrap_or(30),
+                request.enabled.unwrap_or(true) as i32,
+                now,
+            ],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        drop(conn);
+        self.get_alert_rule(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    /// Get an alert rule by ID
+    pub fn get_alert_rule(&self, id: i64) -> SqliteResult<Option<AlertRule>> {
+        let conn = self.conn();
+        let rule = conn
+            .query_row(
+                &format!("SELECT {} FROM alert_rules WHERE id = ?1", RULE_COLUMNS),
+                [id],
+                |row| Self::row_to_alert_rule(row),
+            )
+            .ok();
+        Ok(rule)
+    }
+
+    /// List all alert rules ordered by creation
+    pub fn list_alert_rules(&self) -> SqliteResult<Vec<AlertRule>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM alert_rules ORDER BY id ASC", RULE_COLUMNS))?;
+        let rules = stmt
+            .query_map([], |row| Self::row_to_alert_rule(row))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rules)
+    }
+
+    /// List only enabled alert rules (used by the evaluation worker)
+    pub fn list_enabled_alert_rules(&self) -> SqliteResult<Vec<AlertRule>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM alert_rules WHERE enabled = 1 ORDER BY id ASC",
+            RULE_COLUMNS
+        ))?;
+        let rules = stmt
+            .query_map([], |row| Self::row_to_alert_rule(row))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rules)
+    }
+
+    /// Update an alert rule. Unset fields keep their current value.
+    pub fn update_alert_rule(&self, id: i64, request: &UpdateAlertRuleRequest) -> SqliteResult<Option<AlertRule>> {
+        let existing = match self.get_alert_rule(id)? {
+            Some(r) => r,
+            None => return Ok(None),
+        };
+
+        let name = request.name.as_deref().map(str::trim).unwrap_or(&existing.name).to_string();
+        let description = request.description.clone().or(existing.description);
+        let condition = request.condition.as_ref().unwrap_or(&existing.condition);
+        let actions = request.actions.as_ref().unwrap_or(&existing.actions);
+        let cooldown = request.cooldown_minutes.unwrap_or(existing.cooldown_minutes);
+        let enabled = request.enabled.unwrap_or(existing.enabled);
+
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE alert_rules SET name = ?1, description = ?2, condition_json = ?3, actions_json = ?4,
+                    cooldown_minutes = ?5, enabled = ?6, updated_at = ?7
+             WHERE id = ?8",
+            rusqlite::params![
+                name,
+                description,
+                serde_json::to_string(condition).unwrap_or_default(),
+                serde_json::to_string(actions).unwrap_or_else(|_| "[]".to_string()),
+                cooldown,
+                enabled as i32,
+                Utc::now().to_rfc3339(),
+                id,
+            ],
+        )?;
+        drop(conn);
+        self.get_alert_rule(id)
+    }
+
+    /// Delete an alert rule (its event history is kept)
+    pub fn delete_alert_rule(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM alert_rules WHERE id = ?1", [id])?;
+        Ok(rows > 0)
+    }
+
+    /// Record that a rule fired and bump its last_fired_at
+    pub fn record_alert_event(
+        &self,
+        rule: &AlertRule,
+        message: &str,
+        value: f64,
+        actions_taken: &[String],
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO alert_events (rule_id, rule_name, message, value, actions_taken, fired_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                rule.id,
+                rule.name,
+                message,
+                value,
+                serde_json::to_string(actions_taken).unwrap_or_else(|_| "[]".to_string()),
+                now,
+            ],
+        )?;
+        let event_id = conn.last_insert_rowid();
+        conn.execute(
+            "UPDATE alert_rules SET last_fired_at = ?1 WHERE id = ?2",
+            rusqlite::params![now, rule.id],
+        )?;
+        Ok(event_id)
+    }
+
+    /// List recent alert events, newest first (optionally for one rule)
+    pub fn list_alert_events(&self, rule_id: Option<i64>, limit: usize) -> SqliteResult<Vec<AlertEvent>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, rule_id, rule_name, message, value, actions_taken, fired_at
+             FROM alert_events WHERE (?1 IS NULL OR rule_id = ?1)
+             ORDER BY id DESC LIMIT ?2",
+        )?;
+        let events = stmt
+            .query_map(rusqlite::params![rule_id, limit as i64], |row| {
+                let actions_json: String = row.get(5)?;
+                Ok(AlertEvent {
+                    id: row.get(0)?,
+                    rule_id: row.get(1)?,
+                    rule_name: row.get(2)?,
+                    message: row.get(3)?,
+                    value: row.get(4)?,
+                    actions_taken: serde_json::from_str(&actions_json).unwrap_or_default(),
+                    fired_at: row.get(6)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(events)
+    }
+
+    fn row_to_alert_rule(row: &rusqlite::Row) -> rusqlite::Result<AlertRule> {
+        let condition_json: String = row.get(3)?;
+        let actions_json: String = row.get(4)?;
+        let condition: AlertCondition = serde_json::from_str(&condition_json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+        let actions: Vec<AlertAction> = serde_json::from_str(&actions_json).unwrap_or_default();
+
+        Ok(AlertRule {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            condition,
+            actions,
+            cooldown_minutes: row.get(5)?,
+            enabled: row.get::<_, i32>(6)? != 0,
+            last_fired_at: row.get(7)?,
+            created_at: row.get(8)?,
+            updated_at: row.get(9)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Database {
+        Database::new(":memory:").expect("in-memory db")
+    }
+
+    fn disk_rule() -> CreateAlertRuleRequest {
+        CreateAlertRuleRequest {
+            name: "disk".to_string(),
+            description: None,
+            condition: AlertCondition::DiskQuota { threshold_percent: 90 },
+            actions: vec![AlertAction::CreateMemory { importance: None }],
+            cooldown_minutes: None,
+            enabled: None,
+        }
+    }
+
+    #[test]
+    fn test_alert_rule_crud() {
+        let db = setup_db();
+        let rule = db.create_alert_rule(&disk_rule()).unwrap();
+        assert_eq!(rule.cooldown_minutes, 30);
+        assert!(rule.enabled);
+        assert_eq!(rule.condition, AlertCondition::DiskQuota { threshold_percent: 90 });
+
+        let updated = db
+            .update_alert_rule(rule.id, &UpdateAlertRuleRequest {
+                enabled: Some(false),
+                ..Default::default()
+            })
+            .unwrap()
+            .unwrap();
+        assert!(!updated.enabled);
+        assert_eq!(updated.name, "disk");
+        assert!(db.list_enabled_alert_rules().unwrap().is_empty());
+        assert_eq!(db.list_alert_rules().unwrap().len(), 1);
+
+        assert!(db.delete_alert_rule(rule.id).unwrap());
+        assert!(db.get_alert_rule(rule.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_record_alert_event_sets_last_fired() {
+        let db = setup_db();
+        let rule = db.create_alert_rule(&disk_rule()).unwrap();
+        db.record_alert_event(&rule, "Disk usage at 95%", 95.0, &["created memory 1".to_string()])
+            .unwrap();
+
+        let rule = db.get_alert_rule(rule.id).unwrap().unwrap();
+        assert!(rule.last_fired_at.is_some());
+
+        let events = db.list_alert_events(Some(rule.id), 10).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].actions_taken, vec!["created memory 1".to_string()]);
+    }
+}
@@ -94,6 +94,8 @@ impl Database {
 
         drop(conn);
         self.cache.invalidate_api_key(service_name);
+        // Failures blamed on this key being missing no longer hold
+        crate::memory::negative_results::resolve_api_key(self, service_name);
 
         // Return the upserted key
         self.get_api_key(service_name).map(|opt| opt.unwrap())
@@ -0,0 +1,187 @@
+//! Execution artifacts (artifacts)
+//!
+//! Files an execution produced for the user — reports, CSVs, images, signed
+//! transaction blobs. Each row links a workspace-relative path to the
+//! execution and session that generated it, so the API can list them per
+//! execution and channel adapters can upload them with the final reply.
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::super::Database;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Artifact {
+    pub id: i64,
+    pub execution_id: String,
+    pub session_id: Option<i64>,
+    pub channel_id: Option<i64>,
+    /// File name shown to the user / used for uploads
+    pub name: String,
+    /// Path relative to the workspace directory
+    pub path: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub created_at: String,
+}
+
+impl Artifact {
+    /// Absolute location of the artifact on disk
+    pub fn absolute_path(&self) -> PathBuf {
+        PathBuf::from(crate::config::workspace_dir()).join(&self.path)
+    }
+
+    pub fn is_image(&self) -> bool {
+        self.mime_type.starts_with("image/")
+    }
+}
+
+/// Best-effort MIME type from the file extension
+pub fn mime_for_path(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match ext.as_str() {
+        "png" => "image/png",
+        "svg" => "image/svg+xml",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "csv" => "text/csv",
+        "md" => "text/markdown",
+        "txt" | "log" => "text/plain",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+const ARTIFACT_COLS: &str =
+    "id, execution_id, session_id, channel_id, name, path, mime_type, size_bytes, created_at";
+
+fn row_to_artifact(row: &rusqlite::Row) -> rusqlite::Result<Artifact> {
+    Ok(Artifact {
+        id: row.get(0)?,
+        execution_id: row.get(1)?,
+        session_id: row.get(2)?,
+        channel_id: row.get(3)?,
+        name: row.get(4)?,
+        path: row.get(5)?,
+        mime_type: row.get(6)?,
+        size_bytes: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+impl Database {
+    /// Register a generated file as an artifact of an execution.
+    /// Re-registering the same path refreshes its size and timestamp.
+    pub fn register_artifact(
+        &self,
+        execution_id: &str,
+        session_id: Option<i64>,
+        channel_id: Option<i64>,
+        path: &str,
+        size_bytes: u64,
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let rel = Path::new(path);
+        let name = rel
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        conn.execute(
+            "INSERT INTO artifacts (execution_id, session_id, channel_id, name, path, mime_type, size_bytes, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(execution_id, path) DO UPDATE SET
+                size_bytes = excluded.size_bytes,
+                created_at = excluded.created_at",
+            rusqlite::params![
+                execution_id,
+                session_id,
+                channel_id,
+                name,
+                path,
+                mime_for_path(rel),
+                size_bytes as i64,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        conn.query_row(
+            "SELECT id FROM artifacts WHERE execution_id = ?1 AND path = ?2",
+            rusqlite::params![execution_id, path],
+            |row| row.get(0),
+        )
+    }
+
+    /// All artifacts generated by an execution, oldest first
+    pub fn list_artifacts_for_execution(&self, execution_id: &str) -> SqliteResult<Vec<Artifact>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM artifacts WHERE execution_id = ?1 ORDER BY id ASC",
+            ARTIFACT_COLS
+        ))?;
+        let rows = stmt.query_map([execution_id], row_to_artifact)?;
+        rows.collect()
+    }
+
+    /// All artifacts generated in a session, oldest first
+    pub fn list_artifacts_for_session(&self, session_id: i64) -> SqliteResult<Vec<Artifact>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM artifacts WHERE session_id = ?1 ORDER BY id ASC",
+            ARTIFACT_COLS
+        ))?;
+        let rows = stmt.query_map([session_id], row_to_artifact)?;
+        rows.collect()
+    }
+
+    pub fn get_artifact(&self, id: i64) -> SqliteResult<Option<Artifact>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM artifacts WHERE id = ?1", ARTIFACT_COLS),
+            [id],
+            row_to_artifact,
+        )
+        .optional()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_list_artifacts() {
+        let db = Database::new(":memory:").unwrap();
+        let first = db
+            .register_artifact("exec-1", Some(7), Some(0), "artifacts/exec-1/report.csv", 10)
+            .unwrap();
+        db.register_artifact("exec-1", Some(7), Some(0), "artifacts/exec-1/chart.png", 2048)
+            .unwrap();
+        db.register_artifact("exec-2", Some(8), Some(0), "artifacts/exec-2/tx.json", 5)
+            .unwrap();
+
+        // Re-registering the same path updates in place
+        let again = db
+            .register_artifact("exec-1", Some(7), Some(0), "artifacts/exec-1/report.csv", 20)
+            .unwrap();
+        assert_eq!(first, again);
+
+        let listed = db.list_artifacts_for_execution("exec-1").unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].name, "report.csv");
+        assert_eq!(listed[0].mime_type, "text/csv");
+        assert_eq!(listed[0].size_bytes, 20);
+        assert!(listed[1].is_image());
+
+        assert_eq!(db.list_artifacts_for_session(8).unwrap().len(), 1);
+        assert!(db.get_artifact(first).unwrap().is_some());
+    }
+}
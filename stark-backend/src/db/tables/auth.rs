@@ -1,11 +1,39 @@
 //! Auth sessions and auth challenges database operations
 
 use chrono::{DateTime, Duration, Utc};
-use rusqlite::Result as SqliteResult;
+use rusqlite::{OptionalExtension, Result as SqliteResult, TransactionBehavior};
+use sha2::{Digest, Sha256};
 
-use crate::models::Session;
+use crate::models::{AuthDevice, Session};
 use super::super::Database;
 
+/// How long a refresh token can be exchanged for a new session token
+const REFRESH_TOKEN_DAYS: i64 = 30;
+
+/// Outcome of exchanging a refresh token
+pub enum RefreshOutcome {
+    /// New session token and refresh token for the same device
+    Rotated {
+        session: Session,
+        refresh_token: String,
+        refresh_expires_at: DateTime<Utc>,
+    },
+    /// An already rotated refresh token was presented again, so it was copied;
+    /// the device's session has been revoked
+    Reused,
+    Invalid,
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// A fresh refresh token and its expiry
+fn new_refresh_token() -> (String, DateTime<Utc>) {
+    let bytes: [u8; 32] = rand::random();
+    (hex::encode(bytes), Utc::now() + Duration::days(REFRESH_TOKEN_DAYS))
+}
+
 impl Database {
     // ============================================
     // Auth Session methods (for web login sessions)
@@ -50,6 +78,12 @@ impl Database {
     }
 
     pub fn validate_session(&self, token: &str) -> SqliteResult<Option<Session>> {
+        // Access keys are never a session on their own: they are accepted
+        // only through the scope-aware access key checks
+        if token.starts_with(super::access_keys::ACCESS_KEY_PREFIX) {
+            return Ok(None);
+        }
+
         let conn = self.conn();
         let now = Utc::now();
         let now_str = now.to_rfc3339();
@@ -80,8 +114,8 @@ impl Database {
         if session.is_some() {
             let new_expires = (now + Duration::hours(24)).to_rfc3339();
             let _ = conn.execute(
-                "UPDATE auth_sessions SET expires_at = ?1 WHERE token = ?2",
-                [&new_expires, token],
+                "UPDATE auth_sessions SET expires_at = ?1, last_active_at = ?2 WHERE token = ?3",
+                [&new_expires, &now_str, token],
             );
         }
 
@@ -94,6 +128,157 @@ impl Database {
         Ok(rows_affected > 0)
     }
 
+    // ============================================
+    // Devices and refresh tokens
+    // ============================================
+
+    /// Record the client a session was created from
+    pub fn set_session_device(
+        &self,
+        session_id: i64,
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE auth_sessions SET user_agent = ?1, ip_address = ?2, last_active_at = ?3 WHERE id = ?4",
+            rusqlite::params![user_agent, ip_address, Utc::now().to_rfc3339(), session_id],
+        )?;
+        Ok(())
+    }
+
+    /// Give a session a new refresh token (stored hashed), retiring the old one
+    pub fn issue_refresh_token(&self, session_id: i64) -> SqliteResult<(String, DateTime<Utc>)> {
+        let conn = self.conn();
+        let (refresh_token, expires_at) = new_refresh_token();
+        conn.execute(
+            "UPDATE auth_sessions SET previous_refresh_token_hash = refresh_token_hash,
+                 refresh_token_hash = ?1, refresh_expires_at = ?2
+             WHERE id = ?3",
+            rusqlite::params![hash_token(&refresh_token), expires_at.to_rfc3339(), session_id],
+        )?;
+        Ok((refresh_token, expires_at))
+    }
+
+    /// Exchange a refresh token for a new session token and refresh token.
+    /// Both old tokens stop working.
+    ///
+    /// The exchange is a compare-and-swap on the presented token's hash inside
+    /// one write transaction, so of two concurrent exchanges of the same token
+    /// only one rotates and the other revokes the device as a reuse.
+    pub fn refresh_session(&self, refresh_token: &str) -> SqliteResult<RefreshOutcome> {
+        let hash = hash_token(refresh_token);
+        let now = Utc::now();
+        let mut conn = self.conn();
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let current: Option<(i64, String)> = tx
+            .query_row(
+                "SELECT id, created_at FROM auth_sessions WHERE refresh_token_hash = ?1 AND refresh_expires_at > ?2",
+                rusqlite::params![hash, now.to_rfc3339()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        let Some((id, created_at)) = current else {
+            // A refresh token that was already rotated away means two clients
+            // hold it: revoke the device rather than guess which one is legitimate
+            let revoked = tx.execute(
+                "DELETE FROM auth_sessions WHERE previous_refresh_token_hash = ?1",
+                [&hash],
+            )?;
+            tx.commit()?;
+            return Ok(if revoked > 0 { RefreshOutcome::Reused } else { RefreshOutcome::Invalid });
+        };
+
+        let token = Self::generate_session_token();
+        let expires_at = now + Duration::hours(24);
+        let (refresh_token, refresh_expires_at) = new_refresh_token();
+        let rotated = tx.execute(
+            "UPDATE auth_sessions SET token = ?1, expires_at = ?2, last_active_at = ?3,
+                 previous_refresh_token_hash = refresh_token_hash,
+                 refresh_token_hash = ?4, refresh_expires_at = ?5
+             WHERE id = ?6 AND refresh_token_hash = ?7",
+            rusqlite::params![
+                token,
+                expires_at.to_rfc3339(),
+                now.to_rfc3339(),
+                hash_token(&refresh_token),
+                refresh_expires_at.to_rfc3339(),
+                id,
+                hash,
+            ],
+        )?;
+        if rotated == 0 {
+            // Someone else exchanged this token first
+            tx.execute("DELETE FROM auth_sessions WHERE id = ?1", [id])?;
+            tx.commit()?;
+            return Ok(RefreshOutcome::Reused);
+        }
+        tx.commit()?;
+
+        Ok(RefreshOutcome::Rotated {
+            session: Session {
+                id,
+                token,
+                created_at: DateTime::parse_from_rfc3339(&created_at)
+                    .map(|d| d.with_timezone(&Utc))
+                    .unwrap_or(now),
+                expires_at,
+            },
+            refresh_token,
+            refresh_expires_at,
+        })
+    }
+
+    /// Sessions that are still usable or can still be refreshed, most recently active first
+    pub fn list_auth_devices(&self) -> SqliteResult<Vec<AuthDevice>> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let mut stmt = conn.prepare(
+            "SELECT id, public_address, user_agent, ip_address, created_at, last_active_at,
+                    expires_at, refresh_expires_at, second_factor_at
+             FROM auth_sessions
+             WHERE expires_at > ?1 OR refresh_expires_at > ?1
+             ORDER BY COALESCE(last_active_at, created_at) DESC",
+        )?;
+        stmt
+            .query_map([&now], |row| {
+                Ok(AuthDevice {
+                    id: row.get(0)?,
+                    public_address: row.get(1)?,
+                    user_agent: row.get(2)?,
+                    ip_address: row.get(3)?,
+                    created_at: row.get(4)?,
+                    last_active_at: row.get(5)?,
+                    expires_at: row.get(6)?,
+                    refresh_expires_at: row.get(7)?,
+                    second_factor_at: row.get(8)?,
+                })
+            })?
+            .collect()
+    }
+
+    /// Id of the session behind a token, expired or not
+    pub fn session_id_for_token(&self, token: &str) -> SqliteResult<Option<i64>> {
+        let conn = self.conn();
+        conn.query_row("SELECT id FROM auth_sessions WHERE token = ?1", [token], |row| row.get(0))
+            .optional()
+    }
+
+    /// Revoke one device: its session token and refresh token stop working
+    pub fn delete_session_by_id(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows_affected = conn.execute("DELETE FROM auth_sessions WHERE id = ?1", [id])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Revoke every device except the one using `token`
+    pub fn delete_other_sessions(&self, token: &str) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM auth_sessions WHERE token != ?1", [token])
+    }
+
     // ============================================
     // Auth Challenge methods (for SIWE)
     // ============================================
@@ -148,3 +333,60 @@ impl Database {
         Ok(rows_affected > 0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_rotates_and_detects_reuse() {
+        let db = Database::new(":memory:").unwrap();
+        let session = db.create_session_for_address(Some("0xabc")).unwrap();
+        db.set_session_device(session.id, Some("Firefox"), Some("10.0.0.1")).unwrap();
+        let (first, _) = db.issue_refresh_token(session.id).unwrap();
+
+        let RefreshOutcome::Rotated { session: rotated, refresh_token: second, .. } =
+            db.refresh_session(&first).unwrap()
+        else {
+            panic!("first refresh should rotate");
+        };
+        assert_eq!(rotated.id, session.id);
+        assert!(db.validate_session(&session.token).unwrap().is_none());
+        assert!(db.validate_session(&rotated.token).unwrap().is_some());
+        assert_eq!(db.list_auth_devices().unwrap()[0].user_agent.as_deref(), Some("Firefox"));
+
+        // Replaying the old refresh token revokes the device
+        assert!(matches!(db.refresh_session(&first).unwrap(), RefreshOutcome::Reused));
+        assert!(db.validate_session(&rotated.token).unwrap().is_none());
+        assert!(matches!(db.refresh_session(&second).unwrap(), RefreshOutcome::Invalid));
+    }
+
+    #[test]
+    fn test_concurrent_refresh_rotates_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = std::sync::Arc::new(Database::new(dir.path().join("auth.db").to_str().unwrap()).unwrap());
+        let session = db.create_session().unwrap();
+        let (refresh, _) = db.issue_refresh_token(session.id).unwrap();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let db = db.clone();
+                let refresh = refresh.clone();
+                std::thread::spawn(move || db.refresh_session(&refresh).unwrap())
+            })
+            .collect();
+        let outcomes: Vec<RefreshOutcome> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        let rotated = outcomes.iter().filter(|o| matches!(o, RefreshOutcome::Rotated { .. })).count();
+        let reused = outcomes.iter().filter(|o| matches!(o, RefreshOutcome::Reused)).count();
+        assert_eq!(rotated, 1);
+        assert!(reused >= 1);
+        // The duplicate exchange revoked the device, winner included
+        for outcome in outcomes {
+            if let RefreshOutcome::Rotated { session, refresh_token, .. } = outcome {
+                assert!(db.validate_session(&session.token).unwrap().is_none());
+                assert!(matches!(db.refresh_session(&refresh_token).unwrap(), RefreshOutcome::Invalid));
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Database operations for away_requests — messages queued while the owner
+//! is away (see `crate::away`)
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use crate::channels::types::NormalizedMessage;
+use crate::db::Database;
+use super::super::encryption::{decrypt_field, encrypt_message};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AwayRequest {
+    pub id: i64,
+    pub channel_id: i64,
+    pub channel_type: String,
+    pub chat_id: String,
+    pub chat_name: Option<String>,
+    pub user_id: String,
+    pub user_name: String,
+    pub text: String,
+    pub message_id: Option<String>,
+    pub force_safe_mode: bool,
+    pub shared_chat: bool,
+    pub created_at: String,
+}
+
+impl AwayRequest {
+    /// The queued message, ready to be dispatched again
+    pub fn to_message(&self) -> NormalizedMessage {
+        NormalizedMessage {
+            channel_id: self.channel_id,
+            channel_type: self.channel_type.clone(),
+            chat_id: self.chat_id.clone(),
+            chat_name: self.chat_name.clone(),
+            user_id: self.user_id.clone(),
+            user_name: self.user_name.clone(),
+            text: self.text.clone(),
+            message_id: self.message_id.clone(),
+            session_mode: None,
+            selected_network: None,
+            force_safe_mode: self.force_safe_mode,
+            shared_chat: self.shared_chat,
+            platform_role_ids: vec![],
+            chat_context: None,
+            action: None,
+            attachments: vec![],
+        }
+    }
+}
+
+const COLUMNS: &str = "id, channel_id, channel_type, chat_id, chat_name, user_id, user_name, text, message_id, \
+                       force_safe_mode, shared_chat, created_at";
+
+fn row_to_request(row: &rusqlite::Row) -> rusqlite::Result<AwayRequest> {
+    Ok(AwayRequest {
+        id: row.get(0)?,
+        channel_id: row.get(1)?,
+        channel_type: row.get(2)?,
+        chat_id: row.get(3)?,
+        chat_name: row.get(4)?,
+        user_id: row.get(5)?,
+        user_name: row.get(6)?,
+        text: decrypt_field(row.get(7)?),
+        message_id: row.get(8)?,
+        force_safe_mode: row.get::<_, i64>(9)? != 0,
+        shared_chat: row.get::<_, i64>(10)? != 0,
+        created_at: row.get(11)?,
+    })
+}
+
+impl Database {
+    /// Queue a message until away mode ends
+    pub fn queue_away_request(&self, message: &NormalizedMessage) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO away_requests (channel_id, channel_type, chat_id, chat_name, user_id, user_name, text,
+                                        message_id, force_safe_mode, shared_chat, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                message.channel_id,
+                message.channel_type,
+                message.chat_id,
+                message.chat_name,
+                message.user_id,
+                message.user_name,
+                encrypt_message(&message.text)?,
+                message.message_id,
+                message.force_safe_mode as i64,
+                message.shared_chat as i64,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Queued requests, oldest first
+    pub fn list_away_requests(&self) -> SqliteResult<Vec<AwayRequest>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM away_requests ORDER BY id ASC", COLUMNS))?;
+        let requests = stmt.query_map([], row_to_request)?;
+        requests.collect()
+    }
+
+    pub fn count_away_requests(&self) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.query_row("SELECT COUNT(*) FROM away_requests", [], |row| row.get(0))
+    }
+
+    pub fn delete_away_request(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let deleted = conn.execute("DELETE FROM away_requests WHERE id = ?1", [id])?;
+        Ok(deleted > 0)
+    }
+
+    /// Drop every queued request; returns how many there were
+    pub fn clear_away_requests(&self) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM away_requests", [])
+    }
+}
@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 use rusqlite::Result as SqliteResult;
 use std::collections::HashMap;
 
-use crate::models::{BotSettings, DEFAULT_MAX_TOOL_ITERATIONS, DEFAULT_SAFE_MODE_MAX_QUERIES_PER_10MIN};
+use crate::models::{BotSettings, DEFAULT_MAX_TOOL_ITERATIONS, DEFAULT_SAFE_MODE_MAX_QUERIES_PER_10MIN, DEFAULT_TX_APPROVAL_TTL_SECS};
 use super::super::Database;
 
 impl Database {
@@ -17,7 +17,7 @@ impl Database {
         let conn = self.conn();
 
         let result = conn.query_row(
-            "SELECT id, bot_name, bot_email, web3_tx_requires_confirmation, rpc_provider, custom_rpc_endpoints, max_tool_iterations, rogue_mode_enabled, safe_mode_max_queries_per_10min, keystore_url, chat_session_memory_generation, guest_dashboard_enabled, theme_accent, proxy_url, kanban_auto_execute, created_at, updated_at, coalescing_enabled, coalescing_debounce_ms, coalescing_max_wait_ms, compaction_background_threshold, compaction_aggressive_threshold, compaction_emergency_threshold, whisper_server_url, embeddings_server_url FROM bot_settings LIMIT 1",
+            "SELECT id, bot_name, bot_email, web3_tx_requires_confirmation, rpc_provider, custom_rpc_endpoints, max_tool_iterations, rogue_mode_enabled, safe_mode_max_queries_per_10min, keystore_url, chat_session_memory_generation, guest_dashboard_enabled, theme_accent, proxy_url, kanban_auto_execute, created_at, updated_at, coalescing_enabled, coalescing_debounce_ms, coalescing_max_wait_ms, compaction_background_threshold, compaction_aggressive_threshold, compaction_emergency_threshold, whisper_server_url, embeddings_server_url, tx_approval_threshold_eth, tx_approval_channel_id, tx_approval_chat_id, tx_approval_ttl_secs, safe_address, safe_network, paper_trading_enabled, receipts_log_enabled, read_only_mode, cors_allowed_origins FROM bot_settings LIMIT 1",
             [],
             |row| {
                 let web3_tx_confirmation: i64 = row.get(3)?;
@@ -42,6 +42,19 @@ impl Database {
                 let compaction_emergency_threshold: f64 = row.get::<_, Option<f64>>(22)?.unwrap_or(0.95);
                 let whisper_server_url: Option<String> = row.get(23)?;
                 let embeddings_server_url: Option<String> = row.get(24)?;
+                let tx_approval_threshold_eth: Option<f64> = row.get(25)?;
+                let tx_approval_channel_id: Option<i64> = row.get(26)?;
+                let tx_approval_chat_id: Option<String> = row.get(27)?;
+                let tx_approval_ttl_secs: i64 = row.get::<_, Option<i64>>(28)?.unwrap_or(DEFAULT_TX_APPROVAL_TTL_SECS);
+                let safe_address: Option<String> = row.get(29)?;
+                let safe_network: Option<String> = row.get(30)?;
+                let paper_trading_enabled: i64 = row.get::<_, Option<i64>>(31)?.unwrap_or(0);
+                let receipts_log_enabled: i64 = row.get::<_, Option<i64>>(32)?.unwrap_or(0);
+                let read_only_mode: i64 = row.get::<_, Option<i64>>(33)?.unwrap_or(0);
+                let cors_allowed_origins: Vec<String> = row
+                    .get::<_, Option<String>>(34)?
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default();
 
                 let custom_rpc_endpoints: Option<HashMap<String, String>> = custom_rpc_endpoints_json
                     .and_then(|json| serde_json::from_str(&json).ok());
@@ -70,6 +83,16 @@ impl Database {
                     compaction_background_threshold,
                     compaction_aggressive_threshold,
                     compaction_emergency_threshold,
+                    tx_approval_threshold_eth,
+                    tx_approval_channel_id,
+                    tx_approval_chat_id,
+                    tx_approval_ttl_secs,
+                    safe_address,
+                    safe_network,
+                    paper_trading_enabled: paper_trading_enabled != 0,
+                    receipts_log_enabled: receipts_log_enabled != 0,
+                    read_only_mode: read_only_mode != 0,
+                    cors_allowed_origins,
                     created_at: DateTime::parse_from_rfc3339(&created_at_str)
                         .unwrap()
                         .with_timezone(&Utc),
@@ -264,4 +287,84 @@ impl Database {
         self.cache.invalidate_bot_settings();
         self.get_bot_settings()
     }
+
+    /// Update the chat approval policy for transactions.
+    /// A None threshold disables chat approvals; channel and chat are replaced as given.
+    pub fn update_tx_approval_policy(
+        &self,
+        threshold_eth: Option<f64>,
+        channel_id: Option<i64>,
+        chat_id: Option<&str>,
+        ttl_secs: i64,
+    ) -> SqliteResult<BotSettings> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE bot_settings SET tx_approval_threshold_eth = ?1, tx_approval_channel_id = ?2, tx_approval_chat_id = ?3, tx_approval_ttl_secs = ?4, updated_at = ?5",
+            rusqlite::params![threshold_eth, channel_id, chat_id.filter(|c| !c.is_empty()), ttl_secs, Utc::now().to_rfc3339()],
+        )?;
+        drop(conn);
+        self.cache.invalidate_bot_settings();
+        self.get_bot_settings()
+    }
+
+    /// Set (or clear) the treasury Safe used by propose_safe_tx
+    pub fn update_safe_config(&self, safe_address: Option<&str>, safe_network: Option<&str>) -> SqliteResult<BotSettings> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE bot_settings SET safe_address = ?1, safe_network = ?2, updated_at = ?3",
+            rusqlite::params![safe_address, safe_network, Utc::now().to_rfc3339()],
+        )?;
+        drop(conn);
+        self.cache.invalidate_bot_settings();
+        self.get_bot_settings()
+    }
+
+    /// Turn global paper trading on or off
+    pub fn update_paper_trading(&self, enabled: bool) -> SqliteResult<BotSettings> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE bot_settings SET paper_trading_enabled = ?1, updated_at = ?2",
+            rusqlite::params![enabled as i64, Utc::now().to_rfc3339()],
+        )?;
+        drop(conn);
+        self.cache.invalidate_bot_settings();
+        self.get_bot_settings()
+    }
+
+    /// Turn the public action receipts log on or off
+    pub fn update_receipts_log(&self, enabled: bool) -> SqliteResult<BotSettings> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE bot_settings SET receipts_log_enabled = ?1, updated_at = ?2",
+            rusqlite::params![enabled as i64, Utc::now().to_rfc3339()],
+        )?;
+        drop(conn);
+        self.cache.invalidate_bot_settings();
+        self.get_bot_settings()
+    }
+
+    /// Replace the CORS origin allow-list
+    pub fn update_cors_allowed_origins(&self, origins: &[String]) -> SqliteResult<BotSettings> {
+        let conn = self.conn();
+        let json = serde_json::to_string(origins).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            "UPDATE bot_settings SET cors_allowed_origins = ?1, updated_at = ?2",
+            rusqlite::params![json, Utc::now().to_rfc3339()],
+        )?;
+        drop(conn);
+        self.cache.invalidate_bot_settings();
+        self.get_bot_settings()
+    }
+
+    /// Turn read-only mirror mode on or off
+    pub fn update_read_only_mode(&self, enabled: bool) -> SqliteResult<BotSettings> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE bot_settings SET read_only_mode = ?1, updated_at = ?2",
+            rusqlite::params![enabled as i64, Utc::now().to_rfc3339()],
+        )?;
+        drop(conn);
+        self.cache.invalidate_bot_settings();
+        self.get_bot_settings()
+    }
 }
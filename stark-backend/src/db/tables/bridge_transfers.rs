@@ -0,0 +1,228 @@
+//! Bridge transfer database operations (bridge_transfers)
+//!
+//! One row per bridge queued by `bridge_usdc`, keyed by the UUID of the queued
+//! bridge transaction. The bridge tracker moves a transfer from `queued` to
+//! `in_flight` once the source transaction is broadcast, and to `completed`
+//! when the matching USDC transfer lands on the destination chain (or
+//! `timed_out` / `cancelled` when it doesn't).
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+pub const BRIDGE_QUEUED: &str = "queued";
+pub const BRIDGE_IN_FLIGHT: &str = "in_flight";
+pub const BRIDGE_COMPLETED: &str = "completed";
+pub const BRIDGE_TIMED_OUT: &str = "timed_out";
+pub const BRIDGE_CANCELLED: &str = "cancelled";
+
+/// A cross-chain transfer being tracked to arrival
+#[derive(Debug, Clone, Serialize)]
+pub struct BridgeTransfer {
+    pub id: i64,
+    /// UUID of the queued bridge transaction
+    pub bridge_tx_uuid: String,
+    /// Source and destination networks (RPC names: base, mainnet, arbitrum, ...)
+    pub from_network: String,
+    pub to_network: String,
+    /// Human-readable amount sent, e.g. "100"
+    pub amount: String,
+    pub amount_raw: String,
+    /// Smallest destination amount that counts as the fill (after fees and slippage)
+    pub min_output_raw: String,
+    pub recipient: String,
+    /// Token contract credited on the destination chain
+    pub dest_token: String,
+    /// First destination block to search for the fill
+    pub dest_start_block: Option<i64>,
+    pub source_tx_hash: Option<String>,
+    pub dest_tx_hash: Option<String>,
+    /// Channel and chat to notify on completion or timeout
+    pub channel_id: Option<i64>,
+    pub chat_id: Option<String>,
+    pub status: String,
+    pub broadcast_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const BRIDGE_TRANSFER_COLUMNS: &str = "id, bridge_tx_uuid, from_network, to_network, amount, amount_raw, min_output_raw, recipient, \
+                                       dest_token, dest_start_block, source_tx_hash, dest_tx_hash, channel_id, chat_id, status, \
+                                       broadcast_at, completed_at, created_at, updated_at";
+
+fn row_to_bridge_transfer(row: &rusqlite::Row) -> rusqlite::Result<BridgeTransfer> {
+    Ok(BridgeTransfer {
+        id: row.get(0)?,
+        bridge_tx_uuid: row.get(1)?,
+        from_network: row.get(2)?,
+        to_network: row.get(3)?,
+        amount: row.get(4)?,
+        amount_raw: row.get(5)?,
+        min_output_raw: row.get(6)?,
+        recipient: row.get(7)?,
+        dest_token: row.get(8)?,
+        dest_start_block: row.get(9)?,
+        source_tx_hash: row.get(10)?,
+        dest_tx_hash: row.get(11)?,
+        channel_id: row.get(12)?,
+        chat_id: row.get(13)?,
+        status: row.get(14)?,
+        broadcast_at: row.get(15)?,
+        completed_at: row.get(16)?,
+        created_at: row.get(17)?,
+        updated_at: row.get(18)?,
+    })
+}
+
+impl Database {
+    /// Start tracking a bridge that was just queued
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_bridge_transfer(
+        &self,
+        bridge_tx_uuid: &str,
+        from_network: &str,
+        to_network: &str,
+        amount: &str,
+        amount_raw: &str,
+        min_output_raw: &str,
+        recipient: &str,
+        dest_token: &str,
+        dest_start_block: Option<i64>,
+        channel_id: Option<i64>,
+        chat_id: Option<&str>,
+    ) -> SqliteResult<BridgeTransfer> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO bridge_transfers (bridge_tx_uuid, from_network, to_network, amount, amount_raw, min_output_raw, recipient,
+                                           dest_token, dest_start_block, channel_id, chat_id, status, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?13)",
+            rusqlite::params![
+                bridge_tx_uuid,
+                from_network,
+                to_network,
+                amount,
+                amount_raw,
+                min_output_raw,
+                recipient.to_lowercase(),
+                dest_token,
+                dest_start_block,
+                channel_id,
+                chat_id,
+                BRIDGE_QUEUED,
+                now
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.query_row(
+            &format!("SELECT {} FROM bridge_transfers WHERE id = ?1", BRIDGE_TRANSFER_COLUMNS),
+            [id],
+            row_to_bridge_transfer,
+        )
+    }
+
+    /// Get a transfer by the UUID of its queued bridge transaction
+    pub fn get_bridge_transfer(&self, bridge_tx_uuid: &str) -> SqliteResult<Option<BridgeTransfer>> {
+        let conn = self.conn();
+        match conn.query_row(
+            &format!("SELECT {} FROM bridge_transfers WHERE bridge_tx_uuid = ?1", BRIDGE_TRANSFER_COLUMNS),
+            [bridge_tx_uuid],
+            row_to_bridge_transfer,
+        ) {
+            Ok(transfer) => Ok(Some(transfer)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Record the broadcast source transaction of a queued transfer
+    pub fn mark_bridge_transfer_in_flight(
+        &self,
+        bridge_tx_uuid: &str,
+        source_tx_hash: &str,
+        dest_start_block: Option<i64>,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE bridge_transfers
+             SET status = ?1, source_tx_hash = ?2, dest_start_block = COALESCE(dest_start_block, ?3),
+                 broadcast_at = ?4, updated_at = ?4
+             WHERE bridge_tx_uuid = ?5 AND status = ?6",
+            rusqlite::params![BRIDGE_IN_FLIGHT, source_tx_hash, dest_start_block, now, bridge_tx_uuid, BRIDGE_QUEUED],
+        )?;
+        Ok(())
+    }
+
+    /// Move a transfer to `completed`, `timed_out` or `cancelled`. A timed-out
+    /// transfer can still complete if the fill turns up late; completed and
+    /// cancelled transfers are never changed.
+    pub fn finish_bridge_transfer(&self, bridge_tx_uuid: &str, status: &str, dest_tx_hash: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let completed_at = (status == BRIDGE_COMPLETED).then(|| now.clone());
+        conn.execute(
+            "UPDATE bridge_transfers SET status = ?1, dest_tx_hash = COALESCE(?2, dest_tx_hash), completed_at = ?3, updated_at = ?4
+             WHERE bridge_tx_uuid = ?5 AND status NOT IN (?6, ?7)",
+            rusqlite::params![status, dest_tx_hash, completed_at, now, bridge_tx_uuid, BRIDGE_COMPLETED, BRIDGE_CANCELLED],
+        )?;
+        Ok(())
+    }
+
+    /// Transfers the tracker still has to follow (queued or in flight), oldest first
+    pub fn list_active_bridge_transfers(&self, limit: usize) -> SqliteResult<Vec<BridgeTransfer>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM bridge_transfers WHERE status IN (?1, ?2) ORDER BY id ASC LIMIT ?3",
+            BRIDGE_TRANSFER_COLUMNS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![BRIDGE_QUEUED, BRIDGE_IN_FLIGHT, limit as i64], row_to_bridge_transfer)?;
+        rows.collect()
+    }
+
+    /// List transfers, newest first
+    pub fn list_bridge_transfers(&self, status: Option<&str>, limit: usize) -> SqliteResult<Vec<BridgeTransfer>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM bridge_transfers WHERE ?1 IS NULL OR status = ?1 ORDER BY id DESC LIMIT ?2",
+            BRIDGE_TRANSFER_COLUMNS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![status, limit as i64], row_to_bridge_transfer)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_lifecycle() {
+        let db = Database::new(":memory:").unwrap();
+        db.create_bridge_transfer(
+            "uuid-1", "base", "polygon", "100", "100000000", "99000000", "0xABC", "0xusdc", None, Some(1), Some("42"),
+        )
+        .unwrap();
+        assert_eq!(db.list_active_bridge_transfers(10).unwrap().len(), 1);
+
+        db.mark_bridge_transfer_in_flight("uuid-1", "0xsource", Some(500)).unwrap();
+        let transfer = db.get_bridge_transfer("uuid-1").unwrap().unwrap();
+        assert_eq!(transfer.status, BRIDGE_IN_FLIGHT);
+        assert_eq!(transfer.dest_start_block, Some(500));
+        assert_eq!(transfer.recipient, "0xabc");
+
+        // A late fill still completes a timed-out transfer...
+        db.finish_bridge_transfer("uuid-1", BRIDGE_TIMED_OUT, None).unwrap();
+        assert!(db.list_active_bridge_transfers(10).unwrap().is_empty());
+        db.finish_bridge_transfer("uuid-1", BRIDGE_COMPLETED, Some("0xdest")).unwrap();
+        // ...but a completed one is final
+        db.finish_bridge_transfer("uuid-1", BRIDGE_TIMED_OUT, None).unwrap();
+        let transfer = db.get_bridge_transfer("uuid-1").unwrap().unwrap();
+        assert_eq!(transfer.status, BRIDGE_COMPLETED);
+        assert_eq!(transfer.dest_tx_hash.as_deref(), Some("0xdest"));
+        assert!(transfer.completed_at.is_some());
+    }
+}
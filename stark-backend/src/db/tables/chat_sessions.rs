@@ -1,11 +1,36 @@
 //! Chat session and session message database operations
 
 use chrono::{DateTime, Timelike, Utc};
-use rusqlite::Result as SqliteResult;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
 
-use crate::models::{ChatSession, CompletionStatus, MessageRole, ResetPolicy, SessionMessage, SessionScope};
+use crate::models::{
+    ChatSession, CompletionStatus, MessageAttachment, MessageRole, ResetPolicy, SessionMessage, SessionScope,
+    ToolCallRecord,
+};
+use super::super::encryption::{decrypt_field, encrypt_message};
 use super::super::Database;
 
+/// One row for `add_session_messages_batch`: session id, role, content,
+/// user id, user name and the structured tool call, if any
+pub type BatchMessage = (i64, MessageRole, String, Option<String>, Option<String>, Option<ToolCallRecord>);
+
+/// Filters for `list_chat_sessions_page`
+#[derive(Debug, Default)]
+pub struct SessionListFilter {
+    pub channel_type: Option<String>,
+    pub channel_id: Option<i64>,
+    /// Substring of the session key (channel type, channel id, platform chat id)
+    pub text: Option<String>,
+}
+
+/// Number of user messages with one sentiment/urgency combination
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MessageTagCount {
+    pub sentiment: String,
+    pub urgency: String,
+    pub count: i64,
+}
+
 impl Database {
     // ============================================
     // Chat Session methods
@@ -125,7 +150,7 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, session_key, agent_id, scope, channel_type, channel_id, platform_chat_id,
              is_active, reset_policy, idle_timeout_minutes, daily_reset_hour,
-             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name
+             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name, response_style
              FROM chat_sessions WHERE id = ?1",
         )?;
 
@@ -143,7 +168,7 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, session_key, agent_id, scope, channel_type, channel_id, platform_chat_id,
              is_active, reset_policy, idle_timeout_minutes, daily_reset_hour,
-             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name
+             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name, response_style
              FROM chat_sessions ORDER BY last_activity_at DESC LIMIT 500",
         )?;
 
@@ -155,6 +180,48 @@ impl Database {
         Ok(sessions)
     }
 
+    /// One page of chat sessions matching `filter`, with the number of sessions
+    /// that match. `sort` must be a column name the caller has whitelisted.
+    pub fn list_chat_sessions_page(
+        &self,
+        filter: &SessionListFilter,
+        sort: &str,
+        descending: bool,
+        limit: usize,
+        offset: usize,
+    ) -> SqliteResult<(Vec<ChatSession>, usize)> {
+        let conn = self.conn();
+        let condition = "WHERE (?1 IS NULL OR channel_type = ?1) AND (?2 IS NULL OR channel_id = ?2)
+             AND (?3 IS NULL OR session_key LIKE '%' || ?3 || '%')";
+        let channel_type = filter.channel_type.as_deref();
+        let text = filter.text.as_deref();
+
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM chat_sessions {}", condition),
+            rusqlite::params![channel_type, filter.channel_id, text],
+            |row| row.get(0),
+        )?;
+
+        let direction = if descending { "DESC" } else { "ASC" };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, session_key, agent_id, scope, channel_type, channel_id, platform_chat_id,
+             is_active, reset_policy, idle_timeout_minutes, daily_reset_hour,
+             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name, response_style
+             FROM chat_sessions {} ORDER BY {} {}, id {} LIMIT ?4 OFFSET ?5",
+            condition, sort, direction, direction,
+        ))?;
+
+        let sessions = stmt
+            .query_map(
+                rusqlite::params![channel_type, filter.channel_id, text, limit as i64, offset as i64],
+                |row| Self::row_to_chat_session(row),
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok((sessions, total as usize))
+    }
+
     /// Get a chat session by session key
     pub fn get_chat_session_by_key(&self, session_key: &str) -> SqliteResult<Option<ChatSession>> {
         let conn = self.conn();
@@ -162,7 +229,7 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, session_key, agent_id, scope, channel_type, channel_id, platform_chat_id,
              is_active, reset_policy, idle_timeout_minutes, daily_reset_hour,
-             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name
+             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name, response_style
              FROM chat_sessions WHERE session_key = ?1 AND is_active = 1",
         )?;
 
@@ -185,7 +252,7 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, session_key, agent_id, scope, channel_type, channel_id, platform_chat_id,
              is_active, reset_policy, idle_timeout_minutes, daily_reset_hour,
-             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name
+             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name, response_style
              FROM chat_sessions
              WHERE channel_type = ?1 AND channel_id = ?2 AND is_active = 1
              ORDER BY last_activity_at DESC LIMIT 1",
@@ -405,6 +472,16 @@ impl Database {
         Ok(())
     }
 
+    /// Set (or with None, clear) the response style preset on a session
+    pub fn set_session_response_style(&self, id: i64, style: Option<&str>) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let updated = conn.execute(
+            "UPDATE chat_sessions SET response_style = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![style, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(updated > 0)
+    }
+
     fn row_to_chat_session(row: &rusqlite::Row) -> rusqlite::Result<ChatSession> {
         let created_at_str: String = row.get(11)?;
         let updated_at_str: String = row.get(12)?;
@@ -448,6 +525,7 @@ impl Database {
             },
             safe_mode: row.get::<_, i32>(19).unwrap_or(0) != 0,
             special_role_name: row.get::<_, Option<String>>(20).unwrap_or(None),
+            response_style: row.get::<_, Option<String>>(21).unwrap_or(None),
         })
     }
 
@@ -465,23 +543,54 @@ impl Database {
         user_name: Option<&str>,
         platform_message_id: Option<&str>,
         tokens_used: Option<i32>,
+    ) -> SqliteResult<SessionMessage> {
+        self.add_session_message_with_attachments(
+            session_id,
+            role,
+            content,
+            user_id,
+            user_name,
+            platform_message_id,
+            tokens_used,
+            &[],
+        )
+    }
+
+    /// Add a message together with references to the files attached to it
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_session_message_with_attachments(
+        &self,
+        session_id: i64,
+        role: MessageRole,
+        content: &str,
+        user_id: Option<&str>,
+        user_name: Option<&str>,
+        platform_message_id: Option<&str>,
+        tokens_used: Option<i32>,
+        attachments: &[MessageAttachment],
     ) -> SqliteResult<SessionMessage> {
         let conn = self.conn();
         let now = Utc::now();
         let now_str = now.to_rfc3339();
+        let attachments_json = if attachments.is_empty() {
+            None
+        } else {
+            serde_json::to_string(attachments).ok()
+        };
 
         conn.execute(
-            "INSERT INTO session_messages (session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO session_messages (session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at, attachments)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             rusqlite::params![
                 session_id,
                 role.as_str(),
-                content,
+                encrypt_message(content)?,
                 user_id,
                 user_name,
                 platform_message_id,
                 tokens_used,
                 &now_str,
+                attachments_json,
             ],
         )?;
 
@@ -497,15 +606,33 @@ impl Database {
             platform_message_id: platform_message_id.map(|s| s.to_string()),
             tokens_used,
             created_at: now,
+            attachments: attachments.to_vec(),
+            tool_call: None,
         })
     }
 
-    /// Batch insert multiple session messages in a single transaction.
-    /// Much faster than individual inserts when saving tool call/result pairs.
-    pub fn add_session_messages_batch(
+    /// Add a ToolCall or ToolResult message with its structured record
+    pub fn add_tool_session_message(
         &self,
-        messages: &[(i64, MessageRole, String, Option<String>, Option<String>)],
+        session_id: i64,
+        role: MessageRole,
+        content: &str,
+        user_name: Option<&str>,
+        record: &ToolCallRecord,
     ) -> SqliteResult<()> {
+        self.add_session_messages_batch(&[(
+            session_id,
+            role,
+            content.to_string(),
+            None,
+            user_name.map(|s| s.to_string()),
+            Some(record.clone()),
+        )])
+    }
+
+    /// Batch insert multiple session messages in a single transaction.
+    /// Much faster than individual inserts when saving tool call/result pairs.
+    pub fn add_session_messages_batch(&self, messages: &[BatchMessage]) -> SqliteResult<()> {
         if messages.is_empty() {
             return Ok(());
         }
@@ -513,18 +640,25 @@ impl Database {
         let tx = conn.unchecked_transaction()?;
         {
             let mut stmt = tx.prepare_cached(
-                "INSERT INTO session_messages (session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, NULL, NULL, ?6)",
+                "INSERT INTO session_messages (session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at, tool_call, tool_call_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, NULL, NULL, ?6, ?7, ?8)",
             )?;
             let now_str = Utc::now().to_rfc3339();
-            for (session_id, role, content, _user_id, user_name) in messages {
+            for (session_id, role, content, _user_id, user_name, tool_call) in messages {
+                let tool_call_json = tool_call
+                    .as_ref()
+                    .and_then(|r| serde_json::to_string(r).ok())
+                    .map(|j| encrypt_message(&j))
+                    .transpose()?;
                 stmt.execute(rusqlite::params![
                     session_id,
                     role.as_str(),
-                    content,
+                    encrypt_message(content)?,
                     Option::<&str>::None,
                     user_name.as_deref(),
                     &now_str,
+                    tool_call_json,
+                    tool_call.as_ref().map(|r| r.call_id.as_str()),
                 ])?;
             }
         }
@@ -537,7 +671,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at
+            "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at, attachments, tool_call
              FROM session_messages WHERE session_id = ?1 ORDER BY created_at ASC",
         )?;
 
@@ -554,7 +688,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at
+            "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at, attachments, tool_call
              FROM session_messages WHERE session_id = ?1 ORDER BY created_at DESC LIMIT ?2",
         )?;
 
@@ -578,6 +712,33 @@ impl Database {
         )
     }
 
+    /// Store the triage tags of a user message
+    pub fn set_session_message_tags(&self, message_id: i64, sentiment: &str, urgency: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE session_messages SET sentiment = ?1, urgency = ?2 WHERE id = ?3",
+            rusqlite::params![sentiment, urgency, message_id],
+        )?;
+        Ok(())
+    }
+
+    /// Tagged user messages per sentiment and urgency, optionally only the last `since_hours`
+    pub fn count_message_tags(&self, since_hours: Option<i64>) -> SqliteResult<Vec<MessageTagCount>> {
+        let cutoff = since_hours
+            .map(|h| (Utc::now() - chrono::Duration::hours(h)).to_rfc3339())
+            .unwrap_or_default();
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT sentiment, urgency, COUNT(*) FROM session_messages
+             WHERE role = 'user' AND sentiment IS NOT NULL AND created_at >= ?1
+             GROUP BY sentiment, urgency ORDER BY sentiment, urgency",
+        )?;
+        let counts = stmt.query_map([cutoff], |row| {
+            Ok(MessageTagCount { sentiment: row.get(0)?, urgency: row.get(1)?, count: row.get(2)? })
+        })?;
+        counts.collect()
+    }
+
     /// Get the first user message for a session (for showing initial query)
     pub fn get_first_user_message(&self, session_id: i64) -> SqliteResult<Option<String>> {
         let conn = self.conn();
@@ -586,7 +747,7 @@ impl Database {
              WHERE session_id = ?1 AND role = 'user'
              ORDER BY created_at ASC LIMIT 1",
             [session_id],
-            |row| row.get(0),
+            |row| row.get(0).map(decrypt_field),
         ).map(Some).or_else(|e| {
             if matches!(e, rusqlite::Error::QueryReturnedNoRows) {
                 Ok(None)
@@ -605,7 +766,7 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, session_key, agent_id, scope, channel_type, channel_id, platform_chat_id,
              is_active, reset_policy, idle_timeout_minutes, daily_reset_hour,
-             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name
+             created_at, updated_at, last_activity_at, expires_at, context_tokens, max_context_tokens, compaction_id, completion_status, safe_mode, special_role_name, response_style
              FROM chat_sessions
              WHERE channel_type = 'heartbeat'
              ORDER BY created_at DESC
@@ -637,7 +798,7 @@ impl Database {
              WHERE session_id = ?1 AND role = 'user'
              ORDER BY created_at ASC LIMIT 1",
             [session_id],
-            |row| row.get(0),
+            |row| row.get(0).map(decrypt_field),
         ).ok();
 
         content.and_then(|text| {
@@ -662,7 +823,7 @@ impl Database {
             id: row.get(0)?,
             session_id: row.get(1)?,
             role: MessageRole::from_str(&role_str).unwrap_or(MessageRole::User),
-            content: row.get(3)?,
+            content: decrypt_field(row.get(3)?),
             user_id: row.get(4)?,
             user_name: row.get(5)?,
             platform_message_id: row.get(6)?,
@@ -670,9 +831,52 @@ impl Database {
             created_at: DateTime::parse_from_rfc3339(&created_at_str)
                 .unwrap()
                 .with_timezone(&Utc),
+            attachments: Self::parse_attachments(row.get(9)?),
+            tool_call: Self::parse_tool_call(row.get(10)?),
         })
     }
 
+    fn parse_attachments(json: Option<String>) -> Vec<MessageAttachment> {
+        json.and_then(|j| serde_json::from_str(&j).ok()).unwrap_or_default()
+    }
+
+    fn parse_tool_call(json: Option<String>) -> Option<ToolCallRecord> {
+        json.and_then(|j| serde_json::from_str(&decrypt_field(j)).ok())
+    }
+
+    /// Tool calls and results since the last user message, oldest first, so a
+    /// client that reconnects mid-run can rebuild the run from the database
+    pub fn get_current_turn_tool_messages(&self, session_id: i64) -> SqliteResult<Vec<SessionMessage>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at, attachments, tool_call
+             FROM session_messages
+             WHERE session_id = ?1 AND role IN ('tool_call', 'tool_result')
+               AND id > COALESCE((SELECT MAX(id) FROM session_messages WHERE session_id = ?1 AND role = 'user'), 0)
+             ORDER BY id ASC",
+        )?;
+        let messages = stmt
+            .query_map([session_id], |row| Self::row_to_session_message(row))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(messages)
+    }
+
+    /// The tool call still running in a session: tools run one at a time, so
+    /// it is the newest message when that is a call with no result after it
+    pub fn get_running_tool_call(&self, session_id: i64) -> SqliteResult<Option<SessionMessage>> {
+        let conn = self.conn();
+        let latest = conn
+            .query_row(
+                "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at, attachments, tool_call
+                 FROM session_messages WHERE session_id = ?1 ORDER BY id DESC LIMIT 1",
+                [session_id],
+                |row| Self::row_to_session_message(row),
+            )
+            .optional()?;
+        Ok(latest.filter(|m| m.role == MessageRole::ToolCall && m.tool_call.is_some()))
+    }
+
     // ============================================
     // Context Management methods (compaction)
     // ============================================
@@ -716,7 +920,7 @@ impl Database {
         }
 
         let mut stmt = conn.prepare(
-            "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at
+            "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at, attachments, tool_call
              FROM session_messages WHERE session_id = ?1 ORDER BY created_at ASC LIMIT ?2",
         )?;
 
@@ -729,7 +933,7 @@ impl Database {
                     id: row.get(0)?,
                     session_id: row.get(1)?,
                     role: MessageRole::from_str(&role_str).unwrap_or(MessageRole::User),
-                    content: row.get(3)?,
+                    content: decrypt_field(row.get(3)?),
                     user_id: row.get(4)?,
                     user_name: row.get(5)?,
                     platform_message_id: row.get(6)?,
@@ -737,6 +941,8 @@ impl Database {
                     created_at: chrono::DateTime::parse_from_rfc3339(&created_at_str)
                         .unwrap()
                         .with_timezone(&Utc),
+                    attachments: Self::parse_attachments(row.get(9)?),
+                    tool_call: Self::parse_tool_call(row.get(10)?),
                 })
             })?
             .filter_map(|r| r.ok())
@@ -774,6 +980,18 @@ impl Database {
         Ok(summary)
     }
 
+    /// Sessions that have a compaction summary: (session_id, session_key, summary, updated_at)
+    pub fn list_session_summaries(&self) -> SqliteResult<Vec<(i64, String, String, String)>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, session_key, compaction_summary, updated_at FROM chat_sessions
+             WHERE compaction_summary IS NOT NULL AND compaction_summary != ''
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?;
+        rows.collect()
+    }
+
     /// Set the compaction summary text for a session
     pub fn set_session_compaction_summary(&self, session_id: i64, summary: &str) -> SqliteResult<()> {
         let conn = self.conn();
@@ -810,6 +1028,20 @@ impl Database {
         }))
     }
 
+    /// Get when the session was last compacted
+    pub fn get_session_last_compaction(&self, session_id: i64) -> SqliteResult<Option<chrono::DateTime<Utc>>> {
+        let conn = self.conn();
+        let compacted_str: Option<String> = conn.query_row(
+            "SELECT last_compaction_at FROM chat_sessions WHERE id = ?1",
+            [session_id],
+            |row| row.get(0),
+        ).ok().flatten();
+
+        Ok(compacted_str.and_then(|s| {
+            chrono::DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))
+        }))
+    }
+
     // ============================================
     // Sliding Window Compaction methods
     // ============================================
@@ -819,7 +1051,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at
+            "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at, attachments, tool_call
              FROM session_messages WHERE session_id = ?1 ORDER BY created_at ASC LIMIT ?2",
         )?;
 
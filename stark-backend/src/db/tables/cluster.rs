@@ -0,0 +1,179 @@
+//! Cluster coordination database operations (cluster_leases, cluster_instances)
+//!
+//! Leases are time-bounded named locks: a holder keeps one by renewing it before
+//! `expires_at`; anyone may take over an expired lease. They back both leader
+//! election for singleton workers and per-conversation execution locks.
+
+use chrono::{Duration, Utc};
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+/// A named lease and its current holder
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterLease {
+    pub name: String,
+    pub holder: String,
+    pub acquired_at: String,
+    pub expires_at: String,
+}
+
+/// A backend instance sharing this database
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterInstance {
+    pub instance_id: String,
+    pub hostname: String,
+    pub pid: i64,
+    pub version: String,
+    pub started_at: String,
+    pub last_seen: String,
+}
+
+impl Database {
+    /// Acquire or renew a lease. Succeeds if the lease is free, expired, or
+    /// already held by `holder`; returns false if another holder owns it.
+    pub fn try_acquire_lease(&self, name: &str, holder: &str, ttl_secs: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let now = Utc::now();
+        let expires_at = (now + Duration::seconds(ttl_secs)).to_rfc3339();
+        let changed = conn.execute(
+            "INSERT INTO cluster_leases (name, holder, acquired_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(name) DO UPDATE SET
+                acquired_at = CASE WHEN cluster_leases.holder = excluded.holder
+                                   THEN cluster_leases.acquired_at ELSE excluded.acquired_at END,
+                holder = excluded.holder,
+                expires_at = excluded.expires_at
+             WHERE cluster_leases.holder = excluded.holder OR cluster_leases.expires_at < ?3",
+            rusqlite::params![name, holder, now.to_rfc3339(), expires_at],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Release a lease if (and only if) `holder` still owns it
+    pub fn release_lease(&self, name: &str, holder: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let deleted = conn.execute(
+            "DELETE FROM cluster_leases WHERE name = ?1 AND holder = ?2",
+            rusqlite::params![name, holder],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Release every lease held by `holder` (graceful shutdown)
+    pub fn release_all_leases(&self, holder: &str) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM cluster_leases WHERE holder = ?1", [holder])
+    }
+
+    /// List all unexpired leases
+    pub fn list_active_leases(&self) -> SqliteResult<Vec<ClusterLease>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT name, holder, acquired_at, expires_at FROM cluster_leases
+             WHERE expires_at >= ?1 ORDER BY name",
+        )?;
+        let leases = stmt
+            .query_map([Utc::now().to_rfc3339()], |row| {
+                Ok(ClusterLease {
+                    name: row.get(0)?,
+                    holder: row.get(1)?,
+                    acquired_at: row.get(2)?,
+                    expires_at: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(leases)
+    }
+
+    /// Register this instance or refresh its last_seen timestamp
+    pub fn heartbeat_cluster_instance(
+        &self,
+        instance_id: &str,
+        hostname: &str,
+        version: &str,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO cluster_instances (instance_id, hostname, pid, version, started_at, last_seen)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+             ON CONFLICT(instance_id) DO UPDATE SET last_seen = excluded.last_seen",
+            rusqlite::params![instance_id, hostname, std::process::id() as i64, version, now],
+        )?;
+        Ok(())
+    }
+
+    /// List instances seen within the last `active_within_secs` seconds
+    pub fn list_cluster_instances(&self, active_within_secs: i64) -> SqliteResult<Vec<ClusterInstance>> {
+        let conn = self.conn();
+        let cutoff = (Utc::now() - Duration::seconds(active_within_secs)).to_rfc3339();
+        let mut stmt = conn.prepare(
+            "SELECT instance_id, hostname, pid, version, started_at, last_seen
+             FROM cluster_instances WHERE last_seen >= ?1 ORDER BY started_at",
+        )?;
+        let instances = stmt
+            .query_map([cutoff], |row| {
+                Ok(ClusterInstance {
+                    instance_id: row.get(0)?,
+                    hostname: row.get(1)?,
+                    pid: row.get(2)?,
+                    version: row.get(3)?,
+                    started_at: row.get(4)?,
+                    last_seen: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(instances)
+    }
+
+    /// Remove an instance from the registry (graceful shutdown)
+    pub fn remove_cluster_instance(&self, instance_id: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM cluster_instances WHERE instance_id = ?1", [instance_id])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lease_exclusive_until_expiry() {
+        let db = Database::new(":memory:").unwrap();
+        assert!(db.try_acquire_lease("scheduler", "a", 30).unwrap());
+        // Renewal by the holder succeeds, a competitor is refused
+        assert!(db.try_acquire_lease("scheduler", "a", 30).unwrap());
+        assert!(!db.try_acquire_lease("scheduler", "b", 30).unwrap());
+
+        // Only the holder can release
+        assert!(!db.release_lease("scheduler", "b").unwrap());
+        assert!(db.release_lease("scheduler", "a").unwrap());
+        assert!(db.try_acquire_lease("scheduler", "b", 30).unwrap());
+
+        // An expired lease can be taken over
+        assert!(db.try_acquire_lease("exec:web:0:x", "a", -1).unwrap());
+        assert!(db.try_acquire_lease("exec:web:0:x", "b", 30).unwrap());
+        let leases = db.list_active_leases().unwrap();
+        assert_eq!(leases.len(), 2);
+        assert!(leases.iter().all(|l| l.holder == "b"));
+
+        assert_eq!(db.release_all_leases("b").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_instance_registry() {
+        let db = Database::new(":memory:").unwrap();
+        db.heartbeat_cluster_instance("node-1", "host-a", "1.0.0").unwrap();
+        db.heartbeat_cluster_instance("node-1", "host-a", "1.0.0").unwrap();
+        db.heartbeat_cluster_instance("node-2", "host-b", "1.0.0").unwrap();
+        assert_eq!(db.list_cluster_instances(60).unwrap().len(), 2);
+
+        db.remove_cluster_instance("node-2").unwrap();
+        let instances = db.list_cluster_instances(60).unwrap();
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].instance_id, "node-1");
+    }
+}
@@ -0,0 +1,165 @@
+//! Context pre-warming settings and cache (context_prewarm_config, context_prewarm_cache)
+//!
+//! The cache holds one rendered block per section (portfolio, calendar,
+//! watchlist), refreshed by the pre-warm worker ahead of the configured active
+//! hours and injected into the system prompt with its age.
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+use super::super::Database;
+
+/// Pre-warm settings (single row)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrewarmConfig {
+    pub enabled: bool,
+    /// Local-time windows, comma-separated, e.g. "09:00-12:00,18:00-23:00"
+    pub active_hours: String,
+    /// How long before a window opens to start refreshing
+    pub lead_minutes: i64,
+    /// Refresh a section once it is older than this while in a window
+    pub refresh_minutes: i64,
+    /// Comma-separated sections to pre-warm
+    pub sections: String,
+    pub updated_at: Option<String>,
+}
+
+impl Default for PrewarmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            active_hours: "09:00-18:00".to_string(),
+            lead_minutes: 15,
+            refresh_minutes: 30,
+            sections: "portfolio,calendar,watchlist".to_string(),
+            updated_at: None,
+        }
+    }
+}
+
+impl PrewarmConfig {
+    pub fn section_list(&self) -> Vec<String> {
+        self.sections
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+/// A cached, rendered context section
+#[derive(Debug, Clone, Serialize)]
+pub struct PrewarmEntry {
+    pub section: String,
+    pub content: String,
+    pub refreshed_at: String,
+}
+
+impl Database {
+    /// Get the pre-warm settings (defaults if never saved)
+    pub fn get_prewarm_config(&self) -> SqliteResult<PrewarmConfig> {
+        let conn = self.conn();
+        let config = conn
+            .query_row(
+                "SELECT enabled, active_hours, lead_minutes, refresh_minutes, sections, updated_at
+                 FROM context_prewarm_config WHERE id = 1",
+                [],
+                |row| {
+                    Ok(PrewarmConfig {
+                        enabled: row.get::<_, i32>(0)? != 0,
+                        active_hours: row.get(1)?,
+                        lead_minutes: row.get(2)?,
+                        refresh_minutes: row.get(3)?,
+                        sections: row.get(4)?,
+                        updated_at: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(config.unwrap_or_default())
+    }
+
+    /// Save the pre-warm settings
+    pub fn save_prewarm_config(&self, config: &PrewarmConfig) -> SqliteResult<PrewarmConfig> {
+        {
+            let conn = self.conn();
+            conn.execute(
+                "INSERT INTO context_prewarm_config (id, enabled, active_hours, lead_minutes, refresh_minutes, sections, updated_at)
+                 VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(id) DO UPDATE SET
+                    enabled = excluded.enabled,
+                    active_hours = excluded.active_hours,
+                    lead_minutes = excluded.lead_minutes,
+                    refresh_minutes = excluded.refresh_minutes,
+                    sections = excluded.sections,
+                    updated_at = excluded.updated_at",
+                rusqlite::params![
+                    config.enabled as i32,
+                    config.active_hours,
+                    config.lead_minutes,
+                    config.refresh_minutes,
+                    config.sections,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+        }
+        self.get_prewarm_config()
+    }
+
+    /// Replace a cached section
+    pub fn upsert_prewarm_entry(&self, section: &str, content: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO context_prewarm_cache (section, content, refreshed_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(section) DO UPDATE SET content = excluded.content, refreshed_at = excluded.refreshed_at",
+            rusqlite::params![section, content, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn list_prewarm_entries(&self) -> SqliteResult<Vec<PrewarmEntry>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT section, content, refreshed_at FROM context_prewarm_cache ORDER BY section")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(PrewarmEntry {
+                section: row.get(0)?,
+                content: row.get(1)?,
+                refreshed_at: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    pub fn clear_prewarm_cache(&self) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM context_prewarm_cache", [])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults_and_cache_roundtrip() {
+        let db = Database::new(":memory:").unwrap();
+        let config = db.get_prewarm_config().unwrap();
+        assert!(!config.enabled);
+        assert_eq!(config.section_list(), vec!["portfolio", "calendar", "watchlist"]);
+
+        let saved = db
+            .save_prewarm_config(&PrewarmConfig { enabled: true, sections: "portfolio".to_string(), ..config })
+            .unwrap();
+        assert!(saved.enabled);
+        assert!(saved.updated_at.is_some());
+
+        db.upsert_prewarm_entry("portfolio", "old").unwrap();
+        db.upsert_prewarm_entry("portfolio", "new").unwrap();
+        let entries = db.list_prewarm_entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "new");
+
+        assert_eq!(db.clear_prewarm_cache().unwrap(), 1);
+    }
+}
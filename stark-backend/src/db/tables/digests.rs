@@ -0,0 +1,328 @@
+//! Activity digest settings, history and aggregation (digest_config, digest_runs)
+//!
+//! The digest worker reads what the agent did over the period — runs from
+//! `rollouts`, transactions from `broadcasted_transactions`, skills from
+//! `use_skill` calls in `tool_executions`, and notable `memories` — and keeps
+//! each generated digest in `digest_runs`. Those tables mix RFC 3339 and
+//! SQLite `datetime('now')` timestamps, so period filters compare through `datetime()`.
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+use super::super::Database;
+
+/// Digest settings (single row)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestConfig {
+    pub enabled: bool,
+    /// "daily" or "weekly"
+    pub frequency: String,
+    /// Local hour (0-23) the digest goes out
+    pub send_hour: u32,
+    /// Day of a weekly digest, 0 = Monday .. 6 = Sunday
+    pub weekday: u32,
+    /// Comma-separated sections to include
+    pub sections: String,
+    /// Delivery chat; None = the owner's chat from bot settings
+    pub channel_id: Option<i64>,
+    pub chat_id: Option<String>,
+    pub last_sent_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            frequency: "daily".to_string(),
+            send_hour: 8,
+            weekday: 0,
+            sections: "executions,transactions,skills,memories".to_string(),
+            channel_id: None,
+            chat_id: None,
+            last_sent_at: None,
+            updated_at: None,
+        }
+    }
+}
+
+impl DigestConfig {
+    pub fn section_list(&self) -> Vec<String> {
+        self.sections
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+/// A generated digest
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestRun {
+    pub id: i64,
+    pub period_start: String,
+    pub period_end: String,
+    pub content: String,
+    pub delivered: bool,
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+/// Agent runs in the period
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExecutionStats {
+    pub total: i64,
+    pub succeeded: i64,
+    pub failed: i64,
+    pub cancelled: i64,
+    pub tool_calls: i64,
+    pub failed_tool_calls: i64,
+    /// Most used tools: (name, calls)
+    pub top_tools: Vec<(String, i64)>,
+}
+
+/// A transaction broadcast in the period
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestTransaction {
+    pub network: String,
+    pub to_address: String,
+    pub value_formatted: String,
+    pub status: String,
+    pub tx_hash: Option<String>,
+}
+
+/// A memory created in the period
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestMemory {
+    pub memory_type: String,
+    pub content: String,
+    pub importance: i64,
+}
+
+impl Database {
+    /// Get the digest settings (defaults if never saved)
+    pub fn get_digest_config(&self) -> SqliteResult<DigestConfig> {
+        let conn = self.conn();
+        let config = conn
+            .query_row(
+                "SELECT enabled, frequency, send_hour, weekday, sections, channel_id, chat_id, last_sent_at, updated_at
+                 FROM digest_config WHERE id = 1",
+                [],
+                |row| {
+                    Ok(DigestConfig {
+                        enabled: row.get::<_, i32>(0)? != 0,
+                        frequency: row.get(1)?,
+                        send_hour: row.get(2)?,
+                        weekday: row.get(3)?,
+                        sections: row.get(4)?,
+                        channel_id: row.get(5)?,
+                        chat_id: row.get(6)?,
+                        last_sent_at: row.get(7)?,
+                        updated_at: row.get(8)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(config.unwrap_or_default())
+    }
+
+    /// Save the digest settings (last_sent_at is kept as is)
+    pub fn save_digest_config(&self, config: &DigestConfig) -> SqliteResult<DigestConfig> {
+        {
+            let conn = self.conn();
+            conn.execute(
+                "INSERT INTO digest_config (id, enabled, frequency, send_hour, weekday, sections, channel_id, chat_id, updated_at)
+                 VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT(id) DO UPDATE SET
+                    enabled = excluded.enabled,
+                    frequency = excluded.frequency,
+                    send_hour = excluded.send_hour,
+                    weekday = excluded.weekday,
+                    sections = excluded.sections,
+                    channel_id = excluded.channel_id,
+                    chat_id = excluded.chat_id,
+                    updated_at = excluded.updated_at",
+                rusqlite::params![
+                    config.enabled as i32,
+                    config.frequency,
+                    config.send_hour,
+                    config.weekday,
+                    config.sections,
+                    config.channel_id,
+                    config.chat_id,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+        }
+        self.get_digest_config()
+    }
+
+    /// Record when the last scheduled digest went out
+    pub fn mark_digest_sent(&self, sent_at: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO digest_config (id, last_sent_at, updated_at) VALUES (1, ?1, ?1)
+             ON CONFLICT(id) DO UPDATE SET last_sent_at = excluded.last_sent_at",
+            [sent_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_digest_run(
+        &self,
+        period_start: &str,
+        period_end: &str,
+        content: &str,
+        delivered: bool,
+        error: Option<&str>,
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO digest_runs (period_start, period_end, content, delivered, error, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![period_start, period_end, content, delivered as i32, error, Utc::now().to_rfc3339()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Generated digests, newest first
+    pub fn list_digest_runs(&self, limit: usize) -> SqliteResult<Vec<DigestRun>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, period_start, period_end, content, delivered, error, created_at
+             FROM digest_runs ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map([limit as i64], |row| {
+            Ok(DigestRun {
+                id: row.get(0)?,
+                period_start: row.get(1)?,
+                period_end: row.get(2)?,
+                content: row.get(3)?,
+                delivered: row.get::<_, i32>(4)? != 0,
+                error: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Agent runs and tool calls since `since` (RFC 3339)
+    pub fn digest_execution_stats(&self, since: &str, top_tools: usize) -> SqliteResult<ExecutionStats> {
+        let conn = self.conn();
+        let (total, succeeded, failed, cancelled) = conn.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(status = 'succeeded'), 0),
+                    COALESCE(SUM(status = 'failed'), 0),
+                    COALESCE(SUM(status = 'cancelled'), 0)
+             FROM rollouts WHERE datetime(created_at) >= datetime(?1)",
+            [since],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+        let (tool_calls, failed_tool_calls) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(success = 0), 0) FROM tool_executions WHERE datetime(executed_at) >= datetime(?1)",
+            [since],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let mut stmt = conn.prepare(
+            "SELECT tool_name, COUNT(*) AS calls FROM tool_executions WHERE datetime(executed_at) >= datetime(?1)
+             GROUP BY tool_name ORDER BY calls DESC, tool_name LIMIT ?2",
+        )?;
+        let top_tools = stmt
+            .query_map(rusqlite::params![since, top_tools as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        Ok(ExecutionStats { total, succeeded, failed, cancelled, tool_calls, failed_tool_calls, top_tools })
+    }
+
+    /// Transactions broadcast since `since`, newest first
+    pub fn digest_transactions(&self, since: &str, limit: usize) -> SqliteResult<Vec<DigestTransaction>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT network, to_address, value_formatted, status, tx_hash FROM broadcasted_transactions
+             WHERE datetime(broadcast_at) >= datetime(?1) ORDER BY broadcast_at DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since, limit as i64], |row| {
+            Ok(DigestTransaction {
+                network: row.get(0)?,
+                to_address: row.get(1)?,
+                value_formatted: row.get(2)?,
+                status: row.get(3)?,
+                tx_hash: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Skills run since `since`: (skill, runs), most used first
+    pub fn digest_skill_runs(&self, since: &str, limit: usize) -> SqliteResult<Vec<(String, i64)>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(json_extract(parameters, '$.skill_name'), json_extract(parameters, '$.name')) AS skill,
+                    COUNT(*) AS runs
+             FROM tool_executions
+             WHERE tool_name IN ('use_skill', 'invoke_skill') AND datetime(executed_at) >= datetime(?1) AND json_valid(parameters)
+             GROUP BY skill HAVING skill IS NOT NULL ORDER BY runs DESC, skill LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since, limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    /// Memories of at least `min_importance` created since `since`, most important first
+    pub fn digest_notable_memories(&self, since: &str, min_importance: i64, limit: usize) -> SqliteResult<Vec<DigestMemory>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT memory_type, content, importance FROM memories
+             WHERE datetime(created_at) >= datetime(?1) AND importance >= ?2
+             ORDER BY importance DESC, id DESC LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since, min_importance, limit as i64], |row| {
+            Ok(DigestMemory {
+                memory_type: row.get(0)?,
+                content: crate::db::encryption::decrypt_field(row.get(1)?),
+                importance: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_roundtrip_keeps_last_sent() {
+        let db = Database::new(":memory:").unwrap();
+        assert!(!db.get_digest_config().unwrap().enabled);
+
+        db.mark_digest_sent("2026-01-01T08:00:00+00:00").unwrap();
+        let mut config = db.get_digest_config().unwrap();
+        config.enabled = true;
+        config.frequency = "weekly".to_string();
+        config.sections = "executions,memories".to_string();
+        let saved = db.save_digest_config(&config).unwrap();
+        assert!(saved.enabled);
+        assert_eq!(saved.section_list(), vec!["executions".to_string(), "memories".to_string()]);
+        assert_eq!(saved.last_sent_at.as_deref(), Some("2026-01-01T08:00:00+00:00"));
+
+        let stats = db.digest_execution_stats("2026-01-01T00:00:00+00:00", 5).unwrap();
+        assert_eq!(stats.total, 0);
+        assert!(db.digest_skill_runs("2026-01-01T00:00:00+00:00", 5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_notable_memories_are_decrypted() {
+        use crate::db::encryption::{is_encrypted, set_test_cipher, FieldCipher};
+
+        set_test_cipher(FieldCipher::new("digest-key", &[], true));
+        let db = Database::new(":memory:").unwrap();
+        db.insert_memory("fact", "moving to Lisbon in May", None, None, 8, None, None, None, None, None, None, None)
+            .unwrap();
+        let stored: String = db.conn().query_row("SELECT content FROM memories", [], |r| r.get(0)).unwrap();
+        assert!(is_encrypted(&stored));
+
+        let memories = db.digest_notable_memories("2000-01-01T00:00:00+00:00", 5, 10).unwrap();
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0].content, "moving to Lisbon in May");
+    }
+}
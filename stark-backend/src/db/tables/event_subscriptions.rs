@@ -0,0 +1,138 @@
+//! Database operations for event_subscriptions — event bus subscriptions
+//! registered by modules and skills
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use crate::db::Database;
+
+/// Consecutive failed callback deliveries before a subscription is disabled
+pub const MAX_DELIVERY_FAILURES: i64 = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventSubscription {
+    pub id: i64,
+    /// Module or skill name that registered it
+    pub subscriber: String,
+    /// Event patterns: exact names, `prefix.*` or `*`
+    pub events: Vec<String>,
+    /// Events are POSTed here; None for polling-only subscriptions
+    pub callback_url: Option<String>,
+    pub enabled: bool,
+    pub failure_count: i64,
+    pub last_error: Option<String>,
+    pub last_delivered_at: Option<String>,
+    pub created_at: String,
+}
+
+const COLUMNS: &str =
+    "id, subscriber, events, callback_url, enabled, failure_count, last_error, last_delivered_at, created_at";
+
+fn row_to_subscription(row: &rusqlite::Row) -> rusqlite::Result<EventSubscription> {
+    let events: String = row.get(2)?;
+    Ok(EventSubscription {
+        id: row.get(0)?,
+        subscriber: row.get(1)?,
+        events: serde_json::from_str(&events).unwrap_or_default(),
+        callback_url: row.get(3)?,
+        enabled: row.get::<_, i64>(4)? != 0,
+        failure_count: row.get(5)?,
+        last_error: row.get(6)?,
+        last_delivered_at: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+impl Database {
+    pub fn create_event_subscription(
+        &self,
+        subscriber: &str,
+        events: &[String],
+        callback_url: Option<&str>,
+    ) -> SqliteResult<EventSubscription> {
+        let conn = self.conn();
+        let events_json = serde_json::to_string(events).unwrap_or_else(|_| "[]".to_string());
+        conn.query_row(
+            &format!(
+                "INSERT INTO event_subscriptions (subscriber, events, callback_url, created_at)
+                 VALUES (?1, ?2, ?3, ?4) RETURNING {}",
+                COLUMNS
+            ),
+            rusqlite::params![subscriber, events_json, callback_url, Utc::now().to_rfc3339()],
+            row_to_subscription,
+        )
+    }
+
+    pub fn get_event_subscription(&self, id: i64) -> SqliteResult<Option<EventSubscription>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM event_subscriptions WHERE id = ?1", COLUMNS),
+            [id],
+            row_to_subscription,
+        )
+        .optional()
+    }
+
+    pub fn list_event_subscriptions(&self, subscriber: Option<&str>) -> SqliteResult<Vec<EventSubscription>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM event_subscriptions WHERE ?1 IS NULL OR subscriber = ?1 ORDER BY id",
+            COLUMNS
+        ))?;
+        let rows = stmt.query_map([subscriber], row_to_subscription)?;
+        rows.collect()
+    }
+
+    /// Enabled subscriptions that want events pushed to a callback
+    pub fn list_callback_subscriptions(&self) -> SqliteResult<Vec<EventSubscription>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM event_subscriptions WHERE enabled = 1 AND callback_url IS NOT NULL ORDER BY id",
+            COLUMNS
+        ))?;
+        let rows = stmt.query_map([], row_to_subscription)?;
+        rows.collect()
+    }
+
+    pub fn delete_event_subscription(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        Ok(conn.execute("DELETE FROM event_subscriptions WHERE id = ?1", [id])? > 0)
+    }
+
+    /// Re-enable a subscription and reset its failure count
+    pub fn enable_event_subscription(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        Ok(conn.execute(
+            "UPDATE event_subscriptions SET enabled = 1, failure_count = 0, last_error = NULL WHERE id = ?1",
+            [id],
+        )? > 0)
+    }
+
+    /// Record a callback delivery. Returns true when this failure disabled
+    /// the subscription.
+    pub fn record_event_delivery(&self, id: i64, result: &Result<(), String>) -> SqliteResult<bool> {
+        let conn = self.conn();
+        match result {
+            Ok(()) => {
+                conn.execute(
+                    "UPDATE event_subscriptions SET failure_count = 0, last_error = NULL, last_delivered_at = ?2
+                     WHERE id = ?1",
+                    rusqlite::params![id, Utc::now().to_rfc3339()],
+                )?;
+                Ok(false)
+            }
+            Err(e) => {
+                conn.execute(
+                    "UPDATE event_subscriptions SET failure_count = failure_count + 1, last_error = ?2,
+                        enabled = CASE WHEN failure_count + 1 >= ?3 THEN 0 ELSE enabled END
+                     WHERE id = ?1",
+                    rusqlite::params![id, e, MAX_DELIVERY_FAILURES],
+                )?;
+                let enabled: i64 =
+                    conn.query_row("SELECT enabled FROM event_subscriptions WHERE id = ?1", [id], |row| row.get(0))?;
+                Ok(enabled == 0)
+            }
+        }
+    }
+}
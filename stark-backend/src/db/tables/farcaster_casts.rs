@@ -0,0 +1,80 @@
+//! Farcaster mention tracking - prevent duplicate processing of casts
+//!
+//! Stores the hashes of mention/reply casts a Farcaster channel has handled,
+//! whether it answered them or skipped them.
+
+use crate::db::Database;
+use rusqlite::Result as SqliteResult;
+
+impl Database {
+    /// Check if a cast has already been processed by a channel
+    pub fn is_cast_processed(&self, channel_id: i64, cast_hash: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM farcaster_processed_casts WHERE channel_id = ?1 AND cast_hash = ?2",
+            rusqlite::params![channel_id, cast_hash],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Mark a cast as processed
+    pub fn mark_cast_processed(
+        &self,
+        channel_id: i64,
+        cast_hash: &str,
+        author_fid: u64,
+        author_username: &str,
+        cast_text: &str,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR IGNORE INTO farcaster_processed_casts
+             (channel_id, cast_hash, author_fid, author_username, cast_text, processed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))",
+            rusqlite::params![channel_id, cast_hash, author_fid as i64, author_username, cast_text],
+        )?;
+        Ok(())
+    }
+
+    /// Whether a channel has processed any cast yet.
+    /// False on first start, when existing notifications are skipped rather than answered.
+    pub fn has_processed_casts(&self, channel_id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM farcaster_processed_casts WHERE channel_id = ?1",
+            [channel_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Clean up old processed casts
+    pub fn cleanup_old_processed_casts(&self, days: i64) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "DELETE FROM farcaster_processed_casts WHERE processed_at < datetime('now', ?1)",
+            [format!("-{} days", days)],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::Database;
+
+    #[test]
+    fn test_processed_casts() {
+        let db = Database::new(":memory:").unwrap();
+        let channel_id = db.create_channel("farcaster", "fc", "", None).unwrap().id;
+
+        assert!(!db.has_processed_casts(channel_id).unwrap());
+        db.mark_cast_processed(channel_id, "0xabc", 3, "dwr", "gm").unwrap();
+        db.mark_cast_processed(channel_id, "0xabc", 3, "dwr", "gm").unwrap();
+
+        assert!(db.has_processed_casts(channel_id).unwrap());
+        assert!(db.is_cast_processed(channel_id, "0xabc").unwrap());
+        assert!(!db.is_cast_processed(channel_id, "0xdef").unwrap());
+        assert_eq!(db.cleanup_old_processed_casts(30).unwrap(), 0);
+    }
+}
@@ -13,7 +13,8 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, email, access_token, refresh_token, token_expires_at,
                     watch_labels, project_id, topic_name, watch_expires_at, history_id,
-                    enabled, response_channel_id, auto_reply, created_at, updated_at
+                    enabled, response_channel_id, auto_reply, created_at, updated_at,
+                    send_allowlist, review_before_send
              FROM gmail_configs LIMIT 1"
         )?;
 
@@ -30,7 +31,8 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, email, access_token, refresh_token, token_expires_at,
                     watch_labels, project_id, topic_name, watch_expires_at, history_id,
-                    enabled, response_channel_id, auto_reply, created_at, updated_at
+                    enabled, response_channel_id, auto_reply, created_at, updated_at,
+                    send_allowlist, review_before_send
              FROM gmail_configs WHERE email = ?1"
         )?;
 
@@ -77,17 +79,21 @@ impl Database {
         response_channel_id: Option<i64>,
         auto_reply: Option<bool>,
         enabled: Option<bool>,
+        send_allowlist: Option<&str>,
+        review_before_send: Option<bool>,
     ) -> SqliteResult<GmailConfig> {
         let conn = self.conn();
         let now = Utc::now().to_rfc3339();
 
         // Build dynamic update
         let sql = format!(
-            "UPDATE gmail_configs SET updated_at = ?1{}{}{}{}",
+            "UPDATE gmail_configs SET updated_at = ?1{}{}{}{}{}{}",
             watch_labels.map(|_| ", watch_labels = ?2").unwrap_or(""),
             response_channel_id.map(|_| ", response_channel_id = ?3").unwrap_or(""),
             auto_reply.map(|_| ", auto_reply = ?4").unwrap_or(""),
             enabled.map(|_| ", enabled = ?5").unwrap_or(""),
+            send_allowlist.map(|_| ", send_allowlist = ?6").unwrap_or(""),
+            review_before_send.map(|_| ", review_before_send = ?7").unwrap_or(""),
         );
 
         conn.execute(
@@ -98,6 +104,8 @@ impl Database {
                 response_channel_id.unwrap_or(0),
                 auto_reply.unwrap_or(false) as i32,
                 enabled.unwrap_or(true) as i32,
+                send_allowlist.unwrap_or(""),
+                review_before_send.unwrap_or(false) as i32,
             ],
         )?;
 
@@ -187,6 +195,8 @@ impl Database {
             enabled: row.get::<_, i32>(10)? != 0,
             response_channel_id: row.get(11)?,
             auto_reply: row.get::<_, i32>(12)? != 0,
+            send_allowlist: row.get(15)?,
+            review_before_send: row.get::<_, i32>(16)? != 0,
             created_at: DateTime::parse_from_rfc3339(&created_at_str)
                 .unwrap()
                 .with_timezone(&Utc),
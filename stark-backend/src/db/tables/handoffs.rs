@@ -0,0 +1,149 @@
+//! Database operations for handoffs — chats handed over to a human operator
+//! (see `crate::handoff`)
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use crate::db::Database;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Handoff {
+    pub id: i64,
+    /// Session the conversation is kept in while the operator handles it
+    pub session_id: Option<i64>,
+    pub channel_id: i64,
+    pub channel_type: String,
+    pub chat_id: String,
+    /// Who the operator will be talking to, when known
+    pub user_name: Option<String>,
+    pub reason: String,
+    /// "agent", "rule" or "operator"
+    pub requested_by: String,
+    /// "waiting" until an operator joins, then "joined"; "released" once the agent is back
+    pub status: String,
+    pub operator: Option<String>,
+    pub created_at: String,
+    pub joined_at: Option<String>,
+    pub released_at: Option<String>,
+}
+
+impl Handoff {
+    pub fn is_active(&self) -> bool {
+        self.status != "released"
+    }
+}
+
+/// A handoff about to be opened
+pub struct NewHandoff<'a> {
+    pub session_id: Option<i64>,
+    pub channel_id: i64,
+    pub channel_type: &'a str,
+    pub chat_id: &'a str,
+    pub user_name: Option<&'a str>,
+    pub reason: &'a str,
+    pub requested_by: &'a str,
+}
+
+const COLUMNS: &str = "id, session_id, channel_id, channel_type, chat_id, user_name, reason, requested_by, status, \
+                       operator, created_at, joined_at, released_at";
+
+fn row_to_handoff(row: &rusqlite::Row) -> rusqlite::Result<Handoff> {
+    Ok(Handoff {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        channel_id: row.get(2)?,
+        channel_type: row.get(3)?,
+        chat_id: row.get(4)?,
+        user_name: row.get(5)?,
+        reason: row.get(6)?,
+        requested_by: row.get(7)?,
+        status: row.get(8)?,
+        operator: row.get(9)?,
+        created_at: row.get(10)?,
+        joined_at: row.get(11)?,
+        released_at: row.get(12)?,
+    })
+}
+
+impl Database {
+    pub fn create_handoff(&self, handoff: &NewHandoff) -> SqliteResult<Handoff> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!(
+                "INSERT INTO handoffs (session_id, channel_id, channel_type, chat_id, user_name, reason, requested_by,
+                                       status, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'waiting', ?8) RETURNING {}",
+                COLUMNS
+            ),
+            rusqlite::params![
+                handoff.session_id,
+                handoff.channel_id,
+                handoff.channel_type,
+                handoff.chat_id,
+                handoff.user_name,
+                handoff.reason,
+                handoff.requested_by,
+                Utc::now().to_rfc3339(),
+            ],
+            row_to_handoff,
+        )
+    }
+
+    pub fn get_handoff(&self, id: i64) -> SqliteResult<Option<Handoff>> {
+        let conn = self.conn();
+        conn.query_row(&format!("SELECT {} FROM handoffs WHERE id = ?1", COLUMNS), [id], row_to_handoff)
+            .optional()
+    }
+
+    /// The open handoff for a chat, if a human currently has it
+    pub fn get_active_handoff(&self, channel_id: i64, chat_id: &str) -> SqliteResult<Option<Handoff>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM handoffs WHERE channel_id = ?1 AND chat_id = ?2 AND status != 'released'
+                 ORDER BY id DESC LIMIT 1",
+                COLUMNS
+            ),
+            rusqlite::params![channel_id, chat_id],
+            row_to_handoff,
+        )
+        .optional()
+    }
+
+    /// Handoffs, newest first
+    pub fn list_handoffs(&self, active_only: bool, limit: i64) -> SqliteResult<Vec<Handoff>> {
+        let conn = self.conn();
+        let filter = if active_only { "WHERE status != 'released'" } else { "" };
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM handoffs {} ORDER BY id DESC LIMIT ?1", COLUMNS, filter))?;
+        let handoffs = stmt.query_map([limit], row_to_handoff)?;
+        handoffs.collect()
+    }
+
+    pub fn set_handoff_session(&self, id: i64, session_id: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute("UPDATE handoffs SET session_id = ?1 WHERE id = ?2", rusqlite::params![session_id, id])?;
+        Ok(())
+    }
+
+    /// An operator takes the chat over; false if the handoff is closed
+    pub fn join_handoff(&self, id: i64, operator: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let updated = conn.execute(
+            "UPDATE handoffs SET status = 'joined', operator = ?1, joined_at = COALESCE(joined_at, ?2)
+             WHERE id = ?3 AND status != 'released'",
+            rusqlite::params![operator, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Hand the chat back to the agent; false if it already was
+    pub fn release_handoff(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let updated = conn.execute(
+            "UPDATE handoffs SET status = 'released', released_at = ?1 WHERE id = ?2 AND status != 'released'",
+            rusqlite::params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(updated > 0)
+    }
+}
@@ -3,7 +3,7 @@
 use chrono::Utc;
 use rusqlite::{OptionalExtension, Result as SqliteResult};
 
-use crate::models::HeartbeatConfig;
+use crate::models::{HeartbeatConfig, MaintenanceJobConfig};
 use super::super::Database;
 
 impl Database {
@@ -16,7 +16,7 @@ impl Database {
             conn.query_row(
                 "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                         active_days, enabled, last_beat_at, next_beat_at, current_impulse_node_id, last_session_id,
-                        created_at, updated_at
+                        created_at, updated_at, maintenance_jobs
                  FROM heartbeat_configs WHERE channel_id = ?1",
                 [cid],
                 |row| self.map_heartbeat_config_row(row),
@@ -25,7 +25,7 @@ impl Database {
             conn.query_row(
                 "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                         active_days, enabled, last_beat_at, next_beat_at, current_impulse_node_id, last_session_id,
-                        created_at, updated_at
+                        created_at, updated_at, maintenance_jobs
                  FROM heartbeat_configs WHERE channel_id IS NULL",
                 [],
                 |row| self.map_heartbeat_config_row(row),
@@ -60,6 +60,7 @@ impl Database {
             next_beat_at: None,
             current_impulse_node_id: None,
             last_session_id: None,
+            maintenance_jobs: Vec::new(),
             created_at: now.clone(),
             updated_at: now,
         })
@@ -79,6 +80,10 @@ impl Database {
             next_beat_at: row.get(9)?,
             current_impulse_node_id: row.get(10)?,
             last_session_id: row.get(11)?,
+            maintenance_jobs: row
+                .get::<_, Option<String>>(14)?
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
             created_at: row.get(12)?,
             updated_at: row.get(13)?,
         })
@@ -129,13 +134,24 @@ impl Database {
         conn.query_row(
             "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                     active_days, enabled, last_beat_at, next_beat_at, current_impulse_node_id, last_session_id,
-                    created_at, updated_at
+                    created_at, updated_at, maintenance_jobs
              FROM heartbeat_configs WHERE id = ?1",
             [id],
             |row| self.map_heartbeat_config_row(row),
         )
     }
 
+    /// Replace the maintenance jobs of a heartbeat config
+    pub fn set_heartbeat_maintenance_jobs(&self, id: i64, jobs: &[MaintenanceJobConfig]) -> SqliteResult<()> {
+        let conn = self.conn();
+        let json = serde_json::to_string(jobs).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            "UPDATE heartbeat_configs SET maintenance_jobs = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![json, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
     /// Update heartbeat next_beat_at BEFORE execution (prevents race conditions)
     pub fn update_heartbeat_next_beat(&self, id: i64, next_beat_at: &str) -> SqliteResult<()> {
         let conn = self.conn();
@@ -182,7 +198,7 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                     active_days, enabled, last_beat_at, next_beat_at, current_impulse_node_id, last_session_id,
-                    created_at, updated_at
+                    created_at, updated_at, maintenance_jobs
              FROM heartbeat_configs ORDER BY id"
         )?;
 
@@ -201,7 +217,7 @@ impl Database {
         conn.query_row(
             "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                     active_days, enabled, last_beat_at, next_beat_at, current_impulse_node_id, last_session_id,
-                    created_at, updated_at
+                    created_at, updated_at, maintenance_jobs
              FROM heartbeat_configs WHERE id = ?1",
             [id],
             |row| self.map_heartbeat_config_row(row),
@@ -216,7 +232,7 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                     active_days, enabled, last_beat_at, next_beat_at, current_impulse_node_id, last_session_id,
-                    created_at, updated_at
+                    created_at, updated_at, maintenance_jobs
              FROM heartbeat_configs
              WHERE enabled = 1 AND (next_beat_at IS NULL OR next_beat_at <= ?1)
              ORDER BY next_beat_at ASC"
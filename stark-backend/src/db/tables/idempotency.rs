@@ -0,0 +1,179 @@
+//! Idempotency key database operations (idempotency_keys)
+//!
+//! Used to dedupe redelivered inbound channel messages and to let API clients
+//! retry `/api/chat` with an `Idempotency-Key` header without re-running the agent.
+//!
+//! A claim is only a short lease (`PENDING_LEASE_SECS`), so a run that died
+//! without completing or releasing its key doesn't block retries for the full
+//! TTL. Stored replies go through the message field cipher, and a key claimed
+//! with a request hash only replays for a request with the same hash.
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Result as SqliteResult;
+
+use super::super::encryption::{decrypt_field, encrypt_message};
+use super::super::Database;
+
+/// How long a claimed but not yet completed key blocks other requests
+pub const PENDING_LEASE_SECS: i64 = 10 * 60;
+
+/// A completed result stored against an idempotency key
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredDispatchResult {
+    pub response: String,
+    pub message_id: Option<String>,
+}
+
+/// Outcome of claiming an idempotency key
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyClaim {
+    /// First time this key was seen — the caller should do the work
+    New,
+    /// Another request with this key is still running
+    InProgress,
+    /// The key already completed — return this result instead of re-running
+    Completed(StoredDispatchResult),
+    /// The key was used for a different request
+    Mismatch,
+}
+
+impl Database {
+    /// Claim an idempotency key for `PENDING_LEASE_SECS`. Expired keys are
+    /// treated as never seen. `request_hash` identifies the request body; a
+    /// key stored with another hash is a `Mismatch`.
+    pub fn claim_idempotency_key(
+        &self,
+        scope: &str,
+        key: &str,
+        request_hash: Option<&str>,
+    ) -> SqliteResult<IdempotencyClaim> {
+        let conn = self.conn();
+        let now = Utc::now();
+        let lease_until = (now + Duration::seconds(PENDING_LEASE_SECS)).to_rfc3339();
+
+        // Drop an expired row (or lapsed lease) for this key so the insert below can claim it
+        conn.execute(
+            "DELETE FROM idempotency_keys WHERE scope = ?1 AND key = ?2 AND expires_at < ?3",
+            rusqlite::params![scope, key, now.to_rfc3339()],
+        )?;
+
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO idempotency_keys (scope, key, status, request_hash, created_at, expires_at)
+             VALUES (?1, ?2, 'pending', ?3, ?4, ?5)",
+            rusqlite::params![scope, key, request_hash, now.to_rfc3339(), lease_until],
+        )?;
+        if inserted > 0 {
+            return Ok(IdempotencyClaim::New);
+        }
+
+        let (status, stored_hash, response, message_id): (String, Option<String>, Option<String>, Option<String>) =
+            conn.query_row(
+                "SELECT status, request_hash, response, message_id FROM idempotency_keys WHERE scope = ?1 AND key = ?2",
+                rusqlite::params![scope, key],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )?;
+        if stored_hash.as_deref() != request_hash {
+            return Ok(IdempotencyClaim::Mismatch);
+        }
+        if status == "completed" {
+            Ok(IdempotencyClaim::Completed(StoredDispatchResult {
+                response: response.map(decrypt_field).unwrap_or_default(),
+                message_id,
+            }))
+        } else {
+            Ok(IdempotencyClaim::InProgress)
+        }
+    }
+
+    /// Record the result for a claimed key so later duplicates can replay it
+    /// for `ttl_secs`
+    pub fn complete_idempotency_key(
+        &self,
+        scope: &str,
+        key: &str,
+        response: &str,
+        message_id: Option<&str>,
+        ttl_secs: i64,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        let expires_at = (Utc::now() + Duration::seconds(ttl_secs)).to_rfc3339();
+        conn.execute(
+            "UPDATE idempotency_keys SET status = 'completed', response = ?1, message_id = ?2, expires_at = ?3
+             WHERE scope = ?4 AND key = ?5",
+            rusqlite::params![encrypt_message(response)?, message_id, expires_at, scope, key],
+        )?;
+        Ok(())
+    }
+
+    /// Release a claimed key (e.g. after a failed run) so a retry can run again
+    pub fn release_idempotency_key(&self, scope: &str, key: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "DELETE FROM idempotency_keys WHERE scope = ?1 AND key = ?2",
+            rusqlite::params![scope, key],
+        )?;
+        Ok(())
+    }
+
+    /// Delete all expired idempotency keys
+    pub fn purge_expired_idempotency_keys(&self, now: DateTime<Utc>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "DELETE FROM idempotency_keys WHERE expires_at < ?1",
+            [now.to_rfc3339()],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_complete_replay() {
+        let db = Database::new(":memory:").unwrap();
+        assert_eq!(db.claim_idempotency_key("discord", "1:42", None).unwrap(), IdempotencyClaim::New);
+        assert_eq!(db.claim_idempotency_key("discord", "1:42", None).unwrap(), IdempotencyClaim::InProgress);
+        // Same key in a different scope is independent
+        assert_eq!(db.claim_idempotency_key("telegram", "1:42", None).unwrap(), IdempotencyClaim::New);
+
+        db.complete_idempotency_key("discord", "1:42", "hi there", Some("m-1"), 60).unwrap();
+        assert_eq!(
+            db.claim_idempotency_key("discord", "1:42", None).unwrap(),
+            IdempotencyClaim::Completed(StoredDispatchResult {
+                response: "hi there".to_string(),
+                message_id: Some("m-1".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_release_and_expiry() {
+        let db = Database::new(":memory:").unwrap();
+        db.claim_idempotency_key("http", "abc", None).unwrap();
+        db.release_idempotency_key("http", "abc").unwrap();
+        assert_eq!(db.claim_idempotency_key("http", "abc", None).unwrap(), IdempotencyClaim::New);
+
+        // A negative TTL makes the completed key expire immediately
+        db.claim_idempotency_key("http", "old", None).unwrap();
+        db.complete_idempotency_key("http", "old", "done", None, -10).unwrap();
+        assert_eq!(db.claim_idempotency_key("http", "old", None).unwrap(), IdempotencyClaim::New);
+
+        // Pending claims only hold their short lease
+        let leased = db.purge_expired_idempotency_keys(Utc::now() + Duration::seconds(PENDING_LEASE_SECS + 1)).unwrap();
+        assert_eq!(leased, 2);
+    }
+
+    #[test]
+    fn test_reused_key_with_another_body_is_refused() {
+        let db = Database::new(":memory:").unwrap();
+        assert_eq!(db.claim_idempotency_key("http", "k", Some("hash-a")).unwrap(), IdempotencyClaim::New);
+        assert_eq!(db.claim_idempotency_key("http", "k", Some("hash-b")).unwrap(), IdempotencyClaim::Mismatch);
+        db.complete_idempotency_key("http", "k", "answer", None, 60).unwrap();
+        assert_eq!(db.claim_idempotency_key("http", "k", Some("hash-b")).unwrap(), IdempotencyClaim::Mismatch);
+        assert!(matches!(
+            db.claim_idempotency_key("http", "k", Some("hash-a")).unwrap(),
+            IdempotencyClaim::Completed(stored) if stored.response == "answer"
+        ));
+    }
+}
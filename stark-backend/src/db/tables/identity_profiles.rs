@@ -0,0 +1,161 @@
+//! Structured long-term profiles of counterparty identities
+//!
+//! One row per `identity_links.identity_id`. Fields are learned by the profile
+//! builder after each session; a field the user corrected is listed in
+//! `locked_fields` and never overwritten by the builder again. The learned
+//! values are encrypted like the memories they come from (see
+//! `db::encryption::encrypt_memory`); `locked_fields` only names fields.
+
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::db::encryption::{decrypt_field, encrypt_memory};
+use crate::db::Database;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IdentityProfile {
+    pub identity_id: String,
+    pub display_name: Option<String>,
+    pub timezone: Option<String>,
+    pub language: Option<String>,
+    /// Stable preferences, e.g. `"units" => "metric"`
+    pub preferences: BTreeMap<String, String>,
+    pub wallet_addresses: Vec<String>,
+    pub projects: Vec<String>,
+    /// Fields corrected by the user
+    pub locked_fields: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const PROFILE_COLS: &str = "identity_id, display_name, timezone, language, preferences, wallet_addresses, \
+     projects, locked_fields, created_at, updated_at";
+
+/// Columns holding learned values, encrypted with `encrypt_memory`
+pub(crate) const ENCRYPTED_COLUMNS: &[&str] =
+    &["display_name", "timezone", "language", "preferences", "wallet_addresses", "projects"];
+
+fn json_or_default<T: serde::de::DeserializeOwned + Default>(raw: Option<String>) -> T {
+    raw.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default()
+}
+
+fn decrypted(raw: Option<String>) -> Option<String> {
+    raw.map(decrypt_field)
+}
+
+fn encrypted_json<T: Serialize>(value: &T, empty: &str) -> rusqlite::Result<String> {
+    encrypt_memory(&serde_json::to_string(value).unwrap_or_else(|_| empty.to_string()))
+}
+
+fn row_to_profile(row: &rusqlite::Row) -> rusqlite::Result<IdentityProfile> {
+    Ok(IdentityProfile {
+        identity_id: row.get(0)?,
+        display_name: decrypted(row.get(1)?),
+        timezone: decrypted(row.get(2)?),
+        language: decrypted(row.get(3)?),
+        preferences: json_or_default(decrypted(row.get(4)?)),
+        wallet_addresses: json_or_default(decrypted(row.get(5)?)),
+        projects: json_or_default(decrypted(row.get(6)?)),
+        locked_fields: json_or_default(row.get(7)?),
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+impl Database {
+    pub fn get_identity_profile(&self, identity_id: &str) -> SqliteResult<Option<IdentityProfile>> {
+        let conn = self.conn();
+        let sql = format!("SELECT {} FROM identity_profiles WHERE identity_id = ?1", PROFILE_COLS);
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows = stmt.query_map([identity_id], row_to_profile)?;
+        rows.next().transpose()
+    }
+
+    /// Most recently updated first
+    pub fn list_identity_profiles(&self, limit: usize) -> SqliteResult<Vec<IdentityProfile>> {
+        let conn = self.conn();
+        let sql = format!("SELECT {} FROM identity_profiles ORDER BY updated_at DESC LIMIT ?1", PROFILE_COLS);
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([limit as i64], row_to_profile)?;
+        rows.collect()
+    }
+
+    /// Insert or replace all fields of a profile
+    pub fn save_identity_profile(&self, profile: &IdentityProfile) -> SqliteResult<()> {
+        let encrypt = |value: &Option<String>| value.as_deref().map(encrypt_memory).transpose();
+        let conn = self.conn();
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO identity_profiles
+             (identity_id, display_name, timezone, language, preferences, wallet_addresses, projects,
+              locked_fields, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)
+             ON CONFLICT(identity_id) DO UPDATE SET
+                display_name = excluded.display_name,
+                timezone = excluded.timezone,
+                language = excluded.language,
+                preferences = excluded.preferences,
+                wallet_addresses = excluded.wallet_addresses,
+                projects = excluded.projects,
+                locked_fields = excluded.locked_fields,
+                updated_at = excluded.updated_at",
+            rusqlite::params![
+                profile.identity_id,
+                encrypt(&profile.display_name)?,
+                encrypt(&profile.timezone)?,
+                encrypt(&profile.language)?,
+                encrypted_json(&profile.preferences, "{}")?,
+                encrypted_json(&profile.wallet_addresses, "[]")?,
+                encrypted_json(&profile.projects, "[]")?,
+                serde_json::to_string(&profile.locked_fields).unwrap_or_else(|_| "[]".to_string()),
+                now,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_identity_profile(&self, identity_id: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        Ok(delete_profile(&conn, identity_id)? > 0)
+    }
+}
+
+/// Delete a profile on `conn` (which may be inside a transaction)
+pub(crate) fn delete_profile(conn: &rusqlite::Connection, identity_id: &str) -> SqliteResult<usize> {
+    conn.execute("DELETE FROM identity_profiles WHERE identity_id = ?1", [identity_id])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_reload_profile() {
+        let db = Database::new(":memory:").unwrap();
+        assert!(db.get_identity_profile("id-1").unwrap().is_none());
+
+        let mut profile = IdentityProfile {
+            identity_id: "id-1".to_string(),
+            timezone: Some("Europe/Berlin".to_string()),
+            wallet_addresses: vec!["0xabc".to_string()],
+            ..Default::default()
+        };
+        profile.preferences.insert("units".to_string(), "metric".to_string());
+        db.save_identity_profile(&profile).unwrap();
+
+        profile.projects.push("dex aggregator".to_string());
+        profile.locked_fields.push("timezone".to_string());
+        db.save_identity_profile(&profile).unwrap();
+
+        let loaded = db.get_identity_profile("id-1").unwrap().unwrap();
+        assert_eq!(loaded.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(loaded.preferences.get("units").map(String::as_str), Some("metric"));
+        assert_eq!(loaded.projects, vec!["dex aggregator".to_string()]);
+        assert_eq!(loaded.locked_fields, vec!["timezone".to_string()]);
+        assert_eq!(db.list_identity_profiles(10).unwrap().len(), 1);
+
+        assert!(db.delete_identity_profile("id-1").unwrap());
+        assert!(db.get_identity_profile("id-1").unwrap().is_none());
+    }
+}
@@ -0,0 +1,257 @@
+//! Database operations for the knowledge graph tables
+//!
+//! `knowledge_entities` holds one row per (type, normalized name),
+//! `knowledge_relations` the typed edges between them and `knowledge_mentions`
+//! where each entity was seen (a memory, the user's message or a tool).
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use crate::db::Database;
+
+/// Strength a relation gains every time it is seen again
+const STRENGTH_STEP: f64 = 0.1;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeEntity {
+    pub id: i64,
+    pub entity_type: String,
+    /// Normalized name (lowercase), unique per type
+    pub name: String,
+    pub display_name: String,
+    pub mention_count: i64,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeRelation {
+    pub id: i64,
+    pub source_entity_id: i64,
+    pub target_entity_id: i64,
+    pub relation_type: String,
+    pub strength: f64,
+    pub mention_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeMention {
+    pub id: i64,
+    pub entity_id: i64,
+    /// "memory", "user" or the tool the entity came from
+    pub source: String,
+    pub memory_id: Option<i64>,
+    pub session_id: Option<i64>,
+    pub created_at: String,
+}
+
+/// Counts for the graph overview
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KnowledgeGraphStats {
+    pub entities: i64,
+    pub relations: i64,
+    pub mentions: i64,
+    pub entity_types: Vec<(String, i64)>,
+    pub relation_types: Vec<(String, i64)>,
+}
+
+const ENTITY_COLS: &str = "id, entity_type, name, display_name, mention_count, first_seen, last_seen";
+const RELATION_COLS: &str =
+    "id, source_entity_id, target_entity_id, relation_type, strength, mention_count, created_at, updated_at";
+
+fn row_to_entity(row: &rusqlite::Row) -> rusqlite::Result<KnowledgeEntity> {
+    Ok(KnowledgeEntity {
+        id: row.get(0)?,
+        entity_type: row.get(1)?,
+        name: row.get(2)?,
+        display_name: row.get(3)?,
+        mention_count: row.get(4)?,
+        first_seen: row.get(5)?,
+        last_seen: row.get(6)?,
+    })
+}
+
+fn row_to_relation(row: &rusqlite::Row) -> rusqlite::Result<KnowledgeRelation> {
+    Ok(KnowledgeRelation {
+        id: row.get(0)?,
+        source_entity_id: row.get(1)?,
+        target_entity_id: row.get(2)?,
+        relation_type: row.get(3)?,
+        strength: row.get(4)?,
+        mention_count: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+impl Database {
+    /// Insert an entity or count another mention of it. Returns its id.
+    pub fn upsert_knowledge_entity(&self, entity_type: &str, name: &str, display_name: &str) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.query_row(
+            "INSERT INTO knowledge_entities (entity_type, name, display_name, first_seen, last_seen)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(entity_type, name) DO UPDATE SET
+                mention_count = mention_count + 1, last_seen = excluded.last_seen
+             RETURNING id",
+            rusqlite::params![entity_type, name, display_name, now],
+            |row| row.get(0),
+        )
+    }
+
+    /// Insert a relation or strengthen it when seen again. Returns its id.
+    pub fn upsert_knowledge_relation(
+        &self,
+        source_entity_id: i64,
+        target_entity_id: i64,
+        relation_type: &str,
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.query_row(
+            "INSERT INTO knowledge_relations (source_entity_id, target_entity_id, relation_type, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(source_entity_id, target_entity_id, relation_type) DO UPDATE SET
+                mention_count = mention_count + 1,
+                strength = MIN(1.0, strength + ?5),
+                updated_at = excluded.updated_at
+             RETURNING id",
+            rusqlite::params![source_entity_id, target_entity_id, relation_type, now, STRENGTH_STEP],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn add_knowledge_mention(
+        &self,
+        entity_id: i64,
+        source: &str,
+        memory_id: Option<i64>,
+        session_id: Option<i64>,
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO knowledge_mentions (entity_id, source, memory_id, session_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![entity_id, source, memory_id, session_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn get_knowledge_entity(&self, id: i64) -> SqliteResult<Option<KnowledgeEntity>> {
+        let conn = self.conn();
+        let sql = format!("SELECT {} FROM knowledge_entities WHERE id = ?1", ENTITY_COLS);
+        conn.query_row(&sql, [id], row_to_entity).optional()
+    }
+
+    /// Entities whose name contains `query` (case-insensitive), most mentioned
+    /// first, optionally of one type. An empty query lists everything.
+    pub fn search_knowledge_entities(
+        &self,
+        query: &str,
+        entity_type: Option<&str>,
+        limit: usize,
+    ) -> SqliteResult<Vec<KnowledgeEntity>> {
+        let conn = self.conn();
+        let sql = format!(
+            "SELECT {} FROM knowledge_entities
+             WHERE (name LIKE '%' || ?1 || '%' OR display_name LIKE '%' || ?1 || '%')
+               AND (?2 IS NULL OR entity_type = ?2)
+             ORDER BY (name = ?1) DESC, mention_count DESC, id
+             LIMIT ?3",
+            ENTITY_COLS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            rusqlite::params![query.trim().to_lowercase(), entity_type, limit as i64],
+            row_to_entity,
+        )?;
+        rows.collect()
+    }
+
+    /// Relations touching an entity, in either direction, strongest first
+    pub fn list_knowledge_relations_for(&self, entity_id: i64) -> SqliteResult<Vec<KnowledgeRelation>> {
+        let conn = self.conn();
+        let sql = format!(
+            "SELECT {} FROM knowledge_relations
+             WHERE source_entity_id = ?1 OR target_entity_id = ?1
+             ORDER BY strength DESC, id",
+            RELATION_COLS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([entity_id], row_to_relation)?;
+        rows.collect()
+    }
+
+    /// Relations among the given entities (for drawing a subgraph)
+    pub fn list_knowledge_relations_among(&self, entity_ids: &[i64]) -> SqliteResult<Vec<KnowledgeRelation>> {
+        if entity_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids = serde_json::to_string(entity_ids).unwrap_or_else(|_| "[]".to_string());
+        let conn = self.conn();
+        let sql = format!(
+            "SELECT {} FROM knowledge_relations
+             WHERE source_entity_id IN (SELECT value FROM json_each(?1))
+               AND target_entity_id IN (SELECT value FROM json_each(?1))
+             ORDER BY id",
+            RELATION_COLS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([ids], row_to_relation)?;
+        rows.collect()
+    }
+
+    /// Where an entity was seen, newest first
+    pub fn list_knowledge_mentions(&self, entity_id: i64, limit: usize) -> SqliteResult<Vec<KnowledgeMention>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, entity_id, source, memory_id, session_id, created_at FROM knowledge_mentions
+             WHERE entity_id = ?1 ORDER BY id DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![entity_id, limit as i64], |row| {
+            Ok(KnowledgeMention {
+                id: row.get(0)?,
+                entity_id: row.get(1)?,
+                source: row.get(2)?,
+                memory_id: row.get(3)?,
+                session_id: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    pub fn get_knowledge_graph_stats(&self) -> SqliteResult<KnowledgeGraphStats> {
+        let conn = self.conn();
+        let count = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, i64>(0));
+        let grouped = |sql: &str| -> SqliteResult<Vec<(String, i64)>> {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        };
+        Ok(KnowledgeGraphStats {
+            entities: count("SELECT COUNT(*) FROM knowledge_entities")?,
+            relations: count("SELECT COUNT(*) FROM knowledge_relations")?,
+            mentions: count("SELECT COUNT(*) FROM knowledge_mentions")?,
+            entity_types: grouped(
+                "SELECT entity_type, COUNT(*) FROM knowledge_entities GROUP BY entity_type ORDER BY COUNT(*) DESC",
+            )?,
+            relation_types: grouped(
+                "SELECT relation_type, COUNT(*) FROM knowledge_relations GROUP BY relation_type ORDER BY COUNT(*) DESC",
+            )?,
+        })
+    }
+
+    /// Drop the whole graph (before a rebuild)
+    pub fn clear_knowledge_graph(&self) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM knowledge_mentions", [])?;
+        conn.execute("DELETE FROM knowledge_relations", [])?;
+        conn.execute("DELETE FROM knowledge_entities", [])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+//! Durable agent variables (kv_store)
+//!
+//! A namespaced key-value store that outlives a single execution — unlike the
+//! per-execution RegisterStore. Namespaces are `global` or `identity:<id>`;
+//! entries can carry a TTL and expired entries are ignored and purged lazily.
+//! Expiry comparisons rely on RFC 3339 UTC strings sorting chronologically.
+
+use chrono::{Duration, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::super::Database;
+
+/// Namespace shared by every identity
+pub const GLOBAL_NAMESPACE: &str = "global";
+
+/// Namespace private to one identity
+pub fn identity_namespace(identity_id: &str) -> String {
+    format!("identity:{}", identity_id)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KvEntry {
+    pub namespace: String,
+    pub key: String,
+    pub value: Value,
+    pub expires_at: Option<String>,
+    pub updated_at: String,
+}
+
+const KV_COLS: &str = "namespace, key, value, expires_at, updated_at";
+
+fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<KvEntry> {
+    let raw: String = row.get(2)?;
+    Ok(KvEntry {
+        namespace: row.get(0)?,
+        key: row.get(1)?,
+        // Values are stored as JSON; fall back to a plain string for hand-edited rows
+        value: serde_json::from_str(&raw).unwrap_or(Value::String(raw)),
+        expires_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+impl Database {
+    /// Set a value, replacing any existing one. `ttl_secs` of None keeps it forever.
+    pub fn kv_set(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: &Value,
+        ttl_secs: Option<i64>,
+    ) -> SqliteResult<KvEntry> {
+        let conn = self.conn();
+        let now = Utc::now();
+        let expires_at = ttl_secs.map(|s| (now + Duration::seconds(s.max(1))).to_rfc3339());
+        conn.execute(
+            "INSERT INTO kv_store (namespace, key, value, expires_at, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+             ON CONFLICT(namespace, key) DO UPDATE SET
+                value = excluded.value,
+                expires_at = excluded.expires_at,
+                updated_at = excluded.updated_at",
+            rusqlite::params![namespace, key, value.to_string(), expires_at, now.to_rfc3339()],
+        )?;
+        Ok(KvEntry {
+            namespace: namespace.to_string(),
+            key: key.to_string(),
+            value: value.clone(),
+            expires_at,
+            updated_at: now.to_rfc3339(),
+        })
+    }
+
+    /// Get a live (non-expired) value
+    pub fn kv_get(&self, namespace: &str, key: &str) -> SqliteResult<Option<KvEntry>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM kv_store
+                 WHERE namespace = ?1 AND key = ?2 AND (expires_at IS NULL OR expires_at > ?3)",
+                KV_COLS
+            ),
+            rusqlite::params![namespace, key, Utc::now().to_rfc3339()],
+            row_to_entry,
+        )
+        .optional()
+    }
+
+    /// Delete a value. Returns whether it existed.
+    pub fn kv_delete(&self, namespace: &str, key: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let deleted = conn.execute(
+            "DELETE FROM kv_store WHERE namespace = ?1 AND key = ?2",
+            rusqlite::params![namespace, key],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Live entries in a namespace, optionally filtered by key prefix
+    pub fn kv_list(&self, namespace: &str, prefix: Option<&str>, limit: usize) -> SqliteResult<Vec<KvEntry>> {
+        let conn = self.conn();
+        let pattern = format!(
+            "{}%",
+            prefix.unwrap_or("").replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+        );
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM kv_store
+             WHERE namespace = ?1 AND key LIKE ?2 ESCAPE '\\' AND (expires_at IS NULL OR expires_at > ?3)
+             ORDER BY key ASC LIMIT ?4",
+            KV_COLS
+        ))?;
+        let rows = stmt.query_map(
+            rusqlite::params![namespace, pattern, Utc::now().to_rfc3339(), limit as i64],
+            row_to_entry,
+        )?;
+        rows.collect()
+    }
+
+    /// Number of live entries in a namespace
+    pub fn kv_count(&self, namespace: &str) -> SqliteResult<usize> {
+        let conn = self.conn();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM kv_store
+             WHERE namespace = ?1 AND (expires_at IS NULL OR expires_at > ?2)",
+            rusqlite::params![namespace, Utc::now().to_rfc3339()],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Remove expired entries. Returns how many were deleted.
+    pub fn kv_purge_expired(&self) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "DELETE FROM kv_store WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+            [Utc::now().to_rfc3339()],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_kv_roundtrip_namespaces_and_ttl() {
+        let db = Database::new(":memory:").unwrap();
+        let alice = identity_namespace("alice");
+
+        db.kv_set(&alice, "last_block", &json!(19_000_000), None).unwrap();
+        db.kv_set(&alice, "slippage", &json!({"bps": 50}), None).unwrap();
+        db.kv_set(GLOBAL_NAMESPACE, "last_block", &json!(1), None).unwrap();
+
+        assert_eq!(db.kv_get(&alice, "last_block").unwrap().unwrap().value, json!(19_000_000));
+        assert_eq!(db.kv_get(GLOBAL_NAMESPACE, "last_block").unwrap().unwrap().value, json!(1));
+        assert_eq!(db.kv_list(&alice, Some("last"), 10).unwrap().len(), 1);
+        assert_eq!(db.kv_count(&alice).unwrap(), 2);
+
+        // Overwrite keeps a single row
+        db.kv_set(&alice, "last_block", &json!(19_000_001), None).unwrap();
+        assert_eq!(db.kv_count(&alice).unwrap(), 2);
+
+        assert!(db.kv_delete(&alice, "slippage").unwrap());
+        assert!(db.kv_get(&alice, "slippage").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_kv_expired_entries_are_hidden_and_purged() {
+        let db = Database::new(":memory:").unwrap();
+        db.kv_set(GLOBAL_NAMESPACE, "fresh", &json!("x"), Some(3600)).unwrap();
+        db.kv_set(GLOBAL_NAMESPACE, "stale", &json!("y"), None).unwrap();
+        // Backdate the expiry of "stale"
+        db.conn()
+            .execute("UPDATE kv_store SET expires_at = '2000-01-01T00:00:00+00:00' WHERE key = 'stale'", [])
+            .unwrap();
+
+        assert!(db.kv_get(GLOBAL_NAMESPACE, "fresh").unwrap().is_some());
+        assert!(db.kv_get(GLOBAL_NAMESPACE, "stale").unwrap().is_none());
+        assert_eq!(db.kv_list(GLOBAL_NAMESPACE, None, 10).unwrap().len(), 1);
+        assert_eq!(db.kv_purge_expired().unwrap(), 1);
+    }
+}
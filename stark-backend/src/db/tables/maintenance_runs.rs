@@ -0,0 +1,84 @@
+//! Database operations for maintenance_runs — one row per self-maintenance
+//! job run, newest kept for the health report
+
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use crate::db::Database;
+
+/// Runs kept per job; older ones are dropped as new ones are recorded
+const RUNS_KEPT_PER_JOB: i64 = 50;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceRun {
+    pub id: i64,
+    pub job: String,
+    pub started_at: String,
+    pub duration_ms: i64,
+    pub success: bool,
+    pub summary: String,
+    pub error: Option<String>,
+}
+
+fn row_to_run(row: &rusqlite::Row) -> rusqlite::Result<MaintenanceRun> {
+    Ok(MaintenanceRun {
+        id: row.get(0)?,
+        job: row.get(1)?,
+        started_at: row.get(2)?,
+        duration_ms: row.get(3)?,
+        success: row.get::<_, i64>(4)? != 0,
+        summary: row.get(5)?,
+        error: row.get(6)?,
+    })
+}
+
+impl Database {
+    pub fn record_maintenance_run(
+        &self,
+        job: &str,
+        started_at: DateTime<Utc>,
+        duration_ms: i64,
+        result: &Result<String, String>,
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let (success, summary, error) = match result {
+            Ok(summary) => (true, summary.as_str(), None),
+            Err(e) => (false, "", Some(e.as_str())),
+        };
+        conn.execute(
+            "INSERT INTO maintenance_runs (job, started_at, duration_ms, success, summary, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![job, started_at.to_rfc3339(), duration_ms, success as i64, summary, error],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.execute(
+            "DELETE FROM maintenance_runs WHERE job = ?1 AND id NOT IN
+                (SELECT id FROM maintenance_runs WHERE job = ?1 ORDER BY id DESC LIMIT ?2)",
+            rusqlite::params![job, RUNS_KEPT_PER_JOB],
+        )?;
+        Ok(id)
+    }
+
+    pub fn last_maintenance_run(&self, job: &str) -> SqliteResult<Option<MaintenanceRun>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT id, job, started_at, duration_ms, success, summary, error FROM maintenance_runs
+             WHERE job = ?1 ORDER BY id DESC LIMIT 1",
+            [job],
+            row_to_run,
+        )
+        .optional()
+    }
+
+    /// Most recent runs across all jobs, newest first
+    pub fn list_maintenance_runs(&self, limit: usize) -> SqliteResult<Vec<MaintenanceRun>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, job, started_at, duration_ms, success, summary, error FROM maintenance_runs
+             ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map([limit as i64], row_to_run)?;
+        rows.collect()
+    }
+}
@@ -1,19 +1,20 @@
 //! Database operations for the `memories` table
 //! Core CRUD for the structured memory system (SQL-backed).
 
+use crate::db::encryption::{decrypt_field, encrypt_memory};
 use crate::db::Database;
 
 /// Standard SELECT columns for memory queries
 const MEMORY_SELECT_COLS: &str =
     "id, memory_type, content, category, tags, importance,
      identity_id, session_id, entity_type, entity_name,
-     source_type, log_date, created_at, updated_at, last_accessed, agent_subtype";
+     source_type, log_date, created_at, updated_at, last_accessed, agent_subtype, external_page_id";
 
 /// Table-qualified SELECT columns for JOIN queries (avoids ambiguous column names with FTS)
 const MEMORY_SELECT_COLS_QUALIFIED: &str =
     "memories.id, memories.memory_type, memories.content, memories.category, memories.tags, memories.importance,
      memories.identity_id, memories.session_id, memories.entity_type, memories.entity_name,
-     memories.source_type, memories.log_date, memories.created_at, memories.updated_at, memories.last_accessed, memories.agent_subtype";
+     memories.source_type, memories.log_date, memories.created_at, memories.updated_at, memories.last_accessed, memories.agent_subtype, memories.external_page_id";
 
 /// A row from the `memories` table
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -34,6 +35,18 @@ pub struct MemoryRow {
     pub updated_at: String,
     pub last_accessed: Option<String>,
     pub agent_subtype: Option<String>,
+    /// Notion page ID / Obsidian vault path this memory is exported to
+    #[serde(default)]
+    pub external_page_id: Option<String>,
+}
+
+/// Filters for `list_memories_page`
+#[derive(Debug, Default)]
+pub struct MemoryListFilter {
+    pub memory_type: Option<String>,
+    pub identity_id: Option<String>,
+    pub category: Option<String>,
+    pub min_importance: Option<i64>,
 }
 
 /// Parse a MemoryRow from a rusqlite::Row using the standard column order.
@@ -41,7 +54,7 @@ fn row_to_memory(row: &rusqlite::Row) -> rusqlite::Result<MemoryRow> {
     Ok(MemoryRow {
         id: row.get(0)?,
         memory_type: row.get(1)?,
-        content: row.get(2)?,
+        content: decrypt_field(row.get(2)?),
         category: row.get(3)?,
         tags: row.get(4)?,
         importance: row.get::<_, Option<f64>>(5)?.map(|v| v.round() as i64).unwrap_or(5),
@@ -55,6 +68,7 @@ fn row_to_memory(row: &rusqlite::Row) -> rusqlite::Result<MemoryRow> {
         updated_at: row.get(13)?,
         last_accessed: row.get(14)?,
         agent_subtype: row.get(15)?,
+        external_page_id: row.get(16)?,
     })
 }
 
@@ -100,7 +114,7 @@ impl Database {
                 ?10, ?11, ?12, datetime('now'), datetime('now'), datetime('now')
             )",
             rusqlite::params![
-                memory_type, content, category, tags, importance,
+                memory_type, encrypt_memory(content)?, category, tags, importance,
                 identity_id, session_id, entity_type, entity_name,
                 source_type, log_date, agent_subtype,
             ],
@@ -142,7 +156,7 @@ impl Database {
                 ?10, ?11, ?12, ?13, datetime('now'), datetime('now')
             )",
             rusqlite::params![
-                memory_type, content, category, tags, importance,
+                memory_type, encrypt_memory(content)?, category, tags, importance,
                 identity_id, session_id, entity_type, entity_name,
                 source_type, log_date, agent_subtype, created_at,
             ],
@@ -227,6 +241,64 @@ impl Database {
         }
     }
 
+    /// Non-superseded memories selected for note export (see `note_exports`).
+    pub fn list_memories_for_export(
+        &self,
+        min_importance: i64,
+        memory_types: &[String],
+    ) -> Result<Vec<MemoryRow>, rusqlite::Error> {
+        if memory_types.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders: Vec<String> = (0..memory_types.len()).map(|i| format!("?{}", i + 2)).collect();
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM memories
+             WHERE superseded_by IS NULL AND importance >= ?1 AND memory_type IN ({})
+             ORDER BY id",
+            MEMORY_SELECT_COLS,
+            placeholders.join(", ")
+        ))?;
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&min_importance];
+        params.extend(memory_types.iter().map(|t| t as &dyn rusqlite::ToSql));
+        let rows = stmt.query_map(params.as_slice(), row_to_memory)?;
+        rows.collect()
+    }
+
+    /// Current (not superseded) memories with an id above `after_id`, oldest
+    /// first, for incremental passes like the knowledge graph sync.
+    pub fn list_memories_after(&self, after_id: i64, limit: usize) -> Result<Vec<MemoryRow>, rusqlite::Error> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM memories WHERE id > ?1 AND superseded_by IS NULL ORDER BY id LIMIT ?2",
+            MEMORY_SELECT_COLS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![after_id, limit as i64], row_to_memory)?;
+        rows.collect()
+    }
+
+    /// Link a memory to the external note it is mirrored to.
+    pub fn set_memory_external_page_id(&self, memory_id: i64, external_page_id: Option<&str>) -> Result<(), rusqlite::Error> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE memories SET external_page_id = ?1 WHERE id = ?2",
+            rusqlite::params![external_page_id, memory_id],
+        )?;
+        Ok(())
+    }
+
+    /// Replace a memory's content (e.g. with an edit pulled from an external note).
+    /// Content is redacted and encrypted like on insert; FTS is updated by trigger.
+    pub fn update_memory_content(&self, memory_id: i64, content: &str) -> Result<(), rusqlite::Error> {
+        let redaction = crate::memory::redaction::redact_content(content);
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE memories SET content = ?1, updated_at = datetime('now') WHERE id = ?2",
+            rusqlite::params![encrypt_memory(&redaction.content)?, memory_id],
+        )?;
+        Ok(())
+    }
+
     // ====================================================================
     // Query helpers for the unified memory system
     // ====================================================================
@@ -389,12 +461,65 @@ impl Database {
         let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
         let rows = stmt.query_map(param_refs.as_slice(), |row| {
             let memory = row_to_memory(row)?;
-            let rank: f64 = row.get(16)?; // rank is after the 16 standard columns
+            let rank: f64 = row.get(17)?; // rank is after the 17 standard columns
             Ok((memory, rank))
         })?;
         rows.collect()
     }
 
+    /// FTS search restricted to negative_result memories (failed approaches).
+    /// `query` must be valid FTS5 syntax, as for `search_memories_fts`.
+    pub fn search_negative_results(
+        &self,
+        query: &str,
+        identity_id: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<MemoryRow>, rusqlite::Error> {
+        let conn = self.conn();
+        let sql = format!(
+            "SELECT {cols}
+             FROM memories
+             JOIN memories_fts ON memories.id = memories_fts.rowid
+             WHERE memories_fts MATCH ?1 AND memories.memory_type = 'negative_result'
+               AND (?2 IS NULL OR memories.identity_id IS NULL OR memories.identity_id = ?2)
+             ORDER BY bm25(memories_fts)
+             LIMIT ?3",
+            cols = MEMORY_SELECT_COLS_QUALIFIED
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params![query, identity_id, limit], |row| row_to_memory(row))?;
+        rows.collect()
+    }
+
+    /// The negative_result memory for a tool failing for one cause, if recorded.
+    pub fn find_negative_result(
+        &self,
+        tool_name: &str,
+        category: &str,
+        identity_id: Option<&str>,
+    ) -> Result<Option<MemoryRow>, rusqlite::Error> {
+        let conn = self.conn();
+        let sql = format!(
+            "SELECT {} FROM memories
+             WHERE memory_type = 'negative_result' AND entity_name = ?1 AND category = ?2
+               AND identity_id IS ?3
+             ORDER BY id DESC LIMIT 1",
+            MEMORY_SELECT_COLS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows = stmt.query_map(rusqlite::params![tool_name, category, identity_id], |row| row_to_memory(row))?;
+        rows.next().transpose()
+    }
+
+    /// Delete the negative_result memories filed under `category` (a resolved cause).
+    pub fn clear_negative_results(&self, category: &str) -> Result<usize, rusqlite::Error> {
+        let conn = self.conn();
+        conn.execute(
+            "DELETE FROM memories WHERE memory_type = 'negative_result' AND category = ?1",
+            [category],
+        )
+    }
+
     /// List distinct dates that have daily_log entries (for calendar display).
     pub fn list_memory_dates(
         &self,
@@ -499,7 +624,7 @@ impl Database {
         let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
         let rows = stmt.query_map(param_refs.as_slice(), |row| {
             let memory = row_to_memory(row)?;
-            let rank: f64 = row.get(16)?;
+            let rank: f64 = row.get(17)?;
             Ok((memory, rank))
         })?;
         rows.collect()
@@ -657,6 +782,53 @@ impl Database {
         rows.collect()
     }
 
+    /// One page of current (not superseded) memories, optionally of one type,
+    /// identity or category, with the number that match. `sort` must be a
+    /// column name the caller has whitelisted.
+    pub fn list_memories_page(
+        &self,
+        filter: &MemoryListFilter,
+        sort: &str,
+        descending: bool,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<MemoryRow>, usize), rusqlite::Error> {
+        let conn = self.conn();
+        let condition = "WHERE superseded_by IS NULL
+             AND (?1 IS NULL OR memory_type = ?1) AND (?2 IS NULL OR identity_id = ?2)
+             AND (?3 IS NULL OR category = ?3) AND (?4 IS NULL OR importance >= ?4)";
+        let filter_params = rusqlite::params![
+            filter.memory_type,
+            filter.identity_id,
+            filter.category,
+            filter.min_importance,
+        ];
+
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM memories {}", condition),
+            filter_params,
+            |row| row.get(0),
+        )?;
+
+        let direction = if descending { "DESC" } else { "ASC" };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM memories {} ORDER BY {} {}, id {} LIMIT ?5 OFFSET ?6",
+            MEMORY_SELECT_COLS, condition, sort, direction, direction
+        ))?;
+        let rows = stmt.query_map(
+            rusqlite::params![
+                filter.memory_type,
+                filter.identity_id,
+                filter.category,
+                filter.min_importance,
+                limit as i64,
+                offset as i64,
+            ],
+            |row| row_to_memory(row),
+        )?;
+        Ok((rows.collect::<Result<Vec<_>, _>>()?, total as usize))
+    }
+
     /// Rebuild the FTS5 index from the external content table.
     /// Use this when the FTS index gets out of sync (e.g., after restore,
     /// or if the FTS table was created after memories already existed).
@@ -0,0 +1,125 @@
+//! Interactive button database operations (message_actions)
+//!
+//! Platforms only round-trip a short opaque string when a button is pressed
+//! (Telegram allows 64 bytes of callback data), so each button is stored under
+//! a random token. Buttons sent together share a `group_id` and are single-use
+//! as a group: once one is pressed, the whole set is spent — pressing "Approve"
+//! twice, or "Approve" then "Reject", only acts once.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Result as SqliteResult;
+
+use super::super::Database;
+use crate::channels::types::ActionButton;
+
+fn new_token() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+impl Database {
+    /// Store a set of buttons for a message. Returns one token per button, in order.
+    pub fn create_message_actions(
+        &self,
+        channel_id: i64,
+        chat_id: &str,
+        buttons: &[ActionButton],
+    ) -> SqliteResult<Vec<String>> {
+        let conn = self.conn();
+        let tx = conn.unchecked_transaction()?;
+        let group_id = new_token();
+        let now = Utc::now().to_rfc3339();
+        let mut tokens = Vec::with_capacity(buttons.len());
+        for button in buttons {
+            let token = new_token();
+            tx.execute(
+                "INSERT INTO message_actions (token, group_id, channel_id, chat_id, label, action, payload, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                rusqlite::params![
+                    token,
+                    group_id,
+                    channel_id,
+                    chat_id,
+                    button.label,
+                    button.action,
+                    button.payload.as_ref().map(|p| p.to_string()),
+                    now
+                ],
+            )?;
+            tokens.push(token);
+        }
+        tx.commit()?;
+        Ok(tokens)
+    }
+
+    /// Resolve a pressed button and spend its group. Returns None for unknown
+    /// tokens, tokens from another channel, or buttons already used.
+    pub fn consume_message_action(&self, channel_id: i64, token: &str) -> SqliteResult<Option<ActionButton>> {
+        let conn = self.conn();
+        let row = conn.query_row(
+            "SELECT group_id, label, action, payload FROM message_actions WHERE token = ?1 AND channel_id = ?2",
+            rusqlite::params![token, channel_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            },
+        );
+        let (group_id, label, action, payload) = match row {
+            Ok(row) => row,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        // Only the first press of the group wins
+        let claimed = conn.execute(
+            "UPDATE message_actions SET used_at = ?1 WHERE group_id = ?2 AND used_at IS NULL",
+            rusqlite::params![Utc::now().to_rfc3339(), group_id],
+        )?;
+        if claimed == 0 {
+            return Ok(None);
+        }
+        Ok(Some(ActionButton {
+            label,
+            action,
+            payload: payload.and_then(|p| serde_json::from_str(&p).ok()),
+        }))
+    }
+
+    /// Delete buttons created before `cutoff`
+    pub fn purge_message_actions(&self, cutoff: DateTime<Utc>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "DELETE FROM message_actions WHERE created_at < ?1",
+            [cutoff.to_rfc3339()],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_button_group_is_single_use() {
+        let db = Database::new(":memory:").unwrap();
+        let buttons = vec![
+            ActionButton { label: "Approve".into(), action: "approve_tx".into(), payload: Some(serde_json::json!({"tx_id": 3})) },
+            ActionButton { label: "Reject".into(), action: "reject_tx".into(), payload: None },
+        ];
+        let tokens = db.create_message_actions(5, "42", &buttons).unwrap();
+        assert_eq!(tokens.len(), 2);
+
+        // Wrong channel can't resolve it
+        assert!(db.consume_message_action(6, &tokens[0]).unwrap().is_none());
+
+        let pressed = db.consume_message_action(5, &tokens[0]).unwrap().unwrap();
+        assert_eq!(pressed, buttons[0]);
+        // Second press of either button in the set is ignored
+        assert!(db.consume_message_action(5, &tokens[0]).unwrap().is_none());
+        assert!(db.consume_message_action(5, &tokens[1]).unwrap().is_none());
+        assert!(db.consume_message_action(5, "missing").unwrap().is_none());
+    }
+}
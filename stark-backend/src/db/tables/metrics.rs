@@ -0,0 +1,193 @@
+//! Skill-defined counters and metrics (metric_events)
+//!
+//! A lightweight event table skills use to track their own domain events
+//! ("alerts_sent", "gas_price_gwei"). Counters store one row per increment
+//! and are summed; metrics store one row per observation and are summarized
+//! (count/sum/avg/min/max/last). Read by the get_metric tool and the
+//! analytics API.
+
+use chrono::{Duration, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use super::super::Database;
+
+pub const KIND_COUNTER: &str = "counter";
+pub const KIND_METRIC: &str = "metric";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricSummary {
+    pub name: String,
+    pub kind: String,
+    /// Number of increments / observations in the window
+    pub count: i64,
+    /// Counter total, or sum of observations
+    pub sum: f64,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Most recent observation
+    pub last: f64,
+    pub last_recorded_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricBucket {
+    /// Bucket start: `YYYY-MM-DD` for days, `YYYY-MM-DDTHH` for hours
+    pub bucket: String,
+    pub count: i64,
+    pub sum: f64,
+    pub avg: f64,
+}
+
+fn since_cutoff(since_hours: Option<i64>) -> String {
+    since_hours
+        .map(|h| (Utc::now() - Duration::hours(h)).to_rfc3339())
+        .unwrap_or_default()
+}
+
+const SUMMARY_SELECT: &str = "SELECT e.name, e.kind, COUNT(*), SUM(e.value), AVG(e.value), MIN(e.value), MAX(e.value),
+        (SELECT l.value FROM metric_events l WHERE l.name = e.name ORDER BY l.recorded_at DESC, l.id DESC LIMIT 1),
+        MAX(e.recorded_at)
+     FROM metric_events e";
+
+fn row_to_summary(row: &rusqlite::Row) -> rusqlite::Result<MetricSummary> {
+    Ok(MetricSummary {
+        name: row.get(0)?,
+        kind: row.get(1)?,
+        count: row.get(2)?,
+        sum: row.get(3)?,
+        avg: row.get(4)?,
+        min: row.get(5)?,
+        max: row.get(6)?,
+        last: row.get(7)?,
+        last_recorded_at: row.get(8)?,
+    })
+}
+
+impl Database {
+    /// Record a counter increment or metric observation
+    pub fn record_metric_event(
+        &self,
+        name: &str,
+        kind: &str,
+        value: f64,
+        skill: Option<&str>,
+        labels: Option<&serde_json::Value>,
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO metric_events (name, kind, value, skill, labels, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                name,
+                kind,
+                value,
+                skill,
+                labels.map(|l| l.to_string()),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Kind a metric was first recorded with (counters and metrics can't share a name)
+    pub fn get_metric_kind(&self, name: &str) -> SqliteResult<Option<String>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT kind FROM metric_events WHERE name = ?1 ORDER BY id ASC LIMIT 1",
+            [name],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// Summary of one metric, optionally limited to the last `since_hours`
+    pub fn get_metric_summary(&self, name: &str, since_hours: Option<i64>) -> SqliteResult<Option<MetricSummary>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("{} WHERE e.name = ?1 AND e.recorded_at >= ?2 GROUP BY e.name, e.kind", SUMMARY_SELECT),
+            rusqlite::params![name, since_cutoff(since_hours)],
+            row_to_summary,
+        )
+        .optional()
+    }
+
+    /// Summaries of every metric (optionally only those recorded by one skill)
+    pub fn list_metric_summaries(
+        &self,
+        since_hours: Option<i64>,
+        skill: Option<&str>,
+    ) -> SqliteResult<Vec<MetricSummary>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE e.recorded_at >= ?1 AND (?2 IS NULL OR e.skill = ?2)
+             GROUP BY e.name, e.kind ORDER BY e.name ASC",
+            SUMMARY_SELECT
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![since_cutoff(since_hours), skill], row_to_summary)?;
+        rows.collect()
+    }
+
+    /// Time series of a metric bucketed by `hour` or `day`
+    pub fn get_metric_series(
+        &self,
+        name: &str,
+        bucket: &str,
+        since_hours: Option<i64>,
+    ) -> SqliteResult<Vec<MetricBucket>> {
+        let conn = self.conn();
+        // RFC 3339 prefixes: 10 chars = day, 13 chars = hour
+        let width = if bucket == "hour" { 13 } else { 10 };
+        let mut stmt = conn.prepare(
+            "SELECT substr(recorded_at, 1, ?3) AS b, COUNT(*), SUM(value), AVG(value)
+             FROM metric_events WHERE name = ?1 AND recorded_at >= ?2
+             GROUP BY b ORDER BY b ASC",
+        )?;
+        let rows = stmt.query_map(
+            rusqlite::params![name, since_cutoff(since_hours), width],
+            |row| {
+                Ok(MetricBucket {
+                    bucket: row.get(0)?,
+                    count: row.get(1)?,
+                    sum: row.get(2)?,
+                    avg: row.get(3)?,
+                })
+            },
+        )?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_and_metrics_summarize() {
+        let db = Database::new(":memory:").unwrap();
+        db.record_metric_event("alerts_sent", KIND_COUNTER, 1.0, Some("price_alerts"), None).unwrap();
+        db.record_metric_event("alerts_sent", KIND_COUNTER, 2.0, Some("price_alerts"), None).unwrap();
+        db.record_metric_event("gas_gwei", KIND_METRIC, 10.0, None, None).unwrap();
+        db.record_metric_event("gas_gwei", KIND_METRIC, 30.0, None, None).unwrap();
+
+        let alerts = db.get_metric_summary("alerts_sent", None).unwrap().unwrap();
+        assert_eq!(alerts.kind, KIND_COUNTER);
+        assert_eq!(alerts.count, 2);
+        assert_eq!(alerts.sum, 3.0);
+
+        let gas = db.get_metric_summary("gas_gwei", Some(24)).unwrap().unwrap();
+        assert_eq!(gas.avg, 20.0);
+        assert_eq!(gas.min, 10.0);
+        assert_eq!(gas.last, 30.0);
+
+        assert_eq!(db.get_metric_kind("gas_gwei").unwrap().as_deref(), Some(KIND_METRIC));
+        assert_eq!(db.list_metric_summaries(None, Some("price_alerts")).unwrap().len(), 1);
+        assert_eq!(db.list_metric_summaries(None, None).unwrap().len(), 2);
+
+        let series = db.get_metric_series("gas_gwei", "day", None).unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].count, 2);
+        assert!(db.get_metric_summary("missing", None).unwrap().is_none());
+    }
+}
@@ -3,13 +3,13 @@
 //! Each module adds `impl Database` blocks with methods for a specific table group.
 
 pub mod agent_subtypes; // agent_subtypes (configurable agent toolboxes)
-mod auth;           // auth_sessions, auth_challenges
+pub mod auth;       // auth_sessions (+ devices, refresh tokens), auth_challenges
 mod api_keys;       // external_api_keys
 mod channels;       // external_channels
 mod channel_settings; // channel_settings (per-channel config)
 mod agent_settings; // agent_settings
 mod bot_settings;   // bot_settings
-mod chat_sessions;  // chat_sessions, session_messages (+ compaction)
+pub mod chat_sessions;  // chat_sessions, session_messages (+ compaction)
 mod identities;     // identity_links
 mod tool_configs;   // tool_configs, tool_executions
 mod skills;         // skills, skill_scripts
@@ -31,3 +31,51 @@ pub mod memory_embeddings; // memory_embeddings (vector search)
 pub mod memory_associations; // memory_associations (knowledge graph)
 pub mod skill_embeddings;  // skill_embeddings (vector search for skill discovery)
 pub mod skill_associations; // skill_associations (skill relationship graph)
+pub mod alert_rules;       // alert_rules, alert_events (operational alerting)
+pub mod retention;         // retention_policies, deleted_records, forget_audit (retention & soft delete)
+pub mod idempotency;       // idempotency_keys (inbound message dedup, Idempotency-Key replay)
+pub mod cluster;           // cluster_leases, cluster_instances (multi-instance leader election & locks)
+pub mod outbound;          // outbound_messages (persistent channel delivery queue + dead letters)
+pub mod proactive_messages; // proactive_messages (unsolicited messages sent per channel, for daily limits)
+pub mod message_actions;   // message_actions (interactive button callbacks for channel messages)
+pub mod tx_approvals;      // tx_approvals (owner approvals for queued transactions + decision audit)
+pub mod safe_proposals;    // safe_proposals (transactions proposed to a Safe multisig + signer tracking)
+pub mod bridge_transfers;  // bridge_transfers (queued bridges tracked until arrival on the destination chain)
+pub mod trades;            // trades (trade journal: swaps/transfers with execution-time USD prices)
+pub mod paper_trades;      // paper_trades (paper trading ledger: simulated fills recorded instead of signing)
+pub mod strategies;        // strategies, strategy_runs (trigger/condition/action automations and their run history)
+pub mod module_launches;   // module_launches (audit of module service launches: binary hash, sandbox, outcome)
+pub mod module_updates;    // module_updates (newer StarkHub versions of installed modules, notification state)
+pub mod agent_subtype_installs; // agent_subtype_installs (StarkHub provenance of installed agent subtypes)
+pub mod note_exports;      // note_export_config, note_exports (Notion/Obsidian mirror of memories and summaries)
+pub mod tracked_issues;    // tracked_issues (Jira/Linear issues filed by the agent, linked to their session)
+pub mod twitter_outgoing;  // twitter_outgoing_queue (tweets queued by the agent, drained by the Twitter channel)
+pub mod social_posts;      // social_posts (content calendar: drafted posts, owner approval, publishing, engagement)
+pub mod farcaster_casts;   // farcaster_processed_casts (dedupe of mentions/replies handled by Farcaster channels)
+pub mod reputation_feedback; // reputation_feedback (EIP-8004 feedback given to other agents and signed feedback received)
+pub mod remote_delegations; // remote_delegations (subtasks delegated to remote EIP-8004 agents)
+pub mod action_receipts;   // action_receipts (hash-chained transparency log of txs and paid tasks)
+pub mod identity_profiles; // identity_profiles (structured long-term profile per counterparty identity)
+pub mod context_prewarm;   // context_prewarm_config, context_prewarm_cache (context refreshed ahead of busy hours)
+pub mod tool_outputs;      // tool_outputs (raw tool results condensed out of the context, paged by handle)
+pub mod outbox_drafts;     // outbox_drafts (review-before-send drafts for tweets and email replies, approval + expiry)
+pub mod paused_executions; // paused_executions (checkpointed tool loops waiting for /api/chat/resume)
+pub mod artifacts;         // artifacts (files generated by an execution, linked to execution + session)
+pub mod query_views;       // q_* views (whitelisted read-only introspection over sessions, memories, activity, usage)
+pub mod kv_store;          // kv_store (durable agent variables, per identity or global, optional TTL)
+pub mod metrics;           // metric_events (skill-defined counters and metrics, summarized for analytics)
+pub mod workflow_templates; // workflow_templates, session_plans (parameterized plans saved from a session and re-instantiated)
+pub mod tool_confirmations; // tool_confirmations (tool calls held for user confirmation + decision audit)
+pub mod digests;           // digest_config, digest_runs (scheduled activity digest settings and history)
+pub mod access_keys;       // access_keys (scoped API keys: hashed key, scopes, rate limit, expiry, last use)
+pub mod two_factor;        // two_factor, two_factor_recovery_codes (TOTP secret, hashed recovery codes)
+pub mod ai_usage;          // ai_usage, ai_quotas (LLM call ledger and daily/monthly token or USD quotas per instance/channel)
+pub mod token_metadata;    // token_metadata, token_metadata_overrides (cached token symbol/name/decimals + manual overrides)
+pub mod rpc_endpoints;     // rpc_endpoints (user-added RPC URLs per network for the failover pool)
+pub mod skill_permissions; // skill_permissions (per-skill finance capability grants: read / send)
+pub mod skill_runs;        // skill_runs (invoke_skill runs, nested under their parent run)
+pub mod knowledge_graph;   // knowledge_entities, knowledge_relations, knowledge_mentions (entity graph from memories + context bank)
+pub mod maintenance_runs;  // maintenance_runs (heartbeat self-maintenance job results)
+pub mod event_subscriptions; // event_subscriptions (event bus callbacks and polling filters of modules/skills)
+pub mod away_requests;     // away_requests (non-urgent messages queued while away mode is on)
+pub mod handoffs;          // handoffs (chats handed to a human operator: reason, status, operator)
@@ -0,0 +1,92 @@
+//! Module launch audit (module_launches)
+//!
+//! One row per attempt to start a module service: the binary's SHA-256 and
+//! the hash pinned at install, the sandbox it ran in, and whether the launch
+//! was allowed.
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+/// A module service launch attempt
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleLaunch {
+    pub id: i64,
+    pub module_name: String,
+    /// Binary path or manifest command
+    pub target: String,
+    /// SHA-256 of the binary that was (or would have been) executed
+    pub sha256: Option<String>,
+    /// SHA-256 pinned at install
+    pub expected_sha256: Option<String>,
+    /// "bwrap" or "off"
+    pub sandbox: String,
+    pub uid: Option<u32>,
+    pub allowed: bool,
+    pub error: Option<String>,
+    pub launched_at: String,
+}
+
+impl Database {
+    /// Record a launch attempt
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_module_launch(
+        &self,
+        module_name: &str,
+        target: &str,
+        sha256: Option<&str>,
+        expected_sha256: Option<&str>,
+        sandbox: &str,
+        uid: Option<u32>,
+        allowed: bool,
+        error: Option<&str>,
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO module_launches (module_name, target, sha256, expected_sha256, sandbox, uid, allowed, error, launched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                module_name,
+                target,
+                sha256,
+                expected_sha256,
+                sandbox,
+                uid,
+                allowed as i32,
+                error,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Recent launch attempts, newest first (optionally for one module)
+    pub fn list_module_launches(&self, module_name: Option<&str>, limit: usize) -> SqliteResult<Vec<ModuleLaunch>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, module_name, target, sha256, expected_sha256, sandbox, uid, allowed, error, launched_at
+             FROM module_launches
+             WHERE ?1 IS NULL OR module_name = ?1
+             ORDER BY id DESC LIMIT ?2",
+        )?;
+        let launches = stmt
+            .query_map(rusqlite::params![module_name, limit as i64], |row| {
+                Ok(ModuleLaunch {
+                    id: row.get(0)?,
+                    module_name: row.get(1)?,
+                    target: row.get(2)?,
+                    sha256: row.get(3)?,
+                    expected_sha256: row.get(4)?,
+                    sandbox: row.get(5)?,
+                    uid: row.get(6)?,
+                    allowed: row.get::<_, i32>(7)? != 0,
+                    error: row.get(8)?,
+                    launched_at: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(launches)
+    }
+}
@@ -0,0 +1,126 @@
+//! Available module updates (module_updates)
+//!
+//! The update worker records one row per installed StarkHub module that has
+//! a newer version on the hub. `notified_version` remembers which version the
+//! owner was last told about so each release is announced once.
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use super::super::Database;
+
+/// A newer version of an installed module
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleUpdate {
+    pub module_name: String,
+    pub current_version: String,
+    pub available_version: String,
+    pub checked_at: String,
+    pub notified_version: Option<String>,
+}
+
+impl ModuleUpdate {
+    /// Whether the owner hasn't been told about this version yet
+    pub fn needs_notification(&self) -> bool {
+        self.notified_version.as_deref() != Some(self.available_version.as_str())
+    }
+}
+
+fn row_to_update(row: &rusqlite::Row) -> rusqlite::Result<ModuleUpdate> {
+    Ok(ModuleUpdate {
+        module_name: row.get(0)?,
+        current_version: row.get(1)?,
+        available_version: row.get(2)?,
+        checked_at: row.get(3)?,
+        notified_version: row.get(4)?,
+    })
+}
+
+impl Database {
+    /// Record (or refresh) an available update, keeping its notification state
+    pub fn upsert_module_update(
+        &self,
+        module_name: &str,
+        current_version: &str,
+        available_version: &str,
+    ) -> SqliteResult<ModuleUpdate> {
+        {
+            let conn = self.conn();
+            conn.execute(
+                "INSERT INTO module_updates (module_name, current_version, available_version, checked_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(module_name) DO UPDATE SET
+                    current_version = excluded.current_version,
+                    available_version = excluded.available_version,
+                    checked_at = excluded.checked_at",
+                rusqlite::params![module_name, current_version, available_version, Utc::now().to_rfc3339()],
+            )?;
+        }
+        self.get_module_update(module_name)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    /// The available update for a module, if any
+    pub fn get_module_update(&self, module_name: &str) -> SqliteResult<Option<ModuleUpdate>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT module_name, current_version, available_version, checked_at, notified_version
+             FROM module_updates WHERE module_name = ?1",
+            [module_name],
+            row_to_update,
+        )
+        .optional()
+    }
+
+    /// All available updates
+    pub fn list_module_updates(&self) -> SqliteResult<Vec<ModuleUpdate>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT module_name, current_version, available_version, checked_at, notified_version
+             FROM module_updates ORDER BY module_name",
+        )?;
+        let updates = stmt.query_map([], row_to_update)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(updates)
+    }
+
+    /// Remember that the owner was told about `version`
+    pub fn mark_module_update_notified(&self, module_name: &str, version: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE module_updates SET notified_version = ?1 WHERE module_name = ?2",
+            rusqlite::params![version, module_name],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Drop a module's update (upgraded, up to date, or uninstalled)
+    pub fn clear_module_update(&self, module_name: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM module_updates WHERE module_name = ?1", [module_name])?;
+        Ok(rows > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_notified_once_per_version() {
+        let db = Database::new(":memory:").unwrap();
+        let update = db.upsert_module_update("demo", "1.0.0", "1.1.0").unwrap();
+        assert!(update.needs_notification());
+
+        db.mark_module_update_notified("demo", "1.1.0").unwrap();
+        let update = db.upsert_module_update("demo", "1.0.0", "1.1.0").unwrap();
+        assert!(!update.needs_notification());
+
+        // A newer release is announced again
+        let update = db.upsert_module_update("demo", "1.0.0", "1.2.0").unwrap();
+        assert!(update.needs_notification());
+        assert_eq!(db.list_module_updates().unwrap().len(), 1);
+
+        assert!(db.clear_module_update("demo").unwrap());
+        assert!(db.get_module_update("demo").unwrap().is_none());
+    }
+}
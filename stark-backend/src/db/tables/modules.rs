@@ -25,6 +25,8 @@ pub struct InstalledModule {
     pub author: Option<String>,
     /// SHA-256 checksum of the downloaded archive
     pub sha256_checksum: Option<String>,
+    /// SHA-256 of the service binary, pinned at install and checked before every launch
+    pub binary_sha256: Option<String>,
     pub installed_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -36,7 +38,7 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, module_name, enabled, version, description, has_tools, has_dashboard,
                     source, manifest_path, binary_path, author, sha256_checksum,
-                    installed_at, updated_at
+                    installed_at, updated_at, binary_sha256
              FROM installed_modules ORDER BY installed_at ASC",
         )?;
 
@@ -130,6 +132,7 @@ impl Database {
             binary_path: binary_path.map(|s| s.to_string()),
             author: author.map(|s| s.to_string()),
             sha256_checksum: sha256_checksum.map(|s| s.to_string()),
+            binary_sha256: None,
             installed_at,
             updated_at: installed_at,
         })
@@ -153,6 +156,32 @@ impl Database {
             "UPDATE installed_modules SET enabled = ?1, updated_at = ?2 WHERE module_name = ?3",
             rusqlite::params![enabled, now, name],
         )?;
+        drop(conn);
+        // Failures blamed on the module being disabled no longer hold
+        if enabled && rows > 0 {
+            crate::memory::negative_results::resolve_module(self, name);
+        }
+        Ok(rows > 0)
+    }
+
+    /// Record a module's new version after an upgrade
+    pub fn set_module_version(&self, name: &str, version: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let now = chrono::Utc::now().to_rfc3339();
+        let rows = conn.execute(
+            "UPDATE installed_modules SET version = ?1, updated_at = ?2 WHERE module_name = ?3",
+            rusqlite::params![version, now, name],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Pin the service binary's SHA-256 (checked by the sandbox before every launch)
+    pub fn set_module_binary_sha256(&self, name: &str, sha256: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE installed_modules SET binary_sha256 = ?1 WHERE module_name = ?2",
+            rusqlite::params![sha256, name],
+        )?;
         Ok(rows > 0)
     }
 
@@ -162,7 +191,7 @@ impl Database {
         let result = conn.query_row(
             "SELECT id, module_name, enabled, version, description, has_tools, has_dashboard,
                     source, manifest_path, binary_path, author, sha256_checksum,
-                    installed_at, updated_at
+                    installed_at, updated_at, binary_sha256
              FROM installed_modules WHERE module_name = ?1",
             [name],
             |row| Self::row_to_installed_module(row),
@@ -197,6 +226,7 @@ impl Database {
             binary_path: row.get(9)?,
             author: row.get(10)?,
             sha256_checksum: row.get(11)?,
+            binary_sha256: row.get(14)?,
             installed_at,
             updated_at,
         })
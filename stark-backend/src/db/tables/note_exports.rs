@@ -0,0 +1,280 @@
+//! Note export configuration and links (note_export_config, note_exports)
+//!
+//! `note_exports` tracks every memory / session summary mirrored to Notion or
+//! an Obsidian vault. `local_hash` is the rendered note last pushed and
+//! `remote_hash` the external body as last seen, so the sync worker can tell
+//! which side changed since the previous run.
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+use super::super::Database;
+
+/// Note export settings (single row)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteExportConfig {
+    /// "notion" or "obsidian"
+    pub target: String,
+    pub enabled: bool,
+    pub notion_token: Option<String>,
+    pub notion_parent_page_id: Option<String>,
+    pub obsidian_vault_path: Option<String>,
+    /// Vault subfolder (Obsidian) / title prefix (Notion)
+    pub folder: String,
+    /// Only memories at or above this importance are exported
+    pub min_importance: i64,
+    /// Comma-separated memory types to export (e.g. "long_term")
+    pub memory_types: String,
+    pub include_session_summaries: bool,
+    pub last_sync_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl Default for NoteExportConfig {
+    fn default() -> Self {
+        Self {
+            target: "obsidian".to_string(),
+            enabled: false,
+            notion_token: None,
+            notion_parent_page_id: None,
+            obsidian_vault_path: None,
+            folder: "StarkBot".to_string(),
+            min_importance: 7,
+            memory_types: "long_term".to_string(),
+            include_session_summaries: true,
+            last_sync_at: None,
+            updated_at: None,
+        }
+    }
+}
+
+impl NoteExportConfig {
+    pub fn memory_type_list(&self) -> Vec<String> {
+        self.memory_types
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect()
+    }
+}
+
+/// Link between a local item and its external note
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteExport {
+    pub id: i64,
+    /// "memory" or "session"
+    pub source_kind: String,
+    pub source_id: i64,
+    pub target: String,
+    pub external_id: String,
+    pub local_hash: String,
+    pub remote_hash: String,
+    /// "synced", "conflict" or "remote_deleted"
+    pub status: String,
+    pub last_error: Option<String>,
+    pub synced_at: String,
+}
+
+const EXPORT_COLS: &str = "id, source_kind, source_id, target, external_id, local_hash, remote_hash, status, last_error, synced_at";
+
+fn row_to_export(row: &rusqlite::Row) -> rusqlite::Result<NoteExport> {
+    Ok(NoteExport {
+        id: row.get(0)?,
+        source_kind: row.get(1)?,
+        source_id: row.get(2)?,
+        target: row.get(3)?,
+        external_id: row.get(4)?,
+        local_hash: row.get(5)?,
+        remote_hash: row.get(6)?,
+        status: row.get(7)?,
+        last_error: row.get(8)?,
+        synced_at: row.get(9)?,
+    })
+}
+
+impl Database {
+    /// Get the note export settings (defaults if never saved)
+    pub fn get_note_export_config(&self) -> SqliteResult<NoteExportConfig> {
+        let conn = self.conn();
+        let config = conn
+            .query_row(
+                "SELECT target, enabled, notion_token, notion_parent_page_id, obsidian_vault_path,
+                        folder, min_importance, memory_types, include_session_summaries,
+                        last_sync_at, updated_at
+                 FROM note_export_config WHERE id = 1",
+                [],
+                |row| {
+                    Ok(NoteExportConfig {
+                        target: row.get(0)?,
+                        enabled: row.get::<_, i32>(1)? != 0,
+                        notion_token: row.get(2)?,
+                        notion_parent_page_id: row.get(3)?,
+                        obsidian_vault_path: row.get(4)?,
+                        folder: row.get(5)?,
+                        min_importance: row.get(6)?,
+                        memory_types: row.get(7)?,
+                        include_session_summaries: row.get::<_, i32>(8)? != 0,
+                        last_sync_at: row.get(9)?,
+                        updated_at: row.get(10)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(config.unwrap_or_default())
+    }
+
+    /// Save the note export settings
+    pub fn save_note_export_config(&self, config: &NoteExportConfig) -> SqliteResult<NoteExportConfig> {
+        {
+            let conn = self.conn();
+            conn.execute(
+                "INSERT INTO note_export_config (id, target, enabled, notion_token, notion_parent_page_id,
+                    obsidian_vault_path, folder, min_importance, memory_types, include_session_summaries, updated_at)
+                 VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT(id) DO UPDATE SET
+                    target = excluded.target,
+                    enabled = excluded.enabled,
+                    notion_token = excluded.notion_token,
+                    notion_parent_page_id = excluded.notion_parent_page_id,
+                    obsidian_vault_path = excluded.obsidian_vault_path,
+                    folder = excluded.folder,
+                    min_importance = excluded.min_importance,
+                    memory_types = excluded.memory_types,
+                    include_session_summaries = excluded.include_session_summaries,
+                    updated_at = excluded.updated_at",
+                rusqlite::params![
+                    config.target,
+                    config.enabled as i32,
+                    config.notion_token,
+                    config.notion_parent_page_id,
+                    config.obsidian_vault_path,
+                    config.folder,
+                    config.min_importance,
+                    config.memory_types,
+                    config.include_session_summaries as i32,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+        }
+        self.get_note_export_config()
+    }
+
+    /// Record the time of the last completed sync run
+    pub fn set_note_export_last_sync(&self) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE note_export_config SET last_sync_at = ?1 WHERE id = 1",
+            [Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Get the export link for a local item on a target
+    pub fn get_note_export(&self, source_kind: &str, source_id: i64, target: &str) -> SqliteResult<Option<NoteExport>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM note_exports WHERE source_kind = ?1 AND source_id = ?2 AND target = ?3",
+                EXPORT_COLS
+            ),
+            rusqlite::params![source_kind, source_id, target],
+            row_to_export,
+        )
+        .optional()
+    }
+
+    pub fn get_note_export_by_id(&self, id: i64) -> SqliteResult<Option<NoteExport>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM note_exports WHERE id = ?1", EXPORT_COLS),
+            [id],
+            row_to_export,
+        )
+        .optional()
+    }
+
+    /// Record a successful push/pull: both sides now match the given hashes
+    pub fn upsert_note_export(
+        &self,
+        source_kind: &str,
+        source_id: i64,
+        target: &str,
+        external_id: &str,
+        local_hash: &str,
+        remote_hash: &str,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO note_exports (source_kind, source_id, target, external_id, local_hash, remote_hash, status, synced_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'synced', ?7)
+             ON CONFLICT(source_kind, source_id, target) DO UPDATE SET
+                external_id = excluded.external_id,
+                local_hash = excluded.local_hash,
+                remote_hash = excluded.remote_hash,
+                status = 'synced',
+                last_error = NULL,
+                synced_at = excluded.synced_at",
+            rusqlite::params![source_kind, source_id, target, external_id, local_hash, remote_hash, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Flag an export (conflict, remote_deleted, or a sync error on a synced row)
+    pub fn set_note_export_status(&self, id: i64, status: &str, error: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE note_exports SET status = ?1, last_error = ?2 WHERE id = ?3",
+            rusqlite::params![status, error, id],
+        )?;
+        Ok(())
+    }
+
+    /// List export links, optionally filtered by status
+    pub fn list_note_exports(&self, status: Option<&str>) -> SqliteResult<Vec<NoteExport>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM note_exports WHERE (?1 IS NULL OR status = ?1) ORDER BY synced_at DESC",
+            EXPORT_COLS
+        ))?;
+        let rows = stmt.query_map([status], row_to_export)?;
+        rows.collect()
+    }
+
+    pub fn delete_note_export(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        Ok(conn.execute("DELETE FROM note_exports WHERE id = ?1", [id])? > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::Database;
+
+    #[test]
+    fn test_note_export_roundtrip() {
+        let db = Database::new(":memory:").unwrap();
+
+        let mut config = db.get_note_export_config().unwrap();
+        assert!(!config.enabled);
+        config.enabled = true;
+        config.obsidian_vault_path = Some("/vault".to_string());
+        let saved = db.save_note_export_config(&config).unwrap();
+        assert!(saved.enabled);
+        assert_eq!(saved.obsidian_vault_path.as_deref(), Some("/vault"));
+
+        db.upsert_note_export("memory", 7, "obsidian", "StarkBot/Memories/7.md", "l1", "r1").unwrap();
+        let export = db.get_note_export("memory", 7, "obsidian").unwrap().unwrap();
+        assert_eq!(export.status, "synced");
+
+        db.set_note_export_status(export.id, "conflict", None).unwrap();
+        assert_eq!(db.list_note_exports(Some("conflict")).unwrap().len(), 1);
+
+        // A later successful sync clears the conflict
+        db.upsert_note_export("memory", 7, "obsidian", "StarkBot/Memories/7.md", "l2", "r2").unwrap();
+        let export = db.get_note_export_by_id(export.id).unwrap().unwrap();
+        assert_eq!(export.status, "synced");
+        assert_eq!(export.local_hash, "l2");
+        assert!(db.list_note_exports(Some("conflict")).unwrap().is_empty());
+    }
+}
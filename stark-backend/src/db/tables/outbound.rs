@@ -0,0 +1,288 @@
+//! Outbound delivery queue database operations (outbound_messages)
+//!
+//! Replies that could not be delivered to a platform are persisted here and
+//! retried by the delivery worker. Messages for the same chat are delivered
+//! strictly in insertion order: only the oldest undelivered message of a chat
+//! is ever eligible to send.
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+pub const OUTBOUND_PENDING: &str = "pending";
+pub const OUTBOUND_SENDING: &str = "sending";
+pub const OUTBOUND_SENT: &str = "sent";
+pub const OUTBOUND_DEAD: &str = "dead";
+
+/// How long a claimed message may stay in `sending` before another worker reclaims it
+const SEND_CLAIM_SECS: i64 = 300;
+
+/// A queued outbound message
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboundMessage {
+    pub id: i64,
+    pub channel_id: i64,
+    pub channel_type: String,
+    pub chat_id: String,
+    pub reply_to: Option<String>,
+    pub content: String,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: String,
+    pub created_at: String,
+    pub sent_at: Option<String>,
+}
+
+/// Queue depth by status
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OutboundStats {
+    pub pending: i64,
+    pub sending: i64,
+    pub sent: i64,
+    pub dead: i64,
+}
+
+const OUTBOUND_COLUMNS: &str = "id, channel_id, channel_type, chat_id, reply_to, content, status, attempts, \
+                                last_error, next_attempt_at, created_at, sent_at";
+
+fn row_to_outbound(row: &rusqlite::Row) -> rusqlite::Result<OutboundMessage> {
+    Ok(OutboundMessage {
+        id: row.get(0)?,
+        channel_id: row.get(1)?,
+        channel_type: row.get(2)?,
+        chat_id: row.get(3)?,
+        reply_to: row.get(4)?,
+        content: row.get(5)?,
+        status: row.get(6)?,
+        attempts: row.get(7)?,
+        last_error: row.get(8)?,
+        next_attempt_at: row.get(9)?,
+        created_at: row.get(10)?,
+        sent_at: row.get(11)?,
+    })
+}
+
+impl Database {
+    /// Queue a message for delivery. `failed_attempt` records an immediate send
+    /// failure that happened before queueing (counts as attempt #1).
+    pub fn enqueue_outbound_message(
+        &self,
+        channel_id: i64,
+        channel_type: &str,
+        chat_id: &str,
+        reply_to: Option<&str>,
+        content: &str,
+        failed_attempt: Option<(&str, DateTime<Utc>)>,
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let now = Utc::now();
+        let (attempts, last_error, next_attempt_at) = match failed_attempt {
+            Some((error, retry_at)) => (1, Some(error), retry_at),
+            None => (0, None, now),
+        };
+        conn.execute(
+            "INSERT INTO outbound_messages
+                (channel_id, channel_type, chat_id, reply_to, content, status, attempts, last_error, next_attempt_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 'pending', ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                channel_id,
+                channel_type,
+                chat_id,
+                reply_to,
+                content,
+                attempts,
+                last_error,
+                next_attempt_at.to_rfc3339(),
+                now.to_rfc3339()
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Whether a chat still has undelivered messages queued (new replies must wait behind them)
+    pub fn has_queued_outbound(&self, channel_id: i64, chat_id: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM outbound_messages
+                           WHERE channel_id = ?1 AND chat_id = ?2 AND status IN ('pending', 'sending'))",
+            rusqlite::params![channel_id, chat_id],
+            |row| row.get(0),
+        )
+    }
+
+    /// Claim up to `limit` deliverable messages: the head of each chat's queue,
+    /// if due. Claimed messages move to `sending` so no other worker picks them up.
+    pub fn claim_due_outbound_messages(&self, limit: usize) -> SqliteResult<Vec<OutboundMessage>> {
+        let conn = self.conn();
+        let now = Utc::now();
+        let now_str = now.to_rfc3339();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM outbound_messages o
+             WHERE status IN ('pending', 'sending') AND next_attempt_at <= ?1
+               AND id = (SELECT MIN(id) FROM outbound_messages h
+                         WHERE h.channel_id = o.channel_id AND h.chat_id = o.chat_id
+                           AND h.status IN ('pending', 'sending'))
+             ORDER BY id LIMIT ?2",
+            OUTBOUND_COLUMNS
+        ))?;
+        let candidates = stmt
+            .query_map(rusqlite::params![now_str, limit as i64], row_to_outbound)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let claim_until = (now + Duration::seconds(SEND_CLAIM_SECS)).to_rfc3339();
+        let mut claimed = Vec::with_capacity(candidates.len());
+        for mut msg in candidates {
+            // Compare-and-set on the row we read, so concurrent workers can't both claim it
+            let updated = conn.execute(
+                "UPDATE outbound_messages SET status = 'sending', next_attempt_at = ?1
+                 WHERE id = ?2 AND status = ?3 AND next_attempt_at = ?4",
+                rusqlite::params![claim_until, msg.id, msg.status, msg.next_attempt_at],
+            )?;
+            if updated > 0 {
+                msg.status = OUTBOUND_SENDING.to_string();
+                msg.next_attempt_at = claim_until.clone();
+                claimed.push(msg);
+            }
+        }
+        Ok(claimed)
+    }
+
+    /// Mark a message delivered
+    pub fn mark_outbound_sent(&self, id: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE outbound_messages SET status = 'sent', sent_at = ?1, last_error = NULL WHERE id = ?2",
+            rusqlite::params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Record a failed attempt. `retry_at = None` moves the message to the dead-letter queue.
+    pub fn mark_outbound_failed(&self, id: i64, error: &str, retry_at: Option<DateTime<Utc>>) -> SqliteResult<()> {
+        let conn = self.conn();
+        let (status, next_attempt_at) = match retry_at {
+            Some(at) => (OUTBOUND_PENDING, at.to_rfc3339()),
+            None => (OUTBOUND_DEAD, Utc::now().to_rfc3339()),
+        };
+        conn.execute(
+            "UPDATE outbound_messages
+             SET status = ?1, attempts = attempts + 1, last_error = ?2, next_attempt_at = ?3
+             WHERE id = ?4",
+            rusqlite::params![status, error, next_attempt_at, id],
+        )?;
+        Ok(())
+    }
+
+    /// List queued messages, optionally filtered by status (newest first)
+    pub fn list_outbound_messages(&self, status: Option<&str>, limit: usize) -> SqliteResult<Vec<OutboundMessage>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM outbound_messages WHERE (?1 IS NULL OR status = ?1) ORDER BY id DESC LIMIT ?2",
+            OUTBOUND_COLUMNS
+        ))?;
+        let messages = stmt
+            .query_map(rusqlite::params![status, limit as i64], row_to_outbound)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(messages)
+    }
+
+    /// Count messages by status
+    pub fn outbound_stats(&self) -> SqliteResult<OutboundStats> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT status, COUNT(*) FROM outbound_messages GROUP BY status")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+        let mut stats = OutboundStats::default();
+        for row in rows {
+            let (status, count) = row?;
+            match status.as_str() {
+                OUTBOUND_PENDING => stats.pending = count,
+                OUTBOUND_SENDING => stats.sending = count,
+                OUTBOUND_SENT => stats.sent = count,
+                OUTBOUND_DEAD => stats.dead = count,
+                _ => {}
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Requeue a dead-lettered message for immediate delivery
+    pub fn retry_dead_outbound_message(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let updated = conn.execute(
+            "UPDATE outbound_messages SET status = 'pending', attempts = 0, next_attempt_at = ?1
+             WHERE id = ?2 AND status = 'dead'",
+            rusqlite::params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Delete a queued message (any status except one mid-send)
+    pub fn delete_outbound_message(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let deleted = conn.execute(
+            "DELETE FROM outbound_messages WHERE id = ?1 AND status != 'sending'",
+            [id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Delete delivered messages sent before `cutoff`
+    pub fn purge_sent_outbound_messages(&self, cutoff: DateTime<Utc>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "DELETE FROM outbound_messages WHERE status = 'sent' AND sent_at < ?1",
+            [cutoff.to_rfc3339()],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_chat_head_is_claimed() {
+        let db = Database::new(":memory:").unwrap();
+        let first = db.enqueue_outbound_message(1, "telegram", "42", None, "one", None).unwrap();
+        let second = db.enqueue_outbound_message(1, "telegram", "42", None, "two", None).unwrap();
+        let other = db.enqueue_outbound_message(1, "telegram", "7", None, "other chat", None).unwrap();
+        assert!(db.has_queued_outbound(1, "42").unwrap());
+
+        let claimed: Vec<i64> = db.claim_due_outbound_messages(10).unwrap().iter().map(|m| m.id).collect();
+        assert_eq!(claimed, vec![first, other]);
+        // Already claimed — nothing new until the heads are resolved
+        assert!(db.claim_due_outbound_messages(10).unwrap().is_empty());
+
+        db.mark_outbound_sent(first).unwrap();
+        db.mark_outbound_sent(other).unwrap();
+        let claimed = db.claim_due_outbound_messages(10).unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].id, second);
+        assert_eq!(claimed[0].content, "two");
+    }
+
+    #[test]
+    fn test_backoff_and_dead_letter() {
+        let db = Database::new(":memory:").unwrap();
+        let id = db
+            .enqueue_outbound_message(3, "discord", "99", None, "hi", Some(("timeout", Utc::now() + Duration::hours(1))))
+            .unwrap();
+        // Not due yet
+        assert!(db.claim_due_outbound_messages(10).unwrap().is_empty());
+
+        db.mark_outbound_failed(id, "still down", None).unwrap();
+        let dead = db.list_outbound_messages(Some(OUTBOUND_DEAD), 10).unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].attempts, 2);
+        assert!(!db.has_queued_outbound(3, "99").unwrap());
+
+        assert!(db.retry_dead_outbound_message(id).unwrap());
+        assert_eq!(db.outbound_stats().unwrap().pending, 1);
+        assert_eq!(db.claim_due_outbound_messages(10).unwrap().len(), 1);
+        // Mid-send messages can't be deleted
+        assert!(!db.delete_outbound_message(id).unwrap());
+    }
+}
@@ -0,0 +1,273 @@
+//! Review-before-send outbox (outbox_drafts)
+//!
+//! On channels with review enabled, agent output bound for a public or
+//! external destination (tweets, email replies) is stored here as `pending`
+//! instead of being sent. The owner approves (optionally after editing),
+//! rejects, or lets it expire; only approved drafts are released to the
+//! platform, and only while still pending and unexpired.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+use super::super::Database;
+
+pub const OUTBOX_PENDING: &str = "pending";
+pub const OUTBOX_REJECTED: &str = "rejected";
+pub const OUTBOX_EXPIRED: &str = "expired";
+/// Approved and handed to the platform (or its send queue)
+pub const OUTBOX_RELEASED: &str = "released";
+/// Approved, but the platform send failed
+pub const OUTBOX_FAILED: &str = "failed";
+
+/// Where a held draft goes once approved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "platform", rename_all = "snake_case")]
+pub enum DraftTarget {
+    Twitter {
+        /// Channel that should post it (None = any running Twitter channel)
+        #[serde(default)]
+        channel_id: Option<i64>,
+        #[serde(default)]
+        reply_to_id: Option<String>,
+        #[serde(default)]
+        quote_tweet_id: Option<String>,
+        /// Earliest time to post once approved
+        #[serde(default)]
+        not_before: Option<DateTime<Utc>>,
+    },
+    Gmail {
+        thread_id: String,
+        to: String,
+        subject: String,
+        #[serde(default)]
+        in_reply_to: Option<String>,
+    },
+}
+
+impl DraftTarget {
+    pub fn platform(&self) -> &'static str {
+        match self {
+            DraftTarget::Twitter { .. } => "twitter",
+            DraftTarget::Gmail { .. } => "gmail",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxDraft {
+    pub id: i64,
+    pub platform: String,
+    pub target: DraftTarget,
+    pub content: String,
+    pub status: String,
+    pub session_id: Option<i64>,
+    pub expires_at: String,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<String>,
+    /// Platform reference once released (tweet queue ID, Gmail message ID)
+    pub delivery_ref: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const DRAFT_COLS: &str = "id, platform, target_json, content, status, session_id, expires_at, decided_by, \
+                          decided_at, delivery_ref, last_error, created_at, updated_at";
+
+fn row_to_draft(row: &rusqlite::Row) -> rusqlite::Result<OutboxDraft> {
+    let target_json: String = row.get(2)?;
+    let target = serde_json::from_str(&target_json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(OutboxDraft {
+        id: row.get(0)?,
+        platform: row.get(1)?,
+        target,
+        content: row.get(3)?,
+        status: row.get(4)?,
+        session_id: row.get(5)?,
+        expires_at: row.get(6)?,
+        decided_by: row.get(7)?,
+        decided_at: row.get(8)?,
+        delivery_ref: row.get(9)?,
+        last_error: row.get(10)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+impl Database {
+    /// Hold a draft for review until `expires_at`
+    pub fn create_outbox_draft(
+        &self,
+        target: &DraftTarget,
+        content: &str,
+        session_id: Option<i64>,
+        expires_at: DateTime<Utc>,
+    ) -> SqliteResult<OutboxDraft> {
+        let id = {
+            let conn = self.conn();
+            let now = Utc::now().to_rfc3339();
+            let target_json = serde_json::to_string(target).unwrap_or_default();
+            conn.execute(
+                "INSERT INTO outbox_drafts (platform, target_json, content, status, session_id, expires_at, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+                rusqlite::params![
+                    target.platform(),
+                    target_json,
+                    content,
+                    OUTBOX_PENDING,
+                    session_id,
+                    expires_at.to_rfc3339(),
+                    now,
+                ],
+            )?;
+            conn.last_insert_rowid()
+        };
+        self.get_outbox_draft(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    pub fn get_outbox_draft(&self, id: i64) -> SqliteResult<Option<OutboxDraft>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM outbox_drafts WHERE id = ?1", DRAFT_COLS),
+            [id],
+            row_to_draft,
+        )
+        .optional()
+    }
+
+    /// List drafts, newest first
+    pub fn list_outbox_drafts(&self, status: Option<&str>, limit: usize) -> SqliteResult<Vec<OutboxDraft>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM outbox_drafts WHERE ?1 IS NULL OR status = ?1 ORDER BY id DESC LIMIT ?2",
+            DRAFT_COLS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![status, limit as i64], row_to_draft)?;
+        rows.collect()
+    }
+
+    /// Replace the text of a pending draft before it is approved
+    pub fn edit_outbox_draft(&self, id: i64, content: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let updated = conn.execute(
+            "UPDATE outbox_drafts SET content = ?1, updated_at = ?2
+             WHERE id = ?3 AND status = ?4 AND expires_at > ?2",
+            rusqlite::params![content, now, id, OUTBOX_PENDING],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Approve or reject a pending draft. Returns false if it was already
+    /// decided or has expired. Approved drafts are left `pending` with
+    /// `decided_by` set until [`Database::mark_outbox_draft_released`] or
+    /// [`Database::mark_outbox_draft_failed`] records the send.
+    pub fn decide_outbox_draft(&self, id: i64, approve: bool, decided_by: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let updated = if approve {
+            conn.execute(
+                "UPDATE outbox_drafts SET decided_by = ?1, decided_at = ?2, updated_at = ?2
+                 WHERE id = ?3 AND status = ?4 AND decided_at IS NULL AND expires_at > ?2",
+                rusqlite::params![decided_by, now, id, OUTBOX_PENDING],
+            )?
+        } else {
+            conn.execute(
+                "UPDATE outbox_drafts SET status = ?1, decided_by = ?2, decided_at = ?3, updated_at = ?3
+                 WHERE id = ?4 AND status = ?5 AND decided_at IS NULL",
+                rusqlite::params![OUTBOX_REJECTED, decided_by, now, id, OUTBOX_PENDING],
+            )?
+        };
+        Ok(updated > 0)
+    }
+
+    pub fn mark_outbox_draft_released(&self, id: i64, delivery_ref: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE outbox_drafts SET status = ?1, delivery_ref = ?2, last_error = NULL, updated_at = ?3 WHERE id = ?4",
+            rusqlite::params![OUTBOX_RELEASED, delivery_ref, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    pub fn mark_outbox_draft_failed(&self, id: i64, error: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE outbox_drafts SET status = ?1, last_error = ?2, updated_at = ?3 WHERE id = ?4",
+            rusqlite::params![OUTBOX_FAILED, error, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Mark every undecided draft past its deadline as expired
+    pub fn expire_outbox_drafts(&self, now: DateTime<Utc>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        let now = now.to_rfc3339();
+        conn.execute(
+            "UPDATE outbox_drafts SET status = ?1, updated_at = ?2
+             WHERE status = ?3 AND decided_at IS NULL AND expires_at <= ?2",
+            rusqlite::params![OUTBOX_EXPIRED, now, OUTBOX_PENDING],
+        )
+    }
+
+    /// Count of drafts waiting for a decision
+    pub fn count_pending_outbox_drafts(&self) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT COUNT(*) FROM outbox_drafts WHERE status = ?1 AND decided_at IS NULL",
+            [OUTBOX_PENDING],
+            |row| row.get(0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn tweet() -> DraftTarget {
+        DraftTarget::Twitter {
+            channel_id: Some(1),
+            reply_to_id: Some("1893027483920175104".to_string()),
+            quote_tweet_id: None,
+            not_before: None,
+        }
+    }
+
+    #[test]
+    fn test_draft_lifecycle() {
+        let db = Database::new(":memory:").unwrap();
+        let draft = db
+            .create_outbox_draft(&tweet(), "gm", Some(7), Utc::now() + Duration::hours(1))
+            .unwrap();
+        assert_eq!(draft.platform, "twitter");
+        assert_eq!(draft.target, tweet());
+        assert_eq!(db.count_pending_outbox_drafts().unwrap(), 1);
+
+        assert!(db.edit_outbox_draft(draft.id, "gm frens").unwrap());
+        assert!(db.decide_outbox_draft(draft.id, true, "owner").unwrap());
+        // A decision is final
+        assert!(!db.decide_outbox_draft(draft.id, false, "owner").unwrap());
+        db.mark_outbox_draft_released(draft.id, Some("queue:3")).unwrap();
+
+        let released = db.get_outbox_draft(draft.id).unwrap().unwrap();
+        assert_eq!(released.status, OUTBOX_RELEASED);
+        assert_eq!(released.content, "gm frens");
+        assert_eq!(db.count_pending_outbox_drafts().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_expired_drafts_cannot_be_approved() {
+        let db = Database::new(":memory:").unwrap();
+        let draft = db
+            .create_outbox_draft(&tweet(), "late", None, Utc::now() - Duration::minutes(1))
+            .unwrap();
+        assert!(!db.decide_outbox_draft(draft.id, true, "owner").unwrap());
+        assert_eq!(db.expire_outbox_drafts(Utc::now()).unwrap(), 1);
+        assert_eq!(db.get_outbox_draft(draft.id).unwrap().unwrap().status, OUTBOX_EXPIRED);
+    }
+}
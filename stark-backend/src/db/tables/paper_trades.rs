@@ -0,0 +1,236 @@
+//! Paper trading ledger database operations (paper_trades)
+//!
+//! In paper mode the finance tools record a simulated fill here instead of
+//! signing. Fills come from live quotes and are priced in USD when recorded,
+//! so the ledger can be replayed into PnL exactly like the real journal.
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::super::Database;
+use super::trades::{Trade, TRADE_EXECUTED};
+
+pub const PAPER_KIND_SWAP: &str = "swap";
+pub const PAPER_KIND_TRANSFER: &str = "transfer";
+/// Arbitrary contract call (only the native value sent is tracked)
+pub const PAPER_KIND_CALL: &str = "contract_call";
+/// Cross-chain bridge (source side leaves; arrival is not simulated)
+pub const PAPER_KIND_BRIDGE: &str = "bridge";
+
+/// A simulated fill
+#[derive(Debug, Clone, Serialize)]
+pub struct PaperTrade {
+    pub id: i64,
+    pub kind: String,
+    pub network: String,
+    pub sell_token: String,
+    pub sell_symbol: String,
+    pub sell_amount_raw: String,
+    pub sell_decimals: u8,
+    pub buy_token: Option<String>,
+    pub buy_symbol: Option<String>,
+    pub buy_amount_raw: Option<String>,
+    pub buy_decimals: Option<u8>,
+    /// Transfer recipient, contract called, or bridge destination
+    pub counterparty: Option<String>,
+    /// USD prices when the fill was simulated
+    pub sell_price_usd: Option<f64>,
+    pub buy_price_usd: Option<f64>,
+    /// Quote / call details the fill was simulated from
+    pub details: Option<Value>,
+    pub channel_id: Option<i64>,
+    pub created_at: String,
+}
+
+impl PaperTrade {
+    /// As an executed journal entry, so the journal's PnL replay applies
+    pub fn to_trade(&self) -> Trade {
+        Trade {
+            id: self.id,
+            tx_uuid: format!("paper-{}", self.id),
+            kind: self.kind.clone(),
+            network: self.network.clone(),
+            sell_token: self.sell_token.clone(),
+            sell_symbol: self.sell_symbol.clone(),
+            sell_amount_raw: self.sell_amount_raw.clone(),
+            sell_decimals: self.sell_decimals,
+            buy_token: self.buy_token.clone(),
+            buy_symbol: self.buy_symbol.clone(),
+            buy_amount_raw: self.buy_amount_raw.clone(),
+            buy_decimals: self.buy_decimals,
+            counterparty: self.counterparty.clone(),
+            sell_price_usd: self.sell_price_usd,
+            buy_price_usd: self.buy_price_usd,
+            priced_at: Some(self.created_at.clone()),
+            status: TRADE_EXECUTED.to_string(),
+            tx_hash: None,
+            channel_id: self.channel_id,
+            created_at: self.created_at.clone(),
+            executed_at: Some(self.created_at.clone()),
+        }
+    }
+}
+
+/// Fields for a new simulated fill
+pub struct RecordPaperTradeRequest {
+    pub kind: &'static str,
+    pub network: String,
+    pub sell_token: String,
+    pub sell_symbol: String,
+    pub sell_amount_raw: String,
+    pub sell_decimals: u8,
+    pub buy_token: Option<String>,
+    pub buy_symbol: Option<String>,
+    pub buy_amount_raw: Option<String>,
+    pub buy_decimals: Option<u8>,
+    pub counterparty: Option<String>,
+    pub sell_price_usd: Option<f64>,
+    pub buy_price_usd: Option<f64>,
+    pub details: Option<Value>,
+    pub channel_id: Option<i64>,
+}
+
+const PAPER_TRADE_COLUMNS: &str = "id, kind, network, sell_token, sell_symbol, sell_amount_raw, sell_decimals, \
+                                   buy_token, buy_symbol, buy_amount_raw, buy_decimals, counterparty, sell_price_usd, \
+                                   buy_price_usd, details, channel_id, created_at";
+
+fn row_to_paper_trade(row: &rusqlite::Row) -> rusqlite::Result<PaperTrade> {
+    let details: Option<String> = row.get(14)?;
+    Ok(PaperTrade {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        network: row.get(2)?,
+        sell_token: row.get(3)?,
+        sell_symbol: row.get(4)?,
+        sell_amount_raw: row.get(5)?,
+        sell_decimals: row.get(6)?,
+        buy_token: row.get(7)?,
+        buy_symbol: row.get(8)?,
+        buy_amount_raw: row.get(9)?,
+        buy_decimals: row.get(10)?,
+        counterparty: row.get(11)?,
+        sell_price_usd: row.get(12)?,
+        buy_price_usd: row.get(13)?,
+        details: details.and_then(|d| serde_json::from_str(&d).ok()),
+        channel_id: row.get(15)?,
+        created_at: row.get(16)?,
+    })
+}
+
+impl Database {
+    /// Record a simulated fill
+    pub fn record_paper_trade(&self, req: RecordPaperTradeRequest) -> SqliteResult<PaperTrade> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO paper_trades (kind, network, sell_token, sell_symbol, sell_amount_raw, sell_decimals,
+                                       buy_token, buy_symbol, buy_amount_raw, buy_decimals, counterparty,
+                                       sell_price_usd, buy_price_usd, details, channel_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            rusqlite::params![
+                req.kind,
+                req.network,
+                req.sell_token.to_lowercase(),
+                req.sell_symbol,
+                req.sell_amount_raw,
+                req.sell_decimals,
+                req.buy_token.map(|t| t.to_lowercase()),
+                req.buy_symbol,
+                req.buy_amount_raw,
+                req.buy_decimals,
+                req.counterparty,
+                req.sell_price_usd,
+                req.buy_price_usd,
+                req.details.map(|d| d.to_string()),
+                req.channel_id,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.query_row(
+            &format!("SELECT {} FROM paper_trades WHERE id = ?1", PAPER_TRADE_COLUMNS),
+            [id],
+            row_to_paper_trade,
+        )
+    }
+
+    /// Paper fills, newest first, optionally for one channel and since an RFC 3339 time
+    pub fn list_paper_trades(
+        &self,
+        channel_id: Option<i64>,
+        since: Option<&str>,
+        limit: usize,
+    ) -> SqliteResult<Vec<PaperTrade>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM paper_trades
+             WHERE (?1 IS NULL OR channel_id = ?1) AND (?2 IS NULL OR created_at >= ?2)
+             ORDER BY id DESC LIMIT ?3",
+            PAPER_TRADE_COLUMNS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![channel_id, since, limit as i64], row_to_paper_trade)?;
+        rows.collect()
+    }
+
+    /// Every paper fill in fill order (the input to paper PnL)
+    pub fn list_all_paper_trades(&self, channel_id: Option<i64>) -> SqliteResult<Vec<PaperTrade>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM paper_trades WHERE (?1 IS NULL OR channel_id = ?1) ORDER BY id ASC",
+            PAPER_TRADE_COLUMNS
+        ))?;
+        let rows = stmt.query_map([channel_id], row_to_paper_trade)?;
+        rows.collect()
+    }
+
+    /// Wipe the paper ledger (one channel, or everything). Returns rows deleted.
+    pub fn clear_paper_trades(&self, channel_id: Option<i64>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM paper_trades WHERE (?1 IS NULL OR channel_id = ?1)", [channel_id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap(channel_id: i64) -> RecordPaperTradeRequest {
+        RecordPaperTradeRequest {
+            kind: PAPER_KIND_SWAP,
+            network: "base".to_string(),
+            sell_token: "0xUSDC".to_string(),
+            sell_symbol: "USDC".to_string(),
+            sell_amount_raw: "100000000".to_string(),
+            sell_decimals: 6,
+            buy_token: Some("0xWETH".to_string()),
+            buy_symbol: Some("WETH".to_string()),
+            buy_amount_raw: Some("40000000000000000".to_string()),
+            buy_decimals: Some(18),
+            counterparty: None,
+            sell_price_usd: Some(1.0),
+            buy_price_usd: Some(2500.0),
+            details: Some(serde_json::json!({ "source": "quote" })),
+            channel_id: Some(channel_id),
+        }
+    }
+
+    #[test]
+    fn test_paper_ledger() {
+        let db = Database::new(":memory:").unwrap();
+        let fill = db.record_paper_trade(swap(1)).unwrap();
+        assert_eq!(fill.sell_token, "0xusdc");
+        assert_eq!(fill.details.as_ref().unwrap()["source"], "quote");
+        db.record_paper_trade(swap(2)).unwrap();
+
+        assert_eq!(db.list_paper_trades(None, None, 10).unwrap().len(), 2);
+        assert_eq!(db.list_all_paper_trades(Some(1)).unwrap().len(), 1);
+
+        let trade = fill.to_trade();
+        assert_eq!(trade.status, TRADE_EXECUTED);
+        assert_eq!(trade.buy_amount(), Some(0.04));
+
+        assert_eq!(db.clear_paper_trades(Some(1)).unwrap(), 1);
+        assert_eq!(db.list_paper_trades(None, None, 10).unwrap().len(), 1);
+    }
+}
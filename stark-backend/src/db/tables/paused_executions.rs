@@ -0,0 +1,166 @@
+//! Paused executions (paused_executions)
+//!
+//! When the owner pauses a long-running execution, the tool loop stops at its
+//! next safe boundary and stores everything needed to pick up again: the full
+//! AgentContext (task queue included, which agent_contexts doesn't keep), the
+//! tool history of the native loop or the extra messages of the text loop,
+//! and the log of tool calls made so far. One paused execution per chat.
+//! The planner parks executions here too while it waits for the answer to a
+//! clarification question (`AgentContext::pending_clarification`).
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use super::super::Database;
+use crate::ai::multi_agent::types::AgentContext;
+use crate::ai::{Message, ToolHistoryEntry};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PausedExecution {
+    pub id: i64,
+    pub channel_id: i64,
+    pub chat_id: String,
+    /// Session the execution was running in when paused
+    pub session_id: i64,
+    pub context: AgentContext,
+    /// Native tool calling: tool rounds so far
+    pub tool_history: Vec<ToolHistoryEntry>,
+    /// Text tool calling: messages appended to the conversation so far
+    pub messages: Vec<Message>,
+    pub tool_call_log: Vec<String>,
+    pub paused_at: String,
+}
+
+const PAUSED_COLS: &str = "id, channel_id, chat_id, session_id, context_json, tool_history_json, \
+                           messages_json, tool_call_log_json, paused_at";
+
+fn json_col<T: serde::de::DeserializeOwned>(row: &rusqlite::Row, idx: usize) -> rusqlite::Result<T> {
+    let raw: String = row.get(idx)?;
+    serde_json::from_str(&raw).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn row_to_paused(row: &rusqlite::Row) -> rusqlite::Result<PausedExecution> {
+    Ok(PausedExecution {
+        id: row.get(0)?,
+        channel_id: row.get(1)?,
+        chat_id: row.get(2)?,
+        session_id: row.get(3)?,
+        context: json_col(row, 4)?,
+        tool_history: json_col(row, 5)?,
+        messages: json_col(row, 6)?,
+        tool_call_log: json_col(row, 7)?,
+        paused_at: row.get(8)?,
+    })
+}
+
+impl Database {
+    /// Store a paused execution, replacing any earlier one for the same chat
+    pub fn save_paused_execution(
+        &self,
+        channel_id: i64,
+        chat_id: &str,
+        session_id: i64,
+        context: &AgentContext,
+        tool_history: &[ToolHistoryEntry],
+        messages: &[Message],
+        tool_call_log: &[String],
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let to_json = |value: serde_json::Result<String>| {
+            value.map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+        };
+        conn.execute(
+            "INSERT OR REPLACE INTO paused_executions
+                (channel_id, chat_id, session_id, context_json, tool_history_json, messages_json, tool_call_log_json, paused_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                channel_id,
+                chat_id,
+                session_id,
+                to_json(serde_json::to_string(context))?,
+                to_json(serde_json::to_string(tool_history))?,
+                to_json(serde_json::to_string(messages))?,
+                to_json(serde_json::to_string(tool_call_log))?,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Most recently paused execution on a channel
+    pub fn get_latest_paused_execution(&self, channel_id: i64) -> SqliteResult<Option<PausedExecution>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM paused_executions WHERE channel_id = ?1 ORDER BY paused_at DESC, id DESC LIMIT 1",
+                PAUSED_COLS
+            ),
+            [channel_id],
+            row_to_paused,
+        )
+        .optional()
+    }
+
+    /// The paused execution for a chat, left in place
+    pub fn get_paused_execution(&self, channel_id: i64, chat_id: &str) -> SqliteResult<Option<PausedExecution>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM paused_executions WHERE channel_id = ?1 AND chat_id = ?2",
+                PAUSED_COLS
+            ),
+            rusqlite::params![channel_id, chat_id],
+            row_to_paused,
+        )
+        .optional()
+    }
+
+    /// Remove and return the paused execution for a chat (used when resuming)
+    pub fn take_paused_execution(&self, channel_id: i64, chat_id: &str) -> SqliteResult<Option<PausedExecution>> {
+        let paused = self.get_paused_execution(channel_id, chat_id)?;
+        if let Some(ref p) = paused {
+            self.delete_paused_execution(p.id)?;
+        }
+        Ok(paused)
+    }
+
+    /// Discard a paused execution without resuming it
+    pub fn delete_paused_execution(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let deleted = conn.execute("DELETE FROM paused_executions WHERE id = ?1", [id])?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::multi_agent::types::TaskQueue;
+
+    #[test]
+    fn test_pause_roundtrip_keeps_task_queue() {
+        let db = Database::new(":memory:").unwrap();
+        let mut context = AgentContext {
+            original_request: "research L2 fees".to_string(),
+            planner_completed: true,
+            task_queue: TaskQueue::from_descriptions(vec!["collect".to_string(), "compare".to_string()]),
+            ..Default::default()
+        };
+        context.task_queue.pop_next();
+
+        db.save_paused_execution(0, "web-abc", 12, &context, &[], &[], &["web_fetch".to_string()])
+            .unwrap();
+        let latest = db.get_latest_paused_execution(0).unwrap().unwrap();
+        assert_eq!(latest.chat_id, "web-abc");
+
+        let paused = db.take_paused_execution(0, "web-abc").unwrap().unwrap();
+        assert_eq!(paused.session_id, 12);
+        assert_eq!(paused.context.task_queue.current_task().map(|t| t.id), Some(1));
+        assert_eq!(paused.tool_call_log, vec!["web_fetch".to_string()]);
+        // Resuming consumes it
+        assert!(db.take_paused_execution(0, "web-abc").unwrap().is_none());
+    }
+}
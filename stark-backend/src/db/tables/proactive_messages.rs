@@ -0,0 +1,50 @@
+//! Proactive message log (proactive_messages)
+//!
+//! One row per unsolicited message sent to a channel. Rows only exist to
+//! count against the channel's daily limit, so old ones are pruned as new
+//! ones are reserved.
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Result as SqliteResult;
+
+use super::super::Database;
+
+/// Rows older than this are no longer needed for any daily count
+const KEEP_DAYS: i64 = 2;
+
+impl Database {
+    /// Record a proactive message for `channel_id` unless `limit` messages were
+    /// already sent since `since`. Check and insert are one statement, so
+    /// concurrent senders can't overshoot the limit. Returns the new row id, or
+    /// None when the limit is reached.
+    pub fn reserve_proactive_message(
+        &self,
+        channel_id: i64,
+        chat_id: &str,
+        kind: &str,
+        since: DateTime<Utc>,
+        limit: Option<u32>,
+    ) -> SqliteResult<Option<i64>> {
+        let conn = self.conn();
+        let now = Utc::now();
+        conn.execute(
+            "DELETE FROM proactive_messages WHERE created_at < ?1",
+            [(now - Duration::days(KEEP_DAYS)).to_rfc3339()],
+        )?;
+        let inserted = conn.execute(
+            "INSERT INTO proactive_messages (channel_id, chat_id, kind, created_at)
+             SELECT ?1, ?2, ?3, ?4
+             WHERE ?5 IS NULL
+                OR (SELECT COUNT(*) FROM proactive_messages WHERE channel_id = ?1 AND created_at >= ?6) < ?5",
+            rusqlite::params![channel_id, chat_id, kind, now.to_rfc3339(), limit, since.to_rfc3339()],
+        )?;
+        Ok((inserted > 0).then(|| conn.last_insert_rowid()))
+    }
+
+    /// Give back a reservation whose message could not be delivered
+    pub fn release_proactive_message(&self, id: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM proactive_messages WHERE id = ?1", [id])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+//! Read-only introspection queries (q_* views)
+//!
+//! The agent and the admin API can run parameterized SELECTs against a small
+//! whitelist of views over sessions, messages, memories, tool activity and
+//! usage. Queries may only reference those views (never the underlying
+//! tables), must be a single read-only statement, and are capped in rows,
+//! columns, cell length and run time. Encrypted values are decrypted and every string
+//! cell goes through the memory secret redactor before it leaves the DB layer.
+
+use std::time::{Duration, Instant};
+
+use rusqlite::types::ValueRef;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::super::{Database, DbConn};
+use crate::db::encryption::decrypt_field;
+use crate::memory::redaction::redact_content;
+
+/// Hard cap on rows returned by one query
+pub const MAX_QUERY_ROWS: usize = 1000;
+/// Rows returned when the caller doesn't ask for a limit
+pub const DEFAULT_QUERY_ROWS: usize = 100;
+const MAX_QUERY_COLUMNS: usize = 50;
+const MAX_CELL_CHARS: usize = 500;
+/// Wall-clock budget for one query before SQLite interrupts it
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+/// VM instructions between deadline checks
+const PROGRESS_STEPS: i32 = 10_000;
+
+/// Whitelisted views: (name, description, definition)
+pub const QUERY_VIEWS: &[(&str, &str, &str)] = &[
+    (
+        "q_sessions",
+        "Chat sessions: id, channel_type, channel_id, platform_chat_id, scope, is_active, completion_status, safe_mode, context_tokens, created_at, last_activity_at",
+        "SELECT id, channel_type, channel_id, platform_chat_id, scope, is_active, completion_status,
+                safe_mode, context_tokens, created_at, last_activity_at
+         FROM chat_sessions",
+    ),
+    (
+        "q_messages",
+        "Session messages joined with their session: id, session_id, channel_type, channel_id, role, user_id, user_name, content, tokens_used, created_at",
+        "SELECT m.id, m.session_id, s.channel_type, s.channel_id, m.role, m.user_id, m.user_name,
+                m.content, m.tokens_used, m.created_at
+         FROM session_messages m JOIN chat_sessions s ON s.id = m.session_id",
+    ),
+    (
+        "q_memories",
+        "Memories: id, memory_type, category, content, importance, identity_id, entity_type, entity_name, superseded_by, created_at, updated_at",
+        "SELECT id, memory_type, category, content, importance, identity_id, entity_type, entity_name,
+                superseded_by, created_at, updated_at
+         FROM memories",
+    ),
+    (
+        "q_activity",
+        "Tool executions: id, channel_id, session_id, tool_name, success, duration_ms, executed_at",
+        "SELECT id, channel_id, session_id, tool_name, success, duration_ms, executed_at
+         FROM tool_executions",
+    ),
+    (
+        "q_usage",
+        "Daily message/token usage per channel type: day, channel_type, role, messages, tokens",
+        "SELECT date(m.created_at) AS day, s.channel_type, m.role, COUNT(*) AS messages,
+                SUM(COALESCE(m.tokens_used, 0)) AS tokens
+         FROM session_messages m JOIN chat_sessions s ON s.id = m.session_id
+         GROUP BY day, s.channel_type, m.role",
+    ),
+    (
+        "q_metrics",
+        "Skill counters and metrics, one row per increment/observation: id, name, kind ('counter' or 'metric'), value, skill, labels (JSON), recorded_at",
+        "SELECT id, name, kind, value, skill, labels, recorded_at
+         FROM metric_events",
+    ),
+];
+
+/// (Re)create the whitelisted views so their definitions track this build
+pub(crate) fn create_query_views(conn: &DbConn) -> rusqlite::Result<()> {
+    for (name, _, definition) in QUERY_VIEWS {
+        conn.execute(&format!("DROP VIEW IF EXISTS {}", name), [])?;
+        conn.execute(&format!("CREATE VIEW {} AS {}", name, definition), [])?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    /// More rows matched than were returned
+    pub truncated: bool,
+    /// Number of string cells that had secrets redacted
+    pub redactions: usize,
+}
+
+/// Identifiers in the SQL outside of string literals and comments
+fn identifiers(sql: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                // String literal ('' escapes a quote)
+                while let Some(n) = chars.next() {
+                    if n == '\'' {
+                        if chars.peek() == Some(&'\'') {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            '"' | '`' | '[' => {
+                // Quoted identifier
+                let close = if c == '[' { ']' } else { c };
+                let mut ident = String::new();
+                for n in chars.by_ref() {
+                    if n == close {
+                        break;
+                    }
+                    ident.push(n);
+                }
+                out.push(ident.to_lowercase());
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for n in chars.by_ref() {
+                    if n == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for n in chars.by_ref() {
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(&n) = chars.peek() {
+                    if n.is_ascii_alphanumeric() || n == '_' || n == '$' {
+                        ident.push(n);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                out.push(ident.to_lowercase());
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Reject anything that isn't a single SELECT over the whitelisted views
+fn validate_query(conn: &DbConn, sql: &str) -> Result<(), String> {
+    let trimmed = sql.trim().trim_end_matches(';').trim();
+    if trimmed.is_empty() {
+        return Err("Query is empty".to_string());
+    }
+    if trimmed.contains(';') {
+        return Err("Only a single statement is allowed".to_string());
+    }
+    let idents = identifiers(trimmed);
+    match idents.first().map(String::as_str) {
+        Some("select") | Some("with") => {}
+        _ => return Err("Only SELECT queries are allowed".to_string()),
+    }
+    if idents
+        .iter()
+        .any(|i| i == "pragma" || i == "attach" || i.starts_with("pragma_") || i.starts_with("sqlite_"))
+    {
+        return Err("PRAGMA, ATTACH, pragma_* functions and sqlite_* objects are not allowed".to_string());
+    }
+
+    // Any real table or non-whitelisted view name is off limits
+    let mut stmt = conn
+        .prepare("SELECT lower(name) FROM sqlite_master WHERE type IN ('table', 'view')")
+        .map_err(|e| e.to_string())?;
+    let objects: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    let allowed = |name: &str| QUERY_VIEWS.iter().any(|(v, _, _)| *v == name);
+    if let Some(forbidden) = idents.iter().find(|i| objects.contains(i) && !allowed(i)) {
+        return Err(format!(
+            "'{}' is not queryable. Use one of: {}",
+            forbidden,
+            QUERY_VIEWS.iter().map(|(v, _, _)| *v).collect::<Vec<_>>().join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn json_to_sql(value: &Value) -> rusqlite::types::Value {
+    use rusqlite::types::Value as Sql;
+    match value {
+        Value::Null => Sql::Null,
+        Value::Bool(b) => Sql::Integer(*b as i64),
+        Value::Number(n) => n
+            .as_i64()
+            .map(Sql::Integer)
+            .unwrap_or_else(|| Sql::Real(n.as_f64().unwrap_or_default())),
+        Value::String(s) => Sql::Text(s.clone()),
+        other => Sql::Text(other.to_string()),
+    }
+}
+
+/// Decrypt, redact and truncate a text cell
+fn clean_text(raw: String, redactions: &mut usize) -> Value {
+    let redacted = redact_content(&decrypt_field(raw));
+    if redacted.redaction_count > 0 {
+        *redactions += 1;
+    }
+    let mut text = redacted.content;
+    if text.chars().count() > MAX_CELL_CHARS {
+        text = text.chars().take(MAX_CELL_CHARS).collect::<String>() + "…";
+    }
+    Value::String(text)
+}
+
+impl Database {
+    /// Run a parameterized read-only query against the q_* views.
+    ///
+    /// Blocks for up to the query time limit; call it from a blocking task.
+    pub fn run_introspection_query(
+        &self,
+        sql: &str,
+        params: &[Value],
+        limit: Option<usize>,
+    ) -> Result<QueryResult, String> {
+        self.run_introspection_query_within(sql, params, limit, QUERY_TIMEOUT)
+    }
+
+    fn run_introspection_query_within(
+        &self,
+        sql: &str,
+        params: &[Value],
+        limit: Option<usize>,
+        timeout: Duration,
+    ) -> Result<QueryResult, String> {
+        let conn = self.conn();
+        validate_query(&conn, sql)?;
+
+        let deadline = Instant::now() + timeout;
+        conn.progress_handler(PROGRESS_STEPS, Some(move || Instant::now() >= deadline));
+        let result = execute_query(&conn, sql, params, limit);
+        // The connection goes back to the pool, so drop the deadline with it
+        conn.progress_handler(0, None::<fn() -> bool>);
+        if result.is_err() && Instant::now() >= deadline {
+            return Err(format!("Query exceeded the {}s time limit", timeout.as_secs_f64()));
+        }
+        result
+    }
+}
+
+/// Prepare, run and collect one validated query
+fn execute_query(conn: &DbConn, sql: &str, params: &[Value], limit: Option<usize>) -> Result<QueryResult, String> {
+    let sql = sql.trim().trim_end_matches(';');
+    let mut stmt = conn.prepare(sql).map_err(|e| format!("Invalid query: {}", e))?;
+    if !stmt.readonly() {
+        return Err("Only read-only queries are allowed".to_string());
+    }
+    let column_count = stmt.column_count();
+    if column_count > MAX_QUERY_COLUMNS {
+        return Err(format!("Too many columns ({}; max {})", column_count, MAX_QUERY_COLUMNS));
+    }
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+
+    let limit = limit.unwrap_or(DEFAULT_QUERY_ROWS).clamp(1, MAX_QUERY_ROWS);
+    let bound: Vec<rusqlite::types::Value> = params.iter().map(json_to_sql).collect();
+    let mut rows = stmt
+        .query(rusqlite::params_from_iter(bound))
+        .map_err(|e| format!("Query failed: {}", e))?;
+
+    let mut out = Vec::new();
+    let mut redactions = 0;
+    let mut truncated = false;
+    while let Some(row) = rows.next().map_err(|e| format!("Query failed: {}", e))? {
+        if out.len() >= limit {
+            truncated = true;
+            break;
+        }
+        let mut values = Vec::with_capacity(column_count);
+        for i in 0..column_count {
+            let value = match row.get_ref(i).map_err(|e| e.to_string())? {
+                ValueRef::Null => Value::Null,
+                ValueRef::Integer(n) => json!(n),
+                ValueRef::Real(f) => json!(f),
+                ValueRef::Text(t) => clean_text(String::from_utf8_lossy(t).to_string(), &mut redactions),
+                ValueRef::Blob(b) => Value::String(format!("<blob {} bytes>", b.len())),
+            };
+            values.push(value);
+        }
+        out.push(values);
+    }
+
+    Ok(QueryResult { columns, rows: out, truncated, redactions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_views_only() {
+        let db = Database::new(":memory:").unwrap();
+        let result = db
+            .run_introspection_query("SELECT COUNT(*) AS n FROM q_sessions WHERE channel_type = ?1", &[json!("discord")], None)
+            .unwrap();
+        assert_eq!(result.columns, vec!["n".to_string()]);
+        assert_eq!(result.rows, vec![vec![json!(0)]]);
+
+        // Underlying tables, writes and multiple statements are rejected
+        assert!(db.run_introspection_query("SELECT * FROM chat_sessions", &[], None).is_err());
+        assert!(db.run_introspection_query("SELECT * FROM q_sessions WHERE id IN (SELECT id FROM \"memories\")", &[], None).is_err());
+        assert!(db.run_introspection_query("DELETE FROM q_sessions", &[], None).is_err());
+        assert!(db.run_introspection_query("SELECT 1; DROP TABLE memories", &[], None).is_err());
+        assert!(db.run_introspection_query("SELECT name FROM sqlite_master", &[], None).is_err());
+        assert!(db.run_introspection_query("SELECT * FROM pragma_table_info('memories')", &[], None).is_err());
+        assert!(db.run_introspection_query("SELECT * FROM q_sessions, PRAGMA_TABLE_LIST", &[], None).is_err());
+        // Table names inside string literals are fine
+        assert!(db.run_introspection_query("SELECT 'memories' AS label FROM q_memories", &[], None).is_ok());
+    }
+
+    #[test]
+    fn test_runaway_queries_are_interrupted() {
+        let db = Database::new(":memory:").unwrap();
+        let sql = "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) SELECT COUNT(*) FROM n, q_sessions";
+        let err = db
+            .run_introspection_query_within(sql, &[], None, Duration::from_millis(50))
+            .unwrap_err();
+        assert!(err.contains("time limit"), "{}", err);
+
+        // The pooled connection doesn't keep the expired deadline
+        assert!(db.run_introspection_query("SELECT COUNT(*) FROM q_sessions", &[], None).is_ok());
+    }
+
+    #[test]
+    fn test_limit_and_redaction() {
+        let db = Database::new(":memory:").unwrap();
+        let result = db
+            .run_introspection_query(
+                "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 20)
+                 SELECT i, 'password: hunter2' AS note FROM n",
+                &[],
+                Some(5),
+            )
+            .unwrap();
+        assert_eq!(result.rows.len(), 5);
+        assert!(result.truncated);
+        assert_eq!(result.redactions, 5);
+        assert_eq!(result.rows[0][1], json!("[REDACTED:password]"));
+    }
+}
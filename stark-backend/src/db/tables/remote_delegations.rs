@@ -0,0 +1,148 @@
+//! Remote agent delegations - audit trail of subtasks sent to other EIP-8004 agents
+//!
+//! Every delegation is recorded with what was paid and whether the result
+//! passed verification (`completed`, `unverified` or `failed`).
+
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use crate::db::Database;
+
+pub const DELEGATION_COMPLETED: &str = "completed";
+pub const DELEGATION_UNVERIFIED: &str = "unverified";
+pub const DELEGATION_FAILED: &str = "failed";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteDelegation {
+    pub id: i64,
+    pub agent_id: i64,
+    pub agent_registry: String,
+    pub agent_name: Option<String>,
+    pub protocol: String,
+    pub endpoint: String,
+    pub task: String,
+    pub budget_usdc: String,
+    pub amount_paid: Option<String>,
+    pub pay_to: Option<String>,
+    pub status: String,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub channel_id: Option<i64>,
+    pub created_at: String,
+}
+
+pub struct NewRemoteDelegation<'a> {
+    pub agent_id: i64,
+    pub agent_registry: &'a str,
+    pub agent_name: Option<&'a str>,
+    pub protocol: &'a str,
+    pub endpoint: &'a str,
+    pub task: &'a str,
+    pub budget_usdc: &'a str,
+    pub amount_paid: Option<&'a str>,
+    pub pay_to: Option<&'a str>,
+    pub status: &'a str,
+    pub result: Option<&'a str>,
+    pub error: Option<&'a str>,
+    pub channel_id: Option<i64>,
+}
+
+impl Database {
+    pub fn insert_remote_delegation(&self, d: &NewRemoteDelegation) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO remote_delegations
+             (agent_id, agent_registry, agent_name, protocol, endpoint, task, budget_usdc,
+              amount_paid, pay_to, status, result, error, channel_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            rusqlite::params![
+                d.agent_id,
+                d.agent_registry,
+                d.agent_name,
+                d.protocol,
+                d.endpoint,
+                d.task,
+                d.budget_usdc,
+                d.amount_paid,
+                d.pay_to,
+                d.status,
+                d.result,
+                d.error,
+                d.channel_id,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Newest first, optionally for one agent
+    pub fn list_remote_delegations(&self, agent_id: Option<i64>, limit: usize) -> SqliteResult<Vec<RemoteDelegation>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, agent_id, agent_registry, agent_name, protocol, endpoint, task, budget_usdc,
+                    amount_paid, pay_to, status, result, error, channel_id, created_at
+             FROM remote_delegations WHERE (?1 IS NULL OR agent_id = ?1)
+             ORDER BY id DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![agent_id, limit as i64], |row| {
+            Ok(RemoteDelegation {
+                id: row.get(0)?,
+                agent_id: row.get(1)?,
+                agent_registry: row.get(2)?,
+                agent_name: row.get(3)?,
+                protocol: row.get(4)?,
+                endpoint: row.get(5)?,
+                task: row.get(6)?,
+                budget_usdc: row.get(7)?,
+                amount_paid: row.get(8)?,
+                pay_to: row.get(9)?,
+                status: row.get(10)?,
+                result: row.get(11)?,
+                error: row.get(12)?,
+                channel_id: row.get(13)?,
+                created_at: row.get(14)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_list_delegations() {
+        let db = Database::new(":memory:").unwrap();
+        let delegation = NewRemoteDelegation {
+            agent_id: 5,
+            agent_registry: "eip155:8453:0xreg",
+            agent_name: Some("Remote"),
+            protocol: "a2a",
+            endpoint: "https://remote.example/a2a",
+            task: "price of ETH",
+            budget_usdc: "0.10",
+            amount_paid: Some("0.01"),
+            pay_to: Some("0xpay"),
+            status: DELEGATION_COMPLETED,
+            result: Some("ETH is 3000"),
+            error: None,
+            channel_id: None,
+        };
+        db.insert_remote_delegation(&delegation).unwrap();
+        db.insert_remote_delegation(&NewRemoteDelegation {
+            agent_id: 6,
+            status: DELEGATION_FAILED,
+            result: None,
+            error: Some("timeout"),
+            ..delegation
+        })
+        .unwrap();
+
+        let all = db.list_remote_delegations(None, 10).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].status, DELEGATION_FAILED);
+        let one = db.list_remote_delegations(Some(5), 10).unwrap();
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].result.as_deref(), Some("ETH is 3000"));
+    }
+}
@@ -0,0 +1,204 @@
+//! EIP-8004 reputation feedback (given to other agents, received by this agent)
+//!
+//! Received rows are signed submissions accepted at `/api/feedback`. Their
+//! `feedback_hash` is the hash of the signed message, so a replayed submission
+//! is recognised. The agent's score counts only each client's latest feedback.
+
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use crate::db::Database;
+
+pub const FEEDBACK_RECEIVED: &str = "received";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReputationFeedback {
+    pub id: i64,
+    pub direction: String,
+    pub agent_id: i64,
+    pub agent_registry: String,
+    pub client_address: String,
+    pub value: i64,
+    pub value_decimals: i64,
+    pub tag1: Option<String>,
+    pub tag2: Option<String>,
+    pub endpoint: Option<String>,
+    pub feedback_uri: Option<String>,
+    pub feedback_hash: Option<String>,
+    pub comment: Option<String>,
+    pub proof_of_payment_tx: Option<String>,
+    pub is_revoked: bool,
+    pub tx_hash: Option<String>,
+    pub created_at: String,
+}
+
+pub struct NewReputationFeedback<'a> {
+    pub direction: &'a str,
+    pub agent_id: i64,
+    pub agent_registry: &'a str,
+    pub client_address: &'a str,
+    pub value: i64,
+    pub tag1: Option<&'a str>,
+    pub tag2: Option<&'a str>,
+    pub endpoint: Option<&'a str>,
+    pub feedback_uri: Option<&'a str>,
+    pub feedback_hash: Option<&'a str>,
+    pub comment: Option<&'a str>,
+    pub proof_of_payment_tx: Option<&'a str>,
+    pub tx_hash: Option<&'a str>,
+}
+
+/// Aggregate of the feedback an agent received
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeedbackStats {
+    pub count: u64,
+    pub average_score: f64,
+    pub last_feedback_at: Option<String>,
+}
+
+const FEEDBACK_COLS: &str = "id, direction, agent_id, agent_registry, client_address, value, value_decimals, \
+     tag1, tag2, endpoint, feedback_uri, feedback_hash, comment, proof_of_payment_tx, is_revoked, tx_hash, created_at";
+
+fn row_to_feedback(row: &rusqlite::Row) -> rusqlite::Result<ReputationFeedback> {
+    Ok(ReputationFeedback {
+        id: row.get(0)?,
+        direction: row.get(1)?,
+        agent_id: row.get(2)?,
+        agent_registry: row.get(3)?,
+        client_address: row.get(4)?,
+        value: row.get(5)?,
+        value_decimals: row.get(6)?,
+        tag1: row.get(7)?,
+        tag2: row.get(8)?,
+        endpoint: row.get(9)?,
+        feedback_uri: row.get(10)?,
+        feedback_hash: row.get(11)?,
+        comment: row.get(12)?,
+        proof_of_payment_tx: row.get(13)?,
+        is_revoked: row.get::<_, i64>(14)? != 0,
+        tx_hash: row.get(15)?,
+        created_at: row.get(16)?,
+    })
+}
+
+impl Database {
+    pub fn insert_reputation_feedback(&self, feedback: &NewReputationFeedback) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO reputation_feedback
+             (direction, agent_id, agent_registry, client_address, value, tag1, tag2, endpoint,
+              feedback_uri, feedback_hash, comment, proof_of_payment_tx, tx_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            rusqlite::params![
+                feedback.direction,
+                feedback.agent_id,
+                feedback.agent_registry,
+                feedback.client_address,
+                feedback.value,
+                feedback.tag1,
+                feedback.tag2,
+                feedback.endpoint,
+                feedback.feedback_uri,
+                feedback.feedback_hash,
+                feedback.comment,
+                feedback.proof_of_payment_tx,
+                feedback.tx_hash,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Whether feedback with this hash was already recorded (replay check)
+    pub fn reputation_feedback_exists(&self, feedback_hash: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM reputation_feedback WHERE feedback_hash = ?1",
+            [feedback_hash],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Newest first, optionally filtered by direction and agent
+    pub fn list_reputation_feedback(
+        &self,
+        direction: Option<&str>,
+        agent_id: Option<i64>,
+        limit: usize,
+    ) -> SqliteResult<Vec<ReputationFeedback>> {
+        let conn = self.conn();
+        let sql = format!(
+            "SELECT {} FROM reputation_feedback
+             WHERE (?1 IS NULL OR direction = ?1) AND (?2 IS NULL OR agent_id = ?2)
+             ORDER BY id DESC LIMIT ?3",
+            FEEDBACK_COLS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params![direction, agent_id, limit as i64], row_to_feedback)?;
+        rows.collect()
+    }
+
+    /// Score from received, non-revoked feedback — one vote per client (their latest)
+    pub fn received_feedback_stats(&self, agent_id: i64, agent_registry: &str) -> SqliteResult<FeedbackStats> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT COUNT(*), AVG(f.value * 1.0), MAX(f.created_at) FROM reputation_feedback f
+             WHERE f.direction = 'received' AND f.agent_id = ?1 AND f.agent_registry = ?2 AND f.is_revoked = 0
+               AND f.id = (SELECT MAX(g.id) FROM reputation_feedback g
+                           WHERE g.direction = 'received' AND g.agent_id = f.agent_id
+                             AND g.agent_registry = f.agent_registry
+                             AND g.client_address = f.client_address AND g.is_revoked = 0)",
+            rusqlite::params![agent_id, agent_registry],
+            |row| {
+                Ok(FeedbackStats {
+                    count: row.get::<_, i64>(0)? as u64,
+                    average_score: row.get::<_, Option<f64>>(1)?.unwrap_or(0.0),
+                    last_feedback_at: row.get(2)?,
+                })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn received<'a>(client: &'a str, value: i64, hash: &'a str) -> NewReputationFeedback<'a> {
+        NewReputationFeedback {
+            direction: FEEDBACK_RECEIVED,
+            agent_id: 7,
+            agent_registry: "eip155:8453:0xreg",
+            client_address: client,
+            value,
+            tag1: None,
+            tag2: None,
+            endpoint: None,
+            feedback_uri: None,
+            feedback_hash: Some(hash),
+            comment: None,
+            proof_of_payment_tx: None,
+            tx_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_stats_count_latest_per_client() {
+        let db = Database::new(":memory:").unwrap();
+        assert_eq!(db.received_feedback_stats(7, "eip155:8453:0xreg").unwrap().count, 0);
+
+        db.insert_reputation_feedback(&received("0xaaa", 100, "h1")).unwrap();
+        db.insert_reputation_feedback(&received("0xaaa", 20, "h2")).unwrap();
+        db.insert_reputation_feedback(&received("0xbbb", 60, "h3")).unwrap();
+
+        let stats = db.received_feedback_stats(7, "eip155:8453:0xreg").unwrap();
+        assert_eq!(stats.count, 2);
+        assert!((stats.average_score - 40.0).abs() < f64::EPSILON);
+        assert!(stats.last_feedback_at.is_some());
+
+        assert!(db.reputation_feedback_exists("h2").unwrap());
+        assert!(!db.reputation_feedback_exists("h4").unwrap());
+        assert_eq!(db.list_reputation_feedback(Some(FEEDBACK_RECEIVED), Some(7), 10).unwrap().len(), 3);
+        assert!(db.list_reputation_feedback(Some("given"), None, 10).unwrap().is_empty());
+    }
+}
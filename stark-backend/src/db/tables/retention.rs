@@ -0,0 +1,770 @@
+//! Retention, soft-delete and forget-identity operations
+//! (retention_policies, deleted_records, forget_audit)
+//!
+//! Soft-deleted rows are snapshotted as JSON into `deleted_records` and removed
+//! from their source tables, so every existing query keeps working unchanged.
+//! Restoring re-inserts the snapshot with the original IDs.
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{Connection, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+
+use super::super::encryption::{decrypt_field, encrypt_message};
+use super::super::Database;
+
+/// Scope for auto-purging compacted session messages
+pub const SCOPE_SESSION_MESSAGES: &str = "session_messages";
+/// Scope for the soft-delete grace period
+pub const SCOPE_TRASH: &str = "trash";
+/// Prefix for per-memory-type scopes, e.g. `memory:daily_log`
+pub const SCOPE_MEMORY_PREFIX: &str = "memory:";
+/// Grace period used when no `trash` policy is configured
+pub const DEFAULT_TRASH_GRACE_DAYS: i64 = 7;
+
+/// Tables a trash payload is allowed to restore into
+const RESTORABLE_TABLES: &[&str] = &["memories", "chat_sessions", "session_messages"];
+
+/// A retention window for one scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub scope: String,
+    pub retention_days: i64,
+    pub enabled: bool,
+    pub updated_at: String,
+}
+
+/// A soft-deleted record awaiting restore or purge
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletedRecord {
+    pub id: i64,
+    pub record_type: String,
+    pub record_id: i64,
+    pub label: Option<String>,
+    pub deleted_at: String,
+    pub purge_after: String,
+}
+
+/// Counts of what a forget-identity request erased
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ForgetSummary {
+    pub identity_id: String,
+    pub memories_deleted: i64,
+    pub embeddings_deleted: i64,
+    pub sessions_deleted: i64,
+    pub messages_deleted: i64,
+    pub links_deleted: i64,
+    pub profiles_deleted: i64,
+    pub trash_purged: i64,
+}
+
+/// A forget_audit row
+#[derive(Debug, Clone, Serialize)]
+pub struct ForgetAuditEntry {
+    pub id: i64,
+    pub requested_by: Option<String>,
+    #[serde(flatten)]
+    pub summary: ForgetSummary,
+    pub created_at: String,
+}
+
+/// Check that a scope is one the retention worker understands.
+pub fn validate_retention_scope(scope: &str) -> Result<(), String> {
+    if scope == SCOPE_SESSION_MESSAGES || scope == SCOPE_TRASH {
+        return Ok(());
+    }
+    match scope.strip_prefix(SCOPE_MEMORY_PREFIX) {
+        Some(memory_type) if !memory_type.trim().is_empty() => Ok(()),
+        _ => Err(format!(
+            "Unknown scope '{}'. Expected '{}', '{}' or '{}<memory_type>'",
+            scope, SCOPE_SESSION_MESSAGES, SCOPE_TRASH, SCOPE_MEMORY_PREFIX
+        )),
+    }
+}
+
+/// Snapshot every row of `table` where `column = id` as a JSON object keyed by column name.
+fn snapshot_rows(conn: &Connection, table: &str, column: &str, id: i64) -> SqliteResult<Vec<Map<String, JsonValue>>> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {} WHERE {} = ?1", table, column))?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let rows = stmt
+        .query_map([id], |row| {
+            let mut obj = Map::new();
+            for (i, name) in columns.iter().enumerate() {
+                let value = match row.get_ref(i)? {
+                    ValueRef::Null => JsonValue::Null,
+                    ValueRef::Integer(n) => JsonValue::from(n),
+                    ValueRef::Real(f) => JsonValue::from(f),
+                    ValueRef::Text(t) => JsonValue::from(String::from_utf8_lossy(t).into_owned()),
+                    ValueRef::Blob(b) => JsonValue::from(b.to_vec()),
+                };
+                obj.insert(name.clone(), value);
+            }
+            Ok(obj)
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(rows)
+}
+
+/// Re-insert a snapshotted row into `table`.
+fn insert_snapshot_row(conn: &Connection, table: &str, row: &Map<String, JsonValue>) -> SqliteResult<()> {
+    let columns: Vec<&String> = row.keys().collect();
+    let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+    let values: Vec<Value> = row
+        .values()
+        .map(|v| match v {
+            JsonValue::Null => Value::Null,
+            JsonValue::Bool(b) => Value::Integer(*b as i64),
+            JsonValue::Number(n) => match n.as_i64() {
+                Some(i) => Value::Integer(i),
+                None => Value::Real(n.as_f64().unwrap_or(0.0)),
+            },
+            JsonValue::String(s) => Value::Text(s.clone()),
+            JsonValue::Array(bytes) => {
+                Value::Blob(bytes.iter().filter_map(|b| b.as_u64().map(|b| b as u8)).collect())
+            }
+            JsonValue::Object(_) => Value::Text(v.to_string()),
+        })
+        .collect();
+    conn.execute(
+        &format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table,
+            columns.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", "),
+            placeholders.join(", ")
+        ),
+        rusqlite::params_from_iter(values),
+    )?;
+    Ok(())
+}
+
+/// Delete a memory and the rows that reference it. Returns (memories, embeddings) deleted.
+fn hard_delete_memory(conn: &Connection, memory_id: i64) -> SqliteResult<(usize, usize)> {
+    let embeddings = conn.execute("DELETE FROM memory_embeddings WHERE memory_id = ?1", [memory_id])?;
+    conn.execute(
+        "DELETE FROM memory_associations WHERE source_memory_id = ?1 OR target_memory_id = ?1",
+        [memory_id],
+    )?;
+    let memories = conn.execute("DELETE FROM memories WHERE id = ?1", [memory_id])?;
+    Ok((memories, embeddings))
+}
+
+/// Delete a session, its messages, and the agent state that references it.
+fn hard_delete_session(conn: &Connection, session_id: i64) -> SqliteResult<(usize, usize)> {
+    conn.execute("DELETE FROM agent_contexts WHERE session_id = ?1", [session_id])?;
+    conn.execute(
+        "DELETE FROM sub_agents WHERE parent_session_id = ?1 OR session_id = ?1",
+        [session_id],
+    )?;
+    // Explicit rather than relying on the FK cascade, so the count is accurate
+    let messages = conn.execute("DELETE FROM session_messages WHERE session_id = ?1", [session_id])?;
+    let sessions = conn.execute("DELETE FROM chat_sessions WHERE id = ?1", [session_id])?;
+    Ok((sessions, messages))
+}
+
+impl Database {
+    // =====================================================
+    // Retention policies
+    // =====================================================
+
+    /// List all retention policies
+    pub fn list_retention_policies(&self) -> SqliteResult<Vec<RetentionPolicy>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT scope, retention_days, enabled, updated_at FROM retention_policies ORDER BY scope ASC",
+        )?;
+        let policies = stmt
+            .query_map([], |row| Self::row_to_retention_policy(row))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(policies)
+    }
+
+    /// Get the policy for a scope, if one is configured
+    pub fn get_retention_policy(&self, scope: &str) -> SqliteResult<Option<RetentionPolicy>> {
+        let conn = self.conn();
+        let policy = conn
+            .query_row(
+                "SELECT scope, retention_days, enabled, updated_at FROM retention_policies WHERE scope = ?1",
+                [scope],
+                |row| Self::row_to_retention_policy(row),
+            )
+            .ok();
+        Ok(policy)
+    }
+
+    /// Create or replace the policy for a scope
+    pub fn set_retention_policy(&self, scope: &str, retention_days: i64, enabled: bool) -> SqliteResult<RetentionPolicy> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO retention_policies (scope, retention_days, enabled, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(scope) DO UPDATE SET retention_days = ?2, enabled = ?3, updated_at = ?4",
+            rusqlite::params![scope, retention_days, enabled as i32, now],
+        )?;
+        Ok(RetentionPolicy {
+            scope: scope.to_string(),
+            retention_days,
+            enabled,
+            updated_at: now,
+        })
+    }
+
+    /// Remove the policy for a scope
+    pub fn delete_retention_policy(&self, scope: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM retention_policies WHERE scope = ?1", [scope])?;
+        Ok(rows > 0)
+    }
+
+    /// Soft-delete grace period in days (from the `trash` policy, else the default)
+    pub fn trash_grace_days(&self) -> i64 {
+        match self.get_retention_policy(SCOPE_TRASH) {
+            Ok(Some(p)) if p.enabled => p.retention_days.max(0),
+            _ => DEFAULT_TRASH_GRACE_DAYS,
+        }
+    }
+
+    fn row_to_retention_policy(row: &rusqlite::Row) -> rusqlite::Result<RetentionPolicy> {
+        Ok(RetentionPolicy {
+            scope: row.get(0)?,
+            retention_days: row.get(1)?,
+            enabled: row.get::<_, i32>(2)? != 0,
+            updated_at: row.get(3)?,
+        })
+    }
+
+    // =====================================================
+    // Retention purges
+    // =====================================================
+
+    /// Delete session messages older than the cutoff, but only those already
+    /// folded into a compaction summary (created before the session's last compaction).
+    pub fn purge_compacted_session_messages(&self, older_than: DateTime<Utc>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        let deleted = conn.execute(
+            "DELETE FROM session_messages
+             WHERE created_at < ?1
+               AND EXISTS (
+                   SELECT 1 FROM chat_sessions cs
+                   WHERE cs.id = session_messages.session_id
+                     AND cs.compaction_id IS NOT NULL
+                     AND cs.last_compaction_at IS NOT NULL
+                     AND session_messages.created_at < cs.last_compaction_at
+               )",
+            [older_than.to_rfc3339()],
+        )?;
+        Ok(deleted)
+    }
+
+    /// Hard-delete memories of one type created before the cutoff (with their embeddings and associations).
+    /// Compared via `datetime()` since memories mix SQLite and RFC 3339 timestamps.
+    pub fn purge_memories_older_than(&self, memory_type: &str, older_than: DateTime<Utc>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        let ids: Vec<i64> = {
+            let mut stmt = conn.prepare("SELECT id FROM memories WHERE memory_type = ?1 AND datetime(created_at) < datetime(?2)")?;
+            stmt.query_map(rusqlite::params![memory_type, older_than.to_rfc3339()], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect()
+        };
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        let mut deleted = 0;
+        for id in ids {
+            deleted += hard_delete_memory(&tx, id)?.0;
+        }
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    // =====================================================
+    // Soft delete (trash)
+    // =====================================================
+
+    /// Move a memory to the trash. Returns the trash record ID, or None if the memory doesn't exist.
+    pub fn soft_delete_memory(&self, memory_id: i64) -> SqliteResult<Option<i64>> {
+        let grace_days = self.trash_grace_days();
+        let conn = self.conn();
+        let rows = snapshot_rows(&conn, "memories", "id", memory_id)?;
+        let Some(memory) = rows.first() else {
+            return Ok(None);
+        };
+        // Never copy encrypted content into the plaintext label
+        let label = memory
+            .get("content")
+            .and_then(|v| v.as_str())
+            .filter(|s| !super::super::encryption::is_encrypted(s))
+            .map(|s| s.chars().take(80).collect::<String>());
+        let subject_ids: Vec<String> = memory
+            .get("identity_id")
+            .and_then(|v| v.as_str())
+            .map(|s| vec![s.to_string()])
+            .unwrap_or_default();
+        let payload = serde_json::json!([{ "table": "memories", "rows": rows }]);
+
+        let tx = conn.unchecked_transaction()?;
+        let trash_id =
+            Self::insert_deleted_record(&tx, "memory", memory_id, label.as_deref(), &subject_ids, &payload, grace_days)?;
+        hard_delete_memory(&tx, memory_id)?;
+        tx.commit()?;
+        Ok(Some(trash_id))
+    }
+
+    /// Move a session and its messages to the trash. Returns the trash record ID, or None if not found.
+    /// Agent contexts and sub-agent rows are runtime state and are not kept.
+    pub fn soft_delete_chat_session(&self, session_id: i64) -> SqliteResult<Option<i64>> {
+        let grace_days = self.trash_grace_days();
+        let conn = self.conn();
+        let sessions = snapshot_rows(&conn, "chat_sessions", "id", session_id)?;
+        let Some(session) = sessions.first() else {
+            return Ok(None);
+        };
+        let messages = snapshot_rows(&conn, "session_messages", "session_id", session_id)?;
+
+        let label = session.get("session_key").and_then(|v| v.as_str()).map(String::from);
+        let mut subject_ids: Vec<String> = messages
+            .iter()
+            .filter_map(|m| m.get("user_id").and_then(|v| v.as_str()).map(String::from))
+            .collect();
+        subject_ids.sort();
+        subject_ids.dedup();
+        let payload = serde_json::json!([
+            { "table": "chat_sessions", "rows": sessions },
+            { "table": "session_messages", "rows": messages },
+        ]);
+
+        let tx = conn.unchecked_transaction()?;
+        let trash_id =
+            Self::insert_deleted_record(&tx, "session", session_id, label.as_deref(), &subject_ids, &payload, grace_days)?;
+        hard_delete_session(&tx, session_id)?;
+        tx.commit()?;
+        Ok(Some(trash_id))
+    }
+
+    fn insert_deleted_record(
+        conn: &Connection,
+        record_type: &str,
+        record_id: i64,
+        label: Option<&str>,
+        subject_ids: &[String],
+        payload: &JsonValue,
+        grace_days: i64,
+    ) -> SqliteResult<i64> {
+        let now = Utc::now();
+        let purge_after = now + Duration::days(grace_days);
+        conn.execute(
+            "INSERT INTO deleted_records (record_type, record_id, label, subject_ids, payload, deleted_at, purge_after)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                record_type,
+                record_id,
+                label,
+                serde_json::to_string(subject_ids).unwrap_or_else(|_| "[]".to_string()),
+                encrypt_message(&payload.to_string())?,
+                now.to_rfc3339(),
+                purge_after.to_rfc3339(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// List trashed records, newest first (optionally of one type)
+    pub fn list_deleted_records(&self, record_type: Option<&str>, limit: usize) -> SqliteResult<Vec<DeletedRecord>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, record_type, record_id, label, deleted_at, purge_after
+             FROM deleted_records WHERE (?1 IS NULL OR record_type = ?1)
+             ORDER BY id DESC LIMIT ?2",
+        )?;
+        let records = stmt
+            .query_map(rusqlite::params![record_type, limit as i64], |row| {
+                Ok(DeletedRecord {
+                    id: row.get(0)?,
+                    record_type: row.get(1)?,
+                    record_id: row.get(2)?,
+                    label: row.get(3)?,
+                    deleted_at: row.get(4)?,
+                    purge_after: row.get(5)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(records)
+    }
+
+    /// Restore a trashed record into its original tables.
+    /// Returns the restored record type, or None if the trash entry doesn't exist.
+    pub fn restore_deleted_record(&self, trash_id: i64) -> SqliteResult<Option<String>> {
+        let conn = self.conn();
+        let found = conn
+            .query_row(
+                "SELECT record_type, payload FROM deleted_records WHERE id = ?1",
+                [trash_id],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .ok();
+        let Some((record_type, payload)) = found else {
+            return Ok(None);
+        };
+        let groups: Vec<JsonValue> = serde_json::from_str(&decrypt_field(payload)).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+
+        let tx = conn.unchecked_transaction()?;
+        for group in &groups {
+            let table = group.get("table").and_then(|t| t.as_str()).unwrap_or_default();
+            if !RESTORABLE_TABLES.contains(&table) {
+                return Err(rusqlite::Error::InvalidParameterName(format!(
+                    "Trash payload references unsupported table '{}'",
+                    table
+                )));
+            }
+            for row in group.get("rows").and_then(|r| r.as_array()).into_iter().flatten() {
+                if let Some(obj) = row.as_object() {
+                    insert_snapshot_row(&tx, table, obj)?;
+                }
+            }
+        }
+        tx.execute("DELETE FROM deleted_records WHERE id = ?1", [trash_id])?;
+        tx.commit()?;
+        Ok(Some(record_type))
+    }
+
+    /// Permanently delete one trashed record
+    pub fn purge_deleted_record(&self, trash_id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM deleted_records WHERE id = ?1", [trash_id])?;
+        Ok(rows > 0)
+    }
+
+    /// Permanently delete trashed records whose grace period has passed
+    pub fn purge_expired_deleted_records(&self, now: DateTime<Utc>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "DELETE FROM deleted_records WHERE purge_after < ?1",
+            [now.to_rfc3339()],
+        )?;
+        Ok(rows)
+    }
+
+    // =====================================================
+    // Forget identity
+    // =====================================================
+
+    /// Erase everything tied to an identity: its memories (and embeddings),
+    /// DM sessions it took part in, its messages in shared sessions, matching
+    /// trash entries, its platform links and its structured profile. Records an
+    /// audit row of the counts.
+    pub fn forget_identity(&self, identity_id: &str, requested_by: Option<&str>) -> SqliteResult<ForgetSummary> {
+        let conn = self.conn();
+        let mut summary = ForgetSummary {
+            identity_id: identity_id.to_string(),
+            ..Default::default()
+        };
+
+        let platform_user_ids: Vec<String> = {
+            let mut stmt = conn.prepare("SELECT platform_user_id FROM identity_links WHERE identity_id = ?1")?;
+            stmt.query_map([identity_id], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect()
+        };
+
+        let tx = conn.unchecked_transaction()?;
+
+        // Memories and their embeddings
+        let memory_ids: Vec<i64> = {
+            let mut stmt = tx.prepare("SELECT id FROM memories WHERE identity_id = ?1")?;
+            stmt.query_map([identity_id], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect()
+        };
+        for id in memory_ids {
+            let (memories, embeddings) = hard_delete_memory(&tx, id)?;
+            summary.memories_deleted += memories as i64;
+            summary.embeddings_deleted += embeddings as i64;
+        }
+
+        // Sessions: DMs go entirely, shared sessions lose only this identity's messages
+        for user_id in &platform_user_ids {
+            let sessions: Vec<(i64, String)> = {
+                let mut stmt = tx.prepare(
+                    "SELECT DISTINCT cs.id, cs.scope FROM chat_sessions cs
+                     INNER JOIN session_messages sm ON sm.session_id = cs.id
+                     WHERE sm.user_id = ?1",
+                )?;
+                stmt.query_map([user_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .filter_map(|r| r.ok())
+                    .collect()
+            };
+            for (session_id, scope) in sessions {
+                if scope == "dm" {
+                    let (sessions, messages) = hard_delete_session(&tx, session_id)?;
+                    summary.sessions_deleted += sessions as i64;
+                    summary.messages_deleted += messages as i64;
+                } else {
+                    summary.messages_deleted += tx.execute(
+                        "DELETE FROM session_messages WHERE session_id = ?1 AND user_id = ?2",
+                        rusqlite::params![session_id, user_id],
+                    )? as i64;
+                }
+            }
+        }
+
+        // Trash entries that still hold this identity's data
+        let trashed: Vec<(i64, String)> = {
+            let mut stmt = tx.prepare("SELECT id, subject_ids FROM deleted_records")?;
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .filter_map(|r| r.ok())
+                .collect()
+        };
+        for (trash_id, subject_json) in trashed {
+            let subjects: Vec<String> = serde_json::from_str(&subject_json).unwrap_or_default();
+            if subjects.iter().any(|s| s == identity_id || platform_user_ids.contains(s)) {
+                summary.trash_purged += tx.execute("DELETE FROM deleted_records WHERE id = ?1", [trash_id])? as i64;
+            }
+        }
+
+        summary.links_deleted = tx.execute("DELETE FROM identity_links WHERE identity_id = ?1", [identity_id])? as i64;
+        summary.profiles_deleted = super::identity_profiles::delete_profile(&tx, identity_id)? as i64;
+
+        tx.execute(
+            "INSERT INTO forget_audit (identity_id, requested_by, memories_deleted, embeddings_deleted,
+                    sessions_deleted, messages_deleted, links_deleted, profiles_deleted, trash_purged, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                identity_id,
+                requested_by,
+                summary.memories_deleted,
+                summary.embeddings_deleted,
+                summary.sessions_deleted,
+                summary.messages_deleted,
+                summary.links_deleted,
+                summary.profiles_deleted,
+                summary.trash_purged,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        tx.commit()?;
+        Ok(summary)
+    }
+
+    /// List forget-identity audit entries, newest first
+    pub fn list_forget_audit(&self, limit: usize) -> SqliteResult<Vec<ForgetAuditEntry>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, identity_id, requested_by, memories_deleted, embeddings_deleted, sessions_deleted,
+                    messages_deleted, links_deleted, trash_purged, created_at, profiles_deleted
+             FROM forget_audit ORDER BY id DESC LIMIT ?1",
+        )?;
+        let entries = stmt
+            .query_map([limit as i64], |row| {
+                Ok(ForgetAuditEntry {
+                    id: row.get(0)?,
+                    requested_by: row.get(2)?,
+                    summary: ForgetSummary {
+                        identity_id: row.get(1)?,
+                        memories_deleted: row.get(3)?,
+                        embeddings_deleted: row.get(4)?,
+                        sessions_deleted: row.get(5)?,
+                        messages_deleted: row.get(6)?,
+                        links_deleted: row.get(7)?,
+                        profiles_deleted: row.get(10)?,
+                        trash_purged: row.get(8)?,
+                    },
+                    created_at: row.get(9)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Database {
+        Database::new(":memory:").expect("in-memory db")
+    }
+
+    fn insert_session(db: &Database, key: &str, scope: &str) -> i64 {
+        let conn = db.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO chat_sessions (session_key, scope, channel_type, channel_id, platform_chat_id,
+                    created_at, updated_at, last_activity_at)
+             VALUES (?1, ?2, 'discord', 1, 'chat', ?3, ?3, ?3)",
+            rusqlite::params![key, scope, now],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn insert_message(db: &Database, session_id: i64, user_id: &str, created_at: &str) {
+        db.conn()
+            .execute(
+                "INSERT INTO session_messages (session_id, role, content, user_id, created_at)
+                 VALUES (?1, 'user', 'hello', ?2, ?3)",
+                rusqlite::params![session_id, user_id, created_at],
+            )
+            .unwrap();
+    }
+
+    fn count(db: &Database, sql: &str) -> i64 {
+        db.conn().query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_validate_scope() {
+        assert!(validate_retention_scope("session_messages").is_ok());
+        assert!(validate_retention_scope("trash").is_ok());
+        assert!(validate_retention_scope("memory:daily_log").is_ok());
+        assert!(validate_retention_scope("memory:").is_err());
+        assert!(validate_retention_scope("everything").is_err());
+    }
+
+    #[test]
+    fn test_policy_upsert_and_grace() {
+        let db = setup_db();
+        assert_eq!(db.trash_grace_days(), DEFAULT_TRASH_GRACE_DAYS);
+        db.set_retention_policy("trash", 3, true).unwrap();
+        db.set_retention_policy("trash", 14, true).unwrap();
+        assert_eq!(db.list_retention_policies().unwrap().len(), 1);
+        assert_eq!(db.trash_grace_days(), 14);
+        assert!(db.delete_retention_policy("trash").unwrap());
+        assert!(db.get_retention_policy("trash").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_soft_delete_and_restore_memory() {
+        let db = setup_db();
+        let id = db
+            .insert_memory("fact", "likes tea", None, None, 5, Some("id-1"), None, None, None, None, None, None)
+            .unwrap();
+
+        let trash_id = db.soft_delete_memory(id).unwrap().unwrap();
+        assert!(db.get_memory(id).unwrap().is_none());
+        assert_eq!(db.list_deleted_records(Some("memory"), 10).unwrap().len(), 1);
+
+        assert_eq!(db.restore_deleted_record(trash_id).unwrap().as_deref(), Some("memory"));
+        let restored = db.get_memory(id).unwrap().unwrap();
+        assert_eq!(restored.content, "likes tea");
+        assert_eq!(restored.identity_id.as_deref(), Some("id-1"));
+        assert!(db.list_deleted_records(None, 10).unwrap().is_empty());
+        assert!(db.soft_delete_memory(9999).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_soft_delete_and_restore_session() {
+        let db = setup_db();
+        let session_id = insert_session(&db, "s1", "group");
+        insert_message(&db, session_id, "u1", &Utc::now().to_rfc3339());
+        insert_message(&db, session_id, "u2", &Utc::now().to_rfc3339());
+
+        let trash_id = db.soft_delete_chat_session(session_id).unwrap().unwrap();
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM session_messages"), 0);
+
+        db.restore_deleted_record(trash_id).unwrap();
+        assert!(db.get_chat_session(session_id).unwrap().is_some());
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM session_messages"), 2);
+    }
+
+    #[test]
+    fn test_purge_expired_trash() {
+        let db = setup_db();
+        let id = db
+            .insert_memory("fact", "x", None, None, 5, None, None, None, None, None, None, None)
+            .unwrap();
+        db.soft_delete_memory(id).unwrap();
+        assert_eq!(db.purge_expired_deleted_records(Utc::now()).unwrap(), 0);
+        let later = Utc::now() + Duration::days(DEFAULT_TRASH_GRACE_DAYS + 1);
+        assert_eq!(db.purge_expired_deleted_records(later).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_purge_only_compacted_messages() {
+        let db = setup_db();
+        let compacted = insert_session(&db, "compacted", "dm");
+        let uncompacted = insert_session(&db, "raw", "dm");
+        let old = (Utc::now() - Duration::days(40)).to_rfc3339();
+        insert_message(&db, compacted, "u1", &old);
+        insert_message(&db, compacted, "u1", &Utc::now().to_rfc3339());
+        insert_message(&db, uncompacted, "u1", &old);
+        db.conn()
+            .execute(
+                "UPDATE chat_sessions SET compaction_id = 1, last_compaction_at = ?1 WHERE id = ?2",
+                rusqlite::params![(Utc::now() - Duration::days(1)).to_rfc3339(), compacted],
+            )
+            .unwrap();
+
+        let deleted = db
+            .purge_compacted_session_messages(Utc::now() - Duration::days(30))
+            .unwrap();
+        assert_eq!(deleted, 1);
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM session_messages"), 2);
+    }
+
+    #[test]
+    fn test_purge_memories_by_type() {
+        let db = setup_db();
+        db.insert_memory("daily_log", "old log", None, None, 5, None, None, None, None, None, None, None)
+            .unwrap();
+        db.insert_memory("fact", "kept", None, None, 5, None, None, None, None, None, None, None)
+            .unwrap();
+        let future = Utc::now() + Duration::days(1);
+        assert_eq!(db.purge_memories_older_than("daily_log", future).unwrap(), 1);
+        assert_eq!(db.count_memories().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_forget_identity() {
+        let db = setup_db();
+        db.link_identity("id-1", "discord", "u1", None).unwrap();
+        db.insert_memory("fact", "secret", None, None, 5, Some("id-1"), None, None, None, None, None, None)
+            .unwrap();
+        let kept = db
+            .insert_memory("fact", "other", None, None, 5, Some("id-2"), None, None, None, None, None, None)
+            .unwrap();
+        let trashed = db
+            .insert_memory("fact", "trashed", None, None, 5, Some("id-1"), None, None, None, None, None, None)
+            .unwrap();
+        db.soft_delete_memory(trashed).unwrap();
+        let profile = crate::db::tables::identity_profiles::IdentityProfile {
+            identity_id: "id-1".to_string(),
+            timezone: Some("Europe/Berlin".to_string()),
+            ..Default::default()
+        };
+        db.save_identity_profile(&profile).unwrap();
+
+        let dm = insert_session(&db, "dm", "dm");
+        insert_message(&db, dm, "u1", &Utc::now().to_rfc3339());
+        let group = insert_session(&db, "group", "group");
+        insert_message(&db, group, "u1", &Utc::now().to_rfc3339());
+        insert_message(&db, group, "u2", &Utc::now().to_rfc3339());
+
+        let summary = db.forget_identity("id-1", Some("admin")).unwrap();
+        assert_eq!(summary.memories_deleted, 1);
+        assert_eq!(summary.sessions_deleted, 1);
+        assert_eq!(summary.messages_deleted, 2);
+        assert_eq!(summary.links_deleted, 1);
+        assert_eq!(summary.profiles_deleted, 1);
+        assert_eq!(summary.trash_purged, 1);
+        assert!(db.get_identity_profile("id-1").unwrap().is_none());
+
+        assert!(db.get_memory(kept).unwrap().is_some());
+        assert!(db.get_chat_session(dm).unwrap().is_none());
+        assert!(db.get_chat_session(group).unwrap().is_some());
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM session_messages"), 1);
+
+        let audit = db.list_forget_audit(10).unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].summary.identity_id, "id-1");
+        assert_eq!(audit[0].requested_by.as_deref(), Some("admin"));
+        assert_eq!(audit[0].summary.profiles_deleted, 1);
+    }
+}
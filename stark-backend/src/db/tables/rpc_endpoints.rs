@@ -0,0 +1,94 @@
+//! User-added RPC endpoints (rpc_endpoints)
+//!
+//! Extra RPC URLs per network — public mirrors, paid plans, a private node —
+//! that join the RPC pool alongside the built-in Alchemy, public and DeFi
+//! Relay endpoints. Lower `priority` is preferred.
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RpcEndpoint {
+    pub id: i64,
+    pub network: String,
+    /// May embed an API key; never returned unmasked by the API
+    #[serde(skip_serializing)]
+    pub url: String,
+    pub label: String,
+    pub priority: i64,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const COLUMNS: &str = "id, network, url, label, priority, enabled, created_at, updated_at";
+
+fn row_to_endpoint(row: &rusqlite::Row) -> rusqlite::Result<RpcEndpoint> {
+    Ok(RpcEndpoint {
+        id: row.get(0)?,
+        network: row.get(1)?,
+        url: row.get(2)?,
+        label: row.get(3)?,
+        priority: row.get(4)?,
+        enabled: row.get::<_, i64>(5)? != 0,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+impl Database {
+    pub fn add_rpc_endpoint(&self, network: &str, url: &str, label: &str, priority: i64) -> SqliteResult<RpcEndpoint> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO rpc_endpoints (network, url, label, priority, enabled, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, 1, ?5, ?5)",
+            rusqlite::params![network, url, label, priority, now],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.query_row(&format!("SELECT {} FROM rpc_endpoints WHERE id = ?1", COLUMNS), [id], row_to_endpoint)
+    }
+
+    pub fn list_rpc_endpoints(&self) -> SqliteResult<Vec<RpcEndpoint>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM rpc_endpoints ORDER BY network, priority, id",
+            COLUMNS
+        ))?;
+        let rows = stmt.query_map([], row_to_endpoint)?;
+        rows.collect()
+    }
+
+    /// Change an endpoint's label, priority or enabled flag; None leaves a field as is
+    pub fn update_rpc_endpoint(
+        &self,
+        id: i64,
+        label: Option<&str>,
+        priority: Option<i64>,
+        enabled: Option<bool>,
+    ) -> SqliteResult<Option<RpcEndpoint>> {
+        let conn = self.conn();
+        let updated = conn.execute(
+            "UPDATE rpc_endpoints SET
+                label = COALESCE(?2, label),
+                priority = COALESCE(?3, priority),
+                enabled = COALESCE(?4, enabled),
+                updated_at = ?5
+             WHERE id = ?1",
+            rusqlite::params![id, label, priority, enabled.map(|e| e as i64), Utc::now().to_rfc3339()],
+        )?;
+        if updated == 0 {
+            return Ok(None);
+        }
+        conn.query_row(&format!("SELECT {} FROM rpc_endpoints WHERE id = ?1", COLUMNS), [id], row_to_endpoint)
+            .map(Some)
+    }
+
+    pub fn delete_rpc_endpoint(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        Ok(conn.execute("DELETE FROM rpc_endpoints WHERE id = ?1", [id])? > 0)
+    }
+}
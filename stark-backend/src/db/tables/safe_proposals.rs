@@ -0,0 +1,203 @@
+//! Safe proposal database operations (safe_proposals)
+//!
+//! One row per transaction the bot proposed to a Safe. The status worker keeps
+//! the confirmation count and signer list in sync with the Safe transaction
+//! service until the proposal is executed, fails, or is replaced by another
+//! transaction with the same nonce.
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+pub const SAFE_PROPOSAL_PENDING: &str = "pending";
+pub const SAFE_PROPOSAL_EXECUTED: &str = "executed";
+pub const SAFE_PROPOSAL_FAILED: &str = "failed";
+pub const SAFE_PROPOSAL_REPLACED: &str = "replaced";
+
+/// A transaction proposed to a Safe
+#[derive(Debug, Clone, Serialize)]
+pub struct SafeProposal {
+    pub id: i64,
+    pub safe_tx_hash: String,
+    pub safe_address: String,
+    pub network: String,
+    pub to_address: String,
+    pub value_wei: String,
+    pub data: String,
+    pub nonce: i64,
+    pub description: String,
+    /// Channel and chat to notify as co-signers confirm
+    pub channel_id: Option<i64>,
+    pub chat_id: Option<String>,
+    pub confirmations: i64,
+    pub threshold: i64,
+    /// Owner addresses that have signed, in signing order
+    pub confirmed_by: Vec<String>,
+    pub status: String,
+    pub tx_hash: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+const SAFE_PROPOSAL_COLUMNS: &str = "id, safe_tx_hash, safe_address, network, to_address, value_wei, data, nonce, description, \
+                                     channel_id, chat_id, confirmations, threshold, confirmed_by, status, tx_hash, created_at, updated_at";
+
+fn row_to_safe_proposal(row: &rusqlite::Row) -> rusqlite::Result<SafeProposal> {
+    let confirmed_by: String = row.get(13)?;
+    Ok(SafeProposal {
+        id: row.get(0)?,
+        safe_tx_hash: row.get(1)?,
+        safe_address: row.get(2)?,
+        network: row.get(3)?,
+        to_address: row.get(4)?,
+        value_wei: row.get(5)?,
+        data: row.get(6)?,
+        nonce: row.get(7)?,
+        description: row.get(8)?,
+        channel_id: row.get(9)?,
+        chat_id: row.get(10)?,
+        confirmations: row.get(11)?,
+        threshold: row.get(12)?,
+        confirmed_by: serde_json::from_str(&confirmed_by).unwrap_or_default(),
+        status: row.get(14)?,
+        tx_hash: row.get(15)?,
+        created_at: row.get(16)?,
+        updated_at: row.get(17)?,
+    })
+}
+
+impl Database {
+    /// Record a proposal the bot just submitted (and signed) to the transaction service
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_safe_proposal(
+        &self,
+        safe_tx_hash: &str,
+        safe_address: &str,
+        network: &str,
+        to_address: &str,
+        value_wei: &str,
+        data: &str,
+        nonce: i64,
+        description: &str,
+        channel_id: Option<i64>,
+        chat_id: Option<&str>,
+        threshold: i64,
+        proposer: &str,
+    ) -> SqliteResult<SafeProposal> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let confirmed_by = serde_json::to_string(&[proposer]).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            "INSERT INTO safe_proposals (safe_tx_hash, safe_address, network, to_address, value_wei, data, nonce, description,
+                                         channel_id, chat_id, confirmations, threshold, confirmed_by, status, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 1, ?11, ?12, ?13, ?14, ?14)",
+            rusqlite::params![
+                safe_tx_hash.to_lowercase(),
+                safe_address,
+                network,
+                to_address,
+                value_wei,
+                data,
+                nonce,
+                description,
+                channel_id,
+                chat_id,
+                threshold,
+                confirmed_by,
+                SAFE_PROPOSAL_PENDING,
+                now
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.query_row(
+            &format!("SELECT {} FROM safe_proposals WHERE id = ?1", SAFE_PROPOSAL_COLUMNS),
+            [id],
+            row_to_safe_proposal,
+        )
+    }
+
+    /// Get a proposal by its Safe transaction hash
+    pub fn get_safe_proposal(&self, safe_tx_hash: &str) -> SqliteResult<Option<SafeProposal>> {
+        let conn = self.conn();
+        match conn.query_row(
+            &format!("SELECT {} FROM safe_proposals WHERE safe_tx_hash = ?1", SAFE_PROPOSAL_COLUMNS),
+            [safe_tx_hash.to_lowercase()],
+            row_to_safe_proposal,
+        ) {
+            Ok(proposal) => Ok(Some(proposal)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Record the current signer set of a pending proposal
+    pub fn update_safe_proposal_confirmations(
+        &self,
+        safe_tx_hash: &str,
+        confirmed_by: &[String],
+        threshold: i64,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE safe_proposals SET confirmations = ?1, confirmed_by = ?2, threshold = ?3, updated_at = ?4 WHERE safe_tx_hash = ?5",
+            rusqlite::params![
+                confirmed_by.len() as i64,
+                serde_json::to_string(confirmed_by).unwrap_or_else(|_| "[]".to_string()),
+                threshold,
+                Utc::now().to_rfc3339(),
+                safe_tx_hash
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Move a pending proposal to a final status
+    pub fn finish_safe_proposal(&self, safe_tx_hash: &str, status: &str, tx_hash: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE safe_proposals SET status = ?1, tx_hash = ?2, updated_at = ?3 WHERE safe_tx_hash = ?4 AND status = ?5",
+            rusqlite::params![status, tx_hash, Utc::now().to_rfc3339(), safe_tx_hash, SAFE_PROPOSAL_PENDING],
+        )?;
+        Ok(())
+    }
+
+    /// List proposals, newest first
+    pub fn list_safe_proposals(&self, status: Option<&str>, limit: usize) -> SqliteResult<Vec<SafeProposal>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM safe_proposals WHERE ?1 IS NULL OR status = ?1 ORDER BY id DESC LIMIT ?2",
+            SAFE_PROPOSAL_COLUMNS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![status, limit as i64], row_to_safe_proposal)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proposal_tracks_signers_until_finished() {
+        let db = Database::new(":memory:").unwrap();
+        let hash = "0xabc";
+        db.create_safe_proposal(hash, "0xsafe", "base", "0xto", "0", "0x", 4, "Pay invoice", Some(1), Some("42"), 2, "0xbot")
+            .unwrap();
+
+        let owners = vec!["0xbot".to_string(), "0xalice".to_string()];
+        db.update_safe_proposal_confirmations(hash, &owners, 2).unwrap();
+        let proposal = db.get_safe_proposal(hash).unwrap().unwrap();
+        assert_eq!(proposal.confirmations, 2);
+        assert_eq!(proposal.confirmed_by, owners);
+
+        db.finish_safe_proposal(hash, SAFE_PROPOSAL_EXECUTED, Some("0xdead")).unwrap();
+        // A final status is never overwritten
+        db.finish_safe_proposal(hash, SAFE_PROPOSAL_REPLACED, None).unwrap();
+        let proposal = db.get_safe_proposal(hash).unwrap().unwrap();
+        assert_eq!(proposal.status, SAFE_PROPOSAL_EXECUTED);
+        assert_eq!(proposal.tx_hash.as_deref(), Some("0xdead"));
+        assert!(db.list_safe_proposals(Some(SAFE_PROPOSAL_PENDING), 10).unwrap().is_empty());
+    }
+}
@@ -75,6 +75,21 @@ impl Database {
         rows.collect()
     }
 
+    /// List enabled skills edited since their embedding was generated
+    pub fn list_skills_with_stale_embeddings(&self, limit: i32) -> Result<Vec<i64>, rusqlite::Error> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT s.id FROM skills s
+             JOIN skill_embeddings se ON s.id = se.skill_id
+             WHERE s.enabled = 1
+               AND datetime(s.updated_at) > datetime(COALESCE(se.updated_at, se.created_at))
+             ORDER BY s.id
+             LIMIT ?1"
+        )?;
+        let rows = stmt.query_map(rusqlite::params![limit], |row| row.get(0))?;
+        rows.collect()
+    }
+
     /// Count total skill embeddings
     pub fn count_skill_embeddings(&self) -> Result<i64, rusqlite::Error> {
         let conn = self.conn();
@@ -0,0 +1,45 @@
+//! Finance permission grants per skill (skill_permissions)
+//!
+//! One row per skill and capability the user has decided on. A missing row
+//! means undecided: bundled skills then hold what they declare, anything else
+//! holds nothing.
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+
+use super::super::Database;
+
+impl Database {
+    /// Decided capabilities for a skill as (capability, granted)
+    pub fn list_skill_permissions(&self, skill_name: &str) -> SqliteResult<Vec<(String, bool)>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT capability, granted FROM skill_permissions WHERE skill_name = ?1 ORDER BY capability",
+        )?;
+        let rows = stmt
+            .query_map([skill_name], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? != 0)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Grant or deny a capability to a skill
+    pub fn set_skill_permission(&self, skill_name: &str, capability: &str, granted: bool) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO skill_permissions (skill_name, capability, granted, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(skill_name, capability) DO UPDATE SET
+                granted = excluded.granted,
+                updated_at = excluded.updated_at",
+            rusqlite::params![skill_name, capability, granted as i64, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Forget every decision for a skill (on uninstall)
+    pub fn delete_skill_permissions(&self, skill_name: &str) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM skill_permissions WHERE skill_name = ?1", [skill_name])
+    }
+}
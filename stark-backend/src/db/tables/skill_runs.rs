@@ -0,0 +1,229 @@
+//! Skill runs - one row per skill invoked through invoke_skill
+//!
+//! Runs started from inside another invoked skill point at it through
+//! `parent_run_id` and share the top-level run as `root_run_id` (NULL on the
+//! root itself), so a composite skill's whole tree is one query.
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use crate::db::Database;
+
+pub const SKILL_RUN_RUNNING: &str = "running";
+pub const SKILL_RUN_COMPLETED: &str = "completed";
+pub const SKILL_RUN_FAILED: &str = "failed";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillRun {
+    pub id: i64,
+    pub skill_name: String,
+    /// Skill that was active when the root run was invoked (root runs only)
+    pub caller_skill: Option<String>,
+    pub parent_run_id: Option<i64>,
+    pub root_run_id: Option<i64>,
+    /// 1 for a skill invoked by the main agent, +1 per nesting level
+    pub depth: i64,
+    pub session_id: Option<i64>,
+    pub channel_id: Option<i64>,
+    pub subagent_id: Option<String>,
+    pub input: String,
+    /// JSON object of argument values
+    pub args: String,
+    pub status: String,
+    pub output: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+}
+
+/// Totals over a run and everything it invoked
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SkillRunTotals {
+    pub runs: usize,
+    pub failed: usize,
+    pub running: usize,
+    pub max_depth: i64,
+    /// Wall time of the root run (children run inside it)
+    pub duration_ms: Option<i64>,
+    pub skills: Vec<String>,
+}
+
+impl SkillRunTotals {
+    pub fn from_tree(tree: &[SkillRun]) -> Self {
+        let mut totals = SkillRunTotals { runs: tree.len(), ..Default::default() };
+        for run in tree {
+            match run.status.as_str() {
+                SKILL_RUN_RUNNING => totals.running += 1,
+                SKILL_RUN_COMPLETED => {}
+                _ => totals.failed += 1,
+            }
+            totals.max_depth = totals.max_depth.max(run.depth);
+            if run.root_run_id.is_none() {
+                totals.duration_ms = run.duration_ms;
+            }
+            if !totals.skills.contains(&run.skill_name) {
+                totals.skills.push(run.skill_name.clone());
+            }
+        }
+        totals
+    }
+}
+
+const SKILL_RUN_COLS: &str = "id, skill_name, caller_skill, parent_run_id, root_run_id, depth, session_id, channel_id, \
+     subagent_id, input, args, status, output, error, duration_ms, started_at, completed_at";
+
+fn row_to_skill_run(row: &rusqlite::Row) -> rusqlite::Result<SkillRun> {
+    Ok(SkillRun {
+        id: row.get(0)?,
+        skill_name: row.get(1)?,
+        caller_skill: row.get(2)?,
+        parent_run_id: row.get(3)?,
+        root_run_id: row.get(4)?,
+        depth: row.get(5)?,
+        session_id: row.get(6)?,
+        channel_id: row.get(7)?,
+        subagent_id: row.get(8)?,
+        input: row.get(9)?,
+        args: row.get(10)?,
+        status: row.get(11)?,
+        output: row.get(12)?,
+        error: row.get(13)?,
+        duration_ms: row.get(14)?,
+        started_at: row.get(15)?,
+        completed_at: row.get(16)?,
+    })
+}
+
+impl Database {
+    /// Record a run as started. The root is inherited from `parent`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn start_skill_run(
+        &self,
+        skill_name: &str,
+        caller_skill: Option<&str>,
+        parent: Option<&SkillRun>,
+        session_id: Option<i64>,
+        channel_id: Option<i64>,
+        input: &str,
+        args_json: &str,
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let (parent_run_id, root_run_id, depth) = match parent {
+            Some(p) => (Some(p.id), Some(p.root_run_id.unwrap_or(p.id)), p.depth + 1),
+            None => (None, None, 1),
+        };
+        conn.execute(
+            "INSERT INTO skill_runs (skill_name, caller_skill, parent_run_id, root_run_id, depth, session_id,
+                                     channel_id, input, args, status, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                skill_name,
+                caller_skill,
+                parent_run_id,
+                root_run_id,
+                depth,
+                session_id,
+                channel_id,
+                input,
+                args_json,
+                SKILL_RUN_RUNNING,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Link a run to the sub-agent executing it
+    pub fn set_skill_run_subagent(&self, id: i64, subagent_id: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute("UPDATE skill_runs SET subagent_id = ?1 WHERE id = ?2", rusqlite::params![subagent_id, id])?;
+        Ok(())
+    }
+
+    pub fn finish_skill_run(
+        &self,
+        id: i64,
+        status: &str,
+        output: Option<&str>,
+        error: Option<&str>,
+        duration_ms: i64,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE skill_runs SET status = ?1, output = ?2, error = ?3, duration_ms = ?4, completed_at = ?5
+             WHERE id = ?6",
+            rusqlite::params![status, output, error, duration_ms, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_skill_run(&self, id: i64) -> SqliteResult<Option<SkillRun>> {
+        let conn = self.conn();
+        let sql = format!("SELECT {} FROM skill_runs WHERE id = ?1", SKILL_RUN_COLS);
+        conn.query_row(&sql, [id], row_to_skill_run).optional()
+    }
+
+    /// The run a sub-agent was spawned to execute
+    pub fn get_skill_run_by_subagent(&self, subagent_id: &str) -> SqliteResult<Option<SkillRun>> {
+        let conn = self.conn();
+        let sql = format!("SELECT {} FROM skill_runs WHERE subagent_id = ?1", SKILL_RUN_COLS);
+        conn.query_row(&sql, [subagent_id], row_to_skill_run).optional()
+    }
+
+    /// Top-level runs, newest first, optionally for one skill
+    pub fn list_root_skill_runs(&self, skill_name: Option<&str>, limit: usize) -> SqliteResult<Vec<SkillRun>> {
+        let conn = self.conn();
+        let sql = format!(
+            "SELECT {} FROM skill_runs WHERE root_run_id IS NULL AND (?1 IS NULL OR skill_name = ?1)
+             ORDER BY id DESC LIMIT ?2",
+            SKILL_RUN_COLS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params![skill_name, limit as i64], row_to_skill_run)?;
+        rows.collect()
+    }
+
+    /// A root run and every run beneath it, in start order
+    pub fn list_skill_run_tree(&self, root_id: i64) -> SqliteResult<Vec<SkillRun>> {
+        let conn = self.conn();
+        let sql = format!(
+            "SELECT {} FROM skill_runs WHERE id = ?1 OR root_run_id = ?1 ORDER BY id ASC",
+            SKILL_RUN_COLS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([root_id], row_to_skill_run)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_runs_share_root() {
+        let db = Database::new(":memory:").unwrap();
+        let root_id = db.start_skill_run("plan_trip", Some("concierge"), None, Some(1), Some(0), "go", "{}").unwrap();
+        let root = db.get_skill_run(root_id).unwrap().unwrap();
+        let child_id = db.start_skill_run("weather", None, Some(&root), Some(1), Some(0), "paris", "{}").unwrap();
+        db.set_skill_run_subagent(child_id, "subagent-skill-weather-1").unwrap();
+        let child = db.get_skill_run_by_subagent("subagent-skill-weather-1").unwrap().unwrap();
+        let grandchild_id = db.start_skill_run("geo", None, Some(&child), Some(1), Some(0), "", "{}").unwrap();
+
+        db.finish_skill_run(grandchild_id, SKILL_RUN_FAILED, None, Some("boom"), 5).unwrap();
+        db.finish_skill_run(child_id, SKILL_RUN_COMPLETED, Some("sunny"), None, 20).unwrap();
+        db.finish_skill_run(root_id, SKILL_RUN_COMPLETED, Some("done"), None, 40).unwrap();
+
+        let tree = db.list_skill_run_tree(root_id).unwrap();
+        assert_eq!(tree.iter().map(|r| r.depth).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(tree[1..].iter().all(|r| r.root_run_id == Some(root_id)));
+
+        let totals = SkillRunTotals::from_tree(&tree);
+        assert_eq!((totals.runs, totals.failed, totals.max_depth), (3, 1, 3));
+        assert_eq!(totals.duration_ms, Some(40));
+        assert_eq!(db.list_root_skill_runs(Some("plan_trip"), 10).unwrap().len(), 1);
+        assert!(db.list_root_skill_runs(Some("weather"), 10).unwrap().is_empty());
+    }
+}
@@ -97,10 +97,11 @@ impl Database {
         let arguments_json = serde_json::to_string(&skill.arguments).unwrap_or_default();
         let tags_json = serde_json::to_string(&skill.tags).unwrap_or_default();
         let requires_api_keys_json = serde_json::to_string(&skill.requires_api_keys).unwrap_or_default();
+        let finance_permissions_json = serde_json::to_string(&skill.finance_permissions).unwrap_or_default();
 
         conn.execute(
-            "INSERT INTO skills (name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?15)
+            "INSERT INTO skills (name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at, finance_permissions)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?15, ?16)
              ON CONFLICT(name) DO UPDATE SET
                 description = excluded.description,
                 body = excluded.body,
@@ -114,6 +115,7 @@ impl Database {
                 tags = excluded.tags,
                 subagent_type = excluded.subagent_type,
                 requires_api_keys = excluded.requires_api_keys,
+                finance_permissions = excluded.finance_permissions,
                 updated_at = excluded.updated_at",
             rusqlite::params![
                 skill.name,
@@ -130,7 +132,8 @@ impl Database {
                 tags_json,
                 skill.subagent_type,
                 requires_api_keys_json,
-                now
+                now,
+                finance_permissions_json
             ],
         )?;
 
@@ -149,7 +152,7 @@ impl Database {
     pub fn get_skill(&self, name: &str) -> SqliteResult<Option<DbSkill>> {
         let conn = self.conn();
         let mut stmt = conn.prepare(
-            "SELECT id, name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at
+            "SELECT id, name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at, finance_permissions
              FROM skills WHERE name = ?1"
         )?;
 
@@ -164,7 +167,7 @@ impl Database {
     pub fn get_skill_by_id(&self, id: i64) -> SqliteResult<Option<DbSkill>> {
         let conn = self.conn();
         let mut stmt = conn.prepare(
-            "SELECT id, name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at
+            "SELECT id, name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at, finance_permissions
              FROM skills WHERE id = ?1"
         )?;
 
@@ -179,7 +182,7 @@ impl Database {
     pub fn get_enabled_skill_by_name(&self, name: &str) -> SqliteResult<Option<DbSkill>> {
         let conn = self.conn();
         let mut stmt = conn.prepare(
-            "SELECT id, name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at
+            "SELECT id, name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at, finance_permissions
              FROM skills WHERE name = ?1 AND enabled = 1 LIMIT 1"
         )?;
 
@@ -194,7 +197,7 @@ impl Database {
     pub fn list_skills(&self) -> SqliteResult<Vec<DbSkill>> {
         let conn = self.conn();
         let mut stmt = conn.prepare(
-            "SELECT id, name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at
+            "SELECT id, name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at, finance_permissions
              FROM skills ORDER BY name"
         )?;
 
@@ -210,7 +213,7 @@ impl Database {
     pub fn list_enabled_skills(&self) -> SqliteResult<Vec<DbSkill>> {
         let conn = self.conn();
         let mut stmt = conn.prepare(
-            "SELECT id, name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at
+            "SELECT id, name, description, body, version, author, homepage, metadata, enabled, requires_tools, requires_binaries, arguments, tags, subagent_type, requires_api_keys, created_at, updated_at, finance_permissions
              FROM skills WHERE enabled = 1 ORDER BY name"
         )?;
 
@@ -249,6 +252,7 @@ impl Database {
         let arguments_str: String = row.get(11)?;
         let tags_str: String = row.get(12)?;
         let requires_api_keys_str: String = row.get::<_, Option<String>>(14)?.unwrap_or_else(|| "{}".to_string());
+        let finance_permissions_str: String = row.get::<_, Option<String>>(17)?.unwrap_or_else(|| "[]".to_string());
 
         Ok(DbSkill {
             id: row.get(0)?,
@@ -269,6 +273,7 @@ impl Database {
             requires_api_keys: serde_json::from_str(&requires_api_keys_str).unwrap_or_default(),
             created_at: row.get(15)?,
             updated_at: row.get(16)?,
+            finance_permissions: serde_json::from_str(&finance_permissions_str).unwrap_or_default(),
         })
     }
 
@@ -587,4 +592,16 @@ impl Database {
         )?;
         Ok(rows_affected as i64)
     }
+
+    /// Skills activated in a session through use_skill or invoke_skill
+    pub fn skills_used_in_session(&self, session_id: i64) -> SqliteResult<Vec<String>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT COALESCE(json_extract(parameters, '$.skill_name'), json_extract(parameters, '$.name'))
+             FROM tool_executions
+             WHERE session_id = ?1 AND tool_name IN ('use_skill', 'invoke_skill') AND json_valid(parameters)",
+        )?;
+        let rows = stmt.query_map([session_id], |row| row.get::<_, Option<String>>(0))?;
+        Ok(rows.filter_map(|r| r.ok().flatten()).collect())
+    }
 }
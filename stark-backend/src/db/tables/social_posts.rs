@@ -0,0 +1,322 @@
+//! Social content calendar (social_posts)
+//!
+//! Posts drafted by the agent start `pending` and are only published after the
+//! owner approves them (chat buttons or the dashboard). Approved posts go out
+//! at `scheduled_at` (or as soon as possible when unset); published posts keep
+//! their platform ID and the latest engagement metrics.
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use super::super::Database;
+
+pub const SOCIAL_POST_PENDING: &str = "pending";
+pub const SOCIAL_POST_APPROVED: &str = "approved";
+pub const SOCIAL_POST_REJECTED: &str = "rejected";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SocialPost {
+    pub id: i64,
+    /// "twitter" or "discord"
+    pub platform: String,
+    /// External channel to publish through (Discord)
+    pub target_channel_id: Option<i64>,
+    /// Platform channel to post in (Discord channel ID)
+    pub target_chat_id: Option<String>,
+    pub text: String,
+    /// pending, approved, rejected, published, failed or cancelled
+    pub status: String,
+    /// None = publish as soon as approved
+    pub scheduled_at: Option<String>,
+    pub session_id: Option<i64>,
+    /// Chat the approval card was sent to, if any
+    pub approval_channel_id: Option<i64>,
+    pub approval_chat_id: Option<String>,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<String>,
+    pub external_id: Option<String>,
+    pub external_url: Option<String>,
+    pub last_error: Option<String>,
+    pub metrics: Option<serde_json::Value>,
+    pub metrics_updated_at: Option<String>,
+    pub published_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Fields recorded when the agent drafts a post
+pub struct NewSocialPost<'a> {
+    pub platform: &'a str,
+    pub target_channel_id: Option<i64>,
+    pub target_chat_id: Option<&'a str>,
+    pub text: &'a str,
+    pub scheduled_at: Option<DateTime<Utc>>,
+    pub session_id: Option<i64>,
+}
+
+const POST_COLS: &str = "id, platform, target_channel_id, target_chat_id, text, status, scheduled_at, session_id, \
+                         approval_channel_id, approval_chat_id, decided_by, decided_at, external_id, external_url, \
+                         last_error, metrics_json, metrics_updated_at, published_at, created_at, updated_at";
+
+fn row_to_post(row: &rusqlite::Row) -> rusqlite::Result<SocialPost> {
+    let metrics: Option<String> = row.get(15)?;
+    Ok(SocialPost {
+        id: row.get(0)?,
+        platform: row.get(1)?,
+        target_channel_id: row.get(2)?,
+        target_chat_id: row.get(3)?,
+        text: row.get(4)?,
+        status: row.get(5)?,
+        scheduled_at: row.get(6)?,
+        session_id: row.get(7)?,
+        approval_channel_id: row.get(8)?,
+        approval_chat_id: row.get(9)?,
+        decided_by: row.get(10)?,
+        decided_at: row.get(11)?,
+        external_id: row.get(12)?,
+        external_url: row.get(13)?,
+        last_error: row.get(14)?,
+        metrics: metrics.and_then(|m| serde_json::from_str(&m).ok()),
+        metrics_updated_at: row.get(16)?,
+        published_at: row.get(17)?,
+        created_at: row.get(18)?,
+        updated_at: row.get(19)?,
+    })
+}
+
+impl Database {
+    /// Record a drafted post awaiting approval
+    pub fn create_social_post(&self, post: &NewSocialPost) -> SqliteResult<SocialPost> {
+        let id = {
+            let conn = self.conn();
+            let now = Utc::now().to_rfc3339();
+            conn.execute(
+                "INSERT INTO social_posts (platform, target_channel_id, target_chat_id, text, status, scheduled_at, session_id, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+                rusqlite::params![
+                    post.platform,
+                    post.target_channel_id,
+                    post.target_chat_id,
+                    post.text,
+                    SOCIAL_POST_PENDING,
+                    post.scheduled_at.map(|t| t.to_rfc3339()),
+                    post.session_id,
+                    now,
+                ],
+            )?;
+            conn.last_insert_rowid()
+        };
+        self.get_social_post(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    pub fn get_social_post(&self, id: i64) -> SqliteResult<Option<SocialPost>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM social_posts WHERE id = ?1", POST_COLS),
+            [id],
+            row_to_post,
+        )
+        .optional()
+    }
+
+    /// List posts, optionally filtered by status; scheduled order, unscheduled last
+    pub fn list_social_posts(&self, status: Option<&str>, limit: usize) -> SqliteResult<Vec<SocialPost>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM social_posts WHERE (?1 IS NULL OR status = ?1)
+             ORDER BY scheduled_at IS NULL, scheduled_at ASC, id DESC LIMIT ?2",
+            POST_COLS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![status, limit as i64], row_to_post)?;
+        rows.collect()
+    }
+
+    /// Remember where the approval card was sent
+    pub fn set_social_post_approval_chat(&self, id: i64, channel_id: i64, chat_id: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE social_posts SET approval_channel_id = ?1, approval_chat_id = ?2 WHERE id = ?3",
+            rusqlite::params![channel_id, chat_id, id],
+        )?;
+        Ok(())
+    }
+
+    /// Owner edit of text and/or schedule. Only unpublished, undecided-or-approved
+    /// posts can change. `scheduled_at`: None = keep, Some(None) = as soon as possible.
+    pub fn update_social_post(
+        &self,
+        id: i64,
+        text: Option<&str>,
+        scheduled_at: Option<Option<DateTime<Utc>>>,
+    ) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let changed = conn.execute(
+            "UPDATE social_posts SET
+                text = COALESCE(?1, text),
+                scheduled_at = CASE WHEN ?2 THEN ?3 ELSE scheduled_at END,
+                updated_at = ?4
+             WHERE id = ?5 AND status IN ('pending', 'approved')",
+            rusqlite::params![
+                text,
+                scheduled_at.is_some(),
+                scheduled_at.flatten().map(|t| t.to_rfc3339()),
+                Utc::now().to_rfc3339(),
+                id,
+            ],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Approve or reject a pending post. False if it was no longer pending.
+    pub fn decide_social_post(&self, id: i64, approve: bool, decided_by: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let status = if approve { SOCIAL_POST_APPROVED } else { SOCIAL_POST_REJECTED };
+        let changed = conn.execute(
+            "UPDATE social_posts SET status = ?1, decided_by = ?2, decided_at = ?3, updated_at = ?3
+             WHERE id = ?4 AND status = 'pending'",
+            rusqlite::params![status, decided_by, now, id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Cancel a post that hasn't been published
+    pub fn cancel_social_post(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let changed = conn.execute(
+            "UPDATE social_posts SET status = 'cancelled', updated_at = ?1
+             WHERE id = ?2 AND status IN ('pending', 'approved', 'failed')",
+            rusqlite::params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Approved posts whose time has come
+    pub fn list_due_social_posts(&self, limit: usize) -> SqliteResult<Vec<SocialPost>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM social_posts
+             WHERE status = 'approved' AND (scheduled_at IS NULL OR scheduled_at <= ?1)
+             ORDER BY scheduled_at ASC, id ASC LIMIT ?2",
+            POST_COLS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![Utc::now().to_rfc3339(), limit as i64], row_to_post)?;
+        rows.collect()
+    }
+
+    pub fn mark_social_post_published(&self, id: i64, external_id: &str, external_url: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE social_posts SET status = 'published', external_id = ?1, external_url = ?2,
+                last_error = NULL, published_at = ?3, updated_at = ?3
+             WHERE id = ?4",
+            rusqlite::params![external_id, external_url, now, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn mark_social_post_failed(&self, id: i64, error: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE social_posts SET status = 'failed', last_error = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![error, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Posts published within `max_age` whose metrics are older than `refresh_after`
+    pub fn list_social_posts_needing_metrics(
+        &self,
+        max_age: Duration,
+        refresh_after: Duration,
+        limit: usize,
+    ) -> SqliteResult<Vec<SocialPost>> {
+        let conn = self.conn();
+        let now = Utc::now();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM social_posts
+             WHERE status = 'published' AND external_id IS NOT NULL AND published_at >= ?1
+               AND (metrics_updated_at IS NULL OR metrics_updated_at <= ?2)
+             ORDER BY metrics_updated_at IS NOT NULL, metrics_updated_at ASC LIMIT ?3",
+            POST_COLS
+        ))?;
+        let rows = stmt.query_map(
+            rusqlite::params![
+                (now - max_age).to_rfc3339(),
+                (now - refresh_after).to_rfc3339(),
+                limit as i64
+            ],
+            row_to_post,
+        )?;
+        rows.collect()
+    }
+
+    pub fn set_social_post_metrics(&self, id: i64, metrics: &serde_json::Value) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE social_posts SET metrics_json = ?1, metrics_updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![metrics.to_string(), Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_social_post_approval_flow() {
+        let db = Database::new(":memory:").unwrap();
+        let later = db
+            .create_social_post(&NewSocialPost {
+                platform: "twitter",
+                target_channel_id: None,
+                target_chat_id: None,
+                text: "Launch day",
+                scheduled_at: Some(Utc::now() + Duration::hours(2)),
+                session_id: Some(1),
+            })
+            .unwrap();
+        let now = db
+            .create_social_post(&NewSocialPost {
+                platform: "discord",
+                target_channel_id: Some(4),
+                target_chat_id: Some("123"),
+                text: "gm",
+                scheduled_at: None,
+                session_id: None,
+            })
+            .unwrap();
+        assert_eq!(now.status, SOCIAL_POST_PENDING);
+
+        // Pending posts are never due
+        assert!(db.list_due_social_posts(10).unwrap().is_empty());
+
+        assert!(db.decide_social_post(now.id, true, "owner").unwrap());
+        assert!(!db.decide_social_post(now.id, false, "owner").unwrap());
+        assert!(db.decide_social_post(later.id, true, "owner").unwrap());
+        let due = db.list_due_social_posts(10).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, now.id);
+
+        // Owner pulls the scheduled one forward and edits it
+        assert!(db.update_social_post(later.id, Some("Launch day!"), Some(None)).unwrap());
+        assert_eq!(db.list_due_social_posts(10).unwrap().len(), 2);
+
+        db.mark_social_post_published(now.id, "999", None).unwrap();
+        assert!(!db.update_social_post(now.id, Some("too late"), None).unwrap());
+        let needing = db
+            .list_social_posts_needing_metrics(Duration::days(7), Duration::hours(1), 10)
+            .unwrap();
+        assert_eq!(needing.len(), 1);
+        db.set_social_post_metrics(now.id, &serde_json::json!({"reactions": 3})).unwrap();
+        assert!(db
+            .list_social_posts_needing_metrics(Duration::days(7), Duration::hours(1), 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(db.get_social_post(now.id).unwrap().unwrap().metrics.unwrap()["reactions"], 3);
+    }
+}
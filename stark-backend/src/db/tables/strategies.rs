@@ -0,0 +1,304 @@
+//! Strategy database operations (strategies, strategy_runs)
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde_json::Value;
+
+use super::super::Database;
+use crate::strategies::types::{
+    CreateStrategyRequest, StepResult, Strategy, StrategyRun, UpdateStrategyRequest, RUN_SKIPPED,
+};
+
+const STRATEGY_COLUMNS: &str = "id, name, description, trigger_json, conditions_json, actions_json, guardrails_json,
+                                channel_id, chat_id, enabled, state_json, last_run_at, created_at, updated_at";
+
+const RUN_COLUMNS: &str = "id, strategy_id, strategy_name, reason, status, steps_json, error, started_at, finished_at";
+
+fn row_to_strategy(row: &rusqlite::Row) -> rusqlite::Result<Strategy> {
+    let trigger_json: String = row.get(3)?;
+    let trigger = serde_json::from_str(&trigger_json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    let conditions_json: String = row.get(4)?;
+    let actions_json: String = row.get(5)?;
+    let guardrails_json: String = row.get(6)?;
+    let state_json: Option<String> = row.get(10)?;
+    Ok(Strategy {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        trigger,
+        conditions: serde_json::from_str(&conditions_json).unwrap_or_default(),
+        actions: serde_json::from_str(&actions_json).unwrap_or_default(),
+        guardrails: serde_json::from_str(&guardrails_json).unwrap_or_default(),
+        channel_id: row.get(7)?,
+        chat_id: row.get(8)?,
+        enabled: row.get::<_, i32>(9)? != 0,
+        state: state_json.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or(Value::Null),
+        last_run_at: row.get(11)?,
+        created_at: row.get(12)?,
+        updated_at: row.get(13)?,
+    })
+}
+
+fn row_to_run(row: &rusqlite::Row) -> rusqlite::Result<StrategyRun> {
+    let steps_json: String = row.get(5)?;
+    Ok(StrategyRun {
+        id: row.get(0)?,
+        strategy_id: row.get(1)?,
+        strategy_name: row.get(2)?,
+        reason: row.get(3)?,
+        status: row.get(4)?,
+        steps: serde_json::from_str(&steps_json).unwrap_or_default(),
+        error: row.get(6)?,
+        started_at: row.get(7)?,
+        finished_at: row.get(8)?,
+    })
+}
+
+impl Database {
+    /// Create a new strategy
+    pub fn create_strategy(&self, request: &CreateStrategyRequest) -> SqliteResult<Strategy> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO strategies (name, description, trigger_json, conditions_json, actions_json, guardrails_json,
+                                     channel_id, chat_id, enabled, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+            rusqlite::params![
+                request.name.trim(),
+                request.description,
+                serde_json::to_string(&request.trigger).unwrap_or_default(),
+                serde_json::to_string(&request.conditions).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&request.actions).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&request.guardrails).unwrap_or_else(|_| "{}".to_string()),
+                request.channel_id,
+                request.chat_id.as_deref().filter(|c| !c.is_empty()),
+                request.enabled.unwrap_or(true) as i32,
+                now,
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        drop(conn);
+        self.get_strategy(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    /// Get a strategy by ID
+    pub fn get_strategy(&self, id: i64) -> SqliteResult<Option<Strategy>> {
+        let conn = self.conn();
+        let strategy = conn
+            .query_row(
+                &format!("SELECT {} FROM strategies WHERE id = ?1", STRATEGY_COLUMNS),
+                [id],
+                row_to_strategy,
+            )
+            .ok();
+        Ok(strategy)
+    }
+
+    /// List all strategies ordered by creation
+    pub fn list_strategies(&self) -> SqliteResult<Vec<Strategy>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM strategies ORDER BY id ASC", STRATEGY_COLUMNS))?;
+        let strategies = stmt.query_map([], row_to_strategy)?.filter_map(|r| r.ok()).collect();
+        Ok(strategies)
+    }
+
+    /// List only enabled strategies (used by the evaluation worker)
+    pub fn list_enabled_strategies(&self) -> SqliteResult<Vec<Strategy>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM strategies WHERE enabled = 1 ORDER BY id ASC",
+            STRATEGY_COLUMNS
+        ))?;
+        let strategies = stmt.query_map([], row_to_strategy)?.filter_map(|r| r.ok()).collect();
+        Ok(strategies)
+    }
+
+    /// Update a strategy. Unset fields keep their current value. Changing the
+    /// trigger resets its bookkeeping.
+    pub fn update_strategy(&self, id: i64, request: &UpdateStrategyRequest) -> SqliteResult<Option<Strategy>> {
+        let existing = match self.get_strategy(id)? {
+            Some(s) => s,
+            None => return Ok(None),
+        };
+
+        let name = request.name.as_deref().map(str::trim).unwrap_or(&existing.name).to_string();
+        let description = request.description.clone().or(existing.description);
+        let trigger = request.trigger.as_ref().unwrap_or(&existing.trigger);
+        let conditions = request.conditions.as_ref().unwrap_or(&existing.conditions);
+        let actions = request.actions.as_ref().unwrap_or(&existing.actions);
+        let guardrails = request.guardrails.as_ref().unwrap_or(&existing.guardrails);
+        let channel_id = request.channel_id.or(existing.channel_id);
+        let chat_id = match request.chat_id.as_deref() {
+            Some(c) => Some(c).filter(|c| !c.is_empty()).map(str::to_string),
+            None => existing.chat_id,
+        };
+        let enabled = request.enabled.unwrap_or(existing.enabled);
+        let state = if *trigger == existing.trigger { existing.state } else { Value::Null };
+
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE strategies SET name = ?1, description = ?2, trigger_json = ?3, conditions_json = ?4,
+                    actions_json = ?5, guardrails_json = ?6, channel_id = ?7, chat_id = ?8, enabled = ?9,
+                    state_json = ?10, updated_at = ?11
+             WHERE id = ?12",
+            rusqlite::params![
+                name,
+                description,
+                serde_json::to_string(trigger).unwrap_or_default(),
+                serde_json::to_string(conditions).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(actions).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(guardrails).unwrap_or_else(|_| "{}".to_string()),
+                channel_id,
+                chat_id,
+                enabled as i32,
+                (!state.is_null()).then(|| state.to_string()),
+                Utc::now().to_rfc3339(),
+                id,
+            ],
+        )?;
+        drop(conn);
+        self.get_strategy(id)
+    }
+
+    /// Delete a strategy (its run history is kept)
+    pub fn delete_strategy(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM strategies WHERE id = ?1", [id])?;
+        Ok(rows > 0)
+    }
+
+    /// Persist trigger bookkeeping between evaluation passes
+    pub fn set_strategy_state(&self, id: i64, state: &Value) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE strategies SET state_json = ?1 WHERE id = ?2",
+            rusqlite::params![state.to_string(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Record a run. Runs that did something (not skipped) bump last_run_at,
+    /// which the cooldown is measured from.
+    pub fn record_strategy_run(
+        &self,
+        strategy: &Strategy,
+        reason: &str,
+        status: &str,
+        steps: &[StepResult],
+        error: Option<&str>,
+        started_at: &str,
+    ) -> SqliteResult<StrategyRun> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO strategy_runs (strategy_id, strategy_name, reason, status, steps_json, error, started_at, finished_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                strategy.id,
+                strategy.name,
+                reason,
+                status,
+                serde_json::to_string(steps).unwrap_or_else(|_| "[]".to_string()),
+                error,
+                started_at,
+                now,
+            ],
+        )?;
+        let run_id = conn.last_insert_rowid();
+        if status != RUN_SKIPPED {
+            conn.execute(
+                "UPDATE strategies SET last_run_at = ?1 WHERE id = ?2",
+                rusqlite::params![started_at, strategy.id],
+            )?;
+        }
+        conn.query_row(
+            &format!("SELECT {} FROM strategy_runs WHERE id = ?1", RUN_COLUMNS),
+            [run_id],
+            row_to_run,
+        )
+    }
+
+    /// Runs (not skipped) of a strategy started at or after an RFC 3339 time
+    pub fn count_strategy_runs_since(&self, strategy_id: i64, since: &str) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT COUNT(*) FROM strategy_runs WHERE strategy_id = ?1 AND status != ?2 AND started_at >= ?3",
+            rusqlite::params![strategy_id, RUN_SKIPPED, since],
+            |row| row.get(0),
+        )
+    }
+
+    /// Run history, newest first (optionally for one strategy)
+    pub fn list_strategy_runs(&self, strategy_id: Option<i64>, limit: usize) -> SqliteResult<Vec<StrategyRun>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM strategy_runs WHERE (?1 IS NULL OR strategy_id = ?1) ORDER BY id DESC LIMIT ?2",
+            RUN_COLUMNS
+        ))?;
+        let runs = stmt
+            .query_map(rusqlite::params![strategy_id, limit as i64], row_to_run)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::types::{
+        StrategyAction, StrategyGuardrails, StrategyTrigger, RUN_SUCCEEDED,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_strategy_crud_and_runs() {
+        let db = Database::new(":memory:").unwrap();
+        let strategy = db
+            .create_strategy(&CreateStrategyRequest {
+                name: "Buy the dip".to_string(),
+                description: None,
+                trigger: StrategyTrigger::Price {
+                    token: "WETH".to_string(),
+                    network: "base".to_string(),
+                    above: None,
+                    below: Some(2000.0),
+                },
+                conditions: vec![],
+                actions: vec![StrategyAction {
+                    tool: "swap_token".to_string(),
+                    params: json!({ "sell_token": "USDC", "buy_token": "WETH", "amount": "50" }),
+                }],
+                guardrails: StrategyGuardrails::default(),
+                channel_id: None,
+                chat_id: None,
+                enabled: None,
+            })
+            .unwrap();
+        assert!(strategy.enabled);
+        assert_eq!(db.list_enabled_strategies().unwrap().len(), 1);
+
+        db.set_strategy_state(strategy.id, &json!({ "hit": false })).unwrap();
+        let started = Utc::now().to_rfc3339();
+        db.record_strategy_run(&strategy, "skipped by condition", RUN_SKIPPED, &[], None, &started).unwrap();
+        assert!(db.get_strategy(strategy.id).unwrap().unwrap().last_run_at.is_none());
+        db.record_strategy_run(&strategy, "WETH below $2000", RUN_SUCCEEDED, &[], None, &started).unwrap();
+        assert_eq!(db.count_strategy_runs_since(strategy.id, &started).unwrap(), 1);
+        assert_eq!(db.list_strategy_runs(Some(strategy.id), 10).unwrap().len(), 2);
+
+        // Changing the trigger resets its bookkeeping
+        let updated = db
+            .update_strategy(strategy.id, &UpdateStrategyRequest {
+                trigger: Some(StrategyTrigger::Schedule { cron: None, every_minutes: Some(60) }),
+                enabled: Some(false),
+                ..Default::default()
+            })
+            .unwrap()
+            .unwrap();
+        assert!(updated.state.is_null());
+        assert!(!updated.enabled);
+        assert!(db.delete_strategy(strategy.id).unwrap());
+    }
+}
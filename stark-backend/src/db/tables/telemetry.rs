@@ -120,6 +120,27 @@ impl Database {
         Ok(spans)
     }
 
+    /// Count terminal spans of a type since a timestamp, optionally filtered by name.
+    /// Returns (total, failed) where failed includes timed-out spans.
+    pub fn count_spans_since(
+        &self,
+        span_type: SpanType,
+        name: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> SqliteResult<(i64, i64)> {
+        let conn = self.conn();
+        let type_str = serde_json::to_string(&span_type).unwrap_or_default().trim_matches('"').to_string();
+        conn.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(CASE WHEN status IN ('failed', 'timed_out') THEN 1 ELSE 0 END), 0)
+             FROM execution_spans
+             WHERE span_type = ?1 AND started_at >= ?2 AND status != 'running'
+               AND (?3 IS NULL OR name = ?3)",
+            rusqlite::params![type_str, since.to_rfc3339(), name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+
     pub fn prune_spans_before(&self, before: &str) -> SqliteResult<usize> {
         let conn = self.conn();
         conn.execute(
@@ -216,6 +237,15 @@ impl Database {
         Ok(())
     }
 
+    pub fn update_rollout_metadata(&self, rollout_id: &str, metadata: &serde_json::Value) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE rollouts SET metadata = ?1 WHERE rollout_id = ?2",
+            [serde_json::to_string(metadata).unwrap_or_default().as_str(), rollout_id],
+        )?;
+        Ok(())
+    }
+
     pub fn prune_rollouts_before(&self, before: &str) -> SqliteResult<usize> {
         let conn = self.conn();
         // Also clean up associated attempts and spans
@@ -0,0 +1,135 @@
+//! Token metadata cache and overrides (token_metadata, token_metadata_overrides)
+//!
+//! `token_metadata` keeps what Alchemy or CoinGecko reported for a contract
+//! so symbols and decimals survive restarts; `token_metadata_overrides` holds
+//! hand-entered values that win over every other source. Addresses are
+//! stored lowercase.
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+/// Symbol, name and decimals of one token contract
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenMetadata {
+    pub network: String,
+    pub address: String,
+    pub symbol: String,
+    pub name: String,
+    pub decimals: u8,
+    /// "override", "config", "alchemy" or "coingecko"
+    pub source: String,
+    /// When it was fetched (or the override last changed)
+    pub updated_at: String,
+}
+
+impl Database {
+    pub fn upsert_token_metadata(&self, token: &TokenMetadata) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO token_metadata (network, address, symbol, name, decimals, source, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(network, address) DO UPDATE SET
+                symbol = excluded.symbol,
+                name = excluded.name,
+                decimals = excluded.decimals,
+                source = excluded.source,
+                fetched_at = excluded.fetched_at",
+            rusqlite::params![
+                token.network,
+                token.address.to_lowercase(),
+                token.symbol,
+                token.name,
+                token.decimals,
+                token.source,
+                token.updated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn list_token_metadata(&self) -> SqliteResult<Vec<TokenMetadata>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT network, address, symbol, name, decimals, source, fetched_at FROM token_metadata ORDER BY network, symbol",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(TokenMetadata {
+                network: row.get(0)?,
+                address: row.get(1)?,
+                symbol: row.get(2)?,
+                name: row.get(3)?,
+                decimals: row.get(4)?,
+                source: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Overrides, with their notes
+    pub fn list_token_overrides(&self) -> SqliteResult<Vec<(TokenMetadata, Option<String>)>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT network, address, symbol, name, decimals, note, updated_at FROM token_metadata_overrides ORDER BY network, symbol",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                TokenMetadata {
+                    network: row.get(0)?,
+                    address: row.get(1)?,
+                    symbol: row.get(2)?,
+                    name: row.get(3)?,
+                    decimals: row.get(4)?,
+                    source: "override".to_string(),
+                    updated_at: row.get(6)?,
+                },
+                row.get(5)?,
+            ))
+        })?;
+        rows.collect()
+    }
+
+    pub fn upsert_token_override(
+        &self,
+        network: &str,
+        address: &str,
+        symbol: &str,
+        name: &str,
+        decimals: u8,
+        note: Option<&str>,
+    ) -> SqliteResult<TokenMetadata> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO token_metadata_overrides (network, address, symbol, name, decimals, note, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(network, address) DO UPDATE SET
+                symbol = excluded.symbol,
+                name = excluded.name,
+                decimals = excluded.decimals,
+                note = excluded.note,
+                updated_at = excluded.updated_at",
+            rusqlite::params![network, address.to_lowercase(), symbol, name, decimals, note, now],
+        )?;
+        Ok(TokenMetadata {
+            network: network.to_string(),
+            address: address.to_lowercase(),
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            decimals,
+            source: "override".to_string(),
+            updated_at: now,
+        })
+    }
+
+    pub fn delete_token_override(&self, network: &str, address: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        Ok(conn.execute(
+            "DELETE FROM token_metadata_overrides WHERE network = ?1 AND address = ?2",
+            rusqlite::params![network, address.to_lowercase()],
+        )? > 0)
+    }
+}
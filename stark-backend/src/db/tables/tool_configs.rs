@@ -247,4 +247,15 @@ impl Database {
 
         Ok(executions)
     }
+
+    /// Number of recorded tool executions, for one channel or all of them
+    pub fn count_tool_executions(&self, channel_id: Option<i64>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM tool_executions WHERE (?1 IS NULL OR channel_id = ?1)",
+            [channel_id],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
 }
@@ -0,0 +1,176 @@
+//! Tool confirmation database operations (tool_confirmations)
+//!
+//! One row per tool call that had to wait for the user's confirmation because
+//! its safety level is below the channel's threshold, or because its tool has
+//! parameter previews on (kind `preview`). Rows are never deleted:
+//! the tool, its (redacted) arguments, the decision, who made it and when stay
+//! behind as the audit trail. A decision can only be recorded once, and only
+//! while the request is still pending and unexpired.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use super::super::Database;
+
+pub const TOOL_CONFIRMATION_PENDING: &str = "pending";
+pub const TOOL_CONFIRMATION_CONFIRMED: &str = "confirmed";
+pub const TOOL_CONFIRMATION_DECLINED: &str = "declined";
+pub const TOOL_CONFIRMATION_EXPIRED: &str = "expired";
+
+pub const TOOL_CONFIRMATION_KIND_FULL: &str = "confirmation";
+pub const TOOL_CONFIRMATION_KIND_PREVIEW: &str = "preview";
+
+/// A confirmation request for one tool call
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolConfirmation {
+    pub id: i64,
+    pub uuid: String,
+    pub channel_id: i64,
+    pub chat_id: String,
+    pub session_id: Option<i64>,
+    pub tool_name: String,
+    pub arguments: String,
+    pub safety_level: String,
+    /// "confirmation", or "preview" for a parameter preview
+    pub kind: String,
+    pub status: String,
+    pub requested_at: String,
+    pub expires_at: String,
+    pub decided_at: Option<String>,
+    pub decided_by: Option<String>,
+}
+
+const TOOL_CONFIRMATION_COLUMNS: &str = "id, uuid, channel_id, chat_id, session_id, tool_name, arguments, safety_level, \
+                                         status, requested_at, expires_at, decided_at, decided_by, kind";
+
+fn row_to_tool_confirmation(row: &rusqlite::Row) -> rusqlite::Result<ToolConfirmation> {
+    Ok(ToolConfirmation {
+        id: row.get(0)?,
+        uuid: row.get(1)?,
+        channel_id: row.get(2)?,
+        chat_id: row.get(3)?,
+        session_id: row.get(4)?,
+        tool_name: row.get(5)?,
+        arguments: row.get(6)?,
+        safety_level: row.get(7)?,
+        status: row.get(8)?,
+        requested_at: row.get(9)?,
+        expires_at: row.get(10)?,
+        decided_at: row.get(11)?,
+        decided_by: row.get(12)?,
+        kind: row.get(13)?,
+    })
+}
+
+impl Database {
+    /// Record a pending confirmation for a tool call
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_tool_confirmation(
+        &self,
+        uuid: &str,
+        channel_id: i64,
+        chat_id: &str,
+        session_id: Option<i64>,
+        tool_name: &str,
+        arguments: &str,
+        safety_level: &str,
+        kind: &str,
+        expires_at: DateTime<Utc>,
+    ) -> SqliteResult<ToolConfirmation> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO tool_confirmations (uuid, channel_id, chat_id, session_id, tool_name, arguments, safety_level, status, requested_at, expires_at, kind)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                uuid,
+                channel_id,
+                chat_id,
+                session_id,
+                tool_name,
+                arguments,
+                safety_level,
+                TOOL_CONFIRMATION_PENDING,
+                Utc::now().to_rfc3339(),
+                expires_at.to_rfc3339(),
+                kind
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.query_row(
+            &format!("SELECT {} FROM tool_confirmations WHERE id = ?1", TOOL_CONFIRMATION_COLUMNS),
+            [id],
+            row_to_tool_confirmation,
+        )
+    }
+
+    pub fn get_tool_confirmation(&self, uuid: &str) -> SqliteResult<Option<ToolConfirmation>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM tool_confirmations WHERE uuid = ?1", TOOL_CONFIRMATION_COLUMNS),
+            [uuid],
+            row_to_tool_confirmation,
+        )
+        .optional()
+    }
+
+    /// Record the user's decision. Returns false if the request is missing,
+    /// already decided, or expired.
+    pub fn decide_tool_confirmation(&self, uuid: &str, status: &str, decided_by: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let updated = conn.execute(
+            "UPDATE tool_confirmations SET status = ?1, decided_by = ?2, decided_at = ?3
+             WHERE uuid = ?4 AND status = ?5 AND expires_at > ?3",
+            rusqlite::params![status, decided_by, now, uuid, TOOL_CONFIRMATION_PENDING],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Mark a request that was never answered as expired. Returns false if it was decided meanwhile.
+    pub fn expire_tool_confirmation(&self, uuid: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let updated = conn.execute(
+            "UPDATE tool_confirmations SET status = ?1, decided_at = ?2 WHERE uuid = ?3 AND status = ?4",
+            rusqlite::params![TOOL_CONFIRMATION_EXPIRED, Utc::now().to_rfc3339(), uuid, TOOL_CONFIRMATION_PENDING],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// List confirmation requests, newest first
+    pub fn list_tool_confirmations(&self, status: Option<&str>, limit: usize) -> SqliteResult<Vec<ToolConfirmation>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM tool_confirmations WHERE ?1 IS NULL OR status = ?1 ORDER BY id DESC LIMIT ?2",
+            TOOL_CONFIRMATION_COLUMNS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![status, limit as i64], row_to_tool_confirmation)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_decision_is_recorded_once() {
+        let db = Database::new(":memory:").unwrap();
+        let in_future = Utc::now() + Duration::minutes(5);
+        db.create_tool_confirmation("c-1", 1, "42", Some(7), "delete_file", "{}", "standard", TOOL_CONFIRMATION_KIND_FULL, in_future).unwrap();
+
+        assert!(db.decide_tool_confirmation("c-1", TOOL_CONFIRMATION_CONFIRMED, "alice").unwrap());
+        assert!(!db.decide_tool_confirmation("c-1", TOOL_CONFIRMATION_DECLINED, "bob").unwrap());
+        assert!(!db.expire_tool_confirmation("c-1").unwrap());
+        let confirmation = db.get_tool_confirmation("c-1").unwrap().unwrap();
+        assert_eq!(confirmation.status, TOOL_CONFIRMATION_CONFIRMED);
+        assert_eq!(confirmation.decided_by.as_deref(), Some("alice"));
+
+        // Past its deadline: can't be confirmed, only expired
+        db.create_tool_confirmation("c-2", 1, "42", None, "exec", "{}", "standard", TOOL_CONFIRMATION_KIND_PREVIEW, Utc::now() - Duration::seconds(1)).unwrap();
+        assert!(!db.decide_tool_confirmation("c-2", TOOL_CONFIRMATION_CONFIRMED, "alice").unwrap());
+        assert!(db.expire_tool_confirmation("c-2").unwrap());
+        assert_eq!(db.list_tool_confirmations(Some(TOOL_CONFIRMATION_EXPIRED), 10).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,82 @@
+//! Full tool outputs kept behind a handle (tool_outputs)
+//!
+//! When a tool result exceeds the token budget, the model only sees a summary;
+//! the raw output is stored here so `fetch_full_output` can page through it.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use super::super::Database;
+
+/// A stored raw tool output
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredToolOutput {
+    pub handle: String,
+    pub session_id: Option<i64>,
+    pub tool_name: String,
+    pub content: String,
+    pub created_at: String,
+}
+
+impl Database {
+    /// Store a raw tool output under `handle`
+    pub fn save_tool_output(
+        &self,
+        handle: &str,
+        session_id: Option<i64>,
+        tool_name: &str,
+        content: &str,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO tool_outputs (handle, session_id, tool_name, content, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![handle, session_id, tool_name, content, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_tool_output(&self, handle: &str) -> SqliteResult<Option<StoredToolOutput>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT handle, session_id, tool_name, content, created_at FROM tool_outputs WHERE handle = ?1",
+            [handle],
+            |row| {
+                Ok(StoredToolOutput {
+                    handle: row.get(0)?,
+                    session_id: row.get(1)?,
+                    tool_name: row.get(2)?,
+                    content: row.get(3)?,
+                    created_at: row.get(4)?,
+                })
+            },
+        )
+        .optional()
+    }
+
+    /// Delete outputs stored before `cutoff`
+    pub fn purge_tool_outputs_before(&self, cutoff: DateTime<Utc>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM tool_outputs WHERE created_at < ?1", [cutoff.to_rfc3339()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_output_roundtrip_and_purge() {
+        let db = Database::new(":memory:").unwrap();
+        db.save_tool_output("out_abc", Some(7), "web_fetch", "raw page").unwrap();
+
+        let stored = db.get_tool_output("out_abc").unwrap().unwrap();
+        assert_eq!(stored.session_id, Some(7));
+        assert_eq!(stored.content, "raw page");
+        assert!(db.get_tool_output("out_missing").unwrap().is_none());
+
+        assert_eq!(db.purge_tool_outputs_before(Utc::now() - chrono::Duration::days(1)).unwrap(), 0);
+        assert_eq!(db.purge_tool_outputs_before(Utc::now() + chrono::Duration::seconds(1)).unwrap(), 1);
+    }
+}
@@ -0,0 +1,177 @@
+//! Jira/Linear issues filed by the agent (tracked_issues)
+//!
+//! Each row links an external issue to the channel and session it was filed
+//! from (and the planner task, if any), so webhook status changes can be
+//! reported back into that conversation.
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use super::super::Database;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackedIssue {
+    pub id: i64,
+    /// "linear" or "jira"
+    pub tracker: String,
+    pub external_id: String,
+    /// Human-readable key (ENG-123 / OPS-45)
+    pub issue_key: String,
+    pub url: Option<String>,
+    pub title: String,
+    pub status: Option<String>,
+    pub channel_id: Option<i64>,
+    pub session_id: Option<i64>,
+    pub task_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Fields recorded when an issue is filed
+pub struct NewTrackedIssue<'a> {
+    pub tracker: &'a str,
+    pub external_id: &'a str,
+    pub issue_key: &'a str,
+    pub url: Option<&'a str>,
+    pub title: &'a str,
+    pub status: Option<&'a str>,
+    pub channel_id: Option<i64>,
+    pub session_id: Option<i64>,
+    pub task_id: Option<i64>,
+}
+
+const ISSUE_COLS: &str = "id, tracker, external_id, issue_key, url, title, status, channel_id, session_id, task_id, created_at, updated_at";
+
+fn row_to_issue(row: &rusqlite::Row) -> rusqlite::Result<TrackedIssue> {
+    Ok(TrackedIssue {
+        id: row.get(0)?,
+        tracker: row.get(1)?,
+        external_id: row.get(2)?,
+        issue_key: row.get(3)?,
+        url: row.get(4)?,
+        title: row.get(5)?,
+        status: row.get(6)?,
+        channel_id: row.get(7)?,
+        session_id: row.get(8)?,
+        task_id: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
+impl Database {
+    pub fn insert_tracked_issue(&self, issue: &NewTrackedIssue) -> SqliteResult<TrackedIssue> {
+        let now = Utc::now().to_rfc3339();
+        let id = {
+            let conn = self.conn();
+            conn.execute(
+                "INSERT INTO tracked_issues (tracker, external_id, issue_key, url, title, status,
+                    channel_id, session_id, task_id, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+                rusqlite::params![
+                    issue.tracker, issue.external_id, issue.issue_key, issue.url, issue.title,
+                    issue.status, issue.channel_id, issue.session_id, issue.task_id, now
+                ],
+            )?;
+            conn.last_insert_rowid()
+        };
+        self.get_tracked_issue(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    pub fn get_tracked_issue(&self, id: i64) -> SqliteResult<Option<TrackedIssue>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM tracked_issues WHERE id = ?1", ISSUE_COLS),
+            [id],
+            row_to_issue,
+        )
+        .optional()
+    }
+
+    /// Find an issue by its external ID or human-readable key
+    pub fn find_tracked_issue(&self, tracker: &str, id_or_key: &str) -> SqliteResult<Option<TrackedIssue>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM tracked_issues WHERE tracker = ?1 AND (external_id = ?2 OR issue_key = ?2)",
+                ISSUE_COLS
+            ),
+            [tracker, id_or_key],
+            row_to_issue,
+        )
+        .optional()
+    }
+
+    /// The issue already filed for a planner task in a session, if any
+    pub fn find_tracked_issue_for_task(&self, session_id: i64, task_id: i64) -> SqliteResult<Option<TrackedIssue>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM tracked_issues WHERE session_id = ?1 AND task_id = ?2",
+                ISSUE_COLS
+            ),
+            [session_id, task_id],
+            row_to_issue,
+        )
+        .optional()
+    }
+
+    /// Update the cached title/status after an edit or webhook
+    pub fn update_tracked_issue(&self, id: i64, title: Option<&str>, status: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE tracked_issues SET title = COALESCE(?1, title), status = COALESCE(?2, status), updated_at = ?3
+             WHERE id = ?4",
+            rusqlite::params![title, status, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    /// List tracked issues, newest first, optionally for one session
+    pub fn list_tracked_issues(&self, session_id: Option<i64>, limit: usize) -> SqliteResult<Vec<TrackedIssue>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM tracked_issues WHERE (?1 IS NULL OR session_id = ?1)
+             ORDER BY created_at DESC LIMIT ?2",
+            ISSUE_COLS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![session_id, limit as i64], row_to_issue)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NewTrackedIssue;
+    use crate::db::Database;
+
+    #[test]
+    fn test_tracked_issue_lookup_and_update() {
+        let db = Database::new(":memory:").unwrap();
+        let issue = db
+            .insert_tracked_issue(&NewTrackedIssue {
+                tracker: "linear",
+                external_id: "uuid-1",
+                issue_key: "ENG-12",
+                url: Some("https://linear.app/acme/issue/ENG-12"),
+                title: "Fix login",
+                status: Some("Todo"),
+                channel_id: Some(1),
+                session_id: Some(5),
+                task_id: Some(2),
+            })
+            .unwrap();
+
+        assert_eq!(db.find_tracked_issue("linear", "ENG-12").unwrap().unwrap().id, issue.id);
+        assert_eq!(db.find_tracked_issue("linear", "uuid-1").unwrap().unwrap().id, issue.id);
+        assert!(db.find_tracked_issue("jira", "ENG-12").unwrap().is_none());
+        assert_eq!(db.find_tracked_issue_for_task(5, 2).unwrap().unwrap().id, issue.id);
+
+        db.update_tracked_issue(issue.id, None, Some("Done")).unwrap();
+        let updated = db.get_tracked_issue(issue.id).unwrap().unwrap();
+        assert_eq!(updated.status.as_deref(), Some("Done"));
+        assert_eq!(updated.title, "Fix login");
+        assert_eq!(db.list_tracked_issues(Some(5), 10).unwrap().len(), 1);
+    }
+}
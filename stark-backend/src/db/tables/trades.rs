@@ -0,0 +1,262 @@
+//! Trade journal database operations (trades)
+//!
+//! One row per swap or transfer the agent queued, keyed by the queued
+//! transaction UUID. Rows start as `queued`, become `executed` when the
+//! transaction is broadcast (or `failed` if it never is, or reverts), and are
+//! priced in USD at their execution time by the journal's pricing worker.
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+pub const TRADE_KIND_SWAP: &str = "swap";
+pub const TRADE_KIND_TRANSFER: &str = "transfer";
+
+pub const TRADE_QUEUED: &str = "queued";
+pub const TRADE_EXECUTED: &str = "executed";
+pub const TRADE_FAILED: &str = "failed";
+
+/// A swap or transfer made by the agent
+#[derive(Debug, Clone, Serialize)]
+pub struct Trade {
+    pub id: i64,
+    /// UUID of the queued transaction
+    pub tx_uuid: String,
+    /// `swap` or `transfer`
+    pub kind: String,
+    pub network: String,
+    /// Token leaving the wallet (lowercase address; native = 0xeeee... sentinel)
+    pub sell_token: String,
+    pub sell_symbol: String,
+    pub sell_amount_raw: String,
+    pub sell_decimals: u8,
+    /// Token received (swaps only), with the quoted amount
+    pub buy_token: Option<String>,
+    pub buy_symbol: Option<String>,
+    pub buy_amount_raw: Option<String>,
+    pub buy_decimals: Option<u8>,
+    /// Transfer recipient
+    pub counterparty: Option<String>,
+    /// USD prices at execution time (None until priced, or if no price was found)
+    pub sell_price_usd: Option<f64>,
+    pub buy_price_usd: Option<f64>,
+    pub priced_at: Option<String>,
+    pub status: String,
+    pub tx_hash: Option<String>,
+    pub channel_id: Option<i64>,
+    pub created_at: String,
+    pub executed_at: Option<String>,
+}
+
+impl Trade {
+    pub fn sell_amount(&self) -> f64 {
+        scale(&self.sell_amount_raw, self.sell_decimals)
+    }
+
+    pub fn buy_amount(&self) -> Option<f64> {
+        Some(scale(self.buy_amount_raw.as_deref()?, self.buy_decimals?))
+    }
+}
+
+/// Raw integer amount (decimal or 0x-hex) in whole tokens
+fn scale(raw: &str, decimals: u8) -> f64 {
+    let raw = raw.trim();
+    let value = match raw.strip_prefix("0x") {
+        Some(hex) => u128::from_str_radix(hex, 16).map(|v| v as f64).unwrap_or(0.0),
+        None => raw.parse::<f64>().unwrap_or(0.0),
+    };
+    value / 10f64.powi(decimals as i32)
+}
+
+/// Fields for a new journal entry
+pub struct RecordTradeRequest {
+    pub tx_uuid: String,
+    pub kind: &'static str,
+    pub network: String,
+    pub sell_token: String,
+    pub sell_symbol: String,
+    pub sell_amount_raw: String,
+    pub sell_decimals: u8,
+    pub buy_token: Option<String>,
+    pub buy_symbol: Option<String>,
+    pub buy_amount_raw: Option<String>,
+    pub buy_decimals: Option<u8>,
+    pub counterparty: Option<String>,
+    pub channel_id: Option<i64>,
+}
+
+const TRADE_COLUMNS: &str = "id, tx_uuid, kind, network, sell_token, sell_symbol, sell_amount_raw, sell_decimals, \
+                             buy_token, buy_symbol, buy_amount_raw, buy_decimals, counterparty, sell_price_usd, buy_price_usd, \
+                             priced_at, status, tx_hash, channel_id, created_at, executed_at";
+
+fn row_to_trade(row: &rusqlite::Row) -> rusqlite::Result<Trade> {
+    Ok(Trade {
+        id: row.get(0)?,
+        tx_uuid: row.get(1)?,
+        kind: row.get(2)?,
+        network: row.get(3)?,
+        sell_token: row.get(4)?,
+        sell_symbol: row.get(5)?,
+        sell_amount_raw: row.get(6)?,
+        sell_decimals: row.get(7)?,
+        buy_token: row.get(8)?,
+        buy_symbol: row.get(9)?,
+        buy_amount_raw: row.get(10)?,
+        buy_decimals: row.get(11)?,
+        counterparty: row.get(12)?,
+        sell_price_usd: row.get(13)?,
+        buy_price_usd: row.get(14)?,
+        priced_at: row.get(15)?,
+        status: row.get(16)?,
+        tx_hash: row.get(17)?,
+        channel_id: row.get(18)?,
+        created_at: row.get(19)?,
+        executed_at: row.get(20)?,
+    })
+}
+
+impl Database {
+    /// Journal a swap or transfer that was just queued
+    pub fn record_trade(&self, req: RecordTradeRequest) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR IGNORE INTO trades (tx_uuid, kind, network, sell_token, sell_symbol, sell_amount_raw, sell_decimals,
+                                           buy_token, buy_symbol, buy_amount_raw, buy_decimals, counterparty, status,
+                                           channel_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            rusqlite::params![
+                req.tx_uuid,
+                req.kind,
+                req.network,
+                req.sell_token.to_lowercase(),
+                req.sell_symbol,
+                req.sell_amount_raw,
+                req.sell_decimals,
+                req.buy_token.map(|t| t.to_lowercase()),
+                req.buy_symbol,
+                req.buy_amount_raw,
+                req.buy_decimals,
+                req.counterparty,
+                TRADE_QUEUED,
+                req.channel_id,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// The queued transaction was broadcast: the trade is now executed
+    pub fn mark_trade_executed(&self, tx_uuid: &str, tx_hash: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE trades SET status = ?1, tx_hash = ?2, executed_at = ?3 WHERE tx_uuid = ?4 AND status = ?5",
+            rusqlite::params![TRADE_EXECUTED, tx_hash, Utc::now().to_rfc3339(), tx_uuid, TRADE_QUEUED],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// The transaction expired unbroadcast, failed to broadcast, or reverted
+    pub fn mark_trade_failed(&self, tx_uuid: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE trades SET status = ?1 WHERE tx_uuid = ?2 AND status IN (?3, ?4)",
+            rusqlite::params![TRADE_FAILED, tx_uuid, TRADE_QUEUED, TRADE_EXECUTED],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Executed trades the pricing worker hasn't handled yet
+    pub fn list_unpriced_trades(&self, limit: usize) -> SqliteResult<Vec<Trade>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM trades WHERE status = ?1 AND priced_at IS NULL ORDER BY id ASC LIMIT ?2",
+            TRADE_COLUMNS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![TRADE_EXECUTED, limit as i64], row_to_trade)?;
+        rows.collect()
+    }
+
+    /// Store execution-time USD prices (None when no price source knew the token)
+    pub fn set_trade_prices(&self, id: i64, sell_price_usd: Option<f64>, buy_price_usd: Option<f64>) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE trades SET sell_price_usd = ?1, buy_price_usd = ?2, priced_at = ?3 WHERE id = ?4",
+            rusqlite::params![sell_price_usd, buy_price_usd, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    /// List trades, newest first, optionally by status and executed since an RFC 3339 time
+    pub fn list_trades(&self, status: Option<&str>, since: Option<&str>, limit: usize) -> SqliteResult<Vec<Trade>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM trades
+             WHERE (?1 IS NULL OR status = ?1) AND (?2 IS NULL OR COALESCE(executed_at, created_at) >= ?2)
+             ORDER BY id DESC LIMIT ?3",
+            TRADE_COLUMNS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![status, since, limit as i64], row_to_trade)?;
+        rows.collect()
+    }
+
+    /// Every executed trade in execution order (the input to PnL reporting)
+    pub fn list_executed_trades(&self) -> SqliteResult<Vec<Trade>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM trades WHERE status = ?1 ORDER BY executed_at ASC, id ASC",
+            TRADE_COLUMNS
+        ))?;
+        let rows = stmt.query_map([TRADE_EXECUTED], row_to_trade)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap(uuid: &str) -> RecordTradeRequest {
+        RecordTradeRequest {
+            tx_uuid: uuid.to_string(),
+            kind: TRADE_KIND_SWAP,
+            network: "base".to_string(),
+            sell_token: "0xUSDC".to_string(),
+            sell_symbol: "USDC".to_string(),
+            sell_amount_raw: "100000000".to_string(),
+            sell_decimals: 6,
+            buy_token: Some("0xWETH".to_string()),
+            buy_symbol: Some("WETH".to_string()),
+            buy_amount_raw: Some("40000000000000000".to_string()),
+            buy_decimals: Some(18),
+            counterparty: None,
+            channel_id: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_trade_lifecycle() {
+        let db = Database::new(":memory:").unwrap();
+        db.record_trade(swap("tx-1")).unwrap();
+        db.record_trade(swap("tx-2")).unwrap();
+
+        assert!(db.mark_trade_executed("tx-1", "0xhash").unwrap());
+        assert!(db.mark_trade_failed("tx-2").unwrap());
+        // A failed trade never becomes executed
+        assert!(!db.mark_trade_executed("tx-2", "0xother").unwrap());
+
+        let unpriced = db.list_unpriced_trades(10).unwrap();
+        assert_eq!(unpriced.len(), 1);
+        assert_eq!(unpriced[0].sell_token, "0xusdc");
+        assert_eq!(unpriced[0].sell_amount(), 100.0);
+        assert_eq!(unpriced[0].buy_amount(), Some(0.04));
+
+        db.set_trade_prices(unpriced[0].id, Some(1.0), Some(2500.0)).unwrap();
+        assert!(db.list_unpriced_trades(10).unwrap().is_empty());
+        let executed = db.list_executed_trades().unwrap();
+        assert_eq!(executed.len(), 1);
+        assert_eq!(executed[0].buy_price_usd, Some(2500.0));
+        assert_eq!(db.list_trades(Some(TRADE_FAILED), None, 10).unwrap().len(), 1);
+    }
+}
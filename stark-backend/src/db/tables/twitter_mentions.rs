@@ -1,6 +1,7 @@
 //! Twitter mentions tracking - prevent duplicate processing of tweets
 //!
 //! Stores processed tweet IDs to avoid responding to the same mention twice.
+//! Direct messages share the table with a `dm:` prefix on the event ID.
 
 use crate::db::Database;
 use rusqlite::Result as SqliteResult;
@@ -42,7 +43,7 @@ impl Database {
         let conn = self.conn();
         let result = conn.query_row(
             "SELECT tweet_id FROM twitter_processed_mentions
-             WHERE channel_id = ?1
+             WHERE channel_id = ?1 AND tweet_id NOT LIKE 'dm:%'
              ORDER BY processed_at DESC LIMIT 1",
             [channel_id],
             |row| row.get(0),
@@ -54,6 +55,18 @@ impl Database {
         }
     }
 
+    /// Whether any direct message has been processed for a channel.
+    /// False on first start, when the existing DM backlog is skipped rather than answered.
+    pub fn has_processed_dms(&self, channel_id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM twitter_processed_mentions WHERE channel_id = ?1 AND tweet_id LIKE 'dm:%'",
+            [channel_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
     /// Clean up old processed mentions (keep last 30 days by default)
     pub fn cleanup_old_processed_mentions(&self, days: i64) -> SqliteResult<usize> {
         let conn = self.conn();
@@ -0,0 +1,232 @@
+//! Outgoing tweet queue (twitter_outgoing_queue)
+//!
+//! Tweets queued by the agent (twitter_post with `queue`) instead of being
+//! posted immediately. The running Twitter channel drains due rows on each
+//! poll tick, backing off when the API reports the posting limit exhausted.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::Serialize;
+
+use super::super::Database;
+
+/// Attempts before a queued tweet is given up on
+pub const MAX_QUEUED_TWEET_ATTEMPTS: i64 = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedTweet {
+    pub id: i64,
+    /// Channel that should post it (None = any running Twitter channel)
+    pub channel_id: Option<i64>,
+    pub text: String,
+    pub reply_to_id: Option<String>,
+    pub quote_tweet_id: Option<String>,
+    /// Session that queued it, for reporting the posted tweet back
+    pub session_id: Option<i64>,
+    /// "pending", "sent", "failed" or "cancelled"
+    pub status: String,
+    pub not_before: String,
+    pub attempts: i64,
+    pub posted_tweet_id: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub sent_at: Option<String>,
+}
+
+/// Fields recorded when a tweet is queued
+pub struct NewQueuedTweet<'a> {
+    pub channel_id: Option<i64>,
+    pub text: &'a str,
+    pub reply_to_id: Option<&'a str>,
+    pub quote_tweet_id: Option<&'a str>,
+    pub session_id: Option<i64>,
+    /// Earliest time to post (None = as soon as possible)
+    pub not_before: Option<DateTime<Utc>>,
+}
+
+const QUEUE_COLS: &str = "id, channel_id, text, reply_to_id, quote_tweet_id, session_id, status, not_before, attempts, posted_tweet_id, last_error, created_at, sent_at";
+
+fn row_to_queued(row: &rusqlite::Row) -> rusqlite::Result<QueuedTweet> {
+    Ok(QueuedTweet {
+        id: row.get(0)?,
+        channel_id: row.get(1)?,
+        text: row.get(2)?,
+        reply_to_id: row.get(3)?,
+        quote_tweet_id: row.get(4)?,
+        session_id: row.get(5)?,
+        status: row.get(6)?,
+        not_before: row.get(7)?,
+        attempts: row.get(8)?,
+        posted_tweet_id: row.get(9)?,
+        last_error: row.get(10)?,
+        created_at: row.get(11)?,
+        sent_at: row.get(12)?,
+    })
+}
+
+impl Database {
+    /// Queue a tweet for the Twitter channel to post
+    pub fn enqueue_tweet(&self, tweet: &NewQueuedTweet) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let now = Utc::now();
+        conn.execute(
+            "INSERT INTO twitter_outgoing_queue
+                (channel_id, text, reply_to_id, quote_tweet_id, session_id, status, not_before, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 'pending', ?6, ?7)",
+            rusqlite::params![
+                tweet.channel_id,
+                tweet.text,
+                tweet.reply_to_id,
+                tweet.quote_tweet_id,
+                tweet.session_id,
+                tweet.not_before.unwrap_or(now).to_rfc3339(),
+                now.to_rfc3339(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn get_queued_tweet(&self, id: i64) -> SqliteResult<Option<QueuedTweet>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM twitter_outgoing_queue WHERE id = ?1", QUEUE_COLS),
+            [id],
+            row_to_queued,
+        )
+        .optional()
+    }
+
+    /// Pending tweets due for a channel, oldest first
+    pub fn list_due_queued_tweets(&self, channel_id: i64, limit: usize) -> SqliteResult<Vec<QueuedTweet>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM twitter_outgoing_queue
+             WHERE status = 'pending' AND not_before <= ?1
+               AND (channel_id IS NULL OR channel_id = ?2)
+             ORDER BY not_before ASC, id ASC LIMIT ?3",
+            QUEUE_COLS
+        ))?;
+        let rows = stmt.query_map(
+            rusqlite::params![Utc::now().to_rfc3339(), channel_id, limit as i64],
+            row_to_queued,
+        )?;
+        rows.collect()
+    }
+
+    pub fn mark_queued_tweet_sent(&self, id: i64, posted_tweet_id: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE twitter_outgoing_queue
+             SET status = 'sent', posted_tweet_id = ?1, last_error = NULL, attempts = attempts + 1, sent_at = ?2
+             WHERE id = ?3",
+            rusqlite::params![posted_tweet_id, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Record a failed post attempt. With `retry_at` the tweet stays pending
+    /// until then, unless it has used up its attempts.
+    pub fn mark_queued_tweet_failed(&self, id: i64, error: &str, retry_at: Option<DateTime<Utc>>) -> SqliteResult<()> {
+        let conn = self.conn();
+        match retry_at {
+            Some(retry_at) => conn.execute(
+                "UPDATE twitter_outgoing_queue
+                 SET attempts = attempts + 1, last_error = ?1, not_before = ?2,
+                     status = CASE WHEN attempts + 1 >= ?3 THEN 'failed' ELSE 'pending' END
+                 WHERE id = ?4",
+                rusqlite::params![error, retry_at.to_rfc3339(), MAX_QUEUED_TWEET_ATTEMPTS, id],
+            )?,
+            None => conn.execute(
+                "UPDATE twitter_outgoing_queue
+                 SET attempts = attempts + 1, last_error = ?1, status = 'failed'
+                 WHERE id = ?2",
+                rusqlite::params![error, id],
+            )?,
+        };
+        Ok(())
+    }
+
+    /// Push every due pending tweet back (used when the posting limit is exhausted)
+    pub fn defer_queued_tweets(&self, channel_id: i64, until: DateTime<Utc>) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE twitter_outgoing_queue SET not_before = ?1
+             WHERE status = 'pending' AND not_before < ?1 AND (channel_id IS NULL OR channel_id = ?2)",
+            rusqlite::params![until.to_rfc3339(), channel_id],
+        )
+    }
+
+    /// Cancel a tweet that has not been posted yet
+    pub fn cancel_queued_tweet(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        Ok(conn.execute(
+            "UPDATE twitter_outgoing_queue SET status = 'cancelled' WHERE id = ?1 AND status = 'pending'",
+            [id],
+        )? > 0)
+    }
+
+    /// List queued tweets, optionally filtered by status, newest first
+    pub fn list_queued_tweets(&self, status: Option<&str>, limit: usize) -> SqliteResult<Vec<QueuedTweet>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM twitter_outgoing_queue WHERE (?1 IS NULL OR status = ?1)
+             ORDER BY id DESC LIMIT ?2",
+            QUEUE_COLS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![status, limit as i64], row_to_queued)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_outgoing_queue_lifecycle() {
+        let db = Database::new(":memory:").unwrap();
+
+        let now_id = db
+            .enqueue_tweet(&NewQueuedTweet {
+                channel_id: None,
+                text: "gm",
+                reply_to_id: None,
+                quote_tweet_id: None,
+                session_id: Some(3),
+                not_before: None,
+            })
+            .unwrap();
+        let later_id = db
+            .enqueue_tweet(&NewQueuedTweet {
+                channel_id: Some(9),
+                text: "later",
+                reply_to_id: Some("123"),
+                quote_tweet_id: None,
+                session_id: None,
+                not_before: Some(Utc::now() + Duration::hours(1)),
+            })
+            .unwrap();
+
+        // Only the due tweet is returned; channel-less rows match any channel
+        let due = db.list_due_queued_tweets(1, 10).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, now_id);
+
+        // A retryable failure keeps it pending but pushes it back
+        db.mark_queued_tweet_failed(now_id, "429", Some(Utc::now() + Duration::minutes(15))).unwrap();
+        assert!(db.list_due_queued_tweets(1, 10).unwrap().is_empty());
+        let queued = db.get_queued_tweet(now_id).unwrap().unwrap();
+        assert_eq!(queued.status, "pending");
+        assert_eq!(queued.attempts, 1);
+
+        db.mark_queued_tweet_sent(now_id, "555").unwrap();
+        let queued = db.get_queued_tweet(now_id).unwrap().unwrap();
+        assert_eq!(queued.status, "sent");
+        assert_eq!(queued.posted_tweet_id.as_deref(), Some("555"));
+
+        assert!(db.cancel_queued_tweet(later_id).unwrap());
+        assert!(!db.cancel_queued_tweet(now_id).unwrap());
+        assert_eq!(db.list_queued_tweets(Some("cancelled"), 10).unwrap().len(), 1);
+    }
+}
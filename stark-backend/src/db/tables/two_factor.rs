@@ -0,0 +1,228 @@
+//! Second factor database operations (two_factor, two_factor_recovery_codes,
+//! two_factor_failures, auth_sessions.second_factor_at)
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+
+use super::super::encryption::{decrypt_field, encrypt_message};
+use super::super::Database;
+
+/// The enrolled (or pending) TOTP secret
+#[derive(Debug, Clone)]
+pub struct TwoFactor {
+    /// Base32 secret, decrypted
+    pub secret: String,
+    /// False until the first code is confirmed
+    pub enabled: bool,
+    pub last_used_step: Option<i64>,
+    pub enabled_at: Option<String>,
+}
+
+impl Database {
+    pub fn get_two_factor(&self) -> SqliteResult<Option<TwoFactor>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT secret, enabled, last_used_step, enabled_at FROM two_factor WHERE id = 1",
+            [],
+            |row| {
+                Ok(TwoFactor {
+                    secret: decrypt_field(row.get(0)?),
+                    enabled: row.get::<_, i64>(1)? != 0,
+                    last_used_step: row.get(2)?,
+                    enabled_at: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+    }
+
+    /// Whether sensitive actions need a second factor
+    pub fn is_two_factor_enabled(&self) -> SqliteResult<bool> {
+        Ok(self.get_two_factor()?.is_some_and(|tf| tf.enabled))
+    }
+
+    /// Start enrollment with a new secret, replacing any earlier pending one
+    pub fn save_pending_two_factor(&self, secret: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR REPLACE INTO two_factor (id, secret, enabled, last_used_step, created_at, enabled_at)
+             VALUES (1, ?1, 0, NULL, ?2, NULL)",
+            rusqlite::params![encrypt_message(secret)?, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Finish enrollment: turn the pending secret on and store the recovery codes
+    pub fn enable_two_factor(&self, step: i64, recovery_code_hashes: &[String]) -> SqliteResult<()> {
+        let conn = self.conn();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE two_factor SET enabled = 1, last_used_step = ?1, enabled_at = ?2 WHERE id = 1",
+            rusqlite::params![step, Utc::now().to_rfc3339()],
+        )?;
+        replace_recovery_codes(&tx, recovery_code_hashes)?;
+        tx.commit()
+    }
+
+    /// Remove the secret, its recovery codes and every session's verification
+    pub fn disable_two_factor(&self) -> SqliteResult<()> {
+        let conn = self.conn();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM two_factor", [])?;
+        tx.execute("DELETE FROM two_factor_recovery_codes", [])?;
+        tx.execute("DELETE FROM two_factor_failures", [])?;
+        tx.execute("UPDATE auth_sessions SET second_factor_at = NULL", [])?;
+        tx.commit()
+    }
+
+    /// Remember the step of an accepted code so it can't be used again.
+    /// Returns false if that step (or a later one) was already used.
+    pub fn set_two_factor_last_step(&self, step: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let changed = conn.execute(
+            "UPDATE two_factor SET last_used_step = ?1
+             WHERE id = 1 AND (last_used_step IS NULL OR last_used_step < ?1)",
+            [step],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Until when `subject` may not try another code
+    pub fn two_factor_locked_until(&self, subject: &str) -> SqliteResult<Option<DateTime<Utc>>> {
+        let conn = self.conn();
+        let until: Option<Option<String>> = conn
+            .query_row(
+                "SELECT locked_until FROM two_factor_failures WHERE subject = ?1",
+                [subject],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(until
+            .flatten()
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|d| d.with_timezone(&Utc))
+            .filter(|until| *until > Utc::now()))
+    }
+
+    /// Count a wrong code against `subject`, locking it out once it has
+    /// `max_failures`. Returns the new failure count.
+    pub fn record_two_factor_failure(&self, subject: &str, max_failures: i64) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let tx = conn.unchecked_transaction()?;
+        let failures: i64 = tx.query_row(
+            "INSERT INTO two_factor_failures (subject, failures) VALUES (?1, 1)
+             ON CONFLICT(subject) DO UPDATE SET failures = failures + 1
+             RETURNING failures",
+            [subject],
+            |row| row.get(0),
+        )?;
+        if let Some(secs) = crate::two_factor::lockout_secs(failures, max_failures) {
+            tx.execute(
+                "UPDATE two_factor_failures SET locked_until = ?1 WHERE subject = ?2",
+                rusqlite::params![(Utc::now() + Duration::seconds(secs)).to_rfc3339(), subject],
+            )?;
+        }
+        tx.commit()?;
+        Ok(failures)
+    }
+
+    /// Forget the failures of `subjects` after a correct code
+    pub fn clear_two_factor_failures(&self, subjects: &[&str]) -> SqliteResult<()> {
+        let conn = self.conn();
+        for subject in subjects {
+            conn.execute("DELETE FROM two_factor_failures WHERE subject = ?1", [subject])?;
+        }
+        Ok(())
+    }
+
+    /// Swap all recovery codes for new ones
+    pub fn replace_two_factor_recovery_codes(&self, hashes: &[String]) -> SqliteResult<()> {
+        let conn = self.conn();
+        let tx = conn.unchecked_transaction()?;
+        replace_recovery_codes(&tx, hashes)?;
+        tx.commit()
+    }
+
+    /// Use up a recovery code. Returns false if it is unknown or already used.
+    pub fn use_two_factor_recovery_code(&self, hash: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let changed = conn.execute(
+            "UPDATE two_factor_recovery_codes SET used_at = ?1 WHERE code_hash = ?2 AND used_at IS NULL",
+            rusqlite::params![Utc::now().to_rfc3339(), hash],
+        )?;
+        Ok(changed > 0)
+    }
+
+    pub fn count_unused_two_factor_recovery_codes(&self) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT COUNT(*) FROM two_factor_recovery_codes WHERE used_at IS NULL",
+            [],
+            |row| row.get(0),
+        )
+    }
+
+    /// Record that the session behind `token` just passed a second-factor check
+    pub fn mark_session_second_factor(&self, token: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE auth_sessions SET second_factor_at = ?1 WHERE token = ?2",
+            rusqlite::params![Utc::now().to_rfc3339(), token],
+        )?;
+        Ok(())
+    }
+
+    /// When the session behind `token` last passed a second-factor check
+    pub fn session_second_factor_at(&self, token: &str) -> SqliteResult<Option<DateTime<Utc>>> {
+        let conn = self.conn();
+        let at: Option<Option<String>> = conn
+            .query_row(
+                "SELECT second_factor_at FROM auth_sessions WHERE token = ?1",
+                [token],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(at
+            .flatten()
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|d| d.with_timezone(&Utc)))
+    }
+}
+
+fn replace_recovery_codes(conn: &rusqlite::Connection, hashes: &[String]) -> SqliteResult<()> {
+    conn.execute("DELETE FROM two_factor_recovery_codes", [])?;
+    let now = Utc::now().to_rfc3339();
+    for hash in hashes {
+        conn.execute(
+            "INSERT INTO two_factor_recovery_codes (code_hash, created_at) VALUES (?1, ?2)",
+            rusqlite::params![hash, now],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_are_used_once_and_failures_lock_out() {
+        let db = Database::new(":memory:").unwrap();
+        db.save_pending_two_factor("JBSWY3DPEHPK3PXP").unwrap();
+        db.enable_two_factor(100, &[]).unwrap();
+        assert!(!db.set_two_factor_last_step(100).unwrap());
+        assert!(db.set_two_factor_last_step(101).unwrap());
+        assert!(!db.set_two_factor_last_step(101).unwrap());
+
+        for _ in 1..3 {
+            db.record_two_factor_failure("session:1", 3).unwrap();
+        }
+        assert!(db.two_factor_locked_until("session:1").unwrap().is_none());
+        assert_eq!(db.record_two_factor_failure("session:1", 3).unwrap(), 3);
+        assert!(db.two_factor_locked_until("session:1").unwrap().is_some());
+        assert!(db.two_factor_locked_until("session:2").unwrap().is_none());
+
+        db.clear_two_factor_failures(&["session:1"]).unwrap();
+        assert!(db.two_factor_locked_until("session:1").unwrap().is_none());
+    }
+}
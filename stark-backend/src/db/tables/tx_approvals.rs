@@ -0,0 +1,204 @@
+//! Transaction approval database operations (tx_approvals)
+//!
+//! One row per queued transaction that needed the owner's sign-off. Decided
+//! rows are never deleted: the decision, who made it, when, and the broadcast
+//! outcome stay behind as the audit trail. A decision can only be recorded once, and
+//! only while the approval is still pending and unexpired.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+pub const TX_APPROVAL_PENDING: &str = "pending";
+pub const TX_APPROVAL_APPROVED: &str = "approved";
+pub const TX_APPROVAL_REJECTED: &str = "rejected";
+pub const TX_APPROVAL_EXPIRED: &str = "expired";
+
+/// An approval request for a queued transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct TxApproval {
+    pub id: i64,
+    pub tx_uuid: String,
+    pub channel_id: i64,
+    pub chat_id: String,
+    pub network: String,
+    pub to_address: String,
+    pub value_wei: String,
+    pub summary: String,
+    pub status: String,
+    pub requested_at: String,
+    pub expires_at: String,
+    pub decided_at: Option<String>,
+    pub decided_by: Option<String>,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+const TX_APPROVAL_COLUMNS: &str = "id, tx_uuid, channel_id, chat_id, network, to_address, value_wei, summary, status, \
+                                   requested_at, expires_at, decided_at, decided_by, tx_hash, error";
+
+fn row_to_tx_approval(row: &rusqlite::Row) -> rusqlite::Result<TxApproval> {
+    Ok(TxApproval {
+        id: row.get(0)?,
+        tx_uuid: row.get(1)?,
+        channel_id: row.get(2)?,
+        chat_id: row.get(3)?,
+        network: row.get(4)?,
+        to_address: row.get(5)?,
+        value_wei: row.get(6)?,
+        summary: row.get(7)?,
+        status: row.get(8)?,
+        requested_at: row.get(9)?,
+        expires_at: row.get(10)?,
+        decided_at: row.get(11)?,
+        decided_by: row.get(12)?,
+        tx_hash: row.get(13)?,
+        error: row.get(14)?,
+    })
+}
+
+impl Database {
+    /// Record a pending approval for a queued transaction
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_tx_approval(
+        &self,
+        tx_uuid: &str,
+        channel_id: i64,
+        chat_id: &str,
+        network: &str,
+        to_address: &str,
+        value_wei: &str,
+        summary: &str,
+        expires_at: DateTime<Utc>,
+    ) -> SqliteResult<TxApproval> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO tx_approvals (tx_uuid, channel_id, chat_id, network, to_address, value_wei, summary, status, requested_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                tx_uuid,
+                channel_id,
+                chat_id,
+                network,
+                to_address,
+                value_wei,
+                summary,
+                TX_APPROVAL_PENDING,
+                Utc::now().to_rfc3339(),
+                expires_at.to_rfc3339()
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.query_row(
+            &format!("SELECT {} FROM tx_approvals WHERE id = ?1", TX_APPROVAL_COLUMNS),
+            [id],
+            row_to_tx_approval,
+        )
+    }
+
+    /// Get the approval for a transaction, if it needed one
+    pub fn get_tx_approval(&self, tx_uuid: &str) -> SqliteResult<Option<TxApproval>> {
+        let conn = self.conn();
+        match conn.query_row(
+            &format!("SELECT {} FROM tx_approvals WHERE tx_uuid = ?1", TX_APPROVAL_COLUMNS),
+            [tx_uuid],
+            row_to_tx_approval,
+        ) {
+            Ok(approval) => Ok(Some(approval)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Record the owner's decision. Returns false if the approval is missing,
+    /// already decided, or expired.
+    pub fn decide_tx_approval(&self, tx_uuid: &str, status: &str, decided_by: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let updated = conn.execute(
+            "UPDATE tx_approvals SET status = ?1, decided_by = ?2, decided_at = ?3
+             WHERE tx_uuid = ?4 AND status = ?5 AND expires_at > ?3",
+            rusqlite::params![status, decided_by, now, tx_uuid, TX_APPROVAL_PENDING],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Record the broadcast outcome of an approved transaction
+    pub fn record_tx_approval_result(&self, tx_uuid: &str, tx_hash: Option<&str>, error: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE tx_approvals SET tx_hash = ?1, error = ?2 WHERE tx_uuid = ?3",
+            rusqlite::params![tx_hash, error, tx_uuid],
+        )?;
+        Ok(())
+    }
+
+    /// Delete an approval that was never delivered to the owner
+    pub fn delete_tx_approval(&self, tx_uuid: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM tx_approvals WHERE tx_uuid = ?1 AND status = ?2", rusqlite::params![tx_uuid, TX_APPROVAL_PENDING])?;
+        Ok(())
+    }
+
+    /// Mark every pending approval past its deadline as expired and return them
+    pub fn expire_tx_approvals(&self, now: DateTime<Utc>) -> SqliteResult<Vec<TxApproval>> {
+        let conn = self.conn();
+        let tx = conn.unchecked_transaction()?;
+        let now = now.to_rfc3339();
+        let expired = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT {} FROM tx_approvals WHERE status = ?1 AND expires_at <= ?2",
+                TX_APPROVAL_COLUMNS
+            ))?;
+            let rows = stmt.query_map(rusqlite::params![TX_APPROVAL_PENDING, now], row_to_tx_approval)?;
+            rows.collect::<SqliteResult<Vec<_>>>()?
+        };
+        tx.execute(
+            "UPDATE tx_approvals SET status = ?1, decided_at = ?2 WHERE status = ?3 AND expires_at <= ?2",
+            rusqlite::params![TX_APPROVAL_EXPIRED, now, TX_APPROVAL_PENDING],
+        )?;
+        tx.commit()?;
+        Ok(expired)
+    }
+
+    /// List approvals, newest first
+    pub fn list_tx_approvals(&self, status: Option<&str>, limit: usize) -> SqliteResult<Vec<TxApproval>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM tx_approvals WHERE ?1 IS NULL OR status = ?1 ORDER BY id DESC LIMIT ?2",
+            TX_APPROVAL_COLUMNS
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![status, limit as i64], row_to_tx_approval)?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_decision_is_recorded_once_and_expiry_wins() {
+        let db = Database::new(":memory:").unwrap();
+        let in_future = Utc::now() + Duration::minutes(15);
+        db.create_tx_approval("tx-1", 1, "42", "base", "0xabc", "1000", "Send 1000 wei", in_future).unwrap();
+
+        assert!(db.decide_tx_approval("tx-1", TX_APPROVAL_APPROVED, "alice").unwrap());
+        // A second press (or a late reject) can't overwrite the decision
+        assert!(!db.decide_tx_approval("tx-1", TX_APPROVAL_REJECTED, "bob").unwrap());
+        let approval = db.get_tx_approval("tx-1").unwrap().unwrap();
+        assert_eq!(approval.status, TX_APPROVAL_APPROVED);
+        assert_eq!(approval.decided_by.as_deref(), Some("alice"));
+
+        // Past its deadline: can't be approved, and the sweep expires it
+        db.create_tx_approval("tx-2", 1, "42", "base", "0xabc", "1000", "Send 1000 wei", Utc::now() - Duration::seconds(1)).unwrap();
+        assert!(!db.decide_tx_approval("tx-2", TX_APPROVAL_APPROVED, "alice").unwrap());
+        let expired = db.expire_tx_approvals(Utc::now()).unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].tx_uuid, "tx-2");
+        assert_eq!(db.list_tx_approvals(Some(TX_APPROVAL_EXPIRED), 10).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,293 @@
+//! Reusable workflow templates (workflow_templates, session_plans)
+//!
+//! A template is a parameterized plan: tasks whose descriptions contain
+//! `{{param}}` placeholders, with optional dependencies on earlier tasks.
+//! Templates are saved from the last plan a session ran (session_plans is
+//! updated every time define_tasks replaces the queue) and instantiated into
+//! an ordered task list for the orchestrator's queue.
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::super::Database;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TemplateParam {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Value used when instantiation doesn't supply one
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TemplateTask {
+    pub description: String,
+    /// Indices (0-based) of tasks that must run before this one
+    #[serde(default)]
+    pub depends_on: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowTemplate {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub params: Vec<TemplateParam>,
+    pub tasks: Vec<TemplateTask>,
+    pub source_session_id: Option<i64>,
+    pub use_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// The most recent plan a session executed
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionPlan {
+    pub session_id: i64,
+    pub request: String,
+    pub tasks: Vec<String>,
+    pub updated_at: String,
+}
+
+/// Replace literal values in plan tasks with `{{param}}` placeholders.
+/// Longer values are replaced first so overlapping literals don't clobber each other.
+pub fn parameterize(tasks: &[String], values: &HashMap<String, String>) -> Vec<TemplateTask> {
+    let mut ordered: Vec<(&String, &String)> = values.iter().filter(|(_, v)| !v.is_empty()).collect();
+    ordered.sort_by_key(|(_, v)| std::cmp::Reverse(v.len()));
+    tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let mut description = task.clone();
+            for (name, value) in &ordered {
+                description = description.replace(value.as_str(), &format!("{{{{{}}}}}", name));
+            }
+            TemplateTask {
+                description,
+                // Saved plans ran sequentially: each task follows the previous one
+                depends_on: if i == 0 { vec![] } else { vec![i - 1] },
+            }
+        })
+        .collect()
+}
+
+/// Order tasks so every task comes after its dependencies (stable for independent tasks)
+fn dependency_order(tasks: &[TemplateTask]) -> Result<Vec<usize>, String> {
+    let mut order = Vec::with_capacity(tasks.len());
+    let mut placed = vec![false; tasks.len()];
+    while order.len() < tasks.len() {
+        let next = (0..tasks.len()).find(|&i| {
+            !placed[i] && tasks[i].depends_on.iter().all(|&d| d < tasks.len() && placed[d])
+        });
+        match next {
+            Some(i) => {
+                placed[i] = true;
+                order.push(i);
+            }
+            None => {
+                return Err("Template has a dependency cycle or a dependency on a missing task".to_string())
+            }
+        }
+    }
+    Ok(order)
+}
+
+impl WorkflowTemplate {
+    /// Render the template into ordered task descriptions
+    pub fn instantiate(&self, args: &HashMap<String, String>) -> Result<Vec<String>, String> {
+        let mut values = HashMap::new();
+        for param in &self.params {
+            match args.get(&param.name).or(param.default.as_ref()) {
+                Some(v) => {
+                    values.insert(param.name.clone(), v.clone());
+                }
+                None => return Err(format!("Missing value for parameter '{}'", param.name)),
+            }
+        }
+        let order = dependency_order(&self.tasks)?;
+        Ok(order
+            .into_iter()
+            .map(|i| {
+                let mut description = self.tasks[i].description.clone();
+                for (name, value) in &values {
+                    description = description.replace(&format!("{{{{{}}}}}", name), value);
+                }
+                description
+            })
+            .collect())
+    }
+}
+
+const TEMPLATE_COLS: &str =
+    "id, name, description, params, tasks, source_session_id, use_count, created_at, updated_at";
+
+fn row_to_template(row: &rusqlite::Row) -> rusqlite::Result<WorkflowTemplate> {
+    let params: String = row.get(3)?;
+    let tasks: String = row.get(4)?;
+    Ok(WorkflowTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        params: serde_json::from_str(&params).unwrap_or_default(),
+        tasks: serde_json::from_str(&tasks).unwrap_or_default(),
+        source_session_id: row.get(5)?,
+        use_count: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+impl Database {
+    /// Create or replace a template by name
+    pub fn save_workflow_template(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        params: &[TemplateParam],
+        tasks: &[TemplateTask],
+        source_session_id: Option<i64>,
+    ) -> SqliteResult<WorkflowTemplate> {
+        let now = Utc::now().to_rfc3339();
+        {
+            let conn = self.conn();
+            conn.execute(
+                "INSERT INTO workflow_templates (name, description, params, tasks, source_session_id, use_count, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, ?6)
+                 ON CONFLICT(name) DO UPDATE SET
+                    description = excluded.description,
+                    params = excluded.params,
+                    tasks = excluded.tasks,
+                    source_session_id = excluded.source_session_id,
+                    updated_at = excluded.updated_at",
+                rusqlite::params![
+                    name,
+                    description,
+                    serde_json::to_string(params).unwrap_or_else(|_| "[]".to_string()),
+                    serde_json::to_string(tasks).unwrap_or_else(|_| "[]".to_string()),
+                    source_session_id,
+                    now,
+                ],
+            )?;
+        }
+        self.get_workflow_template(name)?
+            .ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    pub fn get_workflow_template(&self, name: &str) -> SqliteResult<Option<WorkflowTemplate>> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM workflow_templates WHERE name = ?1", TEMPLATE_COLS),
+            [name],
+            row_to_template,
+        )
+        .optional()
+    }
+
+    /// All templates, most used first
+    pub fn list_workflow_templates(&self) -> SqliteResult<Vec<WorkflowTemplate>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM workflow_templates ORDER BY use_count DESC, name ASC",
+            TEMPLATE_COLS
+        ))?;
+        let rows = stmt.query_map([], row_to_template)?;
+        rows.collect()
+    }
+
+    pub fn delete_workflow_template(&self, name: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let deleted = conn.execute("DELETE FROM workflow_templates WHERE name = ?1", [name])?;
+        Ok(deleted > 0)
+    }
+
+    pub fn mark_workflow_template_used(&self, name: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE workflow_templates SET use_count = use_count + 1 WHERE name = ?1",
+            [name],
+        )?;
+        Ok(())
+    }
+
+    /// Remember the plan a session just started (called when define_tasks replaces the queue)
+    pub fn record_session_plan(&self, session_id: i64, request: &str, tasks: &[String]) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO session_plans (session_id, request, tasks, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(session_id) DO UPDATE SET
+                request = excluded.request,
+                tasks = excluded.tasks,
+                updated_at = excluded.updated_at",
+            rusqlite::params![
+                session_id,
+                request,
+                serde_json::to_string(tasks).unwrap_or_else(|_| "[]".to_string()),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_session_plan(&self, session_id: i64) -> SqliteResult<Option<SessionPlan>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT session_id, request, tasks, updated_at FROM session_plans WHERE session_id = ?1",
+            [session_id],
+            |row| {
+                let tasks: String = row.get(2)?;
+                Ok(SessionPlan {
+                    session_id: row.get(0)?,
+                    request: row.get(1)?,
+                    tasks: serde_json::from_str(&tasks).unwrap_or_default(),
+                    updated_at: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_plan_as_template_and_instantiate() {
+        let db = Database::new(":memory:").unwrap();
+        let plan = vec![
+            "Bump version to 1.4.0 in Cargo.toml".to_string(),
+            "Tag release v1.4.0 and push".to_string(),
+        ];
+        db.record_session_plan(7, "release 1.4.0", &plan).unwrap();
+        let saved = db.get_session_plan(7).unwrap().unwrap();
+
+        let values = HashMap::from([("version".to_string(), "1.4.0".to_string())]);
+        let tasks = parameterize(&saved.tasks, &values);
+        assert_eq!(tasks[1].description, "Tag release v{{version}} and push");
+        assert_eq!(tasks[1].depends_on, vec![0]);
+
+        let params = vec![TemplateParam { name: "version".to_string(), description: None, default: None }];
+        db.save_workflow_template("release-checklist", None, &params, &tasks, Some(7)).unwrap();
+        let template = db.get_workflow_template("release-checklist").unwrap().unwrap();
+
+        let args = HashMap::from([("version".to_string(), "2.0.0".to_string())]);
+        assert_eq!(
+            template.instantiate(&args).unwrap(),
+            vec!["Bump version to 2.0.0 in Cargo.toml", "Tag release v2.0.0 and push"]
+        );
+        assert!(template.instantiate(&HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let task = |d: &str, deps: Vec<usize>| TemplateTask { description: d.to_string(), depends_on: deps };
+        let order = dependency_order(&[task("c", vec![1]), task("a", vec![]), task("b", vec![1])]).unwrap();
+        assert_eq!(order, vec![1, 0, 2]);
+        assert!(dependency_order(&[task("x", vec![1]), task("y", vec![0])]).is_err());
+    }
+}
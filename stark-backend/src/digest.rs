@@ -0,0 +1,274 @@
+//! Scheduled digest of agent activity
+//!
+//! Once a day (or week) at the configured local hour, the digest worker
+//! gathers what the agent did over the period — runs and tool calls,
+//! transactions, skills used, notable memories — for the enabled sections,
+//! asks the AI client to turn those facts into a short digest, and sends it to
+//! the configured chat (the owner's chat by default). If no AI endpoint is
+//! available the facts are sent as they are. Every digest is kept in
+//! `digest_runs`.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Duration, Local, Timelike, Utc};
+
+use crate::ai::{AiClient, Message, MessageRole};
+use crate::channels::outbound;
+use crate::db::tables::digests::{DigestConfig, DigestRun};
+use crate::db::Database;
+
+pub const SECTION_EXECUTIONS: &str = "executions";
+pub const SECTION_TRANSACTIONS: &str = "transactions";
+pub const SECTION_SKILLS: &str = "skills";
+pub const SECTION_MEMORIES: &str = "memories";
+
+pub const ALL_SECTIONS: &[&str] = &[SECTION_EXECUTIONS, SECTION_TRANSACTIONS, SECTION_SKILLS, SECTION_MEMORIES];
+
+pub const FREQUENCY_DAILY: &str = "daily";
+pub const FREQUENCY_WEEKLY: &str = "weekly";
+
+/// Items listed per section
+const MAX_ITEMS: usize = 10;
+/// Memories at least this important count as notable
+const NOTABLE_MEMORY_IMPORTANCE: i64 = 7;
+const MEMORY_PREVIEW_CHARS: usize = 200;
+
+/// Length of the period a digest covers
+pub fn period_length(config: &DigestConfig) -> Duration {
+    if config.frequency == FREQUENCY_WEEKLY {
+        Duration::days(7)
+    } else {
+        Duration::days(1)
+    }
+}
+
+/// Whether a scheduled digest should go out now: past the send hour on a send
+/// day, and none sent yet for this day (or, weekly, in the last six days).
+pub fn is_due(config: &DigestConfig, now: DateTime<Local>, last_sent: Option<DateTime<Local>>) -> bool {
+    if !config.enabled || now.hour() < config.send_hour {
+        return false;
+    }
+    let weekly = config.frequency == FREQUENCY_WEEKLY;
+    if weekly && now.weekday().num_days_from_monday() != config.weekday {
+        return false;
+    }
+    match last_sent {
+        None => true,
+        Some(last) if weekly => now - last >= Duration::days(6),
+        Some(last) => last.date_naive() < now.date_naive(),
+    }
+}
+
+/// Render the enabled sections as plain facts for the period starting at `since`
+pub fn collect_facts(db: &Database, sections: &[String], since: DateTime<Utc>) -> String {
+    let since = since.to_rfc3339();
+    let mut out = Vec::new();
+    let enabled = |section: &str| sections.iter().any(|s| s == section);
+
+    if enabled(SECTION_EXECUTIONS) {
+        match db.digest_execution_stats(&since, 5) {
+            Ok(stats) => {
+                let mut text = format!(
+                    "## Executions\n{} runs ({} succeeded, {} failed, {} cancelled); {} tool calls, {} failed.",
+                    stats.total, stats.succeeded, stats.failed, stats.cancelled, stats.tool_calls, stats.failed_tool_calls
+                );
+                if !stats.top_tools.is_empty() {
+                    let tools: Vec<String> = stats.top_tools.iter().map(|(t, n)| format!("{} ({})", t, n)).collect();
+                    text.push_str(&format!("\nMost used tools: {}", tools.join(", ")));
+                }
+                out.push(text);
+            }
+            Err(e) => log::warn!("[DIGEST] Failed to load execution stats: {}", e),
+        }
+    }
+
+    if enabled(SECTION_TRANSACTIONS) {
+        match db.digest_transactions(&since, MAX_ITEMS) {
+            Ok(txs) if txs.is_empty() => out.push("## Transactions\nNone.".to_string()),
+            Ok(txs) => {
+                let mut text = format!("## Transactions\n{} transaction(s):", txs.len());
+                for tx in txs {
+                    text.push_str(&format!(
+                        "\n- {} to {} on {} ({}){}",
+                        tx.value_formatted,
+                        tx.to_address,
+                        tx.network,
+                        tx.status,
+                        tx.tx_hash.map(|h| format!(" {}", h)).unwrap_or_default()
+                    ));
+                }
+                out.push(text);
+            }
+            Err(e) => log::warn!("[DIGEST] Failed to load transactions: {}", e),
+        }
+    }
+
+    if enabled(SECTION_SKILLS) {
+        match db.digest_skill_runs(&since, MAX_ITEMS) {
+            Ok(skills) if skills.is_empty() => out.push("## Skills\nNo skills run.".to_string()),
+            Ok(skills) => {
+                let list: Vec<String> = skills.iter().map(|(s, n)| format!("{} ({})", s, n)).collect();
+                out.push(format!("## Skills\n{}", list.join(", ")));
+            }
+            Err(e) => log::warn!("[DIGEST] Failed to load skill runs: {}", e),
+        }
+    }
+
+    if enabled(SECTION_MEMORIES) {
+        match db.digest_notable_memories(&since, NOTABLE_MEMORY_IMPORTANCE, MAX_ITEMS) {
+            Ok(memories) if memories.is_empty() => out.push("## Notable memories\nNone.".to_string()),
+            Ok(memories) => {
+                let mut text = "## Notable memories".to_string();
+                for memory in memories {
+                    let preview: String = memory.content.split_whitespace().collect::<Vec<_>>().join(" ");
+                    let preview: String = preview.chars().take(MEMORY_PREVIEW_CHARS).collect();
+                    text.push_str(&format!("\n- [{} {}] {}", memory.memory_type, memory.importance, preview));
+                }
+                out.push(text);
+            }
+            Err(e) => log::warn!("[DIGEST] Failed to load memories: {}", e),
+        }
+    }
+
+    out.join("\n\n")
+}
+
+/// Turn the facts into a natural-language digest. Falls back to the facts.
+async fn write_digest(db: &Database, period: &str, facts: &str) -> String {
+    let fallback = format!("**Activity digest — {}**\n\n{}", period, facts);
+    let client = match db.get_active_agent_settings() {
+        Ok(Some(settings)) => match AiClient::from_settings(&settings) {
+            Ok(client) => client,
+            Err(e) => {
+                log::warn!("[DIGEST] Failed to create AI client: {}", e);
+                return fallback;
+            }
+        },
+        _ => return fallback,
+    };
+    let messages = vec![
+        Message {
+            role: MessageRole::System,
+            content: "You write short activity digests for the owner of an AI agent. Use only the facts given. \
+                      Lead with what matters most (failures, money moved, notable findings), keep it under 200 words, \
+                      and use brief markdown bullets."
+                .to_string(),
+        },
+        Message {
+            role: MessageRole::User,
+            content: format!("Write the digest for {}.\n\nFacts:\n{}", period, facts),
+        },
+    ];
+    match client.generate_text(messages).await {
+        Ok(text) if !text.trim().is_empty() => format!("**Activity digest — {}**\n\n{}", period, text.trim()),
+        Ok(_) => fallback,
+        Err(e) => {
+            log::warn!("[DIGEST] Failed to generate digest text: {}", e);
+            fallback
+        }
+    }
+}
+
+/// Generate a digest for the period ending now and deliver it. Recorded in
+/// `digest_runs` either way; errors only if there's nowhere to send it.
+pub async fn run_digest(db: &Database, config: &DigestConfig) -> Result<DigestRun, String> {
+    let target = match (config.channel_id, config.chat_id.clone().filter(|c| !c.is_empty())) {
+        (Some(channel_id), Some(chat_id)) => (channel_id, chat_id),
+        _ => db
+            .get_bot_settings()
+            .ok()
+            .and_then(|s| s.owner_chat())
+            .ok_or_else(|| "No digest chat configured and no owner chat set in bot settings".to_string())?,
+    };
+
+    let end = Utc::now();
+    let start = end - period_length(config);
+    let period = if config.frequency == FREQUENCY_WEEKLY {
+        format!("week of {}", start.with_timezone(&Local).format("%b %-d"))
+    } else {
+        end.with_timezone(&Local).format("%a %b %-d").to_string()
+    };
+    let facts = collect_facts(db, &config.section_list(), start);
+    let content = write_digest(db, &period, &facts).await;
+
+    let error = outbound::send_proactive(db, target.0, &target.1, &content, "digest").await.err();
+    if let Some(ref e) = error {
+        log::warn!("[DIGEST] Failed to deliver digest: {}", e);
+    }
+    let id = db
+        .record_digest_run(&start.to_rfc3339(), &end.to_rfc3339(), &content, error.is_none(), error.as_deref())
+        .map_err(|e| format!("Failed to record digest: {}", e))?;
+    Ok(DigestRun {
+        id,
+        period_start: start.to_rfc3339(),
+        period_end: end.to_rfc3339(),
+        content,
+        delivered: error.is_none(),
+        error,
+        created_at: Utc::now().to_rfc3339(),
+    })
+}
+
+/// Spawn the digest worker: every `interval_secs` it sends the digest if one is due
+pub fn spawn_digest_worker(
+    db: Arc<Database>,
+    interval_secs: u64,
+    is_leader: impl Fn() -> bool + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if !is_leader() {
+                continue;
+            }
+            let config = match db.get_digest_config() {
+                Ok(c) if c.enabled => c,
+                _ => continue,
+            };
+            let last_sent = config
+                .last_sent_at
+                .as_deref()
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .map(|t| t.with_timezone(&Local));
+            if !is_due(&config, Local::now(), last_sent) {
+                continue;
+            }
+            // Mark first so a failed delivery isn't retried (and re-generated) every pass
+            let _ = db.mark_digest_sent(&Utc::now().to_rfc3339());
+            match run_digest(&db, &config).await {
+                Ok(run) => log::info!("[DIGEST] Digest {} generated (delivered: {})", run.id, run.delivered),
+                Err(e) => log::warn!("[DIGEST] Digest skipped: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        // June 2026: the 1st is a Monday
+        Local.with_ymd_and_hms(2026, 6, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_is_due() {
+        let mut config = DigestConfig { enabled: true, send_hour: 8, ..Default::default() };
+        assert!(!is_due(&config, at(2, 7), None));
+        assert!(is_due(&config, at(2, 9), None));
+        assert!(!is_due(&config, at(2, 20), Some(at(2, 8))));
+        assert!(is_due(&config, at(3, 8), Some(at(2, 8))));
+
+        config.frequency = FREQUENCY_WEEKLY.to_string();
+        config.weekday = 0;
+        assert!(!is_due(&config, at(2, 9), None));
+        assert!(is_due(&config, at(8, 9), Some(at(1, 8))));
+        assert!(!is_due(&config, at(8, 9), Some(at(8, 8))));
+
+        config.enabled = false;
+        assert!(!is_due(&config, at(8, 9), None));
+    }
+}
@@ -20,6 +20,8 @@ pub mod tools;
 use rand::seq::SliceRandom;
 use serenity::all::{Context, Message, UserId};
 
+use crate::channels::group_gate::{Addressing, GateDecision, GroupGate};
+
 pub use config::DiscordHooksConfig;
 pub use db::DiscordUserProfile;
 
@@ -32,6 +34,8 @@ pub struct ProcessResult {
     pub response: Option<String>,
     /// Request to forward to the agent (if admin command)
     pub forward_to_agent: Option<ForwardRequest>,
+    /// Keep the message as session context without answering (passive group chat)
+    pub remember: bool,
 }
 
 impl ProcessResult {
@@ -41,6 +45,15 @@ impl ProcessResult {
             handled: false,
             response: None,
             forward_to_agent: None,
+            remember: false,
+        }
+    }
+
+    /// Message didn't summon the bot but should be kept as context
+    pub fn remember() -> Self {
+        Self {
+            remember: true,
+            ..Self::not_handled()
         }
     }
 
@@ -50,6 +63,7 @@ impl ProcessResult {
             handled: true,
             response: Some(response),
             forward_to_agent: None,
+            remember: false,
         }
     }
 
@@ -59,6 +73,7 @@ impl ProcessResult {
             handled: true,
             response: None,
             forward_to_agent: Some(request),
+            remember: false,
         }
     }
 }
@@ -164,8 +179,10 @@ pub async fn process(
         is_reply_to_bot
     );
 
-    // Check if bot is mentioned OR if user is replying to the bot
-    if !is_reply_to_bot && !is_bot_mentioned(msg, bot_id) {
+    // Answer per the channel's group triggers (mention and reply by default)
+    let addressing = Addressing { mentioned: is_bot_mentioned(msg, bot_id), reply_to_bot: is_reply_to_bot };
+    let decision = GroupGate::load(db, channel_id).decide(&msg.content, addressing);
+    if decision != GateDecision::Respond {
         // Check if they mentioned a role the bot has (common mistake)
         if !msg.mention_roles.is_empty() {
             if let Some(guild_id) = msg.guild_id {
@@ -185,14 +202,19 @@ pub async fn process(
                 }
             }
         }
-        // In DMs, we might want to process without mention
-        // For now, require mention in all contexts
+        if decision == GateDecision::Remember {
+            return Ok(ProcessResult::remember());
+        }
         return Ok(ProcessResult::not_handled());
     }
 
     // Extract command text (remove bot mention)
     let command_text = extract_command_text(&msg.content, bot_id);
 
+    // Without a mention an empty message (e.g. only an image) isn't a greeting
+    if command_text.is_empty() && !addressing.mentioned {
+        return Ok(ProcessResult::not_handled());
+    }
     if command_text.is_empty() {
         return Ok(ProcessResult::handled(
             "Hi! I'm StarkBot. Try `@starkbot help` to see available commands.".to_string(),
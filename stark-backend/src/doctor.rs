@@ -0,0 +1,379 @@
+//! Configuration doctor — a self-test of the settings the bot depends on
+//!
+//! A bad API key, an unreachable embeddings server or a revoked channel token
+//! otherwise only shows up as an error the first time something needs it.
+//! The doctor probes each of them up front (at startup, after channels have
+//! been started, and on demand via `POST /api/admin/doctor`) and returns one
+//! finding per check, each with a suggested fix when something is wrong.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::ai::{AiClient, Message, MessageRole};
+use crate::channels::ChannelManager;
+use crate::db::Database;
+use crate::disk_quota::DiskQuotaManager;
+use crate::models::{ChannelType, DEFAULT_EMBEDDINGS_SERVER_URL};
+use crate::wallet::WalletProvider;
+
+/// Timeout for each network probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+/// The AI probe is a real (one-word) completion and gets longer
+const AI_PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Disk usage above this share of the quota is worth a warning
+const DISK_WARN_PERCENT: u64 = 90;
+
+/// Tables and columns added by the most recent migrations. Migrations run
+/// with errors ignored, so a missing one here means one failed silently.
+const EXPECTED_SCHEMA: &[(&str, &[&str])] = &[
+    ("bot_settings", &["read_only_mode", "cors_allowed_origins"]),
+    ("heartbeat_configs", &["impulse_evolver", "maintenance_jobs"]),
+    ("maintenance_runs", &["job", "success"]),
+    ("knowledge_entities", &["entity_type", "mention_count"]),
+    ("knowledge_relations", &["relation_type", "strength"]),
+    ("session_messages", &["sentiment", "urgency"]),
+    ("away_requests", &["chat_id", "force_safe_mode"]),
+    ("handoffs", &["status", "operator"]),
+    ("tool_confirmations", &["kind"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingStatus {
+    Ok,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub check: String,
+    pub status: FindingStatus,
+    pub message: String,
+    /// What to change when the check did not pass
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    fn ok(check: &str, message: impl Into<String>) -> Self {
+        Finding { check: check.to_string(), status: FindingStatus::Ok, message: message.into(), fix: None }
+    }
+
+    fn warn(check: &str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Finding { check: check.to_string(), status: FindingStatus::Warn, message: message.into(), fix: Some(fix.into()) }
+    }
+
+    fn error(check: &str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Finding { check: check.to_string(), status: FindingStatus::Error, message: message.into(), fix: Some(fix.into()) }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DoctorReport {
+    /// False when any check ended in an error
+    pub healthy: bool,
+    pub checked_at: String,
+    pub findings: Vec<Finding>,
+}
+
+/// Run every check. Network probes run concurrently.
+pub async fn run(
+    db: &Database,
+    channel_manager: &ChannelManager,
+    disk_quota: Option<&DiskQuotaManager>,
+    wallet_provider: Option<Arc<dyn WalletProvider>>,
+) -> DoctorReport {
+    let (ai, embeddings, alchemy, channels) = tokio::join!(
+        check_ai_provider(db, wallet_provider),
+        check_embeddings(db),
+        check_alchemy(),
+        check_channels(db, channel_manager),
+    );
+
+    let mut findings = vec![ai, embeddings, alchemy];
+    findings.extend(channels);
+    findings.push(check_disk_quota(disk_quota));
+    findings.push(check_migrations(db));
+
+    DoctorReport {
+        healthy: findings.iter().all(|f| f.status != FindingStatus::Error),
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        findings,
+    }
+}
+
+/// Log each finding at a level matching its status
+pub fn log_report(report: &DoctorReport) {
+    for finding in &report.findings {
+        let fix = finding.fix.as_deref().map(|fix| format!(" — fix: {}", fix)).unwrap_or_default();
+        match finding.status {
+            FindingStatus::Ok => log::info!("[DOCTOR] {}: {}", finding.check, finding.message),
+            FindingStatus::Warn => log::warn!("[DOCTOR] {}: {}{}", finding.check, finding.message, fix),
+            FindingStatus::Error => log::error!("[DOCTOR] {}: {}{}", finding.check, finding.message, fix),
+        }
+    }
+}
+
+/// The active AI endpoint answers with the configured key (or wallet, for
+/// x402/credits endpoints)
+async fn check_ai_provider(db: &Database, wallet_provider: Option<Arc<dyn WalletProvider>>) -> Finding {
+    const CHECK: &str = "ai_provider";
+    let settings = match db.get_active_agent_settings() {
+        Ok(Some(settings)) => settings,
+        Ok(None) => {
+            return Finding::error(CHECK, "No AI endpoint is configured", "Select a model under Settings > Agent")
+        }
+        Err(e) => return Finding::error(CHECK, format!("Failed to load agent settings: {}", e), "Check the database"),
+    };
+    if settings.payment_mode == "none" {
+        return Finding::warn(CHECK, "AI is disabled (payment_mode=none)", "Select a model under Settings > Agent");
+    }
+
+    let wallet_paid = matches!(settings.payment_mode.as_str(), "x402" | "credits")
+        || crate::x402::is_x402_endpoint(&settings.endpoint);
+    if wallet_paid {
+        // Probing would spend funds; a configured wallet is as far as we go
+        return match wallet_provider {
+            Some(_) => Finding::ok(CHECK, format!("{} is paid from the bot wallet", settings.endpoint)),
+            None => Finding::error(
+                CHECK,
+                format!("{} is paid via {} but no wallet is configured", settings.endpoint, settings.payment_mode),
+                "Configure a wallet or switch to an endpoint with an API key",
+            ),
+        };
+    }
+    if settings.secret_key.as_deref().is_none_or(|key| key.trim().is_empty()) {
+        return Finding::error(
+            CHECK,
+            format!("No API key set for {}", settings.endpoint),
+            "Add the provider's API key under Settings > Agent",
+        );
+    }
+
+    let client = match AiClient::from_settings_with_wallet_provider(&settings, wallet_provider) {
+        Ok(client) => client,
+        Err(e) => return Finding::error(CHECK, e, "Review the endpoint and model under Settings > Agent"),
+    };
+    let ping = vec![Message { role: MessageRole::User, content: "Reply with OK.".to_string() }];
+    match tokio::time::timeout(AI_PROBE_TIMEOUT, client.generate_text(ping)).await {
+        Ok(Ok(_)) => Finding::ok(CHECK, format!("{} accepted the API key", settings.endpoint)),
+        Ok(Err(e)) => {
+            let lower = e.to_lowercase();
+            let fix = if ["401", "403", "unauthorized", "invalid api key", "authentication"]
+                .iter()
+                .any(|needle| lower.contains(needle))
+            {
+                "The provider rejected the API key; replace it under Settings > Agent"
+            } else {
+                "Check the endpoint URL and model name under Settings > Agent"
+            };
+            Finding::error(CHECK, format!("Test completion failed: {}", e), fix)
+        }
+        Err(_) => Finding::error(
+            CHECK,
+            format!("{} did not answer within {}s", settings.endpoint, AI_PROBE_TIMEOUT.as_secs()),
+            "Check that the endpoint is reachable from this host",
+        ),
+    }
+}
+
+/// The embeddings server (used for memory and skill search) is reachable
+async fn check_embeddings(db: &Database) -> Finding {
+    const CHECK: &str = "embeddings";
+    let url = db
+        .get_bot_settings()
+        .ok()
+        .and_then(|s| s.embeddings_server_url)
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| DEFAULT_EMBEDDINGS_SERVER_URL.to_string());
+    let health_url = format!("{}/health", url.trim_end_matches('/'));
+    match crate::http::shared_client().get(&health_url).timeout(PROBE_TIMEOUT).send().await {
+        Ok(resp) if resp.status().is_success() => Finding::ok(CHECK, format!("{} is reachable", url)),
+        Ok(resp) => Finding::warn(
+            CHECK,
+            format!("{} answered {}", health_url, resp.status()),
+            "Memory search falls back to full-text only; check the embeddings server URL under Settings > Bot",
+        ),
+        Err(e) => Finding::warn(
+            CHECK,
+            format!("{} is unreachable: {}", url, e),
+            "Memory search falls back to full-text only; check the embeddings server URL under Settings > Bot",
+        ),
+    }
+}
+
+/// The Alchemy key, when set, is accepted by Alchemy
+async fn check_alchemy() -> Finding {
+    const CHECK: &str = "alchemy";
+    let Some(url) = crate::tools::rpc_config::alchemy_rpc_url("mainnet") else {
+        return Finding::warn(
+            CHECK,
+            "No Alchemy API key set; RPC calls use public endpoints",
+            "Add ALCHEMY_API_KEY under API Keys for reliable RPC and log queries",
+        );
+    };
+    let body = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber", "params": [] });
+    let resp = match crate::http::shared_client().post(&url).json(&body).timeout(PROBE_TIMEOUT).send().await {
+        Ok(resp) => resp,
+        Err(e) => {
+            return Finding::warn(CHECK, format!("Alchemy is unreachable: {}", e), "Check outbound network access")
+        }
+    };
+    let status = resp.status();
+    let json: serde_json::Value = resp.json().await.unwrap_or_default();
+    if status.is_success() && json.get("result").is_some() {
+        Finding::ok(CHECK, "Alchemy API key is valid")
+    } else {
+        let detail = json
+            .pointer("/error/message")
+            .and_then(|m| m.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| status.to_string());
+        Finding::error(CHECK, format!("Alchemy rejected the API key: {}", detail), "Replace ALCHEMY_API_KEY under API Keys")
+    }
+}
+
+/// Every enabled channel is running, and chat platforms accept its token
+async fn check_channels(db: &Database, channel_manager: &ChannelManager) -> Vec<Finding> {
+    let channels = match db.list_enabled_channels() {
+        Ok(channels) => channels,
+        Err(e) => return vec![Finding::error("channels", format!("Failed to list channels: {}", e), "Check the database")],
+    };
+    let mut findings = Vec::new();
+    for channel in channels {
+        let check = format!("channel:{}", channel.name);
+        let fix = format!("Update the credentials of '{}' under Channels, or disable it", channel.name);
+        let auth = match channel.channel_type_enum() {
+            Some(ChannelType::Telegram | ChannelType::Discord) if channel.bot_token.trim().is_empty() => {
+                Err("no bot token set".to_string())
+            }
+            Some(ChannelType::Slack)
+                if channel.bot_token.trim().is_empty()
+                    || channel.app_token.as_deref().is_none_or(|t| t.trim().is_empty()) =>
+            {
+                Err("bot token or app token missing".to_string())
+            }
+            Some(channel_type) => probe_channel_token(channel_type, &channel.bot_token).await,
+            None => Err(format!("unknown channel type '{}'", channel.channel_type)),
+        };
+        let finding = match auth {
+            Err(e) => Finding::error(&check, format!("{} channel: {}", channel.channel_type, e), fix),
+            Ok(()) if !channel_manager.is_running(channel.id) => Finding::warn(
+                &check,
+                format!("{} channel is enabled but not running", channel.channel_type),
+                format!("Check the logs for why '{}' failed to start, then restart it", channel.name),
+            ),
+            Ok(()) => Finding::ok(&check, format!("{} channel is connected", channel.channel_type)),
+        };
+        findings.push(finding);
+    }
+    findings
+}
+
+/// Ask the platform who the token belongs to. Platforms without a cheap
+/// identity call are taken as authenticated.
+async fn probe_channel_token(channel_type: ChannelType, token: &str) -> Result<(), String> {
+    let client = crate::http::shared_client();
+    let request = match channel_type {
+        ChannelType::Telegram => client.get(format!("https://api.telegram.org/bot{}/getMe", token)),
+        ChannelType::Discord => {
+            client.get("https://discord.com/api/v10/users/@me").header("Authorization", format!("Bot {}", token))
+        }
+        ChannelType::Slack => client.post("https://slack.com/api/auth.test").bearer_auth(token),
+        _ => return Ok(()),
+    };
+    let resp = request
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("platform unreachable: {}", e))?;
+    let status = resp.status();
+    let json: serde_json::Value = resp.json().await.unwrap_or_default();
+    // Telegram and Slack answer 200 with ok=false for bad tokens
+    let ok = status.is_success() && json.get("ok").and_then(|v| v.as_bool()).unwrap_or(true);
+    if ok {
+        Ok(())
+    } else {
+        let detail = json
+            .get("error")
+            .or_else(|| json.get("description"))
+            .or_else(|| json.get("message"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| status.to_string());
+        Err(format!("token rejected ({})", detail))
+    }
+}
+
+fn check_disk_quota(disk_quota: Option<&DiskQuotaManager>) -> Finding {
+    const CHECK: &str = "disk_quota";
+    let Some(quota) = disk_quota.filter(|q| q.is_enabled()) else {
+        return Finding::warn(
+            CHECK,
+            "No disk quota set; workspace and memory files can grow without limit",
+            "Set STARK_DISK_QUOTA_MB to a non-zero size to cap disk usage",
+        );
+    };
+    let percent = quota.usage_percentage();
+    if percent >= 100 {
+        Finding::error(CHECK, quota.status_line(), "Free space (e.g. via /api/system/cleanup/workspace) or raise STARK_DISK_QUOTA_MB")
+    } else if percent >= DISK_WARN_PERCENT {
+        Finding::warn(CHECK, quota.status_line(), "Free space (e.g. via /api/system/cleanup/workspace) or raise STARK_DISK_QUOTA_MB")
+    } else {
+        Finding::ok(CHECK, quota.status_line())
+    }
+}
+
+/// The tables and columns of recent migrations exist
+fn check_migrations(db: &Database) -> Finding {
+    const CHECK: &str = "db_migrations";
+    let conn = db.conn();
+    let mut missing = Vec::new();
+    for (table, columns) in EXPECTED_SCHEMA {
+        let present: Vec<String> = conn
+            .prepare("SELECT name FROM pragma_table_info(?1)")
+            .and_then(|mut stmt| {
+                stmt.query_map([table], |row| row.get(0))?.collect::<rusqlite::Result<Vec<String>>>()
+            })
+            .unwrap_or_default();
+        if present.is_empty() {
+            missing.push(table.to_string());
+            continue;
+        }
+        missing.extend(
+            columns
+                .iter()
+                .filter(|column| !present.iter().any(|p| p == *column))
+                .map(|column| format!("{}.{}", table, column)),
+        );
+    }
+    if missing.is_empty() {
+        Finding::ok(CHECK, "Database schema is up to date")
+    } else {
+        Finding::error(
+            CHECK,
+            format!("Missing from the database schema: {}", missing.join(", ")),
+            "Check the startup log for migration errors; restore from a backup if the database file is damaged",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_checks() {
+        let db = Database::new(":memory:").unwrap();
+        assert_eq!(check_migrations(&db).status, FindingStatus::Ok);
+        db.conn().execute_batch("DROP TABLE maintenance_runs").unwrap();
+        let finding = check_migrations(&db);
+        assert_eq!(finding.status, FindingStatus::Error);
+        assert!(finding.message.contains("maintenance_runs"));
+        assert!(finding.fix.is_some());
+
+        assert_eq!(check_disk_quota(None).status, FindingStatus::Warn);
+    }
+}
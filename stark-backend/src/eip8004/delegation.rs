@@ -0,0 +1,451 @@
+//! Delegation to remote agents
+//!
+//! Evaluates the services a discovered EIP-8004 agent advertises and sends it
+//! a subtask over A2A (`message/send`) or an x402 entrypoint. Any payment the
+//! remote agent asks for is capped by the caller's budget, and the returned
+//! result is checked before it is handed back to the local agent.
+
+use super::discovery::SearchCriteria;
+use super::types::{DiscoveredAgent, ServiceEntry, TrustLevel};
+use crate::x402::{X402Client, X402PaymentInfo};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Timeout for a single remote call
+const REMOTE_TIMEOUT_SECS: u64 = 120;
+
+/// Remote result text kept for the local agent
+pub const MAX_RESULT_CHARS: usize = 20_000;
+
+/// How a task is sent to a remote agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DelegationProtocol {
+    /// A2A JSON-RPC `message/send`
+    A2a,
+    /// x402 agent entrypoint (`/entrypoints/{name}/invoke`)
+    X402Entrypoint,
+}
+
+impl DelegationProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DelegationProtocol::A2a => "a2a",
+            DelegationProtocol::X402Entrypoint => "x402_entrypoint",
+        }
+    }
+}
+
+/// A service of a remote agent that tasks can be delegated to
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceCandidate {
+    pub protocol: DelegationProtocol,
+    pub endpoint: String,
+    pub version: String,
+}
+
+/// What the local agent needs to decide whether to delegate to an agent
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentEvaluation {
+    pub agent_id: u64,
+    pub name: String,
+    pub description: String,
+    pub active: bool,
+    pub x402_support: bool,
+    pub trust_level: TrustLevel,
+    pub reputation_count: u64,
+    pub average_score: f64,
+    pub services: Vec<String>,
+    pub candidates: Vec<ServiceCandidate>,
+}
+
+impl AgentEvaluation {
+    pub fn can_delegate(&self) -> bool {
+        self.active && !self.candidates.is_empty()
+    }
+}
+
+fn is_http(endpoint: &str) -> bool {
+    endpoint.starts_with("https://") || endpoint.starts_with("http://")
+}
+
+fn candidate(protocol: DelegationProtocol, service: &ServiceEntry) -> ServiceCandidate {
+    ServiceCandidate {
+        protocol,
+        endpoint: service.endpoint.trim_end_matches('/').to_string(),
+        version: service.version.clone(),
+    }
+}
+
+/// Services that tasks can be sent to, preferred first (A2A, then x402 entrypoints)
+pub fn delegation_candidates(agent: &DiscoveredAgent) -> Vec<ServiceCandidate> {
+    let Some(reg) = agent.registration.as_ref() else {
+        return Vec::new();
+    };
+    let http_services = || reg.services.iter().filter(|s| is_http(&s.endpoint));
+
+    let mut candidates: Vec<ServiceCandidate> = http_services()
+        .filter(|s| s.name.eq_ignore_ascii_case("a2a"))
+        .map(|s| candidate(DelegationProtocol::A2a, s))
+        .collect();
+    if reg.x402_support {
+        candidates.extend(
+            http_services()
+                .filter(|s| s.name.eq_ignore_ascii_case("x402") || s.name.eq_ignore_ascii_case("web"))
+                .map(|s| candidate(DelegationProtocol::X402Entrypoint, s)),
+        );
+    }
+    candidates
+}
+
+pub fn evaluate(agent: &DiscoveredAgent) -> AgentEvaluation {
+    let reg = agent.registration.as_ref();
+    AgentEvaluation {
+        agent_id: agent.identifier.agent_id,
+        name: reg.map(|r| r.name.clone()).unwrap_or_default(),
+        description: reg.map(|r| r.description.clone()).unwrap_or_default(),
+        active: agent.is_active(),
+        x402_support: agent.is_x402_enabled(),
+        trust_level: agent.trust_level(),
+        reputation_count: agent.reputation.as_ref().map(|r| r.count).unwrap_or(0),
+        average_score: agent.reputation.as_ref().map(|r| r.average_score).unwrap_or(0.0),
+        services: reg.map(|r| r.services.iter().map(|s| s.name.clone()).collect()).unwrap_or_default(),
+        candidates: delegation_candidates(agent),
+    }
+}
+
+/// Whether the agent's on-chain trust meets `minimum`
+pub fn meets_trust(agent: &DiscoveredAgent, minimum: TrustLevel) -> bool {
+    SearchCriteria {
+        min_trust_level: Some(minimum),
+        ..Default::default()
+    }
+    .matches(agent)
+}
+
+/// Result returned by a remote agent
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RemoteResult {
+    /// A2A task state, if the agent answered with a task
+    pub state: Option<String>,
+    pub task_id: Option<String>,
+    pub text: String,
+    /// Structured output, if any
+    pub data: Option<Value>,
+}
+
+/// Checks applied to a remote result before it is accepted
+#[derive(Debug, Clone, Default)]
+pub struct ResultCheck {
+    /// Case-insensitive substrings the result text must contain
+    pub must_contain: Vec<String>,
+    /// Top-level fields the structured output must contain
+    pub required_fields: Vec<String>,
+}
+
+impl ResultCheck {
+    pub fn verify(&self, result: &RemoteResult) -> Result<(), String> {
+        if let Some(state) = result.state.as_deref() {
+            if state != "completed" {
+                return Err(format!("Remote task ended in state \"{}\"", state));
+            }
+        }
+        if result.text.trim().is_empty() && result.data.is_none() {
+            return Err("Remote agent returned an empty result".to_string());
+        }
+        let text = result.text.to_lowercase();
+        if let Some(missing) = self.must_contain.iter().find(|s| !text.contains(&s.to_lowercase())) {
+            return Err(format!("Result does not mention \"{}\"", missing));
+        }
+        if !self.required_fields.is_empty() {
+            let Some(Value::Object(data)) = result.data.as_ref() else {
+                return Err("Result has no structured output to check required fields against".to_string());
+            };
+            if let Some(missing) = self.required_fields.iter().find(|f| !data.contains_key(f.as_str())) {
+                return Err(format!("Result is missing field \"{}\"", missing));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// JSON-RPC body of an A2A `message/send` call
+pub fn a2a_request(task: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": uuid::Uuid::new_v4().to_string(),
+        "method": "message/send",
+        "params": {
+            "message": {
+                "role": "user",
+                "kind": "message",
+                "messageId": uuid::Uuid::new_v4().to_string(),
+                "parts": [{ "kind": "text", "text": task }],
+            },
+            "configuration": { "blocking": true },
+        },
+    })
+}
+
+/// Collect text and the first data part from A2A parts
+fn collect_parts(parts: &Value, text: &mut Vec<String>, data: &mut Option<Value>) {
+    for part in parts.as_array().into_iter().flatten() {
+        if let Some(t) = part.get("text").and_then(|t| t.as_str()) {
+            text.push(t.to_string());
+        } else if let Some(d) = part.get("data") {
+            if data.is_none() {
+                *data = Some(d.clone());
+            }
+        }
+    }
+}
+
+/// Parse an A2A JSON-RPC response (a Message or a Task)
+pub fn parse_a2a_response(body: &Value) -> Result<RemoteResult, String> {
+    if let Some(error) = body.get("error") {
+        let message = error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error");
+        return Err(format!("Remote agent error: {}", message));
+    }
+    let result = body.get("result").ok_or("A2A response has no result")?;
+
+    let mut text = Vec::new();
+    let mut data = None;
+    let mut state = None;
+    let mut task_id = None;
+
+    if result.get("kind").and_then(|k| k.as_str()) == Some("task") || result.get("status").is_some() {
+        task_id = result.get("id").and_then(|i| i.as_str()).map(String::from);
+        state = result
+            .pointer("/status/state")
+            .and_then(|s| s.as_str())
+            .map(String::from);
+        for artifact in result.get("artifacts").and_then(|a| a.as_array()).into_iter().flatten() {
+            collect_parts(&artifact["parts"], &mut text, &mut data);
+        }
+        if text.is_empty() && data.is_none() {
+            if let Some(parts) = result.pointer("/status/message/parts") {
+                collect_parts(parts, &mut text, &mut data);
+            }
+        }
+    } else {
+        collect_parts(&result["parts"], &mut text, &mut data);
+    }
+
+    Ok(RemoteResult {
+        state,
+        task_id,
+        text: text.join("\n"),
+        data,
+    })
+}
+
+/// Parse an x402 entrypoint response (`{"output": ...}` or any JSON)
+pub fn parse_entrypoint_response(body: &Value) -> RemoteResult {
+    let output = body.get("output").unwrap_or(body);
+    let text = match output {
+        Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    };
+    RemoteResult {
+        state: body.get("status").and_then(|s| s.as_str()).map(|s| match s {
+            "succeeded" | "success" | "ok" => "completed".to_string(),
+            other => other.to_string(),
+        }),
+        task_id: body.get("run_id").and_then(|r| r.as_str()).map(String::from),
+        text,
+        data: output.is_object().then(|| output.clone()),
+    }
+}
+
+/// Client that sends tasks to remote agents, paying through x402 within a budget
+pub struct RemoteAgentClient {
+    x402: X402Client,
+}
+
+impl RemoteAgentClient {
+    /// `x402` should carry the delegation budget (`X402Client::with_max_payment`)
+    pub fn new(x402: X402Client) -> Self {
+        Self { x402 }
+    }
+
+    /// A2A services may advertise their agent card; the card's `url` is the RPC endpoint
+    async fn resolve_a2a_url(&self, endpoint: &str) -> Result<String, String> {
+        if !endpoint.ends_with(".json") {
+            return Ok(endpoint.to_string());
+        }
+        let card: Value = crate::http::shared_client()
+            .get(endpoint)
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch agent card: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid agent card: {}", e))?;
+        card.get("url")
+            .and_then(|u| u.as_str())
+            .filter(|u| is_http(u))
+            .map(String::from)
+            .ok_or_else(|| "Agent card has no http(s) url".to_string())
+    }
+
+    /// Send a task and return the parsed result plus any payment made
+    pub async fn delegate(
+        &self,
+        service: &ServiceCandidate,
+        task: &str,
+        entrypoint: Option<&str>,
+        input: Option<&Value>,
+    ) -> Result<(RemoteResult, Option<X402PaymentInfo>), String> {
+        let (url, body) = match service.protocol {
+            DelegationProtocol::A2a => (self.resolve_a2a_url(&service.endpoint).await?, a2a_request(task)),
+            DelegationProtocol::X402Entrypoint => {
+                let entrypoint = entrypoint
+                    .filter(|e| !e.trim().is_empty())
+                    .ok_or("An entrypoint is required to delegate to an x402 agent")?;
+                let input = input.cloned().unwrap_or_else(|| json!({ "task": task }));
+                (
+                    format!("{}/entrypoints/{}/invoke", service.endpoint, entrypoint.trim()),
+                    json!({ "input": input }),
+                )
+            }
+        };
+
+        let call = self.x402.post_with_payment(&url, &body);
+        let response = tokio::time::timeout(Duration::from_secs(REMOTE_TIMEOUT_SECS), call)
+            .await
+            .map_err(|_| format!("Remote agent did not answer within {}s", REMOTE_TIMEOUT_SECS))??;
+
+        let status = response.response.status();
+        let text = response.response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(format!("Remote agent returned HTTP {}: {}", status, truncate(&text, 500)));
+        }
+        let json: Value = serde_json::from_str(&text).map_err(|_| format!("Remote agent returned non-JSON: {}", truncate(&text, 500)))?;
+
+        let mut result = match service.protocol {
+            DelegationProtocol::A2a => parse_a2a_response(&json)?,
+            DelegationProtocol::X402Entrypoint => parse_entrypoint_response(&json),
+        };
+        result.text = truncate(&result.text, MAX_RESULT_CHARS);
+        Ok((result, response.payment))
+    }
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        s.to_string()
+    } else {
+        format!("{}…", s.chars().take(max_chars).collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eip8004::types::{AgentIdentifier, RegistrationFile};
+
+    fn agent(services: Vec<(&str, &str)>, x402: bool) -> DiscoveredAgent {
+        let mut reg = RegistrationFile::new("Remote", "does things");
+        reg.x402_support = x402;
+        reg.services = services
+            .into_iter()
+            .map(|(name, endpoint)| ServiceEntry {
+                name: name.to_string(),
+                endpoint: endpoint.to_string(),
+                version: "1.0".to_string(),
+            })
+            .collect();
+        DiscoveredAgent {
+            identifier: AgentIdentifier {
+                agent_id: 5,
+                agent_registry: "eip155:8453:0xreg".to_string(),
+            },
+            registration: Some(reg),
+            owner_address: "0xowner".to_string(),
+            wallet_address: None,
+            reputation: None,
+            discovered_at: String::new(),
+            last_updated: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_delegation_candidates() {
+        let a = agent(
+            vec![
+                ("web", "https://remote.example/"),
+                ("A2A", "https://remote.example/.well-known/agent-card.json"),
+                ("mcp", "https://remote.example/mcp"),
+                ("a2a", "ipfs://nope"),
+            ],
+            true,
+        );
+        let candidates = delegation_candidates(&a);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].protocol, DelegationProtocol::A2a);
+        assert_eq!(candidates[1].protocol, DelegationProtocol::X402Entrypoint);
+        assert_eq!(candidates[1].endpoint, "https://remote.example");
+
+        let no_x402 = agent(vec![("web", "https://remote.example")], false);
+        assert!(delegation_candidates(&no_x402).is_empty());
+        assert!(meets_trust(&no_x402, TrustLevel::Unverified));
+        assert!(!meets_trust(&no_x402, TrustLevel::Low));
+    }
+
+    #[test]
+    fn test_parse_a2a_task_and_message() {
+        let task = json!({"jsonrpc": "2.0", "id": "1", "result": {
+            "kind": "task", "id": "t1",
+            "status": {"state": "completed"},
+            "artifacts": [{"parts": [{"kind": "text", "text": "ETH is 3000"}, {"kind": "data", "data": {"price": 3000}}]}]
+        }});
+        let result = parse_a2a_response(&task).unwrap();
+        assert_eq!(result.state.as_deref(), Some("completed"));
+        assert_eq!(result.task_id.as_deref(), Some("t1"));
+        assert_eq!(result.text, "ETH is 3000");
+        assert_eq!(result.data, Some(json!({"price": 3000})));
+
+        let message = json!({"result": {"kind": "message", "parts": [{"kind": "text", "text": "hi"}]}});
+        assert_eq!(parse_a2a_response(&message).unwrap().text, "hi");
+
+        let error = json!({"error": {"code": -32600, "message": "bad"}});
+        assert!(parse_a2a_response(&error).unwrap_err().contains("bad"));
+    }
+
+    #[test]
+    fn test_result_check() {
+        let result = RemoteResult {
+            state: Some("completed".to_string()),
+            task_id: None,
+            text: "ETH is 3000".to_string(),
+            data: Some(json!({"price": 3000})),
+        };
+        let check = ResultCheck {
+            must_contain: vec!["eth".to_string()],
+            required_fields: vec!["price".to_string()],
+        };
+        assert!(check.verify(&result).is_ok());
+
+        let check = ResultCheck {
+            must_contain: vec!["btc".to_string()],
+            ..Default::default()
+        };
+        assert!(check.verify(&result).is_err());
+
+        let failed = RemoteResult {
+            state: Some("failed".to_string()),
+            ..result
+        };
+        assert!(ResultCheck::default().verify(&failed).is_err());
+    }
+
+    #[test]
+    fn test_parse_entrypoint_response() {
+        let result = parse_entrypoint_response(&json!({"run_id": "r1", "status": "succeeded", "output": {"joke": "x"}}));
+        assert_eq!(result.state.as_deref(), Some("completed"));
+        assert_eq!(result.data, Some(json!({"joke": "x"})));
+        assert!(ResultCheck::default().verify(&result).is_ok());
+    }
+}
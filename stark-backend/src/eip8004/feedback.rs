@@ -0,0 +1,211 @@
+//! Signed off-chain feedback for this agent
+//!
+//! Clients rate the agent by POSTing a submission signed (EIP-191) by the
+//! address they rate from. Accepted feedback is stored in `reputation_feedback`
+//! and aggregated into the `reputation` block of the registration file.
+
+use super::abi::common::keccak256;
+use super::types::{RegistrationFile, ReputationScore, ServiceEntry, TrustLevel};
+use crate::db::sqlite::AgentIdentityRow;
+use crate::db::Database;
+use ethers::types::{Address, Signature};
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Service name of the feedback endpoint in the registration file
+pub const FEEDBACK_SERVICE_NAME: &str = "feedback";
+
+/// Maximum clock skew accepted for a submission timestamp (seconds)
+pub const MAX_TIMESTAMP_SKEW_SECS: i64 = 600;
+
+const MAX_TAG_LEN: usize = 64;
+const MAX_URI_LEN: usize = 512;
+const MAX_COMMENT_LEN: usize = 2000;
+
+/// Public URL where signed feedback is accepted
+pub fn feedback_endpoint() -> String {
+    format!("{}/api/feedback", crate::config::self_url())
+}
+
+/// A feedback submission as POSTed by a client
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedbackSubmission {
+    pub agent_id: u64,
+    pub client_address: String,
+    /// Score from -100 to 100
+    pub value: i64,
+    #[serde(default)]
+    pub tag1: String,
+    #[serde(default)]
+    pub tag2: String,
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default)]
+    pub feedback_uri: String,
+    #[serde(default)]
+    pub comment: String,
+    #[serde(default)]
+    pub proof_of_payment_tx: String,
+    /// Unix seconds at signing time
+    pub timestamp: i64,
+    /// EIP-191 signature over `signing_message`
+    pub signature: String,
+}
+
+impl FeedbackSubmission {
+    /// Canonical message the client signs. The comment is bound by its hash.
+    pub fn signing_message(&self, agent_registry: &str) -> String {
+        format!(
+            "EIP-8004 Feedback\nagentRegistry: {}\nagentId: {}\nclient: {}\nvalue: {}\ntag1: {}\ntag2: {}\nendpoint: {}\nfeedbackURI: {}\ncommentHash: 0x{}\nproofOfPayment: {}\ntimestamp: {}",
+            agent_registry,
+            self.agent_id,
+            self.client_address.to_lowercase(),
+            self.value,
+            self.tag1,
+            self.tag2,
+            self.endpoint,
+            self.feedback_uri,
+            hex::encode(keccak256(self.comment.as_bytes())),
+            self.proof_of_payment_tx,
+            self.timestamp,
+        )
+    }
+
+    /// Validate the submission against this agent and recover its signer.
+    /// Returns the hash of the signed message, used as the feedback hash.
+    pub fn verify(&self, agent_id: u64, agent_registry: &str, now: i64) -> Result<String, String> {
+        if self.agent_id != agent_id {
+            return Err(format!("Feedback is for agent {}, this is agent {}", self.agent_id, agent_id));
+        }
+        if !(-100..=100).contains(&self.value) {
+            return Err("value must be between -100 and 100".to_string());
+        }
+        if (now - self.timestamp).abs() > MAX_TIMESTAMP_SKEW_SECS {
+            return Err("timestamp is too far from the current time — sign a fresh submission".to_string());
+        }
+        if self.tag1.len() > MAX_TAG_LEN || self.tag2.len() > MAX_TAG_LEN {
+            return Err(format!("Tags are limited to {} characters", MAX_TAG_LEN));
+        }
+        if self.endpoint.len() > MAX_URI_LEN
+            || self.feedback_uri.len() > MAX_URI_LEN
+            || self.proof_of_payment_tx.len() > MAX_URI_LEN
+        {
+            return Err(format!("URIs are limited to {} characters", MAX_URI_LEN));
+        }
+        if self.comment.len() > MAX_COMMENT_LEN {
+            return Err(format!("comment is limited to {} characters", MAX_COMMENT_LEN));
+        }
+
+        let client = Address::from_str(&self.client_address).map_err(|_| "Invalid client_address".to_string())?;
+        let sig_bytes = hex::decode(self.signature.trim_start_matches("0x"))
+            .map_err(|_| "Invalid signature hex".to_string())?;
+        let signature = Signature::try_from(sig_bytes.as_slice()).map_err(|_| "Invalid signature".to_string())?;
+
+        let message = self.signing_message(agent_registry);
+        let hash = ethers::utils::hash_message(&message);
+        let signer = signature
+            .recover(hash)
+            .map_err(|_| "Could not recover signer from signature".to_string())?;
+        if signer != client {
+            return Err("Signature was not made by client_address".to_string());
+        }
+
+        Ok(format!("{:?}", hash))
+    }
+}
+
+/// Aggregate the signed feedback received for this agent
+pub fn score(db: &Database, identity: &AgentIdentityRow) -> ReputationScore {
+    let stats = db
+        .received_feedback_stats(identity.agent_id, &identity.agent_registry)
+        .unwrap_or_default();
+    ReputationScore {
+        count: stats.count,
+        average_score: (stats.average_score * 100.0).round() / 100.0,
+        trust_level: TrustLevel::from_feedback(stats.count, stats.average_score),
+        last_feedback_at: stats.last_feedback_at,
+        feedback_endpoint: Some(feedback_endpoint()),
+    }
+}
+
+/// Registration file with the current score, the feedback service and (when
+/// enabled) the receipts feed listed
+pub fn registration_file(db: &Database, identity: &AgentIdentityRow) -> RegistrationFile {
+    let mut reg = identity.to_registration_file();
+    if !reg.services.iter().any(|s| s.name == FEEDBACK_SERVICE_NAME) {
+        reg.services.push(ServiceEntry {
+            name: FEEDBACK_SERVICE_NAME.to_string(),
+            endpoint: feedback_endpoint(),
+            version: "1.0".to_string(),
+        });
+    }
+    if super::receipts::is_enabled(db) && !reg.services.iter().any(|s| s.name == super::receipts::RECEIPTS_SERVICE_NAME) {
+        reg.services.push(ServiceEntry {
+            name: super::receipts::RECEIPTS_SERVICE_NAME.to_string(),
+            endpoint: super::receipts::feed_url(),
+            version: "1.0".to_string(),
+        });
+    }
+    reg.reputation = Some(score(db, identity));
+    reg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::{LocalWallet, Signer};
+
+    const REGISTRY: &str = "eip155:8453:0xa23a42d266653846e05d8f356a52298844537472";
+
+    fn signed(wallet: &LocalWallet, value: i64, timestamp: i64) -> FeedbackSubmission {
+        let mut submission = FeedbackSubmission {
+            agent_id: 42,
+            client_address: format!("{:?}", wallet.address()),
+            value,
+            tag1: "swap".to_string(),
+            tag2: String::new(),
+            endpoint: String::new(),
+            feedback_uri: String::new(),
+            comment: "fast and correct".to_string(),
+            proof_of_payment_tx: String::new(),
+            timestamp,
+            signature: String::new(),
+        };
+        let hash = ethers::utils::hash_message(submission.signing_message(REGISTRY));
+        let sig = wallet.sign_hash(hash).unwrap();
+        submission.signature = format!("0x{}", hex::encode(sig.to_vec()));
+        submission
+    }
+
+    fn wallet() -> LocalWallet {
+        "0x0123456789012345678901234567890123456789012345678901234567890123"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verify_signed_feedback() {
+        let wallet = wallet();
+        let submission = signed(&wallet, 80, 1_000);
+        let hash = submission.verify(42, REGISTRY, 1_100).unwrap();
+        assert!(hash.starts_with("0x") && hash.len() == 66);
+
+        assert!(submission.verify(43, REGISTRY, 1_100).is_err());
+        assert!(submission.verify(42, REGISTRY, 1_000 + MAX_TIMESTAMP_SKEW_SECS + 1).is_err());
+        assert!(submission.verify(42, "eip155:1:0xother", 1_100).is_err());
+
+        let mut tampered = submission.clone();
+        tampered.value = 100;
+        assert!(tampered.verify(42, REGISTRY, 1_100).is_err());
+
+        let mut tampered = submission;
+        tampered.comment = "slow".to_string();
+        assert!(tampered.verify(42, REGISTRY, 1_100).is_err());
+    }
+
+    #[test]
+    fn test_verify_rejects_out_of_range_value() {
+        let submission = signed(&wallet(), 101, 1_000);
+        assert!(submission.verify(42, REGISTRY, 1_000).is_err());
+    }
+}
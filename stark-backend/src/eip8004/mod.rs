@@ -3,6 +3,9 @@
 //! This module provides integration with EIP-8004 registries:
 //! - Identity Registry: ERC-721 agent handles for discovery
 //! - Reputation Registry: On-chain feedback with payment proofs
+//! - Feedback endpoint: Signed off-chain feedback aggregated into the registration file
+//! - Delegation: Sending subtasks to discovered agents over A2A or x402
+//! - Receipts: Signed, hash-chained public log of transactions and paid tasks
 //! - Validation Registry: Independent work verification
 //!
 //! Combined with x402 payments, this enables trustless agent economies.
@@ -13,6 +16,9 @@ pub mod identity;
 pub mod reputation;
 pub mod discovery;
 pub mod config;
+pub mod delegation;
+pub mod feedback;
+pub mod receipts;
 
 pub use types::*;
 pub use config::Eip8004Config;
@@ -0,0 +1,155 @@
+//! Action receipts — a public transparency log of what the agent has done
+//!
+//! When enabled, confirmed transactions and completed paid tasks are appended
+//! to a hash chain (`action_receipts`). Each entry commits to the previous one
+//! and is signed (EIP-191) by the agent wallet, so counterparties can verify the
+//! agent's claimed history from `/.well-known/agent-receipts.json`.
+
+use super::abi::common::keccak256;
+use crate::db::tables::action_receipts::ActionReceipt;
+use crate::db::Database;
+use crate::wallet::WalletProvider;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Service name of the receipts feed in the registration file
+pub const RECEIPTS_SERVICE_NAME: &str = "receipts";
+
+/// A transaction the agent broadcast that confirmed on-chain
+pub const RECEIPT_TRANSACTION: &str = "transaction";
+/// A paid request the agent served (x402 payment verified, request completed)
+pub const RECEIPT_TASK_SERVED: &str = "task_served";
+/// A paid subtask the agent delegated to another agent that passed verification
+pub const RECEIPT_TASK_DELEGATED: &str = "task_delegated";
+
+/// `prev_hash` of the first entry
+pub const GENESIS_HASH: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Entries signed per feed request
+const SIGN_BATCH: usize = 50;
+
+/// Public URL of the receipts feed
+pub fn feed_url() -> String {
+    format!("{}/.well-known/agent-receipts.json", crate::config::self_url())
+}
+
+/// Hash of an entry: keccak256 over its fields, one per line, in this order
+pub fn entry_hash(
+    prev_hash: &str,
+    kind: &str,
+    reference: &str,
+    network: &str,
+    summary: &str,
+    details_json: &str,
+    created_at: &str,
+) -> String {
+    let preimage = [prev_hash, kind, reference, network, summary, details_json, created_at].join("\n");
+    format!("0x{}", hex::encode(keccak256(preimage.as_bytes())))
+}
+
+/// Message the agent wallet signs for an entry
+pub fn signing_message(entry_hash: &str) -> String {
+    format!("EIP-8004 Receipt\n{}", entry_hash)
+}
+
+/// Whether receipts are being recorded
+pub fn is_enabled(db: &Database) -> bool {
+    db.get_bot_settings().map(|s| s.receipts_log_enabled).unwrap_or(false)
+}
+
+/// Append a receipt if the log is enabled. Failures are logged, never surfaced —
+/// the action itself already happened.
+pub fn record(db: &Database, kind: &str, reference: &str, network: &str, summary: &str, details: Value) {
+    if !is_enabled(db) {
+        return;
+    }
+    match db.append_action_receipt(kind, reference, network, summary, &details.to_string()) {
+        Ok(Some(_)) => log::info!("[RECEIPTS] Logged {} {}", kind, reference),
+        Ok(None) => log::debug!("[RECEIPTS] {} {} already logged", kind, reference),
+        Err(e) => log::error!("[RECEIPTS] Failed to log {} {}: {}", kind, reference, e),
+    }
+}
+
+/// Sign entries that have no signature yet, oldest first
+pub async fn sign_pending(db: &Database, wallet: &dyn WalletProvider) -> Result<usize, String> {
+    let pending = db.unsigned_action_receipts(SIGN_BATCH).map_err(|e| e.to_string())?;
+    let signer = wallet.get_address().to_lowercase();
+    let mut signed = 0;
+    for receipt in pending {
+        let signature = wallet
+            .sign_message(signing_message(&receipt.entry_hash).as_bytes())
+            .await?;
+        db.set_action_receipt_signature(receipt.id, &signer, &format!("0x{}", hex::encode(signature.to_vec())))
+            .map_err(|e| e.to_string())?;
+        signed += 1;
+    }
+    Ok(signed)
+}
+
+/// Check that entries form an unbroken chain with correct hashes.
+/// `entries` must be in ascending order, starting from the first entry.
+#[cfg(test)]
+pub fn verify_chain(entries: &[ActionReceipt]) -> Result<(), String> {
+    let mut prev = GENESIS_HASH.to_string();
+    for e in entries {
+        if e.prev_hash != prev {
+            return Err(format!("Entry {} does not link to the previous entry", e.id));
+        }
+        let expected = entry_hash(&e.prev_hash, &e.kind, &e.reference, &e.network, &e.summary, &e.details, &e.created_at);
+        if e.entry_hash != expected {
+            return Err(format!("Entry {} hash mismatch", e.id));
+        }
+        prev = e.entry_hash.clone();
+    }
+    Ok(())
+}
+
+/// The public feed document
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiptFeed {
+    #[serde(rename = "agentId")]
+    pub agent_id: i64,
+    #[serde(rename = "agentRegistry")]
+    pub agent_registry: String,
+    /// Address that signs entries
+    pub signer: Option<String>,
+    /// Hash of the latest entry
+    pub head: String,
+    pub total: i64,
+    /// How to verify entries
+    pub scheme: &'static str,
+    pub entries: Vec<ActionReceipt>,
+}
+
+pub const FEED_SCHEME: &str = "entryHash = keccak256(prevHash, kind, reference, network, summary, details, createdAt joined by \\n); signature = EIP-191 personal_sign(\"EIP-8004 Receipt\\n\" + entryHash)";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::Signature;
+
+    #[test]
+    fn test_entry_hash_is_deterministic() {
+        let a = entry_hash(GENESIS_HASH, RECEIPT_TRANSACTION, "0xabc", "base", "sent 1 ETH", "{}", "2026-01-01T00:00:00Z");
+        let b = entry_hash(GENESIS_HASH, RECEIPT_TRANSACTION, "0xabc", "base", "sent 1 ETH", "{}", "2026-01-01T00:00:00Z");
+        let c = entry_hash(GENESIS_HASH, RECEIPT_TRANSACTION, "0xabc", "base", "sent 2 ETH", "{}", "2026-01-01T00:00:00Z");
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.len(), 66);
+    }
+
+    #[test]
+    fn test_signature_recovers_to_signer() {
+        let wallet: LocalWallet = "0x0123456789012345678901234567890123456789012345678901234567890123"
+            .parse()
+            .unwrap();
+        let hash = entry_hash(GENESIS_HASH, RECEIPT_TASK_SERVED, "r1", "base", "served", "{}", "t");
+        let sig = wallet
+            .sign_hash(ethers::utils::hash_message(signing_message(&hash)))
+            .unwrap();
+        let sig = Signature::try_from(sig.to_vec().as_slice()).unwrap();
+        let recovered = sig.recover(ethers::utils::hash_message(signing_message(&hash))).unwrap();
+        assert_eq!(recovered, wallet.address());
+    }
+}
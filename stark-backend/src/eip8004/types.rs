@@ -73,6 +73,10 @@ pub struct RegistrationFile {
 
     #[serde(rename = "supportedTrust", default)]
     pub supported_trust: Vec<String>,
+
+    /// Aggregate of signed feedback received at the agent's feedback endpoint
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reputation: Option<ReputationScore>,
 }
 
 fn default_true() -> bool {
@@ -91,6 +95,7 @@ impl RegistrationFile {
             active: true,
             registrations: None,
             supported_trust: vec!["reputation".to_string(), "x402-payments".to_string()],
+            reputation: None,
         }
     }
 
@@ -196,18 +201,28 @@ pub struct ReputationSummary {
 
 impl ReputationSummary {
     pub fn trust_level(&self) -> TrustLevel {
-        if self.count >= 10 && self.average_score >= 75.0 {
-            TrustLevel::High
-        } else if self.count >= 5 && self.average_score >= 50.0 {
-            TrustLevel::Medium
-        } else if self.count >= 3 && self.average_score >= 25.0 {
-            TrustLevel::Low
-        } else {
-            TrustLevel::Unverified
-        }
+        TrustLevel::from_feedback(self.count, self.average_score)
     }
 }
 
+/// Reputation published in an agent's registration file, aggregated from the
+/// signed feedback its feedback endpoint accepted. Self-hosted, so callers
+/// should weigh it below the on-chain Reputation Registry summary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReputationScore {
+    /// Feedback counted (latest submission per client)
+    pub count: u64,
+    #[serde(rename = "averageScore")]
+    pub average_score: f64,
+    #[serde(rename = "trustLevel")]
+    pub trust_level: TrustLevel,
+    #[serde(rename = "lastFeedbackAt", skip_serializing_if = "Option::is_none", default)]
+    pub last_feedback_at: Option<String>,
+    /// Where signed feedback is accepted
+    #[serde(rename = "feedbackEndpoint", skip_serializing_if = "Option::is_none", default)]
+    pub feedback_endpoint: Option<String>,
+}
+
 /// Trust level derived from reputation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TrustLevel {
@@ -218,6 +233,23 @@ pub enum TrustLevel {
     Negative,   // score < 0
 }
 
+impl TrustLevel {
+    /// Trust level for `count` feedback entries averaging `average_score` (-100..100)
+    pub fn from_feedback(count: u64, average_score: f64) -> Self {
+        if count >= 10 && average_score >= 75.0 {
+            TrustLevel::High
+        } else if count >= 5 && average_score >= 50.0 {
+            TrustLevel::Medium
+        } else if count >= 3 && average_score >= 25.0 {
+            TrustLevel::Low
+        } else if count >= 3 && average_score < 0.0 {
+            TrustLevel::Negative
+        } else {
+            TrustLevel::Unverified
+        }
+    }
+}
+
 impl std::fmt::Display for TrustLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -0,0 +1,130 @@
+//! Backend error type
+//!
+//! `AppError` is the error type for controllers that return
+//! `AppResult<HttpResponse>` and use `?` instead of building an error
+//! response per call. Each variant belongs to a category (db, validation,
+//! not found, rate limited) that decides the HTTP status. Wrapped errors keep
+//! their `source()` chain, which is logged in full when a request fails on the
+//! server side.
+//!
+//! Scope: the journal, paper trading, auth, access key and two-factor
+//! controllers are on `AppError`. The rest of the backend (AI clients, tools,
+//! integrations and their controllers) still returns `Result<_, String>`;
+//! variants for those failures are added when a caller is converted. There is no
+//! `From<String>`: a `String` error says nothing about whose fault it was, so
+//! callers pick the category (`AppError::validation`, `not_found`, ...)
+//! explicitly rather than have every message become a 500.
+
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use serde::Serialize;
+use serde_json::json;
+
+pub type AppResult<T> = Result<T, AppError>;
+
+/// What kind of failure an error is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, strum::AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ErrorCategory {
+    Db,
+    Validation,
+    NotFound,
+    RateLimited,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("Database error: {0}")]
+    Db(#[from] rusqlite::Error),
+
+    #[error("Database pool error: {0}")]
+    Pool(#[from] r2d2::Error),
+
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    RateLimited(String),
+}
+
+impl AppError {
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::Validation(message.into())
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound(message.into())
+    }
+
+    pub fn rate_limited(message: impl Into<String>) -> Self {
+        AppError::RateLimited(message.into())
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            AppError::Db(_) | AppError::Pool(_) => ErrorCategory::Db,
+            AppError::Validation(_) => ErrorCategory::Validation,
+            AppError::NotFound(_) => ErrorCategory::NotFound,
+            AppError::RateLimited(_) => ErrorCategory::RateLimited,
+        }
+    }
+
+    /// The error followed by every error in its source chain. Wrapping
+    /// variants already print their direct source, so repeats are skipped.
+    pub fn chain(&self) -> String {
+        let mut out = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(err) = source {
+            let text = err.to_string();
+            if !out.ends_with(&text) {
+                out.push_str(&format!(": {}", text));
+            }
+            source = err.source();
+        }
+        out
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self.category() {
+            ErrorCategory::Validation => StatusCode::BAD_REQUEST,
+            ErrorCategory::NotFound => StatusCode::NOT_FOUND,
+            ErrorCategory::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCategory::Db => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        if status.is_server_error() {
+            log::error!("[{}] {}", self.category().as_ref(), self.chain());
+        }
+        HttpResponse::build(status).json(json!({
+            "error": self.to_string(),
+            "category": self.category(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categories_and_statuses() {
+        let db = AppError::from(rusqlite::Error::QueryReturnedNoRows);
+        assert_eq!(db.category(), ErrorCategory::Db);
+        assert_eq!(db.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(db.to_string().starts_with("Database error:"));
+
+        assert_eq!(AppError::validation("bad period").status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(AppError::not_found("no such trade").status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(AppError::rate_limited("slow down").status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(AppError::not_found("no such trade").category().as_ref(), "not_found");
+    }
+}
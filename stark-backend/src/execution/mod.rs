@@ -6,11 +6,14 @@
 //!
 //! Also provides session lane serialization to prevent race conditions when
 //! multiple requests arrive for the same session.
+//!
+//! Each execution also gets an isolated scratch directory (see `scratch`).
 
 mod tracker;
 mod pending_confirmation;
 mod process_manager;
 mod session_lanes;
+pub mod scratch;
 
 pub use tracker::ExecutionTracker;
 pub use pending_confirmation::{PendingConfirmation, PendingConfirmationManager};
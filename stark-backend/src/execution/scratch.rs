@@ -0,0 +1,171 @@
+//! Per-execution scratch directories
+//!
+//! Every dispatch gets an isolated `<workspace>/.scratch/<execution_id>` directory
+//! for intermediate files. File, shell and script tools default to it through
+//! `ToolContext::scratch_dir`, writes into it are capped by `STARK_SCRATCH_MAX_MB`
+//! (on top of the global disk quota), and it is removed when the execution
+//! completes. Files the user wants to keep are archived into
+//! `<workspace>/artifacts/<execution_id>` before that happens.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Directory (under the workspace) holding all live scratch directories
+pub const SCRATCH_DIR_NAME: &str = ".scratch";
+
+/// Directory (under the workspace) that archived scratch files are copied into
+pub const ARTIFACTS_DIR_NAME: &str = "artifacts";
+
+fn workspace() -> PathBuf {
+    PathBuf::from(crate::config::workspace_dir())
+}
+
+/// Keep execution IDs usable as a single path component.
+fn sanitize_id(execution_id: &str) -> String {
+    execution_id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
+
+fn scratch_path_in(workspace: &Path, execution_id: &str) -> PathBuf {
+    workspace.join(SCRATCH_DIR_NAME).join(sanitize_id(execution_id))
+}
+
+fn artifacts_path_in(workspace: &Path, execution_id: &str) -> PathBuf {
+    workspace.join(ARTIFACTS_DIR_NAME).join(sanitize_id(execution_id))
+}
+
+/// Path of the scratch directory for an execution (may not exist yet)
+pub fn scratch_path(execution_id: &str) -> PathBuf {
+    scratch_path_in(&workspace(), execution_id)
+}
+
+/// Path of the persistent artifacts directory for an execution (may not exist yet)
+pub fn artifacts_path(execution_id: &str) -> PathBuf {
+    artifacts_path_in(&workspace(), execution_id)
+}
+
+/// Create the scratch directory for an execution and return its path
+pub fn create(execution_id: &str) -> io::Result<PathBuf> {
+    let path = scratch_path(execution_id);
+    std::fs::create_dir_all(&path)?;
+    Ok(path)
+}
+
+/// Total size in bytes of all files under a scratch directory
+pub fn usage_bytes(dir: &Path) -> u64 {
+    if !dir.exists() {
+        return 0;
+    }
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+fn remove_in(workspace: &Path, execution_id: &str) {
+    let path = scratch_path_in(workspace, execution_id);
+    match std::fs::remove_dir_all(&path) {
+        Ok(()) => log::debug!("[SCRATCH] Removed scratch dir for execution {}", execution_id),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("[SCRATCH] Failed to remove {}: {}", path.display(), e),
+    }
+}
+
+/// Garbage-collect the scratch directory of a finished execution
+pub fn remove(execution_id: &str) {
+    remove_in(&workspace(), execution_id)
+}
+
+fn archive_in(workspace: &Path, execution_id: &str) -> io::Result<Vec<String>> {
+    let source = scratch_path_in(workspace, execution_id);
+    let target = artifacts_path_in(workspace, execution_id);
+    let mut archived = Vec::new();
+
+    if !source.exists() {
+        return Ok(archived);
+    }
+
+    for entry in WalkDir::new(&source).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = match entry.path().strip_prefix(&source) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        let dest = target.join(relative);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(entry.path(), &dest)?;
+        archived.push(
+            Path::new(ARTIFACTS_DIR_NAME)
+                .join(sanitize_id(execution_id))
+                .join(relative)
+                .to_string_lossy()
+                .to_string(),
+        );
+    }
+
+    Ok(archived)
+}
+
+/// Copy an execution's scratch files into `<workspace>/artifacts/<execution_id>`.
+/// Returns the archived files as workspace-relative paths.
+pub fn archive(execution_id: &str) -> io::Result<Vec<String>> {
+    archive_in(&workspace(), execution_id)
+}
+
+/// Remove scratch directories left behind by a previous run (crash or restart).
+/// Only call this before any execution has started.
+pub fn sweep_orphans() -> usize {
+    let root = workspace().join(SCRATCH_DIR_NAME);
+    let entries = match std::fs::read_dir(&root) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+    let mut removed = 0;
+    for entry in entries.filter_map(|e| e.ok()) {
+        if std::fs::remove_dir_all(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_archive_then_remove() {
+        let ws = tempdir().unwrap();
+        let scratch = scratch_path_in(ws.path(), "exec-1");
+        std::fs::create_dir_all(scratch.join("out")).unwrap();
+        std::fs::write(scratch.join("out/report.csv"), "a,b\n1,2\n").unwrap();
+        assert_eq!(usage_bytes(&scratch), 8);
+
+        let archived = archive_in(ws.path(), "exec-1").unwrap();
+        assert_eq!(archived, vec!["artifacts/exec-1/out/report.csv".to_string()]);
+        assert!(ws.path().join("artifacts/exec-1/out/report.csv").exists());
+
+        remove_in(ws.path(), "exec-1");
+        assert!(!scratch.exists());
+        // Archived copies survive garbage collection
+        assert!(ws.path().join("artifacts/exec-1/out/report.csv").exists());
+    }
+
+    #[test]
+    fn test_execution_id_cannot_escape_scratch_root() {
+        let ws = tempdir().unwrap();
+        let path = scratch_path_in(ws.path(), "../../etc");
+        assert_eq!(path, ws.path().join(SCRATCH_DIR_NAME).join("etc"));
+    }
+}
@@ -88,6 +88,8 @@ pub struct SessionLaneManager {
     global_lane: Arc<Semaphore>,
     /// Optional workspace-based locking for git operations
     workspace_lanes: DashMap<String, Arc<Semaphore>>,
+    /// Priority requests waiting per session; regular requests yield to them
+    priority_waiting: DashMap<String, usize>,
 }
 
 impl SessionLaneManager {
@@ -98,6 +100,7 @@ impl SessionLaneManager {
             metadata: DashMap::new(),
             global_lane: Arc::new(Semaphore::new(1)),
             workspace_lanes: DashMap::new(),
+            priority_waiting: DashMap::new(),
         })
     }
 
@@ -109,32 +112,38 @@ impl SessionLaneManager {
         // Get or create the semaphore for this session
         let semaphore = self.get_or_create_lane(session_id);
 
-        // Acquire the permit (this will block if another request has it)
-        let permit = semaphore
-            .clone()
-            .acquire_owned()
-            .await
-            .expect("Semaphore should not be closed");
+        // Acquire the permit (this will block if another request has it).
+        // A permit that arrives while a priority request waits is handed on.
+        let permit = loop {
+            let permit = semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("Semaphore should not be closed");
+            if self.priority_waiting.get(session_id).is_none_or(|n| *n == 0) {
+                break permit;
+            }
+            drop(permit);
+            tokio::task::yield_now().await;
+        };
 
-        // Update metadata
-        self.metadata
-            .entry(session_id.to_string())
-            .and_modify(|m| {
-                m.last_used = Instant::now();
-                m.total_uses += 1;
-            })
-            .or_insert_with(|| {
-                let mut m = LaneMetadata::default();
-                m.total_uses = 1;
-                m
-            });
+        self.make_guard(session_id, permit)
+    }
 
-        SessionLaneGuard {
-            session_id: session_id.to_string(),
-            _permit: permit,
-            acquired_at: Instant::now(),
-            manager: Arc::clone(self),
+    /// Acquire a session lane ahead of regular requests already waiting for it
+    ///
+    /// Used for urgent messages. The request currently holding the lane is not
+    /// interrupted.
+    pub async fn acquire_priority(self: &Arc<Self>, session_id: &str) -> SessionLaneGuard {
+        let semaphore = self.get_or_create_lane(session_id);
+        *self.priority_waiting.entry(session_id.to_string()).or_insert(0) += 1;
+        let permit = semaphore.clone().acquire_owned().await;
+        if let Some(mut waiting) = self.priority_waiting.get_mut(session_id) {
+            *waiting = waiting.saturating_sub(1);
         }
+        self.priority_waiting.remove_if(session_id, |_, n| *n == 0);
+
+        self.make_guard(session_id, permit.expect("Semaphore should not be closed"))
     }
 
     /// Try to acquire a session lane without blocking
@@ -144,26 +153,7 @@ impl SessionLaneManager {
         let semaphore = self.get_or_create_lane(session_id);
 
         match semaphore.clone().try_acquire_owned() {
-            Ok(permit) => {
-                self.metadata
-                    .entry(session_id.to_string())
-                    .and_modify(|m| {
-                        m.last_used = Instant::now();
-                        m.total_uses += 1;
-                    })
-                    .or_insert_with(|| {
-                        let mut m = LaneMetadata::default();
-                        m.total_uses = 1;
-                        m
-                    });
-
-                Some(SessionLaneGuard {
-                    session_id: session_id.to_string(),
-                    _permit: permit,
-                    acquired_at: Instant::now(),
-                    manager: Arc::clone(self),
-                })
-            }
+            Ok(permit) => Some(self.make_guard(session_id, permit)),
             Err(_) => None,
         }
     }
@@ -270,6 +260,27 @@ impl SessionLaneManager {
         }
     }
 
+    /// Record the use of a lane and wrap its permit in a guard
+    fn make_guard(self: &Arc<Self>, session_id: &str, permit: OwnedSemaphorePermit) -> SessionLaneGuard {
+        self.metadata
+            .entry(session_id.to_string())
+            .and_modify(|m| {
+                m.last_used = Instant::now();
+                m.total_uses += 1;
+            })
+            .or_insert_with(|| LaneMetadata {
+                total_uses: 1,
+                ..Default::default()
+            });
+
+        SessionLaneGuard {
+            session_id: session_id.to_string(),
+            _permit: permit,
+            acquired_at: Instant::now(),
+            manager: Arc::clone(self),
+        }
+    }
+
     fn get_or_create_lane(&self, session_id: &str) -> Arc<Semaphore> {
         self.lanes
             .entry(session_id.to_string())
@@ -285,6 +296,7 @@ impl Default for SessionLaneManager {
             metadata: DashMap::new(),
             global_lane: Arc::new(Semaphore::new(1)),
             workspace_lanes: DashMap::new(),
+            priority_waiting: DashMap::new(),
         }
     }
 }
@@ -340,6 +352,35 @@ mod tests {
         drop(guard2);
     }
 
+    #[tokio::test]
+    async fn test_priority_jumps_the_queue() {
+        let manager = SessionLaneManager::new();
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let holder = manager.acquire("session").await;
+
+        let regular = {
+            let (manager, order) = (manager.clone(), order.clone());
+            tokio::spawn(async move {
+                let _guard = manager.acquire("session").await;
+                order.lock().unwrap().push("regular");
+            })
+        };
+        sleep(Duration::from_millis(20)).await;
+        let urgent = {
+            let (manager, order) = (manager.clone(), order.clone());
+            tokio::spawn(async move {
+                let _guard = manager.acquire_priority("session").await;
+                order.lock().unwrap().push("urgent");
+            })
+        };
+        sleep(Duration::from_millis(20)).await;
+
+        drop(holder);
+        regular.await.unwrap();
+        urgent.await.unwrap();
+        assert_eq!(*order.lock().unwrap(), vec!["urgent", "regular"]);
+    }
+
     #[tokio::test]
     async fn test_stats() {
         let manager = SessionLaneManager::new();
@@ -30,6 +30,11 @@ pub struct ExecutionTracker {
     pending_task_deletions: DashMap<i64, Vec<u32>>,
     /// Current planner tasks per channel (for API access on page refresh)
     channel_planner_tasks: DashMap<i64, Vec<crate::ai::multi_agent::types::PlannerTask>>,
+    /// The in-progress planner task per channel and its cancellation token
+    /// (a child of the channel token, so stopping the execution cancels it too)
+    planner_task_tokens: DashMap<i64, (u32, CancellationToken)>,
+    /// Channels whose execution should pause at the next safe boundary
+    pause_requests: DashMap<i64, bool>,
 }
 
 impl ExecutionTracker {
@@ -46,6 +51,8 @@ impl ExecutionTracker {
             session_cancellation_tokens: DashMap::new(),
             pending_task_deletions: DashMap::new(),
             channel_planner_tasks: DashMap::new(),
+            planner_task_tokens: DashMap::new(),
+            pause_requests: DashMap::new(),
         }
     }
 
@@ -98,6 +105,24 @@ impl ExecutionTracker {
         self.cancelled_channels.remove(&channel_id);
         // Replace with a fresh token for the new execution
         self.cancellation_tokens.insert(channel_id, CancellationToken::new());
+        self.planner_task_tokens.remove(&channel_id);
+        self.pause_requests.remove(&channel_id);
+    }
+
+    /// Ask the running execution on a channel to pause at its next safe
+    /// boundary. Returns false if nothing is running there.
+    pub fn request_pause(&self, channel_id: i64) -> bool {
+        if self.get_execution_id(channel_id).is_none() {
+            return false;
+        }
+        log::info!("[EXECUTION_TRACKER] Pause requested for channel {}", channel_id);
+        self.pause_requests.insert(channel_id, true);
+        true
+    }
+
+    /// Consume a pending pause request (called by the tool loop at checkpoints)
+    pub fn take_pause_request(&self, channel_id: i64) -> bool {
+        self.pause_requests.remove(&channel_id).is_some()
     }
 
     // =====================================================
@@ -247,6 +272,60 @@ impl ExecutionTracker {
             .push(task_id);
     }
 
+    /// Cancel a planner task right away.
+    ///
+    /// If it is the task currently being performed, its token is cancelled so
+    /// the in-flight AI call and tool calls stop immediately. The task is
+    /// marked `cancelled` in the stored list and queued so the dispatcher
+    /// updates its own queue at the next checkpoint. Returns whether the task
+    /// was the current one.
+    pub fn cancel_planner_task(&self, channel_id: i64, task_id: u32) -> bool {
+        let was_current = match self.planner_task_tokens.get(&channel_id) {
+            Some(entry) if entry.0 == task_id => {
+                entry.1.cancel();
+                true
+            }
+            _ => false,
+        };
+        if let Some(mut tasks) = self.channel_planner_tasks.get_mut(&channel_id) {
+            if let Some(task) = tasks.iter_mut().find(|t| t.id == task_id) {
+                task.status = crate::ai::multi_agent::types::TaskStatus::Cancelled;
+            }
+        }
+        log::info!(
+            "[EXECUTION_TRACKER] Cancelling task {} for channel {} (current: {})",
+            task_id, channel_id, was_current
+        );
+        self.queue_task_deletion(channel_id, task_id);
+        was_current
+    }
+
+    /// Record that a planner task has started and return its cancellation token
+    pub fn begin_planner_task(&self, channel_id: i64, task_id: u32) -> CancellationToken {
+        let token = self.get_cancellation_token(channel_id).child_token();
+        self.planner_task_tokens.insert(channel_id, (task_id, token.clone()));
+        token
+    }
+
+    /// Cancellation token of the planner task currently being performed, if any
+    pub fn planner_task_token(&self, channel_id: i64) -> Option<CancellationToken> {
+        self.planner_task_tokens.get(&channel_id).map(|entry| entry.1.clone())
+    }
+
+    /// Whether the current planner task was cancelled (but not the whole execution)
+    pub fn is_planner_task_cancelled(&self, channel_id: i64) -> bool {
+        !self.is_cancelled(channel_id)
+            && self
+                .planner_task_tokens
+                .get(&channel_id)
+                .is_some_and(|entry| entry.1.is_cancelled())
+    }
+
+    /// Forget the current planner task (it finished or was cancelled)
+    pub fn end_planner_task(&self, channel_id: i64) {
+        self.planner_task_tokens.remove(&channel_id);
+    }
+
     /// Get and clear pending task deletions for a channel
     pub fn take_pending_task_deletions(&self, channel_id: i64) -> Vec<u32> {
         self.pending_task_deletions
@@ -285,6 +364,7 @@ impl ExecutionTracker {
     pub fn clear_planner_tasks(&self, channel_id: i64) {
         log::debug!("[EXECUTION_TRACKER] Clearing planner tasks for channel {}", channel_id);
         self.channel_planner_tasks.remove(&channel_id);
+        self.planner_task_tokens.remove(&channel_id);
     }
 
     /// Start a new execution for a channel
@@ -776,6 +856,9 @@ impl ExecutionTracker {
             for task_id in task_ids_to_remove {
                 self.tasks.remove(&task_id);
             }
+
+            // Garbage-collect the execution's scratch directory
+            super::scratch::remove(&execution_id);
         }
     }
 
@@ -891,4 +974,41 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_cancel_current_planner_task() {
+        use crate::ai::multi_agent::types::{PlannerTask, TaskStatus as PlannerStatus};
+
+        let tracker = create_test_tracker();
+        tracker.start_execution(1, None, "execute", Some("Test execution"));
+        tracker.set_planner_tasks(1, vec![
+            PlannerTask::new(1, "first".to_string()),
+            PlannerTask::new(2, "second".to_string()),
+        ]);
+        let token = tracker.begin_planner_task(1, 1);
+
+        // Cancelling a task that is not running leaves the current one alone
+        assert!(!tracker.cancel_planner_task(1, 2));
+        assert!(!token.is_cancelled());
+
+        assert!(tracker.cancel_planner_task(1, 1));
+        assert!(token.is_cancelled());
+        assert!(tracker.is_planner_task_cancelled(1));
+        // The execution itself keeps running
+        assert!(!tracker.is_cancelled(1));
+        assert!(tracker.get_planner_tasks(1).iter().all(|t| t.status == PlannerStatus::Cancelled));
+        assert_eq!(tracker.take_pending_task_deletions(1), vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_pause_request_needs_running_execution() {
+        let tracker = create_test_tracker();
+        assert!(!tracker.request_pause(1));
+
+        tracker.start_execution(1, None, "execute", Some("Long research"));
+        assert!(tracker.request_pause(1));
+        assert!(tracker.take_pause_request(1));
+        // Consumed once
+        assert!(!tracker.take_pause_request(1));
+    }
 }
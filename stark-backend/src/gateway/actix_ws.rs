@@ -3,12 +3,16 @@
 //! which is required for platforms like DigitalOcean App Platform that only expose one port.
 
 use crate::channels::ChannelManager;
+use crate::db::tables::access_keys::ACCESS_KEY_PREFIX;
 use crate::db::Database;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::methods;
 use crate::gateway::protocol::{ChannelIdParams, RpcError, RpcRequest, RpcResponse};
+use crate::middleware::access_keys::{self, requirement, KeyDenial, Requirement};
+use crate::models::Session;
 use crate::tx_queue::TxQueueManager;
 use crate::wallet::WalletProvider;
+use actix_web::http::Method;
 use actix_web::{web, HttpRequest, HttpResponse};
 use actix_ws::AggregatedMessage;
 use futures_util::StreamExt;
@@ -212,6 +216,26 @@ async fn handle_ws_connection(
     log::info!("Gateway client {} disconnected", client_id);
 }
 
+/// Check the token sent with the `auth` method. Access keys go through the
+/// same scope, expiry and rate limit checks as on HTTP, against the scope the
+/// `/ws` route needs; anything else must be a login session.
+fn authenticate(db: &Database, token: &str) -> rusqlite::Result<Option<Session>> {
+    if !token.starts_with(ACCESS_KEY_PREFIX) {
+        return db.validate_session(token);
+    }
+    let Requirement::Scope(scope) = requirement(&Method::GET, "/ws") else {
+        return Ok(None);
+    };
+    match access_keys::authorize(db, token, scope) {
+        Ok(key) => Ok(Some(key.as_session(token))),
+        Err(KeyDenial::Database(e)) => Err(e),
+        Err(denial) => {
+            log::warn!("[ACCESS_KEY] Gateway auth refused: {:?}", denial);
+            Ok(None)
+        }
+    }
+}
+
 /// Wait for authentication from the client
 async fn wait_for_auth(
     session: &mut actix_ws::Session,
@@ -257,7 +281,7 @@ async fn wait_for_auth(
                         };
 
                         // Validate token against database
-                        match db.validate_session(&params.token) {
+                        match authenticate(db, &params.token) {
                             Ok(Some(_session)) => {
                                 let response = RpcResponse::success(
                                     request.id,
@@ -362,6 +386,14 @@ async fn dispatch_method(
     tx_queue: &Arc<TxQueueManager>,
     wallet_provider: &Option<Arc<dyn WalletProvider>>,
 ) -> Result<serde_json::Value, RpcError> {
+    // The dashboard's WebSocket confirms and denies transactions too; the
+    // HTTP read-only check doesn't see those
+    if request.method.starts_with("tx_queue.") && crate::tools::read_only_mode::is_enabled(db) {
+        return Err(RpcError::new(
+            -32000,
+            "This bot is running in read-only mirror mode, so transactions can't be confirmed or denied".to_string(),
+        ));
+    }
     match request.method.as_str() {
         "ping" => methods::handle_ping().await,
         "status" => methods::handle_status(broadcaster.clone()).await,
@@ -386,11 +418,15 @@ async fn dispatch_method(
         "tx_queue.confirm" => {
             let params: methods::TxQueueParams = serde_json::from_value(request.params.clone())
                 .map_err(|e| RpcError::invalid_params(format!("Invalid params: {}", e)))?;
+            crate::tx_queue::approval::settle_from_dashboard(db, &params.uuid, true)
+                .map_err(|e| RpcError::new(-32000, e))?;
             methods::handle_tx_queue_confirm(params, tx_queue.clone(), broadcaster.clone(), wallet_provider.clone()).await
         }
         "tx_queue.deny" => {
             let params: methods::TxQueueParams = serde_json::from_value(request.params.clone())
                 .map_err(|e| RpcError::invalid_params(format!("Invalid params: {}", e)))?;
+            crate::tx_queue::approval::settle_from_dashboard(db, &params.uuid, false)
+                .map_err(|e| RpcError::new(-32000, e))?;
             methods::handle_tx_queue_deny(params, tx_queue.clone(), broadcaster.clone()).await
         }
         _ => Err(RpcError::method_not_found()),
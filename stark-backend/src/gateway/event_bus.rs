@@ -0,0 +1,308 @@
+//! Plugin event bus — the slice of gateway events that modules and skills
+//! may react to, under stable names
+//!
+//! Core events are tapped from the `EventBroadcaster` and renamed to the
+//! types in [`BusEventType`], so the WebSocket protocol can change without
+//! breaking extensions. Modules and skills can also publish their own events,
+//! named `<source>.<name>`. Every event gets a sequence number and sits in a
+//! ring buffer for polling (`GET /api/events/poll?after=<seq>`); subscriptions
+//! with a callback URL additionally get each matching event POSTed to them.
+//! Sequence numbers restart with the process: a poller whose cursor is ahead
+//! of the latest event is served from the start of the buffer.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::db::Database;
+use crate::gateway::protocol::GatewayEvent;
+use crate::gateway::EventBroadcaster;
+
+/// Events kept for polling
+const BUFFER_SIZE: usize = 1000;
+/// Most events returned by one poll
+pub const MAX_POLL_LIMIT: usize = 500;
+/// Timeout for one callback delivery
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+static NAME_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-z0-9_]+(\.[a-z0-9_]+)*$").unwrap());
+
+/// Core events visible to extensions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusEventType {
+    MessageReceived,
+    MessageSent,
+    TaskCompleted,
+    ExecutionCompleted,
+    ToolCompleted,
+    TxConfirmed,
+    ChannelStarted,
+    ChannelStopped,
+}
+
+impl BusEventType {
+    pub const ALL: &'static [BusEventType] = &[
+        Self::MessageReceived,
+        Self::MessageSent,
+        Self::TaskCompleted,
+        Self::ExecutionCompleted,
+        Self::ToolCompleted,
+        Self::TxConfirmed,
+        Self::ChannelStarted,
+        Self::ChannelStopped,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MessageReceived => "message.received",
+            Self::MessageSent => "message.sent",
+            Self::TaskCompleted => "task.completed",
+            Self::ExecutionCompleted => "execution.completed",
+            Self::ToolCompleted => "tool.completed",
+            Self::TxConfirmed => "tx.confirmed",
+            Self::ChannelStarted => "channel.started",
+            Self::ChannelStopped => "channel.stopped",
+        }
+    }
+
+    /// The bus type a gateway event is published as, if any
+    pub fn from_gateway(event: &str) -> Option<Self> {
+        match event {
+            "channel.message" => Some(Self::MessageReceived),
+            "agent.response" => Some(Self::MessageSent),
+            "execution.task_completed" => Some(Self::TaskCompleted),
+            "execution.completed" => Some(Self::ExecutionCompleted),
+            "tool.result" => Some(Self::ToolCompleted),
+            "tx.confirmed" => Some(Self::TxConfirmed),
+            "channel.started" => Some(Self::ChannelStarted),
+            "channel.stopped" => Some(Self::ChannelStopped),
+            _ => None,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::MessageReceived => "A user message arrived on a channel",
+            Self::MessageSent => "The agent replied on a channel",
+            Self::TaskCompleted => "A task of an agent execution finished",
+            Self::ExecutionCompleted => "An agent execution finished",
+            Self::ToolCompleted => "A tool call returned",
+            Self::TxConfirmed => "A broadcast transaction was mined",
+            Self::ChannelStarted => "A channel connected",
+            Self::ChannelStopped => "A channel disconnected",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BusEvent {
+    pub seq: u64,
+    pub event: String,
+    /// "core", or the module/skill that published it
+    pub source: String,
+    pub data: Value,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PollResult {
+    pub events: Vec<BusEvent>,
+    /// Pass as `after` on the next poll
+    pub latest_seq: u64,
+}
+
+/// Whether an event name matches any of the patterns (`*`, `prefix.*` or exact)
+pub fn matches(patterns: &[String], event: &str) -> bool {
+    patterns.iter().any(|pattern| {
+        if pattern == "*" {
+            return true;
+        }
+        match pattern.strip_suffix(".*") {
+            Some(prefix) => event.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('.')),
+            None => pattern == event,
+        }
+    })
+}
+
+/// Check event patterns before they are saved or polled with
+pub fn validate_patterns(patterns: &[String]) -> Result<(), String> {
+    for pattern in patterns {
+        let name = pattern.strip_suffix(".*").unwrap_or(pattern);
+        if pattern != "*" && !NAME_RE.is_match(name) {
+            return Err(format!("Invalid event pattern '{}'", pattern));
+        }
+    }
+    Ok(())
+}
+
+pub struct EventBus {
+    broadcaster: Arc<EventBroadcaster>,
+    buffer: Mutex<VecDeque<BusEvent>>,
+    next_seq: AtomicU64,
+    delivery_tx: mpsc::UnboundedSender<BusEvent>,
+}
+
+impl EventBus {
+    /// Create the bus, tap the broadcaster and start callback delivery
+    pub fn start(db: Arc<Database>, broadcaster: Arc<EventBroadcaster>) -> Arc<Self> {
+        let (delivery_tx, delivery_rx) = mpsc::unbounded_channel();
+        let bus = Arc::new(EventBus {
+            broadcaster: broadcaster.clone(),
+            buffer: Mutex::new(VecDeque::with_capacity(BUFFER_SIZE)),
+            next_seq: AtomicU64::new(1),
+            delivery_tx,
+        });
+
+        let mut tap = broadcaster.tap(|name| BusEventType::from_gateway(name).is_some());
+        let tap_bus = bus.clone();
+        tokio::spawn(async move {
+            while let Some(event) = tap.recv().await {
+                if let Some(bus_type) = BusEventType::from_gateway(&event.event) {
+                    tap_bus.publish(bus_type.as_str(), "core", event.data);
+                }
+            }
+        });
+        tokio::spawn(deliver_loop(db, delivery_rx));
+        bus
+    }
+
+    /// Add an event to the bus
+    pub fn publish(&self, event: &str, source: &str, data: Value) -> BusEvent {
+        let bus_event = BusEvent {
+            seq: self.next_seq.fetch_add(1, Ordering::SeqCst),
+            event: event.to_string(),
+            source: source.to_string(),
+            data,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        if let Ok(mut buffer) = self.buffer.lock() {
+            if buffer.len() >= BUFFER_SIZE {
+                buffer.pop_front();
+            }
+            buffer.push_back(bus_event.clone());
+        }
+        let _ = self.delivery_tx.send(bus_event.clone());
+        bus_event
+    }
+
+    /// Publish an extension's own event as `<source>.<name>`; it also goes
+    /// out to WebSocket clients under that name
+    pub fn publish_extension(&self, source: &str, name: &str, data: Value) -> Result<BusEvent, String> {
+        if !NAME_RE.is_match(source) || source.contains('.') {
+            return Err(format!("Invalid source '{}': use lowercase letters, digits and _", source));
+        }
+        if source == "core" || BusEventType::ALL.iter().any(|t| t.as_str().starts_with(&format!("{}.", source))) {
+            return Err(format!("Source '{}' is reserved for core events", source));
+        }
+        if !NAME_RE.is_match(name) {
+            return Err(format!("Invalid event name '{}'", name));
+        }
+        let event = format!("{}.{}", source, name);
+        self.broadcaster.broadcast(GatewayEvent::custom(&event, data.clone()));
+        Ok(self.publish(&event, source, data))
+    }
+
+    /// Buffered events after `after` that match `patterns` (all when empty)
+    pub fn poll(&self, after: u64, patterns: &[String], limit: usize) -> PollResult {
+        let latest_seq = self.next_seq.load(Ordering::SeqCst) - 1;
+        // A cursor from before a restart is ahead of us; start over
+        let after = if after > latest_seq { 0 } else { after };
+        let events = self
+            .buffer
+            .lock()
+            .map(|buffer| {
+                buffer
+                    .iter()
+                    .filter(|e| e.seq > after && (patterns.is_empty() || matches(patterns, &e.event)))
+                    .take(limit.clamp(1, MAX_POLL_LIMIT))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        PollResult { events, latest_seq }
+    }
+}
+
+/// POST each event to the callbacks subscribed to it, one at a time
+async fn deliver_loop(db: Arc<Database>, mut rx: mpsc::UnboundedReceiver<BusEvent>) {
+    let client = crate::http::shared_client();
+    while let Some(event) = rx.recv().await {
+        let subscriptions = match db.list_callback_subscriptions() {
+            Ok(subscriptions) => subscriptions,
+            Err(e) => {
+                log::warn!("[EVENT_BUS] Failed to load subscriptions: {}", e);
+                continue;
+            }
+        };
+        for subscription in subscriptions.iter().filter(|s| matches(&s.events, &event.event)) {
+            let Some(url) = subscription.callback_url.as_deref() else { continue };
+            let result = client
+                .post(url)
+                .header("X-Stark-Event", &event.event)
+                .header("X-Stark-Subscription", subscription.id.to_string())
+                .json(&event)
+                .timeout(DELIVERY_TIMEOUT)
+                .send()
+                .await
+                .and_then(|resp| resp.error_for_status())
+                .map(|_| ())
+                .map_err(|e| e.to_string());
+            if let Err(ref e) = result {
+                log::debug!("[EVENT_BUS] Delivery of {} to {} failed: {}", event.event, subscription.subscriber, e);
+            }
+            match db.record_event_delivery(subscription.id, &result) {
+                Ok(true) => log::warn!(
+                    "[EVENT_BUS] Disabled subscription {} of '{}' after repeated delivery failures",
+                    subscription.id,
+                    subscription.subscriber
+                ),
+                Ok(false) => {}
+                Err(e) => log::warn!("[EVENT_BUS] Failed to record delivery: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_poll_and_match() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let broadcaster = Arc::new(EventBroadcaster::new());
+        let bus = EventBus::start(db, broadcaster.clone());
+
+        broadcaster.broadcast(GatewayEvent::tx_confirmed(1, "0xabc", "base", "confirmed"));
+        broadcaster.broadcast(GatewayEvent::stream_start(1, None));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let polled = bus.poll(0, &[], 10);
+        assert_eq!(polled.events.len(), 1);
+        assert_eq!(polled.events[0].event, "tx.confirmed");
+        assert_eq!(polled.events[0].data["tx_hash"], "0xabc");
+
+        let published = bus.publish_extension("price_alerts", "triggered", serde_json::json!({ "symbol": "ETH" })).unwrap();
+        assert_eq!(published.event, "price_alerts.triggered");
+        assert!(bus.publish_extension("tx", "confirmed", Value::Null).is_err());
+        assert!(bus.publish_extension("price_alerts", "Bad Name", Value::Null).is_err());
+
+        let alerts = bus.poll(0, &["price_alerts.*".to_string()], 10);
+        assert_eq!(alerts.events.len(), 1);
+        assert!(bus.poll(polled.latest_seq + 1, &[], 10).events.is_empty());
+        // A cursor from a previous process replays the buffer
+        assert_eq!(bus.poll(10_000, &[], 10).events.len(), 2);
+
+        assert!(matches(&["*".to_string()], "message.received"));
+        assert!(!matches(&["tx.*".to_string()], "txn.sent"));
+        assert!(validate_patterns(&["tx.*".to_string(), "message.received".to_string()]).is_ok());
+        assert!(validate_patterns(&["TX!".to_string()]).is_err());
+    }
+}
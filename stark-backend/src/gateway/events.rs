@@ -21,6 +21,14 @@ enum BroadcastCmd {
     Unsubscribe(String),
 }
 
+/// An in-process consumer of the event stream (e.g. the plugin event bus).
+/// Unlike WebSocket clients, taps get no replay, are not counted as
+/// connected clients and only receive events their filter accepts.
+struct EventTap {
+    filter: fn(&str) -> bool,
+    sender: mpsc::UnboundedSender<GatewayEvent>,
+}
+
 /// Broadcasts events to all connected WebSocket clients.
 ///
 /// Calling `broadcast()` is non-blocking: the event is sent to an internal
@@ -34,6 +42,8 @@ pub struct EventBroadcaster {
     clients: Arc<DashMap<String, mpsc::Sender<GatewayEvent>>>,
     /// Ring buffer accessible for replay on new connections.
     recent_events: Arc<std::sync::Mutex<VecDeque<GatewayEvent>>>,
+    /// In-process consumers fed by the background task
+    taps: Arc<std::sync::Mutex<Vec<EventTap>>>,
 }
 
 impl EventBroadcaster {
@@ -43,6 +53,8 @@ impl EventBroadcaster {
         let recent_events =
             Arc::new(std::sync::Mutex::new(VecDeque::with_capacity(EVENT_BUFFER_SIZE)));
 
+        let taps = Arc::new(std::sync::Mutex::new(Vec::new()));
+
         let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
 
         // Spawn the background broadcast loop
@@ -50,13 +62,25 @@ impl EventBroadcaster {
             cmd_rx,
             clients.clone(),
             recent_events.clone(),
+            taps.clone(),
         ));
 
         Self {
             cmd_tx,
             clients,
             recent_events,
+            taps,
+        }
+    }
+
+    /// Receive every future event whose name passes `filter`. The tap is
+    /// dropped once its receiver is.
+    pub fn tap(&self, filter: fn(&str) -> bool) -> mpsc::UnboundedReceiver<GatewayEvent> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        if let Ok(mut taps) = self.taps.lock() {
+            taps.push(EventTap { filter, sender });
         }
+        receiver
     }
 
     /// Subscribe a new client and return (client_id, receiver).
@@ -109,6 +133,7 @@ impl EventBroadcaster {
         mut cmd_rx: mpsc::UnboundedReceiver<BroadcastCmd>,
         clients: Arc<DashMap<String, mpsc::Sender<GatewayEvent>>>,
         recent_events: Arc<std::sync::Mutex<VecDeque<GatewayEvent>>>,
+        taps: Arc<std::sync::Mutex<Vec<EventTap>>>,
     ) {
         while let Some(cmd) = cmd_rx.recv().await {
             match cmd {
@@ -123,6 +148,12 @@ impl EventBroadcaster {
 
                     let event_name = event.event.clone();
 
+                    if let Ok(mut taps) = taps.lock() {
+                        taps.retain(|tap| {
+                            !(tap.filter)(&event_name) || tap.sender.send(event.clone()).is_ok()
+                        });
+                    }
+
                     // Log tool call and result events at info level for visibility
                     if event_name == "agent.tool_call" || event_name == "tool.result" {
                         log::info!(
@@ -1,4 +1,5 @@
 pub mod actix_ws;
+pub mod event_bus;
 pub mod events;
 pub mod methods;
 pub mod protocol;
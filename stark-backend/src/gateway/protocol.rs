@@ -84,11 +84,17 @@ pub enum EventType {
     TxQueueDenied,                // User denied, tx deleted
     // Context management events
     ContextCompacting,  // Session context is being compacted to reduce token usage
+    CompactionStarted,  // Automatic compaction started in the background between turns
+    CompactionFinished, // Automatic compaction finished (or failed)
     // Telemetry events
     SpanEmitted,        // A telemetry span was emitted (for real-time telemetry streaming)
     RolloutStatusChange, // Rollout lifecycle status changed
     // Module TUI events
     ModuleTuiInvalidate, // Module TUI dashboard needs re-render
+    // Config events
+    ConfigChanged,       // Settings changed and were reloaded live
+    // Interactive message events
+    ChannelAction,       // A user pressed a button attached to a channel message
 }
 
 impl EventType {
@@ -154,9 +160,13 @@ impl EventType {
             Self::TxQueueConfirmed => "tx_queue.confirmed",
             Self::TxQueueDenied => "tx_queue.denied",
             Self::ContextCompacting => "context.compacting",
+            Self::CompactionStarted => "compaction.started",
+            Self::CompactionFinished => "compaction.finished",
             Self::SpanEmitted => "telemetry.span_emitted",
             Self::RolloutStatusChange => "telemetry.rollout_status",
             Self::ModuleTuiInvalidate => "module.tui_invalidate",
+            Self::ConfigChanged => "config.changed",
+            Self::ChannelAction => "channel.action",
         }
     }
 
@@ -222,9 +232,13 @@ impl EventType {
             "tx_queue.confirmed" => Some(EventType::TxQueueConfirmed),
             "tx_queue.denied" => Some(EventType::TxQueueDenied),
             "context.compacting" => Some(EventType::ContextCompacting),
+            "compaction.started" => Some(EventType::CompactionStarted),
+            "compaction.finished" => Some(EventType::CompactionFinished),
             "telemetry.span_emitted" => Some(EventType::SpanEmitted),
             "telemetry.rollout_status" => Some(EventType::RolloutStatusChange),
             "module.tui_invalidate" => Some(EventType::ModuleTuiInvalidate),
+            "config.changed" => Some(EventType::ConfigChanged),
+            "channel.action" => Some(EventType::ChannelAction),
             _ => None,
         }
     }
@@ -752,6 +766,32 @@ impl GatewayEvent {
         )
     }
 
+    /// Settings changed and subscribers reloaded them (frontend can refetch)
+    pub fn config_changed(change: Value) -> Self {
+        Self::new(
+            EventType::ConfigChanged,
+            serde_json::json!({
+                "change": change,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            }),
+        )
+    }
+
+    /// A button attached to a channel message was pressed
+    pub fn channel_action(channel_id: i64, chat_id: &str, user_name: &str, action: &crate::channels::types::ActionEvent) -> Self {
+        Self::new(
+            EventType::ChannelAction,
+            serde_json::json!({
+                "channel_id": channel_id,
+                "chat_id": chat_id,
+                "user": user_name,
+                "action": action.action,
+                "label": action.label,
+                "payload": action.payload,
+            }),
+        )
+    }
+
     /// Transaction pending - broadcast when tx is sent but not yet mined
     pub fn tx_pending(
         channel_id: i64,
@@ -1350,6 +1390,51 @@ impl GatewayEvent {
         )
     }
 
+    /// Automatic compaction started for a session (runs between turns)
+    pub fn compaction_started(
+        channel_id: i64,
+        session_id: i64,
+        level: &str,
+        context_tokens: i32,
+        threshold: f64,
+    ) -> Self {
+        Self::new(
+            EventType::CompactionStarted,
+            serde_json::json!({
+                "channel_id": channel_id,
+                "session_id": session_id,
+                "level": level,  // "background", "aggressive" or "emergency"
+                "context_tokens": context_tokens,
+                "threshold": threshold,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            }),
+        )
+    }
+
+    /// Automatic compaction finished for a session
+    pub fn compaction_finished(
+        channel_id: i64,
+        session_id: i64,
+        compaction_type: Option<&str>,
+        tokens_before: i32,
+        tokens_after: i32,
+        error: Option<&str>,
+    ) -> Self {
+        Self::new(
+            EventType::CompactionFinished,
+            serde_json::json!({
+                "channel_id": channel_id,
+                "session_id": session_id,
+                "compaction_type": compaction_type,  // "incremental", "full" or "emergency"
+                "success": error.is_none(),
+                "tokens_before": tokens_before,
+                "tokens_after": tokens_after,
+                "error": error,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            }),
+        )
+    }
+
     /// A telemetry span was emitted
     pub fn span_emitted(
         channel_id: i64,
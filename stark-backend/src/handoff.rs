@@ -0,0 +1,151 @@
+//! Conversation handoff to a human operator
+//!
+//! A chat can be marked as needing a human by the agent (the `request_human`
+//! tool), by a rule (configured keywords, or a message that is both urgent and
+//! negative — see `channels::triage`), or by an operator from the web UI.
+//! The configured operators are notified; one of them joins through
+//! `/api/handoffs` and replies as themselves. Until the handoff is released
+//! the dispatcher keeps the chat's messages in its session for the operator
+//! and sends no automated answers. The config lives in the kv_store.
+
+use serde::{Deserialize, Serialize};
+
+use crate::channels::outbound;
+use crate::channels::triage::{MessageTags, Sentiment, Urgency};
+use crate::db::tables::handoffs::{Handoff, NewHandoff};
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+
+/// kv_store namespace for the config (outside what the kv_store tool can reach)
+const NAMESPACE: &str = "handoff";
+const CONFIG_KEY: &str = "config";
+
+/// Sent to the chat when a rule hands it over
+pub const RULE_REPLY: &str = "I've asked a member of the team to take over — a person will reply here shortly.";
+
+/// Someone notified when a chat needs a human
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Operator {
+    pub name: String,
+    /// Channel and chat the notification is sent to
+    pub channel_id: i64,
+    pub chat_id: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HandoffConfig {
+    /// Notified on every new handoff (the owner's chat when empty)
+    #[serde(default)]
+    pub operators: Vec<Operator>,
+    /// A correspondent's message containing one of these is handed over
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Hand over messages that are both urgent and negative
+    #[serde(default)]
+    pub escalate_urgent_negative: bool,
+}
+
+pub fn load_config(db: &Database) -> HandoffConfig {
+    db.kv_get(NAMESPACE, CONFIG_KEY)
+        .ok()
+        .flatten()
+        .and_then(|entry| serde_json::from_value(entry.value).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_config(db: &Database, config: &HandoffConfig) -> Result<(), String> {
+    let value = serde_json::to_value(config).map_err(|e| e.to_string())?;
+    db.kv_set(NAMESPACE, CONFIG_KEY, &value, None).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// The reason a rule hands this message over, if one does
+pub fn rule_match(config: &HandoffConfig, text: &str, tags: MessageTags) -> Option<String> {
+    let lower = text.to_lowercase();
+    if let Some(keyword) = config
+        .keywords
+        .iter()
+        .map(|k| k.trim().to_lowercase())
+        .find(|k| !k.is_empty() && lower.contains(k.as_str()))
+    {
+        return Some(format!("Asked for \"{}\"", keyword));
+    }
+    if config.escalate_urgent_negative && tags.urgency == Urgency::Urgent && tags.sentiment == Sentiment::Negative {
+        return Some("Urgent and upset".to_string());
+    }
+    None
+}
+
+/// Open a handoff for a chat and notify the operators. A chat that already
+/// has one keeps it, and nobody is notified twice.
+pub async fn escalate(
+    db: &Database,
+    broadcaster: Option<&EventBroadcaster>,
+    request: &NewHandoff<'_>,
+) -> Result<Handoff, String> {
+    if let Some(existing) = db
+        .get_active_handoff(request.channel_id, request.chat_id)
+        .map_err(|e| format!("Database error: {}", e))?
+    {
+        return Ok(existing);
+    }
+    let handoff = db.create_handoff(request).map_err(|e| format!("Database error: {}", e))?;
+    log::info!(
+        "[HANDOFF] #{} opened for {} chat {} by {}: {}",
+        handoff.id,
+        handoff.channel_type,
+        handoff.chat_id,
+        handoff.requested_by,
+        handoff.reason
+    );
+    if let Some(broadcaster) = broadcaster {
+        broadcaster.broadcast(GatewayEvent::custom("handoff.requested", serde_json::json!(handoff)));
+    }
+    notify_operators(db, &handoff).await;
+    Ok(handoff)
+}
+
+async fn notify_operators(db: &Database, handoff: &Handoff) {
+    let config = load_config(db);
+    let mut targets: Vec<(i64, String)> = config.operators.iter().map(|o| (o.channel_id, o.chat_id.clone())).collect();
+    if targets.is_empty() {
+        targets.extend(db.get_bot_settings().ok().and_then(|s| s.owner_chat()));
+    }
+    let text = format!(
+        "**Human needed** in a {} chat{} (handoff #{}):\n> {}\nJoin from the web UI to reply.",
+        handoff.channel_type,
+        handoff.user_name.as_deref().map(|u| format!(" with {}", u)).unwrap_or_default(),
+        handoff.id,
+        handoff.reason
+    );
+    for (channel_id, chat_id) in targets {
+        if channel_id == handoff.channel_id && chat_id == handoff.chat_id {
+            continue;
+        }
+        if let Err(e) = outbound::send_proactive(db, channel_id, &chat_id, &text, "handoff").await {
+            log::warn!("[HANDOFF] Failed to notify operator chat {}: {}", chat_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_match() {
+        let calm = MessageTags { sentiment: Sentiment::Neutral, urgency: Urgency::Normal };
+        let upset = MessageTags { sentiment: Sentiment::Negative, urgency: Urgency::Urgent };
+        let mut config = HandoffConfig { keywords: vec!["Real Person".to_string(), " ".to_string()], ..Default::default() };
+
+        assert!(rule_match(&config, "can I talk to a real person?", calm).is_some());
+        assert!(rule_match(&config, "what's the gas price", calm).is_none());
+        assert!(rule_match(&config, "my wallet was drained!!", upset).is_none());
+        config.escalate_urgent_negative = true;
+        assert_eq!(rule_match(&config, "my wallet was drained!!", upset).as_deref(), Some("Urgent and upset"));
+
+        let db = Database::new(":memory:").unwrap();
+        save_config(&db, &config).unwrap();
+        assert_eq!(load_config(&db), config);
+    }
+}
@@ -0,0 +1,378 @@
+//! Farcaster integration (via the Neynar API)
+//!
+//! Casts are published through a Neynar managed signer (`FARCASTER_SIGNER_UUID`)
+//! authorised for the agent's Farcaster account; reads only need
+//! `NEYNAR_API_KEY`. The `farcaster` channel polls mention/reply notifications
+//! and answers with casts, and the `farcaster_cast` tool posts on demand.
+//!
+//! The Farcaster account is linked to the agent's EIP-8004 identity by adding a
+//! `farcaster` service entry to its registration file (and, when asked, by
+//! pointing the Farcaster profile URL back at the registration).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::controllers::api_keys::ApiKeyId;
+use crate::db::Database;
+use crate::eip8004::types::ServiceEntry;
+use crate::integrations::issue_tracker::api_key;
+
+const NEYNAR_API_BASE: &str = "https://api.neynar.com/v2/farcaster";
+
+/// Protocol limit for a cast's text (bytes, not characters)
+pub const MAX_CAST_BYTES: usize = 320;
+
+/// Embeds allowed per cast
+pub const MAX_CAST_EMBEDS: usize = 2;
+
+/// Service name used for the Farcaster entry in the EIP-8004 registration
+pub const FARCASTER_SERVICE_NAME: &str = "farcaster";
+
+/// A Farcaster account as returned by Neynar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FarcasterUser {
+    pub fid: u64,
+    pub username: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+/// A cast as returned by Neynar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cast {
+    pub hash: String,
+    #[serde(default)]
+    pub parent_hash: Option<String>,
+    #[serde(default)]
+    pub thread_hash: Option<String>,
+    #[serde(default)]
+    pub text: String,
+    pub author: FarcasterUser,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotificationsResponse {
+    #[serde(default)]
+    notifications: Vec<Notification>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Notification {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    cast: Option<Cast>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsersResponse {
+    #[serde(default)]
+    users: Vec<FarcasterUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CastResponse {
+    cast: Cast,
+}
+
+/// Neynar REST client
+#[derive(Clone)]
+pub struct NeynarClient {
+    api_key: String,
+    signer_uuid: Option<String>,
+}
+
+impl NeynarClient {
+    pub fn new(api_key: String, signer_uuid: Option<String>) -> Self {
+        Self { api_key, signer_uuid }
+    }
+
+    /// Build a client from the API keys page (env var fallback)
+    pub fn from_keys(db: &Database) -> Result<Self, String> {
+        let key = api_key(db, ApiKeyId::NeynarApiKey).ok_or("NEYNAR_API_KEY is not configured")?;
+        Ok(Self::new(key, api_key(db, ApiKeyId::FarcasterSignerUuid)))
+    }
+
+    /// Whether a signer is configured (publishing is possible)
+    pub fn can_cast(&self) -> bool {
+        self.signer_uuid.is_some()
+    }
+
+    fn signer(&self) -> Result<&str, String> {
+        self.signer_uuid
+            .as_deref()
+            .ok_or_else(|| "FARCASTER_SIGNER_UUID is not configured — casting needs a Neynar managed signer".to_string())
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, String)]) -> Result<Value, String> {
+        let response = crate::http::shared_client()
+            .get(format!("{}{}", NEYNAR_API_BASE, path))
+            .header("x-api-key", &self.api_key)
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| format!("Neynar request failed: {}", e))?;
+        Self::read(response).await
+    }
+
+    async fn send(&self, method: reqwest::Method, path: &str, body: &Value) -> Result<Value, String> {
+        let response = crate::http::shared_client()
+            .request(method, format!("{}{}", NEYNAR_API_BASE, path))
+            .header("x-api-key", &self.api_key)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| format!("Neynar request failed: {}", e))?;
+        Self::read(response).await
+    }
+
+    async fn read(response: reqwest::Response) -> Result<Value, String> {
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or_default();
+        if !status.is_success() {
+            let message = body["message"].as_str().map(|m| m.to_string()).unwrap_or_else(|| body.to_string());
+            return Err(format!("Neynar API error ({}): {}", status, message));
+        }
+        Ok(body)
+    }
+
+    /// Publish a cast. `parent` is a cast hash to reply to; `channel` a Farcaster channel ID.
+    pub async fn publish_cast(
+        &self,
+        text: &str,
+        parent: Option<&str>,
+        channel: Option<&str>,
+        embeds: &[String],
+    ) -> Result<String, String> {
+        let mut body = json!({ "signer_uuid": self.signer()?, "text": text });
+        if let Some(parent) = parent {
+            body["parent"] = json!(parent);
+        }
+        if let Some(channel) = channel {
+            body["channel_id"] = json!(channel);
+        }
+        if !embeds.is_empty() {
+            body["embeds"] = embeds.iter().map(|url| json!({ "url": url })).collect();
+        }
+
+        let response = self.send(reqwest::Method::POST, "/cast", &body).await?;
+        response["cast"]["hash"]
+            .as_str()
+            .map(|h| h.to_string())
+            .ok_or_else(|| format!("Unexpected Neynar response: {}", response))
+    }
+
+    /// Publish text as a reply chain, splitting it at the cast size limit.
+    /// Returns the hashes of the casts posted, in order.
+    pub async fn publish_thread(
+        &self,
+        text: &str,
+        parent: Option<&str>,
+        channel: Option<&str>,
+        embeds: &[String],
+    ) -> Result<Vec<String>, String> {
+        let mut hashes: Vec<String> = Vec::new();
+        for (i, chunk) in split_for_cast(text, MAX_CAST_BYTES).iter().enumerate() {
+            // Only the first cast goes to the channel and carries the embeds
+            let hash = if i == 0 {
+                self.publish_cast(chunk, parent, channel, embeds).await?
+            } else {
+                self.publish_cast(chunk, hashes.last().map(String::as_str), None, &[]).await?
+            };
+            hashes.push(hash);
+        }
+        Ok(hashes)
+    }
+
+    /// Recent casts that mention or reply to `fid`, newest first
+    pub async fn mentions(&self, fid: u64) -> Result<Vec<Cast>, String> {
+        let body = self
+            .get(
+                "/notifications",
+                &[("fid", fid.to_string()), ("type", "mentions,replies".to_string())],
+            )
+            .await?;
+        let parsed: NotificationsResponse =
+            serde_json::from_value(body).map_err(|e| format!("Unexpected notifications response: {}", e))?;
+        Ok(parsed
+            .notifications
+            .into_iter()
+            .filter(|n| n.kind == "mention" || n.kind == "reply")
+            .filter_map(|n| n.cast)
+            .collect())
+    }
+
+    pub async fn user(&self, fid: u64) -> Result<FarcasterUser, String> {
+        let body = self.get("/user/bulk", &[("fids", fid.to_string())]).await?;
+        let parsed: UsersResponse =
+            serde_json::from_value(body).map_err(|e| format!("Unexpected user response: {}", e))?;
+        parsed
+            .users
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("Farcaster user {} not found", fid))
+    }
+
+    pub async fn cast(&self, hash: &str) -> Result<Cast, String> {
+        let body = self
+            .get("/cast", &[("identifier", hash.to_string()), ("type", "hash".to_string())])
+            .await?;
+        let parsed: CastResponse =
+            serde_json::from_value(body).map_err(|e| format!("Unexpected cast response: {}", e))?;
+        Ok(parsed.cast)
+    }
+
+    /// Set the profile URL of the signer's account
+    pub async fn set_profile_url(&self, url: &str) -> Result<(), String> {
+        let body = json!({ "signer_uuid": self.signer()?, "url": url });
+        self.send(reqwest::Method::PATCH, "/user", &body).await.map(|_| ())
+    }
+}
+
+pub fn profile_url(username: &str) -> String {
+    format!("https://farcaster.xyz/{}", username)
+}
+
+/// Public link to a cast by its full hash
+pub fn cast_url(hash: &str) -> String {
+    format!("https://farcaster.xyz/~/conversations/{}", hash)
+}
+
+/// Whether `hash` looks like a Farcaster cast hash (0x + 40 hex chars)
+pub fn is_cast_hash(hash: &str) -> bool {
+    hash.len() == 42 && hash.starts_with("0x") && hash[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Split text into casts of at most `max_bytes` bytes, breaking on whitespace
+/// where possible and numbering the parts when there is more than one.
+pub fn split_for_cast(text: &str, max_bytes: usize) -> Vec<String> {
+    let text = text.trim();
+    if text.len() <= max_bytes {
+        return vec![text.to_string()];
+    }
+
+    // Leave room for a " (nn/nn)" suffix
+    let budget = max_bytes.saturating_sub(8).max(1);
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let needed = if current.is_empty() { word.len() } else { current.len() + 1 + word.len() };
+        if needed <= budget {
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+            continue;
+        }
+        if !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+        // A single word longer than the budget is broken on char boundaries
+        let mut rest = word;
+        while rest.len() > budget {
+            let mut cut = budget;
+            while !rest.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            chunks.push(rest[..cut].to_string());
+            rest = &rest[cut..];
+        }
+        current.push_str(rest);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    let total = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| format!("{} ({}/{})", chunk, i + 1, total))
+        .collect()
+}
+
+/// Add or refresh the `farcaster` service entry in the agent's EIP-8004
+/// registration. Returns Ok(false) when no onchain identity is registered or
+/// the entry was already current.
+pub fn link_agent_identity(db: &Database, user: &FarcasterUser) -> Result<bool, String> {
+    let Some(identity) = db.get_agent_identity_full() else {
+        return Ok(false);
+    };
+    let mut services: Vec<ServiceEntry> = serde_json::from_str(&identity.services_json).unwrap_or_default();
+    let entry = ServiceEntry {
+        name: FARCASTER_SERVICE_NAME.to_string(),
+        endpoint: profile_url(&user.username),
+        // The fid is the stable account identifier; usernames can change
+        version: format!("fid:{}", user.fid),
+    };
+
+    match services.iter_mut().find(|s| s.name == FARCASTER_SERVICE_NAME) {
+        Some(existing) if existing.endpoint == entry.endpoint && existing.version == entry.version => {
+            return Ok(false)
+        }
+        Some(existing) => *existing = entry,
+        None => services.push(entry),
+    }
+
+    let json = serde_json::to_string(&services).map_err(|e| e.to_string())?;
+    db.update_agent_identity_field("services_json", &json)
+        .map_err(|e| format!("Failed to update agent identity: {}", e))?;
+    log::info!(
+        "[FARCASTER] Linked @{} (fid {}) to EIP-8004 agent #{}",
+        user.username,
+        user.fid,
+        identity.agent_id
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_for_cast() {
+        assert_eq!(split_for_cast("gm", MAX_CAST_BYTES), vec!["gm".to_string()]);
+
+        let long = "word ".repeat(150);
+        let chunks = split_for_cast(&long, MAX_CAST_BYTES);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.len() <= MAX_CAST_BYTES));
+        assert!(chunks[0].ends_with(&format!("(1/{})", chunks.len())));
+
+        // Multi-byte text is split on byte length without breaking characters
+        let emoji = "🚀".repeat(200);
+        assert!(split_for_cast(&emoji, MAX_CAST_BYTES).iter().all(|c| c.len() <= MAX_CAST_BYTES));
+    }
+
+    #[test]
+    fn test_is_cast_hash() {
+        assert!(is_cast_hash("0x71d5225f77e0164388b1d4c120825f3a2c1f131c"));
+        assert!(!is_cast_hash("0x71d5225f"));
+        assert!(!is_cast_hash("71d5225f77e0164388b1d4c120825f3a2c1f131cab"));
+    }
+
+    #[test]
+    fn test_link_agent_identity() {
+        let db = Database::new(":memory:").unwrap();
+        let user = FarcasterUser { fid: 42, username: "starkbot".to_string(), display_name: None };
+
+        // No onchain identity yet
+        assert!(!link_agent_identity(&db, &user).unwrap());
+
+        let services = r#"[{"name":"chat","endpoint":"https://example.com","version":"1"}]"#;
+        db.upsert_agent_identity(7, "eip155:8453:0xabc", 8453, Some("Stark"), None, None, true, true, services, "[]", None)
+            .unwrap();
+        assert!(link_agent_identity(&db, &user).unwrap());
+        assert!(!link_agent_identity(&db, &user).unwrap());
+
+        let row = db.get_agent_identity_full().unwrap();
+        let services: Vec<ServiceEntry> = serde_json::from_str(&row.services_json).unwrap();
+        assert_eq!(services.len(), 2);
+        assert_eq!(services[1].endpoint, "https://farcaster.xyz/starkbot");
+        assert_eq!(services[1].version, "fid:42");
+    }
+}
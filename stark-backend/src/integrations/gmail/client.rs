@@ -1,6 +1,7 @@
 //! Gmail API client
 
 use super::types::*;
+use crate::circuit_breaker::{self, GuardedSend};
 use reqwest::Client;
 use serde_json::json;
 
@@ -55,7 +56,7 @@ impl GmailClient {
                 ("refresh_token", &self.refresh_token),
                 ("grant_type", "refresh_token"),
             ])
-            .send()
+            .send_guarded(circuit_breaker::GMAIL)
             .await
             .map_err(|e| format!("Failed to refresh token: {}", e))?;
 
@@ -97,7 +98,7 @@ impl GmailClient {
         let response = self.http
             .get(&url)
             .header("Authorization", format!("Bearer {}", self.access_token))
-            .send()
+            .send_guarded(circuit_breaker::GMAIL)
             .await
             .map_err(|e| format!("Failed to get history: {}", e))?;
 
@@ -127,7 +128,7 @@ impl GmailClient {
         let response = self.http
             .get(&url)
             .header("Authorization", format!("Bearer {}", self.access_token))
-            .send()
+            .send_guarded(circuit_breaker::GMAIL)
             .await
             .map_err(|e| format!("Failed to get message: {}", e))?;
 
@@ -161,7 +162,7 @@ impl GmailClient {
             .header("Authorization", format!("Bearer {}", self.access_token))
             .header("Content-Type", "application/json")
             .body(body.to_string())
-            .send()
+            .send_guarded(circuit_breaker::GMAIL)
             .await
             .map_err(|e| format!("Failed to setup watch: {}", e))?;
 
@@ -182,7 +183,7 @@ impl GmailClient {
         let response = self.http
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.access_token))
-            .send()
+            .send_guarded(circuit_breaker::GMAIL)
             .await
             .map_err(|e| format!("Failed to stop watch: {}", e))?;
 
@@ -195,7 +196,9 @@ impl GmailClient {
         Ok(())
     }
 
-    /// Send a reply to an email
+    /// Send a reply to an email. With `html_body`, the message is sent as
+    /// multipart/alternative so clients that can't render HTML show `body`.
+    #[allow(clippy::too_many_arguments)]
     pub async fn send_reply(
         &self,
         user_id: &str,
@@ -203,30 +206,113 @@ impl GmailClient {
         to: &str,
         subject: &str,
         body: &str,
+        html_body: Option<&str>,
         in_reply_to: Option<&str>,
     ) -> Result<GmailMessage, String> {
-        // Build RFC 2822 message
-        let mut message = format!(
-            "To: {}\r\nSubject: Re: {}\r\nContent-Type: text/plain; charset=utf-8\r\n",
-            to, subject
-        );
+        let email = OutgoingEmail {
+            to: vec![to.to_string()],
+            subject: format!("Re: {}", subject),
+            body: body.to_string(),
+            html_body: html_body.map(|s| s.to_string()),
+            thread_id: Some(thread_id.to_string()),
+            in_reply_to: in_reply_to.map(|s| s.to_string()),
+            ..Default::default()
+        };
+        self.send_message(user_id, &email).await
+    }
+
+    /// Send a new message (or a reply, when `thread_id` is set)
+    pub async fn send_message(&self, user_id: &str, email: &OutgoingEmail) -> Result<GmailMessage, String> {
+        let url = format!("{}/users/{}/messages/send", GMAIL_API_BASE, user_id);
 
-        if let Some(msg_id) = in_reply_to {
-            message.push_str(&format!("In-Reply-To: {}\r\nReferences: {}\r\n", msg_id, msg_id));
+        let mut request_body = json!({ "raw": email.to_raw() });
+        if let Some(thread_id) = &email.thread_id {
+            request_body["threadId"] = json!(thread_id);
         }
 
-        message.push_str(&format!("\r\n{}", body));
+        let response = self.http
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("Content-Type", "application/json")
+            .body(request_body.to_string())
+            .send_guarded(circuit_breaker::GMAIL)
+            .await
+            .map_err(|e| format!("Failed to send message: {}", e))?;
 
-        // Base64url encode the message
-        use base64::Engine;
-        let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
-        let encoded = engine.encode(message.as_bytes());
+        if !response.status().is_success() {
+            let status = response.status();
+            let error = response.text().await.unwrap_or_default();
+            return Err(format!("Gmail API error ({}): {}", status, error));
+        }
 
-        let url = format!("{}/users/{}/messages/send", GMAIL_API_BASE, user_id);
+        response.json().await
+            .map_err(|e| format!("Failed to parse send response: {}", e))
+    }
+
+    /// Create a draft for the user to review and send from Gmail
+    pub async fn create_draft(&self, user_id: &str, email: &OutgoingEmail) -> Result<GmailDraft, String> {
+        let url = format!("{}/users/{}/drafts", GMAIL_API_BASE, user_id);
+
+        let mut message = json!({ "raw": email.to_raw() });
+        if let Some(thread_id) = &email.thread_id {
+            message["threadId"] = json!(thread_id);
+        }
+
+        let response = self.http
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("Content-Type", "application/json")
+            .body(json!({ "message": message }).to_string())
+            .send_guarded(circuit_breaker::GMAIL)
+            .await
+            .map_err(|e| format!("Failed to create draft: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error = response.text().await.unwrap_or_default();
+            return Err(format!("Gmail API error ({}): {}", status, error));
+        }
+
+        response.json().await
+            .map_err(|e| format!("Failed to parse draft response: {}", e))
+    }
+
+    /// List the mailbox's system and user labels
+    pub async fn list_labels(&self, user_id: &str) -> Result<Vec<GmailLabel>, String> {
+        let url = format!("{}/users/{}/labels", GMAIL_API_BASE, user_id);
+
+        let response = self.http
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .send_guarded(circuit_breaker::GMAIL)
+            .await
+            .map_err(|e| format!("Failed to list labels: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error = response.text().await.unwrap_or_default();
+            return Err(format!("Gmail API error ({}): {}", status, error));
+        }
+
+        let labels: GmailLabelsResponse = response.json().await
+            .map_err(|e| format!("Failed to parse labels response: {}", e))?;
+        Ok(labels.labels.unwrap_or_default())
+    }
+
+    /// Add and/or remove labels on every message in a thread.
+    /// Archiving is removing the `INBOX` label.
+    pub async fn modify_thread(
+        &self,
+        user_id: &str,
+        thread_id: &str,
+        add_label_ids: &[String],
+        remove_label_ids: &[String],
+    ) -> Result<(), String> {
+        let url = format!("{}/users/{}/threads/{}/modify", GMAIL_API_BASE, user_id, thread_id);
 
         let request_body = json!({
-            "raw": encoded,
-            "threadId": thread_id
+            "addLabelIds": add_label_ids,
+            "removeLabelIds": remove_label_ids,
         });
 
         let response = self.http
@@ -234,9 +320,9 @@ impl GmailClient {
             .header("Authorization", format!("Bearer {}", self.access_token))
             .header("Content-Type", "application/json")
             .body(request_body.to_string())
-            .send()
+            .send_guarded(circuit_breaker::GMAIL)
             .await
-            .map_err(|e| format!("Failed to send reply: {}", e))?;
+            .map_err(|e| format!("Failed to modify thread: {}", e))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -244,8 +330,7 @@ impl GmailClient {
             return Err(format!("Gmail API error ({}): {}", status, error));
         }
 
-        response.json().await
-            .map_err(|e| format!("Failed to parse send response: {}", e))
+        Ok(())
     }
 
     /// Get user profile (email address)
@@ -255,7 +340,7 @@ impl GmailClient {
         let response = self.http
             .get(&url)
             .header("Authorization", format!("Bearer {}", self.access_token))
-            .send()
+            .send_guarded(circuit_breaker::GMAIL)
             .await
             .map_err(|e| format!("Failed to get profile: {}", e))?;
 
@@ -12,6 +12,7 @@
 //! 4. Watch set up via Gmail API to monitor specific labels
 
 mod client;
+pub mod policy;
 mod types;
 
 pub use client::GmailClient;
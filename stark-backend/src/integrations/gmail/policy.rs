@@ -0,0 +1,73 @@
+//! Outbound send policy
+//!
+//! The agent may only send mail directly to recipients on the config's
+//! allow-list; everything else has to go through a draft the user reviews.
+
+/// Extract the bare address from `"Name <addr@host>"` or `addr@host`
+pub fn extract_address(recipient: &str) -> String {
+    let trimmed = recipient.trim();
+    let addr = match (trimmed.rfind('<'), trimmed.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &trimmed[start + 1..end],
+        _ => trimmed,
+    };
+    addr.trim().to_lowercase()
+}
+
+/// Whether `recipient` matches the comma-separated allow-list.
+///
+/// Entries are exact addresses, `@domain` for a whole domain, or `*` for
+/// anyone. An empty allow-list blocks every direct send.
+pub fn recipient_allowed(allowlist: &str, recipient: &str) -> bool {
+    let address = extract_address(recipient);
+    if address.is_empty() {
+        return false;
+    }
+
+    allowlist
+        .split(',')
+        .map(|entry| entry.trim().to_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            if entry == "*" {
+                true
+            } else if entry.starts_with('@') {
+                address.ends_with(&entry)
+            } else {
+                address == entry
+            }
+        })
+}
+
+/// Recipients that are not on the allow-list
+pub fn blocked_recipients<'a>(
+    allowlist: &str,
+    recipients: impl IntoIterator<Item = &'a String>,
+) -> Vec<String> {
+    recipients
+        .into_iter()
+        .filter(|r| !recipient_allowed(allowlist, r))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_address() {
+        assert_eq!(extract_address("Alice <Alice@Example.com>"), "alice@example.com");
+        assert_eq!(extract_address(" bob@example.com "), "bob@example.com");
+    }
+
+    #[test]
+    fn test_recipient_allowed() {
+        let allowlist = "alice@example.com, @team.io";
+        assert!(recipient_allowed(allowlist, "Alice <alice@example.com>"));
+        assert!(recipient_allowed(allowlist, "carol@team.io"));
+        assert!(!recipient_allowed(allowlist, "mallory@evil.com"));
+        assert!(!recipient_allowed(allowlist, "carol@notteam.io"));
+        assert!(!recipient_allowed("", "alice@example.com"));
+        assert!(recipient_allowed("*", "anyone@anywhere.org"));
+    }
+}
@@ -31,6 +31,11 @@ pub struct GmailConfig {
     pub response_channel_id: Option<i64>,
     /// Whether to auto-reply to emails
     pub auto_reply: bool,
+    /// Recipients the agent may send to without review (comma-separated
+    /// addresses, `@domain` or `*`); anything else must go through a draft
+    pub send_allowlist: String,
+    /// Hold auto-replies in the outbox until the owner approves them
+    pub review_before_send: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -70,6 +75,7 @@ pub struct GmailNotificationData {
 
 /// Gmail message from API
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GmailMessage {
     pub id: String,
     pub thread_id: String,
@@ -140,7 +146,7 @@ pub struct GmailMessageAdded {
 }
 
 /// Reference to a Gmail message
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct GmailMessageRef {
     pub id: String,
     #[serde(rename = "threadId")]
@@ -289,6 +295,85 @@ fn strip_html(html: &str) -> String {
         .join("\n")
 }
 
+/// Outgoing email, rendered to RFC 2822 for the send and draft endpoints
+#[derive(Debug, Clone, Default)]
+pub struct OutgoingEmail {
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub subject: String,
+    pub body: String,
+    pub html_body: Option<String>,
+    pub thread_id: Option<String>,
+    pub in_reply_to: Option<String>,
+}
+
+impl OutgoingEmail {
+    /// Every address the message will be delivered to (To + Cc)
+    pub fn recipients(&self) -> impl Iterator<Item = &String> {
+        self.to.iter().chain(self.cc.iter())
+    }
+
+    /// Build the raw RFC 2822 message. With `html_body`, the message is sent
+    /// as multipart/alternative so clients that can't render HTML show `body`.
+    pub fn to_rfc2822(&self) -> String {
+        let mut message = format!("To: {}\r\n", self.to.join(", "));
+        if !self.cc.is_empty() {
+            message.push_str(&format!("Cc: {}\r\n", self.cc.join(", ")));
+        }
+        message.push_str(&format!("Subject: {}\r\n", self.subject));
+
+        if let Some(msg_id) = &self.in_reply_to {
+            message.push_str(&format!("In-Reply-To: {}\r\nReferences: {}\r\n", msg_id, msg_id));
+        }
+
+        match &self.html_body {
+            Some(html) => {
+                let boundary = format!("starkbot-{}", uuid::Uuid::new_v4().simple());
+                message.push_str(&format!(
+                    "MIME-Version: 1.0\r\nContent-Type: multipart/alternative; boundary=\"{0}\"\r\n\r\n\
+                     --{0}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{1}\r\n\
+                     --{0}\r\nContent-Type: text/html; charset=utf-8\r\n\r\n<html><body>{2}</body></html>\r\n\
+                     --{0}--",
+                    boundary, self.body, html
+                ));
+            }
+            None => {
+                message.push_str(&format!("Content-Type: text/plain; charset=utf-8\r\n\r\n{}", self.body));
+            }
+        }
+
+        message
+    }
+
+    /// Base64url-encoded message, as the Gmail API expects in `raw`
+    pub fn to_raw(&self) -> String {
+        use base64::Engine;
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(self.to_rfc2822().as_bytes())
+    }
+}
+
+/// Gmail label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GmailLabel {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub label_type: Option<String>,
+}
+
+/// Labels list response from Gmail API
+#[derive(Debug, Deserialize)]
+pub struct GmailLabelsResponse {
+    pub labels: Option<Vec<GmailLabel>>,
+}
+
+/// Draft created through the Gmail API
+#[derive(Debug, Clone, Deserialize)]
+pub struct GmailDraft {
+    pub id: String,
+    pub message: GmailMessageRef,
+}
+
 /// Request to set up Gmail integration
 #[derive(Debug, Deserialize)]
 pub struct SetupGmailRequest {
@@ -309,6 +394,8 @@ pub struct UpdateGmailRequest {
     pub response_channel_id: Option<i64>,
     pub auto_reply: Option<bool>,
     pub enabled: Option<bool>,
+    pub send_allowlist: Option<String>,
+    pub review_before_send: Option<bool>,
 }
 
 /// Response for Gmail config operations
@@ -331,6 +418,8 @@ pub struct GmailConfigSummary {
     pub enabled: bool,
     pub auto_reply: bool,
     pub response_channel_id: Option<i64>,
+    pub send_allowlist: String,
+    pub review_before_send: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -347,6 +436,8 @@ impl From<GmailConfig> for GmailConfigSummary {
             enabled: config.enabled,
             auto_reply: config.auto_reply,
             response_channel_id: config.response_channel_id,
+            send_allowlist: config.send_allowlist,
+            review_before_send: config.review_before_send,
             created_at: config.created_at,
             updated_at: config.updated_at,
         }
@@ -0,0 +1,153 @@
+//! Jira Cloud REST (v3) client
+
+use async_trait::async_trait;
+use reqwest::{Client, Method};
+use serde_json::{json, Value};
+
+use super::{IssueRef, IssueTracker, IssueUpdate};
+
+pub struct JiraTracker {
+    http: Client,
+    base_url: String,
+    email: String,
+    token: String,
+}
+
+impl JiraTracker {
+    pub fn new(base_url: String, email: String, token: String) -> Self {
+        Self {
+            http: crate::http::shared_client().clone(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            email,
+            token,
+        }
+    }
+
+    async fn request(&self, method: Method, path: &str, body: Option<Value>) -> Result<Value, String> {
+        let mut req = self
+            .http
+            .request(method, format!("{}/rest/api/3{}", self.base_url, path))
+            .basic_auth(&self.email, Some(&self.token))
+            .header("Accept", "application/json");
+        if let Some(body) = body {
+            req = req.json(&body);
+        }
+
+        let response = req.send().await.map_err(|e| format!("Jira request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let error = response.text().await.unwrap_or_default();
+            return Err(format!("Jira API error ({}): {}", status, error));
+        }
+        // 204 No Content on updates/transitions
+        let text = response.text().await.unwrap_or_default();
+        if text.is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).map_err(|e| format!("Failed to parse Jira response: {}", e))
+    }
+
+    fn browse_url(&self, key: &str) -> String {
+        format!("{}/browse/{}", self.base_url, key)
+    }
+
+    async fn transition(&self, id: &str, status: &str) -> Result<(), String> {
+        let data = self.request(Method::GET, &format!("/issue/{}/transitions", id), None).await?;
+        let transitions = data["transitions"].as_array().cloned().unwrap_or_default();
+        let matches = |t: &&Value| {
+            [t["to"]["name"].as_str(), t["name"].as_str()]
+                .into_iter()
+                .flatten()
+                .any(|n| n.eq_ignore_ascii_case(status))
+        };
+        let Some(transition_id) = transitions.iter().find(matches).and_then(|t| t["id"].as_str()) else {
+            let names: Vec<&str> = transitions.iter().filter_map(|t| t["to"]["name"].as_str()).collect();
+            return Err(format!("Can't move issue to '{}'. Available: {}", status, names.join(", ")));
+        };
+        self.request(
+            Method::POST,
+            &format!("/issue/{}/transitions", id),
+            Some(json!({ "transition": { "id": transition_id } })),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Atlassian Document Format: one paragraph per non-empty line
+fn adf(text: &str) -> Value {
+    let paragraphs: Vec<Value> = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| json!({ "type": "paragraph", "content": [{ "type": "text", "text": l }] }))
+        .collect();
+    json!({ "type": "doc", "version": 1, "content": paragraphs })
+}
+
+#[async_trait]
+impl IssueTracker for JiraTracker {
+    fn name(&self) -> &'static str {
+        "jira"
+    }
+
+    async fn create_issue(&self, project: &str, title: &str, description: &str) -> Result<IssueRef, String> {
+        let created = self
+            .request(
+                Method::POST,
+                "/issue",
+                Some(json!({
+                    "fields": {
+                        "project": { "key": project },
+                        "summary": title,
+                        "description": adf(description),
+                        "issuetype": { "name": "Task" },
+                    }
+                })),
+            )
+            .await?;
+        let key = created["key"].as_str().ok_or("Jira response missing issue key")?;
+        self.get_issue(key).await
+    }
+
+    async fn get_issue(&self, id: &str) -> Result<IssueRef, String> {
+        let issue = self
+            .request(Method::GET, &format!("/issue/{}?fields=summary,status", id), None)
+            .await?;
+        let key = issue["key"].as_str().ok_or("Jira response missing issue key")?.to_string();
+        Ok(IssueRef {
+            external_id: issue["id"].as_str().unwrap_or(&key).to_string(),
+            url: Some(self.browse_url(&key)),
+            title: issue["fields"]["summary"].as_str().unwrap_or_default().to_string(),
+            status: issue["fields"]["status"]["name"].as_str().map(|s| s.to_string()),
+            key,
+        })
+    }
+
+    async fn update_issue(&self, id: &str, update: &IssueUpdate) -> Result<IssueRef, String> {
+        let mut fields = serde_json::Map::new();
+        if let Some(title) = &update.title {
+            fields.insert("summary".to_string(), json!(title));
+        }
+        if let Some(description) = &update.description {
+            fields.insert("description".to_string(), adf(description));
+        }
+        if !fields.is_empty() {
+            self.request(Method::PUT, &format!("/issue/{}", id), Some(json!({ "fields": fields })))
+                .await?;
+        }
+        if let Some(status) = &update.status {
+            self.transition(id, status).await?;
+        }
+        self.get_issue(id).await
+    }
+
+    async fn add_comment(&self, id: &str, body: &str) -> Result<(), String> {
+        self.request(
+            Method::POST,
+            &format!("/issue/{}/comment", id),
+            Some(json!({ "body": adf(body) })),
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+//! Linear GraphQL client
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::{IssueRef, IssueTracker, IssueUpdate};
+
+const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
+const ISSUE_FIELDS: &str = "id identifier title url state { name }";
+
+pub struct LinearTracker {
+    http: Client,
+    api_key: String,
+}
+
+impl LinearTracker {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            http: crate::http::shared_client().clone(),
+            api_key,
+        }
+    }
+
+    async fn graphql(&self, query: &str, variables: Value) -> Result<Value, String> {
+        let response = self
+            .http
+            .post(LINEAR_API_URL)
+            .header("Authorization", &self.api_key)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .map_err(|e| format!("Linear request failed: {}", e))?;
+
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Linear response: {}", e))?;
+        if let Some(errors) = body["errors"].as_array().filter(|e| !e.is_empty()) {
+            let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+            return Err(format!("Linear API error: {}", messages.join("; ")));
+        }
+        if !status.is_success() {
+            return Err(format!("Linear API error ({})", status));
+        }
+        Ok(body["data"].clone())
+    }
+
+    async fn team_id(&self, team_key: &str) -> Result<String, String> {
+        let data = self
+            .graphql(
+                "query($key: String!) { teams(filter: { key: { eq: $key } }) { nodes { id } } }",
+                json!({ "key": team_key }),
+            )
+            .await?;
+        data["teams"]["nodes"][0]["id"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| format!("Linear team '{}' not found", team_key))
+    }
+
+    async fn state_id(&self, issue_id: &str, state_name: &str) -> Result<String, String> {
+        let data = self
+            .graphql(
+                "query($id: String!) { issue(id: $id) { team { states { nodes { id name } } } } }",
+                json!({ "id": issue_id }),
+            )
+            .await?;
+        let states = data["issue"]["team"]["states"]["nodes"].as_array().cloned().unwrap_or_default();
+        states
+            .iter()
+            .find(|s| s["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(state_name)))
+            .and_then(|s| s["id"].as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| {
+                let names: Vec<&str> = states.iter().filter_map(|s| s["name"].as_str()).collect();
+                format!("Unknown Linear state '{}'. Available: {}", state_name, names.join(", "))
+            })
+    }
+}
+
+fn issue_ref(issue: &Value) -> Result<IssueRef, String> {
+    Ok(IssueRef {
+        external_id: issue["id"].as_str().ok_or("Linear response missing issue id")?.to_string(),
+        key: issue["identifier"].as_str().unwrap_or_default().to_string(),
+        title: issue["title"].as_str().unwrap_or_default().to_string(),
+        url: issue["url"].as_str().map(|s| s.to_string()),
+        status: issue["state"]["name"].as_str().map(|s| s.to_string()),
+    })
+}
+
+#[async_trait]
+impl IssueTracker for LinearTracker {
+    fn name(&self) -> &'static str {
+        "linear"
+    }
+
+    async fn create_issue(&self, project: &str, title: &str, description: &str) -> Result<IssueRef, String> {
+        let team_id = self.team_id(project).await?;
+        let data = self
+            .graphql(
+                &format!(
+                    "mutation($input: IssueCreateInput!) {{ issueCreate(input: $input) {{ success issue {{ {} }} }} }}",
+                    ISSUE_FIELDS
+                ),
+                json!({ "input": { "teamId": team_id, "title": title, "description": description } }),
+            )
+            .await?;
+        issue_ref(&data["issueCreate"]["issue"])
+    }
+
+    async fn get_issue(&self, id: &str) -> Result<IssueRef, String> {
+        let data = self
+            .graphql(
+                &format!("query($id: String!) {{ issue(id: $id) {{ {} }} }}", ISSUE_FIELDS),
+                json!({ "id": id }),
+            )
+            .await?;
+        issue_ref(&data["issue"])
+    }
+
+    async fn update_issue(&self, id: &str, update: &IssueUpdate) -> Result<IssueRef, String> {
+        let mut input = serde_json::Map::new();
+        if let Some(title) = &update.title {
+            input.insert("title".to_string(), json!(title));
+        }
+        if let Some(description) = &update.description {
+            input.insert("description".to_string(), json!(description));
+        }
+        if let Some(status) = &update.status {
+            input.insert("stateId".to_string(), json!(self.state_id(id, status).await?));
+        }
+        if input.is_empty() {
+            return self.get_issue(id).await;
+        }
+
+        let data = self
+            .graphql(
+                &format!(
+                    "mutation($id: String!, $input: IssueUpdateInput!) {{ issueUpdate(id: $id, input: $input) {{ success issue {{ {} }} }} }}",
+                    ISSUE_FIELDS
+                ),
+                json!({ "id": id, "input": input }),
+            )
+            .await?;
+        issue_ref(&data["issueUpdate"]["issue"])
+    }
+
+    async fn add_comment(&self, id: &str, body: &str) -> Result<(), String> {
+        let data = self
+            .graphql(
+                "mutation($input: CommentCreateInput!) { commentCreate(input: $input) { success } }",
+                json!({ "input": { "issueId": id, "body": body } }),
+            )
+            .await?;
+        if data["commentCreate"]["success"].as_bool() == Some(true) {
+            Ok(())
+        } else {
+            Err("Linear did not accept the comment".to_string())
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Issue tracker integration (Linear, Jira)
+//!
+//! The `issue_tracker` tool files the agent's completed tasks or user
+//! requests as issues, updates them and comments on them. Each filed issue is
+//! recorded in `tracked_issues` with the session it came from; status-change
+//! webhooks (`/api/issue-tracker/webhook/{tracker}`) are matched against those
+//! rows and reported back into that session.
+//!
+//! Which team/project an issue goes to is configured per channel with the
+//! `issue_project` channel setting ("linear:ENG", "jira:OPS").
+
+mod jira;
+mod linear;
+pub mod webhook;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::controllers::api_keys::ApiKeyId;
+use crate::db::Database;
+
+pub use jira::JiraTracker;
+pub use linear::LinearTracker;
+
+/// An issue as reported by the tracker
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueRef {
+    pub external_id: String,
+    /// Human-readable key (ENG-123 / OPS-45)
+    pub key: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub status: Option<String>,
+}
+
+/// Fields to change on an existing issue
+#[derive(Debug, Clone, Default)]
+pub struct IssueUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Target workflow state / status name (e.g. "In Progress", "Done")
+    pub status: Option<String>,
+}
+
+#[async_trait]
+pub trait IssueTracker: Send + Sync {
+    /// Tracker name stored in `tracked_issues.tracker`
+    fn name(&self) -> &'static str;
+
+    /// Create an issue in a team (Linear team key) or project (Jira project key)
+    async fn create_issue(&self, project: &str, title: &str, description: &str) -> Result<IssueRef, String>;
+
+    /// Fetch an issue by external ID or key
+    async fn get_issue(&self, id: &str) -> Result<IssueRef, String>;
+
+    async fn update_issue(&self, id: &str, update: &IssueUpdate) -> Result<IssueRef, String>;
+
+    async fn add_comment(&self, id: &str, body: &str) -> Result<(), String>;
+}
+
+/// A `tracker:project` mapping, as stored in the `issue_project` channel setting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectMapping {
+    pub tracker: String,
+    pub project: String,
+}
+
+impl ProjectMapping {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (tracker, project) = value
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid issue project '{}': expected linear:<TEAM> or jira:<PROJECT>", value))?;
+        let tracker = tracker.trim().to_lowercase();
+        let project = project.trim();
+        if tracker != "linear" && tracker != "jira" {
+            return Err(format!("Unknown issue tracker '{}': expected linear or jira", tracker));
+        }
+        if project.is_empty() {
+            return Err(format!("Invalid issue project '{}': missing team/project key", value));
+        }
+        Ok(Self {
+            tracker,
+            project: project.to_string(),
+        })
+    }
+}
+
+/// Read a key from the API keys page, falling back to the environment
+pub fn api_key(db: &Database, key_id: ApiKeyId) -> Option<String> {
+    db.get_api_key(key_id.as_str())
+        .ok()
+        .flatten()
+        .map(|k| k.api_key)
+        .filter(|k| !k.is_empty())
+        .or_else(|| std::env::var(key_id.as_str()).ok().filter(|k| !k.is_empty()))
+}
+
+/// Build a tracker client from the configured API keys
+pub fn tracker_from_keys(
+    tracker: &str,
+    key: impl Fn(ApiKeyId) -> Option<String>,
+) -> Result<Box<dyn IssueTracker>, String> {
+    match tracker {
+        "linear" => {
+            let api_key = key(ApiKeyId::LinearApiKey).ok_or("LINEAR_API_KEY is not configured")?;
+            Ok(Box::new(LinearTracker::new(api_key)))
+        }
+        "jira" => {
+            let base_url = key(ApiKeyId::JiraBaseUrl).ok_or("JIRA_BASE_URL is not configured")?;
+            let email = key(ApiKeyId::JiraEmail).ok_or("JIRA_EMAIL is not configured")?;
+            let token = key(ApiKeyId::JiraApiToken).ok_or("JIRA_API_TOKEN is not configured")?;
+            Ok(Box::new(JiraTracker::new(base_url, email, token)))
+        }
+        other => Err(format!("Unknown issue tracker '{}': expected linear or jira", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_mapping_parse() {
+        assert_eq!(
+            ProjectMapping::parse(" Linear:ENG ").unwrap(),
+            ProjectMapping { tracker: "linear".to_string(), project: "ENG".to_string() }
+        );
+        assert_eq!(ProjectMapping::parse("jira:OPS").unwrap().project, "OPS");
+        assert!(ProjectMapping::parse("ENG").is_err());
+        assert!(ProjectMapping::parse("github:repo").is_err());
+        assert!(ProjectMapping::parse("jira:").is_err());
+    }
+}
@@ -0,0 +1,184 @@
+//! Status-change webhooks from Linear and Jira
+//!
+//! Both trackers sign the raw body with HMAC-SHA256 using a shared secret
+//! (Linear: `Linear-Signature: <hex>`, Jira: `X-Hub-Signature: sha256=<hex>`).
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use crate::db::tables::tracked_issues::TrackedIssue;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::models::MessageRole;
+
+/// An issue moved to a new status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusChange {
+    pub external_id: String,
+    pub key: String,
+    pub title: String,
+    pub status: String,
+    pub url: Option<String>,
+}
+
+/// Verify a hex HMAC-SHA256 signature (optionally prefixed with `sha256=`)
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let signature = signature.trim();
+    let hex_sig = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let Ok(expected) = hex::decode(hex_sig) else {
+        return false;
+    };
+    let Ok(mut mac) = <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Linear: Issue update whose workflow state changed
+pub fn parse_linear(payload: &Value) -> Option<StatusChange> {
+    if payload["type"].as_str() != Some("Issue") || payload["action"].as_str() != Some("update") {
+        return None;
+    }
+    payload["updatedFrom"].get("stateId")?;
+    let data = &payload["data"];
+    Some(StatusChange {
+        external_id: data["id"].as_str()?.to_string(),
+        key: data["identifier"].as_str().unwrap_or_default().to_string(),
+        title: data["title"].as_str().unwrap_or_default().to_string(),
+        status: data["state"]["name"].as_str()?.to_string(),
+        url: data["url"].as_str().map(|s| s.to_string()),
+    })
+}
+
+/// Jira: issue_updated event with a status change in the changelog
+pub fn parse_jira(payload: &Value) -> Option<StatusChange> {
+    if payload["webhookEvent"].as_str() != Some("jira:issue_updated") {
+        return None;
+    }
+    let status_item = payload["changelog"]["items"]
+        .as_array()?
+        .iter()
+        .find(|item| item["field"].as_str() == Some("status"))?;
+    let issue = &payload["issue"];
+    Some(StatusChange {
+        external_id: issue["id"].as_str()?.to_string(),
+        key: issue["key"].as_str().unwrap_or_default().to_string(),
+        title: issue["fields"]["summary"].as_str().unwrap_or_default().to_string(),
+        status: status_item["toString"].as_str()?.to_string(),
+        url: None,
+    })
+}
+
+/// Record a status change on a tracked issue and report it into the session
+/// the issue was filed from. Returns None for issues the agent didn't file.
+pub fn apply_status_change(
+    db: &Database,
+    broadcaster: &EventBroadcaster,
+    tracker: &str,
+    change: &StatusChange,
+) -> Result<Option<TrackedIssue>, String> {
+    let issue = match db.find_tracked_issue(tracker, &change.external_id).map_err(|e| e.to_string())? {
+        Some(issue) => issue,
+        None => match db.find_tracked_issue(tracker, &change.key).map_err(|e| e.to_string())? {
+            Some(issue) => issue,
+            None => return Ok(None),
+        },
+    };
+    if issue.status.as_deref() == Some(change.status.as_str()) {
+        return Ok(Some(issue));
+    }
+
+    db.update_tracked_issue(issue.id, None, Some(&change.status))
+        .map_err(|e| e.to_string())?;
+
+    if let Some(session_id) = issue.session_id {
+        let note = format!(
+            "[Issue update] {} \"{}\" moved from {} to {}",
+            issue.issue_key,
+            issue.title,
+            issue.status.as_deref().unwrap_or("unknown"),
+            change.status
+        );
+        if let Err(e) = db.add_session_message(session_id, MessageRole::System, &note, None, None, None, None) {
+            log::warn!("[ISSUE_TRACKER] Failed to record status change in session {}: {}", session_id, e);
+        }
+    }
+
+    broadcaster.broadcast(GatewayEvent::custom(
+        "issue_status_changed",
+        json!({
+            "tracker": tracker,
+            "issue_key": issue.issue_key,
+            "title": issue.title,
+            "from": issue.status,
+            "to": change.status,
+            "url": change.url.clone().or(issue.url.clone()),
+            "session_id": issue.session_id,
+        }),
+    ));
+
+    db.get_tracked_issue(issue.id).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_signature() {
+        let body = br#"{"action":"update"}"#;
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(b"secret").unwrap();
+        mac.update(body);
+        let sig = hex::encode(mac.finalize().into_bytes());
+
+        assert!(verify_signature("secret", body, &sig));
+        assert!(verify_signature("secret", body, &format!("sha256={}", sig)));
+        assert!(!verify_signature("other", body, &sig));
+        assert!(!verify_signature("secret", b"tampered", &sig));
+        assert!(!verify_signature("secret", body, "not-hex"));
+    }
+
+    #[test]
+    fn test_parse_linear() {
+        let payload = json!({
+            "action": "update",
+            "type": "Issue",
+            "data": { "id": "uuid-1", "identifier": "ENG-12", "title": "Fix login",
+                      "url": "https://linear.app/acme/issue/ENG-12", "state": { "name": "Done" } },
+            "updatedFrom": { "stateId": "old" }
+        });
+        let change = parse_linear(&payload).unwrap();
+        assert_eq!(change.key, "ENG-12");
+        assert_eq!(change.status, "Done");
+
+        // Title-only edits are not status changes
+        let payload = json!({
+            "action": "update", "type": "Issue",
+            "data": { "id": "uuid-1", "state": { "name": "Done" } },
+            "updatedFrom": { "title": "Old" }
+        });
+        assert!(parse_linear(&payload).is_none());
+    }
+
+    #[test]
+    fn test_parse_jira() {
+        let payload = json!({
+            "webhookEvent": "jira:issue_updated",
+            "issue": { "id": "10001", "key": "OPS-4", "fields": { "summary": "Rotate keys" } },
+            "changelog": { "items": [
+                { "field": "assignee", "toString": "Bob" },
+                { "field": "status", "fromString": "To Do", "toString": "In Progress" }
+            ] }
+        });
+        let change = parse_jira(&payload).unwrap();
+        assert_eq!(change.external_id, "10001");
+        assert_eq!(change.status, "In Progress");
+
+        let payload = json!({ "webhookEvent": "jira:issue_created", "issue": { "id": "1" } });
+        assert!(parse_jira(&payload).is_none());
+    }
+}
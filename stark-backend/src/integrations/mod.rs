@@ -2,5 +2,8 @@
 //!
 //! This module contains integrations with external services like Gmail, etc.
 
+pub mod farcaster;
 pub mod gmail;
+pub mod issue_tracker;
+pub mod note_export;
 pub mod starkhub_client;
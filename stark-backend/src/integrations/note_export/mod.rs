@@ -0,0 +1,103 @@
+//! Note export integration
+//!
+//! Mirrors selected memories and session summaries into an external
+//! knowledge store — a Notion workspace or a local Obsidian vault folder.
+//!
+//! ## Linking
+//! Each exported item gets a `note_exports` row, and memories also store the
+//! external page ID (`memories.external_page_id`), so the agent can point at
+//! the note and edits can be traced back.
+//!
+//! ## Conflicts
+//! Each sync compares the local rendering and the external body with the
+//! hashes recorded at the previous sync: local-only changes are pushed,
+//! external-only edits are pulled into the memory, and items changed on both
+//! sides are flagged `conflict` and left alone until resolved.
+
+mod notion;
+mod obsidian;
+pub mod sync;
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+use crate::db::tables::note_exports::NoteExportConfig;
+
+pub use notion::NotionTarget;
+pub use obsidian::ObsidianTarget;
+
+/// A note rendered from a local item
+#[derive(Debug, Clone)]
+pub struct ExportNote {
+    /// "memory" or "session"
+    pub kind: &'static str,
+    pub source_id: i64,
+    pub title: String,
+    pub body: String,
+    pub tags: Vec<String>,
+}
+
+impl ExportNote {
+    /// Hash of everything that gets exported; changes when a push is needed
+    pub fn local_hash(&self) -> String {
+        content_hash(&format!("{}\n{}\n{}", self.title, normalize_body(&self.body), self.tags.join(",")))
+    }
+}
+
+/// An external knowledge store notes are mirrored into
+#[async_trait]
+pub trait NoteTarget: Send + Sync {
+    /// Target name stored in `note_exports.target`
+    fn name(&self) -> &'static str;
+
+    /// Create the external note and return its ID
+    async fn create(&self, note: &ExportNote) -> Result<String, String>;
+
+    /// Overwrite an existing external note
+    async fn update(&self, external_id: &str, note: &ExportNote) -> Result<(), String>;
+
+    /// Current body of the external note, or None if it was deleted
+    async fn fetch_body(&self, external_id: &str) -> Result<Option<String>, String>;
+}
+
+/// Build the configured target
+pub fn target_for(config: &NoteExportConfig) -> Result<Box<dyn NoteTarget>, String> {
+    match config.target.as_str() {
+        "notion" => {
+            let token = config
+                .notion_token
+                .clone()
+                .filter(|t| !t.is_empty())
+                .ok_or("Notion export needs an integration token")?;
+            let parent = config
+                .notion_parent_page_id
+                .clone()
+                .filter(|p| !p.is_empty())
+                .ok_or("Notion export needs a parent page ID")?;
+            Ok(Box::new(NotionTarget::new(token, parent)))
+        }
+        "obsidian" => {
+            let vault = config
+                .obsidian_vault_path
+                .clone()
+                .filter(|p| !p.is_empty())
+                .ok_or("Obsidian export needs a vault path")?;
+            Ok(Box::new(ObsidianTarget::new(vault.into(), config.folder.clone())))
+        }
+        other => Err(format!("Unknown note export target '{}'", other)),
+    }
+}
+
+/// Normalize a body so both targets hash the same text the same way
+/// (Notion stores one paragraph per line and drops blank lines).
+pub fn normalize_body(body: &str) -> String {
+    body.lines()
+        .map(|l| l.trim_end())
+        .filter(|l| !l.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn content_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
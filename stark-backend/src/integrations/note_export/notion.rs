@@ -0,0 +1,217 @@
+//! Notion target — one child page per item under a configured parent page
+
+use async_trait::async_trait;
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Value};
+
+use super::{normalize_body, ExportNote, NoteTarget};
+
+const NOTION_API_BASE: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// Notion rejects rich_text items longer than this
+const MAX_TEXT_LEN: usize = 2000;
+/// Max blocks per append request
+const MAX_BLOCKS_PER_REQUEST: usize = 100;
+
+pub struct NotionTarget {
+    http: Client,
+    token: String,
+    parent_page_id: String,
+}
+
+impl NotionTarget {
+    pub fn new(token: String, parent_page_id: String) -> Self {
+        Self {
+            http: crate::http::shared_client().clone(),
+            token,
+            parent_page_id,
+        }
+    }
+
+    /// Send a request; Ok(None) on 404 so callers can detect deleted pages
+    async fn request(&self, method: Method, path: &str, body: Option<Value>) -> Result<Option<Value>, String> {
+        let mut req = self
+            .http
+            .request(method, format!("{}{}", NOTION_API_BASE, path))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Notion-Version", NOTION_VERSION);
+        if let Some(body) = body {
+            req = req.json(&body);
+        }
+
+        let response = req.send().await.map_err(|e| format!("Notion request failed: {}", e))?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let error = response.text().await.unwrap_or_default();
+            return Err(format!("Notion API error ({}): {}", status, error));
+        }
+        response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| format!("Failed to parse Notion response: {}", e))
+    }
+
+    fn title_property(title: &str) -> Value {
+        json!({ "title": { "title": [{ "text": { "content": truncate(title, MAX_TEXT_LEN) } }] } })
+    }
+
+    /// One paragraph block per non-empty line, split into 2000-char text runs
+    fn blocks(body: &str) -> Vec<Value> {
+        normalize_body(body)
+            .lines()
+            .map(|line| {
+                let chars: Vec<char> = line.chars().collect();
+                let rich_text: Vec<Value> = chars
+                    .chunks(MAX_TEXT_LEN)
+                    .map(|c| json!({ "type": "text", "text": { "content": c.iter().collect::<String>() } }))
+                    .collect();
+                json!({ "object": "block", "type": "paragraph", "paragraph": { "rich_text": rich_text } })
+            })
+            .collect()
+    }
+
+    async fn append_blocks(&self, page_id: &str, blocks: Vec<Value>) -> Result<(), String> {
+        for chunk in blocks.chunks(MAX_BLOCKS_PER_REQUEST) {
+            self.request(
+                Method::PATCH,
+                &format!("/blocks/{}/children", page_id),
+                Some(json!({ "children": chunk })),
+            )
+            .await?
+            .ok_or("Notion page not found")?;
+        }
+        Ok(())
+    }
+
+    /// All child blocks of a page, following pagination
+    async fn list_children(&self, page_id: &str) -> Result<Option<Vec<Value>>, String> {
+        let mut blocks = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut path = format!("/blocks/{}/children?page_size=100", page_id);
+            if let Some(c) = &cursor {
+                path.push_str(&format!("&start_cursor={}", c));
+            }
+            let Some(page) = self.request(Method::GET, &path, None).await? else {
+                return Ok(None);
+            };
+            if let Some(results) = page["results"].as_array() {
+                blocks.extend(results.iter().cloned());
+            }
+            cursor = page["next_cursor"].as_str().map(|s| s.to_string());
+            if !page["has_more"].as_bool().unwrap_or(false) || cursor.is_none() {
+                return Ok(Some(blocks));
+            }
+        }
+    }
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    s.chars().take(max_chars).collect()
+}
+
+/// Plain text of a block's rich_text, whatever its block type
+fn block_text(block: &Value) -> String {
+    let Some(block_type) = block["type"].as_str() else {
+        return String::new();
+    };
+    block[block_type]["rich_text"]
+        .as_array()
+        .map(|runs| runs.iter().filter_map(|r| r["plain_text"].as_str()).collect())
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl NoteTarget for NotionTarget {
+    fn name(&self) -> &'static str {
+        "notion"
+    }
+
+    async fn create(&self, note: &ExportNote) -> Result<String, String> {
+        let blocks = Self::blocks(&note.body);
+        let (first, rest) = blocks.split_at(blocks.len().min(MAX_BLOCKS_PER_REQUEST));
+        let page = self
+            .request(
+                Method::POST,
+                "/pages",
+                Some(json!({
+                    "parent": { "page_id": self.parent_page_id },
+                    "properties": Self::title_property(&note.title),
+                    "children": first,
+                })),
+            )
+            .await?
+            .ok_or("Notion parent page not found (is it shared with the integration?)")?;
+
+        let page_id = page["id"].as_str().ok_or("Notion response missing page id")?.to_string();
+        if !rest.is_empty() {
+            self.append_blocks(&page_id, rest.to_vec()).await?;
+        }
+        Ok(page_id)
+    }
+
+    async fn update(&self, external_id: &str, note: &ExportNote) -> Result<(), String> {
+        self.request(
+            Method::PATCH,
+            &format!("/pages/{}", external_id),
+            Some(json!({ "properties": Self::title_property(&note.title) })),
+        )
+        .await?
+        .ok_or("Notion page not found")?;
+
+        // Notion has no "replace content" call: delete the old blocks, then append
+        let existing = self.list_children(external_id).await?.unwrap_or_default();
+        for block in existing {
+            if let Some(id) = block["id"].as_str() {
+                self.request(Method::DELETE, &format!("/blocks/{}", id), None).await?;
+            }
+        }
+        self.append_blocks(external_id, Self::blocks(&note.body)).await
+    }
+
+    async fn fetch_body(&self, external_id: &str) -> Result<Option<String>, String> {
+        let Some(page) = self.request(Method::GET, &format!("/pages/{}", external_id), None).await? else {
+            return Ok(None);
+        };
+        if page["archived"].as_bool().unwrap_or(false) || page["in_trash"].as_bool().unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let Some(blocks) = self.list_children(external_id).await? else {
+            return Ok(None);
+        };
+        let text: Vec<String> = blocks.iter().map(block_text).collect();
+        Ok(Some(normalize_body(&text.join("\n"))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_roundtrip_text() {
+        let long_line = "x".repeat(MAX_TEXT_LEN + 10);
+        let body = format!("first\n\nsecond\n{}", long_line);
+        let blocks = NotionTarget::blocks(&body);
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[2]["paragraph"]["rich_text"].as_array().unwrap().len(), 2);
+
+        // Blocks read back from the API carry plain_text on each run
+        let read_back: Vec<String> = blocks
+            .iter()
+            .map(|b| {
+                let mut b = b.clone();
+                for run in b["paragraph"]["rich_text"].as_array_mut().unwrap() {
+                    run["plain_text"] = run["text"]["content"].clone();
+                }
+                block_text(&b)
+            })
+            .collect();
+        assert_eq!(normalize_body(&read_back.join("\n")), normalize_body(&body));
+    }
+}
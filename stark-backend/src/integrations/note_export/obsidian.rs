@@ -0,0 +1,117 @@
+//! Obsidian vault target — one markdown file per item under `<vault>/<folder>/`
+
+use async_trait::async_trait;
+use std::path::{Component, Path, PathBuf};
+
+use super::{normalize_body, ExportNote, NoteTarget};
+use crate::notes::frontmatter::{generate_frontmatter, parse_note};
+
+pub struct ObsidianTarget {
+    vault: PathBuf,
+    folder: String,
+}
+
+impl ObsidianTarget {
+    pub fn new(vault: PathBuf, folder: String) -> Self {
+        Self { vault, folder }
+    }
+
+    /// Vault-relative path for a note; stable across title changes
+    fn relative_path(&self, note: &ExportNote) -> String {
+        let sub = if note.kind == "memory" { "Memories" } else { "Sessions" };
+        format!("{}/{}/{}-{}.md", self.folder.trim_matches('/'), sub, note.kind, note.source_id)
+    }
+
+    fn resolve(&self, external_id: &str) -> Result<PathBuf, String> {
+        let rel = Path::new(external_id);
+        if rel.components().any(|c| !matches!(c, Component::Normal(_))) {
+            return Err(format!("Invalid vault path '{}'", external_id));
+        }
+        Ok(self.vault.join(rel))
+    }
+
+    fn render(note: &ExportNote) -> String {
+        let tags: Vec<String> = note.tags.iter().map(|t| t.replace(' ', "-")).collect();
+        let note_type = if note.kind == "memory" { "memory" } else { "summary" };
+        format!(
+            "{}\n\n{}\n",
+            generate_frontmatter(&note.title, &tags, &[], note_type, None),
+            note.body.trim_end()
+        )
+    }
+
+    async fn write(&self, external_id: &str, note: &ExportNote) -> Result<(), String> {
+        if !self.vault.is_dir() {
+            return Err(format!("Obsidian vault not found at {}", self.vault.display()));
+        }
+        let path = self.resolve(external_id)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        tokio::fs::write(&path, Self::render(note))
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+#[async_trait]
+impl NoteTarget for ObsidianTarget {
+    fn name(&self) -> &'static str {
+        "obsidian"
+    }
+
+    async fn create(&self, note: &ExportNote) -> Result<String, String> {
+        let external_id = self.relative_path(note);
+        self.write(&external_id, note).await?;
+        Ok(external_id)
+    }
+
+    async fn update(&self, external_id: &str, note: &ExportNote) -> Result<(), String> {
+        self.write(external_id, note).await
+    }
+
+    async fn fetch_body(&self, external_id: &str) -> Result<Option<String>, String> {
+        let path = self.resolve(external_id)?;
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => Ok(Some(normalize_body(&parse_note(&content).body))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_roundtrip_and_external_edit() {
+        let vault = tempfile::tempdir().unwrap();
+        let target = ObsidianTarget::new(vault.path().to_path_buf(), "StarkBot".to_string());
+        let note = ExportNote {
+            kind: "memory",
+            source_id: 3,
+            title: "Prefers dark mode".to_string(),
+            body: "User prefers dark mode.\n\nAlso likes vim.".to_string(),
+            tags: vec!["preferences".to_string()],
+        };
+
+        let id = target.create(&note).await.unwrap();
+        assert_eq!(id, "StarkBot/Memories/memory-3.md");
+        let body = target.fetch_body(&id).await.unwrap().unwrap();
+        assert_eq!(body, normalize_body(&note.body));
+
+        // An edit made in Obsidian is visible to the next sync
+        let path = vault.path().join(&id);
+        let edited = std::fs::read_to_string(&path).unwrap().replace("vim", "helix");
+        std::fs::write(&path, edited).unwrap();
+        let body = target.fetch_body(&id).await.unwrap().unwrap();
+        assert!(body.contains("helix"));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(target.fetch_body(&id).await.unwrap().is_none());
+        assert!(target.fetch_body("../escape.md").await.is_err());
+    }
+}
@@ -0,0 +1,353 @@
+//! Note export sync: push local changes, pull external edits, flag conflicts
+
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{content_hash, target_for, ExportNote, NoteTarget};
+use crate::db::tables::memories::MemoryRow;
+use crate::db::tables::note_exports::NoteExport;
+use crate::db::Database;
+
+/// Outcome of one sync run
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SyncReport {
+    pub created: usize,
+    pub updated: usize,
+    pub pulled: usize,
+    pub conflicts: usize,
+    pub errors: Vec<String>,
+}
+
+/// What to do with an already-exported item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    None,
+    Push,
+    Pull,
+    Conflict,
+}
+
+/// Decide from which sides changed since the last sync
+pub fn decide(local_changed: bool, remote_changed: bool) -> SyncAction {
+    match (local_changed, remote_changed) {
+        (false, false) => SyncAction::None,
+        (true, false) => SyncAction::Push,
+        (false, true) => SyncAction::Pull,
+        (true, true) => SyncAction::Conflict,
+    }
+}
+
+fn memory_note(memory: &MemoryRow) -> ExportNote {
+    let title = memory.entity_name.clone().filter(|n| !n.is_empty()).unwrap_or_else(|| {
+        let first_line = memory.content.lines().next().unwrap_or_default();
+        let title: String = first_line.chars().take(60).collect();
+        if title.is_empty() { format!("Memory {}", memory.id) } else { title }
+    });
+
+    let mut tags: Vec<String> = memory
+        .tags
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    if let Some(category) = memory.category.as_ref().filter(|c| !c.is_empty()) {
+        tags.push(category.clone());
+    }
+
+    ExportNote {
+        kind: "memory",
+        source_id: memory.id,
+        title,
+        body: memory.content.clone(),
+        tags,
+    }
+}
+
+fn session_note(session_id: i64, session_key: &str, summary: &str) -> ExportNote {
+    ExportNote {
+        kind: "session",
+        source_id: session_id,
+        title: format!("Session summary: {}", session_key),
+        body: summary.to_string(),
+        tags: vec!["session-summary".to_string()],
+    }
+}
+
+/// Re-render a single local item (None if it no longer exists)
+fn load_note(db: &Database, kind: &str, source_id: i64) -> Result<Option<ExportNote>, String> {
+    match kind {
+        "memory" => Ok(db.get_memory(source_id).map_err(|e| e.to_string())?.map(|m| memory_note(&m))),
+        "session" => {
+            let Some(session) = db.get_chat_session(source_id).map_err(|e| e.to_string())? else {
+                return Ok(None);
+            };
+            let summary = db.get_session_compaction_summary(source_id).map_err(|e| e.to_string())?;
+            Ok(summary.map(|s| session_note(source_id, &session.session_key, &s)))
+        }
+        other => Err(format!("Unknown export kind '{}'", other)),
+    }
+}
+
+/// Push a note and record the link with the external body as now stored
+async fn push(
+    db: &Database,
+    target: &dyn NoteTarget,
+    note: &ExportNote,
+    external_id: Option<&str>,
+) -> Result<(), String> {
+    let external_id = match external_id {
+        Some(id) => {
+            target.update(id, note).await?;
+            id.to_string()
+        }
+        None => target.create(note).await?,
+    };
+    let remote = target.fetch_body(&external_id).await?.unwrap_or_default();
+
+    db.upsert_note_export(
+        note.kind,
+        note.source_id,
+        target.name(),
+        &external_id,
+        &note.local_hash(),
+        &content_hash(&remote),
+    )
+    .map_err(|e| e.to_string())?;
+    if note.kind == "memory" {
+        db.set_memory_external_page_id(note.source_id, Some(&external_id))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Take the external body as the new truth. Memories get their content
+/// replaced; session summaries are agent-generated, so the edit is accepted
+/// on the external side only.
+fn pull(db: &Database, target: &dyn NoteTarget, export: &NoteExport, note: &ExportNote, remote: &str) -> Result<(), String> {
+    let local_hash = if note.kind == "memory" {
+        db.update_memory_content(note.source_id, remote).map_err(|e| e.to_string())?;
+        ExportNote { body: remote.to_string(), ..note.clone() }.local_hash()
+    } else {
+        note.local_hash()
+    };
+
+    db.upsert_note_export(
+        note.kind,
+        note.source_id,
+        target.name(),
+        &export.external_id,
+        &local_hash,
+        &content_hash(remote),
+    )
+    .map_err(|e| e.to_string())
+}
+
+async fn sync_note(db: &Database, target: &dyn NoteTarget, note: &ExportNote, report: &mut SyncReport) -> Result<(), String> {
+    let export = db
+        .get_note_export(note.kind, note.source_id, target.name())
+        .map_err(|e| e.to_string())?;
+
+    let Some(export) = export else {
+        push(db, target, note, None).await?;
+        report.created += 1;
+        return Ok(());
+    };
+
+    // Conflicted and externally deleted items wait for an explicit resolution
+    match export.status.as_str() {
+        "conflict" => {
+            report.conflicts += 1;
+            return Ok(());
+        }
+        "remote_deleted" => return Ok(()),
+        _ => {}
+    }
+
+    let Some(remote) = target.fetch_body(&export.external_id).await? else {
+        db.set_note_export_status(export.id, "remote_deleted", None)
+            .map_err(|e| e.to_string())?;
+        return Ok(());
+    };
+
+    let local_changed = note.local_hash() != export.local_hash;
+    let remote_changed = content_hash(&remote) != export.remote_hash;
+    match decide(local_changed, remote_changed) {
+        SyncAction::None => {}
+        SyncAction::Push => {
+            push(db, target, note, Some(&export.external_id)).await?;
+            report.updated += 1;
+        }
+        SyncAction::Pull => {
+            pull(db, target, &export, note, &remote)?;
+            report.pulled += 1;
+        }
+        SyncAction::Conflict => {
+            db.set_note_export_status(
+                export.id,
+                "conflict",
+                Some("Changed both locally and in the external note since the last sync"),
+            )
+            .map_err(|e| e.to_string())?;
+            report.conflicts += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Run one sync pass over every selected memory and session summary
+pub async fn run_sync(db: &Database) -> Result<SyncReport, String> {
+    let config = db.get_note_export_config().map_err(|e| e.to_string())?;
+    let target = target_for(&config)?;
+
+    let mut notes: Vec<ExportNote> = db
+        .list_memories_for_export(config.min_importance, &config.memory_type_list())
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(memory_note)
+        .collect();
+    if config.include_session_summaries {
+        for (id, key, summary, _) in db.list_session_summaries().map_err(|e| e.to_string())? {
+            notes.push(session_note(id, &key, &summary));
+        }
+    }
+
+    let mut report = SyncReport::default();
+    for note in &notes {
+        if let Err(e) = sync_note(db, target.as_ref(), note, &mut report).await {
+            log::warn!("[NOTE_EXPORT] Failed to sync {} {}: {}", note.kind, note.source_id, e);
+            report.errors.push(format!("{} {}: {}", note.kind, note.source_id, e));
+            if let Ok(Some(export)) = db.get_note_export(note.kind, note.source_id, target.name()) {
+                let _ = db.set_note_export_status(export.id, &export.status, Some(&e));
+            }
+        }
+    }
+
+    let _ = db.set_note_export_last_sync();
+    Ok(report)
+}
+
+/// Resolve a conflicted (or externally deleted) export.
+/// `keep_local` pushes the local version (recreating the note if needed);
+/// otherwise the external side wins — its body is pulled, or for a deleted
+/// note the link is dropped.
+pub async fn resolve_export(db: &Database, export_id: i64, keep_local: bool) -> Result<(), String> {
+    let export = db
+        .get_note_export_by_id(export_id)
+        .map_err(|e| e.to_string())?
+        .ok_or("Export not found")?;
+    let config = db.get_note_export_config().map_err(|e| e.to_string())?;
+    let target = target_for(&config)?;
+    if target.name() != export.target {
+        return Err(format!("Export belongs to the '{}' target, which is no longer configured", export.target));
+    }
+
+    let note = load_note(db, &export.source_kind, export.source_id)?;
+    let remote = target.fetch_body(&export.external_id).await?;
+
+    match (keep_local, note, remote) {
+        (true, Some(note), Some(_)) => push(db, target.as_ref(), &note, Some(&export.external_id)).await,
+        (true, Some(note), None) => push(db, target.as_ref(), &note, None).await,
+        (false, Some(note), Some(remote)) => pull(db, target.as_ref(), &export, &note, &remote),
+        (_, None, _) | (false, _, None) => {
+            db.delete_note_export(export.id).map_err(|e| e.to_string())?;
+            if export.source_kind == "memory" {
+                db.set_memory_external_page_id(export.source_id, None)
+                    .map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Spawn the note export worker. Runs a sync every `interval_secs` while the
+/// export is enabled and this instance holds the leader lease.
+pub fn spawn_note_export_worker(
+    db: Arc<Database>,
+    interval_secs: u64,
+    is_leader: impl Fn() -> bool + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if !is_leader() {
+                continue;
+            }
+            match db.get_note_export_config() {
+                Ok(config) if config.enabled => {}
+                _ => continue,
+            }
+            match run_sync(&db).await {
+                Ok(report) => {
+                    if report.created + report.updated + report.pulled + report.conflicts > 0 || !report.errors.is_empty() {
+                        log::info!(
+                            "[NOTE_EXPORT] Sync: {} created, {} updated, {} pulled, {} conflicts, {} errors",
+                            report.created,
+                            report.updated,
+                            report.pulled,
+                            report.conflicts,
+                            report.errors.len()
+                        );
+                    }
+                }
+                Err(e) => log::warn!("[NOTE_EXPORT] Sync failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide() {
+        assert_eq!(decide(false, false), SyncAction::None);
+        assert_eq!(decide(true, false), SyncAction::Push);
+        assert_eq!(decide(false, true), SyncAction::Pull);
+        assert_eq!(decide(true, true), SyncAction::Conflict);
+    }
+
+    #[tokio::test]
+    async fn test_sync_pushes_pulls_and_flags_conflicts() {
+        let db = Database::new(":memory:").unwrap();
+        let vault = tempfile::tempdir().unwrap();
+        let mut config = db.get_note_export_config().unwrap();
+        config.obsidian_vault_path = Some(vault.path().to_string_lossy().to_string());
+        db.save_note_export_config(&config).unwrap();
+
+        let id = db
+            .insert_memory("long_term", "Likes tea", None, None, 9, None, None, None, None, None, None, None)
+            .unwrap();
+
+        let report = run_sync(&db).await.unwrap();
+        assert_eq!(report.created, 1);
+        let path = db.get_memory(id).unwrap().unwrap().external_page_id.unwrap();
+        let file = vault.path().join(&path);
+
+        // External edit is pulled into the memory
+        let content = std::fs::read_to_string(&file).unwrap();
+        std::fs::write(&file, content.replace("Likes tea", "Likes green tea")).unwrap();
+        let report = run_sync(&db).await.unwrap();
+        assert_eq!(report.pulled, 1);
+        assert_eq!(db.get_memory(id).unwrap().unwrap().content, "Likes green tea");
+
+        // Both sides edited -> conflict, nothing overwritten
+        db.update_memory_content(id, "Likes coffee").unwrap();
+        let content = std::fs::read_to_string(&file).unwrap();
+        std::fs::write(&file, content.replace("Likes green tea", "Likes oolong")).unwrap();
+        let report = run_sync(&db).await.unwrap();
+        assert_eq!(report.conflicts, 1);
+        assert!(std::fs::read_to_string(&file).unwrap().contains("Likes oolong"));
+
+        // Keeping the local version pushes it out
+        let export = db.get_note_export("memory", id, "obsidian").unwrap().unwrap();
+        resolve_export(&db, export.id, true).await.unwrap();
+        assert!(std::fs::read_to_string(&file).unwrap().contains("Likes coffee"));
+        assert_eq!(db.get_note_export_by_id(export.id).unwrap().unwrap().status, "synced");
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::circuit_breaker::{self, GuardedSend};
+
 const DEFAULT_HUB_URL: &str = "https://hub.starkbot.ai/api";
 
 /// Client for the StarkHub module registry API.
@@ -185,12 +187,17 @@ impl StarkHubClient {
     }
 
     /// Get featured modules from StarkHub.
+    /// While StarkHub's circuit is open the last good listing is served.
     pub async fn get_featured_modules(&self) -> Result<Vec<ModuleSummary>, String> {
+        circuit_breaker::with_fallback(circuit_breaker::STARKHUB, "modules/featured", self.fetch_featured_modules()).await
+    }
+
+    async fn fetch_featured_modules(&self) -> Result<Vec<ModuleSummary>, String> {
         let url = format!("{}/modules/featured", self.base_url);
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -210,7 +217,7 @@ impl StarkHubClient {
             .http
             .get(&url)
             .query(&[("q", query)])
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -232,7 +239,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -262,7 +269,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -281,7 +288,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(download_url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to download binary: {}", e))?;
 
@@ -308,7 +315,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to list module files: {}", e))?;
 
@@ -344,7 +351,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to download file '{}': {}", file_name, e))?;
 
@@ -367,12 +374,17 @@ impl StarkHubClient {
     // --- Agent Subtype methods ---
 
     /// List agent subtypes from StarkHub.
+    /// While StarkHub's circuit is open the last good listing is served.
     pub async fn list_agent_subtypes(&self) -> Result<Vec<AgentSubtypeSummary>, String> {
+        circuit_breaker::with_fallback(circuit_breaker::STARKHUB, "agent-subtypes", self.fetch_agent_subtypes()).await
+    }
+
+    async fn fetch_agent_subtypes(&self) -> Result<Vec<AgentSubtypeSummary>, String> {
         let url = format!("{}/agent-subtypes", self.base_url);
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -398,7 +410,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -432,7 +444,7 @@ impl StarkHubClient {
             .http
             .get(&url)
             .header("Authorization", format!("Bearer {}", auth_token))
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -459,7 +471,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -492,7 +504,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -519,7 +531,7 @@ impl StarkHubClient {
             .header("Authorization", format!("Bearer {}", auth_token))
             .header("Content-Type", "application/json")
             .json(&serde_json::json!({ "raw_agent_md": raw_agent_md }))
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -555,7 +567,7 @@ impl StarkHubClient {
                 "file_name": file_name,
                 "content": content,
             }))
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -582,7 +594,7 @@ impl StarkHubClient {
             .header("Authorization", format!("Bearer {}", auth_token))
             .header("Content-Type", "application/json")
             .json(&serde_json::json!({ "manifest_toml": manifest_toml }))
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -618,7 +630,7 @@ impl StarkHubClient {
                 "file_name": file_name,
                 "content": content,
             }))
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -635,12 +647,17 @@ impl StarkHubClient {
     // --- Skill methods ---
 
     /// Get featured skills from StarkHub.
+    /// While StarkHub's circuit is open the last good listing is served.
     pub async fn get_featured_skills(&self) -> Result<Vec<SkillSummary>, String> {
+        circuit_breaker::with_fallback(circuit_breaker::STARKHUB, "skills/featured", self.fetch_featured_skills()).await
+    }
+
+    async fn fetch_featured_skills(&self) -> Result<Vec<SkillSummary>, String> {
         let url = format!("{}/skills/featured", self.base_url);
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -660,7 +677,7 @@ impl StarkHubClient {
             .http
             .get(&url)
             .query(&[("q", query)])
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -686,7 +703,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -715,7 +732,7 @@ impl StarkHubClient {
             .header("Authorization", format!("Bearer {}", auth_token))
             .header("Content-Type", "application/json")
             .json(&serde_json::json!({ "raw_markdown": raw_markdown }))
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -742,7 +759,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -775,7 +792,7 @@ impl StarkHubClient {
         let resp = self
             .http
             .get(&url)
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -816,7 +833,7 @@ impl StarkHubClient {
         }
 
         let resp = req
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -868,7 +885,7 @@ impl StarkHubClient {
                 "file_name": file_name,
                 "content": content,
             }))
-            .send()
+            .send_guarded(circuit_breaker::STARKHUB)
             .await
             .map_err(|e| format!("Failed to connect to StarkHub: {}", e))?;
 
@@ -0,0 +1,128 @@
+//! Trade journal
+//!
+//! Every swap and transfer the agent queues is written to the `trades` table
+//! (by `swap_token`, `send_eth` and ERC-20 `transfer` calls). The transaction
+//! queue marks entries executed when they are broadcast, and a background
+//! worker prices them in USD at their execution time. `pnl::compute` replays
+//! the journal into per-token realized/unrealized PnL for the `trade_journal`
+//! tool and the `/api/trades` endpoints.
+
+pub mod pnl;
+pub mod prices;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::db::tables::trades::RecordTradeRequest;
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+
+use pnl::PnlReport;
+
+/// Journal a queued swap or transfer. Failures are logged, never surfaced:
+/// the journal must not get in the way of the transaction itself.
+pub fn record(db: &Database, req: RecordTradeRequest) {
+    let uuid = req.tx_uuid.clone();
+    if let Err(e) = db.record_trade(req) {
+        log::error!("[JOURNAL] Failed to journal trade {}: {}", uuid, e);
+    }
+}
+
+/// Start of a reporting period: day, week, month, year, or all (None)
+pub fn period_start(period: &str) -> AppResult<Option<String>> {
+    let days = match period.to_lowercase().as_str() {
+        "all" | "" => return Ok(None),
+        "day" | "24h" => 1,
+        "week" | "7d" => 7,
+        "month" | "30d" => 30,
+        "year" | "365d" => 365,
+        other => {
+            return Err(AppError::validation(format!(
+                "Unknown period '{}'. Use day, week, month, year or all.",
+                other
+            )))
+        }
+    };
+    Ok(Some((Utc::now() - Duration::days(days)).to_rfc3339()))
+}
+
+/// PnL over a period, with unrealized PnL at current prices (best effort)
+pub async fn pnl_report(db: &Database, since: Option<&str>) -> AppResult<PnlReport> {
+    let trades = db.list_executed_trades()?;
+    let mut report = pnl::compute(&trades, since);
+    mark_to_market(&mut report).await;
+    Ok(report)
+}
+
+/// Fill in unrealized PnL for held tokens at current prices (best effort)
+pub async fn mark_to_market(report: &mut PnlReport) {
+    let held: Vec<(String, String, String)> = report
+        .tokens
+        .iter()
+        .filter(|t| t.held > 0.0)
+        .filter_map(|t| Some((t.network.clone(), t.token.clone(), prices::coin_id(&t.network, &t.token)?)))
+        .collect();
+    let ids: Vec<String> = held.iter().map(|(_, _, id)| id.clone()).collect();
+    match prices::current_prices(&ids).await {
+        Ok(current) => {
+            let by_token: HashMap<(String, String), f64> = held
+                .into_iter()
+                .filter_map(|(network, token, id)| Some(((network, token), *current.get(&id.to_lowercase())?)))
+                .collect();
+            pnl::apply_current_prices(report, &by_token);
+        }
+        Err(e) => log::warn!("[JOURNAL] Current prices unavailable, skipping unrealized PnL: {}", e),
+    }
+}
+
+/// Price executed trades at their execution time. Returns how many were priced.
+pub async fn run_pricing_pass(db: &Database) -> usize {
+    let trades = match db.list_unpriced_trades(50) {
+        Ok(trades) => trades,
+        Err(e) => {
+            log::error!("[JOURNAL] Failed to load unpriced trades: {}", e);
+            return 0;
+        }
+    };
+    let mut priced = 0;
+    for trade in &trades {
+        let executed_at = trade
+            .executed_at
+            .as_deref()
+            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            .map(|at| at.timestamp())
+            .unwrap_or_else(|| Utc::now().timestamp());
+        let sell_id = prices::coin_id(&trade.network, &trade.sell_token);
+        let buy_id = trade.buy_token.as_deref().and_then(|t| prices::coin_id(&trade.network, t));
+        let ids: Vec<String> = sell_id.iter().chain(buy_id.iter()).cloned().collect();
+
+        let found = match prices::historical_prices(executed_at, &ids).await {
+            Ok(found) => found,
+            Err(e) => {
+                // Transient: leave unpriced and retry next pass
+                log::warn!("[JOURNAL] Pricing {} failed: {}", trade.tx_uuid, e);
+                continue;
+            }
+        };
+        let sell_price = sell_id.and_then(|id| found.get(&id.to_lowercase()).copied());
+        let buy_price = buy_id.and_then(|id| found.get(&id.to_lowercase()).copied());
+        match db.set_trade_prices(trade.id, sell_price, buy_price) {
+            Ok(()) => priced += 1,
+            Err(e) => log::error!("[JOURNAL] Failed to store prices for {}: {}", trade.tx_uuid, e),
+        }
+    }
+    priced
+}
+
+/// Spawn the background worker that prices newly executed trades (every `interval_secs`).
+pub fn spawn_pricing_worker(db: Arc<Database>, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            run_pricing_pass(&db).await;
+        }
+    })
+}
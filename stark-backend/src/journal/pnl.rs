@@ -0,0 +1,219 @@
+//! Realized/unrealized PnL from the trade journal (average cost basis)
+//!
+//! Trades are replayed in execution order. Buying a token adds to the position
+//! at its execution-time USD price; selling it realizes the difference between
+//! the sale price and the position's average cost. Transfers out remove tokens
+//! at average cost without realizing anything. Tokens sold beyond what the
+//! journal saw being acquired (funded from outside the agent) have no known
+//! basis and are counted as `untracked_sold` with zero PnL.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::db::tables::trades::{Trade, TRADE_KIND_SWAP};
+
+/// Position and PnL for one token on one network
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TokenPnl {
+    pub network: String,
+    pub token: String,
+    pub symbol: String,
+    /// Quantity the journal believes is still held
+    pub held: f64,
+    pub cost_basis_usd: f64,
+    pub avg_cost_usd: Option<f64>,
+    /// Realized within the reporting period
+    pub realized_usd: f64,
+    pub current_price_usd: Option<f64>,
+    pub unrealized_usd: Option<f64>,
+    /// Quantities within the reporting period
+    pub bought: f64,
+    pub sold: f64,
+    pub transferred_out: f64,
+    pub untracked_sold: f64,
+    pub trades: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PnlReport {
+    /// Start of the reporting period (RFC 3339); None = all time
+    pub since: Option<String>,
+    pub tokens: Vec<TokenPnl>,
+    pub realized_usd: f64,
+    pub unrealized_usd: f64,
+    /// Swaps left out because no USD price was available
+    pub unpriced_trades: usize,
+}
+
+fn position<'a>(
+    positions: &'a mut HashMap<(String, String), TokenPnl>,
+    network: &str,
+    token: &str,
+    symbol: &str,
+) -> &'a mut TokenPnl {
+    positions
+        .entry((network.to_string(), token.to_string()))
+        .or_insert_with(|| TokenPnl {
+            network: network.to_string(),
+            token: token.to_string(),
+            symbol: symbol.to_string(),
+            ..Default::default()
+        })
+}
+
+/// Replay executed trades (in execution order) into per-token positions.
+/// Only disposals at or after `since` count towards realized PnL.
+pub fn compute(trades: &[Trade], since: Option<&str>) -> PnlReport {
+    let mut positions: HashMap<(String, String), TokenPnl> = HashMap::new();
+    let mut unpriced_trades = 0;
+
+    for trade in trades {
+        let in_period = since.is_none_or(|s| trade.executed_at.as_deref().is_some_and(|at| at >= s));
+        let sell_qty = trade.sell_amount();
+        let is_swap = trade.kind == TRADE_KIND_SWAP;
+
+        // Swaps need a USD price on at least one side; the other is implied by the fill
+        let buy_qty = trade.buy_amount().filter(|q| *q > 0.0);
+        let (sell_price, buy_price) = match (trade.sell_price_usd, trade.buy_price_usd, buy_qty) {
+            (Some(s), Some(b), _) => (Some(s), Some(b)),
+            (Some(s), None, Some(q)) => (Some(s), Some(s * sell_qty / q)),
+            (None, Some(b), Some(q)) if sell_qty > 0.0 => (Some(b * q / sell_qty), Some(b)),
+            (s, b, _) => (s, b),
+        };
+        if is_swap && (sell_price.is_none() || buy_price.is_none() || buy_qty.is_none()) {
+            unpriced_trades += 1;
+            continue;
+        }
+
+        // Sell side: consume the position at average cost
+        let sold = position(&mut positions, &trade.network, &trade.sell_token, &trade.sell_symbol);
+        let covered = sell_qty.min(sold.held);
+        let basis_removed = if sold.held > 0.0 { sold.cost_basis_usd * covered / sold.held } else { 0.0 };
+        sold.held -= covered;
+        sold.cost_basis_usd -= basis_removed;
+        if in_period {
+            sold.trades += 1;
+            if is_swap {
+                sold.realized_usd += covered * sell_price.unwrap_or(0.0) - basis_removed;
+                sold.sold += sell_qty;
+                sold.untracked_sold += sell_qty - covered;
+            } else {
+                sold.transferred_out += sell_qty;
+            }
+        }
+
+        // Buy side: add to the position at the execution price
+        if let (Some(token), Some(qty), Some(price)) = (trade.buy_token.as_deref(), buy_qty, buy_price) {
+            let symbol = trade.buy_symbol.as_deref().unwrap_or("?");
+            let bought = position(&mut positions, &trade.network, token, symbol);
+            bought.held += qty;
+            bought.cost_basis_usd += qty * price;
+            if in_period {
+                bought.bought += qty;
+                bought.trades += 1;
+            }
+        }
+    }
+
+    let mut tokens: Vec<TokenPnl> = positions
+        .into_values()
+        .filter(|p| p.trades > 0 || p.held > 0.0)
+        .map(|mut p| {
+            p.avg_cost_usd = (p.held > 0.0).then(|| p.cost_basis_usd / p.held);
+            p
+        })
+        .collect();
+    tokens.sort_by(|a, b| (&a.network, &a.symbol).cmp(&(&b.network, &b.symbol)));
+
+    PnlReport {
+        since: since.map(str::to_string),
+        realized_usd: tokens.iter().map(|t| t.realized_usd).sum(),
+        unrealized_usd: 0.0,
+        tokens,
+        unpriced_trades,
+    }
+}
+
+/// Fill in unrealized PnL from current prices keyed by (network, token)
+pub fn apply_current_prices(report: &mut PnlReport, prices: &HashMap<(String, String), f64>) {
+    for token in &mut report.tokens {
+        if let Some(price) = prices.get(&(token.network.clone(), token.token.clone())) {
+            token.current_price_usd = Some(*price);
+            token.unrealized_usd = (token.held > 0.0).then(|| token.held * price - token.cost_basis_usd);
+        }
+    }
+    report.unrealized_usd = report.tokens.iter().filter_map(|t| t.unrealized_usd).sum();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::tables::trades::{TRADE_EXECUTED, TRADE_KIND_TRANSFER};
+
+    #[allow(clippy::too_many_arguments)]
+    fn trade(
+        kind: &str,
+        sell: (&str, &str, u8),
+        buy: Option<(&str, &str, u8)>,
+        sell_price: Option<f64>,
+        buy_price: Option<f64>,
+        at: &str,
+    ) -> Trade {
+        Trade {
+            id: 0,
+            tx_uuid: at.to_string(),
+            kind: kind.to_string(),
+            network: "base".to_string(),
+            sell_token: sell.0.to_string(),
+            sell_symbol: sell.0.to_uppercase(),
+            sell_amount_raw: sell.1.to_string(),
+            sell_decimals: sell.2,
+            buy_token: buy.map(|b| b.0.to_string()),
+            buy_symbol: buy.map(|b| b.0.to_uppercase()),
+            buy_amount_raw: buy.map(|b| b.1.to_string()),
+            buy_decimals: buy.map(|b| b.2),
+            counterparty: None,
+            sell_price_usd: sell_price,
+            buy_price_usd: buy_price,
+            priced_at: Some(at.to_string()),
+            status: TRADE_EXECUTED.to_string(),
+            tx_hash: None,
+            channel_id: None,
+            created_at: at.to_string(),
+            executed_at: Some(at.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_average_cost_realized_and_unrealized() {
+        let trades = vec![
+            // Buy 1 WETH for 2000 USDC
+            trade(TRADE_KIND_SWAP, ("usdc", "2000000000", 6), Some(("weth", "1000000000000000000", 18)), Some(1.0), Some(2000.0), "2026-01-01T00:00:00Z"),
+            // Sell 0.5 WETH at 3000 (buy price missing: implied from the USDC received)
+            trade(TRADE_KIND_SWAP, ("weth", "500000000000000000", 18), Some(("usdc", "1500000000", 6)), Some(3000.0), None, "2026-02-01T00:00:00Z"),
+            // Send 0.1 WETH away: leaves the position at cost, realizes nothing
+            trade(TRADE_KIND_TRANSFER, ("weth", "100000000000000000", 18), None, None, None, "2026-02-02T00:00:00Z"),
+        ];
+
+        let mut report = compute(&trades, None);
+        let weth = report.tokens.iter().find(|t| t.symbol == "WETH").unwrap();
+        assert!((weth.realized_usd - 500.0).abs() < 1e-6);
+        assert!((weth.held - 0.4).abs() < 1e-9);
+        assert!((weth.avg_cost_usd.unwrap() - 2000.0).abs() < 1e-6);
+        // USDC sold from outside funds has no basis
+        let usdc = report.tokens.iter().find(|t| t.symbol == "USDC").unwrap();
+        assert!((usdc.untracked_sold - 2000.0).abs() < 1e-6);
+        assert_eq!(usdc.realized_usd, 0.0);
+
+        let prices = HashMap::from([(("base".to_string(), "weth".to_string()), 2500.0)]);
+        apply_current_prices(&mut report, &prices);
+        assert!((report.unrealized_usd - 200.0).abs() < 1e-6);
+
+        // Only the February sale counts in a February report; the January buy still sets the basis
+        let feb = compute(&trades, Some("2026-02-01T00:00:00Z"));
+        let weth = feb.tokens.iter().find(|t| t.symbol == "WETH").unwrap();
+        assert!((weth.realized_usd - 500.0).abs() < 1e-6);
+        assert_eq!(weth.bought, 0.0);
+    }
+}
@@ -0,0 +1,76 @@
+//! USD token prices from DefiLlama's coins API (free, no API key)
+//!
+//! Tokens are identified as `{chain}:{address}`; native gas tokens use their
+//! CoinGecko ids. Historical lookups price a trade at its execution time.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+const LLAMA_COINS_URL: &str = "https://coins.llama.fi/prices";
+
+/// Sentinel address used for the native gas token (as in 0x quotes)
+pub const NATIVE_TOKEN: &str = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+
+fn llama_chain(network: &str) -> Option<&'static str> {
+    match network {
+        "base" => Some("base"),
+        "mainnet" | "ethereum" => Some("ethereum"),
+        "polygon" => Some("polygon"),
+        "arbitrum" => Some("arbitrum"),
+        "optimism" => Some("optimism"),
+        _ => None,
+    }
+}
+
+/// DefiLlama coin id for a token on a network
+pub fn coin_id(network: &str, token: &str) -> Option<String> {
+    if token.eq_ignore_ascii_case(NATIVE_TOKEN) {
+        return Some(
+            match network {
+                "polygon" => "coingecko:matic-network",
+                _ => "coingecko:ethereum",
+            }
+            .to_string(),
+        );
+    }
+    Some(format!("{}:{}", llama_chain(network)?, token.to_lowercase()))
+}
+
+async fn fetch(url: &str) -> Result<HashMap<String, f64>, String> {
+    let body: Value = crate::http::shared_client()
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Price request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse price response: {}", e))?;
+    let coins = body["coins"].as_object().ok_or("Price response has no 'coins'")?;
+    Ok(coins
+        .iter()
+        .filter_map(|(id, coin)| Some((id.to_lowercase(), coin["price"].as_f64()?)))
+        .collect())
+}
+
+/// Current USD prices, keyed by lowercase coin id. Unknown coins are absent.
+pub async fn current_prices(ids: &[String]) -> Result<HashMap<String, f64>, String> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    fetch(&format!("{}/current/{}?searchWidth=4h", LLAMA_COINS_URL, ids.join(","))).await
+}
+
+/// USD prices at a unix timestamp, keyed by lowercase coin id
+pub async fn historical_prices(timestamp: i64, ids: &[String]) -> Result<HashMap<String, f64>, String> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    fetch(&format!(
+        "{}/historical/{}/{}?searchWidth=1h",
+        LLAMA_COINS_URL,
+        timestamp,
+        ids.join(",")
+    ))
+    .await
+}
@@ -6,7 +6,9 @@ use std::sync::Arc;
 
 mod agents;
 mod ai;
+mod alerts;
 mod ai_endpoint_config;
+mod ai_quota;
 mod backup;
 mod channels;
 mod config;
@@ -16,6 +18,7 @@ mod db;
 mod disk_quota;
 mod discord_hooks;
 mod domain_types;
+mod error;
 mod execution;
 mod gateway;
 mod integrations;
@@ -34,13 +37,32 @@ mod erc8128;
 mod eip8004;
 mod hooks;
 pub mod http;
+mod circuit_breaker;
 mod tool_validators;
 mod tx_queue;
+mod two_factor;
 mod web3;
 mod keystore_client;
 mod identity_client;
 mod modules;
 mod telemetry;
+mod retention;
+mod config_watch;
+mod cluster;
+mod safe;
+mod bridge;
+mod journal;
+mod paper;
+mod away;
+mod handoff;
+mod digest;
+mod doctor;
+mod self_update;
+mod strategies;
+mod social_calendar;
+mod token_metadata;
+#[cfg(feature = "perf")]
+mod perf;
 
 use channels::{ChannelManager, MessageDispatcher, SafeModeChannelRateLimiter};
 use tx_queue::TxQueueManager;
@@ -91,6 +113,16 @@ pub struct AppState {
     pub internal_token: String,
     /// In-memory cache for active session metadata (shared with dispatcher for admin invalidation)
     pub active_cache: Arc<ActiveSessionCache>,
+    /// Alert rules engine (shared with the background evaluation worker)
+    pub alert_engine: Arc<alerts::AlertEngine>,
+    /// Strategy automation engine (shared with the background evaluation worker)
+    pub strategy_engine: Arc<strategies::StrategyEngine>,
+    /// Settings change bus — controllers publish, the reload worker applies changes live
+    pub config_watch: Arc<config_watch::ConfigWatch>,
+    /// Cluster coordinator (instance identity, leader leases)
+    pub cluster: Arc<cluster::Cluster>,
+    /// Event bus modules and skills publish to and subscribe on
+    pub event_bus: Arc<gateway::event_bus::EventBus>,
 }
 
 /// Auto-retrieve backup from keystore on fresh instance
@@ -389,9 +421,9 @@ fn start_module_services(db: &Database) {
             .collect();
 
         if let Some(ref command) = svc.command {
-            start_service_command(command, &svc.module_dir, &svc.name, port, &env_refs);
+            start_service_command(db, svc, command, port, &env_refs);
         } else {
-            start_service_binary(&svc.binary_path, &svc.name, port, &env_refs);
+            start_service_binary(db, svc, port, &env_refs);
         }
 
         // Set env vars in parent process so manifest.service_url() resolves correctly
@@ -426,10 +458,18 @@ fn find_free_port() -> Option<u16> {
         .map(|addr| addr.port())
 }
 
-/// Start a single service binary.
+/// Start a single service binary (checksum-verified and sandboxed).
 /// The caller is responsible for checking port availability before calling this.
-fn start_service_binary(exe_path: &std::path::Path, name: &str, port: u16, envs: &[(&str, &str)]) {
-    let mut cmd = std::process::Command::new(exe_path);
+fn start_service_binary(db: &Database, svc: &modules::loader::DynamicServiceInfo, port: u16, envs: &[(&str, &str)]) {
+    let name = &svc.name;
+    let target = modules::sandbox::LaunchTarget::Binary(&svc.binary_path);
+    let mut cmd = match modules::sandbox::prepare_launch(db, name, target, &svc.module_dir, &svc.fs_allow) {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            log::error!("[MODULE] Not starting {}: {}", name, e);
+            return;
+        }
+    };
     cmd.stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped());
 
@@ -453,11 +493,23 @@ fn start_service_binary(exe_path: &std::path::Path, name: &str, port: u16, envs:
 }
 
 /// Start a service via a shell command (e.g. "uv run service.py").
-/// The command is run from the module directory with `sh -c`.
-fn start_service_command(command: &str, cwd: &std::path::Path, name: &str, port: u16, envs: &[(&str, &str)]) {
-    let mut cmd = std::process::Command::new("sh");
-    cmd.arg("-c").arg(command);
-    cmd.current_dir(cwd);
+/// The command is run from the module directory with `sh -c` (sandboxed).
+fn start_service_command(
+    db: &Database,
+    svc: &modules::loader::DynamicServiceInfo,
+    command: &str,
+    port: u16,
+    envs: &[(&str, &str)],
+) {
+    let (name, cwd) = (&svc.name, &svc.module_dir);
+    let target = modules::sandbox::LaunchTarget::Command(command);
+    let mut cmd = match modules::sandbox::prepare_launch(db, name, target, cwd, &svc.fs_allow) {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            log::error!("[MODULE] Not starting {}: {}", name, e);
+            return;
+        }
+    };
     cmd.stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped());
 
@@ -666,7 +718,32 @@ fn ensure_cli_gateway_channel(db: &std::sync::Arc<db::Database>, token: &str) {
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
-    env_logger::init();
+    if cluster::cluster_mode_enabled() {
+        // Tag every log line with the instance ID so interleaved cluster logs stay readable
+        use std::io::Write;
+        let instance = cluster::instance_id();
+        env_logger::Builder::from_default_env()
+            .format(move |buf, record| {
+                writeln!(
+                    buf,
+                    "[{} {} {} {}] {}",
+                    buf.timestamp(),
+                    record.level(),
+                    instance,
+                    record.target(),
+                    record.args()
+                )
+            })
+            .init();
+    } else {
+        env_logger::init();
+    }
+
+    #[cfg(feature = "perf")]
+    if std::env::args().nth(1).as_deref() == Some("perf") {
+        perf::run(std::env::args().nth(2));
+        return Ok(());
+    }
 
     // Load presets and tokens from config directory
     // Check ./config first, then ../config (for running from subdirectory)
@@ -691,6 +768,8 @@ async fn main() -> std::io::Result<()> {
     ai_endpoint_config::load_ai_endpoints().await;
     log::info!("Loading x402 payment limit defaults from config directory");
     x402::payment_limits::load_defaults(config_dir);
+    log::info!("Loading typed data signing policy from config directory");
+    web3::typed_data::load_policy(config_dir);
 
     let mut config = Config::from_env();
     let port = config.port;
@@ -714,6 +793,12 @@ async fn main() -> std::io::Result<()> {
         log::error!("Failed to seed modules: {}", e);
     }
 
+    // Drop scratch directories orphaned by a previous run before measuring disk usage
+    let orphaned_scratch = execution::scratch::sweep_orphans();
+    if orphaned_scratch > 0 {
+        log::info!("Removed {} orphaned execution scratch dir(s)", orphaned_scratch);
+    }
+
     // Initialize disk quota manager
     let disk_quota_mb = config::disk_quota_mb();
     let disk_quota: Option<Arc<disk_quota::DiskQuotaManager>> = if disk_quota_mb > 0 {
@@ -742,6 +827,39 @@ async fn main() -> std::io::Result<()> {
     let db = Database::new(&config.database_url).expect("Failed to initialize database");
     let db = Arc::new(db);
 
+    // One-shot migration: `stark-backend --encrypt-db` encrypts (or re-keys) existing rows and exits
+    if std::env::args().any(|a| a == "--encrypt-db") {
+        let Some(cipher) = db::encryption::cipher() else {
+            log::error!("--encrypt-db requires {} to be set", db::encryption::ENCRYPTION_KEY_ENV);
+            std::process::exit(1);
+        };
+        match db.encrypt_existing_rows(cipher) {
+            Ok(rewritten) => {
+                log::info!(
+                    "[DB_ENCRYPTION] Migration complete under key {}: {} message(s), {} memory row(s), {} trashed record(s), {} other value(s) rewritten",
+                    cipher.current_key_id(),
+                    rewritten.messages,
+                    rewritten.memories,
+                    rewritten.trash,
+                    rewritten.other
+                );
+                return Ok(());
+            }
+            Err(e) => {
+                log::error!("[DB_ENCRYPTION] Migration failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Cluster coordination: campaign once up front so singleton work starts on the right instance
+    let cluster = Arc::new(cluster::Cluster::from_env(db.clone()));
+    cluster::install(cluster.clone());
+    if cluster.enabled() {
+        log::info!("[CLUSTER] Cluster mode enabled — instance {}", cluster.instance_id());
+        cluster.campaign();
+    }
+
     // Override x402 payment limit defaults with any user-configured values from DB
     match db.get_all_x402_payment_limits() {
         Ok(limits) => {
@@ -758,12 +876,9 @@ async fn main() -> std::io::Result<()> {
     // Load RPC configuration into the unified resolver so ALL codepaths
     // (tools, eip8004, x402 signer, etc.) share the same resolution logic.
     {
-        let alchemy_key = db.get_api_key("ALCHEMY_API_KEY").ok().flatten()
-            .map(|k| k.api_key)
-            .or_else(|| std::env::var("ALCHEMY_API_KEY").ok().filter(|v| !v.is_empty()));
-        if let Some(key) = alchemy_key {
+        config_watch::reload_alchemy_api_key(&db);
+        if tools::rpc_config::get_alchemy_api_key().is_some() {
             log::info!("[rpc_config] Alchemy API key loaded — Tier 1 RPC available");
-            tools::rpc_config::set_alchemy_api_key(key);
         }
 
         if let Ok(settings) = db.get_bot_settings() {
@@ -771,6 +886,7 @@ async fn main() -> std::io::Result<()> {
                 tools::rpc_config::set_custom_rpc_endpoints(endpoints);
             }
         }
+        tools::rpc_pool::load(&db);
     }
 
     // Load agent subtypes from agents/ folders (disk-based, no DB).
@@ -847,10 +963,16 @@ async fn main() -> std::io::Result<()> {
     // Auto-start module service binaries as child processes.
     // Only starts services for modules that are enabled in the database.
     // Set DISABLE_MODULE_SERVICES=1 to skip auto-start entirely.
-    if std::env::var("DISABLE_MODULE_SERVICES").map(|v| v == "1" || v == "true").unwrap_or(false) {
+    // In cluster mode only the module-services leader runs them (wallet monitor etc. are singletons).
+    let module_services_disabled = std::env::var("DISABLE_MODULE_SERVICES").map(|v| v == "1" || v == "true").unwrap_or(false);
+    // Modules whose version changed on disk run their upgrade hook first
+    modules::lifecycle::run_pending_upgrades(&db).await;
+    if module_services_disabled {
         log::info!("[MODULE] Module service auto-start disabled via DISABLE_MODULE_SERVICES");
-    } else {
+    } else if cluster.is_leader(cluster::LEASE_MODULE_SERVICES) {
         start_module_services(&db);
+    } else {
+        log::info!("[MODULE] Another instance leads module services — skipping auto-start");
     }
 
     // Initialize Tool Registry with built-in tools + installed module tools
@@ -910,6 +1032,7 @@ async fn main() -> std::io::Result<()> {
     // Load skill ABIs and presets from DB into in-memory indexes
     web3::load_all_abis_from_db(&db);
     tools::presets::load_all_skill_presets_from_db(&db);
+    token_metadata::load(&db);
 
     // Initialize Transaction Queue Manager with DB for persistent broadcast history
     // NOTE: Must be created before Gateway so channels can use it for web3 transactions
@@ -1064,12 +1187,15 @@ async fn main() -> std::io::Result<()> {
             store.set_disk_quota(dq.clone());
         }
     }
-    let dispatcher = Arc::new(dispatcher_builder);
+    let dispatcher = Arc::new(dispatcher_builder.with_cluster(cluster.clone()));
 
     // Get broadcaster and channel_manager for the /ws route
     let broadcaster = gateway.broadcaster();
     let channel_manager = gateway.channel_manager();
 
+    // Plugin event bus (before channels start so their events are on it)
+    let event_bus = gateway::event_bus::EventBus::start(db.clone(), broadcaster.clone());
+
     // Initialize and start the scheduler
     log::info!("Initializing scheduler");
     let scheduler_config = SchedulerConfig::default();
@@ -1081,7 +1207,8 @@ async fn main() -> std::io::Result<()> {
         scheduler_config,
         wallet_provider.clone(),
         Some(skill_registry.clone()),
-    ));
+    ).with_cluster(cluster.clone())
+     .with_embedding_generator(embedding_generator.clone()));
 
     // Start scheduler background task
     let scheduler_handle = Arc::clone(&scheduler);
@@ -1134,11 +1261,25 @@ async fn main() -> std::io::Result<()> {
         log::info!("Background memory decay task spawned (every 6h)");
     }
 
+    // Spawn background knowledge graph sync (folds new memories into the entity graph every 10 min)
+    {
+        let db_kg = db.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+            loop {
+                memory::knowledge_graph::sync_from_memories(&db_kg);
+                tokio::time::sleep(tokio::time::Duration::from_secs(600)).await;
+            }
+        });
+        log::info!("Background knowledge graph sync spawned (every 10m)");
+    }
+
     // Spawn slow network-dependent init in background so HTTP server starts immediately
     {
         let db_bg = db.clone();
         let gateway_bg = gateway.clone();
         let wallet_provider_bg = wallet_provider.clone();
+        let disk_quota_bg = disk_quota.clone();
         tokio::spawn(async move {
             // Keystore auto-retrieve (works in both Standard and Flash mode via wallet provider)
             if let Some(ref wp) = wallet_provider_bg {
@@ -1157,6 +1298,16 @@ async fn main() -> std::io::Result<()> {
             log::info!("Starting enabled channels (background)");
             gateway_bg.start_enabled_channels().await;
             log::info!("All enabled channels started");
+
+            // Self-test the configuration now, rather than failing at first use
+            let report = doctor::run(
+                &db_bg,
+                &gateway_bg.channel_manager(),
+                disk_quota_bg.as_deref(),
+                wallet_provider_bg,
+            )
+            .await;
+            doctor::log_report(&report);
         });
     }
 
@@ -1242,6 +1393,18 @@ async fn main() -> std::io::Result<()> {
                         log::error!("[SESSION_CLEANUP] Failed to clean up stale sessions: {}", e);
                     }
                 }
+                // Expired idempotency keys (inbound dedup + Idempotency-Key replay)
+                if let Err(e) = db_cleanup.purge_expired_idempotency_keys(chrono::Utc::now()) {
+                    log::error!("[SESSION_CLEANUP] Failed to purge expired idempotency keys: {}", e);
+                }
+                // Delivered outbound messages (the queue keeps them 7 days for inspection)
+                if let Err(e) = db_cleanup.purge_sent_outbound_messages(chrono::Utc::now() - chrono::Duration::days(7)) {
+                    log::error!("[SESSION_CLEANUP] Failed to purge delivered outbound messages: {}", e);
+                }
+                // Button callbacks older than a week (their messages are long scrolled away)
+                if let Err(e) = db_cleanup.purge_message_actions(chrono::Utc::now() - chrono::Duration::days(7)) {
+                    log::error!("[SESSION_CLEANUP] Failed to purge expired message buttons: {}", e);
+                }
                 // FIFO: delete oldest inactive sessions when total exceeds 500
                 match db_cleanup.cleanup_excess_sessions(500) {
                     Ok(0) => {}
@@ -1256,6 +1419,172 @@ async fn main() -> std::io::Result<()> {
         });
     }
 
+    // Spawn data retention worker (applies retention policies and purges expired trash hourly)
+    {
+        let _retention_handle = retention::spawn_retention_worker(db.clone(), 3600);
+        log::info!("Background retention worker spawned (every 1h)");
+    }
+
+    // Spawn outbound delivery worker (retries channel replies that failed to send)
+    {
+        let _outbound_handle = channels::outbound::spawn_delivery_worker(db.clone(), 5);
+        log::info!("Background outbound delivery worker spawned (every 5s)");
+    }
+
+    // Spawn tx approval expiry worker (drops transactions the owner didn't answer in time)
+    {
+        let _approval_handle = tx_queue::approval::spawn_expiry_worker(db.clone(), tx_queue.clone(), 30);
+        log::info!("Background tx approval expiry worker spawned (every 30s)");
+    }
+
+    // Spawn outbox expiry worker (drafts held for review that nobody approved in time)
+    {
+        let _outbox_handle = channels::outbox::spawn_expiry_worker(db.clone(), 60);
+        log::info!("Background outbox expiry worker spawned (every 60s)");
+    }
+
+    // Spawn Safe proposal status worker (announces co-signer confirmations and executions)
+    {
+        let _safe_handle = safe::spawn_status_worker(db.clone(), broadcaster.clone(), 60);
+        log::info!("Background Safe proposal status worker spawned (every 60s)");
+    }
+
+    // Spawn bridge tracking worker (follows queued bridges to arrival on the destination chain)
+    {
+        let _bridge_handle = bridge::spawn_tracking_worker(db.clone(), broadcaster.clone(), tx_queue.clone(), 30);
+        log::info!("Background bridge tracking worker spawned (every 30s)");
+    }
+
+    // Spawn trade journal pricing worker (USD prices for executed swaps/transfers)
+    {
+        let _journal_handle = journal::spawn_pricing_worker(db.clone(), 60);
+        log::info!("Background trade journal pricing worker spawned (every 60s)");
+    }
+
+    // Spawn token metadata worker (fetches unknown tokens, refreshes stale symbols/decimals)
+    {
+        let _token_metadata_handle = token_metadata::spawn_refresh_worker(db.clone(), 120);
+        log::info!("Background token metadata worker spawned (every 2m)");
+    }
+
+    // Spawn away mode release worker (answers requests queued while the owner was away)
+    {
+        let _away_handle = away::spawn_release_worker(db.clone(), dispatcher.clone(), 60);
+        log::info!("Background away mode release worker spawned (every 60s)");
+    }
+
+    // Spawn RPC pool health worker (probes free endpoints, clears cooldowns)
+    {
+        let _rpc_health_handle = tools::rpc_pool::spawn_health_worker(60);
+        log::info!("Background RPC pool health worker spawned (every 60s)");
+    }
+
+    // Spawn alert rules worker (evaluates user-defined rules every 60s)
+    let alert_engine = Arc::new(alerts::AlertEngine::new(
+        db.clone(),
+        tool_registry.clone(),
+        broadcaster.clone(),
+        disk_quota.clone(),
+    ));
+    {
+        let _alert_handle = alerts::spawn_alert_worker(alert_engine.clone(), alerts::AlertWorkerConfig::default());
+        log::info!("Background alert rules worker spawned");
+    }
+
+    // Spawn strategy worker (evaluates user-defined trigger/condition/action automations every 60s)
+    let strategy_engine = Arc::new(strategies::StrategyEngine::new(
+        db.clone(),
+        tool_registry.clone(),
+        broadcaster.clone(),
+        tx_queue.clone(),
+        wallet_provider.clone(),
+    ));
+    {
+        let _strategy_handle = strategies::spawn_strategy_worker(
+            strategy_engine.clone(),
+            strategies::StrategyWorkerConfig::default(),
+        );
+        log::info!("Background strategy worker spawned");
+    }
+
+    // Spawn module health worker (checks module services every 30s, restarts crashed ones with backoff)
+    if !module_services_disabled {
+        let cluster_health = cluster.clone();
+        let _health_handle = modules::supervisor::spawn_health_worker(db.clone(), 30, move || {
+            cluster_health.is_leader(cluster::LEASE_MODULE_SERVICES)
+        });
+        log::info!("Background module health worker spawned");
+    }
+
+    // Spawn module update worker (checks StarkHub for newer module versions every 6h)
+    {
+        let cluster_updates = cluster.clone();
+        let _update_handle = modules::updates::spawn_update_worker(db.clone(), broadcaster.clone(), 6 * 3600, move || {
+            cluster_updates.is_leader(cluster::LEASE_MODULE_SERVICES)
+        });
+        log::info!("Background module update worker spawned (every 6h)");
+    }
+
+    // Spawn note export worker (mirrors memories/session summaries to Notion or Obsidian every 15m)
+    {
+        let cluster_notes = cluster.clone();
+        let _note_export_handle = integrations::note_export::sync::spawn_note_export_worker(db.clone(), 15 * 60, move || {
+            cluster_notes.is_leader(cluster::LEASE_SCHEDULER)
+        });
+        log::info!("Background note export worker spawned (every 15m)");
+    }
+
+    // Spawn social calendar worker (publishes approved posts when due, refreshes engagement metrics)
+    {
+        let cluster_social = cluster.clone();
+        let _social_handle = social_calendar::publisher::spawn_social_calendar_worker(db.clone(), broadcaster.clone(), 60, move || {
+            cluster_social.is_leader(cluster::LEASE_SCHEDULER)
+        });
+        log::info!("Background social calendar worker spawned (every 60s)");
+    }
+
+    // Spawn context pre-warm worker (refreshes portfolio/calendar/watchlist ahead of active hours)
+    {
+        let cluster_prewarm = cluster.clone();
+        let _prewarm_handle = context::prewarm::spawn_prewarm_worker(db.clone(), 60, move || {
+            cluster_prewarm.is_leader(cluster::LEASE_SCHEDULER)
+        });
+        log::info!("Background context pre-warm worker spawned (every 60s)");
+    }
+
+    // Spawn activity digest worker (sends the daily/weekly digest to the owner's chat when due)
+    {
+        let cluster_digest = cluster.clone();
+        let _digest_handle = digest::spawn_digest_worker(db.clone(), 300, move || {
+            cluster_digest.is_leader(cluster::LEASE_SCHEDULER)
+        });
+        log::info!("Background activity digest worker spawned (every 5m)");
+    }
+
+    // Spawn cluster lease worker (renews leadership; a newly elected instance takes over module services)
+    if cluster.enabled() {
+        let db_cluster = db.clone();
+        let _cluster_handle = cluster::spawn_cluster_worker(cluster.clone(), move |lease| {
+            if lease == cluster::LEASE_MODULE_SERVICES && !module_services_disabled {
+                log::info!("[CLUSTER] Took over module services leadership — starting services");
+                start_module_services(&db_cluster);
+            }
+        });
+        log::info!("Background cluster lease worker spawned");
+    }
+
+    // Spawn live config reload worker (applies settings changes without a restart)
+    let config_watch = Arc::new(config_watch::ConfigWatch::new());
+    {
+        let _config_handle = config_watch::spawn_config_reload_worker(
+            config_watch.clone(),
+            db.clone(),
+            channel_manager.clone(),
+            broadcaster.clone(),
+        );
+        log::info!("Background config reload worker spawned");
+    }
+
     // Module workers are now managed by standalone services — no workers to spawn here.
     // Keep an empty map in AppState for API compatibility.
     let module_workers = Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::<String, tokio::task::JoinHandle<()>>::new()));
@@ -1293,6 +1622,7 @@ async fn main() -> std::io::Result<()> {
     // Clones needed for shutdown handler (before HttpServer moves db)
     let shutdown_db = db.clone();
     let shutdown_cache = dispatcher.active_cache().clone();
+    let shutdown_cluster = cluster.clone();
 
     let tool_reg = tool_registry.clone();
     let skill_reg = skill_registry.clone();
@@ -1308,6 +1638,11 @@ async fn main() -> std::io::Result<()> {
     let disk_q = disk_quota.clone();
     let mod_workers = module_workers.clone();
     let hybrid_search_engine = hybrid_search_engine.clone();
+    let alert_eng = alert_engine.clone();
+    let strategy_eng = strategy_engine.clone();
+    let cfg_watch = config_watch.clone();
+    let cluster_state = cluster.clone();
+    let evt_bus = event_bus.clone();
     let frontend_dist = frontend_dist.to_string();
     let dev_mode = dev_mode;
     // Internal token for module-to-backend API calls (wallet signing proxy, etc.)
@@ -1316,10 +1651,26 @@ async fn main() -> std::io::Result<()> {
         .expect("STARKBOT_INTERNAL_TOKEN should have been set during startup");
 
     let server = HttpServer::new(move || {
+        // Cross-origin callers must be on the allow-list in bot settings
+        // (read per request from the settings cache, so edits apply at once)
+        let cors_db = Arc::clone(&db);
         let cors = Cors::default()
-            .allow_any_origin()
+            .allowed_origin_fn(move |origin, _req| {
+                let allowed = cors_db
+                    .get_bot_settings()
+                    .map(|s| s.cors_allowed_origins)
+                    .unwrap_or_default();
+                origin
+                    .to_str()
+                    .is_ok_and(|o| middleware::http_security::origin_allowed(o, &allowed, dev_mode))
+            })
+            .supports_credentials()
             .allow_any_method()
             .allow_any_header()
+            .expose_headers([
+                controllers::pagination::TOTAL_COUNT_HEADER,
+                controllers::pagination::NEXT_CURSOR_HEADER,
+            ])
             .max_age(3600);
 
         let mut app = App::new()
@@ -1347,6 +1698,11 @@ async fn main() -> std::io::Result<()> {
                 remote_embedding_generator: Some(Arc::clone(&remote_embedding_generator)),
                 internal_token: internal_token.clone(),
                 active_cache: disp.active_cache().clone(),
+                alert_engine: Arc::clone(&alert_eng),
+                strategy_engine: Arc::clone(&strategy_eng),
+                config_watch: Arc::clone(&cfg_watch),
+                cluster: Arc::clone(&cluster_state),
+                event_bus: Arc::clone(&evt_bus),
             }))
             .app_data(web::Data::new(Arc::clone(&sched)))
             // WebSocket data for /ws route
@@ -1355,13 +1711,20 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(Arc::clone(&bcast)))
             .app_data(web::Data::new(Arc::clone(&tx_q)))
             .app_data(web::Data::new(wallet_prov.clone()))
+            .app_data(controllers::validation::json_config())
+            .wrap(actix_web::middleware::from_fn(middleware::read_only_mode::enforce))
+            .wrap(actix_web::middleware::from_fn(middleware::access_keys::enforce))
+            .wrap(actix_web::middleware::from_fn(middleware::http_security::protect))
             .wrap(Logger::default())
             .wrap(cors)
             .configure(controllers::health::config_routes)
+            .configure(controllers::openapi::config)
             .configure(controllers::auth::config)
             .configure(controllers::dashboard::config)
             .configure(controllers::chat::config)
             .configure(controllers::api_keys::config)
+            .configure(controllers::access_keys::config)
+            .configure(controllers::two_factor::config)
             .configure(controllers::channels::config)
             .configure(controllers::agent_settings::configure)
             .configure(controllers::sessions::config)
@@ -1371,11 +1734,19 @@ async fn main() -> std::io::Result<()> {
             .configure(controllers::cron::config)
             .configure(controllers::heartbeat::config)
             .configure(controllers::gmail::config)
+            .configure(controllers::issue_tracker::config)
+            .configure(controllers::social_posts::config)
+            .configure(controllers::farcaster::config)
+            .configure(controllers::reputation_feedback::config)
+            .configure(controllers::identity_profiles::config)
+            .configure(controllers::context_prewarm::config)
+            .configure(controllers::digest::config)
             .configure(controllers::payments::config)
             .configure(controllers::eip8004::config)
             .configure(controllers::files::config)
             .configure(controllers::intrinsic::config)
             .configure(controllers::notes::config)
+            .configure(controllers::note_export::config)
             .configure(controllers::tx_queue::config)
             .configure(controllers::broadcasted_transactions::config)
             .configure(controllers::impulse_map::config)
@@ -1383,6 +1754,7 @@ async fn main() -> std::io::Result<()> {
             .configure(controllers::modules::config)
             .configure(controllers::memory::config)
             .configure(controllers::system::config)
+            .configure(controllers::doctor::config)
             .configure(controllers::well_known::config)
             .configure(controllers::x402::config)
             .configure(controllers::x402_limits::config)
@@ -1393,6 +1765,30 @@ async fn main() -> std::io::Result<()> {
             .configure(controllers::internal_wallet::config)
             .configure(controllers::transcribe::config)
             .configure(controllers::hooks_api::config)
+            .configure(controllers::event_bus::config)
+            .configure(controllers::rules::config)
+            .configure(controllers::retention::config)
+            .configure(controllers::cluster::config)
+            .configure(controllers::outbound::config)
+            .configure(controllers::outbox::config)
+            .configure(controllers::executions::config)
+            .configure(controllers::introspection::config)
+            .configure(controllers::analytics::config)
+            .configure(controllers::tx_approvals::config)
+            .configure(controllers::tool_confirmations::config)
+            .configure(controllers::tool_timeouts::config)
+            .configure(controllers::safe::config)
+            .configure(controllers::trades::config)
+            .configure(controllers::paper::config)
+            .configure(controllers::read_only_mode::config)
+            .configure(controllers::away::config)
+            .configure(controllers::handoffs::config)
+            .configure(controllers::http_security::config)
+            .configure(controllers::strategies::config)
+            .configure(controllers::ai_quotas::config)
+            .configure(controllers::wallet_monitor::config)
+            .configure(controllers::tokens::config)
+            .configure(controllers::rpc_endpoints::config)
             // Public ext proxy — must be before the SPA catch-all
             .configure(controllers::ext::config)
             .configure(controllers::public_files::config)
@@ -1419,10 +1815,22 @@ async fn main() -> std::io::Result<()> {
     // Clone channel_manager for shutdown handler
     let shutdown_channel_manager = channel_manager.clone();
 
+    // Set when the shutdown below was asked for by an installed update
+    let restart_on_exit = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let shutdown_restart = restart_on_exit.clone();
+
     // Spawn Ctrl+C handler
     tokio::spawn(async move {
-        tokio::signal::ctrl_c().await.expect("Failed to listen for Ctrl+C");
-        log::info!("Received Ctrl+C, shutting down...");
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                result.expect("Failed to listen for Ctrl+C");
+                log::info!("Received Ctrl+C, shutting down...");
+            }
+            _ = self_update::restart_requested() => {
+                log::info!("Update installed, shutting down to restart...");
+                shutdown_restart.store(true, std::sync::atomic::Ordering::SeqCst);
+            }
+        }
 
         // Flush active session cache to SQLite before shutdown
         log::info!("Flushing active session cache...");
@@ -1438,6 +1846,9 @@ async fn main() -> std::io::Result<()> {
         // Signal scheduler to stop
         let _ = scheduler_shutdown_tx.send(());
 
+        // Hand leadership to the remaining instances right away instead of waiting for lease expiry
+        shutdown_cluster.shutdown();
+
         // Stop the HTTP server with timeout
         log::info!("Stopping HTTP server...");
         let server_stop = server_handle.stop(true);
@@ -1448,5 +1859,15 @@ async fn main() -> std::io::Result<()> {
         log::info!("Shutdown complete");
     });
 
-    server.await
+    server.await?;
+
+    // A self-update swapped the binary and asked for this shutdown: start it in place of
+    // this process. Any other shutdown (Ctrl+C, a signal) exits normally.
+    if restart_on_exit.load(std::sync::atomic::Ordering::SeqCst) {
+        if let Some(e) = self_update::exec_updated_binary() {
+            log::error!("Failed to restart into the updated binary: {}", e);
+            return Err(e);
+        }
+    }
+    Ok(())
 }
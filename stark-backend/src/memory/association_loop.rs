@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use crate::db::encryption::decrypt_field;
 use crate::db::Database;
 use super::embeddings::EmbeddingGenerator;
 use super::vector_search;
@@ -179,7 +180,7 @@ pub fn backfill_memory_metadata(db: &Database) -> Result<usize, String> {
 
     let rows: Vec<(i64, String)> = stmt
         .query_map([], |row| {
-            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            Ok((row.get::<_, i64>(0)?, decrypt_field(row.get::<_, String>(1)?)))
         })
         .map_err(|e| format!("Failed to read memories for backfill: {}", e))?
         .filter_map(|r| r.ok())
@@ -434,7 +435,7 @@ async fn auto_backfill_embeddings_if_needed(
             )
             .map_err(|e| format!("Failed to query memories for auto-backfill: {}", e))?;
 
-        stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+        stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, decrypt_field(row.get::<_, String>(1)?))))
             .map_err(|e| format!("Failed to read memories: {}", e))?
             .filter_map(|r| r.ok())
             .collect()
@@ -926,7 +927,7 @@ fn load_all_memory_metas(db: &Database) -> Result<Vec<MemoryMeta>, String> {
         .query_map([], |row| {
             Ok(MemoryMeta {
                 id: row.get(0)?,
-                content: row.get(1)?,
+                content: decrypt_field(row.get(1)?),
                 memory_type: row.get::<_, String>(2).unwrap_or_else(|_| "unknown".to_string()),
                 category: row.get::<_, Option<String>>(3).unwrap_or(None),
                 entity_type: row.get::<_, Option<String>>(4).unwrap_or(None),
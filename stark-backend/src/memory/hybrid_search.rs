@@ -5,6 +5,7 @@ use std::time::Duration;
 
 use moka::sync::Cache;
 
+use crate::db::encryption::decrypt_field;
 use crate::db::Database;
 use super::embeddings::EmbeddingGenerator;
 use super::vector_search;
@@ -415,7 +416,7 @@ impl HybridSearchEngine {
                 .query_row(
                     "SELECT content FROM memories WHERE id = ?1",
                     rusqlite::params![hit.memory_id],
-                    |row| row.get::<_, String>(0),
+                    |row| row.get::<_, String>(0).map(decrypt_field),
                 )
                 .ok()
                 .map(|c| {
@@ -474,7 +475,7 @@ impl HybridSearchEngine {
                 .map_err(|e| format!("Failed to prepare backfill query: {}", e))?;
 
             stmt.query_map([], |row| {
-                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+                Ok((row.get::<_, i64>(0)?, decrypt_field(row.get::<_, String>(1)?)))
             })
             .map_err(|e| format!("Failed to query memories for backfill: {}", e))?
             .filter_map(|r| r.ok())
@@ -584,7 +585,7 @@ impl HybridSearchEngine {
                 rusqlite::params![memory_id],
                 |row| {
                     Ok((
-                        row.get::<_, String>(0)?,
+                        decrypt_field(row.get::<_, String>(0)?),
                         row.get::<_, String>(1)?,
                         row.get::<_, f64>(2)?.round() as i32,
                     ))
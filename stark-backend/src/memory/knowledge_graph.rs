@@ -0,0 +1,254 @@
+//! Knowledge graph — the people, projects, contracts and tokens the agent
+//! has come across, and how they relate
+//!
+//! The memory graph links memories to memories; this one links the things
+//! memories are *about*. Entities come from two places: each memory (its
+//! `entity_type`/`entity_name` plus whatever the context bank scanner finds
+//! in its content) and the context-bank items of the user's messages. Every
+//! time two entities turn up together the relation between them gets
+//! stronger, so the graph reflects what keeps coming up across sessions.
+//!
+//! Memories are folded in incrementally by a background pass that keeps its
+//! place in the kv store; `rebuild` starts over from the first memory.
+
+use std::collections::HashSet;
+
+use serde_json::json;
+
+use crate::db::Database;
+use crate::memory::negative_results::NEGATIVE_RESULT_TYPE;
+use crate::tools::ContextBankItem;
+
+/// kv store namespace/key holding the id of the last memory ingested
+const SYNC_NAMESPACE: &str = "knowledge_graph";
+const SYNC_KEY: &str = "last_memory_id";
+/// Memories ingested per sync batch
+const SYNC_BATCH: usize = 200;
+/// Above this many entities in one memory, pairwise relations are noise
+const MAX_PAIRWISE: usize = 6;
+
+pub const RELATION_RELATED_TO: &str = "related_to";
+pub const RELATION_RESOLVES_TO: &str = "resolves_to";
+pub const RELATION_MENTIONED_WITH: &str = "mentioned_with";
+
+/// An entity before it is stored: (type, normalized name, display name)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntityRef {
+    pub entity_type: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+impl EntityRef {
+    pub fn new(entity_type: &str, display_name: &str) -> Self {
+        EntityRef {
+            entity_type: entity_type.trim().to_lowercase(),
+            name: display_name.trim().to_lowercase(),
+            display_name: display_name.trim().to_string(),
+        }
+    }
+}
+
+/// The graph entity a context bank item stands for. Numbers, dates and plain
+/// URLs are context, not things worth tracking, and map to None.
+pub fn entity_for_item(item: &ContextBankItem) -> Option<EntityRef> {
+    match item.item_type.as_str() {
+        "eth_address" => Some(EntityRef::new("address", &item.value)),
+        "ens_name" => Some(EntityRef::new("ens_name", &item.value)),
+        "token_symbol" | "ticker" => Some(EntityRef::new("token", &item.value.to_uppercase())),
+        "network" => Some(EntityRef::new("network", item.label.as_deref().unwrap_or(&item.value))),
+        "github_url" => item.label.as_deref().map(|repo| EntityRef::new("project", repo)),
+        _ => None,
+    }
+}
+
+fn entities_in(items: &[ContextBankItem]) -> Vec<EntityRef> {
+    let mut seen = HashSet::new();
+    items
+        .iter()
+        .filter_map(entity_for_item)
+        .filter(|e| !e.name.is_empty() && seen.insert((e.entity_type.clone(), e.name.clone())))
+        .collect()
+}
+
+/// Store entities seen together and relate them. With a subject (the entity a
+/// memory is about) everything else is `related_to` it; without one they are
+/// `mentioned_with` each other. A lone ENS name next to a lone address is
+/// taken to resolve to it. Returns the stored entity ids.
+pub fn ingest(
+    db: &Database,
+    subject: Option<&EntityRef>,
+    entities: &[EntityRef],
+    source: &str,
+    memory_id: Option<i64>,
+    session_id: Option<i64>,
+) -> Vec<i64> {
+    let upsert = |entity: &EntityRef| -> Option<i64> {
+        let id = db
+            .upsert_knowledge_entity(&entity.entity_type, &entity.name, &entity.display_name)
+            .map_err(|e| log::warn!("[KNOWLEDGE_GRAPH] Failed to store entity {}: {}", entity.name, e))
+            .ok()?;
+        let _ = db.add_knowledge_mention(id, source, memory_id, session_id);
+        Some(id)
+    };
+    let relate = |source_id: i64, target_id: i64, relation: &str| {
+        if source_id != target_id {
+            if let Err(e) = db.upsert_knowledge_relation(source_id, target_id, relation) {
+                log::warn!("[KNOWLEDGE_GRAPH] Failed to relate {} -> {}: {}", source_id, target_id, e);
+            }
+        }
+    };
+
+    let subject_id = subject.and_then(upsert);
+    let others: Vec<(&EntityRef, i64)> = entities
+        .iter()
+        .filter(|e| Some(*e) != subject)
+        .filter_map(|e| upsert(e).map(|id| (e, id)))
+        .collect();
+
+    let of_type = |t: &str| others.iter().filter(|(e, _)| e.entity_type == t).collect::<Vec<_>>();
+    if let ([(_, ens)], [(_, address)]) = (of_type("ens_name").as_slice(), of_type("address").as_slice()) {
+        relate(*ens, *address, RELATION_RESOLVES_TO);
+    }
+
+    match subject_id {
+        Some(subject_id) => {
+            for (_, id) in &others {
+                relate(subject_id, *id, RELATION_RELATED_TO);
+            }
+        }
+        None if others.len() <= MAX_PAIRWISE => {
+            for (i, (_, a)) in others.iter().enumerate() {
+                for (_, b) in &others[i + 1..] {
+                    relate((*a).min(*b), (*a).max(*b), RELATION_MENTIONED_WITH);
+                }
+            }
+        }
+        None => {}
+    }
+
+    subject_id.into_iter().chain(others.into_iter().map(|(_, id)| id)).collect()
+}
+
+/// Fold the entities of a user message into the graph. Items found in tool
+/// output are left out; web pages aren't the user's world.
+pub fn ingest_context_items(db: &Database, items: &[ContextBankItem], session_id: Option<i64>) -> usize {
+    let from_user: Vec<ContextBankItem> = items.iter().filter(|i| i.is_from_user()).cloned().collect();
+    let entities = entities_in(&from_user);
+    if entities.is_empty() {
+        return 0;
+    }
+    ingest(db, None, &entities, "user", None, session_id).len()
+}
+
+/// Fold memories added since the last sync into the graph. Returns how many
+/// memories were read.
+pub fn sync_from_memories(db: &Database) -> usize {
+    let mut last_id = db
+        .kv_get(SYNC_NAMESPACE, SYNC_KEY)
+        .ok()
+        .flatten()
+        .and_then(|entry| entry.value.as_i64())
+        .unwrap_or(0);
+    let mut processed = 0;
+
+    loop {
+        let batch = match db.list_memories_after(last_id, SYNC_BATCH) {
+            Ok(batch) => batch,
+            Err(e) => {
+                log::warn!("[KNOWLEDGE_GRAPH] Failed to list memories after {}: {}", last_id, e);
+                break;
+            }
+        };
+        let Some(last) = batch.last() else { break };
+        last_id = last.id;
+        processed += batch.len();
+
+        for memory in batch.iter().filter(|m| m.memory_type != NEGATIVE_RESULT_TYPE) {
+            let subject = match (&memory.entity_type, &memory.entity_name) {
+                (Some(t), Some(n)) if !t.trim().is_empty() && !n.trim().is_empty() => Some(EntityRef::new(t, n)),
+                _ => None,
+            };
+            let entities = entities_in(&crate::tools::scan_input(&memory.content));
+            if subject.is_some() || !entities.is_empty() {
+                ingest(db, subject.as_ref(), &entities, "memory", Some(memory.id), memory.session_id);
+            }
+        }
+
+        if let Err(e) = db.kv_set(SYNC_NAMESPACE, SYNC_KEY, &json!(last_id), None) {
+            log::warn!("[KNOWLEDGE_GRAPH] Failed to save sync position: {}", e);
+            break;
+        }
+        if batch.len() < SYNC_BATCH {
+            break;
+        }
+    }
+
+    if processed > 0 {
+        log::info!("[KNOWLEDGE_GRAPH] Ingested {} memories (up to id {})", processed, last_id);
+    }
+    processed
+}
+
+/// Clear the graph and ingest every memory again. Entities from past user
+/// messages are not kept anywhere else, so they are lost.
+pub fn rebuild(db: &Database) -> Result<usize, String> {
+    db.clear_knowledge_graph().map_err(|e| e.to_string())?;
+    db.kv_delete(SYNC_NAMESPACE, SYNC_KEY).map_err(|e| e.to_string())?;
+    Ok(sync_from_memories(db))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ingest_relates_entities() {
+        let db = Database::new(":memory:").unwrap();
+        let address = "0xd8da6bf26964af9d7eed9e10c7db5f6a4c1a2b3c";
+        db.insert_memory(
+            "long_term",
+            &format!("Vitalik uses vitalik.eth which points to {}", address),
+            None,
+            None,
+            5,
+            None,
+            None,
+            Some("person"),
+            Some("Vitalik"),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(sync_from_memories(&db), 1);
+        // Already ingested memories are not read again
+        assert_eq!(sync_from_memories(&db), 0);
+
+        let person = &db.search_knowledge_entities("vitalik", Some("person"), 5).unwrap()[0];
+        assert_eq!(person.display_name, "Vitalik");
+        let relations = db.list_knowledge_relations_for(person.id).unwrap();
+        assert_eq!(relations.len(), 2);
+        assert!(relations.iter().all(|r| r.relation_type == RELATION_RELATED_TO));
+
+        let ens = &db.search_knowledge_entities("vitalik.eth", Some("ens_name"), 5).unwrap()[0];
+        let resolves: Vec<_> = db
+            .list_knowledge_relations_for(ens.id)
+            .unwrap()
+            .into_iter()
+            .filter(|r| r.relation_type == RELATION_RESOLVES_TO)
+            .collect();
+        assert_eq!(resolves.len(), 1);
+
+        // The address turning up in a user message counts as another mention
+        let items = crate::tools::scan_input(&format!("send 1 ETH to {}", address));
+        assert!(ingest_context_items(&db, &items, None) >= 1);
+        let stored = &db.search_knowledge_entities(address, Some("address"), 1).unwrap()[0];
+        assert_eq!(stored.mention_count, 2);
+
+        assert_eq!(rebuild(&db).unwrap(), 1);
+        let stats = db.get_knowledge_graph_stats().unwrap();
+        assert_eq!(stats.entities, 3);
+        assert_eq!(stats.relations, 3);
+    }
+}
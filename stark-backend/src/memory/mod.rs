@@ -4,6 +4,9 @@ pub mod decay;
 pub mod embeddings;
 pub mod fts_utils;
 pub mod hybrid_search;
+pub mod knowledge_graph;
+pub mod negative_results;
+pub mod profile;
 pub mod redaction;
 pub mod vector_search;
 
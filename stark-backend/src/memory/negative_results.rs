@@ -0,0 +1,240 @@
+//! Negative-result memories — approaches that already failed
+//!
+//! When a tool call fails for a reason retries and repair can't fix, the
+//! failure is kept as a `negative_result` memory: which tool, for what
+//! request, and why. New requests look up the ones that match and the agent
+//! sees them before it plans, so it doesn't walk into the same wall twice.
+//!
+//! Failures with a known cause are keyed by it in the memory's category
+//! (`api_key:TWITTER_CONSUMER_KEY`, `module:wallet_monitor`) and are cleared
+//! as soon as that key is saved or that module is enabled again. Everything
+//! else decays with the rest of memory.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::db::tables::memories::MemoryRow;
+use crate::db::Database;
+
+pub const NEGATIVE_RESULT_TYPE: &str = "negative_result";
+const SOURCE_TYPE: &str = "tool_failure";
+const IMPORTANCE: i64 = 6;
+/// Longest error text kept in a memory
+const MAX_ERROR_CHARS: usize = 240;
+/// Longest request excerpt kept in a memory
+const MAX_REQUEST_CHARS: usize = 120;
+/// Failed approaches shown for a new request
+const MAX_RECALLED: i32 = 3;
+
+/// Key names in errors like "TWITTER_CONSUMER_KEY not configured"
+static MISSING_KEY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b([A-Z][A-Z0-9]*(?:_[A-Z0-9]+)+)\b (?:is )?not (?:set|configured|found)").unwrap()
+});
+
+/// Why a tool call failed, as far as it can be told from the outside
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FailureCause {
+    MissingApiKey(String),
+    ModuleDisabled(String),
+    Other,
+}
+
+impl FailureCause {
+    /// Classify a failure. `module_of` names the module a tool belongs to if
+    /// that module is installed but disabled.
+    pub fn classify(tool_name: &str, error: &str, module_of: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(caps) = MISSING_KEY.captures(error) {
+            return FailureCause::MissingApiKey(caps[1].to_string());
+        }
+        let lower = error.to_lowercase();
+        if lower.contains("not found") || lower.contains("module service") {
+            if let Some(module) = module_of(tool_name) {
+                return FailureCause::ModuleDisabled(module);
+            }
+        }
+        FailureCause::Other
+    }
+
+    /// Category the memory is filed under; one memory per tool and category
+    pub fn category(&self, tool_name: &str) -> String {
+        match self {
+            FailureCause::MissingApiKey(key) => api_key_category(key),
+            FailureCause::ModuleDisabled(module) => module_category(module),
+            FailureCause::Other => format!("tool:{}", tool_name),
+        }
+    }
+
+    fn reason(&self, error: &str) -> String {
+        match self {
+            FailureCause::MissingApiKey(key) => format!("the {} API key is not set", key),
+            FailureCause::ModuleDisabled(module) => format!("the {} module is disabled", module),
+            FailureCause::Other => truncate(error.trim(), MAX_ERROR_CHARS),
+        }
+    }
+}
+
+pub fn api_key_category(key_name: &str) -> String {
+    format!("api_key:{}", key_name)
+}
+
+pub fn module_category(module_name: &str) -> String {
+    format!("module:{}", module_name)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(max).collect::<String>())
+    }
+}
+
+/// The installed-but-disabled module that provides `tool_name`, if any
+fn disabled_module_for_tool(db: &Database, tool_name: &str) -> Option<String> {
+    let disabled: Vec<String> = db
+        .list_installed_modules()
+        .ok()?
+        .into_iter()
+        .filter(|m| !m.enabled && m.has_tools)
+        .map(|m| m.module_name)
+        .collect();
+    if disabled.is_empty() {
+        return None;
+    }
+    let registry = crate::modules::ModuleRegistry::new();
+    disabled.into_iter().find(|name| {
+        registry
+            .get(name)
+            .map(|module| module.create_tools().iter().any(|t| t.name() == tool_name))
+            .unwrap_or(false)
+    })
+}
+
+/// Remember that `tool_name` failed while handling `request`. A failure of the
+/// same tool for the same cause refreshes the existing memory instead.
+pub fn record(
+    db: &Database,
+    tool_name: &str,
+    error: &str,
+    request: &str,
+    identity_id: Option<&str>,
+    session_id: i64,
+) {
+    let cause = FailureCause::classify(tool_name, error, |tool| disabled_module_for_tool(db, tool));
+    let category = cause.category(tool_name);
+    let content = format!(
+        "`{}` failed while handling \"{}\" because {}",
+        tool_name,
+        truncate(request.trim(), MAX_REQUEST_CHARS),
+        cause.reason(error)
+    );
+
+    let result = match db.find_negative_result(tool_name, &category, identity_id) {
+        Ok(Some(existing)) => db
+            .update_memory_content(existing.id, &content)
+            .and_then(|_| db.touch_memory(existing.id))
+            .map(|_| existing.id),
+        Ok(None) => db.insert_memory(
+            NEGATIVE_RESULT_TYPE,
+            &content,
+            Some(&category),
+            Some(tool_name),
+            IMPORTANCE,
+            identity_id,
+            Some(session_id),
+            Some("tool"),
+            Some(tool_name),
+            Some(SOURCE_TYPE),
+            None,
+            None,
+        ),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(id) => log::info!("[NEGATIVE_RESULT] Recorded failure of '{}' ({}) as memory {}", tool_name, category, id),
+        Err(e) => log::warn!("[NEGATIVE_RESULT] Failed to record failure of '{}': {}", tool_name, e),
+    }
+}
+
+/// Failed approaches that match `request`, most relevant first
+pub fn recall(db: &Database, request: &str, identity_id: Option<&str>) -> Vec<MemoryRow> {
+    let query = crate::memory::fts_utils::normalize_fts_query(request);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    match db.search_negative_results(&query, identity_id, MAX_RECALLED) {
+        Ok(rows) => {
+            for row in &rows {
+                let _ = db.touch_memory(row.id);
+            }
+            rows
+        }
+        Err(e) => {
+            log::debug!("[NEGATIVE_RESULT] Search failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// System prompt section listing failed approaches
+pub fn prompt_section(rows: &[MemoryRow]) -> String {
+    let mut section = String::from(
+        "## Approaches That Failed Before\n\
+         These failed on similar requests. Don't repeat them unless the cause has been fixed; \
+         tell the user what's missing instead, or try another way.\n",
+    );
+    for row in rows {
+        section.push_str(&format!("- {}\n", row.content));
+    }
+    section
+}
+
+/// Forget failures caused by a missing API key once it is set
+pub fn resolve_api_key(db: &Database, key_name: &str) {
+    resolve(db, &api_key_category(key_name));
+}
+
+/// Forget failures caused by a disabled module once it is enabled
+pub fn resolve_module(db: &Database, module_name: &str) {
+    resolve(db, &module_category(module_name));
+}
+
+fn resolve(db: &Database, category: &str) {
+    match db.clear_negative_results(category) {
+        Ok(0) => {}
+        Ok(n) => log::info!("[NEGATIVE_RESULT] Cleared {} failure(s) for resolved cause {}", n, category),
+        Err(e) => log::warn!("[NEGATIVE_RESULT] Failed to clear failures for {}: {}", category, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SessionScope;
+
+    #[test]
+    fn test_classify_and_resolve() {
+        let no_module = |_: &str| None;
+        let cause = FailureCause::classify(
+            "twitter_post",
+            "TWITTER_CONSUMER_KEY not configured. Add it in Settings > API Keys.",
+            no_module,
+        );
+        assert_eq!(cause, FailureCause::MissingApiKey("TWITTER_CONSUMER_KEY".to_string()));
+        let cause = FailureCause::classify("watchlist", "Tool 'watchlist' not found", |_| Some("wallet_monitor".to_string()));
+        assert_eq!(cause.category("watchlist"), "module:wallet_monitor");
+        assert_eq!(FailureCause::classify("web_fetch", "HTTP 404 Not Found", no_module).category("web_fetch"), "tool:web_fetch");
+
+        let db = Database::new(":memory:").unwrap();
+        let session = db.get_or_create_chat_session("web", 0, "u1", SessionScope::Dm, None).unwrap();
+        let error = "TWITTER_CONSUMER_KEY not configured";
+        record(&db, "twitter_post", error, "post a tweet about the launch", None, session.id);
+        record(&db, "twitter_post", error, "tweet the launch announcement", None, session.id);
+        let recalled = recall(&db, "tweet about launch", None);
+        assert_eq!(recalled.len(), 1);
+        assert!(recalled[0].content.contains("TWITTER_CONSUMER_KEY API key is not set"));
+
+        resolve_api_key(&db, "TWITTER_CONSUMER_KEY");
+        assert!(recall(&db, "tweet about launch", None).is_empty());
+    }
+}
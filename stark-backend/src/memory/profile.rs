@@ -0,0 +1,331 @@
+//! Per-identity profile builder
+//!
+//! After a completed session, asks the model for stable facts about the person
+//! the agent talked to (name, timezone, language, preferences, wallet addresses,
+//! ongoing projects) and merges them into the structured `identity_profiles`
+//! row instead of free-text memories. Fields the user corrected are locked and
+//! skipped by the builder.
+
+use crate::ai::{AiClient, Message, MessageRole};
+use crate::db::tables::identity_profiles::IdentityProfile;
+use crate::db::Database;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+pub const FIELD_DISPLAY_NAME: &str = "display_name";
+pub const FIELD_TIMEZONE: &str = "timezone";
+pub const FIELD_LANGUAGE: &str = "language";
+pub const FIELD_PREFERENCES: &str = "preferences";
+pub const FIELD_WALLET_ADDRESSES: &str = "wallet_addresses";
+pub const FIELD_PROJECTS: &str = "projects";
+
+/// Every field that can be corrected and locked
+pub const PROFILE_FIELDS: &[&str] = &[
+    FIELD_DISPLAY_NAME,
+    FIELD_TIMEZONE,
+    FIELD_LANGUAGE,
+    FIELD_PREFERENCES,
+    FIELD_WALLET_ADDRESSES,
+    FIELD_PROJECTS,
+];
+
+const MAX_PREFERENCES: usize = 30;
+const MAX_WALLETS: usize = 20;
+const MAX_PROJECTS: usize = 20;
+const MAX_VALUE_LEN: usize = 200;
+
+static ADDRESS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"0x[0-9a-fA-F]{40}\b").unwrap());
+
+/// Facts to merge into a profile. Missing fields leave the profile unchanged.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProfileUpdate {
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub preferences: BTreeMap<String, String>,
+    #[serde(default)]
+    pub wallet_addresses: Vec<String>,
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+impl ProfileUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.display_name.is_none()
+            && self.timezone.is_none()
+            && self.language.is_none()
+            && self.preferences.is_empty()
+            && self.wallet_addresses.is_empty()
+            && self.projects.is_empty()
+    }
+}
+
+/// EVM addresses appearing in `text`, lowercased, in order of first appearance
+pub fn extract_wallet_addresses(text: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for m in ADDRESS_RE.find_iter(text) {
+        let addr = m.as_str().to_lowercase();
+        if !out.contains(&addr) {
+            out.push(addr);
+        }
+    }
+    out
+}
+
+fn clean(value: &str) -> Option<String> {
+    let v = value.trim();
+    if v.is_empty() || v.eq_ignore_ascii_case("null") || v.eq_ignore_ascii_case("unknown") {
+        return None;
+    }
+    Some(v.chars().take(MAX_VALUE_LEN).collect())
+}
+
+/// Append `items` not already present (case-insensitive), keeping the newest `cap`
+fn merge_list(list: &mut Vec<String>, items: &[String], cap: usize) -> bool {
+    let mut changed = false;
+    for item in items.iter().filter_map(|i| clean(i)) {
+        if !list.iter().any(|e| e.eq_ignore_ascii_case(&item)) {
+            list.push(item);
+            changed = true;
+        }
+    }
+    if list.len() > cap {
+        let excess = list.len() - cap;
+        list.drain(..excess);
+    }
+    changed
+}
+
+fn set_scalar(slot: &mut Option<String>, value: &Option<String>) -> bool {
+    match value.as_deref().and_then(clean) {
+        Some(v) if slot.as_deref() != Some(v.as_str()) => {
+            *slot = Some(v);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Merge facts learned by the builder. Locked fields are left alone; lists and
+/// preferences accumulate. Returns whether anything changed.
+pub fn apply_learned(profile: &mut IdentityProfile, update: &ProfileUpdate) -> bool {
+    let locked = |field: &str| profile.locked_fields.iter().any(|f| f == field);
+    let (lock_name, lock_tz, lock_lang, lock_prefs, lock_wallets, lock_projects) = (
+        locked(FIELD_DISPLAY_NAME),
+        locked(FIELD_TIMEZONE),
+        locked(FIELD_LANGUAGE),
+        locked(FIELD_PREFERENCES),
+        locked(FIELD_WALLET_ADDRESSES),
+        locked(FIELD_PROJECTS),
+    );
+
+    let mut changed = false;
+    if !lock_name {
+        changed |= set_scalar(&mut profile.display_name, &update.display_name);
+    }
+    if !lock_tz {
+        changed |= set_scalar(&mut profile.timezone, &update.timezone);
+    }
+    if !lock_lang {
+        changed |= set_scalar(&mut profile.language, &update.language);
+    }
+    if !lock_prefs {
+        for (key, value) in &update.preferences {
+            let (Some(key), Some(value)) = (clean(key), clean(value)) else { continue };
+            if profile.preferences.len() >= MAX_PREFERENCES && !profile.preferences.contains_key(&key) {
+                continue;
+            }
+            if profile.preferences.get(&key) != Some(&value) {
+                profile.preferences.insert(key, value);
+                changed = true;
+            }
+        }
+    }
+    if !lock_wallets {
+        let wallets: Vec<String> = update.wallet_addresses.iter().map(|a| a.to_lowercase()).collect();
+        changed |= merge_list(&mut profile.wallet_addresses, &wallets, MAX_WALLETS);
+    }
+    if !lock_projects {
+        changed |= merge_list(&mut profile.projects, &update.projects, MAX_PROJECTS);
+    }
+    changed
+}
+
+/// Apply a user correction: provided fields replace the stored value and are
+/// locked against the builder. Fields in `unlock` are released again.
+pub fn apply_correction(profile: &mut IdentityProfile, update: &ProfileUpdate, provided: &[String], unlock: &[String]) {
+    for field in provided {
+        match field.as_str() {
+            FIELD_DISPLAY_NAME => profile.display_name = update.display_name.as_deref().and_then(clean),
+            FIELD_TIMEZONE => profile.timezone = update.timezone.as_deref().and_then(clean),
+            FIELD_LANGUAGE => profile.language = update.language.as_deref().and_then(clean),
+            FIELD_PREFERENCES => profile.preferences = update.preferences.clone(),
+            FIELD_WALLET_ADDRESSES => {
+                profile.wallet_addresses = update.wallet_addresses.iter().map(|a| a.trim().to_lowercase()).collect()
+            }
+            FIELD_PROJECTS => profile.projects = update.projects.clone(),
+            _ => continue,
+        }
+        if !profile.locked_fields.contains(field) {
+            profile.locked_fields.push(field.clone());
+        }
+    }
+    profile.locked_fields.retain(|f| !unlock.contains(f));
+}
+
+/// Parse the model's JSON reply (tolerates surrounding prose or code fences)
+pub fn parse_extraction(response: &str) -> Option<ProfileUpdate> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    if end < start {
+        return None;
+    }
+    serde_json::from_str(&response[start..=end]).ok()
+}
+
+/// Markdown rendering used by the `identity_profile` tool
+pub fn format_profile(profile: &IdentityProfile) -> String {
+    let or_unknown = |v: &Option<String>| v.clone().unwrap_or_else(|| "_unknown_".to_string());
+    let mut out = format!(
+        "## Profile\n- **Name:** {}\n- **Timezone:** {}\n- **Language:** {}\n",
+        or_unknown(&profile.display_name),
+        or_unknown(&profile.timezone),
+        or_unknown(&profile.language),
+    );
+    if !profile.preferences.is_empty() {
+        out.push_str("\n**Preferences:**\n");
+        for (k, v) in &profile.preferences {
+            out.push_str(&format!("- {}: {}\n", k, v));
+        }
+    }
+    if !profile.wallet_addresses.is_empty() {
+        out.push_str(&format!("\n**Wallets:** {}\n", profile.wallet_addresses.join(", ")));
+    }
+    if !profile.projects.is_empty() {
+        out.push_str("\n**Ongoing projects:**\n");
+        for p in &profile.projects {
+            out.push_str(&format!("- {}\n", p));
+        }
+    }
+    if !profile.locked_fields.is_empty() {
+        out.push_str(&format!("\n_Confirmed by the user: {}_\n", profile.locked_fields.join(", ")));
+    }
+    out
+}
+
+const EXTRACTION_PROMPT: &str = "You maintain a structured profile of the person the assistant is talking to. \
+From the exchange below, extract only STABLE facts the person stated or clearly implied about themselves — \
+not one-off requests. Respond with a single JSON object using only these keys, omitting anything not learned:\n\
+{\"display_name\": string, \"timezone\": IANA name, \"language\": string, \
+\"preferences\": {short key: short value}, \"wallet_addresses\": [addresses the person said are theirs], \
+\"projects\": [ongoing projects, a few words each]}\n\
+Respond with {} if nothing stable was learned.";
+
+/// Learn from one completed exchange and persist the merged profile.
+/// Returns whether the stored profile changed.
+pub async fn update_from_session(
+    db: &Arc<Database>,
+    client: &AiClient,
+    identity_id: &str,
+    user_input: &str,
+    bot_response: &str,
+) -> Result<bool, String> {
+    let mut profile = db
+        .get_identity_profile(identity_id)
+        .map_err(|e| format!("Failed to load profile: {}", e))?
+        .unwrap_or_else(|| IdentityProfile { identity_id: identity_id.to_string(), ..Default::default() });
+
+    let user_text: String = user_input.chars().take(2000).collect();
+    let bot_text: String = bot_response.chars().take(1000).collect();
+    let messages = vec![
+        Message { role: MessageRole::System, content: EXTRACTION_PROMPT.to_string() },
+        Message {
+            role: MessageRole::User,
+            content: format!("Person: {}\n\nAssistant: {}", user_text, bot_text),
+        },
+    ];
+    let response = client
+        .generate_text(messages)
+        .await
+        .map_err(|e| format!("Profile extraction failed: {}", e))?;
+
+    let Some(mut update) = parse_extraction(&response) else {
+        return Ok(false);
+    };
+    // Only keep addresses the person actually wrote — never ones from the reply
+    let typed = extract_wallet_addresses(user_input);
+    update.wallet_addresses.retain(|a| typed.contains(&a.to_lowercase()));
+    if update.is_empty() || !apply_learned(&mut profile, &update) {
+        return Ok(false);
+    }
+
+    db.save_identity_profile(&profile)
+        .map_err(|e| format!("Failed to save profile: {}", e))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> IdentityProfile {
+        IdentityProfile { identity_id: "id-1".to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_extract_wallet_addresses() {
+        let text = "send to 0xAbCdEf0123456789abcdef0123456789ABCDEF01 or 0xabcdef0123456789abcdef0123456789abcdef01, not 0x1234";
+        assert_eq!(
+            extract_wallet_addresses(text),
+            vec!["0xabcdef0123456789abcdef0123456789abcdef01".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_extraction_tolerates_fences() {
+        let update = parse_extraction("```json\n{\"timezone\": \"Asia/Tokyo\", \"projects\": [\"nft drop\"]}\n```").unwrap();
+        assert_eq!(update.timezone.as_deref(), Some("Asia/Tokyo"));
+        assert_eq!(update.projects, vec!["nft drop".to_string()]);
+        assert!(parse_extraction("{}").unwrap().is_empty());
+        assert!(parse_extraction("no json here").is_none());
+    }
+
+    #[test]
+    fn test_learned_facts_respect_locked_fields() {
+        let mut p = profile();
+        let update = ProfileUpdate {
+            timezone: Some("America/New_York".to_string()),
+            projects: vec!["yield vault".to_string()],
+            ..Default::default()
+        };
+        assert!(apply_learned(&mut p, &update));
+        assert!(!apply_learned(&mut p, &update), "same facts are not a change");
+
+        apply_correction(
+            &mut p,
+            &ProfileUpdate { timezone: Some("Europe/Lisbon".to_string()), ..Default::default() },
+            &[FIELD_TIMEZONE.to_string()],
+            &[],
+        );
+        assert_eq!(p.locked_fields, vec![FIELD_TIMEZONE.to_string()]);
+
+        let update = ProfileUpdate {
+            timezone: Some("America/Chicago".to_string()),
+            projects: vec!["Yield Vault".to_string(), "bridge bot".to_string()],
+            ..Default::default()
+        };
+        assert!(apply_learned(&mut p, &update));
+        assert_eq!(p.timezone.as_deref(), Some("Europe/Lisbon"));
+        assert_eq!(p.projects, vec!["yield vault".to_string(), "bridge bot".to_string()]);
+
+        apply_correction(&mut p, &ProfileUpdate::default(), &[], &[FIELD_TIMEZONE.to_string()]);
+        assert!(p.locked_fields.is_empty());
+    }
+}
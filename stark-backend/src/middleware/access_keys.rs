@@ -0,0 +1,303 @@
+//! Access key enforcement
+//!
+//! Requests carrying an access key (`Authorization: Bearer sk_stark_...`)
+//! are checked here before they reach a handler: the key must be known, not
+//! revoked and not expired, must hold the scope the route needs, and must be
+//! under its per-minute rate limit. A key that passes is recorded in the
+//! request extensions as an [`AuthorizedAccessKey`]; handlers accept a key
+//! only through that record, never as a plain session token. Session tokens
+//! pass through untouched and handlers validate those as before.
+//!
+//! Scopes:
+//! - `chat`: chat, sessions and transcription
+//! - `skills:read` / `skills:write`: reading vs. installing or changing skills
+//! - `finance`: transactions, payments, wallets, trades and strategies
+//! - `admin`: everything, including modules and settings
+//!
+//! Managing access keys, two-factor enrollment and devices always needs a
+//! login session.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpMessage, HttpResponse};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use strum::{AsRefStr, EnumIter, EnumString};
+
+use crate::db::tables::access_keys::{AccessKey, ACCESS_KEY_PREFIX};
+use crate::db::Database;
+use crate::models::Session;
+use crate::AppState;
+
+/// What an access key may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumString, AsRefStr)]
+pub enum AccessScope {
+    #[strum(serialize = "chat")]
+    Chat,
+    #[strum(serialize = "skills:read")]
+    SkillsRead,
+    #[strum(serialize = "skills:write")]
+    SkillsWrite,
+    #[strum(serialize = "finance")]
+    Finance,
+    #[strum(serialize = "admin")]
+    Admin,
+}
+
+impl AccessScope {
+    /// Whether holding `scopes` grants this scope (admin grants all, and
+    /// skills:write includes skills:read)
+    pub fn granted_by(&self, scopes: &[String]) -> bool {
+        scopes.iter().any(|s| {
+            s == AccessScope::Admin.as_ref()
+                || s == self.as_ref()
+                || (*self == AccessScope::SkillsRead && s == AccessScope::SkillsWrite.as_ref())
+        })
+    }
+}
+
+/// What a route needs from an access key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Requirement {
+    /// Open to anyone
+    Public,
+    Scope(AccessScope),
+    /// Only a login session will do
+    SessionOnly,
+}
+
+const PUBLIC_PREFIXES: &[&str] = &[
+    "/api/health",
+    "/api/version",
+    "/api/openapi.json",
+    "/api/docs",
+    "/api/auth/",
+    "/.well-known/",
+];
+
+const CHAT_PREFIXES: &[&str] = &["/api/chat", "/api/sessions", "/api/transcribe"];
+
+const FINANCE_PREFIXES: &[&str] = &[
+    "/api/tx-queue",
+    "/api/tx-approvals",
+    "/api/broadcasted-transactions",
+    "/api/payments",
+    "/api/x402-limits",
+    "/api/internal/wallet",
+    "/api/safe",
+    "/api/trades",
+    "/api/paper",
+    "/api/strategies",
+    "/api/eip8004",
+];
+
+fn under(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => prefix.ends_with('/') || rest.is_empty() || rest.starts_with('/') || rest.starts_with('?'),
+        None => false,
+    }
+}
+
+/// The requirement for a method and path
+pub fn requirement(method: &Method, path: &str) -> Requirement {
+    if under(path, "/api/access-keys")
+        || under(path, "/api/two-factor")
+        || under(path, "/api/auth/sessions")
+        || (method == Method::POST && under(path, "/api/system/update"))
+    {
+        return Requirement::SessionOnly;
+    }
+    if PUBLIC_PREFIXES.iter().any(|p| under(path, p)) {
+        return Requirement::Public;
+    }
+    // The SPA and its assets
+    if !path.starts_with("/api/") && !path.starts_with("/rpc/") && path != "/ws" {
+        return Requirement::Public;
+    }
+    if CHAT_PREFIXES.iter().any(|p| under(path, p)) {
+        return Requirement::Scope(AccessScope::Chat);
+    }
+    if under(path, "/api/skills") {
+        return if method == Method::GET {
+            Requirement::Scope(AccessScope::SkillsRead)
+        } else {
+            Requirement::Scope(AccessScope::SkillsWrite)
+        };
+    }
+    if FINANCE_PREFIXES.iter().any(|p| under(path, p)) {
+        return Requirement::Scope(AccessScope::Finance);
+    }
+    Requirement::Scope(AccessScope::Admin)
+}
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Request times per key within the last window
+static RATE_LIMITS: Lazy<DashMap<i64, VecDeque<Instant>>> = Lazy::new(DashMap::new);
+
+/// Record a request for `key_id`. Returns the seconds to wait when the key is
+/// already at `per_minute` requests in the last minute.
+fn check_rate_limit(key_id: i64, per_minute: i64, now: Instant) -> Result<(), u64> {
+    if per_minute <= 0 {
+        return Ok(());
+    }
+    let mut hits = RATE_LIMITS.entry(key_id).or_default();
+    while hits.front().is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW) {
+        hits.pop_front();
+    }
+    if hits.len() as i64 >= per_minute {
+        let oldest = *hits.front().expect("non-empty at limit");
+        let wait = RATE_WINDOW.saturating_sub(now.duration_since(oldest));
+        return Err(wait.as_secs().max(1));
+    }
+    hits.push_back(now);
+    Ok(())
+}
+
+/// A key that passed the scope, expiry and rate limit checks for the current
+/// request. The middleware leaves it in the request extensions; it is the only
+/// way a handler accepts an access key in place of a login session.
+#[derive(Debug, Clone)]
+pub struct AuthorizedAccessKey {
+    pub token: String,
+    pub session: Session,
+}
+
+/// Why an access key was turned away
+#[derive(Debug)]
+pub enum KeyDenial {
+    /// Unknown, revoked or expired
+    Invalid,
+    MissingScope(AccessScope),
+    /// Over the per-minute limit; `retry_after` is in seconds
+    RateLimited { retry_after: u64, per_minute: i64 },
+    Database(rusqlite::Error),
+}
+
+/// Check an access key against a scope: it must be known, usable, hold the
+/// scope and be under its rate limit. A use is recorded on success.
+pub fn authorize(db: &Database, token: &str, scope: AccessScope) -> Result<AccessKey, KeyDenial> {
+    let key = match db.find_access_key(token) {
+        Ok(Some(key)) if key.is_usable() => key,
+        Ok(_) => return Err(KeyDenial::Invalid),
+        Err(e) => return Err(KeyDenial::Database(e)),
+    };
+    if !scope.granted_by(&key.scopes) {
+        log::warn!(
+            "[ACCESS_KEY] Key '{}' ({}) denied: needs scope '{}'",
+            key.name, key.key_prefix, scope.as_ref()
+        );
+        return Err(KeyDenial::MissingScope(scope));
+    }
+    check_rate_limit(key.id, key.rate_limit_per_minute, Instant::now()).map_err(|retry_after| {
+        KeyDenial::RateLimited { retry_after, per_minute: key.rate_limit_per_minute }
+    })?;
+    if let Err(e) = db.touch_access_key(key.id) {
+        log::warn!("Failed to record access key use: {}", e);
+    }
+    Ok(key)
+}
+
+fn reject<B>(req: ServiceRequest, resp: HttpResponse) -> ServiceResponse<EitherBody<B>> {
+    req.into_response(resp).map_into_right_body()
+}
+
+/// Middleware: enforce scope, expiry and rate limit for access key requests
+pub async fn enforce<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .filter(|t| t.starts_with(ACCESS_KEY_PREFIX))
+        .map(str::to_string);
+    let (Some(token), Some(state)) = (token, req.app_data::<web::Data<AppState>>().cloned()) else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+
+    let scope = match requirement(req.method(), req.path()) {
+        Requirement::Public => return next.call(req).await.map(ServiceResponse::map_into_left_body),
+        Requirement::SessionOnly => {
+            return Ok(reject(req, HttpResponse::Forbidden().json(serde_json::json!({
+                "error": "This endpoint needs a login session, not an access key"
+            }))));
+        }
+        Requirement::Scope(scope) => scope,
+    };
+
+    let key = match authorize(&state.db, &token, scope) {
+        Ok(key) => key,
+        Err(KeyDenial::Invalid) => {
+            return Ok(reject(req, HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Invalid, revoked or expired access key"
+            }))));
+        }
+        Err(KeyDenial::MissingScope(scope)) => {
+            return Ok(reject(req, HttpResponse::Forbidden().json(serde_json::json!({
+                "error": format!("This access key lacks the '{}' scope", scope.as_ref()),
+                "required_scope": scope.as_ref(),
+            }))));
+        }
+        Err(KeyDenial::RateLimited { retry_after, per_minute }) => {
+            return Ok(reject(
+                req,
+                HttpResponse::TooManyRequests()
+                    .insert_header(("Retry-After", retry_after.to_string()))
+                    .json(serde_json::json!({
+                        "error": format!("Rate limit of {} requests per minute exceeded", per_minute),
+                    })),
+            ));
+        }
+        Err(KeyDenial::Database(e)) => {
+            log::error!("Access key lookup failed: {}", e);
+            return Ok(reject(req, HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            }))));
+        }
+    };
+
+    let session = key.as_session(&token);
+    req.extensions_mut().insert(AuthorizedAccessKey { token, session });
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requirements_and_rate_limit() {
+        let scope = |m: Method, p: &str| requirement(&m, p);
+        assert_eq!(scope(Method::POST, "/api/chat"), Requirement::Scope(AccessScope::Chat));
+        assert_eq!(scope(Method::GET, "/api/skills"), Requirement::Scope(AccessScope::SkillsRead));
+        assert_eq!(scope(Method::POST, "/api/skills/upload"), Requirement::Scope(AccessScope::SkillsWrite));
+        assert_eq!(scope(Method::POST, "/api/tx-queue/1/approve"), Requirement::Scope(AccessScope::Finance));
+        assert_eq!(scope(Method::POST, "/api/modules/install"), Requirement::Scope(AccessScope::Admin));
+        assert_eq!(scope(Method::GET, "/api/chatter"), Requirement::Scope(AccessScope::Admin));
+        assert_eq!(scope(Method::GET, "/ws"), Requirement::Scope(AccessScope::Admin));
+        assert_eq!(scope(Method::GET, "/api/health"), Requirement::Public);
+        assert_eq!(scope(Method::GET, "/assets/app.js"), Requirement::Public);
+        assert_eq!(scope(Method::POST, "/api/access-keys"), Requirement::SessionOnly);
+        assert_eq!(scope(Method::POST, "/api/system/update"), Requirement::SessionOnly);
+        assert_eq!(scope(Method::GET, "/api/system/update"), Requirement::Scope(AccessScope::Admin));
+
+        let dashboard = vec!["chat".to_string(), "skills:write".to_string()];
+        assert!(AccessScope::SkillsRead.granted_by(&dashboard));
+        assert!(!AccessScope::Finance.granted_by(&dashboard));
+        assert!(AccessScope::Finance.granted_by(&["admin".to_string()]));
+
+        let start = Instant::now();
+        assert!(check_rate_limit(-1, 2, start).is_ok());
+        assert!(check_rate_limit(-1, 2, start).is_ok());
+        assert_eq!(check_rate_limit(-1, 2, start + Duration::from_secs(15)), Err(45));
+        assert!(check_rate_limit(-1, 2, start + RATE_WINDOW).is_ok());
+    }
+}
@@ -0,0 +1,169 @@
+//! HTTP hardening: CORS allow-list, cookie sessions with CSRF checks, and
+//! security headers
+//!
+//! - **CORS**: cross-origin requests are only allowed from the origins listed
+//!   in `bot_settings.cors_allowed_origins`, plus the bot's own public URL and,
+//!   in dev mode, localhost. `*` in the list restores the old allow-all policy.
+//! - **Cookie sessions**: logins also set an HttpOnly `stark_session` cookie.
+//!   A request without an `Authorization` header but with that cookie is
+//!   treated as bearing its token, so handlers need no changes.
+//! - **CSRF**: cookie-authenticated requests that change state (anything but
+//!   GET/HEAD/OPTIONS) must echo the `stark_csrf` cookie in an `X-CSRF-Token`
+//!   header (double-submit). Bearer-token requests can't be forged
+//!   cross-site and are not checked.
+//! - **Headers**: nosniff, frame and referrer policies on every response, and
+//!   HSTS when the request came over HTTPS.
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::cookie::{time::Duration as CookieDuration, Cookie, SameSite};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpResponse};
+
+pub const SESSION_COOKIE: &str = "stark_session";
+pub const CSRF_COOKIE: &str = "stark_csrf";
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// Turn the settings text (comma or whitespace separated) into normalized origins.
+/// Entries that aren't `*` or an http(s) origin are dropped.
+pub fn parse_origins(text: &str) -> Vec<String> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .map(|o| o.trim().trim_end_matches('/').to_lowercase())
+        .filter(|o| o == "*" || o.starts_with("http://") || o.starts_with("https://"))
+        .collect()
+}
+
+/// Whether a browser origin may call the API
+pub fn origin_allowed(origin: &str, allowed: &[String], dev_mode: bool) -> bool {
+    let origin = origin.trim_end_matches('/').to_lowercase();
+    if allowed.iter().any(|a| a == "*" || *a == origin) {
+        return true;
+    }
+    if origin == crate::config::self_url().to_lowercase() {
+        return true;
+    }
+    dev_mode
+        && ["http://localhost", "http://127.0.0.1"]
+            .iter()
+            .any(|host| origin == *host || origin.starts_with(&format!("{}:", host)))
+}
+
+/// Cookies set on login and refresh: the session token (HttpOnly) and a CSRF
+/// token the frontend reads and echoes back
+pub fn session_cookies(token: &str, max_age_secs: i64, secure: bool) -> (Cookie<'static>, Cookie<'static>) {
+    let csrf: [u8; 16] = rand::random();
+    let session = Cookie::build(SESSION_COOKIE, token.to_string())
+        .path("/")
+        .http_only(true)
+        .secure(secure)
+        .same_site(SameSite::Strict)
+        .max_age(CookieDuration::seconds(max_age_secs))
+        .finish();
+    let csrf = Cookie::build(CSRF_COOKIE, hex::encode(csrf))
+        .path("/")
+        .secure(secure)
+        .same_site(SameSite::Strict)
+        .max_age(CookieDuration::seconds(max_age_secs))
+        .finish();
+    (session, csrf)
+}
+
+/// Expired copies of the session cookies, for logout
+pub fn cleared_cookies() -> (Cookie<'static>, Cookie<'static>) {
+    let mut session = Cookie::build(SESSION_COOKIE, "").path("/").finish();
+    session.make_removal();
+    let mut csrf = Cookie::build(CSRF_COOKIE, "").path("/").finish();
+    csrf.make_removal();
+    (session, csrf)
+}
+
+pub(crate) fn is_safe_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Constant-time comparison, so the token can't be guessed byte by byte
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+const SECURITY_HEADERS: &[(&str, &str)] = &[
+    ("x-content-type-options", "nosniff"),
+    ("x-frame-options", "SAMEORIGIN"),
+    ("referrer-policy", "strict-origin-when-cross-origin"),
+    ("permissions-policy", "camera=(), geolocation=(), payment=()"),
+    ("cross-origin-opener-policy", "same-origin"),
+];
+
+/// Middleware: cookie sessions, CSRF check and security headers
+pub async fn protect<B: MessageBody>(
+    mut req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    let has_bearer = req.headers().contains_key(header::AUTHORIZATION);
+    let session_cookie = req.cookie(SESSION_COOKIE).map(|c| c.value().to_string());
+
+    if let (false, Some(token)) = (has_bearer, session_cookie) {
+        if !is_safe_method(req.method()) {
+            let expected = req.cookie(CSRF_COOKIE).map(|c| c.value().to_string());
+            let presented = req
+                .headers()
+                .get(CSRF_HEADER)
+                .and_then(|h| h.to_str().ok())
+                .map(str::to_string);
+            let valid = matches!((&expected, &presented), (Some(e), Some(p)) if !e.is_empty() && tokens_match(e, p));
+            if !valid {
+                log::warn!("[CSRF] Rejected {} {}: missing or mismatched {}", req.method(), req.path(), CSRF_HEADER);
+                let resp = HttpResponse::Forbidden().json(serde_json::json!({
+                    "error": format!("Missing or invalid {} header", CSRF_HEADER)
+                }));
+                return Ok(req.into_response(resp).map_into_right_body());
+            }
+        }
+        if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", token)) {
+            req.headers_mut().insert(header::AUTHORIZATION, value);
+        }
+    }
+
+    let https = req.connection_info().scheme() == "https";
+    let mut res = next.call(req).await?;
+    let headers = res.headers_mut();
+    for (name, value) in SECURITY_HEADERS {
+        let name = HeaderName::from_static(name);
+        if !headers.contains_key(&name) {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+    }
+    if https {
+        headers.insert(
+            header::STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_static("max-age=31536000; includeSubDomains"),
+        );
+    }
+    Ok(res.map_into_left_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_origins_and_csrf_tokens() {
+        let allowed = parse_origins("https://dash.example.com/, https://Other.example.com\nnot-an-origin");
+        assert_eq!(allowed, vec!["https://dash.example.com", "https://other.example.com"]);
+        assert!(origin_allowed("https://dash.example.com", &allowed, false));
+        assert!(!origin_allowed("https://evil.example.com", &allowed, false));
+        assert!(!origin_allowed("http://localhost:5173", &allowed, false));
+        assert!(origin_allowed("http://localhost:5173", &allowed, true));
+        assert!(!origin_allowed("http://localhost.evil.com", &allowed, true));
+        assert!(origin_allowed("https://anything.test", &parse_origins("*"), false));
+
+        assert!(tokens_match("abc123", "abc123"));
+        assert!(!tokens_match("abc123", "abc124"));
+        assert!(!tokens_match("abc", "abc123"));
+        let (session, csrf) = session_cookies("tok", 60, true);
+        assert!(session.http_only().unwrap_or(false));
+        assert_eq!(csrf.value().len(), 32);
+    }
+}
@@ -1 +1,4 @@
 pub mod session_auth;
+pub mod access_keys;
+pub mod http_security;
+pub mod read_only_mode;
@@ -0,0 +1,69 @@
+//! Read-only mirror mode for the HTTP API
+//!
+//! While `bot_settings.read_only_mode` is on, requests that change state
+//! (anything but GET/HEAD/OPTIONS) are refused with 403, so confirming
+//! transactions, editing settings or installing modules over HTTP can't get
+//! around the tool-level check. Signing in, chatting (whose tools are checked
+//! by the registry), read-only POST queries and turning the mode off stay open.
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpResponse};
+
+use super::http_security::is_safe_method;
+use crate::AppState;
+
+/// Routes (and everything under them) that may still change state
+const ALLOWED_WRITES: &[&str] = &[
+    "/api/auth",
+    "/api/read-only-mode",
+    "/api/two-factor/verify",
+    "/api/chat",
+    "/api/introspection/query",
+    "/api/transcribe",
+];
+
+/// Whether read-only mode lets a state-changing request to `path` through
+pub fn allows_write(path: &str) -> bool {
+    ALLOWED_WRITES
+        .iter()
+        .any(|p| path == *p || path.strip_prefix(p).is_some_and(|rest| rest.starts_with('/')))
+}
+
+pub async fn enforce<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    if is_safe_method(req.method()) || allows_write(req.path()) {
+        return Ok(next.call(req).await?.map_into_left_body());
+    }
+    let enabled = req
+        .app_data::<web::Data<AppState>>()
+        .is_some_and(|state| crate::tools::read_only_mode::is_enabled(&state.db));
+    if !enabled {
+        return Ok(next.call(req).await?.map_into_left_body());
+    }
+    log::info!("[READ_ONLY_MODE] Refused {} {}", req.method(), req.path());
+    let resp = HttpResponse::Forbidden().json(serde_json::json!({
+        "error": "This bot is running in read-only mirror mode, so nothing can be changed"
+    }));
+    Ok(req.into_response(resp).map_into_right_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_writes() {
+        assert!(allows_write("/api/auth/login"));
+        assert!(allows_write("/api/read-only-mode"));
+        assert!(allows_write("/api/chat"));
+        assert!(allows_write("/api/chat/stop"));
+        assert!(!allows_write("/api/chatter"));
+        assert!(!allows_write("/api/tx-approvals/abc/approve"));
+        assert!(!allows_write("/api/bot-settings"));
+        assert!(!allows_write("/api/two-factor/disable"));
+    }
+}
@@ -3,16 +3,37 @@
 // Currently, authentication is handled directly in controllers, but this module
 // can be extended to provide a reusable middleware wrapper for protected endpoints.
 
-use actix_web::{HttpRequest, HttpResponse};
+use actix_web::{HttpMessage, HttpRequest, HttpResponse};
+use rusqlite::Result as SqliteResult;
 use std::sync::Arc;
 
+use crate::db::tables::access_keys::ACCESS_KEY_PREFIX;
 use crate::db::Database;
+use crate::middleware::access_keys::AuthorizedAccessKey;
+use crate::models::Session;
 
+/// The token of an `Authorization: Bearer <token>` header. Anything else,
+/// including a bare token without the scheme, is no token at all.
 pub fn extract_token(req: &HttpRequest) -> Option<String> {
     req.headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.trim_start_matches("Bearer ").to_string())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(str::to_string)
+}
+
+/// Validate a request's bearer token. Login sessions are looked up in the
+/// database; an access key is accepted only if the access key middleware
+/// authorized it for this request's route.
+pub fn validate_token(db: &Database, req: &HttpRequest, token: &str) -> SqliteResult<Option<Session>> {
+    if token.starts_with(ACCESS_KEY_PREFIX) {
+        return Ok(req
+            .extensions()
+            .get::<AuthorizedAccessKey>()
+            .filter(|key| key.token == token)
+            .map(|key| key.session.clone()));
+    }
+    db.validate_session(token)
 }
 
 pub async fn validate_request(db: &Arc<Database>, req: &HttpRequest) -> Result<(), HttpResponse> {
@@ -22,7 +43,7 @@ pub async fn validate_request(db: &Arc<Database>, req: &HttpRequest) -> Result<(
         }))
     })?;
 
-    match db.validate_session(&token) {
+    match validate_token(db, req, &token) {
         Ok(Some(_)) => Ok(()),
         Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
             "error": "Invalid or expired session"
@@ -35,3 +56,37 @@ pub async fn validate_request(db: &Arc<Database>, req: &HttpRequest) -> Result<(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::tables::access_keys::generate_access_key;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_access_keys_need_the_middleware() {
+        let db = Database::new(":memory:").unwrap();
+        let key = generate_access_key();
+        let record = db.create_access_key("ci", &key, &["admin".to_string()], 0, None).unwrap();
+        let session = db.create_session().unwrap();
+
+        // The scheme is required: a bare token is no token
+        let bare = TestRequest::default().insert_header(("Authorization", key.as_str())).to_http_request();
+        assert_eq!(extract_token(&bare), None);
+        let bearer = TestRequest::default()
+            .insert_header(("Authorization", format!("Bearer {}", key)))
+            .to_http_request();
+        assert_eq!(extract_token(&bearer).as_deref(), Some(key.as_str()));
+
+        // A key is never a session by itself
+        assert!(db.validate_session(&key).unwrap().is_none());
+        assert!(validate_token(&db, &bearer, &key).unwrap().is_none());
+        bearer.extensions_mut().insert(AuthorizedAccessKey {
+            token: key.clone(),
+            session: record.as_session(&key),
+        });
+        assert!(validate_token(&db, &bearer, &key).unwrap().is_some());
+
+        assert!(validate_token(&db, &bare, &session.token).unwrap().is_some());
+    }
+}
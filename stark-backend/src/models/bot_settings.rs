@@ -14,6 +14,9 @@ pub const DEFAULT_WHISPER_SERVER_URL: &str = "https://whisper.defirelay.com";
 /// Default embeddings server URL
 pub const DEFAULT_EMBEDDINGS_SERVER_URL: &str = "https://embeddings.defirelay.com";
 
+/// Default time an owner has to answer a transaction approval card
+pub const DEFAULT_TX_APPROVAL_TTL_SECS: i64 = 15 * 60;
+
 /// Bot settings stored in database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BotSettings {
@@ -65,6 +68,37 @@ pub struct BotSettings {
     /// Emergency compaction threshold
     #[serde(default = "default_emergency_threshold")]
     pub compaction_emergency_threshold: f64,
+    /// Transactions above this native value (in ETH) need owner approval via chat buttons.
+    /// None = no chat approvals (partner mode confirms in the dashboard only)
+    #[serde(default)]
+    pub tx_approval_threshold_eth: Option<f64>,
+    /// Channel the approval cards are sent through
+    #[serde(default)]
+    pub tx_approval_channel_id: Option<i64>,
+    /// Platform chat ID (owner's DM) the approval cards are sent to
+    #[serde(default)]
+    pub tx_approval_chat_id: Option<String>,
+    /// Seconds before an unanswered approval expires and the transaction is dropped
+    #[serde(default = "default_tx_approval_ttl")]
+    pub tx_approval_ttl_secs: i64,
+    /// Treasury Safe that propose_safe_tx targets when no address is given
+    #[serde(default)]
+    pub safe_address: Option<String>,
+    /// Network the treasury Safe lives on (None = base)
+    #[serde(default)]
+    pub safe_network: Option<String>,
+    /// Paper trading for every channel: finance tools simulate fills instead of signing
+    #[serde(default)]
+    pub paper_trading_enabled: bool,
+    /// Publish hash-chained, signed receipts of confirmed txs and paid tasks
+    #[serde(default)]
+    pub receipts_log_enabled: bool,
+    /// Read-only mirror mode: mutating tools are refused, lookups keep working (public demos)
+    #[serde(default)]
+    pub read_only_mode: bool,
+    /// Browser origins allowed to call the API cross-origin ("*" = any)
+    #[serde(default)]
+    pub cors_allowed_origins: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -95,17 +129,38 @@ impl Default for BotSettings {
             compaction_background_threshold: 0.80,
             compaction_aggressive_threshold: 0.85,
             compaction_emergency_threshold: 0.95,
+            tx_approval_threshold_eth: None,
+            tx_approval_channel_id: None,
+            tx_approval_chat_id: None,
+            tx_approval_ttl_secs: DEFAULT_TX_APPROVAL_TTL_SECS,
+            safe_address: None,
+            safe_network: None,
+            paper_trading_enabled: false,
+            receipts_log_enabled: false,
+            read_only_mode: false,
+            cors_allowed_origins: Vec::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
     }
 }
 
+impl BotSettings {
+    /// The owner's DM (channel ID, chat ID) — the chat configured for
+    /// transaction approvals, also used for other owner notifications
+    pub fn owner_chat(&self) -> Option<(i64, String)> {
+        let channel_id = self.tx_approval_channel_id?;
+        let chat_id = self.tx_approval_chat_id.clone().filter(|c| !c.is_empty())?;
+        Some((channel_id, chat_id))
+    }
+}
+
 fn default_coalescing_debounce() -> u64 { 1500 }
 fn default_coalescing_max_wait() -> u64 { 5000 }
 fn default_background_threshold() -> f64 { 0.80 }
 fn default_aggressive_threshold() -> f64 { 0.85 }
 fn default_emergency_threshold() -> f64 { 0.95 }
+fn default_tx_approval_ttl() -> i64 { DEFAULT_TX_APPROVAL_TTL_SECS }
 
 /// Request type for updating bot settings
 #[derive(Debug, Clone, Deserialize)]
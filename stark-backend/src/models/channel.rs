@@ -8,6 +8,7 @@ pub enum ChannelType {
     Slack,
     Discord,
     Twitter,
+    Farcaster,
     ExternalChannel,
 }
 
@@ -18,6 +19,7 @@ impl ChannelType {
             ChannelType::Slack => "slack",
             ChannelType::Discord => "discord",
             ChannelType::Twitter => "twitter",
+            ChannelType::Farcaster => "farcaster",
             ChannelType::ExternalChannel => "external_channel",
         }
     }
@@ -28,6 +30,7 @@ impl ChannelType {
             "slack" => Some(ChannelType::Slack),
             "discord" => Some(ChannelType::Discord),
             "twitter" => Some(ChannelType::Twitter),
+            "farcaster" => Some(ChannelType::Farcaster),
             "external_channel" => Some(ChannelType::ExternalChannel),
             _ => None,
         }
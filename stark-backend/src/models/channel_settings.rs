@@ -53,6 +53,44 @@ impl ToolOutputVerbosity {
 pub enum ChannelSettingKey {
     /// Common: Auto-start this channel when the server boots (after restore from backup)
     AutoStartOnBoot,
+    /// Common: Simulate finance tool transactions in a paper ledger instead of signing
+    PaperTrading,
+    /// Common: Agent subtype every conversation in this channel runs as (empty = director routing)
+    AgentSubtype,
+    /// Common: Issue tracker project for issues filed from this channel ("linear:ENG", "jira:OPS")
+    IssueProject,
+    /// Common: Compact long sessions automatically in the background between turns
+    AutoCompaction,
+    /// Common: Context usage (% of the window) that triggers automatic compaction (empty = global)
+    CompactionThreshold,
+    /// Common: Tools below this safety level wait for the user's confirmation before running (empty = off)
+    ToolConfirmation,
+    /// Common: Comma-separated slash commands the backend should not answer here (empty = all enabled)
+    DisabledCommands,
+    /// Common: Default response style for sessions in this channel (empty = none)
+    ResponseStyle,
+    /// Common: How failed tool calls are recovered: retry, repair, skip (empty = all)
+    ToolErrorRecovery,
+    /// Common: Match requests against installed skills: suggest (default), auto-activate or off
+    SkillSuggestions,
+    /// Common: Similarity % a request needs to a skill before it is suggested (empty = 80)
+    SkillSuggestionThreshold,
+    /// Group chats: What happens to messages that don't summon the bot: ignore, remember (passive) or answer all
+    GroupResponseMode,
+    /// Group chats: Answer when the bot is @mentioned
+    GroupTriggerMention,
+    /// Group chats: Answer replies to the bot's messages
+    GroupTriggerReply,
+    /// Group chats: Answer messages matching this regex (empty = off)
+    GroupTriggerPattern,
+    /// Group chats: Hide private data (balances, email, other people's memories) the requester doesn't own
+    ResponseRedaction,
+    /// Push channels: Allow unsolicited messages (cron results, alerts, heartbeat check-ins, notifications)
+    ProactiveMessages,
+    /// Push channels: Local time range with no proactive messages, e.g. "22:00-07:00" (empty = none)
+    ProactiveQuietHours,
+    /// Push channels: Maximum proactive messages per day (0 = unlimited)
+    ProactiveDailyLimit,
     /// Discord: Bot authentication token
     DiscordBotToken,
     /// Discord: Comma-separated list of Discord user IDs with admin access
@@ -76,6 +114,18 @@ pub enum ChannelSettingKey {
     TwitterMaxMentionsPerHour,
     /// Twitter: Admin X account numeric user ID — tweets from this account bypass safe mode
     TwitterAdminXAccount,
+    /// Twitter: Also poll direct messages and reply to them in the DM conversation
+    TwitterReadDms,
+    /// Twitter: Hold replies in the outbox until they are approved
+    TwitterReviewBeforeSend,
+    /// Farcaster: The bot account's numeric fid (mentions of it are answered)
+    FarcasterBotFid,
+    /// Farcaster: Poll interval in seconds (min 30, default 60)
+    FarcasterPollIntervalSecs,
+    /// Farcaster: Maximum number of non-admin casts to reply to per hour
+    FarcasterMaxRepliesPerHour,
+    /// Farcaster: Admin fid — casts from this account bypass safe mode
+    FarcasterAdminFid,
     /// Telegram: Admin user ID — messages from this user bypass safe mode
     TelegramAdminUserId,
     /// Slack: Comma-separated list of Slack user IDs with admin access
@@ -91,6 +141,25 @@ impl ChannelSettingKey {
     pub fn label(&self) -> &'static str {
         match self {
             Self::AutoStartOnBoot => "Auto-Start on Boot",
+            Self::PaperTrading => "Paper Trading",
+            Self::AgentSubtype => "Agent Subtype (Optional)",
+            Self::IssueProject => "Issue Tracker Project (Optional)",
+            Self::AutoCompaction => "Automatic Compaction",
+            Self::CompactionThreshold => "Compaction Threshold % (Optional)",
+            Self::ToolConfirmation => "Confirm Tool Calls",
+            Self::DisabledCommands => "Disabled Chat Commands (Optional)",
+            Self::ResponseStyle => "Response Style",
+            Self::ToolErrorRecovery => "Tool Error Recovery",
+            Self::SkillSuggestions => "Skill Suggestions",
+            Self::SkillSuggestionThreshold => "Skill Suggestion Threshold % (Optional)",
+            Self::GroupResponseMode => "Group Chat Mode",
+            Self::GroupTriggerMention => "Answer Mentions",
+            Self::GroupTriggerReply => "Answer Replies",
+            Self::GroupTriggerPattern => "Trigger Pattern (Optional)",
+            Self::ResponseRedaction => "Redact Private Data",
+            Self::ProactiveMessages => "Allow Proactive Messages",
+            Self::ProactiveQuietHours => "Quiet Hours (Optional)",
+            Self::ProactiveDailyLimit => "Max Proactive Messages Per Day",
             Self::DiscordBotToken => "Bot Token",
             Self::DiscordAdminUserIds => "Admin User IDs (Optional)",
             Self::TelegramBotToken => "Bot Token",
@@ -102,6 +171,12 @@ impl ChannelSettingKey {
             Self::TwitterReplyChance => "Reply Chance",
             Self::TwitterMaxMentionsPerHour => "Max Replies Per Hour",
             Self::TwitterAdminXAccount => "Admin X User ID (Optional)",
+            Self::TwitterReadDms => "Read Direct Messages",
+            Self::TwitterReviewBeforeSend => "Review Before Send",
+            Self::FarcasterBotFid => "Bot FID",
+            Self::FarcasterPollIntervalSecs => "Poll Interval (seconds)",
+            Self::FarcasterMaxRepliesPerHour => "Max Replies Per Hour",
+            Self::FarcasterAdminFid => "Admin FID (Optional)",
             Self::TelegramAdminUserId => "Admin User ID (Optional)",
             Self::SlackAdminUserIds => "Admin User IDs (Optional)",
             Self::ExternalChannelApiToken => "API Token",
@@ -116,6 +191,87 @@ impl ChannelSettingKey {
                 "Automatically start this channel when the server boots or restores from backup. \
                  Useful for ensuring your bot is always running after container updates."
             }
+            Self::PaperTrading => {
+                "Swaps, transfers and bridges requested from this channel are simulated from live quotes \
+                 and recorded in the paper ledger. Nothing is signed or broadcast."
+            }
+            Self::AgentSubtype => {
+                "Key of the agent subtype this channel always runs as (e.g. \"finance\"). \
+                 Leave empty to let the director route each message."
+            }
+            Self::IssueProject => {
+                "Where issues filed from this channel go: \"linear:<TEAM_KEY>\" or \"jira:<PROJECT_KEY>\". \
+                 Leave empty to require the project on every issue_tracker call."
+            }
+            Self::AutoCompaction => {
+                "Summarize older messages in the background once a session's context grows past the \
+                 threshold. Runs between turns and never delays a reply."
+            }
+            Self::CompactionThreshold => {
+                "Percentage of the context window at which background compaction starts (10-95). \
+                 Leave empty to use the global compaction threshold."
+            }
+            Self::ToolConfirmation => {
+                "Ask before running tools that aren't cleared for a more restricted context. \
+                 The request appears in the dashboard (and as Confirm/Cancel buttons on Telegram and Discord) \
+                 and the tool is skipped if nobody answers within 5 minutes."
+            }
+            Self::DisabledCommands => {
+                "Comma-separated chat commands (e.g. \"memory, mode\") the bot should not answer itself in this channel. \
+                 Disabled commands are passed to the agent as ordinary messages. /help is always available."
+            }
+            Self::ResponseStyle => {
+                "How the agent answers in this channel: concise, verbose or technical. \
+                 Each style adjusts the prompt's directives and caps the reply length. \
+                 A style picked with /style in a chat takes precedence for that session."
+            }
+            Self::ToolErrorRecovery => {
+                "What happens when a tool call fails. By default transient errors are retried with backoff, \
+                 calls with bad parameters are sent back to the agent to fix, and failures in optional tasks \
+                 are skipped with a note. Limit this to retries, or turn it off to pass every failure to the agent."
+            }
+            Self::SkillSuggestions => {
+                "When a request closely matches an installed skill the session hasn't used, point the agent at it. \
+                 Auto-activate loads the skill before the agent starts when the match is especially close. \
+                 Turn it off if skills get suggested for requests they don't fit."
+            }
+            Self::SkillSuggestionThreshold => {
+                "How similar (50-99%) a request must be to a skill's description before it is suggested. \
+                 Auto-activation needs 5 points more. Leave empty for 80%."
+            }
+            Self::GroupResponseMode => {
+                "What the bot does with group messages that don't summon it (see the triggers below). \
+                 Triggered ignores them, Passive keeps them in the session so the bot knows the conversation \
+                 when it is summoned, and Every Message answers everything."
+            }
+            Self::GroupTriggerMention => {
+                "Answer messages that @mention the bot."
+            }
+            Self::GroupTriggerReply => {
+                "Answer messages that reply to one of the bot's messages (Telegram and Discord)."
+            }
+            Self::GroupTriggerPattern => {
+                "Regular expression (case-insensitive) that summons the bot when a message matches, \
+                 e.g. \"^(hey )?stark\\b\". Leave empty to rely on mentions and replies."
+            }
+            Self::ResponseRedaction => {
+                "In group chats, replace wallet balances, email contents and details from other people's memories \
+                 with [redacted] unless the person asking owns them. Wallet and email belong to the admins \
+                 configured above. Direct messages are never redacted."
+            }
+            Self::ProactiveMessages => {
+                "Let the bot post here without being asked: cron job results, alerts, heartbeat check-ins \
+                 and notifications. Replies to messages and approval requests are always sent."
+            }
+            Self::ProactiveQuietHours => {
+                "Server-local time range (HH:MM-HH:MM) during which proactive messages are held back, \
+                 e.g. \"22:00-07:00\". Messages suppressed during quiet hours are dropped, not delayed."
+            }
+            Self::ProactiveDailyLimit => {
+                "Maximum number of proactive messages per day (server-local midnight to midnight). \
+                 Once the limit is reached, further proactive messages are dropped until the next day. \
+                 Set to 0 for unlimited."
+            }
             Self::DiscordBotToken => {
                 "Your Discord bot token from the Discord Developer Portal. \
                  Found under Bot > Token in your application settings."
@@ -168,6 +324,34 @@ impl ChannelSettingKey {
                  Find your ID at tweeterid.com. \
                  WARNING: This account will have full agent access — only set this to an account you control."
             }
+            Self::TwitterReadDms => {
+                "Also poll direct messages on the same interval and reply in the DM conversation. \
+                 Admin DMs get full tool access; all other DMs run in safe mode and count toward \
+                 the hourly reply limit. Requires DM read/write permission on the X app. \
+                 Messages already in the inbox when this is first enabled are skipped."
+            }
+            Self::TwitterReviewBeforeSend => {
+                "Hold every reply and twitter_post tweet in the outbox instead of posting it. \
+                 Drafts are posted only after approval from the Outbox API or dashboard, and \
+                 expire if nobody approves them in time."
+            }
+            Self::FarcasterBotFid => {
+                "The numeric fid of the bot's Farcaster account — the account your Neynar signer \
+                 casts as. Casts that mention or reply to it are answered with a reply cast. \
+                 Notifications already waiting when the channel first starts are skipped."
+            }
+            Self::FarcasterPollIntervalSecs => {
+                "How often to check for new mentions and replies in seconds. Minimum is 30 seconds."
+            }
+            Self::FarcasterMaxRepliesPerHour => {
+                "Maximum number of non-admin casts to reply to per hour. Once the limit is reached, \
+                 remaining casts are skipped until the next hour. Set to 0 for unlimited."
+            }
+            Self::FarcasterAdminFid => {
+                "Numeric fid of an admin account. Casts from this account get full tool access; \
+                 everyone else is answered in safe mode. If not set, all casts use safe mode. \
+                 WARNING: This account will have full agent access — only set this to an account you control."
+            }
             Self::TelegramAdminUserId => {
                 "Telegram numeric user ID of the admin. Messages from this user get full agent access; \
                  all other users are restricted to safe mode. If not set, all users get full access \
@@ -197,6 +381,25 @@ impl ChannelSettingKey {
     pub fn input_type(&self) -> SettingInputType {
         match self {
             Self::AutoStartOnBoot => SettingInputType::Toggle,
+            Self::PaperTrading => SettingInputType::Toggle,
+            Self::AgentSubtype => SettingInputType::Text,
+            Self::IssueProject => SettingInputType::Text,
+            Self::AutoCompaction => SettingInputType::Toggle,
+            Self::CompactionThreshold => SettingInputType::Number,
+            Self::ToolConfirmation => SettingInputType::Select,
+            Self::DisabledCommands => SettingInputType::Text,
+            Self::ResponseStyle => SettingInputType::Select,
+            Self::ToolErrorRecovery => SettingInputType::Select,
+            Self::SkillSuggestions => SettingInputType::Select,
+            Self::SkillSuggestionThreshold => SettingInputType::Number,
+            Self::GroupResponseMode => SettingInputType::Select,
+            Self::GroupTriggerMention => SettingInputType::Toggle,
+            Self::GroupTriggerReply => SettingInputType::Toggle,
+            Self::GroupTriggerPattern => SettingInputType::Text,
+            Self::ResponseRedaction => SettingInputType::Toggle,
+            Self::ProactiveMessages => SettingInputType::Toggle,
+            Self::ProactiveQuietHours => SettingInputType::Text,
+            Self::ProactiveDailyLimit => SettingInputType::Number,
             Self::DiscordBotToken => SettingInputType::Text,
             Self::DiscordAdminUserIds => SettingInputType::Text,
             Self::TelegramBotToken => SettingInputType::Text,
@@ -208,6 +411,12 @@ impl ChannelSettingKey {
             Self::TwitterReplyChance => SettingInputType::Select,
             Self::TwitterMaxMentionsPerHour => SettingInputType::Number,
             Self::TwitterAdminXAccount => SettingInputType::Text,
+            Self::TwitterReadDms => SettingInputType::Toggle,
+            Self::TwitterReviewBeforeSend => SettingInputType::Toggle,
+            Self::FarcasterBotFid => SettingInputType::Text,
+            Self::FarcasterPollIntervalSecs => SettingInputType::Number,
+            Self::FarcasterMaxRepliesPerHour => SettingInputType::Number,
+            Self::FarcasterAdminFid => SettingInputType::Text,
             Self::TelegramAdminUserId => SettingInputType::Text,
             Self::SlackAdminUserIds => SettingInputType::Text,
             Self::ExternalChannelApiToken => SettingInputType::Text,
@@ -219,6 +428,25 @@ impl ChannelSettingKey {
     pub fn placeholder(&self) -> &'static str {
         match self {
             Self::AutoStartOnBoot => "",
+            Self::PaperTrading => "",
+            Self::AgentSubtype => "finance",
+            Self::IssueProject => "linear:ENG",
+            Self::AutoCompaction => "",
+            Self::CompactionThreshold => "80",
+            Self::ToolConfirmation => "",
+            Self::DisabledCommands => "memory, mode",
+            Self::ResponseStyle => "",
+            Self::ToolErrorRecovery => "",
+            Self::SkillSuggestions => "",
+            Self::SkillSuggestionThreshold => "80",
+            Self::GroupResponseMode => "",
+            Self::GroupTriggerMention => "",
+            Self::GroupTriggerReply => "",
+            Self::GroupTriggerPattern => "^(hey )?stark\\b",
+            Self::ResponseRedaction => "",
+            Self::ProactiveMessages => "",
+            Self::ProactiveQuietHours => "22:00-07:00",
+            Self::ProactiveDailyLimit => "0",
             Self::DiscordBotToken => "MTIz...abc",
             Self::DiscordAdminUserIds => "123456789012345678, 987654321098765432",
             Self::TelegramBotToken => "123456:ABC-DEF...",
@@ -230,6 +458,12 @@ impl ChannelSettingKey {
             Self::TwitterReplyChance => "",
             Self::TwitterMaxMentionsPerHour => "0",
             Self::TwitterAdminXAccount => "1234567890123456789",
+            Self::TwitterReadDms => "",
+            Self::TwitterReviewBeforeSend => "",
+            Self::FarcasterBotFid => "123456",
+            Self::FarcasterPollIntervalSecs => "60",
+            Self::FarcasterMaxRepliesPerHour => "0",
+            Self::FarcasterAdminFid => "123456",
             Self::TelegramAdminUserId => "123456789",
             Self::SlackAdminUserIds => "U12345678,U87654321",
             Self::ExternalChannelApiToken => "Click dice to generate a secure token",
@@ -240,6 +474,32 @@ impl ChannelSettingKey {
     /// Get the available options for select inputs
     pub fn options(&self) -> Option<Vec<(&'static str, &'static str)>> {
         match self {
+            Self::ToolConfirmation => Some(vec![
+                ("", "Off"),
+                ("read_only", "Tools with side effects"),
+                ("safe_mode", "All tools not cleared for safe mode"),
+            ]),
+            Self::ResponseStyle => Some(vec![
+                ("", "Default"),
+                ("concise", "Concise"),
+                ("verbose", "Verbose"),
+                ("technical", "Technical"),
+            ]),
+            Self::ToolErrorRecovery => Some(vec![
+                ("", "Retry, repair and skip"),
+                ("retry", "Retry transient errors only"),
+                ("off", "Off"),
+            ]),
+            Self::SkillSuggestions => Some(vec![
+                ("", "Suggest"),
+                ("auto", "Auto-activate close matches"),
+                ("off", "Off"),
+            ]),
+            Self::GroupResponseMode => Some(vec![
+                ("", "Triggered only"),
+                ("passive", "Passive (listen, answer when summoned)"),
+                ("all", "Every message"),
+            ]),
             Self::TwitterReplyChance => Some(vec![
                 ("100", "100% (reply to all)"),
                 ("50", "50%"),
@@ -256,6 +516,25 @@ impl ChannelSettingKey {
     pub fn default_value(&self) -> &'static str {
         match self {
             Self::AutoStartOnBoot => "false",
+            Self::PaperTrading => "false",
+            Self::AgentSubtype => "",
+            Self::IssueProject => "",
+            Self::AutoCompaction => "true",
+            Self::CompactionThreshold => "",
+            Self::ToolConfirmation => "",
+            Self::DisabledCommands => "",
+            Self::ResponseStyle => "",
+            Self::ToolErrorRecovery => "",
+            Self::SkillSuggestions => "",
+            Self::SkillSuggestionThreshold => "",
+            Self::GroupResponseMode => "",
+            Self::GroupTriggerMention => "true",
+            Self::GroupTriggerReply => "true",
+            Self::GroupTriggerPattern => "",
+            Self::ResponseRedaction => "true",
+            Self::ProactiveMessages => "true",
+            Self::ProactiveQuietHours => "",
+            Self::ProactiveDailyLimit => "0",
             Self::DiscordBotToken => "",
             Self::DiscordAdminUserIds => "",
             Self::TelegramBotToken => "",
@@ -267,6 +546,12 @@ impl ChannelSettingKey {
             Self::TwitterReplyChance => "100",
             Self::TwitterMaxMentionsPerHour => "0",
             Self::TwitterAdminXAccount => "",
+            Self::TwitterReadDms => "false",
+            Self::TwitterReviewBeforeSend => "false",
+            Self::FarcasterBotFid => "",
+            Self::FarcasterPollIntervalSecs => "60",
+            Self::FarcasterMaxRepliesPerHour => "0",
+            Self::FarcasterAdminFid => "",
             Self::TelegramAdminUserId => "",
             Self::SlackAdminUserIds => "",
             Self::ExternalChannelApiToken => "",
@@ -276,7 +561,21 @@ impl ChannelSettingKey {
 
     /// Check if this setting applies to all channel types (common setting)
     pub fn is_common(&self) -> bool {
-        matches!(self, Self::AutoStartOnBoot)
+        matches!(
+            self,
+            Self::AutoStartOnBoot
+                | Self::PaperTrading
+                | Self::AgentSubtype
+                | Self::IssueProject
+                | Self::AutoCompaction
+                | Self::CompactionThreshold
+                | Self::ToolConfirmation
+                | Self::DisabledCommands
+                | Self::ResponseStyle
+                | Self::ToolErrorRecovery
+                | Self::SkillSuggestions
+                | Self::SkillSuggestionThreshold
+        )
     }
 }
 
@@ -377,6 +676,37 @@ pub struct SettingUpdate {
 fn get_common_settings() -> Vec<ChannelSettingDefinition> {
     vec![
         ChannelSettingKey::AutoStartOnBoot.into(),
+        ChannelSettingKey::PaperTrading.into(),
+        ChannelSettingKey::AgentSubtype.into(),
+        ChannelSettingKey::IssueProject.into(),
+        ChannelSettingKey::AutoCompaction.into(),
+        ChannelSettingKey::CompactionThreshold.into(),
+        ChannelSettingKey::ToolConfirmation.into(),
+        ChannelSettingKey::DisabledCommands.into(),
+        ChannelSettingKey::ResponseStyle.into(),
+        ChannelSettingKey::ToolErrorRecovery.into(),
+        ChannelSettingKey::SkillSuggestions.into(),
+        ChannelSettingKey::SkillSuggestionThreshold.into(),
+    ]
+}
+
+/// Get the group-chat settings: triggers and redaction (channels that join group chats)
+fn get_group_chat_settings() -> Vec<ChannelSettingDefinition> {
+    vec![
+        ChannelSettingKey::GroupResponseMode.into(),
+        ChannelSettingKey::GroupTriggerMention.into(),
+        ChannelSettingKey::GroupTriggerReply.into(),
+        ChannelSettingKey::GroupTriggerPattern.into(),
+        ChannelSettingKey::ResponseRedaction.into(),
+    ]
+}
+
+/// Get the proactive messaging limits (channels the bot can post to unprompted)
+fn get_proactive_settings() -> Vec<ChannelSettingDefinition> {
+    vec![
+        ChannelSettingKey::ProactiveMessages.into(),
+        ChannelSettingKey::ProactiveQuietHours.into(),
+        ChannelSettingKey::ProactiveDailyLimit.into(),
     ]
 }
 
@@ -405,6 +735,14 @@ pub fn get_settings_for_channel_type(channel_type: ChannelType) -> Vec<ChannelSe
             ChannelSettingKey::TwitterReplyChance.into(),
             ChannelSettingKey::TwitterMaxMentionsPerHour.into(),
             ChannelSettingKey::TwitterAdminXAccount.into(),
+            ChannelSettingKey::TwitterReadDms.into(),
+            ChannelSettingKey::TwitterReviewBeforeSend.into(),
+        ],
+        ChannelType::Farcaster => vec![
+            ChannelSettingKey::FarcasterBotFid.into(),
+            ChannelSettingKey::FarcasterPollIntervalSecs.into(),
+            ChannelSettingKey::FarcasterMaxRepliesPerHour.into(),
+            ChannelSettingKey::FarcasterAdminFid.into(),
         ],
         ChannelType::ExternalChannel => vec![
             ChannelSettingKey::ExternalChannelApiToken.into(),
@@ -413,6 +751,10 @@ pub fn get_settings_for_channel_type(channel_type: ChannelType) -> Vec<ChannelSe
     };
 
     settings.extend(type_specific);
+    if matches!(channel_type, ChannelType::Discord | ChannelType::Telegram | ChannelType::Slack) {
+        settings.extend(get_group_chat_settings());
+        settings.extend(get_proactive_settings());
+    }
     settings
 }
 
@@ -429,32 +771,79 @@ mod tests {
     #[test]
     fn test_discord_settings() {
         let settings = get_settings_for_channel_type(ChannelType::Discord);
-        // 1 common + 2 Discord-specific (bot_token, admin_user_ids)
-        assert_eq!(settings.len(), 3);
+        // 12 common + 2 Discord-specific (bot_token, admin_user_ids) + 5 group chat + 3 proactive
+        assert_eq!(settings.len(), 22);
         assert_eq!(settings[0].key, "auto_start_on_boot");
-        assert_eq!(settings[1].key, "discord_bot_token");
-        assert_eq!(settings[2].key, "discord_admin_user_ids");
+        assert_eq!(settings[1].key, "paper_trading");
+        assert_eq!(settings[2].key, "agent_subtype");
+        assert_eq!(settings[3].key, "issue_project");
+        assert_eq!(settings[4].key, "auto_compaction");
+        assert_eq!(settings[5].key, "compaction_threshold");
+        assert_eq!(settings[6].key, "tool_confirmation");
+        assert_eq!(settings[7].key, "disabled_commands");
+        assert_eq!(settings[8].key, "response_style");
+        assert_eq!(settings[9].key, "tool_error_recovery");
+        assert_eq!(settings[10].key, "skill_suggestions");
+        assert_eq!(settings[11].key, "skill_suggestion_threshold");
+        assert_eq!(settings[12].key, "discord_bot_token");
+        assert_eq!(settings[13].key, "discord_admin_user_ids");
+        assert_eq!(settings[14].key, "group_response_mode");
+        assert_eq!(settings[17].key, "group_trigger_pattern");
+        assert_eq!(settings[18].key, "response_redaction");
+        assert_eq!(settings[19].key, "proactive_messages");
+        assert_eq!(settings[21].key, "proactive_daily_limit");
     }
 
     #[test]
     fn test_telegram_settings() {
         let settings = get_settings_for_channel_type(ChannelType::Telegram);
-        // 1 common + 2 Telegram-specific (bot_token, admin_user_id)
-        assert_eq!(settings.len(), 3);
+        // 12 common + 2 Telegram-specific (bot_token, admin_user_id) + 5 group chat + 3 proactive
+        assert_eq!(settings.len(), 22);
         assert_eq!(settings[0].key, "auto_start_on_boot");
-        assert_eq!(settings[1].key, "telegram_bot_token");
-        assert_eq!(settings[2].key, "telegram_admin_user_id");
+        assert_eq!(settings[1].key, "paper_trading");
+        assert_eq!(settings[2].key, "agent_subtype");
+        assert_eq!(settings[3].key, "issue_project");
+        assert_eq!(settings[4].key, "auto_compaction");
+        assert_eq!(settings[5].key, "compaction_threshold");
+        assert_eq!(settings[6].key, "tool_confirmation");
+        assert_eq!(settings[7].key, "disabled_commands");
+        assert_eq!(settings[8].key, "response_style");
+        assert_eq!(settings[9].key, "tool_error_recovery");
+        assert_eq!(settings[10].key, "skill_suggestions");
+        assert_eq!(settings[11].key, "skill_suggestion_threshold");
+        assert_eq!(settings[12].key, "telegram_bot_token");
+        assert_eq!(settings[13].key, "telegram_admin_user_id");
     }
 
     #[test]
     fn test_slack_settings() {
         let settings = get_settings_for_channel_type(ChannelType::Slack);
-        // 1 common + 3 Slack-specific (bot_token, app_token, admin_user_ids)
-        assert_eq!(settings.len(), 4);
+        // 12 common + 3 Slack-specific (bot_token, app_token, admin_user_ids) + 5 group chat + 3 proactive
+        assert_eq!(settings.len(), 23);
         assert_eq!(settings[0].key, "auto_start_on_boot");
-        assert_eq!(settings[1].key, "slack_bot_token");
-        assert_eq!(settings[2].key, "slack_app_token");
-        assert_eq!(settings[3].key, "slack_admin_user_ids");
+        assert_eq!(settings[1].key, "paper_trading");
+        assert_eq!(settings[2].key, "agent_subtype");
+        assert_eq!(settings[3].key, "issue_project");
+        assert_eq!(settings[4].key, "auto_compaction");
+        assert_eq!(settings[5].key, "compaction_threshold");
+        assert_eq!(settings[6].key, "tool_confirmation");
+        assert_eq!(settings[7].key, "disabled_commands");
+        assert_eq!(settings[8].key, "response_style");
+        assert_eq!(settings[9].key, "tool_error_recovery");
+        assert_eq!(settings[10].key, "skill_suggestions");
+        assert_eq!(settings[11].key, "skill_suggestion_threshold");
+        assert_eq!(settings[12].key, "slack_bot_token");
+        assert_eq!(settings[13].key, "slack_app_token");
+        assert_eq!(settings[14].key, "slack_admin_user_ids");
+    }
+
+    #[test]
+    fn test_farcaster_settings() {
+        let settings = get_settings_for_channel_type(ChannelType::Farcaster);
+        // 12 common + 4 Farcaster-specific
+        assert_eq!(settings.len(), 16);
+        assert_eq!(settings[12].key, "farcaster_bot_fid");
+        assert_eq!(settings[15].key, "farcaster_admin_fid");
     }
 
     #[test]
@@ -165,6 +165,9 @@ pub struct ChatSession {
     /// Special role name if this safe-mode session has enriched permissions
     #[serde(default)]
     pub special_role_name: Option<String>,
+    /// Response style preset (concise/verbose/technical); None = channel default
+    #[serde(default)]
+    pub response_style: Option<String>,
 }
 
 /// Request to get or create a chat session
@@ -219,6 +222,8 @@ pub struct ChatSessionResponse {
     // Special role name if this safe-mode session has enriched permissions
     #[serde(skip_serializing_if = "Option::is_none")]
     pub special_role_name: Option<String>,
+    // Response style preset set on this session
+    pub response_style: Option<String>,
 }
 
 impl From<ChatSession> for ChatSessionResponse {
@@ -246,6 +251,7 @@ impl From<ChatSession> for ChatSessionResponse {
             initial_query: None,
             safe_mode: if session.safe_mode { Some(true) } else { None },
             special_role_name: session.special_role_name,
+            response_style: session.response_style,
         }
     }
 }
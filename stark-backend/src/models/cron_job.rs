@@ -248,10 +248,31 @@ pub struct HeartbeatConfig {
     pub current_impulse_node_id: Option<i64>,
     /// Last heartbeat session ID (for context continuity)
     pub last_session_id: Option<i64>,
+    /// Self-maintenance jobs run on their own schedules (global config only)
+    #[serde(default)]
+    pub maintenance_jobs: Vec<MaintenanceJobConfig>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// A self-maintenance job in the heartbeat config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceJobConfig {
+    /// "vacuum_db", "prune_expired_files", "reembed_skills" or "verify_module_checksums"
+    pub job: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Minutes between runs
+    pub interval_minutes: i64,
+    /// prune_expired_files: age (in days) after which a public file is removed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<i64>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
 /// Request to update heartbeat configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateHeartbeatConfigRequest {
@@ -267,6 +288,9 @@ pub struct UpdateHeartbeatConfigRequest {
     pub active_days: Option<String>,
     #[serde(default)]
     pub enabled: Option<bool>,
+    /// Replaces the maintenance job list when present
+    #[serde(default)]
+    pub maintenance_jobs: Option<Vec<MaintenanceJobConfig>>,
 }
 
 /// Response for heartbeat config operations
@@ -12,7 +12,7 @@ pub mod session_message;
 pub mod special_role;
 
 pub use agent_settings::{AgentSettings, AgentSettingsResponse, UpdateAgentSettingsRequest, MIN_CONTEXT_TOKENS, DEFAULT_CONTEXT_TOKENS};
-pub use bot_settings::{BotSettings, UpdateBotSettingsRequest, DEFAULT_MAX_TOOL_ITERATIONS, DEFAULT_SAFE_MODE_MAX_QUERIES_PER_10MIN, DEFAULT_WHISPER_SERVER_URL, DEFAULT_EMBEDDINGS_SERVER_URL};
+pub use bot_settings::{BotSettings, UpdateBotSettingsRequest, DEFAULT_MAX_TOOL_ITERATIONS, DEFAULT_SAFE_MODE_MAX_QUERIES_PER_10MIN, DEFAULT_WHISPER_SERVER_URL, DEFAULT_EMBEDDINGS_SERVER_URL, DEFAULT_TX_APPROVAL_TTL_SECS};
 pub use api_key::{ApiKey, ApiKeyResponse};
 pub use channel::{Channel, ChannelResponse, ChannelType, CreateChannelRequest, CreateSafeModeChannelRequest, UpdateChannelRequest};
 pub use channel_settings::{
@@ -28,11 +28,14 @@ pub use identity::{
     GetOrCreateIdentityRequest, IdentityLink, IdentityResponse, LinkIdentityRequest,
     LinkedAccountInfo,
 };
-pub use session::Session;
-pub use session_message::{AddMessageRequest, MessageRole, SessionMessage, SessionTranscriptResponse};
+pub use session::{AuthDevice, Session};
+pub use session_message::{
+    AddMessageRequest, MessageAttachment, MessageRole, SessionMessage, SessionTranscriptResponse,
+    ToolCallRecord,
+};
 pub use cron_job::{
     CreateCronJobRequest, CronJob, CronJobResponse, CronJobRun, HeartbeatConfig,
-    HeartbeatConfigResponse, JobStatus, ScheduleType, SessionMode, UpdateCronJobRequest,
+    HeartbeatConfigResponse, JobStatus, MaintenanceJobConfig, ScheduleType, SessionMode, UpdateCronJobRequest,
     UpdateHeartbeatConfigRequest,
 };
 pub use execution::{ExecutionTask, TaskMetrics, TaskStatus, TaskType};
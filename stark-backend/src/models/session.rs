@@ -8,3 +8,17 @@ pub struct Session {
     pub created_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
 }
+
+/// An auth session as shown in the device list (never the tokens)
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthDevice {
+    pub id: i64,
+    pub public_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: String,
+    pub last_active_at: Option<String>,
+    pub expires_at: String,
+    pub refresh_expires_at: Option<String>,
+    pub second_factor_at: Option<String>,
+}
@@ -35,6 +35,118 @@ impl MessageRole {
     }
 }
 
+/// File or media attached to a message. Only the reference is stored; the
+/// bytes stay with the platform (or the storage the reference points to).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageAttachment {
+    /// "image", "audio", "video" or "file"
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Download URL, when the platform provides one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Platform storage reference (e.g. a Telegram file_id or a workspace path)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<i64>,
+}
+
+impl MessageAttachment {
+    /// Attachment type for a MIME type ("image/png" -> "image")
+    pub fn kind_for_mime(mime_type: Option<&str>) -> &'static str {
+        match mime_type.and_then(|m| m.split('/').next()) {
+            Some("image") => "image",
+            Some("audio") => "audio",
+            Some("video") => "video",
+            _ => "file",
+        }
+    }
+
+    /// One-line reference for the model, e.g. `image "chart.png" (image/png, 48 KB): https://...`
+    pub fn reference(&self) -> String {
+        let mut line = self.kind.clone();
+        if let Some(ref name) = self.file_name {
+            line.push_str(&format!(" \"{}\"", name));
+        }
+        let mut details = Vec::new();
+        if let Some(ref mime) = self.mime_type {
+            details.push(mime.clone());
+        }
+        if let Some(size) = self.size_bytes {
+            details.push(format_size(size));
+        }
+        if !details.is_empty() {
+            line.push_str(&format!(" ({})", details.join(", ")));
+        }
+        if let Some(location) = self.url.as_ref().or(self.storage_ref.as_ref()) {
+            line.push_str(&format!(": {}", location));
+        }
+        line
+    }
+}
+
+fn format_size(bytes: i64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{} KB", bytes / 1024)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Attachment block appended to message content in the AI context
+pub fn format_attachment_references(attachments: &[MessageAttachment]) -> Option<String> {
+    if attachments.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = attachments.iter().map(|a| format!("- {}", a.reference())).collect();
+    Some(format!("[Attachments]\n{}", lines.join("\n")))
+}
+
+/// Structured side of a ToolCall / ToolResult message. The call and its result
+/// share `call_id`, so a call without a result is still running.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    pub call_id: String,
+    pub tool_name: String,
+    /// Arguments the tool was called with (ToolCall rows)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<serde_json::Value>,
+    /// Whether the tool succeeded (ToolResult rows)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+}
+
+impl ToolCallRecord {
+    pub fn call(call_id: &str, tool_name: &str, arguments: &serde_json::Value) -> Self {
+        Self {
+            call_id: call_id.to_string(),
+            tool_name: tool_name.to_string(),
+            arguments: Some(arguments.clone()),
+            success: None,
+            duration_ms: None,
+        }
+    }
+
+    pub fn result(call_id: &str, tool_name: &str, success: bool, duration_ms: i64) -> Self {
+        Self {
+            call_id: call_id.to_string(),
+            tool_name: tool_name.to_string(),
+            arguments: None,
+            success: Some(success),
+            duration_ms: Some(duration_ms),
+        }
+    }
+}
+
 /// Session message - individual message in a conversation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionMessage {
@@ -47,6 +159,21 @@ pub struct SessionMessage {
     pub platform_message_id: Option<String>,
     pub tokens_used: Option<i32>,
     pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<MessageAttachment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call: Option<ToolCallRecord>,
+}
+
+impl SessionMessage {
+    /// Content with attachment references appended, as the model should see it
+    pub fn content_with_attachments(&self) -> String {
+        match format_attachment_references(&self.attachments) {
+            Some(refs) if self.content.is_empty() => refs,
+            Some(refs) => format!("{}\n\n{}", self.content, refs),
+            None => self.content.clone(),
+        }
+    }
 }
 
 /// Request to add a message to a session
@@ -67,3 +194,77 @@ pub struct SessionTranscriptResponse {
     pub messages: Vec<SessionMessage>,
     pub total_count: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use crate::models::SessionScope;
+
+    fn chart() -> MessageAttachment {
+        MessageAttachment {
+            kind: MessageAttachment::kind_for_mime(Some("image/png")).to_string(),
+            url: Some("https://cdn.example.com/chart.png".to_string()),
+            storage_ref: None,
+            file_name: Some("chart.png".to_string()),
+            mime_type: Some("image/png".to_string()),
+            size_bytes: Some(48 * 1024),
+        }
+    }
+
+    #[test]
+    fn test_attachment_reference() {
+        assert_eq!(
+            chart().reference(),
+            "image \"chart.png\" (image/png, 48 KB): https://cdn.example.com/chart.png"
+        );
+        assert_eq!(MessageAttachment::kind_for_mime(Some("application/pdf")), "file");
+        assert!(format_attachment_references(&[]).is_none());
+    }
+
+    #[test]
+    fn test_attachments_persist_with_message() {
+        let db = Database::new(":memory:").unwrap();
+        let session = db.get_or_create_chat_session("web", 0, "u1", SessionScope::Dm, None).unwrap();
+        db.add_session_message_with_attachments(
+            session.id, MessageRole::User, "what is this?", None, None, None, None, &[chart()],
+        )
+        .unwrap();
+        db.add_session_message(session.id, MessageRole::Assistant, "A price chart.", None, None, None, None)
+            .unwrap();
+
+        let messages = db.get_session_messages(session.id).unwrap();
+        assert_eq!(messages[0].attachments, vec![chart()]);
+        assert!(messages[1].attachments.is_empty());
+        assert!(messages[0].content_with_attachments().ends_with("[Attachments]\n- image \"chart.png\" (image/png, 48 KB): https://cdn.example.com/chart.png"));
+    }
+
+    #[test]
+    fn test_tool_progress_persists_as_records() {
+        let db = Database::new(":memory:").unwrap();
+        let session = db.get_or_create_chat_session("web", 0, "u1", SessionScope::Dm, None).unwrap();
+        db.add_session_message(session.id, MessageRole::User, "price of ETH?", None, None, None, None)
+            .unwrap();
+        let args = serde_json::json!({ "symbol": "ETH" });
+        db.add_tool_session_message(
+            session.id, MessageRole::ToolCall, "token_lookup", Some("token_lookup"),
+            &ToolCallRecord::call("c1", "token_lookup", &args),
+        )
+        .unwrap();
+
+        let running = db.get_running_tool_call(session.id).unwrap().unwrap();
+        assert_eq!(running.tool_call.unwrap().arguments, Some(args));
+
+        db.add_tool_session_message(
+            session.id, MessageRole::ToolResult, "$3000", Some("token_lookup"),
+            &ToolCallRecord::result("c1", "token_lookup", true, 42),
+        )
+        .unwrap();
+        assert!(db.get_running_tool_call(session.id).unwrap().is_none());
+
+        let turn = db.get_current_turn_tool_messages(session.id).unwrap();
+        let ids: Vec<_> = turn.iter().map(|m| m.tool_call.as_ref().unwrap().call_id.as_str()).collect();
+        assert_eq!(ids, vec!["c1", "c1"]);
+        assert_eq!(turn[1].tool_call.as_ref().unwrap().success, Some(true));
+    }
+}
@@ -226,6 +226,19 @@ impl super::Module for DynamicModule {
         self.manifest.service_url()
     }
 
+    fn health_url(&self) -> String {
+        // Prefer the port assigned at startup over the manifest default
+        let base = match super::port_registry::resolve(&self.manifest.module.name) {
+            Some(port) => format!("http://127.0.0.1:{}", port),
+            None => self.manifest.service_url(),
+        };
+        format!("{}{}", base, self.manifest.service.health_endpoint)
+    }
+
+    fn can_start_service(&self) -> bool {
+        self.manifest.service.command.is_some() || self.binary_path().exists()
+    }
+
     fn has_tools(&self) -> bool {
         !self.manifest.tools.is_empty()
     }
@@ -263,6 +276,10 @@ impl super::Module for DynamicModule {
         self.manifest.service.env_vars.keys().cloned().collect()
     }
 
+    fn manifest_fs_allow(&self) -> Vec<String> {
+        self.manifest.service.fs_allow.clone()
+    }
+
     fn module_dir(&self) -> Option<&PathBuf> {
         Some(&self.module_dir)
     }
@@ -0,0 +1,153 @@
+//! Module lifecycle hooks — install/uninstall/upgrade commands from the
+//! manifest's `[hooks]` section.
+//!
+//! Hooks run with `sh -c` from the module directory through the same
+//! checksum/sandbox launch path as the module's service, with a timeout.
+//! Output is captured into the module's service log buffer.
+
+use std::path::Path;
+
+use super::manifest::{LifecycleHooks, ModuleManifest};
+use super::sandbox::{prepare_launch, LaunchTarget};
+use crate::db::Database;
+
+/// Which lifecycle hook to run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LifecycleEvent {
+    Install,
+    Uninstall,
+    Upgrade,
+}
+
+impl LifecycleEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LifecycleEvent::Install => "install",
+            LifecycleEvent::Uninstall => "uninstall",
+            LifecycleEvent::Upgrade => "upgrade",
+        }
+    }
+
+    fn command<'a>(&self, hooks: &'a LifecycleHooks) -> Option<&'a str> {
+        match self {
+            LifecycleEvent::Install => hooks.install.as_deref(),
+            LifecycleEvent::Uninstall => hooks.uninstall.as_deref(),
+            LifecycleEvent::Upgrade => hooks.upgrade.as_deref(),
+        }
+    }
+}
+
+/// Run a module's hook for `event`, if its manifest declares one.
+/// Returns Ok(false) when there is no hook to run.
+pub async fn run_hook(
+    db: &Database,
+    module_dir: &Path,
+    event: LifecycleEvent,
+    previous_version: Option<&str>,
+) -> Result<bool, String> {
+    let manifest = ModuleManifest::from_file(&module_dir.join("module.toml"))?;
+    let Some(command) = event.command(&manifest.hooks) else {
+        return Ok(false);
+    };
+    let name = &manifest.module.name;
+    log::info!("[MODULE] Running {} hook for {}: `{}`", event.as_str(), name, command);
+
+    let mut cmd = prepare_launch(
+        db,
+        name,
+        LaunchTarget::Command(command),
+        module_dir,
+        &manifest.service.fs_allow,
+    )?;
+    cmd.env("MODULE_LIFECYCLE_EVENT", event.as_str());
+    cmd.env("MODULE_VERSION", &manifest.module.version);
+    if let Some(prev) = previous_version {
+        cmd.env("MODULE_PREVIOUS_VERSION", prev);
+    }
+    if let Ok(url) = std::env::var("STARKBOT_SELF_URL") {
+        cmd.env("STARKBOT_SELF_URL", url);
+    }
+
+    let mut cmd = tokio::process::Command::from(cmd);
+    cmd.kill_on_drop(true);
+    let timeout = std::time::Duration::from_secs(manifest.hooks.timeout_secs.max(1));
+    let output = match tokio::time::timeout(timeout, cmd.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(format!("{} hook failed to start: {}", event.as_str(), e)),
+        Err(_) => {
+            return Err(format!("{} hook timed out after {}s", event.as_str(), timeout.as_secs()));
+        }
+    };
+
+    let buf = super::service_logs::get_or_create(name);
+    for line in String::from_utf8_lossy(&output.stdout).lines().chain(String::from_utf8_lossy(&output.stderr).lines()) {
+        super::service_logs::push_line(&buf, format!("[{} hook] {}", event.as_str(), line));
+    }
+
+    if output.status.success() {
+        Ok(true)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let last = stderr.lines().last().unwrap_or_default();
+        Err(format!("{} hook exited with {}: {}", event.as_str(), output.status, last))
+    }
+}
+
+/// Run the install hook after a module is registered. On failure the install
+/// is rolled back: the DB row is removed, and so are the files when they were
+/// downloaded for this install (`remove_files`).
+pub async fn run_install_hook(
+    db: &Database,
+    module_name: &str,
+    module_dir: &Path,
+    remove_files: bool,
+) -> Result<(), String> {
+    match run_hook(db, module_dir, LifecycleEvent::Install, None).await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            log::error!("[MODULE] Install hook for {} failed — rolling back: {}", module_name, e);
+            let _ = db.uninstall_module(module_name);
+            if remove_files {
+                let _ = std::fs::remove_dir_all(module_dir);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Run the uninstall hook before a module is removed. Failures are logged only.
+pub async fn run_uninstall_hook(db: &Database, module_name: &str, module_dir: Option<&Path>) {
+    let Some(dir) = module_dir else {
+        return;
+    };
+    if let Err(e) = run_hook(db, dir, LifecycleEvent::Uninstall, None).await {
+        log::warn!("[MODULE] Uninstall hook for {} failed (continuing): {}", module_name, e);
+    }
+}
+
+/// Run upgrade hooks for installed modules whose on-disk version differs
+/// from the recorded one, then record the new version. A module whose hook
+/// fails is disabled so its service isn't started against unmigrated state.
+pub async fn run_pending_upgrades(db: &Database) {
+    let installed = db.list_installed_modules().unwrap_or_default();
+    for module in super::loader::load_dynamic_modules() {
+        use super::Module;
+        let name = module.name();
+        let Some(entry) = installed.iter().find(|m| m.module_name == name) else {
+            continue;
+        };
+        if entry.version == module.version() {
+            continue;
+        }
+        log::info!("[MODULE] {} changed on disk: v{} -> v{}", name, entry.version, module.version());
+        match run_hook(db, module.module_dir(), LifecycleEvent::Upgrade, Some(&entry.version)).await {
+            Ok(_) => {
+                let _ = db.set_module_version(name, module.version());
+            }
+            Err(e) => {
+                log::error!("[MODULE] Upgrade hook for {} failed — disabling the module: {}", name, e);
+                let _ = db.set_module_enabled(name, false);
+            }
+        }
+    }
+}
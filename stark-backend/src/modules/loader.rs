@@ -62,7 +62,7 @@ pub fn load_dynamic_modules() -> Vec<DynamicModule> {
                 let name = manifest.module.name.clone();
                 let version = manifest.module.version.clone();
                 modules.push(DynamicModule::new(manifest, path));
-                log::info!(
+                log::debug!(
                     "[MODULE] Loaded dynamic module: {} v{} from {}",
                     name,
                     version,
@@ -95,6 +95,7 @@ pub fn get_dynamic_service_binaries() -> Vec<DynamicServiceInfo> {
             let port_env = m.manifest_port_env_var();
             let url_env = m.manifest_url_env_var();
             let command = m.manifest_command();
+            let fs_allow = m.manifest_fs_allow();
             let module_dir = m.module_dir().clone();
             DynamicServiceInfo {
                 name,
@@ -104,6 +105,7 @@ pub fn get_dynamic_service_binaries() -> Vec<DynamicServiceInfo> {
                 url_env_var: url_env,
                 command,
                 module_dir,
+                fs_allow,
             }
         })
         .collect()
@@ -121,4 +123,6 @@ pub struct DynamicServiceInfo {
     pub command: Option<String>,
     /// Directory containing the module — used as working directory for command.
     pub module_dir: PathBuf,
+    /// Extra writable paths for the sandbox (manifest `[service] fs_allow`)
+    pub fs_allow: Vec<String>,
 }
@@ -25,6 +25,9 @@ pub struct ModuleManifest {
     /// External HTTP endpoints exposed publicly via `/ext/{module}/{method}`.
     #[serde(default)]
     pub ext_endpoints: Vec<ExtEndpointManifest>,
+    /// Lifecycle hook commands (install/uninstall/upgrade).
+    #[serde(default)]
+    pub hooks: LifecycleHooks,
 }
 
 /// Basic module metadata.
@@ -72,6 +75,10 @@ pub struct ServiceConfig {
     /// Extra environment variables the service needs.
     #[serde(default)]
     pub env_vars: HashMap<String, EnvVarSpec>,
+    /// Paths the sandboxed service may write outside its module directory
+    /// (relative paths are inside the module directory, `~/` is the home dir).
+    #[serde(default)]
+    pub fs_allow: Vec<String>,
 }
 
 fn default_health_endpoint() -> String {
@@ -108,6 +115,35 @@ pub struct AgentConfig {
     pub dir: String,
 }
 
+/// Lifecycle hooks — shell commands run (sandboxed) from the module directory.
+/// Each receives `MODULE_LIFECYCLE_EVENT`, `MODULE_VERSION` and, for
+/// upgrades, `MODULE_PREVIOUS_VERSION`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LifecycleHooks {
+    /// After the module's files are in place; failure aborts the install.
+    #[serde(default)]
+    pub install: Option<String>,
+    /// Before the module is removed; failure is logged and the uninstall proceeds.
+    #[serde(default)]
+    pub uninstall: Option<String>,
+    /// When the on-disk version differs from the installed one, before the service starts.
+    #[serde(default)]
+    pub upgrade: Option<String>,
+    /// Per-hook timeout (default 120s).
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_hook_timeout_secs() -> u64 {
+    120
+}
+
+impl Default for LifecycleHooks {
+    fn default() -> Self {
+        Self { install: None, uninstall: None, upgrade: None, timeout_secs: default_hook_timeout_secs() }
+    }
+}
+
 /// Supported platforms list.
 #[derive(Debug, Clone, Deserialize)]
 pub struct PlatformConfig {
@@ -283,6 +319,30 @@ default_port = 9200
         assert_eq!(manifest.module.name, "test_module");
         assert_eq!(manifest.service.default_port, 9200);
         assert!(manifest.tools.is_empty());
+        assert!(manifest.hooks.install.is_none());
+        assert_eq!(manifest.hooks.timeout_secs, 120);
+    }
+
+    #[test]
+    fn test_parse_manifest_with_hooks() {
+        let toml = r#"
+[module]
+name = "price_tracker"
+version = "0.2.0"
+description = "Track token prices"
+
+[service]
+default_port = 9200
+
+[hooks]
+install = "sh hooks/install.sh"
+upgrade = "sh hooks/migrate.sh"
+"#;
+        let manifest = ModuleManifest::from_str(toml).unwrap();
+        assert_eq!(manifest.hooks.install.as_deref(), Some("sh hooks/install.sh"));
+        assert_eq!(manifest.hooks.upgrade.as_deref(), Some("sh hooks/migrate.sh"));
+        assert!(manifest.hooks.uninstall.is_none());
+        assert_eq!(manifest.hooks.timeout_secs, 120);
     }
 
     #[test]
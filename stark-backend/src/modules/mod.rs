@@ -6,11 +6,15 @@
 
 pub mod dynamic_module;
 pub mod dynamic_tool;
+pub mod lifecycle;
 pub mod loader;
 pub mod manifest;
 pub mod port_registry;
 pub mod registry;
+pub mod sandbox;
 pub mod service_logs;
+pub mod supervisor;
+pub mod updates;
 pub mod zip_parser;
 
 use async_trait::async_trait;
@@ -79,6 +83,16 @@ pub trait Module: Send + Sync {
     /// The base URL of the running service (reads from env or falls back to default)
     fn service_url(&self) -> String;
 
+    /// Full URL of the service's health endpoint
+    fn health_url(&self) -> String {
+        format!("{}/rpc/status", self.service_url())
+    }
+
+    /// Whether the bot can start this module's service itself (command or binary present)
+    fn can_start_service(&self) -> bool {
+        false
+    }
+
     /// Whether this module provides tools to the bot
     fn has_tools(&self) -> bool;
     /// Whether this module has a standalone dashboard (served by the service itself)
@@ -106,6 +120,11 @@ pub trait Module: Send + Sync {
         Vec::new()
     }
 
+    /// Extra writable paths for the sandboxed service (manifest `[service] fs_allow`)
+    fn manifest_fs_allow(&self) -> Vec<String> {
+        Vec::new()
+    }
+
     /// Directory containing the module on disk (if available)
     fn module_dir(&self) -> Option<&PathBuf> {
         None
@@ -21,7 +21,7 @@ impl ModuleRegistry {
         let dynamic = loader::load_dynamic_modules();
         for module in dynamic {
             let name = module.name().to_string();
-            log::info!("[MODULE] Registered dynamic module: {}", name);
+            log::debug!("[MODULE] Registered dynamic module: {}", name);
             reg.modules.insert(name, Box::new(module));
         }
 
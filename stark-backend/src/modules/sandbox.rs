@@ -0,0 +1,330 @@
+//! Module process sandboxing and binary checksum enforcement
+//!
+//! Every module service launch goes through [`prepare_launch`]:
+//! - The service binary is hashed and compared with the SHA-256 pinned at
+//!   install time (taken from the archive StarkHub's registry checksum
+//!   verified). A mismatch refuses the launch.
+//! - The process is wrapped in bubblewrap (`bwrap`) with fresh PID/IPC/UTS
+//!   namespaces and a filesystem allow-list: read-only system paths, the
+//!   module's own directory, and the paths its manifest declares in
+//!   `[service] fs_allow`. The network namespace is shared so the backend can
+//!   still reach the service on loopback.
+//! - Optionally the process drops to an unprivileged uid/gid.
+//!
+//! Each launch attempt (allowed or refused) is recorded in `module_launches`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::env_vars;
+use crate::db::Database;
+
+/// Read-only system paths visible inside the sandbox (skipped if absent)
+const SYSTEM_RO_PATHS: &[&str] = &[
+    "/usr",
+    "/bin",
+    "/sbin",
+    "/lib",
+    "/lib64",
+    "/etc/ssl",
+    "/etc/ca-certificates",
+    "/etc/pki",
+    "/etc/resolv.conf",
+    "/etc/hosts",
+    "/etc/nsswitch.conf",
+    "/etc/passwd",
+    "/etc/group",
+    "/etc/localtime",
+];
+
+/// How module processes are isolated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SandboxMode {
+    /// Run directly
+    Off,
+    /// Use bubblewrap when it's installed, otherwise run directly
+    Auto,
+    /// Require bubblewrap; refuse to launch without it
+    Bwrap,
+}
+
+impl SandboxMode {
+    pub fn from_env() -> Self {
+        match std::env::var(env_vars::MODULE_SANDBOX).unwrap_or_default().to_lowercase().as_str() {
+            "off" | "false" | "0" | "none" => SandboxMode::Off,
+            "bwrap" | "required" | "strict" => SandboxMode::Bwrap,
+            _ => SandboxMode::Auto,
+        }
+    }
+}
+
+/// What a module service is started from
+#[derive(Clone, Copy)]
+pub enum LaunchTarget<'a> {
+    /// A service binary
+    Binary(&'a Path),
+    /// A manifest `command`, run with `sh -c` from the module directory
+    Command(&'a str),
+}
+
+/// SHA-256 of a file as lowercase hex
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    use sha2::{Digest, Sha256};
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Resolve manifest `fs_allow` entries: relative paths are inside the module
+/// directory, `~/` is the bot user's home.
+pub fn resolve_fs_allow(module_dir: &Path, entries: &[String]) -> Vec<PathBuf> {
+    entries
+        .iter()
+        .filter(|e| !e.trim().is_empty())
+        .map(|entry| {
+            if let Some(rest) = entry.strip_prefix("~/") {
+                std::env::var("HOME").map(PathBuf::from).unwrap_or_default().join(rest)
+            } else if Path::new(entry).is_absolute() {
+                PathBuf::from(entry)
+            } else {
+                module_dir.join(entry)
+            }
+        })
+        .collect()
+}
+
+/// bubblewrap arguments for a module (everything before the program)
+pub fn bwrap_args(module_dir: &Path, extra_ro: &[PathBuf], fs_allow: &[PathBuf]) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "--die-with-parent",
+        "--new-session",
+        "--unshare-pid",
+        "--unshare-ipc",
+        "--unshare-uts",
+        "--unshare-cgroup-try",
+        "--proc",
+        "/proc",
+        "--dev",
+        "/dev",
+        "--tmpfs",
+        "/tmp",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+
+    let mut bind = |flag: &str, path: &Path| {
+        let p = path.to_string_lossy().to_string();
+        args.extend([flag.to_string(), p.clone(), p]);
+    };
+    for path in SYSTEM_RO_PATHS {
+        bind("--ro-bind-try", Path::new(path));
+    }
+    for path in extra_ro {
+        bind("--ro-bind-try", path);
+    }
+    bind("--bind", module_dir);
+    for path in fs_allow {
+        bind("--bind-try", path);
+    }
+    args.extend(["--chdir".to_string(), module_dir.to_string_lossy().to_string()]);
+    args
+}
+
+/// Interpreter caches a `command` launch (e.g. `uv run service.py`) needs
+fn interpreter_paths() -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let Ok(home) = std::env::var("HOME").map(PathBuf::from) else {
+        return (Vec::new(), Vec::new());
+    };
+    (vec![home.join(".local")], vec![home.join(".cache")])
+}
+
+/// Check a service binary's hash against the one pinned for the module.
+/// Returns the expected hash (None for modules that aren't pinned, such as
+/// bundled services).
+///
+/// StarkHub modules installed before hashes were pinned get pinned on their
+/// first launch.
+pub fn check_binary(db: &Database, module_name: &str, actual: &str) -> Result<Option<String>, String> {
+    let installed = db.get_installed_module(module_name).ok().flatten();
+    match installed.as_ref().and_then(|m| m.binary_sha256.clone()) {
+        Some(expected) if expected != actual => Err(format!(
+            "Checksum mismatch: expected {}, found {}. The binary changed since it was installed — reinstall the module.",
+            expected, actual
+        )),
+        Some(expected) => Ok(Some(expected)),
+        None if installed.as_ref().is_some_and(|m| m.source == "starkhub") => {
+            log::warn!(
+                "[MODULE] {} has no pinned binary checksum — pinning {} on first launch",
+                module_name,
+                actual
+            );
+            let _ = db.set_module_binary_sha256(module_name, actual);
+            Ok(Some(actual.to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Build the command that launches a module service, enforcing the checksum
+/// and sandbox policy. The caller adds env vars and stdio, then spawns it.
+pub fn prepare_launch(
+    db: &Database,
+    module_name: &str,
+    target: LaunchTarget,
+    module_dir: &Path,
+    fs_allow: &[String],
+) -> Result<Command, String> {
+    let mode = SandboxMode::from_env();
+    let bwrap = match mode {
+        SandboxMode::Off => None,
+        _ => which::which("bwrap").ok(),
+    };
+
+    let mut refused = None;
+    let mut sha256 = None;
+    let mut expected = None;
+    let target_desc = match target {
+        LaunchTarget::Binary(path) => {
+            match sha256_file(path) {
+                Ok(actual) => {
+                    match check_binary(db, module_name, &actual) {
+                        Ok(e) => expected = e,
+                        Err(e) => {
+                            expected = db.get_installed_module(module_name).ok().flatten().and_then(|m| m.binary_sha256);
+                            refused = Some(e);
+                        }
+                    }
+                    sha256 = Some(actual);
+                }
+                Err(e) => refused = Some(format!("Cannot read service binary: {}", e)),
+            }
+            path.display().to_string()
+        }
+        LaunchTarget::Command(command) => command.to_string(),
+    };
+    if refused.is_none() && mode == SandboxMode::Bwrap && bwrap.is_none() {
+        refused = Some(format!("{} requires bubblewrap but `bwrap` is not installed", env_vars::MODULE_SANDBOX));
+    }
+    let uid = std::env::var(env_vars::MODULE_UID).ok().and_then(|v| v.parse::<u32>().ok());
+    let gid = std::env::var(env_vars::MODULE_GID).ok().and_then(|v| v.parse::<u32>().ok());
+    let sandbox = if bwrap.is_some() { "bwrap" } else { "off" };
+
+    let _ = db.record_module_launch(
+        module_name,
+        &target_desc,
+        sha256.as_deref(),
+        expected.as_deref(),
+        sandbox,
+        uid,
+        refused.is_none(),
+        refused.as_deref(),
+    );
+    if let Some(error) = refused {
+        log::error!("[MODULE] Refusing to launch {}: {}", module_name, error);
+        return Err(error);
+    }
+
+    let (program, program_args): (PathBuf, Vec<String>) = match target {
+        LaunchTarget::Binary(path) => (path.to_path_buf(), Vec::new()),
+        LaunchTarget::Command(command) => (PathBuf::from("sh"), vec!["-c".to_string(), command.to_string()]),
+    };
+
+    let mut cmd = match bwrap {
+        Some(bwrap) => {
+            let mut extra_ro = Vec::new();
+            let mut allow = resolve_fs_allow(module_dir, fs_allow);
+            match target {
+                // Binaries outside the module directory (bundled services) need their directory visible
+                LaunchTarget::Binary(path) if !path.starts_with(module_dir) => {
+                    extra_ro.extend(path.parent().map(Path::to_path_buf));
+                }
+                LaunchTarget::Command(_) => {
+                    let (ro, rw) = interpreter_paths();
+                    extra_ro.extend(ro);
+                    allow.extend(rw);
+                }
+                _ => {}
+            }
+            let mut cmd = Command::new(bwrap);
+            cmd.args(bwrap_args(module_dir, &extra_ro, &allow));
+            cmd.arg("--").arg(&program).args(&program_args);
+            cmd
+        }
+        None => {
+            let mut cmd = Command::new(&program);
+            cmd.args(&program_args);
+            cmd
+        }
+    };
+    if module_dir.is_dir() {
+        cmd.current_dir(module_dir);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        if let Some(gid) = gid {
+            cmd.gid(gid);
+        }
+        if let Some(uid) = uid {
+            cmd.uid(uid);
+        }
+    }
+
+    log::info!(
+        "[MODULE] Launching {} (sandbox: {}, sha256: {})",
+        module_name,
+        sandbox,
+        sha256.as_deref().unwrap_or("n/a")
+    );
+    Ok(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_enforcement() {
+        let db = Database::new(":memory:").unwrap();
+        let dir = std::env::temp_dir().join(format!("stark-sandbox-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let binary = dir.join("demo-service");
+        std::fs::write(&binary, b"original").unwrap();
+        let hash = sha256_file(&binary).unwrap();
+
+        db.install_module_full("demo", "demo", "1.0.0", false, false, "starkhub", None, None, None, None)
+            .unwrap();
+        // Legacy install without a pinned hash: pinned on first launch
+        assert_eq!(check_binary(&db, "demo", &hash).unwrap(), Some(hash.clone()));
+        assert_eq!(db.get_installed_module("demo").unwrap().unwrap().binary_sha256, Some(hash.clone()));
+
+        std::fs::write(&binary, b"tampered").unwrap();
+        let tampered = sha256_file(&binary).unwrap();
+        assert!(check_binary(&db, "demo", &tampered).is_err());
+        assert!(prepare_launch(&db, "demo", LaunchTarget::Binary(&binary), &dir, &[]).is_err());
+        let launches = db.list_module_launches(Some("demo"), 10).unwrap();
+        assert_eq!(launches.len(), 1);
+        assert!(!launches[0].allowed);
+        assert_eq!(launches[0].sha256.as_deref(), Some(tampered.as_str()));
+        assert_eq!(launches[0].expected_sha256, Some(hash));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bwrap_allow_list() {
+        let module_dir = Path::new("/opt/modules/demo");
+        let allow = resolve_fs_allow(module_dir, &["data".to_string(), "/var/lib/demo".to_string()]);
+        assert_eq!(allow, vec![module_dir.join("data"), PathBuf::from("/var/lib/demo")]);
+
+        let args = bwrap_args(module_dir, &[], &allow);
+        let joined = args.join(" ");
+        assert!(joined.contains("--bind /opt/modules/demo /opt/modules/demo"));
+        assert!(joined.contains("--bind-try /var/lib/demo /var/lib/demo"));
+        assert!(joined.contains("--ro-bind-try /usr /usr"));
+        assert!(!joined.contains("--unshare-net"));
+    }
+}
@@ -0,0 +1,438 @@
+//! Module service supervision — start/stop helpers, periodic health checks
+//! and automatic restart with backoff.
+//!
+//! The health worker polls each enabled module's health endpoint. After
+//! [`FAILURES_BEFORE_RESTART`] consecutive failures the service is killed and
+//! started again, waiting longer after each restart ([`restart_delay`]). A
+//! module that keeps crashing is marked `failed` after [`MAX_RESTARTS`] and
+//! left alone until someone restarts or re-enables it. Health is kept in a
+//! process-wide store so `/api/modules` and `manage_modules` can report it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use super::Module;
+use crate::db::Database;
+
+/// Consecutive failed checks before a restart
+pub const FAILURES_BEFORE_RESTART: u32 = 2;
+/// Restarts before giving up (reset once the service stays healthy)
+pub const MAX_RESTARTS: u32 = 5;
+/// Healthy time after the last restart that clears the restart count
+const STABLE_AFTER_MINUTES: i64 = 10;
+const BASE_RESTART_DELAY_SECS: i64 = 30;
+const MAX_RESTART_DELAY_SECS: i64 = 600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    Unhealthy,
+    Restarting,
+    /// Gave up restarting; needs a manual restart
+    Failed,
+}
+
+/// Supervisor's view of a module service
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleHealth {
+    pub status: HealthStatus,
+    pub consecutive_failures: u32,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    pub last_check_at: Option<DateTime<Utc>>,
+    pub last_healthy_at: Option<DateTime<Utc>>,
+    pub last_restart_at: Option<DateTime<Utc>>,
+    /// Earliest time the next automatic restart may happen
+    pub next_restart_at: Option<DateTime<Utc>>,
+}
+
+impl Default for ModuleHealth {
+    fn default() -> Self {
+        Self {
+            status: HealthStatus::Healthy,
+            consecutive_failures: 0,
+            restarts: 0,
+            last_error: None,
+            last_check_at: None,
+            last_healthy_at: None,
+            last_restart_at: None,
+            next_restart_at: None,
+        }
+    }
+}
+
+impl ModuleHealth {
+    /// One-line summary for tool output
+    pub fn summary(&self) -> String {
+        let mut s = format!("{:?}", self.status).to_lowercase();
+        if self.restarts > 0 {
+            s.push_str(&format!(", {} restart(s)", self.restarts));
+        }
+        if let Some(ref e) = self.last_error {
+            if self.status != HealthStatus::Healthy {
+                s.push_str(&format!(", last error: {}", e));
+            }
+        }
+        s
+    }
+}
+
+/// Wait before restart number `restarts` may be followed by another: 30s, 60s, 120s… capped at 10 min
+pub fn restart_delay(restarts: u32) -> Duration {
+    let secs = BASE_RESTART_DELAY_SECS.saturating_mul(1i64 << restarts.saturating_sub(1).min(16));
+    Duration::seconds(secs.min(MAX_RESTART_DELAY_SECS))
+}
+
+/// Apply a health check result. Returns true when the service should be restarted now.
+pub fn observe(health: &mut ModuleHealth, result: Result<(), String>, now: DateTime<Utc>) -> bool {
+    health.last_check_at = Some(now);
+    match result {
+        Ok(()) => {
+            health.status = HealthStatus::Healthy;
+            health.consecutive_failures = 0;
+            health.last_healthy_at = Some(now);
+            health.next_restart_at = None;
+            if health.last_restart_at.is_some_and(|t| now - t >= Duration::minutes(STABLE_AFTER_MINUTES)) {
+                health.restarts = 0;
+            }
+            false
+        }
+        Err(e) => {
+            health.consecutive_failures += 1;
+            health.last_error = Some(e);
+            if health.status == HealthStatus::Failed {
+                return false;
+            }
+            if health.consecutive_failures < FAILURES_BEFORE_RESTART {
+                health.status = HealthStatus::Unhealthy;
+                return false;
+            }
+            if health.restarts >= MAX_RESTARTS {
+                health.status = HealthStatus::Failed;
+                return false;
+            }
+            if health.next_restart_at.is_some_and(|t| now < t) {
+                return false;
+            }
+            health.restarts += 1;
+            health.last_restart_at = Some(now);
+            health.next_restart_at = Some(now + restart_delay(health.restarts));
+            health.status = HealthStatus::Restarting;
+            true
+        }
+    }
+}
+
+static STORE: OnceLock<Mutex<HashMap<String, ModuleHealth>>> = OnceLock::new();
+
+fn store() -> &'static Mutex<HashMap<String, ModuleHealth>> {
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Current health of a module (None until it has been checked)
+pub fn health(name: &str) -> Option<ModuleHealth> {
+    store().lock().unwrap().get(name).cloned()
+}
+
+/// Clear a module's health history (manual restart / re-enable)
+pub fn reset(name: &str) {
+    store().lock().unwrap().insert(name.to_string(), ModuleHealth::default());
+}
+
+/// Stop tracking a module (disabled / uninstalled)
+pub fn forget(name: &str) {
+    store().lock().unwrap().remove(name);
+}
+
+/// Kill the service process listening on a given port (if any).
+pub fn kill_service_on_port(port: u16) {
+    let output = std::process::Command::new("lsof")
+        .args(["-ti", &format!("tcp:{}", port)])
+        .output();
+    if let Ok(out) = output {
+        let pids = String::from_utf8_lossy(&out.stdout);
+        let my_pid = std::process::id().to_string();
+        for pid_str in pids.split_whitespace() {
+            let pid = pid_str.trim();
+            if !pid.is_empty() && pid != my_pid {
+                log::info!("[MODULE] Killing service process PID {} on port {}", pid, port);
+                let _ = std::process::Command::new("kill").arg(pid).output();
+            }
+        }
+    }
+}
+
+/// The port a module's service actually runs on (assigned at startup, or from its URL/env)
+pub fn runtime_port(module: &dyn Module) -> u16 {
+    super::port_registry::resolve(module.name()).unwrap_or_else(|| {
+        module
+            .service_url()
+            .rsplit(':')
+            .next()
+            .and_then(|s| s.trim_end_matches('/').parse::<u16>().ok())
+            .unwrap_or(module.default_port())
+    })
+}
+
+/// Start a module's service if not already running.
+/// Checks the module manifest for a `command` field first, falling back to binary discovery.
+pub fn start_module_service(module_name: &str, port: u16, db: &Database) {
+    // Already running?
+    if std::net::TcpStream::connect(format!("127.0.0.1:{}", port)).is_ok() {
+        log::info!("[MODULE] {} already running on port {} — skipping start", module_name, port);
+        return;
+    }
+
+    // Check if the module has a command in its manifest
+    let registry = super::ModuleRegistry::new();
+    if let Some(module) = registry.get(module_name) {
+        if let Some(command) = module.manifest_command() {
+            let module_dir = match module.module_dir() {
+                Some(dir) => dir.clone(),
+                None => {
+                    log::warn!("[MODULE] {} has command but no module_dir — cannot start", module_name);
+                    return;
+                }
+            };
+            let target = super::sandbox::LaunchTarget::Command(&command);
+            let mut cmd = match super::sandbox::prepare_launch(
+                db,
+                module_name,
+                target,
+                &module_dir,
+                &module.manifest_fs_allow(),
+            ) {
+                Ok(cmd) => cmd,
+                Err(e) => {
+                    log::error!("[MODULE] Not starting {}: {}", module_name, e);
+                    return;
+                }
+            };
+            cmd.stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped());
+            cmd.env("MODULE_PORT", port.to_string());
+            pass_backend_env(&mut cmd);
+
+            // Also set the module-specific port env var (e.g. OPENAGENT_PORT, WALLET_MONITOR_PORT)
+            if let Some(port_var) = module.manifest_port_env_var() {
+                cmd.env(&port_var, port.to_string());
+            }
+
+            // Pass all declared env vars from DB api_keys, falling back to process env
+            for env_key in module.manifest_env_var_keys() {
+                if let Ok(Some(key)) = db.get_api_key(&env_key) {
+                    cmd.env(&env_key, &key.api_key);
+                } else if let Ok(val) = std::env::var(&env_key) {
+                    if !val.is_empty() {
+                        cmd.env(&env_key, &val);
+                    }
+                }
+            }
+
+            match cmd.spawn() {
+                Ok(mut child) => {
+                    log::info!("[MODULE] Started {} via `{}` (port {})", module_name, command, port);
+                    super::service_logs::spawn_log_capture_threads(module_name, child.stdout.take(), child.stderr.take());
+                }
+                Err(e) => log::error!("[MODULE] Failed to start {} via `{}`: {}", module_name, command, e),
+            }
+            return;
+        }
+    }
+
+    // Fallback: binary discovery
+    let self_exe = std::env::current_exe().unwrap_or_default();
+    let exe_dir = self_exe.parent().unwrap_or(std::path::Path::new("."));
+
+    let binary_name = module_name.replace('_', "-") + "-service";
+    let exe_path = exe_dir.join(&binary_name);
+    if !exe_path.exists() {
+        log::warn!("[MODULE] Service binary not found: {} — cannot start", exe_path.display());
+        return;
+    }
+
+    let target = super::sandbox::LaunchTarget::Binary(&exe_path);
+    let mut cmd = match super::sandbox::prepare_launch(db, module_name, target, exe_dir, &[]) {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            log::error!("[MODULE] Not starting {}: {}", binary_name, e);
+            return;
+        }
+    };
+    cmd.stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    cmd.env("MODULE_PORT", port.to_string());
+    pass_backend_env(&mut cmd);
+
+    match cmd.spawn() {
+        Ok(mut child) => {
+            log::info!("[MODULE] Started {} (port {})", binary_name, port);
+            super::service_logs::spawn_log_capture_threads(module_name, child.stdout.take(), child.stderr.take());
+        }
+        Err(e) => log::error!("[MODULE] Failed to start {}: {}", binary_name, e),
+    }
+}
+
+/// Internal token and self URL so a (re)started service can call back to the backend
+fn pass_backend_env(cmd: &mut std::process::Command) {
+    if let Ok(token) = std::env::var("STARKBOT_INTERNAL_TOKEN") {
+        cmd.env("STARKBOT_INTERNAL_TOKEN", token);
+    }
+    cmd.env("STARKBOT_SELF_URL", crate::config::self_url());
+}
+
+/// Kill and start a module's service on its runtime port. Returns the port.
+pub fn restart_module_service(db: &Database, name: &str) -> Result<u16, String> {
+    let registry = super::ModuleRegistry::new();
+    let module = registry.get(name).ok_or_else(|| format!("Unknown module: '{}'", name))?;
+    let port = runtime_port(module);
+    kill_service_on_port(port);
+    // Also kill on default port in case the service was started there
+    if port != module.default_port() {
+        kill_service_on_port(module.default_port());
+    }
+    // Brief pause to let the port free up
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    start_module_service(name, port, db);
+    Ok(port)
+}
+
+/// GET the module's health endpoint
+pub async fn check_health(client: &reqwest::Client, module: &dyn Module) -> Result<(), String> {
+    match client.get(module.health_url()).send().await {
+        Ok(resp) if resp.status().is_success() => Ok(()),
+        Ok(resp) => Err(format!("health endpoint returned {}", resp.status())),
+        Err(e) if e.is_timeout() => Err("health check timed out".to_string()),
+        Err(_) => Err("service unreachable".to_string()),
+    }
+}
+
+/// Run one supervision pass over enabled modules. Returns the number restarted.
+pub async fn supervise_once(db: &Arc<Database>, client: &reqwest::Client) -> usize {
+    let installed = db.list_installed_modules().unwrap_or_default();
+    let registry = super::ModuleRegistry::new();
+    let mut restarted = 0;
+
+    for entry in &installed {
+        let module = match registry.get(&entry.module_name) {
+            Some(m) if entry.enabled && m.can_start_service() => m,
+            _ => {
+                forget(&entry.module_name);
+                continue;
+            }
+        };
+        let result = check_health(client, module).await;
+        let now = Utc::now();
+        let (restart, health) = {
+            let mut map = store().lock().unwrap();
+            let health = map.entry(entry.module_name.clone()).or_default();
+            let was = health.status;
+            let restart = observe(health, result, now);
+            if was != HealthStatus::Failed && health.status == HealthStatus::Failed {
+                log::error!(
+                    "[MODULE] {} keeps failing after {} restarts — giving up until it is restarted manually",
+                    entry.module_name,
+                    MAX_RESTARTS
+                );
+            }
+            (restart, health.clone())
+        };
+        if !restart {
+            continue;
+        }
+
+        log::warn!(
+            "[MODULE] {} is down ({}) — restart {} of {}",
+            entry.module_name,
+            health.last_error.as_deref().unwrap_or("unknown error"),
+            health.restarts,
+            MAX_RESTARTS
+        );
+        let db = db.clone();
+        let name = entry.module_name.clone();
+        match tokio::task::spawn_blocking(move || restart_module_service(&db, &name)).await {
+            Ok(Ok(port)) => {
+                log::info!("[MODULE] Restarted {} on port {}", entry.module_name, port);
+                restarted += 1;
+            }
+            Ok(Err(e)) => log::error!("[MODULE] Failed to restart {}: {}", entry.module_name, e),
+            Err(e) => log::error!("[MODULE] Restart task for {} panicked: {}", entry.module_name, e),
+        }
+    }
+    restarted
+}
+
+/// Spawn the background health worker. `is_leader` gates supervision to the
+/// instance that runs module services.
+pub fn spawn_health_worker<F>(db: Arc<Database>, interval_secs: u64, is_leader: F) -> tokio::task::JoinHandle<()>
+where
+    F: Fn() -> bool + Send + 'static,
+{
+    tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(5))
+            .build()
+            .unwrap_or_default();
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        interval.tick().await; // skip immediate tick (services are still starting)
+        loop {
+            interval.tick().await;
+            if !is_leader() {
+                continue;
+            }
+            let restarted = supervise_once(&db, &client).await;
+            if restarted > 0 {
+                log::info!("[MODULE] Health pass complete: {} service(s) restarted", restarted);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_delay_backoff() {
+        assert_eq!(restart_delay(1), Duration::seconds(30));
+        assert_eq!(restart_delay(2), Duration::seconds(60));
+        assert_eq!(restart_delay(3), Duration::seconds(120));
+        assert_eq!(restart_delay(10), Duration::seconds(600));
+    }
+
+    #[test]
+    fn test_observe_restarts_with_backoff_then_gives_up() {
+        let mut h = ModuleHealth::default();
+        let t0 = Utc::now();
+        let down = || Err("service unreachable".to_string());
+
+        // First failure only marks it unhealthy
+        assert!(!observe(&mut h, down(), t0));
+        assert_eq!(h.status, HealthStatus::Unhealthy);
+        // Second consecutive failure restarts
+        assert!(observe(&mut h, down(), t0));
+        assert_eq!((h.status, h.restarts), (HealthStatus::Restarting, 1));
+        // Still down within the backoff window: no restart
+        assert!(!observe(&mut h, down(), t0 + Duration::seconds(10)));
+
+        let mut t = t0;
+        for _ in 1..MAX_RESTARTS {
+            t += Duration::seconds(601);
+            assert!(observe(&mut h, down(), t));
+        }
+        assert_eq!(h.restarts, MAX_RESTARTS);
+        t += Duration::seconds(601);
+        assert!(!observe(&mut h, down(), t));
+        assert_eq!(h.status, HealthStatus::Failed);
+
+        // Recovery clears failures; restart count resets once stable
+        assert!(!observe(&mut h, Ok(()), t));
+        assert_eq!((h.status, h.consecutive_failures), (HealthStatus::Healthy, 0));
+        assert!(!observe(&mut h, Ok(()), t + Duration::minutes(STABLE_AFTER_MINUTES + 1)));
+        assert_eq!(h.restarts, 0);
+    }
+}
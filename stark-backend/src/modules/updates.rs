@@ -0,0 +1,353 @@
+//! Module updates — StarkHub version checks, owner notifications and
+//! in-place upgrades.
+//!
+//! The update worker compares each installed StarkHub module with the hub's
+//! latest version and records newer ones in `module_updates`. Each release is
+//! announced once: as a `module.update_available` gateway event and, when the
+//! owner's DM is configured, as a chat message.
+//!
+//! [`upgrade_module`] stages the new release next to the installed one,
+//! verifying the archive (or each file) against the hub's SHA-256, then stops
+//! the service, swaps the directories, runs the manifest's upgrade hook and
+//! starts the service again. A failed hook restores the previous release.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::json;
+
+use super::lifecycle::{self, LifecycleEvent};
+use super::supervisor;
+use crate::channels::outbound;
+use crate::db::tables::module_updates::ModuleUpdate;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::integrations::starkhub_client::{current_platform, ModuleDetail, StarkHubClient};
+
+/// Result of a successful upgrade
+#[derive(Debug, Clone, Serialize)]
+pub struct UpgradeOutcome {
+    pub module_name: String,
+    pub previous_version: String,
+    pub version: String,
+}
+
+/// Hub author and slug for an installed StarkHub module
+fn hub_coordinates(author: Option<&str>, module_name: &str) -> Option<(String, String)> {
+    let author = author?.trim();
+    let username = author.strip_prefix('@')?;
+    if username.is_empty() {
+        return None;
+    }
+    Some((username.to_string(), module_name.to_string()))
+}
+
+/// Look up a module on the hub (installed names use `_`, hub slugs may use `-`)
+async fn fetch_detail(client: &StarkHubClient, username: &str, name: &str) -> Result<(String, ModuleDetail), String> {
+    match client.get_module(username, name).await {
+        Ok(detail) => Ok((name.to_string(), detail)),
+        Err(e) if name.contains('_') => {
+            let slug = name.replace('_', "-");
+            client.get_module(username, &slug).await.map(|d| (slug, d)).map_err(|_| e)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Check every installed StarkHub module once. Returns the updates the owner
+/// hasn't been told about yet.
+pub async fn check_for_updates(db: &Database, client: &StarkHubClient) -> Vec<ModuleUpdate> {
+    let installed = db.list_installed_modules().unwrap_or_default();
+    let mut fresh = Vec::new();
+    for module in installed.iter().filter(|m| m.source == "starkhub") {
+        let Some((username, name)) = hub_coordinates(module.author.as_deref(), &module.module_name) else {
+            continue;
+        };
+        let remote = match fetch_detail(client, &username, &name).await {
+            Ok((_, detail)) => detail,
+            Err(e) => {
+                log::debug!("[MODULE] Update check for {} failed: {}", module.module_name, e);
+                continue;
+            }
+        };
+        if !crate::config::semver_is_newer(&remote.version, &module.version) {
+            let _ = db.clear_module_update(&module.module_name);
+            continue;
+        }
+        match db.upsert_module_update(&module.module_name, &module.version, &remote.version) {
+            Ok(update) if update.needs_notification() => fresh.push(update),
+            Ok(_) => {}
+            Err(e) => log::warn!("[MODULE] Failed to record update for {}: {}", module.module_name, e),
+        }
+    }
+    fresh
+}
+
+/// Announce updates to the web UI and the owner's DM, then mark them notified
+pub async fn notify_updates(db: &Database, broadcaster: &EventBroadcaster, updates: &[ModuleUpdate]) {
+    if updates.is_empty() {
+        return;
+    }
+    for update in updates {
+        broadcaster.broadcast(GatewayEvent::custom(
+            "module.update_available",
+            json!({
+                "module": update.module_name,
+                "current_version": update.current_version,
+                "available_version": update.available_version,
+            }),
+        ));
+    }
+
+    let owner = db.get_bot_settings().ok().and_then(|s| s.owner_chat());
+    if let Some((channel_id, chat_id)) = owner {
+        let mut text = String::from("**Module updates available**");
+        for update in updates {
+            text.push_str(&format!(
+                "\n- {}: v{} → v{}",
+                update.module_name, update.current_version, update.available_version
+            ));
+        }
+        text.push_str("\n\nUpgrade from the Modules page in the dashboard.");
+        if let Err(e) = outbound::send_proactive(db, channel_id, &chat_id, &text, "module_updates").await {
+            // Leave them un-notified so the next pass retries the message
+            log::warn!("[MODULE] Failed to send update notification: {}", e);
+            return;
+        }
+    }
+
+    for update in updates {
+        let _ = db.mark_module_update_notified(&update.module_name, &update.available_version);
+    }
+}
+
+/// Download a release into `dir`, verifying it against the hub's checksums.
+/// Returns the verified archive checksum (None for manifest-only modules).
+async fn stage_release(
+    client: &StarkHubClient,
+    username: &str,
+    slug: &str,
+    dir: &Path,
+) -> Result<Option<String>, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+    if let Ok(download) = client.get_download_info(username, slug, current_platform()).await {
+        let archive = client.download_binary(&download.download_url).await?;
+        let computed = sha256_hex(&archive);
+        if computed != download.sha256_checksum {
+            return Err(format!(
+                "Checksum mismatch! Expected {}, got {}. Download may be corrupted.",
+                download.sha256_checksum, computed
+            ));
+        }
+        let decoder = flate2::read::GzDecoder::new(&archive[..]);
+        tar::Archive::new(decoder)
+            .unpack(dir)
+            .map_err(|e| format!("Failed to extract module archive: {}", e))?;
+        return Ok(Some(computed));
+    }
+
+    // No binary for this platform — manifest plus individual files
+    let manifest_url = format!(
+        "{}/modules/@{}/{}/manifest",
+        std::env::var("STARKHUB_API_URL").unwrap_or_else(|_| "https://hub.starkbot.ai/api".to_string()),
+        username,
+        slug
+    );
+    let manifest_json: serde_json::Value = reqwest::get(&manifest_url)
+        .await
+        .map_err(|e| format!("Failed to fetch manifest: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to fetch manifest: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse manifest: {}", e))?;
+    let manifest_toml = manifest_json
+        .get("manifest_toml")
+        .and_then(|v| v.as_str())
+        .ok_or("StarkHub manifest response missing manifest_toml field")?;
+    std::fs::write(dir.join("module.toml"), manifest_toml).map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    for file in client.list_module_files(username, slug).await.unwrap_or_default() {
+        let content = client.download_module_file(username, slug, &file.file_name).await?;
+        let computed = sha256_hex(content.as_bytes());
+        if !file.sha256_checksum.is_empty() && computed != file.sha256_checksum {
+            return Err(format!("Checksum mismatch for '{}'", file.file_name));
+        }
+        let path = dir.join(&file.file_name);
+        if !path.starts_with(dir) || file.file_name.contains("..") {
+            return Err(format!("Refusing file outside the module directory: '{}'", file.file_name));
+        }
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        std::fs::write(&path, content).map_err(|e| format!("Failed to write '{}': {}", file.file_name, e))?;
+    }
+    Ok(None)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Upgrade an installed StarkHub module to the hub's latest version.
+///
+/// The caller unregisters the module's tools before and re-registers them
+/// after, so tools removed in the new release disappear.
+pub async fn upgrade_module(db: &Arc<Database>, name: &str) -> Result<UpgradeOutcome, String> {
+    let installed = db
+        .get_installed_module(name)
+        .map_err(|e| format!("Failed to load module: {}", e))?
+        .ok_or_else(|| format!("Module '{}' is not installed", name))?;
+    if installed.source != "starkhub" {
+        return Err(format!("Module '{}' is not from StarkHub (source: {})", name, installed.source));
+    }
+    let (username, name_on_hub) = hub_coordinates(installed.author.as_deref(), name)
+        .ok_or_else(|| format!("Module '{}' has no StarkHub author recorded", name))?;
+
+    let client = StarkHubClient::new();
+    let (slug, remote) = fetch_detail(&client, &username, &name_on_hub).await?;
+    if !crate::config::semver_is_newer(&remote.version, &installed.version) {
+        let _ = db.clear_module_update(name);
+        return Err(format!("Module '{}' is up to date (v{})", name, installed.version));
+    }
+
+    let modules_dir = crate::config::runtime_modules_dir();
+    let module_dir = modules_dir.join(name);
+    let staging_dir = modules_dir.join(format!(".{}.upgrade", name));
+    let backup_dir = modules_dir.join(format!(".{}.v{}", name, installed.version));
+    let _ = std::fs::remove_dir_all(&staging_dir);
+    let _ = std::fs::remove_dir_all(&backup_dir);
+
+    let archive_checksum = match stage_release(&client, &username, &slug, &staging_dir).await {
+        Ok(checksum) => checksum,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging_dir);
+            return Err(e);
+        }
+    };
+    if let Err(e) = super::manifest::ModuleManifest::from_file(&staging_dir.join("module.toml")) {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+        return Err(format!("New release has an invalid manifest: {}", e));
+    }
+    let binary_path = staging_dir.join("bin").join(format!("{}-service", slug));
+    #[cfg(unix)]
+    if binary_path.exists() {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&binary_path, std::fs::Permissions::from_mode(0o755));
+    }
+    let binary_hash = super::sandbox::sha256_file(&binary_path).ok();
+
+    // Stop the running service and swap the release in
+    stop_service(name);
+    swap_dirs(&module_dir, &staging_dir, &backup_dir)?;
+
+    if let Err(e) = lifecycle::run_hook(db, &module_dir, LifecycleEvent::Upgrade, Some(&installed.version)).await {
+        log::error!("[MODULE] Upgrade hook for {} failed — restoring v{}: {}", name, installed.version, e);
+        let _ = std::fs::remove_dir_all(&module_dir);
+        let _ = std::fs::rename(&backup_dir, &module_dir);
+        restart_if_enabled(db, name).await;
+        return Err(format!("Upgrade hook failed, v{} restored: {}", installed.version, e));
+    }
+
+    let _ = db.set_module_version(name, &remote.version);
+    if let Some(ref hash) = binary_hash {
+        // Re-pin: the sandbox refuses binaries that differ from the pinned hash
+        let _ = db.set_module_binary_sha256(name, hash);
+    }
+    if let Some(ref checksum) = archive_checksum {
+        log::info!("[MODULE] {} v{} archive verified (sha256 {})", name, remote.version, checksum);
+    }
+    let _ = db.clear_module_update(name);
+    let _ = std::fs::remove_dir_all(&backup_dir);
+    restart_if_enabled(db, name).await;
+
+    log::info!("[MODULE] Upgraded {} from v{} to v{}", name, installed.version, remote.version);
+    Ok(UpgradeOutcome {
+        module_name: name.to_string(),
+        previous_version: installed.version,
+        version: remote.version,
+    })
+}
+
+fn stop_service(name: &str) {
+    let registry = super::ModuleRegistry::new();
+    if let Some(module) = registry.get(name) {
+        supervisor::kill_service_on_port(supervisor::runtime_port(module));
+    }
+}
+
+/// Move `current` to `backup` and `staged` into its place (restoring on failure)
+fn swap_dirs(current: &Path, staged: &Path, backup: &Path) -> Result<(), String> {
+    if current.exists() {
+        std::fs::rename(current, backup).map_err(|e| format!("Failed to back up the installed release: {}", e))?;
+    }
+    if let Err(e) = std::fs::rename(staged, current) {
+        let _ = std::fs::rename(backup, current);
+        let _ = std::fs::remove_dir_all(staged);
+        return Err(format!("Failed to install the new release: {}", e));
+    }
+    Ok(())
+}
+
+async fn restart_if_enabled(db: &Arc<Database>, name: &str) {
+    let enabled = db.get_installed_module(name).ok().flatten().is_some_and(|m| m.enabled);
+    if !enabled {
+        return;
+    }
+    supervisor::reset(name);
+    let (db, name) = (db.clone(), name.to_string());
+    match tokio::task::spawn_blocking(move || supervisor::restart_module_service(&db, &name)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => log::warn!("[MODULE] Failed to restart after upgrade: {}", e),
+        Err(e) => log::warn!("[MODULE] Restart task panicked: {}", e),
+    }
+}
+
+/// Spawn the update worker (checks StarkHub every `interval_secs`). `is_leader`
+/// gates checks to one instance so the owner isn't notified twice.
+pub fn spawn_update_worker<F>(
+    db: Arc<Database>,
+    broadcaster: Arc<EventBroadcaster>,
+    interval_secs: u64,
+    is_leader: F,
+) -> tokio::task::JoinHandle<()>
+where
+    F: Fn() -> bool + Send + 'static,
+{
+    tokio::spawn(async move {
+        let client = StarkHubClient::new();
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if !is_leader() {
+                continue;
+            }
+            let fresh = check_for_updates(&db, &client).await;
+            if !fresh.is_empty() {
+                log::info!("[MODULE] {} module update(s) available", fresh.len());
+                notify_updates(&db, &broadcaster, &fresh).await;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hub_coordinates() {
+        assert_eq!(
+            hub_coordinates(Some("@alice"), "price_feed"),
+            Some(("alice".to_string(), "price_feed".to_string()))
+        );
+        // Wallet-address authors can't be looked up by username
+        assert_eq!(hub_coordinates(Some("0xabc"), "price_feed"), None);
+        assert_eq!(hub_coordinates(Some("@"), "price_feed"), None);
+        assert_eq!(hub_coordinates(None, "price_feed"), None);
+    }
+}
@@ -0,0 +1,129 @@
+//! Paper trading (dry-run mode for finance tools)
+//!
+//! Paper mode is on when the global `paper_trading_enabled` bot setting is set,
+//! or when the channel has its `paper_trading` setting on; the dispatcher
+//! resolves both into the `paper_mode` context flag. With it on, `swap_token`,
+//! `send_eth`, `bridge_usdc` and contract writes stop before signing: they
+//! fetch the same live quote they would have used, record the simulated fill
+//! (priced in USD at fill time) in `paper_trades`, and report it. Nothing is
+//! signed, queued or broadcast. The ledger replays through the journal's PnL
+//! so strategies can be judged the same way as live trading.
+
+use std::collections::HashMap;
+
+use serde_json::json;
+
+use crate::db::tables::paper_trades::{PaperTrade, RecordPaperTradeRequest, PAPER_KIND_SWAP};
+use crate::db::Database;
+use crate::error::AppResult;
+use crate::journal::{self, pnl::PnlReport, prices};
+use crate::tools::types::{ToolContext, ToolResult};
+
+/// Whether tools in this context should simulate instead of signing
+pub fn is_enabled(context: &ToolContext) -> bool {
+    context.extra.get("paper_mode").and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// USD prices for both sides of a fill right now, keyed like the request
+async fn fill_prices(req: &RecordPaperTradeRequest) -> (Option<f64>, Option<f64>) {
+    let sell_id = prices::coin_id(&req.network, &req.sell_token);
+    let buy_id = req.buy_token.as_deref().and_then(|t| prices::coin_id(&req.network, t));
+    let ids: Vec<String> = sell_id.iter().chain(buy_id.iter()).cloned().collect();
+    let found: HashMap<String, f64> = match prices::current_prices(&ids).await {
+        Ok(found) => found,
+        Err(e) => {
+            // An unpriced fill still counts; PnL implies the missing side from the fill ratio
+            log::warn!("[PAPER] Prices unavailable for fill: {}", e);
+            HashMap::new()
+        }
+    };
+    (
+        sell_id.and_then(|id| found.get(&id.to_lowercase()).copied()),
+        buy_id.and_then(|id| found.get(&id.to_lowercase()).copied()),
+    )
+}
+
+/// Price and record a simulated fill in the paper ledger
+pub async fn record_fill(context: &ToolContext, mut req: RecordPaperTradeRequest) -> Result<PaperTrade, String> {
+    let db = context.database.as_ref().ok_or("Database not available for the paper ledger.")?;
+    if req.sell_price_usd.is_none() && req.buy_price_usd.is_none() {
+        let (sell, buy) = fill_prices(&req).await;
+        req.sell_price_usd = sell;
+        req.buy_price_usd = buy;
+    }
+    let fill = db.record_paper_trade(req).map_err(|e| format!("Database error: {}", e))?;
+    log::info!(
+        "[PAPER] Simulated {} #{}: {} {} on {}",
+        fill.kind,
+        fill.id,
+        fill.to_trade().sell_amount(),
+        fill.sell_symbol,
+        fill.network
+    );
+    Ok(fill)
+}
+
+/// One-line summary of a fill
+pub fn describe(fill: &PaperTrade) -> String {
+    let trade = fill.to_trade();
+    let mut line = if fill.kind == PAPER_KIND_SWAP {
+        format!(
+            "{} {} → {} {} on {}",
+            trade.sell_amount(),
+            fill.sell_symbol,
+            trade.buy_amount().map(|a| a.to_string()).unwrap_or_else(|| "?".to_string()),
+            fill.buy_symbol.as_deref().unwrap_or("?"),
+            fill.network
+        )
+    } else {
+        format!(
+            "{} {} {}{} on {}",
+            fill.kind,
+            trade.sell_amount(),
+            fill.sell_symbol,
+            fill.counterparty.as_deref().map(|c| format!(" to {}", c)).unwrap_or_default(),
+            fill.network
+        )
+    };
+    if let Some(price) = fill.sell_price_usd {
+        line.push_str(&format!(" (@ ${:.2} per {})", price, fill.sell_symbol));
+    }
+    line
+}
+
+/// Tool result for a simulated fill, in place of the queued-transaction result
+pub fn fill_result(fill: &PaperTrade) -> ToolResult {
+    ToolResult::success(format!(
+        "PAPER TRADE (simulated, nothing signed)\n\n\
+        {}\n\n\
+        Recorded in the paper ledger as #{}. Paper mode is on for this channel: \
+        no transaction was queued and there is nothing to broadcast. \
+        Use paper_trading to review the ledger and PnL.",
+        describe(fill),
+        fill.id
+    ))
+    .with_metadata(json!({
+        "status": "paper_filled",
+        "paper": true,
+        "paper_trade_id": fill.id,
+        "fill": fill,
+    }))
+}
+
+/// Refusal for signing tools that have no simulated equivalent
+pub fn unsupported(tool: &str) -> ToolResult {
+    ToolResult::error(format!(
+        "Paper mode is on: {} cannot be simulated, so nothing was signed. \
+        Turn paper trading off for this channel to use it.",
+        tool
+    ))
+}
+
+/// Paper PnL (optionally for one channel) over a period, marked to current prices
+pub async fn pnl_report(db: &Database, channel_id: Option<i64>, since: Option<&str>) -> AppResult<PnlReport> {
+    let fills = db.list_all_paper_trades(channel_id)?;
+    let trades: Vec<_> = fills.iter().map(PaperTrade::to_trade).collect();
+    let mut report = journal::pnl::compute(&trades, since);
+    journal::mark_to_market(&mut report).await;
+    Ok(report)
+}
@@ -0,0 +1,218 @@
+//! Hot-path benchmarks (compiled with the `perf` feature)
+//!
+//! `cargo run --release --features perf -- perf [filter]` seeds an in-memory
+//! database and runs criterion over the paths that grow with usage:
+//!
+//! - token estimation over large sessions (`estimate_messages_tokens`)
+//! - context assembly (`build_context`, `build_context_with_memories`)
+//! - embedding search over 10k memories
+//! - strategy activity (run history and cooldown counts)
+//!
+//! Criterion keeps its baselines under `target/criterion`, so running the
+//! suite on the previous release and then on a candidate reports regressions.
+
+use crate::context::{estimate_messages_tokens, ContextManager};
+use crate::db::Database;
+use crate::memory::vector_search;
+use crate::models::session_message::MessageRole;
+use crate::models::SessionScope;
+use crate::strategies::types::{
+    CreateStrategyRequest, StrategyAction, StrategyGuardrails, StrategyTrigger, RUN_SKIPPED, RUN_SUCCEEDED,
+};
+use criterion::{BenchmarkId, Criterion};
+use serde_json::json;
+use std::sync::Arc;
+
+const SESSION_SIZES: &[usize] = &[500, 5_000];
+const MEMORY_COUNT: usize = 10_000;
+const EMBEDDING_DIMENSIONS: usize = 384;
+const STRATEGY_COUNT: usize = 20;
+const RUNS_PER_STRATEGY: usize = 1_000;
+const PERF_IDENTITY: &str = "perf-identity";
+
+/// Run every benchmark whose id contains `filter` (all when `None`)
+pub fn run(filter: Option<String>) {
+    // Criterion drives its own loop; keep it off the server's async runtime
+    std::thread::spawn(move || {
+        let mut c = Criterion::default().sample_size(20);
+        if let Some(filter) = filter {
+            c = c.with_filter(filter);
+        }
+        bench_token_estimation(&mut c);
+        bench_context_assembly(&mut c);
+        bench_embedding_search(&mut c);
+        bench_strategy_activity(&mut c);
+        c.final_summary();
+    })
+    .join()
+    .expect("benchmark thread panicked");
+}
+
+fn new_db() -> Arc<Database> {
+    Arc::new(Database::new(":memory:").expect("in-memory db"))
+}
+
+/// A chat session with `count` alternating user/assistant/tool messages
+fn seed_session(db: &Database, count: usize) -> i64 {
+    let channel = db
+        .create_channel_with_safe_mode("web", "perf", "perf-token", None, false)
+        .expect("create channel");
+    let session = db
+        .get_or_create_chat_session("web", channel.id, &format!("perf-{}", count), SessionScope::Dm, None)
+        .expect("create session");
+    let messages: Vec<_> = (0..count)
+        .map(|i| {
+            let (role, content) = match i % 4 {
+                0 => (MessageRole::User, format!("What is the price of token #{} on base and should I swap 50 USDC?", i)),
+                1 => (MessageRole::ToolCall, format!("🔧 **Tool Call:** `token_lookup`\n```json\n{{\"symbol\": \"TKN{}\", \"network\": \"base\"}}\n```", i)),
+                2 => (MessageRole::ToolResult, json!({ "symbol": format!("TKN{}", i), "price_usd": 1.0 + i as f64 / 100.0, "liquidity": 1_250_000, "pairs": ["USDC", "WETH"] }).to_string()),
+                _ => (MessageRole::Assistant, format!("Token #{} trades at ${:.2}. Liquidity is deep enough for a 50 USDC swap with under 0.3% price impact.", i, 1.0 + i as f64 / 100.0)),
+            };
+            (session.id, role, content, Some("perf-user".to_string()), Some("Perf".to_string()), None)
+        })
+        .collect();
+    db.add_session_messages_batch(&messages).expect("seed messages");
+    session.id
+}
+
+/// Deterministic pseudo-random unit-ish vector
+fn embedding(seed: usize) -> Vec<f32> {
+    let mut x = seed as u64 ^ 0x9E37_79B9_7F4A_7C15;
+    (0..EMBEDDING_DIMENSIONS)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            (x % 2_000) as f32 / 1_000.0 - 1.0
+        })
+        .collect()
+}
+
+fn seed_memories(db: &Database, count: usize) {
+    for i in 0..count {
+        let id = db
+            .insert_memory(
+                "fact",
+                &format!("User prefers swapping on base with slippage {}bps; watched token #{} last week", 30 + i % 70, i),
+                Some("preferences"),
+                Some("trading,base"),
+                5,
+                Some(PERF_IDENTITY),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .expect("seed memory");
+        db.upsert_memory_embedding(id, &embedding(i), "perf", EMBEDDING_DIMENSIONS as i32)
+            .expect("seed embedding");
+    }
+}
+
+fn seed_strategy_runs(db: &Database) -> Vec<i64> {
+    let started = chrono::Utc::now().to_rfc3339();
+    (0..STRATEGY_COUNT)
+        .map(|i| {
+            let strategy = db
+                .create_strategy(&CreateStrategyRequest {
+                    name: format!("Watch TKN{}", i),
+                    description: None,
+                    trigger: StrategyTrigger::Price {
+                        token: format!("TKN{}", i),
+                        network: "base".to_string(),
+                        above: Some(2.0),
+                        below: Some(0.5),
+                    },
+                    conditions: vec![],
+                    actions: vec![StrategyAction {
+                        tool: "say_to_user".to_string(),
+                        params: json!({ "message": "price alert" }),
+                    }],
+                    guardrails: StrategyGuardrails::default(),
+                    channel_id: None,
+                    chat_id: None,
+                    enabled: None,
+                })
+                .expect("seed strategy");
+            for run in 0..RUNS_PER_STRATEGY {
+                let status = if run % 3 == 0 { RUN_SUCCEEDED } else { RUN_SKIPPED };
+                db.record_strategy_run(&strategy, "price check", status, &[], None, &started)
+                    .expect("seed run");
+            }
+            strategy.id
+        })
+        .collect()
+}
+
+fn bench_token_estimation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("estimate_messages_tokens");
+    for &size in SESSION_SIZES {
+        let db = new_db();
+        let session_id = seed_session(&db, size);
+        let messages = db.get_session_messages(session_id).expect("load messages");
+        group.bench_with_input(BenchmarkId::from_parameter(size), &messages, |b, messages| {
+            b.iter(|| estimate_messages_tokens(messages))
+        });
+    }
+    group.finish();
+}
+
+fn bench_context_assembly(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime");
+    let mut group = c.benchmark_group("context_assembly");
+    for &size in SESSION_SIZES {
+        let db = new_db();
+        let session_id = seed_session(&db, size);
+        seed_memories(&db, 1_000);
+        let manager = ContextManager::new(db.clone());
+
+        group.bench_with_input(BenchmarkId::new("build_context", size), &session_id, |b, &id| {
+            b.iter(|| manager.build_context(id, 200))
+        });
+        group.bench_with_input(BenchmarkId::new("build_context_with_memories", size), &session_id, |b, &id| {
+            b.iter(|| rt.block_on(manager.build_context_with_memories(id, Some(PERF_IDENTITY), 200)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_embedding_search(c: &mut Criterion) {
+    let db = new_db();
+    seed_memories(&db, MEMORY_COUNT);
+    let candidates = db.list_memory_embeddings().expect("load embeddings");
+    let query = embedding(MEMORY_COUNT + 1);
+
+    let mut group = c.benchmark_group("embedding_search");
+    group.bench_function("load_10k", |b| b.iter(|| db.list_memory_embeddings().expect("load embeddings")));
+    group.bench_function("find_similar_10k", |b| {
+        b.iter(|| vector_search::find_similar(&query, &candidates, 10, 0.3))
+    });
+    group.finish();
+}
+
+fn bench_strategy_activity(c: &mut Criterion) {
+    let db = new_db();
+    let strategy_ids = seed_strategy_runs(&db);
+    let since = (chrono::Utc::now() - chrono::Duration::hours(24)).to_rfc3339();
+
+    let mut group = c.benchmark_group("strategy_activity");
+    group.bench_function("list_enabled_strategies", |b| b.iter(|| db.list_enabled_strategies().expect("list")));
+    group.bench_function("recent_runs_all", |b| b.iter(|| db.list_strategy_runs(None, 50).expect("runs")));
+    group.bench_function("recent_runs_one", |b| {
+        b.iter(|| db.list_strategy_runs(Some(strategy_ids[STRATEGY_COUNT / 2]), 50).expect("runs"))
+    });
+    group.bench_function("cooldown_counts", |b| {
+        b.iter(|| {
+            strategy_ids
+                .iter()
+                .map(|&id| db.count_strategy_runs_since(id, &since).expect("count"))
+                .sum::<i64>()
+        })
+    });
+    group.finish();
+}
@@ -79,8 +79,11 @@ fn spawn_hook_session(
         session_mode: Some("isolated".to_string()),
         selected_network: None,
         force_safe_mode: safe_mode,
+        shared_chat: false,
         platform_role_ids: vec![],
+        attachments: vec![],
         chat_context: None,
+        action: None,
     };
 
     log::info!(
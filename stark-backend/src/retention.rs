@@ -0,0 +1,111 @@
+//! Data retention worker
+//!
+//! Applies the policies stored in `retention_policies`:
+//! - `session_messages` — purge messages older than N days that were already
+//!   folded into a compaction summary
+//! - `memory:<type>` — purge memories of that type older than N days
+//! - `trash` — grace period before soft-deleted records are purged for good
+//!   (defaults to 7 days when unset)
+//!
+//! Condensed tool outputs (`tool_outputs`) are always dropped after 7 days.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use serde::Serialize;
+
+use crate::db::tables::retention::{SCOPE_MEMORY_PREFIX, SCOPE_SESSION_MESSAGES};
+use crate::db::Database;
+
+/// Raw outputs behind `fetch_full_output` handles are kept this long
+const TOOL_OUTPUT_RETENTION_DAYS: i64 = 7;
+
+/// Counts from a single retention pass
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RetentionReport {
+    pub messages_purged: usize,
+    pub memories_purged: usize,
+    pub trash_purged: usize,
+    pub tool_outputs_purged: usize,
+}
+
+impl RetentionReport {
+    pub fn total(&self) -> usize {
+        self.messages_purged + self.memories_purged + self.trash_purged + self.tool_outputs_purged
+    }
+}
+
+/// Run every enabled retention policy once, then purge expired trash.
+pub fn run_retention_pass(db: &Database) -> Result<RetentionReport, String> {
+    let policies = db
+        .list_retention_policies()
+        .map_err(|e| format!("Failed to load retention policies: {}", e))?;
+    let now = Utc::now();
+    let mut report = RetentionReport::default();
+
+    for policy in policies.iter().filter(|p| p.enabled && p.retention_days > 0) {
+        let cutoff = now - Duration::days(policy.retention_days);
+        if policy.scope == SCOPE_SESSION_MESSAGES {
+            report.messages_purged += db
+                .purge_compacted_session_messages(cutoff)
+                .map_err(|e| format!("Failed to purge session messages: {}", e))?;
+        } else if let Some(memory_type) = policy.scope.strip_prefix(SCOPE_MEMORY_PREFIX) {
+            report.memories_purged += db
+                .purge_memories_older_than(memory_type, cutoff)
+                .map_err(|e| format!("Failed to purge '{}' memories: {}", memory_type, e))?;
+        }
+    }
+
+    report.trash_purged = db
+        .purge_expired_deleted_records(now)
+        .map_err(|e| format!("Failed to purge expired trash: {}", e))?;
+    report.tool_outputs_purged = db
+        .purge_tool_outputs_before(now - Duration::days(TOOL_OUTPUT_RETENTION_DAYS))
+        .map_err(|e| format!("Failed to purge tool outputs: {}", e))?;
+
+    Ok(report)
+}
+
+/// Spawn the background retention worker (runs every `interval_secs`).
+pub fn spawn_retention_worker(db: Arc<Database>, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        interval.tick().await; // skip immediate tick
+        loop {
+            interval.tick().await;
+            match run_retention_pass(&db) {
+                Ok(report) if report.total() > 0 => log::info!(
+                    "[RETENTION] Pass complete: purged {} message(s), {} memory(ies), {} trashed record(s), {} tool output(s)",
+                    report.messages_purged,
+                    report.memories_purged,
+                    report.trash_purged,
+                    report.tool_outputs_purged
+                ),
+                Ok(_) => {}
+                Err(e) => log::error!("[RETENTION] Pass failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pass_applies_memory_policy_only_when_enabled() {
+        let db = Database::new(":memory:").unwrap();
+        db.insert_memory("daily_log", "log", None, None, 5, None, None, None, None, None, None, None)
+            .unwrap();
+        db.conn()
+            .execute("UPDATE memories SET created_at = datetime('now', '-60 days')", [])
+            .unwrap();
+
+        db.set_retention_policy("memory:daily_log", 30, false).unwrap();
+        assert_eq!(run_retention_pass(&db).unwrap().memories_purged, 0);
+
+        db.set_retention_policy("memory:daily_log", 30, true).unwrap();
+        assert_eq!(run_retention_pass(&db).unwrap().memories_purged, 1);
+        assert_eq!(db.count_memories().unwrap(), 0);
+    }
+}
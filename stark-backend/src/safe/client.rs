@@ -0,0 +1,299 @@
+//! Safe Transaction Service client
+//!
+//! Proposes multisig transactions and reads back their signatures. With a
+//! SAFE_API_KEY the authenticated gateway (api.safe.global) is used; without
+//! one, the per-network public hosts, which are rate limited.
+
+use ethers::abi::{encode, Token};
+use ethers::types::{Address, H256, U256};
+use ethers::utils::{keccak256, to_checksum};
+use serde_json::{json, Value};
+
+const DOMAIN_TYPE: &str = "EIP712Domain(uint256 chainId,address verifyingContract)";
+/// Safe < 1.3.0 signs without a chain id in the domain
+const LEGACY_DOMAIN_TYPE: &str = "EIP712Domain(address verifyingContract)";
+const SAFE_TX_TYPE: &str = "SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,uint256 nonce)";
+
+/// Chain short name (gateway path and Safe app prefix) and public host slug
+fn service_names(network: &str) -> Option<(&'static str, &'static str)> {
+    match network {
+        "base" => Some(("base", "base")),
+        "mainnet" => Some(("eth", "mainnet")),
+        "polygon" => Some(("matic", "polygon")),
+        _ => None,
+    }
+}
+
+/// Link co-signers can open to review and sign a proposal in the Safe app
+pub fn safe_app_url(network: &str, safe: &str, safe_tx_hash: &str) -> String {
+    let prefix = service_names(network).map(|(short, _)| short).unwrap_or(network);
+    format!(
+        "https://app.safe.global/transactions/tx?safe={}:{}&id=multisig_{}_{}",
+        prefix, safe, safe, safe_tx_hash
+    )
+}
+
+/// A plain CALL from the Safe. Delegate calls and gas refunds are never proposed.
+#[derive(Debug, Clone)]
+pub struct SafeTx {
+    pub to: Address,
+    pub value: U256,
+    pub data: Vec<u8>,
+    pub nonce: u64,
+}
+
+impl SafeTx {
+    /// EIP-712 hash the owners sign. `chain_id` is None for Safes older than 1.3.0.
+    pub fn hash(&self, chain_id: Option<u64>, safe: Address) -> H256 {
+        let domain_separator = match chain_id {
+            Some(chain_id) => keccak256(encode(&[
+                Token::FixedBytes(keccak256(DOMAIN_TYPE).to_vec()),
+                Token::Uint(U256::from(chain_id)),
+                Token::Address(safe),
+            ])),
+            None => keccak256(encode(&[
+                Token::FixedBytes(keccak256(LEGACY_DOMAIN_TYPE).to_vec()),
+                Token::Address(safe),
+            ])),
+        };
+        let struct_hash = keccak256(encode(&[
+            Token::FixedBytes(keccak256(SAFE_TX_TYPE).to_vec()),
+            Token::Address(self.to),
+            Token::Uint(self.value),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+            Token::Uint(U256::zero()), // operation: CALL
+            Token::Uint(U256::zero()), // safeTxGas
+            Token::Uint(U256::zero()), // baseGas
+            Token::Uint(U256::zero()), // gasPrice
+            Token::Address(Address::zero()), // gasToken
+            Token::Address(Address::zero()), // refundReceiver
+            Token::Uint(U256::from(self.nonce)),
+        ]));
+
+        let mut preimage = Vec::with_capacity(66);
+        preimage.extend_from_slice(&[0x19, 0x01]);
+        preimage.extend_from_slice(&domain_separator);
+        preimage.extend_from_slice(&struct_hash);
+        H256(keccak256(preimage))
+    }
+}
+
+/// Safe account state
+#[derive(Debug, Clone)]
+pub struct SafeInfo {
+    pub nonce: u64,
+    pub threshold: u64,
+    pub owners: Vec<String>,
+    pub version: Option<String>,
+}
+
+impl SafeInfo {
+    pub fn is_owner(&self, address: &str) -> bool {
+        self.owners.iter().any(|o| o.eq_ignore_ascii_case(address))
+    }
+
+    /// Whether the Safe signs with a chain-bound domain (1.3.0 and later)
+    pub fn uses_chain_id(&self) -> bool {
+        let Some(version) = self.version.as_deref() else {
+            return true;
+        };
+        let mut parts = version.split(['.', '+', '-']).map(|p| p.parse::<u32>().unwrap_or(0));
+        let major = parts.next().unwrap_or(0);
+        let minor = parts.next().unwrap_or(0);
+        (major, minor) >= (1, 3)
+    }
+}
+
+/// A multisig transaction as the service sees it
+#[derive(Debug, Clone)]
+pub struct MultisigTxStatus {
+    /// Owners that have signed, in submission order
+    pub confirmations: Vec<String>,
+    pub confirmations_required: Option<u64>,
+    pub is_executed: bool,
+    pub is_successful: Option<bool>,
+    pub transaction_hash: Option<String>,
+}
+
+/// Numbers arrive as JSON numbers or decimal strings depending on service version
+fn value_u64(v: &Value) -> Option<u64> {
+    v.as_u64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+}
+
+fn parse_safe_info(v: &Value) -> Result<SafeInfo, String> {
+    Ok(SafeInfo {
+        nonce: value_u64(&v["nonce"]).ok_or("Safe info is missing 'nonce'")?,
+        threshold: value_u64(&v["threshold"]).ok_or("Safe info is missing 'threshold'")?,
+        owners: v["owners"]
+            .as_array()
+            .map(|a| a.iter().filter_map(|o| o.as_str().map(str::to_string)).collect())
+            .unwrap_or_default(),
+        version: v["version"].as_str().map(str::to_string),
+    })
+}
+
+fn parse_multisig_tx(v: &Value) -> MultisigTxStatus {
+    MultisigTxStatus {
+        confirmations: v["confirmations"]
+            .as_array()
+            .map(|a| {
+                a.iter()
+                    .filter_map(|c| c["owner"].as_str().map(|o| o.to_lowercase()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        confirmations_required: value_u64(&v["confirmationsRequired"]),
+        is_executed: v["isExecuted"].as_bool().unwrap_or(false),
+        is_successful: v["isSuccessful"].as_bool(),
+        transaction_hash: v["transactionHash"].as_str().map(str::to_string),
+    }
+}
+
+/// Client for one network's Safe Transaction Service
+pub struct SafeTxService {
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl SafeTxService {
+    pub fn new(network: &str, api_key: Option<String>) -> Result<Self, String> {
+        let (short, slug) = service_names(network)
+            .ok_or_else(|| format!("No Safe transaction service for network '{}'", network))?;
+        let base_url = match api_key {
+            Some(_) => format!("https://api.safe.global/tx-service/{}", short),
+            None => format!("https://safe-transaction-{}.safe.global", slug),
+        };
+        Ok(Self { base_url, api_key })
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value, String> {
+        let request = match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Safe transaction service request failed: {}", e))?;
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            let hint = if status.as_u16() == 401 || status.as_u16() == 429 {
+                " (set SAFE_API_KEY on the API keys page)"
+            } else {
+                ""
+            };
+            return Err(format!("Safe transaction service returned {}{}: {}", status, hint, body));
+        }
+        if body.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&body).map_err(|e| format!("Invalid Safe transaction service response: {}", e))
+    }
+
+    async fn get(&self, path: &str) -> Result<Value, String> {
+        let url = format!("{}{}", self.base_url, path);
+        self.send(crate::http::shared_client().get(url)).await
+    }
+
+    /// Owners, threshold and on-chain nonce of a Safe
+    pub async fn get_safe(&self, safe: Address) -> Result<SafeInfo, String> {
+        let v = self.get(&format!("/api/v1/safes/{}/", to_checksum(&safe, None))).await?;
+        parse_safe_info(&v)
+    }
+
+    /// Nonce for a new proposal: after the on-chain nonce and anything already queued
+    pub async fn next_nonce(&self, safe: Address, onchain_nonce: u64) -> Result<u64, String> {
+        let v = self
+            .get(&format!(
+                "/api/v1/safes/{}/multisig-transactions/?executed=false&nonce__gte={}&ordering=-nonce&limit=1",
+                to_checksum(&safe, None),
+                onchain_nonce
+            ))
+            .await?;
+        let queued_max = v["results"]
+            .as_array()
+            .and_then(|r| r.first())
+            .and_then(|tx| value_u64(&tx["nonce"]));
+        Ok(queued_max.map_or(onchain_nonce, |n| onchain_nonce.max(n + 1)))
+    }
+
+    /// Submit a transaction with the proposer's signature
+    pub async fn propose(
+        &self,
+        safe: Address,
+        tx: &SafeTx,
+        safe_tx_hash: H256,
+        sender: Address,
+        signature: &str,
+    ) -> Result<(), String> {
+        let zero = to_checksum(&Address::zero(), None);
+        let body = json!({
+            "to": to_checksum(&tx.to, None),
+            "value": tx.value.to_string(),
+            "data": if tx.data.is_empty() { Value::Null } else { json!(format!("0x{}", hex::encode(&tx.data))) },
+            "operation": 0,
+            "safeTxGas": "0",
+            "baseGas": "0",
+            "gasPrice": "0",
+            "gasToken": zero,
+            "refundReceiver": zero,
+            "nonce": tx.nonce,
+            "contractTransactionHash": format!("{:?}", safe_tx_hash),
+            "sender": to_checksum(&sender, None),
+            "signature": signature,
+            "origin": "starkbot",
+        });
+        let url = format!("{}/api/v1/safes/{}/multisig-transactions/", self.base_url, to_checksum(&safe, None));
+        self.send(crate::http::shared_client().post(url).json(&body)).await.map(|_| ())
+    }
+
+    /// Signatures and execution state of a proposed transaction
+    pub async fn get_transaction(&self, safe_tx_hash: &str) -> Result<MultisigTxStatus, String> {
+        let v = self.get(&format!("/api/v1/multisig-transactions/{}/", safe_tx_hash)).await?;
+        Ok(parse_multisig_tx(&v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_tx_hash_is_bound_to_chain_safe_and_nonce() {
+        let safe: Address = "0x00000000000000000000000000000000000000aa".parse().unwrap();
+        let tx = SafeTx {
+            to: "0x00000000000000000000000000000000000000bb".parse().unwrap(),
+            value: U256::from(1_000u64),
+            data: vec![],
+            nonce: 3,
+        };
+        let hash = tx.hash(Some(8453), safe);
+        assert_eq!(hash, tx.hash(Some(8453), safe));
+        assert_ne!(hash, tx.hash(Some(1), safe));
+        assert_ne!(hash, tx.hash(None, safe));
+        assert_ne!(hash, SafeTx { nonce: 4, ..tx.clone() }.hash(Some(8453), safe));
+    }
+
+    #[test]
+    fn test_parses_service_responses() {
+        let info = parse_safe_info(&json!({
+            "nonce": "7", "threshold": 2, "owners": ["0xAbC", "0xdef"], "version": "1.1.1"
+        }))
+        .unwrap();
+        assert_eq!((info.nonce, info.threshold), (7, 2));
+        assert!(info.is_owner("0xabc"));
+        assert!(!info.uses_chain_id());
+
+        let status = parse_multisig_tx(&json!({
+            "confirmationsRequired": 2,
+            "confirmations": [{ "owner": "0xAbC" }, { "owner": "0xdef" }],
+            "isExecuted": true,
+            "isSuccessful": true,
+            "transactionHash": "0x123"
+        }));
+        assert_eq!(status.confirmations, vec!["0xabc", "0xdef"]);
+        assert_eq!(status.confirmations_required, Some(2));
+        assert!(status.is_executed);
+    }
+}
@@ -0,0 +1,226 @@
+//! Safe (Gnosis) multisig support
+//!
+//! Instead of signing and broadcasting from its own wallet, the bot can propose
+//! a transaction to a Safe it co-owns (`propose_safe_tx`): it signs the Safe
+//! transaction hash as one owner and submits it to the Safe Transaction
+//! Service, where the other owners review and sign. Every proposal is tracked
+//! in `safe_proposals`; a background worker polls the service and notifies the
+//! proposing chat (and the dashboard) as co-signers confirm, when the threshold
+//! is reached, and when the transaction is executed or replaced.
+
+pub mod client;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ethers::types::{Address, H256};
+use serde_json::json;
+
+use crate::channels::outbound;
+use crate::controllers::api_keys::ApiKeyId;
+use crate::db::tables::safe_proposals::{
+    SafeProposal, SAFE_PROPOSAL_EXECUTED, SAFE_PROPOSAL_FAILED, SAFE_PROPOSAL_PENDING, SAFE_PROPOSAL_REPLACED,
+};
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::wallet::WalletProvider;
+
+use client::{safe_app_url, SafeTxService};
+
+/// SAFE_API_KEY from the API keys page, falling back to the environment
+pub fn api_key(db: &Database) -> Option<String> {
+    let key_id = ApiKeyId::SafeApiKey;
+    db.get_api_key(key_id.as_str())
+        .ok()
+        .flatten()
+        .map(|k| k.api_key)
+        .filter(|k| !k.is_empty())
+        .or_else(|| std::env::var(key_id.as_str()).ok().filter(|k| !k.is_empty()))
+}
+
+/// Sign a Safe transaction hash as an owner and return the hex signature.
+///
+/// Wallets that can sign raw hashes produce a plain ECDSA signature (v = 27/28).
+/// Flash wallets can't, so they sign the hash as an EIP-191 message instead,
+/// which the Safe contract accepts as an `eth_sign` signature when v is shifted by 4.
+pub async fn sign_safe_tx_hash(wallet: &Arc<dyn WalletProvider>, hash: H256) -> Result<String, String> {
+    let signature = match wallet.sign_hash(hash).await {
+        Ok(mut sig) => {
+            if sig.v < 27 {
+                sig.v += 27;
+            }
+            sig
+        }
+        Err(e) => {
+            log::debug!("[SAFE] sign_hash unavailable ({}), using eth_sign signature", e);
+            let mut sig = wallet.sign_message(hash.as_bytes()).await?;
+            if sig.v < 27 {
+                sig.v += 27;
+            }
+            sig.v += 4;
+            sig
+        }
+    };
+    Ok(format!("0x{}", hex::encode(signature.to_vec())))
+}
+
+/// Tell the dashboard and the proposing chat about a proposal update
+async fn notify(db: &Database, broadcaster: Option<&EventBroadcaster>, proposal: &SafeProposal, text: &str) {
+    if let Some(broadcaster) = broadcaster {
+        broadcaster.broadcast(GatewayEvent::custom(
+            "safe.proposal_updated",
+            json!({
+                "safe_tx_hash": proposal.safe_tx_hash,
+                "safe_address": proposal.safe_address,
+                "status": proposal.status,
+                "confirmations": proposal.confirmations,
+                "threshold": proposal.threshold,
+                "message": text,
+            }),
+        ));
+    }
+    if let (Some(channel_id), Some(chat_id)) = (proposal.channel_id, proposal.chat_id.as_deref()) {
+        if let Err(e) = outbound::send_direct(db, channel_id, chat_id, text, &[]).await {
+            log::warn!("[SAFE] Failed to notify chat about {}: {}", proposal.safe_tx_hash, e);
+        }
+    }
+}
+
+fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(10)]
+}
+
+/// Sync one pending proposal with the transaction service, sending notifications
+/// for new signatures and final outcomes. `safe_nonce` is the Safe's current
+/// on-chain nonce, used to detect proposals replaced by another transaction.
+/// Returns the updated proposal.
+pub async fn refresh_proposal(
+    db: &Database,
+    broadcaster: Option<&EventBroadcaster>,
+    service: &SafeTxService,
+    proposal: &SafeProposal,
+    safe_nonce: Option<u64>,
+) -> Result<SafeProposal, String> {
+    let status = service.get_transaction(&proposal.safe_tx_hash).await?;
+    let mut updated = proposal.clone();
+    let link = safe_app_url(&proposal.network, &proposal.safe_address, &proposal.safe_tx_hash);
+
+    let new_signers: Vec<&String> = status
+        .confirmations
+        .iter()
+        .filter(|owner| !proposal.confirmed_by.iter().any(|c| c.eq_ignore_ascii_case(owner)))
+        .collect();
+    let threshold = status.confirmations_required.map(|t| t as i64).unwrap_or(proposal.threshold);
+
+    if !new_signers.is_empty() || threshold != proposal.threshold {
+        db.update_safe_proposal_confirmations(&proposal.safe_tx_hash, &status.confirmations, threshold)
+            .map_err(|e| format!("Database error: {}", e))?;
+        updated.confirmed_by = status.confirmations.clone();
+        updated.confirmations = status.confirmations.len() as i64;
+        updated.threshold = threshold;
+
+        if !new_signers.is_empty() && !status.is_executed {
+            let signers: Vec<&str> = new_signers.iter().map(|s| s.as_str()).collect();
+            let mut text = format!(
+                "✍️ Safe tx `{}` ({}) signed by {} — {}/{} signatures.",
+                short_hash(&proposal.safe_tx_hash),
+                proposal.description,
+                signers.join(", "),
+                updated.confirmations,
+                threshold
+            );
+            if updated.confirmations >= threshold {
+                text.push_str(&format!("\n\nThreshold reached — ready to execute: {}", link));
+            }
+            notify(db, broadcaster, &updated, &text).await;
+        }
+    }
+
+    if status.is_executed {
+        let (final_status, text) = if status.is_successful == Some(false) {
+            (SAFE_PROPOSAL_FAILED, format!("❌ Safe tx `{}` ({}) was executed but reverted.", short_hash(&proposal.safe_tx_hash), proposal.description))
+        } else {
+            (SAFE_PROPOSAL_EXECUTED, format!("✅ Safe tx `{}` ({}) executed.", short_hash(&proposal.safe_tx_hash), proposal.description))
+        };
+        let text = match &status.transaction_hash {
+            Some(hash) => format!("{}\nTransaction: `{}`", text, hash),
+            None => text,
+        };
+        db.finish_safe_proposal(&proposal.safe_tx_hash, final_status, status.transaction_hash.as_deref())
+            .map_err(|e| format!("Database error: {}", e))?;
+        updated.status = final_status.to_string();
+        updated.tx_hash = status.transaction_hash.clone();
+        notify(db, broadcaster, &updated, &text).await;
+    } else if safe_nonce.is_some_and(|n| n > proposal.nonce as u64) {
+        // Another transaction with this nonce was executed; this one can never run
+        db.finish_safe_proposal(&proposal.safe_tx_hash, SAFE_PROPOSAL_REPLACED, None)
+            .map_err(|e| format!("Database error: {}", e))?;
+        updated.status = SAFE_PROPOSAL_REPLACED.to_string();
+        let text = format!(
+            "Safe tx `{}` ({}) was replaced by another transaction with nonce {} and will not execute.",
+            short_hash(&proposal.safe_tx_hash),
+            proposal.description,
+            proposal.nonce
+        );
+        notify(db, broadcaster, &updated, &text).await;
+    }
+
+    Ok(updated)
+}
+
+/// Refresh every pending proposal once. Returns how many were checked.
+pub async fn run_status_pass(db: &Database, broadcaster: &EventBroadcaster) -> usize {
+    let pending = match db.list_safe_proposals(Some(SAFE_PROPOSAL_PENDING), 200) {
+        Ok(pending) => pending,
+        Err(e) => {
+            log::error!("[SAFE] Failed to load pending proposals: {}", e);
+            return 0;
+        }
+    };
+    if pending.is_empty() {
+        return 0;
+    }
+
+    let key = api_key(db);
+    // One service client and nonce lookup per (network, safe)
+    let mut nonces: HashMap<(String, String), Option<u64>> = HashMap::new();
+    let mut checked = 0;
+    for proposal in &pending {
+        let service = match SafeTxService::new(&proposal.network, key.clone()) {
+            Ok(s) => s,
+            Err(e) => {
+                log::warn!("[SAFE] Skipping {}: {}", proposal.safe_tx_hash, e);
+                continue;
+            }
+        };
+        let nonce_key = (proposal.network.clone(), proposal.safe_address.to_lowercase());
+        if !nonces.contains_key(&nonce_key) {
+            let nonce = match proposal.safe_address.parse::<Address>() {
+                Ok(safe) => service.get_safe(safe).await.ok().map(|info| info.nonce),
+                Err(_) => None,
+            };
+            nonces.insert(nonce_key.clone(), nonce);
+        }
+        match refresh_proposal(db, Some(broadcaster), &service, proposal, nonces[&nonce_key]).await {
+            Ok(_) => checked += 1,
+            Err(e) => log::warn!("[SAFE] Failed to refresh {}: {}", proposal.safe_tx_hash, e),
+        }
+    }
+    checked
+}
+
+/// Spawn the background worker that tracks pending proposals (every `interval_secs`).
+pub fn spawn_status_worker(
+    db: Arc<Database>,
+    broadcaster: Arc<EventBroadcaster>,
+    interval_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            run_status_pass(&db, &broadcaster).await;
+        }
+    })
+}
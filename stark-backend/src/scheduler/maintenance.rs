@@ -0,0 +1,256 @@
+//! Self-maintenance jobs configured on the global heartbeat config
+//!
+//! Each job runs on its own interval from the scheduler tick (leader only),
+//! independent of whether the heartbeat itself is enabled, so operators get
+//! upkeep without an external cron. Every run is recorded in
+//! `maintenance_runs` and the latest one per job is reported by
+//! `/api/services/health`.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::db::tables::maintenance_runs::MaintenanceRun;
+use crate::db::Database;
+use crate::memory::EmbeddingGenerator;
+use crate::models::MaintenanceJobConfig;
+
+pub const JOB_VACUUM_DB: &str = "vacuum_db";
+pub const JOB_PRUNE_EXPIRED_FILES: &str = "prune_expired_files";
+pub const JOB_REEMBED_SKILLS: &str = "reembed_skills";
+pub const JOB_VERIFY_MODULE_CHECKSUMS: &str = "verify_module_checksums";
+
+pub const JOBS: &[&str] = &[JOB_VACUUM_DB, JOB_PRUNE_EXPIRED_FILES, JOB_REEMBED_SKILLS, JOB_VERIFY_MODULE_CHECKSUMS];
+
+/// Public files older than this are pruned unless the job sets max_age_days
+const DEFAULT_MAX_AGE_DAYS: i64 = 30;
+/// Shortest interval a job may run at
+const MIN_INTERVAL_MINUTES: i64 = 5;
+
+/// Check a job list before it is saved
+pub fn validate_jobs(jobs: &[MaintenanceJobConfig]) -> Result<(), String> {
+    for (i, job) in jobs.iter().enumerate() {
+        if !JOBS.contains(&job.job.as_str()) {
+            return Err(format!("Unknown maintenance job '{}' (expected one of: {})", job.job, JOBS.join(", ")));
+        }
+        if jobs[..i].iter().any(|other| other.job == job.job) {
+            return Err(format!("Maintenance job '{}' is listed twice", job.job));
+        }
+        if job.interval_minutes < MIN_INTERVAL_MINUTES {
+            return Err(format!("{}: interval_minutes must be at least {}", job.job, MIN_INTERVAL_MINUTES));
+        }
+        if job.max_age_days.is_some_and(|days| days < 1) {
+            return Err(format!("{}: max_age_days must be at least 1", job.job));
+        }
+    }
+    Ok(())
+}
+
+/// Whether a job is due given when it last ran
+pub fn is_due(job: &MaintenanceJobConfig, last_run: Option<&MaintenanceRun>, now: DateTime<Utc>) -> bool {
+    if !job.enabled {
+        return false;
+    }
+    match last_run.and_then(|run| DateTime::parse_from_rfc3339(&run.started_at).ok()) {
+        Some(last) => now - last.with_timezone(&Utc) >= Duration::minutes(job.interval_minutes),
+        None => true,
+    }
+}
+
+/// Run every due job of the global heartbeat config, one after another
+pub async fn run_due_jobs(db: &Arc<Database>, embedding_gen: Option<&Arc<dyn EmbeddingGenerator + Send + Sync>>) {
+    let jobs = match db.get_or_create_heartbeat_config(None) {
+        Ok(config) => config.maintenance_jobs,
+        Err(e) => {
+            log::warn!("[MAINTENANCE] Failed to load heartbeat config: {}", e);
+            return;
+        }
+    };
+    for job in jobs {
+        let last_run = db.last_maintenance_run(&job.job).ok().flatten();
+        if is_due(&job, last_run.as_ref(), Utc::now()) {
+            let _ = run_job(db, embedding_gen, &job).await;
+        }
+    }
+}
+
+/// Run one job now and record the result
+pub async fn run_job(
+    db: &Arc<Database>,
+    embedding_gen: Option<&Arc<dyn EmbeddingGenerator + Send + Sync>>,
+    job: &MaintenanceJobConfig,
+) -> Result<String, String> {
+    let started_at = Utc::now();
+    let timer = std::time::Instant::now();
+    log::info!("[MAINTENANCE] Running {}", job.job);
+
+    let result = match job.job.as_str() {
+        JOB_VACUUM_DB => vacuum(db),
+        JOB_PRUNE_EXPIRED_FILES => prune_files(
+            Path::new(&crate::config::public_dir()),
+            job.max_age_days.unwrap_or(DEFAULT_MAX_AGE_DAYS),
+            started_at,
+        ),
+        JOB_REEMBED_SKILLS => match embedding_gen {
+            Some(generator) => match crate::skills::embeddings::reembed_changed_skills(db, generator).await {
+                Ok((_, 0)) => Ok("No changed skills".to_string()),
+                Ok((done, needed)) if done == needed => Ok(format!("Re-embedded {} skill(s)", done)),
+                Ok((done, needed)) => Err(format!("Re-embedded only {} of {} changed skill(s)", done, needed)),
+                Err(e) => Err(e),
+            },
+            None => Err("No embedding generator configured".to_string()),
+        },
+        JOB_VERIFY_MODULE_CHECKSUMS => verify_module_checksums(db),
+        other => Err(format!("Unknown maintenance job '{}'", other)),
+    };
+
+    let duration_ms = timer.elapsed().as_millis() as i64;
+    match &result {
+        Ok(summary) => log::info!("[MAINTENANCE] {} done in {}ms: {}", job.job, duration_ms, summary),
+        Err(e) => log::warn!("[MAINTENANCE] {} failed after {}ms: {}", job.job, duration_ms, e),
+    }
+    if let Err(e) = db.record_maintenance_run(&job.job, started_at, duration_ms, &result) {
+        log::warn!("[MAINTENANCE] Failed to record {} run: {}", job.job, e);
+    }
+    result
+}
+
+fn db_size(db: &Database) -> rusqlite::Result<i64> {
+    db.conn().query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Rebuild the database file to reclaim free pages
+fn vacuum(db: &Database) -> Result<String, String> {
+    let before = db_size(db).map_err(|e| e.to_string())?;
+    {
+        let conn = db.conn();
+        conn.execute_batch("PRAGMA optimize; VACUUM;").map_err(|e| format!("VACUUM failed: {}", e))?;
+    }
+    let after = db_size(db).map_err(|e| e.to_string())?;
+    Ok(format!("Database {} KB -> {} KB", before / 1024, after / 1024))
+}
+
+/// Delete public files last modified more than `max_age_days` ago
+fn prune_files(dir: &Path, max_age_days: i64, now: DateTime<Utc>) -> Result<String, String> {
+    if !dir.exists() {
+        return Ok("No public files".to_string());
+    }
+    let cutoff = now - Duration::days(max_age_days);
+    let (mut removed, mut freed) = (0usize, 0u64);
+    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+        let Ok(meta) = entry.metadata() else { continue };
+        let expired = meta
+            .modified()
+            .map(|modified| DateTime::<Utc>::from(modified) < cutoff)
+            .unwrap_or(false);
+        if meta.is_file() && expired {
+            match std::fs::remove_file(entry.path()) {
+                Ok(()) => {
+                    removed += 1;
+                    freed += meta.len();
+                }
+                Err(e) => log::warn!("[MAINTENANCE] Failed to remove {}: {}", entry.path().display(), e),
+            }
+        }
+    }
+    Ok(format!(
+        "Removed {} public file(s) older than {} days ({} KB)",
+        removed,
+        max_age_days,
+        freed / 1024
+    ))
+}
+
+/// Hash every pinned module service binary and compare it with its pin
+fn verify_module_checksums(db: &Database) -> Result<String, String> {
+    let (mut verified, mut mismatched) = (0usize, Vec::new());
+    for service in crate::modules::loader::get_dynamic_service_binaries() {
+        if service.command.is_some() || !service.binary_path.exists() {
+            continue;
+        }
+        let pinned = db.get_installed_module(&service.name).ok().flatten().and_then(|m| m.binary_sha256);
+        let Some(pinned) = pinned else { continue };
+        match crate::modules::sandbox::sha256_file(&service.binary_path) {
+            Ok(actual) if actual == pinned => verified += 1,
+            Ok(_) => mismatched.push(service.name),
+            Err(e) => mismatched.push(format!("{} (unreadable: {})", service.name, e)),
+        }
+    }
+    if mismatched.is_empty() {
+        Ok(format!("{} module binaries match their pinned checksums", verified))
+    } else {
+        Err(format!(
+            "Checksum mismatch for {} (they will be refused on next launch; reinstall them)",
+            mismatched.join(", ")
+        ))
+    }
+}
+
+/// Per-job status for the health report
+#[derive(Debug, Serialize)]
+pub struct JobStatus {
+    #[serde(flatten)]
+    pub config: MaintenanceJobConfig,
+    pub last_run: Option<MaintenanceRun>,
+    pub next_run_at: Option<String>,
+}
+
+pub fn status(db: &Database) -> Vec<JobStatus> {
+    let jobs = db.get_or_create_heartbeat_config(None).map(|c| c.maintenance_jobs).unwrap_or_default();
+    jobs.into_iter()
+        .map(|config| {
+            let last_run = db.last_maintenance_run(&config.job).ok().flatten();
+            let next_run_at = config.enabled.then(|| {
+                last_run
+                    .as_ref()
+                    .and_then(|run| DateTime::parse_from_rfc3339(&run.started_at).ok())
+                    .map(|last| (last.with_timezone(&Utc) + Duration::minutes(config.interval_minutes)).to_rfc3339())
+                    .unwrap_or_else(|| Utc::now().to_rfc3339())
+            });
+            JobStatus { config, last_run, next_run_at }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(name: &str, interval_minutes: i64) -> MaintenanceJobConfig {
+        MaintenanceJobConfig { job: name.to_string(), enabled: true, interval_minutes, max_age_days: None }
+    }
+
+    #[test]
+    fn test_validate_and_schedule() {
+        assert!(validate_jobs(&[job(JOB_VACUUM_DB, 1440), job(JOB_REEMBED_SKILLS, 60)]).is_ok());
+        assert!(validate_jobs(&[job("defrag", 60)]).is_err());
+        assert!(validate_jobs(&[job(JOB_VACUUM_DB, 60), job(JOB_VACUUM_DB, 120)]).is_err());
+        assert!(validate_jobs(&[job(JOB_VACUUM_DB, 1)]).is_err());
+
+        let db = Database::new(":memory:").unwrap();
+        let now = Utc::now();
+        let vacuum_job = job(JOB_VACUUM_DB, 60);
+        assert!(is_due(&vacuum_job, None, now));
+        db.record_maintenance_run(JOB_VACUUM_DB, now, 5, &vacuum(&db)).unwrap();
+        let last = db.last_maintenance_run(JOB_VACUUM_DB).unwrap().unwrap();
+        assert!(last.success);
+        assert!(!is_due(&vacuum_job, Some(&last), now + Duration::minutes(30)));
+        assert!(is_due(&vacuum_job, Some(&last), now + Duration::minutes(61)));
+        assert!(!is_due(&MaintenanceJobConfig { enabled: false, ..vacuum_job }, None, now));
+
+        let dir = std::env::temp_dir().join(format!("stark-prune-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("report.html"), "old").unwrap();
+        // A file written now is only expired from a week in the future
+        assert!(prune_files(&dir, 3, now).unwrap().starts_with("Removed 0"));
+        assert!(prune_files(&dir, 3, now + Duration::days(7)).unwrap().starts_with("Removed 1"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
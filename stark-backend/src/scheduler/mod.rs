@@ -1,3 +1,4 @@
+pub mod maintenance;
 pub mod runner;
 
 pub use runner::{Scheduler, SchedulerConfig};
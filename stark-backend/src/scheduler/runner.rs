@@ -6,6 +6,7 @@ use crate::gateway::protocol::GatewayEvent;
 use crate::models::{CronJob, HeartbeatConfig, ScheduleType};
 use crate::wallet;
 use chrono::{DateTime, Duration, Local, NaiveTime, Utc, Weekday, Datelike, Timelike};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::oneshot;
 use tokio::time::{interval, timeout, Duration as TokioDuration};
@@ -63,6 +64,12 @@ pub struct Scheduler {
     /// Wallet provider for x402 payments in scheduled tasks (heartbeats, cron jobs)
     wallet_provider: Option<Arc<dyn wallet::WalletProvider>>,
     skill_registry: Option<Arc<crate::skills::SkillRegistry>>,
+    /// Cluster coordinator — cron and heartbeats only run on the elected leader
+    cluster: Option<Arc<crate::cluster::Cluster>>,
+    /// Embedding generator for the reembed_skills maintenance job
+    embedding_generator: Option<Arc<dyn crate::memory::EmbeddingGenerator + Send + Sync>>,
+    /// Set while a maintenance pass runs, so passes never overlap
+    maintenance_running: Arc<AtomicBool>,
 }
 
 impl Scheduler {
@@ -83,9 +90,34 @@ impl Scheduler {
             config,
             wallet_provider,
             skill_registry,
+            cluster: None,
+            embedding_generator: None,
+            maintenance_running: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Set the cluster coordinator (singleton work is skipped unless this instance leads)
+    pub fn with_cluster(mut self, cluster: Arc<crate::cluster::Cluster>) -> Self {
+        self.cluster = Some(cluster);
+        self
+    }
+
+    /// Set the embedding generator used by maintenance jobs
+    pub fn with_embedding_generator(
+        mut self,
+        generator: Arc<dyn crate::memory::EmbeddingGenerator + Send + Sync>,
+    ) -> Self {
+        self.embedding_generator = Some(generator);
+        self
+    }
+
+    /// Whether this instance should run singleton scheduler work
+    fn is_leader(&self) -> bool {
+        self.cluster
+            .as_ref()
+            .is_none_or(|c| c.is_leader(crate::cluster::LEASE_SCHEDULER))
+    }
+
     /// Compatibility method - db_url is no longer needed with connection pool
     #[deprecated(note = "Use new() instead - db_url is no longer needed with r2d2 connection pool")]
     pub fn new_with_db_url(
@@ -126,8 +158,12 @@ impl Scheduler {
 
     /// Process one tick of the scheduler
     async fn tick(&self) {
+        // Kanban picks are atomic, so every instance may work the board;
+        // cron, heartbeats and cleanup run only on the scheduler leader.
+        let is_leader = self.is_leader();
+
         // Process cron jobs
-        if self.config.cron_enabled {
+        if self.config.cron_enabled && is_leader {
             if let Err(e) = self.process_cron_jobs().await {
                 log::error!("Error processing cron jobs: {}", e);
             }
@@ -138,11 +174,18 @@ impl Scheduler {
             log::error!("Error processing kanban tasks: {}", e);
         }
 
+        if !is_leader {
+            return;
+        }
+
         // Process heartbeats (always enabled - individual configs control their own enabled state)
         if let Err(e) = self.process_heartbeats().await {
             log::error!("Error processing heartbeats: {}", e);
         }
 
+        // Self-maintenance jobs from the heartbeat config (in the background)
+        self.spawn_maintenance();
+
         // Run periodic cleanup tasks once per hour (at minute 0, within first poll window)
         let now = Local::now();
         if now.minute() == 0 && now.second() < self.config.poll_interval_secs as u32 {
@@ -150,6 +193,20 @@ impl Scheduler {
         }
     }
 
+    /// Run due maintenance jobs unless a previous pass is still going
+    fn spawn_maintenance(&self) {
+        if self.maintenance_running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let db = Arc::clone(&self.db);
+        let generator = self.embedding_generator.clone();
+        let running = Arc::clone(&self.maintenance_running);
+        tokio::spawn(async move {
+            super::maintenance::run_due_jobs(&db, generator.as_ref()).await;
+            running.store(false, Ordering::SeqCst);
+        });
+    }
+
     /// Run periodic cleanup tasks (called approximately once per hour)
     fn run_periodic_cleanup(&self) {
         // Cleanup old Twitter processed mentions (keep last 30 days)
@@ -163,6 +220,17 @@ impl Scheduler {
             }
         }
 
+        // Cleanup old Farcaster processed casts (keep last 30 days)
+        match self.db.cleanup_old_processed_casts(30) {
+            Ok(count) if count > 0 => {
+                log::info!("Scheduler: Cleaned up {} old Farcaster processed casts", count);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log::error!("Scheduler: Failed to cleanup Farcaster casts: {}", e);
+            }
+        }
+
         // Cleanup old safe mode channels (keep last 60 minutes - more aggressive than FIFO logic)
         match self.db.cleanup_old_safe_mode_channels(60) {
             Ok(count) if count > 0 => {
@@ -265,8 +333,11 @@ impl Scheduler {
             session_mode: Some("isolated".to_string()),
             selected_network: None,
             force_safe_mode: false,
+            shared_chat: false,
             platform_role_ids: vec![],
+            attachments: vec![],
             chat_context: None,
+            action: None,
         };
 
         // Execute with 10-minute timeout (same as cron default)
@@ -340,6 +411,9 @@ impl Scheduler {
             config: self.config.clone(),
             wallet_provider: self.wallet_provider.clone(),
             skill_registry: self.skill_registry.clone(),
+            cluster: self.cluster.clone(),
+            embedding_generator: self.embedding_generator.clone(),
+            maintenance_running: Arc::clone(&self.maintenance_running),
         }
     }
 
@@ -425,8 +499,11 @@ impl Scheduler {
             session_mode: Some(job.session_mode.clone()),
             selected_network: None,
             force_safe_mode: false,
+            shared_chat: false,
             platform_role_ids: vec![],
+            attachments: vec![],
             chat_context: None,
+            action: None,
         };
 
         // Execute the job with timeout
@@ -513,7 +590,9 @@ impl Scheduler {
 
         // Handle delivery if configured
         if job.deliver && job.channel_id.is_some() {
-            self.deliver_result(job, &response).await?;
+            if let Err(e) = self.deliver_result(job, &response).await {
+                log::warn!("Cron job '{}' result not delivered: {}", job.name, e);
+            }
         }
 
         // Broadcast job completion event
@@ -569,25 +648,17 @@ impl Scheduler {
         }
     }
 
-    /// Deliver job result to the configured channel
+    /// Deliver job result to the configured channel, subject to its proactive message settings
     async fn deliver_result(&self, job: &CronJob, response: &str) -> Result<(), String> {
-        // For now, we just log that we would deliver
-        // In a full implementation, this would send to the channel
-        log::info!(
-            "Would deliver cron job '{}' result to channel {} (to: {:?}): {}",
-            job.name,
-            job.channel_id.unwrap_or(0),
-            job.deliver_to,
-            if response.len() > 100 {
-                format!("{}...", &response[..100])
-            } else {
-                response.to_string()
-            }
-        );
-
-        // TODO: Implement actual channel delivery
-        // This would involve looking up the channel and sending a message
-
+        let channel_id = job.channel_id.ok_or("no delivery channel")?;
+        let chat_id = job
+            .deliver_to
+            .as_deref()
+            .filter(|to| !to.trim().is_empty())
+            .ok_or("no recipient (deliver_to) set")?;
+        let text = format!("**{}**\n\n{}", job.name, response);
+        crate::channels::outbound::send_proactive(&self.db, channel_id, chat_id, &text, "cron").await?;
+        log::info!("Delivered cron job '{}' result to channel {} ({})", job.name, channel_id, chat_id);
         Ok(())
     }
 
@@ -0,0 +1,304 @@
+//! In-place self-update from published releases
+//!
+//! The release endpoint (GitHub's "latest release" API by default, see
+//! `STARK_RELEASES_URL`) is expected to carry one raw `stark-backend-{platform}`
+//! binary per platform next to a `stark-backend-{platform}.sha256` file. An
+//! update downloads the binary beside the running one, verifies its checksum,
+//! keeps the old binary as `<exe>.prev` and renames the new one over it, so
+//! the swap is atomic. The process then stops taking new executions, waits
+//! for running ones to finish, shuts down the usual way and re-executes
+//! itself from the new file.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::controllers::health::VERSION;
+use crate::execution::ExecutionTracker;
+use crate::integrations::starkhub_client::current_platform;
+
+/// Timeout for the release check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+/// Timeout for the binary download
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+/// How long a restart waits for running executions before going ahead
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Set while an update is downloading or waiting to restart
+static UPDATE_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+/// Set once an installed update is waiting for executions to finish; no new ones start
+static DRAINING: AtomicBool = AtomicBool::new(false);
+/// Binary to re-execute once the server has shut down
+static RESTART_TARGET: Mutex<Option<PathBuf>> = Mutex::new(None);
+static RESTART: Lazy<Notify> = Lazy::new(Notify::new);
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// The latest release and the assets for this platform
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub notes: Option<String>,
+    pub published_at: Option<String>,
+    pub binary_url: String,
+    pub checksum_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateCheck {
+    pub current_version: String,
+    pub platform: String,
+    pub update_available: bool,
+    pub release: Option<ReleaseInfo>,
+    pub in_progress: bool,
+}
+
+fn binary_asset_name(platform: &str) -> String {
+    format!("stark-backend-{}", platform)
+}
+
+/// Pick this platform's binary and checksum out of a release
+fn release_for_platform(release: GithubRelease, platform: &str) -> Result<ReleaseInfo, String> {
+    let binary_name = binary_asset_name(platform);
+    let checksum_name = format!("{}.sha256", binary_name);
+    let url_of = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| a.browser_download_url.clone())
+            .ok_or_else(|| format!("Release {} has no '{}' asset", release.tag_name, name))
+    };
+    Ok(ReleaseInfo {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        binary_url: url_of(&binary_name)?,
+        checksum_url: url_of(&checksum_name)?,
+        notes: release.body,
+        published_at: release.published_at,
+    })
+}
+
+/// The hex digest from a `sha256sum`-style file: either a bare digest or
+/// `<digest>  <file name>` lines
+fn parse_checksum(text: &str, file_name: &str) -> Option<String> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let digest = parts.next()?;
+            match parts.next().map(|name| name.trim_start_matches('*')) {
+                None => Some(digest),
+                Some(name) if name == file_name => Some(digest),
+                Some(_) => None,
+            }
+        })
+        .find(|digest| digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(|digest| digest.to_lowercase())
+}
+
+/// Ask the release endpoint for the latest version
+pub async fn check() -> Result<UpdateCheck, String> {
+    let platform = current_platform();
+    let release: GithubRelease = crate::http::shared_client()
+        .get(crate::config::releases_url())
+        .header("User-Agent", format!("stark-backend/{}", VERSION))
+        .header("Accept", "application/vnd.github+json")
+        .timeout(CHECK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach release endpoint: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Release endpoint error: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse release: {}", e))?;
+
+    let newer = crate::config::semver_is_newer(release.tag_name.trim_start_matches('v'), VERSION);
+    let release = if newer { Some(release_for_platform(release, platform)?) } else { None };
+    Ok(UpdateCheck {
+        current_version: VERSION.to_string(),
+        platform: platform.to_string(),
+        update_available: release.is_some(),
+        release,
+        in_progress: UPDATE_IN_PROGRESS.load(Ordering::SeqCst),
+    })
+}
+
+/// Download and verify `release` and swap it in for the running binary.
+/// Returns the path of the installed binary. Only one update runs at a time.
+pub async fn install(release: &ReleaseInfo) -> Result<PathBuf, String> {
+    if UPDATE_IN_PROGRESS.swap(true, Ordering::SeqCst) {
+        return Err("An update is already in progress".to_string());
+    }
+    let result = download_and_swap(release).await;
+    if result.is_err() {
+        UPDATE_IN_PROGRESS.store(false, Ordering::SeqCst);
+    }
+    result
+}
+
+async fn download_and_swap(release: &ReleaseInfo) -> Result<PathBuf, String> {
+    if current_platform() == "unknown" {
+        return Err("Self-update is not supported on this platform".to_string());
+    }
+    let exe = std::env::current_exe().map_err(|e| format!("Cannot locate the running binary: {}", e))?;
+    let dir = exe.parent().ok_or("The running binary has no parent directory")?;
+    // Staged next to the binary so the final rename stays on one filesystem
+    let staged = dir.join(".stark-backend.update");
+
+    let client = crate::http::shared_client();
+    let fetch = |url: &str| {
+        client
+            .get(url)
+            .header("User-Agent", format!("stark-backend/{}", VERSION))
+            .timeout(DOWNLOAD_TIMEOUT)
+            .send()
+    };
+    let checksum_text = fetch(&release.checksum_url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download checksum: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read checksum: {}", e))?;
+    let expected = parse_checksum(&checksum_text, &binary_asset_name(current_platform()))
+        .ok_or("Checksum file does not contain a sha256 digest for this platform")?;
+
+    log::info!("[SELF_UPDATE] Downloading v{} from {}", release.version, release.binary_url);
+    let bytes = fetch(&release.binary_url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download binary: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read binary: {}", e))?;
+    std::fs::write(&staged, &bytes).map_err(|e| format!("Failed to stage binary: {}", e))?;
+
+    let computed = crate::modules::sandbox::sha256_file(&staged).map_err(|e| e.to_string())?;
+    if computed != expected {
+        let _ = std::fs::remove_file(&staged);
+        return Err(format!(
+            "Checksum mismatch! Expected {}, got {}. Download may be corrupted.",
+            expected, computed
+        ));
+    }
+
+    swap_binary(&staged, &exe)?;
+    log::info!("[SELF_UPDATE] Installed v{} at {} (sha256 {})", release.version, exe.display(), computed);
+    *RESTART_TARGET.lock().unwrap_or_else(|e| e.into_inner()) = Some(exe.clone());
+    Ok(exe)
+}
+
+/// Keep a copy of the old binary as `<exe>.prev`, then rename the staged one
+/// over it. The rename is atomic, so a crash leaves one binary or the other.
+fn swap_binary(staged: &Path, exe: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(staged, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to mark binary executable: {}", e))?;
+    }
+    let backup = exe.with_extension("prev");
+    std::fs::copy(exe, &backup).map_err(|e| format!("Failed to back up current binary: {}", e))?;
+    std::fs::rename(staged, exe).map_err(|e| format!("Failed to replace binary: {}", e))
+}
+
+/// Wait until no execution is running, up to `timeout`. Returns whether
+/// everything finished in time.
+pub async fn drain_executions(tracker: &ExecutionTracker, timeout: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    while tracker.has_any_active_executions() {
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    true
+}
+
+/// Drain executions, then ask the server to shut down and restart
+pub async fn restart_after_drain(tracker: &ExecutionTracker) {
+    DRAINING.store(true, Ordering::SeqCst);
+    log::info!("[SELF_UPDATE] Waiting for running executions before restarting");
+    if !drain_executions(tracker, DRAIN_TIMEOUT).await {
+        log::warn!(
+            "[SELF_UPDATE] Executions still running after {}s, restarting anyway",
+            DRAIN_TIMEOUT.as_secs()
+        );
+    }
+    RESTART.notify_one();
+}
+
+/// Whether the process is draining for a restart and should refuse new executions
+pub fn is_draining() -> bool {
+    DRAINING.load(Ordering::SeqCst)
+}
+
+/// Resolves when an installed update asks for a restart
+pub async fn restart_requested() {
+    RESTART.notified().await;
+}
+
+/// Replace this process with the updated binary. Only returns on failure.
+pub fn exec_updated_binary() -> Option<std::io::Error> {
+    let target = RESTART_TARGET.lock().unwrap_or_else(|e| e.into_inner()).take()?;
+    log::info!("[SELF_UPDATE] Re-executing {}", target.display());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        Some(std::process::Command::new(&target).args(std::env::args_os().skip(1)).exec())
+    }
+    #[cfg(not(unix))]
+    {
+        Some(std::io::Error::other("Re-exec is only supported on Unix"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_assets_and_checksum() {
+        let release: GithubRelease = serde_json::from_value(serde_json::json!({
+            "tag_name": "v9.1.0",
+            "assets": [
+                { "name": "stark-backend-linux-x86_64", "browser_download_url": "https://example.com/bin" },
+                { "name": "stark-backend-linux-x86_64.sha256", "browser_download_url": "https://example.com/sum" },
+            ]
+        }))
+        .unwrap();
+        let info = release_for_platform(release, "linux-x86_64").unwrap();
+        assert_eq!(info.version, "9.1.0");
+        assert_eq!(info.binary_url, "https://example.com/bin");
+
+        let missing: GithubRelease =
+            serde_json::from_value(serde_json::json!({ "tag_name": "v9.1.0", "assets": [] })).unwrap();
+        assert!(release_for_platform(missing, "darwin-aarch64").is_err());
+
+        let digest = "ab".repeat(32);
+        assert_eq!(parse_checksum(&digest, "x").as_deref(), Some(digest.as_str()));
+        let sums = format!("{}  stark-backend-darwin-aarch64\n{} *stark-backend-linux-x86_64\n", "cd".repeat(32), digest);
+        assert_eq!(parse_checksum(&sums, "stark-backend-linux-x86_64"), Some(digest));
+        assert_eq!(parse_checksum("not a digest", "x"), None);
+    }
+}
@@ -90,9 +90,43 @@ pub async fn backfill_skill_embeddings(
         return Ok(0);
     }
 
+    let count = embed_skills(db, embedding_gen, &missing_ids).await;
+    log::info!("[SKILL-EMB] Backfilled {} skill embeddings", count);
+    Ok(count)
+}
+
+/// Regenerate embeddings for skills edited since they were embedded (and
+/// generate any that are missing). Returns (generated, needed).
+pub async fn reembed_changed_skills(
+    db: &Arc<Database>,
+    embedding_gen: &Arc<dyn EmbeddingGenerator + Send + Sync>,
+) -> Result<(usize, usize), String> {
+    let mut ids = db.list_skills_with_stale_embeddings(500)
+        .map_err(|e| format!("Failed to list skills with stale embeddings: {}", e))?;
+    ids.extend(
+        db.list_skills_without_embeddings(500)
+            .map_err(|e| format!("Failed to list skills without embeddings: {}", e))?,
+    );
+
+    if ids.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let count = embed_skills(db, embedding_gen, &ids).await;
+    log::info!("[SKILL-EMB] Re-embedded {} of {} changed skills", count, ids.len());
+    Ok((count, ids.len()))
+}
+
+/// Generate and store embeddings for the given skills in batches.
+/// Returns the number stored; stops at the first failed batch.
+async fn embed_skills(
+    db: &Arc<Database>,
+    embedding_gen: &Arc<dyn EmbeddingGenerator + Send + Sync>,
+    skill_ids: &[i64],
+) -> usize {
     // Load all skills that need embeddings
     let mut skills_to_embed: Vec<(i64, String, String)> = Vec::new();
-    for skill_id in &missing_ids {
+    for skill_id in skill_ids {
         if let Ok(Some(skill)) = db.get_skill_by_id(*skill_id) {
             let text = build_skill_embedding_text(&skill);
             skills_to_embed.push((*skill_id, skill.name.clone(), text));
@@ -122,8 +156,7 @@ pub async fn backfill_skill_embeddings(
         }
     }
 
-    log::info!("[SKILL-EMB] Backfilled {} skill embeddings", count);
-    Ok(count)
+    count
 }
 
 /// Search skills by semantic similarity to a query string.
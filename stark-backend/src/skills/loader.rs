@@ -238,6 +238,11 @@ pub fn serde_yaml_parse(yaml: &str) -> Result<SkillMetadata, String> {
                             metadata.tags = parse_inline_list(value);
                         }
                     }
+                    "finance_permissions" => {
+                        if value.starts_with('[') {
+                            metadata.finance_permissions = parse_inline_list(value);
+                        }
+                    }
                     "scripts" => {
                         if value.starts_with('[') {
                             metadata.scripts = Some(parse_inline_list(value));
@@ -271,6 +276,7 @@ pub fn serde_yaml_parse(yaml: &str) -> Result<SkillMetadata, String> {
                     "requires_tools" => metadata.requires_tools.push(unquote(value)),
                     "requires_binaries" => metadata.requires_binaries.push(unquote(value)),
                     "tags" => metadata.tags.push(unquote(value)),
+                    "finance_permissions" => metadata.finance_permissions.push(unquote(value)),
                     "scripts" => metadata.scripts.get_or_insert_with(Vec::new).push(unquote(value)),
                     "abis" => metadata.abis.get_or_insert_with(Vec::new).push(unquote(value)),
                     _ => {}
@@ -0,0 +1,190 @@
+//! Localized skill blocks
+//!
+//! A skill body may carry translations of itself in blocks like
+//!
+//! ```text
+//! <!-- locale: es -->
+//! description: Revisa el código y sugiere mejoras
+//! Eres un revisor de código...
+//! <!-- /locale -->
+//! ```
+//!
+//! The optional `description:` first line overrides the frontmatter
+//! description; the rest is the prompt. Everything outside the blocks is the
+//! default prompt. Blocks live in the body so they travel with the skill
+//! through the database, zip export and backups unchanged.
+
+use crate::db::Database;
+
+const OPEN: &str = "<!-- locale:";
+const CLOSE: &str = "<!-- /locale -->";
+
+/// One localized block of a skill body
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedBlock {
+    /// Normalized language tag, e.g. "es" or "pt-br"
+    pub locale: String,
+    pub description: Option<String>,
+    pub prompt: String,
+}
+
+/// A skill body split into its default prompt and localized blocks
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedBody {
+    pub default_prompt: String,
+    pub blocks: Vec<LocalizedBlock>,
+}
+
+impl LocalizedBody {
+    pub fn parse(body: &str) -> Self {
+        let mut default_prompt = String::new();
+        let mut blocks = Vec::new();
+        let mut rest = body;
+
+        while let Some(start) = rest.find(OPEN) {
+            let after_open = &rest[start + OPEN.len()..];
+            let Some(tag_end) = after_open.find("-->") else { break };
+            let Some(close) = after_open[tag_end..].find(CLOSE) else { break };
+            let close = tag_end + close;
+
+            default_prompt.push_str(&rest[..start]);
+            let locale = normalize_tag(&after_open[..tag_end]);
+            let content = after_open[tag_end + 3..close].trim();
+            let (description, prompt) = match content.split_once('\n') {
+                Some((first, prompt)) if first.trim_start().starts_with("description:") => {
+                    (Some(first.trim_start()["description:".len()..].trim().to_string()), prompt.trim())
+                }
+                None if content.starts_with("description:") => {
+                    (Some(content["description:".len()..].trim().to_string()), "")
+                }
+                _ => (None, content),
+            };
+            if !locale.is_empty() {
+                blocks.push(LocalizedBlock {
+                    locale,
+                    description: description.filter(|d| !d.is_empty()),
+                    prompt: prompt.to_string(),
+                });
+            }
+            rest = &after_open[close + CLOSE.len()..];
+        }
+        default_prompt.push_str(rest);
+
+        LocalizedBody { default_prompt: default_prompt.trim().to_string(), blocks }
+    }
+
+    /// Locales that have a block, in body order
+    pub fn locales(&self) -> Vec<String> {
+        self.blocks.iter().map(|b| b.locale.clone()).collect()
+    }
+
+    /// The block best matching `preferred` (a tag like "pt-BR" or a name like
+    /// "Spanish"): exact tag first, then the same primary language
+    pub fn best_match(&self, preferred: &str) -> Option<&LocalizedBlock> {
+        let wanted = language_code(preferred)?;
+        let primary = |tag: &str| tag.split('-').next().unwrap_or_default().to_string();
+        self.blocks
+            .iter()
+            .find(|b| b.locale == wanted)
+            .or_else(|| self.blocks.iter().find(|b| primary(&b.locale) == primary(&wanted)))
+    }
+
+    /// (description, prompt) for `preferred`, falling back to `default_description`
+    /// and the default prompt when no block matches or a block leaves one out
+    pub fn resolve(&self, preferred: Option<&str>, default_description: &str) -> (String, String) {
+        match preferred.and_then(|p| self.best_match(p)) {
+            Some(block) => (
+                block.description.clone().unwrap_or_else(|| default_description.to_string()),
+                if block.prompt.is_empty() { self.default_prompt.clone() } else { block.prompt.clone() },
+            ),
+            None => (default_description.to_string(), self.default_prompt.clone()),
+        }
+    }
+}
+
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase().replace('_', "-")
+}
+
+/// Language names people (and the profile builder) write instead of tags
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("english", "en"),
+    ("spanish", "es"),
+    ("español", "es"),
+    ("french", "fr"),
+    ("français", "fr"),
+    ("german", "de"),
+    ("deutsch", "de"),
+    ("italian", "it"),
+    ("italiano", "it"),
+    ("portuguese", "pt"),
+    ("português", "pt"),
+    ("dutch", "nl"),
+    ("russian", "ru"),
+    ("ukrainian", "uk"),
+    ("polish", "pl"),
+    ("turkish", "tr"),
+    ("arabic", "ar"),
+    ("hindi", "hi"),
+    ("japanese", "ja"),
+    ("korean", "ko"),
+    ("chinese", "zh"),
+    ("mandarin", "zh"),
+    ("vietnamese", "vi"),
+    ("indonesian", "id"),
+];
+
+/// Normalized tag for a language preference, or None if it's empty
+fn language_code(preferred: &str) -> Option<String> {
+    let tag = normalize_tag(preferred);
+    let first_word = tag.split(|c: char| c.is_whitespace() || c == '(' || c == ',').next().unwrap_or_default();
+    if first_word.is_empty() {
+        return None;
+    }
+    Some(
+        LANGUAGE_NAMES
+            .iter()
+            .find(|(name, _)| *name == first_word)
+            .map(|(_, code)| code.to_string())
+            .unwrap_or_else(|| first_word.to_string()),
+    )
+}
+
+/// The language preference recorded in an identity's profile
+pub fn preferred_language(db: &Database, identity_id: Option<&str>) -> Option<String> {
+    let identity_id = identity_id.filter(|id| !id.is_empty())?;
+    db.get_identity_profile(identity_id)
+        .ok()
+        .flatten()
+        .and_then(|p| p.language)
+        .filter(|l| !l.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "Review the code at {{path}}.\n\n\
+        <!-- locale: es -->\ndescription: Revisa el código\nRevisa el código en {{path}}.\n<!-- /locale -->\n\n\
+        <!-- locale: pt_BR -->\nRevise o código em {{path}}.\n<!-- /locale -->";
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let body = LocalizedBody::parse(BODY);
+        assert_eq!(body.default_prompt, "Review the code at {{path}}.");
+        assert_eq!(body.locales(), vec!["es", "pt-br"]);
+
+        let (desc, prompt) = body.resolve(Some("Spanish"), "Review code");
+        assert_eq!(desc, "Revisa el código");
+        assert_eq!(prompt, "Revisa el código en {{path}}.");
+
+        // Primary-language match, frontmatter description when the block has none
+        let (desc, prompt) = body.resolve(Some("pt"), "Review code");
+        assert_eq!(desc, "Review code");
+        assert_eq!(prompt, "Revise o código em {{path}}.");
+
+        let (desc, prompt) = body.resolve(Some("fr"), "Review code");
+        assert_eq!((desc.as_str(), prompt.as_str()), ("Review code", "Review the code at {{path}}."));
+        assert_eq!(body.resolve(None, "Review code").1, "Review the code at {{path}}.");
+    }
+}
@@ -1,6 +1,9 @@
 pub mod embeddings;
 pub mod loader;
+pub mod locales;
+pub mod permissions;
 pub mod registry;
+pub mod suggest;
 pub mod types;
 pub mod zip_parser;
 
@@ -42,6 +42,12 @@ impl SkillRegistry {
         }
     }
 
+    /// Get a skill with the description and prompt best matching `language`,
+    /// falling back to the defaults
+    pub fn get_localized(&self, name: &str, language: Option<&str>) -> Option<Skill> {
+        self.get(name).map(|skill| skill.localized(language))
+    }
+
     /// List all registered skills (from DB — synced index)
     pub fn list(&self) -> Vec<Skill> {
         match self.db.list_skills() {
@@ -115,6 +121,7 @@ impl SkillRegistry {
             tags: metadata.tags,
             subagent_type: metadata.subagent_type,
             requires_api_keys: metadata.requires_api_keys,
+            finance_permissions: metadata.finance_permissions,
             scripts: Vec::new(),
             abis: Vec::new(),
             presets_content: None,
@@ -143,6 +150,7 @@ impl SkillRegistry {
             tags: metadata.tags,
             subagent_type: metadata.subagent_type,
             requires_api_keys: metadata.requires_api_keys,
+            finance_permissions: metadata.finance_permissions,
             scripts: Vec::new(),
             abis: Vec::new(),
             presets_content: None,
@@ -188,6 +196,7 @@ impl SkillRegistry {
             requires_api_keys: parsed.requires_api_keys,
             created_at: now.clone(),
             updated_at: now.clone(),
+            finance_permissions: parsed.finance_permissions,
         };
 
         // Insert skill into database
@@ -311,6 +320,11 @@ impl SkillRegistry {
         // Delete from disk (idempotent — safe if already removed)
         delete_skill_folder(&self.skills_dir, name);
 
+        // A reinstall starts without grants
+        if let Err(e) = self.db.delete_skill_permissions(name) {
+            log::warn!("Failed to clear permissions for skill '{}': {}", name, e);
+        }
+
         // Delete from DB
         self.db.delete_skill(name)
             .map_err(|e| format!("Failed to delete skill: {}", e))
@@ -401,6 +415,7 @@ impl SkillRegistry {
             requires_api_keys: skill.metadata.requires_api_keys.clone(),
             created_at: now.clone(),
             updated_at: now.clone(),
+            finance_permissions: skill.metadata.finance_permissions.clone(),
         };
 
         let skill_id = self.db.create_skill(&db_skill)
@@ -880,6 +895,9 @@ pub fn reconstruct_skill_md(parsed: &ParsedSkill) -> String {
     if !parsed.tags.is_empty() {
         lines.push(format!("tags: [{}]", parsed.tags.join(", ")));
     }
+    if !parsed.finance_permissions.is_empty() {
+        lines.push(format!("finance_permissions: [{}]", parsed.finance_permissions.join(", ")));
+    }
 
     if !parsed.scripts.is_empty() {
         let script_names: Vec<&str> = parsed.scripts.iter().map(|s| s.name.as_str()).collect();
@@ -934,6 +952,7 @@ pub fn reconstruct_skill_md_from_db(db_skill: &DbSkill) -> String {
         tags: db_skill.tags.clone(),
         subagent_type: db_skill.subagent_type.clone(),
         requires_api_keys: db_skill.requires_api_keys.clone(),
+        finance_permissions: db_skill.finance_permissions.clone(),
         scripts: Vec::new(),
         abis: Vec::new(),
         presets_content: None,
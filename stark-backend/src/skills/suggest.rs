@@ -0,0 +1,180 @@
+//! Automatic skill suggestion
+//!
+//! Before the agent starts on a message, the request is matched against the
+//! skill embeddings. A close, unambiguous match to a skill the session hasn't
+//! used yet is either suggested in the system prompt or, on channels that opt
+//! in, activated outright. The `skill_suggestions` channel setting picks the
+//! mode (suggest by default, auto or off) and `skill_suggestion_threshold` the
+//! similarity a match needs.
+
+use std::sync::Arc;
+
+use crate::db::Database;
+use crate::memory::EmbeddingGenerator;
+use crate::models::ChannelSettingKey;
+use crate::skills::types::DbSkill;
+
+/// Similarity a match needs when the channel doesn't set a threshold
+pub const DEFAULT_THRESHOLD: f32 = 0.80;
+/// Auto-activation needs this much more similarity than a suggestion
+pub const AUTO_MARGIN: f32 = 0.05;
+/// The best match must beat the runner-up by this much, or it's ambiguous
+pub const MIN_LEAD: f32 = 0.05;
+/// Shorter messages ("hi", "thanks!") never trigger a suggestion
+const MIN_QUERY_WORDS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionMode {
+    Off,
+    /// Point the agent at the skill in the system prompt (the default)
+    Suggest,
+    /// Activate the skill before the agent starts
+    Auto,
+}
+
+impl SuggestionMode {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_lowercase()).as_deref() {
+            Some("off") => SuggestionMode::Off,
+            Some("auto") => SuggestionMode::Auto,
+            _ => SuggestionMode::Suggest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SuggestionSettings {
+    pub mode: SuggestionMode,
+    pub threshold: f32,
+}
+
+impl SuggestionSettings {
+    pub fn for_channel(db: &Database, channel_id: i64) -> Self {
+        let setting = |key: ChannelSettingKey| db.get_channel_setting(channel_id, key.as_ref()).ok().flatten();
+        SuggestionSettings {
+            mode: SuggestionMode::from_setting(setting(ChannelSettingKey::SkillSuggestions).as_deref()),
+            threshold: setting(ChannelSettingKey::SkillSuggestionThreshold)
+                .and_then(|v| v.trim().parse::<f32>().ok())
+                .map(|pct| pct.clamp(50.0, 99.0) / 100.0)
+                .unwrap_or(DEFAULT_THRESHOLD),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub skill: DbSkill,
+    pub similarity: f32,
+    /// Activate the skill instead of only suggesting it
+    pub activate: bool,
+}
+
+impl Suggestion {
+    /// System prompt section pointing the agent at the suggested skill
+    pub fn prompt_section(&self) -> String {
+        format!(
+            "## Suggested Skill\n\
+             This request closely matches the **{}** skill ({:.0}% match): {}\n\
+             Activate it with `use_skill` before doing the work yourself, unless it clearly doesn't fit the request.\n\n",
+            self.skill.name,
+            self.similarity * 100.0,
+            self.skill.description
+        )
+    }
+}
+
+fn is_substantive(text: &str) -> bool {
+    let text = text.trim();
+    !text.starts_with('/') && text.split_whitespace().count() >= MIN_QUERY_WORDS
+}
+
+/// The suggestion `matches` support, if any: the best match must clear the
+/// threshold, lead the runner-up by `MIN_LEAD` and not be a skill already used
+pub fn pick(mut matches: Vec<(DbSkill, f32)>, settings: &SuggestionSettings, used: &[String]) -> Option<Suggestion> {
+    if settings.mode == SuggestionMode::Off {
+        return None;
+    }
+    matches.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    let runner_up = matches.get(1).map(|(_, sim)| *sim).unwrap_or(0.0);
+    let (skill, similarity) = matches.into_iter().next()?;
+    if similarity < settings.threshold || similarity - runner_up < MIN_LEAD || used.contains(&skill.name) {
+        return None;
+    }
+    let activate = settings.mode == SuggestionMode::Auto && similarity >= settings.threshold + AUTO_MARGIN;
+    Some(Suggestion { skill, similarity, activate })
+}
+
+/// Match a chat message against the skill embeddings for this channel and session
+pub async fn suggest(
+    db: &Arc<Database>,
+    embedding_gen: &Arc<dyn EmbeddingGenerator + Send + Sync>,
+    text: &str,
+    channel_id: i64,
+    session_id: i64,
+) -> Option<Suggestion> {
+    let settings = SuggestionSettings::for_channel(db, channel_id);
+    if settings.mode == SuggestionMode::Off || !is_substantive(text) {
+        return None;
+    }
+    // Anything below threshold - MIN_LEAD can't be a runner-up that matters
+    let floor = (settings.threshold - MIN_LEAD).max(0.0);
+    let matches = match super::embeddings::search_skills(db, embedding_gen, text, 3, floor).await {
+        Ok(m) => m,
+        Err(e) => {
+            log::debug!("[SKILL_SUGGEST] Skill search failed: {}", e);
+            return None;
+        }
+    };
+    let used = db.skills_used_in_session(session_id).unwrap_or_default();
+    pick(matches, &settings, &used)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(name: &str) -> DbSkill {
+        DbSkill {
+            id: None,
+            name: name.to_string(),
+            description: String::new(),
+            body: String::new(),
+            version: "1.0.0".to_string(),
+            author: None,
+            homepage: None,
+            metadata: None,
+            enabled: true,
+            requires_tools: vec![],
+            requires_binaries: vec![],
+            arguments: Default::default(),
+            tags: vec![],
+            subagent_type: None,
+            requires_api_keys: Default::default(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            finance_permissions: vec![],
+        }
+    }
+
+    #[test]
+    fn test_pick_thresholds() {
+        let suggest = SuggestionSettings { mode: SuggestionMode::Suggest, threshold: 0.80 };
+        let auto = SuggestionSettings { mode: SuggestionMode::Auto, ..suggest };
+
+        let picked = pick(vec![(skill("swap"), 0.83), (skill("bridge"), 0.70)], &suggest, &[]).unwrap();
+        assert_eq!((picked.skill.name.as_str(), picked.activate), ("swap", false));
+        // Auto mode only activates with the extra margin
+        assert!(!pick(vec![(skill("swap"), 0.83)], &auto, &[]).unwrap().activate);
+        assert!(pick(vec![(skill("swap"), 0.90)], &auto, &[]).unwrap().activate);
+
+        // Below threshold, ambiguous, already used, or off
+        assert!(pick(vec![(skill("swap"), 0.75)], &suggest, &[]).is_none());
+        assert!(pick(vec![(skill("swap"), 0.85), (skill("bridge"), 0.83)], &suggest, &[]).is_none());
+        assert!(pick(vec![(skill("swap"), 0.90)], &suggest, &["swap".to_string()]).is_none());
+        let off = SuggestionSettings { mode: SuggestionMode::Off, ..suggest };
+        assert!(pick(vec![(skill("swap"), 0.99)], &off, &[]).is_none());
+
+        assert!(!is_substantive("thanks!"));
+        assert!(is_substantive("swap 10 usdc to eth"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::skills::locales::LocalizedBody;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -102,6 +103,10 @@ pub struct SkillMetadata {
     /// Flow files bundled with this skill (e.g. ["identity_flow.md"])
     #[serde(default)]
     pub flows: Option<Vec<String>>,
+    /// Finance capabilities the skill needs ("read", "send"); tools listed in
+    /// requires_tools imply theirs as well
+    #[serde(default)]
+    pub finance_permissions: Vec<String>,
 }
 
 fn default_version() -> String {
@@ -127,6 +132,7 @@ impl Default for SkillMetadata {
             abis: None,
             presets_file: None,
             flows: None,
+            finance_permissions: vec![],
         }
     }
 }
@@ -153,23 +159,77 @@ fn default_enabled() -> bool {
     true
 }
 
+/// Replace argument placeholders {{arg_name}} with values (or the argument's default)
+fn render_placeholders(
+    template: &str,
+    arguments: &HashMap<String, SkillArgument>,
+    args: &HashMap<String, String>,
+) -> String {
+    let mut prompt = template.to_string();
+    for (name, arg_def) in arguments {
+        let placeholder = format!("{{{{{}}}}}", name);
+        let value = args
+            .get(name)
+            .cloned()
+            .or_else(|| arg_def.default.clone())
+            .unwrap_or_default();
+        prompt = prompt.replace(&placeholder, &value);
+    }
+    prompt
+}
+
+/// Argument values from a JSON object; non-string values keep their JSON text
+pub fn args_from_json(value: &serde_json::Value) -> HashMap<String, String> {
+    value
+        .as_object()
+        .map(|map| {
+            map.iter()
+                .map(|(k, v)| (k.clone(), v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 impl Skill {
     /// Render the skill prompt with provided arguments
     pub fn render_prompt(&self, args: &HashMap<String, String>) -> String {
-        let mut prompt = self.prompt_template.clone();
-
-        // Replace argument placeholders {{arg_name}} with values
-        for (name, arg_def) in &self.metadata.arguments {
-            let placeholder = format!("{{{{{}}}}}", name);
-            let value = args
-                .get(name)
-                .cloned()
-                .or_else(|| arg_def.default.clone())
-                .unwrap_or_default();
-            prompt = prompt.replace(&placeholder, &value);
-        }
+        render_placeholders(&self.prompt_template, &self.metadata.arguments, args)
+    }
+
+    /// Locales this skill has localized blocks for
+    pub fn locales(&self) -> Vec<String> {
+        LocalizedBody::parse(&self.prompt_template).locales()
+    }
 
-        prompt
+    /// This skill with the description and prompt for `preferred` language,
+    /// or the defaults with localized blocks stripped
+    pub fn localized(&self, preferred: Option<&str>) -> Skill {
+        let (description, prompt_template) =
+            LocalizedBody::parse(&self.prompt_template).resolve(preferred, &self.metadata.description);
+        let mut skill = self.clone();
+        skill.metadata.description = description;
+        skill.prompt_template = prompt_template;
+        skill
+    }
+
+    /// Names of `{{placeholder}}`s still left in a rendered prompt (typos,
+    /// undeclared arguments), in order of first appearance
+    pub fn unresolved_placeholders(rendered: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut rest = rendered;
+        while let Some(start) = rest.find("{{") {
+            rest = &rest[start + 2..];
+            let Some(end) = rest.find("}}") else { break };
+            let name = rest[..end].trim();
+            if !name.is_empty()
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+                && !names.iter().any(|n| n == name)
+            {
+                names.push(name.to_string());
+            }
+            rest = &rest[end + 2..];
+        }
+        names
     }
 
     /// Check if all required binaries are available
@@ -228,9 +288,23 @@ pub struct DbSkill {
     pub requires_api_keys: HashMap<String, SkillApiKey>,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub finance_permissions: Vec<String>,
 }
 
 impl DbSkill {
+    /// The body with its declared `{{argument}}` placeholders filled in
+    pub fn render_body(&self, args: &HashMap<String, String>) -> String {
+        render_placeholders(&self.body, &self.arguments, args)
+    }
+
+    /// This skill with the description and body for `preferred` language,
+    /// or the defaults with localized blocks stripped
+    pub fn localized(&self, preferred: Option<&str>) -> DbSkill {
+        let (description, body) = LocalizedBody::parse(&self.body).resolve(preferred, &self.description);
+        DbSkill { description, body, ..self.clone() }
+    }
+
     /// Convert to Skill for API compatibility
     pub fn into_skill(self) -> Skill {
         Skill {
@@ -251,6 +325,7 @@ impl DbSkill {
                 abis: None,
                 presets_file: None,
                 flows: None,
+                finance_permissions: self.finance_permissions,
             },
             prompt_template: self.body,
             source: SkillSource::Managed, // All DB skills are "managed"
@@ -341,6 +416,13 @@ mod tests {
         assert_eq!(skill.render_prompt(&empty_args), "Review code at .");
     }
 
+    #[test]
+    fn test_unresolved_placeholders() {
+        let rendered = "Review {{ path }} with {{depth}} then {{path}}; JSON {{\"a\": 1}} is not one";
+        assert_eq!(Skill::unresolved_placeholders(rendered), vec!["path", "depth"]);
+        assert!(Skill::unresolved_placeholders("nothing left").is_empty());
+    }
+
     #[test]
     fn test_skill_source_priority() {
         assert!(SkillSource::Workspace.priority() > SkillSource::Managed.priority());
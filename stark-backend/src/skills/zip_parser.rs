@@ -33,6 +33,7 @@ pub struct ParsedSkill {
     pub tags: Vec<String>,
     pub subagent_type: Option<String>,
     pub requires_api_keys: HashMap<String, SkillApiKey>,
+    pub finance_permissions: Vec<String>,
     pub scripts: Vec<ParsedScript>,
     pub abis: Vec<ParsedAbi>,
     pub presets_content: Option<String>,
@@ -244,6 +245,7 @@ pub fn parse_skill_zip(data: &[u8]) -> Result<ParsedSkill, String> {
         tags: metadata.tags,
         subagent_type: metadata.subagent_type,
         requires_api_keys: metadata.requires_api_keys,
+        finance_permissions: metadata.finance_permissions,
         scripts,
         abis,
         presets_content,
@@ -0,0 +1,181 @@
+//! Social content calendar
+//!
+//! The agent drafts posts (Twitter or Discord) with the `social_calendar` tool.
+//! Drafts are stored `pending` and never published on the agent's say-so: the
+//! owner approves, edits or rejects them from the dashboard, or with the
+//! Approve/Reject buttons on the card sent to the chat the draft came from.
+//! The publisher worker (see [`publisher`]) posts approved drafts at their
+//! scheduled time and keeps engagement metrics fresh for a week afterwards.
+
+pub mod publisher;
+
+use serde_json::json;
+
+use crate::channels::outbound;
+use crate::channels::types::{ActionButton, ActionEvent, ChannelType, NormalizedMessage};
+use crate::db::tables::social_posts::{SocialPost, SOCIAL_POST_PENDING};
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::models::MessageRole;
+
+/// Button action that approves a drafted post
+pub const ACTION_APPROVE_POST: &str = "approve_social_post";
+/// Button action that rejects a drafted post
+pub const ACTION_REJECT_POST: &str = "reject_social_post";
+
+/// Platforms the calendar can publish to
+pub const PLATFORMS: &[&str] = &["twitter", "discord"];
+
+fn when(post: &SocialPost) -> String {
+    match post.scheduled_at.as_deref() {
+        Some(at) => format!("at {}", at),
+        None => "as soon as it is approved".to_string(),
+    }
+}
+
+fn approval_card(post: &SocialPost) -> String {
+    let quoted: String = post.text.lines().map(|l| format!("> {}\n", l)).collect();
+    format!(
+        "**Post approval needed** — {} #{}\n\n{}\nPublishes {}. Nothing is posted unless you approve; edit it from the dashboard.",
+        post.platform,
+        post.id,
+        quoted,
+        when(post)
+    )
+}
+
+/// Send the approval card to the chat the draft came from, if that chat can
+/// render buttons. Otherwise the draft waits for a dashboard decision.
+pub async fn request_approval(db: &Database, post: &SocialPost, channel_id: Option<i64>, chat_id: Option<&str>) {
+    let (Some(channel_id), Some(chat_id)) = (channel_id, chat_id) else {
+        return;
+    };
+    let supports_buttons = db
+        .get_channel(channel_id)
+        .ok()
+        .flatten()
+        .and_then(|c| ChannelType::from_str(&c.channel_type))
+        .is_some_and(|t| matches!(t, ChannelType::Telegram | ChannelType::Discord));
+    if !supports_buttons {
+        return;
+    }
+
+    let payload = Some(json!({ "post_id": post.id }));
+    let buttons = vec![
+        ActionButton { label: "Approve".to_string(), action: ACTION_APPROVE_POST.to_string(), payload: payload.clone() },
+        ActionButton { label: "Reject".to_string(), action: ACTION_REJECT_POST.to_string(), payload },
+    ];
+    match outbound::send_direct(db, channel_id, chat_id, &approval_card(post), &buttons).await {
+        Ok(()) => {
+            let _ = db.set_social_post_approval_chat(post.id, channel_id, chat_id);
+        }
+        Err(e) => log::warn!("[SOCIAL_CALENDAR] Failed to send approval card for post {}: {}", post.id, e),
+    }
+}
+
+/// Record an approve/reject decision. Err if the post is gone or already decided.
+pub fn decide(
+    db: &Database,
+    broadcaster: &EventBroadcaster,
+    post_id: i64,
+    approve: bool,
+    decided_by: &str,
+) -> Result<SocialPost, String> {
+    let post = db
+        .get_social_post(post_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Post {} not found", post_id))?;
+    if !db.decide_social_post(post_id, approve, decided_by).map_err(|e| e.to_string())? {
+        return Err(format!("Post {} is already {}", post_id, post.status));
+    }
+    let post = db
+        .get_social_post(post_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Post {} not found", post_id))?;
+
+    log::info!("[SOCIAL_CALENDAR] Post {} {} by {}", post.id, post.status, decided_by);
+    note_in_session(
+        db,
+        &post,
+        &format!("Social post #{} was {} by the owner.", post.id, post.status),
+    );
+    broadcaster.broadcast(GatewayEvent::custom(
+        "social_post_decided",
+        json!({ "post_id": post.id, "status": post.status, "decided_by": decided_by }),
+    ));
+    Ok(post)
+}
+
+/// Leave a system note in the session that drafted the post
+pub fn note_in_session(db: &Database, post: &SocialPost, note: &str) {
+    if let Some(session_id) = post.session_id {
+        if let Err(e) = db.add_session_message(session_id, MessageRole::System, note, None, None, None, None) {
+            log::warn!("[SOCIAL_CALENDAR] Failed to record note in session {}: {}", session_id, e);
+        }
+    }
+}
+
+/// Handle an Approve/Reject button press. None if the action isn't a post approval action.
+pub fn handle_action(
+    db: &Database,
+    broadcaster: &EventBroadcaster,
+    message: &NormalizedMessage,
+    event: &ActionEvent,
+) -> Option<String> {
+    let approve = match event.action.as_str() {
+        ACTION_APPROVE_POST => true,
+        ACTION_REJECT_POST => false,
+        _ => return None,
+    };
+    let post_id = match event.payload.as_ref().and_then(|p| p.get("post_id")).and_then(|v| v.as_i64()) {
+        Some(id) => id,
+        None => return Some("This approval button is missing its post.".to_string()),
+    };
+    let post = match db.get_social_post(post_id) {
+        Ok(Some(post)) => post,
+        Ok(None) => return Some(format!("Post {} no longer exists.", post_id)),
+        Err(e) => return Some(format!("Failed to load post: {}", e)),
+    };
+    if post.approval_channel_id != Some(message.channel_id) || post.approval_chat_id.as_deref() != Some(message.chat_id.as_str()) {
+        return Some("This approval belongs to a different chat.".to_string());
+    }
+    if message.force_safe_mode {
+        log::warn!("[SOCIAL_CALENDAR] Non-admin {} tried to decide post {}", message.user_name, post_id);
+        return Some("Only the owner can approve or reject posts.".to_string());
+    }
+    if post.status != SOCIAL_POST_PENDING {
+        return Some(format!("Post #{} is already {} — nothing changed.", post_id, post.status));
+    }
+
+    let decided_by = format!("{} ({})", message.user_name, message.user_id);
+    match decide(db, broadcaster, post_id, approve, &decided_by) {
+        Ok(post) if approve => Some(format!("Approved — post #{} publishes {}.", post.id, when(&post))),
+        Ok(post) => Some(format!("Rejected — post #{} will not be published.", post.id)),
+        Err(e) => Some(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::tables::social_posts::NewSocialPost;
+
+    #[test]
+    fn test_approval_card_quotes_text() {
+        let db = Database::new(":memory:").unwrap();
+        let post = db
+            .create_social_post(&NewSocialPost {
+                platform: "twitter",
+                target_channel_id: None,
+                target_chat_id: None,
+                text: "line one\nline two",
+                scheduled_at: None,
+                session_id: None,
+            })
+            .unwrap();
+        let card = approval_card(&post);
+        assert!(card.contains("> line one\n> line two"));
+        assert!(card.contains("as soon as it is approved"));
+    }
+}
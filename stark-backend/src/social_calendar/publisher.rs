@@ -0,0 +1,254 @@
+//! Publishes approved posts at their scheduled time and refreshes engagement
+//! metrics (Twitter public metrics, Discord reaction counts) for recent posts.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Duration as ChronoDuration;
+use serde_json::{json, Value};
+
+use super::note_in_session;
+use crate::channels::types::ChannelType;
+use crate::circuit_breaker::{self, GuardedSend};
+use crate::db::tables::social_posts::SocialPost;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::tools::builtin::social_media::generate_oauth_header;
+
+const TWITTER_TWEETS_URL: &str = "https://api.twitter.com/2/tweets";
+
+/// Posts published per worker pass
+const PUBLISH_BATCH_SIZE: usize = 10;
+/// Metrics are tracked for this long after publishing
+const METRICS_WINDOW_DAYS: i64 = 7;
+/// Minimum time between metric refreshes of one post
+const METRICS_REFRESH_MINS: i64 = 60;
+/// Posts refreshed per worker pass
+const METRICS_BATCH_SIZE: usize = 20;
+
+/// Publish one post. Returns the platform ID and a public URL where known.
+pub async fn publish(db: &Database, post: &SocialPost) -> Result<(String, Option<String>), String> {
+    match post.platform.as_str() {
+        "twitter" => {
+            let tweet_id = post_tweet(db, &post.text).await?;
+            let url = format!("https://twitter.com/i/web/status/{}", tweet_id);
+            Ok((tweet_id, Some(url)))
+        }
+        "discord" => {
+            let (http, discord_channel) = discord_target(db, post)?;
+            let message = discord_channel
+                .say(&http, &post.text)
+                .await
+                .map_err(|e| format!("Discord send failed: {}", e))?;
+            let url = message.guild_id.map(|guild| {
+                format!("https://discord.com/channels/{}/{}/{}", guild, discord_channel, message.id)
+            });
+            Ok((message.id.to_string(), url))
+        }
+        other => Err(format!("Unsupported platform '{}'", other)),
+    }
+}
+
+async fn post_tweet(db: &Database, text: &str) -> Result<String, String> {
+    let credentials = crate::channels::twitter::credentials_from_keys(db)?;
+    let auth_header = generate_oauth_header("POST", TWITTER_TWEETS_URL, &credentials, None);
+
+    let response = crate::http::shared_client()
+        .post(TWITTER_TWEETS_URL)
+        .header("Authorization", auth_header)
+        .json(&json!({ "text": text }))
+        .send_guarded(circuit_breaker::TWITTER)
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(format!("Twitter API error ({}): {}", status, body));
+    }
+    body["data"]["id"]
+        .as_str()
+        .map(|id| id.to_string())
+        .ok_or_else(|| format!("Unexpected Twitter response: {}", body))
+}
+
+fn discord_target(
+    db: &Database,
+    post: &SocialPost,
+) -> Result<(Arc<serenity::http::Http>, serenity::all::ChannelId), String> {
+    let channel_id = post.target_channel_id.ok_or("Discord post has no target channel")?;
+    let channel = db
+        .get_channel(channel_id)
+        .map_err(|e| format!("Failed to load channel: {}", e))?
+        .ok_or_else(|| format!("Channel {} no longer exists", channel_id))?;
+    if ChannelType::from_str(&channel.channel_type) != Some(ChannelType::Discord) {
+        return Err(format!("Channel {} is not a Discord channel", channel_id));
+    }
+    let chat_id: u64 = post
+        .target_chat_id
+        .as_deref()
+        .and_then(|c| c.parse().ok())
+        .ok_or("Discord post has no valid target channel ID")?;
+    Ok((
+        Arc::new(serenity::http::Http::new(&channel.bot_token)),
+        serenity::all::ChannelId::new(chat_id),
+    ))
+}
+
+/// Current engagement numbers for a published post
+pub async fn fetch_metrics(db: &Database, post: &SocialPost) -> Result<Value, String> {
+    let external_id = post.external_id.as_deref().ok_or("Post has not been published")?;
+    match post.platform.as_str() {
+        "twitter" => {
+            let credentials = crate::channels::twitter::credentials_from_keys(db)?;
+            let params: Vec<(&str, &str)> = vec![("ids", external_id), ("tweet.fields", "public_metrics")];
+            let auth_header = generate_oauth_header("GET", TWITTER_TWEETS_URL, &credentials, Some(&params));
+            let response = crate::http::shared_client()
+                .get(TWITTER_TWEETS_URL)
+                .query(&params)
+                .header("Authorization", auth_header)
+                .send_guarded(circuit_breaker::TWITTER)
+                .await
+                .map_err(|e| format!("Request failed: {}", e))?;
+            let status = response.status();
+            let body: Value = response.json().await.unwrap_or_default();
+            if !status.is_success() {
+                return Err(format!("Twitter API error ({}): {}", status, body));
+            }
+            body["data"][0]["public_metrics"]
+                .as_object()
+                .map(|m| Value::Object(m.clone()))
+                .ok_or_else(|| "Tweet not found (deleted?)".to_string())
+        }
+        "discord" => {
+            let (http, discord_channel) = discord_target(db, post)?;
+            let message_id: u64 = external_id.parse().map_err(|_| "Invalid Discord message ID")?;
+            let message = discord_channel
+                .message(&http, serenity::all::MessageId::new(message_id))
+                .await
+                .map_err(|e| format!("Discord fetch failed: {}", e))?;
+            let by_emoji: serde_json::Map<String, Value> = message
+                .reactions
+                .iter()
+                .map(|r| (r.reaction_type.to_string(), json!(r.count)))
+                .collect();
+            let total: u64 = message.reactions.iter().map(|r| r.count).sum();
+            Ok(json!({ "reactions": total, "by_emoji": by_emoji }))
+        }
+        other => Err(format!("Unsupported platform '{}'", other)),
+    }
+}
+
+/// Publish every approved post that is due. Returns (published, failed).
+pub async fn run_publish_pass(db: &Database, broadcaster: &EventBroadcaster) -> (usize, usize) {
+    let due = match db.list_due_social_posts(PUBLISH_BATCH_SIZE) {
+        Ok(due) => due,
+        Err(e) => {
+            log::error!("[SOCIAL_CALENDAR] Failed to load due posts: {}", e);
+            return (0, 0);
+        }
+    };
+
+    let (mut published, mut failed) = (0, 0);
+    for post in due {
+        // Leave tweets queued while Twitter's circuit is open; they go out on a later pass
+        if post.platform == "twitter" && circuit_breaker::is_open(circuit_breaker::TWITTER) {
+            continue;
+        }
+        match publish(db, &post).await {
+            Ok((external_id, url)) => {
+                published += 1;
+                log::info!("[SOCIAL_CALENDAR] Published post {} to {} as {}", post.id, post.platform, external_id);
+                let _ = db.mark_social_post_published(post.id, &external_id, url.as_deref());
+                note_in_session(
+                    db,
+                    &post,
+                    &format!(
+                        "Social post #{} was published to {}{}",
+                        post.id,
+                        post.platform,
+                        url.as_deref().map(|u| format!(": {}", u)).unwrap_or_default()
+                    ),
+                );
+                broadcaster.broadcast(GatewayEvent::custom(
+                    "social_post_published",
+                    json!({ "post_id": post.id, "platform": post.platform, "external_id": external_id, "url": url }),
+                ));
+            }
+            Err(e) => {
+                failed += 1;
+                log::error!("[SOCIAL_CALENDAR] Failed to publish post {}: {}", post.id, e);
+                let _ = db.mark_social_post_failed(post.id, &e);
+                note_in_session(db, &post, &format!("Social post #{} failed to publish: {}", post.id, e));
+                broadcaster.broadcast(GatewayEvent::custom(
+                    "social_post_failed",
+                    json!({ "post_id": post.id, "platform": post.platform, "error": e }),
+                ));
+            }
+        }
+    }
+    (published, failed)
+}
+
+/// Refresh engagement metrics for recently published posts
+pub async fn run_metrics_pass(db: &Database) -> usize {
+    let posts = match db.list_social_posts_needing_metrics(
+        ChronoDuration::days(METRICS_WINDOW_DAYS),
+        ChronoDuration::minutes(METRICS_REFRESH_MINS),
+        METRICS_BATCH_SIZE,
+    ) {
+        Ok(posts) => posts,
+        Err(e) => {
+            log::error!("[SOCIAL_CALENDAR] Failed to load posts for metrics: {}", e);
+            return 0;
+        }
+    };
+
+    let mut refreshed = 0;
+    for post in posts {
+        match fetch_metrics(db, &post).await {
+            Ok(metrics) => {
+                if db.set_social_post_metrics(post.id, &metrics).is_ok() {
+                    refreshed += 1;
+                }
+            }
+            Err(e) => {
+                log::debug!("[SOCIAL_CALENDAR] Metrics unavailable for post {}: {}", post.id, e);
+                // Stamp the attempt (keeping the last good numbers) so it isn't retried every pass
+                let metrics = post.metrics.clone().unwrap_or_else(|| json!({ "error": e }));
+                let _ = db.set_social_post_metrics(post.id, &metrics);
+            }
+        }
+    }
+    refreshed
+}
+
+/// Spawn the publisher worker (publishes and refreshes metrics every
+/// `interval_secs` while this instance holds the leader lease).
+pub fn spawn_social_calendar_worker(
+    db: Arc<Database>,
+    broadcaster: Arc<EventBroadcaster>,
+    interval_secs: u64,
+    is_leader: impl Fn() -> bool + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if !is_leader() {
+                continue;
+            }
+            // Scheduled posts wait until read-only mirror mode is turned off
+            if crate::tools::read_only_mode::is_enabled(&db) {
+                log::debug!("[SOCIAL_CALENDAR] Read-only mirror mode is on; not publishing");
+            } else {
+                let (published, failed) = run_publish_pass(&db, &broadcaster).await;
+                if published + failed > 0 {
+                    log::info!("[SOCIAL_CALENDAR] Publish pass: {} published, {} failed", published, failed);
+                }
+            }
+            run_metrics_pass(&db).await;
+        }
+    })
+}
@@ -0,0 +1,532 @@
+//! Strategy evaluation and execution.
+//!
+//! Each pass observes every enabled strategy's trigger (persisting its
+//! bookkeeping in the strategy's state), and when it fires outside the
+//! cooldown and the daily run limit, checks the conditions and runs the
+//! actions in order on a fresh tool context. Transactions the actions queue
+//! are broadcast through `broadcast_web3_tx`, so the transaction approval
+//! policy still applies; anything over the strategy's own `max_value_eth` is
+//! left queued for a human.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Timelike, Utc};
+use ethers::types::U256;
+use serde_json::{json, Value};
+
+use super::types::{
+    StepResult, Strategy, StrategyCondition, StrategyRun, StrategyTrigger, WalletDirection, RUN_FAILED, RUN_SKIPPED,
+    RUN_SUCCEEDED,
+};
+use crate::alerts::engine::is_in_cooldown;
+use crate::channels::outbound;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::journal::prices;
+use crate::tools::builtin::cryptocurrency::token_lookup::TokenLookupTool;
+use crate::tools::{ToolContext, ToolRegistry};
+use crate::tx_queue::{QueuedTxStatus, TxQueueManager};
+use crate::wallet::WalletProvider;
+
+/// Longest tool output kept per step in the run history
+const MAX_STEP_OUTPUT: usize = 500;
+
+/// Whether a price is past either threshold of a trigger
+pub fn price_hit(price: f64, above: Option<f64>, below: Option<f64>) -> bool {
+    above.is_some_and(|a| price > a) || below.is_some_and(|b| price < b)
+}
+
+/// Whether a value lies within a condition's bounds
+pub fn within(value: f64, min: Option<f64>, max: Option<f64>) -> bool {
+    min.is_none_or(|m| value > m) && max.is_none_or(|m| value < m)
+}
+
+/// Balance change that counts as a wallet event, if any
+pub fn wallet_moved(previous: f64, current: f64, direction: WalletDirection, min_amount: f64) -> Option<f64> {
+    let delta = current - previous;
+    let matches = match direction {
+        WalletDirection::In => delta > 0.0,
+        WalletDirection::Out => delta < 0.0,
+        WalletDirection::Any => delta != 0.0,
+    };
+    (matches && delta.abs() >= min_amount).then_some(delta)
+}
+
+/// Whether `hour` is in [start, end), wrapping past midnight
+pub fn hour_in_window(hour: u32, start: u32, end: u32) -> bool {
+    if start < end {
+        hour >= start && hour < end
+    } else {
+        hour >= start || hour < end
+    }
+}
+
+/// Next scheduled time after `after`
+pub fn next_scheduled(cron: Option<&str>, every_minutes: Option<i64>, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    match (cron, every_minutes) {
+        (Some(expr), _) => cron::Schedule::from_str(expr).ok()?.after(&after).next(),
+        (None, Some(minutes)) => Some(after + Duration::minutes(minutes.max(1))),
+        (None, None) => None,
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_STEP_OUTPUT {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(MAX_STEP_OUTPUT).collect::<String>())
+    }
+}
+
+/// Current USD price of a token symbol on a network
+pub async fn price_usd(token: &str, network: &str) -> Result<f64, String> {
+    let info = TokenLookupTool::lookup(token, network).ok_or_else(|| format!("Unknown token '{}' on {}", token, network))?;
+    let id = prices::coin_id(network, &info.address).ok_or_else(|| format!("No price source for {} on {}", token, network))?;
+    prices::current_prices(std::slice::from_ref(&id))
+        .await?
+        .get(&id.to_lowercase())
+        .copied()
+        .ok_or_else(|| format!("No current price for {} on {}", token, network))
+}
+
+/// Balance in whole tokens (`token` = None is the native token)
+async fn balance(network: &str, address: &str, token: Option<&str>) -> Result<f64, String> {
+    let (result, decimals) = match token {
+        None => (crate::bridge::rpc_request(network, "eth_getBalance", json!([address, "latest"])).await?, 18),
+        Some(symbol) => {
+            let info = TokenLookupTool::lookup(symbol, network)
+                .ok_or_else(|| format!("Unknown token '{}' on {}", symbol, network))?;
+            // balanceOf(address)
+            let data = format!("0x70a08231{:0>64}", address.trim_start_matches("0x").to_lowercase());
+            let result = crate::bridge::rpc_request(
+                network,
+                "eth_call",
+                json!([{ "to": info.address, "data": data }, "latest"]),
+            )
+            .await?;
+            (result, info.decimals)
+        }
+    };
+    let raw = result.as_str().ok_or_else(|| format!("Unexpected balance result: {}", result))?;
+    let raw = U256::from_str_radix(raw.trim_start_matches("0x"), 16).map_err(|e| format!("Bad balance: {}", e))?;
+    let raw: f64 = raw.to_string().parse().unwrap_or(0.0);
+    Ok(raw / 10f64.powi(decimals as i32))
+}
+
+pub struct StrategyEngine {
+    db: Arc<Database>,
+    tool_registry: Arc<ToolRegistry>,
+    broadcaster: Arc<EventBroadcaster>,
+    tx_queue: Arc<TxQueueManager>,
+    wallet_provider: Option<Arc<dyn WalletProvider>>,
+}
+
+impl StrategyEngine {
+    pub fn new(
+        db: Arc<Database>,
+        tool_registry: Arc<ToolRegistry>,
+        broadcaster: Arc<EventBroadcaster>,
+        tx_queue: Arc<TxQueueManager>,
+        wallet_provider: Option<Arc<dyn WalletProvider>>,
+    ) -> Self {
+        Self { db, tool_registry, broadcaster, tx_queue, wallet_provider }
+    }
+
+    fn wallet_address(&self) -> Result<String, String> {
+        self.wallet_provider
+            .as_ref()
+            .map(|wp| wp.get_address())
+            .ok_or_else(|| "Wallet not configured".to_string())
+    }
+
+    /// Observe a trigger. Returns why it fired (if it did) and its new bookkeeping.
+    pub async fn observe_trigger(&self, trigger: &StrategyTrigger, state: &Value) -> Result<(Option<String>, Value), String> {
+        let now = Utc::now();
+        match trigger {
+            StrategyTrigger::Price { token, network, above, below } => {
+                let price = price_usd(token, network).await?;
+                let hit = price_hit(price, *above, *below);
+                // Fire on the crossing: the previous observation was not past the threshold
+                let was_hit = state["hit"].as_bool().unwrap_or(false);
+                let fired = (hit && !was_hit).then(|| format!("{} is ${:.4} on {}", token.to_uppercase(), price, network));
+                Ok((fired, json!({ "hit": hit, "price": price, "observed_at": now.to_rfc3339() })))
+            }
+            StrategyTrigger::Schedule { cron, every_minutes } => {
+                let due = state["next_run_at"]
+                    .as_str()
+                    .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+                    .map(|at| at.with_timezone(&Utc));
+                let next = next_scheduled(cron.as_deref(), *every_minutes, now)
+                    .ok_or("Schedule has no next run")?;
+                match due {
+                    // First observation only arms the schedule
+                    None => Ok((None, json!({ "next_run_at": next.to_rfc3339() }))),
+                    Some(due) if due <= now => Ok((
+                        Some(format!("scheduled run due {}", due.format("%Y-%m-%d %H:%M UTC"))),
+                        json!({ "next_run_at": next.to_rfc3339() }),
+                    )),
+                    Some(_) => Ok((None, state.clone())),
+                }
+            }
+            StrategyTrigger::WalletEvent { address, network, token, direction, min_amount } => {
+                let address = match address {
+                    Some(a) => a.clone(),
+                    None => self.wallet_address()?,
+                };
+                let current = balance(network, &address, token.as_deref()).await?;
+                let symbol = token.clone().unwrap_or_else(|| "native".to_string()).to_uppercase();
+                let fired = state["balance"]
+                    .as_f64()
+                    .and_then(|previous| wallet_moved(previous, current, *direction, *min_amount))
+                    .map(|delta| format!("{} balance of {} changed by {:+} on {}", symbol, address, delta, network));
+                Ok((fired, json!({ "balance": current, "observed_at": now.to_rfc3339() })))
+            }
+        }
+    }
+
+    /// The first condition that doesn't hold, if any
+    pub async fn check_conditions(&self, conditions: &[StrategyCondition]) -> Result<Option<String>, String> {
+        for condition in conditions {
+            let blocked = match condition {
+                StrategyCondition::Price { token, network, above, below } => {
+                    let price = price_usd(token, network).await?;
+                    (!within(price, *above, *below))
+                        .then(|| format!("{} price ${:.4} is outside the condition bounds", token.to_uppercase(), price))
+                }
+                StrategyCondition::Balance { network, token, min, max } => {
+                    let current = balance(network, &self.wallet_address()?, token.as_deref()).await?;
+                    let symbol = token.clone().unwrap_or_else(|| "native".to_string()).to_uppercase();
+                    (!within(current, *min, *max))
+                        .then(|| format!("{} balance {} is outside the condition bounds", symbol, current))
+                }
+                StrategyCondition::TimeWindow { start_hour, end_hour } => {
+                    let hour = Utc::now().hour();
+                    (!hour_in_window(hour, *start_hour, *end_hour))
+                        .then(|| format!("{}:00 UTC is outside {}:00-{}:00", hour, start_hour, end_hour))
+                }
+            };
+            if blocked.is_some() {
+                return Ok(blocked);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Evaluate all enabled strategies once. Returns the number of runs.
+    pub async fn run_once(&self) -> usize {
+        // Strategies trade and broadcast on their own, which the mirror mode forbids
+        if crate::tools::read_only_mode::is_enabled(&self.db) {
+            log::debug!("[STRATEGY] Read-only mirror mode is on; skipping the evaluation pass");
+            return 0;
+        }
+        let strategies = match self.db.list_enabled_strategies() {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("[STRATEGY] Failed to load strategies: {}", e);
+                return 0;
+            }
+        };
+
+        let mut runs = 0;
+        for strategy in &strategies {
+            let (fired, state) = match self.observe_trigger(&strategy.trigger, &strategy.state).await {
+                Ok(o) => o,
+                Err(e) => {
+                    log::warn!("[STRATEGY] '{}' trigger check failed: {}", strategy.name, e);
+                    continue;
+                }
+            };
+            if state != strategy.state {
+                if let Err(e) = self.db.set_strategy_state(strategy.id, &state) {
+                    log::error!("[STRATEGY] Failed to store state for '{}': {}", strategy.name, e);
+                }
+            }
+            let Some(reason) = fired else {
+                continue;
+            };
+
+            let started_at = Utc::now().to_rfc3339();
+            if let Some(blocked) = self.guardrail_block(strategy) {
+                log::info!("[STRATEGY] '{}' fired ({}) but {}", strategy.name, reason, blocked);
+                let _ = self.db.record_strategy_run(strategy, &reason, RUN_SKIPPED, &[], Some(&blocked), &started_at);
+                continue;
+            }
+            match self.check_conditions(&strategy.conditions).await {
+                Ok(None) => {}
+                Ok(Some(blocked)) => {
+                    log::info!("[STRATEGY] '{}' fired ({}) but {}", strategy.name, reason, blocked);
+                    let _ = self.db.record_strategy_run(strategy, &reason, RUN_SKIPPED, &[], Some(&blocked), &started_at);
+                    continue;
+                }
+                Err(e) => {
+                    log::warn!("[STRATEGY] '{}' condition check failed: {}", strategy.name, e);
+                    let _ = self.db.record_strategy_run(strategy, &reason, RUN_SKIPPED, &[], Some(&e), &started_at);
+                    continue;
+                }
+            }
+
+            log::info!("[STRATEGY] '{}' fired: {}", strategy.name, reason);
+            if self.run(strategy, &reason).await.is_ok() {
+                runs += 1;
+            }
+        }
+        runs
+    }
+
+    /// Cooldown / daily limit that stops a fired strategy from running
+    fn guardrail_block(&self, strategy: &Strategy) -> Option<String> {
+        let now = Utc::now();
+        if is_in_cooldown(strategy.last_run_at.as_deref(), strategy.guardrails.cooldown_minutes, now) {
+            return Some(format!("is cooling down ({} min between runs)", strategy.guardrails.cooldown_minutes));
+        }
+        let max = strategy.guardrails.max_runs_per_day?;
+        let since = (now - Duration::hours(24)).to_rfc3339();
+        let count = self.db.count_strategy_runs_since(strategy.id, &since).unwrap_or(0);
+        (count >= max).then(|| format!("reached its limit of {} run(s) per day", max))
+    }
+
+    fn tool_context(&self, strategy: &Strategy, reason: &str) -> ToolContext {
+        let mut context = ToolContext::new()
+            .with_database(self.db.clone())
+            .with_broadcaster(self.broadcaster.clone())
+            .with_tx_queue(self.tx_queue.clone());
+        if let Some(ref wp) = self.wallet_provider {
+            context = context.with_wallet_provider(wp.clone());
+        }
+        // Nobody asked for this run in a chat, so away mode limits what it may do
+        context.extra.insert(crate::away::AUTONOMOUS_RUN_KEY.to_string(), json!(true));
+        let settings = self.db.get_bot_settings().unwrap_or_default();
+        context.extra.insert("rpc_provider".to_string(), json!(settings.rpc_provider));
+        if let Some(ref endpoints) = settings.custom_rpc_endpoints {
+            context.extra.insert("custom_rpc_endpoints".to_string(), json!(endpoints));
+        }
+        context.extra.insert(
+            "paper_mode".to_string(),
+            json!(settings.paper_trading_enabled || strategy.guardrails.paper),
+        );
+        // What verify_intent checks each transaction against
+        let actions: Vec<String> = strategy.actions.iter().map(|a| format!("{} {}", a.tool, a.params)).collect();
+        context.extra.insert(
+            "original_user_message".to_string(),
+            json!(format!(
+                "Automated strategy '{}'{}. It ran because {}. Configured actions: {}",
+                strategy.name,
+                strategy.description.as_deref().map(|d| format!(" ({})", d)).unwrap_or_default(),
+                reason,
+                actions.join("; ")
+            )),
+        );
+        context
+    }
+
+    /// Broadcast (or hold) a transaction an action queued. Returns a note and whether it failed.
+    async fn settle_transaction(&self, strategy: &Strategy, uuid: &str, context: &ToolContext) -> (String, bool) {
+        let Some(tx) = self.tx_queue.get(uuid) else {
+            return ("not found in the queue".to_string(), true);
+        };
+        if tx.status != QueuedTxStatus::Pending {
+            return (format!("already {}", tx.status), false);
+        }
+        let value_eth = tx.value.parse::<u128>().map(|wei| wei as f64 / 1e18).unwrap_or(f64::INFINITY);
+        if let Some(max) = strategy.guardrails.max_value_eth {
+            if value_eth > max {
+                return (format!("left queued: {} ETH is over the strategy limit of {} ETH", value_eth, max), false);
+            }
+        }
+        if !strategy.guardrails.auto_broadcast {
+            return ("left queued (auto-broadcast is off)".to_string(), false);
+        }
+        let result = self
+            .tool_registry
+            .execute("broadcast_web3_tx", json!({ "uuid": uuid }), context, None)
+            .await;
+        let first_line = |s: &str| s.lines().next().unwrap_or_default().to_string();
+        if result.success {
+            (first_line(&result.content), false)
+        } else {
+            (first_line(result.error.as_deref().unwrap_or(&result.content)), true)
+        }
+    }
+
+    /// Run a strategy's actions now and record the run
+    pub async fn run(&self, strategy: &Strategy, reason: &str) -> Result<StrategyRun, String> {
+        let started_at = Utc::now().to_rfc3339();
+        let context = self.tool_context(strategy, reason);
+        let mut steps = Vec::new();
+        let mut error = None;
+
+        for action in &strategy.actions {
+            let params = if action.params.is_null() { json!({}) } else { action.params.clone() };
+            let result =
+                crate::tools::timeouts::execute(&self.db, &self.tool_registry, &action.tool, params, &context, None).await;
+            let output = if result.success {
+                result.content_for_model()
+            } else {
+                result.error.clone().unwrap_or_else(|| result.content.clone())
+            };
+            let mut step = StepResult {
+                tool: action.tool.clone(),
+                success: result.success,
+                output: truncate(&output),
+                tx_uuid: None,
+                tx_note: None,
+            };
+            if !result.success {
+                error = Some(format!("{} failed: {}", action.tool, truncate(&output)));
+                steps.push(step);
+                break;
+            }
+
+            // Transactions the action queued (bridges queue more than one)
+            let metadata = result.metadata.unwrap_or(Value::Null);
+            let uuids: Vec<String> = metadata["uuid"]
+                .as_str()
+                .map(str::to_string)
+                .into_iter()
+                .chain(
+                    metadata["queued_transactions"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|u| u.as_str().map(str::to_string)),
+                )
+                .collect();
+            let mut notes = Vec::new();
+            for uuid in &uuids {
+                let (note, failed) = self.settle_transaction(strategy, uuid, &context).await;
+                notes.push(note.clone());
+                if failed {
+                    step.success = false;
+                    error = Some(format!("{}: transaction {} {}", action.tool, uuid, note));
+                }
+            }
+            step.tx_uuid = uuids.first().cloned();
+            step.tx_note = (!notes.is_empty()).then(|| notes.join("; "));
+            let failed = !step.success;
+            steps.push(step);
+            if failed {
+                break;
+            }
+        }
+
+        let status = if error.is_some() { RUN_FAILED } else { RUN_SUCCEEDED };
+        let run = self
+            .db
+            .record_strategy_run(strategy, reason, status, &steps, error.as_deref(), &started_at)
+            .map_err(|e| format!("Database error: {}", e))?;
+        self.notify(strategy, &run).await;
+        Ok(run)
+    }
+
+    /// Tell the dashboard and the strategy's chat how a run went
+    async fn notify(&self, strategy: &Strategy, run: &StrategyRun) {
+        self.broadcaster.broadcast(GatewayEvent::custom(
+            "strategy.run",
+            json!({
+                "strategy_id": strategy.id,
+                "strategy_name": strategy.name,
+                "run_id": run.id,
+                "status": run.status,
+                "reason": run.reason,
+                "error": run.error,
+            }),
+        ));
+        let (Some(channel_id), Some(chat_id)) = (strategy.channel_id, strategy.chat_id.as_deref()) else {
+            return;
+        };
+        let mut text = format!("**Strategy '{}' {}**\nTrigger: {}", strategy.name, run.status, run.reason);
+        for step in &run.steps {
+            text.push_str(&format!("\n- {} {}", if step.success { "✅" } else { "❌" }, step.tool));
+            if let Some(ref note) = step.tx_note {
+                text.push_str(&format!(" ({})", note));
+            }
+        }
+        if let Some(ref error) = run.error {
+            text.push_str(&format!("\n\n{}", error));
+        }
+        if let Err(e) = outbound::send_proactive(&self.db, channel_id, chat_id, &text, "strategy").await {
+            log::warn!("[STRATEGY] Failed to notify chat for '{}': {}", strategy.name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_helpers() {
+        assert!(price_hit(1900.0, None, Some(2000.0)));
+        assert!(!price_hit(2100.0, None, Some(2000.0)));
+        assert!(price_hit(3100.0, Some(3000.0), Some(2000.0)));
+        assert!(within(2500.0, Some(2000.0), Some(3000.0)));
+        assert!(!within(3500.0, None, Some(3000.0)));
+
+        assert_eq!(wallet_moved(1.0, 1.5, WalletDirection::In, 0.1), Some(0.5));
+        assert_eq!(wallet_moved(1.0, 1.5, WalletDirection::Out, 0.1), None);
+        assert_eq!(wallet_moved(1.0, 1.05, WalletDirection::Any, 0.1), None);
+
+        assert!(hour_in_window(10, 9, 17));
+        assert!(!hour_in_window(17, 9, 17));
+        assert!(hour_in_window(23, 22, 6));
+        assert!(hour_in_window(2, 22, 6));
+
+        let now = Utc::now();
+        assert_eq!(next_scheduled(None, Some(30), now), Some(now + Duration::minutes(30)));
+        let next = next_scheduled(Some("0 0 9 * * *"), None, now).unwrap();
+        assert!(next > now && next.hour() == 9);
+    }
+
+    struct SwapTool;
+
+    #[async_trait::async_trait]
+    impl crate::tools::registry::Tool for SwapTool {
+        fn definition(&self) -> crate::tools::ToolDefinition {
+            crate::tools::ToolDefinition {
+                name: "swap".to_string(),
+                description: "Swap tokens".to_string(),
+                input_schema: Default::default(),
+                group: crate::tools::types::ToolGroup::Finance,
+                hidden: false,
+            }
+        }
+
+        async fn execute(&self, _params: Value, _context: &ToolContext) -> crate::tools::ToolResult {
+            crate::tools::ToolResult::success("swapped")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_away_mode_refuses_actions_outside_the_allow_list() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(SwapTool));
+        let engine = StrategyEngine::new(
+            db.clone(),
+            Arc::new(registry),
+            Arc::new(EventBroadcaster::new()),
+            Arc::new(TxQueueManager::new()),
+            None,
+        );
+        let request: crate::strategies::types::CreateStrategyRequest = serde_json::from_value(json!({
+            "name": "dip buyer",
+            "trigger": { "type": "schedule", "every_minutes": 60 },
+            "actions": [{ "tool": "swap", "params": {} }],
+        }))
+        .unwrap();
+        let strategy = db.create_strategy(&request).unwrap();
+
+        let mut settings = crate::away::AwaySettings { enabled: true, ..Default::default() };
+        crate::away::save(&db, &settings).unwrap();
+        let run = engine.run(&strategy, "test").await.unwrap();
+        assert_eq!(run.status, RUN_FAILED);
+        assert!(run.error.unwrap().contains("the owner is away"));
+
+        settings.allowed_actions = vec!["swap".to_string()];
+        crate::away::save(&db, &settings).unwrap();
+        let run = engine.run(&strategy, "test").await.unwrap();
+        assert_eq!(run.status, RUN_SUCCEEDED);
+    }
+}
@@ -0,0 +1,47 @@
+//! Strategy automation
+//!
+//! A strategy (managed via `/api/strategies`) pairs a trigger — a price
+//! crossing, a schedule, or a wallet balance movement — with optional
+//! conditions and an ordered list of tool calls with fixed parameters, so
+//! users can set up "buy the dip" or DCA automations without writing a
+//! skill. Guardrails cap how often a strategy runs and how much value it may
+//! move on its own; queued transactions still pass the transaction approval
+//! policy when broadcast. Every run is recorded in `strategy_runs`.
+
+pub mod engine;
+pub mod types;
+
+pub use engine::StrategyEngine;
+pub use types::StrategyTrigger;
+
+use std::sync::Arc;
+
+/// Configuration for the background strategy worker.
+pub struct StrategyWorkerConfig {
+    /// Interval between evaluation passes in seconds (default: 60).
+    pub interval_secs: u64,
+}
+
+impl Default for StrategyWorkerConfig {
+    fn default() -> Self {
+        Self { interval_secs: 60 }
+    }
+}
+
+/// Spawn the background worker that evaluates strategies.
+pub fn spawn_strategy_worker(
+    engine: Arc<StrategyEngine>,
+    config: StrategyWorkerConfig,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(config.interval_secs));
+        interval.tick().await; // skip immediate tick
+        loop {
+            interval.tick().await;
+            let runs = engine.run_once().await;
+            if runs > 0 {
+                log::info!("[STRATEGY] Evaluation pass complete: {} strategy run(s)", runs);
+            }
+        }
+    })
+}
@@ -0,0 +1,351 @@
+//! Strategy types — triggers, conditions, actions, guardrails and run records.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+fn default_network() -> String {
+    "base".to_string()
+}
+
+fn default_cooldown_minutes() -> i64 {
+    60
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Which balance movements a wallet trigger reacts to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletDirection {
+    In,
+    Out,
+    #[default]
+    Any,
+}
+
+/// What starts a strategy run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StrategyTrigger {
+    /// Token USD price crosses above `above` or below `below`. Fires on the
+    /// crossing, not on every check while the price stays past the threshold.
+    Price {
+        token: String,
+        #[serde(default = "default_network")]
+        network: String,
+        #[serde(default)]
+        above: Option<f64>,
+        #[serde(default)]
+        below: Option<f64>,
+    },
+    /// Cron expression (UTC, with seconds field) or a fixed interval in minutes
+    Schedule {
+        #[serde(default)]
+        cron: Option<String>,
+        #[serde(default)]
+        every_minutes: Option<i64>,
+    },
+    /// A token balance of the bot wallet (or `address`) moves by at least
+    /// `min_amount` (whole tokens). `token` = None watches the native token.
+    WalletEvent {
+        #[serde(default)]
+        address: Option<String>,
+        #[serde(default = "default_network")]
+        network: String,
+        #[serde(default)]
+        token: Option<String>,
+        #[serde(default)]
+        direction: WalletDirection,
+        #[serde(default)]
+        min_amount: f64,
+    },
+}
+
+impl StrategyTrigger {
+    /// Short machine-readable key (matches the serde tag)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            StrategyTrigger::Price { .. } => "price",
+            StrategyTrigger::Schedule { .. } => "schedule",
+            StrategyTrigger::WalletEvent { .. } => "wallet_event",
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            StrategyTrigger::Price { token, above, below, .. } => {
+                if token.trim().is_empty() {
+                    return Err("price trigger requires a token".to_string());
+                }
+                if above.is_none() && below.is_none() {
+                    return Err("price trigger requires 'above' or 'below'".to_string());
+                }
+            }
+            StrategyTrigger::Schedule { cron, every_minutes } => match (cron, every_minutes) {
+                (Some(expr), None) => {
+                    use std::str::FromStr;
+                    cron::Schedule::from_str(expr).map_err(|e| format!("Invalid cron expression '{}': {}", expr, e))?;
+                }
+                (None, Some(minutes)) if *minutes >= 1 => {}
+                (None, Some(_)) => return Err("every_minutes must be at least 1".to_string()),
+                _ => return Err("schedule trigger requires exactly one of 'cron' or 'every_minutes'".to_string()),
+            },
+            StrategyTrigger::WalletEvent { address, min_amount, .. } => {
+                if let Some(address) = address {
+                    if !address.starts_with("0x") || address.len() != 42 {
+                        return Err(format!("Invalid wallet address: {}", address));
+                    }
+                }
+                if *min_amount < 0.0 {
+                    return Err("min_amount must be non-negative".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Extra checks that must all hold when the trigger fires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StrategyCondition {
+    /// Token USD price is currently within the bounds
+    Price {
+        token: String,
+        #[serde(default = "default_network")]
+        network: String,
+        #[serde(default)]
+        above: Option<f64>,
+        #[serde(default)]
+        below: Option<f64>,
+    },
+    /// Bot wallet balance (whole tokens) is within the bounds; `token` = None is native
+    Balance {
+        #[serde(default = "default_network")]
+        network: String,
+        #[serde(default)]
+        token: Option<String>,
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+    },
+    /// Current UTC hour is in [start_hour, end_hour) (wraps past midnight)
+    TimeWindow { start_hour: u32, end_hour: u32 },
+}
+
+impl StrategyCondition {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            StrategyCondition::Price { token, above, below, .. } => {
+                if token.trim().is_empty() || (above.is_none() && below.is_none()) {
+                    return Err("price condition requires a token and 'above' or 'below'".to_string());
+                }
+            }
+            StrategyCondition::Balance { min, max, .. } => {
+                if min.is_none() && max.is_none() {
+                    return Err("balance condition requires 'min' or 'max'".to_string());
+                }
+            }
+            StrategyCondition::TimeWindow { start_hour, end_hour } => {
+                if *start_hour > 23 || *end_hour > 24 || start_hour == end_hour {
+                    return Err("time_window hours must be 0-24 and differ".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A tool call with fixed parameters. Actions run in order on one tool
+/// context, so registers set by one action are visible to the next.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyAction {
+    pub tool: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// Limits on how often and how much a strategy may act on its own
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyGuardrails {
+    /// Minimum minutes between runs
+    #[serde(default = "default_cooldown_minutes")]
+    pub cooldown_minutes: i64,
+    /// Maximum runs per rolling 24 hours (None = unlimited)
+    #[serde(default)]
+    pub max_runs_per_day: Option<i64>,
+    /// Transactions above this native value (ETH) are left queued for a human
+    #[serde(default)]
+    pub max_value_eth: Option<f64>,
+    /// Broadcast queued transactions automatically. Broadcasts still go through
+    /// the transaction approval policy, so large ones wait for the owner.
+    #[serde(default = "default_true")]
+    pub auto_broadcast: bool,
+    /// Simulate in the paper ledger instead of signing
+    #[serde(default)]
+    pub paper: bool,
+}
+
+impl Default for StrategyGuardrails {
+    fn default() -> Self {
+        Self {
+            cooldown_minutes: default_cooldown_minutes(),
+            max_runs_per_day: None,
+            max_value_eth: None,
+            auto_broadcast: true,
+            paper: false,
+        }
+    }
+}
+
+/// A user-defined automation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Strategy {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub trigger: StrategyTrigger,
+    pub conditions: Vec<StrategyCondition>,
+    pub actions: Vec<StrategyAction>,
+    pub guardrails: StrategyGuardrails,
+    /// Channel/chat that is told about runs (Telegram/Discord)
+    pub channel_id: Option<i64>,
+    pub chat_id: Option<String>,
+    pub enabled: bool,
+    /// Trigger bookkeeping (last price side, next scheduled run, last balance)
+    pub state: Value,
+    pub last_run_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Request body for creating a strategy
+#[derive(Debug, Deserialize)]
+pub struct CreateStrategyRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub trigger: StrategyTrigger,
+    #[serde(default)]
+    pub conditions: Vec<StrategyCondition>,
+    #[serde(default)]
+    pub actions: Vec<StrategyAction>,
+    #[serde(default)]
+    pub guardrails: StrategyGuardrails,
+    pub channel_id: Option<i64>,
+    pub chat_id: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// Request body for updating a strategy (all fields optional)
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateStrategyRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub trigger: Option<StrategyTrigger>,
+    pub conditions: Option<Vec<StrategyCondition>>,
+    pub actions: Option<Vec<StrategyAction>>,
+    pub guardrails: Option<StrategyGuardrails>,
+    pub channel_id: Option<i64>,
+    pub chat_id: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+pub const RUN_SUCCEEDED: &str = "succeeded";
+pub const RUN_FAILED: &str = "failed";
+/// Trigger fired but a condition or guardrail held the run back
+pub const RUN_SKIPPED: &str = "skipped";
+
+/// Outcome of one action in a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepResult {
+    pub tool: String,
+    pub success: bool,
+    pub output: String,
+    /// Queued transaction the step produced, if any
+    #[serde(default)]
+    pub tx_uuid: Option<String>,
+    /// What happened to that transaction (broadcast, awaiting approval, left queued)
+    #[serde(default)]
+    pub tx_note: Option<String>,
+}
+
+/// A record of a strategy run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyRun {
+    pub id: i64,
+    pub strategy_id: i64,
+    pub strategy_name: String,
+    /// Why it ran (trigger message, or "manual run")
+    pub reason: String,
+    pub status: String,
+    pub steps: Vec<StepResult>,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+/// Validate a full strategy definition
+pub fn validate_strategy(
+    name: &str,
+    trigger: &StrategyTrigger,
+    conditions: &[StrategyCondition],
+    actions: &[StrategyAction],
+    guardrails: &StrategyGuardrails,
+) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Strategy name is required".to_string());
+    }
+    trigger.validate()?;
+    for condition in conditions {
+        condition.validate()?;
+    }
+    if actions.is_empty() {
+        return Err("At least one action is required".to_string());
+    }
+    if let Some(action) = actions.iter().find(|a| a.tool.trim().is_empty()) {
+        return Err(format!("Action is missing a tool name: {:?}", action));
+    }
+    if guardrails.cooldown_minutes < 0 {
+        return Err("cooldown_minutes must be non-negative".to_string());
+    }
+    if guardrails.max_runs_per_day.is_some_and(|n| n < 1) {
+        return Err("max_runs_per_day must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_deserialize_defaults() {
+        let trigger: StrategyTrigger = serde_json::from_str(r#"{"type":"price","token":"WETH","below":2000}"#).unwrap();
+        assert_eq!(
+            trigger,
+            StrategyTrigger::Price { token: "WETH".to_string(), network: "base".to_string(), above: None, below: Some(2000.0) }
+        );
+        let guardrails: StrategyGuardrails = serde_json::from_str("{}").unwrap();
+        assert_eq!(guardrails, StrategyGuardrails::default());
+    }
+
+    #[test]
+    fn test_validate_strategy() {
+        let action = vec![StrategyAction { tool: "swap_token".to_string(), params: Value::Null }];
+        let guardrails = StrategyGuardrails::default();
+        let schedule = StrategyTrigger::Schedule { cron: Some("0 0 9 * * *".to_string()), every_minutes: None };
+        assert!(validate_strategy("dca", &schedule, &[], &action, &guardrails).is_ok());
+        assert!(validate_strategy("dca", &schedule, &[], &[], &guardrails).is_err());
+
+        let both = StrategyTrigger::Schedule { cron: Some("0 0 9 * * *".to_string()), every_minutes: Some(5) };
+        assert!(validate_strategy("dca", &both, &[], &action, &guardrails).is_err());
+        let bad_cron = StrategyTrigger::Schedule { cron: Some("whenever".to_string()), every_minutes: None };
+        assert!(validate_strategy("dca", &bad_cron, &[], &action, &guardrails).is_err());
+
+        let window = StrategyCondition::TimeWindow { start_hour: 9, end_hour: 9 };
+        assert!(validate_strategy("dca", &schedule, &[window], &action, &guardrails).is_err());
+    }
+}
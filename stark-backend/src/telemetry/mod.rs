@@ -19,7 +19,7 @@ pub use span::{Span, SpanCollector, SpanGuard, SpanStatus, SpanType};
 pub use rollout::{Attempt, FailureReason, Rollout, RolloutConfig, RolloutManager, RolloutStatus};
 pub use emitter::{clear_active_collector, emit_annotation, set_active_collector};
 pub use reward::RewardEmitter;
-pub use watchdog::{Watchdog, WatchdogConfig, WatchdogError};
+pub use watchdog::{Watchdog, WatchdogConfig, WatchdogError, WatchdogNotifier};
 pub use resource_version::{Resource, ResourceBundle, ResourceManager, ResourceType};
 pub use adapter::{Adapter, ExecutionSummary, SpansToSummary, SpansToTimeline, SpansToTriplets, Timeline, Triplet};
 pub use store::{RetentionPolicy, RewardStats, TelemetryStore};
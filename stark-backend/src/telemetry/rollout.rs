@@ -407,6 +407,20 @@ impl RolloutManager {
         self.persist_rollout_completion(rollout);
     }
 
+    /// Flag the rollout as degraded (a step timed out or stalled) with the reasons.
+    pub fn mark_degraded(&self, rollout: &mut Rollout, reasons: &[String]) {
+        if reasons.is_empty() {
+            return;
+        }
+        if let Value::Object(ref mut map) = rollout.metadata {
+            map.insert("degraded".to_string(), Value::Bool(true));
+            map.insert("degraded_reasons".to_string(), serde_json::json!(reasons));
+        }
+        if let Err(e) = self.db.update_rollout_metadata(&rollout.rollout_id, &rollout.metadata) {
+            log::error!("[ROLLOUT] Failed to persist degraded flag: {}", e);
+        }
+    }
+
     /// Get the retry delay for the current attempt.
     pub fn retry_delay(&self, rollout: &Rollout) -> u64 {
         let idx = rollout.attempt_count().saturating_sub(1);
@@ -2,6 +2,12 @@
 //!
 //! Heartbeat monitoring detects unresponsive executions.
 //! Integrates with rollout retry on timeout.
+//!
+//! A timed-out LLM call (or read-only tool) is retried before giving up, and any
+//! timeout marks the execution degraded: the reasons end up in the rollout's
+//! metadata, and the first one is reported to the chat the message came from.
+//! An execution that makes no progress for longer than its current phase allows
+//! is cancelled by the heartbeat monitor.
 
 use std::future::Future;
 use std::sync::Arc;
@@ -29,6 +35,15 @@ pub struct WatchdogConfig {
     pub heartbeat_max_silence_secs: u64,
     /// Per-tool timeout overrides (tool_name → timeout_secs)
     pub tool_overrides: std::collections::HashMap<String, u64>,
+    /// Extra attempts for an LLM call that timed out
+    pub llm_timeout_retries: u32,
+    /// Extra attempts for a read-only tool that timed out
+    pub read_only_tool_retries: u32,
+    /// Silence outside any guarded call before the execution is cancelled (seconds)
+    pub stall_cancel_secs: u64,
+    /// How long past its own timeout a guarded call may hang before the
+    /// execution is cancelled (seconds)
+    pub stall_grace_secs: u64,
 }
 
 impl Default for WatchdogConfig {
@@ -47,6 +62,10 @@ impl Default for WatchdogConfig {
             heartbeat_interval_secs: 30,
             heartbeat_max_silence_secs: 120,
             tool_overrides,
+            llm_timeout_retries: 1,
+            read_only_tool_retries: 1,
+            stall_cancel_secs: 900,
+            stall_grace_secs: 60,
         }
     }
 }
@@ -66,6 +85,62 @@ impl WatchdogConfig {
     pub fn timeout_for_llm(&self) -> Duration {
         Duration::from_secs(self.llm_timeout_secs)
     }
+
+    /// How long an execution may go without a heartbeat before it counts as
+    /// stuck, given the phase it's in (name and timeout), if any.
+    pub fn stall_limit(&self, phase: Option<&(String, Duration)>) -> Duration {
+        match phase {
+            Some((_, phase_timeout)) => *phase_timeout + Duration::from_secs(self.stall_grace_secs),
+            None => Duration::from_secs(self.stall_cancel_secs),
+        }
+    }
+}
+
+/// Where degraded-execution notices go: the chat the message came from.
+#[derive(Clone)]
+pub struct WatchdogNotifier {
+    pub db: Arc<crate::db::Database>,
+    pub broadcaster: Arc<crate::gateway::events::EventBroadcaster>,
+    pub channel_id: i64,
+    pub chat_id: String,
+}
+
+impl WatchdogNotifier {
+    fn notify(&self, reason: &str, first: bool) {
+        self.broadcaster.broadcast(crate::gateway::protocol::GatewayEvent::custom(
+            "execution.degraded",
+            json!({
+                "channel_id": self.channel_id,
+                "chat_id": self.chat_id,
+                "reason": reason,
+            }),
+        ));
+        // One chat message per execution is enough; later reasons only go to the event stream
+        if !first {
+            return;
+        }
+        let notifier = self.clone();
+        let text = format!("⚠️ Something got stuck ({}). I'm working around it — the result may be incomplete.", reason);
+        tokio::spawn(async move {
+            // Web and other non-push channels already see the gateway event
+            if let Err(e) = crate::channels::outbound::send_direct(&notifier.db, notifier.channel_id, &notifier.chat_id, &text, &[]).await {
+                log::debug!("[WATCHDOG] Degraded notice not delivered to channel {}: {}", notifier.channel_id, e);
+            }
+        });
+    }
+}
+
+/// Marks the watchdog as inside a guarded phase until dropped.
+pub struct PhaseGuard {
+    phase: Arc<Mutex<Option<(String, Duration)>>>,
+    last_heartbeat: Arc<Mutex<chrono::DateTime<Utc>>>,
+}
+
+impl Drop for PhaseGuard {
+    fn drop(&mut self) {
+        *self.phase.lock() = None;
+        *self.last_heartbeat.lock() = Utc::now();
+    }
 }
 
 /// Error type for watchdog-guarded operations.
@@ -98,6 +173,11 @@ pub struct Watchdog {
     reward_emitter: Arc<RewardEmitter>,
     /// Tracks the last heartbeat time for the current execution
     last_heartbeat: Arc<Mutex<chrono::DateTime<Utc>>>,
+    /// The guarded call in progress (name and timeout), if any
+    phase: Arc<Mutex<Option<(String, Duration)>>>,
+    /// Why the execution is degraded (empty if it isn't)
+    degraded: Mutex<Vec<String>>,
+    notifier: Option<WatchdogNotifier>,
 }
 
 impl Watchdog {
@@ -111,9 +191,18 @@ impl Watchdog {
             collector,
             reward_emitter,
             last_heartbeat: Arc::new(Mutex::new(Utc::now())),
+            phase: Arc::new(Mutex::new(None)),
+            degraded: Mutex::new(Vec::new()),
+            notifier: None,
         }
     }
 
+    /// Report degraded executions to the originating chat.
+    pub fn with_notifier(mut self, notifier: WatchdogNotifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
     /// Get the watchdog configuration.
     pub fn config(&self) -> &WatchdogConfig {
         &self.config
@@ -136,6 +225,42 @@ impl Watchdog {
         silence > self.config.heartbeat_max_silence_secs as i64
     }
 
+    /// Enter a guarded phase (an LLM or tool call) with its timeout. The phase
+    /// ends, with a heartbeat, when the returned guard is dropped.
+    pub fn enter_phase(&self, name: impl Into<String>, phase_timeout: Duration) -> PhaseGuard {
+        *self.phase.lock() = Some((name.into(), phase_timeout));
+        self.heartbeat();
+        PhaseGuard {
+            phase: Arc::clone(&self.phase),
+            last_heartbeat: Arc::clone(&self.last_heartbeat),
+        }
+    }
+
+    /// Whether the execution has been silent for longer than its current phase allows.
+    pub fn is_stalled(&self) -> bool {
+        let silence = (Utc::now() - *self.last_heartbeat.lock()).to_std().unwrap_or_default();
+        silence > self.config.stall_limit(self.phase.lock().as_ref())
+    }
+
+    /// Record that a step timed out. The first reason is sent to the originating chat.
+    pub fn mark_degraded(&self, reason: impl Into<String>) {
+        let reason = reason.into();
+        let first = {
+            let mut degraded = self.degraded.lock();
+            degraded.push(reason.clone());
+            degraded.len() == 1
+        };
+        log::warn!("[WATCHDOG] Execution degraded: {}", reason);
+        if let Some(ref notifier) = self.notifier {
+            notifier.notify(&reason, first);
+        }
+    }
+
+    /// Why the execution is degraded; empty if nothing timed out.
+    pub fn degraded_reasons(&self) -> Vec<String> {
+        self.degraded.lock().clone()
+    }
+
     /// Guard a tool execution with a timeout.
     ///
     /// Works with infallible futures (e.g., `tool_registry.execute()` which returns
@@ -158,7 +283,7 @@ impl Watchdog {
             "timeout_ms": timeout_ms,
         });
 
-        self.heartbeat();
+        let _phase = self.enter_phase(format!("tool:{}", tool_name), tool_timeout);
 
         match timeout(tool_timeout, fut).await {
             Ok(result) => {
@@ -199,7 +324,7 @@ impl Watchdog {
             "timeout_ms": timeout_ms,
         });
 
-        self.heartbeat();
+        let _phase = self.enter_phase(format!("tool:{}", tool_name), tool_timeout);
 
         match timeout(tool_timeout, fut).await {
             Ok(Ok(result)) => {
@@ -249,7 +374,7 @@ impl Watchdog {
             "timeout_ms": timeout_ms,
         });
 
-        self.heartbeat();
+        let _phase = self.enter_phase(format!("llm:{}", model_name), llm_timeout);
 
         match timeout(llm_timeout, fut).await {
             Ok(Ok(result)) => {
@@ -285,11 +410,14 @@ impl Watchdog {
     ///
     /// The monitor only observes — it does NOT reset the heartbeat. Only actual
     /// execution (guard_tool_call, guard_tool, guard_llm) registers heartbeats.
+    /// An execution that stays silent past its stall limit is marked degraded
+    /// and cancelled, and the monitor stops.
     /// Returns a JoinHandle that should be aborted when the dispatch completes.
     pub fn start_heartbeat_monitor(
         self: &Arc<Self>,
         channel_id: i64,
         broadcaster: Arc<crate::gateway::events::EventBroadcaster>,
+        execution_tracker: Arc<crate::execution::ExecutionTracker>,
     ) -> tokio::task::JoinHandle<()> {
         let watchdog = Arc::clone(self);
         let interval = Duration::from_secs(watchdog.config.heartbeat_interval_secs);
@@ -301,6 +429,22 @@ impl Watchdog {
             loop {
                 ticker.tick().await;
 
+                if watchdog.is_stalled() {
+                    let phase = watchdog.phase.lock().as_ref().map(|(name, _)| name.clone());
+                    let reason = match phase {
+                        Some(name) => format!("{} hung past its timeout", name),
+                        None => format!("no progress for over {}s", watchdog.config.stall_cancel_secs),
+                    };
+                    log::error!("[WATCHDOG] Channel {} execution stalled ({}), cancelling", channel_id, reason);
+                    watchdog.mark_degraded(format!("execution cancelled: {}", reason));
+                    broadcaster.broadcast(crate::gateway::protocol::GatewayEvent::agent_error(
+                        channel_id,
+                        &format!("Execution stalled ({}) and was cancelled.", reason),
+                    ));
+                    execution_tracker.cancel_execution(channel_id);
+                    break;
+                }
+
                 if watchdog.is_unresponsive() {
                     log::warn!(
                         "[WATCHDOG] Channel {} execution appears unresponsive (no heartbeat for >{}s)",
@@ -318,3 +462,16 @@ impl Watchdog {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stall_limit_follows_phase() {
+        let config = WatchdogConfig::default();
+        assert_eq!(config.stall_limit(None), Duration::from_secs(900));
+        let phase = ("tool:spawn_subagents".to_string(), config.timeout_for_tool("spawn_subagents"));
+        assert_eq!(config.stall_limit(Some(&phase)), Duration::from_secs(3660));
+    }
+}
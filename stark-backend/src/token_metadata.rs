@@ -0,0 +1,363 @@
+//! Token metadata service — symbol, name and decimals for any token contract
+//!
+//! Sources, highest priority first:
+//!
+//! 1. **overrides** (`token_metadata_overrides`) for tokens the APIs get wrong
+//! 2. **config/tokens.ron**, the bundled list of well-known tokens
+//! 3. **the on-disk cache** (`token_metadata`), filled from Alchemy's
+//!    `alchemy_getTokenMetadata`, falling back to CoinGecko
+//!
+//! [`lookup`] never touches the network, so formatting code can call it
+//! anywhere: a miss is queued and the background worker fetches it, along with
+//! refreshing entries older than a week. Code that can wait for an unknown
+//! token uses [`resolve`], which fetches on the spot.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+
+use crate::db::Database;
+use crate::tools::builtin::cryptocurrency::token_lookup;
+use crate::tools::rpc_config;
+
+pub use crate::db::tables::token_metadata::TokenMetadata;
+
+/// Cached entries older than this are fetched again
+const REFRESH_AFTER_DAYS: i64 = 7;
+/// A token no source knew is not asked about again for this long
+const RETRY_MISSING_AFTER_MINS: i64 = 60;
+/// Fetches per worker pass (CoinGecko's free tier allows ~30/min)
+const MAX_FETCHES_PER_PASS: usize = 20;
+
+const COINGECKO_URL: &str = "https://api.coingecko.com/api/v3/coins";
+
+/// (network, lowercase address)
+type Key = (String, String);
+
+#[derive(Default)]
+struct Store {
+    overrides: HashMap<Key, TokenMetadata>,
+    cache: HashMap<Key, TokenMetadata>,
+    /// Misses from [`lookup`] waiting for the worker
+    pending: HashSet<Key>,
+    /// Tokens no source knew, and when that was last checked
+    missing: HashMap<Key, DateTime<Utc>>,
+}
+
+static STORE: Lazy<RwLock<Store>> = Lazy::new(|| RwLock::new(Store::default()));
+
+fn key(network: &str, address: &str) -> Key {
+    (network.to_string(), address.trim().to_lowercase())
+}
+
+fn is_address(address: &str) -> bool {
+    let hex = address.trim().trim_start_matches("0x");
+    address.trim().starts_with("0x") && hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Load overrides and cached metadata from the database
+pub fn load(db: &Database) {
+    let overrides = db.list_token_overrides().unwrap_or_else(|e| {
+        log::warn!("[TOKEN_METADATA] Failed to load overrides: {}", e);
+        Vec::new()
+    });
+    let cached = db.list_token_metadata().unwrap_or_else(|e| {
+        log::warn!("[TOKEN_METADATA] Failed to load cache: {}", e);
+        Vec::new()
+    });
+    log::info!("[TOKEN_METADATA] Loaded {} cached tokens and {} overrides", cached.len(), overrides.len());
+    if let Ok(mut store) = STORE.write() {
+        store.overrides = overrides
+            .into_iter()
+            .map(|(token, _)| (key(&token.network, &token.address), token))
+            .collect();
+        store.cache = cached.into_iter().map(|token| (key(&token.network, &token.address), token)).collect();
+    }
+}
+
+fn from_config(network: &str, address: &str) -> Option<TokenMetadata> {
+    let (symbol, info) = token_lookup::lookup_by_address(address, network)?;
+    Some(TokenMetadata {
+        network: network.to_string(),
+        address: address.trim().to_lowercase(),
+        symbol,
+        name: info.name,
+        decimals: info.decimals,
+        source: "config".to_string(),
+        updated_at: String::new(),
+    })
+}
+
+/// Metadata available without a network call
+fn known(network: &str, address: &str) -> Option<TokenMetadata> {
+    let k = key(network, address);
+    let store = STORE.read().ok()?;
+    if let Some(token) = store.overrides.get(&k) {
+        return Some(token.clone());
+    }
+    drop(store);
+    if let Some(token) = from_config(network, address) {
+        return Some(token);
+    }
+    STORE.read().ok()?.cache.get(&k).cloned()
+}
+
+fn recently_missing(store: &Store, k: &Key) -> bool {
+    store
+        .missing
+        .get(k)
+        .is_some_and(|at| Utc::now() - *at < Duration::minutes(RETRY_MISSING_AFTER_MINS))
+}
+
+/// Symbol, name and decimals of a token, from memory only. On a miss the
+/// token is queued so a later lookup finds it.
+pub fn lookup(network: &str, address: &str) -> Option<TokenMetadata> {
+    if let Some(token) = known(network, address) {
+        return Some(token);
+    }
+    if is_address(address) {
+        let k = key(network, address);
+        if let Ok(mut store) = STORE.write() {
+            if !recently_missing(&store, &k) {
+                store.pending.insert(k);
+            }
+        }
+    }
+    None
+}
+
+/// Like [`lookup`], but fetches (and caches) an unknown token before giving up
+pub async fn resolve(db: &Database, network: &str, address: &str) -> Option<TokenMetadata> {
+    if let Some(token) = known(network, address) {
+        return Some(token);
+    }
+    if !is_address(address) || STORE.read().is_ok_and(|store| recently_missing(&store, &key(network, address))) {
+        return None;
+    }
+    fetch_and_store(db, network, address).await
+}
+
+async fn fetch_alchemy(url: &str, network: &str, address: &str) -> Result<Option<TokenMetadata>, String> {
+    let body: Value = crate::http::shared_client()
+        .post(url)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "alchemy_getTokenMetadata", "params": [address] }))
+        .send()
+        .await
+        .map_err(|e| format!("Alchemy request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Alchemy response: {}", e))?;
+    let result = &body["result"];
+    let (Some(symbol), Some(decimals)) = (result["symbol"].as_str(), result["decimals"].as_u64()) else {
+        return Ok(None);
+    };
+    if symbol.trim().is_empty() || decimals > u8::MAX as u64 {
+        return Ok(None);
+    }
+    Ok(Some(TokenMetadata {
+        network: network.to_string(),
+        address: address.to_lowercase(),
+        symbol: symbol.trim().to_string(),
+        name: result["name"].as_str().unwrap_or(symbol).trim().to_string(),
+        decimals: decimals as u8,
+        source: "alchemy".to_string(),
+        updated_at: Utc::now().to_rfc3339(),
+    }))
+}
+
+fn coingecko_platform(network: &str) -> Option<&'static str> {
+    match network {
+        "mainnet" | "ethereum" => Some("ethereum"),
+        "base" => Some("base"),
+        "polygon" => Some("polygon-pos"),
+        "arbitrum" => Some("arbitrum-one"),
+        "optimism" => Some("optimistic-ethereum"),
+        _ => None,
+    }
+}
+
+async fn fetch_coingecko(network: &str, address: &str) -> Result<Option<TokenMetadata>, String> {
+    let Some(platform) = coingecko_platform(network) else {
+        return Ok(None);
+    };
+    let resp = crate::http::shared_client()
+        .get(format!("{}/{}/contract/{}", COINGECKO_URL, platform, address.to_lowercase()))
+        .send()
+        .await
+        .map_err(|e| format!("CoinGecko request failed: {}", e))?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !resp.status().is_success() {
+        return Err(format!("CoinGecko returned {}", resp.status()));
+    }
+    let body: Value = resp.json().await.map_err(|e| format!("Failed to parse CoinGecko response: {}", e))?;
+    let (Some(symbol), Some(decimals)) = (
+        body["symbol"].as_str(),
+        body["detail_platforms"][platform]["decimal_place"].as_u64(),
+    ) else {
+        return Ok(None);
+    };
+    if decimals > u8::MAX as u64 {
+        return Ok(None);
+    }
+    Ok(Some(TokenMetadata {
+        network: network.to_string(),
+        address: address.to_lowercase(),
+        symbol: symbol.to_uppercase(),
+        name: body["name"].as_str().unwrap_or(symbol).to_string(),
+        decimals: decimals as u8,
+        source: "coingecko".to_string(),
+        updated_at: Utc::now().to_rfc3339(),
+    }))
+}
+
+/// Ask Alchemy (when a key is set), then CoinGecko
+async fn fetch(network: &str, address: &str) -> Result<Option<TokenMetadata>, String> {
+    if let Some(url) = rpc_config::alchemy_rpc_url(network) {
+        match fetch_alchemy(&url, network, address).await {
+            Ok(Some(token)) => return Ok(Some(token)),
+            Ok(None) => {}
+            Err(e) => log::debug!("[TOKEN_METADATA] {} on {}: {}", address, network, e),
+        }
+    }
+    fetch_coingecko(network, address).await
+}
+
+async fn fetch_and_store(db: &Database, network: &str, address: &str) -> Option<TokenMetadata> {
+    let k = key(network, address);
+    let fetched = fetch(network, address).await;
+    let mut store = STORE.write().ok()?;
+    store.pending.remove(&k);
+    match fetched {
+        Ok(Some(token)) => {
+            if let Err(e) = db.upsert_token_metadata(&token) {
+                log::warn!("[TOKEN_METADATA] Failed to cache {} on {}: {}", address, network, e);
+            }
+            store.missing.remove(&k);
+            store.cache.insert(k, token.clone());
+            Some(token)
+        }
+        Ok(None) => {
+            log::debug!("[TOKEN_METADATA] No source knows {} on {}", address, network);
+            store.missing.insert(k, Utc::now());
+            None
+        }
+        Err(e) => {
+            log::warn!("[TOKEN_METADATA] Failed to fetch {} on {}: {}", address, network, e);
+            store.missing.insert(k, Utc::now());
+            None
+        }
+    }
+}
+
+/// Fetch queued misses, then refresh the stalest cached entries. Returns how many were fetched.
+pub async fn run_refresh_pass(db: &Database) -> usize {
+    let todo: Vec<Key> = {
+        let Ok(store) = STORE.read() else {
+            return 0;
+        };
+        let stale_before = (Utc::now() - Duration::days(REFRESH_AFTER_DAYS)).to_rfc3339();
+        let mut stale: Vec<&TokenMetadata> =
+            store.cache.values().filter(|t| t.updated_at < stale_before).collect();
+        stale.sort_by(|a, b| a.updated_at.cmp(&b.updated_at));
+        store
+            .pending
+            .iter()
+            .cloned()
+            .chain(stale.into_iter().map(|t| key(&t.network, &t.address)))
+            .take(MAX_FETCHES_PER_PASS)
+            .collect()
+    };
+    let mut fetched = 0;
+    for (network, address) in &todo {
+        if fetch_and_store(db, network, address).await.is_some() {
+            fetched += 1;
+        }
+    }
+    if !todo.is_empty() {
+        log::info!("[TOKEN_METADATA] Refresh pass: {}/{} tokens fetched", fetched, todo.len());
+    }
+    fetched
+}
+
+pub fn spawn_refresh_worker(db: Arc<Database>, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            run_refresh_pass(&db).await;
+        }
+    })
+}
+
+/// Create or replace an override; it applies immediately
+pub fn set_override(
+    db: &Database,
+    network: &str,
+    address: &str,
+    symbol: &str,
+    name: &str,
+    decimals: u8,
+    note: Option<&str>,
+) -> Result<TokenMetadata, String> {
+    if !is_address(address) {
+        return Err(format!("'{}' is not a contract address", address));
+    }
+    let token = db
+        .upsert_token_override(network, address, symbol, name, decimals, note)
+        .map_err(|e| format!("Database error: {}", e))?;
+    if let Ok(mut store) = STORE.write() {
+        store.overrides.insert(key(network, address), token.clone());
+    }
+    Ok(token)
+}
+
+pub fn remove_override(db: &Database, network: &str, address: &str) -> Result<bool, String> {
+    let removed = db.delete_token_override(network, address).map_err(|e| format!("Database error: {}", e))?;
+    if let Ok(mut store) = STORE.write() {
+        store.overrides.remove(&key(network, address));
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_win_and_misses_are_queued() {
+        let db = Database::new(":memory:").unwrap();
+        let address = "0x00000000000000000000000000000000000000Aa";
+
+        assert!(lookup("base", address).is_none());
+        assert!(STORE.read().unwrap().pending.contains(&key("base", address)));
+        assert!(lookup("base", "not-an-address").is_none());
+        assert!(!STORE.read().unwrap().pending.contains(&key("base", "not-an-address")));
+
+        let cached = TokenMetadata {
+            network: "base".to_string(),
+            address: address.to_lowercase(),
+            symbol: "WEIRD".to_string(),
+            name: "Weird Token".to_string(),
+            decimals: 18,
+            source: "alchemy".to_string(),
+            updated_at: Utc::now().to_rfc3339(),
+        };
+        db.upsert_token_metadata(&cached).unwrap();
+        load(&db);
+        assert_eq!(lookup("base", address).map(|t| t.decimals), Some(18));
+
+        set_override(&db, "base", address, "WRD", "Weird (fixed)", 9, Some("contract lies about decimals")).unwrap();
+        let token = lookup("base", &address.to_uppercase().replace("0X", "0x")).unwrap();
+        assert_eq!((token.symbol.as_str(), token.decimals, token.source.as_str()), ("WRD", 9, "override"));
+        assert_eq!(db.list_token_overrides().unwrap()[0].1.as_deref(), Some("contract lies about decimals"));
+
+        assert!(remove_override(&db, "base", address).unwrap());
+        assert_eq!(lookup("base", address).map(|t| t.decimals), Some(18));
+        assert!(set_override(&db, "base", "0x1234", "X", "X", 18, None).is_err());
+    }
+}
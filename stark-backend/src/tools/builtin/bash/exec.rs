@@ -58,7 +58,7 @@ impl ExecTool {
             "workdir".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "Working directory for command execution (defaults to workspace)".to_string(),
+                description: "Working directory for command execution (defaults to workspace). Use \"scratch\" for this execution's throwaway scratch directory, also exported as $SCRATCH_DIR".to_string(),
                 default: None,
                 items: None,
                 enum_values: None,
@@ -272,7 +272,9 @@ impl ExecTool {
 
         let working_dir = if let Some(ref wd) = params.workdir {
             let wd_path = PathBuf::from(wd);
-            if wd_path.is_absolute() {
+            if let Some(scratch_path) = context.resolve_scratch_path(wd) {
+                scratch_path
+            } else if wd_path.is_absolute() {
                 wd_path
             } else {
                 workspace.join(wd_path)
@@ -477,6 +479,10 @@ impl Tool for ExecTool {
         }
 
         let timeout_secs = params.timeout.unwrap_or(60).min(self.max_timeout);
+        // Give up in time to report back, rather than being cut off by the executor's timeout
+        let timeout_secs = crate::tools::timeouts::working_time(context, Duration::from_secs(timeout_secs), Duration::from_secs(2))
+            .as_secs()
+            .max(1);
 
         // Determine working directory
         let workspace = context
@@ -487,7 +493,9 @@ impl Tool for ExecTool {
 
         let working_dir = if let Some(ref wd) = params.workdir {
             let wd_path = PathBuf::from(wd);
-            if wd_path.is_absolute() {
+            if let Some(scratch_path) = context.resolve_scratch_path(wd) {
+                scratch_path
+            } else if wd_path.is_absolute() {
                 wd_path
             } else {
                 workspace.join(wd_path)
@@ -523,6 +531,14 @@ impl Tool for ExecTool {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
+        // Point temp files at the execution's scratch dir so they are cleaned up with it
+        if let Some(ref scratch) = context.scratch_dir {
+            if let Err(e) = context.check_scratch_quota(0) {
+                return ToolResult::error(e);
+            }
+            cmd.env("SCRATCH_DIR", scratch).env("TMPDIR", scratch);
+        }
+
         // Set environment variables from context (API keys)
         // Track which keys are available for diagnostic output
         let mut available_env_vars: Vec<String> = Vec::new();
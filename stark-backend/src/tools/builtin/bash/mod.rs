@@ -15,6 +15,7 @@ mod read_file;
 mod read_symbol;
 mod rename_file;
 mod run_skill_script;
+mod save_artifacts;
 mod write_file;
 
 pub use apply_patch::ApplyPatchTool;
@@ -30,4 +31,5 @@ pub use read_file::ReadFileTool;
 pub use read_symbol::ReadSymbolTool;
 pub use rename_file::RenameFileTool;
 pub use run_skill_script::RunSkillScriptTool;
+pub use save_artifacts::SaveArtifactsTool;
 pub use write_file::WriteFileTool;
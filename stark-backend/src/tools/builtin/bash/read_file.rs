@@ -30,7 +30,7 @@ impl ReadFileTool {
             "path".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "Path to the file to read (relative to workspace directory)"
+                description: "Path to the file to read (relative to workspace directory, or scratch/<name> for this execution's scratch files)"
                     .to_string(),
                 default: None,
                 items: None,
@@ -139,6 +139,9 @@ impl Tool for ReadFileTool {
                 // Strip "notes/" prefix and use notes directory
                 let relative = params.path.strip_prefix("notes/").unwrap_or(&params.path);
                 (notes.join(relative), notes.clone())
+            } else if let Some(scratch_path) = context.resolve_scratch_path(&params.path) {
+                // Per-execution scratch dir lives under the workspace
+                (scratch_path, workspace.clone())
             } else if requested_path.is_absolute() {
                 (requested_path.to_path_buf(), workspace.clone())
             } else {
@@ -358,6 +358,13 @@ impl Tool for RunSkillScriptTool {
         cmd.env("SKILL_NAME", &skill_name);
         cmd.env("SKILL_DIR", skill_dir.to_string_lossy().as_ref());
         cmd.env("WORKSPACE_DIR", workspace.to_string_lossy().as_ref());
+        if let Some(ref scratch) = context.scratch_dir {
+            if let Err(e) = context.check_scratch_quota(0) {
+                return ToolResult::error(e);
+            }
+            cmd.env("SCRATCH_DIR", scratch);
+            cmd.env("TMPDIR", scratch);
+        }
 
         log::info!(
             "[run_skill_script] skill={} script={} action={:?} interpreter={} timeout={}s",
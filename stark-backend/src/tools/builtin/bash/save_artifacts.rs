@@ -0,0 +1,109 @@
+use crate::execution::scratch;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Save artifacts tool - archives this execution's scratch files so they survive cleanup
+/// and registers them as artifacts of the execution
+pub struct SaveArtifactsTool {
+    definition: ToolDefinition,
+}
+
+impl SaveArtifactsTool {
+    pub fn new() -> Self {
+        SaveArtifactsTool {
+            definition: ToolDefinition {
+                name: "save_artifacts".to_string(),
+                description: "Archive every file in this execution's scratch/ directory into artifacts/<execution_id>/ in the workspace and register them as artifacts of this execution (they are attached to your final reply where the channel supports uploads). Scratch files are deleted when the execution ends, so call this when the user asks to keep generated files.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::new(),
+                    required: vec![],
+                },
+                group: ToolGroup::Development,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for SaveArtifactsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for SaveArtifactsTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, _params: Value, context: &ToolContext) -> ToolResult {
+        let scratch_dir = match context.scratch_dir.as_ref() {
+            Some(dir) => dir.clone(),
+            None => return ToolResult::error("No scratch directory is attached to this execution"),
+        };
+
+        // The scratch dir is named after the execution it belongs to
+        let execution_id = match context
+            .execution_id
+            .clone()
+            .or_else(|| Path::new(&scratch_dir).file_name().and_then(|n| n.to_str()).map(String::from))
+        {
+            Some(id) => id,
+            None => return ToolResult::error("Cannot determine the execution for this scratch directory"),
+        };
+
+        // Archived copies count against the global disk quota
+        let size = scratch::usage_bytes(Path::new(&scratch_dir));
+        if let Err(e) = context.check_disk_quota(size as usize) {
+            return ToolResult::error(e);
+        }
+
+        let archived = match tokio::task::spawn_blocking({
+            let execution_id = execution_id.clone();
+            move || scratch::archive(&execution_id)
+        })
+        .await
+        {
+            Ok(Ok(files)) => files,
+            Ok(Err(e)) => return ToolResult::error(format!("Failed to archive scratch files: {}", e)),
+            Err(e) => return ToolResult::error(format!("Archive task failed: {}", e)),
+        };
+
+        if archived.is_empty() {
+            return ToolResult::success("Scratch directory is empty — nothing to archive.");
+        }
+
+        context.record_disk_write(size as usize);
+
+        // Register each file so it can be listed per execution and attached to the reply
+        let workspace = context.workspace_dir.as_ref().map(PathBuf::from);
+        for file in &archived {
+            let file_size = workspace
+                .as_ref()
+                .and_then(|ws| std::fs::metadata(ws.join(file)).ok())
+                .map(|m| m.len())
+                .unwrap_or(0);
+            context.register_artifact(file, file_size);
+        }
+
+        ToolResult::success(format!(
+            "Archived {} file(s) to artifacts/{}/:\n{}",
+            archived.len(),
+            execution_id,
+            archived.join("\n")
+        ))
+        .with_metadata(json!({
+            "execution_id": execution_id,
+            "files": archived,
+            "bytes": size,
+        }))
+    }
+}
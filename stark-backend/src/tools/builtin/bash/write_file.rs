@@ -21,7 +21,7 @@ impl WriteFileTool {
             "path".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "Path to the file to write (relative to workspace directory)"
+                description: "Path to the file to write (relative to workspace directory). Use scratch/<name> for intermediate files that should be cleaned up when this execution ends"
                     .to_string(),
                 default: None,
                 items: None,
@@ -160,6 +160,13 @@ impl Tool for WriteFileTool {
                 );
             }
             (public.join(relative), public.clone())
+        } else if let Some(scratch_path) = context.resolve_scratch_path(&params.path) {
+            // Per-execution scratch dir — capped separately and removed when the execution ends
+            if let Err(e) = context.check_scratch_quota(params.content.len()) {
+                return ToolResult::error(e);
+            }
+            let scratch = PathBuf::from(context.scratch_dir.as_deref().unwrap_or_default());
+            (scratch_path, scratch)
         } else if requested_path.is_absolute() {
             (requested_path.to_path_buf(), workspace.clone())
         } else {
@@ -167,8 +174,8 @@ impl Tool for WriteFileTool {
         };
 
         // Canonicalize base directory for comparison
-        // For notes, public, or modules, create it if it doesn't exist
-        if (params.path.starts_with("notes") || params.path.starts_with("public") || params.path.starts_with("modules")) && !base_dir.exists() {
+        // For notes, public, modules, or scratch, create it if it doesn't exist
+        if (params.path.starts_with("notes") || params.path.starts_with("public") || params.path.starts_with("modules") || params.path.starts_with("scratch")) && !base_dir.exists() {
             if let Err(e) = tokio::fs::create_dir_all(&base_dir).await {
                 return ToolResult::error(format!("Cannot create directory: {}", e));
             }
@@ -0,0 +1,454 @@
+//! Jira/Linear issue tool
+//!
+//! Files user requests and completed planner tasks as issues, updates and
+//! comments on them. Issues are linked to the current session in
+//! `tracked_issues` so status-change webhooks come back to this conversation.
+
+use crate::ai::multi_agent::types::{PlannerTask, TaskStatus};
+use crate::controllers::api_keys::ApiKeyId;
+use crate::db::tables::tracked_issues::NewTrackedIssue;
+use crate::db::Database;
+use crate::integrations::issue_tracker::{self, IssueRef, IssueTracker, IssueUpdate, ProjectMapping};
+use crate::models::ChannelSettingKey;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+pub struct IssueTrackerTool {
+    definition: ToolDefinition,
+}
+
+impl IssueTrackerTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "create: file a new issue (or one for a planner task via task_id). \
+                    export_completed: file every completed planner task that has no issue yet. \
+                    get/update/comment: act on an existing issue. list: issues filed from this session."
+                    .to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec![
+                    "create".to_string(),
+                    "export_completed".to_string(),
+                    "get".to_string(),
+                    "update".to_string(),
+                    "comment".to_string(),
+                    "list".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "project".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Target as tracker:key, e.g. \"linear:ENG\" or \"jira:OPS\". Defaults to the channel's issue project setting.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "issue".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Issue key or ID (ENG-123, OPS-45) for get/update/comment".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "title".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Issue title (create/update)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "description".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Issue description, markdown (create/update)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "status".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "New status / workflow state name for update (e.g. \"In Progress\", \"Done\")".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "comment".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Comment body for the comment action".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "task_id".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Planner task ID to file as an issue (create)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        IssueTrackerTool {
+            definition: ToolDefinition {
+                name: "issue_tracker".to_string(),
+                description: "Create, update and comment on Jira or Linear issues, and file completed planner tasks or user requests as issues. Status changes made in the tracker are reported back to this conversation.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["action".to_string()],
+                },
+                group: ToolGroup::Development,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for IssueTrackerTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueTrackerParams {
+    action: String,
+    project: Option<String>,
+    issue: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    status: Option<String>,
+    comment: Option<String>,
+    task_id: Option<u32>,
+}
+
+/// Title for an issue filed from a planner task: its first line, shortened
+fn task_title(task: &PlannerTask) -> String {
+    let first_line = task.description.lines().next().unwrap_or_default().trim();
+    if first_line.chars().count() > 120 {
+        format!("{}…", first_line.chars().take(119).collect::<String>())
+    } else {
+        first_line.to_string()
+    }
+}
+
+fn issue_summary(issue: &IssueRef) -> String {
+    format!(
+        "{} \"{}\"{}{}",
+        issue.key,
+        issue.title,
+        issue.status.as_ref().map(|s| format!(" [{}]", s)).unwrap_or_default(),
+        issue.url.as_ref().map(|u| format!(" — {}", u)).unwrap_or_default()
+    )
+}
+
+#[async_trait]
+impl Tool for IssueTrackerTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: IssueTrackerParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let Some(db) = context.database.as_deref() else {
+            return ToolResult::error("Database not available");
+        };
+
+        let result = match params.action.as_str() {
+            "create" => self.create(&params, db, context).await,
+            "export_completed" => self.export_completed(&params, db, context).await,
+            "get" | "update" | "comment" => self.existing(&params, db, context).await,
+            "list" => self.list(db, context),
+            other => Err(format!(
+                "Unknown action '{}'. Use create, export_completed, get, update, comment or list.",
+                other
+            )),
+        };
+        result.unwrap_or_else(ToolResult::error)
+    }
+}
+
+impl IssueTrackerTool {
+    fn client(tracker: &str, db: &Database, context: &ToolContext) -> Result<Box<dyn IssueTracker>, String> {
+        issue_tracker::tracker_from_keys(tracker, |key: ApiKeyId| {
+            context
+                .get_api_key_by_id(key)
+                .or_else(|| issue_tracker::api_key(db, key))
+        })
+    }
+
+    /// Explicit `project`, else the channel's `issue_project` setting
+    fn project(params: &IssueTrackerParams, db: &Database, context: &ToolContext) -> Result<ProjectMapping, String> {
+        if let Some(project) = params.project.as_deref().filter(|p| !p.trim().is_empty()) {
+            return ProjectMapping::parse(project);
+        }
+        let setting = context
+            .channel_id
+            .and_then(|id| db.get_channel_setting(id, ChannelSettingKey::IssueProject.as_ref()).ok().flatten())
+            .filter(|v| !v.trim().is_empty())
+            .ok_or("No project given and this channel has no issue project configured. Pass project as \"linear:<TEAM>\" or \"jira:<PROJECT>\".")?;
+        ProjectMapping::parse(&setting)
+    }
+
+    fn planner_tasks(context: &ToolContext) -> Vec<PlannerTask> {
+        context
+            .extra
+            .get("planner_tasks")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Create an issue and link it to this session
+    async fn file_issue(
+        client: &dyn IssueTracker,
+        mapping: &ProjectMapping,
+        title: &str,
+        description: &str,
+        task_id: Option<u32>,
+        db: &Database,
+        context: &ToolContext,
+    ) -> Result<IssueRef, String> {
+        let issue = client.create_issue(&mapping.project, title, description).await?;
+        if let Err(e) = db.insert_tracked_issue(&NewTrackedIssue {
+            tracker: client.name(),
+            external_id: &issue.external_id,
+            issue_key: &issue.key,
+            url: issue.url.as_deref(),
+            title: &issue.title,
+            status: issue.status.as_deref(),
+            channel_id: context.channel_id,
+            session_id: context.session_id,
+            task_id: task_id.map(i64::from),
+        }) {
+            log::warn!("[ISSUE_TRACKER] Created {} but failed to record it: {}", issue.key, e);
+        }
+        Ok(issue)
+    }
+
+    async fn create(&self, params: &IssueTrackerParams, db: &Database, context: &ToolContext) -> Result<ToolResult, String> {
+        let mapping = Self::project(params, db, context)?;
+
+        let task = match params.task_id {
+            Some(task_id) => {
+                let task = Self::planner_tasks(context)
+                    .into_iter()
+                    .find(|t| t.id == task_id)
+                    .ok_or_else(|| format!("Planner task {} not found in this session", task_id))?;
+                let existing = match context.session_id {
+                    Some(session_id) => db
+                        .find_tracked_issue_for_task(session_id, i64::from(task_id))
+                        .map_err(|e| e.to_string())?,
+                    None => None,
+                };
+                if let Some(existing) = existing {
+                    return Ok(ToolResult::success(format!(
+                        "Task {} is already filed as {}",
+                        task_id, existing.issue_key
+                    )));
+                }
+                Some(task)
+            }
+            None => None,
+        };
+
+        let title = params
+            .title
+            .clone()
+            .or_else(|| task.as_ref().map(task_title))
+            .filter(|t| !t.trim().is_empty())
+            .ok_or("'title' is required")?;
+        let description = params
+            .description
+            .clone()
+            .or_else(|| task.as_ref().map(|t| t.description.clone()))
+            .unwrap_or_default();
+
+        let client = Self::client(&mapping.tracker, db, context)?;
+        let issue = Self::file_issue(client.as_ref(), &mapping, &title, &description, params.task_id, db, context).await?;
+
+        Ok(ToolResult::success(format!("Created {}", issue_summary(&issue)))
+            .with_metadata(json!({ "tracker": mapping.tracker, "issue": issue })))
+    }
+
+    async fn export_completed(&self, params: &IssueTrackerParams, db: &Database, context: &ToolContext) -> Result<ToolResult, String> {
+        let session_id = context.session_id.ok_or("No session to export planner tasks from")?;
+        let completed: Vec<PlannerTask> = Self::planner_tasks(context)
+            .into_iter()
+            .filter(|t| t.status == TaskStatus::Completed)
+            .collect();
+        if completed.is_empty() {
+            return Ok(ToolResult::success("No completed planner tasks to export"));
+        }
+
+        let mapping = Self::project(params, db, context)?;
+        let client = Self::client(&mapping.tracker, db, context)?;
+
+        let mut created = Vec::new();
+        let mut skipped = 0;
+        for task in &completed {
+            if db
+                .find_tracked_issue_for_task(session_id, i64::from(task.id))
+                .map_err(|e| e.to_string())?
+                .is_some()
+            {
+                skipped += 1;
+                continue;
+            }
+            let issue = Self::file_issue(
+                client.as_ref(),
+                &mapping,
+                &task_title(task),
+                &task.description,
+                Some(task.id),
+                db,
+                context,
+            )
+            .await?;
+            created.push(issue);
+        }
+
+        let mut lines: Vec<String> = created.iter().map(|i| format!("- {}", issue_summary(i))).collect();
+        if skipped > 0 {
+            lines.push(format!("({} task(s) were already filed)", skipped));
+        }
+        Ok(ToolResult::success(format!(
+            "Filed {} completed task(s) in {}:\n{}",
+            created.len(),
+            mapping.project,
+            lines.join("\n")
+        ))
+        .with_metadata(json!({ "tracker": mapping.tracker, "issues": created })))
+    }
+
+    async fn existing(&self, params: &IssueTrackerParams, db: &Database, context: &ToolContext) -> Result<ToolResult, String> {
+        let id = params.issue.as_deref().ok_or("'issue' is required")?;
+
+        // Prefer the tracker the issue was filed in; otherwise the project mapping
+        let tracked = ["linear", "jira"]
+            .into_iter()
+            .find_map(|t| db.find_tracked_issue(t, id).ok().flatten());
+        let tracker = match &tracked {
+            Some(t) => t.tracker.clone(),
+            None => Self::project(params, db, context)?.tracker,
+        };
+        let client = Self::client(&tracker, db, context)?;
+        let external_id = tracked.as_ref().map(|t| t.external_id.as_str()).unwrap_or(id);
+
+        let issue = match params.action.as_str() {
+            "get" => client.get_issue(external_id).await?,
+            "update" => {
+                let update = IssueUpdate {
+                    title: params.title.clone(),
+                    description: params.description.clone(),
+                    status: params.status.clone(),
+                };
+                client.update_issue(external_id, &update).await?
+            }
+            _ => {
+                let body = params.comment.as_deref().filter(|c| !c.trim().is_empty()).ok_or("'comment' is required")?;
+                client.add_comment(external_id, body).await?;
+                client.get_issue(external_id).await?
+            }
+        };
+
+        if let Some(t) = &tracked {
+            let _ = db.update_tracked_issue(t.id, Some(&issue.title), issue.status.as_deref());
+        }
+
+        let verb = match params.action.as_str() {
+            "get" => "",
+            "update" => "Updated ",
+            _ => "Commented on ",
+        };
+        Ok(ToolResult::success(format!("{}{}", verb, issue_summary(&issue)))
+            .with_metadata(json!({ "tracker": tracker, "issue": issue })))
+    }
+
+    fn list(&self, db: &Database, context: &ToolContext) -> Result<ToolResult, String> {
+        let issues = db
+            .list_tracked_issues(context.session_id, 50)
+            .map_err(|e| e.to_string())?;
+        if issues.is_empty() {
+            return Ok(ToolResult::success("No issues have been filed from this session"));
+        }
+        let lines: Vec<String> = issues
+            .iter()
+            .map(|i| {
+                format!(
+                    "- {} \"{}\" [{}] ({})",
+                    i.issue_key,
+                    i.title,
+                    i.status.as_deref().unwrap_or("unknown"),
+                    i.tracker
+                )
+            })
+            .collect();
+        Ok(ToolResult::success(lines.join("\n")).with_metadata(json!({ "issues": issues })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definition() {
+        let tool = IssueTrackerTool::new();
+        let def = tool.definition();
+
+        assert_eq!(def.name, "issue_tracker");
+        assert_eq!(def.group, ToolGroup::Development);
+        assert!(def.input_schema.required.contains(&"action".to_string()));
+        let actions = def.input_schema.properties["action"].enum_values.as_ref().unwrap();
+        assert!(actions.contains(&"export_completed".to_string()));
+    }
+
+    #[test]
+    fn test_task_title() {
+        let mut task = PlannerTask::new(1, "Add retry to RPC client\nDetails follow".to_string());
+        assert_eq!(task_title(&task), "Add retry to RPC client");
+        task.description = "x".repeat(200);
+        assert_eq!(task_title(&task).chars().count(), 120);
+    }
+}
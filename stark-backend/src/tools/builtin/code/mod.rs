@@ -5,11 +5,13 @@
 mod committer;
 mod deploy;
 mod index_project;
+mod issue_tracker;
 mod pr_quality;
 mod verify_changes;
 
 pub use committer::CommitterTool;
 pub use deploy::DeployTool;
 pub use index_project::IndexProjectTool;
+pub use issue_tracker::IssueTrackerTool;
 pub use pr_quality::PrQualityTool;
 pub use verify_changes::VerifyChangesTool;
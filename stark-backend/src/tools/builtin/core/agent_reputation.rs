@@ -0,0 +1,168 @@
+//! Agent reputation tool
+//!
+//! Looks up another EIP-8004 agent's reputation before work is delegated to it:
+//! the on-chain Reputation Registry summary, plus the score the agent publishes
+//! in its own registration file (self-reported, so it never decides on its own).
+
+use crate::eip8004::config::Eip8004Config;
+use crate::eip8004::identity::IdentityRegistry;
+use crate::eip8004::reputation::ReputationRegistry;
+use crate::eip8004::types::TrustLevel;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+pub struct AgentReputationTool {
+    definition: ToolDefinition,
+}
+
+impl AgentReputationTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "agent_id".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "EIP-8004 agent ID (identity NFT token ID) to look up".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        AgentReputationTool {
+            definition: ToolDefinition {
+                name: "agent_reputation".to_string(),
+                description: "Check another EIP-8004 agent's reputation before delegating work or paying it. \
+                    Returns the on-chain feedback summary, the score the agent self-publishes in its registration file, \
+                    a trust level, and should_trust (true only for High/Medium on-chain trust)."
+                    .to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["agent_id".to_string()],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for AgentReputationTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AgentReputationParams {
+    agent_id: u64,
+}
+
+#[async_trait]
+impl Tool for AgentReputationTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: AgentReputationParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let wallet_provider = match &context.wallet_provider {
+            Some(wp) => wp,
+            None => return ToolResult::error("Wallet not configured. Cannot query EIP-8004 registries."),
+        };
+
+        let config = Eip8004Config::from_env();
+        let (identity_rpc, reputation_rpc) = match (
+            config.build_rpc(Some(wallet_provider.clone())),
+            config.build_rpc(Some(wallet_provider.clone())),
+        ) {
+            (Ok(a), Ok(b)) => (a, b),
+            (Err(e), _) | (_, Err(e)) => return ToolResult::error(format!("Failed to build RPC: {}", e)),
+        };
+        let identity = IdentityRegistry::new(config.clone(), identity_rpc);
+        let reputation = ReputationRegistry::new(config, reputation_rpc);
+
+        let agent = match identity.get_agent_details(params.agent_id).await {
+            Ok(a) => a,
+            Err(e) => return ToolResult::error(format!("Agent #{} not found: {}", params.agent_id, e)),
+        };
+
+        let onchain = if reputation.is_deployed() {
+            reputation.get_summary(params.agent_id, &[], "", "").await.ok()
+        } else {
+            None
+        };
+        let self_reported = agent.registration.as_ref().and_then(|r| r.reputation.clone());
+
+        let trust_level = onchain
+            .as_ref()
+            .map(|s| s.trust_level())
+            .unwrap_or(TrustLevel::Unverified);
+        let should_trust = matches!(trust_level, TrustLevel::High | TrustLevel::Medium);
+
+        let name = agent.registration.as_ref().map(|r| r.name.clone()).unwrap_or_default();
+        let mut summary = format!(
+            "Agent #{} {}— trust level {:?}",
+            params.agent_id,
+            if name.is_empty() { String::new() } else { format!("({}) ", name) },
+            trust_level
+        );
+        match &onchain {
+            Some(s) => summary.push_str(&format!(
+                "\nOn-chain: {} feedback, average {:.1}", s.count, s.average_score
+            )),
+            None => summary.push_str("\nOn-chain: no summary available"),
+        }
+        if let Some(s) = &self_reported {
+            summary.push_str(&format!(
+                "\nSelf-reported: {} feedback, average {:.1} ({:?}) — published by the agent itself, do not rely on it alone",
+                s.count, s.average_score, s.trust_level
+            ));
+        }
+        if !agent.is_active() {
+            summary.push_str("\nWarning: the agent's registration is marked inactive");
+        }
+        summary.push_str(if should_trust {
+            "\nOK to delegate."
+        } else {
+            "\nNot enough trusted reputation — ask the user before delegating or paying this agent."
+        });
+
+        ToolResult::success(summary).with_metadata(json!({
+            "agent_id": params.agent_id,
+            "name": name,
+            "owner": agent.owner_address,
+            "active": agent.is_active(),
+            "onchain": onchain,
+            "self_reported": self_reported,
+            "trust_level": trust_level,
+            "should_trust": should_trust,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definition() {
+        let tool = AgentReputationTool::new();
+        let def = tool.definition();
+        assert_eq!(def.name, "agent_reputation");
+        assert_eq!(def.group, ToolGroup::Finance);
+        assert!(def.input_schema.required.contains(&"agent_id".to_string()));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::channels::proactive;
 use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
@@ -127,36 +128,50 @@ impl Tool for AgentSendTool {
             }
         });
 
-        // For now, we'll implement a simple version that uses HTTP APIs directly
-        // In a full implementation, this would integrate with the ChannelManager
-        match platform.as_str() {
-            "telegram" => {
-                self.send_telegram(&params, context).await
-            }
-            "discord" => {
-                self.send_discord(&params, context).await
-            }
-            "slack" => {
-                self.send_slack(&params, context).await
-            }
+        let platform_name = match platform.as_str() {
+            "telegram" => "Telegram",
+            "discord" => "Discord",
+            "slack" => "Slack",
             other => {
-                ToolResult::error(format!("Unsupported platform: {}. Supported: telegram, discord, slack", other))
+                return ToolResult::error(format!("Unsupported platform: {}. Supported: telegram, discord, slack", other));
             }
+        };
+
+        // Get bot token from channel settings
+        let Some((channel_id, bot_token)) =
+            context.find_channel_with_bot_token(&platform, &format!("{}_bot_token", platform))
+        else {
+            return ToolResult::error(format!(
+                "{0} bot token not available. Configure it in your {0} channel settings.",
+                platform_name
+            ));
+        };
+
+        // Unsolicited by definition: the channel's proactive limits apply
+        let reservation = match context.database.as_deref() {
+            Some(db) => match proactive::admit(db, channel_id, &params.channel, "agent_send") {
+                Ok(id) => Some((db, id)),
+                Err(e) => return ToolResult::error(e),
+            },
+            None => None,
+        };
+
+        // For now, we'll implement a simple version that uses HTTP APIs directly
+        // In a full implementation, this would integrate with the ChannelManager
+        let result = match platform.as_str() {
+            "telegram" => self.send_telegram(&params, &bot_token, context).await,
+            "discord" => self.send_discord(&params, &bot_token, context).await,
+            _ => self.send_slack(&params, &bot_token, context).await,
+        };
+        if let (false, Some((db, id))) = (result.success, reservation) {
+            proactive::release(db, id);
         }
+        result
     }
 }
 
 impl AgentSendTool {
-    async fn send_telegram(&self, params: &AgentSendParams, context: &ToolContext) -> ToolResult {
-        // Get bot token from channel settings
-        let bot_token = match context.find_channel_bot_token("telegram", "telegram_bot_token") {
-            Some(token) => token,
-            None => {
-                return ToolResult::error(
-                    "Telegram bot token not available. Configure it in your Telegram channel settings."
-                );
-            }
-        };
+    async fn send_telegram(&self, params: &AgentSendParams, bot_token: &str, context: &ToolContext) -> ToolResult {
 
         // Build the request
         let url = format!(
@@ -204,16 +219,7 @@ impl AgentSendTool {
         }
     }
 
-    async fn send_discord(&self, params: &AgentSendParams, context: &ToolContext) -> ToolResult {
-        // Get bot token from channel settings
-        let bot_token = match context.find_channel_bot_token("discord", "discord_bot_token") {
-            Some(token) => token,
-            None => {
-                return ToolResult::error(
-                    "Discord bot token not available. Configure it in your Discord channel settings."
-                );
-            }
-        };
+    async fn send_discord(&self, params: &AgentSendParams, bot_token: &str, context: &ToolContext) -> ToolResult {
 
         // Build the request
         let url = format!(
@@ -266,16 +272,7 @@ impl AgentSendTool {
         }
     }
 
-    async fn send_slack(&self, params: &AgentSendParams, context: &ToolContext) -> ToolResult {
-        // Get bot token from channel settings
-        let bot_token = match context.find_channel_bot_token("slack", "slack_bot_token") {
-            Some(token) => token,
-            None => {
-                return ToolResult::error(
-                    "Slack bot token not available. Configure it in your Slack channel settings."
-                );
-            }
-        };
+    async fn send_slack(&self, params: &AgentSendParams, bot_token: &str, context: &ToolContext) -> ToolResult {
 
         // Build the request
         let url = "https://slack.com/api/chat.postMessage";
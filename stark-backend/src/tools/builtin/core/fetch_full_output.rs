@@ -0,0 +1,141 @@
+//! Fetch Full Output Tool
+//!
+//! Pages through a tool result that was condensed because it exceeded the
+//! tool output token budget (see `context::tool_output`).
+
+use crate::context::tool_output::{self, FETCH_TOOL_NAME};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tools::ToolSafetyLevel;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Tool for reading a condensed tool output page by page
+pub struct FetchFullOutputTool {
+    definition: ToolDefinition,
+}
+
+impl FetchFullOutputTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "handle".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Handle from a condensed tool result, e.g. \"out_3f2a9c0d1b7e\".".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "page".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Page to read, starting at 1.".to_string(),
+                default: Some(json!(1)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        Self {
+            definition: ToolDefinition {
+                name: FETCH_TOOL_NAME.to_string(),
+                description: "Read the original content of a tool result that was condensed into a summary because it was too large. Pass the handle from the condensed result and a page number; only fetch the pages you need.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["handle".to_string()],
+                },
+                group: ToolGroup::System,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for FetchFullOutputTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FetchParams {
+    handle: String,
+    #[serde(default = "default_page")]
+    page: usize,
+}
+
+fn default_page() -> usize {
+    1
+}
+
+#[async_trait]
+impl Tool for FetchFullOutputTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: FetchParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available."),
+        };
+
+        let output = match db.get_tool_output(params.handle.trim()) {
+            Ok(Some(o)) => o,
+            Ok(None) => return ToolResult::error(format!("No stored output with handle \"{}\".", params.handle)),
+            Err(e) => return ToolResult::error(format!("Failed to load output: {}", e)),
+        };
+        // Outputs are only readable from the session that produced them
+        if output.session_id.is_some() && output.session_id != context.session_id {
+            return ToolResult::error(format!("No stored output with handle \"{}\".", params.handle));
+        }
+
+        let pages = tool_output::page_count(&output.content);
+        match tool_output::page(&output.content, params.page) {
+            Some(text) => ToolResult::success(format!(
+                "[{} output {} — page {}/{}]\n{}",
+                output.tool_name, output.handle, params.page, pages, text
+            ))
+            .with_metadata(json!({
+                "handle": output.handle,
+                "page": params.page,
+                "pages": pages,
+            })),
+            None => ToolResult::error(format!("Page {} out of range (1-{}).", params.page, pages)),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::ReadOnly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_full_output_definition() {
+        let tool = FetchFullOutputTool::new();
+        let def = tool.definition();
+
+        assert_eq!(def.name, "fetch_full_output");
+        assert_eq!(def.group, ToolGroup::System);
+        assert_eq!(def.input_schema.required, vec!["handle".to_string()]);
+    }
+}
@@ -0,0 +1,338 @@
+//! invoke_skill — run another skill to completion as a building block
+//!
+//! The invoked skill runs in a sub-agent that activates it with `use_skill`
+//! (so its instructions, arguments and required tools apply as usual) and its
+//! final answer comes back as this tool's result. Every invocation is recorded
+//! in skill_runs under the run that invoked it. The chain of skills above an
+//! invocation is checked for cycles and capped at `MAX_SKILL_DEPTH`.
+
+use crate::ai::archetypes::minimax::strip_think_blocks;
+use crate::ai::multi_agent::{SubAgentContext, SubAgentManager, SubAgentStatus};
+use crate::db::tables::skill_runs::{SkillRun, SkillRunTotals, SKILL_RUN_COMPLETED, SKILL_RUN_FAILED};
+use crate::db::Database;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// How deep skills may nest. Each waiting level holds a sub-agent slot, so
+/// this stays within the per-channel sub-agent limit.
+pub const MAX_SKILL_DEPTH: i64 = 3;
+/// Default time the invoked skill gets, in seconds
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+/// Poll interval for the invoked skill's sub-agent (seconds)
+const POLL_INTERVAL_SECS: u64 = 2;
+
+/// Tool for invoking a skill from within another skill and using its result
+pub struct InvokeSkillTool {
+    definition: ToolDefinition,
+}
+
+impl InvokeSkillTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+        properties.insert(
+            "skill_name".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "The skill to run".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "input".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Input or query for the skill".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "args".to_string(),
+            PropertySchema {
+                schema_type: "object".to_string(),
+                description: "Values for the skill's declared arguments, e.g. {\"path\": \"./src\"}".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "timeout".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Seconds the skill may run (default 300, max 3600)".to_string(),
+                default: Some(json!(DEFAULT_TIMEOUT_SECS)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        InvokeSkillTool {
+            definition: ToolDefinition {
+                name: "invoke_skill".to_string(),
+                description: format!(
+                    "Run another skill to completion and get its result back, to use a skill as a \
+                     building block of the current one. Unlike use_skill, the skill runs separately \
+                     and this call waits for its final answer. Skills can nest {} levels deep and \
+                     can't invoke a skill that is already running above them.",
+                    MAX_SKILL_DEPTH
+                ),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["skill_name".to_string()],
+                },
+                group: ToolGroup::System,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for InvokeSkillTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InvokeSkillParams {
+    #[serde(alias = "name")]
+    skill_name: String,
+    #[serde(default)]
+    input: String,
+    #[serde(default)]
+    args: Value,
+    timeout: Option<u64>,
+}
+
+/// Skills above a new invocation, outermost first: the run chain up to its
+/// root, then the skill that was active when the root was invoked
+fn skill_chain(db: &Database, parent: Option<&SkillRun>, active_skill: Option<String>) -> Vec<String> {
+    let Some(parent) = parent else {
+        return active_skill.into_iter().collect();
+    };
+    let mut chain = vec![parent.skill_name.clone()];
+    let mut current = parent.clone();
+    while let Some(parent_id) = current.parent_run_id {
+        match db.get_skill_run(parent_id) {
+            Ok(Some(run)) => {
+                chain.push(run.skill_name.clone());
+                current = run;
+            }
+            _ => break,
+        }
+    }
+    chain.extend(current.caller_skill);
+    chain.reverse();
+    chain
+}
+
+/// Refuse cycles and invocations nested deeper than `MAX_SKILL_DEPTH`
+fn check_invocation(chain: &[String], skill_name: &str, depth: i64) -> Result<(), String> {
+    if chain.iter().any(|s| s == skill_name) {
+        return Err(format!(
+            "Skill cycle: {} → {} is already running above this call",
+            chain.join(" → "),
+            skill_name
+        ));
+    }
+    if depth > MAX_SKILL_DEPTH {
+        return Err(format!(
+            "Skill nesting limit reached ({} levels): {} → {}",
+            MAX_SKILL_DEPTH,
+            chain.join(" → "),
+            skill_name
+        ));
+    }
+    Ok(())
+}
+
+fn skill_task(skill_name: &str, input: &str, args: &Value) -> String {
+    let args_part = match args.as_object() {
+        Some(map) if !map.is_empty() => format!(", and args {}", args),
+        _ => String::new(),
+    };
+    format!(
+        "Run the `{}` skill and return its result.\n\n\
+         1. Call use_skill with skill_name \"{}\", input {:?}{}.\n\
+         2. Follow the skill's instructions to completion.\n\
+         3. Finish with the skill's final result as your answer — it is handed back to the skill that invoked this one.",
+        skill_name, skill_name, input, args_part
+    )
+}
+
+#[async_trait]
+impl Tool for InvokeSkillTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: InvokeSkillParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let Some(db) = &context.database else {
+            return ToolResult::error("Database not available");
+        };
+        let (Some(manager), Some(session_id), Some(channel_id)) =
+            (&context.subagent_manager, context.session_id, context.channel_id)
+        else {
+            return ToolResult::error(
+                "invoke_skill needs an active session with a SubAgentManager to run the skill in",
+            );
+        };
+
+        let skill = match db.get_enabled_skill_by_name(&params.skill_name) {
+            Ok(Some(s)) => s,
+            Ok(None) => {
+                return ToolResult::error(format!("Skill '{}' not found or not enabled", params.skill_name))
+            }
+            Err(e) => return ToolResult::error(format!("Failed to load skill: {}", e)),
+        };
+
+        // Where this call sits: under the run whose sub-agent is making it, or at the top
+        let parent = context
+            .current_subagent_id
+            .as_deref()
+            .and_then(|id| db.get_skill_run_by_subagent(id).ok().flatten());
+        let active_skill = if parent.is_none() {
+            db.get_agent_context(session_id)
+                .ok()
+                .flatten()
+                .and_then(|ctx| ctx.active_skill)
+                .map(|s| s.name)
+        } else {
+            None
+        };
+        let chain = skill_chain(db, parent.as_ref(), active_skill.clone());
+        let depth = parent.as_ref().map(|p| p.depth + 1).unwrap_or(1);
+        if let Err(e) = check_invocation(&chain, &skill.name, depth) {
+            log::warn!("[INVOKE_SKILL] Refused '{}': {}", skill.name, e);
+            return ToolResult::error(e);
+        }
+
+        let run_id = match db.start_skill_run(
+            &skill.name,
+            active_skill.as_deref(),
+            parent.as_ref(),
+            Some(session_id),
+            Some(channel_id),
+            &params.input,
+            &params.args.as_object().map(|_| params.args.to_string()).unwrap_or_else(|| "{}".to_string()),
+        ) {
+            Ok(id) => id,
+            Err(e) => return ToolResult::error(format!("Failed to record skill run: {}", e)),
+        };
+        let started = std::time::Instant::now();
+        let fail = |error: String| {
+            let _ = db.finish_skill_run(run_id, SKILL_RUN_FAILED, None, Some(&error), started.elapsed().as_millis() as i64);
+            ToolResult::error(format!("Skill '{}' failed: {}", skill.name, error))
+        };
+
+        let label = format!("skill-{}", skill.name);
+        let subagent_id = SubAgentManager::generate_id(&label);
+        let timeout_secs = params.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS).min(3600);
+        let mut subagent_context = SubAgentContext::new(
+            subagent_id.clone(),
+            session_id,
+            channel_id,
+            label,
+            skill_task(&skill.name, &params.input, &params.args),
+            timeout_secs,
+        )
+        .with_agent_subtype(skill.subagent_type.clone())
+        .with_identity_id(context.identity_id.clone())
+        .with_active_skill(Some(skill.name.clone()));
+        if let (Some(parent_id), Some(parent_depth)) =
+            (&context.current_subagent_id, context.current_subagent_depth)
+        {
+            subagent_context = subagent_context.with_parent_subagent(parent_id.clone(), parent_depth);
+        }
+
+        log::info!(
+            "[INVOKE_SKILL] Running '{}' (run {}, depth {}, chain {:?})",
+            skill.name, run_id, depth, chain
+        );
+        if let Err(e) = db.set_skill_run_subagent(run_id, &subagent_id) {
+            log::warn!("[INVOKE_SKILL] Failed to link run {} to {}: {}", run_id, subagent_id, e);
+        }
+        if let Err(e) = manager.spawn(subagent_context).await {
+            return fail(format!("could not start: {}", e));
+        }
+
+        // The sub-agent enforces its own timeout; the margin covers its wind-down
+        let deadline = started + std::time::Duration::from_secs(timeout_secs + 60);
+        let status = loop {
+            tokio::time::sleep(std::time::Duration::from_secs(POLL_INTERVAL_SECS)).await;
+            match manager.get_status(&subagent_id) {
+                Ok(Some(status)) if status.status.is_terminal() => break status,
+                Ok(_) if std::time::Instant::now() < deadline => continue,
+                Ok(_) => {
+                    let _ = manager.cancel(&subagent_id);
+                    return fail(format!("no result after {}s", timeout_secs));
+                }
+                Err(e) => return fail(format!("lost track of the run: {}", e)),
+            }
+        };
+
+        let duration_ms = started.elapsed().as_millis() as i64;
+        if status.status != SubAgentStatus::Completed {
+            return fail(status.error.unwrap_or_else(|| status.status.to_string()));
+        }
+        let output = strip_think_blocks(status.result.as_deref().unwrap_or_default());
+        if let Err(e) = db.finish_skill_run(run_id, SKILL_RUN_COMPLETED, Some(&output), None, duration_ms) {
+            log::warn!("[INVOKE_SKILL] Failed to record result of run {}: {}", run_id, e);
+        }
+
+        let mut metadata = json!({
+            "skill_name": skill.name,
+            "run_id": run_id,
+            "root_run_id": parent.as_ref().map(|p| p.root_run_id.unwrap_or(p.id)).unwrap_or(run_id),
+            "depth": depth,
+            "subagent_id": subagent_id,
+            "duration_ms": duration_ms,
+        });
+        // A top-level run reports the totals of everything it invoked
+        if parent.is_none() {
+            if let Ok(tree) = db.list_skill_run_tree(run_id) {
+                metadata["totals"] = json!(SkillRunTotals::from_tree(&tree));
+            }
+        }
+
+        ToolResult::success(format!("## Skill result: {}\n\n{}", skill.name, output)).with_metadata(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_invocation_refuses_cycles_and_depth() {
+        let chain = vec!["plan_trip".to_string(), "weather".to_string()];
+        assert!(check_invocation(&chain, "geo", 3).is_ok());
+        assert!(check_invocation(&chain, "plan_trip", 3).unwrap_err().contains("cycle"));
+        assert!(check_invocation(&chain, "geo", MAX_SKILL_DEPTH + 1).unwrap_err().contains("limit"));
+    }
+
+    #[tokio::test]
+    async fn test_invoke_skill_no_database() {
+        let result = InvokeSkillTool::new()
+            .execute(json!({ "skill_name": "weather" }), &ToolContext::new())
+            .await;
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Database not available"));
+    }
+}
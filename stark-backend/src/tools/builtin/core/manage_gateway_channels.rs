@@ -56,6 +56,7 @@ impl ManageGatewayChannelsTool {
                     "slack".to_string(),
                     "discord".to_string(),
                     "twitter".to_string(),
+                    "farcaster".to_string(),
                     "external_channel".to_string(),
                 ]),
             },
@@ -108,7 +109,7 @@ impl ManageGatewayChannelsTool {
         ManageGatewayChannelsTool {
             definition: ToolDefinition {
                 name: "manage_gateway_channels".to_string(),
-                description: "Manage messaging gateway channels: list, view, create, update, or delete channels (Telegram, Slack, Discord, Twitter, External).".to_string(),
+                description: "Manage messaging gateway channels: list, view, create, update, or delete channels (Telegram, Slack, Discord, Twitter, Farcaster, External).".to_string(),
                 input_schema: ToolInputSchema {
                     schema_type: "object".to_string(),
                     properties,
@@ -164,6 +165,7 @@ const VALID_CHANNEL_TYPES: &[&str] = &[
     "slack",
     "discord",
     "twitter",
+    "farcaster",
     "external_channel",
 ];
 
@@ -149,13 +149,17 @@ impl Tool for ManageModulesTool {
                     let source = installed_entry
                         .map(|e| e.source.as_str())
                         .unwrap_or("builtin");
+                    let health = crate::modules::supervisor::health(module.name())
+                        .map(|h| h.summary())
+                        .unwrap_or_else(|| "unchecked".to_string());
 
                     output.push_str(&format!(
-                        "**{}** v{} — {}\n  Status: {} | Source: {} | Service: {} | Tools: {} | Dashboard: {}\n\n",
+                        "**{}** v{} — {}\n  Status: {} | Health: {} | Source: {} | Service: {} | Tools: {} | Dashboard: {}\n\n",
                         module.name(),
                         module.version(),
                         module.description(),
                         status,
+                        health,
                         source,
                         module.service_url(),
                         if module.has_tools() { "yes" } else { "no" },
@@ -190,6 +194,12 @@ impl Tool for ManageModulesTool {
                     module.has_dashboard(),
                 ) {
                     Ok(_entry) => {
+                        if let Some(dir) = module.module_dir() {
+                            if let Err(e) = crate::modules::lifecycle::run_install_hook(db, name, dir, false).await {
+                                return ToolResult::error(format!("Install hook failed, install rolled back: {}", e));
+                            }
+                        }
+
                         let mut result_parts = vec![
                             format!("Module '{}' installed successfully!", name),
                             format!("Service URL: {}", module.service_url()),
@@ -218,6 +228,13 @@ impl Tool for ManageModulesTool {
                     Some(n) => n,
                     None => return ToolResult::error("'name' is required for 'uninstall' action"),
                 };
+                let registry = crate::modules::ModuleRegistry::new();
+                if let Some(module) = registry.get(name) {
+                    if db.is_module_installed(name).unwrap_or(false) {
+                        crate::modules::lifecycle::run_uninstall_hook(db, name, module.module_dir().map(|d| d.as_path())).await;
+                    }
+                }
+                crate::modules::supervisor::forget(name);
                 match db.uninstall_module(name) {
                     Ok(true) => ToolResult::success(format!(
                         "Module '{}' uninstalled. The service continues running independently.",
@@ -293,6 +310,7 @@ impl Tool for ManageModulesTool {
                             "author": m.author,
                             "service_url": module.service_url(),
                             "installed_at": m.installed_at.to_rfc3339(),
+                            "health": crate::modules::supervisor::health(name),
                         }).to_string())
                     }
                     Ok(None) => ToolResult::error(format!("Module '{}' is not installed", name)),
@@ -441,6 +459,13 @@ impl Tool for ManageModulesTool {
                     Some(&computed_hash),
                 ) {
                     Ok(_) => {
+                        // Pin the extracted binary; every launch is checked against it
+                        if let Ok(binary_hash) = crate::modules::sandbox::sha256_file(&binary_path) {
+                            let _ = db.set_module_binary_sha256(slug, &binary_hash);
+                        }
+                        if let Err(e) = crate::modules::lifecycle::run_install_hook(db, slug, &module_dir, true).await {
+                            return ToolResult::error(format!("Install hook failed, install rolled back: {}", e));
+                        }
                         let mut result = vec![
                             format!("Module '@{}/{}' installed from StarkHub!", username, slug),
                             format!("Version: {}", module_info.version),
@@ -481,15 +506,17 @@ impl Tool for ManageModulesTool {
                 let client = crate::integrations::starkhub_client::StarkHubClient::new();
                 match client.get_module(username, name).await {
                     Ok(remote) => {
-                        if remote.version == installed.version {
+                        if !crate::config::semver_is_newer(&remote.version, &installed.version) {
+                            let _ = db.clear_module_update(name);
                             ToolResult::success(format!(
                                 "Module '{}' is up to date (v{}).",
                                 name, installed.version
                             ))
                         } else {
+                            let _ = db.upsert_module_update(name, &installed.version, &remote.version);
                             ToolResult::success(format!(
-                                "Update available for '{}': v{} -> v{}\nRun: manage_modules(action=\"install_remote\", name=\"@{}/{}\")",
-                                name, installed.version, remote.version, username, name
+                                "Update available for '{}': v{} -> v{}\nUpgrade it from the Modules page in the dashboard (one-click upgrade).",
+                                name, installed.version, remote.version
                             ))
                         }
                     }
@@ -561,6 +588,10 @@ impl Tool for ManageModulesTool {
                     None, // no checksum
                 ) {
                     Ok(_) => {
+                        if let Err(e) = crate::modules::lifecycle::run_install_hook(db, &module_name, &module_dir, true).await {
+                            return ToolResult::error(format!("Install hook failed, install rolled back: {}", e));
+                        }
+
                         // Install bundled skill if present (prefer skill_dir, fall back to content_file)
                         if let Some(ref skill_cfg) = manifest.skill {
                             if let Some(skill_registry) = context.skill_registry.as_ref() {
@@ -3,17 +3,23 @@
 //! Essential tools for agent operation, user interaction, and task management.
 
 mod add_task;
+mod agent_reputation;
 mod define_tasks;
+mod fetch_full_output;
 mod agent_send;
 mod api_keys_check;
 mod ask_user;
 mod heartbeat_config;
 mod import_identity;
+mod invoke_skill;
 mod install_api_key;
 mod manage_modules;
 mod manage_skills;
 mod impulse_map_manage;
 mod read_skill;
+mod remote_agent;
+mod request_clarification;
+mod request_human;
 mod identity_post_register;
 mod register_new_identity;
 mod unregister_identity;
@@ -36,16 +42,22 @@ mod set_theme_accent;
 
 pub use add_task::AddTaskTool;
 pub use define_tasks::DefineTasksTool;
+pub use fetch_full_output::FetchFullOutputTool;
+pub use agent_reputation::AgentReputationTool;
 pub use agent_send::AgentSendTool;
 pub use api_keys_check::ApiKeysCheckTool;
 pub use ask_user::AskUserTool;
 pub use heartbeat_config::HeartbeatConfigTool;
 pub use import_identity::ImportIdentityTool;
+pub use invoke_skill::InvokeSkillTool;
 pub use install_api_key::InstallApiKeyTool;
 pub use manage_modules::ManageModulesTool;
 pub use manage_skills::ManageSkillsTool;
 pub use impulse_map_manage::ImpulseMapManageTool;
 pub use read_skill::ReadSkillTool;
+pub use remote_agent::RemoteAgentTool;
+pub use request_clarification::RequestClarificationTool;
+pub use request_human::RequestHumanTool;
 pub use identity_post_register::IdentityPostRegisterTool;
 pub use register_new_identity::RegisterNewIdentityTool;
 pub use unregister_identity::UnregisterIdentityTool;
@@ -98,12 +98,13 @@ impl ModifySpecialRoleTool {
             "channel_type".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "Channel type for assignment (discord, twitter, telegram, slack, external_channel)".to_string(),
+                description: "Channel type for assignment (discord, twitter, farcaster, telegram, slack, external_channel)".to_string(),
                 default: None,
                 items: None,
                 enum_values: Some(vec![
                     "discord".to_string(),
                     "twitter".to_string(),
+                    "farcaster".to_string(),
                     "telegram".to_string(),
                     "slack".to_string(),
                     "external_channel".to_string(),
@@ -1,3 +1,4 @@
+use crate::tools::payload::ToolPayload;
 use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
@@ -114,77 +115,36 @@ impl Tool for ReadRecentTransactionsTool {
                         .with_metadata(json!({ "count": 0 }));
                 }
 
-                let mut lines = Vec::new();
-                let mut tx_data = Vec::new();
-
-                for tx in &transactions {
-                    let date = tx.broadcast_at.format("%Y-%m-%d %H:%M UTC").to_string();
-                    let hash_display = tx
-                        .tx_hash
-                        .as_deref()
-                        .map(|h| {
-                            if h.len() > 14 {
-                                format!("{}...{}", &h[..8], &h[h.len() - 4..])
-                            } else {
-                                h.to_string()
-                            }
+                let tx_data: Vec<Value> = transactions
+                    .iter()
+                    .map(|tx| {
+                        json!({
+                            "uuid": tx.uuid,
+                            "network": tx.network,
+                            "from": tx.from_address,
+                            "to": tx.to_address,
+                            "value": tx.value,
+                            "value_formatted": tx.value_formatted,
+                            "tx_hash": tx.tx_hash,
+                            "explorer_url": tx.explorer_url,
+                            "status": tx.status.to_string(),
+                            "broadcast_mode": tx.broadcast_mode.to_string(),
+                            "broadcast_at": tx.broadcast_at.to_rfc3339(),
                         })
-                        .unwrap_or_else(|| "pending".to_string());
-
-                    let from_short = if tx.from_address.len() > 12 {
-                        format!(
-                            "{}...{}",
-                            &tx.from_address[..6],
-                            &tx.from_address[tx.from_address.len() - 4..]
-                        )
-                    } else {
-                        tx.from_address.clone()
-                    };
-                    let to_short = if tx.to_address.len() > 12 {
-                        format!(
-                            "{}...{}",
-                            &tx.to_address[..6],
-                            &tx.to_address[tx.to_address.len() - 4..]
-                        )
-                    } else {
-                        tx.to_address.clone()
-                    };
-
-                    lines.push(format!(
-                        "{} | {} | {} → {} | {} | {} | {}",
-                        date,
-                        tx.network,
-                        from_short,
-                        to_short,
-                        tx.value_formatted,
-                        tx.status,
-                        tx.explorer_url.as_deref().unwrap_or(&hash_display),
-                    ));
-
-                    tx_data.push(json!({
-                        "uuid": tx.uuid,
-                        "network": tx.network,
-                        "from": tx.from_address,
-                        "to": tx.to_address,
-                        "value": tx.value,
-                        "value_formatted": tx.value_formatted,
-                        "tx_hash": tx.tx_hash,
-                        "explorer_url": tx.explorer_url,
-                        "status": tx.status.to_string(),
-                        "broadcast_mode": tx.broadcast_mode.to_string(),
-                        "broadcast_at": tx.broadcast_at.to_rfc3339(),
-                    }));
-                }
-
-                ToolResult::success(format!(
-                    "Recent transactions ({}):\n\n{}",
-                    transactions.len(),
-                    lines.join("\n")
-                ))
-                .with_metadata(json!({
-                    "count": transactions.len(),
-                    "transactions": tx_data
-                }))
+                    })
+                    .collect();
+
+                let table = ToolPayload::table(
+                    tx_data.clone(),
+                    &["broadcast_at", "network", "from", "to", "value_formatted", "status", "tx_hash"],
+                )
+                .titled("Recent transactions");
+                ToolResult::success(format!("Recent transactions ({}):", transactions.len()))
+                    .with_payload(table)
+                    .with_metadata(json!({
+                        "count": transactions.len(),
+                        "transactions": tx_data
+                    }))
             }
             Err(e) => ToolResult::error(format!("Failed to query transactions: {}", e)),
         }
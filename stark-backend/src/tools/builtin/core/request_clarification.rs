@@ -0,0 +1,193 @@
+//! Request clarification tool - lets the task planner ask instead of guessing
+//!
+//! Offered to the planner alongside `define_tasks` while the request is still
+//! being planned. The dispatcher intercepts the metadata, parks the execution
+//! and sends the question (with its suggested answers as quick replies) to the
+//! channel; the user's next message resumes the same execution with the answer
+//! attached to the request.
+
+use crate::channels::actions;
+use crate::channels::types::ActionButton;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tools::ToolSafetyLevel;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Suggested answers beyond this are dropped
+const MAX_OPTIONS: usize = 4;
+
+/// Tool for asking the user to clarify an ambiguous request
+pub struct RequestClarificationTool {
+    definition: ToolDefinition,
+}
+
+impl RequestClarificationTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "question".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "One short, specific question that resolves the ambiguity.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "options".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "2-4 suggested answers, shown as quick replies. The user may also answer freely.".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "A suggested answer".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "reason".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Optional: what is ambiguous about the request, shown above the question.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        RequestClarificationTool {
+            definition: ToolDefinition {
+                name: "request_clarification".to_string(),
+                description: "Ask the user to clarify an ambiguous request before planning. The execution waits for the answer and then resumes planning with it. Only use this when the request could reasonably mean different things; never for details that can be looked up.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["question".to_string()],
+                },
+                group: ToolGroup::System,
+                hidden: true,
+            },
+        }
+    }
+}
+
+impl Default for RequestClarificationTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RequestClarificationParams {
+    question: String,
+    #[serde(default)]
+    options: Vec<String>,
+    reason: Option<String>,
+}
+
+#[async_trait]
+impl Tool for RequestClarificationTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: RequestClarificationParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let question = params.question.trim().to_string();
+        if question.is_empty() {
+            return ToolResult::error("'question' must not be empty.");
+        }
+        let options: Vec<String> = params
+            .options
+            .iter()
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty())
+            .take(MAX_OPTIONS)
+            .collect();
+
+        let mut output = String::new();
+        if let Some(reason) = params.reason.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+            output.push_str(&format!("📋 {}\n\n", reason));
+        }
+        output.push_str(&format!("❓ **{}**\n", question));
+        if !options.is_empty() {
+            output.push_str("\nOptions:\n");
+            for (i, opt) in options.iter().enumerate() {
+                output.push_str(&format!("  {}. {}\n", i + 1, opt));
+            }
+        }
+
+        // Suggested answers become quick replies on channels that render buttons
+        let buttons: Vec<ActionButton> = options
+            .iter()
+            .map(|o| ActionButton {
+                label: o.clone(),
+                action: actions::ACTION_REPLY.to_string(),
+                payload: None,
+            })
+            .collect();
+        context.registers.set(
+            actions::REPLY_ACTIONS_REGISTER,
+            serde_json::to_value(actions::sanitize(buttons)).unwrap_or_default(),
+            "request_clarification",
+        );
+
+        ToolResult::success(output).with_metadata(json!({
+            "request_clarification": true,
+            "question": question,
+            "options": options,
+        }))
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::SafeMode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_clarification_metadata_and_quick_replies() {
+        let tool = RequestClarificationTool::new();
+        assert!(tool.definition().hidden);
+
+        let context = ToolContext::default();
+        let result = tool
+            .execute(
+                json!({"question": "Which token?", "options": ["USDC", " ", "DAI"], "reason": "You hold several"}),
+                &context,
+            )
+            .await;
+
+        assert!(result.success);
+        assert!(result.content.contains("❓ **Which token?**"));
+        assert!(result.content.contains("2. DAI"));
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata["request_clarification"], true);
+        assert_eq!(metadata["options"].as_array().unwrap().len(), 2);
+        assert_eq!(actions::actions_from_registers(&context.registers).len(), 2);
+
+        let empty = tool.execute(json!({"question": "  "}), &context).await;
+        assert!(!empty.success);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::db::tables::handoffs::NewHandoff;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult, ToolSafetyLevel,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Tool for handing the current chat over to a human operator
+pub struct RequestHumanTool {
+    definition: ToolDefinition,
+}
+
+impl RequestHumanTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+        properties.insert(
+            "reason".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Why a person needs to take over, for the operator who picks it up.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        RequestHumanTool {
+            definition: ToolDefinition {
+                name: "request_human".to_string(),
+                description: "Hand this conversation over to a human operator. Use when the user asks for a person, \
+                              is upset in a way you can't resolve, or needs a decision you're not allowed to make. \
+                              Operators are notified and you stop receiving this chat's messages until they hand it back."
+                    .to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["reason".to_string()],
+                },
+                group: ToolGroup::System,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for RequestHumanTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RequestHumanParams {
+    reason: String,
+}
+
+#[async_trait]
+impl Tool for RequestHumanTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: RequestHumanParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        let reason = params.reason.trim();
+        if reason.is_empty() {
+            return ToolResult::error("Give a reason for the operator");
+        }
+
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let (Some(channel_id), Some(chat_id)) = (context.channel_id, context.platform_chat_id.as_deref()) else {
+            return ToolResult::error("Only a conversation on a channel can be handed to a human");
+        };
+
+        let request = NewHandoff {
+            session_id: context.session_id,
+            channel_id,
+            channel_type: context.channel_type.as_deref().unwrap_or("unknown"),
+            chat_id,
+            user_name: None,
+            reason,
+            requested_by: "agent",
+        };
+        match crate::handoff::escalate(db, context.broadcaster.as_deref(), &request).await {
+            Ok(handoff) => ToolResult::success(format!(
+                "Handoff #{} is open: an operator has been notified. Tell the user a person will reply here \
+                 shortly. You won't see this chat's messages until the operator hands it back.",
+                handoff.id
+            ))
+            .with_metadata(json!({ "handoff_id": handoff.id, "status": handoff.status })),
+            Err(e) => ToolResult::error(format!("Failed to request a human: {}", e)),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::SafeMode
+    }
+}
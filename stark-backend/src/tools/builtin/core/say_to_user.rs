@@ -6,7 +6,12 @@
 //!
 //! When `finished_task` is true, this also terminates the orchestrator loop,
 //! acting as both a communication and completion signal.
+//!
+//! Optional `buttons` are attached to the message on channels that support them
+//! (see `channels::actions`).
 
+use crate::channels::actions;
+use crate::channels::types::ActionButton;
 use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
@@ -48,6 +53,23 @@ impl SayToUserTool {
             },
         );
 
+        properties.insert(
+            "buttons".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Optional buttons shown under the message on Telegram/Discord. Each is {\"label\": string, \"action\": string, \"payload\": object (optional)}. Use action \"reply\" for quick replies (pressing sends the label as the user's message); any other action (e.g. \"approve_tx\", \"rerun_task\", \"snooze_reminder\") comes back to you as a button-press event with its payload.".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "object".to_string(),
+                    description: "A button: {label, action, payload?}".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
         SayToUserTool {
             definition: ToolDefinition {
                 name: "say_to_user".to_string(),
@@ -75,6 +97,8 @@ struct SayToUserParams {
     message: String,
     #[serde(default)]
     finished_task: bool,
+    #[serde(default)]
+    buttons: Vec<ActionButton>,
 }
 
 #[async_trait]
@@ -91,6 +115,14 @@ impl Tool for SayToUserTool {
 
         let message = context.registers.expand_templates(&params.message);
 
+        // Buttons belong to this message only — a later say_to_user without buttons clears them
+        let buttons = actions::sanitize(params.buttons);
+        context.registers.set(
+            actions::REPLY_ACTIONS_REGISTER,
+            serde_json::to_value(&buttons).unwrap_or_default(),
+            "say_to_user",
+        );
+
         let mut result = ToolResult::success(message);
 
         // Signal to the orchestrator that this completes the task
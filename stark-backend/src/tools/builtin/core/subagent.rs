@@ -225,7 +225,8 @@ impl SpawnSubagentsTool {
         .with_thinking(spec.thinking.clone())
         .with_read_only(read_only)
         .with_agent_subtype(spec.agent_subtype.clone())
-        .with_identity_id(context.identity_id.clone());
+        .with_identity_id(context.identity_id.clone())
+        .with_active_skill(context.active_skill.clone());
 
         // Propagate parent identity for depth tracking
         if let (Some(parent_id), Some(parent_depth)) =
@@ -37,6 +37,9 @@ struct UseSkillParams {
     skill_name: String,
     #[serde(default, alias = "inputs")]
     input: String,
+    /// Values for the skill's `{{argument}}` placeholders
+    #[serde(default)]
+    args: Value,
 }
 
 #[async_trait]
@@ -63,6 +66,16 @@ impl Tool for UseSkillTool {
                 enum_values: None,
             },
         );
+        properties.insert(
+            "args".to_string(),
+            PropertySchema {
+                schema_type: "object".to_string(),
+                description: "Values for the skill's declared arguments, e.g. {\"path\": \"./src\"}".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
 
         ToolDefinition {
             name: "use_skill".to_string(),
@@ -120,6 +133,10 @@ impl Tool for UseSkillTool {
             }
         };
 
+        // Description and prompt in the user's language when the skill has them
+        let language = crate::skills::locales::preferred_language(db, context.identity_id.as_deref());
+        let skill = skill.localized(language.as_deref());
+
         // Pre-flight: check required binaries are installed
         let missing_bins: Vec<&String> = skill
             .requires_binaries
@@ -163,8 +180,10 @@ impl Tool for UseSkillTool {
         // Replace {baseDir} placeholder with actual skill directory
         let skills_dir = crate::config::runtime_skills_dir();
         let skill_base_dir = format!("{}/{}", skills_dir, skill.name);
+        let args = crate::skills::types::args_from_json(&params.args);
         let instructions = if !skill.body.is_empty() {
-            skill.body.replace("{baseDir}", &skill_base_dir)
+            let body = if args.is_empty() { skill.body.clone() } else { skill.render_body(&args) };
+            body.replace("{baseDir}", &skill_base_dir)
         } else {
             String::new()
         };
@@ -0,0 +1,461 @@
+//! batch_tx tool - compose several queued transactions into one
+//!
+//! Takes queued (not yet broadcast) transactions from the same wallet and
+//! network and shows a combined decoded preview. When every call is safe to
+//! batch, they are merged into a single Multicall3 `aggregate3Value`
+//! transaction (atomic: one failing call reverts all) that replaces the
+//! originals and saves the per-transaction base gas. A call is only safe to
+//! batch when it doesn't depend on who sends it — through Multicall3 the
+//! sender becomes the Multicall3 contract, so token transfers, approvals and
+//! swaps (which act on the caller's balance or allowance) can't be merged
+//! from a plain wallet. Those fall back to sequential execution in nonce
+//! order. With `execute`, the batch (or each transaction in turn, stopping
+//! at the first failure) is broadcast through broadcast_web3_tx.
+
+use super::broadcast_web3_tx::BroadcastWeb3TxTool;
+use crate::tools::registry::Tool;
+use crate::tools::rpc_config::resolve_rpc_from_context;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tx_queue::{funds, nonce, QueuedTransaction, QueuedTxStatus};
+use crate::web3::{amounts, decode, get_chain_id};
+use crate::x402::X402EvmRpc;
+use async_trait::async_trait;
+use ethers::abi::{encode, Token};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Eip1559TransactionRequest, U256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Multicall3 (same address on every supported chain)
+const MULTICALL3: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+/// aggregate3Value((address,bool,uint256,bytes)[])
+const AGGREGATE3_VALUE_SELECTOR: &str = "174dea71";
+/// Base cost every transaction pays, saved for each merged transaction
+const TX_BASE_GAS: u64 = 21_000;
+const MAX_BATCH: usize = 20;
+
+/// Batch transaction composer tool
+pub struct BatchTxTool {
+    definition: ToolDefinition,
+}
+
+impl BatchTxTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "uuids".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "UUIDs of the queued transactions to compose (at least two, same wallet and network).".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "Queued transaction UUID".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "mode".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'auto' merges into one multicall when every call is safe to batch, else plans sequential execution; 'sequential' never merges.".to_string(),
+                default: Some(json!("auto")),
+                items: None,
+                enum_values: Some(vec!["auto".to_string(), "sequential".to_string()]),
+            },
+        );
+
+        properties.insert(
+            "execute".to_string(),
+            PropertySchema {
+                schema_type: "boolean".to_string(),
+                description: "Broadcast the result right away (default false: only compose and preview).".to_string(),
+                default: Some(json!(false)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        BatchTxTool {
+            definition: ToolDefinition {
+                name: "batch_tx".to_string(),
+                description: "Compose several queued transactions: shows a combined decoded preview, merges them into one Multicall3 transaction when that is safe (plain native transfers), and otherwise plans sequential execution in nonce order. Set execute to broadcast.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["uuids".to_string()],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for BatchTxTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchTxParams {
+    uuids: Vec<String>,
+    #[serde(default = "default_mode")]
+    mode: String,
+    #[serde(default)]
+    execute: bool,
+}
+
+fn default_mode() -> String {
+    "auto".to_string()
+}
+
+/// Why a call can't go through Multicall3, or None if it can
+fn unbatchable_reason(tx: &QueuedTransaction) -> Option<&'static str> {
+    let has_calldata = !tx.data.trim().trim_start_matches("0x").is_empty();
+    if has_calldata {
+        Some("contract call acts as msg.sender (the wallet's tokens/allowances)")
+    } else if tx.to.eq_ignore_ascii_case(MULTICALL3) {
+        Some("already a multicall")
+    } else {
+        None
+    }
+}
+
+/// aggregate3Value calldata for the transactions (allowFailure = false: all or nothing)
+fn multicall_calldata(txs: &[QueuedTransaction]) -> Result<(Vec<u8>, U256), String> {
+    let mut total_value = U256::zero();
+    let mut calls = Vec::with_capacity(txs.len());
+    for tx in txs {
+        let target: Address = tx.to.parse().map_err(|_| format!("Invalid address '{}' in {}", tx.to, tx.uuid))?;
+        let value = amounts::parse_raw(&tx.value).map_err(|e| format!("Invalid value in {}: {}", tx.uuid, e))?;
+        let data = hex::decode(tx.data.trim().trim_start_matches("0x"))
+            .map_err(|e| format!("Invalid calldata in {}: {}", tx.uuid, e))?;
+        total_value = total_value
+            .checked_add(value)
+            .ok_or_else(|| format!("Total value overflows at {}", tx.uuid))?;
+        calls.push(Token::Tuple(vec![
+            Token::Address(target),
+            Token::Bool(false),
+            Token::Uint(value),
+            Token::Bytes(data),
+        ]));
+    }
+    let mut calldata = hex::decode(AGGREGATE3_VALUE_SELECTOR).unwrap_or_default();
+    calldata.extend(encode(&[Token::Array(calls)]));
+    Ok((calldata, total_value))
+}
+
+/// The UUIDs in the order given; a UUID listed twice would run its call (and send its value) twice
+fn unique_uuids(uuids: &[String]) -> Result<Vec<String>, String> {
+    let mut seen = HashSet::new();
+    for uuid in uuids {
+        if !seen.insert(uuid.as_str()) {
+            return Err(format!("Transaction {} is listed more than once", uuid));
+        }
+    }
+    Ok(uuids.to_vec())
+}
+
+impl BatchTxTool {
+    /// Load, check and order the transactions by nonce
+    fn load(params: &BatchTxParams, context: &ToolContext) -> Result<Vec<QueuedTransaction>, String> {
+        let tx_queue = context.tx_queue.as_ref().ok_or("Transaction queue not available.")?;
+        let uuids = unique_uuids(&params.uuids)?;
+        if uuids.len() < 2 {
+            return Err("Provide at least two queued transaction UUIDs.".to_string());
+        }
+        if uuids.len() > MAX_BATCH {
+            return Err(format!("At most {} transactions can be composed at once.", MAX_BATCH));
+        }
+        let mut txs = Vec::with_capacity(uuids.len());
+        for uuid in &uuids {
+            let tx = tx_queue.get(uuid).ok_or_else(|| format!("Transaction {} not found in the queue", uuid))?;
+            if tx.status != QueuedTxStatus::Pending {
+                return Err(format!("Transaction {} is {}; only queued (pending) transactions can be composed", uuid, tx.status));
+            }
+            if tx.replaces.is_some() {
+                return Err(format!("Transaction {} is a speed-up/cancel replacement; broadcast it on its own", uuid));
+            }
+            txs.push(tx);
+        }
+        let first = &txs[0];
+        if let Some(other) = txs.iter().find(|tx| tx.network != first.network || !tx.from.eq_ignore_ascii_case(&first.from)) {
+            return Err(format!(
+                "Transaction {} is from {} on {}, but {} is from {} on {}; compose one wallet and network at a time",
+                other.uuid, other.from, other.network, first.uuid, first.from, first.network
+            ));
+        }
+        txs.sort_by_key(|tx| tx.nonce);
+        Ok(txs)
+    }
+
+    async fn merge(&self, txs: &[QueuedTransaction], context: &ToolContext) -> Result<QueuedTransaction, String> {
+        let (Some(wallet_provider), Some(tx_queue)) = (&context.wallet_provider, &context.tx_queue) else {
+            return Err("Wallet or transaction queue not available.".to_string());
+        };
+        let network = txs[0].network.clone();
+        let from = txs[0].from.clone();
+        let from_address: Address = from.parse().map_err(|_| format!("Invalid wallet address: {}", from))?;
+        let multicall: Address = MULTICALL3.parse().map_err(|_| "Invalid Multicall3 address".to_string())?;
+        let (calldata, total_value) = multicall_calldata(txs)?;
+
+        let rpc_config = resolve_rpc_from_context(&context.extra, &network);
+        let rpc = X402EvmRpc::new_with_wallet_provider(
+            wallet_provider.clone(),
+            &network,
+            Some(rpc_config.url.clone()),
+            rpc_config.use_x402,
+        )?;
+        let gas = rpc
+            .estimate_gas(from_address, multicall, &calldata, total_value)
+            .await
+            .map_err(|e| format!("Batch gas estimation failed (would revert?): {}", e))?;
+        let gas = gas * U256::from(120) / U256::from(100);
+        let (max_fee, priority_fee) = rpc.estimate_eip1559_fees().await?;
+
+        // The batch takes over the lowest nonce; the others are given back
+        let batch_nonce = txs[0].nonce;
+        let tx = Eip1559TransactionRequest::new()
+            .from(from_address)
+            .to(multicall)
+            .value(total_value)
+            .data(calldata.clone())
+            .nonce(batch_nonce)
+            .gas(gas)
+            .max_fee_per_gas(max_fee)
+            .max_priority_fee_per_gas(priority_fee)
+            .chain_id(get_chain_id(&network));
+        let typed_tx: TypedTransaction = tx.into();
+        let signature = wallet_provider
+            .sign_transaction(&typed_tx)
+            .await
+            .map_err(|e| format!("Failed to sign batch: {}", e))?;
+
+        let batch = QueuedTransaction::new(
+            Uuid::new_v4().to_string(),
+            network.clone(),
+            from.clone(),
+            MULTICALL3.to_string(),
+            total_value.to_string(),
+            format!("0x{}", hex::encode(&calldata)),
+            gas.to_string(),
+            max_fee.to_string(),
+            priority_fee.to_string(),
+            batch_nonce,
+            format!("0x{}", hex::encode(typed_tx.rlp_signed(&signature))),
+            context.channel_id,
+        );
+        // The batch takes the place of the transactions it merges, so only its own cost counts
+        let merged: Vec<String> = txs.iter().map(|tx| tx.uuid.clone()).collect();
+        funds::ensure_affordable(tx_queue, &batch, None, &merged).await?;
+        tx_queue.queue(batch.clone());
+        for tx in txs {
+            tx_queue.mark_failed(&tx.uuid, &format!("Merged into batch transaction {}", batch.uuid));
+        }
+        nonce::hold(&network, &from, batch_nonce);
+        log::info!("[batch_tx] Merged {} transactions into {} (nonce {})", txs.len(), batch.uuid, batch_nonce);
+        Ok(batch)
+    }
+}
+
+#[async_trait]
+impl Tool for BatchTxTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: BatchTxParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        let txs = match Self::load(&params, context) {
+            Ok(txs) => txs,
+            Err(e) => return ToolResult::error(e),
+        };
+        let network = txs[0].network.clone();
+
+        // Combined preview
+        let abis = decode::registry_abis();
+        let mut content = format!("BATCH OF {} TRANSACTIONS on {}\n", txs.len(), network);
+        let mut items = Vec::new();
+        let mut total_value = U256::zero();
+        let mut total_gas = U256::zero();
+        for (i, tx) in txs.iter().enumerate() {
+            let preview = decode::preview_with(&abis, &tx.network, &tx.to, &tx.value, &tx.data);
+            total_value = total_value.saturating_add(amounts::parse_raw(&tx.value).unwrap_or_default());
+            total_gas = total_gas.saturating_add(amounts::parse_raw(&tx.gas_limit).unwrap_or_default());
+            content.push_str(&format!("\n{}. nonce {} · uuid {}\n{}\n", i + 1, tx.nonce, tx.uuid, preview.explain()));
+            items.push(json!({
+                "uuid": tx.uuid,
+                "nonce": tx.nonce,
+                "preview": preview,
+                "unbatchable": unbatchable_reason(tx),
+            }));
+        }
+        content.push_str(&format!("\nTotal value: {}\n", amounts::format_eth(total_value)));
+
+        // Merging frees every nonce but the lowest; a later queued transaction would be left behind a gap
+        let max_nonce = txs.last().map(|tx| tx.nonce).unwrap_or(0);
+        let blocked_by_later = context.tx_queue.as_ref().is_some_and(|q| {
+            q.list_pending().iter().filter_map(|s| q.get(&s.uuid)).any(|tx| {
+                tx.network == network
+                    && tx.from.eq_ignore_ascii_case(&txs[0].from)
+                    && tx.nonce > max_nonce
+                    && !txs.iter().any(|t| t.uuid == tx.uuid)
+            })
+        });
+        let reasons: Vec<String> = txs
+            .iter()
+            .filter_map(|tx| unbatchable_reason(tx).map(|r| format!("{}: {}", tx.uuid, r)))
+            .collect();
+        let fallback_reason = if params.mode == "sequential" {
+            Some("sequential mode requested".to_string())
+        } else if !reasons.is_empty() {
+            Some(format!("not safe to batch from a wallet — {}", reasons.join("; ")))
+        } else if blocked_by_later {
+            Some("other queued transactions use later nonces; merging would leave them stuck behind a nonce gap".to_string())
+        } else {
+            None
+        };
+
+        let broadcast = BroadcastWeb3TxTool::new();
+        match fallback_reason {
+            None => {
+                let batch = match self.merge(&txs, context).await {
+                    Ok(batch) => batch,
+                    Err(e) => return ToolResult::error(format!("{}\n\nNothing was changed; the transactions are still queued individually.", e)),
+                };
+                let batch_gas = amounts::parse_raw(&batch.gas_limit).unwrap_or_default();
+                let saved = U256::from(TX_BASE_GAS * (txs.len() as u64 - 1));
+                context.set_register("queued_tx_uuid", json!(&batch.uuid), "batch_tx");
+                content.push_str(&format!(
+                    "\nMERGED into one Multicall3 transaction (atomic)\nUUID: {}\nGas limit: {} (was {} across {} transactions; saves ~{} base gas)\nThe original transactions were withdrawn from the queue.\n",
+                    batch.uuid, batch_gas, total_gas, txs.len(), saved
+                ));
+                let mut metadata = json!({
+                    "mode": "multicall",
+                    "uuid": batch.uuid,
+                    "network": network,
+                    "items": items,
+                    "gas_limit": batch.gas_limit,
+                    "gas_limit_before": total_gas.to_string(),
+                });
+                if params.execute {
+                    let result = broadcast.execute(json!({ "uuid": batch.uuid }), context).await;
+                    content.push_str(&format!("\n{}", result.content));
+                    metadata["broadcast"] = json!(result.success);
+                    if !result.success {
+                        return ToolResult::error(content).with_metadata(metadata);
+                    }
+                } else {
+                    content.push_str(&format!("\nNext: broadcast_web3_tx with uuid \"{}\"", batch.uuid));
+                }
+                ToolResult::success(content).with_metadata(metadata)
+            }
+            Some(reason) => {
+                content.push_str(&format!(
+                    "\nSEQUENTIAL EXECUTION ({})\nOrder: {}\nCombined gas limit: {}\n",
+                    reason,
+                    txs.iter().map(|tx| format!("nonce {}", tx.nonce)).collect::<Vec<_>>().join(" → "),
+                    total_gas
+                ));
+                let order: Vec<&str> = txs.iter().map(|tx| tx.uuid.as_str()).collect();
+                context.set_register("queued_tx_uuid", json!(order[0]), "batch_tx");
+                let mut metadata = json!({
+                    "mode": "sequential",
+                    "reason": reason,
+                    "network": network,
+                    "order": order,
+                    "items": items,
+                    "gas_limit": total_gas.to_string(),
+                });
+                if !params.execute {
+                    content.push_str("\nNext: broadcast_web3_tx for each uuid in this order, or batch_tx again with execute=true.");
+                    return ToolResult::success(content).with_metadata(metadata);
+                }
+                let mut sent = Vec::new();
+                for tx in &txs {
+                    let result = broadcast.execute(json!({ "uuid": tx.uuid }), context).await;
+                    content.push_str(&format!("\n--- nonce {} ({}) ---\n{}\n", tx.nonce, tx.uuid, result.content));
+                    if !result.success {
+                        content.push_str("\nStopped at the first failure; the remaining transactions are still queued.");
+                        metadata["broadcast"] = json!(sent);
+                        return ToolResult::error(content).with_metadata(metadata);
+                    }
+                    sent.push(tx.uuid.clone());
+                }
+                metadata["broadcast"] = json!(sent);
+                ToolResult::success(content).with_metadata(metadata)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(uuid: &str, to: &str, value: &str, data: &str) -> QueuedTransaction {
+        QueuedTransaction::new(
+            uuid.to_string(),
+            "base".to_string(),
+            "0x1111111111111111111111111111111111111111".to_string(),
+            to.to_string(),
+            value.to_string(),
+            data.to_string(),
+            "21000".to_string(),
+            "1".to_string(),
+            "1".to_string(),
+            0,
+            "0x".to_string(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_only_plain_transfers_batch_and_values_add_up() {
+        let a = tx("a", "0x2222222222222222222222222222222222222222", "1000", "0x");
+        let b = tx("b", "0x3333333333333333333333333333333333333333", "2500", "");
+        let approve = tx("c", "0x4444444444444444444444444444444444444444", "0", "0x095ea7b3");
+
+        assert!(unbatchable_reason(&a).is_none());
+        assert!(unbatchable_reason(&b).is_none());
+        assert!(unbatchable_reason(&approve).is_some());
+
+        let (calldata, total) = multicall_calldata(&[a, b]).unwrap();
+        assert_eq!(hex::encode(&calldata[..4]), AGGREGATE3_VALUE_SELECTOR);
+        assert_eq!(total, U256::from(3500));
+
+        let dotted = tx("d", "0x2222222222222222222222222222222222222222", "0.1", "0x");
+        assert!(multicall_calldata(&[dotted]).unwrap_err().contains("to_raw_amount"));
+    }
+
+    #[test]
+    fn test_repeated_uuids_are_refused() {
+        let ids = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(unique_uuids(&ids(&["a", "b"])).unwrap(), ids(&["a", "b"]));
+        assert!(unique_uuids(&ids(&["a", "a"])).is_err());
+        // Not next to each other: still caught
+        assert!(unique_uuids(&ids(&["a", "b", "a"])).unwrap_err().contains("Transaction a"));
+
+        let queue = std::sync::Arc::new(crate::tx_queue::TxQueueManager::new());
+        queue.queue(tx("a", "0x2222222222222222222222222222222222222222", "1000", "0x"));
+        queue.queue(tx("b", "0x3333333333333333333333333333333333333333", "2500", "0x"));
+        let context = ToolContext::new().with_tx_queue(queue);
+        let params: BatchTxParams = serde_json::from_value(json!({ "uuids": ["a", "b", "a"] })).unwrap();
+        assert!(BatchTxTool::load(&params, &context).unwrap_err().contains("more than once"));
+    }
+}
@@ -0,0 +1,129 @@
+//! Bridge status tool - arrival tracking for bridges queued with bridge_usdc
+//!
+//! With a tracking id (the UUID of the queued bridge transaction), re-checks
+//! that transfer against the destination chain. Without one, lists recent
+//! transfers and where each one is.
+
+use crate::bridge;
+use crate::db::tables::bridge_transfers::{BridgeTransfer, BRIDGE_CANCELLED, BRIDGE_COMPLETED};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tools::ToolSafetyLevel;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Bridge status tool
+pub struct BridgeStatusTool {
+    definition: ToolDefinition,
+}
+
+impl BridgeStatusTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "id".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Tracking id returned by bridge_usdc (the queued bridge tx UUID). Omit to list recent bridges.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        BridgeStatusTool {
+            definition: ToolDefinition {
+                name: "bridge_status".to_string(),
+                description: "Check whether bridged USDC has arrived on the destination chain. Shows queued, in-flight, completed and timed-out bridges made with bridge_usdc, with source and destination tx hashes.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for BridgeStatusTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeStatusParams {
+    id: Option<String>,
+}
+
+fn describe(t: &BridgeTransfer) -> String {
+    let mut line = format!(
+        "- {} — {} USDC {} → {} to {}: {}",
+        t.bridge_tx_uuid, t.amount, t.from_network, t.to_network, t.recipient, t.status
+    );
+    if let Some(ref hash) = t.source_tx_hash {
+        line.push_str(&format!("\n  Source tx: {}", hash));
+    }
+    if let Some(ref hash) = t.dest_tx_hash {
+        line.push_str(&format!("\n  Destination tx: {}", hash));
+    }
+    line
+}
+
+#[async_trait]
+impl Tool for BridgeStatusTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: BridgeStatusParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available."),
+        };
+
+        let Some(id) = params.id else {
+            return match db.list_bridge_transfers(None, 20) {
+                Ok(transfers) if transfers.is_empty() => ToolResult::success("No bridges have been tracked yet."),
+                Ok(transfers) => {
+                    let lines: Vec<String> = transfers.iter().map(describe).collect();
+                    ToolResult::success(format!("{} recent bridge(s):\n{}", transfers.len(), lines.join("\n")))
+                        .with_metadata(json!({ "transfers": transfers }))
+                }
+                Err(e) => ToolResult::error(format!("Database error: {}", e)),
+            };
+        };
+
+        let transfer = match db.get_bridge_transfer(id.trim()) {
+            Ok(Some(t)) => t,
+            Ok(None) => return ToolResult::error(format!(
+                "No tracked bridge with id {}. Only bridges queued with bridge_usdc are tracked.",
+                id
+            )),
+            Err(e) => return ToolResult::error(format!("Database error: {}", e)),
+        };
+        if transfer.status == BRIDGE_COMPLETED || transfer.status == BRIDGE_CANCELLED {
+            return ToolResult::success(describe(&transfer)).with_metadata(json!(transfer));
+        }
+
+        match bridge::refresh_transfer(db, context.broadcaster.as_deref(), context.tx_queue.as_deref(), &transfer).await {
+            Ok(updated) => ToolResult::success(describe(&updated)).with_metadata(json!(updated)),
+            Err(e) => ToolResult::error(format!("Failed to check bridge: {}", e)),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::ReadOnly
+    }
+}
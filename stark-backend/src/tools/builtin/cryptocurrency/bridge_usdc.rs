@@ -19,13 +19,15 @@
 //! ```
 
 use super::verify_intent::{self, TransactionIntent};
+use crate::db::tables::paper_trades::{RecordPaperTradeRequest, PAPER_KIND_BRIDGE};
 use crate::tools::registry::Tool;
 use crate::tools::rpc_config::{resolve_rpc_from_context, ResolvedRpcConfig};
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
 };
-use crate::tx_queue::QueuedTransaction;
+use crate::tx_queue::{funds, nonce, QueuedTransaction};
 use crate::wallet::WalletProvider;
+use crate::web3::amounts::{self, TokenSpend};
 use crate::x402::X402EvmRpc;
 use async_trait::async_trait;
 use ethers::prelude::*;
@@ -40,6 +42,8 @@ use uuid::Uuid;
 /// Across Protocol API base URL
 const ACROSS_API_URL: &str = "https://app.across.to/api";
 
+const USDC_DECIMALS: u8 = 6;
+
 /// Supported chains with their chain IDs and USDC addresses
 const CHAIN_CONFIG: &[(&str, u64, &str)] = &[
     (
@@ -197,17 +201,14 @@ impl BridgeUsdcTool {
 
     /// Convert human-readable USDC amount to raw (6 decimals)
     fn parse_usdc_amount(amount: &str) -> Result<u64, String> {
-        let parsed: f64 = amount
-            .parse()
-            .map_err(|_| format!("Invalid amount: {}", amount))?;
-
-        if parsed <= 0.0 {
+        let raw = amounts::parse_units(amount, USDC_DECIMALS).map_err(|e| format!("Invalid amount: {}", e))?;
+        if raw.is_zero() {
             return Err("Amount must be positive".to_string());
         }
-
-        // USDC has 6 decimals
-        let raw = (parsed * 1_000_000.0).round() as u64;
-        Ok(raw)
+        if raw > U256::from(u64::MAX) {
+            return Err(format!("Amount {} USDC is too large", amount.trim()));
+        }
+        Ok(raw.as_u64())
     }
 
     /// Map chain name to network name for RPC config
@@ -244,8 +245,8 @@ impl BridgeUsdcTool {
         let from_address: Address = from_str.parse()
             .map_err(|_| format!("Invalid wallet address: {}", from_str))?;
 
-        // Get nonce
-        let nonce = rpc.get_transaction_count(from_address).await?;
+        // Reserve nonce
+        let nonce = nonce::reserve(&rpc, network, from_address).await?;
 
         // Estimate gas
         let gas: U256 = rpc
@@ -500,6 +501,47 @@ impl Tool for BridgeUsdcTool {
             }
         };
 
+        // Paper mode: record the quoted bridge instead of signing it
+        if crate::paper::is_enabled(context) {
+            return match crate::paper::record_fill(context, RecordPaperTradeRequest {
+                kind: PAPER_KIND_BRIDGE,
+                network: Self::chain_to_network(&params.from_chain).to_string(),
+                sell_token: usdc_from.to_string(),
+                sell_symbol: "USDC".to_string(),
+                sell_amount_raw: amount_raw.to_string(),
+                sell_decimals: USDC_DECIMALS,
+                buy_token: None,
+                buy_symbol: None,
+                buy_amount_raw: None,
+                buy_decimals: None,
+                counterparty: Some(format!("{} ({})", params.to_chain, recipient)),
+                sell_price_usd: None,
+                buy_price_usd: None,
+                details: Some(json!({
+                    "source": "Across quote",
+                    "to_chain": params.to_chain,
+                    "recipient": recipient,
+                    "expected_output_raw": across_response.expected_output_amount,
+                    "expected_fill_time": across_response.expected_fill_time,
+                    "fees": across_response.fees,
+                    "bridge_contract": swap_tx.to,
+                })),
+                channel_id: context.channel_id,
+            })
+            .await
+            {
+                Ok(fill) => crate::paper::fill_result(&fill),
+                Err(e) => ToolResult::error(e),
+            };
+        }
+
+        let token_spend = TokenSpend {
+            token: usdc_from.to_string(),
+            symbol: "USDC".to_string(),
+            decimals: USDC_DECIMALS,
+            amount: U256::from(amount_raw),
+        };
+
         // Verify intent before any signing/queueing
         let intent = TransactionIntent {
             tx_type: "bridge".to_string(),
@@ -516,6 +558,7 @@ impl Tool for BridgeUsdcTool {
                 "Bridge {} USDC from {} to {} via Across Protocol, recipient {}",
                 params.amount, params.from_chain, params.to_chain, recipient,
             ),
+            token_spend: Some(token_spend.clone()),
         };
         if let Err(reason) = verify_intent::verify_intent(&intent, context, None).await {
             return ToolResult::error(reason);
@@ -608,6 +651,11 @@ impl Tool for BridgeUsdcTool {
                 context.channel_id,
             );
 
+            if let Err(reason) = funds::ensure_affordable(tx_queue, &queued_approval, Some(&token_spend), &[]).await {
+                nonce::release(&queued_approval.network, &queued_approval.from, queued_approval.nonce);
+                return ToolResult::error(reason);
+            }
+
             tx_queue.queue(queued_approval);
             queued_uuids.push(("approval".to_string(), approval_uuid));
             current_nonce_offset = 1;
@@ -655,11 +703,11 @@ impl Tool for BridgeUsdcTool {
                 Err(_) => return ToolResult::error(format!("Invalid wallet address: {}", from_str)),
             };
 
-            let base_nonce = match rpc.get_transaction_count(from_address).await {
+            // The approval's nonce is reserved, so this lands right after it
+            let nonce = match nonce::reserve(&rpc, network, from_address).await {
                 Ok(n) => n,
                 Err(e) => return ToolResult::error(format!("Failed to get nonce: {}", e)),
             };
-            let nonce = base_nonce + U256::from(current_nonce_offset);
 
             let gas: U256 = match rpc
                 .estimate_gas(from_address, bridge_to, &bridge_data, bridge_value)
@@ -744,6 +792,15 @@ impl Tool for BridgeUsdcTool {
             context.channel_id,
         );
 
+        // The approval is already queued, so its gas counts here too; drop it if the bridge can't go
+        if let Err(reason) = funds::ensure_affordable(tx_queue, &queued_bridge, Some(&token_spend), &[]).await {
+            nonce::release(&queued_bridge.network, &queued_bridge.from, queued_bridge.nonce);
+            for (_, uuid) in &queued_uuids {
+                tx_queue.mark_failed(uuid, &reason);
+            }
+            return ToolResult::error(reason);
+        }
+
         tx_queue.queue(queued_bridge);
         queued_uuids.push(("bridge".to_string(), bridge_uuid.clone()));
 
@@ -752,13 +809,56 @@ impl Tool for BridgeUsdcTool {
             signed_bridge.nonce
         );
 
+        // Track arrival on the destination chain (see crate::bridge)
+        let to_network = Self::chain_to_network(&params.to_chain);
+        let tracked = if let Some(db) = &context.database {
+            let expected_raw = across_response
+                .expected_output_amount
+                .as_ref()
+                .and_then(|o| o.parse::<u64>().ok())
+                .unwrap_or(amount_raw);
+            let min_output_raw = (expected_raw as f64 * (1.0 - params.slippage)).floor() as u64;
+            let dest_start_block = crate::bridge::block_number(to_network).await.ok().map(|b| b as i64);
+            // Arrival notices go back to this chat when it can receive them
+            let notify_chat = context
+                .channel_type
+                .as_deref()
+                .is_some_and(|ct| ct.eq_ignore_ascii_case("telegram") || ct.eq_ignore_ascii_case("discord"));
+            let (channel_id, chat_id) = if notify_chat {
+                (context.channel_id, context.platform_chat_id.as_deref())
+            } else {
+                (None, None)
+            };
+            match db.create_bridge_transfer(
+                &bridge_uuid,
+                network,
+                to_network,
+                &params.amount,
+                &amount_raw.to_string(),
+                &min_output_raw.to_string(),
+                &recipient,
+                usdc_to,
+                dest_start_block,
+                channel_id.filter(|_| chat_id.is_some()),
+                chat_id,
+            ) {
+                Ok(_) => true,
+                Err(e) => {
+                    log::error!("[bridge_usdc] Bridge {} queued but not tracked: {}", bridge_uuid, e);
+                    false
+                }
+            }
+        } else {
+            false
+        };
+
         // Format expected output
         let expected_output_usdc = across_response
             .expected_output_amount
             .as_ref()
             .map(|o| {
-                let raw: u64 = o.parse().unwrap_or(0);
-                format!("{:.2}", raw as f64 / 1_000_000.0)
+                let raw = amounts::parse_raw(o).unwrap_or_default();
+                amounts::format_units(raw, USDC_DECIMALS)
             })
             .unwrap_or_else(|| "~".to_string() + &params.amount);
 
@@ -784,14 +884,19 @@ impl Tool for BridgeUsdcTool {
             --- Next Steps ---\n\
             To view queued: use `list_queued_web3_tx`\n\
             To broadcast: use `broadcast_web3_tx` (broadcasts in order)\n\n\
-            Note: Broadcast approval first, wait for confirmation, then broadcast bridge.",
+            Note: Broadcast approval first, wait for confirmation, then broadcast bridge.{}",
             params.from_chain,
             params.to_chain,
             params.amount,
             expected_output_usdc,
             fill_time,
             recipient,
-            uuids_display.join("\n")
+            uuids_display.join("\n"),
+            if tracked {
+                format!("\nArrival on {} is tracked automatically; check with `bridge_status` (id: {}).", params.to_chain, bridge_uuid)
+            } else {
+                String::new()
+            }
         );
 
         ToolResult::success(result).with_metadata(json!({
@@ -804,6 +909,7 @@ impl Tool for BridgeUsdcTool {
             "estimated_fill_time": across_response.expected_fill_time,
             "recipient": recipient,
             "queued_transactions": queued_uuids,
+            "tracking_id": if tracked { Some(bridge_uuid) } else { None },
             "fees": across_response.fees,
         }))
     }
@@ -1,6 +1,8 @@
 //! Broadcast a queued Web3 transaction
 //!
 //! Takes a UUID from web3_tx and broadcasts the signed transaction to the network.
+//! Transactions above the owner's approval threshold are held for chat approval
+//! in every mode (see `tx_queue::approval`).
 
 use super::web3_tx::SendEthTool;
 use crate::gateway::protocol::GatewayEvent;
@@ -9,6 +11,7 @@ use crate::tools::rpc_config::resolve_rpc_from_context;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
 };
+use crate::tx_queue::approval::{self, ApprovalPolicy};
 use crate::tx_queue::QueuedTxStatus;
 use crate::x402::{TxLog, X402EvmRpc};
 use ethers::types::{H256, U256};
@@ -130,6 +133,11 @@ impl Tool for BroadcastWeb3TxTool {
             if uuid_from_param { "param" } else { &params.uuid_register }
         );
 
+        // Above the approval threshold: hold for the owner's decision in chat
+        if let Some(result) = Self::require_owner_approval(&uuid, context).await {
+            return result;
+        }
+
         // Check rogue mode from bot settings in ToolContext
         let is_rogue_mode = context.extra
             .get("rogue_mode_enabled")
@@ -420,6 +428,56 @@ impl Tool for BroadcastWeb3TxTool {
     }
 }
 
+impl BroadcastWeb3TxTool {
+    /// Request chat approval if the policy requires it. None = proceed as usual.
+    /// When the policy can't be loaded nothing is broadcast.
+    async fn require_owner_approval(uuid: &str, context: &ToolContext) -> Option<ToolResult> {
+        let db = context.database.as_ref()?;
+        let settings = match db.get_bot_settings() {
+            Ok(settings) => settings,
+            Err(e) => return Some(ToolResult::error(format!(
+                "Could not load the transaction approval policy, so {} was not broadcast: {}",
+                uuid, e
+            ))),
+        };
+        let policy = ApprovalPolicy::from_settings(&settings)?;
+        let queued_tx = context.tx_queue.as_ref()?.get(uuid)?;
+        if queued_tx.status != QueuedTxStatus::Pending || !policy.requires_approval(&queued_tx).await {
+            return None;
+        }
+
+        let approval = match approval::request_approval(db, &queued_tx, &policy).await {
+            Ok(approval) => approval,
+            Err(e) => return Some(ToolResult::error(format!(
+                "Transaction {} requires owner approval, but the request failed: {}",
+                uuid, e
+            ))),
+        };
+
+        Some(ToolResult::success(format!(
+            "APPROVAL REQUIRED - Transaction exceeds the owner's approval threshold.\n\n\
+            UUID: {}\n\
+            Network: {}\n\
+            To: {}\n\
+            Value: {}\n\
+            Approval status: {}\n\n\
+            An approval card was sent to the owner. The transaction is broadcast only if they approve it before {}. \
+            Do not call broadcast_web3_tx again for this UUID.",
+            queued_tx.uuid, queued_tx.network, queued_tx.to, queued_tx.format_value_eth(),
+            approval.status, approval.expires_at
+        )).with_metadata(json!({
+            "uuid": queued_tx.uuid,
+            "status": "awaiting_approval",
+            "approval_status": approval.status,
+            "expires_at": approval.expires_at,
+            "network": queued_tx.network,
+            "to": queued_tx.to,
+            "value": queued_tx.value,
+            "value_formatted": queued_tx.format_value_eth()
+        })))
+    }
+}
+
 // ─── Identity registration post-processing ────────────────────────────────────
 
 /// Registered(uint256 indexed agentId, string agentURI, address indexed owner)
@@ -0,0 +1,210 @@
+//! Decode transaction tool - explain what a transaction does before it is sent
+//!
+//! Matches calldata against every known ABI and returns a human-readable
+//! preview: function, parameters, token amounts with decimals, and warnings
+//! for spender approvals. Works on a queued transaction (by UUID), a register
+//! holding a transaction object, or raw calldata.
+
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tools::ToolSafetyLevel;
+use crate::web3::decode;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Decode transaction tool
+pub struct DecodeTxTool {
+    definition: ToolDefinition,
+}
+
+impl DecodeTxTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "uuid".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "UUID of a queued transaction (from web3_tx / list_queued_web3_tx) to explain.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "calldata".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Raw hex calldata to explain (with or without 0x prefix).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "calldata_register".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Register holding calldata, or a transaction object with 'to', 'data' and 'value' fields (e.g. swap_quote).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "to".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Target contract address (improves token amount formatting). Ignored when 'uuid' is given.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "value".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Native value in wei (default 0). Ignored when 'uuid' is given.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "network".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Network of the transaction (default: the selected network, else base).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        DecodeTxTool {
+            definition: ToolDefinition {
+                name: "decode_tx".to_string(),
+                description: "Explain what a transaction will do in plain language: decoded function and parameters, token amounts with decimals, and warnings for spender approvals. Use it to check a queued transaction or swap quote before broadcasting.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for DecodeTxTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DecodeTxParams {
+    uuid: Option<String>,
+    calldata: Option<String>,
+    calldata_register: Option<String>,
+    to: Option<String>,
+    value: Option<String>,
+    network: Option<String>,
+}
+
+/// String form of a JSON field that may be a string or a number
+fn field_string(v: &Value, key: &str) -> Option<String> {
+    match v.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl Tool for DecodeTxTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: DecodeTxParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let default_network = params
+            .network
+            .clone()
+            .or_else(|| context.selected_network.clone())
+            .unwrap_or_else(|| "base".to_string());
+
+        // (network, to, value, calldata)
+        let (network, to, value, calldata) = if let Some(ref uuid) = params.uuid {
+            let tx_queue = match &context.tx_queue {
+                Some(q) => q,
+                None => return ToolResult::error("Transaction queue not available."),
+            };
+            match tx_queue.get(uuid) {
+                Some(tx) => (tx.network, tx.to, tx.value, tx.data),
+                None => return ToolResult::error(format!(
+                    "Transaction with UUID '{}' not found. Use list_queued_web3_tx to see available transactions.",
+                    uuid
+                )),
+            }
+        } else if let Some(ref reg_name) = params.calldata_register {
+            let v = match context.registers.get(reg_name) {
+                Some(v) => v,
+                None => return ToolResult::error(format!(
+                    "Register '{}' not found. Available: {:?}",
+                    reg_name, context.registers.keys()
+                )),
+            };
+            if let Some(s) = v.as_str() {
+                (default_network, params.to.unwrap_or_default(), params.value.unwrap_or_else(|| "0".to_string()), s.to_string())
+            } else if let Some(data) = field_string(&v, "data") {
+                (
+                    default_network,
+                    field_string(&v, "to").or(params.to).unwrap_or_default(),
+                    field_string(&v, "value").or(params.value).unwrap_or_else(|| "0".to_string()),
+                    data,
+                )
+            } else {
+                return ToolResult::error(format!(
+                    "Register '{}' does not contain calldata. Expected a string or an object with a 'data' field.",
+                    reg_name
+                ));
+            }
+        } else if let Some(calldata) = params.calldata {
+            (default_network, params.to.unwrap_or_default(), params.value.unwrap_or_else(|| "0".to_string()), calldata)
+        } else {
+            return ToolResult::error("Provide one of 'uuid', 'calldata_register' or 'calldata'.");
+        };
+
+        let preview = decode::preview(&network, &to, &value, &calldata);
+        log::info!(
+            "[decode_tx] {} on {} -> {} ({} flags)",
+            preview.selector.as_deref().unwrap_or("native transfer"),
+            network,
+            preview.call.as_ref().map(|c| c.signature.as_str()).unwrap_or("undecoded"),
+            preview.flags.len()
+        );
+
+        ToolResult::success(preview.explain()).with_metadata(json!(preview))
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::ReadOnly
+    }
+}
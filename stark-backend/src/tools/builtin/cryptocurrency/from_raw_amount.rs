@@ -7,6 +7,7 @@ use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
 };
+use crate::web3::amounts;
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::{json, Value};
@@ -83,47 +84,7 @@ impl FromRawAmountTool {
     /// Convert raw blockchain units to human-readable amount
     /// Handles large integers properly by inserting decimal point at the right position
     pub fn convert_from_raw(raw: &str, decimals: u8) -> Result<String, String> {
-        let raw = raw.trim().trim_matches('"');
-
-        // Validate input is numeric
-        if raw.is_empty() || !raw.chars().all(|c| c.is_ascii_digit()) {
-            return Err(format!("Invalid raw amount: '{}'. Must be a non-negative integer.", raw));
-        }
-
-        // Remove leading zeros but keep at least one digit
-        let raw = raw.trim_start_matches('0');
-        let raw = if raw.is_empty() { "0" } else { raw };
-
-        let decimals = decimals as usize;
-
-        if decimals == 0 {
-            return Ok(raw.to_string());
-        }
-
-        let raw_len = raw.len();
-
-        if raw_len <= decimals {
-            // Raw value is smaller than 1.0 — need leading zeros after decimal
-            let leading_zeros = decimals - raw_len;
-            let decimal_part = format!("{}{}", "0".repeat(leading_zeros), raw);
-            // Trim trailing zeros
-            let trimmed = decimal_part.trim_end_matches('0');
-            if trimmed.is_empty() {
-                Ok("0".to_string())
-            } else {
-                Ok(format!("0.{}", trimmed))
-            }
-        } else {
-            // Split into integer and decimal parts
-            let split_pos = raw_len - decimals;
-            let integer_part = &raw[..split_pos];
-            let decimal_part = raw[split_pos..].trim_end_matches('0');
-            if decimal_part.is_empty() {
-                Ok(integer_part.to_string())
-            } else {
-                Ok(format!("{}.{}", integer_part, decimal_part))
-            }
-        }
+        amounts::parse_raw(raw).map(|raw| amounts::format_units(raw, decimals))
     }
 }
 
@@ -0,0 +1,463 @@
+//! manage_tx tool - inspect in-flight transactions and replace stuck ones
+//!
+//! `status` lists the wallet's broadcast-but-unconfirmed transactions next to
+//! the chain's mined and pending nonces, flags the ones that have waited too
+//! long, and shows queued transactions and nonce reservations that later
+//! transactions are waiting behind. `speed_up` re-signs a stuck transaction
+//! with the same nonce and a higher fee; `cancel` replaces it with a 0-value
+//! transfer to the wallet itself. Replacements are queued like any other
+//! transaction (approval policy included) and go out via broadcast_web3_tx;
+//! once one confirms, the transaction it replaced is marked failed.
+
+use crate::tools::registry::Tool;
+use crate::tools::rpc_config::resolve_rpc_from_context;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tx_queue::{funds, nonce, QueuedTransaction, QueuedTxStatus};
+use crate::web3::{get_chain_id, resolve_network};
+use crate::x402::X402EvmRpc;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Eip1559TransactionRequest, U256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A broadcast transaction still unmined after this long is reported as stuck
+const STUCK_AFTER_MINS: i64 = 5;
+/// Nodes reject replacements that don't raise both fees by at least 10%
+const MIN_FEE_BUMP_PERCENT: u64 = 10;
+const DEFAULT_FEE_BUMP_PERCENT: u64 = 20;
+
+/// Manage in-flight transactions tool
+pub struct ManageTxTool {
+    definition: ToolDefinition,
+}
+
+impl ManageTxTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'status' lists in-flight transactions and nonces; 'speed_up' re-sends a stuck transaction with a higher fee; 'cancel' replaces it with an empty self-transfer.".to_string(),
+                default: Some(json!("status")),
+                items: None,
+                enum_values: Some(vec!["status".to_string(), "speed_up".to_string(), "cancel".to_string()]),
+            },
+        );
+
+        properties.insert(
+            "uuid".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "UUID of the broadcast transaction to speed up or cancel.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "fee_bump_percent".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: format!(
+                    "How much to raise the fees over the original (default {}, minimum {}). The current network fee is used if higher.",
+                    DEFAULT_FEE_BUMP_PERCENT, MIN_FEE_BUMP_PERCENT
+                ),
+                default: Some(json!(DEFAULT_FEE_BUMP_PERCENT)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "network".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Network for 'status' (default: the selected network, else base).".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec!["base".to_string(), "mainnet".to_string(), "polygon".to_string()]),
+            },
+        );
+
+        ManageTxTool {
+            definition: ToolDefinition {
+                name: "manage_tx".to_string(),
+                description: "Manage in-flight transactions: list broadcast transactions that haven't confirmed (stuck ones flagged) with the wallet's nonces, or queue a speed-up or cancellation of a stuck one. Replacements must be broadcast with broadcast_web3_tx.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for ManageTxTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ManageTxParams {
+    #[serde(default = "default_action")]
+    action: String,
+    uuid: Option<String>,
+    #[serde(default = "default_fee_bump")]
+    fee_bump_percent: u64,
+    network: Option<String>,
+}
+
+fn default_action() -> String {
+    "status".to_string()
+}
+
+fn default_fee_bump() -> u64 {
+    DEFAULT_FEE_BUMP_PERCENT
+}
+
+/// Fees for a replacement: the original's raised by `bump_percent`, or the
+/// current network fees if those are higher. Returns (max_fee, priority_fee).
+fn bumped_fees(
+    old_max_fee: U256,
+    old_priority_fee: U256,
+    current_max_fee: U256,
+    current_priority_fee: U256,
+    bump_percent: u64,
+) -> (U256, U256) {
+    let bump = |fee: U256| fee * U256::from(100 + bump_percent) / U256::from(100) + U256::one();
+    let priority_fee = bump(old_priority_fee).max(current_priority_fee);
+    let max_fee = bump(old_max_fee).max(current_max_fee).max(priority_fee);
+    (max_fee, priority_fee)
+}
+
+fn parse_wei(value: &str, field: &str) -> Result<U256, String> {
+    U256::from_dec_str(value).map_err(|e| format!("Invalid {} '{}': {}", field, value, e))
+}
+
+fn format_gwei(wei: &str) -> String {
+    match wei.parse::<u128>() {
+        Ok(w) => format!("{:.3} gwei", w as f64 / 1e9),
+        Err(_) => format!("{} wei", wei),
+    }
+}
+
+impl ManageTxTool {
+    fn rpc(network: &str, context: &ToolContext) -> Result<X402EvmRpc, String> {
+        let wallet_provider = context
+            .wallet_provider
+            .as_ref()
+            .ok_or_else(|| "Wallet not configured.".to_string())?;
+        let rpc_config = resolve_rpc_from_context(&context.extra, network);
+        X402EvmRpc::new_with_wallet_provider(
+            wallet_provider.clone(),
+            network,
+            Some(rpc_config.url.clone()),
+            rpc_config.use_x402,
+        )
+    }
+
+    async fn status(&self, network: &str, context: &ToolContext) -> ToolResult {
+        let (Some(wallet_provider), Some(tx_queue)) = (&context.wallet_provider, &context.tx_queue) else {
+            return ToolResult::error("Wallet or transaction queue not available.");
+        };
+        let from = wallet_provider.get_address();
+        let from_address: Address = match from.parse() {
+            Ok(a) => a,
+            Err(_) => return ToolResult::error(format!("Invalid wallet address: {}", from)),
+        };
+        let rpc = match Self::rpc(network, context) {
+            Ok(r) => r,
+            Err(e) => return ToolResult::error(format!("Failed to initialize RPC: {}", e)),
+        };
+        let (mined, pending) = match tokio::try_join!(
+            rpc.get_confirmed_transaction_count(from_address),
+            rpc.get_transaction_count(from_address)
+        ) {
+            Ok((m, p)) => (m.as_u64(), p.as_u64()),
+            Err(e) => return ToolResult::error(format!("Failed to fetch nonces: {}", e)),
+        };
+
+        let stuck_cutoff = Utc::now() - Duration::minutes(STUCK_AFTER_MINS);
+        let in_flight: Vec<Value> = tx_queue
+            .list_in_flight(network, &from)
+            .into_iter()
+            .map(|tx| {
+                let state = if tx.nonce < mined {
+                    "mined"
+                } else if tx.broadcast_at.is_some_and(|at| at < stuck_cutoff) {
+                    "stuck"
+                } else {
+                    "waiting"
+                };
+                json!({
+                    "uuid": tx.uuid,
+                    "nonce": tx.nonce,
+                    "to": tx.to,
+                    "value": tx.format_value_eth(),
+                    "tx_hash": tx.tx_hash,
+                    "max_fee_per_gas": format_gwei(&tx.max_fee_per_gas),
+                    "max_priority_fee_per_gas": format_gwei(&tx.max_priority_fee_per_gas),
+                    "broadcast_at": tx.broadcast_at,
+                    "replaces": tx.replaces,
+                    "state": state,
+                })
+            })
+            .collect();
+        let queued: Vec<Value> = tx_queue
+            .list_pending()
+            .into_iter()
+            .filter_map(|s| tx_queue.get(&s.uuid))
+            .filter(|tx| tx.network == network && tx.from.eq_ignore_ascii_case(&from))
+            .map(|tx| json!({ "uuid": tx.uuid, "nonce": tx.nonce, "to": tx.to, "replaces": tx.replaces }))
+            .collect();
+        let reserved = nonce::reserved(network, &from);
+
+        let mut content = format!(
+            "Wallet {} on {}\nMined nonce: {} · next pending nonce: {}\n",
+            from, network, mined, pending
+        );
+        if in_flight.is_empty() {
+            content.push_str("\nNo broadcast transactions awaiting confirmation.\n");
+        } else {
+            content.push_str("\nIn flight:\n");
+            for tx in &in_flight {
+                content.push_str(&format!(
+                    "- nonce {} [{}] {} → {} ({}), max fee {}, uuid {}\n",
+                    tx["nonce"], tx["state"].as_str().unwrap_or(""), tx["value"].as_str().unwrap_or(""),
+                    tx["to"].as_str().unwrap_or(""), tx["tx_hash"].as_str().unwrap_or("no hash"),
+                    tx["max_fee_per_gas"].as_str().unwrap_or(""), tx["uuid"].as_str().unwrap_or("")
+                ));
+            }
+        }
+        if !queued.is_empty() {
+            content.push_str(&format!(
+                "\n{} signed transaction(s) waiting in the queue — later nonces can't mine until they are broadcast or expire.\n",
+                queued.len()
+            ));
+        }
+        if in_flight.iter().any(|tx| tx["state"] == "stuck") {
+            content.push_str("\nStuck transactions can be replaced: manage_tx with action 'speed_up' or 'cancel' and the uuid.");
+        }
+
+        ToolResult::success(content).with_metadata(json!({
+            "network": network,
+            "wallet": from,
+            "mined_nonce": mined,
+            "pending_nonce": pending,
+            "in_flight": in_flight,
+            "queued": queued,
+            "reserved_nonces": reserved,
+        }))
+    }
+
+    async fn replace(&self, params: &ManageTxParams, cancel: bool, context: &ToolContext) -> ToolResult {
+        let (Some(wallet_provider), Some(tx_queue)) = (&context.wallet_provider, &context.tx_queue) else {
+            return ToolResult::error("Wallet or transaction queue not available.");
+        };
+        let Some(uuid) = params.uuid.as_deref() else {
+            return ToolResult::error("'uuid' is required (see manage_tx action 'status').");
+        };
+        let original = match tx_queue.get(uuid) {
+            Some(tx) => tx,
+            None => return ToolResult::error(format!("Transaction {} not found in the queue", uuid)),
+        };
+        if original.status != QueuedTxStatus::Broadcast {
+            return ToolResult::error(format!(
+                "Transaction {} is {}; only broadcast, unconfirmed transactions can be replaced",
+                uuid, original.status
+            ));
+        }
+        if params.fee_bump_percent < MIN_FEE_BUMP_PERCENT {
+            return ToolResult::error(format!("fee_bump_percent must be at least {}", MIN_FEE_BUMP_PERCENT));
+        }
+
+        let rpc = match Self::rpc(&original.network, context) {
+            Ok(r) => r,
+            Err(e) => return ToolResult::error(format!("Failed to initialize RPC: {}", e)),
+        };
+        let from_address: Address = match original.from.parse() {
+            Ok(a) => a,
+            Err(_) => return ToolResult::error(format!("Invalid sender address: {}", original.from)),
+        };
+        match rpc.get_confirmed_transaction_count(from_address).await {
+            Ok(mined) if mined.as_u64() > original.nonce => {
+                return ToolResult::error(format!(
+                    "Nonce {} is already mined; transaction {} can no longer be replaced",
+                    original.nonce, uuid
+                ))
+            }
+            Ok(_) => {}
+            Err(e) => return ToolResult::error(format!("Failed to fetch nonce: {}", e)),
+        }
+
+        let (current_max, current_priority) = match rpc.estimate_eip1559_fees().await {
+            Ok(fees) => fees,
+            Err(e) => return ToolResult::error(format!("Failed to estimate fees: {}", e)),
+        };
+        let (old_max, old_priority) = match (
+            parse_wei(&original.max_fee_per_gas, "max fee"),
+            parse_wei(&original.max_priority_fee_per_gas, "priority fee"),
+        ) {
+            (Ok(m), Ok(p)) => (m, p),
+            (Err(e), _) | (_, Err(e)) => return ToolResult::error(e),
+        };
+        let (max_fee, priority_fee) =
+            bumped_fees(old_max, old_priority, current_max, current_priority, params.fee_bump_percent);
+
+        let (to, value, data, gas) = if cancel {
+            (original.from.clone(), U256::zero(), Vec::new(), U256::from(21000u64))
+        } else {
+            let value = match parse_wei(&original.value, "value") {
+                Ok(v) => v,
+                Err(e) => return ToolResult::error(e),
+            };
+            let gas = match parse_wei(&original.gas_limit, "gas limit") {
+                Ok(g) => g,
+                Err(e) => return ToolResult::error(e),
+            };
+            let data = match hex::decode(original.data.trim_start_matches("0x")) {
+                Ok(d) => d,
+                Err(e) => return ToolResult::error(format!("Invalid calldata: {}", e)),
+            };
+            (original.to.clone(), value, data, gas)
+        };
+        let to_address: Address = match to.parse() {
+            Ok(a) => a,
+            Err(_) => return ToolResult::error(format!("Invalid recipient address: {}", to)),
+        };
+
+        let tx = Eip1559TransactionRequest::new()
+            .from(from_address)
+            .to(to_address)
+            .value(value)
+            .data(data.clone())
+            .nonce(original.nonce)
+            .gas(gas)
+            .max_fee_per_gas(max_fee)
+            .max_priority_fee_per_gas(priority_fee)
+            .chain_id(get_chain_id(&original.network));
+        let typed_tx: TypedTransaction = tx.into();
+        let signature = match wallet_provider.sign_transaction(&typed_tx).await {
+            Ok(s) => s,
+            Err(e) => return ToolResult::error(format!("Failed to sign replacement: {}", e)),
+        };
+        let signed_tx_hex = format!("0x{}", hex::encode(typed_tx.rlp_signed(&signature)));
+
+        let replacement_uuid = Uuid::new_v4().to_string();
+        let replacement = QueuedTransaction::new(
+            replacement_uuid.clone(),
+            original.network.clone(),
+            original.from.clone(),
+            to.clone(),
+            value.to_string(),
+            format!("0x{}", hex::encode(&data)),
+            gas.to_string(),
+            max_fee.to_string(),
+            priority_fee.to_string(),
+            original.nonce,
+            signed_tx_hex,
+            context.channel_id,
+        )
+        .with_preset(original.preset.as_deref().filter(|_| !cancel))
+        .with_replaces(uuid);
+        // The higher fee has to be affordable too (the original's cost is left out)
+        if let Err(reason) = funds::ensure_affordable(tx_queue, &replacement, None, &[]).await {
+            return ToolResult::error(reason);
+        }
+        tx_queue.queue(replacement);
+        context.set_register("queued_tx_uuid", json!(&replacement_uuid), "manage_tx");
+
+        let verb = if cancel { "CANCELLATION" } else { "SPEED-UP" };
+        log::info!(
+            "[manage_tx] Queued {} {} for {} (nonce {}, max fee {} → {})",
+            verb.to_lowercase(), replacement_uuid, uuid, original.nonce, old_max, max_fee
+        );
+
+        ToolResult::success(format!(
+            "{} QUEUED (not yet broadcast)\n\n\
+            Replaces: {} (nonce {})\n\
+            Max fee: {} → {}\n\
+            Priority fee: {} → {}\n\
+            UUID: {}\n\n\
+            Next: broadcast_web3_tx with uuid \"{}\"",
+            verb,
+            uuid,
+            original.nonce,
+            format_gwei(&original.max_fee_per_gas),
+            format_gwei(&max_fee.to_string()),
+            format_gwei(&original.max_priority_fee_per_gas),
+            format_gwei(&priority_fee.to_string()),
+            replacement_uuid,
+            replacement_uuid
+        ))
+        .with_metadata(json!({
+            "status": if cancel { "cancel_queued" } else { "speed_up_queued" },
+            "uuid": replacement_uuid,
+            "replaces": uuid,
+            "nonce": original.nonce,
+            "network": original.network,
+            "max_fee_per_gas": max_fee.to_string(),
+            "max_priority_fee_per_gas": priority_fee.to_string(),
+        }))
+    }
+}
+
+#[async_trait]
+impl Tool for ManageTxTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: ManageTxParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        match params.action.as_str() {
+            "status" => {
+                let network = match resolve_network(params.network.as_deref(), context.selected_network.as_deref()) {
+                    Ok(n) => n,
+                    Err(e) => return ToolResult::error(e),
+                };
+                self.status(network.as_ref(), context).await
+            }
+            "speed_up" => self.replace(&params, false, context).await,
+            "cancel" => self.replace(&params, true, context).await,
+            other => ToolResult::error(format!("Unknown action '{}'. Use 'status', 'speed_up' or 'cancel'.", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bumped_fees_clear_replacement_minimum() {
+        let gwei = |n: u64| U256::from(n) * U256::from(1_000_000_000u64);
+
+        // Quiet network: the bump decides
+        let (max_fee, priority_fee) = bumped_fees(gwei(10), gwei(1), gwei(5), gwei(1) / 2, 10);
+        assert!(max_fee > gwei(11));
+        assert!(priority_fee > gwei(1) * U256::from(11) / U256::from(10));
+
+        // Fees spiked since the original: the current ones win
+        let (max_fee, priority_fee) = bumped_fees(gwei(10), gwei(1), gwei(40), gwei(3), 10);
+        assert_eq!(max_fee, gwei(40));
+        assert_eq!(priority_fee, gwei(3));
+    }
+}
@@ -3,19 +3,29 @@
 //! Tools for interacting with blockchain networks, EVM transactions,
 //! token operations, x402 payment protocol, and prediction markets.
 
+mod batch_tx;
+mod bridge_status;
 mod bridge_usdc;
 mod broadcast_web3_tx;
 pub mod verify_intent;
 mod verify_tx_broadcast;
 mod decode_calldata;
+mod decode_tx;
 mod list_queued_web3_tx;
+mod manage_tx;
+mod paper_trading;
+mod propose_safe_tx;
+mod safe_tx_status;
+mod sign_typed_data;
 pub mod network_lookup;
 mod select_web3_network;
 mod set_address;
 mod set_nft_token_id;
 mod from_raw_amount;
 mod to_raw_amount;
+mod token_approvals;
 pub mod token_lookup;
+mod trade_journal;
 mod web3_function_call;
 mod web3_preset_function_call;
 pub mod web3_tx;
@@ -31,10 +41,18 @@ mod x402_rpc;
 pub use erc8128_fetch::Erc8128FetchTool;
 pub use sign_raw_tx::SignRawTxTool;
 pub use siwa_auth::SiwaAuthTool;
+pub use batch_tx::BatchTxTool;
+pub use bridge_status::BridgeStatusTool;
 pub use bridge_usdc::BridgeUsdcTool;
 pub use broadcast_web3_tx::BroadcastWeb3TxTool;
 pub use decode_calldata::DecodeCalldataTool;
+pub use decode_tx::DecodeTxTool;
 pub use list_queued_web3_tx::ListQueuedWeb3TxTool;
+pub use manage_tx::ManageTxTool;
+pub use paper_trading::PaperTradingTool;
+pub use propose_safe_tx::ProposeSafeTxTool;
+pub use safe_tx_status::SafeTxStatusTool;
+pub use sign_typed_data::SignTypedDataTool;
 pub use network_lookup::load_networks;
 pub use set_address::SetAddressTool;
 pub use set_nft_token_id::SetNftTokenIdTool;
@@ -42,7 +60,9 @@ pub use swap_token::SwapTokenTool;
 pub use select_web3_network::SelectWeb3NetworkTool;
 pub use from_raw_amount::FromRawAmountTool;
 pub use to_raw_amount::ToRawAmountTool;
+pub use token_approvals::TokenApprovalsTool;
 pub use token_lookup::{load_tokens, TokenLookupTool};
+pub use trade_journal::TradeJournalTool;
 pub use web3_preset_function_call::Web3PresetFunctionCallTool;
 pub use verify_tx_broadcast::VerifyTxBroadcastTool;
 pub use web3_tx::SendEthTool;
@@ -0,0 +1,192 @@
+//! Paper trading tool - review the simulated ledger and its PnL
+//!
+//! In paper mode, finance tools record simulated fills instead of signing.
+//! `status` says whether paper mode is on here, `trades` lists fills, `pnl`
+//! replays them into realized/unrealized PnL, and `reset` wipes the ledger
+//! to start a new test run.
+
+use crate::db::tables::paper_trades::PaperTrade;
+use crate::journal::{self, pnl::TokenPnl};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Paper trading tool
+pub struct PaperTradingTool {
+    definition: ToolDefinition,
+}
+
+impl PaperTradingTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'status' (is paper mode on?), 'trades' (simulated fills), 'pnl' (paper PnL per token), or 'reset' (clear this channel's paper ledger).".to_string(),
+                default: Some(json!("pnl")),
+                items: None,
+                enum_values: Some(vec![
+                    "status".to_string(),
+                    "trades".to_string(),
+                    "pnl".to_string(),
+                    "reset".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "period".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Reporting period for trades/pnl: day, week, month, year or all (default: all).".to_string(),
+                default: Some(json!("all")),
+                items: None,
+                enum_values: Some(vec![
+                    "day".to_string(),
+                    "week".to_string(),
+                    "month".to_string(),
+                    "year".to_string(),
+                    "all".to_string(),
+                ]),
+            },
+        );
+
+        PaperTradingTool {
+            definition: ToolDefinition {
+                name: "paper_trading".to_string(),
+                description: "Paper trading ledger for this channel. When paper mode is on, swap_token, send_eth, bridge_usdc and contract calls are simulated from live quotes instead of signed; use this to review the simulated fills and their PnL, or reset the ledger.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for PaperTradingTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PaperTradingParams {
+    #[serde(default = "default_action")]
+    action: String,
+    #[serde(default = "default_period")]
+    period: String,
+}
+
+fn default_action() -> String {
+    "pnl".to_string()
+}
+
+fn default_period() -> String {
+    "all".to_string()
+}
+
+fn usd(v: f64) -> String {
+    if v < 0.0 {
+        format!("-${:.2}", -v)
+    } else {
+        format!("${:.2}", v)
+    }
+}
+
+fn describe_fill(fill: &PaperTrade) -> String {
+    format!("- #{} {} {}", fill.id, fill.created_at, crate::paper::describe(fill))
+}
+
+fn describe_pnl(t: &TokenPnl) -> String {
+    let mut line = format!("- {} ({}): realized {}", t.symbol, t.network, usd(t.realized_usd));
+    if t.held > 0.0 {
+        line.push_str(&format!(", holding {:.6}", t.held));
+        if let Some(u) = t.unrealized_usd {
+            line.push_str(&format!(", unrealized {}", usd(u)));
+        }
+    }
+    line
+}
+
+#[async_trait]
+impl Tool for PaperTradingTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: PaperTradingParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available."),
+        };
+        let since = match journal::period_start(&params.period) {
+            Ok(s) => s,
+            Err(e) => return ToolResult::error(e.to_string()),
+        };
+        let enabled = crate::paper::is_enabled(context);
+
+        match params.action.as_str() {
+            "status" => ToolResult::success(if enabled {
+                "Paper mode is ON: swaps, transfers, bridges and contract calls are simulated and recorded in the paper ledger. Nothing is signed."
+            } else {
+                "Paper mode is OFF: finance tools sign and queue real transactions."
+            })
+            .with_metadata(json!({ "paper_mode": enabled })),
+            "trades" => {
+                let fills = match db.list_paper_trades(context.channel_id, since.as_deref(), 100) {
+                    Ok(f) => f,
+                    Err(e) => return ToolResult::error(format!("Database error: {}", e)),
+                };
+                if fills.is_empty() {
+                    return ToolResult::success(format!("No paper trades for period '{}'.", params.period));
+                }
+                let lines: Vec<String> = fills.iter().map(describe_fill).collect();
+                ToolResult::success(format!("{} paper trade(s), newest first:\n{}", fills.len(), lines.join("\n")))
+                    .with_metadata(json!({ "paper_mode": enabled, "trades": fills }))
+            }
+            "pnl" => {
+                let report = match crate::paper::pnl_report(db, context.channel_id, since.as_deref()).await {
+                    Ok(r) => r,
+                    Err(e) => return ToolResult::error(e.to_string()),
+                };
+                if report.tokens.is_empty() {
+                    return ToolResult::success(format!("No paper trades to report for period '{}'.", params.period));
+                }
+                let mut text = format!(
+                    "Paper PnL ({}): realized {}, unrealized {}\n{}",
+                    params.period,
+                    usd(report.realized_usd),
+                    usd(report.unrealized_usd),
+                    report.tokens.iter().map(describe_pnl).collect::<Vec<_>>().join("\n")
+                );
+                if report.unpriced_trades > 0 {
+                    text.push_str(&format!(
+                        "\n\n{} swap(s) excluded: no USD price was available when simulated.",
+                        report.unpriced_trades
+                    ));
+                }
+                ToolResult::success(text).with_metadata(json!(report))
+            }
+            "reset" => match db.clear_paper_trades(context.channel_id) {
+                Ok(n) => ToolResult::success(format!("Paper ledger cleared ({} trade(s) removed).", n)),
+                Err(e) => ToolResult::error(format!("Database error: {}", e)),
+            },
+            other => ToolResult::error(format!("Unknown action '{}'. Use status, trades, pnl or reset.", other)),
+        }
+    }
+}
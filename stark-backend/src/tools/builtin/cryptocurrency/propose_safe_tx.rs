@@ -0,0 +1,391 @@
+//! Propose Safe transaction tool - route a transaction through a multisig
+//!
+//! Instead of signing from the bot's own wallet, proposes the call to a Safe
+//! the bot co-owns. The intent is verified like any other transaction, the bot
+//! adds its own signature, and the proposal goes to the Safe Transaction Service
+//! for the other owners to sign. Confirmations are tracked and announced in
+//! this chat by the Safe status worker.
+
+use crate::db::tables::safe_proposals::SAFE_PROPOSAL_PENDING;
+use crate::safe::{self, client::{safe_app_url, SafeTx, SafeTxService}};
+use crate::tools::builtin::cryptocurrency::verify_intent::{self, TransactionIntent};
+use crate::tools::builtin::cryptocurrency::web3_tx::parse_u256;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::web3::{decode, resolve_network};
+use async_trait::async_trait;
+use ethers::types::Address;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Propose Safe transaction tool
+pub struct ProposeSafeTxTool {
+    definition: ToolDefinition,
+}
+
+impl ProposeSafeTxTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "description".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Short note for co-signers explaining what the transaction is for (e.g. 'Pay March hosting invoice').".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "to".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Target address. Ignored when 'calldata_register' holds a transaction object.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "value".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Native value in wei sent from the Safe (default 0).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "data".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Hex calldata (default 0x for a plain transfer).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "calldata_register".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Register holding calldata, or a transaction object with 'to', 'data' and 'value' fields.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "safe_address".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Safe to propose to (default: the treasury Safe from settings).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "network".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Network of the Safe (default: the treasury Safe's network, else the selected network).".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec!["base".to_string(), "mainnet".to_string(), "polygon".to_string()]),
+            },
+        );
+
+        properties.insert(
+            "nonce".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Safe nonce to use. Only set this to replace a pending proposal; by default the next free nonce is used.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        ProposeSafeTxTool {
+            definition: ToolDefinition {
+                name: "propose_safe_tx".to_string(),
+                description: "Propose a transaction to the team's Safe multisig instead of sending it from the bot wallet. The bot signs as one owner; the other owners sign in the Safe app and you are notified as they confirm. Use for treasury payments and anything that needs co-signers.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["description".to_string()],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for ProposeSafeTxTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ProposeSafeTxParams {
+    description: String,
+    to: Option<String>,
+    value: Option<String>,
+    data: Option<String>,
+    calldata_register: Option<String>,
+    safe_address: Option<String>,
+    network: Option<String>,
+    nonce: Option<u64>,
+}
+
+/// String form of a JSON field that may be a string or a number
+fn field_string(v: &Value, key: &str) -> Option<String> {
+    match v.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl Tool for ProposeSafeTxTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: ProposeSafeTxParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        if crate::paper::is_enabled(context) {
+            return crate::paper::unsupported("propose_safe_tx");
+        }
+
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available."),
+        };
+        let wallet_provider = match &context.wallet_provider {
+            Some(wp) => wp,
+            None => return ToolResult::error("Wallet not configured. Cannot sign Safe proposals."),
+        };
+        let settings = db.get_bot_settings().unwrap_or_default();
+
+        let safe_str = match params.safe_address.clone().or(settings.safe_address.clone()) {
+            Some(s) => s,
+            None => return ToolResult::error(
+                "No Safe configured. Pass 'safe_address' or set the treasury Safe in settings."
+            ),
+        };
+        let safe_address: Address = match safe_str.trim().parse() {
+            Ok(a) => a,
+            Err(_) => return ToolResult::error(format!("Invalid Safe address: {}", safe_str)),
+        };
+        let network_param = params.network.clone().or_else(|| {
+            // The configured network only applies to the configured Safe
+            params.safe_address.is_none().then(|| settings.safe_network.clone()).flatten()
+        });
+        let network = match resolve_network(network_param.as_deref(), context.selected_network.as_deref()) {
+            Ok(n) => n,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        // (to, value, data) from a register or explicit params
+        let (to_str, value_str, data_str) = if let Some(ref reg_name) = params.calldata_register {
+            let v = match context.registers.get(reg_name) {
+                Some(v) => v,
+                None => return ToolResult::error(format!(
+                    "Register '{}' not found. Available: {:?}",
+                    reg_name, context.registers.keys()
+                )),
+            };
+            if let Some(s) = v.as_str() {
+                (params.to.clone(), params.value.clone(), s.to_string())
+            } else if let Some(data) = field_string(&v, "data") {
+                (
+                    field_string(&v, "to").or(params.to.clone()),
+                    field_string(&v, "value").or(params.value.clone()),
+                    data,
+                )
+            } else {
+                return ToolResult::error(format!(
+                    "Register '{}' does not contain calldata. Expected a string or an object with a 'data' field.",
+                    reg_name
+                ));
+            }
+        } else {
+            (params.to.clone(), params.value.clone(), params.data.clone().unwrap_or_else(|| "0x".to_string()))
+        };
+
+        let to_str = match to_str {
+            Some(t) => t.trim().to_string(),
+            None => return ToolResult::error("'to' is required."),
+        };
+        let to: Address = match to_str.parse() {
+            Ok(a) => a,
+            Err(_) => return ToolResult::error(format!("Invalid 'to' address: {}", to_str)),
+        };
+        let value_str = value_str.unwrap_or_else(|| "0".to_string());
+        let value = match parse_u256(&value_str) {
+            Ok(v) => v,
+            Err(e) => return ToolResult::error(format!("Invalid value: {} - {}", value_str, e)),
+        };
+        let data = match hex::decode(data_str.trim().trim_start_matches("0x")) {
+            Ok(d) => d,
+            Err(e) => return ToolResult::error(format!("Invalid calldata hex: {}", e)),
+        };
+        let data_hex = format!("0x{}", hex::encode(&data));
+
+        let service = match SafeTxService::new(network.as_ref(), safe::api_key(db)) {
+            Ok(s) => s,
+            Err(e) => return ToolResult::error(e),
+        };
+        let info = match service.get_safe(safe_address).await {
+            Ok(info) => info,
+            Err(e) => return ToolResult::error(format!("Failed to load Safe {}: {}", safe_str, e)),
+        };
+        let proposer = wallet_provider.get_address();
+        if !info.is_owner(&proposer) {
+            return ToolResult::error(format!(
+                "The bot wallet {} is not an owner of Safe {} on {}. Add it as an owner (or delegate) first.",
+                proposer, safe_str, network
+            ));
+        }
+        let nonce = match params.nonce {
+            Some(n) if n < info.nonce => {
+                return ToolResult::error(format!("Nonce {} was already used; the Safe is at nonce {}.", n, info.nonce));
+            }
+            Some(n) => n,
+            None => match service.next_nonce(safe_address, info.nonce).await {
+                Ok(n) => n,
+                Err(e) => return ToolResult::error(format!("Failed to determine Safe nonce: {}", e)),
+            },
+        };
+
+        // Verify intent before adding our signature
+        let intent = TransactionIntent {
+            tx_type: "safe_proposal".to_string(),
+            to: format!("{:?}", to),
+            value: value.to_string(),
+            value_display: decode::format_token_amount(value, 18, network.native_currency()),
+            network: network.to_string(),
+            function_name: None,
+            abi_name: None,
+            preset_name: None,
+            destination_chain: None,
+            calldata: (!data.is_empty()).then(|| data_hex.clone()),
+            description: format!("Propose to Safe {}: {}", safe_str, params.description),
+            token_spend: None,
+        };
+        if let Err(reason) = verify_intent::verify_intent(&intent, context, None).await {
+            return ToolResult::error(reason);
+        }
+
+        let safe_tx = SafeTx { to, value, data, nonce };
+        let chain_id = info.uses_chain_id().then(|| network.chain_id());
+        let safe_tx_hash = safe_tx.hash(chain_id, safe_address);
+        let signature = match safe::sign_safe_tx_hash(wallet_provider, safe_tx_hash).await {
+            Ok(s) => s,
+            Err(e) => return ToolResult::error(format!("Failed to sign Safe transaction: {}", e)),
+        };
+        let proposer_address: Address = match proposer.parse() {
+            Ok(a) => a,
+            Err(_) => return ToolResult::error(format!("Invalid wallet address: {}", proposer)),
+        };
+        if let Err(e) = service.propose(safe_address, &safe_tx, safe_tx_hash, proposer_address, &signature).await {
+            return ToolResult::error(format!("Failed to propose Safe transaction: {}", e));
+        }
+
+        let safe_tx_hash = format!("{:?}", safe_tx_hash);
+        let safe_display = ethers::utils::to_checksum(&safe_address, None);
+        log::info!(
+            "[propose_safe_tx] Proposed {} to Safe {} on {} (nonce {}, {}/{} signatures)",
+            safe_tx_hash, safe_display, network, nonce, 1, info.threshold
+        );
+
+        // Co-signer notifications go back to this chat when it can receive them
+        let notify_chat = context
+            .channel_type
+            .as_deref()
+            .is_some_and(|ct| ct.eq_ignore_ascii_case("telegram") || ct.eq_ignore_ascii_case("discord"));
+        let (channel_id, chat_id) = if notify_chat {
+            (context.channel_id, context.platform_chat_id.as_deref())
+        } else {
+            (None, None)
+        };
+        if let Err(e) = db.create_safe_proposal(
+            &safe_tx_hash,
+            &safe_display,
+            network.as_ref(),
+            &format!("{:?}", to),
+            &value.to_string(),
+            &data_hex,
+            nonce as i64,
+            &params.description,
+            channel_id.filter(|_| chat_id.is_some()),
+            chat_id,
+            info.threshold as i64,
+            &proposer.to_lowercase(),
+        ) {
+            log::error!("[propose_safe_tx] Proposal {} submitted but not tracked: {}", safe_tx_hash, e);
+        }
+
+        let link = safe_app_url(network.as_ref(), &safe_display, &safe_tx_hash);
+        let next_step = if info.threshold <= 1 {
+            "The Safe threshold is 1, so this proposal can be executed by any owner right away.".to_string()
+        } else {
+            format!(
+                "Waiting for {} more signature(s). Co-signers can review and sign here:\n{}\n\nYou will be notified as owners confirm.",
+                info.threshold - 1,
+                link
+            )
+        };
+
+        ToolResult::success(format!(
+            "SAFE TRANSACTION PROPOSED\n\n\
+            {}\n\
+            Safe: {} ({})\n\
+            To: {:?}\n\
+            Value: {}\n\
+            Nonce: {}\n\
+            Safe tx hash: {}\n\
+            Signatures: 1/{}\n\n\
+            {}",
+            params.description,
+            safe_display,
+            network,
+            to,
+            intent.value_display,
+            nonce,
+            safe_tx_hash,
+            info.threshold,
+            next_step
+        ))
+        .with_metadata(json!({
+            "status": SAFE_PROPOSAL_PENDING,
+            "safe_tx_hash": safe_tx_hash,
+            "safe_address": safe_display,
+            "network": network,
+            "nonce": nonce,
+            "confirmations": 1,
+            "threshold": info.threshold,
+            "safe_app_url": link,
+        }))
+    }
+}
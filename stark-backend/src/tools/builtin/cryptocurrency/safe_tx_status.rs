@@ -0,0 +1,137 @@
+//! Safe transaction status tool - signature progress of Safe proposals
+//!
+//! With a Safe tx hash, refreshes that proposal from the Safe Transaction
+//! Service (who has signed, whether it executed). Without one, lists the
+//! proposals still collecting signatures.
+
+use crate::db::tables::safe_proposals::{SafeProposal, SAFE_PROPOSAL_PENDING};
+use crate::safe::{self, client::SafeTxService};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tools::ToolSafetyLevel;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Safe transaction status tool
+pub struct SafeTxStatusTool {
+    definition: ToolDefinition,
+}
+
+impl SafeTxStatusTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "safe_tx_hash".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Safe tx hash returned by propose_safe_tx. Omit to list proposals still collecting signatures.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        SafeTxStatusTool {
+            definition: ToolDefinition {
+                name: "safe_tx_status".to_string(),
+                description: "Check signature progress of Safe multisig proposals made with propose_safe_tx: who has signed, how many signatures are still needed, and whether the transaction executed.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for SafeTxStatusTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SafeTxStatusParams {
+    safe_tx_hash: Option<String>,
+}
+
+fn describe(p: &SafeProposal) -> String {
+    let mut line = format!(
+        "- {} — {} on {} (nonce {}): {}, {}/{} signatures",
+        p.safe_tx_hash, p.description, p.network, p.nonce, p.status, p.confirmations, p.threshold
+    );
+    if !p.confirmed_by.is_empty() {
+        line.push_str(&format!("\n  Signed by: {}", p.confirmed_by.join(", ")));
+    }
+    if let Some(ref hash) = p.tx_hash {
+        line.push_str(&format!("\n  Transaction: {}", hash));
+    }
+    line
+}
+
+#[async_trait]
+impl Tool for SafeTxStatusTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: SafeTxStatusParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available."),
+        };
+
+        let Some(hash) = params.safe_tx_hash else {
+            return match db.list_safe_proposals(Some(SAFE_PROPOSAL_PENDING), 20) {
+                Ok(pending) if pending.is_empty() => ToolResult::success("No Safe proposals are waiting for signatures."),
+                Ok(pending) => {
+                    let lines: Vec<String> = pending.iter().map(describe).collect();
+                    ToolResult::success(format!("{} pending Safe proposal(s):\n{}", pending.len(), lines.join("\n")))
+                        .with_metadata(json!({ "proposals": pending }))
+                }
+                Err(e) => ToolResult::error(format!("Database error: {}", e)),
+            };
+        };
+
+        let proposal = match db.get_safe_proposal(hash.trim()) {
+            Ok(Some(p)) => p,
+            Ok(None) => return ToolResult::error(format!(
+                "No tracked Safe proposal with hash {}. Only proposals made with propose_safe_tx are tracked.",
+                hash
+            )),
+            Err(e) => return ToolResult::error(format!("Database error: {}", e)),
+        };
+        if proposal.status != SAFE_PROPOSAL_PENDING {
+            return ToolResult::success(describe(&proposal)).with_metadata(json!(proposal));
+        }
+
+        let service = match SafeTxService::new(&proposal.network, safe::api_key(db)) {
+            Ok(s) => s,
+            Err(e) => return ToolResult::error(e),
+        };
+        let safe_nonce = match proposal.safe_address.parse() {
+            Ok(safe) => service.get_safe(safe).await.ok().map(|info| info.nonce),
+            Err(_) => None,
+        };
+        match safe::refresh_proposal(db, context.broadcaster.as_deref(), &service, &proposal, safe_nonce).await {
+            Ok(updated) => ToolResult::success(describe(&updated)).with_metadata(json!(updated)),
+            Err(e) => ToolResult::error(format!("Failed to refresh proposal: {}", e)),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::ReadOnly
+    }
+}
@@ -183,10 +183,15 @@ impl Tool for SignRawTxTool {
                     error: Some(format!("Invalid parameters: {}", e)),
                     metadata: None,
                     retry_after_secs: None,
+                    payload: None,
                 }
             }
         };
 
+        if crate::paper::is_enabled(context) {
+            return crate::paper::unsupported("sign_raw_tx");
+        }
+
         // Require wallet provider
         let wallet_provider = match &context.wallet_provider {
             Some(wp) => wp.clone(),
@@ -197,6 +202,7 @@ impl Tool for SignRawTxTool {
                     error: Some("No wallet provider configured".to_string()),
                     metadata: None,
                     retry_after_secs: None,
+                    payload: None,
                 }
             }
         };
@@ -219,6 +225,7 @@ impl Tool for SignRawTxTool {
                     error: Some(format!("Failed to create RPC client: {}", e)),
                     metadata: None,
                     retry_after_secs: None,
+                    payload: None,
                 }
             }
         };
@@ -234,6 +241,7 @@ impl Tool for SignRawTxTool {
                     error: Some(format!("Invalid wallet address: {}", from_str)),
                     metadata: None,
                     retry_after_secs: None,
+                    payload: None,
                 }
             }
         };
@@ -246,6 +254,7 @@ impl Tool for SignRawTxTool {
                     error: Some(format!("Invalid 'to' address: {}", p.to)),
                     metadata: None,
                     retry_after_secs: None,
+                    payload: None,
                 }
             }
         };
@@ -260,6 +269,7 @@ impl Tool for SignRawTxTool {
                     error: Some(format!("Invalid value: {}", p.value)),
                     metadata: None,
                     retry_after_secs: None,
+                    payload: None,
                 }
             }
         };
@@ -274,14 +284,15 @@ impl Tool for SignRawTxTool {
                     error: Some(format!("Invalid calldata hex: {}", e)),
                     metadata: None,
                     retry_after_secs: None,
+                    payload: None,
                 }
             }
         };
 
-        // Fetch nonce if not provided
+        // Reserve nonce if not provided
         let nonce = match p.nonce {
             Some(n) => U256::from(n),
-            None => match rpc.get_transaction_count(from_address).await {
+            None => match crate::tx_queue::nonce::reserve(&rpc, network, from_address).await {
                 Ok(n) => n,
                 Err(e) => {
                     return ToolResult {
@@ -290,6 +301,7 @@ impl Tool for SignRawTxTool {
                         error: Some(format!("Failed to fetch nonce: {}", e)),
                         metadata: None,
                         retry_after_secs: None,
+                        payload: None,
                     }
                 }
             },
@@ -311,6 +323,7 @@ impl Tool for SignRawTxTool {
                         error: Some(format!("Failed to estimate gas fees: {}", e)),
                         metadata: None,
                         retry_after_secs: None,
+                        payload: None,
                     }
                 }
             },
@@ -353,6 +366,7 @@ impl Tool for SignRawTxTool {
                     error: Some(format!("Failed to sign transaction: {}", e)),
                     metadata: None,
                     retry_after_secs: None,
+                    payload: None,
                 }
             }
         };
@@ -383,6 +397,7 @@ impl Tool for SignRawTxTool {
             error: None,
             metadata: None,
             retry_after_secs: None,
+            payload: None,
         }
     }
 }
@@ -0,0 +1,275 @@
+//! Sign typed data tool - EIP-712 signatures for permits, orders and attestations
+//!
+//! Nothing is signed unless it passes the deterministic typed data policy
+//! (domain allow-list, permit spender/expiry/amount checks, see
+//! `web3::typed_data`) and the same AI intent verification used for
+//! transactions. The signature is returned and cached in a register for the
+//! tool that submits it.
+
+use crate::tools::builtin::cryptocurrency::verify_intent::{self, TransactionIntent};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::web3::typed_data;
+use async_trait::async_trait;
+use ethers::types::transaction::eip712::{Eip712, TypedData};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Message JSON longer than this is truncated in the verification description
+const MAX_MESSAGE_CHARS: usize = 1500;
+
+/// Sign typed data tool
+pub struct SignTypedDataTool {
+    definition: ToolDefinition,
+}
+
+impl SignTypedDataTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "typed_data".to_string(),
+            PropertySchema {
+                schema_type: "object".to_string(),
+                description: "Full EIP-712 payload: { types, primaryType, domain, message } (object or JSON string).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "typed_data_register".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Register holding the EIP-712 payload (e.g. from a quote or order API). Preferred over 'typed_data'.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "purpose".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "What the signature is for, in the user's terms (e.g. 'Permit2 approval for swapping 50 USDC to ETH').".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "cache_as".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Register to store the signature in (default: typed_data_signature).".to_string(),
+                default: Some(json!("typed_data_signature")),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        SignTypedDataTool {
+            definition: ToolDefinition {
+                name: "sign_typed_data".to_string(),
+                description: "Sign EIP-712 typed data (Permit/Permit2 approvals, off-chain orders, attestations) with the agent wallet. Only allow-listed domains and spenders can be signed, and the request is verified against the user's intent first. Returns the signature and caches it in a register.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["purpose".to_string()],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for SignTypedDataTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SignTypedDataParams {
+    typed_data: Option<Value>,
+    typed_data_register: Option<String>,
+    purpose: String,
+    #[serde(default = "default_cache_as")]
+    cache_as: String,
+}
+
+fn default_cache_as() -> String {
+    "typed_data_signature".to_string()
+}
+
+/// Accept the payload as an object or as a JSON string
+fn as_typed_data_object(v: Value) -> Result<Value, String> {
+    match v {
+        Value::String(s) => serde_json::from_str(&s).map_err(|e| format!("Typed data is not valid JSON: {}", e)),
+        Value::Object(_) => Ok(v),
+        other => Err(format!("Expected an EIP-712 object, got {}", other)),
+    }
+}
+
+fn network_name(chain_id: Option<u64>) -> String {
+    match chain_id {
+        Some(1) => "mainnet".to_string(),
+        Some(8453) => "base".to_string(),
+        Some(137) => "polygon".to_string(),
+        Some(id) => format!("chain {}", id),
+        None => "off-chain".to_string(),
+    }
+}
+
+#[async_trait]
+impl Tool for SignTypedDataTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: SignTypedDataParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        if crate::paper::is_enabled(context) {
+            return crate::paper::unsupported("sign_typed_data");
+        }
+
+        let raw = if let Some(ref reg_name) = params.typed_data_register {
+            match context.registers.get(reg_name) {
+                Some(v) => v,
+                None => return ToolResult::error(format!(
+                    "Register '{}' not found. Available: {:?}",
+                    reg_name, context.registers.keys()
+                )),
+            }
+        } else if let Some(v) = params.typed_data {
+            v
+        } else {
+            return ToolResult::error("Provide 'typed_data' or 'typed_data_register'.");
+        };
+        let payload = match as_typed_data_object(raw) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        let wallet_provider = match &context.wallet_provider {
+            Some(wp) => wp,
+            None => return ToolResult::error("Wallet not configured. Cannot sign typed data."),
+        };
+
+        // Same gate as transactions: no signing from group chats unless rogue mode is on
+        let is_gateway_channel = context
+            .channel_type
+            .as_deref()
+            .is_some_and(|ct| matches!(ct.to_lowercase().as_str(), "discord" | "telegram" | "slack"));
+        let is_rogue_mode = context
+            .extra
+            .get("rogue_mode_enabled")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if is_gateway_channel && !is_rogue_mode {
+            return ToolResult::error(
+                "Signatures cannot be created in Discord/Telegram/Slack channels unless Rogue Mode is enabled."
+            );
+        }
+
+        // Deterministic policy first: cheap, and never overridable by the model
+        let wallet = wallet_provider.get_address();
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        if let Err(reason) = typed_data::check(&typed_data::policy(), &payload, &wallet, now) {
+            log::warn!("[sign_typed_data] Policy blocked signature: {}", reason);
+            return ToolResult::error(reason);
+        }
+
+        let digest = match serde_json::from_value::<TypedData>(payload.clone()) {
+            Ok(td) => match td.encode_eip712() {
+                Ok(d) => d,
+                Err(e) => return ToolResult::error(format!("Failed to hash typed data: {}", e)),
+            },
+            Err(e) => return ToolResult::error(format!("Invalid EIP-712 payload: {}", e)),
+        };
+        let digest_hex = format!("0x{}", hex::encode(digest));
+
+        let primary_type = payload["primaryType"].as_str().unwrap_or("").to_string();
+        let domain = &payload["domain"];
+        let domain_name = domain["name"].as_str().unwrap_or("unnamed domain").to_string();
+        let verifying_contract = domain["verifyingContract"].as_str().map(str::to_string);
+        let chain_id = domain["chainId"]
+            .as_u64()
+            .or_else(|| domain["chainId"].as_str().and_then(|s| s.parse().ok()));
+
+        let mut message_json = serde_json::to_string(&payload["message"]).unwrap_or_default();
+        if message_json.chars().count() > MAX_MESSAGE_CHARS {
+            message_json = format!("{}…", message_json.chars().take(MAX_MESSAGE_CHARS).collect::<String>());
+        }
+        let intent = TransactionIntent {
+            tx_type: "typed_data_signature".to_string(),
+            to: verifying_contract.clone().unwrap_or_else(|| "off-chain".to_string()),
+            value: "0".to_string(),
+            value_display: "no value (signature only)".to_string(),
+            network: network_name(chain_id),
+            function_name: Some(primary_type.clone()),
+            abi_name: None,
+            preset_name: None,
+            destination_chain: None,
+            calldata: None,
+            description: format!(
+                "Sign EIP-712 {} for domain '{}' — {}. Message: {}",
+                primary_type, domain_name, params.purpose, message_json
+            ),
+            token_spend: None,
+        };
+        if let Err(reason) = verify_intent::verify_intent(&intent, context, None).await {
+            return ToolResult::error(reason);
+        }
+
+        // Standard wallets sign the precomputed digest; Flash wallets sign the payload itself
+        let mut to_sign = payload.clone();
+        to_sign["_hash"] = json!(digest_hex);
+        let signature = match wallet_provider.sign_typed_data(&to_sign).await {
+            Ok(sig) => format!("0x{}", hex::encode(sig.to_vec())),
+            Err(e) => return ToolResult::error(format!("Failed to sign typed data: {}", e)),
+        };
+
+        context.set_register(&params.cache_as, json!(signature), "sign_typed_data");
+        log::info!(
+            "[sign_typed_data] Signed {} for '{}' ({}) digest={}",
+            primary_type, domain_name, intent.network, digest_hex
+        );
+
+        ToolResult::success(format!(
+            "SIGNED {} ({})\n\n\
+            Domain: {}{}\n\
+            Network: {}\n\
+            Digest: {}\n\
+            Signature: {}\n\n\
+            Cached in register '{}'.",
+            primary_type,
+            params.purpose,
+            domain_name,
+            verifying_contract.as_deref().map(|c| format!(" ({})", c)).unwrap_or_default(),
+            intent.network,
+            digest_hex,
+            signature,
+            params.cache_as
+        ))
+        .with_metadata(json!({
+            "primary_type": primary_type,
+            "domain": domain,
+            "digest": digest_hex,
+            "signature": signature,
+            "signer": wallet,
+            "register": params.cache_as,
+        }))
+    }
+}
@@ -6,9 +6,11 @@
 //! Reduces a typical 8-iteration swap flow to 1 tool call + 1 broadcast.
 
 use super::broadcast_web3_tx::BroadcastWeb3TxTool;
-use super::token_lookup::TokenLookupTool;
-use super::to_raw_amount::ToRawAmountTool;
+use super::token_lookup::{TokenInfo, TokenLookupTool};
 use super::x402_preset_fetch::fetch_x402_preset;
+use crate::db::tables::paper_trades::{RecordPaperTradeRequest, PAPER_KIND_SWAP};
+use crate::db::tables::trades::{RecordTradeRequest, TRADE_KIND_SWAP};
+use crate::token_metadata;
 use crate::tools::presets::{get_chain_id, get_network_name, get_web3_preset};
 use crate::tools::registry::Tool;
 use crate::tools::rpc_config::resolve_rpc_from_context;
@@ -16,7 +18,7 @@ use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
 };
 use crate::web3::{
-    self, call_function, default_abis_dir, encode_call, execute_resolved_call,
+    self, amounts, call_function, default_abis_dir, encode_call, execute_resolved_call,
     find_function_with_params, load_abi, parse_abi, resolve_network, token_to_value,
 };
 use async_trait::async_trait;
@@ -49,7 +51,7 @@ impl SwapTokenTool {
             "sell_token".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "Token symbol to sell (e.g., 'USDC', 'ETH', 'WETH'). Case-insensitive."
+                description: "Token symbol to sell (e.g., 'USDC', 'ETH', 'WETH'), or a contract address. Case-insensitive."
                     .to_string(),
                 default: None,
                 items: None,
@@ -61,7 +63,7 @@ impl SwapTokenTool {
             "buy_token".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "Token symbol to buy (e.g., 'ETH', 'USDC', 'WETH'). Case-insensitive."
+                description: "Token symbol to buy (e.g., 'ETH', 'USDC', 'WETH'), or a contract address. Case-insensitive."
                     .to_string(),
                 default: None,
                 items: None,
@@ -139,6 +141,25 @@ fn default_network() -> String {
     "base".to_string()
 }
 
+/// Resolve a symbol from tokens.ron, or a contract address through the
+/// token metadata cache. Returns the token and the symbol to display.
+async fn lookup_token(token: &str, network: &str, context: &ToolContext) -> Option<(TokenInfo, String)> {
+    if let Some(info) = TokenLookupTool::lookup(token, network) {
+        return Some((info, token.to_uppercase()));
+    }
+    if !token.starts_with("0x") {
+        return None;
+    }
+    let meta = match &context.database {
+        Some(db) => token_metadata::resolve(db, network, token).await,
+        None => token_metadata::lookup(network, token),
+    }?;
+    Some((
+        TokenInfo { address: token.to_string(), decimals: meta.decimals, name: meta.name },
+        meta.symbol,
+    ))
+}
+
 #[async_trait]
 impl Tool for SwapTokenTool {
     fn definition(&self) -> ToolDefinition {
@@ -188,8 +209,8 @@ impl Tool for SwapTokenTool {
 
         // ─── Step 2: Lookup sell token ─────────────────────────────────────────
 
-        let sell_info = match TokenLookupTool::lookup(&params.sell_token, &network_str) {
-            Some(info) => info,
+        let (sell_info, sell_symbol) = match lookup_token(&params.sell_token, &network_str, context).await {
+            Some(found) => found,
             None => {
                 return ToolResult::error(format!(
                     "Unknown sell token '{}' on {}",
@@ -204,7 +225,6 @@ impl Tool for SwapTokenTool {
         // For native ETH: keep the sentinel address — 0x API handles wrapping.
         // No allowance check needed (native ETH doesn't go through ERC-20 approval).
         let sell_address = &sell_info.address;
-        let sell_symbol = params.sell_token.to_uppercase();
         let sell_decimals = sell_info.decimals;
 
         context.set_register("sell_token", json!(sell_address), "swap_token");
@@ -218,8 +238,8 @@ impl Tool for SwapTokenTool {
 
         // ─── Step 3: Lookup buy token ──────────────────────────────────────────
 
-        let buy_info = match TokenLookupTool::lookup(&params.buy_token, &network_str) {
-            Some(info) => info,
+        let (buy_info, buy_symbol) = match lookup_token(&params.buy_token, &network_str, context).await {
+            Some(found) => found,
             None => {
                 return ToolResult::error(format!(
                     "Unknown buy token '{}' on {}",
@@ -228,7 +248,6 @@ impl Tool for SwapTokenTool {
             }
         };
 
-        let buy_symbol = params.buy_token.to_uppercase();
         context.set_register("buy_token", json!(&buy_info.address), "swap_token");
         context.set_register("buy_token_symbol", json!(&buy_symbol), "swap_token");
         context.set_register("buy_token_decimals", json!(buy_info.decimals), "swap_token");
@@ -252,10 +271,12 @@ impl Tool for SwapTokenTool {
 
         // ─── Step 5: Convert amount to raw ─────────────────────────────────────
 
-        let raw_amount = match ToRawAmountTool::convert_to_raw(&params.amount, sell_decimals) {
+        let sell_amount_u256 = match amounts::parse_units(&params.amount, sell_decimals) {
+            Ok(r) if r.is_zero() => return ToolResult::error("Invalid amount: must be greater than zero"),
             Ok(r) => r,
             Err(e) => return ToolResult::error(format!("Invalid amount: {}", e)),
         };
+        let raw_amount = sell_amount_u256.to_string();
 
         context.set_register("sell_amount", json!(&raw_amount), "swap_token");
 
@@ -264,6 +285,42 @@ impl Tool for SwapTokenTool {
             params.amount, raw_amount, sell_decimals
         );
 
+        // ─── Paper mode: simulate the fill from a live quote ──────────────────
+
+        if crate::paper::is_enabled(context) {
+            let quote = match fetch_x402_preset("swap_quote", &network_str, context).await {
+                Ok(q) => q,
+                Err(e) => return ToolResult::error(format!("Quote fetch failed: {}", e)),
+            };
+            let buy_amount_raw = match &quote["buyAmount"] {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                _ => return ToolResult::error("Quote has no buyAmount; cannot simulate the fill."),
+            };
+            return match crate::paper::record_fill(context, RecordPaperTradeRequest {
+                kind: PAPER_KIND_SWAP,
+                network: network_str.clone(),
+                sell_token: sell_address.clone(),
+                sell_symbol: sell_symbol.clone(),
+                sell_amount_raw: raw_amount.clone(),
+                sell_decimals,
+                buy_token: Some(buy_info.address.clone()),
+                buy_symbol: Some(buy_symbol.clone()),
+                buy_amount_raw: Some(buy_amount_raw),
+                buy_decimals: Some(buy_info.decimals),
+                counterparty: None,
+                sell_price_usd: None,
+                buy_price_usd: None,
+                details: Some(json!({ "source": "0x quote", "quote": quote })),
+                channel_id: context.channel_id,
+            })
+            .await
+            {
+                Ok(fill) => crate::paper::fill_result(&fill),
+                Err(e) => ToolResult::error(e),
+            };
+        }
+
         // ─── Step 6: Check allowance (ERC-20 tokens only) ─────────────────────
 
         if !is_native_eth {
@@ -284,9 +341,6 @@ impl Tool for SwapTokenTool {
                 }
             };
 
-            let sell_amount_u256 =
-                U256::from_dec_str(&raw_amount).unwrap_or(U256::zero());
-
             log::info!(
                 "[swap_token] Allowance: {} (need: {})",
                 allowance, sell_amount_u256
@@ -499,6 +553,29 @@ impl Tool for SwapTokenTool {
             .and_then(|v| v.as_str())
             .unwrap_or("unknown");
 
+        if let Some(db) = &context.database {
+            let buy_amount_raw = match &quote["buyAmount"] {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            };
+            crate::journal::record(db, RecordTradeRequest {
+                tx_uuid: swap_uuid.to_string(),
+                kind: TRADE_KIND_SWAP,
+                network: network_str.clone(),
+                sell_token: sell_address.clone(),
+                sell_symbol: sell_symbol.clone(),
+                sell_amount_raw: raw_amount.clone(),
+                sell_decimals,
+                buy_token: Some(buy_info.address.clone()),
+                buy_symbol: Some(buy_symbol.clone()),
+                buy_amount_raw,
+                buy_decimals: Some(buy_info.decimals),
+                counterparty: None,
+                channel_id: context.channel_id,
+            });
+        }
+
         ToolResult::success(format!(
             "SWAP QUEUED\n\n\
             {} {} → {} on {}\n\n\
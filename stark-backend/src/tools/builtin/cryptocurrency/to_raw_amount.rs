@@ -8,6 +8,7 @@ use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
 };
 use crate::tools::ToolSafetyLevel;
+use crate::web3::amounts;
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::{json, Value};
@@ -26,7 +27,7 @@ impl ToRawAmountTool {
             "amount".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "Human-readable amount (e.g., '1', '100', '0.5', '1.25'). This is what users typically mean when they say 'send 1 token'.".to_string(),
+                description: "Human-readable amount (e.g., '1', '100', '0.5', '1.25'). This is what users typically mean when they say 'send 1 token'. With decimals=18 a unit may follow ('0.1 eth', '20 gwei'). Never use scientific notation.".to_string(),
                 default: None,
                 items: None,
                 enum_values: None,
@@ -82,62 +83,15 @@ impl ToRawAmountTool {
     }
 
     /// Convert human-readable amount to raw units
-    /// Handles decimal amounts like "1.5" properly
+    /// Handles decimal amounts like "1.5" properly (see `crate::web3::amounts`);
+    /// with 18 decimals a native unit may follow ("0.1 eth", "20 gwei", "100 wei")
     pub fn convert_to_raw(amount: &str, decimals: u8) -> Result<String, String> {
-        let amount = amount.trim();
-
-        // Handle the conversion based on whether there's a decimal point
-        let (integer_part, decimal_part) = if let Some(dot_pos) = amount.find('.') {
-            let int_str = &amount[..dot_pos];
-            let dec_str = &amount[dot_pos + 1..];
-
-            // Validate parts are numeric
-            if !int_str.is_empty() && !int_str.chars().all(|c| c.is_ascii_digit()) {
-                return Err(format!("Invalid integer part: '{}'", int_str));
-            }
-            if !dec_str.chars().all(|c| c.is_ascii_digit()) {
-                return Err(format!("Invalid decimal part: '{}'", dec_str));
-            }
-
-            (
-                if int_str.is_empty() { "0" } else { int_str },
-                dec_str,
-            )
+        let raw = if decimals == amounts::ETH_DECIMALS && amount.chars().any(|c| c.is_ascii_alphabetic()) {
+            amounts::parse_native(amount)
         } else {
-            // No decimal point - validate it's a valid integer
-            if !amount.chars().all(|c| c.is_ascii_digit()) {
-                return Err(format!("Invalid amount: '{}'. Must be a number.", amount));
-            }
-            (amount, "")
+            amounts::parse_units(amount, decimals)
         };
-
-        let decimals = decimals as usize;
-        let decimal_len = decimal_part.len();
-
-        if decimal_len > decimals {
-            return Err(format!(
-                "Amount '{}' has {} decimal places but token only has {} decimals. Maximum precision exceeded.",
-                amount, decimal_len, decimals
-            ));
-        }
-
-        // Build the raw amount string
-        // integer_part + decimal_part + remaining zeros
-        let zeros_to_add = decimals - decimal_len;
-        let raw = format!(
-            "{}{}{}",
-            integer_part,
-            decimal_part,
-            "0".repeat(zeros_to_add)
-        );
-
-        // Remove leading zeros (but keep at least one digit)
-        let raw = raw.trim_start_matches('0');
-        if raw.is_empty() {
-            Ok("0".to_string())
-        } else {
-            Ok(raw.to_string())
-        }
+        raw.map(|raw| raw.to_string())
     }
 }
 
@@ -296,6 +250,14 @@ mod tests {
         assert!(ToRawAmountTool::convert_to_raw("-1", 18).is_err());
     }
 
+    #[test]
+    fn test_native_units() {
+        assert_eq!(ToRawAmountTool::convert_to_raw("20 gwei", 18).unwrap(), "20000000000");
+        assert_eq!(ToRawAmountTool::convert_to_raw("0.1 ETH", 18).unwrap(), "100000000000000000");
+        assert!(ToRawAmountTool::convert_to_raw("0.1e18", 18).is_err());
+        assert!(ToRawAmountTool::convert_to_raw("20 gwei", 6).is_err());
+    }
+
     #[test]
     fn test_whitespace_handling() {
         assert_eq!(ToRawAmountTool::convert_to_raw(" 1 ", 18).unwrap(), "1000000000000000000");
@@ -0,0 +1,562 @@
+//! Token approvals tool - audit and revoke spending rights granted by the wallet
+//!
+//! `list` finds every ERC-20 allowance and NFT operator approval the agent's
+//! wallet has ever granted (Approval / ApprovalForAll logs via Alchemy), then
+//! re-checks each one on-chain so only approvals that are still live are shown.
+//! Unlimited allowances are flagged. `revoke` queues `approve(spender, 0)` or
+//! `setApprovalForAll(operator, false)` through the same path as
+//! web3_function_call (intent verification, tx queue), so it still needs
+//! broadcast_web3_tx to go out.
+//!
+//! Single-token ERC-721 approvals are ignored: they are cleared on transfer and
+//! can't move anything else.
+
+use crate::token_metadata;
+use crate::tools::registry::Tool;
+use crate::tools::rpc_config::{alchemy_rpc_url, resolve_rpc_readonly, Network};
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::web3::{decode, default_abis_dir, execute_resolved_call, resolve_network};
+use async_trait::async_trait;
+use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// keccak256("Approval(address,address,uint256)") - shared by ERC-20 and ERC-721
+const APPROVAL_TOPIC: &str = "0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925";
+/// keccak256("ApprovalForAll(address,address,bool)")
+const APPROVAL_FOR_ALL_TOPIC: &str = "0x17307eab39ab6107e8899845ad3d59bd9653f200f220920489ca2b5937696c31";
+
+/// allowance(address,address)
+const ALLOWANCE_SELECTOR: &str = "dd62ed3e";
+/// isApprovedForAll(address,address)
+const IS_APPROVED_FOR_ALL_SELECTOR: &str = "e985e9c5";
+
+/// Most recent (token, spender) pairs re-checked on-chain per listing
+const MAX_CHECKS: usize = 50;
+
+const STANDARD_ERC20: &str = "erc20";
+const STANDARD_NFT: &str = "nft";
+
+/// Token approvals tool
+pub struct TokenApprovalsTool {
+    definition: ToolDefinition,
+}
+
+impl TokenApprovalsTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'list' shows live approvals granted by the wallet; 'revoke' queues a transaction removing one.".to_string(),
+                default: Some(json!("list")),
+                items: None,
+                enum_values: Some(vec!["list".to_string(), "revoke".to_string()]),
+            },
+        );
+
+        properties.insert(
+            "token".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Token or NFT contract address (revoke only).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "spender".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Spender (ERC-20) or operator (NFT) address to revoke (revoke only).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "standard".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Approval kind to revoke, as reported by 'list': 'erc20' allowance or 'nft' operator approval.".to_string(),
+                default: Some(json!(STANDARD_ERC20)),
+                items: None,
+                enum_values: Some(vec![STANDARD_ERC20.to_string(), STANDARD_NFT.to_string()]),
+            },
+        );
+
+        properties.insert(
+            "network".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Network (default: the selected network, else base).".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec!["base".to_string(), "mainnet".to_string(), "polygon".to_string()]),
+            },
+        );
+
+        TokenApprovalsTool {
+            definition: ToolDefinition {
+                name: "token_approvals".to_string(),
+                description: "Audit token approvals: list the ERC-20 allowances and NFT operator approvals the wallet has granted that are still live (unlimited ones flagged), or queue a revocation. Revocations are queued like any other transaction and must be broadcast with broadcast_web3_tx.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for TokenApprovalsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenApprovalsParams {
+    #[serde(default = "default_action")]
+    action: String,
+    token: Option<String>,
+    spender: Option<String>,
+    #[serde(default = "default_standard")]
+    standard: String,
+    network: Option<String>,
+}
+
+fn default_action() -> String {
+    "list".to_string()
+}
+
+fn default_standard() -> String {
+    STANDARD_ERC20.to_string()
+}
+
+/// An approval seen in the wallet's logs, before checking current state
+#[derive(Debug, Clone, PartialEq)]
+struct ApprovalCandidate {
+    standard: &'static str,
+    token: String,
+    spender: String,
+    block: u64,
+}
+
+/// A live approval
+#[derive(Debug, Clone, Serialize)]
+struct LiveApproval {
+    standard: &'static str,
+    token: String,
+    token_symbol: Option<String>,
+    spender: String,
+    /// Raw allowance for ERC-20, absent for operator approvals
+    allowance: Option<String>,
+    display: String,
+    unlimited: bool,
+}
+
+/// Address stored in the low 20 bytes of an indexed topic
+fn topic_address(topic: &str) -> Option<String> {
+    let hex = topic.trim_start_matches("0x");
+    if hex.len() != 64 {
+        return None;
+    }
+    Some(format!("0x{}", hex[24..].to_lowercase()))
+}
+
+fn topic_for_address(address: &str) -> String {
+    format!("0x{:0>64}", address.trim_start_matches("0x").to_lowercase())
+}
+
+fn hex_u64(v: &Value) -> u64 {
+    v.as_str()
+        .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+        .unwrap_or(0)
+}
+
+fn data_is_zero(data: &str) -> bool {
+    data.trim_start_matches("0x").chars().all(|c| c == '0')
+}
+
+/// Reduce the wallet's Approval / ApprovalForAll logs to the latest grant per
+/// (standard, token, spender), newest first. Pairs whose latest event set the
+/// approval back to zero/false are dropped without an RPC call.
+fn candidates_from_logs(logs: &[Value]) -> Vec<ApprovalCandidate> {
+    // key -> (block, log index, still granted)
+    let mut latest: HashMap<(&'static str, String, String), (u64, u64, bool)> = HashMap::new();
+
+    for log in logs {
+        let topics: Vec<&str> = match log.get("topics").and_then(|t| t.as_array()) {
+            Some(t) => t.iter().filter_map(|v| v.as_str()).collect(),
+            None => continue,
+        };
+        let standard = match (topics.first().map(|t| t.to_lowercase()), topics.len()) {
+            (Some(t), 3) if t == APPROVAL_TOPIC => STANDARD_ERC20,
+            (Some(t), 3) if t == APPROVAL_FOR_ALL_TOPIC => STANDARD_NFT,
+            // ERC-721 single-token Approval (tokenId indexed) or unrelated event
+            _ => continue,
+        };
+        let (token, spender) = match (
+            log.get("address").and_then(|a| a.as_str()),
+            topic_address(topics[2]),
+        ) {
+            (Some(token), Some(spender)) => (token.to_lowercase(), spender),
+            _ => continue,
+        };
+        let granted = !data_is_zero(log.get("data").and_then(|d| d.as_str()).unwrap_or("0x"));
+        let position = (hex_u64(&log["blockNumber"]), hex_u64(&log["logIndex"]));
+
+        let entry = latest.entry((standard, token, spender)).or_insert((0, 0, false));
+        if position >= (entry.0, entry.1) {
+            *entry = (position.0, position.1, granted);
+        }
+    }
+
+    let mut candidates: Vec<ApprovalCandidate> = latest
+        .into_iter()
+        .filter(|(_, (_, _, granted))| *granted)
+        .map(|((standard, token, spender), (block, _, _))| ApprovalCandidate { standard, token, spender, block })
+        .collect();
+    candidates.sort_by(|a, b| b.block.cmp(&a.block).then_with(|| a.token.cmp(&b.token)));
+    candidates
+}
+
+/// Plain JSON-RPC request
+async fn rpc_request(url: &str, method: &str, params: Value) -> Result<Value, String> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params,
+        "id": 1
+    });
+    let body: Value = crate::http::shared_client()
+        .post(url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("RPC request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse RPC response: {}", e))?;
+
+    match body.get("result") {
+        Some(result) => Ok(result.clone()),
+        None => Err(format!(
+            "RPC error: {}",
+            body.get("error").map(|e| e.to_string()).unwrap_or_default()
+        )),
+    }
+}
+
+/// `selector(owner, spender)` eth_call, returning the uint256 result
+async fn call_owner_spender(url: &str, contract: &str, selector: &str, owner: &str, spender: &str) -> Result<U256, String> {
+    let data = format!(
+        "0x{}{}{}",
+        selector,
+        topic_for_address(owner).trim_start_matches("0x"),
+        topic_for_address(spender).trim_start_matches("0x")
+    );
+    let result = rpc_request(url, "eth_call", json!([{ "to": contract, "data": data }, "latest"])).await?;
+    let hex = result.as_str().unwrap_or("0x").trim_start_matches("0x");
+    if hex.is_empty() {
+        return Err(format!("{} returned no data", contract));
+    }
+    U256::from_str_radix(&hex[..hex.len().min(64)], 16).map_err(|e| format!("Bad eth_call result: {}", e))
+}
+
+/// Re-check a candidate against current state. `None` if it is no longer live.
+async fn check_candidate(url: &str, owner: &str, network: &str, candidate: &ApprovalCandidate) -> Result<Option<LiveApproval>, String> {
+    let known = token_metadata::lookup(network, &candidate.token);
+    let token_symbol = known.as_ref().map(|token| token.symbol.clone());
+    let token_label = token_symbol.clone().unwrap_or_else(|| candidate.token.clone());
+
+    if candidate.standard == STANDARD_NFT {
+        let approved = call_owner_spender(url, &candidate.token, IS_APPROVED_FOR_ALL_SELECTOR, owner, &candidate.spender).await?;
+        if approved.is_zero() {
+            return Ok(None);
+        }
+        return Ok(Some(LiveApproval {
+            standard: STANDARD_NFT,
+            token: candidate.token.clone(),
+            token_symbol,
+            spender: candidate.spender.clone(),
+            allowance: None,
+            display: format!("ALL NFTs in {}", token_label),
+            unlimited: true,
+        }));
+    }
+
+    let allowance = call_owner_spender(url, &candidate.token, ALLOWANCE_SELECTOR, owner, &candidate.spender).await?;
+    if allowance.is_zero() {
+        return Ok(None);
+    }
+    let display = match &known {
+        Some(token) => decode::format_token_amount(allowance, token.decimals, &token.symbol),
+        None if decode::is_unlimited(allowance) => format!("UNLIMITED {}", token_label),
+        None => format!("{} (raw units) of {}", allowance, token_label),
+    };
+    Ok(Some(LiveApproval {
+        standard: STANDARD_ERC20,
+        token: candidate.token.clone(),
+        token_symbol,
+        spender: candidate.spender.clone(),
+        allowance: Some(allowance.to_string()),
+        display,
+        unlimited: decode::is_unlimited(allowance),
+    }))
+}
+
+impl TokenApprovalsTool {
+    async fn list(&self, network: &str, context: &ToolContext) -> ToolResult {
+        let wallet_provider = match &context.wallet_provider {
+            Some(wp) => wp,
+            None => return ToolResult::error("Wallet not configured."),
+        };
+        let owner = wallet_provider.get_address().to_lowercase();
+
+        // Full-history log scans need Alchemy; public RPCs cap the block range
+        let url = match alchemy_rpc_url(network) {
+            Some(url) => url,
+            None => return ToolResult::error(
+                "Listing approvals requires an Alchemy API key (ALCHEMY_API_KEY on the API keys page)."
+            ),
+        };
+
+        let logs = match rpc_request(&url, "eth_getLogs", json!([{
+            "fromBlock": "0x0",
+            "toBlock": "latest",
+            "topics": [[APPROVAL_TOPIC, APPROVAL_FOR_ALL_TOPIC], topic_for_address(&owner)]
+        }])).await {
+            Ok(Value::Array(logs)) => logs,
+            Ok(other) => return ToolResult::error(format!("Unexpected eth_getLogs result: {}", other)),
+            Err(e) => return ToolResult::error(format!("Failed to fetch approval logs: {}", e)),
+        };
+
+        let candidates = candidates_from_logs(&logs);
+        let truncated = candidates.len() > MAX_CHECKS;
+
+        let mut live = Vec::new();
+        let mut failed = 0;
+        for candidate in candidates.iter().take(MAX_CHECKS) {
+            match check_candidate(&url, &owner, network, candidate).await {
+                Ok(Some(approval)) => live.push(approval),
+                Ok(None) => {}
+                Err(e) => {
+                    log::warn!("[token_approvals] Could not check {} -> {}: {}", candidate.token, candidate.spender, e);
+                    failed += 1;
+                }
+            }
+        }
+
+        let unlimited = live.iter().filter(|a| a.unlimited).count();
+        log::info!(
+            "[token_approvals] {} on {}: {} logs, {} candidates, {} live ({} unlimited)",
+            owner, network, logs.len(), candidates.len(), live.len(), unlimited
+        );
+
+        let mut content = if live.is_empty() {
+            format!("No live token approvals for {} on {}.", owner, network)
+        } else {
+            format!(
+                "{} live approval(s) for {} on {} ({} unlimited):\n",
+                live.len(), owner, network, unlimited
+            )
+        };
+        for approval in &live {
+            content.push_str(&format!(
+                "\n- [{}] {} → spender {}: {}{}",
+                approval.standard,
+                approval.token_symbol.as_deref().unwrap_or(&approval.token),
+                approval.spender,
+                approval.display,
+                if approval.unlimited { "  ⚠ UNLIMITED" } else { "" }
+            ));
+        }
+        if truncated {
+            content.push_str(&format!(
+                "\n\nOnly the {} most recently granted approvals were checked ({} total).",
+                MAX_CHECKS, candidates.len()
+            ));
+        }
+        if failed > 0 {
+            content.push_str(&format!("\n\n{} approval(s) could not be checked.", failed));
+        }
+        if !live.is_empty() {
+            content.push_str("\n\nTo revoke: token_approvals with action 'revoke', the token, spender and standard.");
+        }
+
+        ToolResult::success(content).with_metadata(json!({
+            "owner": owner,
+            "network": network,
+            "approvals": live,
+            "unlimited_count": unlimited,
+            "truncated": truncated,
+            "unchecked": failed,
+        }))
+    }
+
+    async fn revoke(&self, params: &TokenApprovalsParams, network: &Network, context: &ToolContext) -> ToolResult {
+        let (token, spender) = match (params.token.as_deref(), params.spender.as_deref()) {
+            (Some(t), Some(s)) => (t.trim(), s.trim()),
+            _ => return ToolResult::error("'revoke' requires 'token' and 'spender'."),
+        };
+        if token.parse::<Address>().is_err() {
+            return ToolResult::error(format!("Invalid token address: {}", token));
+        }
+        if spender.parse::<Address>().is_err() {
+            return ToolResult::error(format!("Invalid spender address: {}", spender));
+        }
+        let (abi, function, call_params, selector) = match params.standard.as_str() {
+            STANDARD_ERC20 => ("erc20", "approve", vec![json!(spender), json!("0")], ALLOWANCE_SELECTOR),
+            STANDARD_NFT => ("erc721", "setApprovalForAll", vec![json!(spender), json!(false)], IS_APPROVED_FOR_ALL_SELECTOR),
+            other => return ToolResult::error(format!("Unknown standard '{}'. Use 'erc20' or 'nft'.", other)),
+        };
+
+        // Don't queue (and pay gas for) a revocation that changes nothing
+        if let Some(wallet_provider) = &context.wallet_provider {
+            let rpc = resolve_rpc_readonly(network.as_ref());
+            if !rpc.use_x402 {
+                let owner = wallet_provider.get_address();
+                if let Ok(current) = call_owner_spender(&rpc.url, token, selector, &owner, spender).await {
+                    if current.is_zero() {
+                        return ToolResult::success(format!(
+                            "Nothing to revoke: {} has no {} approval for {} on {}.",
+                            spender, params.standard, token, network
+                        ));
+                    }
+                }
+            }
+        }
+
+        log::info!("[token_approvals] Queueing revoke: {}::{}({}) on {} at {}", abi, function, spender, network, token);
+        let result = execute_resolved_call(
+            &default_abis_dir(),
+            abi,
+            token,
+            function,
+            &call_params,
+            "0",
+            false,
+            network,
+            context,
+            None,
+        )
+        .await;
+        if !result.success {
+            return result;
+        }
+
+        let uuid = result
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("uuid"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let label = token_metadata::lookup(network.as_ref(), token)
+            .map(|token| token.symbol)
+            .unwrap_or_else(|| token.to_string());
+
+        ToolResult::success(format!(
+            "REVOCATION QUEUED (not yet broadcast)\n\n\
+            Revokes {} approval on {} for {} ({})\n\
+            UUID: {}\n\n\
+            Next: broadcast_web3_tx with uuid \"{}\"",
+            params.standard, label, spender, network, uuid, uuid
+        ))
+        .with_metadata(json!({
+            "status": "revoke_queued",
+            "uuid": uuid,
+            "standard": params.standard,
+            "token": token,
+            "spender": spender,
+            "network": network,
+        }))
+    }
+}
+
+#[async_trait]
+impl Tool for TokenApprovalsTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: TokenApprovalsParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let network = match resolve_network(params.network.as_deref(), context.selected_network.as_deref()) {
+            Ok(n) => n,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        match params.action.as_str() {
+            "list" => self.list(network.as_ref(), context).await,
+            "revoke" => self.revoke(&params, &network, context).await,
+            other => ToolResult::error(format!("Unknown action '{}'. Use 'list' or 'revoke'.", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(topic0: &str, token: &str, spender: &str, data: &str, block: u64, index: u64) -> Value {
+        json!({
+            "address": token,
+            "topics": [topic0, topic_for_address("0x00000000000000000000000000000000000000aa"), topic_for_address(spender)],
+            "data": data,
+            "blockNumber": format!("0x{:x}", block),
+            "logIndex": format!("0x{:x}", index),
+        })
+    }
+
+    #[test]
+    fn test_candidates_keep_latest_grant_per_pair() {
+        let usdc = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+        let router = "0x00000000000000000000000000000000000000b0";
+        let nft = "0x00000000000000000000000000000000000000c0";
+        let max = format!("0x{}", "f".repeat(64));
+        let logs = vec![
+            // Granted then revoked: dropped
+            log(APPROVAL_TOPIC, usdc, router, &max, 10, 0),
+            log(APPROVAL_TOPIC, usdc, router, "0x0", 12, 3),
+            // Operator approval still set
+            log(APPROVAL_FOR_ALL_TOPIC, nft, router, "0x1", 11, 0),
+            // Single-token ERC-721 Approval (tokenId indexed): ignored
+            json!({
+                "address": nft,
+                "topics": [APPROVAL_TOPIC, topic_for_address(router), topic_for_address(router), format!("0x{:064x}", 7)],
+                "data": "0x",
+                "blockNumber": "0xd",
+                "logIndex": "0x0",
+            }),
+            // Revoked then re-granted in the same block
+            log(APPROVAL_TOPIC, nft, router, "0x0", 14, 1),
+            log(APPROVAL_TOPIC, nft, router, "0x5", 14, 2),
+        ];
+
+        let candidates = candidates_from_logs(&logs);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].standard, STANDARD_ERC20);
+        assert_eq!(candidates[0].token, nft);
+        assert_eq!(candidates[0].block, 14);
+        assert_eq!(candidates[1].standard, STANDARD_NFT);
+        assert_eq!(candidates[1].spender, router);
+    }
+}
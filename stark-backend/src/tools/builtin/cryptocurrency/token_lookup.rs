@@ -80,6 +80,15 @@ pub fn get_all_token_symbols() -> Vec<(String, String)> {
     result
 }
 
+/// Find a known token by contract address on a network. Returns (symbol, info).
+pub fn lookup_by_address(address: &str, network: &str) -> Option<(String, TokenInfo)> {
+    let network_tokens = TOKENS.get()?.get(network)?;
+    network_tokens
+        .iter()
+        .find(|(_, info)| info.address.eq_ignore_ascii_case(address))
+        .map(|(symbol, info)| (symbol.clone(), info.clone()))
+}
+
 /// Token Lookup tool
 pub struct TokenLookupTool {
     definition: ToolDefinition,
@@ -0,0 +1,242 @@
+//! Trade journal tool - audit the agent's swaps and transfers, with PnL
+//!
+//! `trades` lists journal entries (what was sold/bought or sent, prices at
+//! execution, tx hashes). `pnl` replays the journal into realized PnL for the
+//! period and unrealized PnL on what is still held, per token.
+
+use crate::db::tables::trades::{Trade, TRADE_KIND_SWAP};
+use crate::journal::{self, pnl::TokenPnl};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tools::ToolSafetyLevel;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Trade journal tool
+pub struct TradeJournalTool {
+    definition: ToolDefinition,
+}
+
+impl TradeJournalTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'trades' to list journal entries, 'pnl' for realized/unrealized PnL per token.".to_string(),
+                default: Some(json!("pnl")),
+                items: None,
+                enum_values: Some(vec!["trades".to_string(), "pnl".to_string()]),
+            },
+        );
+
+        properties.insert(
+            "period".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Reporting period: day, week, month, year or all (default: all).".to_string(),
+                default: Some(json!("all")),
+                items: None,
+                enum_values: Some(vec![
+                    "day".to_string(),
+                    "week".to_string(),
+                    "month".to_string(),
+                    "year".to_string(),
+                    "all".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "token".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Only show this token symbol (e.g. 'WETH').".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        TradeJournalTool {
+            definition: ToolDefinition {
+                name: "trade_journal".to_string(),
+                description: "Audit what the agent has done with funds: every swap and transfer it executed, with USD prices at execution time, and realized/unrealized PnL per token over a period.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for TradeJournalTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TradeJournalParams {
+    #[serde(default = "default_action")]
+    action: String,
+    #[serde(default = "default_period")]
+    period: String,
+    token: Option<String>,
+}
+
+fn default_action() -> String {
+    "pnl".to_string()
+}
+
+fn default_period() -> String {
+    "all".to_string()
+}
+
+fn usd(v: f64) -> String {
+    if v < 0.0 {
+        format!("-${:.2}", -v)
+    } else {
+        format!("${:.2}", v)
+    }
+}
+
+fn describe_trade(t: &Trade) -> String {
+    let when = t.executed_at.as_deref().unwrap_or(&t.created_at);
+    let sold = format!("{} {}", t.sell_amount(), t.sell_symbol);
+    let mut line = if t.kind == TRADE_KIND_SWAP {
+        format!(
+            "- {} [{}] swap {} → {} {} on {}",
+            when,
+            t.status,
+            sold,
+            t.buy_amount().map(|a| a.to_string()).unwrap_or_else(|| "?".to_string()),
+            t.buy_symbol.as_deref().unwrap_or("?"),
+            t.network
+        )
+    } else {
+        format!(
+            "- {} [{}] sent {} to {} on {}",
+            when,
+            t.status,
+            sold,
+            t.counterparty.as_deref().unwrap_or("?"),
+            t.network
+        )
+    };
+    if let Some(price) = t.sell_price_usd {
+        line.push_str(&format!(" @ {} per {}", usd(price), t.sell_symbol));
+    }
+    if let Some(ref hash) = t.tx_hash {
+        line.push_str(&format!("\n  tx: {}", hash));
+    }
+    line
+}
+
+fn describe_pnl(t: &TokenPnl) -> String {
+    let mut line = format!("- {} ({}): realized {}", t.symbol, t.network, usd(t.realized_usd));
+    if t.held > 0.0 {
+        line.push_str(&format!(", holding {:.6}", t.held));
+        if let Some(avg) = t.avg_cost_usd {
+            line.push_str(&format!(" @ avg {}", usd(avg)));
+        }
+        match t.unrealized_usd {
+            Some(u) => line.push_str(&format!(", unrealized {}", usd(u))),
+            None => line.push_str(", unrealized n/a (no current price)"),
+        }
+    }
+    if t.untracked_sold > 0.0 {
+        line.push_str(&format!(" ({:.6} sold from funds acquired outside the journal)", t.untracked_sold));
+    }
+    line
+}
+
+#[async_trait]
+impl Tool for TradeJournalTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: TradeJournalParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available."),
+        };
+        let since = match journal::period_start(&params.period) {
+            Ok(s) => s,
+            Err(e) => return ToolResult::error(e.to_string()),
+        };
+        let token = params.token.as_deref().map(str::to_uppercase);
+
+        match params.action.as_str() {
+            "trades" => {
+                let trades = match db.list_trades(None, since.as_deref(), 100) {
+                    Ok(t) => t,
+                    Err(e) => return ToolResult::error(format!("Database error: {}", e)),
+                };
+                let trades: Vec<Trade> = trades
+                    .into_iter()
+                    .filter(|t| {
+                        token.as_deref().is_none_or(|sym| {
+                            t.sell_symbol.eq_ignore_ascii_case(sym)
+                                || t.buy_symbol.as_deref().is_some_and(|b| b.eq_ignore_ascii_case(sym))
+                        })
+                    })
+                    .collect();
+                if trades.is_empty() {
+                    return ToolResult::success(format!("No trades in the journal for period '{}'.", params.period));
+                }
+                let lines: Vec<String> = trades.iter().map(describe_trade).collect();
+                ToolResult::success(format!("{} trade(s), newest first:\n{}", trades.len(), lines.join("\n")))
+                    .with_metadata(json!({ "trades": trades }))
+            }
+            "pnl" => {
+                let mut report = match journal::pnl_report(db, since.as_deref()).await {
+                    Ok(r) => r,
+                    Err(e) => return ToolResult::error(e.to_string()),
+                };
+                if let Some(sym) = token.as_deref() {
+                    report.tokens.retain(|t| t.symbol.eq_ignore_ascii_case(sym));
+                    report.realized_usd = report.tokens.iter().map(|t| t.realized_usd).sum();
+                    report.unrealized_usd = report.tokens.iter().filter_map(|t| t.unrealized_usd).sum();
+                }
+                if report.tokens.is_empty() {
+                    return ToolResult::success(format!("No executed trades to report for period '{}'.", params.period));
+                }
+                let mut text = format!(
+                    "PnL ({}): realized {}, unrealized {}\n{}",
+                    params.period,
+                    usd(report.realized_usd),
+                    usd(report.unrealized_usd),
+                    report.tokens.iter().map(describe_pnl).collect::<Vec<_>>().join("\n")
+                );
+                if report.unpriced_trades > 0 {
+                    text.push_str(&format!(
+                        "\n\n{} swap(s) excluded: no USD price was available at execution time.",
+                        report.unpriced_trades
+                    ));
+                }
+                ToolResult::success(text).with_metadata(json!(report))
+            }
+            other => ToolResult::error(format!("Unknown action '{}'. Use 'trades' or 'pnl'.", other)),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::ReadOnly
+    }
+}
@@ -8,12 +8,16 @@
 //! ## Steps
 //! 1. Read `original_user_message` from `context.extra`
 //! 2. Run deterministic checks (fast, no network)
-//! 3. Run isolated AI verification call
-//! 4. Return `Ok(())` or `Err(reason)`
+//! 3. Check the amounts against the wallet's balances and the owner's
+//!    sanity bounds (`web3::amounts::AmountBounds`)
+//! 4. Run isolated AI verification call
+//! 5. Return `Ok(())` or `Err(reason)`
 
 use crate::ai::{AiClient, Message, MessageRole};
 use crate::gateway::protocol::GatewayEvent;
 use crate::tools::types::ToolContext;
+use crate::web3::amounts::{self, BoundCheck, TokenSpend};
+use ethers::types::U256;
 use serde_json::Value;
 
 /// Describes the transaction about to be queued.
@@ -30,6 +34,8 @@ pub struct TransactionIntent {
     pub destination_chain: Option<String>,
     pub calldata: Option<String>,
     pub description: String,
+    /// Token amount leaving the wallet besides the native value (bridges, approvals)
+    pub token_spend: Option<TokenSpend>,
 }
 
 // ─── Public entry point ──────────────────────────────────────────────────────
@@ -53,18 +59,22 @@ pub async fn verify_intent(
     let duration_ms = started.elapsed().as_millis() as i64;
     broadcast_tool_result(context, &result, duration_ms);
 
-    result
+    result.map(|_warnings| ())
 }
 
-/// Inner verification logic (deterministic checks + AI check).
+/// Inner verification logic (deterministic checks + bounds + AI check).
+/// Returns the amount warnings that didn't block.
 async fn run_verification(
     intent: &TransactionIntent,
     context: &ToolContext,
     ai_override: Option<&AiClient>,
-) -> Result<(), String> {
+) -> Result<Vec<String>, String> {
     // 1. Run deterministic checks first (cheap, no network)
     run_deterministic_checks(intent, context)?;
 
+    // 2. Amounts against balances (blocks only above the owner's hard bound)
+    let warnings = check_amount_bounds(intent, context).await?;
+
     // 2. Read original user message
     let user_message = context
         .extra
@@ -76,7 +86,7 @@ async fn run_verification(
     if user_message.is_empty() {
         log::warn!("[verify_intent] No original_user_message in context — skipping AI check");
         // Still pass; deterministic checks already ran.
-        return Ok(());
+        return Ok(warnings);
     }
 
     // 3. Obtain an AI client
@@ -89,14 +99,20 @@ async fn run_verification(
                 Some(c) => c,
                 None => {
                     log::warn!("[verify_intent] Could not build AI client — skipping AI check");
-                    return Ok(());
+                    return Ok(warnings);
                 }
             }
         }
     };
 
     // 4. Run AI verification
-    let prompt = format_verification_prompt(intent, &user_message);
+    let mut prompt = format_verification_prompt(intent, &user_message);
+    if !warnings.is_empty() {
+        prompt.push_str("\n\n## Amount warnings\n");
+        for warning in &warnings {
+            prompt.push_str(&format!("- This transaction {}\n", warning));
+        }
+    }
     let messages = vec![
         Message {
             role: MessageRole::System,
@@ -111,7 +127,7 @@ async fn run_verification(
     let ai_response = client.generate_text(messages).await;
 
     match ai_response {
-        Ok(text) => parse_verification_response(&text),
+        Ok(text) => parse_verification_response(&text).map(|()| warnings),
         Err(e) => {
             // Fail-open on AI errors: deterministic checks already passed,
             // and a flaky AI API shouldn't block legitimate transactions.
@@ -119,7 +135,7 @@ async fn run_verification(
                 "[verify_intent] AI verification failed (allowing tx): {}",
                 e
             );
-            Ok(())
+            Ok(warnings)
         }
     }
 }
@@ -141,10 +157,17 @@ fn broadcast_tool_call(context: &ToolContext, intent: &TransactionIntent) {
     }
 }
 
-fn broadcast_tool_result(context: &ToolContext, result: &Result<(), String>, duration_ms: i64) {
+fn broadcast_tool_result(context: &ToolContext, result: &Result<Vec<String>, String>, duration_ms: i64) {
     if let (Some(broadcaster), Some(channel_id)) = (&context.broadcaster, context.channel_id) {
         let (success, content) = match result {
-            Ok(()) => (true, "Transaction intent verified — checks passed.".to_string()),
+            Ok(warnings) if warnings.is_empty() => (true, "Transaction intent verified — checks passed.".to_string()),
+            Ok(warnings) => (
+                true,
+                format!(
+                    "Transaction intent verified — checks passed, with warnings:\n- This transaction {}",
+                    warnings.join("\n- This transaction ")
+                ),
+            ),
             Err(reason) => (false, reason.clone()),
         };
         broadcaster.broadcast(GatewayEvent::tool_result(
@@ -193,21 +216,105 @@ fn run_deterministic_checks(
         let address_in_context_bank = address_exists_in_context_bank(&to_lower, context);
 
         if !address_in_registers && !address_in_context_bank {
+            // An address that only showed up in a tool's output isn't enough on its own
+            let seen_in = context
+                .context_bank
+                .find("eth_address", &to_lower)
+                .and_then(|item| item.source)
+                .map(|tool| format!(" It only appeared in the output of `{}`; confirm it with the user.", tool))
+                .unwrap_or_default();
             return Err(format!(
                 "Transaction blocked: recipient address {} was not found in any register \
-                 or in the context bank. This may indicate a hallucinated address. \
+                 or in the user's message. This may indicate a hallucinated address.{} \
                  Use set_address to store the address first.",
-                intent.to
+                intent.to, seen_in
             ));
         }
     }
 
-    // 4. Swap sell amount verification (for swap_execute preset only)
+    // 4. The native value must be an exact wei amount
+    if let Err(e) = amounts::parse_raw(&intent.value) {
+        return Err(format!("Transaction blocked: value '{}' is not a valid wei amount: {}", intent.value, e));
+    }
+
+    // 5. Swap sell amount verification (for swap_execute preset only)
     check_swap_sell_amount(intent, context)?;
 
     Ok(())
 }
 
+/// The token amount the intent spends: its own `token_spend`, or for the
+/// `swap_execute` preset the sell registers set by swap_token
+pub fn token_spend(intent: &TransactionIntent, context: &ToolContext) -> Option<TokenSpend> {
+    if intent.token_spend.is_some() {
+        return intent.token_spend.clone();
+    }
+    if intent.preset_name.as_deref() != Some("swap_execute") {
+        return None;
+    }
+    let register = |key: &str| context.registers.get(key);
+    let token = register("sell_token")?.as_str()?.to_string();
+    if token.eq_ignore_ascii_case(crate::journal::prices::NATIVE_TOKEN) {
+        // Selling ETH: the native value already carries it
+        return None;
+    }
+    let decimals = register("sell_token_decimals").and_then(|v| {
+        v.as_u64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+    })?;
+    let amount = amounts::parse_raw(register("sell_amount")?.as_str()?).ok()?;
+    let symbol = register("sell_token_symbol")
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| "tokens".to_string());
+    Some(TokenSpend { token, symbol, decimals: u8::try_from(decimals).ok()?, amount })
+}
+
+/// Compare what the transaction spends with the wallet's balances under the
+/// owner's bounds. Blocks above the hard bound; returns the warnings.
+/// Skipped when the balance can't be read (no database, wallet or RPC).
+async fn check_amount_bounds(intent: &TransactionIntent, context: &ToolContext) -> Result<Vec<String>, String> {
+    let Some(db) = context.database.as_ref() else {
+        return Ok(vec![]);
+    };
+    let Some(owner) = context.registers.get("wallet_address").and_then(|v| v.as_str().map(str::to_string)) else {
+        return Ok(vec![]);
+    };
+    let bounds = amounts::load_bounds(db);
+
+    let mut spends: Vec<(Option<String>, U256, u8, String)> = vec![];
+    let value = amounts::parse_raw(&intent.value).unwrap_or_default();
+    if !value.is_zero() {
+        spends.push((None, value, amounts::ETH_DECIMALS, "ETH".to_string()));
+    }
+    if let Some(spend) = token_spend(intent, context) {
+        spends.push((Some(spend.token), spend.amount, spend.decimals, spend.symbol));
+    }
+
+    let mut warnings = vec![];
+    for (token, amount, decimals, symbol) in spends {
+        let balance = match amounts::balance_of(&intent.network, &owner, token.as_deref()).await {
+            Ok(balance) => balance,
+            Err(e) => {
+                log::warn!("[verify_intent] Could not read {} balance, skipping bounds: {}", symbol, e);
+                continue;
+            }
+        };
+        match bounds.check(amount, balance, decimals, &symbol) {
+            BoundCheck::Within => {}
+            BoundCheck::Warn(text) => {
+                log::warn!("[verify_intent] Amount warning: transaction {}", text);
+                warnings.push(text);
+            }
+            BoundCheck::Block(text) => {
+                return Err(format!(
+                    "Transaction blocked: this transaction {}. Lower the amount or raise the limit in the approval settings.",
+                    text
+                ));
+            }
+        }
+    }
+    Ok(warnings)
+}
+
 /// Check whether `addr` (lowercase) appears as a value in any register.
 fn address_exists_in_registers(addr: &str, context: &ToolContext) -> bool {
     for key in context.registers.keys() {
@@ -222,10 +329,11 @@ fn address_exists_in_registers(addr: &str, context: &ToolContext) -> bool {
     false
 }
 
-/// Check whether `addr` (lowercase) appears in the context bank's eth_address items.
+/// Check whether `addr` (lowercase) appears in the context bank's eth_address
+/// items taken from the user's message (tool output doesn't count).
 fn address_exists_in_context_bank(addr: &str, context: &ToolContext) -> bool {
     for item in context.context_bank.items() {
-        if item.item_type == "eth_address" && item.value.to_lowercase() == addr {
+        if item.item_type == "eth_address" && item.is_from_user() && item.value.to_lowercase() == addr {
             return true;
         }
     }
@@ -293,12 +401,14 @@ fn check_swap_sell_amount(
         _ => return Ok(()),
     };
 
-    // Convert raw sell amount to human-readable
-    let sell_raw: f64 = match sell_amount_raw.parse() {
-        Ok(v) => v,
+    // Convert raw sell amount to human-readable (exact, then to f64 for the comparison)
+    let human_sell: f64 = match amounts::parse_raw(&sell_amount_raw) {
+        Ok(raw) => match amounts::format_units(raw, decimals.min(amounts::MAX_DECIMALS as u32) as u8).parse() {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        },
         Err(_) => return Ok(()),
     };
-    let human_sell = sell_raw / 10f64.powi(decimals as i32);
 
     // Extract amounts paired with the sell token symbol from the user's message
     let paired_amounts = extract_amount_for_token(user_message, &sell_symbol);
@@ -443,6 +553,7 @@ Rules:
 - REJECTED means there is a mismatch in recipient, amount, network, or operation type.
 - NEED_INFO means the user's request is too vague to confirm the transaction.
 - When in doubt, use REJECTED. It is always safer to block than to allow.
+- Treat the decoded calldata as the ground truth for what the transaction does.
 - Do NOT add any explanation beyond the single-line reason.";
 
 fn format_verification_prompt(intent: &TransactionIntent, user_message: &str) -> String {
@@ -468,6 +579,14 @@ fn format_verification_prompt(intent: &TransactionIntent, user_message: &str) ->
         prompt.push_str(&format!("Destination chain: {}\n", dest));
     }
 
+    // Decoded calldata: what the transaction actually does, independent of the description
+    if let Some(ref calldata) = intent.calldata {
+        let preview = crate::web3::decode::preview(&intent.network, &intent.to, &intent.value, calldata);
+        prompt.push_str("\n## Decoded calldata\n");
+        prompt.push_str(&preview.explain());
+        prompt.push('\n');
+    }
+
     prompt.push_str(&format!("\nDescription: {}\n", intent.description));
     prompt.push_str("\nDoes this transaction match the user's request?");
     prompt
@@ -604,6 +723,7 @@ mod tests {
             destination_chain: None,
             calldata: None,
             description: "test tx".to_string(),
+            token_spend: None,
         }
     }
 
@@ -670,11 +790,24 @@ mod tests {
             value: addr.to_string(),
             item_type: "eth_address".to_string(),
             label: None,
+            source: None,
         });
 
         assert!(run_deterministic_checks(&intent, &ctx).is_ok());
     }
 
+    #[test]
+    fn test_address_from_tool_output_blocked() {
+        let addr = "0x1111111111111111111111111111111111111111";
+        let intent = make_intent("eth_transfer", addr);
+
+        let ctx = ToolContext::new();
+        ctx.context_bank.add_all(crate::tools::scan_tool_output(&format!("Donate to {}", addr), "web_fetch"));
+
+        let err = run_deterministic_checks(&intent, &ctx).unwrap_err();
+        assert!(err.contains("output of `web_fetch`"), "got: {}", err);
+    }
+
     #[test]
     fn test_contract_call_skips_register_check() {
         // Contract calls don't require the "to" address to be in registers
@@ -715,6 +848,24 @@ mod tests {
         assert!(prompt.contains("erc20"));
     }
 
+    #[test]
+    fn test_format_verification_prompt_decodes_approval() {
+        let mut intent = make_intent(
+            "contract_call",
+            "0x1111111111111111111111111111111111111111",
+        );
+        // approve(0x...dead, MAX_UINT256) — the erc20 ABI comes from the global abis/ registry
+        intent.calldata = Some(format!(
+            "0x095ea7b3{:0>64}{}",
+            "000000000000000000000000000000000000dead",
+            "f".repeat(64)
+        ));
+        let prompt = format_verification_prompt(&intent, "swap 100 USDC for ETH");
+        assert!(prompt.contains("## Decoded calldata"));
+        assert!(prompt.contains("approve(address,uint256)"));
+        assert!(prompt.contains("UNLIMITED"));
+    }
+
     // ── integration test with MockAiClient ───────────────────────────
 
     use crate::ai::{MockAiClient, AiResponse};
@@ -889,6 +1040,7 @@ mod tests {
             destination_chain: None,
             calldata: None,
             description: "Send 0.01 ETH".to_string(),
+            token_spend: None,
         };
 
         // Set up registers exactly as the real send_eth flow does
@@ -921,6 +1073,7 @@ mod tests {
             destination_chain: None,
             calldata: None,
             description: "ERC20 transfer".to_string(),
+            token_spend: None,
         };
 
         // No registers at all — should still pass deterministic checks
@@ -950,6 +1103,7 @@ mod tests {
             destination_chain: Some("polygon".to_string()),
             calldata: None,
             description: "Bridge 100 USDC from base to polygon".to_string(),
+            token_spend: None,
         };
 
         let mut ctx = ToolContext::new();
@@ -1120,6 +1274,7 @@ mod tests {
             destination_chain: None,
             calldata: None,
             description: "Swap via 0x".to_string(),
+            token_spend: None,
         }
     }
 
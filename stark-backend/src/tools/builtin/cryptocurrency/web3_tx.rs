@@ -12,13 +12,16 @@
 //! All RPC calls go through defirelay.com with x402 payments.
 
 use super::verify_intent::{self, TransactionIntent};
+use crate::db::tables::paper_trades::{RecordPaperTradeRequest, PAPER_KIND_TRANSFER};
+use crate::db::tables::trades::{RecordTradeRequest, TRADE_KIND_TRANSFER};
 use crate::tools::registry::Tool;
 use crate::tools::rpc_config::{resolve_rpc_from_context, Network, ResolvedRpcConfig};
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
 };
-use crate::tx_queue::QueuedTransaction;
+use crate::tx_queue::{funds, QueuedTransaction};
 use crate::wallet::WalletProvider;
+use crate::web3::amounts;
 use crate::x402::X402EvmRpc;
 use async_trait::async_trait;
 use ethers::prelude::*;
@@ -121,8 +124,8 @@ impl SendEthTool {
         // Parse value
         let tx_value: U256 = parse_u256(value)?;
 
-        // Get nonce
-        let nonce = rpc.get_transaction_count(from_address).await?;
+        // Reserve nonce
+        let nonce = crate::tx_queue::nonce::reserve(&rpc, network, from_address).await?;
 
         // Simple ETH transfer is always 21000 gas
         let gas = U256::from(21000u64);
@@ -174,25 +177,17 @@ impl SendEthTool {
 
     /// Format wei as human-readable ETH
     pub fn format_eth(wei: &str) -> String {
-        if let Ok(w) = wei.parse::<u128>() {
-            let eth = w as f64 / 1e18;
-            if eth >= 0.0001 {
-                format!("{:.6} ETH", eth)
-            } else {
-                format!("{} wei", wei)
-            }
-        } else {
-            format!("{} wei", wei)
+        match amounts::parse_raw(wei) {
+            Ok(w) => amounts::format_eth(w),
+            Err(_) => format!("{} wei", wei),
         }
     }
 
     /// Format wei as gwei for gas prices
     pub fn format_gwei(wei: &str) -> String {
-        if let Ok(w) = wei.parse::<u128>() {
-            let gwei = w as f64 / 1e9;
-            format!("{:.4} gwei", gwei)
-        } else {
-            format!("{} wei", wei)
+        match amounts::parse_raw(wei) {
+            Ok(w) => amounts::format_gwei(w),
+            Err(_) => format!("{} wei", wei),
         }
     }
 
@@ -275,13 +270,10 @@ impl ResolvedTxData {
             .ok_or_else(|| "Register 'amount_raw' must be a string (wei value)".to_string())?
             .to_string();
 
-        // Validate it's a numeric string
-        if !value.chars().all(|c| c.is_ascii_digit()) {
-            return Err(format!(
-                "Register 'amount_raw' must be a numeric string (wei), got '{}'. Use to_raw_amount to convert human amounts.",
-                value
-            ));
-        }
+        // Must be a whole number of wei: "0.1" or "1e17" here would send the wrong amount
+        let value = amounts::parse_raw(&value)
+            .map_err(|e| format!("Register 'amount_raw' must be a numeric string (wei): {}", e))?
+            .to_string();
 
         log::info!(
             "[send_eth] Resolved from registers: send_to={}, amount_raw={}",
@@ -359,6 +351,32 @@ impl Tool for SendEthTool {
             tx_data.to, tx_data.value
         );
 
+        // Paper mode: record a simulated transfer instead of signing
+        if crate::paper::is_enabled(context) {
+            return match crate::paper::record_fill(context, RecordPaperTradeRequest {
+                kind: PAPER_KIND_TRANSFER,
+                network: network.as_ref().to_string(),
+                sell_token: crate::journal::prices::NATIVE_TOKEN.to_string(),
+                sell_symbol: network.native_currency().to_string(),
+                sell_amount_raw: tx_data.value.clone(),
+                sell_decimals: 18,
+                buy_token: None,
+                buy_symbol: None,
+                buy_amount_raw: None,
+                buy_decimals: None,
+                counterparty: Some(tx_data.to.clone()),
+                sell_price_usd: None,
+                buy_price_usd: None,
+                details: None,
+                channel_id: context.channel_id,
+            })
+            .await
+            {
+                Ok(fill) => crate::paper::fill_result(&fill),
+                Err(e) => ToolResult::error(e),
+            };
+        }
+
         // Check if we're in a gateway channel without rogue mode
         let is_gateway_channel = context.channel_type
             .as_ref()
@@ -421,6 +439,7 @@ impl Tool for SendEthTool {
                         signed.to,
                         signed.network,
                     ),
+                    token_spend: None,
                 };
                 if let Err(reason) = verify_intent::verify_intent(&intent, context, None).await {
                     return ToolResult::error(reason);
@@ -445,11 +464,35 @@ impl Tool for SendEthTool {
                     context.channel_id,
                 );
 
+                // Refuse what the wallet can't pay for (amount and gas) rather than fail on-chain
+                if let Err(reason) = funds::ensure_affordable(tx_queue, &queued_tx, None, &[]).await {
+                    crate::tx_queue::nonce::release(&queued_tx.network, &queued_tx.from, queued_tx.nonce);
+                    return ToolResult::error(reason);
+                }
+
                 // Queue the transaction
                 tx_queue.queue(queued_tx);
 
                 log::info!("[send_eth] Transaction queued with UUID: {}", uuid);
 
+                if let Some(db) = &context.database {
+                    crate::journal::record(db, RecordTradeRequest {
+                        tx_uuid: uuid.clone(),
+                        kind: TRADE_KIND_TRANSFER,
+                        network: signed.network.clone(),
+                        sell_token: crate::journal::prices::NATIVE_TOKEN.to_string(),
+                        sell_symbol: network.native_currency().to_string(),
+                        sell_amount_raw: signed.value.clone(),
+                        sell_decimals: 18,
+                        buy_token: None,
+                        buy_symbol: None,
+                        buy_amount_raw: None,
+                        buy_decimals: None,
+                        counterparty: Some(signed.to.clone()),
+                        channel_id: context.channel_id,
+                    });
+                }
+
                 // Build response message
                 let mut msg = String::new();
                 msg.push_str("ETH TRANSFER QUEUED (not yet broadcast)\n\n");
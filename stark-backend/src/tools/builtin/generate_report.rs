@@ -0,0 +1,508 @@
+//! Report generation tool
+//!
+//! Renders structured sections (portfolio summaries, activity logs, cron
+//! digests) into a PDF or XLSX file. PDF layout goes through a minijinja
+//! template (a built-in one unless the caller supplies their own), XLSX puts
+//! each tabular section on its own worksheet. The file is stored as an
+//! artifact of the current execution and a download link is returned.
+
+use crate::execution::scratch;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Built-in layout: headings prefixed with "# " are rendered bold by the PDF writer
+const DEFAULT_TEMPLATE: &str = "\
+{% for section in sections %}# {{ section.heading }}
+{% if section.text %}{{ section.text }}
+{% endif %}{% if section.table %}{{ section.table }}
+{% endif %}
+{% endfor %}";
+
+/// A4 portrait, in millimetres
+const PAGE_WIDTH_MM: f32 = 210.0;
+const PAGE_HEIGHT_MM: f32 = 297.0;
+const MARGIN_MM: f32 = 15.0;
+const BODY_FONT_PT: f32 = 9.0;
+const LINE_HEIGHT_MM: f32 = 4.5;
+/// Courier at 9pt fits ~94 characters between the margins
+const MAX_LINE_CHARS: usize = 94;
+const MAX_SECTIONS: usize = 50;
+const MAX_ROWS_PER_SECTION: usize = 10_000;
+
+/// Generate report tool - renders structured data to a PDF/XLSX artifact
+pub struct GenerateReportTool {
+    definition: ToolDefinition,
+}
+
+impl GenerateReportTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+        properties.insert(
+            "title".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Report title (also used for the file name unless filename is set)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "format".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Output format: 'pdf' for a printable document, 'xlsx' for a spreadsheet".to_string(),
+                default: Some(json!("pdf")),
+                items: None,
+                enum_values: Some(vec!["pdf".to_string(), "xlsx".to_string()]),
+            },
+        );
+        properties.insert(
+            "sections".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Report sections in order. Each is an object with 'heading' (string), optional 'text' (paragraph), and optional 'columns' (array of strings) + 'rows' (array of arrays of values) for a table.".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "object".to_string(),
+                    description: "{ heading, text?, columns?, rows? }".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "template".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Optional minijinja template for the PDF body. Receives 'title', 'generated_at' and 'sections' (each with heading, text, columns, rows and a pre-aligned 'table' string). Lines starting with '# ' are rendered as headings.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "filename".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Optional file name without extension".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        GenerateReportTool {
+            definition: ToolDefinition {
+                name: "generate_report".to_string(),
+                description: "Render structured data (portfolio summaries, activity logs, digests) into a PDF or XLSX report. The file is saved as an artifact of this execution and a download link is returned — use it when the user wants a document or spreadsheet, e.g. a weekly treasury report to email.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["title".to_string(), "sections".to_string()],
+                },
+                group: ToolGroup::Filesystem,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for GenerateReportTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateReportParams {
+    title: String,
+    format: Option<String>,
+    sections: Vec<ReportSection>,
+    template: Option<String>,
+    filename: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct ReportSection {
+    heading: String,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    columns: Vec<String>,
+    #[serde(default)]
+    rows: Vec<Vec<Value>>,
+}
+
+/// Plain-text rendering of a cell (strings unquoted, null as empty)
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Align a section's table into fixed-width columns for the PDF body
+fn format_table(section: &ReportSection) -> String {
+    if section.columns.is_empty() && section.rows.is_empty() {
+        return String::new();
+    }
+    let width = section
+        .rows
+        .iter()
+        .map(|r| r.len())
+        .chain(std::iter::once(section.columns.len()))
+        .max()
+        .unwrap_or(0);
+    let cells: Vec<Vec<String>> = section
+        .rows
+        .iter()
+        .map(|r| (0..width).map(|i| r.get(i).map(cell_text).unwrap_or_default()).collect())
+        .collect();
+    let mut widths: Vec<usize> = (0..width)
+        .map(|i| section.columns.get(i).map(|c| c.chars().count()).unwrap_or(0))
+        .collect();
+    for row in &cells {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let join = |row: &[String]| {
+        row.iter()
+            .enumerate()
+            .map(|(i, cell)| format!("{:<w$}", cell, w = widths[i]))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = Vec::new();
+    if !section.columns.is_empty() {
+        let header: Vec<String> = (0..width)
+            .map(|i| section.columns.get(i).cloned().unwrap_or_default())
+            .collect();
+        lines.push(join(&header));
+        lines.push(widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("  "));
+    }
+    for row in &cells {
+        lines.push(join(row));
+    }
+    lines.join("\n")
+}
+
+/// Render the report body through the template
+fn render_text(
+    title: &str,
+    generated_at: &str,
+    sections: &[ReportSection],
+    template: Option<&str>,
+) -> Result<String, String> {
+    let sections: Vec<Value> = sections
+        .iter()
+        .map(|s| {
+            let mut v = serde_json::to_value(s).unwrap_or(Value::Null);
+            v["table"] = json!(format_table(s));
+            v
+        })
+        .collect();
+    let env = minijinja::Environment::new();
+    env.render_str(
+        template.unwrap_or(DEFAULT_TEMPLATE),
+        minijinja::context! { title => title, generated_at => generated_at, sections => sections },
+    )
+    .map_err(|e| format!("Template error: {}", e))
+}
+
+/// Hard-wrap a line to the page width
+fn wrap_line(line: &str) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() <= MAX_LINE_CHARS {
+        return vec![line.to_string()];
+    }
+    chars.chunks(MAX_LINE_CHARS).map(|c| c.iter().collect()).collect()
+}
+
+fn render_pdf(title: &str, generated_at: &str, body: &str) -> Result<Vec<u8>, String> {
+    use printpdf::{BuiltinFont, Mm, PdfDocument};
+
+    let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Layer 1");
+    let heading_font = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(|e| e.to_string())?;
+    let body_font = doc.add_builtin_font(BuiltinFont::Courier).map_err(|e| e.to_string())?;
+    let bold_font = doc.add_builtin_font(BuiltinFont::CourierBold).map_err(|e| e.to_string())?;
+
+    let mut layer = doc.get_page(page).get_layer(layer);
+    let mut y = PAGE_HEIGHT_MM - MARGIN_MM;
+
+    layer.use_text(title, 16.0, Mm(MARGIN_MM), Mm(y), &heading_font);
+    y -= 7.0;
+    layer.use_text(format!("Generated {}", generated_at), 8.0, Mm(MARGIN_MM), Mm(y), &body_font);
+    y -= LINE_HEIGHT_MM * 2.0;
+
+    for raw in body.lines() {
+        let (text, font) = match raw.strip_prefix("# ") {
+            Some(heading) => (heading, &bold_font),
+            None => (raw, &body_font),
+        };
+        for line in wrap_line(text) {
+            if y < MARGIN_MM {
+                let (next_page, next_layer) =
+                    doc.add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Layer 1");
+                layer = doc.get_page(next_page).get_layer(next_layer);
+                y = PAGE_HEIGHT_MM - MARGIN_MM;
+            }
+            layer.use_text(line, BODY_FONT_PT, Mm(MARGIN_MM), Mm(y), font);
+            y -= LINE_HEIGHT_MM;
+        }
+    }
+
+    doc.save_to_bytes().map_err(|e| e.to_string())
+}
+
+/// Excel limits sheet names to 31 chars and forbids a few characters
+fn sheet_name(heading: &str, index: usize, used: &mut Vec<String>) -> String {
+    let mut name: String = heading
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(28)
+        .collect();
+    if name.trim().is_empty() {
+        name = format!("Sheet{}", index + 1);
+    }
+    if used.iter().any(|u| u.eq_ignore_ascii_case(&name)) {
+        name = format!("{}-{}", name.chars().take(25).collect::<String>(), index + 1);
+    }
+    used.push(name.clone());
+    name
+}
+
+fn render_xlsx(title: &str, generated_at: &str, sections: &[ReportSection]) -> Result<Vec<u8>, String> {
+    use rust_xlsxwriter::{Format, Workbook};
+
+    let err = |e: rust_xlsxwriter::XlsxError| e.to_string();
+    let bold = Format::new().set_bold();
+    let mut workbook = Workbook::new();
+    let mut used_names = vec!["Summary".to_string()];
+
+    // Summary sheet: title plus every text section
+    let summary = workbook.add_worksheet();
+    summary.set_name("Summary").map_err(err)?;
+    summary.write_string_with_format(0, 0, title, &bold).map_err(err)?;
+    summary.write_string(1, 0, format!("Generated {}", generated_at)).map_err(err)?;
+    let mut row = 3;
+    for section in sections.iter().filter(|s| s.text.is_some()) {
+        summary.write_string_with_format(row, 0, &section.heading, &bold).map_err(err)?;
+        summary.write_string(row + 1, 0, section.text.as_deref().unwrap_or_default()).map_err(err)?;
+        row += 3;
+    }
+
+    // One worksheet per table
+    for (i, section) in sections.iter().enumerate() {
+        if section.columns.is_empty() && section.rows.is_empty() {
+            continue;
+        }
+        let name = sheet_name(&section.heading, i, &mut used_names);
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(&name).map_err(err)?;
+        for (c, column) in section.columns.iter().enumerate() {
+            sheet.write_string_with_format(0, c as u16, column, &bold).map_err(err)?;
+        }
+        let offset = if section.columns.is_empty() { 0 } else { 1 };
+        for (r, values) in section.rows.iter().enumerate() {
+            let r = (r + offset) as u32;
+            for (c, value) in values.iter().enumerate() {
+                let c = c as u16;
+                match value {
+                    Value::Number(n) => {
+                        sheet.write_number(r, c, n.as_f64().unwrap_or_default()).map_err(err)?;
+                    }
+                    Value::Bool(b) => {
+                        sheet.write_boolean(r, c, *b).map_err(err)?;
+                    }
+                    Value::Null => {}
+                    other => {
+                        sheet.write_string(r, c, cell_text(other)).map_err(err)?;
+                    }
+                }
+            }
+        }
+    }
+
+    workbook.save_to_buffer().map_err(err)
+}
+
+/// File-system friendly version of the title
+fn slugify(title: &str) -> String {
+    let slug: String = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.split('-').filter(|p| !p.is_empty()).collect::<Vec<_>>().join("-");
+    if slug.is_empty() { "report".to_string() } else { slug.chars().take(60).collect() }
+}
+
+#[async_trait]
+impl Tool for GenerateReportTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: GenerateReportParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        if params.sections.is_empty() {
+            return ToolResult::error("A report needs at least one section");
+        }
+        if params.sections.len() > MAX_SECTIONS {
+            return ToolResult::error(format!("Too many sections (max {})", MAX_SECTIONS));
+        }
+        if params.sections.iter().any(|s| s.rows.len() > MAX_ROWS_PER_SECTION) {
+            return ToolResult::error(format!("Too many rows in a section (max {})", MAX_ROWS_PER_SECTION));
+        }
+
+        let format = params.format.as_deref().unwrap_or("pdf").to_lowercase();
+        let generated_at = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string();
+
+        let rendered = match format.as_str() {
+            "pdf" => render_text(&params.title, &generated_at, &params.sections, params.template.as_deref())
+                .and_then(|body| render_pdf(&params.title, &generated_at, &body)),
+            "xlsx" => render_xlsx(&params.title, &generated_at, &params.sections),
+            other => return ToolResult::error(format!("Unsupported format '{}' (use 'pdf' or 'xlsx')", other)),
+        };
+        let bytes = match rendered {
+            Ok(b) => b,
+            Err(e) => return ToolResult::error(format!("Failed to render report: {}", e)),
+        };
+
+        if let Err(e) = context.check_disk_quota(bytes.len()) {
+            return ToolResult::error(e);
+        }
+
+        // Reports live with the execution's other artifacts
+        let execution_id = context
+            .execution_id
+            .clone()
+            .unwrap_or_else(|| format!("report-{}", uuid::Uuid::new_v4()));
+        let file_name = format!(
+            "{}.{}",
+            slugify(params.filename.as_deref().unwrap_or(&params.title)),
+            format
+        );
+        let dir = scratch::artifacts_path(&execution_id);
+        if let Err(e) = tokio::fs::create_dir_all(&dir).await {
+            return ToolResult::error(format!("Failed to create artifacts directory: {}", e));
+        }
+        if let Err(e) = tokio::fs::write(dir.join(&file_name), &bytes).await {
+            return ToolResult::error(format!("Failed to write report: {}", e));
+        }
+        context.record_disk_write(bytes.len());
+
+        let relative = format!(
+            "{}/{}/{}",
+            scratch::ARTIFACTS_DIR_NAME,
+            dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            file_name
+        );
+
+        let artifact_id = context.database.as_ref().and_then(|db| {
+            db.register_artifact(&execution_id, context.session_id, context.channel_id, &relative, bytes.len() as u64)
+                .map_err(|e| log::warn!("[generate_report] Failed to register artifact: {}", e))
+                .ok()
+        });
+        let download_url = artifact_id.map(|id| {
+            format!(
+                "{}/api/executions/{}/artifacts/{}",
+                crate::config::self_url(),
+                urlencoding::encode(&execution_id),
+                id
+            )
+        });
+
+        let mut message = format!(
+            "Generated {} report '{}' ({} bytes) at {}",
+            format.to_uppercase(),
+            params.title,
+            bytes.len(),
+            relative
+        );
+        if let Some(ref url) = download_url {
+            message.push_str(&format!("\nDownload: {}", url));
+        }
+
+        ToolResult::success(message).with_metadata(json!({
+            "execution_id": execution_id,
+            "artifact_id": artifact_id,
+            "path": relative,
+            "format": format,
+            "bytes": bytes.len(),
+            "download_url": download_url,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_sections() -> Vec<ReportSection> {
+        vec![
+            ReportSection {
+                heading: "Overview".to_string(),
+                text: Some("Treasury grew 4% this week.".to_string()),
+                columns: vec![],
+                rows: vec![],
+            },
+            ReportSection {
+                heading: "Holdings".to_string(),
+                text: None,
+                columns: vec!["Token".to_string(), "Balance".to_string()],
+                rows: vec![vec![json!("ETH"), json!(1.5)], vec![json!("USDC"), json!(2500)]],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_default_template_renders_sections_and_tables() {
+        let body = render_text("Weekly", "2026-01-01 00:00 UTC", &sample_sections(), None).unwrap();
+        assert!(body.contains("# Overview"));
+        assert!(body.contains("Treasury grew 4% this week."));
+        assert!(body.contains("Token  Balance"));
+        assert!(body.contains("USDC   2500"));
+    }
+
+    #[test]
+    fn test_custom_template_and_errors() {
+        let body = render_text("Weekly", "now", &sample_sections(), Some("{{ title }}: {{ sections|length }}")).unwrap();
+        assert_eq!(body, "Weekly: 2");
+        assert!(render_text("Weekly", "now", &sample_sections(), Some("{% if %}")).is_err());
+    }
+
+    #[test]
+    fn test_pdf_and_xlsx_output() {
+        let pdf = render_pdf("Weekly", "now", "# Heading\nline").unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+        let xlsx = render_xlsx("Weekly", "now", &sample_sections()).unwrap();
+        // XLSX is a zip container
+        assert!(xlsx.starts_with(b"PK"));
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Weekly Treasury Report: Jan 5"), "weekly-treasury-report-jan-5");
+        assert_eq!(slugify("!!!"), "report");
+    }
+}
@@ -0,0 +1,238 @@
+//! Identity Profile Tool
+//!
+//! Read or extend the structured profile of the person the agent is talking to
+//! (name, timezone, language, preferences, wallets, ongoing projects).
+//! Fields the user corrected are locked and cannot be changed by the agent.
+
+use crate::db::tables::identity_profiles::IdentityProfile;
+use crate::memory::profile::{self, ProfileUpdate};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tools::ToolSafetyLevel;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Tool for reading and extending the current identity's profile
+pub struct IdentityProfileTool {
+    definition: ToolDefinition,
+}
+
+impl IdentityProfileTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "\"get\" to read the profile, \"update\" to record facts the user stated about themselves.".to_string(),
+                default: Some(json!("get")),
+                items: None,
+                enum_values: Some(vec!["get".to_string(), "update".to_string()]),
+            },
+        );
+
+        properties.insert(
+            "display_name".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "How the user wants to be called (update only).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "timezone".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "IANA timezone, e.g. \"Europe/Berlin\" (update only).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "language".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Preferred language (update only).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "preferences".to_string(),
+            PropertySchema {
+                schema_type: "object".to_string(),
+                description: "Stable preferences as short key/value pairs, e.g. {\"slippage\": \"0.5%\"} (update only).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "wallet_addresses".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Wallet addresses the user said are theirs (update only).".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "EVM address".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "projects".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Ongoing projects of the user, a few words each (update only).".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "Project".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        Self {
+            definition: ToolDefinition {
+                name: "identity_profile".to_string(),
+                description: "Read the structured profile of the user you are talking to (name, timezone, language, preferences, wallets, ongoing projects), or record stable facts they told you about themselves. Prefer this over memory_search for facts about the user.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Memory,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for IdentityProfileTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileParams {
+    #[serde(default = "default_action")]
+    action: String,
+    display_name: Option<String>,
+    timezone: Option<String>,
+    language: Option<String>,
+    #[serde(default)]
+    preferences: BTreeMap<String, String>,
+    #[serde(default)]
+    wallet_addresses: Vec<String>,
+    #[serde(default)]
+    projects: Vec<String>,
+}
+
+fn default_action() -> String {
+    "get".to_string()
+}
+
+#[async_trait]
+impl Tool for IdentityProfileTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: ProfileParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available."),
+        };
+        let identity_id = match context.identity_id.as_deref() {
+            Some(id) => id,
+            None => return ToolResult::error("No user identity in this context — profiles are per user."),
+        };
+
+        let existing = match db.get_identity_profile(identity_id) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Failed to load profile: {}", e)),
+        };
+
+        match params.action.as_str() {
+            "get" => match existing {
+                Some(p) => ToolResult::success(profile::format_profile(&p)),
+                None => ToolResult::success("No profile recorded for this user yet."),
+            },
+            "update" => {
+                let mut p = existing.unwrap_or_else(|| IdentityProfile {
+                    identity_id: identity_id.to_string(),
+                    ..Default::default()
+                });
+                let update = ProfileUpdate {
+                    display_name: params.display_name,
+                    timezone: params.timezone,
+                    language: params.language,
+                    preferences: params.preferences,
+                    wallet_addresses: params.wallet_addresses,
+                    projects: params.projects,
+                };
+                if update.is_empty() {
+                    return ToolResult::error("Provide at least one field to update.");
+                }
+                if !profile::apply_learned(&mut p, &update) {
+                    return ToolResult::success(format!(
+                        "Nothing changed (already known, or locked by the user).\n\n{}",
+                        profile::format_profile(&p)
+                    ));
+                }
+                match db.save_identity_profile(&p) {
+                    Ok(()) => ToolResult::success(format!("Profile updated.\n\n{}", profile::format_profile(&p))),
+                    Err(e) => ToolResult::error(format!("Failed to save profile: {}", e)),
+                }
+            }
+            other => ToolResult::error(format!("Unknown action: \"{}\". Use \"get\" or \"update\".", other)),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::Standard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_profile_definition() {
+        let tool = IdentityProfileTool::new();
+        let def = tool.definition();
+
+        assert_eq!(def.name, "identity_profile");
+        assert_eq!(def.group, ToolGroup::Memory);
+        assert!(def.input_schema.required.is_empty());
+    }
+}
@@ -0,0 +1,383 @@
+//! Knowledge Graph Tool
+//!
+//! Query the graph of entities (people, projects, addresses, tokens) the agent
+//! has accumulated from memories and conversations: look entities up, see
+//! what they are connected to, and trace how two of them relate.
+
+use crate::db::tables::knowledge_graph::{KnowledgeEntity, KnowledgeRelation};
+use crate::db::Database;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tools::ToolSafetyLevel;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Longest path searched between two entities
+const MAX_PATH_HOPS: usize = 4;
+
+/// Tool for querying the cross-session knowledge graph
+pub struct KnowledgeGraphTool {
+    definition: ToolDefinition,
+}
+
+impl KnowledgeGraphTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Action to perform: \"search\" to find entities by name, \"neighbors\" to see what an entity is connected to, \"path\" to trace how two entities relate, or \"stats\" for a graph overview.".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec![
+                    "search".to_string(),
+                    "neighbors".to_string(),
+                    "path".to_string(),
+                    "stats".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "entity".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Entity name or ID (search query for search; required for neighbors and path).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "target_entity".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Entity name or ID to trace a path to (required for path).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "entity_type".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Only entities of this type, e.g. \"person\", \"project\", \"address\", \"ens_name\", \"token\", \"network\".".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "depth".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Maximum traversal depth for neighbors. Default: 1, max: 2.".to_string(),
+                default: Some(json!(1)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        Self {
+            definition: ToolDefinition {
+                name: "knowledge_graph".to_string(),
+                description: "Look up what you know about people, projects, addresses, ENS names and tokens across all past conversations and memories. Use `action: \"search\"` to find an entity, `action: \"neighbors\"` to see what it is connected to (e.g. which address an ENS name resolves to, who works on a project), `action: \"path\"` to trace how two entities relate, or `action: \"stats\"` for an overview.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["action".to_string()],
+                },
+                group: ToolGroup::Memory,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for KnowledgeGraphTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct KnowledgeGraphParams {
+    action: String,
+    entity: Option<Value>,
+    target_entity: Option<Value>,
+    entity_type: Option<String>,
+    depth: Option<usize>,
+}
+
+/// An entity by ID, or else by (best matching) name
+fn resolve_entity(db: &Database, entity: &Value, entity_type: Option<&str>) -> Result<KnowledgeEntity, String> {
+    let text = match entity {
+        Value::String(s) => s.trim().to_string(),
+        other => other.to_string(),
+    };
+    if let Ok(id) = text.parse::<i64>() {
+        if let Ok(Some(found)) = db.get_knowledge_entity(id) {
+            return Ok(found);
+        }
+    }
+    db.search_knowledge_entities(&text, entity_type, 1)
+        .map_err(|e| format!("Failed to search entities: {}", e))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("No entity matching \"{}\" in the knowledge graph.", text))
+}
+
+fn other_end(relation: &KnowledgeRelation, entity_id: i64) -> i64 {
+    if relation.source_entity_id == entity_id {
+        relation.target_entity_id
+    } else {
+        relation.source_entity_id
+    }
+}
+
+fn label(entity: &KnowledgeEntity) -> String {
+    format!("{} ({}, #{})", entity.display_name, entity.entity_type, entity.id)
+}
+
+fn entity_label(db: &Database, id: i64, cache: &mut HashMap<i64, String>) -> String {
+    cache
+        .entry(id)
+        .or_insert_with(|| {
+            db.get_knowledge_entity(id)
+                .ok()
+                .flatten()
+                .map(|e| label(&e))
+                .unwrap_or_else(|| format!("#{}", id))
+        })
+        .clone()
+}
+
+#[async_trait]
+impl Tool for KnowledgeGraphTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: KnowledgeGraphParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let db = match &context.database {
+            Some(db) => db,
+            None => {
+                return ToolResult::error(
+                    "Database not available. Knowledge graph requires the database to be initialized.",
+                );
+            }
+        };
+        let entity_type = params.entity_type.as_deref();
+
+        match params.action.as_str() {
+            "search" => {
+                let query = params
+                    .entity
+                    .as_ref()
+                    .map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()))
+                    .unwrap_or_default();
+                let entities = match db.search_knowledge_entities(&query, entity_type, 20) {
+                    Ok(e) => e,
+                    Err(e) => return ToolResult::error(format!("Failed to search entities: {}", e)),
+                };
+                if entities.is_empty() {
+                    return ToolResult::success(format!("No entities matching \"{}\".", query));
+                }
+                let mut output = format!("## Knowledge Graph: \"{}\"\n\n", query);
+                output.push_str("| ID | Entity | Type | Mentions | Last seen |\n");
+                output.push_str("|----|--------|------|----------|-----------|\n");
+                for e in &entities {
+                    output.push_str(&format!(
+                        "| {} | {} | {} | {} | {} |\n",
+                        e.id, e.display_name, e.entity_type, e.mention_count, e.last_seen
+                    ));
+                }
+                ToolResult::success(output).with_metadata(json!({ "entities": entities }))
+            }
+
+            "neighbors" => {
+                let Some(entity) = &params.entity else {
+                    return ToolResult::error("entity is required for neighbors action.");
+                };
+                let start = match resolve_entity(db, entity, entity_type) {
+                    Ok(e) => e,
+                    Err(e) => return ToolResult::error(e),
+                };
+                let max_depth = params.depth.unwrap_or(1).clamp(1, 2);
+
+                let mut labels = HashMap::from([(start.id, label(&start))]);
+                let mut visited = HashSet::from([start.id]);
+                let mut current_level = vec![start.id];
+                let mut output = format!("## Knowledge Graph: {}\n**Depth:** {}\n\n", label(&start), max_depth);
+                let mut total_found = 0;
+
+                for depth in 1..=max_depth {
+                    let mut next_level = Vec::new();
+                    for &current_id in &current_level {
+                        let relations = match db.list_knowledge_relations_for(current_id) {
+                            Ok(r) => r,
+                            Err(e) => return ToolResult::error(format!("Failed to get relations: {}", e)),
+                        };
+                        for relation in &relations {
+                            let connected_id = other_end(relation, current_id);
+                            if !visited.insert(connected_id) {
+                                continue;
+                            }
+                            next_level.push(connected_id);
+                            let direction = if relation.source_entity_id == current_id { "->" } else { "<-" };
+                            output.push_str(&format!(
+                                "{}{} {} [{}] {} (strength: {:.2}, seen {}x)\n",
+                                "  ".repeat(depth),
+                                direction,
+                                entity_label(db, current_id, &mut labels),
+                                relation.relation_type,
+                                entity_label(db, connected_id, &mut labels),
+                                relation.strength,
+                                relation.mention_count
+                            ));
+                            total_found += 1;
+                        }
+                    }
+                    if next_level.is_empty() {
+                        break;
+                    }
+                    current_level = next_level;
+                }
+
+                if total_found == 0 {
+                    output.push_str("No connected entities found.");
+                } else {
+                    output.push_str(&format!("\n**Total connected:** {}", total_found));
+                }
+                ToolResult::success(output).with_metadata(json!({
+                    "entity_id": start.id,
+                    "depth": max_depth,
+                    "total_found": total_found
+                }))
+            }
+
+            "path" => {
+                let (Some(entity), Some(target)) = (&params.entity, &params.target_entity) else {
+                    return ToolResult::error("entity and target_entity are required for path action.");
+                };
+                let (start, target) = match (resolve_entity(db, entity, None), resolve_entity(db, target, None)) {
+                    (Ok(s), Ok(t)) => (s, t),
+                    (Err(e), _) | (_, Err(e)) => return ToolResult::error(e),
+                };
+                let heading = format!("## Path: {} -> {}", label(&start), label(&target));
+                if start.id == target.id {
+                    return ToolResult::success(format!("{}\n\nSource and target are the same entity.", heading));
+                }
+
+                // BFS; each step is (from, relation, to)
+                let mut visited = HashSet::from([start.id]);
+                let mut queue: VecDeque<(i64, Vec<(i64, String, i64)>)> = VecDeque::from([(start.id, vec![])]);
+                let mut found_path = None;
+                'search: while let Some((current_id, path)) = queue.pop_front() {
+                    if path.len() >= MAX_PATH_HOPS {
+                        continue;
+                    }
+                    for relation in db.list_knowledge_relations_for(current_id).unwrap_or_default() {
+                        let connected_id = other_end(&relation, current_id);
+                        if !visited.insert(connected_id) {
+                            continue;
+                        }
+                        let mut new_path = path.clone();
+                        new_path.push((current_id, relation.relation_type.clone(), connected_id));
+                        if connected_id == target.id {
+                            found_path = Some(new_path);
+                            break 'search;
+                        }
+                        queue.push_back((connected_id, new_path));
+                    }
+                }
+
+                let Some(path) = found_path else {
+                    return ToolResult::success(format!(
+                        "{}\n\nNo path found within {} hops.",
+                        heading, MAX_PATH_HOPS
+                    ))
+                    .with_metadata(json!({ "start": start.id, "target": target.id, "found": false }));
+                };
+                let mut labels = HashMap::new();
+                let mut output = format!("{}\n**Hops:** {}\n\n", heading, path.len());
+                for (i, (from, relation_type, to)) in path.iter().enumerate() {
+                    output.push_str(&format!(
+                        "{}. {} --[{}]--> {}\n",
+                        i + 1,
+                        entity_label(db, *from, &mut labels),
+                        relation_type,
+                        entity_label(db, *to, &mut labels)
+                    ));
+                }
+                ToolResult::success(output).with_metadata(json!({
+                    "start": start.id,
+                    "target": target.id,
+                    "hops": path.len(),
+                    "path": path.iter().map(|(from, t, to)| json!({
+                        "from": from, "type": t, "to": to
+                    })).collect::<Vec<_>>()
+                }))
+            }
+
+            "stats" => match db.get_knowledge_graph_stats() {
+                Ok(stats) => {
+                    let mut output = "## Knowledge Graph Statistics\n\n".to_string();
+                    output.push_str("| Metric | Value |\n|--------|-------|\n");
+                    output.push_str(&format!("| Entities | {} |\n", stats.entities));
+                    output.push_str(&format!("| Relations | {} |\n", stats.relations));
+                    output.push_str(&format!("| Mentions | {} |\n", stats.mentions));
+                    for (heading, counts) in [("Entity Types", &stats.entity_types), ("Relation Types", &stats.relation_types)] {
+                        if !counts.is_empty() {
+                            output.push_str(&format!("\n### {}\n\n| Type | Count |\n|------|-------|\n", heading));
+                            for (t, count) in counts {
+                                output.push_str(&format!("| {} | {} |\n", t, count));
+                            }
+                        }
+                    }
+                    ToolResult::success(output).with_metadata(json!(stats))
+                }
+                Err(e) => ToolResult::error(format!("Failed to get graph stats: {}", e)),
+            },
+
+            _ => ToolResult::error(format!(
+                "Unknown action: \"{}\". Use \"search\", \"neighbors\", \"path\", or \"stats\".",
+                params.action
+            )),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::ReadOnly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_knowledge_graph_definition() {
+        let tool = KnowledgeGraphTool::new();
+        let def = tool.definition();
+
+        assert_eq!(def.name, "knowledge_graph");
+        assert_eq!(def.group, ToolGroup::Memory);
+        assert!(def.input_schema.required.contains(&"action".to_string()));
+    }
+}
@@ -0,0 +1,257 @@
+//! KV Store Tool — durable agent variables
+//!
+//! Single tool with `action` parameter: get, set, delete, list.
+//! Values persist across executions (unlike registers) in an identity-scoped
+//! or global namespace, optionally with a TTL. Storage lives in `db::tables::kv_store`.
+
+use crate::db::tables::kv_store::{identity_namespace, GLOBAL_NAMESPACE};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+    ToolSafetyLevel,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const MAX_KEY_CHARS: usize = 128;
+const MAX_VALUE_BYTES: usize = 16 * 1024;
+const MAX_KEYS_PER_NAMESPACE: usize = 1000;
+const DEFAULT_LIST_LIMIT: usize = 100;
+
+pub struct KvStoreTool {
+    definition: ToolDefinition,
+}
+
+impl KvStoreTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Action to perform on the key-value store.".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec![
+                    "get".to_string(),
+                    "set".to_string(),
+                    "delete".to_string(),
+                    "list".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "key".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Variable name (required for get/set/delete), e.g. 'last_processed_block'. For list, an optional key prefix.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "value".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Value to store (for set). Any JSON value is accepted; numbers and objects keep their type.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "scope".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'identity' (default) keeps the variable private to the current user; 'global' shares it across all users.".to_string(),
+                default: Some(json!("identity")),
+                items: None,
+                enum_values: Some(vec!["identity".to_string(), "global".to_string()]),
+            },
+        );
+
+        properties.insert(
+            "ttl_secs".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Optional time-to-live in seconds (for set). Omit to keep the value until deleted.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        KvStoreTool {
+            definition: ToolDefinition {
+                name: "kv_store".to_string(),
+                description: "Durable key-value variables that persist across executions — use for state like 'last processed block' or 'preferred slippage' instead of memories. Actions: get, set, delete, list.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["action".to_string()],
+                },
+                group: ToolGroup::Memory,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for KvStoreTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct KvStoreParams {
+    action: String,
+    key: Option<String>,
+    value: Option<Value>,
+    scope: Option<String>,
+    ttl_secs: Option<i64>,
+}
+
+/// Validate the `key` parameter for actions that need one
+fn require_key<'a>(key: Option<&'a str>, action: &str) -> Result<&'a str, ToolResult> {
+    match key.map(str::trim) {
+        Some(k) if !k.is_empty() && k.chars().count() <= MAX_KEY_CHARS => Ok(k),
+        Some(k) if !k.is_empty() => Err(ToolResult::error(format!(
+            "Key too long (max {} characters)",
+            MAX_KEY_CHARS
+        ))),
+        _ => Err(ToolResult::error(format!("'key' is required for {}", action))),
+    }
+}
+
+#[async_trait]
+impl Tool for KvStoreTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: KvStoreParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let db = match context.database.as_ref() {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+
+        let namespace = match params.scope.as_deref().unwrap_or("identity") {
+            "global" => GLOBAL_NAMESPACE.to_string(),
+            "identity" => match context.identity_id.as_deref() {
+                Some(id) => identity_namespace(id),
+                None => return ToolResult::error("No identity in this context — use scope 'global'"),
+            },
+            other => return ToolResult::error(format!("Unknown scope '{}' (use 'identity' or 'global')", other)),
+        };
+
+        match params.action.as_str() {
+            "get" => {
+                let key = match require_key(params.key.as_deref(), &params.action) {
+                    Ok(k) => k,
+                    Err(e) => return e,
+                };
+                match db.kv_get(&namespace, key) {
+                    Ok(Some(entry)) => ToolResult::success(entry.value.to_string())
+                        .with_metadata(json!({ "key": entry.key, "scope": namespace, "expires_at": entry.expires_at, "updated_at": entry.updated_at })),
+                    Ok(None) => ToolResult::success(format!("No value stored for '{}'", key))
+                        .with_metadata(json!({ "key": key, "scope": namespace, "found": false })),
+                    Err(e) => ToolResult::error(format!("Database error: {}", e)),
+                }
+            }
+            "set" => {
+                let key = match require_key(params.key.as_deref(), &params.action) {
+                    Ok(k) => k,
+                    Err(e) => return e,
+                };
+                let value = match params.value {
+                    Some(ref v) => v,
+                    None => return ToolResult::error("'value' is required for set"),
+                };
+                if value.to_string().len() > MAX_VALUE_BYTES {
+                    return ToolResult::error(format!("Value too large (max {} bytes)", MAX_VALUE_BYTES));
+                }
+                if let Some(ttl) = params.ttl_secs {
+                    if ttl <= 0 {
+                        return ToolResult::error("ttl_secs must be positive");
+                    }
+                }
+                // Cap namespace size (overwrites of existing keys are always allowed)
+                let exists = matches!(db.kv_get(&namespace, key), Ok(Some(_)));
+                if !exists {
+                    let _ = db.kv_purge_expired();
+                    if db.kv_count(&namespace).unwrap_or(0) >= MAX_KEYS_PER_NAMESPACE {
+                        return ToolResult::error(format!(
+                            "Namespace is full ({} keys) — delete unused variables first",
+                            MAX_KEYS_PER_NAMESPACE
+                        ));
+                    }
+                }
+                match db.kv_set(&namespace, key, value, params.ttl_secs) {
+                    Ok(entry) => {
+                        let expiry = entry
+                            .expires_at
+                            .as_ref()
+                            .map(|e| format!(" (expires {})", e))
+                            .unwrap_or_default();
+                        ToolResult::success(format!("Stored '{}'{}", key, expiry))
+                            .with_metadata(json!({ "key": key, "scope": namespace, "expires_at": entry.expires_at }))
+                    }
+                    Err(e) => ToolResult::error(format!("Database error: {}", e)),
+                }
+            }
+            "delete" => {
+                let key = match require_key(params.key.as_deref(), &params.action) {
+                    Ok(k) => k,
+                    Err(e) => return e,
+                };
+                match db.kv_delete(&namespace, key) {
+                    Ok(true) => ToolResult::success(format!("Deleted '{}'", key)),
+                    Ok(false) => ToolResult::success(format!("No value stored for '{}'", key)),
+                    Err(e) => ToolResult::error(format!("Database error: {}", e)),
+                }
+            }
+            "list" => {
+                let prefix = params.key.as_deref().map(str::trim).filter(|p| !p.is_empty());
+                match db.kv_list(&namespace, prefix, DEFAULT_LIST_LIMIT) {
+                    Ok(entries) if entries.is_empty() => ToolResult::success("No variables stored"),
+                    Ok(entries) => {
+                        let lines: Vec<String> = entries
+                            .iter()
+                            .map(|e| {
+                                let mut value = e.value.to_string();
+                                if value.chars().count() > 120 {
+                                    value = value.chars().take(120).collect::<String>() + "…";
+                                }
+                                format!("{} = {}", e.key, value)
+                            })
+                            .collect();
+                        ToolResult::success(lines.join("\n"))
+                            .with_metadata(json!({ "scope": namespace, "count": entries.len() }))
+                    }
+                    Err(e) => ToolResult::error(format!("Database error: {}", e)),
+                }
+            }
+            other => ToolResult::error(format!(
+                "Unknown action '{}' (use get, set, delete or list)",
+                other
+            )),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::Standard
+    }
+}
@@ -0,0 +1,391 @@
+//! Counter and metric tools for skill authors
+//!
+//! `increment_counter` and `record_metric` let skills track their own domain
+//! events ("alerts_sent", "gas_price_gwei"); `get_metric` reads them back.
+//! Storage and aggregation live in `db::tables::metrics`, which also backs
+//! the `/api/analytics/metrics` endpoints.
+
+use crate::db::tables::metrics::{KIND_COUNTER, KIND_METRIC};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const MAX_NAME_CHARS: usize = 64;
+const MAX_LABELS_BYTES: usize = 1024;
+
+fn name_property() -> PropertySchema {
+    PropertySchema {
+        schema_type: "string".to_string(),
+        description: "Metric name in snake_case, e.g. 'alerts_sent'".to_string(),
+        default: None,
+        items: None,
+        enum_values: None,
+    }
+}
+
+fn skill_property() -> PropertySchema {
+    PropertySchema {
+        schema_type: "string".to_string(),
+        description: "Name of the skill recording this metric (lets the analytics API group metrics per skill)".to_string(),
+        default: None,
+        items: None,
+        enum_values: None,
+    }
+}
+
+fn labels_property() -> PropertySchema {
+    PropertySchema {
+        schema_type: "object".to_string(),
+        description: "Optional labels stored with this data point, e.g. {\"token\": \"ETH\"}".to_string(),
+        default: None,
+        items: None,
+        enum_values: None,
+    }
+}
+
+/// Metric names are short snake_case identifiers
+fn validate_name(name: &str) -> Result<&str, ToolResult> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+        return Err(ToolResult::error(format!(
+            "Metric name must be 1-{} characters",
+            MAX_NAME_CHARS
+        )));
+    }
+    if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.') {
+        return Err(ToolResult::error(
+            "Metric name may only contain lowercase letters, digits, '_' and '.'",
+        ));
+    }
+    Ok(name)
+}
+
+/// Shared write path for counters and metrics
+fn record(
+    context: &ToolContext,
+    name: &str,
+    kind: &str,
+    value: f64,
+    skill: Option<&str>,
+    labels: Option<&Value>,
+) -> ToolResult {
+    let db = match context.database.as_ref() {
+        Some(db) => db,
+        None => return ToolResult::error("Database not available"),
+    };
+    let name = match validate_name(name) {
+        Ok(n) => n,
+        Err(e) => return e,
+    };
+    if !value.is_finite() {
+        return ToolResult::error("Value must be a finite number");
+    }
+    if let Some(labels) = labels {
+        if !labels.is_object() {
+            return ToolResult::error("'labels' must be an object");
+        }
+        if labels.to_string().len() > MAX_LABELS_BYTES {
+            return ToolResult::error(format!("Labels too large (max {} bytes)", MAX_LABELS_BYTES));
+        }
+    }
+    match db.get_metric_kind(name) {
+        Ok(Some(existing)) if existing != kind => {
+            return ToolResult::error(format!(
+                "'{}' is already recorded as a {} — pick another name",
+                name, existing
+            ))
+        }
+        Err(e) => return ToolResult::error(format!("Database error: {}", e)),
+        _ => {}
+    }
+    let skill = skill.map(str::trim).filter(|s| !s.is_empty());
+    if let Err(e) = db.record_metric_event(name, kind, value, skill, labels) {
+        return ToolResult::error(format!("Database error: {}", e));
+    }
+
+    match db.get_metric_summary(name, None) {
+        Ok(Some(summary)) if kind == KIND_COUNTER => {
+            ToolResult::success(format!("{} = {}", name, summary.sum))
+                .with_metadata(json!({ "name": name, "kind": kind, "total": summary.sum }))
+        }
+        Ok(_) => ToolResult::success(format!("Recorded {} = {}", name, value))
+            .with_metadata(json!({ "name": name, "kind": kind, "value": value })),
+        Err(e) => ToolResult::error(format!("Database error: {}", e)),
+    }
+}
+
+/// Increment counter tool
+pub struct IncrementCounterTool {
+    definition: ToolDefinition,
+}
+
+impl IncrementCounterTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+        properties.insert("name".to_string(), name_property());
+        properties.insert(
+            "by".to_string(),
+            PropertySchema {
+                schema_type: "number".to_string(),
+                description: "Amount to add (default 1)".to_string(),
+                default: Some(json!(1)),
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert("skill".to_string(), skill_property());
+        properties.insert("labels".to_string(), labels_property());
+
+        IncrementCounterTool {
+            definition: ToolDefinition {
+                name: "increment_counter".to_string(),
+                description: "Increment a named counter to track a domain event (e.g. 'alerts_sent'). Returns the new all-time total. Read it back with get_metric.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["name".to_string()],
+                },
+                group: ToolGroup::System,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for IncrementCounterTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IncrementCounterParams {
+    name: String,
+    by: Option<f64>,
+    skill: Option<String>,
+    labels: Option<Value>,
+}
+
+#[async_trait]
+impl Tool for IncrementCounterTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: IncrementCounterParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        record(
+            context,
+            &params.name,
+            KIND_COUNTER,
+            params.by.unwrap_or(1.0),
+            params.skill.as_deref(),
+            params.labels.as_ref(),
+        )
+    }
+}
+
+/// Record metric tool
+pub struct RecordMetricTool {
+    definition: ToolDefinition,
+}
+
+impl RecordMetricTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+        properties.insert("name".to_string(), name_property());
+        properties.insert(
+            "value".to_string(),
+            PropertySchema {
+                schema_type: "number".to_string(),
+                description: "Observed value, e.g. a price, latency or balance".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert("skill".to_string(), skill_property());
+        properties.insert("labels".to_string(), labels_property());
+
+        RecordMetricTool {
+            definition: ToolDefinition {
+                name: "record_metric".to_string(),
+                description: "Record one observation of a named metric (e.g. 'gas_price_gwei'). Observations are summarized as count/avg/min/max/last by get_metric.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["name".to_string(), "value".to_string()],
+                },
+                group: ToolGroup::System,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for RecordMetricTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordMetricParams {
+    name: String,
+    value: f64,
+    skill: Option<String>,
+    labels: Option<Value>,
+}
+
+#[async_trait]
+impl Tool for RecordMetricTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: RecordMetricParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        record(
+            context,
+            &params.name,
+            KIND_METRIC,
+            params.value,
+            params.skill.as_deref(),
+            params.labels.as_ref(),
+        )
+    }
+}
+
+/// Get metric tool
+pub struct GetMetricTool {
+    definition: ToolDefinition,
+}
+
+impl GetMetricTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+        properties.insert("name".to_string(), name_property());
+        properties.insert(
+            "since_hours".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Only include data from the last N hours (omit for all time)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "bucket".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Also return a time series bucketed by hour or day".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec!["hour".to_string(), "day".to_string()]),
+            },
+        );
+
+        GetMetricTool {
+            definition: ToolDefinition {
+                name: "get_metric".to_string(),
+                description: "Read a counter total or metric summary recorded with increment_counter / record_metric, optionally over a time window and as an hourly/daily series.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["name".to_string()],
+                },
+                group: ToolGroup::System,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for GetMetricTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GetMetricParams {
+    name: String,
+    since_hours: Option<i64>,
+    bucket: Option<String>,
+}
+
+#[async_trait]
+impl Tool for GetMetricTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: GetMetricParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        let db = match context.database.as_ref() {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let name = match validate_name(&params.name) {
+            Ok(n) => n,
+            Err(e) => return e,
+        };
+        let since_hours = params.since_hours.filter(|h| *h > 0);
+
+        let summary = match db.get_metric_summary(name, since_hours) {
+            Ok(Some(s)) => s,
+            Ok(None) => {
+                return ToolResult::success(format!("No data recorded for '{}'", name))
+                    .with_metadata(json!({ "name": name, "found": false }))
+            }
+            Err(e) => return ToolResult::error(format!("Database error: {}", e)),
+        };
+
+        let window = since_hours
+            .map(|h| format!("last {}h", h))
+            .unwrap_or_else(|| "all time".to_string());
+        let mut text = if summary.kind == KIND_COUNTER {
+            format!("{} ({}): {} over {} increments", name, window, summary.sum, summary.count)
+        } else {
+            format!(
+                "{} ({}): last {}, avg {:.4}, min {}, max {} over {} observations",
+                name, window, summary.last, summary.avg, summary.min, summary.max, summary.count
+            )
+        };
+
+        let series = match params.bucket.as_deref() {
+            Some(bucket @ ("hour" | "day")) => match db.get_metric_series(name, bucket, since_hours) {
+                Ok(series) => Some(series),
+                Err(e) => return ToolResult::error(format!("Database error: {}", e)),
+            },
+            Some(other) => return ToolResult::error(format!("Unknown bucket '{}' (use hour or day)", other)),
+            None => None,
+        };
+        if let Some(series) = &series {
+            for b in series {
+                let value = if summary.kind == KIND_COUNTER { b.sum } else { b.avg };
+                text.push_str(&format!("\n{}: {}", b.bucket, value));
+            }
+        }
+
+        ToolResult::success(text).with_metadata(json!({
+            "summary": summary,
+            "series": series,
+        }))
+    }
+}
@@ -15,49 +15,63 @@ pub mod cryptocurrency;
 pub mod social_media;
 
 // Individual tools (remaining uncategorized)
+mod generate_report;
+mod identity_profile;
+mod knowledge_graph;
+mod kv_store;
 mod local_rpc;
+mod metrics;
 mod memory_associate;
 mod memory_graph;
 mod memory_merge;
 mod notes;
 mod process_status;
+mod query_database;
 mod memory_read;
 mod memory_search;
 mod web_fetch;
+mod workflow_template;
 
 // Re-exports from submodules
 pub use bash::{
     ApplyPatchTool, ClaudeCodeRemoteTool, DeleteFileTool, EditFileTool, ExecTool, GitTool,
     GlobTool, GrepTool, ListFilesTool, ReadFileTool, ReadSymbolTool, RenameFileTool,
-    RunSkillScriptTool, WriteFileTool,
+    RunSkillScriptTool, SaveArtifactsTool, WriteFileTool,
 };
-pub use code::{CommitterTool, DeployTool, IndexProjectTool, PrQualityTool, VerifyChangesTool};
+pub use code::{CommitterTool, DeployTool, IndexProjectTool, IssueTrackerTool, PrQualityTool, VerifyChangesTool};
 pub use core::{
-    AddTaskTool, DefineTasksTool, AgentSendTool, ApiKeysCheckTool, AskUserTool, HeartbeatConfigTool,
-    IdentityPostRegisterTool, ImportIdentityTool, InstallApiKeyTool, ManageModulesTool, ManageSkillsTool, ImpulseMapManageTool,
-    ReadSkillTool, RegisterNewIdentityTool, UnregisterIdentityTool, WorkstreamTool, ModifySoulTool, ModifySpecialRoleTool, SayToUserTool,
+    AddTaskTool, AgentReputationTool, DefineTasksTool, FetchFullOutputTool, AgentSendTool, ApiKeysCheckTool, AskUserTool, HeartbeatConfigTool,
+    IdentityPostRegisterTool, ImportIdentityTool, InstallApiKeyTool, InvokeSkillTool, ManageModulesTool, ManageSkillsTool, ImpulseMapManageTool,
+    ReadSkillTool, RegisterNewIdentityTool, RemoteAgentTool, RequestClarificationTool, RequestHumanTool, UnregisterIdentityTool, WorkstreamTool, ModifySoulTool, ModifySpecialRoleTool, SayToUserTool,
     SetAgentSubtypeTool, SubagentStatusTool, SpawnSubagentsTool, TaskFullyCompletedTool, UseSkillTool,
     // Meta tools (self-management)
     CheckCreditBalanceTool, CloudBackupTool, ManageGatewayChannelsTool, ReadOperatingModeTool,
     ReadRecentTransactionsTool, SetThemeAccentTool,
 };
 pub use cryptocurrency::{
-    load_networks, load_tokens, BridgeUsdcTool, BroadcastWeb3TxTool, DecodeCalldataTool,
-    Erc8128FetchTool, FromRawAmountTool, ListQueuedWeb3TxTool,
-    SelectWeb3NetworkTool, SendEthTool, SetAddressTool, SetNftTokenIdTool, SignRawTxTool,
-    SiwaAuthTool, SwapTokenTool, ToRawAmountTool, TokenLookupTool,
-    VerifyTxBroadcastTool, Web3PresetFunctionCallTool, X402AgentInvokeTool, X402FetchTool,
+    load_networks, load_tokens, BatchTxTool, BridgeStatusTool, BridgeUsdcTool, BroadcastWeb3TxTool, DecodeCalldataTool, DecodeTxTool,
+    Erc8128FetchTool, FromRawAmountTool, ListQueuedWeb3TxTool, ManageTxTool, PaperTradingTool, ProposeSafeTxTool,
+    SafeTxStatusTool, SelectWeb3NetworkTool, SendEthTool, SetAddressTool, SetNftTokenIdTool, SignRawTxTool,
+    SignTypedDataTool, SiwaAuthTool, SwapTokenTool, ToRawAmountTool, TokenApprovalsTool, TokenLookupTool,
+    TradeJournalTool, VerifyTxBroadcastTool, Web3PresetFunctionCallTool, X402AgentInvokeTool, X402FetchTool,
     X402PostTool, X402RpcTool,
 };
-pub use social_media::{DiscordLookupTool, DiscordReadTool, DiscordWriteTool, FigmaTool, GithubUserTool, TelegramReadTool, TelegramWriteTool, TwitterPostTool};
+pub use social_media::{DiscordLookupTool, DiscordReadTool, DiscordWriteTool, FarcasterCastTool, FigmaTool, GithubUserTool, GmailTool, SocialCalendarTool, TelegramReadTool, TelegramWriteTool, TwitterPostTool};
 
 // Re-exports from individual tools
+pub use generate_report::GenerateReportTool;
+pub use identity_profile::IdentityProfileTool;
+pub use knowledge_graph::KnowledgeGraphTool;
+pub use kv_store::KvStoreTool;
 pub use local_rpc::LocalRpcTool;
+pub use metrics::{GetMetricTool, IncrementCounterTool, RecordMetricTool};
 pub use memory_associate::MemoryAssociateTool;
 pub use memory_graph::MemoryGraphTool;
 pub use memory_merge::MemoryMergeTool;
 pub use notes::NotesTool;
 pub use process_status::ProcessStatusTool;
+pub use query_database::QueryDatabaseTool;
 pub use memory_read::MemoryReadTool;
 pub use memory_search::MemorySearchTool;
 pub use web_fetch::WebFetchTool;
+pub use workflow_template::WorkflowTemplateTool;
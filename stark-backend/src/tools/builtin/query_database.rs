@@ -0,0 +1,150 @@
+//! Read-only SQL introspection tool
+//!
+//! Lets the agent answer questions about its own history ("how many messages
+//! did I get from Discord last week?") with a parameterized SELECT over the
+//! whitelisted q_* views. Validation, limits and redaction live in
+//! `db::tables::query_views`.
+
+use crate::db::tables::query_views::{DEFAULT_QUERY_ROWS, MAX_QUERY_ROWS, QUERY_VIEWS};
+use crate::tools::payload::ToolPayload;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Query database tool - read-only SQL over the bot's own history
+pub struct QueryDatabaseTool {
+    definition: ToolDefinition,
+}
+
+impl QueryDatabaseTool {
+    pub fn new() -> Self {
+        let views = QUERY_VIEWS
+            .iter()
+            .map(|(name, description, _)| format!("{} — {}", name, description))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let mut properties = HashMap::new();
+        properties.insert(
+            "sql".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "A single SQLite SELECT (or WITH ... SELECT) over the q_* views. Use ?1, ?2 ... placeholders for values. Timestamps are RFC 3339 text, e.g. created_at >= datetime('now', '-7 days').".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "params".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Values bound to ?1, ?2 ... in order".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "A parameter value".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "limit".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: format!("Maximum rows to return (default {}, max {})", DEFAULT_QUERY_ROWS, MAX_QUERY_ROWS),
+                default: Some(json!(DEFAULT_QUERY_ROWS)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        QueryDatabaseTool {
+            definition: ToolDefinition {
+                name: "query_database".to_string(),
+                description: format!(
+                    "Run a read-only SQL query against your own history to answer questions about sessions, messages, memories, tool activity or usage. Only these views can be queried: {}. Secrets in results are redacted.",
+                    views
+                ),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["sql".to_string()],
+                },
+                group: ToolGroup::System,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for QueryDatabaseTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryDatabaseParams {
+    sql: String,
+    #[serde(default)]
+    params: Vec<Value>,
+    limit: Option<usize>,
+}
+
+#[async_trait]
+impl Tool for QueryDatabaseTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: QueryDatabaseParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let db = match context.database.as_ref() {
+            Some(db) => db.clone(),
+            None => return ToolResult::error("Database not available"),
+        };
+
+        let result = match tokio::task::spawn_blocking(move || {
+            db.run_introspection_query(&params.sql, &params.params, params.limit)
+        })
+        .await
+        {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => return ToolResult::error(e),
+            Err(e) => return ToolResult::error(format!("Query task failed: {}", e)),
+        };
+
+        let mut summary = format!("{} row(s)", result.rows.len());
+        if result.truncated {
+            summary.push_str(" (truncated — raise limit or aggregate)");
+        }
+        if result.redactions > 0 {
+            summary.push_str(&format!(", {} value(s) redacted", result.redactions));
+        }
+
+        let row_count = result.rows.len();
+        let columns: Vec<&str> = result.columns.iter().map(String::as_str).collect();
+        let rows = result.rows.into_iter().map(Value::Array).collect();
+
+        ToolResult::success(summary)
+            .with_payload(ToolPayload::table(rows, &columns))
+            .with_metadata(json!({
+                "row_count": row_count,
+                "truncated": result.truncated,
+                "redactions": result.redactions,
+            }))
+    }
+}
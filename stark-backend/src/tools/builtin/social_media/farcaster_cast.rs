@@ -0,0 +1,178 @@
+//! Farcaster casting tool
+//!
+//! Publishes casts (and replies) as the agent's Farcaster account through Neynar.
+
+use crate::integrations::farcaster::{self, NeynarClient, MAX_CAST_EMBEDS};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Tool for publishing casts on Farcaster
+pub struct FarcasterCastTool {
+    definition: ToolDefinition,
+}
+
+impl FarcasterCastTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "text".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Cast text. Over 320 bytes is posted as a reply chain.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "reply_to".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Optional hash of the cast to reply to (0x followed by 40 hex characters)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "channel".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Optional Farcaster channel ID to cast in (e.g. \"base\")".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "embeds".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Optional URLs to embed (at most 2)".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "URL".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        FarcasterCastTool {
+            definition: ToolDefinition {
+                name: "farcaster_cast".to_string(),
+                description: "Publish a cast on Farcaster as the agent's account, optionally as a reply or in a channel. Requires the Neynar API key and signer on the API keys page.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["text".to_string()],
+                },
+                group: ToolGroup::Messaging,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for FarcasterCastTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FarcasterCastParams {
+    text: String,
+    reply_to: Option<String>,
+    channel: Option<String>,
+    #[serde(default)]
+    embeds: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for FarcasterCastTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: FarcasterCastParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let text = params.text.trim();
+        if text.is_empty() {
+            return ToolResult::error("Cast text cannot be empty");
+        }
+        if let Some(ref hash) = params.reply_to {
+            if !farcaster::is_cast_hash(hash) {
+                return ToolResult::error(format!(
+                    "reply_to must be a full cast hash (0x followed by 40 hex characters), got \"{}\"",
+                    hash
+                ));
+            }
+        }
+        if params.embeds.len() > MAX_CAST_EMBEDS {
+            return ToolResult::error(format!("At most {} embeds are allowed per cast", MAX_CAST_EMBEDS));
+        }
+        if let Some(bad) = params.embeds.iter().find(|u| !u.starts_with("https://") && !u.starts_with("http://")) {
+            return ToolResult::error(format!("Embeds must be http(s) URLs, got \"{}\"", bad));
+        }
+
+        let Some(db) = context.database.as_ref() else {
+            return ToolResult::error("Database not available");
+        };
+        let client = match NeynarClient::from_keys(db) {
+            Ok(c) => c,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        let channel = params.channel.as_deref().map(str::trim).filter(|c| !c.is_empty());
+        match client
+            .publish_thread(text, params.reply_to.as_deref(), channel, &params.embeds)
+            .await
+        {
+            Ok(hashes) => {
+                let first = hashes.first().cloned().unwrap_or_default();
+                ToolResult::success(
+                    json!({
+                        "hash": first,
+                        "url": farcaster::cast_url(&first),
+                        "casts": hashes.len(),
+                        "hashes": hashes,
+                    })
+                    .to_string(),
+                )
+            }
+            Err(e) => ToolResult::error(format!("Failed to publish cast: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definition() {
+        let tool = FarcasterCastTool::new();
+        let def = tool.definition();
+        assert_eq!(def.name, "farcaster_cast");
+        assert_eq!(def.group, ToolGroup::Messaging);
+        assert!(def.input_schema.required.contains(&"text".to_string()));
+    }
+}
@@ -0,0 +1,454 @@
+use crate::db::Database;
+use crate::integrations::gmail::{policy, GmailClient, GmailConfig, OutgoingEmail};
+use crate::models::{MessageRole, SessionScope};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tools::ToolSafetyLevel;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Gmail mailbox tool: send mail, create drafts, label and archive threads.
+/// Direct sends are restricted to the configured recipient allow-list;
+/// anything else has to be drafted for the user to review.
+pub struct GmailTool {
+    definition: ToolDefinition,
+}
+
+impl GmailTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "The mailbox action to perform".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec![
+                    "send".to_string(),
+                    "draft".to_string(),
+                    "label".to_string(),
+                    "unlabel".to_string(),
+                    "archive".to_string(),
+                    "list_labels".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "to".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Recipient addresses (for send/draft)".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "Email address".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "cc".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Cc addresses (for send/draft)".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "Email address".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "subject".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Subject line (for send/draft)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "body".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Plain-text body (for send/draft)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "threadId".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Gmail thread ID. Required for label/unlabel/archive; for send/draft, replies within that thread".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "inReplyTo".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Message-ID header of the email being replied to (for send/draft)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "labels".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Label names or IDs (for label/unlabel)".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "Label name or ID".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        GmailTool {
+            definition: ToolDefinition {
+                name: "gmail".to_string(),
+                description: "Manage the connected Gmail mailbox: send email, create drafts for the user to review, apply/remove labels, archive threads, list labels. Sending is only allowed to recipients on the Gmail allow-list; for anyone else, create a draft instead.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["action".to_string()],
+                },
+                group: ToolGroup::Messaging,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for GmailTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GmailParams {
+    action: String,
+    #[serde(default)]
+    to: Vec<String>,
+    #[serde(default)]
+    cc: Vec<String>,
+    subject: Option<String>,
+    body: Option<String>,
+    #[serde(rename = "threadId")]
+    thread_id: Option<String>,
+    #[serde(rename = "inReplyTo")]
+    in_reply_to: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for GmailTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: GmailParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let Some(db) = context.database.as_ref() else {
+            return ToolResult::error("Database not available");
+        };
+
+        let config = match db.get_gmail_config() {
+            Ok(Some(config)) if config.enabled => config,
+            Ok(_) => return ToolResult::error("Gmail is not configured or is disabled. Set it up in the Gmail settings."),
+            Err(e) => return ToolResult::error(format!("Failed to load Gmail config: {}", e)),
+        };
+        let client = GmailClient::new(config.access_token.clone(), config.refresh_token.clone());
+
+        match params.action.as_str() {
+            "send" => self.send(&params, &config, &client, db, context).await,
+            "draft" => self.draft(&params, &config, &client).await,
+            "label" | "unlabel" => self.label(&params, &config, &client).await,
+            "archive" => self.archive(&params, &config, &client).await,
+            "list_labels" => self.list_labels(&config, &client).await,
+            other => ToolResult::error(format!(
+                "Unknown action '{}'. Use send, draft, label, unlabel, archive or list_labels.",
+                other
+            )),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::Standard
+    }
+}
+
+impl GmailTool {
+    fn build_email(params: &GmailParams) -> Result<OutgoingEmail, ToolResult> {
+        if params.to.is_empty() {
+            return Err(ToolResult::error("'to' is required"));
+        }
+        let body = params.body.clone().ok_or_else(|| ToolResult::error("'body' is required"))?;
+
+        Ok(OutgoingEmail {
+            to: params.to.clone(),
+            cc: params.cc.clone(),
+            subject: params.subject.clone().unwrap_or_default(),
+            body,
+            html_body: None,
+            thread_id: params.thread_id.clone(),
+            in_reply_to: params.in_reply_to.clone(),
+        })
+    }
+
+    fn require_thread(params: &GmailParams) -> Result<&str, ToolResult> {
+        params
+            .thread_id
+            .as_deref()
+            .ok_or_else(|| ToolResult::error(format!("'threadId' is required for {}", params.action)))
+    }
+
+    async fn send(
+        &self,
+        params: &GmailParams,
+        config: &GmailConfig,
+        client: &GmailClient,
+        db: &Database,
+        context: &ToolContext,
+    ) -> ToolResult {
+        let email = match Self::build_email(params) {
+            Ok(e) => e,
+            Err(r) => return r,
+        };
+
+        let blocked = policy::blocked_recipients(&config.send_allowlist, email.recipients());
+        if !blocked.is_empty() {
+            return ToolResult::error(format!(
+                "Not allowed to send directly to: {}. These recipients are not on the Gmail send allow-list — use action 'draft' so the user can review and send it.",
+                blocked.join(", ")
+            ));
+        }
+
+        let sent = match client.send_message(&config.email, &email).await {
+            Ok(m) => m,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        record_sent_mail(db, config, &email, &sent.thread_id, &sent.id, context.session_id);
+
+        ToolResult::success(format!(
+            "Email sent to {} (thread {})",
+            email.to.join(", "),
+            sent.thread_id
+        ))
+        .with_metadata(json!({
+            "action": "send",
+            "message_id": sent.id,
+            "thread_id": sent.thread_id,
+            "to": email.to,
+            "cc": email.cc,
+        }))
+    }
+
+    async fn draft(&self, params: &GmailParams, config: &GmailConfig, client: &GmailClient) -> ToolResult {
+        let email = match Self::build_email(params) {
+            Ok(e) => e,
+            Err(r) => return r,
+        };
+
+        match client.create_draft(&config.email, &email).await {
+            Ok(draft) => ToolResult::success(format!(
+                "Draft created for {} — the user can review and send it from Gmail",
+                email.to.join(", ")
+            ))
+            .with_metadata(json!({
+                "action": "draft",
+                "draft_id": draft.id,
+                "thread_id": draft.message.thread_id,
+            })),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+
+    async fn label(&self, params: &GmailParams, config: &GmailConfig, client: &GmailClient) -> ToolResult {
+        let thread_id = match Self::require_thread(params) {
+            Ok(t) => t,
+            Err(r) => return r,
+        };
+        if params.labels.is_empty() {
+            return ToolResult::error("'labels' is required");
+        }
+
+        let available = match client.list_labels(&config.email).await {
+            Ok(l) => l,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        let mut label_ids = Vec::new();
+        for wanted in &params.labels {
+            match available
+                .iter()
+                .find(|l| l.id == *wanted || l.name.eq_ignore_ascii_case(wanted))
+            {
+                Some(label) => label_ids.push(label.id.clone()),
+                None => return ToolResult::error(format!(
+                    "Unknown label '{}'. Use action 'list_labels' to see available labels.",
+                    wanted
+                )),
+            }
+        }
+
+        let (add, remove) = if params.action == "label" {
+            (label_ids.clone(), Vec::new())
+        } else {
+            (Vec::new(), label_ids.clone())
+        };
+
+        match client.modify_thread(&config.email, thread_id, &add, &remove).await {
+            Ok(()) => ToolResult::success(format!(
+                "{} {} on thread {}",
+                if params.action == "label" { "Applied" } else { "Removed" },
+                params.labels.join(", "),
+                thread_id
+            ))
+            .with_metadata(json!({
+                "action": params.action,
+                "thread_id": thread_id,
+                "label_ids": label_ids,
+            })),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+
+    async fn archive(&self, params: &GmailParams, config: &GmailConfig, client: &GmailClient) -> ToolResult {
+        let thread_id = match Self::require_thread(params) {
+            Ok(t) => t,
+            Err(r) => return r,
+        };
+
+        match client
+            .modify_thread(&config.email, thread_id, &[], &["INBOX".to_string()])
+            .await
+        {
+            Ok(()) => ToolResult::success(format!("Archived thread {}", thread_id))
+                .with_metadata(json!({ "action": "archive", "thread_id": thread_id })),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+
+    async fn list_labels(&self, config: &GmailConfig, client: &GmailClient) -> ToolResult {
+        match client.list_labels(&config.email).await {
+            Ok(labels) => {
+                let lines: Vec<String> = labels
+                    .iter()
+                    .map(|l| format!("- {} ({})", l.name, l.id))
+                    .collect();
+                ToolResult::success(format!("{} labels:\n{}", labels.len(), lines.join("\n")))
+                    .with_metadata(json!({ "labels": labels }))
+            }
+            Err(e) => ToolResult::error(e),
+        }
+    }
+}
+
+/// Record a sent email in the Gmail thread's session (and the calling
+/// session, if different) so follow-ups on the thread have the context.
+fn record_sent_mail(
+    db: &Database,
+    config: &GmailConfig,
+    email: &OutgoingEmail,
+    thread_id: &str,
+    message_id: &str,
+    calling_session: Option<i64>,
+) {
+    let mut note = format!("[Sent email]\nTo: {}\n", email.to.join(", "));
+    if !email.cc.is_empty() {
+        note.push_str(&format!("Cc: {}\n", email.cc.join(", ")));
+    }
+    note.push_str(&format!("Subject: {}\n\n{}", email.subject, email.body));
+
+    let thread_session = db
+        .get_or_create_chat_session(
+            "gmail",
+            config.response_channel_id.unwrap_or(0),
+            thread_id,
+            SessionScope::Group,
+            None,
+        )
+        .map(|s| s.id);
+
+    let mut targets = Vec::new();
+    match thread_session {
+        Ok(id) => targets.push(id),
+        Err(e) => log::warn!("[GMAIL] Failed to get session for thread {}: {}", thread_id, e),
+    }
+    if let Some(id) = calling_session.filter(|id| !targets.contains(id)) {
+        targets.push(id);
+    }
+
+    for session_id in targets {
+        if let Err(e) = db.add_session_message(
+            session_id,
+            MessageRole::System,
+            &note,
+            None,
+            None,
+            Some(message_id),
+            None,
+        ) {
+            log::warn!("[GMAIL] Failed to record sent email in session {}: {}", session_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definition() {
+        let tool = GmailTool::new();
+        let def = tool.definition();
+
+        assert_eq!(def.name, "gmail");
+        assert_eq!(def.group, ToolGroup::Messaging);
+        assert!(def.input_schema.required.contains(&"action".to_string()));
+
+        let actions = def.input_schema.properties["action"].enum_values.as_ref().unwrap();
+        for action in ["send", "draft", "label", "unlabel", "archive", "list_labels"] {
+            assert!(actions.contains(&action.to_string()));
+        }
+    }
+}
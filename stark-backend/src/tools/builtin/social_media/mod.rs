@@ -1,22 +1,28 @@
 //! Social media and platform integration tools
 //!
-//! Tools for interacting with Twitter, Discord, GitHub, and other platforms.
+//! Tools for interacting with Twitter, Farcaster, Discord, GitHub, and other platforms.
 
 mod discord_lookup;
 mod discord_read;
 mod discord_write;
+mod farcaster_cast;
 mod figma;
 mod github_user;
+mod gmail;
+mod social_calendar;
 mod telegram_read;
 mod telegram_write;
 mod twitter_post;
 pub mod twitter_oauth;
 
 pub use discord_lookup::DiscordLookupTool;
+pub use farcaster_cast::FarcasterCastTool;
 pub use figma::FigmaTool;
 pub use discord_read::DiscordReadTool;
 pub use discord_write::DiscordWriteTool;
 pub use github_user::GithubUserTool;
+pub use gmail::GmailTool;
+pub use social_calendar::SocialCalendarTool;
 pub use twitter_oauth::{
     check_subscription_tier, generate_oauth_header, percent_encode, TwitterCredentials,
     XSubscriptionTier, TWITTER_MAX_CHARS, TWITTER_PREMIUM_MAX_CHARS,
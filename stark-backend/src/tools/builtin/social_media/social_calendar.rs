@@ -0,0 +1,298 @@
+use super::twitter_oauth::TWITTER_PREMIUM_MAX_CHARS;
+use crate::channels::types::ChannelType;
+use crate::db::tables::social_posts::NewSocialPost;
+use crate::db::Database;
+use crate::gateway::protocol::GatewayEvent;
+use crate::social_calendar::{self, PLATFORMS};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Content calendar tool: draft Twitter/Discord posts for the owner to approve.
+/// Nothing is published by this tool — approved drafts are posted by the
+/// social calendar worker at their scheduled time.
+pub struct SocialCalendarTool {
+    definition: ToolDefinition,
+}
+
+impl SocialCalendarTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "draft: queue a post for owner approval. list: show calendar entries. cancel: withdraw an unpublished post.".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec!["draft".to_string(), "list".to_string(), "cancel".to_string()]),
+            },
+        );
+
+        properties.insert(
+            "platform".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Where to publish (for draft)".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(PLATFORMS.iter().map(|p| p.to_string()).collect()),
+            },
+        );
+
+        properties.insert(
+            "text".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Post content (for draft)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "scheduled_at".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Optional RFC 3339 publish time (e.g. \"2025-06-01T14:00:00Z\"). Omit to publish as soon as approved.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "channel_id".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Discord only: StarkBot channel (bot) to post through. Defaults to the current channel.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "discord_channel_id".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Discord only: Discord channel ID to post in. Defaults to the current Discord channel.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "post_id".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Calendar post ID (for cancel)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "status".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Filter for list: pending, approved, rejected, published, failed or cancelled".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        SocialCalendarTool {
+            definition: ToolDefinition {
+                name: "social_calendar".to_string(),
+                description: "Content calendar for Twitter/Discord posts. Drafted posts wait for the owner's approval (chat buttons or dashboard) and are then published automatically at the scheduled time. Use this instead of posting directly when the owner wants to review content; list shows status and engagement of published posts.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["action".to_string()],
+                },
+                group: ToolGroup::Messaging,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for SocialCalendarTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SocialCalendarParams {
+    action: String,
+    platform: Option<String>,
+    text: Option<String>,
+    scheduled_at: Option<String>,
+    channel_id: Option<i64>,
+    discord_channel_id: Option<String>,
+    post_id: Option<i64>,
+    status: Option<String>,
+}
+
+#[async_trait]
+impl Tool for SocialCalendarTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: SocialCalendarParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let Some(db) = context.database.as_ref() else {
+            return ToolResult::error("Database not available");
+        };
+
+        match params.action.as_str() {
+            "draft" => self.draft(&params, db, context).await,
+            "list" => match db.list_social_posts(params.status.as_deref(), 25) {
+                Ok(posts) => ToolResult::success(json!({ "posts": posts }).to_string()),
+                Err(e) => ToolResult::error(format!("Failed to list posts: {}", e)),
+            },
+            "cancel" => {
+                let Some(post_id) = params.post_id else {
+                    return ToolResult::error("'post_id' is required for cancel");
+                };
+                match db.cancel_social_post(post_id) {
+                    Ok(true) => ToolResult::success(format!("Post #{} cancelled.", post_id)),
+                    Ok(false) => ToolResult::error(format!("Post #{} not found or already published/decided.", post_id)),
+                    Err(e) => ToolResult::error(format!("Failed to cancel post: {}", e)),
+                }
+            }
+            other => ToolResult::error(format!("Unknown action '{}'. Use draft, list or cancel.", other)),
+        }
+    }
+}
+
+impl SocialCalendarTool {
+    /// Resolve the Discord bot channel and Discord channel ID for a draft
+    fn discord_target(
+        params: &SocialCalendarParams,
+        db: &Database,
+        context: &ToolContext,
+    ) -> Result<(i64, String), String> {
+        let current_is_discord = context
+            .channel_type
+            .as_deref()
+            .and_then(ChannelType::from_str)
+            .is_some_and(|t| t == ChannelType::Discord);
+
+        let channel_id = params
+            .channel_id
+            .or(if current_is_discord { context.channel_id } else { None })
+            .ok_or("'channel_id' (a Discord channel configured in StarkBot) is required")?;
+        let channel = db
+            .get_channel(channel_id)
+            .map_err(|e| format!("Failed to load channel: {}", e))?
+            .ok_or_else(|| format!("Channel {} not found", channel_id))?;
+        if ChannelType::from_str(&channel.channel_type) != Some(ChannelType::Discord) {
+            return Err(format!("Channel {} is not a Discord channel", channel_id));
+        }
+
+        let chat_id = params
+            .discord_channel_id
+            .clone()
+            .or(if current_is_discord { context.platform_chat_id.clone() } else { None })
+            .filter(|c| !c.is_empty() && c.chars().all(|ch| ch.is_ascii_digit()))
+            .ok_or("'discord_channel_id' (numeric Discord channel ID) is required")?;
+        Ok((channel_id, chat_id))
+    }
+
+    async fn draft(&self, params: &SocialCalendarParams, db: &Database, context: &ToolContext) -> ToolResult {
+        let platform = match params.platform.as_deref() {
+            Some(p) if PLATFORMS.contains(&p) => p,
+            Some(p) => return ToolResult::error(format!("Unsupported platform '{}'. Use twitter or discord.", p)),
+            None => return ToolResult::error("'platform' is required for draft"),
+        };
+        let text = match params.text.as_deref().map(str::trim) {
+            Some(t) if !t.is_empty() => t,
+            _ => return ToolResult::error("'text' is required for draft"),
+        };
+        if platform == "twitter" && text.chars().count() > TWITTER_PREMIUM_MAX_CHARS {
+            return ToolResult::error(format!("Tweet exceeds {} characters", TWITTER_PREMIUM_MAX_CHARS));
+        }
+
+        let scheduled_at = match params.scheduled_at.as_deref() {
+            Some(raw) => match DateTime::parse_from_rfc3339(raw) {
+                Ok(t) if t.with_timezone(&Utc) > Utc::now() => Some(t.with_timezone(&Utc)),
+                Ok(_) => return ToolResult::error("scheduled_at must be in the future"),
+                Err(e) => return ToolResult::error(format!("Invalid scheduled_at '{}': {}", raw, e)),
+            },
+            None => None,
+        };
+
+        let (target_channel_id, target_chat_id) = if platform == "discord" {
+            match Self::discord_target(params, db, context) {
+                Ok((channel_id, chat_id)) => (Some(channel_id), Some(chat_id)),
+                Err(e) => return ToolResult::error(e),
+            }
+        } else {
+            (None, None)
+        };
+
+        let post = match db.create_social_post(&NewSocialPost {
+            platform,
+            target_channel_id,
+            target_chat_id: target_chat_id.as_deref(),
+            text,
+            scheduled_at,
+            session_id: context.session_id,
+        }) {
+            Ok(post) => post,
+            Err(e) => return ToolResult::error(format!("Failed to save draft: {}", e)),
+        };
+
+        social_calendar::request_approval(db, &post, context.channel_id, context.platform_chat_id.as_deref()).await;
+        if let Some(ref broadcaster) = context.broadcaster {
+            broadcaster.broadcast(GatewayEvent::custom(
+                "social_post_pending",
+                json!({ "post_id": post.id, "platform": post.platform, "scheduled_at": post.scheduled_at }),
+            ));
+        }
+
+        ToolResult::success(
+            json!({
+                "post_id": post.id,
+                "status": post.status,
+                "platform": post.platform,
+                "scheduled_at": post.scheduled_at,
+                "message": "Draft saved. It will only be published after the owner approves it.",
+            })
+            .to_string(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definition() {
+        let tool = SocialCalendarTool::new();
+        let def = tool.definition();
+        assert_eq!(def.name, "social_calendar");
+        assert_eq!(def.group, ToolGroup::Messaging);
+        assert!(def.input_schema.required.contains(&"action".to_string()));
+    }
+}
@@ -3,6 +3,7 @@
 //! Provides shared OAuth functionality for Twitter API v2 access,
 //! used by both the TwitterPostTool and the Twitter mention listener.
 
+use crate::circuit_breaker::{self, GuardedSend};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use hmac::{Hmac, Mac};
 use sha1::Sha1;
@@ -209,7 +210,7 @@ pub async fn check_subscription_tier(
     let result = client
         .get(format!("{}?user.fields=subscription_type", base_url))
         .header("Authorization", auth_header)
-        .send()
+        .send_guarded(circuit_breaker::TWITTER)
         .await;
 
     let response = match result {
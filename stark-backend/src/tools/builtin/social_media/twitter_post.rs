@@ -5,12 +5,17 @@
 use super::twitter_oauth::{
     check_subscription_tier, generate_oauth_header, TwitterCredentials, TWITTER_MAX_CHARS,
 };
+use crate::channels::outbox;
+use crate::circuit_breaker::{self, GuardedSend};
 use crate::controllers::api_keys::ApiKeyId;
+use crate::db::tables::outbox_drafts::DraftTarget;
+use crate::db::tables::twitter_outgoing::NewQueuedTweet;
 use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -68,10 +73,32 @@ impl TwitterPostTool {
             },
         );
 
+        properties.insert(
+            "queue".to_string(),
+            PropertySchema {
+                schema_type: "boolean".to_string(),
+                description: "Optional: Add the tweet to the outgoing queue instead of posting now. The Twitter channel posts queued tweets as rate limits allow and splits long text into a thread. Requires a running Twitter channel.".to_string(),
+                default: Some(json!(false)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "post_at".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Optional: RFC 3339 time (e.g. \"2025-06-01T14:00:00Z\") to post at. Implies queue.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
         TwitterPostTool {
             definition: ToolDefinition {
                 name: "twitter_post".to_string(),
-                description: "Post a tweet to Twitter/X with optional image attachment. Requires Twitter OAuth credentials to be configured in Settings > API Keys.".to_string(),
+                description: "Post a tweet to Twitter/X with optional image attachment, or queue it (optionally for a set time) for the Twitter channel to post. When a Twitter channel has review before send enabled, the tweet is held as a draft until the owner approves it. Requires Twitter OAuth credentials to be configured in Settings > API Keys.".to_string(),
                 input_schema: ToolInputSchema {
                     schema_type: "object".to_string(),
                     properties,
@@ -87,6 +114,84 @@ impl TwitterPostTool {
         context.get_api_key_by_id(key_id).filter(|k| !k.is_empty())
     }
 
+    /// Add the tweet to the outgoing queue drained by the Twitter channel
+    fn enqueue(&self, params: &TwitterPostParams, context: &ToolContext) -> ToolResult {
+        if params.media_url.is_some() {
+            return ToolResult::error("media_url is not supported for queued tweets. Post it directly instead.");
+        }
+        let Some(db) = context.database.as_ref() else {
+            return ToolResult::error("Database not available");
+        };
+
+        let not_before = match params.post_at.as_deref() {
+            Some(raw) => match DateTime::parse_from_rfc3339(raw) {
+                Ok(t) => Some(t.with_timezone(&Utc)),
+                Err(e) => return ToolResult::error(format!("Invalid post_at '{}': {}", raw, e)),
+            },
+            None => None,
+        };
+
+        let queued = NewQueuedTweet {
+            channel_id: None,
+            text: &params.text,
+            reply_to_id: params.reply_to.as_deref(),
+            quote_tweet_id: params.quote_tweet_id.as_deref(),
+            session_id: context.session_id,
+            not_before,
+        };
+        match db.enqueue_tweet(&queued) {
+            Ok(id) => ToolResult::success(
+                json!({
+                    "success": true,
+                    "queued": true,
+                    "queue_id": id,
+                    "post_at": not_before.map(|t| t.to_rfc3339()),
+                })
+                .to_string(),
+            ),
+            Err(e) => ToolResult::error(format!("Failed to queue tweet: {}", e)),
+        }
+    }
+
+    /// Store the tweet as an outbox draft awaiting approval
+    fn hold_for_review(&self, params: &TwitterPostParams, context: &ToolContext) -> ToolResult {
+        if params.media_url.is_some() {
+            return ToolResult::error(
+                "media_url is not supported while tweets require review. Post the text only.",
+            );
+        }
+        let Some(db) = context.database.as_ref() else {
+            return ToolResult::error("Database not available");
+        };
+        let not_before = match params.post_at.as_deref() {
+            Some(raw) => match DateTime::parse_from_rfc3339(raw) {
+                Ok(t) => Some(t.with_timezone(&Utc)),
+                Err(e) => return ToolResult::error(format!("Invalid post_at '{}': {}", raw, e)),
+            },
+            None => None,
+        };
+
+        let target = DraftTarget::Twitter {
+            channel_id: None,
+            reply_to_id: params.reply_to.clone(),
+            quote_tweet_id: params.quote_tweet_id.clone(),
+            not_before,
+        };
+        match outbox::hold(db, context.broadcaster.as_deref(), target, &params.text, context.session_id) {
+            Ok(draft) => ToolResult::success(
+                json!({
+                    "success": true,
+                    "held_for_review": true,
+                    "draft_id": draft.id,
+                    "expires_at": draft.expires_at,
+                    "note": "Review is enabled for Twitter: the tweet is posted only after the owner approves it.",
+                })
+                .to_string(),
+            ),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+
     /// Download an image from a URL and upload it to Twitter's media upload endpoint.
     /// Returns the media_id string on success.
     async fn upload_media(
@@ -155,7 +260,7 @@ impl TwitterPostTool {
             .post(upload_url)
             .header("Authorization", auth_header)
             .multipart(form)
-            .send()
+            .send_guarded(circuit_breaker::TWITTER)
             .await
             .map_err(|e| format!("Media upload request failed: {}", e))?;
 
@@ -201,6 +306,9 @@ struct TwitterPostParams {
     reply_to: Option<String>,
     quote_tweet_id: Option<String>,
     media_url: Option<String>,
+    #[serde(default)]
+    queue: bool,
+    post_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -239,6 +347,37 @@ impl Tool for TwitterPostTool {
             return ToolResult::error("Tweet text cannot be empty");
         }
 
+        // Validate tweet IDs are numeric before calling the API
+        if let Some(reply_to) = &params.reply_to {
+            if !reply_to.chars().all(|c| c.is_ascii_digit()) || reply_to.is_empty() {
+                return ToolResult::error(format!(
+                    "reply_to must be a numeric tweet ID (e.g. \"1893027483920175104\"), got \"{}\"",
+                    reply_to
+                ));
+            }
+        }
+        if let Some(quote_id) = &params.quote_tweet_id {
+            if !quote_id.chars().all(|c| c.is_ascii_digit()) || quote_id.is_empty() {
+                return ToolResult::error(format!(
+                    "quote_tweet_id must be a numeric tweet ID (e.g. \"1893027483920175104\"), got \"{}\"",
+                    quote_id
+                ));
+            }
+        }
+
+        // With review enabled on a Twitter channel, nothing is posted until approved
+        if let Some(db) = context.database.as_ref() {
+            if outbox::twitter_review_enabled(db, None) {
+                return self.hold_for_review(&params, context);
+            }
+        }
+
+        // Queued tweets are posted later by the running Twitter channel, which
+        // threads long text and paces posts around the API rate limit
+        if params.queue || params.post_at.is_some() {
+            return self.enqueue(&params, context);
+        }
+
         // Get all 4 OAuth credentials
         let consumer_key = match self.get_credential(ApiKeyId::TwitterConsumerKey, context) {
             Some(k) => k,
@@ -304,24 +443,6 @@ impl Tool for TwitterPostTool {
             }
         }
 
-        // Validate tweet IDs are numeric before calling the API
-        if let Some(reply_to) = &params.reply_to {
-            if !reply_to.chars().all(|c| c.is_ascii_digit()) || reply_to.is_empty() {
-                return ToolResult::error(format!(
-                    "reply_to must be a numeric tweet ID (e.g. \"1893027483920175104\"), got \"{}\"",
-                    reply_to
-                ));
-            }
-        }
-        if let Some(quote_id) = &params.quote_tweet_id {
-            if !quote_id.chars().all(|c| c.is_ascii_digit()) || quote_id.is_empty() {
-                return ToolResult::error(format!(
-                    "quote_tweet_id must be a numeric tweet ID (e.g. \"1893027483920175104\"), got \"{}\"",
-                    quote_id
-                ));
-            }
-        }
-
         // Upload media if provided
         let media_id = if let Some(ref media_url) = params.media_url {
             match self.upload_media(&client, media_url, &credentials).await {
@@ -371,7 +492,7 @@ impl Tool for TwitterPostTool {
             .header("Authorization", auth_header)
             .header("Content-Type", "application/json")
             .json(&body)
-            .send()
+            .send_guarded(circuit_breaker::TWITTER)
             .await
         {
             Ok(r) => r,
@@ -0,0 +1,324 @@
+//! Workflow Template Tool — reusable parameterized plans
+//!
+//! Single tool with `action` parameter: save, instantiate, list, get, delete.
+//! `save` turns the session's last plan into a template, swapping literal
+//! values for `{{param}}` placeholders; `instantiate` renders a template with
+//! new values and replaces the task queue the same way define_tasks does.
+//! Storage lives in `db::tables::workflow_templates`.
+
+use crate::db::tables::workflow_templates::{parameterize, TemplateParam};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+    ToolSafetyLevel,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const MAX_NAME_CHARS: usize = 64;
+
+pub struct WorkflowTemplateTool {
+    definition: ToolDefinition,
+}
+
+impl WorkflowTemplateTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "save: store the plan this session just ran as a template. instantiate: run a template with new parameter values (replaces the task queue). list/get/delete: manage templates.".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec![
+                    "save".to_string(),
+                    "instantiate".to_string(),
+                    "list".to_string(),
+                    "get".to_string(),
+                    "delete".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "name".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Template name, e.g. 'release-checklist' (required except for list)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "description".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "For save: what the workflow does and when to use it".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "params".to_string(),
+            PropertySchema {
+                schema_type: "object".to_string(),
+                description: "For save: map of parameter name to the literal value used in the last plan, e.g. {\"version\": \"1.4.0\"}. Each literal becomes a {{version}} placeholder (and its default). For instantiate: map of parameter name to the new value.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "tasks".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "For save: explicit task list to store instead of the session's last plan".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "A task description".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        WorkflowTemplateTool {
+            definition: ToolDefinition {
+                name: "workflow_template".to_string(),
+                description: "Save a completed plan as a reusable, parameterized workflow template (\"save this as a template called release-checklist\") and instantiate it later with new parameters instead of re-planning. Actions: save, instantiate, list, get, delete.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["action".to_string()],
+                },
+                group: ToolGroup::System,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for WorkflowTemplateTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkflowTemplateParams {
+    action: String,
+    name: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    params: HashMap<String, Value>,
+    tasks: Option<Vec<String>>,
+}
+
+/// Validate the template name for actions that need one
+fn require_name<'a>(name: Option<&'a str>, action: &str) -> Result<&'a str, ToolResult> {
+    let name = match name.map(str::trim) {
+        Some(n) if !n.is_empty() => n,
+        _ => return Err(ToolResult::error(format!("'name' is required for {}", action))),
+    };
+    if name.chars().count() > MAX_NAME_CHARS
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ToolResult::error(format!(
+            "Template name must be up to {} letters, digits, '-' or '_'",
+            MAX_NAME_CHARS
+        )));
+    }
+    Ok(name)
+}
+
+/// Parameter values as strings (numbers and booleans are accepted too)
+fn string_values(params: &HashMap<String, Value>) -> HashMap<String, String> {
+    params
+        .iter()
+        .map(|(k, v)| {
+            let value = match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (k.clone(), value)
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Tool for WorkflowTemplateTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: WorkflowTemplateParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let db = match context.database.as_ref() {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+
+        match params.action.as_str() {
+            "save" => {
+                let name = match require_name(params.name.as_deref(), &params.action) {
+                    Ok(n) => n,
+                    Err(e) => return e,
+                };
+                let plan = match params.tasks {
+                    Some(ref tasks) if !tasks.is_empty() => tasks.clone(),
+                    _ => {
+                        let session_id = match context.session_id {
+                            Some(id) => id,
+                            None => return ToolResult::error("No session in this context — pass 'tasks' explicitly"),
+                        };
+                        match db.get_session_plan(session_id) {
+                            Ok(Some(plan)) if !plan.tasks.is_empty() => plan.tasks,
+                            Ok(_) => return ToolResult::error("This session has no plan to save yet — pass 'tasks' explicitly"),
+                            Err(e) => return ToolResult::error(format!("Database error: {}", e)),
+                        }
+                    }
+                };
+
+                let values = string_values(&params.params);
+                let tasks = parameterize(&plan, &values);
+                let mut template_params: Vec<TemplateParam> = values
+                    .into_iter()
+                    .map(|(name, value)| TemplateParam { name, description: None, default: Some(value) })
+                    .collect();
+                template_params.sort_by(|a, b| a.name.cmp(&b.name));
+                let unused: Vec<&str> = template_params
+                    .iter()
+                    .filter(|p| !tasks.iter().any(|t| t.description.contains(&format!("{{{{{}}}}}", p.name))))
+                    .map(|p| p.name.as_str())
+                    .collect();
+                if !unused.is_empty() {
+                    return ToolResult::error(format!(
+                        "These parameter values don't appear in the plan: {}",
+                        unused.join(", ")
+                    ));
+                }
+
+                match db.save_workflow_template(
+                    name,
+                    params.description.as_deref(),
+                    &template_params,
+                    &tasks,
+                    context.session_id,
+                ) {
+                    Ok(template) => {
+                        let lines: Vec<String> = template
+                            .tasks
+                            .iter()
+                            .enumerate()
+                            .map(|(i, t)| format!("{}. {}", i + 1, t.description))
+                            .collect();
+                        ToolResult::success(format!(
+                            "Saved workflow template '{}' ({} tasks):\n{}",
+                            name,
+                            template.tasks.len(),
+                            lines.join("\n")
+                        ))
+                        .with_metadata(json!({ "template": template }))
+                    }
+                    Err(e) => ToolResult::error(format!("Database error: {}", e)),
+                }
+            }
+            "instantiate" => {
+                let name = match require_name(params.name.as_deref(), &params.action) {
+                    Ok(n) => n,
+                    Err(e) => return e,
+                };
+                let template = match db.get_workflow_template(name) {
+                    Ok(Some(t)) => t,
+                    Ok(None) => return ToolResult::error(format!("No workflow template named '{}'", name)),
+                    Err(e) => return ToolResult::error(format!("Database error: {}", e)),
+                };
+                let tasks = match template.instantiate(&string_values(&params.params)) {
+                    Ok(t) => t,
+                    Err(e) => return ToolResult::error(e),
+                };
+                let _ = db.mark_workflow_template_used(name);
+
+                // Same metadata contract as define_tasks: the dispatcher replaces the queue
+                ToolResult::success(format!(
+                    "Workflow '{}' planned ({} tasks). Starting task 1 now. Focus on the CURRENT TASK shown in your instructions.",
+                    name,
+                    tasks.len()
+                ))
+                .with_metadata(json!({
+                    "define_tasks": true,
+                    "tasks": tasks,
+                    "workflow_template": name,
+                }))
+            }
+            "list" => match db.list_workflow_templates() {
+                Ok(templates) if templates.is_empty() => ToolResult::success("No workflow templates saved"),
+                Ok(templates) => {
+                    let lines: Vec<String> = templates
+                        .iter()
+                        .map(|t| {
+                            let params: Vec<&str> = t.params.iter().map(|p| p.name.as_str()).collect();
+                            format!(
+                                "{} ({} tasks; params: {}){}",
+                                t.name,
+                                t.tasks.len(),
+                                if params.is_empty() { "none".to_string() } else { params.join(", ") },
+                                t.description.as_ref().map(|d| format!(" — {}", d)).unwrap_or_default()
+                            )
+                        })
+                        .collect();
+                    ToolResult::success(lines.join("\n")).with_metadata(json!({ "count": templates.len() }))
+                }
+                Err(e) => ToolResult::error(format!("Database error: {}", e)),
+            },
+            "get" => {
+                let name = match require_name(params.name.as_deref(), &params.action) {
+                    Ok(n) => n,
+                    Err(e) => return e,
+                };
+                match db.get_workflow_template(name) {
+                    Ok(Some(template)) => ToolResult::success(
+                        serde_json::to_string_pretty(&template).unwrap_or_default(),
+                    ),
+                    Ok(None) => ToolResult::error(format!("No workflow template named '{}'", name)),
+                    Err(e) => ToolResult::error(format!("Database error: {}", e)),
+                }
+            }
+            "delete" => {
+                let name = match require_name(params.name.as_deref(), &params.action) {
+                    Ok(n) => n,
+                    Err(e) => return e,
+                };
+                match db.delete_workflow_template(name) {
+                    Ok(true) => ToolResult::success(format!("Deleted workflow template '{}'", name)),
+                    Ok(false) => ToolResult::error(format!("No workflow template named '{}'", name)),
+                    Err(e) => ToolResult::error(format!("Database error: {}", e)),
+                }
+            }
+            other => ToolResult::error(format!(
+                "Unknown action '{}' (use save, instantiate, list, get or delete)",
+                other
+            )),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::Standard
+    }
+}
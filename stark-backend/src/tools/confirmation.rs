@@ -0,0 +1,285 @@
+//! User confirmation of tool calls before they run
+//!
+//! Each channel can set a safety-level threshold (the `tool_confirmation`
+//! channel setting). A tool whose `ToolSafetyLevel` is below it — i.e. one
+//! that isn't cleared for that more restricted context — doesn't run until the
+//! user confirms: the tool loop records a pending request, broadcasts a
+//! `tool.confirmation_required` gateway event, sends a Confirm/Cancel card to
+//! the chat on channels with buttons, and waits for the decision from the
+//! button or `POST /api/tool-confirmations/{uuid}/decide`. Requests that go
+//! unanswered expire and the tool is not run. Every request and decision is
+//! kept in `tool_confirmations` as the audit trail. Parameter previews (see
+//! `tools::previews`) go through the same flow with a shorter card.
+
+use std::time::Duration as StdDuration;
+
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use tokio_util::sync::CancellationToken;
+
+use crate::channels::outbound;
+use crate::channels::types::{ActionButton, ActionEvent, NormalizedMessage};
+use crate::db::tables::tool_confirmations::{
+    ToolConfirmation, TOOL_CONFIRMATION_CONFIRMED, TOOL_CONFIRMATION_DECLINED, TOOL_CONFIRMATION_EXPIRED,
+    TOOL_CONFIRMATION_KIND_FULL, TOOL_CONFIRMATION_KIND_PREVIEW, TOOL_CONFIRMATION_PENDING,
+};
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::memory::redaction::redact_content;
+use crate::tools::types::{ToolGroup, ToolSafetyLevel};
+
+/// Button action that lets the tool call run
+pub const ACTION_CONFIRM_TOOL: &str = "confirm_tool";
+/// Button action that cancels the tool call
+pub const ACTION_DECLINE_TOOL: &str = "decline_tool";
+
+/// How long a request waits for an answer
+pub const CONFIRMATION_TTL_SECS: i64 = 300;
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(1);
+/// Arguments shown on the card and kept in the audit log
+const MAX_ARGUMENT_CHARS: usize = 1500;
+
+/// Whether a tool call needs confirmation under `threshold` (None = confirmation off).
+/// System tools (task management, ask_user, say_to_user...) never do.
+pub fn requires_confirmation(threshold: Option<ToolSafetyLevel>, level: ToolSafetyLevel, group: ToolGroup) -> bool {
+    match threshold {
+        Some(threshold) => group != ToolGroup::System && level < threshold,
+        None => false,
+    }
+}
+
+/// Outcome of a confirmation request
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmationOutcome {
+    Confirmed { by: String },
+    Declined { by: String },
+    Expired,
+    /// The current task was cancelled while waiting
+    Cancelled,
+}
+
+/// Arguments as shown to the user: pretty JSON, secrets redacted, truncated
+fn display_arguments(arguments: &Value) -> String {
+    let pretty = serde_json::to_string_pretty(arguments).unwrap_or_else(|_| arguments.to_string());
+    let mut text = redact_content(&pretty).content;
+    if text.chars().count() > MAX_ARGUMENT_CHARS {
+        text = text.chars().take(MAX_ARGUMENT_CHARS).collect::<String>() + "…";
+    }
+    text
+}
+
+/// What the user is asked to confirm
+pub enum Prompt<'a> {
+    /// Every argument, for a tool below the channel's threshold
+    Full(&'a Value),
+    /// The parameters that matter, for a tool with previews on
+    Preview { text: String, ttl_secs: i64 },
+}
+
+impl Prompt<'_> {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Prompt::Full(_) => TOOL_CONFIRMATION_KIND_FULL,
+            Prompt::Preview { .. } => TOOL_CONFIRMATION_KIND_PREVIEW,
+        }
+    }
+}
+
+fn preview_card(tool_name: &str, preview: &str, ttl_secs: i64) -> String {
+    format!(
+        "**Quick check** — `{}` is about to run with:\n```\n{}\n```\nConfirm within {}s or it won't run.",
+        tool_name, preview, ttl_secs
+    )
+}
+
+fn confirmation_card(tool_name: &str, arguments: &str) -> String {
+    format!(
+        "**Confirmation needed**\n\nThe agent wants to run `{}` with:\n```json\n{}\n```\nIt won't run unless you confirm. This request expires in {} min.",
+        tool_name,
+        arguments,
+        CONFIRMATION_TTL_SECS / 60
+    )
+}
+
+/// Ask the user to confirm a tool call and wait for the answer
+#[allow(clippy::too_many_arguments)]
+pub async fn request_and_wait(
+    db: &Database,
+    broadcaster: &EventBroadcaster,
+    message: &NormalizedMessage,
+    session_id: i64,
+    tool_name: &str,
+    prompt: Prompt<'_>,
+    level: ToolSafetyLevel,
+    cancel: Option<CancellationToken>,
+) -> Result<ConfirmationOutcome, String> {
+    let uuid = uuid::Uuid::new_v4().to_string();
+    let (shown, ttl_secs) = match &prompt {
+        Prompt::Full(arguments) => (display_arguments(arguments), CONFIRMATION_TTL_SECS),
+        Prompt::Preview { text, ttl_secs } => (text.clone(), *ttl_secs),
+    };
+    let confirmation = db
+        .create_tool_confirmation(
+            &uuid,
+            message.channel_id,
+            &message.chat_id,
+            Some(session_id),
+            tool_name,
+            &shown,
+            level.as_str(),
+            prompt.kind(),
+            Utc::now() + Duration::seconds(ttl_secs),
+        )
+        .map_err(|e| format!("Failed to record confirmation request: {}", e))?;
+
+    broadcaster.broadcast(GatewayEvent::custom(
+        "tool.confirmation_required",
+        json!({
+            "uuid": uuid,
+            "channel_id": message.channel_id,
+            "chat_id": message.chat_id,
+            "session_id": session_id,
+            "tool_name": tool_name,
+            "arguments": shown,
+            "safety_level": level.as_str(),
+            "kind": prompt.kind(),
+            "expires_at": confirmation.expires_at,
+        }),
+    ));
+
+    // Chat card with buttons where the channel supports them; the dashboard works everywhere
+    if matches!(message.channel_type.as_str(), "telegram" | "discord") {
+        let payload = Some(json!({ "uuid": uuid }));
+        let buttons = vec![
+            ActionButton { label: "Confirm".to_string(), action: ACTION_CONFIRM_TOOL.to_string(), payload: payload.clone() },
+            ActionButton { label: "Cancel".to_string(), action: ACTION_DECLINE_TOOL.to_string(), payload },
+        ];
+        let card = match prompt {
+            Prompt::Full(_) => confirmation_card(tool_name, &shown),
+            Prompt::Preview { .. } => preview_card(tool_name, &shown, ttl_secs),
+        };
+        if let Err(e) = outbound::send_direct(db, message.channel_id, &message.chat_id, &card, &buttons).await {
+            log::warn!("[TOOL_CONFIRM] Failed to send confirmation card for {}: {}", uuid, e);
+        }
+    }
+    log::info!(
+        "[TOOL_CONFIRM] Waiting for confirmation {} of '{}' in channel {} chat {}",
+        uuid, tool_name, message.channel_id, message.chat_id
+    );
+
+    let outcome = loop {
+        let sleep = tokio::time::sleep(POLL_INTERVAL);
+        match cancel {
+            Some(ref token) => tokio::select! {
+                _ = sleep => {}
+                _ = token.cancelled() => {
+                    let _ = db.decide_tool_confirmation(&uuid, TOOL_CONFIRMATION_DECLINED, "task cancelled");
+                    break ConfirmationOutcome::Cancelled;
+                }
+            },
+            None => sleep.await,
+        }
+
+        let current = db
+            .get_tool_confirmation(&uuid)
+            .map_err(|e| format!("Failed to load confirmation request: {}", e))?
+            .ok_or_else(|| "Confirmation request disappeared".to_string())?;
+        let decided_by = current.decided_by.clone().unwrap_or_default();
+        match current.status.as_str() {
+            TOOL_CONFIRMATION_CONFIRMED => break ConfirmationOutcome::Confirmed { by: decided_by },
+            TOOL_CONFIRMATION_DECLINED => break ConfirmationOutcome::Declined { by: decided_by },
+            TOOL_CONFIRMATION_EXPIRED => break ConfirmationOutcome::Expired,
+            _ if current.expires_at <= Utc::now().to_rfc3339() => {
+                if db.expire_tool_confirmation(&uuid).unwrap_or(false) {
+                    break ConfirmationOutcome::Expired;
+                }
+                // Decided between the read and the expiry: pick it up on the next pass
+            }
+            _ => {}
+        }
+    };
+
+    broadcaster.broadcast(GatewayEvent::custom(
+        "tool.confirmation_resolved",
+        json!({
+            "uuid": uuid,
+            "channel_id": message.channel_id,
+            "tool_name": tool_name,
+            "outcome": match &outcome {
+                ConfirmationOutcome::Confirmed { .. } => TOOL_CONFIRMATION_CONFIRMED,
+                ConfirmationOutcome::Declined { .. } | ConfirmationOutcome::Cancelled => TOOL_CONFIRMATION_DECLINED,
+                ConfirmationOutcome::Expired => TOOL_CONFIRMATION_EXPIRED,
+            },
+        }),
+    ));
+    log::info!("[TOOL_CONFIRM] Confirmation {} for '{}': {:?}", uuid, tool_name, outcome);
+    Ok(outcome)
+}
+
+/// Record a decision. Fails if the request is unknown, already decided, or expired.
+pub fn decide(db: &Database, uuid: &str, confirm: bool, decided_by: &str) -> Result<ToolConfirmation, String> {
+    let status = if confirm { TOOL_CONFIRMATION_CONFIRMED } else { TOOL_CONFIRMATION_DECLINED };
+    if !db.decide_tool_confirmation(uuid, status, decided_by).map_err(|e| e.to_string())? {
+        let current = db.get_tool_confirmation(uuid).map_err(|e| e.to_string())?;
+        return Err(match current {
+            None => format!("No confirmation request {}", uuid),
+            Some(c) if c.status == TOOL_CONFIRMATION_PENDING => "This request has expired".to_string(),
+            Some(c) => format!("This request was already {}", c.status),
+        });
+    }
+    log::info!("[TOOL_CONFIRM] Request {} {} by {}", uuid, status, decided_by);
+    db.get_tool_confirmation(uuid)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No confirmation request {}", uuid))
+}
+
+/// Handle a Confirm/Cancel button press. None if the action isn't a confirmation action.
+pub fn handle_action(db: &Database, message: &NormalizedMessage, event: &ActionEvent) -> Option<String> {
+    let confirm = match event.action.as_str() {
+        ACTION_CONFIRM_TOOL => true,
+        ACTION_DECLINE_TOOL => false,
+        _ => return None,
+    };
+    let uuid = match event.payload.as_ref().and_then(|p| p.get("uuid")).and_then(|u| u.as_str()) {
+        Some(uuid) => uuid.to_string(),
+        None => return Some("This confirmation button is missing its request.".to_string()),
+    };
+    let request = match db.get_tool_confirmation(&uuid) {
+        Ok(Some(request)) => request,
+        Ok(None) => return Some("No confirmation request found.".to_string()),
+        Err(e) => return Some(format!("Failed to load confirmation request: {}", e)),
+    };
+    if request.channel_id != message.channel_id || request.chat_id != message.chat_id {
+        return Some("This confirmation belongs to a different chat.".to_string());
+    }
+    if message.force_safe_mode {
+        log::warn!("[TOOL_CONFIRM] Non-admin {} tried to decide {}", message.user_name, uuid);
+        return Some("Only the owner can confirm tool calls.".to_string());
+    }
+
+    let decided_by = format!("{} ({})", message.user_name, message.user_id);
+    match decide(db, &uuid, confirm, &decided_by) {
+        Ok(_) if confirm => Some(format!("Confirmed — running `{}`.", request.tool_name)),
+        Ok(_) => Some(format!("Cancelled — `{}` will not run.", request.tool_name)),
+        Err(e) => Some(format!("{} — nothing changed.", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requires_confirmation_threshold() {
+        let read_only = Some(ToolSafetyLevel::ReadOnly);
+        assert!(requires_confirmation(read_only, ToolSafetyLevel::Standard, ToolGroup::Filesystem));
+        assert!(!requires_confirmation(read_only, ToolSafetyLevel::ReadOnly, ToolGroup::Filesystem));
+        assert!(!requires_confirmation(read_only, ToolSafetyLevel::Standard, ToolGroup::System));
+
+        let safe_mode = Some(ToolSafetyLevel::SafeMode);
+        assert!(requires_confirmation(safe_mode, ToolSafetyLevel::ReadOnly, ToolGroup::Web));
+        assert!(!requires_confirmation(safe_mode, ToolSafetyLevel::SafeMode, ToolGroup::Web));
+        assert!(!requires_confirmation(None, ToolSafetyLevel::Standard, ToolGroup::Exec));
+    }
+}
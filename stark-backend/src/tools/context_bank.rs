@@ -1,17 +1,26 @@
-//! Context Bank - extracts and stores key terms from user input
+//! Context Bank - extracts and stores key terms from user input and tool output
 //!
 //! Scans user messages for:
 //! - Ethereum wallet addresses (0x...)
-//! - Token symbols from config/tokens.ron
+//! - ENS names (vitalik.eth)
+//! - Token symbols from config/tokens.ron, and $TICKER cashtags
 //! - Network names from config/networks.ron
 //! - Numeric values (amounts, quantities, etc.)
 //! - URLs (especially GitHub URLs for repo references)
+//! - Dates (2025-03-14, March 14, 2025, 14 Mar 2025)
 //!
-//! These extracted terms are stored in the context bank and made available
-//! to the agent in the system context.
+//! Tool outputs are scanned for the same entities except numbers, config
+//! tokens and networks, which are too noisy in machine output. Every item
+//! records where it came from: `source` is None for the user's message and the
+//! tool name for tool output. Checks that guard against hallucinated values
+//! (verify_intent) only trust what the user said.
+//!
+//! These extracted terms are stored in the context bank, made available to the
+//! agent in the system context and mirrored into the `context_entities` register.
 
 use crate::tools::builtin::cryptocurrency::network_lookup::get_all_network_identifiers;
 use crate::tools::builtin::cryptocurrency::token_lookup::get_all_token_symbols;
+use crate::tools::RegisterStore;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -27,8 +36,29 @@ pub struct ContextBankItem {
     pub item_type: String,
     /// Optional additional info (e.g., token name for symbols)
     pub label: Option<String>,
+    /// Tool whose output the item was found in; None when the user wrote it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl ContextBankItem {
+    /// Whether the item came from the user's own message
+    pub fn is_from_user(&self) -> bool {
+        self.source.is_none()
+    }
+
+    fn same_entity(&self, other: &ContextBankItem) -> bool {
+        self.item_type == other.item_type && self.value.eq_ignore_ascii_case(&other.value)
+    }
 }
 
+/// Register the context bank's entities are mirrored into
+pub const CONTEXT_ENTITIES_REGISTER: &str = "context_entities";
+/// Longest stretch of a tool output that is scanned
+const MAX_TOOL_OUTPUT_SCAN: usize = 20_000;
+/// Most items taken from a single tool output
+const MAX_TOOL_OUTPUT_ITEMS: usize = 25;
+
 /// Context bank storage - thread-safe collection of detected terms
 #[derive(Debug, Clone)]
 pub struct ContextBank {
@@ -48,20 +78,51 @@ impl ContextBank {
         }
     }
 
-    /// Add an item to the context bank
+    /// Add an item to the context bank. An entity already in the bank keeps
+    /// its first provenance, so something the user said stays user-provided.
     pub fn add(&self, item: ContextBankItem) {
-        if let Ok(mut bank) = self.inner.write() {
-            bank.insert(item);
-        }
+        self.add_all(vec![item]);
     }
 
-    /// Add multiple items at once
-    pub fn add_all(&self, items: Vec<ContextBankItem>) {
-        if let Ok(mut bank) = self.inner.write() {
-            for item in items {
+    /// Add multiple items at once, returning how many were new
+    pub fn add_all(&self, items: Vec<ContextBankItem>) -> usize {
+        let Ok(mut bank) = self.inner.write() else {
+            return 0;
+        };
+        let mut added = 0;
+        for item in items {
+            if !bank.iter().any(|existing| existing.same_entity(&item)) {
                 bank.insert(item);
+                added += 1;
             }
         }
+        added
+    }
+
+    /// The item for an entity, if the bank holds it
+    pub fn find(&self, item_type: &str, value: &str) -> Option<ContextBankItem> {
+        self.inner.read().ok()?.iter().find(|i| i.item_type == item_type && i.value.eq_ignore_ascii_case(value)).cloned()
+    }
+
+    /// Mirror the bank into the `context_entities` register: entity type to a
+    /// list of `{value, label, source}`, so tools and templates can read them
+    pub fn sync_registers(&self, registers: &RegisterStore) {
+        let mut entities = serde_json::Map::new();
+        let mut items = self.items();
+        items.sort_by(|a, b| (&a.item_type, &a.value).cmp(&(&b.item_type, &b.value)));
+        for item in items {
+            let list = entities.entry(item.item_type.clone()).or_insert_with(|| serde_json::json!([]));
+            if let Some(list) = list.as_array_mut() {
+                list.push(serde_json::json!({
+                    "value": item.value,
+                    "label": item.label,
+                    "source": item.source.as_deref().unwrap_or("user"),
+                }));
+            }
+        }
+        if !entities.is_empty() {
+            registers.set(CONTEXT_ENTITIES_REGISTER, serde_json::Value::Object(entities), "context_bank");
+        }
     }
 
     /// Get all items in the context bank
@@ -141,6 +202,20 @@ impl ContextBank {
             parts.push(format!("Numbers: {}", number_list.join(", ")));
         }
 
+        for (item_type, heading) in [("ens_name", "ENS names"), ("ticker", "Tickers"), ("date", "Dates")] {
+            let values: Vec<_> = items
+                .iter()
+                .filter(|i| i.item_type == item_type)
+                .map(|i| match &i.label {
+                    Some(label) => format!("{} ({})", i.value, label),
+                    None => i.value.clone(),
+                })
+                .collect();
+            if !values.is_empty() {
+                parts.push(format!("{}: {}", heading, values.join(", ")));
+            }
+        }
+
         if parts.is_empty() {
             None
         } else {
@@ -186,6 +261,15 @@ static ETH_ADDR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"0x[a-fA-F0-9]{40}").
 static URL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"https?://[^\s<>\[\]()]+[^\s<>\[\]().,;:!?]").unwrap());
 static GITHUB_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"github\.com/([^/\s]+)/([^/\s?#]+)").unwrap());
 static NUMBER_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(\d{1,3}(?:,\d{3})*|\d+)(?:\.(\d+))?(k|m|b|mil|million|billion|bil|thousand)?\b").unwrap());
+static ENS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\.)+eth\b").unwrap());
+static CASHTAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$([A-Za-z][A-Za-z0-9]{1,9})\b").unwrap());
+static ISO_DATE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(\d{4})-(\d{2})-(\d{2})\b").unwrap());
+static MONTH_DAY_YEAR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(jan|feb|mar|apr|may|jun|jul|aug|sep|sept|oct|nov|dec)[a-z]*\.? (\d{1,2})(?:st|nd|rd|th)?,? (\d{4})\b").unwrap()
+});
+static DAY_MONTH_YEAR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(\d{1,2})(?:st|nd|rd|th)? (jan|feb|mar|apr|may|jun|jul|aug|sep|sept|oct|nov|dec)[a-z]*\.?,? (\d{4})\b").unwrap()
+});
 
 /// Pre-compiled token/network matchers — built once from config data
 static TOKEN_MATCHERS: Lazy<Vec<(Regex, String, String)>> = Lazy::new(|| {
@@ -219,6 +303,7 @@ pub fn scan_input(text: &str) -> Vec<ContextBankItem> {
             value: addr.to_lowercase(),
             item_type: "eth_address".to_string(),
             label: None,
+            source: None,
         });
     }
 
@@ -229,6 +314,7 @@ pub fn scan_input(text: &str) -> Vec<ContextBankItem> {
                 value: symbol.to_uppercase(),
                 item_type: "token_symbol".to_string(),
                 label: Some(name.clone()),
+                source: None,
             });
         }
     }
@@ -240,6 +326,7 @@ pub fn scan_input(text: &str) -> Vec<ContextBankItem> {
                 value: identifier.to_lowercase(),
                 item_type: "network".to_string(),
                 label: Some(name.clone()),
+                source: None,
             });
         }
     }
@@ -256,12 +343,14 @@ pub fn scan_input(text: &str) -> Vec<ContextBankItem> {
                     value: url.clone(),
                     item_type: "github_url".to_string(),
                     label: Some(format!("{}/{}", owner, repo)),
+                    source: None,
                 });
             } else {
                 items.push(ContextBankItem {
                     value: url,
                     item_type: "github_url".to_string(),
                     label: None,
+                    source: None,
                 });
             }
         } else {
@@ -269,6 +358,7 @@ pub fn scan_input(text: &str) -> Vec<ContextBankItem> {
                 value: url,
                 item_type: "url".to_string(),
                 label: None,
+                source: None,
             });
         }
     }
@@ -305,10 +395,13 @@ pub fn scan_input(text: &str) -> Vec<ContextBankItem> {
                 value,
                 item_type: "number".to_string(),
                 label: None,
+                source: None,
             });
         }
     }
 
+    scan_common(text, &mut items);
+
     // Deduplicate
     let mut seen = HashSet::new();
     items.retain(|item| {
@@ -319,6 +412,89 @@ pub fn scan_input(text: &str) -> Vec<ContextBankItem> {
     items
 }
 
+/// Scan a tool's output for addresses, ENS names, URLs, cashtags and dates.
+/// Items are attributed to `tool_name`.
+pub fn scan_tool_output(text: &str, tool_name: &str) -> Vec<ContextBankItem> {
+    let end = text
+        .char_indices()
+        .nth(MAX_TOOL_OUTPUT_SCAN)
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    let text = &text[..end];
+
+    let mut items: Vec<ContextBankItem> = ETH_ADDR_RE
+        .find_iter(text)
+        .map(|m| item("eth_address", m.as_str().to_lowercase(), None))
+        .collect();
+    items.extend(URL_RE.find_iter(text).map(|m| {
+        let url = m.as_str().to_string();
+        match GITHUB_RE.captures(&url).map(|c| format!("{}/{}", &c[1], &c[2])) {
+            Some(repo) => item("github_url", url, Some(repo)),
+            None => item("url", url, None),
+        }
+    }));
+    scan_common(text, &mut items);
+
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(format!("{}:{}", item.item_type, item.value.to_lowercase())));
+    items.truncate(MAX_TOOL_OUTPUT_ITEMS);
+    for item in &mut items {
+        item.source = Some(tool_name.to_string());
+    }
+    items
+}
+
+fn item(item_type: &str, value: String, label: Option<String>) -> ContextBankItem {
+    ContextBankItem {
+        value,
+        item_type: item_type.to_string(),
+        label,
+        source: None,
+    }
+}
+
+/// Entities scanned for in both user messages and tool output
+fn scan_common(text: &str, items: &mut Vec<ContextBankItem>) {
+    // ENS names; a name inside a URL (vitalik.eth.limo) is still the name
+    for m in ENS_RE.find_iter(text) {
+        items.push(item("ens_name", m.as_str().to_lowercase(), None));
+    }
+
+    // $TICKER cashtags that aren't already a known token symbol
+    for cap in CASHTAG_RE.captures_iter(text) {
+        let symbol = cap[1].to_uppercase();
+        if !items.iter().any(|i| i.item_type == "token_symbol" && i.value == symbol) {
+            items.push(item("ticker", symbol, None));
+        }
+    }
+
+    // Dates, normalized to YYYY-MM-DD with the original text as the label
+    for cap in ISO_DATE_RE.captures_iter(text) {
+        if let Some(date) = iso_date(&cap[1], &cap[2], &cap[3]) {
+            items.push(item("date", date, None));
+        }
+    }
+    for (re, month_group, day_group) in [(&MONTH_DAY_YEAR_RE, 1, 2), (&DAY_MONTH_YEAR_RE, 2, 1)] {
+        for cap in re.captures_iter(text) {
+            let month = month_number(&cap[month_group]);
+            if let Some(date) = iso_date(&cap[3], &month.to_string(), &cap[day_group]) {
+                items.push(item("date", date, Some(cap[0].to_string())));
+            }
+        }
+    }
+}
+
+fn month_number(name: &str) -> u32 {
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+    let prefix = name.to_lowercase();
+    MONTHS.iter().position(|m| prefix.starts_with(m)).map(|i| i as u32 + 1).unwrap_or(0)
+}
+
+fn iso_date(year: &str, month: &str, day: &str) -> Option<String> {
+    let date = chrono::NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)?;
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -458,6 +634,7 @@ mod tests {
             value: "0x123".to_string(),
             item_type: "eth_address".to_string(),
             label: None,
+            source: None,
         });
 
         assert_eq!(bank.len(), 1);
@@ -467,4 +644,29 @@ mod tests {
         assert!(formatted.is_some());
         assert!(formatted.unwrap().contains("0x123"));
     }
+
+    #[test]
+    fn test_scan_entities_and_provenance() {
+        let items = scan_input("Send $PEPE to vitalik.eth on March 14th, 2025 (or 2025-03-15)");
+        let values = |t: &str| items.iter().filter(|i| i.item_type == t).map(|i| i.value.clone()).collect::<Vec<_>>();
+        assert_eq!(values("ens_name"), vec!["vitalik.eth"]);
+        assert_eq!(values("ticker"), vec!["PEPE"]);
+        assert_eq!(values("date"), vec!["2025-03-15", "2025-03-14"]);
+
+        let bank = ContextBank::new();
+        bank.add_all(items);
+        // Tool output repeating a user entity doesn't take it over
+        let output = "Owner of vitalik.eth: 0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045, updated 2025-03-15";
+        let from_tool = scan_tool_output(output, "web_fetch");
+        assert!(from_tool.iter().all(|i| i.source.as_deref() == Some("web_fetch")));
+        assert_eq!(bank.add_all(from_tool), 1);
+        assert!(bank.find("ens_name", "vitalik.eth").unwrap().is_from_user());
+        assert!(!bank.find("eth_address", "0xd8da6bf26964af9d7eed9e03e53415d37aa96045").unwrap().is_from_user());
+
+        let registers = RegisterStore::new();
+        bank.sync_registers(&registers);
+        let entities = registers.get(CONTEXT_ENTITIES_REGISTER).unwrap();
+        assert_eq!(entities["eth_address"][0]["source"], "web_fetch");
+        assert_eq!(entities["ens_name"][0]["source"], "user");
+    }
 }
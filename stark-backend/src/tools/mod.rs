@@ -1,13 +1,20 @@
 pub mod builtin;
+pub mod confirmation;
 pub mod context_bank;
+pub mod payload;
 pub mod http_retry;
 pub mod presets;
+pub mod previews;
+pub mod read_only_mode;
+pub mod recovery;
 pub mod register;
 pub mod registry;
 pub mod rpc_config;
+pub mod rpc_pool;
+pub mod timeouts;
 pub mod types;
 
-pub use context_bank::{scan_input, ContextBank, ContextBankItem};
+pub use context_bank::{scan_input, scan_tool_output, ContextBank, ContextBankItem};
 pub use register::{PresetOrCustom, RegisterStore};
 pub use registry::{Tool, ToolRegistry};
 pub use types::{
@@ -24,14 +31,28 @@ fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(builtin::SubagentStatusTool::new()));
     registry.register(Arc::new(builtin::SetAgentSubtypeTool::new()));
     registry.register(Arc::new(builtin::UseSkillTool::new()));
+    registry.register(Arc::new(builtin::InvokeSkillTool::new()));
     registry.register(Arc::new(builtin::AskUserTool::new()));
     registry.register(Arc::new(builtin::SayToUserTool::new()));
+    registry.register(Arc::new(builtin::FetchFullOutputTool::new()));
+    // Read-only SQL over the q_* introspection views
+    registry.register(Arc::new(builtin::QueryDatabaseTool::new()));
+    // Skill-defined counters and metrics (also served by /api/analytics)
+    registry.register(Arc::new(builtin::IncrementCounterTool::new()));
+    registry.register(Arc::new(builtin::RecordMetricTool::new()));
+    registry.register(Arc::new(builtin::GetMetricTool::new()));
+    // Reusable parameterized plans (saved from a session, instantiated into the task queue)
+    registry.register(Arc::new(builtin::WorkflowTemplateTool::new()));
     // Memory tools (DB-backed unified memory system)
     registry.register(Arc::new(builtin::MemorySearchTool::new()));
     registry.register(Arc::new(builtin::MemoryReadTool::new()));
+    registry.register(Arc::new(builtin::IdentityProfileTool::new()));
+    // Durable key-value variables (persist across executions, unlike registers)
+    registry.register(Arc::new(builtin::KvStoreTool::new()));
     // Memory graph tools (associations + knowledge graph)
     registry.register(Arc::new(builtin::MemoryAssociateTool::new()));
     registry.register(Arc::new(builtin::MemoryGraphTool::new()));
+    registry.register(Arc::new(builtin::KnowledgeGraphTool::new()));
     registry.register(Arc::new(builtin::MemoryMergeTool::new()));
     // Notes tool (Obsidian-compatible notes with FTS5)
     registry.register(Arc::new(builtin::NotesTool::new()));
@@ -40,10 +61,14 @@ fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(builtin::ImportIdentityTool::new()));
     registry.register(Arc::new(builtin::UnregisterIdentityTool::new()));
     registry.register(Arc::new(builtin::IdentityPostRegisterTool::new()));
+    registry.register(Arc::new(builtin::AgentReputationTool::new()));
+    registry.register(Arc::new(builtin::RemoteAgentTool::new()));
     registry.register(Arc::new(builtin::ApiKeysCheckTool::new()));
     registry.register(Arc::new(builtin::TaskFullyCompletedTool::new()));
     registry.register(Arc::new(builtin::AddTaskTool::new()));
     registry.register(Arc::new(builtin::DefineTasksTool::new()));
+    registry.register(Arc::new(builtin::RequestClarificationTool::new()));
+    registry.register(Arc::new(builtin::RequestHumanTool::new()));
     registry.register(Arc::new(builtin::ManageSkillsTool::new()));
     registry.register(Arc::new(builtin::ReadSkillTool::new()));
     registry.register(Arc::new(builtin::ManageModulesTool::new()));
@@ -77,9 +102,26 @@ fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(builtin::SendEthTool::new()));
     registry.register(Arc::new(builtin::BroadcastWeb3TxTool::new()));
     registry.register(Arc::new(builtin::ListQueuedWeb3TxTool::new()));
+    // In-flight transactions: stuck detection, speed-up/cancel by replacement
+    registry.register(Arc::new(builtin::ManageTxTool::new()));
+    // Compose queued transactions into one multicall, or run them in nonce order
+    registry.register(Arc::new(builtin::BatchTxTool::new()));
     registry.register(Arc::new(builtin::Web3PresetFunctionCallTool::new()));
     registry.register(Arc::new(builtin::DecodeCalldataTool::new()));
+    // Human-readable transaction previews (function, amounts, approval warnings)
+    registry.register(Arc::new(builtin::DecodeTxTool::new()));
     registry.register(Arc::new(builtin::TokenLookupTool::new()));
+    // Approval hygiene: list live allowances, queue revocations
+    registry.register(Arc::new(builtin::TokenApprovalsTool::new()));
+    // Trade journal: swaps/transfers with execution-time prices, PnL reporting
+    registry.register(Arc::new(builtin::TradeJournalTool::new()));
+    // Paper trading: simulated fills ledger and PnL
+    registry.register(Arc::new(builtin::PaperTradingTool::new()));
+    // Safe multisig: propose to a shared treasury, track co-signer confirmations
+    registry.register(Arc::new(builtin::ProposeSafeTxTool::new()));
+    registry.register(Arc::new(builtin::SafeTxStatusTool::new()));
+    // EIP-712 signatures (permits, orders) behind a domain/spender policy
+    registry.register(Arc::new(builtin::SignTypedDataTool::new()));
     registry.register(Arc::new(builtin::ToRawAmountTool::new()));
     registry.register(Arc::new(builtin::FromRawAmountTool::new()));
     // Composite swap tool (token lookup + allowance + quote + execute in one call)
@@ -93,6 +135,8 @@ fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(builtin::SelectWeb3NetworkTool::new()));
     // Cross-chain USDC bridging via Across Protocol
     registry.register(Arc::new(builtin::BridgeUsdcTool::new()));
+    // Arrival tracking for queued bridges
+    registry.register(Arc::new(builtin::BridgeStatusTool::new()));
     // ERC-8128 signed HTTP requests (Ethereum identity)
     registry.register(Arc::new(builtin::Erc8128FetchTool::new()));
     // SIWA/SIWE authentication (Sign In With Agent/Ethereum)
@@ -103,6 +147,8 @@ fn register_all_tools(registry: &mut ToolRegistry) {
     // Filesystem tools (read-only, shared)
     registry.register(Arc::new(builtin::ReadFileTool::new()));
     registry.register(Arc::new(builtin::ListFilesTool::new()));
+    // PDF/XLSX reports stored as execution artifacts (shared — treasury reports, digests)
+    registry.register(Arc::new(builtin::GenerateReportTool::new()));
 
     // Development tools (code editing, git, search)
     registry.register(Arc::new(builtin::WriteFileTool::new()));
@@ -115,11 +161,13 @@ fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(builtin::GitTool::new()));
     registry.register(Arc::new(builtin::GithubUserTool::new()));
     registry.register(Arc::new(builtin::ReadSymbolTool::new()));
+    registry.register(Arc::new(builtin::SaveArtifactsTool::new()));
 
     // Advanced development tools (scoped commits, deployment, PR quality)
     registry.register(Arc::new(builtin::CommitterTool::new()));
     registry.register(Arc::new(builtin::DeployTool::new()));
     registry.register(Arc::new(builtin::PrQualityTool::new()));
+    registry.register(Arc::new(builtin::IssueTrackerTool::new()));
     // CodeEngineer boost: project indexing and verification
     registry.register(Arc::new(builtin::VerifyChangesTool::new()));
     registry.register(Arc::new(builtin::IndexProjectTool::new()));
@@ -137,8 +185,11 @@ fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(builtin::DiscordWriteTool::new()));
     registry.register(Arc::new(builtin::DiscordLookupTool::new()));
     registry.register(Arc::new(builtin::TwitterPostTool::new()));
+    registry.register(Arc::new(builtin::SocialCalendarTool::new()));
+    registry.register(Arc::new(builtin::FarcasterCastTool::new()));
     registry.register(Arc::new(builtin::TelegramReadTool::new()));
     registry.register(Arc::new(builtin::TelegramWriteTool::new()));
+    registry.register(Arc::new(builtin::GmailTool::new()));
 
     // Design tools
     registry.register(Arc::new(builtin::FigmaTool::new()));
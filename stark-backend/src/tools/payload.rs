@@ -0,0 +1,219 @@
+//! Typed tool result payloads
+//!
+//! A tool that produces data (rows, records, a single object) attaches it to
+//! its `ToolResult` as a [`ToolPayload`] instead of pasting JSON into the
+//! content string. The content stays a short human summary; the payload
+//! carries the data plus a hint on how to show it. From there:
+//! - the model sees the summary followed by the data as compact JSON
+//!   ([`ToolResult::content_for_model`](crate::tools::ToolResult::content_for_model))
+//! - the data is cached in the `<tool>_result` register, so the next tool can
+//!   read it instead of the agent re-typing values from prose
+//! - the `tool.result` event carries it as `payload`; channels render it with
+//!   [`ToolPayload::render`] (tables become native grids via `channels::format`)
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::channels::format::{self, RenderTarget};
+
+/// Rows shown when a table payload is rendered for people (the model gets all of them)
+const MAX_RENDERED_ROWS: usize = 25;
+/// Characters shown of one cell or value
+const MAX_CELL_CHARS: usize = 80;
+
+/// How a payload is best shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderHint {
+    /// Pretty-printed JSON
+    #[default]
+    Json,
+    /// `data` is an array of rows (objects, or arrays matching `columns`)
+    Table,
+    /// `data` is an object shown as `key: value` lines
+    KeyValue,
+    /// `data` is an array shown as bullets
+    List,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolPayload {
+    pub data: Value,
+    #[serde(default)]
+    pub render: RenderHint,
+    /// Column order for tables (taken from the first row when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl ToolPayload {
+    pub fn json(data: Value) -> Self {
+        ToolPayload { data, render: RenderHint::Json, columns: vec![], title: None }
+    }
+
+    /// Rows of objects, or of arrays in the order of `columns`
+    pub fn table(rows: Vec<Value>, columns: &[&str]) -> Self {
+        ToolPayload {
+            data: Value::Array(rows),
+            render: RenderHint::Table,
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            title: None,
+        }
+    }
+
+    pub fn key_value(data: Value) -> Self {
+        ToolPayload { data, render: RenderHint::KeyValue, columns: vec![], title: None }
+    }
+
+    pub fn list(items: Vec<Value>) -> Self {
+        ToolPayload { data: Value::Array(items), render: RenderHint::List, columns: vec![], title: None }
+    }
+
+    pub fn titled(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// The data as the model and downstream tools see it. Positional table
+    /// rows come with their column names.
+    pub fn structured(&self) -> Value {
+        let positional = self.data.as_array().is_some_and(|rows| rows.iter().any(Value::is_array));
+        if self.render == RenderHint::Table && positional && !self.columns.is_empty() {
+            serde_json::json!({ "columns": self.columns, "rows": self.data })
+        } else {
+            self.data.clone()
+        }
+    }
+
+    /// Canonical markdown for the payload (falls back to JSON when the data
+    /// doesn't have the shape the hint expects)
+    pub fn to_markdown(&self) -> String {
+        let body = match self.render {
+            RenderHint::Table => self.table_markdown(),
+            RenderHint::KeyValue => self.data.as_object().map(|object| {
+                object
+                    .iter()
+                    .map(|(key, value)| format!("- **{}:** {}", key, cell(value)))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }),
+            RenderHint::List => self
+                .data
+                .as_array()
+                .map(|items| items.iter().map(|item| format!("- {}", cell(item))).collect::<Vec<_>>().join("\n")),
+            RenderHint::Json => None,
+        }
+        .unwrap_or_else(|| {
+            format!("```json\n{}\n```", serde_json::to_string_pretty(&self.data).unwrap_or_default())
+        });
+        match &self.title {
+            Some(title) => format!("**{}**\n\n{}", title, body),
+            None => body,
+        }
+    }
+
+    /// The payload formatted for a destination
+    pub fn render(&self, target: RenderTarget) -> String {
+        format::render(&self.to_markdown(), target)
+    }
+
+    fn table_markdown(&self) -> Option<String> {
+        let rows = self.data.as_array()?;
+        let columns: Vec<String> = if self.columns.is_empty() {
+            rows.first()?.as_object()?.keys().cloned().collect()
+        } else {
+            self.columns.clone()
+        };
+        if columns.is_empty() {
+            return None;
+        }
+
+        let mut lines = vec![
+            format!("| {} |", columns.join(" | ")),
+            format!("|{}|", vec!["---"; columns.len()].join("|")),
+        ];
+        for row in rows.iter().take(MAX_RENDERED_ROWS) {
+            let cells: Vec<String> = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| match row {
+                    Value::Object(object) => object.get(column).map(cell).unwrap_or_default(),
+                    Value::Array(values) => values.get(i).map(cell).unwrap_or_default(),
+                    other if i == 0 => cell(other),
+                    _ => String::new(),
+                })
+                .collect();
+            lines.push(format!("| {} |", cells.join(" | ")));
+        }
+        let mut table = lines.join("\n");
+        if rows.len() > MAX_RENDERED_ROWS {
+            table.push_str(&format!("\n\n…and {} more row(s)", rows.len() - MAX_RENDERED_ROWS));
+        }
+        Some(table)
+    }
+}
+
+/// One value as table/list text: strings unquoted, null empty, on one line
+fn cell(value: &Value) -> String {
+    let text = match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let text = text.replace('|', "\\|").replace('\n', " ");
+    if text.chars().count() > MAX_CELL_CHARS {
+        text.chars().take(MAX_CELL_CHARS).collect::<String>() + "…"
+    } else {
+        text
+    }
+}
+
+/// What a channel shows for a `tool.result` event: the content, followed by
+/// the payload rendered for `target` when the event has one
+pub fn event_display(data: &Value, target: RenderTarget) -> String {
+    let content = data.get("content").and_then(|v| v.as_str()).unwrap_or("");
+    let payload = data
+        .get("payload")
+        .filter(|p| !p.is_null())
+        .and_then(|p| serde_json::from_value::<ToolPayload>(p.clone()).ok());
+    match payload {
+        Some(payload) if content.is_empty() => payload.render(target),
+        Some(payload) => format!("{}\n{}", content, payload.render(target)),
+        None => content.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_payload_rendering() {
+        let rows = ToolPayload::table(vec![json!(["discord", 12]), json!(["telegram", null])], &["channel", "messages"]);
+        assert_eq!(
+            rows.to_markdown(),
+            "| channel | messages |\n|---|---|\n| discord | 12 |\n| telegram |  |"
+        );
+        assert_eq!(
+            rows.structured(),
+            json!({ "columns": ["channel", "messages"], "rows": [["discord", 12], ["telegram", null]] })
+        );
+
+        let objects = ToolPayload::table(vec![json!({ "a": "x|y", "b": 1 })], &[]).titled("Rows");
+        assert_eq!(objects.to_markdown(), "**Rows**\n\n| a | b |\n|---|---|\n| x\\|y | 1 |");
+        assert_eq!(objects.structured(), json!([{ "a": "x|y", "b": 1 }]));
+
+        let pair = ToolPayload::key_value(json!({ "status": "ok", "count": 2 }));
+        assert_eq!(pair.to_markdown(), "- **count:** 2\n- **status:** ok");
+        assert_eq!(ToolPayload::list(vec![json!("a"), json!(1)]).to_markdown(), "- a\n- 1");
+        // A hint that doesn't fit the data falls back to JSON
+        assert!(ToolPayload::key_value(json!([1])).to_markdown().starts_with("```json"));
+
+        let event = json!({ "content": "2 row(s)", "payload": rows });
+        assert!(event_display(&event, RenderTarget::Plain).starts_with("2 row(s)\n"));
+        assert_eq!(event_display(&json!({ "content": "done" }), RenderTarget::Plain), "done");
+    }
+}
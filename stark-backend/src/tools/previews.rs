@@ -0,0 +1,147 @@
+//! Parameter previews: a quick confirm before chosen tools run
+//!
+//! Lighter than the channel-wide `tool_confirmation` threshold: the owner
+//! picks individual tools (say `twitter_post`, `gmail`, `committer`), and
+//! each call of one of them first shows a compact preview of the parameters
+//! that matter — the tweet text, the recipients, the commit message — as a
+//! `tool.confirmation_required` event of kind `preview` (plus a card with
+//! buttons on Telegram/Discord). It runs once confirmed; a preview left
+//! unanswered expires quickly and the call is dropped. Only standard-level
+//! tools can have previews; read-only ones can't misfire into anything.
+//! Settings live in the kv_store and are changed with
+//! `/api/tool-confirmations/previews`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::Database;
+use crate::memory::redaction::redact_content;
+use crate::tools::types::ToolSafetyLevel;
+
+/// kv_store namespace for the settings (outside what the kv_store tool can reach)
+const NAMESPACE: &str = "tool_previews";
+const SETTINGS_KEY: &str = "settings";
+
+/// Seconds a preview waits for a confirm unless the tool sets its own
+pub const DEFAULT_TIMEOUT_SECS: i64 = 120;
+const MIN_TIMEOUT_SECS: i64 = 15;
+const MAX_TIMEOUT_SECS: i64 = 900;
+/// Characters shown of one parameter, and of the whole preview
+const MAX_VALUE_CHARS: usize = 280;
+const MAX_PREVIEW_CHARS: usize = 1000;
+
+/// Parameters previewed when a tool doesn't list its own, in this order
+const DEFAULT_FIELDS: &[&str] = &[
+    "action", "to", "cc", "channel", "reply_to", "subject", "title", "message", "text", "content", "body",
+    "files", "push", "command", "url", "amount",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PreviewRule {
+    /// Parameters to show (the common ones when empty)
+    #[serde(default)]
+    pub fields: Vec<String>,
+    /// How long the preview waits for a confirm
+    #[serde(default)]
+    pub timeout_secs: Option<i64>,
+}
+
+impl PreviewRule {
+    pub fn timeout_secs(&self) -> i64 {
+        self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS).clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PreviewSettings {
+    /// Tools that are previewed, by name
+    #[serde(default)]
+    pub tools: BTreeMap<String, PreviewRule>,
+}
+
+pub fn load(db: &Database) -> PreviewSettings {
+    db.kv_get(NAMESPACE, SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|entry| serde_json::from_value(entry.value).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(db: &Database, settings: &PreviewSettings) -> Result<(), String> {
+    let value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    db.kv_set(NAMESPACE, SETTINGS_KEY, &value, None).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// The preview rule for a call of this tool, if it gets one
+pub fn rule_for(db: &Database, tool_name: &str, level: ToolSafetyLevel) -> Option<PreviewRule> {
+    if level != ToolSafetyLevel::Standard {
+        return None;
+    }
+    load(db).tools.remove(tool_name)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() > max {
+        text.chars().take(max).collect::<String>() + "…"
+    } else {
+        text.to_string()
+    }
+}
+
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(render_value).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// The parameters that matter, one `name: value` line each, secrets redacted.
+/// Falls back to the compact JSON when none of the fields are present.
+pub fn compact_preview(arguments: &Value, fields: &[String]) -> String {
+    let names: Vec<&str> = if fields.is_empty() {
+        DEFAULT_FIELDS.to_vec()
+    } else {
+        fields.iter().map(String::as_str).collect()
+    };
+    let lines: Vec<String> = names
+        .iter()
+        .filter_map(|name| {
+            let value = arguments.get(name).filter(|v| !v.is_null())?;
+            Some(format!("{}: {}", name, truncate(&render_value(value), MAX_VALUE_CHARS)))
+        })
+        .collect();
+    let text = if lines.is_empty() { arguments.to_string() } else { lines.join("\n") };
+    truncate(&redact_content(&text).content, MAX_PREVIEW_CHARS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_preview() {
+        let email = serde_json::json!({
+            "action": "send",
+            "to": ["ann@example.com", "bob@example.com"],
+            "subject": "Q3 numbers",
+            "body": "See attached.",
+            "draft": null,
+        });
+        assert_eq!(
+            compact_preview(&email, &[]),
+            "action: send\nto: ann@example.com, bob@example.com\nsubject: Q3 numbers\nbody: See attached."
+        );
+        assert_eq!(compact_preview(&email, &["to".to_string()]), "to: ann@example.com, bob@example.com");
+
+        let tweet = serde_json::json!({ "text": "a".repeat(400) });
+        assert_eq!(compact_preview(&tweet, &[]).chars().count(), "text: ".len() + MAX_VALUE_CHARS + 1);
+        assert_eq!(compact_preview(&serde_json::json!({ "id": 7 }), &[]), "{\"id\":7}");
+
+        let rule = PreviewRule { fields: vec![], timeout_secs: Some(1) };
+        assert_eq!(rule.timeout_secs(), MIN_TIMEOUT_SECS);
+        assert_eq!(PreviewRule::default().timeout_secs(), DEFAULT_TIMEOUT_SECS);
+    }
+}
@@ -0,0 +1,72 @@
+//! Read-only mirror mode
+//!
+//! A global switch (`bot_settings.read_only_mode`) for demoing the bot in
+//! public against production data. While it's on, every tool that can change
+//! something — file writes, git, transactions, posts, settings — is refused
+//! with an explanation, and lookups keep working. A tool counts as mutating
+//! when its safety level is `Standard` (not cleared for read-only subagents),
+//! except for the Standard tools below that only look things up or touch
+//! per-execution state. The same switch gates mutating HTTP requests
+//! (`middleware::read_only_mode`), transaction confirmation over the
+//! WebSocket, strategy runs and scheduled social posts.
+
+use crate::db::Database;
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolResult, ToolSafetyLevel};
+
+/// Standard-level tools that don't change anything outside the current execution
+const READ_ONLY_MODE_ALLOWED: &[&str] = &[
+    "add_task",
+    "decode_calldata",
+    "from_raw_amount",
+    "get_metric",
+    "list_queued_web3_tx",
+    "process_status",
+    "query_database",
+    "read_operating_mode",
+    "read_recent_transactions",
+    "read_skill",
+    "select_web3_network",
+    "set_address",
+    "to_raw_amount",
+    "verify_tx_broadcast",
+];
+
+/// Whether read-only mirror mode is on (bot settings are cached, so this is cheap)
+pub fn is_enabled(db: &Database) -> bool {
+    db.get_bot_settings().map(|s| s.read_only_mode).unwrap_or(false)
+}
+
+/// Whether read-only mode refuses this tool
+pub fn blocks(name: &str, level: ToolSafetyLevel) -> bool {
+    level == ToolSafetyLevel::Standard && !READ_ONLY_MODE_ALLOWED.contains(&name)
+}
+
+/// Refuse a tool call if read-only mode is on and the tool can mutate state
+pub fn check(db: &Database, tool: &dyn Tool) -> Option<ToolResult> {
+    let name = tool.definition().name;
+    if !blocks(&name, tool.safety_level()) || !is_enabled(db) {
+        return None;
+    }
+    log::info!("[READ_ONLY_MODE] Refused '{}'", name);
+    Some(ToolResult::error(format!(
+        "'{}' is disabled: this bot is running in read-only mirror mode (a public demo), so \
+         nothing can be written, committed, posted or sent. Lookups still work — tell the user \
+         what you would have done instead.",
+        name
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_mutating_tools_only() {
+        assert!(blocks("write_file", ToolSafetyLevel::Standard));
+        assert!(blocks("twitter_post", ToolSafetyLevel::Standard));
+        assert!(!blocks("query_database", ToolSafetyLevel::Standard));
+        assert!(!blocks("read_file", ToolSafetyLevel::ReadOnly));
+        assert!(!blocks("say_to_user", ToolSafetyLevel::SafeMode));
+    }
+}
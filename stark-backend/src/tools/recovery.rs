@@ -0,0 +1,171 @@
+//! Tool error recovery in the Perform loop
+//!
+//! What the tool loop does with a failed call before the failure reaches the
+//! agent (and through it, the chat). The channel's `tool_error_recovery`
+//! setting picks the strategies:
+//! - **retry**: transient failures (timeouts, connection errors, 5xx, 429) are
+//!   run again with exponential backoff, waiting at least the tool's own
+//!   `retry_after_secs`. Only tools that asked for a retry or can't change
+//!   anything (read-only) are retried, so a half-sent transaction never goes out twice.
+//! - **repair**: a call rejected for bad parameters comes back to the agent as
+//!   a repair prompt carrying the tool's schema, and the failure isn't posted to the chat.
+//! - **skip**: a failure in a task the planner marked optional cancels that task
+//!   with a note and the loop moves on to the next one.
+
+use crate::db::Database;
+use crate::models::ChannelSettingKey;
+use crate::tools::http_retry::HttpRetryManager;
+use crate::tools::types::{ToolDefinition, ToolResult};
+
+/// Extra attempts for a transient failure
+pub const MAX_RETRIES: u32 = 3;
+/// Backoff before the first retry; doubles with every attempt
+const BASE_BACKOFF_SECS: u64 = 2;
+/// Longest wait between attempts, whatever the tool asks for
+const MAX_BACKOFF_SECS: u64 = 60;
+/// Repair prompts per turn before failures go to the agent as they are
+pub const MAX_REPAIRS_PER_TURN: u32 = 3;
+
+/// Which recovery strategies a channel uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryMode {
+    /// Retry, repair and skip (the default)
+    Full,
+    /// Only retry transient failures
+    RetryOnly,
+    /// Hand every failure to the agent as it is
+    Off,
+}
+
+impl RecoveryMode {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_lowercase()).as_deref() {
+            Some("retry") => RecoveryMode::RetryOnly,
+            Some("off") => RecoveryMode::Off,
+            _ => RecoveryMode::Full,
+        }
+    }
+
+    /// The mode set by the channel's `tool_error_recovery` setting
+    pub fn for_channel(db: &Database, channel_id: i64) -> Self {
+        let value = db
+            .get_channel_setting(channel_id, ChannelSettingKey::ToolErrorRecovery.as_ref())
+            .ok()
+            .flatten();
+        Self::from_setting(value.as_deref())
+    }
+
+    pub fn retries(&self) -> bool {
+        !matches!(self, RecoveryMode::Off)
+    }
+
+    pub fn repairs(&self) -> bool {
+        matches!(self, RecoveryMode::Full)
+    }
+
+    pub fn skips_optional(&self) -> bool {
+        matches!(self, RecoveryMode::Full)
+    }
+}
+
+/// What kind of failure a tool result is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// Likely to succeed if tried again shortly
+    Transient,
+    /// The call itself was wrong (missing or malformed parameters)
+    Validation,
+    Other,
+}
+
+const VALIDATION_MARKERS: &[&str] = &[
+    "invalid parameters",
+    "missing required",
+    "missing or invalid",
+    "missing field",
+    "invalid type:",
+    "unknown variant",
+    "unknown field",
+];
+
+/// Classify a failed result
+pub fn classify(result: &ToolResult) -> FailureKind {
+    if result.retry_after_secs.is_some() {
+        return FailureKind::Transient;
+    }
+    let text = result.error.as_deref().unwrap_or(&result.content);
+    let lower = text.to_lowercase();
+    if VALIDATION_MARKERS.iter().any(|m| lower.contains(m)) {
+        return FailureKind::Validation;
+    }
+    // An open circuit already says when to come back; hammering it won't help
+    if !lower.contains("circuit open") && HttpRetryManager::is_retryable_error(&lower) {
+        return FailureKind::Transient;
+    }
+    FailureKind::Other
+}
+
+/// Seconds to wait before retry number `attempt` (starting at 0)
+pub fn backoff_secs(attempt: u32, retry_after: Option<u64>) -> u64 {
+    let exponential = BASE_BACKOFF_SECS.saturating_mul(1u64 << attempt.min(6));
+    exponential.max(retry_after.unwrap_or(0)).min(MAX_BACKOFF_SECS)
+}
+
+/// Result for a transient failure that kept failing
+pub fn gave_up(result: ToolResult, attempts: u32) -> ToolResult {
+    let error = result.error.clone().unwrap_or_else(|| result.content.clone());
+    ToolResult {
+        content: format!("{}\n\n(Still failing after {} attempts with backoff.)", error, attempts),
+        retry_after_secs: None,
+        ..result
+    }
+}
+
+/// What the agent sees instead of a validation failure
+pub fn repair_prompt(definition: &ToolDefinition, error: &str) -> String {
+    let schema = serde_json::to_string_pretty(&definition.input_schema).unwrap_or_default();
+    format!(
+        "The call to '{}' was rejected because of its parameters: {}\n\n\
+         Call '{}' again with corrected parameters. Expected input schema:\n```json\n{}\n```",
+        definition.name, error, definition.name, schema
+    )
+}
+
+/// Note left when an optional task is skipped
+pub fn skip_note(task_id: u32, task: &str, tool_name: &str, error: &str) -> String {
+    let error: String = error.chars().take(300).collect();
+    format!(
+        "Skipped optional task {} ({}) after '{}' failed: {}",
+        task_id, task, tool_name, error
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_backoff_and_modes() {
+        assert_eq!(classify(&ToolResult::retryable_error("rpc down", 10)), FailureKind::Transient);
+        assert_eq!(classify(&ToolResult::error("Request failed: HTTP 503")), FailureKind::Transient);
+        assert_eq!(
+            classify(&ToolResult::error("Invalid parameters: missing field `network`")),
+            FailureKind::Validation
+        );
+        assert_eq!(
+            classify(&ToolResult::error("Alchemy is temporarily unavailable (circuit open, retrying in 40s)")),
+            FailureKind::Other
+        );
+        assert_eq!(classify(&ToolResult::error("Insufficient balance")), FailureKind::Other);
+
+        assert_eq!(backoff_secs(0, None), 2);
+        assert_eq!(backoff_secs(2, None), 8);
+        assert_eq!(backoff_secs(0, Some(15)), 15);
+        assert_eq!(backoff_secs(10, Some(600)), MAX_BACKOFF_SECS);
+
+        assert_eq!(RecoveryMode::from_setting(None), RecoveryMode::Full);
+        assert_eq!(RecoveryMode::from_setting(Some("retry")), RecoveryMode::RetryOnly);
+        assert!(!RecoveryMode::from_setting(Some("off")).retries());
+        assert!(!RecoveryMode::RetryOnly.repairs());
+    }
+}
@@ -259,6 +259,30 @@ impl ToolRegistry {
             return ToolResult::error(format!("Tool '{}' is not allowed", name));
         }
 
+        // Finance tools run only with the grants of the active skill
+        if let Some(skill_name) = context.active_skill.as_deref() {
+            if let Err(e) = crate::skills::permissions::check_active_skill(
+                context.database.as_deref(),
+                skill_name,
+                name,
+                tool.group(),
+            ) {
+                log::warn!("[SKILL] Blocked '{}' for skill '{}': {}", name, skill_name, e);
+                return ToolResult::error(e);
+            }
+        }
+
+        // Read-only mirror mode refuses anything that can mutate state
+        if let Some(db) = context.database.as_ref() {
+            if let Some(refused) = super::read_only_mode::check(db, tool.as_ref()) {
+                return refused;
+            }
+            // While the owner is away, autonomous runs only get the allow-listed actions
+            if let Some(refused) = crate::away::check(db, context, tool.as_ref()) {
+                return refused;
+            }
+        }
+
         // Execute the tool
         tool.execute(params, context).await
     }
@@ -403,6 +427,7 @@ mod tests {
         registry.register(Arc::new(MockTool::new("discord_lookup", ToolGroup::Messaging)));
         registry.register(Arc::new(MockTool::new("telegram_read", ToolGroup::Messaging)));
         registry.register(Arc::new(MockTool::new("define_tasks", ToolGroup::System)));
+        registry.register(Arc::new(MockTool::new("request_human", ToolGroup::System)));
         registry
     }
 
@@ -830,4 +855,73 @@ mod tests {
         // Allowed groups must be only "web"
         assert_eq!(config.allowed_groups, vec!["web".to_string()]);
     }
+
+    #[tokio::test]
+    async fn test_execute_refuses_mutating_tools_in_read_only_mode() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(MockTool::new("write_file", ToolGroup::Development)));
+        registry.register(Arc::new(MockTool::new("query_database", ToolGroup::System)));
+        let config = ToolConfig {
+            profile: crate::tools::types::ToolProfile::Full,
+            ..Default::default()
+        };
+        let db = Arc::new(crate::db::Database::new(":memory:").unwrap());
+        let context = ToolContext::new().with_database(db.clone());
+
+        db.update_read_only_mode(true).unwrap();
+        let refused = registry.execute("write_file", serde_json::json!({}), &context, Some(&config)).await;
+        assert!(!refused.success);
+        assert!(refused.content.contains("read-only mirror mode"), "{}", refused.content);
+        assert!(registry.execute("query_database", serde_json::json!({}), &context, Some(&config)).await.success);
+
+        db.update_read_only_mode(false).unwrap();
+        assert!(registry.execute("write_file", serde_json::json!({}), &context, Some(&config)).await.success);
+    }
+
+    #[tokio::test]
+    async fn test_execute_enforces_active_skill_finance_grants() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(MockTool::new("send_eth", ToolGroup::Finance)));
+        let config = ToolConfig {
+            profile: crate::tools::types::ToolProfile::Full,
+            ..Default::default()
+        };
+        let db = Arc::new(crate::db::Database::new(":memory:").unwrap());
+        let skill: crate::skills::DbSkill = serde_json::from_value(serde_json::json!({
+            "name": "hub_tipper",
+            "description": "",
+            "body": "",
+            "version": "1.0.0",
+            "author": null,
+            "homepage": null,
+            "metadata": null,
+            "enabled": true,
+            "requires_tools": ["send_eth"],
+            "requires_binaries": [],
+            "arguments": {},
+            "tags": [],
+            "requires_api_keys": {},
+            "created_at": "",
+            "updated_at": "",
+        }))
+        .unwrap();
+        db.create_skill(&skill).unwrap();
+
+        // Sub-agents and invoked skills call execute directly: the check happens here
+        let context = ToolContext::new()
+            .with_database(db.clone())
+            .with_active_skill(Some("hub_tipper".to_string()));
+        let result = registry.execute("send_eth", serde_json::json!({}), &context, Some(&config)).await;
+        assert!(!result.success);
+        assert!(result.content.contains("hasn't granted"), "{}", result.content);
+
+        db.set_skill_permission("hub_tipper", "send", true).unwrap();
+        assert!(registry.execute("send_eth", serde_json::json!({}), &context, Some(&config)).await.success);
+
+        // Without a database, or for a skill that isn't installed, finance tools are refused
+        let no_db = ToolContext::new().with_active_skill(Some("hub_tipper".to_string()));
+        assert!(!registry.execute("send_eth", serde_json::json!({}), &no_db, Some(&config)).await.success);
+        let unknown = context.clone().with_active_skill(Some("gone".to_string()));
+        assert!(!registry.execute("send_eth", serde_json::json!({}), &unknown, Some(&config)).await.success);
+    }
 }
@@ -83,20 +83,19 @@ impl Default for Network {
 /// Global storage for RPC providers
 static RPC_PROVIDERS: OnceLock<HashMap<String, RpcProvider>> = OnceLock::new();
 
-/// Global storage for Alchemy API key (loaded from DB at startup)
-static ALCHEMY_API_KEY: OnceLock<String> = OnceLock::new();
+/// Global storage for Alchemy API key (loaded from DB at startup).
+/// Uses RwLock because the key can be replaced or removed at runtime via the API keys page.
+static ALCHEMY_API_KEY: RwLock<Option<String>> = RwLock::new(None);
 
 /// Store the Alchemy API key for use by the unified RPC resolver.
-/// Called once at startup after loading the key from DB / env.
-pub fn set_alchemy_api_key(key: String) {
-    if ALCHEMY_API_KEY.set(key).is_err() {
-        log::warn!("[rpc_config] Alchemy API key already set");
-    }
+/// Called at startup and whenever the ALCHEMY_API_KEY api key changes; `None` clears it.
+pub fn set_alchemy_api_key(key: Option<String>) {
+    *ALCHEMY_API_KEY.write().unwrap_or_else(|e| e.into_inner()) = key.filter(|k| !k.is_empty());
 }
 
 /// Get the stored Alchemy API key, if any.
-pub fn get_alchemy_api_key() -> Option<&'static String> {
-    ALCHEMY_API_KEY.get()
+pub fn get_alchemy_api_key() -> Option<String> {
+    ALCHEMY_API_KEY.read().unwrap_or_else(|e| e.into_inner()).clone()
 }
 
 /// Global storage for user-configured custom RPC endpoints (from bot_settings).
@@ -118,7 +117,7 @@ pub fn set_custom_rpc_endpoints(endpoints: HashMap<String, String>) {
 }
 
 /// Get a custom RPC endpoint for a network, if configured.
-fn custom_rpc_url(network: &str) -> Option<String> {
+pub(crate) fn custom_rpc_url(network: &str) -> Option<String> {
     CUSTOM_RPC_ENDPOINTS.read().unwrap_or_else(|e| e.into_inner())
         .as_ref()
         .and_then(|endpoints| endpoints.get(network).cloned())
@@ -139,8 +138,19 @@ fn alchemy_url(network: &str, key: &str) -> Option<String> {
     Some(format!("https://{}.g.alchemy.com/v2/{}", subdomain, key))
 }
 
+/// Whether an RPC URL points at Alchemy (calls to it count toward Alchemy's circuit breaker).
+pub fn is_alchemy_url(url: &str) -> bool {
+    url.contains(".g.alchemy.com/")
+}
+
+/// Alchemy RPC URL for a network using the stored API key.
+/// Needed by callers that rely on Alchemy-specific behaviour (e.g. unbounded `eth_getLogs` ranges).
+pub fn alchemy_rpc_url(network: &str) -> Option<String> {
+    alchemy_url(network, &get_alchemy_api_key()?)
+}
+
 /// Best free public RPC URL per network (last resort).
-fn public_rpc_url(network: &str) -> Option<&'static str> {
+pub(crate) fn public_rpc_url(network: &str) -> Option<&'static str> {
     match network {
         "base" => Some("https://mainnet.base.org"),
         "mainnet" => Some("https://eth.llamarpc.com"),
@@ -153,7 +163,7 @@ fn public_rpc_url(network: &str) -> Option<&'static str> {
 }
 
 /// DeFi Relay x402 URL for a network.
-fn defirelay_url(network: &str) -> String {
+pub(crate) fn defirelay_url(network: &str) -> String {
     if let Some((url, _)) = get_rpc_endpoint("defirelay", network) {
         url
     } else {
@@ -161,57 +171,32 @@ fn defirelay_url(network: &str) -> String {
     }
 }
 
-/// Canonical RPC resolution: Custom → Alchemy → DeFi Relay (x402).
+/// Canonical RPC resolution: the healthiest, fastest endpoint of the
+/// network's pool (user endpoints / custom → Alchemy → DeFi Relay (x402)).
 ///
 /// Use this for codepaths that go through X402EvmRpc (which handles 402 responses).
 pub fn resolve_rpc(network: &str) -> ResolvedRpcConfig {
-    // Tier 0: User-configured custom endpoint (from bot_settings)
-    if let Some(url) = custom_rpc_url(network) {
-        log::info!("[rpc_config] Custom endpoint for {}: {}", network, url);
-        return ResolvedRpcConfig { url, use_x402: false };
-    }
-
-    // Tier 1: Alchemy (free, no x402)
-    if let Some(key) = get_alchemy_api_key() {
-        if let Some(url) = alchemy_url(network, key) {
-            log::info!("[rpc_config] Tier 1 (Alchemy) for {}: {}", network, &url[..url.len().min(60)]);
-            return ResolvedRpcConfig { url, use_x402: false };
-        }
-    }
-
-    // Tier 2: DeFi Relay (x402 paid RPC)
-    let url = defirelay_url(network);
-    log::info!("[rpc_config] Tier 2 (DeFi Relay) for {}: {}", network, url);
-    ResolvedRpcConfig { url, use_x402: true }
+    let resolved = super::rpc_pool::select(network, false);
+    log::info!(
+        "[rpc_config] Pool endpoint for {}: {} (x402={})",
+        network,
+        super::rpc_pool::mask_url(&resolved.url),
+        resolved.use_x402
+    );
+    resolved
 }
 
 /// Read-only RPC resolution for raw HTTP callers that can't handle x402 402-responses.
-/// Priority: Custom → Alchemy → Public → DeFi Relay.
+/// Picks from the pool with the public RPC included, so DeFi Relay is used
+/// only when every free endpoint is cooling down.
 pub fn resolve_rpc_readonly(network: &str) -> ResolvedRpcConfig {
-    // Tier 0: User-configured custom endpoint (from bot_settings)
-    if let Some(url) = custom_rpc_url(network) {
-        log::info!("[rpc_config] Custom endpoint readonly for {}: {}", network, url);
-        return ResolvedRpcConfig { url, use_x402: false };
-    }
-
-    // Tier 1: Alchemy (free, no x402)
-    if let Some(key) = get_alchemy_api_key() {
-        if let Some(url) = alchemy_url(network, key) {
-            log::info!("[rpc_config] Tier 1 (Alchemy) readonly for {}: {}", network, &url[..url.len().min(60)]);
-            return ResolvedRpcConfig { url, use_x402: false };
-        }
-    }
-
-    // Tier 2: Public RPC (free, no x402)
-    if let Some(url) = public_rpc_url(network) {
-        log::info!("[rpc_config] Tier 2 (Public) readonly for {}: {}", network, url);
-        return ResolvedRpcConfig { url: url.to_string(), use_x402: false };
+    let resolved = super::rpc_pool::select(network, true);
+    if resolved.use_x402 {
+        log::warn!("[rpc_config] DeFi Relay readonly fallback for {} — caller may not handle x402", network);
+    } else {
+        log::info!("[rpc_config] Pool endpoint readonly for {}: {}", network, super::rpc_pool::mask_url(&resolved.url));
     }
-
-    // Tier 3: DeFi Relay (x402 — caller may not handle this well)
-    let url = defirelay_url(network);
-    log::warn!("[rpc_config] Tier 3 (DeFi Relay) readonly fallback for {}: {} — caller may not handle x402", network, url);
-    ResolvedRpcConfig { url, use_x402: true }
+    resolved
 }
 
 /// RPC Provider configuration
@@ -384,9 +369,10 @@ pub fn resolve_rpc_from_context(
         .and_then(|v| serde_json::from_value(v.clone()).ok());
 
     // Custom endpoints take highest precedence (user-configured, no x402)
+    // unless the pool has them cooling down after failures
     if let Some(ref endpoints) = custom_endpoints {
         if let Some(url) = endpoints.get(network) {
-            if !url.is_empty() {
+            if !url.is_empty() && !super::rpc_pool::is_cooling_down(url) {
                 log::info!("[rpc_config] Custom endpoint for {}: {}", network, url);
                 return ResolvedRpcConfig { url: url.clone(), use_x402: false };
             }
@@ -404,6 +390,6 @@ pub fn resolve_rpc_from_context(
         }
     }
 
-    // Delegate to unified 3-tier resolver
+    // Delegate to the RPC pool
     resolve_rpc(network)
 }
@@ -0,0 +1,123 @@
+//! Tool execution timeouts
+//!
+//! Every tool call runs under a timeout: the watchdog's built-in default and
+//! per-tool overrides (`WatchdogConfig`), adjusted by the owner with
+//! `/api/tool-timeouts`. The dispatcher enforces it through the watchdog;
+//! other executors (strategies, alert actions) go through [`execute`]. Each
+//! call's context carries its deadline, so a long-running tool can check
+//! `ToolContext::remaining_budget` and return what it has before it is cut off.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::Database;
+use crate::telemetry::WatchdogConfig;
+use crate::tools::registry::ToolRegistry;
+use crate::tools::types::{ToolConfig, ToolContext, ToolResult};
+
+/// kv_store namespace for the settings (outside what the kv_store tool can reach)
+const NAMESPACE: &str = "tool_timeouts";
+const SETTINGS_KEY: &str = "settings";
+
+/// Accepted range for a timeout, in seconds
+pub const MIN_TIMEOUT_SECS: u64 = 1;
+pub const MAX_TIMEOUT_SECS: u64 = 7200;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolTimeouts {
+    /// Replaces the built-in default for tools without their own timeout
+    #[serde(default)]
+    pub default_secs: Option<u64>,
+    /// Per-tool timeouts, on top of the built-in ones
+    #[serde(default)]
+    pub tools: BTreeMap<String, u64>,
+}
+
+impl ToolTimeouts {
+    /// The watchdog config with these timeouts applied
+    pub fn apply(&self, base: &WatchdogConfig) -> WatchdogConfig {
+        let mut config = base.clone();
+        if let Some(secs) = self.default_secs {
+            config.tool_timeout_secs = secs.clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS);
+        }
+        for (tool, secs) in &self.tools {
+            config.tool_overrides.insert(tool.clone(), (*secs).clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS));
+        }
+        config
+    }
+}
+
+pub fn load(db: &Database) -> ToolTimeouts {
+    db.kv_get(NAMESPACE, SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|entry| serde_json::from_value(entry.value).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(db: &Database, timeouts: &ToolTimeouts) -> Result<(), String> {
+    let value = serde_json::to_value(timeouts).map_err(|e| e.to_string())?;
+    db.kv_set(NAMESPACE, SETTINGS_KEY, &value, None).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// The watchdog config for a new execution: `base` plus the configured timeouts
+pub fn watchdog_config(db: &Database, base: &WatchdogConfig) -> WatchdogConfig {
+    load(db).apply(base)
+}
+
+/// Run a tool outside the dispatcher under its configured timeout
+pub async fn execute(
+    db: &Database,
+    registry: &ToolRegistry,
+    tool_name: &str,
+    params: Value,
+    context: &ToolContext,
+    config: Option<&ToolConfig>,
+) -> ToolResult {
+    let timeout = watchdog_config(db, &WatchdogConfig::default()).timeout_for_tool(tool_name);
+    let context = context.clone().with_deadline(timeout);
+    match tokio::time::timeout(timeout, registry.execute(tool_name, params, &context, config)).await {
+        Ok(result) => result,
+        Err(_) => {
+            log::warn!("[TOOL_TIMEOUT] '{}' timed out after {}s", tool_name, timeout.as_secs());
+            ToolResult::error(format!("Tool '{}' timed out after {}s", tool_name, timeout.as_secs()))
+        }
+    }
+}
+
+/// How long a tool that checkpoints should keep working: the remaining budget
+/// less `margin` to wrap up, capped at `wanted`
+pub fn working_time(context: &ToolContext, wanted: Duration, margin: Duration) -> Duration {
+    match context.remaining_budget() {
+        Some(left) => wanted.min(left.saturating_sub(margin)),
+        None => wanted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_and_budget() {
+        let timeouts = ToolTimeouts {
+            default_secs: Some(90),
+            tools: BTreeMap::from([("exec".to_string(), 20), ("deploy".to_string(), 99_999)]),
+        };
+        let config = timeouts.apply(&WatchdogConfig::default());
+        assert_eq!(config.timeout_for_tool("read_file"), Duration::from_secs(90));
+        assert_eq!(config.timeout_for_tool("exec"), Duration::from_secs(20));
+        assert_eq!(config.timeout_for_tool("deploy"), Duration::from_secs(MAX_TIMEOUT_SECS));
+        // Built-in overrides that weren't touched stay
+        assert_eq!(config.timeout_for_tool("web_fetch"), Duration::from_secs(120));
+
+        let context = ToolContext::default();
+        assert_eq!(working_time(&context, Duration::from_secs(60), Duration::from_secs(5)), Duration::from_secs(60));
+        let context = context.with_deadline(Duration::from_secs(30));
+        assert!(working_time(&context, Duration::from_secs(60), Duration::from_secs(5)) <= Duration::from_secs(25));
+        assert_eq!(working_time(&context, Duration::from_secs(10), Duration::from_secs(5)), Duration::from_secs(10));
+    }
+}
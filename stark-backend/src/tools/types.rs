@@ -7,6 +7,7 @@ use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
 use crate::notes::NoteStore;
 use crate::skills::SkillRegistry;
+use crate::tools::payload::ToolPayload;
 use crate::tools::register::RegisterStore;
 use crate::tx_queue::TxQueueManager;
 use crate::wallet::WalletProvider;
@@ -52,6 +53,16 @@ impl ToolSafetyLevel {
             ToolSafetyLevel::SafeMode => "safe_mode",
         }
     }
+
+    /// Parse the `as_str` form (None for anything else, including empty)
+    pub fn from_str_opt(s: &str) -> Option<Self> {
+        match s.trim() {
+            "standard" => Some(ToolSafetyLevel::Standard),
+            "read_only" => Some(ToolSafetyLevel::ReadOnly),
+            "safe_mode" => Some(ToolSafetyLevel::SafeMode),
+            _ => None,
+        }
+    }
 }
 
 /// Tool groups for access control
@@ -315,6 +326,9 @@ pub struct ToolResult {
     /// Used for transient network errors with exponential backoff.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry_after_secs: Option<u64>,
+    /// Structured data behind the content (see `crate::tools::payload`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<ToolPayload>,
 }
 
 impl ToolResult {
@@ -325,6 +339,7 @@ impl ToolResult {
             error: None,
             metadata: None,
             retry_after_secs: None,
+            payload: None,
         }
     }
 
@@ -336,6 +351,7 @@ impl ToolResult {
             error: Some(msg),
             metadata: None,
             retry_after_secs: None,
+            payload: None,
         }
     }
 
@@ -352,6 +368,7 @@ impl ToolResult {
             error: Some(msg),
             metadata: None,
             retry_after_secs: Some(retry_after_secs),
+            payload: None,
         }
     }
 
@@ -365,6 +382,25 @@ impl ToolResult {
         self
     }
 
+    pub fn with_payload(mut self, payload: ToolPayload) -> Self {
+        self.payload = Some(payload);
+        self
+    }
+
+    /// The content followed by the payload's data as compact JSON, which is
+    /// what the model reads
+    pub fn content_for_model(&self) -> String {
+        let Some(payload) = &self.payload else {
+            return self.content.clone();
+        };
+        let data = serde_json::to_string(&payload.structured()).unwrap_or_default();
+        if self.content.is_empty() {
+            data
+        } else {
+            format!("{}\n{}", self.content, data)
+        }
+    }
+
     /// Check if this result indicates the tool should be retried
     pub fn should_retry(&self) -> bool {
         self.retry_after_secs.is_some()
@@ -382,6 +418,11 @@ pub struct ToolContext {
     pub identity_id: Option<String>,
     /// Base directory for file operations (sandbox root)
     pub workspace_dir: Option<String>,
+    /// Per-execution scratch directory for intermediate files (garbage-collected
+    /// when the execution finishes). Tools resolve `scratch/...` paths against it.
+    pub scratch_dir: Option<String>,
+    /// Execution this context belongs to (links generated artifacts to it)
+    pub execution_id: Option<String>,
     /// Additional context data
     pub extra: HashMap<String, Value>,
     /// Event broadcaster for real-time events (e.g., tx.pending)
@@ -427,6 +468,14 @@ pub struct ToolContext {
     pub current_subagent_depth: Option<u32>,
     /// Hybrid search engine for combined FTS5 + vector + graph memory search
     pub hybrid_search: Option<Arc<crate::memory::HybridSearchEngine>>,
+    /// Private data tools revealed during this execution (redacted from replies in shared chats)
+    pub sensitive_data: crate::channels::redaction::SensitiveLedger,
+    /// Meters this execution's LLM calls against AI spending quotas (set by the dispatcher)
+    pub ai_usage: Option<crate::ai_quota::UsageMeter>,
+    /// When the executor cuts this call off (set per call; see `tools::timeouts`)
+    pub deadline: Option<std::time::Instant>,
+    /// Skill active for this call; finance tools run only with its grants
+    pub active_skill: Option<String>,
 }
 
 impl std::fmt::Debug for ToolContext {
@@ -438,6 +487,8 @@ impl std::fmt::Debug for ToolContext {
             .field("session_id", &self.session_id)
             .field("identity_id", &self.identity_id)
             .field("workspace_dir", &self.workspace_dir)
+            .field("scratch_dir", &self.scratch_dir)
+            .field("execution_id", &self.execution_id)
             .field("extra", &self.extra)
             .field("broadcaster", &self.broadcaster.is_some())
             .field("registers", &self.registers.keys())
@@ -458,6 +509,10 @@ impl std::fmt::Debug for ToolContext {
             .field("current_subagent_id", &self.current_subagent_id)
             .field("current_subagent_depth", &self.current_subagent_depth)
             .field("hybrid_search", &self.hybrid_search.is_some())
+            .field("sensitive_data", &self.sensitive_data.items().len())
+            .field("ai_usage", &self.ai_usage)
+            .field("deadline", &self.remaining_budget())
+            .field("active_skill", &self.active_skill)
             .finish()
     }
 }
@@ -472,6 +527,8 @@ impl Default for ToolContext {
             session_id: None,
             identity_id: None,
             workspace_dir: None,
+            scratch_dir: None,
+            execution_id: None,
             extra: HashMap::new(),
             broadcaster: None,
             registers: RegisterStore::new(),
@@ -492,6 +549,10 @@ impl Default for ToolContext {
             current_subagent_id: None,
             current_subagent_depth: None,
             hybrid_search: None,
+            sensitive_data: crate::channels::redaction::SensitiveLedger::default(),
+            ai_usage: None,
+            deadline: None,
+            active_skill: None,
         }
     }
 }
@@ -531,6 +592,56 @@ impl ToolContext {
         self
     }
 
+    pub fn with_scratch_dir(mut self, scratch_dir: String) -> Self {
+        self.scratch_dir = Some(scratch_dir);
+        self
+    }
+
+    pub fn with_execution_id(mut self, execution_id: String) -> Self {
+        self.execution_id = Some(execution_id);
+        self
+    }
+
+    /// Register a workspace-relative file as an artifact of the current execution.
+    /// No-op without an execution or database.
+    pub fn register_artifact(&self, path: &str, size_bytes: u64) {
+        if let (Some(execution_id), Some(db)) = (&self.execution_id, &self.database) {
+            if let Err(e) = db.register_artifact(execution_id, self.session_id, self.channel_id, path, size_bytes) {
+                log::warn!("[ARTIFACTS] Failed to register {} for {}: {}", path, execution_id, e);
+            }
+        }
+    }
+
+    /// Resolve a `scratch` or `scratch/...` path against this execution's scratch
+    /// directory. Returns None for other paths or when no scratch dir is attached.
+    pub fn resolve_scratch_path(&self, path: &str) -> Option<std::path::PathBuf> {
+        let scratch = std::path::PathBuf::from(self.scratch_dir.as_ref()?);
+        if path == "scratch" {
+            Some(scratch)
+        } else {
+            path.strip_prefix("scratch/").map(|rest| scratch.join(rest))
+        }
+    }
+
+    /// The same context, for a call that must finish within `budget`
+    pub fn with_deadline(mut self, budget: std::time::Duration) -> Self {
+        self.deadline = Some(std::time::Instant::now() + budget);
+        self
+    }
+
+    /// Time left before the executor cuts this call off (None without a
+    /// deadline). Long operations should check it and return what they have
+    /// instead of being cut off with nothing.
+    pub fn remaining_budget(&self) -> Option<std::time::Duration> {
+        self.deadline.map(|d| d.saturating_duration_since(std::time::Instant::now()))
+    }
+
+    /// Set the skill whose finance grants apply to tool calls
+    pub fn with_active_skill(mut self, skill_name: Option<String>) -> Self {
+        self.active_skill = skill_name;
+        self
+    }
+
     pub fn with_platform_chat_id(mut self, chat_id: String) -> Self {
         self.platform_chat_id = Some(chat_id);
         self
@@ -604,13 +715,18 @@ impl ToolContext {
     /// First checks the current channel (if it matches the type), then falls back to any channel of that type.
     /// `setting_key` is the channel setting name (e.g. "discord_bot_token", "telegram_bot_token", "slack_bot_token").
     pub fn find_channel_bot_token(&self, channel_type: &str, setting_key: &str) -> Option<String> {
+        self.find_channel_with_bot_token(channel_type, setting_key).map(|(_, token)| token)
+    }
+
+    /// Like `find_channel_bot_token`, but also returns the ID of the channel the token belongs to
+    pub fn find_channel_with_bot_token(&self, channel_type: &str, setting_key: &str) -> Option<(i64, String)> {
         let db = self.database.as_ref()?;
 
         // If we're currently in a channel of the right type, use its token
         if let Some(channel_id) = self.channel_id {
             if let Ok(Some(token)) = db.get_channel_setting(channel_id, setting_key) {
                 if !token.is_empty() {
-                    return Some(token);
+                    return Some((channel_id, token));
                 }
             }
         }
@@ -621,12 +737,12 @@ impl ToolContext {
                 if ch.channel_type == channel_type {
                     if let Ok(Some(token)) = db.get_channel_setting(ch.id, setting_key) {
                         if !token.is_empty() {
-                            return Some(token);
+                            return Some((ch.id, token));
                         }
                     }
                     // Also check legacy bot_token field
                     if !ch.bot_token.is_empty() {
-                        return Some(ch.bot_token);
+                        return Some((ch.id, ch.bot_token));
                     }
                 }
             }
@@ -715,6 +831,12 @@ impl ToolContext {
         self
     }
 
+    /// Add the meter that records this execution's LLM calls
+    pub fn with_ai_usage(mut self, meter: crate::ai_quota::UsageMeter) -> Self {
+        self.ai_usage = Some(meter);
+        self
+    }
+
     /// Check disk quota before a write. Returns Ok(()) or a human-readable error string.
     pub fn check_disk_quota(&self, bytes: usize) -> Result<(), String> {
         if let Some(ref dq) = self.disk_quota {
@@ -724,6 +846,23 @@ impl ToolContext {
         }
     }
 
+    /// Check the per-execution scratch cap (and the global disk quota) before
+    /// writing `bytes` into the scratch directory.
+    pub fn check_scratch_quota(&self, bytes: usize) -> Result<(), String> {
+        if let Some(ref dir) = self.scratch_dir {
+            let limit = crate::config::scratch_max_bytes();
+            let used = crate::execution::scratch::usage_bytes(std::path::Path::new(dir));
+            if limit > 0 && used.saturating_add(bytes as u64) > limit {
+                return Err(format!(
+                    "Scratch space exceeded: this execution has used {} of its {} byte scratch limit. \
+                     Delete intermediate files from scratch/ before writing more.",
+                    used, limit
+                ));
+            }
+        }
+        self.check_disk_quota(bytes)
+    }
+
     /// Record a successful write with the disk quota manager.
     pub fn record_disk_write(&self, bytes: usize) {
         if let Some(ref dq) = self.disk_quota {
@@ -886,6 +1025,7 @@ pub const SAFE_MODE_ALLOW_LIST: &[&str] = &[
     "discord_read",         // Read-only Discord operations (safe)
     "discord_lookup",       // Read-only Discord server/channel lookup (safe)
     "telegram_read",        // Read-only Telegram operations (safe)
+    "request_human",        // Hand the chat to a human operator (safe, only pauses the agent)
 ];
 
 /// Tools whose sessions must NEVER be written to memory files.
@@ -0,0 +1,169 @@
+//! Second factor (TOTP) for sensitive actions
+//!
+//! Two-factor authentication is optional. Once enrolled, endpoints that touch
+//! secrets, wallet policy, module installs or exports only run for a login
+//! session that verified a code within `RECENT_VERIFICATION_SECS`; the
+//! verification is stored on the auth session, so signing in again means
+//! verifying again. Codes are standard RFC 6238 TOTP (SHA-1, 6 digits, 30 s),
+//! accepted one step either side of now and never twice. Recovery codes are
+//! single use and only their SHA-256 is stored. Wrong codes are counted per
+//! session and for the account; past `MAX_SESSION_FAILURES` or
+//! `MAX_ACCOUNT_FAILURES` every further attempt is refused for a lockout that
+//! doubles with each failure.
+
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// How long a verification covers sensitive actions
+pub const RECENT_VERIFICATION_SECS: i64 = 10 * 60;
+/// Recovery codes handed out on enrollment
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Wrong codes one session may send before it is locked out
+pub const MAX_SESSION_FAILURES: i64 = 5;
+/// Wrong codes across all sessions before the account is locked out
+pub const MAX_ACCOUNT_FAILURES: i64 = 10;
+const BASE_LOCKOUT_SECS: i64 = 60;
+const MAX_LOCKOUT_SECS: i64 = 24 * 60 * 60;
+
+const STEP_SECS: i64 = 30;
+const DIGITS: u32 = 6;
+/// Steps either side of now that still verify (clock drift)
+const SKEW_STEPS: i64 = 1;
+const ISSUER: &str = "StarkBot";
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// RFC 4648 base32 without padding, the form authenticator apps take
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for &b in bytes {
+        buffer = (buffer << 8) | b as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    out
+}
+
+pub fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in text.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32_ALPHABET.iter().position(|&a| a as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// A new random secret, base32 encoded
+pub fn generate_secret() -> String {
+    let bytes: [u8; 20] = rand::random();
+    base32_encode(&bytes)
+}
+
+/// The URI authenticator apps scan from a QR code
+pub fn otpauth_uri(secret: &str, account: &str) -> String {
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={DIGITS}&period={STEP_SECS}",
+        issuer = ISSUER,
+        account = urlencoding::encode(account),
+    )
+}
+
+/// The code for one time step
+fn code_at(key: &[u8], step: i64) -> String {
+    let mut mac = HmacSha1::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let value = u32::from_be_bytes([hash[offset] & 0x7f, hash[offset + 1], hash[offset + 2], hash[offset + 3]]);
+    format!("{:0width$}", value % 10u32.pow(DIGITS), width = DIGITS as usize)
+}
+
+/// Check a code at unix time `now`. Returns the step it matched, which must be
+/// newer than `last_used_step` so a code can't be replayed.
+pub fn verify_code(secret: &str, code: &str, now: i64, last_used_step: Option<i64>) -> Option<i64> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    if code.len() != DIGITS as usize {
+        return None;
+    }
+    let key = base32_decode(secret)?;
+    let current = now.div_euclid(STEP_SECS);
+    (current - SKEW_STEPS..=current + SKEW_STEPS)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| code_at(&key, *step) == code)
+}
+
+/// How long to refuse codes after `failures` wrong ones, if at all
+pub fn lockout_secs(failures: i64, max_failures: i64) -> Option<i64> {
+    if failures < max_failures {
+        return None;
+    }
+    let doublings = (failures - max_failures).min(20) as u32;
+    Some((BASE_LOCKOUT_SECS << doublings).min(MAX_LOCKOUT_SECS))
+}
+
+/// Fresh recovery codes, e.g. "3f9a-c21e-77b0"
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let bytes: [u8; 6] = rand::random();
+            let hex = hex::encode(bytes);
+            format!("{}-{}-{}", &hex[0..4], &hex[4..8], &hex[8..12])
+        })
+        .collect()
+}
+
+/// What is stored for a recovery code; case and dashes don't matter
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_totp_matches_rfc_vectors() {
+        // RFC 6238 appendix B, SHA-1 secret "12345678901234567890", last 6 digits
+        let secret = base32_encode(b"12345678901234567890");
+        assert_eq!(base32_decode(&secret).unwrap(), b"12345678901234567890");
+        assert_eq!(verify_code(&secret, "287082", 59, None), Some(1));
+        assert_eq!(verify_code(&secret, "081804", 1111111109, None), Some(37037036));
+        assert_eq!(verify_code(&secret, "005924", 1234567890, None), Some(41152263));
+        // One step of drift is fine, a used step isn't
+        assert!(verify_code(&secret, "287082", 89, None).is_some());
+        assert_eq!(verify_code(&secret, "287082", 59, Some(1)), None);
+        assert_eq!(verify_code(&secret, "28708", 59, None), None);
+
+        // Lockouts start at the threshold and double from there
+        assert_eq!(lockout_secs(MAX_SESSION_FAILURES - 1, MAX_SESSION_FAILURES), None);
+        assert_eq!(lockout_secs(MAX_SESSION_FAILURES, MAX_SESSION_FAILURES), Some(60));
+        assert_eq!(lockout_secs(MAX_SESSION_FAILURES + 2, MAX_SESSION_FAILURES), Some(240));
+        assert_eq!(lockout_secs(1000, MAX_SESSION_FAILURES), Some(24 * 60 * 60));
+
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(hash_recovery_code(&codes[0]), hash_recovery_code(&codes[0].to_uppercase().replace('-', "")));
+    }
+}
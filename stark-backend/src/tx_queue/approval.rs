@@ -0,0 +1,402 @@
+//! Owner approval of queued transactions via chat buttons
+//!
+//! When a queued transaction's value exceeds the configured threshold,
+//! `broadcast_web3_tx` doesn't broadcast it: it records a pending approval and
+//! sends an approval card with Approve/Reject buttons to the owner's chat. The
+//! value is the native value plus any token amount the call transfers or
+//! approves, priced in native currency; calldata that can't be valued (swaps,
+//! unknown calls) always asks. The
+//! signed transaction only leaves the queue when the owner presses Approve (or
+//! confirms it in the dashboard) before the approval expires. Every decision
+//! is kept in `tx_approvals` as the audit trail.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use ethers::types::U256;
+use serde_json::json;
+
+use crate::channels::outbound;
+use crate::channels::types::{ActionButton, ActionEvent, NormalizedMessage};
+use crate::db::tables::tx_approvals::{
+    TxApproval, TX_APPROVAL_APPROVED, TX_APPROVAL_PENDING, TX_APPROVAL_REJECTED,
+};
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::methods::{handle_tx_queue_confirm, handle_tx_queue_deny, TxQueueParams};
+use crate::journal::prices;
+use crate::models::BotSettings;
+use crate::wallet::WalletProvider;
+
+use super::{QueuedTransaction, TxQueueManager};
+
+/// Button action that approves and broadcasts the transaction
+pub const ACTION_APPROVE_TX: &str = "approve_tx";
+/// Button action that rejects and discards the transaction
+pub const ACTION_REJECT_TX: &str = "reject_tx";
+
+/// Who gets asked, and for what
+#[derive(Debug, Clone)]
+pub struct ApprovalPolicy {
+    /// Value (ETH, tokens priced in ETH) above which approval is required; 0 = every transaction
+    pub threshold_eth: f64,
+    pub channel_id: i64,
+    pub chat_id: String,
+    pub ttl_secs: i64,
+}
+
+impl ApprovalPolicy {
+    /// Policy from bot settings, or None if chat approvals aren't configured
+    pub fn from_settings(settings: &BotSettings) -> Option<Self> {
+        Some(Self {
+            threshold_eth: settings.tx_approval_threshold_eth?,
+            channel_id: settings.tx_approval_channel_id?,
+            chat_id: settings.tx_approval_chat_id.clone().filter(|c| !c.is_empty())?,
+            ttl_secs: settings.tx_approval_ttl_secs.max(60),
+        })
+    }
+
+    /// Whether `tx` needs the owner's approval. Token spends are priced in
+    /// native currency against the threshold; a spend that can't be priced
+    /// (unknown calldata such as swaps, unlimited approvals, no price) always
+    /// needs approval.
+    pub async fn requires_approval(&self, tx: &QueuedTransaction) -> bool {
+        if self.threshold_eth <= 0.0 || value_eth(tx) > self.threshold_eth {
+            return true;
+        }
+        let decimals = crate::token_metadata::lookup(&tx.network, &tx.to).map(|token| token.decimals);
+        let spent = match spend_of(tx, decimals) {
+            Spend::Native => 0.0,
+            Spend::Unpriced => return true,
+            Spend::Token { token, decimals, amount } => match token_value_eth(&tx.network, &token, decimals, amount).await {
+                Ok(eth) => eth,
+                Err(e) => {
+                    log::warn!("[TX_APPROVAL] Could not price the token spend of {}: {}", tx.uuid, e);
+                    return true;
+                }
+            },
+        };
+        value_eth(tx) + spent > self.threshold_eth
+    }
+}
+
+/// Native value in ETH (unparseable values are treated as unbounded)
+fn value_eth(tx: &QueuedTransaction) -> f64 {
+    tx.value
+        .parse::<u128>()
+        .map(|wei| wei as f64 / 1e18)
+        .unwrap_or(f64::INFINITY)
+}
+
+/// What a transaction spends besides its native value
+#[derive(Debug, PartialEq)]
+enum Spend {
+    /// Nothing: a plain native transfer
+    Native,
+    /// An amount of the target token (transfer, transferFrom, approve)
+    Token { token: String, decimals: u8, amount: U256 },
+    /// Calldata whose spend can't be valued
+    Unpriced,
+}
+
+/// ERC-20 functions whose amount argument is what the call spends or allows
+const TOKEN_SPEND_FUNCTIONS: &[&str] = &["transfer", "transferFrom", "approve", "increaseAllowance"];
+
+/// The spend of `tx`; `token_decimals` are those of the target when it is a known token
+fn spend_of(tx: &QueuedTransaction, token_decimals: Option<u8>) -> Spend {
+    let preview = crate::web3::decode::preview(&tx.network, &tx.to, &tx.value, &tx.data);
+    if preview.selector.is_none() && preview.flags.is_empty() {
+        return Spend::Native;
+    }
+    let (Some(call), Some(decimals)) = (preview.call, token_decimals) else {
+        return Spend::Unpriced;
+    };
+    if !TOKEN_SPEND_FUNCTIONS.contains(&call.function.as_str()) {
+        return Spend::Unpriced;
+    }
+    let amount = call
+        .params
+        .iter()
+        .rev()
+        .find(|p| p.kind.starts_with("uint"))
+        .and_then(|p| p.value.as_str().and_then(|v| crate::web3::amounts::parse_raw(v).ok()));
+    match amount {
+        Some(amount) if !crate::web3::decode::is_unlimited(amount) => {
+            Spend::Token { token: tx.to.clone(), decimals, amount }
+        }
+        _ => Spend::Unpriced,
+    }
+}
+
+/// `amount` of `token` in the network's native currency, at current prices
+async fn token_value_eth(network: &str, token: &str, decimals: u8, amount: U256) -> Result<f64, String> {
+    let token_id = prices::coin_id(network, token).ok_or_else(|| format!("no price source for {} on {}", token, network))?;
+    let native_id = prices::coin_id(network, prices::NATIVE_TOKEN).ok_or("no price source for the native token")?;
+    let quotes = prices::current_prices(&[token_id.clone(), native_id.clone()]).await?;
+    let price = |id: &str| quotes.get(&id.to_lowercase()).copied().filter(|p| *p > 0.0);
+    let (Some(token_usd), Some(native_usd)) = (price(&token_id), price(&native_id)) else {
+        return Err("no current price".to_string());
+    };
+    let whole: f64 = crate::web3::amounts::format_units(amount, decimals).parse().map_err(|_| "amount out of range")?;
+    Ok(whole * token_usd / native_usd)
+}
+
+/// Human-readable description of a queued transaction (markdown)
+pub fn summarize(tx: &QueuedTransaction) -> String {
+    let mut summary = format!("From: `{}`\n", tx.from);
+    summary.push_str(&crate::web3::decode::preview(&tx.network, &tx.to, &tx.value, &tx.data).explain());
+    if let Some(ref preset) = tx.preset {
+        summary.push_str(&format!("\nPreset: {}", preset));
+    }
+    summary
+}
+
+fn approval_card(summary: &str, ttl_secs: i64) -> String {
+    format!(
+        "**Transaction approval needed**\n\n{}\n\nNothing is broadcast unless you approve. This request expires in {} min.",
+        summary,
+        (ttl_secs + 59) / 60
+    )
+}
+
+/// Ask the owner to approve `tx`. Returns the existing approval if one was already requested.
+pub async fn request_approval(
+    db: &Database,
+    tx: &QueuedTransaction,
+    policy: &ApprovalPolicy,
+) -> Result<TxApproval, String> {
+    if let Some(existing) = db.get_tx_approval(&tx.uuid).map_err(|e| e.to_string())? {
+        return Ok(existing);
+    }
+
+    let summary = summarize(tx);
+    let approval = db
+        .create_tx_approval(
+            &tx.uuid,
+            policy.channel_id,
+            &policy.chat_id,
+            &tx.network,
+            &tx.to,
+            &tx.value,
+            &summary,
+            Utc::now() + Duration::seconds(policy.ttl_secs),
+        )
+        .map_err(|e| format!("Failed to record approval: {}", e))?;
+
+    let payload = Some(json!({ "uuid": tx.uuid }));
+    let buttons = vec![
+        ActionButton { label: "Approve".to_string(), action: ACTION_APPROVE_TX.to_string(), payload: payload.clone() },
+        ActionButton { label: "Reject".to_string(), action: ACTION_REJECT_TX.to_string(), payload },
+    ];
+    let card = approval_card(&summary, policy.ttl_secs);
+    if let Err(e) = outbound::send_direct(db, policy.channel_id, &policy.chat_id, &card, &buttons).await {
+        // Nothing was decided — drop the record so the request can be retried
+        let _ = db.delete_tx_approval(&tx.uuid);
+        return Err(format!("Failed to send approval card: {}", e));
+    }
+
+    log::info!(
+        "[TX_APPROVAL] Requested approval for {} ({} to {}) in channel {} chat {}",
+        tx.uuid, tx.format_value_eth(), tx.to, policy.channel_id, policy.chat_id
+    );
+    Ok(approval)
+}
+
+/// Handle an Approve/Reject button press. None if the action isn't an approval action.
+pub async fn handle_action(
+    db: &Database,
+    tx_queue: Option<Arc<TxQueueManager>>,
+    broadcaster: Arc<EventBroadcaster>,
+    wallet_provider: Option<Arc<dyn WalletProvider>>,
+    message: &NormalizedMessage,
+    event: &ActionEvent,
+) -> Option<String> {
+    let approve = match event.action.as_str() {
+        ACTION_APPROVE_TX => true,
+        ACTION_REJECT_TX => false,
+        _ => return None,
+    };
+    let uuid = match event.payload.as_ref().and_then(|p| p.get("uuid")).and_then(|u| u.as_str()) {
+        Some(uuid) => uuid.to_string(),
+        None => return Some("This approval button is missing its transaction.".to_string()),
+    };
+
+    let approval = match db.get_tx_approval(&uuid) {
+        Ok(Some(approval)) => approval,
+        Ok(None) => return Some(format!("No approval request found for transaction {}.", uuid)),
+        Err(e) => return Some(format!("Failed to load approval: {}", e)),
+    };
+    if approval.channel_id != message.channel_id || approval.chat_id != message.chat_id {
+        log::warn!(
+            "[TX_APPROVAL] Ignoring {} for {} from channel {} chat {} (sent to channel {} chat {})",
+            event.action, uuid, message.channel_id, message.chat_id, approval.channel_id, approval.chat_id
+        );
+        return Some("This approval belongs to a different chat.".to_string());
+    }
+    if message.force_safe_mode {
+        log::warn!("[TX_APPROVAL] Non-admin {} tried to decide {}", message.user_name, uuid);
+        return Some("Only the owner can approve or reject transactions.".to_string());
+    }
+    let tx_queue = match tx_queue {
+        Some(q) => q,
+        None => return Some("Transaction queue not available.".to_string()),
+    };
+
+    let decided_by = format!("{} ({})", message.user_name, message.user_id);
+    let status = if approve { TX_APPROVAL_APPROVED } else { TX_APPROVAL_REJECTED };
+    match db.decide_tx_approval(&uuid, status, &decided_by) {
+        Ok(true) => {}
+        Ok(false) => {
+            let current = db
+                .get_tx_approval(&uuid)
+                .ok()
+                .flatten()
+                .map(|a| a.status)
+                .unwrap_or_else(|| "unknown".to_string());
+            let reason = if current == TX_APPROVAL_PENDING { "expired".to_string() } else { current };
+            return Some(format!("This transaction was already {} — nothing changed.", reason));
+        }
+        Err(e) => return Some(format!("Failed to record decision: {}", e)),
+    }
+    log::info!("[TX_APPROVAL] Transaction {} {} by {}", uuid, status, decided_by);
+
+    let channel_id = tx_queue.get(&uuid).and_then(|tx| tx.channel_id).unwrap_or(approval.channel_id);
+    let params = TxQueueParams { uuid: uuid.clone(), channel_id };
+
+    if !approve {
+        let _ = handle_tx_queue_deny(params, tx_queue, broadcaster).await;
+        return Some(format!("Rejected — transaction {} was discarded.", uuid));
+    }
+
+    match handle_tx_queue_confirm(params, tx_queue, broadcaster, wallet_provider).await {
+        Ok(result) => {
+            let tx_hash = result.get("tx_hash").and_then(|v| v.as_str()).unwrap_or_default();
+            let explorer_url = result.get("explorer_url").and_then(|v| v.as_str()).unwrap_or_default();
+            let _ = db.record_tx_approval_result(&uuid, Some(tx_hash), None);
+            Some(format!("Approved — broadcast as `{}`\n{}", tx_hash, explorer_url))
+        }
+        Err(e) => {
+            let _ = db.record_tx_approval_result(&uuid, None, Some(&e.message));
+            Some(format!("Approved, but the broadcast failed: {}", e.message))
+        }
+    }
+}
+
+/// Record a dashboard confirm/deny against the transaction's approval, if it has one.
+/// Fails if the owner already decided it in chat or it expired.
+pub fn settle_from_dashboard(db: &Database, uuid: &str, approve: bool) -> Result<(), String> {
+    let approval = match db.get_tx_approval(uuid).map_err(|e| e.to_string())? {
+        Some(approval) => approval,
+        None => return Ok(()),
+    };
+    let status = if approve { TX_APPROVAL_APPROVED } else { TX_APPROVAL_REJECTED };
+    if db.decide_tx_approval(uuid, status, "dashboard").map_err(|e| e.to_string())? {
+        log::info!("[TX_APPROVAL] Transaction {} {} from the dashboard", uuid, status);
+        return Ok(());
+    }
+    let current = if approval.status == TX_APPROVAL_PENDING { "expired" } else { approval.status.as_str() };
+    Err(format!("Transaction approval is already {}", current))
+}
+
+/// Expire overdue approvals: their transactions are marked expired and the owner is told.
+pub async fn expire_overdue(db: &Database, tx_queue: &TxQueueManager) -> usize {
+    let expired = match db.expire_tx_approvals(Utc::now()) {
+        Ok(expired) => expired,
+        Err(e) => {
+            log::error!("[TX_APPROVAL] Failed to expire approvals: {}", e);
+            return 0;
+        }
+    };
+    for approval in &expired {
+        tx_queue.mark_expired(&approval.tx_uuid);
+        log::info!("[TX_APPROVAL] Approval for {} expired unanswered", approval.tx_uuid);
+        let notice = format!(
+            "Approval request expired — transaction to `{}` was not broadcast.",
+            approval.to_address
+        );
+        if let Err(e) = outbound::send_direct(db, approval.channel_id, &approval.chat_id, &notice, &[]).await {
+            log::warn!("[TX_APPROVAL] Failed to send expiry notice for {}: {}", approval.tx_uuid, e);
+        }
+    }
+    expired.len()
+}
+
+/// Spawn the background expiry worker (checks every `interval_secs`).
+pub fn spawn_expiry_worker(db: Arc<Database>, tx_queue: Arc<TxQueueManager>, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            expire_overdue(&db, &tx_queue).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(value: &str, data: &str) -> QueuedTransaction {
+        QueuedTransaction::new(
+            "tx-1".to_string(),
+            "base".to_string(),
+            "0xfrom".to_string(),
+            "0xto".to_string(),
+            value.to_string(),
+            data.to_string(),
+            "21000".to_string(),
+            "1".to_string(),
+            "1".to_string(),
+            0,
+            "0x".to_string(),
+            Some(1),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_policy_threshold_and_summary() {
+        let settings = BotSettings {
+            tx_approval_threshold_eth: Some(0.5),
+            tx_approval_channel_id: Some(3),
+            tx_approval_chat_id: Some("42".to_string()),
+            ..Default::default()
+        };
+        let policy = ApprovalPolicy::from_settings(&settings).unwrap();
+        assert!(!policy.requires_approval(&tx("100000000000000000", "0x")).await);
+        assert!(policy.requires_approval(&tx("1000000000000000000", "0x")).await);
+
+        // Zero-value calls still spend: calldata that can't be priced always asks
+        assert!(policy.requires_approval(&tx("0", "0xa9059cbb00")).await);
+
+        let zero = ApprovalPolicy { threshold_eth: 0.0, ..policy };
+        assert!(zero.requires_approval(&tx("0", "0xa9059cbb00")).await);
+
+        // Not configured without a destination chat
+        assert!(ApprovalPolicy::from_settings(&BotSettings::default()).is_none());
+
+        // ERC-20 transfer decoded via the global abis/ registry
+        let data = format!(
+            "0xa9059cbb{:0>64}{:064x}",
+            "000000000000000000000000000000000000dead", 5u64
+        );
+        let summary = summarize(&tx("0", &data));
+        assert!(summary.contains("transfer(address,uint256)"));
+        assert!(summary.contains("`0xto`"));
+    }
+
+    #[test]
+    fn test_token_spends_are_valued_or_ask() {
+        let usdc = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+        let on_usdc = |data: String| QueuedTransaction { to: usdc.to_string(), ..tx("0", &data) };
+        let transfer = format!("0xa9059cbb{:0>64}{:064x}", "000000000000000000000000000000000000dead", 5_000_000u64);
+        assert_eq!(
+            spend_of(&on_usdc(transfer), Some(6)),
+            Spend::Token { token: usdc.to_string(), decimals: 6, amount: U256::from(5_000_000u64) }
+        );
+
+        // Unlimited approvals and plain native transfers
+        let approve_all = format!("0x095ea7b3{:0>64}{}", "000000000000000000000000000000000000dead", "f".repeat(64));
+        assert_eq!(spend_of(&on_usdc(approve_all), Some(6)), Spend::Unpriced);
+        assert_eq!(spend_of(&tx("1", "0x"), None), Spend::Native);
+    }
+}
@@ -0,0 +1,202 @@
+//! Balance and gas pre-checks
+//!
+//! Before a transaction is queued, the wallet must hold what it needs on that
+//! network: the native value plus the most its gas can cost (gas limit × max
+//! fee), on top of what the wallet's other queued and in-flight transactions
+//! may still spend, and the token amount for transfers, swaps and bridges. A
+//! shortfall is refused with what is missing ("needs 0.003 ETH more on Base
+//! for gas") instead of being queued to fail on-chain. When a balance can't be
+//! read the check is skipped; a flaky RPC shouldn't block transactions that
+//! would go through.
+
+use std::str::FromStr;
+
+use ethers::types::U256;
+
+use super::{QueuedTransaction, TxQueueManager};
+use crate::tools::rpc_config::Network;
+use crate::web3::amounts::{self, TokenSpend};
+
+/// Decimal places shown for amounts in the errors
+const SHOWN_DECIMALS: u8 = 6;
+
+/// The most a transaction can take from the native balance: value + gas limit × max fee
+pub fn max_native_cost(tx: &QueuedTransaction) -> U256 {
+    let raw = |v: &str| amounts::parse_raw(v).unwrap_or_default();
+    raw(&tx.gas_limit)
+        .saturating_mul(raw(&tx.max_fee_per_gas))
+        .saturating_add(raw(&tx.value))
+}
+
+fn network_name(network: &str) -> String {
+    let mut chars = network.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// An amount with at most `SHOWN_DECIMALS` places; what's missing is rounded
+/// up and what's there rounded down, so the numbers never understate the gap
+fn shown(amount: U256, decimals: u8, symbol: &str, round_up: bool) -> String {
+    let amount = if decimals > SHOWN_DECIMALS {
+        let step = U256::exp10((decimals - SHOWN_DECIMALS) as usize);
+        let mut steps = amount / step;
+        if round_up && !(amount % step).is_zero() {
+            steps += U256::one();
+        }
+        steps.saturating_mul(step)
+    } else {
+        amount
+    };
+    format!("{} {}", amounts::format_units(amount, decimals), symbol)
+}
+
+/// What the wallet lacks in native currency for a transaction, if anything
+fn native_shortfall(
+    value: U256,
+    gas: U256,
+    reserved: U256,
+    balance: U256,
+    symbol: &str,
+    network: &str,
+) -> Option<String> {
+    let needed = reserved.saturating_add(value).saturating_add(gas);
+    if balance >= needed {
+        return None;
+    }
+    let eth = |amount: U256, round_up: bool| shown(amount, amounts::ETH_DECIMALS, symbol, round_up);
+    let purpose = if balance >= reserved.saturating_add(value) { "for gas" } else { "to cover the amount and gas" };
+    let mut detail = if value.is_zero() {
+        format!("this transaction needs up to {} for gas", eth(gas, true))
+    } else {
+        format!("this transaction needs {} plus up to {} for gas", eth(value, true), eth(gas, true))
+    };
+    if !reserved.is_zero() {
+        detail.push_str(&format!(", and queued transactions may spend {}", eth(reserved, true)));
+    }
+    Some(format!(
+        "Insufficient {}: the wallet needs {} more on {} {} ({}; it has {}).",
+        symbol,
+        eth(needed - balance, true),
+        network_name(network),
+        purpose,
+        detail,
+        eth(balance, false),
+    ))
+}
+
+/// What the wallet lacks of the token a transaction spends, if anything
+fn token_shortfall(spend: &TokenSpend, balance: U256, network: &str) -> Option<String> {
+    if balance >= spend.amount {
+        return None;
+    }
+    let token = |amount: U256, round_up: bool| shown(amount, spend.decimals, &spend.symbol, round_up);
+    Some(format!(
+        "Insufficient {}: the wallet needs {} more on {} (this transaction spends {}; it has {}).",
+        spend.symbol,
+        token(spend.amount - balance, true),
+        network_name(network),
+        token(spend.amount, true),
+        token(balance, false),
+    ))
+}
+
+/// Refuse a transaction the wallet can't pay for. `superseded` lists queued
+/// transactions this one takes the place of (a replacement's original is
+/// always left out), so their costs aren't counted twice.
+pub async fn ensure_affordable(
+    tx_queue: &TxQueueManager,
+    tx: &QueuedTransaction,
+    token_spend: Option<&TokenSpend>,
+    superseded: &[String],
+) -> Result<(), String> {
+    if let Some(spend) = token_spend {
+        match amounts::balance_of(&tx.network, &tx.from, Some(&spend.token)).await {
+            Ok(balance) => {
+                if let Some(reason) = token_shortfall(spend, balance, &tx.network) {
+                    log::warn!("[tx_funds] Refusing {}: {}", tx.uuid, reason);
+                    return Err(reason);
+                }
+            }
+            Err(e) => log::warn!("[tx_funds] Could not read {} balance, skipping check: {}", spend.symbol, e),
+        }
+    }
+
+    let reserved = tx_queue
+        .list_outstanding(&tx.network, &tx.from)
+        .iter()
+        .filter(|other| {
+            other.uuid != tx.uuid
+                && tx.replaces.as_deref() != Some(other.uuid.as_str())
+                && !superseded.contains(&other.uuid)
+        })
+        .fold(U256::zero(), |sum, other| sum.saturating_add(max_native_cost(other)));
+    let value = amounts::parse_raw(&tx.value).unwrap_or_default();
+    let gas = max_native_cost(tx).saturating_sub(value);
+    let symbol = Network::from_str(&tx.network).map(|n| n.native_currency()).unwrap_or("ETH");
+
+    match amounts::balance_of(&tx.network, &tx.from, None).await {
+        Ok(balance) => match native_shortfall(value, gas, reserved, balance, symbol, &tx.network) {
+            Some(reason) => {
+                log::warn!("[tx_funds] Refusing {}: {}", tx.uuid, reason);
+                Err(reason)
+            }
+            None => Ok(()),
+        },
+        Err(e) => {
+            log::warn!("[tx_funds] Could not read {} balance, skipping check: {}", symbol, e);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortfalls() {
+        let eth = |milli: u64| U256::from(milli) * U256::exp10(15);
+        assert_eq!(native_shortfall(eth(10), eth(1), eth(0), eth(20), "ETH", "base"), None);
+        assert_eq!(
+            native_shortfall(U256::zero(), eth(3), U256::zero(), U256::zero(), "ETH", "base").unwrap(),
+            "Insufficient ETH: the wallet needs 0.003 ETH more on Base for gas \
+             (this transaction needs up to 0.003 ETH for gas; it has 0 ETH)."
+        );
+        let short = native_shortfall(eth(10), eth(1), eth(5), eth(12), "ETH", "mainnet").unwrap();
+        assert!(short.contains("needs 0.004 ETH more on Mainnet to cover the amount and gas"), "{}", short);
+        assert!(short.contains("queued transactions may spend 0.005 ETH"), "{}", short);
+        // Wei-level gaps still show as a (rounded up) amount
+        let dust = native_shortfall(U256::zero(), U256::from(7u64), U256::zero(), U256::zero(), "ETH", "base").unwrap();
+        assert!(dust.contains("needs 0.000001 ETH more"), "{}", dust);
+
+        let usdc = TokenSpend {
+            token: "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string(),
+            symbol: "USDC".to_string(),
+            decimals: 6,
+            amount: U256::from(100_000_000u64),
+        };
+        assert_eq!(token_shortfall(&usdc, U256::from(100_000_000u64), "base"), None);
+        assert_eq!(
+            token_shortfall(&usdc, U256::from(60_500_000u64), "base").unwrap(),
+            "Insufficient USDC: the wallet needs 39.5 USDC more on Base (this transaction spends 100 USDC; it has 60.5 USDC)."
+        );
+
+        let tx = QueuedTransaction::new(
+            "a".to_string(),
+            "base".to_string(),
+            "0x1".to_string(),
+            "0x2".to_string(),
+            "1000".to_string(),
+            "0x".to_string(),
+            "21000".to_string(),
+            "2".to_string(),
+            "1".to_string(),
+            0,
+            "0x".to_string(),
+            None,
+        );
+        assert_eq!(max_native_cost(&tx), U256::from(43_000u64));
+    }
+}
@@ -11,6 +11,7 @@ use crate::db::tables::broadcasted_transactions::{
     BroadcastMode, BroadcastedTxStatus, RecordBroadcastRequest,
 };
 use crate::db::Database;
+use crate::eip8004::receipts;
 
 /// Manager for the transaction queue
 /// Uses DashMap for thread-safe concurrent access
@@ -42,6 +43,8 @@ impl TxQueueManager {
     pub fn queue(&self, tx: QueuedTransaction) -> String {
         let uuid = tx.uuid.clone();
         log::info!("[TxQueue] Queuing transaction {} to {}", uuid, tx.to);
+        // Its nonce stays reserved while it waits, however long that is
+        super::nonce::hold(&tx.network, &tx.from, tx.nonce);
         self.transactions.insert(uuid.clone(), tx);
         uuid
     }
@@ -139,6 +142,9 @@ impl TxQueueManager {
                 if let Err(e) = db.record_broadcast(req) {
                     log::error!("[TxQueue] Failed to persist broadcast to DB: {}", e);
                 }
+                if let Err(e) = db.mark_trade_executed(uuid, tx_hash) {
+                    log::error!("[TxQueue] Failed to update trade journal: {}", e);
+                }
             }
 
             true
@@ -147,8 +153,13 @@ impl TxQueueManager {
         }
     }
 
-    /// Mark transaction as confirmed
+    /// Mark transaction as confirmed. A confirmed replacement fails the
+    /// transaction it replaced, whose nonce is now used up.
     pub fn mark_confirmed(&self, uuid: &str) -> bool {
+        let replaced = self.transactions.get(uuid).and_then(|tx| tx.replaces.clone());
+        if let Some(ref original) = replaced {
+            self.mark_failed(original, &format!("Replaced by transaction {}", uuid));
+        }
         if let Some(mut tx) = self.transactions.get_mut(uuid) {
             log::info!("[TxQueue] Transaction {} confirmed", uuid);
             tx.status = QueuedTxStatus::Confirmed;
@@ -158,6 +169,16 @@ impl TxQueueManager {
                 if let Err(e) = db.update_broadcast_status(uuid, BroadcastedTxStatus::Confirmed, None) {
                     log::error!("[TxQueue] Failed to update DB status: {}", e);
                 }
+                if let Some(ref tx_hash) = tx.tx_hash {
+                    receipts::record(
+                        db,
+                        receipts::RECEIPT_TRANSACTION,
+                        tx_hash,
+                        &tx.network,
+                        &format!("Sent {} to {}", tx.format_value_eth(), tx.to),
+                        serde_json::json!({ "from": tx.from, "to": tx.to, "value": tx.value }),
+                    );
+                }
             }
 
             true
@@ -172,12 +193,19 @@ impl TxQueueManager {
             log::warn!("[TxQueue] Transaction {} failed: {}", uuid, error);
             tx.status = QueuedTxStatus::Failed;
             tx.error = Some(error.to_string());
+            // Never reached the mempool: its nonce is free again (replacements share theirs)
+            if tx.tx_hash.is_none() && tx.replaces.is_none() {
+                super::nonce::release(&tx.network, &tx.from, tx.nonce);
+            }
 
             // Update database status if available
             if let Some(ref db) = self.db {
                 if let Err(e) = db.update_broadcast_status(uuid, BroadcastedTxStatus::Failed, Some(error)) {
                     log::error!("[TxQueue] Failed to update DB status: {}", e);
                 }
+                if let Err(e) = db.mark_trade_failed(uuid) {
+                    log::error!("[TxQueue] Failed to update trade journal: {}", e);
+                }
             }
 
             true
@@ -191,12 +219,51 @@ impl TxQueueManager {
         if let Some(mut tx) = self.transactions.get_mut(uuid) {
             log::warn!("[TxQueue] Transaction {} expired", uuid);
             tx.status = QueuedTxStatus::Expired;
+            if tx.tx_hash.is_none() && tx.replaces.is_none() {
+                super::nonce::release(&tx.network, &tx.from, tx.nonce);
+            }
+            if let Some(ref db) = self.db {
+                if let Err(e) = db.mark_trade_failed(uuid) {
+                    log::error!("[TxQueue] Failed to update trade journal: {}", e);
+                }
+            }
             true
         } else {
             false
         }
     }
 
+    /// Transactions from `from` on `network` that were broadcast but aren't confirmed yet
+    pub fn list_in_flight(&self, network: &str, from: &str) -> Vec<QueuedTransaction> {
+        let mut txs: Vec<QueuedTransaction> = self.transactions
+            .iter()
+            .filter(|r| {
+                let tx = r.value();
+                tx.status == QueuedTxStatus::Broadcast
+                    && tx.network == network
+                    && tx.from.eq_ignore_ascii_case(from)
+            })
+            .map(|r| r.value().clone())
+            .collect();
+        txs.sort_by_key(|tx| tx.nonce);
+        txs
+    }
+
+    /// Transactions from `from` on `network` that may still spend from the wallet:
+    /// queued, being broadcast, or broadcast and not yet confirmed
+    pub fn list_outstanding(&self, network: &str, from: &str) -> Vec<QueuedTransaction> {
+        self.transactions
+            .iter()
+            .filter(|r| {
+                let tx = r.value();
+                matches!(tx.status, QueuedTxStatus::Pending | QueuedTxStatus::Broadcasting | QueuedTxStatus::Broadcast)
+                    && tx.network == network
+                    && tx.from.eq_ignore_ascii_case(from)
+            })
+            .map(|r| r.value().clone())
+            .collect()
+    }
+
     /// Get count of transactions by status
     pub fn count_by_status(&self, status: QueuedTxStatus) -> usize {
         self.transactions
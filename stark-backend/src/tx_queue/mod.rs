@@ -8,7 +8,15 @@
 //! 3. `broadcast_web3_tx` broadcasts a transaction by UUID
 //!
 //! This creates a safety layer where transactions can be reviewed before broadcast.
+//! Above the configured value threshold, broadcast additionally waits for the
+//! owner's approval in chat (see [`approval`]). Signers reserve nonces
+//! through [`nonce`] so transactions queued back to back don't collide, and
+//! `manage_tx` replaces stuck ones with a higher fee. Nothing is queued that
+//! the wallet can't pay for, gas included (see [`funds`]).
 
+pub mod approval;
+pub mod funds;
+pub mod nonce;
 mod types;
 mod manager;
 
@@ -0,0 +1,177 @@
+//! Per-wallet nonce reservations
+//!
+//! The chain's pending transaction count only covers transactions that were
+//! broadcast, so two transactions signed in quick succession (or a signed
+//! one still waiting in the queue) would otherwise get the same nonce. Every
+//! signer reserves its nonce here instead: the lowest nonce at or above the
+//! chain's pending count that no live reservation holds. A reservation is
+//! dropped once the chain count passes it, or when its transaction fails or
+//! expires before broadcast. A signature that never made it into the queue
+//! is abandoned after `RESERVATION_TTL_MINS`, so it can't leave a gap that
+//! blocks later transactions for long; one whose transaction is queued
+//! (waiting for approval or a broadcast) holds its nonce however long that takes.
+//!
+//! Reservations are process-local, so in cluster mode only the instance
+//! holding the signer lease reserves nonces (see `cluster::check_signer`).
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::{Address, U256};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use crate::x402::X402EvmRpc;
+
+/// How long a reservation whose transaction never reaches the queue holds its nonce
+const RESERVATION_TTL_MINS: i64 = 30;
+
+type WalletKey = (String, String);
+
+#[derive(Debug, Default)]
+struct WalletNonces {
+    /// Reserved nonce -> when it was reserved
+    held: BTreeMap<u64, DateTime<Utc>>,
+    /// Reserved nonces whose transaction is in the queue (these don't expire)
+    queued: BTreeSet<u64>,
+}
+
+impl WalletNonces {
+    /// Drop settled and abandoned reservations, then take the lowest free nonce
+    fn reserve(&mut self, chain_pending: u64, now: DateTime<Utc>) -> u64 {
+        self.queued.retain(|nonce| *nonce >= chain_pending);
+        let queued = &self.queued;
+        self.held.retain(|nonce, at| {
+            *nonce >= chain_pending
+                && (queued.contains(nonce) || now - *at < Duration::minutes(RESERVATION_TTL_MINS))
+        });
+        let mut nonce = chain_pending;
+        while self.held.contains_key(&nonce) {
+            nonce += 1;
+        }
+        self.held.insert(nonce, now);
+        nonce
+    }
+}
+
+static WALLETS: Lazy<Mutex<HashMap<WalletKey, WalletNonces>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn key(network: &str, address: &str) -> WalletKey {
+    (network.to_string(), address.to_lowercase())
+}
+
+/// Reserve the next nonce for `address` on `network`
+pub async fn reserve(rpc: &X402EvmRpc, network: &str, address: Address) -> Result<U256, String> {
+    crate::cluster::check_signer()?;
+    let chain_pending = rpc.get_transaction_count(address).await?.as_u64();
+    let nonce = WALLETS
+        .lock()
+        .entry(key(network, &format!("{:?}", address)))
+        .or_default()
+        .reserve(chain_pending, Utc::now());
+    if nonce != chain_pending {
+        log::info!(
+            "[nonce] Reserved nonce {} for {:?} on {} (chain pending count {})",
+            nonce, address, network, chain_pending
+        );
+    }
+    Ok(U256::from(nonce))
+}
+
+/// Give a nonce back after its transaction failed or expired before broadcast
+pub fn release(network: &str, address: &str, nonce: u64) {
+    if let Some(wallet) = WALLETS.lock().get_mut(&key(network, address)) {
+        wallet.queued.remove(&nonce);
+        if wallet.held.remove(&nonce).is_some() {
+            log::info!("[nonce] Released nonce {} for {} on {}", nonce, address, network);
+        }
+    }
+}
+
+/// Hold a nonce for a transaction in the queue, until it is broadcast and
+/// counted by the chain or released (also for one re-signed with a nonce it took over)
+pub fn hold(network: &str, address: &str, nonce: u64) {
+    let mut wallets = WALLETS.lock();
+    let wallet = wallets.entry(key(network, address)).or_default();
+    wallet.held.entry(nonce).or_insert_with(Utc::now);
+    wallet.queued.insert(nonce);
+}
+
+/// Nonces currently reserved for a wallet, lowest first
+pub fn reserved(network: &str, address: &str) -> Vec<u64> {
+    WALLETS
+        .lock()
+        .get(&key(network, address))
+        .map(|wallet| wallet.held.keys().copied().collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reservations_skip_held_and_expire() {
+        let mut wallet = WalletNonces::default();
+        let now = Utc::now();
+
+        assert_eq!(wallet.reserve(5, now), 5);
+        assert_eq!(wallet.reserve(5, now), 6);
+        // 5 was broadcast and counted by the chain; 6 is still held
+        assert_eq!(wallet.reserve(6, now), 7);
+
+        wallet.held.remove(&6);
+        assert_eq!(wallet.reserve(6, now), 6);
+
+        // Abandoned reservations stop blocking after the TTL
+        let later = now + Duration::minutes(RESERVATION_TTL_MINS + 1);
+        assert_eq!(wallet.reserve(6, later), 6);
+        assert_eq!(wallet.held.len(), 1);
+    }
+
+    #[test]
+    fn test_queued_reservations_outlive_the_ttl() {
+        let mut wallet = WalletNonces::default();
+        let now = Utc::now();
+        assert_eq!(wallet.reserve(5, now), 5);
+        assert_eq!(wallet.reserve(5, now), 6);
+        // 5 went into the queue (waiting for approval); 6 was never queued
+        wallet.queued.insert(5);
+
+        let later = now + Duration::minutes(RESERVATION_TTL_MINS + 60);
+        assert_eq!(wallet.reserve(5, later), 6);
+        assert!(wallet.held.contains_key(&5));
+
+        // Once broadcast and counted by the chain, it's settled
+        assert_eq!(wallet.reserve(7, later), 7);
+        assert!(wallet.queued.is_empty());
+    }
+
+    #[test]
+    fn test_queueing_holds_the_nonce_until_it_fails() {
+        let from = "0x9999999999999999999999999999999999999999";
+        let queue = super::super::TxQueueManager::new();
+        let tx = super::super::QueuedTransaction::new(
+            "nonce-test".to_string(),
+            "nonce-test-net".to_string(),
+            from.to_string(),
+            "0x2222222222222222222222222222222222222222".to_string(),
+            "0".to_string(),
+            "0x".to_string(),
+            "21000".to_string(),
+            "1".to_string(),
+            "1".to_string(),
+            42,
+            "0x".to_string(),
+            None,
+        );
+        queue.queue(tx);
+        assert_eq!(reserved("nonce-test-net", from), vec![42]);
+        // Past the TTL, the queued transaction's nonce is still skipped
+        let stale = Utc::now() + Duration::minutes(RESERVATION_TTL_MINS + 1);
+        assert_eq!(WALLETS.lock().get_mut(&key("nonce-test-net", from)).unwrap().reserve(42, stale), 43);
+
+        queue.mark_failed("nonce-test", "test");
+        assert!(!reserved("nonce-test-net", from).contains(&42));
+    }
+}
@@ -75,6 +75,9 @@ pub struct QueuedTransaction {
     pub explorer_url: Option<String>,
     /// Preset name that created this tx (e.g. "identity_register"), for post-processing hooks
     pub preset: Option<String>,
+    /// UUID of the transaction this one replaces (same nonce, higher fee)
+    #[serde(default)]
+    pub replaces: Option<String>,
 }
 
 impl QueuedTransaction {
@@ -114,6 +117,7 @@ impl QueuedTransaction {
             channel_id,
             explorer_url: None,
             preset: None,
+            replaces: None,
         }
     }
 
@@ -123,6 +127,12 @@ impl QueuedTransaction {
         self
     }
 
+    /// Mark this transaction as a speed-up or cancellation of another
+    pub fn with_replaces(mut self, uuid: &str) -> Self {
+        self.replaces = Some(uuid.to_string());
+        self
+    }
+
     /// Get the explorer URL for this transaction's network
     pub fn get_explorer_base_url(&self) -> &'static str {
         if self.network == "mainnet" {
@@ -0,0 +1,328 @@
+//! Amount conversion and validation
+//!
+//! One place for turning amounts into the integers a transaction carries and
+//! back: human decimals ("1.5") to raw units for any token decimals, native
+//! amounts with a unit ("0.1 eth", "20 gwei", "100 wei"), and raw values read
+//! from registers. Everything is exact `U256` arithmetic with overflow checks;
+//! scientific notation ("0.1e18"), negative numbers, separators and
+//! human-looking values where a raw one is expected are refused with an
+//! error that says what to do instead.
+//!
+//! Sanity bounds ([`AmountBounds`]) are the owner's limits on how much of the
+//! wallet's balance one transaction may spend: above `warn_balance_pct` the
+//! intent verifier flags it, above `block_balance_pct` it refuses it. They
+//! live in the kv_store and are changed with `/api/tx-approvals/bounds`.
+
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::db::Database;
+
+pub const ETH_DECIMALS: u8 = 18;
+pub const GWEI_DECIMALS: u8 = 9;
+/// 10^77 is the largest power of ten a U256 holds
+pub const MAX_DECIMALS: u8 = 77;
+
+/// Below this many wei, ETH amounts are shown in wei (0.0001 ETH)
+const SHOW_WEI_BELOW: u64 = 100_000_000_000_000;
+
+/// kv_store namespace for the bounds (outside what the kv_store tool can reach)
+const NAMESPACE: &str = "amount_bounds";
+const SETTINGS_KEY: &str = "settings";
+
+fn pow10(decimals: u8) -> Result<U256, String> {
+    if decimals > MAX_DECIMALS {
+        return Err(format!("{} decimals is more than any token has (max {})", decimals, MAX_DECIMALS));
+    }
+    Ok(U256::exp10(decimals as usize))
+}
+
+fn refuse_notation(amount: &str) -> Result<(), String> {
+    if amount.starts_with('-') {
+        return Err(format!("Amount '{}' is negative", amount));
+    }
+    let numeric = amount.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'));
+    if numeric && amount.contains(['e', 'E']) {
+        return Err(format!(
+            "Amount '{}' is in scientific notation; give it as a plain number (e.g. '0.1', not '0.1e18')",
+            amount
+        ));
+    }
+    Ok(())
+}
+
+/// A human amount ("1.5", ".5", "100") in raw units of a token with `decimals`
+pub fn parse_units(amount: &str, decimals: u8) -> Result<U256, String> {
+    let amount = amount.trim();
+    if amount.is_empty() {
+        return Err("Amount is empty".to_string());
+    }
+    refuse_notation(amount)?;
+    if amount.contains([',', '_', ' ']) {
+        return Err(format!("Amount '{}' has separators; give it as a plain number (e.g. '1000.5')", amount));
+    }
+
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer.chars().all(|c| c.is_ascii_digit())
+        || !fraction.chars().all(|c| c.is_ascii_digit())
+    {
+        return Err(format!("Invalid amount: '{}'. Must be a number.", amount));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(format!(
+            "Amount '{}' has {} decimal places but token only has {} decimals. Maximum precision exceeded.",
+            amount,
+            fraction.len(),
+            decimals
+        ));
+    }
+
+    let too_large = || format!("Amount '{}' is too large", amount);
+    let whole = match integer {
+        "" => U256::zero(),
+        digits => U256::from_dec_str(digits).map_err(|_| too_large())?,
+    };
+    let part = match fraction {
+        "" => U256::zero(),
+        digits => U256::from_dec_str(digits).map_err(|_| too_large())?,
+    };
+    let part_scale = pow10(decimals - fraction.len() as u8)?;
+    whole
+        .checked_mul(pow10(decimals)?)
+        .zip(part.checked_mul(part_scale))
+        .and_then(|(whole, part)| whole.checked_add(part))
+        .ok_or_else(too_large)
+}
+
+/// A raw amount (whole smallest units, decimal or 0x hex) as stored in a register
+/// or a transaction. Refuses values that look like human amounts.
+pub fn parse_raw(raw: &str) -> Result<U256, String> {
+    let raw = raw.trim().trim_matches('"');
+    if raw.is_empty() {
+        return Err("Raw amount is empty".to_string());
+    }
+    if let Some(hex) = raw.strip_prefix("0x").or_else(|| raw.strip_prefix("0X")) {
+        return U256::from_str_radix(hex, 16).map_err(|e| format!("Invalid hex amount '{}': {}", raw, e));
+    }
+    refuse_notation(raw)?;
+    if raw.contains('.') {
+        return Err(format!(
+            "'{}' looks like a human amount; raw amounts are whole numbers of the smallest unit. \
+             Convert it with to_raw_amount first.",
+            raw
+        ));
+    }
+    if !raw.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid raw amount: '{}'. Must be a non-negative integer.", raw));
+    }
+    U256::from_dec_str(raw).map_err(|_| format!("Raw amount '{}' is too large", raw))
+}
+
+/// A native amount with an optional unit: "0.1", "0.1 eth", "20 gwei", "100 wei"
+/// (no unit means ETH), in wei
+pub fn parse_native(amount: &str) -> Result<U256, String> {
+    let lower = amount.trim().to_lowercase();
+    let split = lower.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(lower.len());
+    let (number, unit) = lower.split_at(split);
+    let decimals = match unit.trim() {
+        "" | "eth" | "ether" => ETH_DECIMALS,
+        "gwei" => GWEI_DECIMALS,
+        "wei" => 0,
+        other => {
+            refuse_notation(&lower)?;
+            return Err(format!("Unknown unit '{}' in '{}' (use eth, gwei or wei)", other, amount.trim()));
+        }
+    };
+    parse_units(number, decimals)
+}
+
+/// Exact decimal form of a raw amount, without trailing zeros ("1.5", "0.000001")
+pub fn format_units(raw: U256, decimals: u8) -> String {
+    let digits = raw.to_string();
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return digits;
+    }
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (integer, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+/// "0.015 ETH", or "N wei" for dust
+pub fn format_eth(wei: U256) -> String {
+    if !wei.is_zero() && wei < U256::from(SHOW_WEI_BELOW) {
+        format!("{} wei", wei)
+    } else {
+        format!("{} ETH", format_units(wei, ETH_DECIMALS))
+    }
+}
+
+/// "1.5 gwei" (gas prices)
+pub fn format_gwei(wei: U256) -> String {
+    format!("{} gwei", format_units(wei, GWEI_DECIMALS))
+}
+
+/// An ERC-20 amount a transaction spends, in raw units
+#[derive(Debug, Clone)]
+pub struct TokenSpend {
+    pub token: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub amount: U256,
+}
+
+/// Share of `balance` that `amount` is, in percent (None for an empty balance)
+fn share_pct(amount: U256, balance: U256) -> Option<f64> {
+    if balance.is_zero() {
+        return None;
+    }
+    // In basis points; amounts past the balance only need to be "over 100%"
+    let capped = amount.min(balance.saturating_mul(U256::from(100u64)));
+    let bps = capped.saturating_mul(U256::from(10_000u64)) / balance;
+    Some(bps.low_u64() as f64 / 100.0)
+}
+
+fn default_warn_pct() -> f64 {
+    50.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmountBounds {
+    /// Flag transactions that spend more than this share of the balance (percent)
+    #[serde(default = "default_warn_pct")]
+    pub warn_balance_pct: f64,
+    /// Refuse transactions above this share (percent); None never refuses
+    #[serde(default)]
+    pub block_balance_pct: Option<f64>,
+}
+
+impl Default for AmountBounds {
+    fn default() -> Self {
+        AmountBounds { warn_balance_pct: default_warn_pct(), block_balance_pct: None }
+    }
+}
+
+/// What the bounds say about one amount
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoundCheck {
+    Within,
+    Warn(String),
+    Block(String),
+}
+
+impl AmountBounds {
+    /// Compare an amount leaving the wallet with the balance it comes from
+    pub fn check(&self, amount: U256, balance: U256, decimals: u8, symbol: &str) -> BoundCheck {
+        if amount.is_zero() {
+            return BoundCheck::Within;
+        }
+        let share = share_pct(amount, balance);
+        let describe = |limit: f64| {
+            let share = share.map(|s| format!("{}%", s)).unwrap_or_else(|| "all".to_string());
+            format!(
+                "spends {} {} — {} of the wallet's {} {} balance (limit {}%)",
+                format_units(amount, decimals),
+                symbol,
+                share,
+                format_units(balance, decimals),
+                symbol,
+                limit
+            )
+        };
+        let over = |limit: f64| share.is_none_or(|s| s > limit);
+        match self.block_balance_pct {
+            Some(limit) if over(limit) => BoundCheck::Block(describe(limit)),
+            _ if over(self.warn_balance_pct) => BoundCheck::Warn(describe(self.warn_balance_pct)),
+            _ => BoundCheck::Within,
+        }
+    }
+}
+
+pub fn load_bounds(db: &Database) -> AmountBounds {
+    db.kv_get(NAMESPACE, SETTINGS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|entry| serde_json::from_value(entry.value).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_bounds(db: &Database, bounds: &AmountBounds) -> Result<(), String> {
+    let value = serde_json::to_value(bounds).map_err(|e| e.to_string())?;
+    db.kv_set(NAMESPACE, SETTINGS_KEY, &value, None).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Raw balance of `owner` on a network: native when `token` is None (or the
+/// native sentinel), otherwise the ERC-20 at that address
+pub async fn balance_of(network: &str, owner: &str, token: Option<&str>) -> Result<U256, String> {
+    let token = token.filter(|t| !t.eq_ignore_ascii_case(crate::journal::prices::NATIVE_TOKEN));
+    let result = match token {
+        None => crate::bridge::rpc_request(network, "eth_getBalance", json!([owner, "latest"])).await?,
+        Some(token) => {
+            // balanceOf(address)
+            let data = format!("0x70a08231{:0>64}", owner.trim_start_matches("0x").to_lowercase());
+            crate::bridge::rpc_request(network, "eth_call", json!([{ "to": token, "data": data }, "latest"])).await?
+        }
+    };
+    let hex = result.as_str().ok_or_else(|| format!("Unexpected balance result: {}", result))?;
+    parse_raw(if hex == "0x" { "0x0" } else { hex })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(parse_units("1.5", 18).unwrap(), U256::exp10(18) * 3 / 2);
+        assert_eq!(parse_units(".5", 6).unwrap(), U256::from(500_000u64));
+        assert_eq!(parse_units("100", 0).unwrap(), U256::from(100u64));
+        assert!(parse_units("0.1e18", 18).unwrap_err().contains("scientific notation"));
+        assert!(parse_units("1,000", 6).is_err());
+        assert!(parse_units("-1", 18).is_err());
+        assert!(parse_units("0.0000001", 6).unwrap_err().contains("precision"));
+        assert!(parse_units("1", 78).is_err());
+        assert!(parse_units(&"9".repeat(70), 18).unwrap_err().contains("too large"));
+
+        assert_eq!(parse_raw("1000").unwrap(), U256::from(1000u64));
+        assert_eq!(parse_raw("0x10").unwrap(), U256::from(16u64));
+        assert!(parse_raw("0.1").unwrap_err().contains("to_raw_amount"));
+        assert!(parse_raw("1e17").is_err());
+        assert!(parse_raw("ether").unwrap_err().contains("non-negative integer"));
+
+        assert_eq!(parse_native("0.1").unwrap(), U256::exp10(17));
+        assert_eq!(parse_native("20 gwei").unwrap(), U256::from(20_000_000_000u64));
+        assert_eq!(parse_native("100wei").unwrap(), U256::from(100u64));
+        assert!(parse_native("5 btc").is_err());
+
+        assert_eq!(format_units(U256::from(871_043_093u64), 6), "871.043093");
+        assert_eq!(format_units(U256::from(1u64), 6), "0.000001");
+        assert_eq!(format_units(U256::exp10(18), 18), "1");
+        assert_eq!(format_eth(U256::exp10(16) * 15 / 10), "0.015 ETH");
+        assert_eq!(format_eth(U256::from(42u64)), "42 wei");
+        assert_eq!(format_gwei(U256::from(1_500_000_000u64)), "1.5 gwei");
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = AmountBounds { warn_balance_pct: 50.0, block_balance_pct: Some(90.0) };
+        let usdc = |n: u64| U256::from(n * 1_000_000);
+        assert_eq!(bounds.check(usdc(10), usdc(100), 6, "USDC"), BoundCheck::Within);
+        match bounds.check(usdc(60), usdc(100), 6, "USDC") {
+            BoundCheck::Warn(text) => assert_eq!(
+                text,
+                "spends 60 USDC — 60% of the wallet's 100 USDC balance (limit 50%)"
+            ),
+            other => panic!("expected a warning, got {:?}", other),
+        }
+        assert!(matches!(bounds.check(usdc(95), usdc(100), 6, "USDC"), BoundCheck::Block(_)));
+        assert!(matches!(bounds.check(usdc(1), U256::zero(), 6, "USDC"), BoundCheck::Block(_)));
+        assert!(matches!(AmountBounds::default().check(usdc(500), usdc(100), 6, "USDC"), BoundCheck::Warn(_)));
+    }
+}
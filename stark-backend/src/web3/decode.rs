@@ -0,0 +1,346 @@
+//! Human-readable transaction previews
+//!
+//! Matches calldata against every ABI in the registry (global abis/ first, then
+//! skill ABIs), decodes the parameters, and explains the call: token amounts
+//! are scaled by decimals when the target is a known token, known token
+//! addresses are labelled, and anything that hands spending rights to another
+//! address (approve, increaseAllowance, permit, setApprovalForAll) is flagged.
+//! Used by verify_intent prompts, tx approval cards, and the decode_tx tool.
+
+use ethers::abi::{Abi, Function, Token};
+use ethers::types::U256;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{default_abis_dir, list_abi_names, load_abi, parse_abi, token_to_value};
+use crate::token_metadata;
+
+/// Allowances at or above this are effectively unlimited (2^255)
+fn unlimited_threshold() -> U256 {
+    U256::one() << 255
+}
+
+/// Byte/array params longer than this are shortened in explanations
+const MAX_DISPLAY_CHARS: usize = 80;
+
+/// One decoded function parameter
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedParam {
+    pub name: String,
+    pub kind: String,
+    pub value: Value,
+    /// Human-readable rendering (token amounts scaled, tokens labelled)
+    pub display: String,
+}
+
+/// A call matched against an ABI
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedCall {
+    pub abi: String,
+    pub function: String,
+    pub signature: String,
+    pub params: Vec<DecodedParam>,
+}
+
+/// Everything a human needs to judge a transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct TxPreview {
+    pub network: String,
+    pub to: String,
+    /// Symbol of the target contract when it is a known token
+    pub to_token: Option<String>,
+    pub value_wei: String,
+    pub value_display: String,
+    /// 4-byte selector (None for plain transfers)
+    pub selector: Option<String>,
+    pub call: Option<DecodedCall>,
+    /// Warnings worth a second look (approvals, undecodable calldata)
+    pub flags: Vec<String>,
+}
+
+impl TxPreview {
+    /// Multi-line plain explanation (markdown-safe)
+    pub fn explain(&self) -> String {
+        let mut lines = vec![format!("Network: {}", self.network)];
+        match self.to_token {
+            Some(ref symbol) => lines.push(format!("To: `{}` ({} token contract)", self.to, symbol)),
+            None => lines.push(format!("To: `{}`", self.to)),
+        }
+        lines.push(format!("Value: {}", self.value_display));
+        match (&self.call, &self.selector) {
+            (Some(call), _) => {
+                lines.push(format!("Function: `{}` (ABI: {})", call.signature, call.abi));
+                for param in &call.params {
+                    let name = if param.name.is_empty() { param.kind.as_str() } else { param.name.as_str() };
+                    lines.push(format!("- {}: {}", name, param.display));
+                }
+            }
+            (None, Some(selector)) => lines.push(format!("Function: unknown (selector `{}`)", selector)),
+            (None, None) => lines.push("Function: none (plain native transfer)".to_string()),
+        }
+        for flag in &self.flags {
+            lines.push(format!("WARNING: {}", flag));
+        }
+        lines.join("\n")
+    }
+}
+
+/// ABIs from the registry, parsed. Unparseable ABIs are skipped.
+pub fn registry_abis() -> Vec<(String, Abi)> {
+    let abis_dir = default_abis_dir();
+    list_abi_names(&abis_dir)
+        .into_iter()
+        .filter_map(|name| {
+            let abi = load_abi(&abis_dir, &name).and_then(|file| parse_abi(&file)).ok()?;
+            Some((name, abi))
+        })
+        .collect()
+}
+
+/// Preview a transaction using the ABI registry
+pub fn preview(network: &str, to: &str, value_wei: &str, calldata_hex: &str) -> TxPreview {
+    preview_with(&registry_abis(), network, to, value_wei, calldata_hex)
+}
+
+/// Preview a transaction against the given ABIs
+pub fn preview_with(abis: &[(String, Abi)], network: &str, to: &str, value_wei: &str, calldata_hex: &str) -> TxPreview {
+    let to_token = token_metadata::lookup(network, to);
+    let mut flags = Vec::new();
+
+    let hex_str = calldata_hex.trim().trim_start_matches("0x");
+    let calldata = match hex::decode(hex_str) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            flags.push("Calldata is not valid hex".to_string());
+            Vec::new()
+        }
+    };
+
+    let selector = (calldata.len() >= 4).then(|| format!("0x{}", hex::encode(&calldata[..4])));
+    let call = selector.as_ref().and_then(|_| {
+        let (abi_name, function, tokens) = match_function(abis, &calldata)?;
+        let token_ctx = to_token.as_ref().map(|token| (token.symbol.as_str(), token.decimals));
+        Some(describe_call(&abi_name, function, &tokens, network, token_ctx, &mut flags))
+    });
+    if selector.is_some() && call.is_none() {
+        flags.push("Calldata could not be decoded against any known ABI — verify the target and function manually".to_string());
+    } else if !calldata.is_empty() && calldata.len() < 4 {
+        flags.push("Calldata is shorter than a function selector".to_string());
+    }
+
+    TxPreview {
+        network: network.to_string(),
+        to: to.to_string(),
+        to_token: to_token.map(|token| token.symbol),
+        value_wei: value_wei.to_string(),
+        value_display: format_native(value_wei),
+        selector,
+        call,
+        flags,
+    }
+}
+
+/// First ABI function whose selector matches and whose parameters decode cleanly
+fn match_function<'a>(abis: &'a [(String, Abi)], calldata: &[u8]) -> Option<(String, &'a Function, Vec<Token>)> {
+    let selector = &calldata[..4];
+    for (name, abi) in abis {
+        for function in abi.functions() {
+            if function.short_signature() != selector {
+                continue;
+            }
+            if let Ok(tokens) = function.decode_input(&calldata[4..]) {
+                return Some((name.clone(), function, tokens));
+            }
+        }
+    }
+    None
+}
+
+fn describe_call(
+    abi_name: &str,
+    function: &Function,
+    tokens: &[Token],
+    network: &str,
+    token_ctx: Option<(&str, u8)>,
+    flags: &mut Vec<String>,
+) -> DecodedCall {
+    let params: Vec<DecodedParam> = function
+        .inputs
+        .iter()
+        .zip(tokens)
+        .map(|(input, token)| DecodedParam {
+            name: input.name.clone(),
+            kind: input.kind.to_string(),
+            value: token_to_value(token),
+            display: display_token(&input.name, token, network, token_ctx),
+        })
+        .collect();
+
+    flag_approvals(&function.name, tokens, &params, token_ctx, flags);
+
+    DecodedCall {
+        abi: abi_name.to_string(),
+        function: function.name.clone(),
+        signature: function.signature().split(':').next().unwrap_or_default().to_string(),
+        params,
+    }
+}
+
+/// Parameter names that carry an amount of the target token
+fn is_amount_param(name: &str) -> bool {
+    let name = name.trim_start_matches('_').to_lowercase();
+    matches!(name.as_str(), "amount" | "value" | "wad" | "addedvalue" | "subtractedvalue" | "rawamount")
+}
+
+fn display_token(name: &str, token: &Token, network: &str, token_ctx: Option<(&str, u8)>) -> String {
+    match token {
+        Token::Address(addr) => {
+            let addr = format!("{:?}", addr);
+            match token_metadata::lookup(network, &addr) {
+                Some(token) => format!("`{}` ({})", addr, token.symbol),
+                None => format!("`{}`", addr),
+            }
+        }
+        Token::Uint(n) => match token_ctx {
+            Some((symbol, decimals)) if is_amount_param(name) => format_token_amount(*n, decimals, symbol),
+            _ => n.to_string(),
+        },
+        other => {
+            let rendered = match token_to_value(other) {
+                Value::String(s) => s,
+                v => v.to_string(),
+            };
+            shorten(&rendered)
+        }
+    }
+}
+
+fn shorten(s: &str) -> String {
+    if s.chars().count() <= MAX_DISPLAY_CHARS {
+        return s.to_string();
+    }
+    let head: String = s.chars().take(MAX_DISPLAY_CHARS).collect();
+    format!("{}… ({} chars)", head, s.chars().count())
+}
+
+/// Whether an allowance is large enough to count as unlimited
+pub fn is_unlimited(amount: U256) -> bool {
+    amount >= unlimited_threshold()
+}
+
+/// `1500000` with 6 decimals and symbol USDC -> `1.5 USDC`
+pub fn format_token_amount(amount: U256, decimals: u8, symbol: &str) -> String {
+    if is_unlimited(amount) {
+        return format!("UNLIMITED {}", symbol);
+    }
+    let scaled = ethers::utils::format_units(amount, decimals as u32).unwrap_or_else(|_| amount.to_string());
+    let trimmed = if scaled.contains('.') {
+        scaled.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        scaled
+    };
+    format!("{} {}", trimmed, symbol)
+}
+
+fn format_native(value_wei: &str) -> String {
+    match U256::from_dec_str(value_wei.trim()) {
+        Ok(wei) if wei.is_zero() => "0 ETH".to_string(),
+        Ok(wei) => format!("{} ({} wei)", format_token_amount(wei, 18, "ETH"), wei),
+        Err(_) => format!("{} wei", value_wei),
+    }
+}
+
+/// Flag calls that let another address move the owner's assets
+fn flag_approvals(
+    function: &str,
+    tokens: &[Token],
+    params: &[DecodedParam],
+    token_ctx: Option<(&str, u8)>,
+    flags: &mut Vec<String>,
+) {
+    let what = token_ctx.map(|(symbol, _)| symbol).unwrap_or("tokens from this contract");
+    match (function, tokens) {
+        ("approve" | "increaseAllowance", [Token::Address(_), Token::Uint(amount)]) => {
+            let allowance = match token_ctx {
+                _ if *amount >= unlimited_threshold() => format!("an UNLIMITED amount of {}", what),
+                Some((symbol, decimals)) => format_token_amount(*amount, decimals, symbol),
+                None => format!("{} raw units of {}", amount, what),
+            };
+            flags.push(format!(
+                "Spender approval: {} may spend {} on your behalf",
+                params[0].display, allowance
+            ));
+        }
+        ("setApprovalForAll", [Token::Address(_), Token::Bool(true)]) => {
+            flags.push(format!(
+                "Operator approval: {} gets control of ALL your NFTs in this collection",
+                params[0].display
+            ));
+        }
+        ("permit", _) => {
+            if let Some(spender) = params.iter().find(|p| p.name.trim_start_matches('_') == "spender") {
+                flags.push(format!("Permit signature: grants {} an allowance of {}", spender.display, what));
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn erc20() -> Vec<(String, Abi)> {
+        let abi: Abi = serde_json::from_value(serde_json::json!([
+            {"name": "approve", "type": "function", "stateMutability": "nonpayable",
+             "inputs": [{"name": "spender", "type": "address"}, {"name": "amount", "type": "uint256"}],
+             "outputs": [{"name": "", "type": "bool"}]},
+            {"name": "transfer", "type": "function", "stateMutability": "nonpayable",
+             "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}],
+             "outputs": [{"name": "", "type": "bool"}]}
+        ]))
+        .unwrap();
+        vec![("erc20".to_string(), abi)]
+    }
+
+    fn calldata(abis: &[(String, Abi)], name: &str, tokens: &[Token]) -> String {
+        let function = abis[0].1.function(name).unwrap();
+        format!("0x{}", hex::encode(function.encode_input(tokens).unwrap()))
+    }
+
+    #[test]
+    fn test_decodes_call_and_flags_unlimited_approval() {
+        let abis = erc20();
+        let spender = Token::Address("0x000000000000000000000000000000000000dead".parse().unwrap());
+        let data = calldata(&abis, "approve", &[spender, Token::Uint(U256::MAX)]);
+
+        let preview = preview_with(&abis, "base", "0x1111111111111111111111111111111111111111", "0", &data);
+        let call = preview.call.as_ref().unwrap();
+        assert_eq!(call.signature, "approve(address,uint256)");
+        assert_eq!(call.params[0].name, "spender");
+        assert_eq!(preview.flags.len(), 1);
+        assert!(preview.flags[0].contains("UNLIMITED"));
+        assert!(preview.explain().contains("WARNING: Spender approval"));
+    }
+
+    #[test]
+    fn test_unknown_selector_and_plain_transfer() {
+        let abis = erc20();
+        let unknown = preview_with(&abis, "base", "0xabc", "0", "0xdeadbeef00");
+        assert!(unknown.call.is_none());
+        assert_eq!(unknown.selector.as_deref(), Some("0xdeadbeef"));
+        assert!(unknown.flags[0].contains("could not be decoded"));
+
+        let plain = preview_with(&abis, "base", "0xabc", "1500000000000000000", "0x");
+        assert!(plain.flags.is_empty());
+        assert!(plain.value_display.starts_with("1.5 ETH"));
+        assert!(plain.explain().contains("plain native transfer"));
+    }
+
+    #[test]
+    fn test_format_token_amount() {
+        assert_eq!(format_token_amount(U256::from(1_500_000u64), 6, "USDC"), "1.5 USDC");
+        assert_eq!(format_token_amount(U256::from(2_000_000u64), 6, "USDC"), "2 USDC");
+        assert_eq!(format_token_amount(U256::MAX, 18, "WETH"), "UNLIMITED WETH");
+    }
+}
@@ -3,12 +3,15 @@
 //! Shared by `web3_function_call` (manual mode) and `web3_preset_function_call` (preset mode).
 //! Provides ABI loading, encoding/decoding, transaction signing, and call execution.
 
+use crate::db::tables::paper_trades::{RecordPaperTradeRequest, PAPER_KIND_CALL, PAPER_KIND_TRANSFER};
+use crate::db::tables::trades::{RecordTradeRequest, TRADE_KIND_TRANSFER};
 use crate::tools::builtin::cryptocurrency::verify_intent::{self, TransactionIntent};
 use crate::tools::builtin::cryptocurrency::web3_tx::parse_u256;
 use crate::tools::rpc_config::{resolve_rpc_from_context, Network, ResolvedRpcConfig};
 use crate::tools::types::{ToolContext, ToolResult};
-use crate::tx_queue::QueuedTransaction;
+use crate::tx_queue::{funds, QueuedTransaction};
 use crate::wallet::WalletProvider;
+use crate::web3::amounts::TokenSpend;
 use crate::x402::X402EvmRpc;
 use ethers::abi::{Abi, Function, ParamType, Token};
 use ethers::prelude::*;
@@ -22,6 +25,10 @@ use std::str::FromStr;
 use std::sync::{Arc, Mutex, OnceLock};
 use uuid::Uuid;
 
+pub mod amounts;
+pub mod decode;
+pub mod typed_data;
+
 // ---- Shared types and helpers (used by both manual and preset tools) ----
 
 /// Signed transaction result for queuing (not broadcast)
@@ -128,6 +135,35 @@ pub fn load_abi(abis_dir: &PathBuf, name: &str) -> Result<AbiFile, String> {
     Err(format!("ABI '{}' not found in {} or any skill", name, abis_dir.display()))
 }
 
+/// Names of every known ABI: global abis/ directory first, then skill ABIs
+pub fn list_abi_names(abis_dir: &PathBuf) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(abis_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| {
+                    let path = e.ok()?.path();
+                    if path.extension()? != "json" {
+                        return None;
+                    }
+                    Some(path.file_stem()?.to_string_lossy().to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+
+    let mut skill_names: Vec<String> = abi_index()
+        .lock()
+        .unwrap()
+        .keys()
+        .filter(|name| !names.contains(name))
+        .cloned()
+        .collect();
+    skill_names.sort();
+    names.extend(skill_names);
+    names
+}
+
 /// Parse ethers Abi from our ABI file format
 pub fn parse_abi(abi_file: &AbiFile) -> Result<Abi, String> {
     let abi_json = serde_json::to_string(&abi_file.abi)
@@ -373,7 +409,7 @@ pub async fn sign_transaction_for_queue(
         .map_err(|_| format!("Invalid wallet address: {}", from_str))?;
     let to_str = format!("{:?}", to);
 
-    let nonce = rpc.get_transaction_count(from_address).await?;
+    let nonce = crate::tx_queue::nonce::reserve(&rpc, network, from_address).await?;
 
     let gas: U256 = rpc.estimate_gas(from_address, to, &calldata, value).await?;
     let gas = gas * U256::from(120) / U256::from(100); // 20% buffer
@@ -473,6 +509,73 @@ fn try_auto_format_result(
     }
 }
 
+/// Paper-mode stand-in for signing a contract write. ERC-20 transfers of known
+/// tokens are recorded as transfers; anything else as a call spending `value`.
+#[allow(clippy::too_many_arguments)]
+async fn paper_call(
+    abi_name: &str,
+    contract_addr: &str,
+    function_name: &str,
+    call_params: &[Value],
+    value: U256,
+    network: &Network,
+    context: &ToolContext,
+    preset_name: Option<&str>,
+) -> ToolResult {
+    let param_str = |v: &Value| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+    let token = if abi_name == "erc20" && function_name == "transfer" && call_params.len() == 2 {
+        crate::token_metadata::lookup(network.as_ref(), contract_addr)
+    } else {
+        None
+    };
+    let details = json!({
+        "abi": abi_name,
+        "function": function_name,
+        "params": call_params,
+        "preset": preset_name,
+    });
+    let req = match token {
+        Some(token) => RecordPaperTradeRequest {
+            kind: PAPER_KIND_TRANSFER,
+            network: network.as_ref().to_string(),
+            sell_token: contract_addr.to_string(),
+            sell_symbol: token.symbol,
+            sell_amount_raw: param_str(&call_params[1]),
+            sell_decimals: token.decimals,
+            buy_token: None,
+            buy_symbol: None,
+            buy_amount_raw: None,
+            buy_decimals: None,
+            counterparty: Some(param_str(&call_params[0])),
+            sell_price_usd: None,
+            buy_price_usd: None,
+            details: Some(details),
+            channel_id: context.channel_id,
+        },
+        None => RecordPaperTradeRequest {
+            kind: PAPER_KIND_CALL,
+            network: network.as_ref().to_string(),
+            sell_token: crate::journal::prices::NATIVE_TOKEN.to_string(),
+            sell_symbol: network.native_currency().to_string(),
+            sell_amount_raw: value.to_string(),
+            sell_decimals: 18,
+            buy_token: None,
+            buy_symbol: None,
+            buy_amount_raw: None,
+            buy_decimals: None,
+            counterparty: Some(format!("{}::{}() at {}", abi_name, function_name, contract_addr)),
+            sell_price_usd: None,
+            buy_price_usd: None,
+            details: Some(details),
+            channel_id: context.channel_id,
+        },
+    };
+    match crate::paper::record_fill(context, req).await {
+        Ok(fill) => crate::paper::fill_result(&fill),
+        Err(e) => ToolResult::error(e),
+    }
+}
+
 /// Shared execution logic: ABI loading, encoding, safety checks, call/sign/queue.
 /// Used by both `Web3FunctionCallTool` (manual) and `Web3PresetFunctionCallTool` (preset).
 pub async fn execute_resolved_call(
@@ -637,6 +740,12 @@ pub async fn execute_resolved_call(
             Err(e) => return ToolResult::error(format!("Invalid value: {} - {}", value, e)),
         };
 
+        // Paper mode: record the call instead of signing it
+        if crate::paper::is_enabled(context) {
+            return paper_call(abi_name, contract_addr, function_name, call_params, tx_value, network, context, preset_name)
+                .await;
+        }
+
         // Check if we're in a gateway channel without rogue mode
         let is_gateway_channel = context.channel_type
             .as_ref()
@@ -694,6 +803,21 @@ pub async fn execute_resolved_call(
                     "contract_call"
                 };
 
+                // ERC-20 transfers of known tokens spend them besides the native value
+                let token_spend = if abi_name == "erc20" && function_name == "transfer" && call_params.len() == 2 {
+                    crate::token_metadata::lookup(network.as_ref(), contract_addr).and_then(|token| {
+                        let raw = call_params[1].as_str().map(str::to_string).unwrap_or_else(|| call_params[1].to_string());
+                        Some(TokenSpend {
+                            token: contract_addr.to_string(),
+                            symbol: token.symbol,
+                            decimals: token.decimals,
+                            amount: amounts::parse_raw(&raw).ok()?,
+                        })
+                    })
+                } else {
+                    None
+                };
+
                 let intent = TransactionIntent {
                     tx_type: tx_type.to_string(),
                     to: contract_addr.to_string(),
@@ -709,6 +833,7 @@ pub async fn execute_resolved_call(
                         "Call {}::{}() on {}",
                         abi_name, function_name, signed.network,
                     ),
+                    token_spend,
                 };
                 if let Err(reason) = verify_intent::verify_intent(&intent, context, None).await {
                     return ToolResult::error(reason);
@@ -732,10 +857,40 @@ pub async fn execute_resolved_call(
                 )
                 .with_preset(preset_name);
 
+                // Refuse what the wallet can't pay for (tokens and gas) rather than fail on-chain
+                let spend = verify_intent::token_spend(&intent, context);
+                if let Err(reason) = funds::ensure_affordable(tx_queue, &queued_tx, spend.as_ref(), &[]).await {
+                    crate::tx_queue::nonce::release(&queued_tx.network, &queued_tx.from, queued_tx.nonce);
+                    return ToolResult::error(reason);
+                }
+
                 tx_queue.queue(queued_tx);
 
                 log::info!("[web3_function_call] Transaction queued with UUID: {}", uuid);
 
+                // Journal ERC-20 transfers of known tokens (amounts need decimals)
+                if abi_name == "erc20" && function_name == "transfer" && call_params.len() == 2 {
+                    let token = crate::token_metadata::lookup(network.as_ref(), contract_addr);
+                    if let (Some(db), Some(token)) = (&context.database, token) {
+                        let param_str = |v: &Value| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+                        crate::journal::record(db, RecordTradeRequest {
+                            tx_uuid: uuid.clone(),
+                            kind: TRADE_KIND_TRANSFER,
+                            network: signed.network.clone(),
+                            sell_token: contract_addr.to_string(),
+                            sell_symbol: token.symbol,
+                            sell_amount_raw: param_str(&call_params[1]),
+                            sell_decimals: token.decimals,
+                            buy_token: None,
+                            buy_symbol: None,
+                            buy_amount_raw: None,
+                            buy_decimals: None,
+                            counterparty: Some(param_str(&call_params[0])),
+                            channel_id: context.channel_id,
+                        });
+                    }
+                }
+
                 let value_eth = if let Ok(w) = signed.value.parse::<u128>() {
                     let eth = w as f64 / 1e18;
                     if eth >= 0.0001 {
@@ -0,0 +1,307 @@
+//! EIP-712 typed data signing policy
+//!
+//! Loaded from `config/typed_data_policy.ron` at startup (built-in defaults if
+//! the file is missing). The checks are deterministic and run before any AI
+//! intent verification in `sign_typed_data`:
+//!
+//! - the signing domain must be on the allow-list (by verifying contract and
+//!   chain, or by name for off-chain attestations without a contract);
+//! - permits (EIP-2612 `Permit` and Permit2) must name an allow-listed spender,
+//!   be owned by the agent's wallet, expire within `max_validity_secs`, and
+//!   not grant an unlimited amount unless `allow_unlimited` is set.
+
+use ethers::types::U256;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::RwLock;
+
+/// Permit2 primary types that move or approve tokens for a spender
+const PERMIT2_TYPES: &[&str] = &[
+    "PermitSingle",
+    "PermitBatch",
+    "PermitTransferFrom",
+    "PermitBatchTransferFrom",
+    "PermitWitnessTransferFrom",
+    "PermitBatchWitnessTransferFrom",
+];
+
+/// A domain the agent may sign for
+#[derive(Debug, Clone, Deserialize)]
+pub struct AllowedDomain {
+    /// EIP-712 domain name (matched case-insensitively when there is no contract)
+    pub name: String,
+    #[serde(default)]
+    pub verifying_contract: Option<String>,
+    /// Chains the contract is trusted on; empty = any chain
+    #[serde(default)]
+    pub chain_ids: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TypedDataPolicy {
+    #[serde(default)]
+    pub domains: Vec<AllowedDomain>,
+    /// Addresses permits may name as spender
+    #[serde(default)]
+    pub allowed_spenders: Vec<String>,
+    /// Longest a permit may stay valid, from now
+    #[serde(default = "default_max_validity_secs")]
+    pub max_validity_secs: u64,
+    /// Allow permits for (effectively) unlimited amounts
+    #[serde(default)]
+    pub allow_unlimited: bool,
+}
+
+fn default_max_validity_secs() -> u64 {
+    7 * 24 * 3600
+}
+
+static POLICY: RwLock<Option<TypedDataPolicy>> = RwLock::new(None);
+
+fn builtin_policy() -> TypedDataPolicy {
+    TypedDataPolicy {
+        domains: vec![AllowedDomain {
+            name: "Permit2".to_string(),
+            verifying_contract: Some("0x000000000022D473030F116dDEE9F6B43aC78BA3".to_string()),
+            chain_ids: vec![],
+        }],
+        allowed_spenders: vec![
+            // 0x AllowanceHolder (swap_token)
+            "0x0000000000001fF3684f28c67538d4D072C22734".to_string(),
+        ],
+        max_validity_secs: default_max_validity_secs(),
+        allow_unlimited: false,
+    }
+}
+
+/// Load the policy from the RON config file. Called once at startup.
+pub fn load_policy(config_dir: &Path) {
+    let path = config_dir.join("typed_data_policy.ron");
+    let policy = match std::fs::read_to_string(&path) {
+        Ok(content) => match ron::from_str::<TypedDataPolicy>(&content) {
+            Ok(policy) => {
+                log::info!(
+                    "[typed_data] Loaded signing policy: {} domains, {} spenders",
+                    policy.domains.len(),
+                    policy.allowed_spenders.len()
+                );
+                policy
+            }
+            Err(e) => {
+                log::error!("[typed_data] Failed to parse {:?}: {}", path, e);
+                builtin_policy()
+            }
+        },
+        Err(_) => {
+            log::warn!("[typed_data] {:?} not found, using built-in policy", path);
+            builtin_policy()
+        }
+    };
+    *POLICY.write().unwrap_or_else(|e| e.into_inner()) = Some(policy);
+}
+
+/// Current policy (built-in defaults if never loaded)
+pub fn policy() -> TypedDataPolicy {
+    POLICY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(builtin_policy)
+}
+
+/// Numbers in typed data may be JSON numbers, decimal strings or hex strings
+fn value_u256(v: &Value) -> Option<U256> {
+    match v {
+        Value::Number(n) => n.as_u64().map(U256::from),
+        Value::String(s) => {
+            let s = s.trim();
+            match s.strip_prefix("0x") {
+                Some(hex) => U256::from_str_radix(hex, 16).ok(),
+                None => U256::from_dec_str(s).ok(),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn same_address(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// Check the domain against the allow-list
+fn check_domain(policy: &TypedDataPolicy, domain: &Value) -> Result<(), String> {
+    let name = domain["name"].as_str().unwrap_or("");
+    let chain_id = value_u256(&domain["chainId"]).map(|c| c.low_u64());
+
+    let allowed = match domain["verifyingContract"].as_str() {
+        Some(contract) => policy.domains.iter().any(|d| {
+            d.verifying_contract.as_deref().is_some_and(|c| same_address(c, contract))
+                && (d.chain_ids.is_empty() || chain_id.is_some_and(|id| d.chain_ids.contains(&id)))
+        }),
+        None => policy
+            .domains
+            .iter()
+            .any(|d| d.verifying_contract.is_none() && d.name.eq_ignore_ascii_case(name)),
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!(
+            "Signing blocked: domain '{}' ({}) is not on the typed data allow-list.",
+            name,
+            domain["verifyingContract"].as_str().unwrap_or("no verifying contract")
+        ))
+    }
+}
+
+/// Amounts at or above this are treated as unlimited (Permit2 caps at uint160)
+fn unlimited_threshold(permit2: bool) -> U256 {
+    if permit2 {
+        (U256::one() << 160) - 1
+    } else {
+        U256::one() << 255
+    }
+}
+
+/// Spender, deadline and amount checks for permits
+fn check_permit(
+    policy: &TypedDataPolicy,
+    primary_type: &str,
+    message: &Value,
+    wallet: &str,
+    now: u64,
+) -> Result<(), String> {
+    let permit2 = PERMIT2_TYPES.contains(&primary_type);
+    if !permit2 && primary_type != "Permit" {
+        return Ok(());
+    }
+
+    let spender = message["spender"].as_str().ok_or("Signing blocked: permit has no spender.")?;
+    if !policy.allowed_spenders.iter().any(|s| same_address(s, spender)) {
+        return Err(format!(
+            "Signing blocked: permit spender {} is not an allow-listed spender.",
+            spender
+        ));
+    }
+
+    // EIP-2612 names the owner explicitly; it must be us
+    if let Some(owner) = message["owner"].as_str() {
+        if !same_address(owner, wallet) {
+            return Err(format!("Signing blocked: permit owner {} is not this wallet.", owner));
+        }
+    }
+
+    // Permit2 allowances carry amount/expiration in `details` (one object or a list)
+    let details: Vec<&Value> = match &message["details"] {
+        Value::Array(items) => items.iter().collect(),
+        Value::Null => vec![],
+        single => vec![single],
+    };
+    let permitted: Vec<&Value> = match &message["permitted"] {
+        Value::Array(items) => items.iter().collect(),
+        Value::Null => vec![],
+        single => vec![single],
+    };
+
+    let limit = now + policy.max_validity_secs;
+    let mut deadlines: Vec<(&str, &Value)> = vec![("deadline", &message["deadline"]), ("sigDeadline", &message["sigDeadline"])];
+    deadlines.extend(details.iter().map(|d| ("expiration", &d["expiration"])));
+    for (field, v) in deadlines {
+        if v.is_null() {
+            continue;
+        }
+        match value_u256(v) {
+            Some(t) if t <= U256::from(limit) => {}
+            _ => {
+                return Err(format!(
+                    "Signing blocked: permit {} is more than {} hours away.",
+                    field,
+                    policy.max_validity_secs / 3600
+                ));
+            }
+        }
+    }
+
+    if !policy.allow_unlimited {
+        let threshold = unlimited_threshold(permit2);
+        let mut amounts: Vec<&Value> = vec![&message["value"]];
+        amounts.extend(details.iter().map(|d| &d["amount"]));
+        amounts.extend(permitted.iter().map(|p| &p["amount"]));
+        if amounts
+            .into_iter()
+            .filter(|v| !v.is_null())
+            .any(|v| value_u256(v).is_none_or(|a| a >= threshold))
+        {
+            return Err("Signing blocked: permit grants an unlimited amount. Permit only the amount needed.".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Run every deterministic policy check on a typed data payload
+pub fn check(policy: &TypedDataPolicy, typed_data: &Value, wallet: &str, now: u64) -> Result<(), String> {
+    let primary_type = typed_data["primaryType"].as_str().ok_or("Typed data is missing 'primaryType'.")?;
+    check_domain(policy, &typed_data["domain"])?;
+    check_permit(policy, primary_type, &typed_data["message"], wallet, now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const WALLET: &str = "0x00000000000000000000000000000000000000aa";
+    const NOW: u64 = 1_700_000_000;
+
+    fn permit_single(spender: &str, amount: &str, expiration: u64) -> Value {
+        json!({
+            "primaryType": "PermitSingle",
+            "domain": { "name": "Permit2", "chainId": 8453, "verifyingContract": "0x000000000022d473030f116ddee9f6b43ac78ba3" },
+            "message": {
+                "details": { "token": "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913", "amount": amount, "expiration": expiration, "nonce": 0 },
+                "spender": spender,
+                "sigDeadline": NOW + 600
+            }
+        })
+    }
+
+    #[test]
+    fn test_permit2_spender_amount_and_expiry() {
+        let policy = builtin_policy();
+        let spender = "0x0000000000001ff3684f28c67538d4d072c22734";
+
+        assert!(check(&policy, &permit_single(spender, "1000000", NOW + 3600), WALLET, NOW).is_ok());
+        // Unknown spender
+        let err = check(&policy, &permit_single("0x00000000000000000000000000000000000000bb", "1000000", NOW + 3600), WALLET, NOW).unwrap_err();
+        assert!(err.contains("spender"));
+        // uint160 max = unlimited
+        let max160 = ((U256::one() << 160) - 1).to_string();
+        assert!(check(&policy, &permit_single(spender, &max160, NOW + 3600), WALLET, NOW).unwrap_err().contains("unlimited"));
+        // Allowance that outlives the policy window
+        assert!(check(&policy, &permit_single(spender, "1", NOW + 365 * 24 * 3600), WALLET, NOW).unwrap_err().contains("expiration"));
+    }
+
+    #[test]
+    fn test_domain_allow_list() {
+        let mut policy = builtin_policy();
+        let attestation = json!({
+            "primaryType": "Attestation",
+            "domain": { "name": "StarkHub Attestations", "version": "1" },
+            "message": { "subject": "agent-1" }
+        });
+        assert!(check(&policy, &attestation, WALLET, NOW).unwrap_err().contains("allow-list"));
+
+        policy.domains.push(AllowedDomain { name: "StarkHub Attestations".to_string(), verifying_contract: None, chain_ids: vec![] });
+        assert!(check(&policy, &attestation, WALLET, NOW).is_ok());
+
+        // A contract-bound entry doesn't match a look-alike name on another contract
+        let spoofed = json!({
+            "primaryType": "Order",
+            "domain": { "name": "Permit2", "chainId": 8453, "verifyingContract": "0x00000000000000000000000000000000000000cc" },
+            "message": {}
+        });
+        assert!(check(&policy, &spoofed, WALLET, NOW).is_err());
+    }
+}
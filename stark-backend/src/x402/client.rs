@@ -14,7 +14,7 @@ use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 
 use super::signer::X402Signer;
-use super::types::{PaymentRequired, X402PaymentInfo};
+use super::types::{PaymentRequired, PaymentRequirements, X402PaymentInfo};
 use crate::erc8128::Erc8128Signer;
 use crate::wallet::WalletProvider;
 
@@ -46,6 +46,8 @@ pub struct X402Client {
     erc8128_credits_hosts: Arc<Mutex<HashSet<String>>>,
     /// Payment mode controlling credit vs x402 negotiation
     payment_mode: PaymentMode,
+    /// Optional cap on any single payment this client signs: (asset, smallest units)
+    max_payment: Option<(String, u128)>,
 }
 
 impl X402Client {
@@ -63,6 +65,7 @@ impl X402Client {
             erc8128_signer,
             erc8128_credits_hosts: Arc::new(Mutex::new(HashSet::new())),
             payment_mode: PaymentMode::Auto,
+            max_payment: None,
         })
     }
 
@@ -72,6 +75,14 @@ impl X402Client {
         self
     }
 
+    /// Refuse to sign any payment above `max_raw` smallest units of `asset`,
+    /// or in any other asset (builder pattern). Applied on top of the global
+    /// per-asset payment limits.
+    pub fn with_max_payment(mut self, asset: &str, max_raw: u128) -> Self {
+        self.max_payment = Some((asset.to_string(), max_raw));
+        self
+    }
+
     /// Create a new x402 client with a private key (backward compatible)
     pub fn from_private_key(private_key: &str) -> Result<Self, String> {
         // For backward compat: create an EnvWalletProvider-equivalent
@@ -93,6 +104,7 @@ impl X402Client {
             erc8128_signer,
             erc8128_credits_hosts: Arc::new(Mutex::new(HashSet::new())),
             payment_mode: PaymentMode::Auto,
+            max_payment: None,
         })
     }
 
@@ -399,6 +411,9 @@ impl X402Client {
             &requirements.asset,
             &requirements.max_amount_required,
         )?;
+        if let Some((asset, max_raw)) = &self.max_payment {
+            check_budget(requirements, asset, *max_raw)?;
+        }
 
         // Create payment info before signing
         let payment_info = X402PaymentInfo::from_requirements(requirements);
@@ -728,3 +743,54 @@ fn parse_url_parts(url: &str) -> (String, String, Option<String>) {
 
     (authority, path, query)
 }
+
+/// A payment must be in the budget's asset and no larger than the budget.
+/// Amounts in different assets aren't comparable (decimals and unit prices
+/// differ), so any other asset is refused.
+fn check_budget(requirements: &PaymentRequirements, asset: &str, max_raw: u128) -> Result<(), String> {
+    if !requirements.asset.eq_ignore_ascii_case(asset) {
+        return Err(format!(
+            "Payment asked for asset {}, but the budget is in {}",
+            requirements.asset, asset
+        ));
+    }
+    let amount: u128 = requirements.max_amount_required.parse()
+        .map_err(|_| format!("Invalid payment amount: {}", requirements.max_amount_required))?;
+    if amount > max_raw {
+        return Err(format!(
+            "Payment of {} exceeds the budget of {} (smallest units of {})",
+            amount, max_raw, requirements.asset
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirements(asset: &str, amount: &str) -> PaymentRequirements {
+        PaymentRequirements {
+            scheme: "exact".to_string(),
+            network: "base".to_string(),
+            max_amount_required: amount.to_string(),
+            pay_to_address: "0x0000000000000000000000000000000000000001".to_string(),
+            asset: asset.to_string(),
+            max_timeout_seconds: 60,
+            resource: None,
+            description: None,
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn test_budget_only_covers_its_own_asset() {
+        let usdc = crate::x402::USDC_ADDRESS;
+        assert!(check_budget(&requirements(&usdc.to_lowercase(), "1000000"), usdc, 1_000_000).is_ok());
+        assert!(check_budget(&requirements(usdc, "1000001"), usdc, 1_000_000).is_err());
+        // 0.01 WBTC is under the raw cap but far more than 1 USDC
+        let wbtc = "0x0555E30da8f98308EdB960aa94C0Db47230d2B9c";
+        let err = check_budget(&requirements(wbtc, "1000000"), usdc, 1_000_000).unwrap_err();
+        assert!(err.contains("budget is in"));
+    }
+}
@@ -155,13 +155,53 @@ impl X402EvmRpc {
             id: 1,
         };
 
-        let url = self.rpc_url();
-        log::debug!("[X402EvmRpc] {} to {} with params: {:?} (x402={})", method, url, request.params, self.use_x402);
-
-        let response = if self.use_x402 {
-            self.client.post_with_payment(&url, &request).await?
-        } else {
-            self.client.post_regular(&url, &request).await?
+        let mut url = self.rpc_url();
+        let mut use_x402 = self.use_x402;
+        let mut failed_over = false;
+        let response = loop {
+            log::debug!("[X402EvmRpc] {} to {} with params: {:?} (x402={})", method, url, request.params, use_x402);
+
+            let started = std::time::Instant::now();
+            let response = if use_x402 {
+                self.client.post_with_payment(&url, &request).await
+            } else {
+                self.client.post_regular(&url, &request).await
+            };
+
+            // Report outcomes to the RPC pool (and Alchemy's circuit breaker) so
+            // resolution steers away from failing endpoints
+            let breaker = crate::tools::rpc_config::is_alchemy_url(&url).then_some(crate::circuit_breaker::ALCHEMY);
+            let failure = match &response {
+                Ok(response) => {
+                    let status = response.response.status();
+                    if let Some(dependency) = breaker {
+                        if crate::circuit_breaker::is_failure_status(status) {
+                            crate::circuit_breaker::record_failure(dependency, &format!("HTTP {}", status));
+                        } else {
+                            crate::circuit_breaker::record_success(dependency);
+                        }
+                    }
+                    crate::tools::rpc_pool::record_status(&url, status, started.elapsed())
+                }
+                Err(e) => {
+                    if let Some(dependency) = breaker {
+                        crate::circuit_breaker::record_failure(dependency, e);
+                    }
+                    crate::tools::rpc_pool::record_failure(&url, e, false);
+                    true
+                }
+            };
+
+            // One retry on another healthy endpoint of the pool
+            if failure && !failed_over {
+                if let Some(next) = crate::tools::rpc_pool::failover(&self.network, &url, false) {
+                    url = next.url;
+                    use_x402 = next.use_x402;
+                    failed_over = true;
+                    continue;
+                }
+            }
+            break response?;
         };
 
         let status = response.response.status();
@@ -309,9 +349,19 @@ impl X402EvmRpc {
         Ok(Some(receipt))
     }
 
-    /// Get transaction count (nonce) for an address
+    /// Get transaction count (nonce) for an address, including mempool transactions
     pub async fn get_transaction_count(&self, address: Address) -> Result<U256, String> {
-        let params = json!([format!("{:?}", address), "pending"]);
+        self.transaction_count_at(address, "pending").await
+    }
+
+    /// Get transaction count of mined transactions only; a broadcast transaction
+    /// with a nonce at or above this hasn't been included yet
+    pub async fn get_confirmed_transaction_count(&self, address: Address) -> Result<U256, String> {
+        self.transaction_count_at(address, "latest").await
+    }
+
+    async fn transaction_count_at(&self, address: Address, block: &str) -> Result<U256, String> {
+        let params = json!([format!("{:?}", address), block]);
 
         let result = self.rpc_call("eth_getTransactionCount", params).await?;
 
@@ -148,6 +148,8 @@ interface ContextBankItem {
   value: string;
   item_type: string;
   label?: string;
+  /** Tool whose output the item came from; absent when the user wrote it */
+  source?: string;
 }
 
 interface ContextBankState {
@@ -959,6 +961,9 @@ export default function DebugPanel({ className }: DebugPanelProps) {
                       {item.label && (
                         <span className="text-slate-500">({item.label})</span>
                       )}
+                      {item.source && (
+                        <span className="text-slate-600 text-[10px]">via {item.source}</span>
+                      )}
                       <span className="text-slate-600 text-[10px]">
                         {item.item_type === 'eth_address' ? '📍' : item.item_type === 'network' ? '🌐' : '🪙'}
                       </span>
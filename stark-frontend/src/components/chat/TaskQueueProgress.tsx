@@ -1,10 +1,10 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
-import { CheckCircle, Circle, ListChecks, X } from 'lucide-react';
+import { Ban, CheckCircle, Circle, ListChecks, X } from 'lucide-react';
 import UnicodeSpinner from '@/components/ui/UnicodeSpinner';
 import clsx from 'clsx';
 import { useGateway } from '@/hooks/useGateway';
 import { deletePlannerTask, getPlannerTasks } from '@/lib/api';
-import type { PlannerTask, TaskQueueUpdateEvent, TaskStatusChangeEvent } from '@/types';
+import type { PlannerTask, TaskQueueUpdateEvent, TaskStatus, TaskStatusChangeEvent } from '@/types';
 
 // Web channel ID - must match backend WEB_CHANNEL_ID
 const WEB_CHANNEL_ID = 0;
@@ -54,7 +54,7 @@ export default function TaskQueueProgress({ className, dbSessionId }: TaskQueueP
           const plannerTasks: PlannerTask[] = response.tasks.map((t) => ({
             id: t.id,
             description: t.description,
-            status: t.status as 'pending' | 'in_progress' | 'completed',
+            status: t.status as TaskStatus,
           }));
           setTasks(plannerTasks);
           setVisible(true);
@@ -68,7 +68,7 @@ export default function TaskQueueProgress({ className, dbSessionId }: TaskQueueP
     fetchTasks();
   }, []);
 
-  // Handle task deletion
+  // Handle task cancellation
   const handleDeleteTask = useCallback(async (taskId: number, e: React.MouseEvent) => {
     e.stopPropagation();
     if (deletingTaskId !== null) return; // Already deleting
@@ -77,14 +77,14 @@ export default function TaskQueueProgress({ className, dbSessionId }: TaskQueueP
     try {
       const result = await deletePlannerTask(taskId);
       if (result.success) {
-        // Optimistically remove the task from local state
+        // Optimistically mark the task cancelled in local state
         // The backend will also broadcast a task_queue_update event
-        setTasks((prev) => prev.filter((t) => t.id !== taskId));
+        setTasks((prev) => prev.map((t) => (t.id === taskId ? { ...t, status: 'cancelled' } : t)));
       } else {
-        console.error('[TaskQueueProgress] Failed to delete task:', result.error);
+        console.error('[TaskQueueProgress] Failed to cancel task:', result.error);
       }
     } catch (error) {
-      console.error('[TaskQueueProgress] Error deleting task:', error);
+      console.error('[TaskQueueProgress] Error cancelling task:', error);
     } finally {
       setDeletingTaskId(null);
     }
@@ -170,6 +170,9 @@ export default function TaskQueueProgress({ className, dbSessionId }: TaskQueueP
     if (task.status === 'completed') {
       return <CheckCircle className="w-4 h-4 text-green-400" />;
     }
+    if (task.status === 'cancelled') {
+      return <Ban className="w-4 h-4 text-slate-500" />;
+    }
     if (task.status === 'in_progress') {
       return <UnicodeSpinner animation="sparkle" size="sm" className="text-cyan-400" />;
     }
@@ -209,7 +212,7 @@ export default function TaskQueueProgress({ className, dbSessionId }: TaskQueueP
             className={clsx(
               'flex items-start gap-2 text-sm py-1 px-2 rounded group',
               task.status === 'in_progress' && 'bg-cyan-500/10 border border-cyan-500/30',
-              task.status === 'completed' && 'opacity-60'
+              (task.status === 'completed' || task.status === 'cancelled') && 'opacity-60'
             )}
           >
             <div className="shrink-0 mt-0.5">
@@ -221,14 +224,15 @@ export default function TaskQueueProgress({ className, dbSessionId }: TaskQueueP
                   'block',
                   task.status === 'in_progress' && 'text-cyan-300 font-medium',
                   task.status === 'completed' && 'text-slate-400 line-through',
+                  task.status === 'cancelled' && 'text-slate-500 line-through',
                   task.status === 'pending' && 'text-slate-300'
                 )}
               >
                 {task.id}. {task.description}
               </span>
             </div>
-            {/* Delete button - always visible for non-completed tasks */}
-            {task.status !== 'completed' && (
+            {/* Cancel button - always visible for unfinished tasks */}
+            {task.status !== 'completed' && task.status !== 'cancelled' && (
               <button
                 onClick={(e) => handleDeleteTask(task.id, e)}
                 disabled={deletingTaskId === task.id}
@@ -238,7 +242,7 @@ export default function TaskQueueProgress({ className, dbSessionId }: TaskQueueP
                   'focus:outline-none focus:ring-1 focus:ring-red-500/50',
                   deletingTaskId === task.id && 'cursor-wait'
                 )}
-                title="Delete task"
+                title="Cancel task"
               >
                 {deletingTaskId === task.id ? (
                   <UnicodeSpinner animation="orbit" size="sm" className="text-slate-400" />
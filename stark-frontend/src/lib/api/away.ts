@@ -0,0 +1,47 @@
+import { apiFetch } from './core';
+
+export interface AwaySettings {
+  enabled: boolean;
+  message: string | null;
+  starts_at: string | null;
+  ends_at: string | null;
+  allowed_actions: string[];
+}
+
+export interface AwayRequest {
+  id: number;
+  channel_id: number;
+  channel_type: string;
+  chat_id: string;
+  chat_name: string | null;
+  user_id: string;
+  user_name: string;
+  text: string;
+  message_id: string | null;
+  force_safe_mode: boolean;
+  shared_chat: boolean;
+  created_at: string;
+}
+
+export async function getAwayMode(): Promise<{ active: boolean; queued: number; settings: AwaySettings }> {
+  return apiFetch('/away');
+}
+
+export async function updateAwayMode(settings: AwaySettings): Promise<{ active: boolean; settings: AwaySettings }> {
+  return apiFetch('/away', {
+    method: 'PUT',
+    body: JSON.stringify(settings),
+  });
+}
+
+export async function listAwayQueue(): Promise<AwayRequest[]> {
+  return apiFetch('/away/queue');
+}
+
+export async function clearAwayQueue(): Promise<{ deleted: number }> {
+  return apiFetch('/away/queue', { method: 'DELETE' });
+}
+
+export async function deleteAwayRequest(id: number): Promise<{ success: boolean }> {
+  return apiFetch(`/away/queue/${id}`, { method: 'DELETE' });
+}
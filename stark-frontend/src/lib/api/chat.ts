@@ -74,6 +74,19 @@ export async function stopExecution(): Promise<StopExecutionResponse> {
   });
 }
 
+// Pause at the next safe step; the paused run is resumed with resumeExecution()
+export async function pauseExecution(): Promise<StopExecutionResponse> {
+  return apiFetch('/chat/pause', {
+    method: 'POST',
+  });
+}
+
+export async function resumeExecution(): Promise<{ success: boolean; message?: { role: string; content: string }; error?: string }> {
+  return apiFetch('/chat/resume', {
+    method: 'POST',
+  });
+}
+
 // Execution Status API
 export interface ExecutionStatusResponse {
   running: boolean;
@@ -83,3 +96,20 @@ export interface ExecutionStatusResponse {
 export async function getExecutionStatus(): Promise<ExecutionStatusResponse> {
   return apiFetch('/chat/execution-status');
 }
+
+// Execution Artifacts API
+export interface ExecutionArtifact {
+  id: number;
+  execution_id: string;
+  session_id: number | null;
+  channel_id: number | null;
+  name: string;
+  path: string;
+  mime_type: string;
+  size_bytes: number;
+  created_at: string;
+}
+
+export async function getExecutionArtifacts(executionId: string): Promise<ExecutionArtifact[]> {
+  return apiFetch(`/executions/${encodeURIComponent(executionId)}/artifacts`);
+}
@@ -0,0 +1,48 @@
+import { apiFetch } from './core';
+
+export interface BusEvent {
+  seq: number;
+  event: string;
+  source: string;
+  data: unknown;
+  timestamp: string;
+}
+
+export interface EventSubscription {
+  id: number;
+  subscriber: string;
+  events: string[];
+  callback_url: string | null;
+  enabled: boolean;
+  failure_count: number;
+  last_error: string | null;
+  last_delivered_at: string | null;
+  created_at: string;
+}
+
+export async function getEventTypes(): Promise<Array<{ event: string; description: string }>> {
+  return apiFetch('/events/types');
+}
+
+export async function pollEvents(
+  after = 0,
+  events?: string[],
+  limit?: number
+): Promise<{ events: BusEvent[]; latest_seq: number }> {
+  const params = new URLSearchParams({ after: String(after) });
+  if (events?.length) params.set('events', events.join(','));
+  if (limit) params.set('limit', String(limit));
+  return apiFetch(`/events/poll?${params}`);
+}
+
+export async function listEventSubscriptions(): Promise<EventSubscription[]> {
+  return apiFetch('/events/subscriptions');
+}
+
+export async function deleteEventSubscription(id: number): Promise<{ success: boolean }> {
+  return apiFetch(`/events/subscriptions/${id}`, { method: 'DELETE' });
+}
+
+export async function enableEventSubscription(id: number): Promise<EventSubscription> {
+  return apiFetch(`/events/subscriptions/${id}/enable`, { method: 'POST' });
+}
@@ -0,0 +1,87 @@
+import { apiFetch } from './core';
+
+export interface Handoff {
+  id: number;
+  session_id: number | null;
+  channel_id: number;
+  channel_type: string;
+  chat_id: string;
+  user_name: string | null;
+  reason: string;
+  requested_by: 'agent' | 'rule' | 'operator';
+  status: 'waiting' | 'joined' | 'released';
+  operator: string | null;
+  created_at: string;
+  joined_at: string | null;
+  released_at: string | null;
+}
+
+export interface HandoffOperator {
+  name: string;
+  channel_id: number;
+  chat_id: string;
+}
+
+export interface HandoffConfig {
+  operators: HandoffOperator[];
+  keywords: string[];
+  escalate_urgent_negative: boolean;
+}
+
+export interface HandoffMessage {
+  id: number;
+  role: string;
+  content: string;
+  user_name: string | null;
+  created_at: string;
+}
+
+export async function listHandoffs(activeOnly = false): Promise<Handoff[]> {
+  return apiFetch(`/handoffs${activeOnly ? '?active=true' : ''}`);
+}
+
+export async function getHandoff(id: number): Promise<{ handoff: Handoff; messages: HandoffMessage[] }> {
+  return apiFetch(`/handoffs/${id}`);
+}
+
+export async function requestHandoff(request: {
+  channel_id: number;
+  chat_id: string;
+  reason: string;
+  session_id?: number;
+  user_name?: string;
+}): Promise<Handoff> {
+  return apiFetch('/handoffs', {
+    method: 'POST',
+    body: JSON.stringify(request),
+  });
+}
+
+export async function joinHandoff(id: number, operator: string): Promise<Handoff> {
+  return apiFetch(`/handoffs/${id}/join`, {
+    method: 'POST',
+    body: JSON.stringify({ operator }),
+  });
+}
+
+export async function replyToHandoff(id: number, text: string, operator?: string): Promise<{ success: boolean }> {
+  return apiFetch(`/handoffs/${id}/reply`, {
+    method: 'POST',
+    body: JSON.stringify({ text, operator }),
+  });
+}
+
+export async function releaseHandoff(id: number): Promise<Handoff> {
+  return apiFetch(`/handoffs/${id}/release`, { method: 'POST' });
+}
+
+export async function getHandoffConfig(): Promise<HandoffConfig> {
+  return apiFetch('/handoffs/config');
+}
+
+export async function updateHandoffConfig(config: HandoffConfig): Promise<HandoffConfig> {
+  return apiFetch('/handoffs/config', {
+    method: 'PUT',
+    body: JSON.stringify(config),
+  });
+}
@@ -26,3 +26,6 @@ export * from './kv';
 export * from './notes';
 export * from './impulse-map';
 export * from './transcribe';
+export * from './events';
+export * from './away';
+export * from './handoffs';
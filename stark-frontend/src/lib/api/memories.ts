@@ -193,6 +193,82 @@ export async function rebuildAssociations(): Promise<{ success: boolean; message
   return apiFetch('/memory/associations/rebuild', { method: 'POST' });
 }
 
+// ============================================
+// Knowledge Graph API
+// ============================================
+
+export interface KnowledgeEntity {
+  id: number;
+  entity_type: string;
+  name: string;
+  display_name: string;
+  mention_count: number;
+  first_seen: string;
+  last_seen: string;
+}
+
+export interface KnowledgeRelation {
+  id: number;
+  source_entity_id: number;
+  target_entity_id: number;
+  relation_type: string;
+  strength: number;
+  mention_count: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface KnowledgeMention {
+  id: number;
+  entity_id: number;
+  source: string;
+  memory_id: number | null;
+  session_id: number | null;
+  created_at: string;
+}
+
+export interface KnowledgeGraphResponse {
+  success: boolean;
+  nodes: KnowledgeEntity[];
+  edges: KnowledgeRelation[];
+  stats?: {
+    entities: number;
+    relations: number;
+    mentions: number;
+    entity_types: Array<[string, number]>;
+    relation_types: Array<[string, number]>;
+  };
+  error?: string;
+}
+
+export async function getKnowledgeGraph(params: {
+  entity_type?: string;
+  query?: string;
+  limit?: number;
+} = {}): Promise<KnowledgeGraphResponse> {
+  const search = new URLSearchParams();
+  if (params.entity_type) search.set('entity_type', params.entity_type);
+  if (params.query) search.set('query', params.query);
+  if (params.limit) search.set('limit', String(params.limit));
+  const qs = search.toString();
+  return apiFetch(`/memory/knowledge/graph${qs ? `?${qs}` : ''}`);
+}
+
+export async function getKnowledgeEntity(id: number): Promise<{
+  success: boolean;
+  entity?: KnowledgeEntity;
+  relations: KnowledgeRelation[];
+  related: KnowledgeEntity[];
+  mentions: KnowledgeMention[];
+  error?: string;
+}> {
+  return apiFetch(`/memory/knowledge/entities/${id}`);
+}
+
+export async function rebuildKnowledgeGraph(): Promise<{ success: boolean; message: string }> {
+  return apiFetch('/memory/knowledge/rebuild', { method: 'POST' });
+}
+
 export async function getCortexBulletin(): Promise<CortexBulletin> {
   return apiFetch('/bulletin');
 }
@@ -157,6 +157,13 @@ export async function getCronJobRuns(id: number, limit?: number): Promise<CronJo
 }
 
 // Heartbeat Config API
+export interface MaintenanceJobConfig {
+  job: 'vacuum_db' | 'prune_expired_files' | 'reembed_skills' | 'verify_module_checksums';
+  enabled: boolean;
+  interval_minutes: number;
+  max_age_days?: number;
+}
+
 export interface HeartbeatConfigInfo {
   id: number;
   channel_id?: number;
@@ -168,6 +175,7 @@ export interface HeartbeatConfigInfo {
   enabled: boolean;
   last_beat_at?: string;
   next_beat_at?: string;
+  maintenance_jobs?: MaintenanceJobConfig[];
   created_at: string;
   updated_at: string;
 }
@@ -190,6 +198,7 @@ export async function updateHeartbeatConfig(data: {
   active_hours_end?: string;
   active_days?: string;
   enabled?: boolean;
+  maintenance_jobs?: MaintenanceJobConfig[];
 }): Promise<HeartbeatConfigInfo> {
   const response = await apiFetch<HeartbeatConfigResponse>('/heartbeat/config', {
     method: 'PUT',
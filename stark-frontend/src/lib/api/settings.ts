@@ -73,6 +73,23 @@ export async function updateBotSettings(data: {
 export interface ServicesHealth {
   whisper: { url: string; healthy: boolean };
   embeddings: { url: string; healthy: boolean };
+  maintenance?: {
+    healthy: boolean;
+    jobs: Array<{
+      job: string;
+      enabled: boolean;
+      interval_minutes: number;
+      max_age_days?: number;
+      last_run: {
+        started_at: string;
+        duration_ms: number;
+        success: boolean;
+        summary: string;
+        error: string | null;
+      } | null;
+      next_run_at: string | null;
+    }>;
+  };
 }
 
 export async function getServicesHealth(): Promise<ServicesHealth> {
@@ -13,6 +13,7 @@ export interface SkillInfo {
   tags: string[];
   homepage?: string;
   metadata?: string;
+  locales: string[];
 }
 
 export interface SkillDetail {
@@ -85,6 +86,90 @@ export async function setSkillEnabled(name: string, enabled: boolean): Promise<v
   });
 }
 
+// Skill render dry-run
+export interface SkillRender {
+  skill: string;
+  prompt: string;
+  args: Record<string, string | null>;
+  missing_args: string[];
+  unknown_args: string[];
+  unresolved_placeholders: string[];
+  subtype: string | null;
+  tools: { name: string; group: string; required_by_skill: boolean }[];
+  unavailable_tools: string[];
+}
+
+export async function renderSkill(
+  name: string,
+  args: Record<string, string> = {},
+  subtype?: string,
+  locale?: string
+): Promise<SkillRender> {
+  const params = new URLSearchParams({ args: JSON.stringify(args) });
+  if (subtype) params.set('subtype', subtype);
+  if (locale) params.set('locale', locale);
+  return apiFetch(`/skills/${encodeURIComponent(name)}/render?${params}`);
+}
+
+// Skill runs (invoke_skill)
+export interface SkillRun {
+  id: number;
+  skill_name: string;
+  caller_skill: string | null;
+  parent_run_id: number | null;
+  root_run_id: number | null;
+  depth: number;
+  subagent_id: string | null;
+  input: string;
+  args: string;
+  status: 'running' | 'completed' | 'failed';
+  output: string | null;
+  error: string | null;
+  duration_ms: number | null;
+  started_at: string;
+  completed_at: string | null;
+}
+
+export interface SkillRunTree {
+  run: SkillRun;
+  root_run_id: number;
+  totals: { runs: number; failed: number; running: number; max_depth: number; duration_ms: number | null; skills: string[] };
+  tree: SkillRun[];
+}
+
+export async function getSkillRuns(skill?: string, limit = 50): Promise<{ runs: SkillRun[] }> {
+  const params = new URLSearchParams({ limit: String(limit) });
+  if (skill) params.set('skill', skill);
+  return apiFetch(`/skills/runs?${params}`);
+}
+
+export async function getSkillRun(id: number): Promise<SkillRunTree> {
+  return apiFetch(`/skills/runs/${id}`);
+}
+
+// Skill finance permissions
+export type FinanceCapability = 'read' | 'send';
+
+export interface SkillPermissions {
+  skill: string;
+  bundled: boolean;
+  capabilities: { capability: FinanceCapability; declared: boolean; granted: boolean }[];
+}
+
+export async function getSkillPermissions(name: string): Promise<SkillPermissions> {
+  return apiFetch(`/skills/${encodeURIComponent(name)}/permissions`);
+}
+
+export async function setSkillPermissions(
+  name: string,
+  grants: Partial<Record<FinanceCapability, boolean>>
+): Promise<SkillPermissions> {
+  return apiFetch(`/skills/${encodeURIComponent(name)}/permissions`, {
+    method: 'PUT',
+    body: JSON.stringify(grants),
+  });
+}
+
 // Bundled Skills API
 export interface BundledSkillInfo {
   name: string;
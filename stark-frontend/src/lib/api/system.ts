@@ -49,3 +49,51 @@ export async function cleanupWorkspace(): Promise<CleanupResult> {
     body: JSON.stringify({ confirm: true }),
   });
 }
+
+// Configuration doctor API
+export interface DoctorFinding {
+  check: string;
+  status: 'ok' | 'warn' | 'error';
+  message: string;
+  fix: string | null;
+}
+
+export interface DoctorReport {
+  healthy: boolean;
+  checked_at: string;
+  findings: DoctorFinding[];
+}
+
+export async function runDoctor(): Promise<DoctorReport> {
+  return apiFetch('/admin/doctor', { method: 'POST' });
+}
+
+// Self-update API
+export interface ReleaseInfo {
+  version: string;
+  notes: string | null;
+  published_at: string | null;
+  binary_url: string;
+  checksum_url: string;
+}
+
+export interface UpdateCheck {
+  current_version: string;
+  platform: string;
+  update_available: boolean;
+  release: ReleaseInfo | null;
+  in_progress: boolean;
+}
+
+export async function checkForUpdate(): Promise<UpdateCheck> {
+  return apiFetch('/system/update');
+}
+
+export async function installUpdate(): Promise<{
+  previous_version: string;
+  version: string;
+  restarting: boolean;
+  drain_timeout_secs: number;
+}> {
+  return apiFetch('/system/update', { method: 'POST' });
+}
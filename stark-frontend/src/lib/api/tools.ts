@@ -42,3 +42,26 @@ export async function updateToolEnabled(name: string, enabled: boolean): Promise
     body: JSON.stringify({ enabled }),
   });
 }
+
+export interface ToolTimeoutSettings {
+  default_secs: number | null;
+  tools: Record<string, number>;
+}
+
+export interface ToolTimeouts {
+  /** Timeouts in effect, built-in ones included */
+  default_secs: number;
+  tools: Record<string, number>;
+  configured: ToolTimeoutSettings;
+}
+
+export async function getToolTimeouts(): Promise<ToolTimeouts> {
+  return apiFetch('/tool-timeouts');
+}
+
+export async function updateToolTimeouts(settings: ToolTimeoutSettings): Promise<ToolTimeouts> {
+  return apiFetch('/tool-timeouts', {
+    method: 'PUT',
+    body: JSON.stringify(settings),
+  });
+}
@@ -18,7 +18,7 @@ import { useGateway } from '@/hooks/useGateway';
 import { useWallet, SUPPORTED_NETWORKS, type SupportedNetwork } from '@/hooks/useWallet';
 import { sendChatMessage, getAgentSettings, getSkills, getTools, confirmTransaction, cancelTransaction, stopExecution, listSubagents, getActiveWebSession, getSessionTranscript, getExecutionStatus, createNewWebSession, getPlannerTasks, getAgentSubtypes, AgentSubtypeInfo, transcribeAudio } from '@/lib/api';
 import { Command, COMMAND_DEFINITIONS, getAllCommands } from '@/lib/commands';
-import type { ChatMessage as ChatMessageType, MessageRole, SlashCommand, TrackedTransaction, TxPendingEvent, TxConfirmedEvent, PendingConfirmation, ConfirmationRequiredEvent, PlannerTask, TaskQueueUpdateEvent, TaskStatusChangeEvent } from '@/types';
+import type { ChatMessage as ChatMessageType, MessageRole, SlashCommand, TrackedTransaction, TxPendingEvent, TxConfirmedEvent, PendingConfirmation, ConfirmationRequiredEvent, PlannerTask, TaskQueueUpdateEvent, TaskStatus, TaskStatusChangeEvent } from '@/types';
 
 interface ConversationMessage {
   role: string;
@@ -680,11 +680,49 @@ export default function AgentChat() {
       });
     };
 
+    const handleCompactionFinished = (data: unknown) => {
+      const event = data as {
+        session_id: number;
+        compaction_type: string | null;
+        success: boolean;
+        tokens_before: number;
+        tokens_after: number;
+        error: string | null;
+        timestamp: string;
+      };
+      if (event.session_id !== dbSessionId) return;
+
+      const content = event.success
+        ? `📦 Compacted conversation history (${event.compaction_type}): ${event.tokens_before} → ${event.tokens_after} tokens`
+        : `📦 Compaction failed: ${event.error}`;
+      setMessages((prev) => {
+        const filtered = prev.filter((m) => !(m.role === 'system' && m.content.startsWith('📦 Compacting')));
+        return [
+          ...filtered,
+          {
+            id: crypto.randomUUID(),
+            role: 'system' as MessageRole,
+            content,
+            timestamp: new Date(event.timestamp),
+            sessionId,
+          },
+        ];
+      });
+    };
+
+    // Background compaction between turns reuses the compacting notice
+    const handleCompactionStarted = (data: unknown) => {
+      const event = data as { channel_id: number; session_id: number; level: string; timestamp: string };
+      handleContextCompacting({ ...event, compaction_type: event.level, reason: 'Context threshold reached' });
+    };
+
     on('agent.thinking', handleThinking);
     on('agent.error', handleError);
     on('agent.warning', handleWarning);
     on('ai.retrying', handleAiRetrying);
     on('context.compacting', handleContextCompacting);
+    on('compaction.started', handleCompactionStarted);
+    on('compaction.finished', handleCompactionFinished);
 
     return () => {
       off('agent.thinking', handleThinking);
@@ -692,6 +730,8 @@ export default function AgentChat() {
       off('agent.warning', handleWarning);
       off('ai.retrying', handleAiRetrying);
       off('context.compacting', handleContextCompacting);
+      off('compaction.started', handleCompactionStarted);
+      off('compaction.finished', handleCompactionFinished);
     };
   }, [on, off, sessionId, dbSessionId]);
 
@@ -1099,7 +1139,7 @@ export default function AgentChat() {
           setPlannerTasks(response.tasks.map((t) => ({
             id: t.id,
             description: t.description,
-            status: t.status as 'pending' | 'in_progress' | 'completed',
+            status: t.status as TaskStatus,
           })));
         }
       } catch {
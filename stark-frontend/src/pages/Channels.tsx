@@ -26,6 +26,7 @@ const CHANNEL_TYPES = [
   { value: 'slack', label: 'Slack', icon: Hash, color: 'purple' },
   { value: 'discord', label: 'Discord', icon: MessageSquare, color: 'indigo' },
   { value: 'twitter', label: 'Twitter / X', icon: Twitter, color: 'sky' },
+  { value: 'farcaster', label: 'Farcaster', icon: MessageSquare, color: 'violet' },
   { value: 'external_channel', label: 'External Channel', icon: Terminal, color: 'emerald' },
 ];
 
@@ -42,6 +43,12 @@ function getChannelHints(channelType: string): string[] {
         'Set your 4 OAuth 1.0a keys (Consumer Key, Consumer Secret, Access Token, Access Token Secret) on the API Keys page.',
         'Configure the Bot Handle (e.g. "starkbot") and Bot User ID (numeric) in channel settings after creation.',
       ];
+    case 'farcaster':
+      return [
+        'Set your Neynar API Key and the UUID of a Neynar managed signer for the bot account on the API Keys page.',
+        'Configure the Bot FID (numeric) in channel settings. Casts that mention or reply to the bot are answered with a reply cast; set an Admin FID for full agent access.',
+        'On start the bot account is added to your EIP-8004 registration as a <strong>farcaster</strong> service.',
+      ];
     case 'telegram':
       return [
         'To use in a group, set an <strong>Admin User ID</strong> in channel settings. Only the admin gets full agent access; all other users are restricted to safe mode. Without an admin configured, all users have full unrestricted access.',
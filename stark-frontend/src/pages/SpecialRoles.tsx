@@ -21,7 +21,7 @@ import {
 
 type Tab = 'roles' | 'assignments' | 'role_assignments';
 
-const CHANNEL_TYPES = ['discord', 'twitter', 'telegram', 'slack', 'external_channel'];
+const CHANNEL_TYPES = ['discord', 'twitter', 'farcaster', 'telegram', 'slack', 'external_channel'];
 const MAX_ROLES = 10;
 const MAX_ASSIGNMENTS = 100;
 const MAX_ROLE_ASSIGNMENTS = 100;
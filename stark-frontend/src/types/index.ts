@@ -181,7 +181,7 @@ export interface Session {
 }
 
 // Task Planner types
-export type TaskStatus = 'pending' | 'in_progress' | 'completed';
+export type TaskStatus = 'pending' | 'in_progress' | 'completed' | 'cancelled';
 
 export interface PlannerTask {
   id: number;